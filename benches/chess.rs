@@ -0,0 +1,43 @@
+//! Chess engine benchmarks: board hashing and move generation
+
+mod common;
+
+use common::{CountingAllocator, Metric};
+use criterion::{criterion_group, criterion_main, Criterion};
+use mate::chess::Board;
+use mate::messages::chess::hash_board_state;
+
+/// Positions measured, from the opening to a busy middlegame
+const POSITIONS: &[(&str, &str)] = &[
+    (
+        "start",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    ),
+    (
+        "middlegame",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    ),
+];
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn bench<M: Metric>(c: &mut Criterion<M>) {
+    let mut group = c.benchmark_group(common::group_name::<M>("chess"));
+
+    for &(label, fen) in POSITIONS {
+        let board = Board::from_fen(fen).expect("position parses");
+        group.bench_function(format!("hash_board_state/{label}"), |b| {
+            b.iter(|| hash_board_state(&board))
+        });
+        group.bench_function(format!("legal_moves/{label}"), |b| {
+            b.iter(|| board.legal_moves())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(name = timed; config = Criterion::default(); targets = bench);
+criterion_group!(name = counted; config = common::allocations(); targets = bench);
+criterion_main!(timed, counted);
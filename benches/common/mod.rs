@@ -0,0 +1,127 @@
+//! Measurements shared by the criterion benches
+//!
+//! Each benchmark runs twice: timed, and counting heap allocations per
+//! iteration with [`Allocations`]. Counting needs [`CountingAllocator`]
+//! registered with `#[global_allocator]` in every bench binary. The two
+//! results go to separate groups, e.g. `wire/time` and `wire/allocs`.
+
+use criterion::measurement::{Measurement, ValueFormatter, WallTime};
+use criterion::{Criterion, Throughput};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// System allocator that counts allocations, for reporting allocation churn
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Heap allocations made while the benchmarked routine runs
+pub struct Allocations;
+
+impl Measurement for Allocations {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> u64 {
+        ALLOCATIONS.load(Ordering::SeqCst)
+    }
+
+    // Criterion rejects a sample of zero, which an allocation-free routine
+    // would give, so each sample counts one extra; spread over the sample's
+    // thousands of iterations it doesn't show in the results
+    fn end(&self, start: u64) -> u64 {
+        ALLOCATIONS.load(Ordering::SeqCst) - start + 1
+    }
+
+    fn add(&self, v1: &u64, v2: &u64) -> u64 {
+        v1 + v2
+    }
+
+    fn zero(&self) -> u64 {
+        0
+    }
+
+    fn to_f64(&self, value: &u64) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &AllocationFormatter
+    }
+}
+
+struct AllocationFormatter;
+
+impl ValueFormatter for AllocationFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (per, unit) = match *throughput {
+            Throughput::Bytes(bytes) | Throughput::BytesDecimal(bytes) => {
+                (bytes as f64 / 1024.0, "allocs/KiB")
+            }
+            Throughput::Elements(elements) => (elements as f64, "allocs/elem"),
+        };
+        for value in values {
+            *value /= per;
+        }
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+}
+
+/// A measurement the benches run with, named in their group so timed and
+/// counted results are kept apart
+pub trait Metric: Measurement {
+    const NAME: &'static str;
+}
+
+impl Metric for WallTime {
+    const NAME: &'static str = "time";
+}
+
+impl Metric for Allocations {
+    const NAME: &'static str = "allocs";
+}
+
+/// Name of the group of `bench` benchmarks measured with `M`
+pub fn group_name<M: Metric>(bench: &str) -> String {
+    format!("{bench}/{}", M::NAME)
+}
+
+/// Criterion counting allocations; the counts barely vary, so a few short
+/// samples are enough
+pub fn allocations() -> Criterion<Allocations> {
+    Criterion::default()
+        .with_measurement(Allocations)
+        .sample_size(10)
+        .warm_up_time(Duration::from_millis(100))
+        .measurement_time(Duration::from_millis(500))
+}
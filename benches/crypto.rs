@@ -0,0 +1,54 @@
+//! Signature verification benchmarks: checking batches of envelopes one by
+//! one and across all cores
+
+mod common;
+
+use common::{CountingAllocator, Metric};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use mate::crypto::Identity;
+use mate::messages::{Message, SignedEnvelope};
+use std::num::NonZeroUsize;
+
+/// Envelopes in a batch, as when ingesting a backlog of messages
+const BATCH_SIZES: &[usize] = &[100, 1000];
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn bench<M: Metric>(c: &mut Criterion<M>) {
+    let identity = Identity::generate().expect("identity generates");
+    let cores = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let mut group = c.benchmark_group(common::group_name::<M>("crypto"));
+
+    for &size in BATCH_SIZES {
+        let envelopes: Vec<SignedEnvelope> = (0..size)
+            .map(|i| {
+                let message = Message::new_ping(i as u64, format!("message {i}"));
+                SignedEnvelope::create(&message, &identity, None).expect("envelope signs")
+            })
+            .collect();
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_function(format!("verify/serial/{size}"), |b| {
+            b.iter(|| {
+                envelopes
+                    .iter()
+                    .map(SignedEnvelope::verify_signature)
+                    .collect::<Vec<_>>()
+            })
+        });
+        // On one core `verify_all` is the serial loop, so timing it again
+        // would only record noise as a difference
+        if cores > 1 {
+            group.bench_function(format!("verify/parallel/{size}"), |b| {
+                b.iter(|| SignedEnvelope::verify_all(&envelopes))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(name = timed; config = Criterion::default(); targets = bench);
+criterion_group!(name = counted; config = common::allocations(); targets = bench);
+criterion_main!(timed, counted);
@@ -0,0 +1,46 @@
+//! Storage benchmarks: SQLite insert rates
+
+mod common;
+
+use common::{CountingAllocator, Metric};
+use criterion::{criterion_group, criterion_main, Criterion};
+use mate::storage::{Database, PlayerColor};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn bench<M: Metric>(c: &mut Criterion<M>) {
+    let dir = tempfile::TempDir::new().expect("temporary directory");
+    let db = Database::new_with_path("bench_peer", &dir.path().join("bench.sqlite"))
+        .expect("database opens");
+    let mut group = c.benchmark_group(common::group_name::<M>("storage"));
+
+    group.bench_function("create_game", |b| {
+        b.iter(|| {
+            db.create_game("opponent_peer".to_string(), PlayerColor::White, None)
+                .expect("game inserts")
+        })
+    });
+
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::White, None)
+        .expect("game inserts");
+    group.bench_function("store_message", |b| {
+        b.iter(|| {
+            db.store_message(
+                game.id.clone(),
+                "Move".to_string(),
+                r#"{"chess_move":"e2e4"}"#.to_string(),
+                "0".repeat(128),
+                "opponent_peer".to_string(),
+            )
+            .expect("message inserts")
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(name = timed; config = Criterion::default(); targets = bench);
+criterion_group!(name = counted; config = common::allocations(); targets = bench);
+criterion_main!(timed, counted);
@@ -0,0 +1,110 @@
+//! Wire protocol benchmarks: framing and unframing signed envelopes
+
+mod common;
+
+use common::{CountingAllocator, Metric};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use mate::crypto::Identity;
+use mate::messages::wire::{FramedMessage, WireConfig};
+use mate::messages::{Message, SignedEnvelope};
+
+/// Payload sizes, from a chat line to a large sync response
+const PAYLOAD_SIZES: &[(&str, usize)] = &[
+    ("64B", 64),
+    ("1KiB", 1024),
+    ("16KiB", 16 * 1024),
+    ("256KiB", 256 * 1024),
+];
+
+/// Messages written and read back in the sustained-load benchmark
+const SUSTAINED_MESSAGES: usize = 100;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn bench<M: Metric>(c: &mut Criterion<M>) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime starts");
+    let identity = Identity::generate().expect("identity generates");
+    let framed = FramedMessage::new(WireConfig::default());
+    let mut group = c.benchmark_group(common::group_name::<M>("wire"));
+
+    for &(label, size) in PAYLOAD_SIZES {
+        let message = Message::new_ping(1, "x".repeat(size));
+        let envelope = SignedEnvelope::create(&message, &identity, None).expect("envelope signs");
+
+        let mut frame = Vec::new();
+        runtime
+            .block_on(framed.write_message(&mut frame, &envelope))
+            .expect("envelope frames");
+        group.throughput(Throughput::Bytes(frame.len() as u64));
+
+        // The output buffer is reused so only the framing's own allocations count
+        let mut buffer = Vec::new();
+        group.bench_function(format!("serialize/{label}"), |b| {
+            b.iter(|| {
+                buffer.clear();
+                runtime
+                    .block_on(framed.write_message(&mut buffer, &envelope))
+                    .expect("envelope frames");
+            })
+        });
+
+        group.bench_function(format!("roundtrip/{label}"), |b| {
+            b.iter(|| {
+                buffer.clear();
+                runtime.block_on(async {
+                    framed
+                        .write_message(&mut buffer, &envelope)
+                        .await
+                        .expect("envelope frames");
+                    framed
+                        .read_message(&mut buffer.as_slice())
+                        .await
+                        .expect("frame reads back")
+                })
+            })
+        });
+    }
+
+    // A steady stream of moves-sized messages over one connection
+    let message = Message::new_ping(1, "x".repeat(256));
+    let envelope = SignedEnvelope::create(&message, &identity, None).expect("envelope signs");
+    let mut stream = Vec::new();
+    runtime.block_on(async {
+        for _ in 0..SUSTAINED_MESSAGES {
+            framed
+                .write_message(&mut stream, &envelope)
+                .await
+                .expect("envelope frames");
+        }
+    });
+    group.throughput(Throughput::Bytes(stream.len() as u64));
+    group.bench_function(format!("sustained/{SUSTAINED_MESSAGES}x256B"), |b| {
+        b.iter(|| {
+            stream.clear();
+            runtime.block_on(async {
+                for _ in 0..SUSTAINED_MESSAGES {
+                    framed
+                        .write_message(&mut stream, &envelope)
+                        .await
+                        .expect("envelope frames");
+                }
+                let mut reader = stream.as_slice();
+                for _ in 0..SUSTAINED_MESSAGES {
+                    framed
+                        .read_message(&mut reader)
+                        .await
+                        .expect("frame reads back");
+                }
+            })
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(name = timed; config = Criterion::default(); targets = bench);
+criterion_group!(name = counted; config = common::allocations(); targets = bench);
+criterion_main!(timed, counted);
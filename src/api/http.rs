@@ -0,0 +1,260 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Longest request line or header accepted
+const MAX_LINE_BYTES: usize = 8 * 1024;
+
+/// Most headers accepted in one request
+const MAX_HEADERS: usize = 64;
+
+/// Largest request body accepted
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// An HTTP/1.1 request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string, as sent
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// Headers by lowercase name
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// A header by case-insensitive name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Percent-decoded path segments between slashes, ignoring empty ones
+    ///
+    /// Segments are split before decoding, so `%2F` stays within a segment.
+    pub fn segments(&self) -> Vec<String> {
+        self.path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(percent_decode)
+            .collect()
+    }
+}
+
+/// Read one request, or `None` if the client closed the connection first
+pub async fn read_request<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<Request>> {
+    let Some(request_line) = read_line(reader).await? else {
+        return Ok(None);
+    };
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        anyhow::bail!("Malformed request line");
+    };
+    if !version.starts_with("HTTP/1.") {
+        anyhow::bail!("Unsupported protocol {version}");
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_ascii_uppercase(),
+        path: path.to_string(),
+        query: parse_query(query),
+        ..Request::default()
+    };
+
+    loop {
+        let line = read_line(reader)
+            .await?
+            .context("Connection closed in the middle of the headers")?;
+        if line.is_empty() {
+            break;
+        }
+        if request.headers.len() == MAX_HEADERS {
+            anyhow::bail!("Too many headers");
+        }
+        let (name, value) = line.split_once(':').context("Malformed header")?;
+        request
+            .headers
+            .insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    if let Some(length) = request.header("content-length") {
+        let length: usize = length.parse().context("Invalid Content-Length")?;
+        if length > MAX_BODY_BYTES {
+            anyhow::bail!("Request body exceeds {MAX_BODY_BYTES} bytes");
+        }
+        request.body = vec![0; length];
+        reader
+            .read_exact(&mut request.body)
+            .await
+            .context("Connection closed in the middle of the body")?;
+    }
+    Ok(Some(request))
+}
+
+/// One CRLF- or LF-terminated line, without the terminator
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<String>> {
+    let mut line = Vec::new();
+    let read = (&mut *reader)
+        .take(MAX_LINE_BYTES as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .await?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.len() > MAX_LINE_BYTES {
+        anyhow::bail!("Request line or header is too long");
+    }
+    while line.last().is_some_and(|b| *b == b'\n' || *b == b'\r') {
+        line.pop();
+    }
+    Ok(Some(
+        String::from_utf8(line).context("Request is not valid UTF-8")?,
+    ))
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                percent_decode(&name.replace('+', " ")),
+                percent_decode(&value.replace('+', " ")),
+            )
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// A complete HTTP response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    /// Extra headers such as `WWW-Authenticate`
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// A JSON response
+    pub fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        match serde_json::to_vec_pretty(value) {
+            Ok(body) => Self {
+                status,
+                content_type: "application/json",
+                headers: Vec::new(),
+                body,
+            },
+            Err(e) => Self::error(500, &format!("Failed to serialize response: {e}")),
+        }
+    }
+
+    /// A plain text response
+    pub fn text(status: u16, text: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            headers: Vec::new(),
+            body: text.into().into_bytes(),
+        }
+    }
+
+    /// A JSON error body, `{"error": message}`
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+
+    /// Add a header
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Write the response and leave the connection to be closed
+    pub async fn write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> Result<()> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            reason_phrase(self.status),
+            self.content_type,
+            self.body.len()
+        );
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        writer.write_all(head.as_bytes()).await?;
+        writer.write_all(&self.body).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+/// Start a `text/event-stream` response; events follow with [`write_event`]
+pub async fn write_event_stream_head<W: AsyncWrite + Unpin>(writer: &mut W) -> Result<()> {
+    writer
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Write one server-sent event with a JSON payload
+pub async fn write_event<W: AsyncWrite + Unpin, T: Serialize>(
+    writer: &mut W,
+    name: &str,
+    data: &T,
+) -> Result<()> {
+    let data = serde_json::to_string(data).context("Failed to serialize event")?;
+    writer
+        .write_all(format!("event: {name}\ndata: {data}\n\n").as_bytes())
+        .await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
@@ -0,0 +1,188 @@
+use crate::api::http::{read_request, Request, Response};
+use crate::network::{StatsRegistry, TrafficSnapshot};
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info};
+
+/// How long a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Endpoint serving the connection statistics of `mate serve`, started when
+/// `metrics.enabled` is set
+///
+/// - `GET /metrics` returns them in the Prometheus text format
+/// - `GET /stats` returns a [`TrafficSnapshot`] as JSON, as read by
+///   `mate status --network`
+/// - `GET /top` returns a [`MonitorSnapshot`](crate::network::MonitorSnapshot)
+///   with every open connection, as read by `mate top`
+///
+/// Only counters are exposed, so no token is required; bind it to a local
+/// address.
+pub struct MetricsServer {
+    listener: TcpListener,
+    stats: StatsRegistry,
+}
+
+impl MetricsServer {
+    /// Listen on `addr`, reporting what `stats` records
+    pub async fn bind(addr: &str, stats: StatsRegistry) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind the metrics endpoint to {addr}"))?;
+        Ok(Self { listener, stats })
+    }
+
+    /// The address the endpoint is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve requests until the task is cancelled
+    pub async fn run(self) -> Result<()> {
+        info!("Metrics listening on {}", self.local_addr()?);
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let stats = self.stats.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &stats).await {
+                    debug!("Metrics connection from {peer} failed: {e:#}");
+                }
+            });
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, stats: &StatsRegistry) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut reader)).await {
+        Ok(Ok(Some(request))) => request,
+        Ok(Ok(None)) | Err(_) => return Ok(()),
+        Ok(Err(e)) => {
+            return Response::error(400, &format!("{e:#}"))
+                .write_to(&mut writer)
+                .await;
+        }
+    };
+    route(stats, &request).write_to(&mut writer).await
+}
+
+/// Answer a metrics request
+pub fn route(stats: &StatsRegistry, request: &Request) -> Response {
+    let segments = request.segments();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["metrics"]) => Response {
+            content_type: "text/plain; version=0.0.4",
+            ..Response::text(200, render_prometheus(&stats.snapshot()))
+        },
+        ("GET", ["stats"]) => Response::json(200, &stats.snapshot()),
+        ("GET", ["top"]) => Response::json(200, &stats.monitor()),
+        (_, ["metrics" | "stats" | "top"]) => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, &format!("No endpoint at {}", request.path)),
+    }
+}
+
+/// `snapshot` in the Prometheus text exposition format
+pub fn render_prometheus(snapshot: &TrafficSnapshot) -> String {
+    let traffic = &snapshot.traffic;
+    let mut text = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+        let _ = write!(
+            text,
+            "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}\n"
+        );
+    };
+    metric(
+        "mate_connections_open",
+        "gauge",
+        "Peer connections currently open",
+        snapshot.open_connections.to_string(),
+    );
+    metric(
+        "mate_connections_total",
+        "counter",
+        "Peer connections opened",
+        snapshot.total_connections.to_string(),
+    );
+    metric(
+        "mate_messages_sent_total",
+        "counter",
+        "Messages sent to peers",
+        traffic.messages_sent.to_string(),
+    );
+    metric(
+        "mate_messages_received_total",
+        "counter",
+        "Messages received from peers",
+        traffic.messages_received.to_string(),
+    );
+    metric(
+        "mate_bytes_sent_total",
+        "counter",
+        "Envelope bytes sent to peers",
+        traffic.bytes_sent.to_string(),
+    );
+    metric(
+        "mate_bytes_received_total",
+        "counter",
+        "Envelope bytes received from peers",
+        traffic.bytes_received.to_string(),
+    );
+    metric(
+        "mate_retries_total",
+        "counter",
+        "Connection attempts retried",
+        traffic.retries.to_string(),
+    );
+    metric(
+        "mate_messages_rejected_total",
+        "counter",
+        "Messages refused as invalid or not allowed",
+        snapshot.rejected_messages.to_string(),
+    );
+    metric(
+        "mate_messages_oversized_total",
+        "counter",
+        "Frames refused for their size",
+        snapshot.oversized_messages.to_string(),
+    );
+    metric(
+        "mate_connections_refused_total",
+        "counter",
+        "Connections turned away at the connection or handshake limit",
+        snapshot.refused_connections.to_string(),
+    );
+    metric(
+        "mate_handshake_timeouts_total",
+        "counter",
+        "Connections closed for not completing the handshake in time",
+        snapshot.handshake_timeouts.to_string(),
+    );
+    metric(
+        "mate_messages_throttled_total",
+        "counter",
+        "Messages read late because their connection was over its quota",
+        snapshot.throttled_messages.to_string(),
+    );
+    metric(
+        "mate_last_activity_seconds",
+        "gauge",
+        "Unix time of the last message sent or received",
+        traffic.last_activity.unwrap_or(0).to_string(),
+    );
+    metric(
+        "mate_round_trip_avg_seconds",
+        "gauge",
+        "Mean time from sending a message to receiving the reply",
+        traffic
+            .avg_round_trip()
+            .map_or(0.0, |avg| avg.as_secs_f64())
+            .to_string(),
+    );
+    text
+}
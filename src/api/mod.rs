@@ -0,0 +1,8 @@
+pub mod http;
+pub mod metrics;
+pub mod pgn_feed;
+pub mod server;
+
+pub use metrics::MetricsServer;
+pub use pgn_feed::PgnFeedServer;
+pub use server::{generate_token, ApiServer};
@@ -0,0 +1,84 @@
+use crate::api::http::{read_request, Request, Response};
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tracing::{debug, info};
+
+/// How long a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Endpoint serving the PGN of the game being played or followed, started
+/// when `live_pgn.http_addr` is set
+///
+/// - `GET /` and `GET /game.pgn` return the latest PGN
+///
+/// Streaming tools such as OBS poll it; the PGN is public once played, so
+/// no token is required.
+pub struct PgnFeedServer {
+    listener: TcpListener,
+    pgn: watch::Receiver<String>,
+}
+
+impl PgnFeedServer {
+    /// Listen on `addr`, serving whatever was last sent on `pgn`
+    pub async fn bind(addr: &str, pgn: watch::Receiver<String>) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind the live PGN endpoint to {addr}"))?;
+        Ok(Self { listener, pgn })
+    }
+
+    /// The address the endpoint is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve requests until the task is cancelled
+    pub async fn run(self) -> Result<()> {
+        info!("Live PGN listening on {}", self.local_addr()?);
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let pgn = self.pgn.borrow().clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &pgn).await {
+                    debug!("Live PGN connection from {peer} failed: {e:#}");
+                }
+            });
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, pgn: &str) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut reader)).await {
+        Ok(Ok(Some(request))) => request,
+        Ok(Ok(None)) | Err(_) => return Ok(()),
+        Ok(Err(e)) => {
+            return Response::error(400, &format!("{e:#}"))
+                .write_to(&mut writer)
+                .await;
+        }
+    };
+    route(pgn, &request).write_to(&mut writer).await
+}
+
+/// Answer a live PGN request
+pub fn route(pgn: &str, request: &Request) -> Response {
+    let segments = request.segments();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", [] | ["game.pgn"]) => Response {
+            content_type: "application/x-chess-pgn",
+            ..Response::text(200, pgn)
+        }
+        // Browser sources in streaming software fetch from another origin
+        .with_header("Access-Control-Allow-Origin", "*")
+        .with_header("Cache-Control", "no-cache"),
+        (_, [] | ["game.pgn"]) => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, &format!("No endpoint at {}", request.path)),
+    }
+}
@@ -0,0 +1,409 @@
+use crate::api::http::{read_request, write_event, write_event_stream_head, Request, Response};
+use crate::chess::{Board, Color, Position};
+use crate::cli::game_ops::{GameOps, GameOpsError, MoveProcessingError, MoveProcessor};
+use crate::cli::App;
+use crate::storage::models::{GameResult, GameStatus};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_stream::StreamExt;
+use tracing::{debug, info, warn};
+
+/// How long a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often an idle event stream is sent a comment so proxies and clients
+/// keep it open
+const EVENT_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Query parameter accepted in place of the `Authorization` header, for
+/// clients such as browser `EventSource` that cannot set headers
+pub const TOKEN_QUERY_PARAMETER: &str = "access_token";
+
+/// A stored game as listed by the API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSummary {
+    pub id: String,
+    pub opponent: String,
+    /// Alias given to the opponent, if any
+    pub opponent_name: Option<String>,
+    pub color: String,
+    pub status: String,
+    pub result: Option<String>,
+    pub your_turn: bool,
+    pub move_count: u32,
+    pub last_move: Option<String>,
+}
+
+/// A game's position
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardState {
+    pub game_id: String,
+    pub fen: String,
+    /// `white` or `black`
+    pub to_move: String,
+    pub your_turn: bool,
+    /// Occupied squares and their pieces as FEN letters, e.g. `"e1": "K"`
+    pub squares: BTreeMap<String, String>,
+    /// Moves played so far in coordinate notation
+    pub moves: Vec<String>,
+}
+
+/// Body of `POST /games/{id}/moves`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveRequest {
+    /// Move in coordinate (`e2e4`) or algebraic (`Nf3`) notation
+    #[serde(rename = "move")]
+    pub chess_move: String,
+}
+
+/// Reply to a submitted move
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MovePlayed {
+    pub game_id: String,
+    #[serde(rename = "move")]
+    pub chess_move: String,
+    pub move_number: u32,
+    pub check: bool,
+    pub checkmate: bool,
+    /// Whether the opponent received the move; undelivered moves are queued
+    /// and resent
+    pub delivered: bool,
+}
+
+/// HTTP API over an [`App`]'s games, for web frontends
+///
+/// Every request must carry the token as `Authorization: Bearer <token>`
+/// (or `?access_token=` for event streams). Endpoints:
+///
+/// - `GET /games` lists games
+/// - `GET /games/{id}` and `GET /games/{id}/board` return the position as
+///   JSON, or as FEN text with `?format=fen`
+/// - `POST /games/{id}/moves` plays `{"move": "e2e4"}`
+/// - `GET /events` streams [`GameEvent`](crate::cli::events::GameEvent)s as
+///   server-sent events
+///
+/// Game IDs may be anything `mate` accepts on the command line: an ID
+/// prefix or an opponent alias.
+pub struct ApiServer {
+    listener: TcpListener,
+    app: Arc<App>,
+    token: Arc<str>,
+}
+
+impl ApiServer {
+    /// Listen on `addr`, accepting requests that present `token`
+    pub async fn bind(addr: &str, app: Arc<App>, token: String) -> Result<Self> {
+        if token.is_empty() {
+            anyhow::bail!("The API token must not be empty");
+        }
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind the HTTP API to {addr}"))?;
+        Ok(Self {
+            listener,
+            app,
+            token: token.into(),
+        })
+    }
+
+    /// The address the API is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve requests until the task is cancelled
+    pub async fn run(self) -> Result<()> {
+        info!("HTTP API listening on {}", self.local_addr()?);
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let app = Arc::clone(&self.app);
+            let token = Arc::clone(&self.token);
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &app, &token).await {
+                    debug!("HTTP API connection from {peer} failed: {e:#}");
+                }
+            });
+        }
+    }
+}
+
+/// A new random API token
+pub fn generate_token() -> String {
+    hex::encode(rand::random::<[u8; 32]>())
+}
+
+async fn handle_connection(stream: TcpStream, app: &App, token: &str) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut reader)).await {
+        Ok(Ok(Some(request))) => request,
+        Ok(Ok(None)) | Err(_) => return Ok(()),
+        Ok(Err(e)) => {
+            Response::error(400, &format!("{e:#}"))
+                .write_to(&mut writer)
+                .await?;
+            return Ok(());
+        }
+    };
+    debug!("HTTP API {} {}", request.method, request.path);
+
+    if !is_authorized(&request, token) {
+        return Response::error(401, "Missing or invalid API token")
+            .with_header("WWW-Authenticate", "Bearer")
+            .write_to(&mut writer)
+            .await;
+    }
+
+    if request.segments() == ["events"] {
+        if request.method != "GET" {
+            return method_not_allowed().write_to(&mut writer).await;
+        }
+        return stream_events(app, &mut writer).await;
+    }
+    route(app, &request).await.write_to(&mut writer).await
+}
+
+/// Whether the request presents `token`
+pub fn is_authorized(request: &Request, token: &str) -> bool {
+    let presented = request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| request.query.get(TOKEN_QUERY_PARAMETER).map(String::as_str));
+    presented.is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes()))
+}
+
+/// Compare without returning early, so response times do not reveal how
+/// much of a guessed token was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Answer any request except the event stream
+pub async fn route(app: &App, request: &Request) -> Response {
+    let segments = request.segments();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["games"]) => list_games(app),
+        ("GET", ["games", game]) | ("GET", ["games", game, "board"]) => {
+            board(app, game, request.query.get("format").map(String::as_str))
+        }
+        ("POST", ["games", game, "moves"]) => play_move(app, game, &request.body).await,
+        (_, ["games"]) | (_, ["games", _]) | (_, ["games", _, "board" | "moves"]) => {
+            method_not_allowed()
+        }
+        _ => Response::error(404, &format!("No endpoint at {}", request.path)),
+    }
+}
+
+fn method_not_allowed() -> Response {
+    Response::error(405, "Method not allowed")
+}
+
+fn list_games(app: &App) -> Response {
+    match GameOps::new(&app.database).list_games() {
+        Ok(records) => {
+            let games: Vec<GameSummary> = records
+                .into_iter()
+                .map(|record| GameSummary {
+                    opponent: record.game.opponent_peer_id.clone(),
+                    opponent_name: record.opponent_name,
+                    color: record.game.my_color.as_str().to_string(),
+                    status: record.game.status.as_str().to_string(),
+                    result: record.game.result.as_ref().map(|r| r.as_str().to_string()),
+                    your_turn: record.your_turn,
+                    move_count: record.move_count,
+                    last_move: record.last_move,
+                    id: record.game.id,
+                })
+                .collect();
+            Response::json(200, &games)
+        }
+        Err(e) => game_error(e),
+    }
+}
+
+fn board(app: &App, selector: &str, format: Option<&str>) -> Response {
+    let game_ops = GameOps::new(&app.database);
+    let state = match game_ops
+        .resolve_game(selector)
+        .and_then(|game| game_ops.reconstruct_game_state(&game.id))
+    {
+        Ok(state) => state,
+        Err(e) => return game_error(e),
+    };
+
+    match format {
+        Some("fen") => Response::text(200, state.board.to_fen()),
+        None | Some("json") => Response::json(
+            200,
+            &BoardState {
+                fen: state.board.to_fen(),
+                to_move: color_name(state.board.active_color()).to_string(),
+                your_turn: state.your_turn,
+                squares: squares(&state.board),
+                moves: state.move_history,
+                game_id: state.game.id,
+            },
+        ),
+        Some(other) => Response::error(400, &format!("Unknown format '{other}'; use json or fen")),
+    }
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+fn squares(board: &Board) -> BTreeMap<String, String> {
+    Position::all_positions()
+        .filter_map(|position| {
+            let piece = board.get_piece(position)?;
+            let letter = piece.piece_type.to_string();
+            let letter = match piece.color {
+                Color::White => letter,
+                Color::Black => letter.to_lowercase(),
+            };
+            Some((position.to_string(), letter))
+        })
+        .collect()
+}
+
+/// Play a move, record a finished game's result, and send the move on
+async fn play_move(app: &App, selector: &str, body: &[u8]) -> Response {
+    let request: MoveRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => return Response::error(400, &format!("Expected {{\"move\": \"e2e4\"}}: {e}")),
+    };
+    let game_ops = GameOps::new(&app.database);
+    let state = match game_ops
+        .resolve_game(selector)
+        .and_then(|game| game_ops.reconstruct_game_state(&game.id))
+    {
+        Ok(state) => state,
+        Err(e) => return game_error(e),
+    };
+    let game = state.game;
+    if game.status != GameStatus::Active {
+        return Response::error(
+            409,
+            &format!("Game {} is {}, not active", game.id, game.status.as_str()),
+        );
+    }
+
+    if !state.your_turn {
+        return Response::error(409, &format!("It's not your turn in game {}", game.id));
+    }
+
+    let coordinate = match state.board.parse_move(&request.chess_move) {
+        Ok(mv) => mv.to_string(),
+        Err(e) => return Response::error(422, &e.to_string()),
+    };
+    let result = match MoveProcessor::new(&app.database)
+        .with_signer(&app.identity)
+        .process_move(&game.id, &coordinate, true)
+    {
+        Ok(result) => result,
+        Err(e) => return move_error(e),
+    };
+
+    let board = &result.updated_board;
+    let game_result = if board.is_checkmate() {
+        Some(GameResult::Win)
+    } else if board.is_stalemate() {
+        Some(GameResult::Draw)
+    } else {
+        None
+    };
+    if let Some(game_result) = &game_result {
+        if let Err(e) = app
+            .database
+            .update_game_result(&game.id, game_result.clone())
+        {
+            return Response::error(500, &format!("Failed to record game result: {e}"));
+        }
+    }
+
+    let delivered = match app
+        .network_manager
+        .send_chess_move(&game.opponent_peer_id, game.id.clone(), result.move_message)
+        .await
+    {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("Could not send move in game {} to opponent: {e:#}", game.id);
+            false
+        }
+    };
+    if game_result.is_some() {
+        app.sign_game_result(&game.id).await;
+    } else {
+        app.update_arbiter(&game.id).await;
+    }
+
+    Response::json(
+        201,
+        &MovePlayed {
+            game_id: game.id,
+            chess_move: coordinate,
+            move_number: result.move_number,
+            check: result.is_check,
+            checkmate: result.is_checkmate,
+            delivered,
+        },
+    )
+}
+
+fn game_error(error: GameOpsError) -> Response {
+    let status = match &error {
+        GameOpsError::GameNotFound(_) | GameOpsError::NoCurrentGame => 404,
+        GameOpsError::Database(e) if e.to_string().contains("not found") => 404,
+        GameOpsError::AmbiguousGame { .. } => 409,
+        _ => 500,
+    };
+    Response::error(status, &error.to_string())
+}
+
+fn move_error(error: MoveProcessingError) -> Response {
+    let status = match error {
+        MoveProcessingError::GameOps(e) => return game_error(e),
+        MoveProcessingError::Chess(_) | MoveProcessingError::InvalidMove(_) => 422,
+        MoveProcessingError::InvalidGameState(_) => 409,
+        _ => 500,
+    };
+    Response::error(status, &error.to_string())
+}
+
+/// Forward the app's game events until the client goes away
+async fn stream_events<W>(app: &App, writer: &mut W) -> Result<()>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    write_event_stream_head(writer).await?;
+    let mut events = Box::pin(app.subscribe());
+    loop {
+        match tokio::time::timeout(EVENT_KEEPALIVE, events.next()).await {
+            Ok(Some(event)) => {
+                let value = serde_json::to_value(&event).context("Failed to serialize event")?;
+                let name = value
+                    .get("event")
+                    .and_then(|name| name.as_str())
+                    .unwrap_or("message")
+                    .to_string();
+                write_event(writer, &name, &value).await?;
+            }
+            Ok(None) => return Ok(()),
+            Err(_) => {
+                writer.write_all(b": keep-alive\n\n").await?;
+                writer.flush().await?;
+            }
+        }
+    }
+}
@@ -185,6 +185,11 @@ impl Board {
         self.active_color
     }
 
+    /// Hand the move to `color`, e.g. while one side plays extra moves
+    pub fn set_active_color(&mut self, color: Color) {
+        self.active_color = color;
+    }
+
     /// Get the current fullmove number
     pub fn fullmove_number(&self) -> u16 {
         self.fullmove_number
@@ -195,6 +200,16 @@ impl Board {
         self.halfmove_clock
     }
 
+    /// Get the current castling rights
+    pub fn castling_rights(&self) -> &CastlingRights {
+        &self.castling_rights
+    }
+
+    /// Get the en passant target square, if any
+    pub fn en_passant_target(&self) -> Option<Position> {
+        self.en_passant_target
+    }
+
     /// Set up the standard chess starting position
     fn setup_starting_position(&mut self) {
         // Clear the board first
@@ -438,7 +453,7 @@ impl Board {
         // Step 4.6: Parse En Passant Target (Field 4)
         if *en_passant != "-" {
             // Validate it's a valid square notation (e.g., "e3", "d6")
-            if en_passant.len() != 2 {
+            if en_passant.chars().count() != 2 {
                 return Err(ChessError::InvalidFen(format!(
                     "Invalid en passant target '{en_passant}' (must be 2 characters like 'e3' or '-' for none)"
                 )));
@@ -1018,6 +1033,51 @@ impl Board {
 
         hasher.finish()
     }
+
+    /// Generate a Zobrist hash identifying the position
+    ///
+    /// Unlike [`Board::hash_state`], the key only covers what makes two
+    /// positions equivalent for analysis purposes: piece placement, side to
+    /// move, castling rights, and the en passant file. Move counters are
+    /// ignored, so transpositions share a key. Keys are derived from a fixed
+    /// seed and are stable across runs, which makes them safe to persist.
+    pub fn zobrist_hash(&self) -> u64 {
+        let keys = super::zobrist::keys();
+        let mut hash = 0u64;
+
+        for (rank, row) in self.squares.iter().enumerate() {
+            for (file, square) in row.iter().enumerate() {
+                if let Some(piece) = square {
+                    hash ^= keys.piece(*piece, rank * 8 + file);
+                }
+            }
+        }
+
+        if self.active_color == Color::Black {
+            hash ^= keys.black_to_move;
+        }
+
+        let rights = &self.castling_rights;
+        for (enabled, key) in [
+            rights.white_kingside,
+            rights.white_queenside,
+            rights.black_kingside,
+            rights.black_queenside,
+        ]
+        .into_iter()
+        .zip(keys.castling)
+        {
+            if enabled {
+                hash ^= key;
+            }
+        }
+
+        if let Some(target) = self.en_passant_target {
+            hash ^= keys.en_passant[target.file as usize];
+        }
+
+        hash
+    }
 }
 
 impl Default for Board {
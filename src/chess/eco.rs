@@ -0,0 +1,125 @@
+/// A named opening from the Encyclopaedia of Chess Openings (ECO)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opening {
+    /// ECO code, a letter A-E followed by two digits
+    pub eco: &'static str,
+    pub name: &'static str,
+    /// Defining moves in SAN, separated by spaces
+    pub moves: &'static str,
+}
+
+/// Common openings; a game is classified by the longest line it follows
+pub const OPENINGS: &[Opening] = &[
+    opening("A01", "Nimzo-Larsen Attack", "b3"),
+    opening("A02", "Bird's Opening", "f4"),
+    opening("A04", "Réti Opening", "Nf3"),
+    opening("A10", "English Opening", "c4"),
+    opening("A20", "English Opening: King's English", "c4 e5"),
+    opening("A40", "Queen's Pawn Game", "d4"),
+    opening("A45", "Indian Defence", "d4 Nf6"),
+    opening("A56", "Benoni Defence", "d4 Nf6 c4 c5"),
+    opening("A57", "Benko Gambit", "d4 Nf6 c4 c5 d5 b5"),
+    opening("A80", "Dutch Defence", "d4 f5"),
+    opening("B00", "King's Pawn Opening", "e4"),
+    opening("B01", "Scandinavian Defence", "e4 d5"),
+    opening("B02", "Alekhine's Defence", "e4 Nf6"),
+    opening("B06", "Modern Defence", "e4 g6"),
+    opening("B07", "Pirc Defence", "e4 d6 d4 Nf6"),
+    opening("B10", "Caro-Kann Defence", "e4 c6"),
+    opening("B20", "Sicilian Defence", "e4 c5"),
+    opening("B22", "Sicilian Defence: Alapin Variation", "e4 c5 c3"),
+    opening("B23", "Sicilian Defence: Closed", "e4 c5 Nc3"),
+    opening("B30", "Sicilian Defence: Old Sicilian", "e4 c5 Nf3 Nc6"),
+    opening(
+        "B31",
+        "Sicilian Defence: Rossolimo Variation",
+        "e4 c5 Nf3 Nc6 Bb5",
+    ),
+    opening("B40", "Sicilian Defence: French Variation", "e4 c5 Nf3 e6"),
+    opening("B50", "Sicilian Defence: Modern Variations", "e4 c5 Nf3 d6"),
+    opening(
+        "B70",
+        "Sicilian Defence: Dragon Variation",
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 g6",
+    ),
+    opening(
+        "B90",
+        "Sicilian Defence: Najdorf Variation",
+        "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 a6",
+    ),
+    opening("C00", "French Defence", "e4 e6"),
+    opening("C20", "King's Pawn Game", "e4 e5"),
+    opening("C23", "Bishop's Opening", "e4 e5 Bc4"),
+    opening("C25", "Vienna Game", "e4 e5 Nc3"),
+    opening("C30", "King's Gambit", "e4 e5 f4"),
+    opening("C33", "King's Gambit Accepted", "e4 e5 f4 exf4"),
+    opening("C40", "King's Knight Opening", "e4 e5 Nf3"),
+    opening("C41", "Philidor Defence", "e4 e5 Nf3 d6"),
+    opening("C42", "Petrov's Defence", "e4 e5 Nf3 Nf6"),
+    opening("C45", "Scotch Game", "e4 e5 Nf3 Nc6 d4"),
+    opening("C47", "Four Knights Game", "e4 e5 Nf3 Nc6 Nc3 Nf6"),
+    opening("C50", "Italian Game", "e4 e5 Nf3 Nc6 Bc4"),
+    opening("C50", "Giuoco Piano", "e4 e5 Nf3 Nc6 Bc4 Bc5"),
+    opening("C51", "Evans Gambit", "e4 e5 Nf3 Nc6 Bc4 Bc5 b4"),
+    opening("C55", "Two Knights Defence", "e4 e5 Nf3 Nc6 Bc4 Nf6"),
+    opening("C60", "Ruy Lopez", "e4 e5 Nf3 Nc6 Bb5"),
+    opening("C65", "Ruy Lopez: Berlin Defence", "e4 e5 Nf3 Nc6 Bb5 Nf6"),
+    opening(
+        "C68",
+        "Ruy Lopez: Exchange Variation",
+        "e4 e5 Nf3 Nc6 Bb5 a6 Bxc6",
+    ),
+    opening(
+        "C70",
+        "Ruy Lopez: Morphy Defence",
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4",
+    ),
+    opening("D00", "Queen's Pawn Game", "d4 d5"),
+    opening("D06", "Queen's Gambit", "d4 d5 c4"),
+    opening("D10", "Slav Defence", "d4 d5 c4 c6"),
+    opening("D20", "Queen's Gambit Accepted", "d4 d5 c4 dxc4"),
+    opening("D30", "Queen's Gambit Declined", "d4 d5 c4 e6"),
+    opening("D43", "Semi-Slav Defence", "d4 d5 c4 c6 Nf3 Nf6 Nc3 e6"),
+    opening("D80", "Grünfeld Defence", "d4 Nf6 c4 g6 Nc3 d5"),
+    opening("E01", "Catalan Opening", "d4 Nf6 c4 e6 g3"),
+    opening("E12", "Queen's Indian Defence", "d4 Nf6 c4 e6 Nf3 b6"),
+    opening("E20", "Nimzo-Indian Defence", "d4 Nf6 c4 e6 Nc3 Bb4"),
+    opening("E60", "King's Indian Defence", "d4 Nf6 c4 g6"),
+];
+
+const fn opening(eco: &'static str, name: &'static str, moves: &'static str) -> Opening {
+    Opening { eco, name, moves }
+}
+
+impl Opening {
+    /// Number of half-moves that define this opening
+    pub fn plies(&self) -> usize {
+        self.moves.split_whitespace().count()
+    }
+}
+
+/// Most half-moves any opening in [`OPENINGS`] needs to be recognized
+pub fn max_opening_plies() -> usize {
+    OPENINGS.iter().map(Opening::plies).max().unwrap_or(0)
+}
+
+/// The most specific known opening that a game beginning with `san_moves` follows
+///
+/// Check and annotation suffixes ("+", "#", "!", "?") are ignored.
+pub fn classify_opening<S: AsRef<str>>(san_moves: &[S]) -> Option<&'static Opening> {
+    OPENINGS
+        .iter()
+        .filter(|opening| {
+            let defining: Vec<&str> = opening.moves.split_whitespace().collect();
+            defining.len() <= san_moves.len()
+                && defining
+                    .iter()
+                    .zip(san_moves)
+                    .all(|(expected, played)| *expected == strip_suffixes(played.as_ref()))
+        })
+        .max_by_key(|opening| opening.plies())
+}
+
+fn strip_suffixes(san: &str) -> &str {
+    san.trim_end_matches(['+', '#', '!', '?'])
+}
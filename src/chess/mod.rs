@@ -1,13 +1,19 @@
 // Re-export all public items
-pub use self::board::Board;
+pub use self::board::{Board, CastlingRights};
+pub use self::eco::{classify_opening, max_opening_plies, Opening, OPENINGS};
 pub use self::error::ChessError;
 pub use self::moves::Move;
+pub use self::odds::{Odds, MAX_EXTRA_MOVES};
 pub use self::piece::{Color, Piece, PieceType};
 pub use self::position::Position;
 
 // Define submodules
 mod board;
+mod eco;
 mod error;
+mod movegen;
 mod moves;
+mod odds;
 mod piece;
 mod position;
+mod zobrist;
@@ -0,0 +1,448 @@
+use super::{Board, ChessError, Color, Move, Piece, PieceType, Position};
+
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+const KING_OFFSETS: [(i8, i8); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+const PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+/// Square reached by moving `df` files and `dr` ranks, if it is on the board
+fn offset(pos: Position, df: i8, dr: i8) -> Option<Position> {
+    let file = pos.file as i8 + df;
+    let rank = pos.rank as i8 + dr;
+    if (0..8).contains(&file) && (0..8).contains(&rank) {
+        Some(Position::new_unchecked(file as u8, rank as u8))
+    } else {
+        None
+    }
+}
+
+/// Rank direction pawns of a color advance in
+fn pawn_direction(color: Color) -> i8 {
+    match color {
+        Color::White => 1,
+        Color::Black => -1,
+    }
+}
+
+impl Board {
+    /// Generate every legal move for the side to move
+    ///
+    /// Moves that would leave the mover's own king in check are excluded.
+    /// Promotions are listed once per promotion piece.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let color = self.active_color();
+        self.pseudo_legal_moves()
+            .into_iter()
+            .filter(|mv| {
+                let mut next = self.clone();
+                next.make_move(*mv).is_ok() && !next.is_in_check(color)
+            })
+            .collect()
+    }
+
+    /// Whether the king of `color` is currently attacked
+    pub fn is_in_check(&self, color: Color) -> bool {
+        self.find_king(color)
+            .is_some_and(|king| self.is_square_attacked(king, color.opposite()))
+    }
+
+    /// Locate the king of the given color
+    pub fn find_king(&self, color: Color) -> Option<Position> {
+        let king = Piece::new(PieceType::King, color);
+        (0..8)
+            .flat_map(|rank| (0..8).map(move |file| Position::new_unchecked(file, rank)))
+            .find(|&pos| self.get_piece(pos) == Some(king))
+    }
+
+    /// Whether the side to move is checkmated
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check(self.active_color()) && self.legal_moves().is_empty()
+    }
+
+    /// Whether the side to move is stalemated
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_in_check(self.active_color()) && self.legal_moves().is_empty()
+    }
+
+    /// Whether any piece of color `by` attacks `square`
+    pub fn is_square_attacked(&self, square: Position, by: Color) -> bool {
+        let holds = |pos: Option<Position>, types: &[PieceType]| {
+            pos.and_then(|pos| self.get_piece(pos))
+                .is_some_and(|piece| piece.color == by && types.contains(&piece.piece_type))
+        };
+
+        // Pawns attack diagonally forward, so look one rank "behind" the square
+        let pawn_rank = -pawn_direction(by);
+        if [-1, 1]
+            .iter()
+            .any(|&df| holds(offset(square, df, pawn_rank), &[PieceType::Pawn]))
+        {
+            return true;
+        }
+
+        if KNIGHT_OFFSETS
+            .iter()
+            .any(|&(df, dr)| holds(offset(square, df, dr), &[PieceType::Knight]))
+        {
+            return true;
+        }
+
+        if KING_OFFSETS
+            .iter()
+            .any(|&(df, dr)| holds(offset(square, df, dr), &[PieceType::King]))
+        {
+            return true;
+        }
+
+        let slides = |directions: &[(i8, i8)], types: &[PieceType]| {
+            directions.iter().any(|&(df, dr)| {
+                let mut pos = offset(square, df, dr);
+                while let Some(current) = pos {
+                    if let Some(piece) = self.get_piece(current) {
+                        return piece.color == by && types.contains(&piece.piece_type);
+                    }
+                    pos = offset(current, df, dr);
+                }
+                false
+            })
+        };
+
+        slides(&ROOK_DIRECTIONS, &[PieceType::Rook, PieceType::Queen])
+            || slides(&BISHOP_DIRECTIONS, &[PieceType::Bishop, PieceType::Queen])
+    }
+
+    /// Parse a move in coordinate ("e2e4", "e7e8q") or SAN ("Nf3", "exd5",
+    /// "O-O", "e8=Q+") notation and check that it is legal in this position
+    pub fn parse_move(&self, input: &str) -> Result<Move, ChessError> {
+        let input = input.trim();
+        let legal = self.legal_moves();
+
+        // Check and annotation suffixes carry no information for matching
+        let stripped = input.trim_end_matches(['+', '#', '!', '?']);
+        if let Ok(mv) = Move::from_str_with_color(stripped, self.active_color()) {
+            return if legal.contains(&mv) {
+                Ok(mv)
+            } else {
+                Err(ChessError::InvalidMove(format!(
+                    "'{input}' is not a legal move in this position"
+                )))
+            };
+        }
+
+        self.parse_san_with(input, stripped, &legal)
+    }
+
+    /// Standard algebraic notation for a legal move ("Nf3", "exd5", "O-O", "e8=Q+")
+    ///
+    /// The origin file or rank is added only when another piece of the same
+    /// type could also reach the target square.
+    pub fn to_san(&self, mv: Move) -> String {
+        let Some(piece) = self.get_piece(mv.from) else {
+            return mv.to_string();
+        };
+
+        let mut san = if piece.piece_type == PieceType::King && mv.is_castling() {
+            if mv.to.file > mv.from.file {
+                "O-O".to_string()
+            } else {
+                "O-O-O".to_string()
+            }
+        } else {
+            // A pawn changing files always captures, including en passant
+            let capture = self.get_piece(mv.to).is_some()
+                || (piece.piece_type == PieceType::Pawn && mv.from.file != mv.to.file);
+            let mut san = String::new();
+
+            if piece.piece_type == PieceType::Pawn {
+                if capture {
+                    san.push((b'a' + mv.from.file) as char);
+                }
+            } else {
+                san.push_str(&piece.piece_type.to_string());
+                let rivals: Vec<Move> = self
+                    .legal_moves()
+                    .into_iter()
+                    .filter(|other| {
+                        other.to == mv.to
+                            && other.from != mv.from
+                            && self.get_piece(other.from) == Some(piece)
+                    })
+                    .collect();
+                if !rivals.is_empty() {
+                    if rivals.iter().all(|other| other.from.file != mv.from.file) {
+                        san.push((b'a' + mv.from.file) as char);
+                    } else if rivals.iter().all(|other| other.from.rank != mv.from.rank) {
+                        san.push((b'1' + mv.from.rank) as char);
+                    } else {
+                        san.push_str(&mv.from.to_string());
+                    }
+                }
+            }
+
+            if capture {
+                san.push('x');
+            }
+            san.push_str(&mv.to.to_string());
+            if let Some(promotion) = mv.promotion {
+                san.push('=');
+                san.push_str(&promotion.to_string());
+            }
+            san
+        };
+
+        let mut next = self.clone();
+        if next.make_move(mv).is_ok() && next.is_in_check(next.active_color()) {
+            san.push(if next.legal_moves().is_empty() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+        san
+    }
+
+    /// Resolve a SAN move against the precomputed legal moves
+    fn parse_san_with(&self, input: &str, san: &str, legal: &[Move]) -> Result<Move, ChessError> {
+        let invalid = || {
+            ChessError::InvalidMove(format!(
+                "Invalid move '{input}'. Use SAN (e.g. 'Nf3', 'exd5', 'O-O') or coordinates (e.g. 'e2e4')"
+            ))
+        };
+
+        // Trailing promotion piece, with or without '='
+        let (body, promotion) = match san.char_indices().last() {
+            Some((i, c)) if "QRBNqrbn".contains(c) && i >= 2 => {
+                let promotion = c.to_string().parse::<PieceType>().map_err(|_| invalid())?;
+                (san[..i].trim_end_matches('='), Some(promotion))
+            }
+            _ => (san, None),
+        };
+
+        if body.len() < 2 || !body.is_ascii() {
+            return Err(invalid());
+        }
+        let target: Position = body[body.len() - 2..].parse().map_err(|_| invalid())?;
+
+        let mut prefix = &body[..body.len() - 2];
+        let piece_type = match prefix.chars().next() {
+            Some(c @ ('K' | 'Q' | 'R' | 'B' | 'N')) => {
+                prefix = &prefix[1..];
+                c.to_string().parse::<PieceType>().map_err(|_| invalid())?
+            }
+            _ => PieceType::Pawn,
+        };
+
+        // Whatever remains is an optional capture marker and disambiguation
+        let mut from_file = None;
+        let mut from_rank = None;
+        for c in prefix.chars() {
+            match c {
+                'a'..='h' => from_file = Some(c as u8 - b'a'),
+                '1'..='8' => from_rank = Some(c as u8 - b'1'),
+                'x' | ':' => {}
+                _ => return Err(invalid()),
+            }
+        }
+
+        let candidates: Vec<Move> = legal
+            .iter()
+            .copied()
+            .filter(|mv| {
+                mv.to == target
+                    && mv.promotion == promotion
+                    && from_file.is_none_or(|file| mv.from.file == file)
+                    && from_rank.is_none_or(|rank| mv.from.rank == rank)
+                    && self
+                        .get_piece(mv.from)
+                        .is_some_and(|piece| piece.piece_type == piece_type)
+            })
+            .collect();
+
+        match candidates.as_slice() {
+            [mv] => Ok(*mv),
+            [] => Err(ChessError::InvalidMove(format!(
+                "'{input}' is not a legal move in this position"
+            ))),
+            _ => Err(ChessError::InvalidMove(format!(
+                "'{input}' is ambiguous; add the origin file or rank (e.g. 'Nbd2')"
+            ))),
+        }
+    }
+
+    /// Generate moves that follow piece movement rules, ignoring king safety
+    fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let color = self.active_color();
+        let mut moves = Vec::new();
+
+        for rank in 0..8 {
+            for file in 0..8 {
+                let from = Position::new_unchecked(file, rank);
+                let Some(piece) = self.get_piece(from) else {
+                    continue;
+                };
+                if piece.color != color {
+                    continue;
+                }
+
+                match piece.piece_type {
+                    PieceType::Pawn => self.pawn_moves(from, color, &mut moves),
+                    PieceType::Knight => self.step_moves(from, color, &KNIGHT_OFFSETS, &mut moves),
+                    PieceType::Bishop => {
+                        self.slide_moves(from, color, &BISHOP_DIRECTIONS, &mut moves)
+                    }
+                    PieceType::Rook => self.slide_moves(from, color, &ROOK_DIRECTIONS, &mut moves),
+                    PieceType::Queen => {
+                        self.slide_moves(from, color, &ROOK_DIRECTIONS, &mut moves);
+                        self.slide_moves(from, color, &BISHOP_DIRECTIONS, &mut moves);
+                    }
+                    PieceType::King => {
+                        self.step_moves(from, color, &KING_OFFSETS, &mut moves);
+                        self.castling_moves(from, color, &mut moves);
+                    }
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// Whether `pos` is empty or holds an opponent piece
+    fn can_land_on(&self, pos: Position, color: Color) -> bool {
+        self.get_piece(pos).is_none_or(|piece| piece.color != color)
+    }
+
+    fn pawn_moves(&self, from: Position, color: Color, moves: &mut Vec<Move>) {
+        let direction = pawn_direction(color);
+        let (start_rank, last_rank) = match color {
+            Color::White => (1, 7),
+            Color::Black => (6, 0),
+        };
+
+        let push = |to: Position, moves: &mut Vec<Move>| {
+            if to.rank == last_rank {
+                for promotion in PROMOTION_PIECES {
+                    moves.push(Move::new_unchecked(from, to, Some(promotion)));
+                }
+            } else {
+                moves.push(Move::new_unchecked(from, to, None));
+            }
+        };
+
+        if let Some(one) = offset(from, 0, direction) {
+            if self.get_piece(one).is_none() {
+                push(one, moves);
+                if from.rank == start_rank {
+                    if let Some(two) = offset(from, 0, 2 * direction) {
+                        if self.get_piece(two).is_none() {
+                            push(two, moves);
+                        }
+                    }
+                }
+            }
+        }
+
+        for df in [-1, 1] {
+            if let Some(to) = offset(from, df, direction) {
+                let captures = self.get_piece(to).is_some_and(|piece| piece.color != color);
+                if captures || self.en_passant_target() == Some(to) {
+                    push(to, moves);
+                }
+            }
+        }
+    }
+
+    fn step_moves(
+        &self,
+        from: Position,
+        color: Color,
+        offsets: &[(i8, i8)],
+        moves: &mut Vec<Move>,
+    ) {
+        for &(df, dr) in offsets {
+            if let Some(to) = offset(from, df, dr) {
+                if self.can_land_on(to, color) {
+                    moves.push(Move::new_unchecked(from, to, None));
+                }
+            }
+        }
+    }
+
+    fn slide_moves(
+        &self,
+        from: Position,
+        color: Color,
+        directions: &[(i8, i8)],
+        moves: &mut Vec<Move>,
+    ) {
+        for &(df, dr) in directions {
+            let mut pos = offset(from, df, dr);
+            while let Some(to) = pos {
+                match self.get_piece(to) {
+                    None => moves.push(Move::new_unchecked(from, to, None)),
+                    Some(piece) => {
+                        if piece.color != color {
+                            moves.push(Move::new_unchecked(from, to, None));
+                        }
+                        break;
+                    }
+                }
+                pos = offset(to, df, dr);
+            }
+        }
+    }
+
+    fn castling_moves(&self, from: Position, color: Color, moves: &mut Vec<Move>) {
+        let home_rank = match color {
+            Color::White => 0,
+            Color::Black => 7,
+        };
+        if from != Position::new_unchecked(4, home_rank) || self.is_in_check(color) {
+            return;
+        }
+
+        let rights = self.castling_rights();
+        let (kingside, queenside) = match color {
+            Color::White => (rights.white_kingside, rights.white_queenside),
+            Color::Black => (rights.black_kingside, rights.black_queenside),
+        };
+        let rook = Some(Piece::new(PieceType::Rook, color));
+        let square = |file: u8| Position::new_unchecked(file, home_rank);
+        let empty = |files: &[u8]| files.iter().all(|&f| self.get_piece(square(f)).is_none());
+        let safe = |file: u8| !self.is_square_attacked(square(file), color.opposite());
+
+        if kingside && self.get_piece(square(7)) == rook && empty(&[5, 6]) && safe(5) {
+            moves.push(Move::new_unchecked(from, square(6), None));
+        }
+        if queenside && self.get_piece(square(0)) == rook && empty(&[1, 2, 3]) && safe(3) {
+            moves.push(Move::new_unchecked(from, square(2), None));
+        }
+    }
+}
@@ -156,6 +156,13 @@ impl Move {
             _ => {} // Continue with standard parsing
         }
 
+        // Squares are sliced out by byte, so anything else is rejected first
+        if !s.is_ascii() {
+            return Err(ChessError::InvalidMove(format!(
+                "Invalid move format '{s}'. Moves are written in ASCII, e.g. 'e2e4'."
+            )));
+        }
+
         // Basic move format (e2e4)
         if s.len() == 4 {
             let from_str = &s[0..2];
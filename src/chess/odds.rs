@@ -0,0 +1,163 @@
+use super::{Board, ChessError, Color, Move, PieceType, Position};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Most extra moves one side may be given
+pub const MAX_EXTRA_MOVES: u8 = 3;
+
+/// Material and tempo odds one player gives the other
+///
+/// The giver starts without the pieces on `removed`, and the receiver plays
+/// `extra_moves` moves in a row before normal play starts with White.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Odds {
+    /// The player giving odds
+    pub giver: Color,
+    /// Starting squares of the giver's pieces that are left off the board
+    pub removed: Vec<Position>,
+    /// Moves the receiver plays before normal play starts
+    pub extra_moves: u8,
+}
+
+impl Odds {
+    /// The player receiving odds
+    pub fn receiver(&self) -> Color {
+        self.giver.opposite()
+    }
+
+    /// Check that the odds describe a playable game
+    ///
+    /// Each removed square must hold one of the giver's pieces other than the
+    /// king in the starting position, and something must actually be given.
+    pub fn validate(&self) -> Result<(), ChessError> {
+        if self.removed.is_empty() && self.extra_moves == 0 {
+            return Err(ChessError::BoardStateError(
+                "Odds must remove a piece or give extra moves".to_string(),
+            ));
+        }
+        if self.extra_moves > MAX_EXTRA_MOVES {
+            return Err(ChessError::BoardStateError(format!(
+                "At most {MAX_EXTRA_MOVES} extra moves can be given, not {}",
+                self.extra_moves
+            )));
+        }
+
+        let start = Board::new();
+        for (index, square) in self.removed.iter().enumerate() {
+            if self.removed[..index].contains(square) {
+                return Err(ChessError::InvalidPosition(format!(
+                    "{square} is removed twice"
+                )));
+            }
+            match start.get_piece(*square) {
+                Some(piece) if piece.color == self.giver && piece.piece_type != PieceType::King => {
+                }
+                Some(piece) if piece.piece_type == PieceType::King => {
+                    return Err(ChessError::InvalidPosition(format!(
+                        "The king on {square} cannot be removed"
+                    )));
+                }
+                _ => {
+                    return Err(ChessError::InvalidPosition(format!(
+                        "{square} does not hold a {} piece at the start",
+                        self.giver
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The position a game starts from, the standard one without odds
+    pub fn starting_board(odds: Option<&Self>) -> Result<Board, ChessError> {
+        let Some(odds) = odds else {
+            return Ok(Board::new());
+        };
+        odds.validate()?;
+
+        let mut board = Board::new();
+        for square in &odds.removed {
+            board.set_piece(*square, None)?;
+        }
+
+        // Castling stays available only on sides that keep their rook
+        let fen = board.to_fen();
+        let placement = fen.split(' ').next().unwrap_or_default();
+        let castling: String = [('K', "h1"), ('Q', "a1"), ('k', "h8"), ('q', "a8")]
+            .into_iter()
+            .filter(|(_, rook)| {
+                rook.parse::<Position>()
+                    .is_ok_and(|square| !odds.removed.contains(&square))
+            })
+            .map(|(right, _)| right)
+            .collect();
+        let castling = if castling.is_empty() {
+            "-".to_string()
+        } else {
+            castling
+        };
+        let to_move = match Self::side_to_move(Some(odds), 0) {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+        Board::from_fen(&format!("{placement} {to_move} {castling} - 0 1"))
+    }
+
+    /// Play the move numbered `ply` (counting from 0) in a game at these
+    /// odds, or in a standard game without them
+    ///
+    /// While the receiver is playing extra moves the turn stays with them, and
+    /// those moves may not give check.
+    pub fn make_move(
+        odds: Option<&Self>,
+        board: &mut Board,
+        ply: usize,
+        mv: Move,
+    ) -> Result<(), ChessError> {
+        board.make_move(mv)?;
+        let Some(odds) = odds else {
+            return Ok(());
+        };
+        if ply < odds.extra_moves as usize {
+            if board.is_in_check(odds.giver) {
+                return Err(ChessError::InvalidMove(format!(
+                    "Extra move {mv} may not give check"
+                )));
+            }
+            board.set_active_color(Self::side_to_move(Some(odds), ply + 1));
+        }
+        Ok(())
+    }
+
+    /// The side to move after `ply` moves of a game at these odds, or of a
+    /// standard game without them
+    pub fn side_to_move(odds: Option<&Self>, ply: usize) -> Color {
+        let extra = odds.map_or(0, |odds| odds.extra_moves as usize);
+        match odds {
+            Some(odds) if ply < extra => odds.receiver(),
+            _ if (ply - extra).is_multiple_of(2) => Color::White,
+            _ => Color::Black,
+        }
+    }
+}
+
+impl fmt::Display for Odds {
+    /// e.g. "White gives Nb1 Ra1 + 1 move"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} gives", self.giver)?;
+        let start = Board::new();
+        for square in &self.removed {
+            match start.get_piece(*square) {
+                Some(piece) => write!(f, " {}{square}", piece.piece_type)?,
+                None => write!(f, " {square}")?,
+            }
+        }
+        match self.extra_moves {
+            0 => Ok(()),
+            1 if self.removed.is_empty() => write!(f, " 1 move"),
+            1 => write!(f, " + 1 move"),
+            n if self.removed.is_empty() => write!(f, " {n} moves"),
+            n => write!(f, " + {n} moves"),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::error::ChessError;
+#[cfg(feature = "native")]
 use crate::storage::models::PlayerColor;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -31,6 +32,7 @@ impl fmt::Display for Color {
 }
 
 // Implement conversion from/to storage PlayerColor
+#[cfg(feature = "native")]
 impl From<PlayerColor> for Color {
     fn from(color: PlayerColor) -> Self {
         match color {
@@ -40,6 +42,7 @@ impl From<PlayerColor> for Color {
     }
 }
 
+#[cfg(feature = "native")]
 impl From<Color> for PlayerColor {
     fn from(color: Color) -> Self {
         match color {
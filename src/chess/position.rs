@@ -106,16 +106,12 @@ impl FromStr for Position {
     type Err = ChessError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 2 {
-            return Err(ChessError::InvalidPosition(format!(
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(file_char), Some(rank_char), None) => Self::from_chars(file_char, rank_char),
+            _ => Err(ChessError::InvalidPosition(format!(
                 "Position must be exactly 2 characters (e.g., 'e4'), got '{s}'"
-            )));
+            ))),
         }
-
-        let mut chars = s.chars();
-        let file_char = chars.next().unwrap();
-        let rank_char = chars.next().unwrap();
-
-        Self::from_chars(file_char, rank_char)
     }
 }
@@ -0,0 +1,74 @@
+use super::{Color, Piece, PieceType};
+use std::sync::OnceLock;
+
+/// Fixed seed so position keys are stable across runs, builds, and machines
+const ZOBRIST_SEED: u64 = 0x6d61_7465_5a6f_6272;
+
+/// Random keys used to build Zobrist position hashes
+pub(crate) struct ZobristKeys {
+    /// One key per piece kind (6 types x 2 colors) per square
+    pub pieces: [[u64; 64]; 12],
+    /// Toggled when Black is to move
+    pub black_to_move: u64,
+    /// White kingside, white queenside, black kingside, black queenside
+    pub castling: [u64; 4],
+    /// One key per en passant file
+    pub en_passant: [u64; 8],
+}
+
+impl ZobristKeys {
+    /// Key for a piece standing on a square (index = rank * 8 + file)
+    pub fn piece(&self, piece: Piece, square: usize) -> u64 {
+        let type_index = match piece.piece_type {
+            PieceType::Pawn => 0,
+            PieceType::Knight => 1,
+            PieceType::Bishop => 2,
+            PieceType::Rook => 3,
+            PieceType::Queen => 4,
+            PieceType::King => 5,
+        };
+        let color_offset = match piece.color {
+            Color::White => 0,
+            Color::Black => 6,
+        };
+        self.pieces[type_index + color_offset][square]
+    }
+}
+
+/// Get the process-wide Zobrist key table
+pub(crate) fn keys() -> &'static ZobristKeys {
+    static KEYS: OnceLock<ZobristKeys> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let mut state = ZOBRIST_SEED;
+        let mut next = || splitmix64(&mut state);
+
+        let mut pieces = [[0u64; 64]; 12];
+        for piece_keys in pieces.iter_mut() {
+            for key in piece_keys.iter_mut() {
+                *key = next();
+            }
+        }
+        let black_to_move = next();
+        let castling = [next(), next(), next(), next()];
+        let mut en_passant = [0u64; 8];
+        for key in en_passant.iter_mut() {
+            *key = next();
+        }
+
+        ZobristKeys {
+            pieces,
+            black_to_move,
+            castling,
+            en_passant,
+        }
+    })
+}
+
+/// SplitMix64 generator step, used only to derive the key table
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
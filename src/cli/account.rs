@@ -0,0 +1,394 @@
+use crate::cli::app::{App, Config};
+use crate::cli::display::{confirm, Confirmation, OutputFormat};
+use crate::cli::progress::{terminal_progress, ProgressCallback, ProgressEvent};
+use crate::crypto::storage::{active_key_path_in, key_path_in, list_identities};
+use crate::crypto::{EncryptedData, Identity};
+use crate::network::known_peers::{KnownPeer, KnownPeers, KNOWN_PEERS_FILE};
+use crate::storage::account::{AccountData, ImportSummary};
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Format identifier written into every account archive
+pub const ACCOUNT_ARCHIVE_FORMAT: &str = "mate-account";
+
+/// Current account archive version
+pub const ACCOUNT_ARCHIVE_VERSION: u32 = 1;
+
+/// Environment variable consulted before prompting for a passphrase
+pub const PASSPHRASE_ENV_VAR: &str = "MATE_PASSPHRASE";
+
+/// Portable bundle of everything needed to move an account to another machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountArchive {
+    pub format: String,
+    pub version: u32,
+    pub created_at: i64,
+    pub peer_id: String,
+    /// Identity secret key, encrypted with the export passphrase
+    pub identity: EncryptedData,
+    /// Settings without the API token, webhook secret, and webhook URLs,
+    /// which stay on the machine they were configured on
+    pub settings: Config,
+    pub data: AccountData,
+    /// Peer keys pinned by address, as in `known_peers.json`; missing from
+    /// archives made before pins were exported
+    #[serde(default)]
+    pub known_peers: BTreeMap<String, KnownPeer>,
+    /// The other identities in the data directory by name, each encrypted
+    /// like `identity`; missing from archives made before they were exported
+    #[serde(default)]
+    pub other_identities: BTreeMap<String, EncryptedData>,
+}
+
+impl AccountArchive {
+    /// Build an archive from an identity, its settings, and the stored account data
+    pub fn create(
+        identity: &Identity,
+        settings: &Config,
+        data: AccountData,
+        passphrase: &str,
+    ) -> Result<Self> {
+        let identity_blob = EncryptedData::encrypt(&identity.to_secret_bytes()?, passphrase)
+            .context("Failed to encrypt identity key")?;
+
+        Ok(Self {
+            format: ACCOUNT_ARCHIVE_FORMAT.to_string(),
+            version: ACCOUNT_ARCHIVE_VERSION,
+            created_at: Database::current_timestamp(),
+            peer_id: identity.peer_id().to_string(),
+            identity: identity_blob,
+            settings: without_secrets(settings),
+            data,
+            known_peers: BTreeMap::new(),
+            other_identities: BTreeMap::new(),
+        })
+    }
+
+    /// Add an identity other than the archived account's under its name
+    pub fn add_identity(
+        &mut self,
+        name: &str,
+        identity: &Identity,
+        passphrase: &str,
+    ) -> Result<()> {
+        let blob = EncryptedData::encrypt(&identity.to_secret_bytes()?, passphrase)
+            .with_context(|| format!("Failed to encrypt identity '{name}'"))?;
+        self.other_identities.insert(name.to_string(), blob);
+        Ok(())
+    }
+
+    /// Read and validate an archive file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read account archive {}", path.display()))?;
+        let archive: AccountArchive =
+            serde_json::from_str(&content).context("Failed to parse account archive")?;
+
+        if archive.format != ACCOUNT_ARCHIVE_FORMAT {
+            anyhow::bail!("Not a mate account archive (format: {})", archive.format);
+        }
+        if archive.version > ACCOUNT_ARCHIVE_VERSION {
+            anyhow::bail!(
+                "Account archive version {} is newer than supported version {}",
+                archive.version,
+                ACCOUNT_ARCHIVE_VERSION
+            );
+        }
+
+        Ok(archive)
+    }
+
+    /// Write the archive to a file only its owner can read
+    pub fn save(&self, path: &Path) -> Result<()> {
+        use std::io::Write;
+
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize account archive")?;
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(path)
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .with_context(|| format!("Failed to write account archive {}", path.display()))
+    }
+
+    /// Decrypt the identity contained in the archive
+    pub fn decrypt_identity(&self, passphrase: &str) -> Result<Identity> {
+        let secret = self.identity.decrypt(passphrase)?;
+        let identity = Identity::from_secret_bytes(&secret)?;
+
+        if identity.peer_id().as_str() != self.peer_id {
+            anyhow::bail!("Archive identity does not match recorded peer ID");
+        }
+
+        Ok(identity)
+    }
+}
+
+/// `settings` without the credentials an account archive leaves out
+fn without_secrets(settings: &Config) -> Config {
+    let mut settings = settings.clone();
+    settings.api.token = None;
+    settings.webhooks.secret = None;
+    settings.webhooks.urls.clear();
+    settings
+}
+
+/// Read a passphrase from the environment or prompt for it on the terminal
+///
+/// When `confirm` is set the passphrase must be entered twice.
+pub fn read_passphrase(prompt: &str, confirm: bool) -> Result<String> {
+    read_passphrase_from_env(PASSPHRASE_ENV_VAR, prompt, confirm)
+}
+
+/// Read a passphrase from `env_var` or prompt for it on the terminal
+pub fn read_passphrase_from_env(env_var: &str, prompt: &str, confirm: bool) -> Result<String> {
+    if let Ok(passphrase) = std::env::var(env_var) {
+        return Ok(passphrase);
+    }
+
+    let passphrase = rpassword::prompt_password(prompt).context("Failed to read passphrase")?;
+    if passphrase.is_empty() {
+        anyhow::bail!("Passphrase cannot be empty");
+    }
+
+    if confirm {
+        let repeated = rpassword::prompt_password("Confirm passphrase: ")
+            .context("Failed to read passphrase")?;
+        if repeated != passphrase {
+            anyhow::bail!("Passphrases do not match");
+        }
+    }
+
+    Ok(passphrase)
+}
+
+/// Read a recovery phrase without echoing it, or from standard input when
+/// it is not a terminal
+pub fn read_recovery_phrase() -> Result<String> {
+    use std::io::{BufRead, IsTerminal};
+
+    let phrase = if std::io::stdin().is_terminal() {
+        rpassword::prompt_password("Recovery phrase: ").context("Failed to read recovery phrase")?
+    } else {
+        let mut line = String::new();
+        std::io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .context("Failed to read recovery phrase from stdin")?;
+        line
+    };
+    if phrase.trim().is_empty() {
+        anyhow::bail!("Recovery phrase cannot be empty");
+    }
+    Ok(phrase)
+}
+
+/// Export the account of a running app to an archive file
+pub fn export_account(app: &App, path: &Path, passphrase: &str) -> Result<AccountArchive> {
+    let data = app
+        .database
+        .export_account_data()
+        .context("Failed to read account data from database")?;
+    let mut archive = AccountArchive::create(&app.identity, &app.config, data, passphrase)?;
+    archive.known_peers = KnownPeers::load(&app.config.data_dir.join(KNOWN_PEERS_FILE))?
+        .iter()
+        .map(|(address, pin)| (address.clone(), pin.clone()))
+        .collect();
+
+    // Keys on a hardware token cannot leave it
+    let active_key_path = active_key_path_in(&app.config.data_dir);
+    for name in list_identities(&app.config.data_dir) {
+        let key_path = key_path_in(&app.config.data_dir, &name)?;
+        if key_path == active_key_path || Identity::is_hardware_at(&key_path)? {
+            continue;
+        }
+        let identity = Identity::from_storage_path(&key_path)
+            .with_context(|| format!("Failed to load identity '{name}'"))?;
+        archive.add_identity(&name, &identity, passphrase)?;
+    }
+    archive.save(path)?;
+    Ok(archive)
+}
+
+/// Import an archive into the given data directory
+///
+/// The identity is written to `data_dir/identity.key`; an existing, different
+/// identity is only replaced when `force` is set. Settings are applied
+/// separately by the caller.
+pub fn import_account(
+    archive: &AccountArchive,
+    passphrase: &str,
+    data_dir: &Path,
+    db_path: &Path,
+    force: bool,
+) -> Result<ImportSummary> {
+    import_account_with_progress(archive, passphrase, data_dir, db_path, force, None)
+}
+
+/// Import an archive, reporting how many records have been written to `progress`
+pub fn import_account_with_progress(
+    archive: &AccountArchive,
+    passphrase: &str,
+    data_dir: &Path,
+    db_path: &Path,
+    force: bool,
+    progress: Option<ProgressCallback>,
+) -> Result<ImportSummary> {
+    let identity = archive.decrypt_identity(passphrase)?;
+
+    let identity_path = active_key_path_in(data_dir);
+    let same_identity = Identity::stored_peer_id(&identity_path)
+        .is_ok_and(|existing| &existing == identity.peer_id());
+    if identity_path.exists() && !same_identity && !force {
+        anyhow::bail!(
+            "A different identity already exists at {}. Use --force to replace it",
+            identity_path.display()
+        );
+    }
+
+    App::ensure_data_dir(&data_dir.to_path_buf()).context("Failed to prepare data directory")?;
+    // Rewriting the same identity would drop any passphrase protection
+    if !same_identity {
+        identity
+            .save_to_storage_path(&identity_path)
+            .context("Failed to save imported identity")?;
+    }
+
+    let database = Database::new_with_path(identity.peer_id().as_str(), db_path)
+        .context("Failed to open database")?;
+    let label = format!("Importing {}", archive.peer_id);
+    let summary = database
+        .import_account_data_with_progress(&archive.data, |done, total| {
+            if let Some(progress) = &progress {
+                progress(&ProgressEvent::Advanced {
+                    label: label.clone(),
+                    done: done as u64,
+                    total: total as u64,
+                });
+            }
+        })
+        .context("Failed to import account data");
+    if let Some(progress) = &progress {
+        progress(&ProgressEvent::Finished);
+    }
+    let mut summary = summary?;
+
+    // Identities that already have a key here keep it
+    for (name, blob) in &archive.other_identities {
+        let key_path = key_path_in(data_dir, name)?;
+        if key_path.exists() {
+            continue;
+        }
+        let secret = blob
+            .decrypt(passphrase)
+            .with_context(|| format!("Failed to decrypt identity '{name}'"))?;
+        Identity::from_secret_bytes(&secret)?
+            .save_to_storage_path(&key_path)
+            .with_context(|| format!("Failed to save imported identity '{name}'"))?;
+        summary.identities += 1;
+    }
+
+    // Keys pinned here are kept over the archived ones
+    let mut known_peers = KnownPeers::load(&data_dir.join(KNOWN_PEERS_FILE))?;
+    summary.pins = known_peers.merge(&archive.known_peers);
+    if summary.pins > 0 {
+        known_peers.save()?;
+    }
+    Ok(summary)
+}
+
+/// Handle the 'import-account' command
+///
+/// Runs without an initialized [`App`] so that no fresh identity is generated
+/// before the archived one is restored.
+///
+/// `db_path` overrides the configured database location, as `--db-path` does
+/// for other commands.
+pub fn handle_import_account(
+    file: &Path,
+    force: bool,
+    db_path: Option<PathBuf>,
+    yes: bool,
+) -> Result<()> {
+    let archive = AccountArchive::load(file)?;
+    let config = Config::load_or_create_default().context("Failed to load configuration")?;
+
+    let mut confirmation = Confirmation::new(format!("Import account {}", archive.peer_id))
+        .consequence(format!(
+            "add {} games with {} messages to the local database",
+            archive.data.games.len(),
+            archive.data.messages.len()
+        ))
+        .consequence("replace your settings with the archived ones");
+    if let Ok(existing) = Identity::stored_peer_id(&active_key_path_in(&config.data_dir)) {
+        if existing.as_str() != archive.peer_id && force {
+            confirmation = confirmation.consequence(format!(
+                "replace your current identity {existing} with the archived one"
+            ));
+        }
+    }
+    if !confirm(&confirmation, yes)? {
+        return Ok(());
+    }
+
+    println!("Importing account {}...", archive.peer_id);
+    let passphrase = read_passphrase("Archive passphrase: ", false)?;
+    let db_path = match db_path {
+        Some(path) => path,
+        None => config.effective_database_path()?,
+    };
+
+    let summary = import_account_with_progress(
+        &archive,
+        &passphrase,
+        &config.data_dir,
+        &db_path,
+        force,
+        terminal_progress(OutputFormat::Text),
+    )?;
+
+    // Keep the machine-specific storage locations and credentials, take
+    // everything else from the archive
+    let mut settings = Config {
+        data_dir: config.data_dir,
+        db_path: config.db_path,
+        ..archive.settings.clone()
+    };
+    settings.api.token = config.api.token;
+    settings.webhooks.secret = config.webhooks.secret;
+    settings.webhooks.urls = config.webhooks.urls;
+    settings
+        .save()
+        .context("Failed to save imported settings")?;
+
+    println!("✓ Account imported successfully!");
+    println!("Peer ID: {}", archive.peer_id);
+    println!(
+        "Imported {} games, {} messages, {} tags, {} notes, {} aliases, {} verifications, {} pinned keys, {} other identities",
+        summary.games,
+        summary.messages,
+        summary.tags,
+        summary.notes,
+        summary.aliases,
+        summary.verifications,
+        summary.pins,
+        summary.identities
+    );
+    if summary.skipped_games > 0 {
+        println!(
+            "Skipped {} games that already exist locally",
+            summary.skipped_games
+        );
+    }
+
+    Ok(())
+}
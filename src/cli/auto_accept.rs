@@ -0,0 +1,345 @@
+use crate::chess::Color;
+use crate::cli::app::AutoAcceptSettings;
+use crate::cli::game_ops::{GameOps, InvitationRecord};
+use crate::cli::hotseat::HOTSEAT_OPPONENT;
+use crate::cli::inbox::format_time_control;
+use crate::cli::network_manager::NetworkManager;
+use crate::crypto::Identity;
+use crate::messages::chess::GameAccept;
+use crate::network::known_peers::KnownPeers;
+use crate::network::Heartbeat;
+use crate::storage::models::{GameStatus, TimeControl};
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// How often `mate serve` looks for invitations to accept
+pub const AUTO_ACCEPT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Message type of the audit record stored with an auto-accepted game
+pub const AUTO_ACCEPT_MESSAGE_TYPE: &str = "auto_accept";
+
+/// An incoming invitation and what is known about the peer who sent it
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub invitation: InvitationRecord,
+    /// The inviter's key was compared with `mate peers verify`
+    pub verified: bool,
+    /// The inviter's key was revoked by its owner
+    pub revoked: bool,
+    /// Aliases naming the inviter
+    pub aliases: Vec<String>,
+}
+
+/// Check `candidate` against every rule in `settings`, with `active_games`
+/// already under way
+///
+/// Returns the first rule the invitation fails, as a reason to log.
+pub fn check(
+    settings: &AutoAcceptSettings,
+    candidate: &Candidate,
+    active_games: usize,
+) -> Result<(), String> {
+    if candidate.revoked {
+        return Err("the inviter's key was revoked".to_string());
+    }
+    if settings.verified_only && !candidate.verified {
+        return Err("the inviter is not verified".to_string());
+    }
+    if !settings.aliases.is_empty()
+        && !candidate.aliases.iter().any(|alias| {
+            settings
+                .aliases
+                .iter()
+                .any(|allowed| allowed.trim().eq_ignore_ascii_case(alias))
+        })
+    {
+        return Err(format!(
+            "the inviter is not one of {}",
+            settings.aliases.join(", ")
+        ));
+    }
+    check_time_control(settings, candidate.invitation.time_control.as_ref())?;
+    if active_games >= settings.max_active_games {
+        return Err(format!(
+            "{active_games} games are active, the most allowed is {}",
+            settings.max_active_games
+        ));
+    }
+    Ok(())
+}
+
+fn check_time_control(
+    settings: &AutoAcceptSettings,
+    time_control: Option<&TimeControl>,
+) -> Result<(), String> {
+    let Some(time_control) = time_control else {
+        return match settings.allow_untimed {
+            true => Ok(()),
+            false => Err("the game is untimed".to_string()),
+        };
+    };
+    let shown = format_time_control(Some(time_control));
+    if let Some(min) = settings.min_base_minutes {
+        if time_control.initial_time_ms < min * 60_000 {
+            return Err(format!("{shown} is shorter than {min} minutes"));
+        }
+    }
+    if let Some(max) = settings.max_base_minutes {
+        if time_control.initial_time_ms > max * 60_000 {
+            return Err(format!("{shown} is longer than {max} minutes"));
+        }
+    }
+    if let Some(max) = settings.max_increment_secs {
+        if time_control.increment_ms > max * 1000 {
+            return Err(format!("{shown} adds more than {max} seconds a move"));
+        }
+    }
+    Ok(())
+}
+
+/// What was known when an invitation was accepted automatically, stored
+/// with the game so the decision can be looked back on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoAcceptRecord {
+    pub inviter: String,
+    pub verified: bool,
+    pub aliases: Vec<String>,
+    /// Time control as shown in the inbox, e.g. `5+3` or `untimed`
+    pub time_control: String,
+    /// Active games before this one was accepted
+    pub active_games: usize,
+    pub accepted_at: i64,
+}
+
+/// Accepts incoming invitations that pass the `[auto_accept]` rules
+///
+/// Invitations are taken oldest first, so when only some fit under
+/// `max_active_games` the peers who asked first get their games.
+pub struct AutoAccept {
+    database: Arc<Database>,
+    identity: Arc<Identity>,
+    network_manager: NetworkManager,
+    settings: AutoAcceptSettings,
+    known_peers: Option<PathBuf>,
+    heartbeat: Option<Heartbeat>,
+}
+
+impl AutoAccept {
+    pub fn new(
+        database: Arc<Database>,
+        identity: Arc<Identity>,
+        network_manager: NetworkManager,
+        settings: AutoAcceptSettings,
+    ) -> Self {
+        Self {
+            database,
+            identity,
+            network_manager,
+            settings,
+            known_peers: None,
+            heartbeat: None,
+        }
+    }
+
+    /// Look up the keys pinned for peers invited by address in `path`
+    pub fn with_known_peers(mut self, path: PathBuf) -> Self {
+        self.known_peers = Some(path);
+        self
+    }
+
+    /// Beat `heartbeat` after every check, for the watchdog of `mate serve`
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Incoming invitations, oldest first, with what is known of each inviter
+    pub fn candidates(&self) -> Result<Vec<Candidate>> {
+        let known_peers = match &self.known_peers {
+            Some(path) if path.exists() => Some(KnownPeers::load(path)?),
+            _ => None,
+        };
+        let mut invitations = GameOps::new(&self.database)
+            .list_incoming_invitations(&self.identity.peer_id().to_string())
+            .context("Failed to retrieve pending invitations")?;
+        invitations.reverse();
+
+        let mut candidates = Vec::with_capacity(invitations.len());
+        for invitation in invitations {
+            // A peer invited by address is known by the key pinned there too
+            let pinned = known_peers
+                .as_ref()
+                .and_then(|known| known.get(&invitation.opponent_peer_id));
+            let mut peer_ids = vec![invitation.opponent_peer_id.clone()];
+            peer_ids.extend(pinned.map(|known| known.peer_id.clone()));
+
+            let mut verified = false;
+            let mut revoked = pinned.is_some_and(|known| known.revoked);
+            let mut aliases = Vec::new();
+            for peer_id in &peer_ids {
+                verified |= self
+                    .database
+                    .is_peer_verified(peer_id)
+                    .context("Failed to look up peer verification")?;
+                revoked |= self
+                    .database
+                    .is_key_revoked(peer_id)
+                    .context("Failed to look up key revocations")?;
+                aliases.extend(
+                    self.database
+                        .get_aliases_for_peer(peer_id)
+                        .context("Failed to look up peer aliases")?,
+                );
+            }
+            candidates.push(Candidate {
+                invitation,
+                verified,
+                revoked,
+                aliases,
+            });
+        }
+        Ok(candidates)
+    }
+
+    /// Games under way against remote opponents
+    pub fn active_games(&self) -> Result<usize> {
+        Ok(self
+            .database
+            .get_games_by_status(GameStatus::Active)
+            .context("Failed to retrieve active games")?
+            .iter()
+            .filter(|game| game.opponent_peer_id != HOTSEAT_OPPONENT)
+            .count())
+    }
+
+    /// Invitations to accept now, each with the number of games that will be
+    /// active before it
+    pub fn matching(&self) -> Result<Vec<(Candidate, usize)>> {
+        let mut active_games = self.active_games()?;
+        let mut matching = Vec::new();
+        for candidate in self.candidates()? {
+            match check(&self.settings, &candidate, active_games) {
+                Ok(()) => {
+                    matching.push((candidate, active_games));
+                    active_games += 1;
+                }
+                Err(reason) => debug!(
+                    "Left invitation {} from {} for you to answer: {}",
+                    candidate.invitation.game_id, candidate.invitation.opponent_peer_id, reason
+                ),
+            }
+        }
+        Ok(matching)
+    }
+
+    /// Accept an invitation, recording why it was accepted with the game
+    ///
+    /// The side the inviter suggested is taken. If the acceptance can't be
+    /// delivered it is queued and the game starts anyway, as it would once
+    /// the inviter is back.
+    pub async fn accept(&self, candidate: &Candidate, active_games: usize) -> Result<()> {
+        let invitation = &candidate.invitation;
+        let game = self
+            .database
+            .get_game(&invitation.game_id)
+            .context("Game not found")?;
+        let accepted_color = invitation
+            .suggested_color
+            .clone()
+            .map(Color::from)
+            .unwrap_or_else(|| Color::from(game.my_color.clone()));
+        let accept = GameAccept::new(invitation.game_id.clone(), accepted_color);
+        let record = AutoAcceptRecord {
+            inviter: invitation.opponent_peer_id.clone(),
+            verified: candidate.verified,
+            aliases: candidate.aliases.clone(),
+            time_control: format_time_control(invitation.time_control.as_ref()),
+            active_games,
+            accepted_at: Database::current_timestamp(),
+        };
+
+        let my_peer_id = self.identity.peer_id().to_string();
+        self.database
+            .transaction(|tx| {
+                tx.update_game_status(&invitation.game_id, GameStatus::Active)?;
+                tx.store_message(
+                    invitation.game_id.clone(),
+                    "game_accept".to_string(),
+                    serde_json::to_string(&accept).unwrap_or_default(),
+                    "local".to_string(), // Placeholder signature for sent messages
+                    my_peer_id.clone(),
+                )?;
+                tx.store_message(
+                    invitation.game_id.clone(),
+                    AUTO_ACCEPT_MESSAGE_TYPE.to_string(),
+                    serde_json::to_string(&record).unwrap_or_default(),
+                    "local".to_string(),
+                    my_peer_id.clone(),
+                )?;
+                Ok(())
+            })
+            .context("Failed to activate game and store acceptance")?;
+        info!(
+            "Auto-accepted invitation {} from {} ({}, {}, aliases: {}, {} games already active), playing {:?}",
+            invitation.game_id,
+            record.inviter,
+            if record.verified { "verified" } else { "unverified" },
+            record.time_control,
+            if record.aliases.is_empty() {
+                "none".to_string()
+            } else {
+                record.aliases.join(", ")
+            },
+            active_games,
+            accepted_color
+        );
+
+        if let Err(e) = self
+            .network_manager
+            .send_game_accept(
+                &invitation.opponent_peer_id,
+                invitation.game_id.clone(),
+                accept,
+            )
+            .await
+        {
+            warn!(
+                "Could not send the acceptance of game {} ({:#}); it is queued and will be resent",
+                invitation.game_id, e
+            );
+        }
+        Ok(())
+    }
+
+    /// Accept matching invitations every [`AUTO_ACCEPT_INTERVAL`] until the
+    /// task is cancelled
+    pub async fn run(self) -> Result<()> {
+        let mut interval = tokio::time::interval(AUTO_ACCEPT_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) =
+                GameOps::new(&self.database).expire_invitations(Database::current_timestamp())
+            {
+                warn!("Failed to expire old invitations: {:#}", e);
+            }
+            match self.matching() {
+                Ok(matching) => {
+                    for (candidate, active_games) in &matching {
+                        if let Err(e) = self.accept(candidate, *active_games).await {
+                            warn!("{:#}", e);
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to check invitations to auto-accept: {:#}", e),
+            }
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
+        }
+    }
+}
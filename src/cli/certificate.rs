@@ -0,0 +1,48 @@
+use crate::cli::display::{print_json, CertificateJson, JSON_SCHEMA_VERSION};
+use crate::crypto::GameCertificate;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Read a game certificate and check the players' and any arbiter's
+/// signatures
+pub fn verify_certificate_file(file: &Path) -> Result<GameCertificate> {
+    let contents = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read certificate {}", file.display()))?;
+    let certificate: GameCertificate = serde_json::from_str(&contents)
+        .with_context(|| format!("{} is not a game certificate", file.display()))?;
+    certificate
+        .verify()
+        .context("Certificate signatures are not valid")?;
+    Ok(certificate)
+}
+
+/// Handle 'certificate verify' - Print what a valid certificate attests to
+pub fn handle_verify_certificate(file: &Path, json: bool) -> Result<()> {
+    let certificate = verify_certificate_file(file)?;
+
+    if json {
+        print_json(&CertificateJson {
+            schema_version: JSON_SCHEMA_VERSION,
+            moves: certificate.move_count(),
+            game_id: certificate.game_id,
+            white: certificate.white,
+            black: certificate.black,
+            result: certificate.result,
+            arbiter: certificate.arbiter,
+        })?;
+        return Ok(());
+    }
+
+    println!("✓ Certificate for game {} is valid", certificate.game_id);
+    println!("  White:  {}", certificate.white);
+    println!("  Black:  {}", certificate.black);
+    println!(
+        "  Result: {} after {} moves",
+        certificate.result,
+        certificate.move_count()
+    );
+    if let Some(arbiter) = &certificate.arbiter {
+        println!("  Arbiter: {arbiter}");
+    }
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use crate::cli::error_handler::CliError;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+/// Scheme prefix of a shareable invite string
+const INVITE_SCHEME: &str = "mate-invite://";
+
+/// One-line `mate-invite://<game_id>/<fingerprint>@<host:port>` invitation
+/// that can be pasted instead of typed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InviteString {
+    pub game_id: String,
+    /// Fingerprint of the inviter's peer ID (see `PeerId::fingerprint`)
+    pub fingerprint: String,
+    /// Address the inviter listens on
+    pub address: String,
+}
+
+impl InviteString {
+    pub fn new(
+        game_id: impl Into<String>,
+        fingerprint: impl Into<String>,
+        address: impl Into<String>,
+    ) -> Self {
+        Self {
+            game_id: game_id.into(),
+            fingerprint: fingerprint.into(),
+            address: address.into(),
+        }
+    }
+}
+
+impl fmt::Display for InviteString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{INVITE_SCHEME}{}/{}@{}",
+            self.game_id, self.fingerprint, self.address
+        )
+    }
+}
+
+impl FromStr for InviteString {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let rest = s
+            .trim()
+            .strip_prefix(INVITE_SCHEME)
+            .with_context(|| format!("Invite string must start with '{INVITE_SCHEME}'"))?;
+        let parsed = rest.split_once('@').and_then(|(ids, address)| {
+            let (game_id, fingerprint) = ids.split_once('/')?;
+            Some(Self::new(game_id, fingerprint, address))
+        });
+        match parsed {
+            Some(invite)
+                if !invite.game_id.is_empty()
+                    && !invite.fingerprint.is_empty()
+                    && invite.fingerprint.chars().all(|c| c.is_ascii_hexdigit())
+                    && invite.address.contains(':') =>
+            {
+                Ok(invite)
+            }
+            _ => anyhow::bail!(
+                "Invite string must look like {INVITE_SCHEME}<game_id>/<fingerprint>@<host:port>"
+            ),
+        }
+    }
+}
+
+/// Commands that write stdin to the system clipboard, in the order they are tried
+fn copy_commands() -> Vec<Vec<&'static str>> {
+    if cfg!(target_os = "macos") {
+        vec![vec!["pbcopy"]]
+    } else if cfg!(windows) {
+        vec![vec!["clip"]]
+    } else {
+        vec![
+            vec!["wl-copy"],
+            vec!["xclip", "-selection", "clipboard"],
+            vec!["xsel", "--clipboard", "--input"],
+        ]
+    }
+}
+
+/// Commands that print the system clipboard, in the order they are tried
+fn paste_commands() -> Vec<Vec<&'static str>> {
+    if cfg!(target_os = "macos") {
+        vec![vec!["pbpaste"]]
+    } else if cfg!(windows) {
+        vec![vec![
+            "powershell",
+            "-NoProfile",
+            "-Command",
+            "Get-Clipboard",
+        ]]
+    } else {
+        vec![
+            vec!["wl-paste", "--no-newline"],
+            vec!["xclip", "-selection", "clipboard", "-o"],
+            vec!["xsel", "--clipboard", "--output"],
+        ]
+    }
+}
+
+fn no_clipboard_tool() -> CliError {
+    CliError::Configuration {
+        setting: "clipboard".to_string(),
+        issue: "no clipboard tool could be run".to_string(),
+        suggestion: "Install wl-clipboard, xclip, or xsel, or copy the text by hand".to_string(),
+    }
+}
+
+/// Put `text` on the system clipboard using the platform's clipboard tool
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    for command in copy_commands() {
+        let Ok(mut child) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .context("Failed to write to the clipboard")?;
+        }
+        if child.wait().is_ok_and(|status| status.success()) {
+            return Ok(());
+        }
+    }
+    Err(no_clipboard_tool().into())
+}
+
+/// Read the system clipboard as text using the platform's clipboard tool
+pub fn paste_from_clipboard() -> Result<String> {
+    for command in paste_commands() {
+        let Ok(output) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+        else {
+            continue;
+        };
+        if output.status.success() {
+            return String::from_utf8(output.stdout).context("Clipboard does not contain text");
+        }
+    }
+    Err(no_clipboard_tool().into())
+}
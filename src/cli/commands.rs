@@ -1,11 +1,110 @@
-use clap::{Parser, Subcommand};
+use crate::cli::data_export::{ExportFormat, ExportRows};
+use crate::cli::display::{GameColumn, OutputFormat, Theme};
+use crate::cli::logging;
+use crate::cli::network_manager::NetworkConfig;
+use crate::cli::openings::OpeningGrouping;
+use crate::cli::validation::InputValidationUtils;
+use crate::storage::game_stats::StatsPeriod;
+use crate::storage::games::GameSort;
+use crate::storage::{Database, GameStatus};
+use clap::builder::PossibleValuesParser;
+use clap::{Args, Parser, Subcommand};
+use clap_complete::Shell;
 
 #[derive(Parser)]
 #[command(name = "mate")]
 #[command(about = "A P2P chess client for playing chess over the network")]
+#[command(version)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Database file to use instead of the configured location
+    #[arg(long, global = true, value_name = "PATH")]
+    pub db_path: Option<std::path::PathBuf>,
+
+    /// Keep everything in memory with a throwaway identity; nothing is written to disk
+    #[arg(long, global = true, conflicts_with = "db_path")]
+    pub ephemeral: bool,
+
+    /// Print results as JSON for scripts and other frontends
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Color theme for boards ('none' turns a configured theme off)
+    #[arg(long, global = true, value_name = "NAME", value_parser = PossibleValuesParser::new(Theme::names()))]
+    pub theme: Option<String>,
+
+    /// Only log errors to the terminal
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Log more to the terminal (-v for debug, -vv for trace)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Also write full debug logs to this file (rotated once it reaches --log-max-size)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<std::path::PathBuf>,
+
+    /// Log line format for the terminal and the log file ('json' for log collectors)
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "text", value_parser = PossibleValuesParser::new(logging::LogFormat::NAMES))]
+    pub log_format: String,
+
+    /// Size in MiB at which the log file is rotated
+    #[arg(long, global = true, value_name = "MIB", default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub log_max_size: u64,
+
+    /// Also start a new log file every hour or day
+    #[arg(long, global = true, value_name = "PERIOD", default_value = "never", value_parser = PossibleValuesParser::new(logging::LogRotation::NAMES))]
+    pub log_rotate: String,
+
+    /// Answer yes to confirmation prompts, for scripts
+    #[arg(short = 'y', long, global = true)]
+    pub yes: bool,
+
+    /// Identity to use instead of the one selected with 'mate key use'
+    #[arg(long, global = true, value_name = "NAME", conflicts_with = "ephemeral")]
+    pub identity: Option<String>,
+}
+
+impl Cli {
+    /// Whether stdout carries data for another program (JSON, completions, man
+    /// page, batch replies) and must not be mixed with log output
+    pub fn stdout_is_data(&self) -> bool {
+        self.json
+            || matches!(
+                self.command,
+                Commands::Completions { .. }
+                    | Commands::Man
+                    | Commands::Connect { stdin: true, .. }
+                    | Commands::Uci { .. }
+            )
+    }
+
+    /// Terminal log filter selected by the -q/-v flags
+    pub fn log_directive(&self) -> &'static str {
+        logging::console_directive(self.quiet, self.verbose)
+    }
+
+    /// Log line format selected by --log-format
+    pub fn log_format(&self) -> logging::LogFormat {
+        logging::LogFormat::from_name(&self.log_format).unwrap_or_default()
+    }
+
+    /// Time-based log file rotation selected by --log-rotate
+    pub fn log_rotation(&self) -> logging::LogRotation {
+        logging::LogRotation::from_name(&self.log_rotate).unwrap_or_default()
+    }
+
+    /// Output format selected by the global flags
+    pub fn output_format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Text
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -14,6 +113,13 @@ pub enum Commands {
     Init,
     /// Show current peer ID and identity info (deprecated - use 'key info' instead)
     Info,
+    /// Walk through first-time setup
+    ///
+    /// Creates an identity, picks a data directory, and sets the listening
+    /// address and board display preferences, then prints a connection string
+    /// to share with opponents. Runs automatically the first time mate is
+    /// used from a terminal.
+    SetupWizard,
     /// Key management commands
     Key {
         #[command(subcommand)]
@@ -21,15 +127,107 @@ pub enum Commands {
     },
     /// Start the echo server
     Serve {
-        #[arg(short, long, default_value = "127.0.0.1:8080")]
-        bind: String,
+        /// Address to listen on (defaults to the configured default_bind_addr)
+        ///
+        /// 'auto' listens on every interface on a free port, asks the router
+        /// to forward it with NAT-PMP or UPnP, and prints the address to
+        /// share. Invites made while it runs carry that address.
+        #[arg(short, long)]
+        bind: Option<String>,
+        /// Also serve the HTTP API for web frontends on this address
+        ///
+        /// Clients authenticate with the api.token setting as a Bearer
+        /// token; without one, a token is generated and printed at startup.
+        #[arg(long, value_name = "ADDR")]
+        http: Option<String>,
+    },
+    /// Administer a running 'mate serve'
+    Server {
+        #[command(subcommand)]
+        command: ServerCommand,
+    },
+    /// Print a shell completion script
+    ///
+    /// For example: 'mate completions bash > /etc/bash_completion.d/mate'
+    /// or 'mate completions zsh > "${fpath[1]}/_mate"'.
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+    /// Print the man page in roff format
+    ///
+    /// For example: 'mate man > /usr/local/share/man/man1/mate.1'
+    Man,
+    /// Read or change settings in the configuration file
+    ///
+    /// Settings are read from config.toml, then from MATE_* environment
+    /// variables (e.g. MATE_NETWORK_RETRY_POLICY for network.retry_policy),
+    /// then from command-line flags, each layer overriding the last.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Check that mate is set up correctly and suggest fixes
+    ///
+    /// Checks the configuration, identity key permissions, database
+    /// integrity, whether the listening port is free, and the local clock.
+    /// With --peer, also connects to a peer to test outbound connectivity and
+    /// compare clocks. Exits with status 1 if any check fails.
+    ///
+    /// Examples:
+    ///   mate doctor
+    ///   mate doctor --peer 192.168.1.20:8080
+    Doctor {
+        /// Peer to test connectivity and clock skew against (host:port or a mate:// connection string)
+        #[arg(short, long)]
+        peer: Option<String>,
+    },
+    /// Load a server with simulated clients and report on it (for development)
+    ///
+    /// Starts a server in-process, or loads the one at --address, and runs
+    /// clients that each send moves and reconnect regularly. Reports round
+    /// trip latencies at the start and end of the run, and open descriptors
+    /// and memory before and after.
+    ///
+    /// Examples:
+    ///   mate stress --clients 48 --duration 300
+    #[command(hide = true)]
+    Stress {
+        /// Number of simulated clients
+        #[arg(long, default_value_t = 32)]
+        clients: usize,
+        /// Seconds to run for
+        #[arg(long, default_value_t = 60)]
+        duration: u64,
+        /// Milliseconds each client waits between moves
+        #[arg(long, default_value_t = 50)]
+        interval: u64,
+        /// Server to load instead of an in-process one (host:port)
+        #[arg(long)]
+        address: Option<String>,
+    },
+    /// Inspect protocol data (for development)
+    Debug {
+        #[command(subcommand)]
+        command: DebugCommand,
     },
     /// Connect to a peer
+    ///
+    /// With --stdin, sends each line read from standard input as a message
+    /// and prints one JSON object per line with the peer's reply. The exit
+    /// status is 0 only if every message was answered.
+    ///
+    /// Example: printf 'one\ntwo\n' | mate connect 127.0.0.1:8080 --stdin
     Connect {
-        /// Address to connect to
+        /// Address to connect to (host:port or a mate:// connection string)
         address: String,
         #[arg(short, long)]
         message: Option<String>,
+        /// Read newline-delimited messages from stdin and print replies as JSON lines
+        #[arg(long, conflicts_with = "message")]
+        stdin: bool,
+        #[command(flatten)]
+        network: NetworkOptions,
     },
 
     // New chess commands
@@ -38,61 +236,174 @@ pub enum Commands {
     /// Lists all ongoing chess games with information about game state,
     /// opponents, and whose turn it is to move.
     ///
-    /// Example: mate games
-    Games,
+    /// Examples:
+    ///   mate games
+    ///   mate games --tag blitz
+    ///   mate games --status active,pending --opponent alice
+    ///   mate games --since 7d --sort created --limit 20 --page 2
+    ///   mate games --columns id,opponent,status,created
+    ///   mate games --compact
+    Games {
+        /// Only show games carrying this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Only show games in these states (comma-separated)
+        #[arg(short, long, value_delimiter = ',', value_parser = PossibleValuesParser::new(GameStatus::NAMES))]
+        status: Vec<String>,
+        /// Only show games against this opponent (alias or peer ID prefix)
+        #[arg(short, long)]
+        opponent: Option<String>,
+        /// Only show games updated since a date (YYYY-MM-DD) or age (12h, 7d, 2w)
+        #[arg(long, value_name = "WHEN", value_parser = since_value)]
+        since: Option<String>,
+        /// Sort order
+        #[arg(long, default_value = "updated", value_parser = PossibleValuesParser::new(GameSort::NAMES))]
+        sort: String,
+        /// Show at most this many games per page
+        #[arg(short = 'n', long, value_parser = clap::value_parser!(u32).range(1..))]
+        limit: Option<u32>,
+        /// Page to show, counting from 1 (pages hold --limit games, 20 by default)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        page: Option<u32>,
+        /// Table columns to show (comma-separated)
+        #[arg(long, value_delimiter = ',', value_parser = PossibleValuesParser::new(GameColumn::NAMES))]
+        columns: Vec<String>,
+        /// One short line per game, for narrow terminals (see display.compact)
+        #[arg(long)]
+        compact: bool,
+    },
+
+    /// Show an overview of identity, server, games, and invitations
+    ///
+    /// Lists games waiting on your move, pending invitations, whether a local
+    /// 'mate serve' is listening, and peers that connected recently.
+    Status {
+        /// Also show connection statistics from the metrics endpoint of
+        /// 'mate serve' (needs metrics.enabled)
+        #[arg(long)]
+        network: bool,
+    },
+
+    /// Watch the connections of a running 'mate serve' live
+    ///
+    /// Shows open connections with the peer on each, message rates,
+    /// per-connection bandwidth, rejected and oversized messages, and how
+    /// close the server is to its connection limit. Reads the metrics
+    /// endpoint, so it needs metrics.enabled. With --json, prints one
+    /// reading and exits.
+    ///
+    /// Examples:
+    ///   mate top
+    ///   mate top --interval 5
+    Top {
+        /// Seconds between readings
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
 
     /// Show the chess board for a specific game
     ///
     /// Displays the current position of a chess game in ASCII format.
-    /// If no game ID is provided, shows the most recently active game.
+    /// If no game is given, shows the most recently active game. With
+    /// --compact, shows two lines instead: the position in FEN, then the
+    /// last move and both clocks.
     ///
     /// Examples:
     ///   mate board
-    ///   mate board --game-id abc123
+    ///   mate board --game abc123
+    ///   mate board --game alice --follow
+    ///   mate board --compact --follow
     Board {
-        /// Specific game ID to show. If not provided, shows most recent game
-        #[arg(short, long)]
+        /// Game ID, unique ID prefix, or opponent alias. If not provided, shows most recent game
+        #[arg(short, long, visible_alias = "game")]
         game_id: Option<String>,
+        /// Keep the board on screen and redraw it as moves arrive
+        #[arg(short, long)]
+        follow: bool,
+        /// Two-line board for narrow terminals (see display.compact)
+        #[arg(long)]
+        compact: bool,
+        #[command(flatten)]
+        live_pgn: LivePgnOptions,
     },
 
     /// Invite someone to play a chess game
     ///
     /// Sends a chess game invitation to the specified peer address.
-    /// You can optionally specify which color you want to play.
+    /// You can optionally specify which color you want to play. With --copy,
+    /// a one-line invite string the opponent can use with 'mate accept
+    /// --paste' is put on the clipboard. With --odds or --extra-moves, you
+    /// give the opponent material or tempo odds. With --rated, the game
+    /// counts toward your rating in 'mate stats' and moves are dated
+    /// strictly by when they arrive.
     ///
     /// Examples:
     ///   mate invite 127.0.0.1:8080
     ///   mate invite 127.0.0.1:8080 --color white
-    ///   mate invite 127.0.0.1:8080 --color black
+    ///   mate invite 127.0.0.1:8080 --color black --copy
+    ///   mate invite 127.0.0.1:8080 --odds b1
+    ///   mate invite 127.0.0.1:8080 --odds f7 --extra-moves 1
+    ///   mate invite 127.0.0.1:8080 --rated
+    ///   mate invite --withdraw abc123
+    ///
+    /// Invitations expire after a week. --withdraw takes back one that has
+    /// not been answered yet.
     Invite {
-        /// Network address of the peer to invite (e.g., 127.0.0.1:8080)
-        address: String,
+        /// Network address of the peer to invite (e.g., 127.0.0.1:8080 or a mate:// connection string)
+        #[arg(required_unless_present = "withdraw")]
+        address: Option<String>,
+        /// Withdraw the unanswered invitation to this game (ID, unique prefix, or alias) instead
+        #[arg(long, value_name = "GAME_ID", conflicts_with_all = ["address", "color", "copy", "odds", "extra_moves", "rated"])]
+        withdraw: Option<String>,
         /// Color preference: 'white', 'black', or 'random' (default: random)
         #[arg(short, long)]
         color: Option<String>,
+        /// Copy a one-line invite string (address, peer ID fingerprint, game ID) to the clipboard
+        #[arg(long)]
+        copy: bool,
+        /// Starting squares of your pieces to leave off the board, e.g. 'b1' or 'a1,b1'
+        #[arg(long, value_delimiter = ',')]
+        odds: Vec<String>,
+        /// Moves the opponent plays in a row before normal play starts
+        #[arg(long, default_value_t = 0)]
+        extra_moves: u8,
+        /// Play a rated game instead of a casual one; odds games are always casual
+        #[arg(long, conflicts_with_all = ["odds", "extra_moves"])]
+        rated: bool,
+        #[command(flatten)]
+        network: NetworkOptions,
     },
 
     /// Accept a pending game invitation
     ///
-    /// Accepts an incoming chess game invitation by game ID.
+    /// Accepts an incoming chess game invitation by game ID, a unique ID
+    /// prefix, or the inviting peer's alias. Without one, lists incoming
+    /// invitations and asks which to accept.
     /// You can optionally specify which color you want to play.
     ///
     /// Examples:
+    ///   mate accept
     ///   mate accept abc123
     ///   mate accept abc123 --color white
     ///   mate accept abc123 --color black
+    ///   mate accept --paste
     Accept {
-        /// Game ID of the invitation to accept
-        game_id: String,
+        /// Game ID (or unique prefix, or alias) of the invitation to accept; chosen from a list if omitted
+        game_id: Option<String>,
+        /// Accept the invite string on the clipboard (from 'mate invite --copy')
+        #[arg(long, conflicts_with = "game_id")]
+        paste: bool,
         /// Color preference: 'white', 'black', or 'random' (default: remaining color)
         #[arg(short, long)]
         color: Option<String>,
+        #[command(flatten)]
+        network: NetworkOptions,
     },
 
     /// Make a chess move in a game
     ///
     /// Makes a move using standard algebraic notation (SAN).
-    /// If no game ID is provided, makes the move in the most recently active game.
+    /// If no game is given, makes the move in the most recently active game.
     ///
     /// Examples:
     ///   mate move e4
@@ -100,37 +411,897 @@ pub enum Commands {
     ///   mate move O-O
     ///   mate move exd5
     ///   mate move Qh5#
-    ///   mate move e4 --game-id abc123
+    ///   mate move e4 --game abc123
+    ///   mate move e4 --game alice
     Move {
         /// The chess move in algebraic notation (e.g., e4, Nf3, O-O, Qxe7+)
         chess_move: String,
-        /// Specific game ID to make the move in. If not provided, uses most recent game
-        #[arg(short, long)]
+        /// Game ID, unique ID prefix, or opponent alias. If not provided, uses most recent game
+        #[arg(short, long, visible_alias = "game")]
+        game_id: Option<String>,
+        #[command(flatten)]
+        network: NetworkOptions,
+    },
+
+    /// Resign a game
+    ///
+    /// Asks for confirmation, records the game as lost, and tells the
+    /// opponent. If no game is given, resigns the most recently active game.
+    ///
+    /// Examples:
+    ///   mate resign
+    ///   mate resign alice --yes
+    Resign {
+        /// Game ID, unique ID prefix, or opponent alias. If not provided, uses most recent game
         game_id: Option<String>,
     },
 
+    /// Offer a draw, or accept the opponent's open draw offer
+    ///
+    /// Asks for confirmation and sends the offer to the opponent. If the
+    /// opponent has already offered a draw, this accepts it and the game ends
+    /// drawn. If no game is given, uses the most recently active game.
+    ///
+    /// Examples:
+    ///   mate offer-draw
+    ///   mate offer-draw abc123 --yes
+    OfferDraw {
+        /// Game ID, unique ID prefix, or opponent alias. If not provided, uses most recent game
+        game_id: Option<String>,
+    },
+
+    /// Seal your next move to adjourn a game, or reveal it to resume
+    ///
+    /// As with an over-the-board adjournment, the player to move writes the
+    /// move down without playing it. The opponent is sent a commitment to
+    /// the move, and the move itself is kept encrypted with your key. On
+    /// resuming, --reveal sends the move; the opponent checks it against the
+    /// commitment, and the move is played. Both players must be online.
+    ///
+    /// Examples:
+    ///   mate seal abc123 Nf3
+    ///   mate seal abc123 --reveal
+    Seal {
+        /// Game ID, unique ID prefix, or opponent alias
+        game_id: String,
+        /// Move to seal, in SAN or coordinate notation
+        #[arg(required_unless_present = "reveal")]
+        chess_move: Option<String>,
+        /// Reveal your sealed move and play it
+        #[arg(long, conflicts_with = "chess_move")]
+        reveal: bool,
+    },
+
+    /// Tell opponents in your active games whether you are available
+    ///
+    /// The status is sent to every opponent you have an active game with and
+    /// shown to them in 'mate games' and 'mate peers list', so they know
+    /// whether to expect a reply soon. Opponents who are offline don't get
+    /// it; send it again later.
+    ///
+    /// Examples:
+    ///   mate presence away
+    ///   mate presence available
+    Presence {
+        /// available, busy, or away
+        status: String,
+    },
+
+    /// Report a peer's misbehavior
+    ///
+    /// The report is kept locally with the peer's stored messages given as
+    /// evidence. When reports.operator is set, a copy signed with your key
+    /// is also sent to that lobby or relay operator. To stop seeing the
+    /// peer's chat, use 'mate peers mute'.
+    ///
+    /// Examples:
+    ///   mate report alice --reason "Abusive chat" --game a1b2c3d4
+    ///   mate report 12D3KooW... --reason "Spam" --evidence 42 --evidence 43
+    Report {
+        /// Peer ID or alias
+        peer: String,
+        /// What the peer did
+        #[arg(long)]
+        reason: String,
+        /// ID of a stored message from the peer to attach (repeatable)
+        #[arg(long, value_name = "MESSAGE_ID")]
+        evidence: Vec<i64>,
+        /// Attach every message the peer sent in this game
+        #[arg(long, value_name = "GAME")]
+        game: Option<String>,
+        /// Keep the report local even when an operator is configured
+        #[arg(long)]
+        local: bool,
+    },
+
     /// Show move history for a chess game
     ///
     /// Displays the complete move history of a chess game in standard
     /// algebraic notation, along with game metadata.
-    /// If no game ID is provided, shows history for the most recently active game.
+    /// If no game is given, shows history for the most recently active game.
     ///
     /// Examples:
     ///   mate history
-    ///   mate history --game-id abc123
+    ///   mate history --game abc123
     History {
-        /// Specific game ID to show history for. If not provided, shows most recent game
+        /// Game ID, unique ID prefix, or opponent alias. If not provided, shows most recent game
+        #[arg(short, long, visible_alias = "game")]
+        game_id: Option<String>,
+    },
+
+    /// Show everything that happened in a game, in order
+    ///
+    /// Interleaves moves, chat, invitations, draw offers, resignations, sync
+    /// events, and connection incidents with the opponent by time. Useful for
+    /// working out what happened in a disputed game.
+    /// If no game is given, shows the most recently active game.
+    ///
+    /// Examples:
+    ///   mate timeline
+    ///   mate timeline abc123
+    Timeline {
+        /// Game ID, unique ID prefix, or opponent alias. If not provided, uses most recent game
+        game_id: Option<String>,
+    },
+
+    /// Summarize the openings you play and how you score with them
+    ///
+    /// Groups your games by ECO opening (or by their first moves with
+    /// '--by moves') and shows wins, draws, losses, and your score for each.
+    /// Give '--moves' to explore the continuations after a line, and
+    /// '--games' to list the games behind each row.
+    ///
+    /// Examples:
+    ///   mate openings
+    ///   mate openings --color white --by moves --depth 6
+    ///   mate openings --moves "e4 c5"
+    ///   mate openings --eco C5 --games
+    Openings {
+        /// Group by ECO opening or by the first moves played
+        #[arg(long, default_value = "eco", value_parser = PossibleValuesParser::new(OpeningGrouping::NAMES))]
+        by: String,
+        /// Only games starting with these moves (e.g. "e4 e5 Nf3" or "e2e4 e7e5")
+        #[arg(short, long, value_name = "MOVES")]
+        moves: Option<String>,
+        /// Half-moves to group by with '--by moves' (counted after '--moves'; default 4, or 1 after '--moves')
+        #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=40))]
+        depth: Option<u32>,
+        /// Only openings whose ECO code starts with this (e.g. B or C50)
+        #[arg(long)]
+        eco: Option<String>,
+        /// Only games where you played this color
+        #[arg(short, long, value_parser = PossibleValuesParser::new(["white", "black"]))]
+        color: Option<String>,
+        /// List the games behind each opening
+        #[arg(short, long)]
+        games: bool,
+    },
+
+    /// Show how your finished games went over time
+    ///
+    /// Charts wins, draws, and losses by week, month, or year and by the
+    /// color you played, with the average game length and the time controls
+    /// you play most. --csv writes the same figures for a spreadsheet, and
+    /// --json prints them as JSON.
+    ///
+    /// Examples:
+    ///   mate stats
+    ///   mate stats --by week
+    ///   mate stats --csv --output stats.csv
+    Stats {
+        /// Period each row of the results chart covers
+        #[arg(long, default_value = "month", value_parser = PossibleValuesParser::new(StatsPeriod::NAMES))]
+        by: String,
+        /// Write the figures as CSV instead of charts
+        #[arg(long)]
+        csv: bool,
+        /// File to write to (default: stdout)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Add or remove a tag on a game
+    ///
+    /// Tags are short labels (letters, digits, '-' and '_') used to
+    /// organize games. Use 'mate games --tag <tag>' to list tagged games.
+    ///
+    /// Examples:
+    ///   mate tag abc123 blitz
+    ///   mate tag abc123 blitz --remove
+    Tag {
+        /// Game ID, unique ID prefix, or opponent alias
+        game_id: String,
+        /// Tag to add or remove
+        tag: String,
+        /// Remove the tag instead of adding it
+        #[arg(short, long)]
+        remove: bool,
+    },
+
+    /// Show or edit the free-form note attached to a game
+    ///
+    /// With text, replaces the note. With --clear, deletes it. Otherwise the
+    /// note is opened in $VISUAL/$EDITOR, or printed if no editor is set.
+    ///
+    /// Examples:
+    ///   mate note abc123
+    ///   mate note abc123 "Missed the knight fork on move 14"
+    ///   mate note abc123 --clear
+    Note {
+        /// Game ID, unique ID prefix, or opponent alias
+        game_id: String,
+        /// New note text
+        text: Option<String>,
+        /// Delete the note
+        #[arg(short, long, conflicts_with = "text")]
+        clear: bool,
+    },
+
+    /// Open the full-screen terminal interface
+    ///
+    /// Shows the game list, board, move list, clock, and message log for the
+    /// selected game. Games refresh automatically as moves are stored.
+    ///
+    /// Keys: ↑/↓ or j/k select a game, r refreshes, q quits.
+    Tui,
+
+    /// Play a game interactively
+    ///
+    /// Opens a session that shows the board, accepts moves in SAN (Nf3, O-O)
+    /// or coordinate (g1f3) notation, and prints the opponent's moves as they
+    /// arrive. Type 'help' in the session for draw, resign, and chat commands.
+    /// With --live-pgn or --live-pgn-http, the game's PGN is kept up to date
+    /// for streaming tools.
+    Play {
+        /// Game ID, unique ID prefix, or opponent alias
+        game_id: String,
+        #[command(flatten)]
+        live_pgn: LivePgnOptions,
+    },
+
+    /// Play a game with two people sharing this terminal
+    ///
+    /// White and Black take turns entering moves, and nothing is sent over
+    /// the network. The game is stored like any other, against the opponent
+    /// 'local', so games, history, replay and export work on it. Give a game
+    /// ID to continue an unfinished hotseat game.
+    ///
+    /// Examples:
+    ///   mate hotseat
+    ///   mate hotseat abc123
+    Hotseat {
+        /// Hotseat game to continue (game ID or unique ID prefix)
+        game_id: Option<String>,
+    },
+
+    /// Play automated games between two engines over loopback connections
+    ///
+    /// Each engine is 'builtin' or the command line of a UCI engine. Both
+    /// sides run their own in-process server and send every move to the
+    /// other's over the real protocol, so the run also exercises rapid move
+    /// exchange. Games are stored tagged 'selfplay' and a result table is
+    /// printed at the end. Engines swap colors after every game.
+    ///
+    /// Examples:
+    ///   mate selfplay --games 10
+    ///   mate selfplay --engines builtin,stockfish --games 4 --tc 0.5+1
+    Selfplay {
+        /// The two engines, comma-separated: 'builtin' or a UCI engine command
+        #[arg(long, value_delimiter = ',', default_value = "builtin,builtin")]
+        engines: Vec<String>,
+        /// Number of games to play
+        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+        games: u32,
+        /// Time control as minutes+increment in seconds
+        #[arg(long, default_value = "1+0")]
+        tc: String,
+    },
+
+    /// Act as a UCI chess engine so a game can be played from a chess GUI
+    ///
+    /// Add 'mate uci <game>' to the GUI as an engine. Moves made on the GUI's
+    /// board are sent to the opponent, and the opponent's moves, picked up
+    /// by a running 'mate serve', come back as the engine's moves. Only games
+    /// from the standard starting position are supported.
+    Uci {
+        /// Game ID, unique ID prefix, or opponent alias
+        game_id: String,
+    },
+
+    /// Step through a stored game move by move
+    ///
+    /// Keys: n/→ next move, p/← previous move, a move number followed by j
+    /// jumps to that move (j alone jumps to the end), g start, q quits.
+    Replay {
+        /// Game ID, unique ID prefix, or opponent alias
+        game_id: String,
+    },
+
+    /// Export games in PGN, CSV, or JSON format
+    ///
+    /// Writes to stdout, or to a file with --output. PGN includes tags and
+    /// notes as custom PGN headers. CSV and JSON are flat exports for
+    /// spreadsheets and data analysis: CSV has one record per move (or per
+    /// game with --rows games), and JSON lists each game with its moves,
+    /// including when each move was stored and the board hash sent with it.
+    ///
+    /// Examples:
+    ///   mate export abc123
+    ///   mate export abc123 --output game.pgn
+    ///   mate export --all --format csv --output moves.csv
+    ///   mate export --all --format csv --rows games
+    ///   mate export --game abc123 --format json
+    Export {
+        /// Game ID, unique ID prefix, or opponent alias
+        #[arg(required_unless_present_any = ["game", "all"], conflicts_with_all = ["game", "all"])]
+        game_id: Option<String>,
+        /// Game to export (same as giving it without the flag)
+        #[arg(long, value_name = "GAME_ID", conflicts_with = "all")]
+        game: Option<String>,
+        /// Export every stored game
+        #[arg(long)]
+        all: bool,
+        /// Output format
+        #[arg(long, default_value = "pgn", value_parser = PossibleValuesParser::new(ExportFormat::NAMES))]
+        format: String,
+        /// What each CSV record describes
+        #[arg(long, default_value = "moves", value_parser = PossibleValuesParser::new(ExportRows::NAMES))]
+        rows: String,
+        /// File to write to (default: stdout)
         #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Export a finished game signed by both players, or check such a certificate
+    ///
+    /// The certificate holds the game's PGN and both players' signatures over
+    /// the result, so anyone can check who played and how it ended, e.g. a
+    /// rating service. Each player signs when their copy of the game ends;
+    /// exporting fails until the opponent's signature has arrived.
+    ///
+    /// Examples:
+    ///   mate certificate abc123 > game.cert.json
+    ///   mate certificate abc123 --output game.cert.json
+    ///   mate certificate verify game.cert.json
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Certificate {
+        #[command(subcommand)]
+        command: Option<CertificateCommand>,
+        /// Game ID, unique ID prefix, or opponent alias
+        #[arg(required = true)]
         game_id: Option<String>,
+        /// File to write the certificate to (default: stdout)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Vote on the next move in a consultation game
+    ///
+    /// In a consultation game one side is played by a group. The player
+    /// hosting the game puts it to a vote with --open; others join with
+    /// --join and --host. Each voter proposes a move, and a later vote
+    /// replaces their earlier one. Once the voting window has passed since
+    /// the group's turn began, the host's 'mate serve' plays the most voted
+    /// move. Without a move, shows the current vote.
+    ///
+    /// Examples:
+    ///   mate vote abc123 --open 600
+    ///   mate vote <game-id> --join --host 192.168.1.20:8080
+    ///   mate vote <game-id> Nf3 --host 192.168.1.20:8080
+    ///   mate vote abc123 e4
+    ///   mate vote abc123
+    Vote {
+        /// Game ID (on the host, also a unique ID prefix or opponent alias)
+        game_id: String,
+        /// Move to vote for, in SAN or coordinate notation
+        chess_move: Option<String>,
+        /// Address of the peer hosting the game, when voting from elsewhere
+        #[arg(long)]
+        host: Option<String>,
+        /// Register with the host as a voter
+        #[arg(long, requires = "host")]
+        join: bool,
+        /// Put one of your games to a vote, closing each vote after this many seconds
+        #[arg(long, value_name = "SECS", conflicts_with_all = ["host", "join", "chess_move"])]
+        open: Option<u64>,
+    },
+
+    /// Share an annotated position or line with a peer
+    ///
+    /// Sends the position (the starting position unless --fen is given) and
+    /// the moves of --line, which may be in SAN or coordinate notation with
+    /// comments in braces. The peer must be running 'mate serve'; they can
+    /// browse what they receive with 'mate studies'.
+    ///
+    /// Examples:
+    ///   mate study 127.0.0.1:8080 --title "Italian" --line "1. e4 e5 2. Nf3 Nc6 3. Bc4 {Aiming at f7}"
+    ///   mate study 127.0.0.1:8080 --title "Find the mate" --fen "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1" --comment "White to move"
+    Study {
+        /// Address of the peer (host:port) or their alias
+        address: String,
+        /// Short title shown in 'mate studies'
+        #[arg(short, long)]
+        title: String,
+        /// Position to start from (default: the starting position)
+        #[arg(long)]
+        fen: Option<String>,
+        /// Moves to play from the position, with optional {comments}
+        #[arg(short, long)]
+        line: Option<String>,
+        /// Comment on the starting position
+        #[arg(short, long)]
+        comment: Option<String>,
+    },
+
+    /// Browse studies shared with or by peers
+    ///
+    /// Lists kept studies, most recent first, or shows one with its position
+    /// and annotated line.
+    ///
+    /// Examples:
+    ///   mate studies
+    ///   mate studies abc123
+    Studies {
+        /// Study ID or unique ID prefix to show
+        study_id: Option<String>,
+    },
+
+    /// Permanently delete stored data for a peer or period
+    ///
+    /// Removes matching games together with their messages, tags, and notes,
+    /// plus the connection history and aliases recorded for the peer. When both
+    /// --peer and --before are given, only data matching both is removed and
+    /// aliases are kept. Shows what will be deleted and asks for confirmation
+    /// unless --yes is given.
+    ///
+    /// Examples:
+    ///   mate purge --peer 12D3KooW... --dry-run
+    ///   mate purge --before 2024-01-01 --yes
+    Purge {
+        /// Peer ID whose data should be removed
+        #[arg(long, required_unless_present = "before")]
+        peer: Option<String>,
+        /// Remove data created before this date (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        before: Option<String>,
+        /// Show what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export the complete account to a portable archive
+    ///
+    /// Bundles the identity key (encrypted with a passphrase), games,
+    /// messages, tags, notes, and settings into a single file for moving
+    /// to another machine. The passphrase is read from $MATE_PASSPHRASE
+    /// or prompted for.
+    ///
+    /// Example: mate export-account backup.mate
+    ExportAccount {
+        /// File to write the archive to
+        file: std::path::PathBuf,
+    },
+
+    /// Import an account archive created with 'export-account'
+    ///
+    /// Restores the identity, settings, and stored games after confirmation.
+    /// Games that already exist locally are skipped.
+    ///
+    /// Examples:
+    ///   mate import-account backup.mate
+    ///   mate import-account backup.mate --force
+    ImportAccount {
+        /// Archive file to import
+        file: std::path::PathBuf,
+        /// Replace an existing, different identity
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Peer connection history and reputation
+    Peers {
+        #[command(subcommand)]
+        command: PeersCommand,
+    },
+
+    /// Import games from Lichess, or export finished games to it
+    ///
+    /// Needs a build with the 'lichess' feature. A personal API token in
+    /// MATE_LICHESS_TOKEN is optional.
+    Lichess {
+        #[command(subcommand)]
+        command: LichessCommand,
+    },
+
+    /// Preview or send the anonymous usage report
+    ///
+    /// Reports are off unless telemetry.usage_reports is set. They hold the
+    /// mate version, the number of games stored by status, and how often
+    /// each command was run and each kind of error was hit, never peers,
+    /// games, moves, or arguments. While on, 'mate serve' sends one to
+    /// telemetry.usage_endpoint every day.
+    Telemetry {
+        #[command(subcommand)]
+        command: TelemetryCommand,
+    },
+
+    /// Link other installations of yours and keep them in step
+    ///
+    /// Linked devices each keep their own key but share the address book,
+    /// the settings that describe you rather than the machine, and an
+    /// index of the games played on each. The other device must be running
+    /// 'mate serve' while pairing or syncing.
+    ///
+    /// Examples:
+    ///   mate devices pair
+    ///   mate devices pair 192.0.2.10:8080 --code 7KQ4-MZ2P
+    ///   mate devices sync
+    Devices {
+        #[command(subcommand)]
+        command: DevicesCommand,
+    },
+
+    /// Have a third peer arbitrate a game, or arbitrate one yourself
+    ///
+    /// Both players appoint the same arbiter, who joins through each of
+    /// them and from then on receives every move. The arbiter rules on
+    /// claims that a move was illegal and co-signs the result, so the
+    /// game's certificate carries three signatures.
+    ///
+    /// Examples:
+    ///   mate arbiter appoint 3f2a club-arbiter
+    ///   mate arbiter join 3f2a9c1e-... --host 192.0.2.10:8080
+    ///   mate arbiter claim 3f2a 14 --reason "king left in check"
+    Arbiter {
+        #[command(subcommand)]
+        command: ArbiterCommand,
     },
 }
 
+/// Where the PGN of a game being played or followed is streamed to
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct LivePgnOptions {
+    /// Keep this file rewritten with the game's PGN (default: live_pgn.path)
+    #[arg(long, value_name = "PATH")]
+    pub live_pgn: Option<std::path::PathBuf>,
+    /// Serve the game's PGN over HTTP on this local address (default: live_pgn.http_addr)
+    #[arg(long, value_name = "ADDR")]
+    pub live_pgn_http: Option<String>,
+}
+
+/// Timeout and retry overrides for commands that talk to a peer
+#[derive(Args, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetworkOptions {
+    /// Seconds to wait for the peer to connect or reply (default: network.connection_timeout_secs)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+    /// Times to retry after a failed attempt (default: depends on the command)
+    #[arg(long, value_name = "N")]
+    pub retries: Option<u32>,
+    /// Trust the peer's key even if it differs from the key seen at this
+    /// address before; only use this after confirming the change with the peer
+    #[arg(long)]
+    pub accept_new_key: bool,
+}
+
+impl NetworkOptions {
+    /// The configured network settings with these overrides applied
+    pub fn apply(&self, mut config: NetworkConfig) -> NetworkConfig {
+        if let Some(timeout) = self.timeout {
+            config = config.with_timeout(std::time::Duration::from_secs(timeout));
+        }
+        if let Some(retries) = self.retries {
+            config = config.with_retries(retries);
+        }
+        config.accept_changed_keys |= self.accept_new_key;
+        config
+    }
+}
+
 #[derive(Subcommand)]
 pub enum KeyCommand {
     /// Show the default key storage path
     Path,
-    /// Generate a new identity (overwrites existing)
+    /// Generate a new identity (asks before overwriting an existing one)
     Generate,
     /// Show current identity info
     Info,
+    /// Encrypt the identity key with a passphrase, or change its passphrase
+    ///
+    /// Converts an unprotected key file in place. The passphrase is asked for
+    /// when the key is loaded, or read from MATE_KEY_PASSPHRASE.
+    Protect,
+    /// Remove passphrase protection from the identity key
+    Unprotect,
+    /// Replace the identity key, keeping game history and trust with peers
+    ///
+    /// Generates a new key and a rotation certificate signed by the old and
+    /// new keys, then sends it to every opponent so they move your games
+    /// and aliases to the new peer ID. The old key is archived, not deleted.
+    Rotate,
+    /// Revoke a compromised identity key and replace it
+    ///
+    /// Signs a revocation with the old key and sends it to every opponent,
+    /// who then refuse new games with that key. A new key is generated and
+    /// named as the successor; peers should verify it before trusting it.
+    /// The record is also saved in the data directory to hand to peers that
+    /// were offline.
+    Revoke {
+        /// Why the key is revoked, shown to peers (e.g. "laptop stolen")
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Print a 24-word recovery phrase for the identity key
+    ///
+    /// The words encode the secret key itself: write them down, keep them
+    /// offline, and never share them. 'mate key recover' restores the same
+    /// peer ID from them.
+    Backup,
+    /// Restore the identity key from a recovery phrase
+    ///
+    /// The phrase is asked for without echoing, or read from standard input
+    /// when it is piped (e.g. 'mate key recover < phrase.txt').
+    Recover,
+    /// List your identities and their peer IDs
+    ///
+    /// The identity in use is marked with '*'. Create another one with
+    /// 'mate --identity <name> key generate'.
+    List,
+    /// Switch to another identity
+    ///
+    /// Every later command uses the identity's key and peer ID until another
+    /// one is chosen. '--identity <name>' picks one for a single command.
+    Use {
+        /// Identity name ('default' for the original identity)
+        name: String,
+    },
+    /// Use an Ed25519 key on a hardware token (PIV through PKCS#11)
+    ///
+    /// The secret key never leaves the token, which signs every handshake
+    /// and move. The token PIN is asked for when the key is loaded, or read
+    /// from MATE_TOKEN_PIN. Needs a build with the 'hardware-keys' feature.
+    Token {
+        /// PKCS#11 module for the token, e.g. /usr/lib/libykcs11.so
+        #[arg(long)]
+        module: std::path::PathBuf,
+        /// Label of the Ed25519 key pair on the token
+        #[arg(long)]
+        label: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ServerCommand {
+    /// Send a notice to every peer connected to the server
+    ///
+    /// Peers see it in chat sessions, 'mate tui', and the HTTP API's event
+    /// stream. The server only accepts announcements signed with its own
+    /// key, so run this with the identity it was started with.
+    ///
+    /// Examples:
+    ///   mate server announce "Restarting for maintenance at 22:00 UTC"
+    ///   mate server announce "Back in 5 minutes" --address 127.0.0.1:9000
+    Announce {
+        /// Text of the announcement
+        text: String,
+        /// Server to announce on (defaults to the configured default_bind_addr)
+        #[arg(short, long)]
+        address: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DebugCommand {
+    /// Decode a captured frame and print the message inside
+    ///
+    /// Reads one or more frames as sent on the wire, each a 4-byte
+    /// big-endian length prefix and a signed envelope, from a file of raw
+    /// bytes (e.g. a TCP payload exported from Wireshark) or of hex. Checks
+    /// each length prefix and envelope signature, and prints the message
+    /// with any reason validation would reject it. Needs no identity or
+    /// database. Exits with status 1 if a signature is invalid.
+    ///
+    /// Examples:
+    ///   mate debug decode frame.hex
+    ///   mate debug decode payload.bin --json
+    ///   echo 0000012c0a00... | mate debug decode -
+    Decode {
+        /// Capture file, hex or raw bytes ('-' for standard input)
+        file: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Show a setting, or every setting when no key is given
+    ///
+    /// Values include environment overrides.
+    Get {
+        /// Dotted setting name, e.g. 'network.connection_timeout_secs'
+        key: Option<String>,
+    },
+    /// Change a setting in the configuration file
+    Set {
+        /// Dotted setting name, e.g. 'display.unicode'
+        key: String,
+        /// New value
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PeersCommand {
+    /// List every peer with recorded connection history
+    List,
+    /// Show connection history and reputation for a peer
+    ///
+    /// Peers that never completed a handshake are recorded by address,
+    /// e.g. 'addr:192.0.2.1'.
+    Info {
+        /// Peer ID (or 'addr:<ip>' for unauthenticated remotes)
+        peer_id: String,
+    },
+    /// Name a peer, show what a name refers to, or remove it
+    ///
+    /// Without arguments, lists every alias.
+    /// An alias can stand in for a game ID: 'mate move e4 --game alice'
+    /// plays in your game against the peer named alice.
+    ///
+    /// Examples:
+    ///   mate peers alias
+    ///   mate peers alias alice 12D3KooW...
+    ///   mate peers alias alice
+    ///   mate peers alias alice --remove
+    Alias {
+        /// Name to give the peer (letters, digits, '-' and '_')
+        alias: Option<String>,
+        /// Peer ID the alias refers to
+        #[arg(conflicts_with = "remove")]
+        peer_id: Option<String>,
+        /// Remove the alias instead of setting it
+        #[arg(short, long, requires = "alias")]
+        remove: bool,
+    },
+    /// Compare verification codes with a peer and mark it verified
+    ///
+    /// Both of you run this command with the other's peer ID and read the
+    /// codes to each other, e.g. over a call. Only confirm when they match;
+    /// a different code means someone else is using that peer's address.
+    /// Invitations from unverified peers carry a warning.
+    Verify {
+        /// Peer ID or alias
+        peer: String,
+    },
+    /// Stop showing chat from a peer
+    ///
+    /// Without a peer, lists the muted peers. A running 'mate serve' no
+    /// longer echoes the peer's chat, and 'mate play' sessions leave it out.
+    Mute {
+        /// Peer ID or alias
+        peer: Option<String>,
+    },
+    /// Show chat from a muted peer again
+    Unmute {
+        /// Peer ID or alias
+        peer: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LichessCommand {
+    /// Download a Lichess user's games into local storage
+    ///
+    /// Games are tagged 'lichess' and recorded against the opponent
+    /// 'lichess:<name>'. Games imported before are skipped.
+    ///
+    /// Examples:
+    ///   mate lichess import DrNykterstein
+    ///   mate lichess import DrNykterstein --max 50
+    Import {
+        /// Lichess username whose games to import
+        username: String,
+        /// Import at most this many of the most recent games
+        #[arg(long, value_name = "N")]
+        max: Option<u32>,
+    },
+    /// Upload a finished game to Lichess for analysis
+    ///
+    /// Prints the URL of the imported game on Lichess.
+    Export {
+        /// Game ID (or unique prefix)
+        game_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TelemetryCommand {
+    /// Show exactly what the next usage report would send
+    Show,
+    /// Send the usage report now and start counting afresh
+    Send,
+}
+
+#[derive(Subcommand)]
+pub enum DevicesCommand {
+    /// Link this installation with another one of yours
+    ///
+    /// Without an address, prints a pairing code for the other device to
+    /// use; this device must be running 'mate serve' until it does. With an
+    /// address and code, links the device at that address and syncs with
+    /// it.
+    Pair {
+        /// Address of the device that printed the pairing code
+        #[arg(requires = "code")]
+        address: Option<String>,
+        /// Pairing code printed by the other device
+        #[arg(long, requires = "address")]
+        code: Option<String>,
+    },
+    /// List linked devices and the games played on each
+    List,
+    /// Exchange changes with every linked device
+    Sync,
+    /// Stop syncing with a linked device
+    Unpair {
+        /// Peer ID of the device, or a unique prefix of it
+        device: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ArbiterCommand {
+    /// Appoint a peer as the arbiter of one of your games
+    ///
+    /// Your opponent must appoint the same peer, or the certificate will not
+    /// verify. Games given with odds cannot be arbitrated.
+    Appoint {
+        /// Game ID, a unique prefix of one, or the opponent's alias
+        game_id: String,
+        /// Peer ID or alias of the arbiter
+        arbiter: String,
+    },
+    /// Join a game you were appointed to arbitrate
+    ///
+    /// Run once for each player, giving the address their 'mate serve'
+    /// listens on, so both can send you their moves. Keep 'mate serve'
+    /// running here until the game ends.
+    Join {
+        /// ID of the game to arbitrate
+        game_id: String,
+        /// Address of one of the players
+        #[arg(long)]
+        host: String,
+    },
+    /// Ask the arbiter to rule that a move was illegal
+    Claim {
+        /// Game ID, a unique prefix of one, or the opponent's alias
+        game_id: String,
+        /// Half-move the claim is about, counting from 1
+        ply: u32,
+        /// Why the move was illegal, for the arbiter's records
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// List the games you arbitrate and your rulings in them
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum CertificateCommand {
+    /// Check both players' signatures in a game certificate
+    ///
+    /// Needs no identity or database, so anyone given the file can run it.
+    /// Exits with status 1 if the certificate is not valid.
+    Verify {
+        /// Certificate file written by 'mate certificate'
+        file: std::path::PathBuf,
+    },
+}
+
+/// Check a `--since` value when the command line is parsed
+fn since_value(value: &str) -> Result<String, String> {
+    match InputValidationUtils::parse_since(value, Database::current_timestamp()) {
+        Some(_) => Ok(value.to_string()),
+        None => Err("expected a YYYY-MM-DD date or an age like 12h, 7d, or 2w".to_string()),
+    }
 }
@@ -0,0 +1,242 @@
+use crate::cli::app::Config;
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, Write};
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Directory under the data directory that crash bundles are written to
+pub const CRASH_DIR: &str = "crashes";
+
+/// Log lines kept for a crash bundle
+pub const RECENT_EVENT_LINES: usize = 200;
+
+/// Written in place of secrets in a crash bundle's configuration
+pub const REDACTED: &str = "<redacted>";
+
+/// The most recent log lines, kept in memory for crash bundles
+///
+/// Used as the writer of a `tracing_subscriber::fmt` layer; only the last
+/// `capacity` lines are kept.
+#[derive(Debug, Clone)]
+pub struct RecentEvents {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl RecentEvents {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// The kept lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        match self.lines.lock() {
+            Ok(lines) => lines.iter().cloned().collect(),
+            // A panic while logging must not hide the lines logged before it
+            Err(poisoned) => poisoned.into_inner().iter().cloned().collect(),
+        }
+    }
+
+    fn push(&self, text: &str) {
+        let mut lines = match self.lines.lock() {
+            Ok(lines) => lines,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+    }
+}
+
+impl Default for RecentEvents {
+    fn default() -> Self {
+        Self::new(RECENT_EVENT_LINES)
+    }
+}
+
+/// Writer handed out by [`RecentEvents`] for one event
+pub struct RecentEventsWriter {
+    events: RecentEvents,
+    buffer: Vec<u8>,
+}
+
+impl Write for RecentEventsWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.events.push(&String::from_utf8_lossy(&self.buffer));
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RecentEventsWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+impl<'a> MakeWriter<'a> for RecentEvents {
+    type Writer = RecentEventsWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RecentEventsWriter {
+            events: self.clone(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+/// What a crash bundle records about a panic
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    pub message: String,
+    /// `file:line:column` of the panic, when known
+    pub location: Option<String>,
+    pub thread: String,
+    pub version: String,
+    pub backtrace: String,
+    /// Recent log lines, oldest first
+    pub events: Vec<String>,
+    /// The configuration as TOML with secrets redacted, if it could be loaded
+    pub config: Option<String>,
+}
+
+impl CrashReport {
+    /// A report for the panic being handled by the hook
+    ///
+    /// `config` is the redacted configuration snapshot taken at startup; the
+    /// configuration file isn't read again while panicking.
+    pub fn capture(
+        info: &PanicHookInfo<'_>,
+        events: &RecentEvents,
+        config: Option<String>,
+    ) -> Self {
+        let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = info.payload().downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic payload".to_string()
+        };
+        Self {
+            message,
+            location: info.location().map(|location| location.to_string()),
+            thread: std::thread::current()
+                .name()
+                .unwrap_or("unnamed")
+                .to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            backtrace: Backtrace::force_capture().to_string(),
+            events: events.lines(),
+            config,
+        }
+    }
+
+    /// Write the report as a bundle directory under `crash_dir`, returning
+    /// its path
+    ///
+    /// The bundle holds `panic.txt` (message, location, version, and
+    /// backtrace), `events.log`, and `config.toml` when the configuration
+    /// was available.
+    pub fn write_bundle(&self, crash_dir: &Path) -> io::Result<PathBuf> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let name = format!("crash-{timestamp}-{}", std::process::id());
+        // A second panic in the same second gets a bundle of its own
+        let mut bundle = crash_dir.join(&name);
+        let mut attempt = 1;
+        while bundle.exists() {
+            attempt += 1;
+            bundle = crash_dir.join(format!("{name}-{attempt}"));
+        }
+        fs::create_dir_all(&bundle)?;
+
+        let mut panic = format!("mate {}\nthread '{}' panicked", self.version, self.thread);
+        if let Some(location) = &self.location {
+            panic.push_str(&format!(" at {location}"));
+        }
+        panic.push_str(&format!(
+            ":\n{}\n\nbacktrace:\n{}\n",
+            self.message, self.backtrace
+        ));
+        fs::write(bundle.join("panic.txt"), panic)?;
+
+        let mut events = self.events.join("\n");
+        if !events.is_empty() {
+            events.push('\n');
+        }
+        fs::write(bundle.join("events.log"), events)?;
+
+        if let Some(config) = &self.config {
+            fs::write(bundle.join("config.toml"), config)?;
+        }
+        Ok(bundle)
+    }
+}
+
+/// The configuration as TOML with tokens, secrets, webhook URLs, and email
+/// addresses replaced by [`REDACTED`]
+///
+/// Slack and Discord webhook URLs carry their own credentials, so every URL
+/// is redacted.
+pub fn redacted_config(config: &Config) -> Result<String, toml::ser::Error> {
+    let mut config = config.clone();
+    for secret in [
+        &mut config.api.token,
+        &mut config.webhooks.secret,
+        &mut config.email.from,
+        &mut config.email.to,
+    ] {
+        if secret.is_some() {
+            *secret = Some(REDACTED.to_string());
+        }
+    }
+    for url in &mut config.webhooks.urls {
+        *url = REDACTED.to_string();
+    }
+    toml::to_string_pretty(&config)
+}
+
+/// Replace the default panic output with a crash bundle written under
+/// `data_dir` and a short message saying where it is
+///
+/// The hook covers every thread, so `mate serve`'s tasks are included. If the
+/// bundle can't be written, the default panic output is shown instead.
+/// `config` is the snapshot from [`redacted_config`] written to each bundle.
+pub fn install_panic_hook(data_dir: PathBuf, events: RecentEvents, config: Option<String>) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report = CrashReport::capture(info, &events, config.clone());
+        match report.write_bundle(&data_dir.join(CRASH_DIR)) {
+            Ok(bundle) => {
+                eprintln!(
+                    "\nmate crashed unexpectedly: {}\n\
+                     A crash report was saved to {}\n\
+                     Please attach it when reporting the problem; secrets in the configuration were left out.",
+                    report.message,
+                    bundle.display()
+                );
+            }
+            Err(_) => default_hook(info),
+        }
+    }));
+}
@@ -0,0 +1,22 @@
+//! Minimal CSV writing for exports meant for spreadsheets
+//!
+//! Fields follow RFC 4180: a field holding a comma, quote, or line break is
+//! quoted, with quotes doubled.
+
+/// One CSV field, quoted only when it has to be
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// One CSV record, without its line ending
+pub fn csv_record<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|field| csv_field(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
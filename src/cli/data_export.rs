@@ -0,0 +1,284 @@
+//! Flat exports of games and their moves for spreadsheets and data analysis
+//!
+//! Where `pgn` writes games for chess software, these exports write one
+//! record per game or per move, with timestamps and board hashes that PGN
+//! has no place for.
+
+use crate::chess::{Color, Odds};
+use crate::cli::csv::csv_record;
+use crate::cli::game_ops::{game_odds, game_time_control};
+use crate::cli::inbox::format_time_control;
+use crate::messages::chess::Move as MoveMessage;
+use crate::storage::models::PlayerColor;
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// What `mate export` writes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One PGN document per game
+    #[default]
+    Pgn,
+    /// A CSV table of moves or of games
+    Csv,
+    /// Every game with its moves, as JSON
+    Json,
+}
+
+impl ExportFormat {
+    /// Every format name, as accepted by `--format`
+    pub const NAMES: [&'static str; 3] = ["pgn", "csv", "json"];
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pgn" => Ok(ExportFormat::Pgn),
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!(
+                "Invalid format '{s}'. Expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// What each record of a CSV export describes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportRows {
+    /// One record per move, with the game it belongs to
+    #[default]
+    Moves,
+    /// One record per game
+    Games,
+}
+
+impl ExportRows {
+    /// Every row kind, as accepted by `--rows`
+    pub const NAMES: [&'static str; 2] = ["moves", "games"];
+}
+
+impl FromStr for ExportRows {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "moves" => Ok(ExportRows::Moves),
+            "games" => Ok(ExportRows::Games),
+            _ => Err(format!(
+                "Invalid rows '{s}'. Expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// One move of an exported game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MoveRow {
+    /// Half-move number, from 1
+    pub ply: usize,
+    /// Full-move number as PGN counts it
+    pub move_number: usize,
+    /// `white` or `black`
+    pub color: String,
+    /// The move in SAN; `None` once a move fails to replay
+    pub san: Option<String>,
+    /// The move as it was sent
+    pub notation: String,
+    /// Hash of the board after the move, as the mover reported it
+    pub board_state_hash: String,
+    /// Whether I played the move
+    pub mine: bool,
+    /// Unix time in seconds the move was stored
+    pub played_at: i64,
+}
+
+/// One exported game with its moves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GameExport {
+    pub game_id: String,
+    pub opponent: String,
+    /// The color I played
+    pub my_color: String,
+    pub status: String,
+    /// `win`, `loss`, `draw`, or `abandoned` from my side, once decided
+    pub result: Option<String>,
+    /// The result as a PGN token, e.g. `1-0` or `*`
+    pub pgn_result: String,
+    /// e.g. `5+3` or `untimed`
+    pub time_control: String,
+    pub tags: Vec<String>,
+    pub created_at: i64,
+    pub completed_at: Option<i64>,
+    pub moves: Vec<MoveRow>,
+}
+
+impl GameExport {
+    /// Read a stored game and its moves
+    ///
+    /// Moves are replayed from the starting position to give each in SAN;
+    /// replay stops at the first move that is not legal, and later moves
+    /// are exported as they were sent.
+    pub fn load(database: &Database, game_id: &str) -> Result<Self> {
+        let game = database
+            .get_game(game_id)
+            .context("Failed to retrieve game from database")?;
+        let messages = database
+            .get_messages_for_game(game_id)
+            .context("Failed to retrieve game messages")?;
+        let tags = database
+            .get_game_tags(game_id)
+            .context("Failed to retrieve game tags")?;
+
+        let odds = game_odds(&game, &messages);
+        let mut board = Odds::starting_board(odds.as_ref()).ok();
+        let my_side = match game.my_color {
+            PlayerColor::White => Color::White,
+            PlayerColor::Black => Color::Black,
+        };
+
+        let mut moves = Vec::new();
+        let mut move_number = 1;
+        let move_messages = messages
+            .iter()
+            .filter(|m| m.message_type.eq_ignore_ascii_case("move"))
+            .filter_map(|m| {
+                serde_json::from_str::<MoveMessage>(&m.content)
+                    .ok()
+                    .map(|content| (m, content))
+            });
+        for (index, (message, content)) in move_messages.enumerate() {
+            let side = Odds::side_to_move(odds.as_ref(), index);
+            let san = board.as_mut().and_then(|board| {
+                let mv = board.parse_move(&content.chess_move).ok()?;
+                let san = board.to_san(mv);
+                Odds::make_move(odds.as_ref(), board, index, mv).ok()?;
+                Some(san)
+            });
+            if san.is_none() {
+                board = None;
+            }
+            moves.push(MoveRow {
+                ply: index + 1,
+                move_number,
+                color: match side {
+                    Color::White => "white",
+                    Color::Black => "black",
+                }
+                .to_string(),
+                san,
+                notation: content.chess_move,
+                board_state_hash: content.board_state_hash,
+                mine: side == my_side,
+                played_at: message.created_at,
+            });
+            // Extra moves given as odds put the same side on move twice in
+            // a row, so numbering follows the side to move
+            if side == Color::Black || Odds::side_to_move(odds.as_ref(), index + 1) == side {
+                move_number += 1;
+            }
+        }
+
+        Ok(Self {
+            game_id: game.id.clone(),
+            opponent: game.opponent_peer_id.clone(),
+            my_color: game.my_color.as_str().to_string(),
+            status: game.status.as_str().to_string(),
+            result: game.result.as_ref().map(|r| r.as_str().to_string()),
+            pgn_result: game.pgn_result().to_string(),
+            time_control: format_time_control(game_time_control(&game).as_ref()),
+            tags,
+            created_at: game.created_at,
+            completed_at: game.completed_at,
+            moves,
+        })
+    }
+
+    /// The fields every CSV record of this game starts with
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.game_id.clone(),
+            self.opponent.clone(),
+            self.my_color.clone(),
+            self.status.clone(),
+            self.result.clone().unwrap_or_default(),
+            self.pgn_result.clone(),
+            self.time_control.clone(),
+            self.tags.join(" "),
+            self.created_at.to_string(),
+            self.completed_at.map(|t| t.to_string()).unwrap_or_default(),
+        ]
+    }
+}
+
+const GAME_CSV_HEADER: [&str; 10] = [
+    "game_id",
+    "opponent",
+    "my_color",
+    "status",
+    "result",
+    "pgn_result",
+    "time_control",
+    "tags",
+    "created_at",
+    "completed_at",
+];
+
+const MOVE_CSV_HEADER: [&str; 8] = [
+    "ply",
+    "move_number",
+    "color",
+    "san",
+    "notation",
+    "board_state_hash",
+    "mine",
+    "played_at",
+];
+
+/// A CSV table of `games`, one record per game or per move, with a header
+pub fn export_csv(games: &[GameExport], rows: ExportRows) -> String {
+    let mut lines = Vec::new();
+    match rows {
+        ExportRows::Games => {
+            let mut header: Vec<&str> = GAME_CSV_HEADER.to_vec();
+            header.push("moves");
+            lines.push(csv_record(&header));
+            for game in games {
+                let mut fields = game.csv_fields();
+                fields.push(game.moves.len().to_string());
+                lines.push(csv_record(&fields));
+            }
+        }
+        ExportRows::Moves => {
+            let mut header: Vec<&str> = GAME_CSV_HEADER.to_vec();
+            header.extend(MOVE_CSV_HEADER);
+            lines.push(csv_record(&header));
+            for game in games {
+                let game_fields = game.csv_fields();
+                for row in &game.moves {
+                    let mut fields = game_fields.clone();
+                    fields.extend([
+                        row.ply.to_string(),
+                        row.move_number.to_string(),
+                        row.color.clone(),
+                        row.san.clone().unwrap_or_default(),
+                        row.notation.clone(),
+                        row.board_state_hash.clone(),
+                        row.mine.to_string(),
+                        row.played_at.to_string(),
+                    ]);
+                    lines.push(csv_record(&fields));
+                }
+            }
+        }
+    }
+    let mut csv = lines.join("\n");
+    csv.push('\n');
+    csv
+}
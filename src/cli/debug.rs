@@ -0,0 +1,201 @@
+use crate::cli::display::{print_json, JSON_SCHEMA_VERSION};
+use crate::cli::timeline::format_timeline_time;
+use crate::messages::types::SignedEnvelope;
+use crate::messages::wire::{LENGTH_PREFIX_SIZE, MAX_MESSAGE_SIZE, MIN_MESSAGE_SIZE};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+/// Read a captured frame from `file`, or from standard input when it is `-`
+///
+/// The capture is either the raw bytes, e.g. a TCP payload exported from
+/// Wireshark, or the same bytes as hex. Hex may be split by whitespace or
+/// colons and prefixed with `0x`.
+pub fn read_capture(file: &Path) -> Result<Vec<u8>> {
+    let bytes = if file.as_os_str() == "-" {
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut bytes)
+            .context("Failed to read the capture from standard input")?;
+        bytes
+    } else {
+        std::fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?
+    };
+    Ok(parse_hex(&bytes).unwrap_or(bytes))
+}
+
+/// The bytes spelled out by `text`, if it is hex
+fn parse_hex(text: &[u8]) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(text).ok()?;
+    let digits: String = text
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .map(|word| word.strip_prefix("0x").unwrap_or(word))
+        .collect();
+    if digits.is_empty() {
+        return None;
+    }
+    hex::decode(digits).ok()
+}
+
+/// One length-prefixed frame found in a capture
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedFrame {
+    /// Where the length prefix starts in the capture
+    pub offset: usize,
+    /// Envelope length given by the prefix
+    pub length: usize,
+    pub sender: String,
+    pub timestamp: u64,
+    pub signature_valid: bool,
+    pub message_type: String,
+    /// Why the message would be rejected by validation, if it would
+    pub validation_error: Option<String>,
+    pub message: serde_json::Value,
+}
+
+/// Every frame in a capture, as a receiving peer would see them
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodeReport {
+    pub schema_version: u32,
+    pub frames: Vec<DecodedFrame>,
+}
+
+impl DecodeReport {
+    /// Frames whose envelope signature does not verify
+    pub fn invalid_signatures(&self) -> usize {
+        self.frames
+            .iter()
+            .filter(|frame| !frame.signature_valid)
+            .count()
+    }
+}
+
+impl fmt::Display for DecodeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, frame) in self.frames.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(
+                f,
+                "Frame {} at byte {}: {} ({} bytes)",
+                index + 1,
+                frame.offset,
+                frame.message_type,
+                frame.length
+            )?;
+            writeln!(f, "  Sender:    {}", frame.sender)?;
+            writeln!(
+                f,
+                "  Sent:      {} UTC",
+                format_timeline_time(frame.timestamp as i64)
+            )?;
+            writeln!(
+                f,
+                "  Signature: {}",
+                if frame.signature_valid {
+                    "valid"
+                } else {
+                    "INVALID"
+                }
+            )?;
+            match &frame.validation_error {
+                Some(error) => writeln!(f, "  Message:   invalid: {error}")?,
+                None => writeln!(f, "  Message:   valid")?,
+            }
+            let pretty = serde_json::to_string_pretty(&frame.message).map_err(|_| fmt::Error)?;
+            for line in pretty.lines() {
+                writeln!(f, "  {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Decode the frames in `capture`, one after another
+///
+/// Fails on anything a peer would drop the connection for: a length prefix
+/// out of range, a frame cut short or followed by a partial one, or bytes
+/// that are not an envelope or message. Signatures and message contents
+/// are checked and reported, but don't fail the decode.
+pub fn decode_frames(capture: &[u8]) -> Result<DecodeReport> {
+    if capture.is_empty() {
+        bail!("The capture is empty");
+    }
+
+    let mut frames = Vec::new();
+    let mut offset = 0;
+    while offset < capture.len() {
+        let rest = &capture[offset..];
+        let Some(prefix) = rest.get(..LENGTH_PREFIX_SIZE) else {
+            bail!(
+                "{} trailing bytes at byte {offset} are too short for a length prefix",
+                rest.len()
+            );
+        };
+        let length = u32::from_be_bytes(prefix.try_into()?) as usize;
+        if !(MIN_MESSAGE_SIZE..=MAX_MESSAGE_SIZE).contains(&length) {
+            bail!(
+                "Length prefix at byte {offset} is {length}, outside {MIN_MESSAGE_SIZE}..={MAX_MESSAGE_SIZE}"
+            );
+        }
+        let body = &rest[LENGTH_PREFIX_SIZE..];
+        if body.len() < length {
+            bail!(
+                "Frame at byte {offset} declares {length} bytes but only {} follow",
+                body.len()
+            );
+        }
+
+        frames.push(decode_frame(offset, &body[..length])?);
+        offset += LENGTH_PREFIX_SIZE + length;
+    }
+
+    Ok(DecodeReport {
+        schema_version: JSON_SCHEMA_VERSION,
+        frames,
+    })
+}
+
+fn decode_frame(offset: usize, body: &[u8]) -> Result<DecodedFrame> {
+    let envelope: SignedEnvelope = bincode::deserialize(body)
+        .with_context(|| format!("Frame at byte {offset} is not a signed envelope"))?;
+    let message = envelope
+        .get_message()
+        .with_context(|| format!("Frame at byte {offset} does not hold a message"))?;
+    Ok(DecodedFrame {
+        offset,
+        length: body.len(),
+        sender: envelope.sender().to_string(),
+        timestamp: envelope.timestamp(),
+        signature_valid: envelope.verify_signature(),
+        message_type: message.message_type().to_string(),
+        validation_error: message.validate().err().map(|e| e.to_string()),
+        message: serde_json::to_value(&message)?,
+    })
+}
+
+/// Handle 'debug decode' - Print the messages in a captured frame
+///
+/// Exits with an error if any envelope signature does not verify.
+pub fn handle_decode(file: &Path, json: bool) -> Result<()> {
+    let capture = read_capture(file)?;
+    let report = decode_frames(&capture)?;
+
+    if json {
+        print_json(&report)?;
+    } else {
+        print!("{report}");
+    }
+
+    let invalid = report.invalid_signatures();
+    if invalid > 0 {
+        bail!(
+            "{invalid} of {} frames have an invalid signature",
+            report.frames.len()
+        );
+    }
+    Ok(())
+}
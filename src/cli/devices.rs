@@ -0,0 +1,182 @@
+use crate::cli::app::Config;
+use crate::cli::network_manager::NetworkManager;
+use crate::messages::chess::security::MAX_SYNC_CHANGES;
+use crate::storage::devices::SYNC_SETTING;
+use crate::storage::models::{LinkedDevice, SyncEntry};
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use rand::Rng;
+use tracing::warn;
+
+/// How long a code from `mate devices pair` can be used, in seconds
+pub const PAIRING_CODE_TTL_SECS: i64 = 10 * 60;
+
+/// Characters pairing codes are made of, leaving out ones easily confused
+/// when read aloud or typed (0/O, 1/I/L)
+const PAIRING_CODE_ALPHABET: &[u8] = b"23456789ABCDEFGHJKMNPQRSTUVWXYZ";
+
+/// Length of a pairing code (about 40 bits)
+const PAIRING_CODE_LENGTH: usize = 8;
+
+/// Settings kept in step between linked devices
+///
+/// These describe the player rather than the machine; addresses, paths,
+/// keys, and credentials stay with each device.
+pub const SYNCED_SETTINGS: &[&str] = &[
+    "display.unicode",
+    "display.theme",
+    "display.locale",
+    "time_control.base_minutes",
+    "time_control.increment_secs",
+    "notifications.moves",
+    "notifications.invites",
+    "reminders.enabled",
+    "reminders.after_hours",
+];
+
+/// A fresh pairing code, e.g. `7KQ4-MZ2P`
+pub fn generate_pairing_code() -> String {
+    let mut rng = rand::thread_rng();
+    let code: String = (0..PAIRING_CODE_LENGTH)
+        .map(|_| PAIRING_CODE_ALPHABET[rng.gen_range(0..PAIRING_CODE_ALPHABET.len())] as char)
+        .collect();
+    format!("{}-{}", &code[..4], &code[4..])
+}
+
+/// A pairing code as typed, in the form it was issued: uppercase, with the
+/// dash in place whether or not it was typed
+pub fn normalize_pairing_code(code: &str) -> String {
+    let code: String = code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    match code.len() {
+        PAIRING_CODE_LENGTH => format!("{}-{}", &code[..4], &code[4..]),
+        _ => code,
+    }
+}
+
+/// What one exchange with a linked device did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    /// Changes sent to the device
+    pub sent: usize,
+    /// Changes received from the device
+    pub received: usize,
+    /// Received changes newer than ours, and so applied
+    pub applied: usize,
+}
+
+/// Write settings received from linked devices to the configuration file
+///
+/// Settings not in [`SYNCED_SETTINGS`], or with a value this version does
+/// not accept, are skipped. Returns how many settings were written.
+pub fn apply_synced_settings(database: &Database) -> Result<usize> {
+    let pending = database
+        .pending_sync_settings()
+        .context("Failed to read synced settings")?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let mut config = Config::load_stored().context("Failed to load configuration")?;
+    let mut written = 0;
+    for entry in &pending {
+        match (&entry.value, SYNCED_SETTINGS.contains(&entry.key.as_str())) {
+            (Some(value), true) => match config.set(&entry.key, value) {
+                Ok(()) => written += 1,
+                Err(e) => warn!("Skipped synced setting {}: {:#}", entry.key, e),
+            },
+            _ => warn!("Skipped synced setting {}", entry.key),
+        }
+        database
+            .clear_pending_sync_setting(&entry.key)
+            .context("Failed to record synced setting")?;
+    }
+    if written > 0 {
+        config.save().context("Failed to save configuration")?;
+    }
+    Ok(written)
+}
+
+/// Bring the sync journal up to date with this device's settings, address
+/// book, and games
+///
+/// Settings changed in the configuration file count as changed now. A
+/// setting never synced before that still has its default value counts as
+/// never changed, so a new device does not undo choices made on the others.
+pub fn record_local_changes(database: &Database, local_peer_id: &str) -> Result<()> {
+    let config = Config::load_stored().context("Failed to load configuration")?;
+    let defaults = Config::default();
+    let synced = database
+        .sync_entries(SYNC_SETTING)
+        .context("Failed to read synced settings")?;
+    let now = Database::current_timestamp();
+    for key in SYNCED_SETTINGS {
+        let Some(value) = config.get(key)? else {
+            continue;
+        };
+        let never_changed = !synced.iter().any(|entry| entry.key == *key)
+            && defaults.get(key)?.as_ref() == Some(&value);
+        let updated_at = if never_changed { 0 } else { now };
+        database
+            .record_sync_change(SYNC_SETTING, key, Some(&value), updated_at)
+            .context("Failed to record setting change")?;
+    }
+    database
+        .record_address_book_changes()
+        .context("Failed to record address book changes")?;
+    database
+        .record_game_index_changes(local_peer_id)
+        .context("Failed to record game changes")?;
+    Ok(())
+}
+
+/// Exchange changes with a linked device until neither side has more
+///
+/// Changes go in batches of at most [`MAX_SYNC_CHANGES`]. Settings received
+/// are left pending for [`apply_synced_settings`].
+pub async fn sync_with_device(
+    network: &NetworkManager,
+    database: &Database,
+    device: &LinkedDevice,
+) -> Result<SyncSummary> {
+    let mut summary = SyncSummary::default();
+    let mut sent_through = device.sent_through;
+    let mut received_through = device.received_through;
+
+    loop {
+        let request = database
+            .device_sync(sent_through, received_through)
+            .context("Failed to read changes to send")?;
+        let through = request.through;
+        let sent = request.changes.len();
+
+        let (reply, sender) = network.send_device_sync(&device.address, request).await?;
+        if sender != device.peer_id {
+            anyhow::bail!(
+                "{} answered for device {}; the device's key may have changed",
+                sender,
+                device.peer_id
+            );
+        }
+
+        let received: Vec<SyncEntry> = reply.changes.iter().map(SyncEntry::from).collect();
+        let applied = database
+            .apply_sync_changes(&received)
+            .context("Failed to apply changes from device")?;
+        database
+            .record_device_sync(&device.peer_id, reply.through, through)
+            .context("Failed to record sync")?;
+
+        summary.sent += sent;
+        summary.received += received.len();
+        summary.applied += applied.len();
+        sent_through = through;
+        received_through = reply.through;
+        if sent < MAX_SYNC_CHANGES && received.len() < MAX_SYNC_CHANGES {
+            return Ok(summary);
+        }
+    }
+}
@@ -1,12 +1,29 @@
-use crate::chess::{Board, Color};
+use crate::chess::{Board, Color, Move, Piece, Position};
+use crate::cli::data_export::GameExport;
+use crate::cli::doctor::Check;
+use crate::cli::error_handler::{CliError, ErrorCode};
+use crate::cli::game_stats::GameStatsReport;
+use crate::cli::i18n::{tr, trf, Msg};
+use crate::cli::inbox::{format_time_control, game_mode};
+use crate::cli::openings::OpeningSummary;
+use crate::cli::timeline::TimelineEntry;
+use crate::cli::tui::format_elapsed;
+use crate::cli::usage::UsageReport;
 use crate::cli::GameRecord;
-use crate::storage::models::GameStatus;
-use std::io::{self, Write};
+use crate::network::{MonitorSnapshot, TrafficSnapshot};
+use crate::storage::game_stats::ResultTally;
+use crate::storage::models::{
+    Arbitration, ArbitrationRuling, GameIndexEntry, GameStatus, LinkedDevice, PeerAlias, PeerEvent,
+    PeerPresence, PeerReport, PeerSummary, PlayerColor,
+};
+use serde::Serialize;
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
 
 /// Display a list of games in a pretty ASCII table format
 pub fn display_games_list(games: &[GameRecord]) {
     if games.is_empty() {
-        println!("No games found.");
+        println!("{}", tr(Msg::NoGamesFound));
         return;
     }
 
@@ -47,12 +64,12 @@ pub fn display_games_list(games: &[GameRecord]) {
 
     println!(
         "│ {:^width_id$} │ {:^width_opp$} │ {:^width_stat$} │ {:^width_col$} │ {:^width_mov$} │ {:^width_turn$} │",
-        "Game ID",
-        "Opponent",
-        "Status",
-        "Color",
-        "Moves",
-        "Turn",
+        tr(Msg::HeaderGameId),
+        tr(Msg::HeaderOpponent),
+        tr(Msg::HeaderStatus),
+        tr(Msg::HeaderColor),
+        tr(Msg::HeaderMoves),
+        tr(Msg::HeaderTurn),
         width_id = id_width,
         width_opp = opponent_width,
         width_stat = status_width,
@@ -100,21 +117,21 @@ pub fn display_games_list(games: &[GameRecord]) {
             });
 
         let status = match game.game.status {
-            GameStatus::Pending => "⏳ Pending",
-            GameStatus::Active => "🎮 Active",
-            GameStatus::Completed => "✅ Done",
-            GameStatus::Abandoned => "❌ Abandoned",
+            GameStatus::Pending => format!("⏳ {}", tr(Msg::StatusPending)),
+            GameStatus::Active => format!("🎮 {}", tr(Msg::StatusActive)),
+            GameStatus::Completed => format!("✅ {}", tr(Msg::StatusDone)),
+            GameStatus::Abandoned => format!("❌ {}", tr(Msg::StatusAbandoned)),
         };
 
         let color = match game.game.my_color {
-            crate::storage::models::PlayerColor::White => "⚪ White",
-            crate::storage::models::PlayerColor::Black => "⚫ Black",
+            crate::storage::models::PlayerColor::White => format!("⚪ {}", tr(Msg::White)),
+            crate::storage::models::PlayerColor::Black => format!("⚫ {}", tr(Msg::Black)),
         };
 
         let turn_indicator = if game.your_turn {
-            "👤 You"
+            format!("👤 {}", tr(Msg::TurnYou))
         } else {
-            "👥 Them"
+            format!("👥 {}", tr(Msg::TurnThem))
         };
 
         println!(
@@ -151,7 +168,152 @@ pub fn display_games_list(games: &[GameRecord]) {
         width_turn = turn_width + 2
     );
 
-    println!("\n{} game(s) total", games.len());
+    println!("\n{}", trf(Msg::GamesTotal, &[("count", &games.len())]));
+}
+
+/// A column of the `mate games` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameColumn {
+    Id,
+    Opponent,
+    Color,
+    Status,
+    Created,
+    Updated,
+    Result,
+}
+
+impl GameColumn {
+    /// Names accepted by `mate games --columns`
+    pub const NAMES: [&'static str; 7] = [
+        "id", "opponent", "color", "status", "created", "updated", "result",
+    ];
+
+    /// Columns shown when `--columns` is not given
+    pub const DEFAULT: [GameColumn; 6] = [
+        GameColumn::Id,
+        GameColumn::Opponent,
+        GameColumn::Color,
+        GameColumn::Status,
+        GameColumn::Updated,
+        GameColumn::Result,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "id" => Some(GameColumn::Id),
+            "opponent" => Some(GameColumn::Opponent),
+            "color" => Some(GameColumn::Color),
+            "status" => Some(GameColumn::Status),
+            "created" => Some(GameColumn::Created),
+            "updated" => Some(GameColumn::Updated),
+            "result" => Some(GameColumn::Result),
+            _ => None,
+        }
+    }
+
+    pub fn header(&self) -> &'static str {
+        tr(match self {
+            GameColumn::Id => Msg::ColumnGameId,
+            GameColumn::Opponent => Msg::ColumnOpponent,
+            GameColumn::Color => Msg::ColumnColor,
+            GameColumn::Status => Msg::ColumnStatus,
+            GameColumn::Created => Msg::ColumnCreated,
+            GameColumn::Updated => Msg::ColumnUpdated,
+            GameColumn::Result => Msg::ColumnResult,
+        })
+    }
+
+    /// Width the column is padded to, excluding the separating space
+    pub fn width(&self) -> usize {
+        match self {
+            GameColumn::Id => 12,
+            GameColumn::Opponent => 20,
+            GameColumn::Color => 8,
+            GameColumn::Status => 10,
+            GameColumn::Created | GameColumn::Updated => 15,
+            GameColumn::Result => 10,
+        }
+    }
+}
+
+/// One line of the `mate games` table, each cell padded to its column's width
+pub fn game_table_line<S: AsRef<str>>(columns: &[GameColumn], cells: &[S]) -> String {
+    columns
+        .iter()
+        .zip(cells)
+        .map(|(column, cell)| format!("{:<width$}", cell.as_ref(), width = column.width()))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end()
+        .to_string()
+}
+
+/// Longest opponent name shown in a compact game list
+const COMPACT_NAME_WIDTH: usize = 12;
+
+/// One line of `mate games --compact`: a `*` when it is your move, the
+/// short game ID, your color, the opponent, and the status or result
+pub fn compact_game_line(record: &GameRecord) -> String {
+    let game = &record.game;
+    let marker = if record.your_turn && game.status == GameStatus::Active {
+        '*'
+    } else {
+        ' '
+    };
+    let id: String = game.id.chars().take(8).collect();
+    let color = match game.my_color {
+        PlayerColor::White => 'W',
+        PlayerColor::Black => 'B',
+    };
+    let opponent = record
+        .opponent_name
+        .as_deref()
+        .unwrap_or(&game.opponent_peer_id);
+    let opponent = if opponent.chars().count() > COMPACT_NAME_WIDTH {
+        let short: String = opponent.chars().take(COMPACT_NAME_WIDTH - 1).collect();
+        format!("{short}~")
+    } else {
+        opponent.to_string()
+    };
+    let state = match &game.result {
+        Some(result) => result.as_str(),
+        None => game.status.as_str(),
+    };
+    format!("{marker} {id:<8} {color} {opponent:<COMPACT_NAME_WIDTH$} {state}")
+}
+
+/// The two lines of `mate board --compact`
+///
+/// The first holds the position as the leading FEN fields (placement, side
+/// to move, castling, en passant), the second the last move and, for timed
+/// games, White's and Black's clocks.
+pub fn compact_board_lines(
+    board: &Board,
+    move_history: &[String],
+    clocks: Option<[u64; 2]>,
+) -> [String; 2] {
+    let fen = board.to_fen();
+    let position = fen.split_whitespace().take(4).collect::<Vec<_>>().join(" ");
+
+    let last_move = match move_history.last() {
+        Some(last) => {
+            let ply = move_history.len();
+            let dots = if ply.is_multiple_of(2) { "..." } else { "." };
+            format!("{}{} {}", ply.div_ceil(2), dots, last)
+        }
+        None => "start".to_string(),
+    };
+    let clocks = match clocks {
+        Some([white, black]) => format!(
+            "W {} B {}",
+            format_elapsed((white / 1000) as i64),
+            format_elapsed((black / 1000) as i64)
+        ),
+        None => "untimed".to_string(),
+    };
+
+    [position, format!("{last_move}  {clocks}")]
 }
 
 /// Display a chess board from the specified perspective
@@ -165,13 +327,7 @@ pub fn display_board(board: &Board, perspective: Color) {
         Color::Black => display_board_black_perspective(board),
     }
 
-    // Show game status information
-    println!("To move: {}", board.active_color());
-    println!("Move #: {}", board.fullmove_number());
-
-    if board.halfmove_clock() > 0 {
-        println!("Halfmove clock: {} (50-move rule)", board.halfmove_clock());
-    }
+    display_board_status(board);
 }
 
 /// Display board with Unicode pieces (default)
@@ -188,12 +344,33 @@ pub fn display_board_ascii(board: &Board, perspective: Color) {
         Color::Black => display_board_black_perspective_ascii(board),
     }
 
-    // Show game status information
-    println!("To move: {}", board.active_color());
-    println!("Move #: {}", board.fullmove_number());
+    display_board_status(board);
+}
+
+/// Show whose turn it is and the move counters below a board
+fn display_board_status(board: &Board) {
+    println!(
+        "{}",
+        trf(Msg::ToMove, &[("color", &side_name(board.active_color()))])
+    );
+    println!(
+        "{}",
+        trf(Msg::MoveNumber, &[("number", &board.fullmove_number())])
+    );
 
     if board.halfmove_clock() > 0 {
-        println!("Halfmove clock: {} (50-move rule)", board.halfmove_clock());
+        println!(
+            "{}",
+            trf(Msg::HalfmoveClock, &[("count", &board.halfmove_clock())])
+        );
+    }
+}
+
+/// Name of a side in the current locale
+pub fn side_name(color: Color) -> &'static str {
+    match color {
+        Color::White => tr(Msg::White),
+        Color::Black => tr(Msg::Black),
     }
 }
 
@@ -363,13 +540,18 @@ fn piece_to_ascii_char(piece: &crate::chess::Piece) -> String {
 /// Display move history in a formatted table
 pub fn display_move_history(history: &[String], current_move: u32) {
     if history.is_empty() {
-        println!("No moves in history.");
+        println!("{}", tr(Msg::NoMovesInHistory));
         return;
     }
 
-    println!("\nMove History");
+    println!("\n{}", tr(Msg::MoveHistory));
     println!("┌──────┬─────────┬─────────┐");
-    println!("│ Move │  White  │  Black  │");
+    println!(
+        "│ {:<4} │ {:^7} │ {:^7} │",
+        tr(Msg::HistoryMove),
+        tr(Msg::White),
+        tr(Msg::Black)
+    );
     println!("├──────┼─────────┼─────────┤");
 
     for (i, move_pair) in history.chunks(2).enumerate() {
@@ -384,7 +566,7 @@ pub fn display_move_history(history: &[String], current_move: u32) {
     }
 
     println!("└──────┴─────────┴─────────┘");
-    println!("Current move: {}", current_move);
+    println!("{}", trf(Msg::CurrentMove, &[("number", &current_move)]));
 }
 
 /// Display game status with color coding
@@ -392,20 +574,19 @@ pub fn display_game_status(
     status: &GameStatus,
     result: Option<&crate::storage::models::GameResult>,
 ) {
-    match status {
-        GameStatus::Pending => println!("⏳ Game Status: Waiting for opponent to accept"),
-        GameStatus::Active => println!("🎮 Game Status: In progress"),
+    let (icon, msg) = match status {
+        GameStatus::Pending => ("⏳", Msg::GameStatusPending),
+        GameStatus::Active => ("🎮", Msg::GameStatusActive),
         GameStatus::Completed => match result {
-            Some(crate::storage::models::GameResult::Win) => println!("🏆 Game Status: You won!"),
-            Some(crate::storage::models::GameResult::Loss) => println!("😞 Game Status: You lost"),
-            Some(crate::storage::models::GameResult::Draw) => println!("🤝 Game Status: Draw"),
-            Some(crate::storage::models::GameResult::Abandoned) => {
-                println!("🚫 Game Status: Abandoned")
-            }
-            None => println!("✅ Game Status: Completed"),
+            Some(crate::storage::models::GameResult::Win) => ("🏆", Msg::GameStatusWon),
+            Some(crate::storage::models::GameResult::Loss) => ("😞", Msg::GameStatusLost),
+            Some(crate::storage::models::GameResult::Draw) => ("🤝", Msg::GameStatusDraw),
+            Some(crate::storage::models::GameResult::Abandoned) => ("🚫", Msg::GameStatusAbandoned),
+            None => ("✅", Msg::GameStatusCompleted),
         },
-        GameStatus::Abandoned => println!("❌ Game Status: Abandoned"),
-    }
+        GameStatus::Abandoned => ("❌", Msg::GameStatusAbandoned),
+    };
+    println!("{icon} {}", tr(msg));
 }
 
 /// Check if terminal supports Unicode chess pieces
@@ -423,10 +604,99 @@ pub fn supports_unicode() -> bool {
         || std::env::var("TERM_PROGRAM").is_ok() // macOS Terminal, iTerm2, etc.
 }
 
+/// A destructive action waiting on the user's go-ahead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confirmation {
+    /// What is about to happen, asked as a question, e.g. "Purge this data"
+    pub question: String,
+    /// What the action will do, one line each, shown before the question
+    pub consequences: Vec<String>,
+}
+
+impl Confirmation {
+    pub fn new(question: impl Into<String>) -> Self {
+        Self {
+            question: question.into(),
+            consequences: Vec::new(),
+        }
+    }
+
+    /// Add a line to the summary of what the action will do
+    pub fn consequence(mut self, consequence: impl Into<String>) -> Self {
+        self.consequences.push(consequence.into());
+        self
+    }
+}
+
+/// Ask on the terminal whether to go ahead with a destructive action
+///
+/// Returns `true` without asking when `yes` (the `--yes` flag) is set.
+/// Fails instead of waiting for input when stdin is not a terminal, so
+/// scripts must opt in with `--yes`.
+pub fn confirm(confirmation: &Confirmation, yes: bool) -> anyhow::Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(CliError::UserError {
+            message: tr(Msg::ErrConfirmationNeedsTerminal).to_string(),
+            suggestion: Some(tr(Msg::SugConfirmWithYes).to_string()),
+            code: ErrorCode::InvalidInput,
+        }
+        .into());
+    }
+    let confirmed = ask_confirmation(confirmation, io::stdin().lock(), io::stdout())
+        .map_err(|e| anyhow::anyhow!("Failed to read confirmation: {e}"))?;
+    if !confirmed {
+        println!("{}", tr(Msg::ConfirmCancelled));
+    }
+    Ok(confirmed)
+}
+
+/// Show the summary on `output` and read the answer from `input`
+///
+/// Only an explicit yes confirms; an empty answer or end of input declines.
+pub fn ask_confirmation<R: io::BufRead, W: Write>(
+    confirmation: &Confirmation,
+    mut input: R,
+    mut output: W,
+) -> io::Result<bool> {
+    if !confirmation.consequences.is_empty() {
+        writeln!(output, "{}", tr(Msg::ConfirmConsequences))?;
+        for consequence in &confirmation.consequences {
+            writeln!(output, "  - {consequence}")?;
+        }
+    }
+
+    let yes_answers: Vec<&str> = tr(Msg::ConfirmYesAnswers).split(',').collect();
+    loop {
+        write!(
+            output,
+            "{}",
+            trf(Msg::ConfirmPrompt, &[("question", &confirmation.question)])
+        )?;
+        output.flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            writeln!(output)?;
+            return Ok(false);
+        }
+        let answer = answer.trim().to_lowercase();
+        if yes_answers.contains(&answer.as_str()) {
+            return Ok(true);
+        }
+        if answer.is_empty() || answer == "n" || answer == "no" {
+            return Ok(false);
+        }
+        writeln!(output, "{}", tr(Msg::ConfirmRetry))?;
+    }
+}
+
 /// Interactive function to get user's display preference
 pub fn get_display_preference() -> bool {
     if supports_unicode() {
-        print!("Use Unicode chess pieces? [Y/n]: ");
+        print!("{}", tr(Msg::UnicodePrompt));
         io::stdout().flush().unwrap();
 
         let mut input = String::new();
@@ -440,3 +710,915 @@ pub fn get_display_preference() -> bool {
         false // Fall back to ASCII for terminals that don't support Unicode
     }
 }
+
+/// Color support of the terminal, from richest to none
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    /// 24-bit RGB colors
+    TrueColor,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// No colors; boards are drawn with the plain renderer
+    None,
+}
+
+impl ColorSupport {
+    /// Detect color support for stdout
+    ///
+    /// Honors `NO_COLOR` and reports no color when stdout is not a terminal.
+    pub fn detect() -> Self {
+        if std::env::var_os("NO_COLOR").is_some() || !io::stdout().is_terminal() {
+            return ColorSupport::None;
+        }
+        Self::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Color support implied by the `COLORTERM` and `TERM` values
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorSupport::TrueColor;
+        }
+        match term {
+            Some(term)
+                if term.contains("256color")
+                    || term.starts_with("tmux")
+                    || term.starts_with("screen")
+                    || term == "alacritty"
+                    || term.contains("kitty") =>
+            {
+                ColorSupport::Ansi256
+            }
+            _ => ColorSupport::None,
+        }
+    }
+}
+
+/// A 24-bit color, downgraded to the 256-color palette when needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Nearest color in the xterm 256-color cube
+    pub fn to_ansi256(self) -> u8 {
+        let level = |c: u8| match c {
+            0..=47 => 0,
+            48..=114 => 1,
+            _ => (c - 35) / 40,
+        };
+        16 + 36 * level(self.0) + 6 * level(self.1) + level(self.2)
+    }
+
+    /// Escape sequence selecting this color as the foreground
+    fn fg(self, support: ColorSupport) -> String {
+        self.sgr(38, support)
+    }
+
+    /// Escape sequence selecting this color as the background
+    fn bg(self, support: ColorSupport) -> String {
+        self.sgr(48, support)
+    }
+
+    fn sgr(self, layer: u8, support: ColorSupport) -> String {
+        match support {
+            ColorSupport::TrueColor => {
+                format!("\x1B[{layer};2;{};{};{}m", self.0, self.1, self.2)
+            }
+            ColorSupport::Ansi256 => format!("\x1B[{layer};5;{}m", self.to_ansi256()),
+            ColorSupport::None => String::new(),
+        }
+    }
+}
+
+/// Resets all colors and attributes
+const RESET: &str = "\x1B[0m";
+
+/// Square and piece colors for the themed board renderer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub light_square: Rgb,
+    pub dark_square: Rgb,
+    pub white_piece: Rgb,
+    pub black_piece: Rgb,
+    /// Background of the squares the last move left and reached
+    pub last_move: Rgb,
+    /// Background of a king in check
+    pub check: Rgb,
+}
+
+/// Built-in board themes, selected with `--theme` or `display.theme`
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "classic",
+        light_square: Rgb(240, 217, 181),
+        dark_square: Rgb(181, 136, 99),
+        white_piece: Rgb(255, 255, 255),
+        black_piece: Rgb(0, 0, 0),
+        last_move: Rgb(205, 210, 106),
+        check: Rgb(235, 97, 80),
+    },
+    Theme {
+        name: "green",
+        light_square: Rgb(238, 238, 210),
+        dark_square: Rgb(118, 150, 86),
+        white_piece: Rgb(255, 255, 255),
+        black_piece: Rgb(0, 0, 0),
+        last_move: Rgb(246, 246, 105),
+        check: Rgb(235, 97, 80),
+    },
+    Theme {
+        name: "blue",
+        light_square: Rgb(222, 227, 230),
+        dark_square: Rgb(140, 162, 173),
+        white_piece: Rgb(255, 255, 255),
+        black_piece: Rgb(0, 0, 0),
+        last_move: Rgb(155, 199, 0),
+        check: Rgb(235, 97, 80),
+    },
+    Theme {
+        name: "mono",
+        light_square: Rgb(208, 208, 208),
+        dark_square: Rgb(138, 138, 138),
+        white_piece: Rgb(255, 255, 255),
+        black_piece: Rgb(0, 0, 0),
+        last_move: Rgb(95, 135, 175),
+        check: Rgb(175, 95, 95),
+    },
+];
+
+/// Theme name that turns a configured theme off
+pub const NO_THEME: &str = "none";
+
+impl Theme {
+    /// Look up a built-in theme by name
+    pub fn by_name(name: &str) -> Option<&'static Theme> {
+        THEMES
+            .iter()
+            .find(|theme| theme.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Names accepted by `--theme`, including [`NO_THEME`]
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        THEMES
+            .iter()
+            .map(|theme| theme.name)
+            .chain(std::iter::once(NO_THEME))
+    }
+}
+
+/// Squares to highlight when drawing a position
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Highlights {
+    /// Origin and destination of the move that led to the position
+    pub last_move: Option<(Position, Position)>,
+    /// Square of the side to move's king when it is in check
+    pub check: Option<Position>,
+}
+
+impl Highlights {
+    /// Highlights for a position reached by `last_move` (coordinate notation)
+    pub fn new(board: &Board, last_move: Option<&str>) -> Self {
+        let mover = board.active_color().opposite();
+        let last_move = last_move
+            .and_then(|notation| Move::from_str_with_color(notation, mover).ok())
+            .map(|mv| (mv.from, mv.to));
+
+        let to_move = board.active_color();
+        let check = if board.is_in_check(to_move) {
+            board.find_king(to_move)
+        } else {
+            None
+        };
+
+        Self { last_move, check }
+    }
+
+    fn contains_last_move(&self, position: Position) -> bool {
+        self.last_move
+            .is_some_and(|(from, to)| position == from || position == to)
+    }
+}
+
+/// How boards are drawn: piece glyphs and an optional color theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardStyle {
+    pub unicode: bool,
+    pub theme: Option<&'static Theme>,
+    pub colors: ColorSupport,
+}
+
+impl BoardStyle {
+    /// Print a board, falling back to the plain renderer without a theme or
+    /// without terminal color support
+    pub fn display(&self, board: &Board, perspective: Color, highlights: &Highlights) {
+        match self.theme {
+            Some(theme) if self.colors != ColorSupport::None => {
+                println!();
+                for line in render_board_themed(
+                    board,
+                    perspective,
+                    theme,
+                    self.colors,
+                    self.unicode,
+                    highlights,
+                ) {
+                    println!("{line}");
+                }
+                display_board_status(board);
+            }
+            _ if self.unicode => display_board(board, perspective),
+            _ => display_board_ascii(board, perspective),
+        }
+    }
+}
+
+/// Render a board with theme colors, one string per line
+pub fn render_board_themed(
+    board: &Board,
+    perspective: Color,
+    theme: &Theme,
+    colors: ColorSupport,
+    unicode: bool,
+    highlights: &Highlights,
+) -> Vec<String> {
+    let ranks: Vec<u8> = match perspective {
+        Color::White => (0..8).rev().collect(),
+        Color::Black => (0..8).collect(),
+    };
+    let files: Vec<u8> = match perspective {
+        Color::White => (0..8).collect(),
+        Color::Black => (0..8).rev().collect(),
+    };
+
+    let mut lines = Vec::with_capacity(9);
+    for &rank in &ranks {
+        let mut line = format!("{} ", rank + 1);
+        for &file in &files {
+            let position = Position::new_unchecked(file, rank);
+            let background = if highlights.check == Some(position) {
+                theme.check
+            } else if highlights.contains_last_move(position) {
+                theme.last_move
+            } else if (file + rank) % 2 == 0 {
+                theme.dark_square
+            } else {
+                theme.light_square
+            };
+
+            line.push_str(&background.bg(colors));
+            match board.get_piece(position) {
+                Some(piece) => {
+                    let color = match piece.color {
+                        Color::White => theme.white_piece,
+                        Color::Black => theme.black_piece,
+                    };
+                    // Solid glyphs for both sides; the foreground color tells them apart
+                    let glyph = if unicode {
+                        Piece::new(piece.piece_type, Color::Black).to_string()
+                    } else {
+                        piece_to_ascii_char(&piece)
+                    };
+                    line.push_str(&format!("{}\x1B[1m {glyph} ", color.fg(colors)));
+                }
+                None => line.push_str("   "),
+            }
+            line.push_str(RESET);
+        }
+        lines.push(line);
+    }
+
+    let footer: String = files
+        .iter()
+        .map(|&file| format!(" {} ", (b'a' + file) as char))
+        .collect();
+    lines.push(format!("  {footer}"));
+    lines
+}
+
+/// Output format selected with the global `--json` flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human-readable tables and boards
+    #[default]
+    Text,
+    /// One JSON document per command, using the `*Json` types below
+    Json,
+}
+
+/// Version of the JSON output schema
+///
+/// Fields may be added without a bump; renaming or removing a field, or
+/// changing its type, increments the version.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Print a value as pretty JSON on stdout
+pub fn print_json<T: Serialize>(value: &T) -> serde_json::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print a value as a single line of JSON on stdout, for streamed output
+pub fn print_json_line<T: Serialize>(value: &T) -> serde_json::Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    io::stdout().flush().map_err(serde_json::Error::io)
+}
+
+/// A game as it appears in `games` and `history` output
+///
+/// Colors, statuses, and results use the lowercase names stored in the
+/// database (`"white"`, `"active"`, `"win"`, ...). Timestamps are Unix seconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameJson {
+    pub id: String,
+    pub opponent: String,
+    pub color: String,
+    pub status: String,
+    pub result: Option<String>,
+    pub move_count: u32,
+    pub your_turn: bool,
+    /// Unix seconds since when the game has waited on your move
+    pub your_move_since: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub completed_at: Option<i64>,
+    pub tags: Vec<String>,
+    /// Why the game is blocked pending a resync, if it is
+    pub needs_sync: Option<String>,
+    /// Odds the game is played at, e.g. "White gives Nb1"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub odds: Option<String>,
+    /// `"rated"` or `"casual"`
+    pub mode: String,
+    /// What the opponent last said about their availability
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opponent_presence: Option<PresenceJson>,
+}
+
+impl GameJson {
+    /// Build from a game record plus its tags and sync flag
+    pub fn new(record: &GameRecord, tags: Vec<String>, needs_sync: Option<String>) -> Self {
+        let game = &record.game;
+        Self {
+            id: game.id.clone(),
+            opponent: game.opponent_peer_id.clone(),
+            color: game.my_color.as_str().to_string(),
+            status: game.status.as_str().to_string(),
+            result: game.result.as_ref().map(|r| r.as_str().to_string()),
+            move_count: record.move_count,
+            your_turn: record.your_turn,
+            your_move_since: None,
+            created_at: game.created_at,
+            updated_at: game.updated_at,
+            completed_at: game.completed_at,
+            tags,
+            needs_sync,
+            odds: record.odds.as_ref().map(ToString::to_string),
+            mode: game_mode(record.rated).to_string(),
+            opponent_presence: None,
+        }
+    }
+}
+
+/// A peer's last presence in `games` and `peers` output
+#[derive(Debug, Clone, Serialize)]
+pub struct PresenceJson {
+    /// `"available"`, `"busy"`, or `"away"`
+    pub status: String,
+    pub updated_at: i64,
+}
+
+impl From<&PeerPresence> for PresenceJson {
+    fn from(presence: &PeerPresence) -> Self {
+        Self {
+            status: presence.status.clone(),
+            updated_at: presence.updated_at,
+        }
+    }
+}
+
+/// `mate games --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct GamesJson {
+    pub schema_version: u32,
+    pub games: Vec<GameJson>,
+    /// Number of games matching the filters, across all pages
+    pub total: u32,
+}
+
+/// `mate board --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardJson {
+    pub schema_version: u32,
+    pub game_id: String,
+    pub fen: String,
+    /// `"white"` or `"black"`
+    pub to_move: String,
+    pub fullmove_number: u16,
+    pub halfmove_clock: u16,
+    pub your_turn: bool,
+    pub in_check: bool,
+    pub checkmate: bool,
+    pub stalemate: bool,
+    /// Moves played so far, in coordinate notation
+    pub moves: Vec<String>,
+}
+
+impl BoardJson {
+    /// Build from a game's current board and move list
+    pub fn new(game_id: &str, board: &Board, moves: Vec<String>, your_turn: bool) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            game_id: game_id.to_string(),
+            fen: board.to_fen(),
+            to_move: color_name(board.active_color()).to_string(),
+            fullmove_number: board.fullmove_number(),
+            halfmove_clock: board.halfmove_clock(),
+            your_turn,
+            in_check: board.is_in_check(board.active_color()),
+            checkmate: board.is_checkmate(),
+            stalemate: board.is_stalemate(),
+            moves,
+        }
+    }
+}
+
+/// A single move in `history` output
+#[derive(Debug, Clone, Serialize)]
+pub struct MoveJson {
+    /// Full-move number the move belongs to
+    pub number: u32,
+    /// `"white"` or `"black"`
+    pub color: String,
+    pub notation: String,
+    pub timestamp: i64,
+}
+
+/// `mate history --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryJson {
+    pub schema_version: u32,
+    pub game: GameJson,
+    pub note: Option<String>,
+    pub moves: Vec<MoveJson>,
+}
+
+/// `mate timeline --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineJson {
+    pub schema_version: u32,
+    pub game: GameJson,
+    /// Oldest first
+    pub entries: Vec<TimelineEntry>,
+}
+
+/// A move of a study line, in `studies` output
+#[derive(Debug, Clone, Serialize)]
+pub struct StudyMoveJson {
+    pub san: String,
+    /// Coordinate notation, e.g. "e2e4"
+    pub coordinate: String,
+    pub comment: Option<String>,
+}
+
+/// A study as it appears in `studies` output
+#[derive(Debug, Clone, Serialize)]
+pub struct StudyJson {
+    pub id: String,
+    /// The peer who sent the study, or to whom it was sent
+    pub peer: String,
+    /// `"sent"` or `"received"`
+    pub direction: String,
+    pub title: String,
+    pub fen: String,
+    pub comment: Option<String>,
+    pub moves: Vec<StudyMoveJson>,
+    pub created_at: i64,
+}
+
+/// `mate studies --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct StudiesJson {
+    pub schema_version: u32,
+    /// Most recent first
+    pub studies: Vec<StudyJson>,
+}
+
+/// A peer as it appears in `peers list` and `peers info` output
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerJson {
+    pub peer_id: String,
+    /// Reputation from 0 to 100
+    pub reputation: u32,
+    pub handshake_successes: u32,
+    pub handshake_failures: u32,
+    pub protocol_violations: u32,
+    pub dos_events: u32,
+    pub first_seen: Option<i64>,
+    pub last_seen: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence: Option<PresenceJson>,
+}
+
+impl From<&PeerSummary> for PeerJson {
+    fn from(summary: &PeerSummary) -> Self {
+        Self {
+            peer_id: summary.peer_id.clone(),
+            reputation: summary.reputation_score(),
+            handshake_successes: summary.handshake_successes,
+            handshake_failures: summary.handshake_failures,
+            protocol_violations: summary.protocol_violations,
+            dos_events: summary.dos_events,
+            first_seen: summary.first_seen,
+            last_seen: summary.last_seen,
+            presence: None,
+        }
+    }
+}
+
+/// `mate peers list --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PeersJson {
+    pub schema_version: u32,
+    pub peers: Vec<PeerJson>,
+}
+
+/// A recorded connection event in `peers info` output
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerEventJson {
+    /// Event name, e.g. `"handshake_success"`
+    pub event_type: String,
+    pub remote_addr: Option<String>,
+    pub details: Option<String>,
+    pub timestamp: i64,
+}
+
+impl From<&PeerEvent> for PeerEventJson {
+    fn from(event: &PeerEvent) -> Self {
+        Self {
+            event_type: event.event_type.as_str().to_string(),
+            remote_addr: event.remote_addr.clone(),
+            details: event.details.clone(),
+            timestamp: event.created_at,
+        }
+    }
+}
+
+/// `mate peers info --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerInfoJson {
+    pub schema_version: u32,
+    pub peer: PeerJson,
+    /// Most recent events first
+    pub recent_events: Vec<PeerEventJson>,
+    /// Whether the peer's chat is dropped
+    pub muted: bool,
+    /// Reports against the peer, newest first
+    pub reports: Vec<PeerReport>,
+}
+
+/// `mate report --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportJson {
+    pub schema_version: u32,
+    pub report: PeerReport,
+    /// Number of messages attached as evidence
+    pub evidence_count: usize,
+}
+
+/// `mate peers mute --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct MutedPeersJson {
+    pub schema_version: u32,
+    pub peers: Vec<String>,
+}
+
+/// A game played on a linked device, in `devices list` output
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceGameJson {
+    pub game_id: String,
+    #[serde(flatten)]
+    pub game: GameIndexEntry,
+}
+
+/// A linked device in `devices list` output
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceJson {
+    #[serde(flatten)]
+    pub device: LinkedDevice,
+    pub games: Vec<DeviceGameJson>,
+}
+
+/// `mate devices list --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct DevicesJson {
+    pub schema_version: u32,
+    pub devices: Vec<DeviceJson>,
+}
+
+/// A game we arbitrate, in `arbiter list` output
+#[derive(Debug, Clone, Serialize)]
+pub struct ArbitrationJson {
+    #[serde(flatten)]
+    pub arbitration: Arbitration,
+    pub rulings: Vec<ArbitrationRuling>,
+}
+
+/// `mate arbiter list --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct ArbitrationsJson {
+    pub schema_version: u32,
+    pub arbitrations: Vec<ArbitrationJson>,
+}
+
+/// `mate telemetry show --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReportJson {
+    pub schema_version: u32,
+    /// Whether usage reports are turned on
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    /// The report exactly as it would be sent
+    pub report: UsageReport,
+}
+
+/// `mate peers alias --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct AliasesJson {
+    pub schema_version: u32,
+    pub aliases: Vec<PeerAlias>,
+}
+
+/// One line of `mate connect --stdin` output, describing one message sent
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectReplyJson {
+    pub schema_version: u32,
+    /// 1-based position of the message in the input
+    pub seq: usize,
+    pub message: String,
+    pub ok: bool,
+    /// Payload echoed back by the peer
+    pub response: Option<String>,
+    pub round_trip_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl ConnectReplyJson {
+    pub fn success(seq: usize, message: String, response: String, round_trip: Duration) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            seq,
+            message,
+            ok: true,
+            response: Some(response),
+            round_trip_ms: Some(round_trip.as_secs_f64() * 1000.0),
+            error: None,
+        }
+    }
+
+    pub fn failure(seq: usize, message: String, error: String) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            seq,
+            message,
+            ok: false,
+            response: None,
+            round_trip_ms: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Listening state of the local server in `mate status --json`
+#[derive(Debug, Serialize)]
+pub struct ServerStatusJson {
+    pub bind_addr: String,
+    pub listening: bool,
+    /// Address the router forwards to the server, when `mate serve` mapped
+    /// a port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_addr: Option<String>,
+}
+
+/// `mate status --json`
+#[derive(Debug, Serialize)]
+pub struct StatusJson {
+    pub schema_version: u32,
+    pub peer_id: String,
+    pub database: String,
+    pub server: ServerStatusJson,
+    /// Peers that completed a handshake recently
+    pub recent_peers: Vec<PeerJson>,
+    pub awaiting_your_move: Vec<GameJson>,
+    pub pending_invitations: Vec<GameJson>,
+    /// Connection statistics of the local server, with `--network`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkStatusJson>,
+}
+
+/// Connection statistics in `mate status --network --json`, read from the
+/// metrics endpoint of `mate serve`
+#[derive(Debug, Serialize)]
+pub struct NetworkStatusJson {
+    pub metrics_addr: String,
+    /// `None` when the endpoint could not be reached
+    pub stats: Option<TrafficSnapshot>,
+}
+
+/// `mate top --json`: one reading of the metrics endpoint of `mate serve`
+#[derive(Debug, Serialize)]
+pub struct TopJson {
+    pub schema_version: u32,
+    pub metrics_addr: String,
+    pub server: MonitorSnapshot,
+}
+
+/// Any command's failure with `--json`
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorJson {
+    pub schema_version: u32,
+    pub error: ErrorDetailJson,
+}
+
+/// What went wrong, in [`ErrorJson`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorDetailJson {
+    /// Stable machine-readable category, e.g. `"chess_rule"` or `"network"`
+    pub code: ErrorCode,
+    /// The status the process exits with
+    pub exit_status: i32,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl ErrorJson {
+    pub fn new(error: &CliError) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            error: ErrorDetailJson {
+                code: error.code(),
+                exit_status: error.exit_status(),
+                message: error.message(),
+                suggestion: error.suggestion().map(str::to_string),
+            },
+        }
+    }
+}
+
+/// `mate doctor --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorJson {
+    pub schema_version: u32,
+    pub checks: Vec<Check>,
+}
+
+/// `mate certificate verify --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateJson {
+    pub schema_version: u32,
+    pub game_id: String,
+    pub white: String,
+    pub black: String,
+    pub result: String,
+    pub moves: usize,
+    /// Peer ID of the arbiter who co-signed the result, if any
+    pub arbiter: Option<String>,
+}
+
+/// One opening or line in `mate openings --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct OpeningJson {
+    pub eco: Option<String>,
+    pub name: Option<String>,
+    /// Moves (SAN) shared by every game in the group
+    pub moves: Vec<String>,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Percentage of points won in finished games
+    pub score: Option<f64>,
+    pub game_ids: Vec<String>,
+}
+
+impl From<&OpeningSummary> for OpeningJson {
+    fn from(summary: &OpeningSummary) -> Self {
+        Self {
+            eco: summary.opening.map(|o| o.eco.to_string()),
+            name: summary.opening.map(|o| o.name.to_string()),
+            moves: summary.moves.clone(),
+            games: summary.games,
+            wins: summary.wins,
+            draws: summary.draws,
+            losses: summary.losses,
+            score: summary.score(),
+            game_ids: summary.game_ids.clone(),
+        }
+    }
+}
+
+/// `mate openings --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct OpeningsJson {
+    pub schema_version: u32,
+    pub openings: Vec<OpeningJson>,
+}
+
+/// One group of games in `mate stats --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsGroupJson {
+    /// Period name, color, time control, or `all`
+    pub group: String,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Percentage of points won
+    pub score: Option<f64>,
+    pub average_moves: Option<f64>,
+}
+
+impl StatsGroupJson {
+    pub fn new(group: String, tally: &ResultTally) -> Self {
+        Self {
+            group,
+            games: tally.games,
+            wins: tally.wins,
+            draws: tally.draws,
+            losses: tally.losses,
+            score: tally.score(),
+            average_moves: tally.average_moves(),
+        }
+    }
+}
+
+/// `mate stats --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsJson {
+    pub schema_version: u32,
+    /// `week`, `month`, or `year`
+    pub period: String,
+    pub totals: StatsGroupJson,
+    /// Oldest first
+    pub periods: Vec<StatsGroupJson>,
+    pub colors: Vec<StatsGroupJson>,
+    /// Most played first
+    pub time_controls: Vec<StatsGroupJson>,
+    /// Elo rating over rated games, rounded; null before any were decided
+    pub rating: Option<i64>,
+    pub rated_games: u32,
+}
+
+impl From<&GameStatsReport> for StatsJson {
+    fn from(report: &GameStatsReport) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            period: report.period.as_str().to_string(),
+            totals: StatsGroupJson::new("all".to_string(), &report.totals),
+            periods: report
+                .periods
+                .iter()
+                .map(|(period, tally)| StatsGroupJson::new(period.clone(), tally))
+                .collect(),
+            colors: report
+                .colors
+                .iter()
+                .map(|(color, tally)| StatsGroupJson::new(color.as_str().to_string(), tally))
+                .collect(),
+            time_controls: report
+                .time_controls
+                .iter()
+                .map(|(time_control, tally)| {
+                    StatsGroupJson::new(format_time_control(time_control.as_ref()), tally)
+                })
+                .collect(),
+            rating: report.rating.map(|rating| rating.rating.round() as i64),
+            rated_games: report.rating.map_or(0, |rating| rating.games),
+        }
+    }
+}
+
+/// `mate export --format json`
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportJson {
+    pub schema_version: u32,
+    pub games: Vec<GameExport>,
+}
+
+impl ExportJson {
+    pub fn new(games: Vec<GameExport>) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            games,
+        }
+    }
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
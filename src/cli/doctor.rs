@@ -0,0 +1,496 @@
+use crate::cli::app::{Config, NetworkSettings};
+use crate::cli::display::{Theme, NO_THEME};
+use crate::cli::i18n::Locale;
+use crate::crypto::storage::active_key_path_in;
+#[cfg(unix)]
+use crate::crypto::storage::is_private_mode;
+use crate::crypto::Identity;
+use crate::messages::types::Message;
+use crate::messages::RetryStrategy;
+use crate::network::Client;
+use crate::storage::{Database, DatabaseLocation};
+use serde::Serialize;
+use std::net::{TcpListener, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Clock difference to a peer, in seconds, beyond which `mate doctor` warns
+pub const CLOCK_SKEW_WARNING_SECS: i64 = 30;
+
+/// Outcome of one diagnostic check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// Not run, usually because an earlier check failed or input was missing
+    Skip,
+}
+
+impl CheckStatus {
+    fn symbol(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✓",
+            CheckStatus::Warn => "⚠",
+            CheckStatus::Fail => "✗",
+            CheckStatus::Skip => "-",
+        }
+    }
+}
+
+/// Result of one diagnostic check, with a suggested fix for anything wrong
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skip,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// What `mate doctor` should examine beyond the local installation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DoctorOptions {
+    /// Database to check instead of the configured one
+    pub db_path: Option<PathBuf>,
+    /// Peer address to test outbound connectivity and clock skew against
+    pub peer: Option<String>,
+}
+
+/// Run every check in order
+///
+/// Checks never abort the run; a check that depends on an earlier failed
+/// one is reported as skipped.
+pub async fn run_checks(options: &DoctorOptions) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let (config_check, config) = check_config();
+    checks.push(config_check);
+    // Later checks still run against the defaults when the file is broken
+    let config = config.unwrap_or_default();
+
+    let (identity_check, identity) = check_identity(&active_key_path_in(&config.data_dir));
+    checks.push(identity_check);
+
+    let db_path = match &options.db_path {
+        Some(path) => Ok(path.clone()),
+        None => config.effective_database_path(),
+    };
+    let (database_check, latest_stored) = match db_path {
+        Ok(path) => check_database(&path),
+        Err(e) => (
+            Check::fail(
+                "database",
+                format!("Cannot determine the database location: {e}"),
+                "Set db_path with 'mate config set db_path <file>' or pass --db-path.",
+            ),
+            None,
+        ),
+    };
+    checks.push(database_check);
+
+    checks.push(check_port(&config.default_bind_addr));
+
+    let (peer_check, skew) = match &options.peer {
+        Some(address) => {
+            let identity = match identity {
+                Some(identity) => Ok(identity),
+                None => Identity::generate(),
+            };
+            match identity {
+                Ok(identity) => check_peer(Arc::new(identity), address, &config.network).await,
+                Err(e) => (
+                    Check::skip("peer", format!("No identity to connect with: {e}")),
+                    None,
+                ),
+            }
+        }
+        None => (
+            Check::skip(
+                "peer",
+                "No peer given; pass --peer <address> to test outbound connectivity",
+            ),
+            None,
+        ),
+    };
+    checks.push(peer_check);
+
+    // Peers refuse connections beyond the largest skew they allow
+    let skew_limit = config.network.max_clock_skew_secs as i64;
+    checks.push(check_clock(unix_now(), skew, latest_stored, skew_limit));
+
+    checks
+}
+
+/// Print checks as a table with fixes under anything that needs attention
+pub fn print_report(checks: &[Check]) {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in checks {
+        println!(
+            "{} {:<width$}  {}",
+            check.status.symbol(),
+            check.name,
+            check.detail
+        );
+        if let Some(fix) = &check.fix {
+            println!("  {:<width$}  💡 {fix}", "");
+        }
+    }
+
+    let failed = count(checks, CheckStatus::Fail);
+    let warned = count(checks, CheckStatus::Warn);
+    println!();
+    match (failed, warned) {
+        (0, 0) => println!("Everything looks good."),
+        (0, _) => println!("{warned} warning(s); mate should still work."),
+        _ => println!("{failed} check(s) failed and {warned} warning(s)."),
+    }
+}
+
+/// Number of checks with the given status
+pub fn count(checks: &[Check], status: CheckStatus) -> usize {
+    checks.iter().filter(|c| c.status == status).count()
+}
+
+fn check_config() -> (Check, Option<Config>) {
+    const NAME: &str = "config";
+
+    let path = match Config::default_config_file() {
+        Ok(path) => path,
+        Err(e) => {
+            return (
+                Check::fail(
+                    NAME,
+                    format!("Cannot locate the configuration file: {e}"),
+                    "Set MATE_CONFIG_DIR to a directory mate can use.",
+                ),
+                None,
+            )
+        }
+    };
+    let location = path.display();
+
+    let config = match Config::load_or_default() {
+        Ok(config) => config,
+        Err(e) => {
+            return (
+                Check::fail(
+                    NAME,
+                    format!("{location}: {e:#}"),
+                    format!("Correct the value, or delete {location} to start over with defaults."),
+                ),
+                None,
+            )
+        }
+    };
+
+    let mut problems = Vec::new();
+    if let Err(e) = config.default_bind_addr.to_socket_addrs() {
+        problems.push(format!(
+            "default_bind_addr '{}' is not a usable address ({e})",
+            config.default_bind_addr
+        ));
+    }
+    if config.network.connection_timeout_secs == 0 {
+        problems.push("network.connection_timeout_secs is 0".to_string());
+    }
+    if config.max_concurrent_games == 0 {
+        problems.push("max_concurrent_games is 0".to_string());
+    }
+    if let Some(theme) = config.display.theme.as_deref() {
+        if theme != NO_THEME && Theme::by_name(theme).is_none() {
+            problems.push(format!("display.theme '{theme}' is not a known theme"));
+        }
+    }
+    if let Some(locale) = config.display.locale.as_deref() {
+        if let Err(e) = locale.parse::<Locale>() {
+            problems.push(format!("display.locale: {e}"));
+        }
+    }
+
+    let check = if problems.is_empty() {
+        let source = if path.exists() {
+            format!("{location} is valid")
+        } else {
+            format!("No file at {location}; using defaults")
+        };
+        Check::pass(NAME, source)
+    } else {
+        Check::fail(
+            NAME,
+            problems.join("; "),
+            "Change the setting with 'mate config set <key> <value>'.",
+        )
+    };
+    (check, Some(config))
+}
+
+fn check_identity(path: &Path) -> (Check, Option<Identity>) {
+    const NAME: &str = "identity";
+    let location = path.display();
+
+    if !path.exists() {
+        return (
+            Check::warn(
+                NAME,
+                format!("No identity key at {location}"),
+                "Run 'mate init' or any game command to create one.",
+            ),
+            None,
+        );
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            let mode = metadata.permissions().mode() & 0o777;
+            if !is_private_mode(mode) {
+                return (
+                    Check::fail(
+                        NAME,
+                        format!("{location} has permissions {mode:o}; the key must be private"),
+                        format!("chmod 600 {location}"),
+                    ),
+                    None,
+                );
+            }
+        }
+    }
+
+    match Identity::from_storage_path(path) {
+        Ok(identity) => {
+            let detail = format!("{} (key at {location})", identity.peer_id());
+            (Check::pass(NAME, detail), Some(identity))
+        }
+        Err(e) => (
+            Check::fail(
+                NAME,
+                format!("Cannot read {location}: {e:#}"),
+                "Restore the key with 'mate import-account <backup>'; a new key changes your peer ID.",
+            ),
+            None,
+        ),
+    }
+}
+
+/// Check the database and return the newest timestamp stored in it
+fn check_database(path: &Path) -> (Check, Option<i64>) {
+    const NAME: &str = "database";
+    let location = path.display();
+
+    if !path.exists() {
+        return (
+            Check::pass(
+                NAME,
+                format!("No database at {location} yet; it is created on first use"),
+            ),
+            None,
+        );
+    }
+
+    let database = match Database::open("doctor", &DatabaseLocation::File(path.to_path_buf())) {
+        Ok(database) => database,
+        Err(e) => return (
+            Check::fail(
+                NAME,
+                format!("Cannot open {location}: {e}"),
+                "Check the file's permissions, or restore it with 'mate import-account <backup>'.",
+            ),
+            None,
+        ),
+    };
+
+    let latest = database.latest_timestamp().ok().flatten();
+    let check = match database.integrity_problems() {
+        Ok(problems) if problems.is_empty() => {
+            Check::pass(NAME, format!("{location} passed the integrity check"))
+        }
+        Ok(problems) => Check::fail(
+            NAME,
+            format!(
+                "{location} is damaged: {}{}",
+                problems[..problems.len().min(3)].join("; "),
+                if problems.len() > 3 { "; ..." } else { "" }
+            ),
+            "Restore it with 'mate import-account <backup>', or copy it aside and run 'sqlite3 <file> .recover'.",
+        ),
+        Err(e) => Check::fail(
+            NAME,
+            format!("Integrity check on {location} failed: {e}"),
+            "Make sure no other program holds the database open and try again.",
+        ),
+    };
+    (check, latest)
+}
+
+fn check_port(bind_addr: &str) -> Check {
+    const NAME: &str = "port";
+
+    match TcpListener::bind(bind_addr) {
+        Ok(_) => Check::pass(NAME, format!("{bind_addr} is free for 'mate serve'")),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => Check::warn(
+            NAME,
+            format!("{bind_addr} is already in use (by a running 'mate serve' or another program)"),
+            "Stop the other program, or pick another address with 'mate config set default_bind_addr 127.0.0.1:<port>'.",
+        ),
+        Err(e) => Check::fail(
+            NAME,
+            format!("Cannot listen on {bind_addr}: {e}"),
+            "Use a local address and a port above 1024: 'mate config set default_bind_addr 127.0.0.1:8080'.",
+        ),
+    }
+}
+
+/// Connect to a peer, exchange a ping, and measure how far its clock is from ours
+async fn check_peer(
+    identity: Arc<Identity>,
+    address: &str,
+    network: &NetworkSettings,
+) -> (Check, Option<i64>) {
+    const NAME: &str = "peer";
+
+    let timeout = Duration::from_secs(network.connection_timeout_secs);
+    let client = Client::new_with_config(identity, network.network_config().wire_config);
+    let started = Instant::now();
+    let mut connection = match tokio::time::timeout(
+        timeout,
+        client.connect_with_strategy(address, RetryStrategy::NoRetry),
+    )
+    .await
+    {
+        Ok(Ok(connection)) => connection,
+        Ok(Err(e)) => {
+            return (
+                Check::fail(
+                    NAME,
+                    format!("Cannot connect to {address}: {e:#}"),
+                    "Check the address, that the peer runs 'mate serve', and that no firewall blocks the port.",
+                ),
+                None,
+            )
+        }
+        Err(_) => {
+            return (
+                Check::fail(
+                    NAME,
+                    format!("Timed out connecting to {address} after {}s", timeout.as_secs()),
+                    "Check the address and your network, or raise network.connection_timeout_secs.",
+                ),
+                None,
+            )
+        }
+    };
+
+    let ping = Message::new_ping(rand::random(), "doctor".to_string());
+    let reply = match connection.send_message(ping).await {
+        Ok(()) => connection.receive_message().await,
+        Err(e) => Err(e),
+    };
+    let round_trip = started.elapsed();
+    // Estimated from the handshake round trip
+    let skew = connection.clock_offset();
+    let _ = connection.close().await;
+
+    let peer_id = connection
+        .peer_identity()
+        .unwrap_or("unknown peer")
+        .to_string();
+    let check = match reply {
+        Ok(_) => Check::pass(
+            NAME,
+            format!(
+                "Reached {peer_id} at {address} in {} ms",
+                round_trip.as_millis()
+            ),
+        ),
+        Err(e) => Check::fail(
+            NAME,
+            format!("Connected to {address} but the ping failed: {e}"),
+            "Make sure both sides run the same mate version; see the clock check below.",
+        ),
+    };
+    (check, skew)
+}
+
+/// Judge the local clock from the skew to a peer, or failing that from stored data
+fn check_clock(now: i64, peer_skew: Option<i64>, latest_stored: Option<i64>, limit: i64) -> Check {
+    const NAME: &str = "clock";
+    const FIX: &str = "Enable time synchronization (NTP) on this machine, or ask the peer to.";
+
+    if let Some(skew) = peer_skew {
+        let direction = if skew > 0 { "ahead of" } else { "behind" };
+        let detail = format!("Peer clock is {}s {direction} this one", skew.abs());
+        return match skew.abs() {
+            diff if diff > limit => Check::fail(
+                NAME,
+                format!("{detail}; connections will be refused for clock skew"),
+                FIX,
+            ),
+            diff if diff > CLOCK_SKEW_WARNING_SECS => Check::warn(NAME, detail, FIX),
+            diff => Check::pass(NAME, format!("Within {diff}s of the peer's clock")),
+        };
+    }
+
+    match latest_stored {
+        Some(latest) if latest > now + limit => Check::fail(
+            NAME,
+            format!(
+                "Clock is {}s behind the newest stored game data",
+                latest - now
+            ),
+            FIX,
+        ),
+        _ => Check::pass(
+            NAME,
+            "Not behind stored game data (pass --peer to compare with a peer)",
+        ),
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
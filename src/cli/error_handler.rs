@@ -1,10 +1,116 @@
 use crate::chess::ChessError;
-use crate::cli::GameOpsError;
+use crate::cli::i18n::{tr, trf, Msg};
+use crate::cli::usage::UsageLog;
+use crate::cli::{GameOpsError, MoveProcessingError};
 use crate::messages::chess::ChessProtocolError;
 use crate::messages::wire::WireProtocolError;
 use crate::network::ConnectionError;
 use crate::storage::errors::StorageError;
+use serde::Serialize;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Machine-readable category of a CLI error
+///
+/// Each code maps to a fixed process exit status, so scripts can tell, say,
+/// a network failure from a move the rules don't allow. Codes and statuses
+/// are stable; new ones are only ever added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Anything not covered by a more specific code
+    Internal,
+    /// A command-line argument or typed value was rejected
+    InvalidInput,
+    /// The configuration or identity setup is unusable
+    Configuration,
+    /// The chess rules don't allow what was asked, e.g. an illegal move
+    ChessRule,
+    /// No game was given and none is in progress
+    NoCurrentGame,
+    /// No game matches the given ID
+    GameNotFound,
+    /// A game selector matched more than one game
+    AmbiguousGame,
+    /// A game's stored state is inconsistent or doesn't allow the action
+    GameState,
+    /// The database could not be read or written
+    Storage,
+    /// The database stayed locked by another process
+    DatabaseLocked,
+    /// The peer could not be reached or the connection failed
+    Network,
+    /// A network operation timed out
+    Timeout,
+    /// The peer sent something the protocol doesn't allow
+    Protocol,
+}
+
+impl ErrorCode {
+    /// Every code, in order of exit status
+    pub const ALL: [ErrorCode; 13] = [
+        ErrorCode::Internal,
+        ErrorCode::InvalidInput,
+        ErrorCode::Configuration,
+        ErrorCode::ChessRule,
+        ErrorCode::NoCurrentGame,
+        ErrorCode::GameNotFound,
+        ErrorCode::AmbiguousGame,
+        ErrorCode::GameState,
+        ErrorCode::Storage,
+        ErrorCode::DatabaseLocked,
+        ErrorCode::Network,
+        ErrorCode::Timeout,
+        ErrorCode::Protocol,
+    ];
+
+    /// The code as it appears in `--json` error output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::Internal => "internal",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::Configuration => "configuration",
+            ErrorCode::ChessRule => "chess_rule",
+            ErrorCode::NoCurrentGame => "no_current_game",
+            ErrorCode::GameNotFound => "game_not_found",
+            ErrorCode::AmbiguousGame => "ambiguous_game",
+            ErrorCode::GameState => "game_state",
+            ErrorCode::Storage => "storage",
+            ErrorCode::DatabaseLocked => "database_locked",
+            ErrorCode::Network => "network",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::Protocol => "protocol",
+        }
+    }
+
+    /// The process exit status for errors with this code
+    ///
+    /// 2 matches the status used for command-line usage errors.
+    pub fn exit_status(self) -> i32 {
+        match self {
+            ErrorCode::Internal => 1,
+            ErrorCode::InvalidInput => 2,
+            ErrorCode::Configuration => 3,
+            ErrorCode::ChessRule => 4,
+            ErrorCode::NoCurrentGame => 5,
+            ErrorCode::GameNotFound => 6,
+            ErrorCode::AmbiguousGame => 7,
+            ErrorCode::GameState => 8,
+            ErrorCode::Storage => 9,
+            ErrorCode::DatabaseLocked => 10,
+            ErrorCode::Network => 11,
+            ErrorCode::Timeout => 12,
+            ErrorCode::Protocol => 13,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 /// Unified error type for CLI operations with user-friendly messages
 #[derive(Debug)]
@@ -44,9 +150,76 @@ pub enum CliError {
     UserError {
         message: String,
         suggestion: Option<String>,
+        code: ErrorCode,
     },
 }
 
+impl CliError {
+    /// The error's machine-readable category
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CliError::GameOps(e) => game_ops_error_code(e),
+            CliError::Chess(e) => chess_error_code(e),
+            CliError::Storage(e) => storage_error_code(e),
+            CliError::Connection(ConnectionError::WireProtocol(e)) => wire_error_code(e),
+            CliError::Connection(_) => ErrorCode::Network,
+            CliError::Protocol(ChessProtocolError::ChessEngine(e)) => chess_error_code(e),
+            CliError::Protocol(ChessProtocolError::Timeout { .. }) => ErrorCode::Timeout,
+            CliError::Protocol(_) => ErrorCode::Protocol,
+            CliError::Wire(e) => wire_error_code(e),
+            CliError::InvalidInput { .. } => ErrorCode::InvalidInput,
+            CliError::Configuration { .. } => ErrorCode::Configuration,
+            CliError::NetworkTimeout { .. } => ErrorCode::Timeout,
+            CliError::UserError { code, .. } => *code,
+        }
+    }
+
+    /// The process exit status for this error
+    pub fn exit_status(&self) -> i32 {
+        self.code().exit_status()
+    }
+
+    /// The error on its own, without decoration or suggestion
+    pub fn message(&self) -> String {
+        match self {
+            CliError::GameOps(e) => e.to_string(),
+            CliError::Chess(e) => e.to_string(),
+            CliError::Storage(e) => e.to_string(),
+            CliError::Connection(e) => e.to_string(),
+            CliError::Protocol(e) => e.to_string(),
+            CliError::Wire(e) => e.to_string(),
+            CliError::InvalidInput {
+                field,
+                value,
+                reason,
+                ..
+            } => format!(
+                "{}: {reason}",
+                trf(Msg::ErrInvalidInput, &[("field", field), ("value", value)])
+            ),
+            CliError::Configuration { setting, issue, .. } => format!(
+                "{}: {issue}",
+                trf(Msg::ErrConfiguration, &[("setting", setting)])
+            ),
+            CliError::NetworkTimeout { operation, .. } => {
+                trf(Msg::ErrNetworkTimeout, &[("operation", operation)])
+            }
+            CliError::UserError { message, .. } => message.clone(),
+        }
+    }
+
+    /// What the user can do about the error, when the error carries a hint
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            CliError::InvalidInput { suggestion, .. }
+            | CliError::Configuration { suggestion, .. }
+            | CliError::NetworkTimeout { suggestion, .. } => Some(suggestion),
+            CliError::UserError { suggestion, .. } => suggestion.as_deref(),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for CliError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -62,10 +235,12 @@ impl fmt::Display for CliError {
                 reason,
                 suggestion,
             } => {
+                let message = trf(Msg::ErrInvalidInput, &[("field", field), ("value", value)]);
                 write!(
                     f,
-                    "❌ Invalid {}: '{}'\n   Reason: {}\n   💡 Suggestion: {}",
-                    field, value, reason, suggestion
+                    "❌ {message}\n   {}: {reason}\n   💡 {}: {suggestion}",
+                    tr(Msg::Reason),
+                    tr(Msg::Suggestion)
                 )
             }
             CliError::Configuration {
@@ -73,10 +248,12 @@ impl fmt::Display for CliError {
                 issue,
                 suggestion,
             } => {
+                let message = trf(Msg::ErrConfiguration, &[("setting", setting)]);
                 write!(
                     f,
-                    "⚙️  Configuration Error: {}\n   Issue: {}\n   💡 Suggestion: {}",
-                    setting, issue, suggestion
+                    "⚙️  {message}\n   {}: {issue}\n   💡 {}: {suggestion}",
+                    tr(Msg::Issue),
+                    tr(Msg::Suggestion)
                 )
             }
             CliError::NetworkTimeout {
@@ -84,18 +261,25 @@ impl fmt::Display for CliError {
                 timeout_seconds,
                 suggestion,
             } => {
+                let message = trf(Msg::ErrNetworkTimeout, &[("operation", operation)]);
+                let timeout = trf(Msg::TimeoutSeconds, &[("seconds", timeout_seconds)]);
                 write!(
                     f,
-                    "⏱️  Network timeout during {}\n   Timeout: {} seconds\n   💡 Suggestion: {}",
-                    operation, timeout_seconds, suggestion
+                    "⏱️  {message}\n   {timeout}\n   💡 {}: {suggestion}",
+                    tr(Msg::Suggestion)
                 )
             }
             CliError::UserError {
                 message,
                 suggestion,
+                ..
             } => {
                 if let Some(suggestion) = suggestion {
-                    write!(f, "❌ {}\n   💡 Suggestion: {}", message, suggestion)
+                    write!(
+                        f,
+                        "{}",
+                        with_suggestion(format!("❌ {message}"), suggestion)
+                    )
                 } else {
                     write!(f, "❌ {}", message)
                 }
@@ -145,18 +329,44 @@ impl From<ConnectionError> for CliError {
 
 impl From<anyhow::Error> for CliError {
     fn from(err: anyhow::Error) -> Self {
+        // Errors already phrased for the user pass through any added context
+        let err = match err.downcast::<CliError>() {
+            Ok(cli_error) => return cli_error,
+            Err(err) => err,
+        };
+
         // For anyhow errors, create a generic user error with the error chain
         let root_cause = err.root_cause();
         let error_string = err.to_string().to_lowercase();
         let root_cause_string = root_cause.to_string().to_lowercase();
 
+        // Surface game selection problems as-is, whatever context was added on top
+        if matches!(
+            err.downcast_ref::<GameOpsError>(),
+            Some(
+                GameOpsError::GameNotFound(_)
+                    | GameOpsError::AmbiguousGame { .. }
+                    | GameOpsError::InvalidGameState(_)
+            )
+        ) {
+            return match err.downcast::<GameOpsError>() {
+                Ok(game_error) => CliError::GameOps(game_error),
+                Err(err) => CliError::UserError {
+                    message: err.to_string(),
+                    suggestion: None,
+                    code: ErrorCode::GameState,
+                },
+            };
+        }
+
         // Check for specific network errors first
         if error_string.contains("failed to connect")
             || root_cause_string.contains("connection refused")
         {
             return CliError::UserError {
-                message: "Failed to connect to server".to_string(),
-                suggestion: Some("Check that the address is correct and the peer is online. Verify network connectivity.".to_string()),
+                message: tr(Msg::ErrConnectFailed).to_string(),
+                suggestion: Some(tr(Msg::SugConnectFailed).to_string()),
+                code: ErrorCode::Network,
             };
         }
 
@@ -164,37 +374,35 @@ impl From<anyhow::Error> for CliError {
             || root_cause_string.contains("address too long")
         {
             return CliError::UserError {
-                message: "Network address is too long".to_string(),
-                suggestion: Some(
-                    "Use a shorter address format like 'host:port' (e.g., '127.0.0.1:8080')."
-                        .to_string(),
-                ),
+                message: tr(Msg::ErrAddressTooLong).to_string(),
+                suggestion: Some(tr(Msg::SugAddressTooLong).to_string()),
+                code: ErrorCode::InvalidInput,
             };
         }
 
         if error_string.contains("timeout") || root_cause_string.contains("timeout") {
             return CliError::UserError {
-                message: "Network operation timed out".to_string(),
-                suggestion: Some("The peer may be slow to respond or unreachable. Check connectivity and try again.".to_string()),
+                message: tr(Msg::ErrTimedOut).to_string(),
+                suggestion: Some(tr(Msg::SugTimedOut).to_string()),
+                code: ErrorCode::Timeout,
             };
         }
 
         if error_string.contains("invalid address") || root_cause_string.contains("invalid address")
         {
             return CliError::UserError {
-                message: "Invalid network address format".to_string(),
-                suggestion: Some(
-                    "Use format 'host:port' (e.g., '192.168.1.100:8080' or 'example.com:8080')."
-                        .to_string(),
-                ),
+                message: tr(Msg::ErrInvalidAddress).to_string(),
+                suggestion: Some(tr(Msg::SugAddressFormat).to_string()),
+                code: ErrorCode::InvalidInput,
             };
         }
 
         // Check for database-related errors
         if error_string.contains("database") || root_cause_string.contains("database") {
             return CliError::UserError {
-                message: "Database operation failed".to_string(),
-                suggestion: Some("Check file permissions and database integrity. Try restarting the application.".to_string()),
+                message: tr(Msg::ErrDatabaseFailed).to_string(),
+                suggestion: Some(tr(Msg::SugDatabaseFailed).to_string()),
+                code: ErrorCode::Storage,
             };
         }
 
@@ -204,17 +412,15 @@ impl From<anyhow::Error> for CliError {
             || root_cause_string.contains("connection")
         {
             return CliError::UserError {
-                message: "Network operation failed".to_string(),
-                suggestion: Some(
-                    "Check network connectivity and peer availability. Try reconnecting."
-                        .to_string(),
-                ),
+                message: tr(Msg::ErrNetworkFailed).to_string(),
+                suggestion: Some(tr(Msg::SugNetworkFailed).to_string()),
+                code: ErrorCode::Network,
             };
         }
 
         // For other anyhow errors, create a generic user error but avoid exposing raw technical details
         let user_message = if error_string.contains("anyhow") || error_string.contains("error:") {
-            "An unexpected error occurred".to_string()
+            tr(Msg::ErrUnexpected).to_string()
         } else {
             // Use the error message but clean it up
             err.to_string()
@@ -222,212 +428,363 @@ impl From<anyhow::Error> for CliError {
 
         CliError::UserError {
             message: user_message,
-            suggestion: Some("Check the error details above and try again. If the problem persists, this may be a bug.".to_string()),
+            suggestion: Some(tr(Msg::SugUnexpected).to_string()),
+            code: anyhow_error_code(&err),
         }
     }
 }
 
+/// The code for an error with no more specific handling, from the first
+/// error in its chain that has one
+fn anyhow_error_code(err: &anyhow::Error) -> ErrorCode {
+    err.chain()
+        .find_map(|cause| {
+            if let Some(e) = cause.downcast_ref::<CliError>() {
+                Some(e.code())
+            } else if let Some(e) = cause.downcast_ref::<GameOpsError>() {
+                Some(game_ops_error_code(e))
+            } else if let Some(e) = cause.downcast_ref::<MoveProcessingError>() {
+                Some(move_processing_error_code(e))
+            } else if let Some(e) = cause.downcast_ref::<ChessError>() {
+                Some(chess_error_code(e))
+            } else if let Some(e) = cause.downcast_ref::<StorageError>() {
+                Some(storage_error_code(e))
+            } else if let Some(e) = cause.downcast_ref::<WireProtocolError>() {
+                Some(wire_error_code(e))
+            } else if let Some(ConnectionError::WireProtocol(e)) =
+                cause.downcast_ref::<ConnectionError>()
+            {
+                Some(wire_error_code(e))
+            } else if cause.downcast_ref::<ConnectionError>().is_some() {
+                Some(ErrorCode::Network)
+            } else if cause.downcast_ref::<ChessProtocolError>().is_some() {
+                Some(ErrorCode::Protocol)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(ErrorCode::Internal)
+}
+
+fn game_ops_error_code(error: &GameOpsError) -> ErrorCode {
+    match error {
+        GameOpsError::Database(e) => storage_error_code(e),
+        GameOpsError::Chess(e) => chess_error_code(e),
+        GameOpsError::Serialization(_) => ErrorCode::Internal,
+        GameOpsError::InvalidGameState(_) => ErrorCode::GameState,
+        GameOpsError::NoCurrentGame => ErrorCode::NoCurrentGame,
+        GameOpsError::GameNotFound(_) => ErrorCode::GameNotFound,
+        GameOpsError::AmbiguousGame { .. } => ErrorCode::AmbiguousGame,
+    }
+}
+
+fn move_processing_error_code(error: &MoveProcessingError) -> ErrorCode {
+    match error {
+        MoveProcessingError::GameOps(e) => game_ops_error_code(e),
+        MoveProcessingError::Chess(e) => chess_error_code(e),
+        MoveProcessingError::InvalidMove(_) => ErrorCode::ChessRule,
+        MoveProcessingError::InvalidGameState(_)
+        | MoveProcessingError::BoardStateError(_)
+        | MoveProcessingError::HistoryError(_) => ErrorCode::GameState,
+        MoveProcessingError::TransactionError(_) => ErrorCode::Storage,
+        MoveProcessingError::SignatureError(_) => ErrorCode::Protocol,
+    }
+}
+
+fn chess_error_code(error: &ChessError) -> ErrorCode {
+    match error {
+        ChessError::InvalidMove(_)
+        | ChessError::InvalidPosition(_)
+        | ChessError::BoardStateError(_) => ErrorCode::ChessRule,
+        ChessError::InvalidFen(_)
+        | ChessError::InvalidColor(_)
+        | ChessError::InvalidPieceType(_) => ErrorCode::InvalidInput,
+    }
+}
+
+fn storage_error_code(error: &StorageError) -> ErrorCode {
+    match error {
+        StorageError::GameNotFound { .. } => ErrorCode::GameNotFound,
+        StorageError::DatabaseLocked { .. } => ErrorCode::DatabaseLocked,
+        _ => ErrorCode::Storage,
+    }
+}
+
+fn wire_error_code(error: &WireProtocolError) -> ErrorCode {
+    match error {
+        WireProtocolError::ReadTimeout { .. }
+        | WireProtocolError::WriteTimeout { .. }
+        | WireProtocolError::OperationTimeout { .. } => ErrorCode::Timeout,
+        WireProtocolError::Io(_)
+        | WireProtocolError::ConnectionClosed { .. }
+        | WireProtocolError::UnexpectedEof { .. } => ErrorCode::Network,
+        _ => ErrorCode::Protocol,
+    }
+}
+
+/// An error line followed by an indented suggestion
+fn with_suggestion(message: impl fmt::Display, suggestion: impl fmt::Display) -> String {
+    format!("{message}\n   💡 {}: {suggestion}", tr(Msg::Suggestion))
+}
+
 /// Format game operations errors with user-friendly messages
 fn format_game_ops_error(error: &GameOpsError) -> String {
     match error {
-        GameOpsError::NoCurrentGame => {
-            "🎮 No active games found.\n   💡 Suggestion: Start a new game with 'mate invite <address>' or use --game-id to specify a game.".to_string()
-        }
-        GameOpsError::GameNotFound(id) => {
-            format!("🎮 Game '{id}' not found.\n   💡 Suggestion: Use 'mate games' to see available games, or check the game ID.")
-        }
-        GameOpsError::InvalidGameState(msg) => {
-            format!("🎮 Invalid game state: {msg}\n   💡 Suggestion: Check the game status with 'mate games' and ensure the game is active.")
-        }
+        GameOpsError::NoCurrentGame => with_suggestion(
+            format!("🎮 {}", tr(Msg::ErrNoActiveGames)),
+            tr(Msg::SugNoActiveGames),
+        ),
+        GameOpsError::GameNotFound(id) => with_suggestion(
+            format!("🎮 {}", trf(Msg::ErrGameNotFound, &[("id", id)])),
+            tr(Msg::SugGameNotFound),
+        ),
+        GameOpsError::AmbiguousGame { .. } => {
+            with_suggestion(format!("🎮 {error}"), tr(Msg::SugAmbiguousGame))
+        }
+        GameOpsError::InvalidGameState(msg) => with_suggestion(
+            format!("🎮 {}", trf(Msg::ErrInvalidGameState, &[("reason", msg)])),
+            tr(Msg::SugInvalidGameState),
+        ),
         GameOpsError::Database(e) => format_storage_error(e),
         GameOpsError::Chess(e) => format_chess_error(e),
-        GameOpsError::Serialization(msg) => {
-            format!("🔧 Data format error: {msg}\n   💡 Suggestion: This may be a bug. Please report this issue.")
-        }
+        GameOpsError::Serialization(msg) => with_suggestion(
+            format!("🔧 {}", trf(Msg::ErrDataFormat, &[("reason", msg)])),
+            tr(Msg::SugReportBug),
+        ),
     }
 }
 
 /// Format chess engine errors with user-friendly messages
 fn format_chess_error(error: &ChessError) -> String {
-    match error {
-        ChessError::InvalidMove(msg) => {
-            format!("♟️  Invalid move: {msg}\n   💡 Suggestion: Use standard algebraic notation (e.g., 'e4', 'Nf3', 'O-O'). Use 'mate board' to see the current position.")
-        }
-        ChessError::InvalidPosition(msg) => {
-            format!("♟️  Invalid position: {msg}\n   💡 Suggestion: Check the board position with 'mate board' command.")
-        }
-        ChessError::InvalidFen(msg) => {
-            format!(
-                "♟️  Invalid board notation: {}\n   💡 Suggestion: Check the FEN string format.",
-                msg
-            )
-        }
-        ChessError::InvalidColor(msg) => {
-            format!("♟️  Invalid color: {msg}\n   💡 Suggestion: Use 'white' or 'black' for color selection.")
-        }
-        ChessError::InvalidPieceType(msg) => {
-            format!("♟️  Invalid piece: {msg}\n   💡 Suggestion: Use standard piece letters (K, Q, R, B, N, P).")
-        }
-        ChessError::BoardStateError(msg) => {
-            format!("♟️  Board state error: {msg}\n   💡 Suggestion: The game state may be corrupted. Try 'mate board' to see the current position.")
-        }
-    }
+    let (message, reason, suggestion) = match error {
+        ChessError::InvalidMove(msg) => (Msg::ErrInvalidMove, msg, Msg::SugMoveNotation),
+        ChessError::InvalidPosition(msg) => (Msg::ErrInvalidPosition, msg, Msg::SugCheckPosition),
+        ChessError::InvalidFen(msg) => (Msg::ErrInvalidFen, msg, Msg::SugCheckFen),
+        ChessError::InvalidColor(msg) => (Msg::ErrInvalidColor, msg, Msg::SugColorSelection),
+        ChessError::InvalidPieceType(msg) => (Msg::ErrInvalidPiece, msg, Msg::SugPieceLetters),
+        ChessError::BoardStateError(msg) => (Msg::ErrBoardState, msg, Msg::SugBoardCorrupted),
+    };
+    with_suggestion(
+        format!("♟️  {}", trf(message, &[("reason", reason)])),
+        tr(suggestion),
+    )
 }
 
 /// Format storage errors with user-friendly messages
 fn format_storage_error(error: &StorageError) -> String {
     match error {
-        StorageError::GameNotFound { id } => {
-            format!("🗃️  Game '{id}' not found in database.\n   💡 Suggestion: Use 'mate games' to see available games.")
-        }
-        StorageError::MessageNotFound { id } => {
-            format!("🗃️  Message '{id}' not found.\n   💡 Suggestion: Check the message ID or game history.")
-        }
-        StorageError::ConnectionFailed(_) => {
-            "🗃️  Database connection failed.\n   💡 Suggestion: Check file permissions and disk space. Try restarting the application.".to_string()
-        }
-        StorageError::DatabaseLocked { operation, timeout_ms } => {
-            format!("🗃️  Database is locked during {operation}.\n   Timeout: {timeout_ms}ms\n   💡 Suggestion: Another process may be using the database. Wait a moment and try again.")
-        }
-        StorageError::InvalidData { field, reason } => {
-            format!("🗃️  Invalid data in {field}: {reason}\n   💡 Suggestion: Check the data format and try again.")
-        }
-        _ => {
-            let recovery = error.recovery_suggestion();
-            format!("🗃️  Database error: {error}\n   💡 Suggestion: {recovery}")
-        }
+        StorageError::GameNotFound { id } => with_suggestion(
+            format!("🗃️  {}", trf(Msg::ErrStoredGameNotFound, &[("id", id)])),
+            tr(Msg::SugSeeGames),
+        ),
+        StorageError::MessageNotFound { id } => with_suggestion(
+            format!("🗃️  {}", trf(Msg::ErrMessageNotFound, &[("id", id)])),
+            tr(Msg::SugCheckMessage),
+        ),
+        StorageError::ConnectionFailed(_) => with_suggestion(
+            format!("🗃️  {}", tr(Msg::ErrDatabaseConnection)),
+            tr(Msg::SugDatabaseConnection),
+        ),
+        StorageError::DatabaseLocked {
+            operation,
+            timeout_ms,
+        } => with_suggestion(
+            format!(
+                "🗃️  {}\n   {}",
+                trf(Msg::ErrDatabaseLocked, &[("operation", operation)]),
+                trf(Msg::TimeoutMillis, &[("ms", timeout_ms)])
+            ),
+            tr(Msg::SugDatabaseLocked),
+        ),
+        StorageError::InvalidData { field, reason } => with_suggestion(
+            format!(
+                "🗃️  {}",
+                trf(Msg::ErrInvalidData, &[("field", field), ("reason", reason)])
+            ),
+            tr(Msg::SugCheckData),
+        ),
+        _ => with_suggestion(
+            format!("🗃️  {}", trf(Msg::ErrDatabase, &[("error", error)])),
+            error.recovery_suggestion(),
+        ),
     }
 }
 
 /// Format connection errors with user-friendly messages
 fn format_connection_error(error: &ConnectionError) -> String {
-    match error {
-        ConnectionError::WireProtocol(_wire_err) => {
-            "🌐 Communication protocol error\n   💡 Suggestion: Check network connection and ensure both players use compatible versions.".to_string()
-        }
-        ConnectionError::HandshakeFailed { reason: _ } => {
-            // Don't expose technical handshake details
-            "🤝 Failed to connect to peer\n   💡 Suggestion: Verify the peer address is correct and the peer is online. Check for network connectivity issues.".to_string()
-        }
-        ConnectionError::AuthenticationFailed { peer_id: _ } => {
-            "🔐 Authentication failed with peer\n   💡 Suggestion: The peer may be using different credentials. Ensure both players have compatible identities.".to_string()
-        }
-        ConnectionError::ConnectionClosed => {
-            "🌐 Connection closed unexpectedly\n   💡 Suggestion: The peer may have disconnected. Try reconnecting to continue the game.".to_string()
-        }
-        ConnectionError::InvalidSignature => {
-            "🔒 Message verification failed\n   💡 Suggestion: This may indicate a security issue or incompatible software versions. Try reconnecting.".to_string()
-        }
-        ConnectionError::InvalidTimestamp => {
-            "🕐 Message timing validation failed\n   💡 Suggestion: Check that your system clock is synchronized. Try reconnecting.".to_string()
-        }
-        ConnectionError::Io(_) => {
-            // Don't expose raw I/O error details
-            "🌐 Failed to connect to server\n   💡 Suggestion: Check that the address is correct and the peer is reachable. Verify network connectivity.".to_string()
-        }
-    }
+    // Technical details such as handshake reasons and raw I/O errors are not shown
+    let (icon, message, suggestion) = match error {
+        ConnectionError::WireProtocol(_) => (
+            "🌐",
+            tr(Msg::ErrProtocolCommunication).to_string(),
+            Msg::SugCompatibleVersions,
+        ),
+        ConnectionError::HandshakeFailed { .. } => (
+            "🤝",
+            tr(Msg::ErrPeerConnect).to_string(),
+            Msg::SugPeerConnect,
+        ),
+        ConnectionError::AuthenticationFailed { .. } => (
+            "🔐",
+            tr(Msg::ErrAuthentication).to_string(),
+            Msg::SugAuthentication,
+        ),
+        ConnectionError::ConnectionClosed => (
+            "🌐",
+            tr(Msg::ErrConnectionClosed).to_string(),
+            Msg::SugConnectionClosed,
+        ),
+        ConnectionError::InvalidSignature => (
+            "🔒",
+            tr(Msg::ErrVerification).to_string(),
+            Msg::SugVerification,
+        ),
+        ConnectionError::InvalidTimestamp => ("🕐", tr(Msg::ErrTiming).to_string(), Msg::SugTiming),
+        ConnectionError::ClockSkew {
+            offset_secs,
+            max_secs,
+        } => (
+            "🕐",
+            trf(
+                Msg::ErrClockSkew,
+                &[("offset", &offset_secs.unsigned_abs()), ("max", max_secs)],
+            ),
+            Msg::SugClockSkew,
+        ),
+        ConnectionError::SendQueueFull { capacity } => (
+            "📦",
+            trf(Msg::ErrSendQueueFull, &[("capacity", capacity)]),
+            Msg::SugSendQueueFull,
+        ),
+        ConnectionError::Io(_) => (
+            "🌐",
+            tr(Msg::ErrConnectFailed).to_string(),
+            Msg::SugServerReachable,
+        ),
+    };
+    with_suggestion(format!("{icon} {message}"), tr(suggestion))
 }
 
 /// Format protocol errors with user-friendly messages
 fn format_protocol_error(error: &ChessProtocolError) -> String {
     match error {
-        ChessProtocolError::Validation(msg) => {
-            format!("🔒 Message validation failed: {msg}\n   💡 Suggestion: This may indicate a communication issue. Try reconnecting.")
-        }
+        ChessProtocolError::Validation(msg) => with_suggestion(
+            format!("🔒 {}", trf(Msg::ErrValidation, &[("reason", msg)])),
+            tr(Msg::SugCommunicationIssue),
+        ),
         ChessProtocolError::Timeout {
             operation,
             duration_ms,
-        } => {
-            format!("⏱️  Operation '{operation}' timed out after {duration_ms}ms\n   💡 Suggestion: The peer may be slow to respond. Try again or check network connection.")
-        }
-        ChessProtocolError::GameStateError { game_id, error } => {
-            format!("🎮 Game state error in {game_id}: {error}\n   💡 Suggestion: The game state may be corrupted. Try 'mate board' to see current state.")
-        }
-        ChessProtocolError::SecurityViolation { game_id, violation } => {
-            format!("🔒 Security violation in game {game_id}: {violation}\n   💡 Suggestion: This may indicate a malicious peer. Consider ending the game.")
-        }
-        _ => {
-            format!("🔒 Protocol error: {error}\n   💡 Suggestion: This may be a communication issue. Try reconnecting to the peer.")
-        }
+        } => with_suggestion(
+            format!(
+                "⏱️  {}",
+                trf(
+                    Msg::ErrOperationTimeout,
+                    &[("operation", operation), ("ms", duration_ms)]
+                )
+            ),
+            tr(Msg::SugSlowPeer),
+        ),
+        ChessProtocolError::GameStateError { game_id, error } => with_suggestion(
+            format!(
+                "🎮 {}",
+                trf(
+                    Msg::ErrGameStateIn,
+                    &[("game_id", game_id), ("error", error)]
+                )
+            ),
+            tr(Msg::SugGameStateCorrupted),
+        ),
+        ChessProtocolError::SecurityViolation { game_id, violation } => with_suggestion(
+            format!(
+                "🔒 {}",
+                trf(
+                    Msg::ErrSecurityViolation,
+                    &[("game_id", game_id), ("violation", violation)]
+                )
+            ),
+            tr(Msg::SugMaliciousPeer),
+        ),
+        _ => with_suggestion(
+            format!("🔒 {}", trf(Msg::ErrProtocol, &[("error", error)])),
+            tr(Msg::SugReconnectPeer),
+        ),
     }
 }
 
 /// Format wire protocol errors with user-friendly messages
 fn format_wire_error(error: &WireProtocolError) -> String {
-    match error {
-        WireProtocolError::InvalidMessageFormat { .. } => {
-            "📡 Invalid message format received\n   💡 Suggestion: This may indicate incompatible versions. Ensure both players are using the same version.".to_string()
-        }
-        WireProtocolError::MessageTooLarge { size, max_size } => {
-            format!("📡 Message too large: {size} bytes (max: {max_size} bytes)\n   💡 Suggestion: The message is too big to send. This may be a bug.")
-        }
-        WireProtocolError::Io(_) => {
-            "📡 Network I/O error\n   💡 Suggestion: Check network connection and try again.".to_string()
-        }
+    let message = match error {
+        WireProtocolError::InvalidMessageFormat { .. } => tr(Msg::ErrInvalidFormat).to_string(),
+        WireProtocolError::MessageTooLarge { size, max_size } => trf(
+            Msg::ErrMessageTooLarge,
+            &[("size", size), ("max_size", max_size)],
+        ),
+        WireProtocolError::Io(_) => tr(Msg::ErrNetworkIo).to_string(),
         WireProtocolError::ProtocolViolation { description } => {
-            format!("📡 Protocol violation: {description}\n   💡 Suggestion: This may indicate incompatible clients. Ensure both players use the same version.")
+            trf(Msg::ErrProtocolViolation, &[("description", description)])
         }
-        _ => {
-            format!("📡 Communication error: {error}\n   💡 Suggestion: Check network connection and try reconnecting.")
-        }
-    }
+        _ => trf(Msg::ErrCommunication, &[("error", error)]),
+    };
+    let suggestion = match error {
+        WireProtocolError::InvalidMessageFormat { .. } => Msg::SugSameVersion,
+        WireProtocolError::MessageTooLarge { .. } => Msg::SugMessageTooLarge,
+        WireProtocolError::Io(_) => Msg::SugCheckNetwork,
+        WireProtocolError::ProtocolViolation { .. } => Msg::SugIncompatibleClients,
+        _ => Msg::SugCheckAndReconnect,
+    };
+    with_suggestion(format!("📡 {message}"), tr(suggestion))
 }
 
 /// Handle specific error scenarios for chess commands
 pub fn handle_chess_command_error(error: CliError, command: &str) -> CliError {
     match command {
         "games" => match error {
-            CliError::Storage(StorageError::ConnectionFailed(_)) => {
-                CliError::UserError {
-                    message: "Cannot access game database".to_string(),
-                    suggestion: Some("Check file permissions and disk space. The database may be corrupted or locked by another process.".to_string()),
-                }
-            }
+            CliError::Storage(StorageError::ConnectionFailed(_)) => CliError::UserError {
+                message: tr(Msg::ErrCannotAccessDatabase).to_string(),
+                suggestion: Some(tr(Msg::SugCannotAccessDatabase).to_string()),
+                code: ErrorCode::Storage,
+            },
             _ => error,
         },
         "board" => match error {
-            CliError::GameOps(GameOpsError::NoCurrentGame) => {
-                CliError::UserError {
-                    message: "No game specified and no active games found".to_string(),
-                    suggestion: Some("Use 'mate games' to see available games, then 'mate board --game-id <id>' to view a specific game.".to_string()),
-                }
-            }
+            CliError::GameOps(GameOpsError::NoCurrentGame) => CliError::UserError {
+                message: tr(Msg::ErrNoGameSpecified).to_string(),
+                suggestion: Some(tr(Msg::SugNoGameSpecified).to_string()),
+                code: ErrorCode::NoCurrentGame,
+            },
             _ => error,
         },
         "invite" => match error {
-            CliError::Connection(_) => {
-                CliError::UserError {
-                    message: "Failed to send game invitation".to_string(),
-                    suggestion: Some("Check that the peer address is correct and reachable. The peer may be offline or behind a firewall.".to_string()),
-                }
-            }
+            CliError::Connection(_) => CliError::UserError {
+                message: tr(Msg::ErrInviteFailed).to_string(),
+                suggestion: Some(tr(Msg::SugInviteFailed).to_string()),
+                code: ErrorCode::Network,
+            },
             _ => error,
         },
         "accept" => match error {
-            CliError::GameOps(GameOpsError::GameNotFound(_)) => {
-                CliError::UserError {
-                    message: "Game invitation not found".to_string(),
-                    suggestion: Some("Use 'mate games' to see pending invitations. The invitation may have expired or been withdrawn.".to_string()),
-                }
-            }
+            CliError::GameOps(GameOpsError::GameNotFound(_)) => CliError::UserError {
+                message: tr(Msg::ErrInvitationNotFound).to_string(),
+                suggestion: Some(tr(Msg::SugInvitationNotFound).to_string()),
+                code: ErrorCode::GameNotFound,
+            },
             _ => error,
         },
         "move" => match error {
-            CliError::Chess(ChessError::InvalidMove(_)) => {
-                CliError::UserError {
-                    message: "Invalid chess move".to_string(),
-                    suggestion: Some("Use standard algebraic notation (e.g., 'e4', 'Nf3', 'O-O', 'Qxe7+'). Use 'mate board' to see the current position and legal moves.".to_string()),
-                }
-            }
+            CliError::Chess(ChessError::InvalidMove(_)) => CliError::UserError {
+                message: tr(Msg::ErrInvalidChessMove).to_string(),
+                suggestion: Some(tr(Msg::SugInvalidChessMove).to_string()),
+                code: ErrorCode::ChessRule,
+            },
             _ => error,
         },
         "history" => match error {
-            CliError::GameOps(GameOpsError::GameNotFound(_)) => {
-                CliError::UserError {
-                    message: "Game not found for history display".to_string(),
-                    suggestion: Some("Use 'mate games' to see available games, then 'mate history --game-id <id>' to view move history.".to_string()),
-                }
-            }
+            CliError::GameOps(GameOpsError::GameNotFound(_)) => CliError::UserError {
+                message: tr(Msg::ErrHistoryGameNotFound).to_string(),
+                suggestion: Some(tr(Msg::SugHistoryGameNotFound).to_string()),
+                code: ErrorCode::GameNotFound,
+            },
             _ => error,
         },
         _ => error,
@@ -436,13 +793,14 @@ pub fn handle_chess_command_error(error: CliError, command: &str) -> CliError {
 
 /// Create a network timeout error with helpful suggestions
 pub fn create_network_timeout_error(operation: &str, timeout_seconds: u64) -> CliError {
-    let suggestion = match operation {
-        "connect" => "The peer may be offline or unreachable. Verify the address and try again.".to_string(),
-        "send_invitation" => "The peer may be slow to respond. Try again or check if the peer is online.".to_string(),
-        "send_move" => "Move could not be sent. The peer may have disconnected. Check connection and try again.".to_string(),
-        "handshake" => "Initial connection handshake failed. The peer may be using incompatible software.".to_string(),
-        _ => "Network operation timed out. Check connection and try again.".to_string(),
-    };
+    let suggestion = tr(match operation {
+        "connect" => Msg::SugTimeoutConnect,
+        "send_invitation" => Msg::SugTimeoutInvite,
+        "send_move" => Msg::SugTimeoutMove,
+        "handshake" => Msg::SugTimeoutHandshake,
+        _ => Msg::SugTimeoutOther,
+    })
+    .to_string();
 
     CliError::NetworkTimeout {
         operation: operation.to_string(),
@@ -453,13 +811,14 @@ pub fn create_network_timeout_error(operation: &str, timeout_seconds: u64) -> Cl
 
 /// Create an input validation error with helpful suggestions
 pub fn create_input_validation_error(field: &str, value: &str, reason: &str) -> CliError {
-    let suggestion = match field {
-        "game_id" => "Game IDs should be in UUID format. Use 'mate games' to see valid game IDs.".to_string(),
-        "chess_move" => "Use standard algebraic notation (e.g., 'e4', 'Nf3', 'O-O'). Use 'mate board' to see the current position.".to_string(),
-        "color" => "Use 'white' or 'black' to specify player color.".to_string(),
-        "address" => "Use format 'host:port' (e.g., '192.168.1.100:8080' or 'example.com:8080').".to_string(),
-        _ => "Check the input format and try again.".to_string(),
-    };
+    let suggestion = tr(match field {
+        "game_id" => Msg::SugGameIdFormat,
+        "chess_move" => Msg::SugMoveNotation,
+        "color" => Msg::SugColorInput,
+        "address" => Msg::SugAddressFormat,
+        _ => Msg::SugCheckInput,
+    })
+    .to_string();
 
     CliError::InvalidInput {
         field: field.to_string(),
@@ -472,10 +831,42 @@ pub fn create_input_validation_error(field: &str, value: &str, reason: &str) ->
 /// Result type for CLI operations
 pub type CliResult<T> = Result<T, CliError>;
 
-/// Display an error with proper formatting and exit codes
-pub fn display_error_and_exit(error: CliError, exit_code: i32) -> ! {
-    eprintln!("\n{}", error);
-    std::process::exit(exit_code);
+/// Whether errors are reported as JSON, set from `--json`
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Report errors as a JSON envelope on stdout from now on
+pub fn set_json_errors(enabled: bool) {
+    JSON_ERRORS.store(enabled, Ordering::Relaxed);
+}
+
+/// Where failed commands are counted, when usage reports are on
+static USAGE_LOG: OnceLock<UsageLog> = OnceLock::new();
+
+/// Count the code of each error displayed by [`display_error_and_exit`]
+/// in `log`
+pub fn set_usage_log(log: UsageLog) {
+    let _ = USAGE_LOG.set(log);
+}
+
+/// Display an error and exit with the status for its code
+///
+/// With `--json` the error is printed on stdout as an
+/// [`ErrorJson`](crate::cli::display::ErrorJson) envelope, so a script
+/// reading stdout always gets JSON back.
+pub fn display_error_and_exit(error: CliError) -> ! {
+    if let Some(log) = USAGE_LOG.get() {
+        // A usage count is never worth a second error message
+        let _ = log.record_error(error.code());
+    }
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        let envelope = crate::cli::display::ErrorJson::new(&error);
+        if crate::cli::display::print_json(&envelope).is_err() {
+            eprintln!("\n{}", error);
+        }
+    } else {
+        eprintln!("\n{}", error);
+    }
+    std::process::exit(error.exit_status());
 }
 
 /// Display an error without exiting (for recoverable errors)
@@ -0,0 +1,146 @@
+use crate::messages::chess::Move as ChessMove;
+use crate::storage::models::{GameResult, GameStatus, Message};
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often [`App::subscribe`](crate::cli::App::subscribe) checks the
+/// database for changes
+pub const EVENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sender IDs used for messages this side stored itself
+const LOCAL_SENDERS: [&str; 2] = ["self", "local"];
+
+/// Something that happened in a game without the local player's doing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// The opponent played a move
+    MoveReceived {
+        game_id: String,
+        from: String,
+        chess_move: String,
+    },
+    /// A peer invited us to a new game
+    InviteReceived { game_id: String, from: String },
+    /// A peer accepted our invitation
+    InviteAccepted { game_id: String, from: String },
+    /// The opponent sent a chat message
+    ChatReceived {
+        game_id: String,
+        from: String,
+        text: String,
+    },
+    /// The opponent offered a draw
+    DrawOffered { game_id: String, from: String },
+    /// The opponent accepted our draw offer
+    DrawAccepted { game_id: String, from: String },
+    /// The opponent resigned
+    Resigned { game_id: String, from: String },
+    /// A game finished, however it ended
+    GameEnded {
+        game_id: String,
+        result: Option<GameResult>,
+    },
+    /// A message could not be delivered to a peer
+    ConnectionLost { peer_address: String, error: String },
+    /// The operator of a server we connected to announced something
+    ServerAnnouncement { from: String, text: String },
+}
+
+/// Turns changes to the database into [`GameEvent`]s
+///
+/// Messages are remembered by ID and games by status, so each call to
+/// [`poll`](Self::poll) reports only what changed since the previous one.
+#[derive(Debug, Clone)]
+pub struct EventWatcher {
+    local_peer_id: String,
+    last_message_id: i64,
+    statuses: HashMap<String, GameStatus>,
+}
+
+impl EventWatcher {
+    /// Watch `database` from its current state, so earlier messages and
+    /// finished games are not reported
+    pub fn start(database: &Database, local_peer_id: &str) -> Result<Self> {
+        let mut watcher = Self {
+            local_peer_id: local_peer_id.to_string(),
+            last_message_id: 0,
+            statuses: HashMap::new(),
+        };
+        watcher.poll(database)?;
+        Ok(watcher)
+    }
+
+    /// Events for everything that changed since the last poll
+    pub fn poll(&mut self, database: &Database) -> Result<Vec<GameEvent>> {
+        let messages = database
+            .get_messages_after(self.last_message_id)
+            .context("Failed to read new messages")?;
+        let muted = database
+            .get_muted_peers()
+            .context("Failed to read muted peers")?;
+        let mut events = Vec::new();
+        for message in &messages {
+            self.last_message_id = self.last_message_id.max(message.id.unwrap_or_default());
+            if let Some(event) = self.message_event(message, &muted) {
+                events.push(event);
+            }
+        }
+
+        let games = database.get_all_games().context("Failed to read games")?;
+        for game in games {
+            let previous = self.statuses.insert(game.id.clone(), game.status.clone());
+            if game.status == GameStatus::Completed
+                && previous.is_some_and(|status| status != GameStatus::Completed)
+            {
+                events.push(GameEvent::GameEnded {
+                    game_id: game.id,
+                    result: game.result,
+                });
+            }
+        }
+        Ok(events)
+    }
+
+    /// The event for a message stored by the other side, if it is one
+    ///
+    /// Chat from a peer in `muted` is left out.
+    fn message_event(&self, message: &Message, muted: &[String]) -> Option<GameEvent> {
+        let from = message.sender_peer_id.clone();
+        if from == self.local_peer_id || LOCAL_SENDERS.contains(&from.as_str()) {
+            return None;
+        }
+        let game_id = message.game_id.clone();
+
+        let kind = message.message_type.replace('_', "").to_ascii_lowercase();
+        let event = match kind.as_str() {
+            "move" => GameEvent::MoveReceived {
+                game_id,
+                from,
+                chess_move: serde_json::from_str::<ChessMove>(&message.content)
+                    .ok()?
+                    .chess_move,
+            },
+            "gameinvite" => GameEvent::InviteReceived { game_id, from },
+            "gameaccept" => GameEvent::InviteAccepted { game_id, from },
+            "chat" if muted.contains(&from) => return None,
+            "chat" => GameEvent::ChatReceived {
+                game_id,
+                from,
+                text: serde_json::from_str::<serde_json::Value>(&message.content)
+                    .ok()?
+                    .get("text")?
+                    .as_str()?
+                    .to_string(),
+            },
+            "drawoffer" => GameEvent::DrawOffered { game_id, from },
+            "drawaccept" => GameEvent::DrawAccepted { game_id, from },
+            "resign" => GameEvent::Resigned { game_id, from },
+            _ => return None,
+        };
+        Some(event)
+    }
+}
@@ -0,0 +1,138 @@
+use crate::chess::Color;
+use crate::cli::app::{compact_clocks, App};
+use crate::cli::display::{compact_board_lines, Highlights};
+use crate::cli::game_ops::{GameOps, GameState};
+use crate::cli::tui::format_elapsed;
+use crate::storage::models::GameStatus;
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::time::Duration;
+
+/// How often the database is checked for new moves
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// ANSI sequence that clears the screen and moves the cursor to the top left
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+/// What was drawn last, so the board is only redrawn when it changes
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    move_count: usize,
+    status: GameStatus,
+}
+
+/// Keep a game's board on screen, redrawing it whenever a move is stored
+///
+/// Moves written by a running `mate serve`, `mate play`, or `mate move` are
+/// picked up by polling the database. Returns once the game is over.
+pub async fn run(app: &App, game_id: Option<String>) -> Result<()> {
+    let game_ops = GameOps::new(&app.database);
+    let game = game_ops
+        .resolve_game_or_current(game_id.as_deref())
+        .context("Failed to find game")?;
+    let perspective: Color = game.my_color.clone().into();
+    let _live_pgn = crate::cli::live_pgn::start_for(app, &game.id).await?;
+
+    let mut shown: Option<Snapshot> = None;
+    let mut last_activity = game.updated_at;
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        poll.tick().await;
+
+        let state = game_ops
+            .reconstruct_game_state(&game.id)
+            .context("Failed to load game state")?;
+        let snapshot = Snapshot {
+            move_count: state.move_history.len(),
+            status: state.game.status.clone(),
+        };
+
+        if shown.as_ref() != Some(&snapshot) {
+            last_activity = last_move_time(&app.database, &game.id)?.unwrap_or(last_activity);
+            print!("{CLEAR_SCREEN}");
+            draw(app, &state, perspective);
+            shown = Some(snapshot);
+        }
+
+        if !matches!(state.game.status, GameStatus::Active | GameStatus::Pending) {
+            println!();
+            return Ok(());
+        }
+
+        // Tick the clock line in place between redraws
+        print!(
+            "\r{} {}",
+            waiting_label(&state),
+            format_elapsed(Database::current_timestamp() - last_activity)
+        );
+        std::io::stdout()
+            .flush()
+            .context("Failed to flush output")?;
+    }
+}
+
+fn draw(app: &App, state: &GameState, perspective: Color) {
+    let game = &state.game;
+    if app.config.display.compact {
+        for line in compact_board_lines(&state.board, &state.move_history, compact_clocks(state)) {
+            println!("{line}");
+        }
+    } else {
+        draw_board(app, state, perspective);
+        println!();
+    }
+
+    match (&game.status, &game.result) {
+        (GameStatus::Active | GameStatus::Pending, _) if state.board.is_checkmate() => {
+            println!("Checkmate.")
+        }
+        (GameStatus::Active | GameStatus::Pending, _) => {}
+        (_, Some(result)) => println!("Game over: {}", result.as_str()),
+        (status, None) => println!("Game over: {}", status.as_str()),
+    }
+}
+
+fn draw_board(app: &App, state: &GameState, perspective: Color) {
+    let game = &state.game;
+    println!(
+        "Game {} against {} ({} to move)",
+        game.id,
+        game.opponent_peer_id,
+        state.board.active_color()
+    );
+    match state.move_history.last() {
+        Some(last) => {
+            let ply = state.move_history.len();
+            let dots = if ply.is_multiple_of(2) { "..." } else { "." };
+            println!("Last move: {}{} {}", ply.div_ceil(2), dots, last);
+        }
+        None => println!("No moves yet"),
+    }
+
+    let highlights = Highlights::new(&state.board, state.move_history.last().map(String::as_str));
+    app.config
+        .display
+        .board_style()
+        .display(&state.board, perspective, &highlights);
+}
+
+fn waiting_label(state: &GameState) -> &'static str {
+    match (&state.game.status, state.your_turn) {
+        (GameStatus::Pending, _) => "Waiting for the invitation to be accepted:",
+        (_, true) => "Your move:",
+        (_, false) => "Waiting for opponent:",
+    }
+}
+
+/// When the most recent move of a game was stored
+fn last_move_time(database: &Database, game_id: &str) -> Result<Option<i64>> {
+    Ok(database
+        .get_messages_for_game(game_id)
+        .context("Failed to retrieve game messages")?
+        .iter()
+        .rev()
+        .find(|m| m.message_type == "Move")
+        .map(|m| m.created_at))
+}
@@ -1,11 +1,30 @@
-use crate::chess::{Board, ChessError, Move as ChessMove};
+use crate::chess::{Board, ChessError, Move as ChessMove, Odds};
+use crate::crypto::batch::verify_in_parallel;
+use crate::crypto::{Identity, PeerId};
 use crate::messages::chess::{GameInvite, Move as MoveMessage};
+use crate::storage::games::GameFilter;
 use crate::storage::{
-    models::{Game, GameStatus, PlayerColor},
+    models::{
+        Game, GameMetadata, GameStatus, GameSyncIssue, Message as StoredMessage, PlayerColor,
+        TimeControl,
+    },
     Database,
 };
 use serde_json;
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
+use tracing::instrument;
+
+/// Most candidates listed in an ambiguous game selector error
+const MAX_LISTED_CANDIDATES: usize = 10;
+
+/// Moves a game must verify cleanly past its last checkpoint before a new
+/// one is saved
+pub const CHECKPOINT_INTERVAL: usize = 50;
+
+/// How long before its arrival an opponent's move in a rated game may be
+/// dated, in seconds
+pub const RATED_SEND_TOLERANCE_SECS: i64 = 5;
 
 /// Result type for game operations
 pub type GameOpsResult<T> = Result<T, GameOpsError>;
@@ -25,6 +44,12 @@ pub enum GameOpsError {
     NoCurrentGame,
     /// Game not found
     GameNotFound(String),
+    /// A game selector matched more than one game
+    AmbiguousGame {
+        selector: String,
+        /// One line describing each matching game
+        candidates: Vec<String>,
+    },
 }
 
 impl std::fmt::Display for GameOpsError {
@@ -36,6 +61,27 @@ impl std::fmt::Display for GameOpsError {
             GameOpsError::InvalidGameState(e) => write!(f, "Invalid game state: {e}"),
             GameOpsError::NoCurrentGame => write!(f, "No current game found"),
             GameOpsError::GameNotFound(id) => write!(f, "Game not found: {id}"),
+            GameOpsError::AmbiguousGame {
+                selector,
+                candidates,
+            } => {
+                write!(
+                    f,
+                    "'{selector}' matches {} games; use a longer game ID prefix:",
+                    candidates.len()
+                )?;
+                for candidate in candidates.iter().take(MAX_LISTED_CANDIDATES) {
+                    write!(f, "\n  {candidate}")?;
+                }
+                if candidates.len() > MAX_LISTED_CANDIDATES {
+                    write!(
+                        f,
+                        "\n  ... and {} more",
+                        candidates.len() - MAX_LISTED_CANDIDATES
+                    )?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -62,6 +108,10 @@ pub struct GameRecord {
     pub last_move: Option<String>,
     pub your_turn: bool,
     pub move_count: u32,
+    /// Odds the game is played at, if any
+    pub odds: Option<Odds>,
+    /// Whether the game counts toward ratings
+    pub rated: bool,
 }
 
 /// Game invitation with tracking information
@@ -70,6 +120,9 @@ pub struct InvitationRecord {
     pub game_id: String,
     pub opponent_peer_id: String,
     pub suggested_color: Option<PlayerColor>,
+    pub time_control: Option<TimeControl>,
+    /// Whether the game would count toward ratings
+    pub rated: bool,
     pub created_at: i64,
     pub status: InvitationStatus,
 }
@@ -89,6 +142,10 @@ pub struct GameState {
     pub board: Board,
     pub move_history: Vec<String>,
     pub your_turn: bool,
+    /// Odds the game is played at, if any
+    pub odds: Option<Odds>,
+    /// When each move in `move_history` was stored
+    pub move_times: Vec<i64>,
 }
 
 /// Game operations manager
@@ -115,6 +172,21 @@ impl<'a> GameOps<'a> {
         Ok(records)
     }
 
+    /// List the games matching a storage filter, in the order it asks for
+    pub fn list_games_matching(&self, filter: &GameFilter) -> GameOpsResult<Vec<GameRecord>> {
+        self.database
+            .query_games(filter)?
+            .into_iter()
+            .map(|game| self.create_game_record(game))
+            .collect()
+    }
+
+    /// Get a single game with extended information
+    pub fn get_game_record(&self, game_id: &str) -> GameOpsResult<GameRecord> {
+        let game = self.database.get_game(game_id)?;
+        self.create_game_record(game)
+    }
+
     /// List games filtered by status
     pub fn list_games_by_status(&self, status: GameStatus) -> GameOpsResult<Vec<GameRecord>> {
         let games = self.database.get_games_by_status(status)?;
@@ -135,41 +207,50 @@ impl<'a> GameOps<'a> {
 
         let mut records = Vec::new();
 
-        for game in pending_games.into_iter().chain(active_games.into_iter()) {
+        for game in pending_games.into_iter().chain(active_games) {
             let record = self.create_game_record(game)?;
             records.push(record);
         }
 
         // Sort by most recently updated
-        records.sort_by(|a, b| b.game.updated_at.cmp(&a.game.updated_at));
+        records.sort_by_key(|record| std::cmp::Reverse(record.game.updated_at));
 
         Ok(records)
     }
 
     /// Reconstruct board state from move history
+    ///
+    /// Replay starts from the game's checkpoint when it still matches the
+    /// stored moves, and from the initial position otherwise.
+    #[instrument(level = "debug", skip(self))]
     pub fn reconstruct_game_state(&self, game_id: &str) -> GameOpsResult<GameState> {
         let game = self.database.get_game(game_id)?;
         let messages = self.database.get_messages_for_game(game_id)?;
+        let odds = game_odds(&game, &messages);
+        let moves: Vec<&StoredMessage> = messages
+            .iter()
+            .filter(|m| m.message_type == "Move")
+            .collect();
 
-        // Start with initial board position
-        let mut board = Board::new();
-        let mut move_history = Vec::new();
-
-        // Apply all moves in chronological order
-        for message in messages {
-            if message.message_type == "Move" {
-                let move_msg: MoveMessage =
-                    serde_json::from_str(&message.content).map_err(|e| {
-                        GameOpsError::Serialization(format!("Failed to parse move message: {e}"))
-                    })?;
+        let mut move_history = Vec::with_capacity(moves.len());
+        for message in &moves {
+            let move_msg: MoveMessage = serde_json::from_str(&message.content).map_err(|e| {
+                GameOpsError::Serialization(format!("Failed to parse move message: {e}"))
+            })?;
+            move_history.push(move_msg.chess_move);
+        }
 
-                // Parse the chess move from algebraic notation
-                let chess_move = ChessMove::from_str(&move_msg.chess_move)?;
+        // Start with the checkpoint, or the initial position less any pieces
+        // given as odds
+        let (start, mut board) = match self.checkpoint_board(game_id, &moves)? {
+            Some(checkpoint) => checkpoint,
+            None => (0, Odds::starting_board(odds.as_ref())?),
+        };
 
-                // Apply the move to the board
-                board.make_move(chess_move)?;
-                move_history.push(move_msg.chess_move);
-            }
+        // Apply the remaining moves in chronological order
+        for (ply, notation) in move_history.iter().enumerate().skip(start) {
+            let chess_move = ChessMove::from_str(notation)?;
+            Odds::make_move(odds.as_ref(), &mut board, ply, chess_move)?;
         }
 
         // Determine whose turn it is
@@ -180,9 +261,141 @@ impl<'a> GameOps<'a> {
             board,
             move_history,
             your_turn,
+            odds,
+            move_times: moves.iter().map(|message| message.created_at).collect(),
         })
     }
 
+    /// Replay a game's moves and compare each position with the recorded board hash
+    ///
+    /// Moves covered by a matching checkpoint were verified when it was saved
+    /// and are not replayed again. Once a game verifies cleanly
+    /// [`CHECKPOINT_INTERVAL`] moves past its last checkpoint, a new one is
+    /// saved.
+    ///
+    /// Returns a description of the first inconsistency, or `None` if the
+    /// stored move list reproduces every recorded position.
+    pub fn verify_game_consistency(&self, game_id: &str) -> GameOpsResult<Option<String>> {
+        let game = self.database.get_game(game_id)?;
+        let messages = self.database.get_messages_for_game(game_id)?;
+        let odds = game_odds(&game, &messages);
+        let moves: Vec<&StoredMessage> = messages
+            .iter()
+            .filter(|m| m.message_type == "Move")
+            .collect();
+
+        let checkpoint = self.checkpoint_board(game_id, &moves)?;
+        let checkpoint_ply = checkpoint.as_ref().map_or(0, |(ply, _)| *ply);
+        let mut board = match checkpoint {
+            Some((_, board)) => board,
+            None => match Odds::starting_board(odds.as_ref()) {
+                Ok(board) => board,
+                Err(e) => return Ok(Some(format!("the game's odds cannot be set up: {e}"))),
+            },
+        };
+
+        for (index, message) in moves.iter().enumerate().skip(checkpoint_ply) {
+            let move_number = index + 1;
+            let move_msg: MoveMessage = match serde_json::from_str(&message.content) {
+                Ok(move_msg) => move_msg,
+                Err(e) => return Ok(Some(format!("move {move_number} is unreadable: {e}"))),
+            };
+
+            let applied = ChessMove::from_str(&move_msg.chess_move).and_then(|chess_move| {
+                Odds::make_move(odds.as_ref(), &mut board, index, chess_move)
+            });
+            if let Err(e) = applied {
+                return Ok(Some(format!(
+                    "move {move_number} ({}) cannot be replayed: {e}",
+                    move_msg.chess_move
+                )));
+            }
+
+            // Moves recorded without a hash can only be checked for legality
+            if !move_msg.board_state_hash.is_empty()
+                && crate::messages::chess::hash_board_state(&board) != move_msg.board_state_hash
+            {
+                return Ok(Some(format!(
+                    "board after move {move_number} ({}) does not match the recorded state",
+                    move_msg.chess_move
+                )));
+            }
+        }
+
+        if moves.len() >= checkpoint_ply + CHECKPOINT_INTERVAL {
+            self.database.save_game_checkpoint(
+                game_id,
+                moves.len() as u32,
+                &board.to_fen(),
+                &moves_hash(&moves),
+                &crate::messages::chess::hash_board_state(&board),
+            )?;
+        }
+
+        Ok(None)
+    }
+
+    /// The ply and board of a game's checkpoint, if it still matches the
+    /// game's stored moves
+    ///
+    /// A checkpoint no longer matches when the moves it covers have changed
+    /// since it was saved, or its position doesn't reproduce its board hash.
+    fn checkpoint_board(
+        &self,
+        game_id: &str,
+        moves: &[&StoredMessage],
+    ) -> GameOpsResult<Option<(usize, Board)>> {
+        let Some(checkpoint) = self.database.get_game_checkpoint(game_id)? else {
+            return Ok(None);
+        };
+        let ply = checkpoint.ply as usize;
+        if ply > moves.len() || moves_hash(&moves[..ply]) != checkpoint.moves_hash {
+            return Ok(None);
+        }
+        match Board::from_fen(&checkpoint.fen) {
+            Ok(board)
+                if crate::messages::chess::hash_board_state(&board) == checkpoint.board_hash =>
+            {
+                Ok(Some((ply, board)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Verify every active game, flagging inconsistent ones as needing sync
+    ///
+    /// Flags left on games that now verify cleanly are cleared. Returns the
+    /// games that currently need sync.
+    pub fn check_active_games(&self) -> GameOpsResult<Vec<GameSyncIssue>> {
+        let mut issues = Vec::new();
+
+        for game in self.database.get_games_by_status(GameStatus::Active)? {
+            match self.verify_game_consistency(&game.id)? {
+                Some(reason) => {
+                    issues.push(self.database.flag_game_needs_sync(&game.id, &reason)?);
+                }
+                None => {
+                    self.database.clear_game_sync_issue(&game.id)?;
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Whether the opponent's most recent draw offer in a game is still open
+    ///
+    /// An offer lapses once a move is made after it.
+    pub fn has_open_draw_offer(&self, game_id: &str, my_peer_id: &str) -> GameOpsResult<bool> {
+        let messages = self.database.get_messages_for_game(game_id)?;
+        let open_offer = messages
+            .iter()
+            .rev()
+            .take_while(|m| !m.message_type.eq_ignore_ascii_case("move"))
+            .any(|m| m.message_type == "DrawOffer" && m.sender_peer_id != my_peer_id);
+        Ok(open_offer)
+    }
+
     /// Get the current game (most recently active)
     pub fn get_current_game(&self) -> GameOpsResult<Game> {
         let active_games = self.list_active_games()?;
@@ -224,6 +437,92 @@ impl<'a> GameOps<'a> {
         }
     }
 
+    /// Resolve what the user typed to pick a game
+    ///
+    /// Accepts, in order of precedence: a full game ID, a peer alias, a
+    /// unique game ID prefix, or a prefix of the opponent's peer ID. When an
+    /// opponent has several games, the single active or pending one is
+    /// chosen, or else the most recently updated one. Anything that still
+    /// matches several games is reported with the candidates listed.
+    pub fn resolve_game(&self, selector: &str) -> GameOpsResult<Game> {
+        self.resolve_game_where(selector, |_| true)
+    }
+
+    /// Resolve a selector for a command that only applies to games in one
+    /// of `statuses`
+    ///
+    /// Game IDs and ID prefixes match as in
+    /// [`resolve_game`](Self::resolve_game), whatever the game's state. An
+    /// opponent's alias or peer ID prefix only picks among their games in
+    /// these states, so with a game against a peer under way and their new
+    /// invitation pending, `mate accept alice` finds the invitation and
+    /// `mate move e4 --game alice` the game.
+    pub fn resolve_game_with_status(
+        &self,
+        selector: &str,
+        statuses: &[GameStatus],
+    ) -> GameOpsResult<Game> {
+        self.resolve_game_where(selector, |game| statuses.contains(&game.status))
+    }
+
+    /// Resolve a selector, counting only the games `eligible` keeps when
+    /// choosing among an opponent's games
+    fn resolve_game_where(
+        &self,
+        selector: &str,
+        eligible: impl Fn(&Game) -> bool,
+    ) -> GameOpsResult<Game> {
+        let selector = selector.trim();
+        if selector.is_empty() {
+            return Err(GameOpsError::GameNotFound(selector.to_string()));
+        }
+
+        if let Ok(game) = self.database.get_game(selector) {
+            return Ok(game);
+        }
+
+        let all_games = self.database.get_all_games()?;
+
+        if let Some(peer_id) = self.database.resolve_peer_alias(selector)? {
+            let games = all_games
+                .into_iter()
+                .filter(|game| game.opponent_peer_id == peer_id && eligible(game))
+                .collect();
+            return pick_opponent_game(selector, games);
+        }
+
+        let lowercase = selector.to_lowercase();
+        let (by_id, others): (Vec<_>, Vec<_>) = all_games
+            .into_iter()
+            .partition(|game| game.id.to_lowercase().starts_with(&lowercase));
+        if !by_id.is_empty() {
+            return single_game(selector, by_id);
+        }
+
+        let by_opponent = others
+            .into_iter()
+            .filter(|game| game.opponent_peer_id.starts_with(selector) && eligible(game))
+            .collect::<Vec<_>>();
+        let mut opponents = by_opponent
+            .iter()
+            .map(|game| game.opponent_peer_id.as_str())
+            .collect::<Vec<_>>();
+        opponents.sort_unstable();
+        opponents.dedup();
+        if opponents.len() > 1 {
+            return single_game(selector, by_opponent);
+        }
+        pick_opponent_game(selector, by_opponent)
+    }
+
+    /// Resolve an optional selector, falling back to the current game
+    pub fn resolve_game_or_current(&self, selector: Option<&str>) -> GameOpsResult<Game> {
+        match selector {
+            Some(selector) => self.resolve_game(selector),
+            None => self.get_current_game(),
+        }
+    }
+
     /// Track pending invitations
     pub fn list_pending_invitations(&self) -> GameOpsResult<Vec<InvitationRecord>> {
         let pending_games = self.database.get_games_by_status(GameStatus::Pending)?;
@@ -245,6 +544,8 @@ impl<'a> GameOps<'a> {
                 });
 
                 invitations.push(InvitationRecord {
+                    time_control: game_time_control(&game),
+                    rated: game_rated(&game, &messages),
                     game_id: game.id,
                     opponent_peer_id: game.opponent_peer_id,
                     suggested_color,
@@ -257,6 +558,83 @@ impl<'a> GameOps<'a> {
         Ok(invitations)
     }
 
+    /// Pending invitations waiting on my answer, newest first
+    ///
+    /// A pending game is incoming unless `my_peer_id` sent its invitation.
+    pub fn list_incoming_invitations(
+        &self,
+        my_peer_id: &str,
+    ) -> GameOpsResult<Vec<InvitationRecord>> {
+        let pending_games = self.database.get_games_by_status(GameStatus::Pending)?;
+        let mut invitations = Vec::new();
+
+        for game in pending_games {
+            let messages = self.database.get_messages_for_game(&game.id)?;
+            let invites: Vec<_> = messages
+                .iter()
+                .filter(|m| {
+                    m.message_type.eq_ignore_ascii_case("GameInvite")
+                        || m.message_type.eq_ignore_ascii_case("game_invite")
+                })
+                .collect();
+            if invites.iter().any(|m| m.sender_peer_id == my_peer_id) {
+                continue;
+            }
+
+            let suggested_color = invites
+                .first()
+                .and_then(|m| serde_json::from_str::<GameInvite>(&m.content).ok())
+                .and_then(|invite| invite.suggested_color)
+                .map(PlayerColor::from);
+
+            invitations.push(InvitationRecord {
+                time_control: game_time_control(&game),
+                rated: game_rated(&game, &messages),
+                game_id: game.id,
+                opponent_peer_id: game.opponent_peer_id,
+                suggested_color,
+                created_at: game.created_at,
+                status: InvitationStatus::Pending,
+            });
+        }
+
+        invitations.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.game_id.cmp(&b.game_id))
+        });
+        Ok(invitations)
+    }
+
+    /// The invitation that opened a game, if one was recorded
+    pub fn invitation_for(&self, game_id: &str) -> GameOpsResult<Option<GameInvite>> {
+        let messages = self.database.get_messages_for_game(game_id)?;
+        Ok(messages
+            .iter()
+            .filter(|m| {
+                m.message_type.eq_ignore_ascii_case("GameInvite")
+                    || m.message_type.eq_ignore_ascii_case("game_invite")
+            })
+            .find_map(|m| serde_json::from_str::<GameInvite>(&m.content).ok()))
+    }
+
+    /// Abandon pending games whose invitation lapsed by `now`, sent or
+    /// received, and return their IDs
+    pub fn expire_invitations(&self, now: i64) -> GameOpsResult<Vec<String>> {
+        let mut expired = Vec::new();
+        for game in self.database.get_games_by_status(GameStatus::Pending)? {
+            let lapsed = self
+                .invitation_for(&game.id)?
+                .is_some_and(|invite| invite.is_expired(now));
+            if lapsed {
+                self.database
+                    .update_game_status(&game.id, GameStatus::Abandoned)?;
+                expired.push(game.id);
+            }
+        }
+        Ok(expired)
+    }
+
     /// Count games by status
     pub fn count_games_by_status(&self, status: GameStatus) -> GameOpsResult<usize> {
         let games = self.database.get_games_by_status(status)?;
@@ -309,15 +687,14 @@ impl<'a> GameOps<'a> {
 
         // Count moves (each move message represents one move)
         let move_count = messages.iter().filter(|m| m.message_type == "Move").count() as u32;
+        let odds = game_odds(&game, &messages);
+        let rated = game_rated(&game, &messages);
 
-        // Determine if it's our turn (simplified - could be more sophisticated)
+        // Determine if it's our turn from the move count, allowing for extra moves
         let your_turn = match game.status {
             GameStatus::Active => {
-                // If move count is even and we're white, or odd and we're black, it's our turn
-                match game.my_color {
-                    PlayerColor::White => move_count % 2 == 0,
-                    PlayerColor::Black => move_count % 2 == 1,
-                }
+                PlayerColor::from(Odds::side_to_move(odds.as_ref(), move_count as usize))
+                    == game.my_color
             }
             GameStatus::Pending => false, // Not our turn until game starts
             _ => false,                   // Game is over
@@ -329,6 +706,8 @@ impl<'a> GameOps<'a> {
             last_move,
             your_turn,
             move_count,
+            odds,
+            rated,
         })
     }
 
@@ -348,6 +727,139 @@ impl<'a> GameOps<'a> {
     }
 }
 
+/// The only game in `games`, or an ambiguity error listing them
+fn single_game(selector: &str, mut games: Vec<Game>) -> GameOpsResult<Game> {
+    match games.len() {
+        0 => Err(GameOpsError::GameNotFound(selector.to_string())),
+        1 => Ok(games.remove(0)),
+        _ => Err(GameOpsError::AmbiguousGame {
+            selector: selector.to_string(),
+            candidates: games.iter().map(describe_candidate).collect(),
+        }),
+    }
+}
+
+/// Pick among the games against a single opponent (newest first)
+///
+/// Prefers the game still in progress; with no game in progress, the most
+/// recent one is used.
+fn pick_opponent_game(selector: &str, games: Vec<Game>) -> GameOpsResult<Game> {
+    let (in_progress, finished): (Vec<_>, Vec<_>) = games
+        .into_iter()
+        .partition(|game| matches!(game.status, GameStatus::Active | GameStatus::Pending));
+
+    if in_progress.is_empty() {
+        finished
+            .into_iter()
+            .max_by_key(|game| game.updated_at)
+            .ok_or_else(|| GameOpsError::GameNotFound(selector.to_string()))
+    } else {
+        single_game(selector, in_progress)
+    }
+}
+
+fn describe_candidate(game: &Game) -> String {
+    format!(
+        "{}  {:<9} vs {}",
+        game.id,
+        game.status.as_str(),
+        game.opponent_peer_id
+    )
+}
+
+/// The odds a game is played at, from its metadata or else its invitation
+pub fn game_odds(game: &Game, messages: &[StoredMessage]) -> Option<Odds> {
+    let from_metadata = game
+        .metadata
+        .clone()
+        .and_then(|metadata| serde_json::from_value::<GameMetadata>(metadata).ok())
+        .and_then(|metadata| metadata.odds);
+    from_metadata.or_else(|| {
+        messages
+            .iter()
+            .filter(|m| {
+                m.message_type.eq_ignore_ascii_case("GameInvite")
+                    || m.message_type.eq_ignore_ascii_case("game_invite")
+            })
+            .find_map(|m| serde_json::from_str::<GameInvite>(&m.content).ok())
+            .and_then(|invite| invite.odds)
+    })
+}
+
+/// Whether a game counts toward ratings, from its metadata or else from the
+/// invitation that opened it
+pub fn game_rated(game: &Game, messages: &[StoredMessage]) -> bool {
+    let from_metadata = game
+        .metadata
+        .clone()
+        .and_then(|metadata| serde_json::from_value::<GameMetadata>(metadata).ok())
+        .and_then(|metadata| metadata.rated);
+    from_metadata.unwrap_or_else(|| {
+        messages
+            .iter()
+            .filter(|m| {
+                m.message_type.eq_ignore_ascii_case("GameInvite")
+                    || m.message_type.eq_ignore_ascii_case("game_invite")
+            })
+            .find_map(|m| serde_json::from_str::<GameInvite>(&m.content).ok())
+            .is_some_and(|invite| invite.rated)
+    })
+}
+
+/// Hash of a game's stored move messages, identifying the moves a checkpoint
+/// covers
+fn moves_hash(moves: &[&StoredMessage]) -> String {
+    let mut hasher = Sha256::new();
+    for message in moves {
+        hasher.update(message.content.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// The time control recorded in a game's metadata, if any
+pub fn game_time_control(game: &Game) -> Option<TimeControl> {
+    let metadata = game.metadata.clone()?;
+    serde_json::from_value::<GameMetadata>(metadata)
+        .ok()?
+        .time_control
+}
+
+/// Time left on White's and Black's clocks, in milliseconds
+///
+/// Each side's clock starts with its first move, so the time taken over
+/// the opening move and over accepting the invitation isn't charged, and
+/// every move earns the increment. With `now`, the side to move is also
+/// charged for the time since the last move.
+pub fn game_clocks(
+    time_control: &TimeControl,
+    odds: Option<&Odds>,
+    move_times: &[i64],
+    now: Option<i64>,
+) -> [u64; 2] {
+    let mut remaining = [time_control.initial_time_ms as i64; 2];
+    let mut started = [false; 2];
+    let side = |ply| Odds::side_to_move(odds, ply) as usize;
+
+    for (ply, &time) in move_times.iter().enumerate() {
+        let mover = side(ply);
+        if started[mover] {
+            remaining[mover] -= (time - move_times[ply - 1]).max(0) * 1000;
+        }
+        started[mover] = true;
+        remaining[mover] += time_control.increment_ms as i64;
+    }
+
+    if let (Some(now), Some(&last)) = (now, move_times.last()) {
+        let to_move = side(move_times.len());
+        if started[to_move] {
+            remaining[to_move] -= (now - last).max(0) * 1000;
+        }
+    }
+
+    remaining.map(|ms| ms.max(0) as u64)
+}
+
 /// Game statistics summary
 #[derive(Debug, Default)]
 pub struct GameStatistics {
@@ -392,6 +904,8 @@ pub enum MoveProcessingError {
     BoardStateError(String),
     /// Move history inconsistency
     HistoryError(String),
+    /// Move signature missing, from the wrong player, or not matching the game
+    SignatureError(String),
 }
 
 impl std::fmt::Display for MoveProcessingError {
@@ -404,6 +918,7 @@ impl std::fmt::Display for MoveProcessingError {
             MoveProcessingError::TransactionError(e) => write!(f, "Transaction error: {}", e),
             MoveProcessingError::BoardStateError(e) => write!(f, "Board state error: {}", e),
             MoveProcessingError::HistoryError(e) => write!(f, "Move history error: {}", e),
+            MoveProcessingError::SignatureError(e) => write!(f, "Move signature error: {}", e),
         }
     }
 }
@@ -433,11 +948,33 @@ pub struct MoveProcessingResult {
     pub is_check: bool,
     pub is_checkmate: bool,
     pub updated_board: Board,
+    /// The move as stored, with its signature when one was made or received
+    pub move_message: MoveMessage,
+}
+
+/// Result of checking the move signatures of a stored game
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignatureReport {
+    /// Moves whose signature verifies in place
+    pub signed: u32,
+    /// Moves stored without a signature
+    pub unsigned: u32,
+    /// Move numbers whose signature does not verify, with the reason
+    pub invalid: Vec<(u32, String)>,
+}
+
+impl SignatureReport {
+    /// Whether no stored signature failed to verify
+    pub fn is_intact(&self) -> bool {
+        self.invalid.is_empty()
+    }
 }
 
 /// Transaction-safe move processor
 pub struct MoveProcessor<'a> {
     game_ops: GameOps<'a>,
+    signer: Option<&'a Identity>,
+    sent_at: Option<i64>,
 }
 
 impl<'a> MoveProcessor<'a> {
@@ -445,12 +982,34 @@ impl<'a> MoveProcessor<'a> {
     pub fn new(database: &'a Database) -> Self {
         Self {
             game_ops: GameOps::new(database),
+            signer: None,
+            sent_at: None,
         }
     }
 
+    /// Sign the moves this processor makes with `identity`
+    pub fn with_signer(mut self, identity: &'a Identity) -> Self {
+        self.signer = Some(identity);
+        self
+    }
+
+    /// Date opponent moves by when they were sent, in Unix seconds on our
+    /// clock (see `Connection::corrected_timestamp`), rather than when they
+    /// are applied
+    ///
+    /// The time is kept between the game's previous message and now, so an
+    /// inexact clock offset can neither reorder the moves nor date one in
+    /// the future. Rated games also keep it within
+    /// `RATED_SEND_TOLERANCE_SECS` of now.
+    pub fn with_sent_at(mut self, timestamp: i64) -> Self {
+        self.sent_at = Some(timestamp);
+        self
+    }
+
     /// Process and validate a move for a game
     /// This is the main entry point for move processing that handles all validation,
     /// board updates, database transactions, and history management
+    #[instrument(level = "debug", skip(self))]
     pub fn process_move(
         &self,
         game_id: &str,
@@ -477,21 +1036,31 @@ impl<'a> MoveProcessor<'a> {
         let mut test_board = game_state.board.clone();
 
         // Apply the move to validate it's legal
-        test_board.make_move(chess_move)?;
-
-        // Create move message with board state hash
+        Odds::make_move(
+            game_state.odds.as_ref(),
+            &mut test_board,
+            game_state.move_history.len(),
+            chess_move,
+        )?;
+
+        // Create move message with board state hash, signed in its place in the game
         let board_hash = crate::messages::chess::hash_board_state(&test_board);
-        let move_message = MoveMessage::new(
+        let move_number = game_state.move_history.len() as u32 + 1;
+        let mut move_message = MoveMessage::new(
             game_id.to_string(),
             move_notation.to_string(),
             board_hash.clone(),
         );
+        if let Some(identity) = self.signer {
+            let prior_hash = crate::messages::chess::hash_board_state(&game_state.board);
+            move_message = move_message
+                .signed(identity, move_number, &prior_hash)
+                .map_err(|e| MoveProcessingError::SignatureError(e.to_string()))?;
+        }
 
-        // Store the move in database with transaction safety
-        self.store_move_with_transaction(game_id, &move_message)?;
-
-        // Update game state if it's now completed
-        self.update_game_status_if_needed(game_id, &test_board)?;
+        // Store the move and any resulting status change atomically
+        let new_status = self.status_after_move(&test_board);
+        self.store_move_with_transaction(game_id, &move_message, new_status, None)?;
 
         // Analyze move characteristics
         let move_info = self.analyze_move(&game_state.board, &test_board, chess_move)?;
@@ -500,11 +1069,12 @@ impl<'a> MoveProcessor<'a> {
             game_id: game_id.to_string(),
             move_notation: move_notation.to_string(),
             board_state_hash: board_hash,
-            move_number: game_state.move_history.len() as u32 + 1,
+            move_number,
             is_capture: move_info.is_capture,
             is_check: move_info.is_check,
             is_checkmate: move_info.is_checkmate,
             updated_board: test_board,
+            move_message,
         })
     }
 
@@ -530,7 +1100,12 @@ impl<'a> MoveProcessor<'a> {
         match self.parse_and_validate_move(move_notation, &game_state.board) {
             Ok(chess_move) => {
                 let mut test_board = game_state.board.clone();
-                match test_board.make_move(chess_move) {
+                match Odds::make_move(
+                    game_state.odds.as_ref(),
+                    &mut test_board,
+                    game_state.move_history.len(),
+                    chess_move,
+                ) {
                     Ok(()) => Ok(true),
                     Err(_) => Ok(false),
                 }
@@ -540,6 +1115,13 @@ impl<'a> MoveProcessor<'a> {
     }
 
     /// Apply a move from an opponent (from network message)
+    ///
+    /// A signed move must verify as the next move from the current position,
+    /// and when the opponent is known by peer ID it must be theirs. Unsigned
+    /// moves are refused from opponents known by peer ID and in games where
+    /// the opponent has signed before; only peers that predate move signing
+    /// may still send them.
+    #[instrument(level = "debug", skip(self, move_message), fields(chess_move = %move_message.chess_move))]
     pub fn apply_opponent_move(
         &self,
         game_id: &str,
@@ -566,7 +1148,12 @@ impl<'a> MoveProcessor<'a> {
 
         // Apply move to board
         let mut updated_board = game_state.board.clone();
-        updated_board.make_move(chess_move)?;
+        Odds::make_move(
+            game_state.odds.as_ref(),
+            &mut updated_board,
+            game_state.move_history.len(),
+            chess_move,
+        )?;
 
         // Verify board hash matches message
         let actual_hash = crate::messages::chess::hash_board_state(&updated_board);
@@ -577,11 +1164,39 @@ impl<'a> MoveProcessor<'a> {
             )));
         }
 
-        // Store the move with transaction safety
-        self.store_move_with_transaction(game_id, move_message)?;
+        let move_number = game_state.move_history.len() as u32 + 1;
+        let opponent = &game_state.game.opponent_peer_id;
+        let opponent_is_key = PeerId::from_string(opponent.clone())
+            .to_verifying_key()
+            .is_ok();
+        if move_message.signature.is_none() {
+            // Without a signature the move could come from anyone, so it is
+            // only taken from opponents whose key isn't known, in games that
+            // have never been signed
+            if opponent_is_key || self.opponent_has_signed(game_id)? {
+                return Err(MoveProcessingError::SignatureError(format!(
+                    "Move is unsigned, but the opponent {opponent} signs their moves"
+                )));
+            }
+        } else {
+            let prior_hash = crate::messages::chess::hash_board_state(&game_state.board);
+            let signer = move_message
+                .verify_signature(move_number, &prior_hash)
+                .map_err(|e| MoveProcessingError::SignatureError(e.to_string()))?;
+            if opponent_is_key && signer != opponent {
+                return Err(MoveProcessingError::SignatureError(format!(
+                    "Move was signed by {signer}, not by the opponent {opponent}"
+                )));
+            }
+        }
 
-        // Update game status if needed
-        self.update_game_status_if_needed(game_id, &updated_board)?;
+        // Store the move and any resulting status change atomically
+        let new_status = self.status_after_move(&updated_board);
+        let sent_at = match self.sent_at {
+            Some(sent_at) => Some(self.recorded_time(&game_state.game, sent_at)?),
+            None => None,
+        };
+        self.store_move_with_transaction(game_id, move_message, new_status, sent_at)?;
 
         // Analyze move characteristics
         let move_info = self.analyze_move(&game_state.board, &updated_board, chess_move)?;
@@ -590,22 +1205,25 @@ impl<'a> MoveProcessor<'a> {
             game_id: game_id.to_string(),
             move_notation: move_message.chess_move.clone(),
             board_state_hash: move_message.board_state_hash.clone(),
-            move_number: game_state.move_history.len() as u32 + 1,
+            move_number,
             is_capture: move_info.is_capture,
             is_check: move_info.is_check,
             is_checkmate: move_info.is_checkmate,
             updated_board,
+            move_message: move_message.clone(),
         })
     }
 
     /// Get all legal moves for current position
     pub fn get_legal_moves(&self, game_id: &str) -> MoveResult<Vec<String>> {
-        let _game_state = self.game_ops.reconstruct_game_state(game_id)?;
+        let game_state = self.game_ops.reconstruct_game_state(game_id)?;
 
-        // TODO: Implement comprehensive legal move generation
-        // For now, return empty vector as this requires complex chess logic
-        // This would be enhanced in a future phase
-        Ok(Vec::new())
+        Ok(game_state
+            .board
+            .legal_moves()
+            .iter()
+            .map(|mv| mv.to_string())
+            .collect())
     }
 
     /// Get move history with analysis
@@ -613,7 +1231,8 @@ impl<'a> MoveProcessor<'a> {
         &self,
         game_id: &str,
     ) -> MoveResult<Vec<MoveHistoryEntry>> {
-        let _game_state = self.game_ops.reconstruct_game_state(game_id)?;
+        let game_state = self.game_ops.reconstruct_game_state(game_id)?;
+        let odds = game_state.odds.as_ref();
         let messages = self
             .game_ops
             .database
@@ -621,7 +1240,7 @@ impl<'a> MoveProcessor<'a> {
             .map_err(|e| MoveProcessingError::GameOps(GameOpsError::Database(e)))?;
 
         let mut history = Vec::new();
-        let mut board = Board::new();
+        let mut board = Odds::starting_board(odds)?;
         let mut move_number = 1;
 
         for message in messages {
@@ -635,7 +1254,7 @@ impl<'a> MoveProcessor<'a> {
                     ChessMove::from_str_with_color(&move_message.chess_move, board.active_color())?;
                 let old_board = board.clone();
 
-                board.make_move(chess_move)?;
+                Odds::make_move(odds, &mut board, move_number as usize - 1, chess_move)?;
 
                 let move_info = self.analyze_move(&old_board, &board, chess_move)?;
 
@@ -656,6 +1275,91 @@ impl<'a> MoveProcessor<'a> {
         Ok(history)
     }
 
+    /// Whether a move signed by someone other than us is stored for the game
+    fn opponent_has_signed(&self, game_id: &str) -> MoveResult<bool> {
+        let own = self.signer.map(|identity| identity.peer_id().to_string());
+        let moves = self
+            .game_ops
+            .database
+            .get_messages_by_type(game_id, "Move")
+            .map_err(|e| MoveProcessingError::GameOps(GameOpsError::Database(e)))?;
+        Ok(moves.iter().any(|message| {
+            !message.signature.is_empty() && own.as_deref() != Some(&message.sender_peer_id)
+        }))
+    }
+
+    /// Replay a stored game and check every move signature in place
+    ///
+    /// A signature that was made for another move number or position, or
+    /// whose move leads to a different board than the one signed, is reported
+    /// as invalid, which makes edits to the stored record visible.
+    #[instrument(level = "debug", skip(self))]
+    pub fn verify_signatures(&self, game_id: &str) -> MoveResult<SignatureReport> {
+        let messages = self
+            .game_ops
+            .database
+            .get_messages_by_type(game_id, "Move")
+            .map_err(|e| MoveProcessingError::GameOps(GameOpsError::Database(e)))?;
+
+        let game = self
+            .game_ops
+            .database
+            .get_game(game_id)
+            .map_err(|e| MoveProcessingError::GameOps(GameOpsError::Database(e)))?;
+        let game_messages = self
+            .game_ops
+            .database
+            .get_messages_for_game(game_id)
+            .map_err(|e| MoveProcessingError::GameOps(GameOpsError::Database(e)))?;
+        let odds = game_odds(&game, &game_messages);
+
+        // Replaying is sequential; the signatures are then checked together
+        let mut report = SignatureReport::default();
+        let mut board = Odds::starting_board(odds.as_ref())?;
+        let mut signed = Vec::new();
+        for (index, message) in messages.iter().enumerate() {
+            let move_number = index as u32 + 1;
+            let move_message: MoveMessage =
+                serde_json::from_str(&message.content).map_err(|e| {
+                    MoveProcessingError::HistoryError(format!("Failed to parse move: {e}"))
+                })?;
+
+            let prior_hash = crate::messages::chess::hash_board_state(&board);
+            let chess_move =
+                ChessMove::from_str_with_color(&move_message.chess_move, board.active_color())?;
+            Odds::make_move(odds.as_ref(), &mut board, index, chess_move)?;
+
+            if move_message.signature.is_none() {
+                report.unsigned += 1;
+                continue;
+            }
+            let replayed_hash = crate::messages::chess::hash_board_state(&board);
+            let follows = replayed_hash == move_message.board_state_hash;
+            signed.push((move_number, move_message, prior_hash, follows));
+        }
+
+        let verified = verify_in_parallel(
+            &signed,
+            |(move_number, move_message, prior_hash, follows)| {
+                if !follows {
+                    return Err("the signed position does not follow from the game".to_string());
+                }
+                move_message
+                    .verify_signature(*move_number, prior_hash)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            },
+        );
+        for ((move_number, ..), verified) in signed.iter().zip(verified) {
+            match verified {
+                Ok(()) => report.signed += 1,
+                Err(reason) => report.invalid.push((*move_number, reason)),
+            }
+        }
+
+        Ok(report)
+    }
+
     /// Validate move preconditions
     fn validate_move_preconditions(
         &self,
@@ -677,6 +1381,18 @@ impl<'a> MoveProcessor<'a> {
             )));
         }
 
+        if let Some(issue) = self
+            .game_ops
+            .database
+            .get_game_sync_issue(game_id)
+            .map_err(|e| MoveProcessingError::GameOps(GameOpsError::Database(e)))?
+        {
+            return Err(MoveProcessingError::InvalidGameState(format!(
+                "Game needs sync with the opponent before moves can be made: {}",
+                issue.reason
+            )));
+        }
+
         // Basic move notation validation
         if move_notation.trim().is_empty() {
             return Err(MoveProcessingError::InvalidMove(
@@ -701,43 +1417,75 @@ impl<'a> MoveProcessor<'a> {
     }
 
     /// Store move in database with transaction safety
+    ///
+    /// The move message and the game status update are written in a single
+    /// transaction so a crash can never leave a move recorded without the
+    /// matching game update (or vice versa).
     fn store_move_with_transaction(
         &self,
         game_id: &str,
         move_message: &MoveMessage,
+        new_status: GameStatus,
+        created_at: Option<i64>,
     ) -> MoveResult<()> {
         // Serialize move message
         let content = serde_json::to_string(move_message).map_err(|e| {
             MoveProcessingError::TransactionError(format!("Failed to serialize move: {e}"))
         })?;
 
-        // Store in database
-        // Note: The current storage layer doesn't expose transaction APIs,
-        // but the individual operations are atomic at the SQLite level
-        self.game_ops
-            .database
-            .store_message(
-                game_id.to_string(),
-                "Move".to_string(),
-                content,
-                "".to_string(),     // Signature would be added in networking layer
-                "self".to_string(), // Sender peer ID would be determined by context
-            )
-            .map_err(|e| MoveProcessingError::TransactionError(format!("Database error: {e}")))?;
+        // Signed moves keep their signature and signer next to the move
+        let (signature, sender) = match &move_message.signature {
+            Some(signature) => (signature.signature.clone(), signature.signer.clone()),
+            None => (String::new(), "self".to_string()),
+        };
 
-        // Update game timestamp
         self.game_ops
             .database
-            .update_game_status(game_id, GameStatus::Active)
-            .map_err(|e| {
-                MoveProcessingError::TransactionError(format!("Failed to update game: {e}"))
-            })?;
+            .transaction(|tx| {
+                tx.store_message_at(
+                    game_id.to_string(),
+                    "Move".to_string(),
+                    content,
+                    signature,
+                    sender,
+                    created_at.unwrap_or_else(Database::current_timestamp),
+                )?;
+
+                // Also refreshes the game's updated_at timestamp
+                tx.update_game_status(game_id, new_status)
+            })
+            .map_err(|e| MoveProcessingError::TransactionError(format!("Database error: {e}")))?;
 
         Ok(())
     }
 
-    /// Update game status if game is completed
-    fn update_game_status_if_needed(&self, game_id: &str, board: &Board) -> MoveResult<()> {
+    /// `sent_at` clamped between the game's latest message and now
+    ///
+    /// Rated games allow a move to be dated at most
+    /// `RATED_SEND_TOLERANCE_SECS` before it arrived, so a late move cannot
+    /// claim to have been played quickly.
+    fn recorded_time(&self, game: &Game, sent_at: i64) -> MoveResult<i64> {
+        let messages = self
+            .game_ops
+            .database
+            .get_messages_for_game(&game.id)
+            .map_err(|e| MoveProcessingError::GameOps(GameOpsError::Database(e)))?;
+        let latest = messages
+            .iter()
+            .map(|message| message.created_at)
+            .max()
+            .unwrap_or(i64::MIN);
+        let now = Database::current_timestamp();
+        let earliest = if game_rated(game, &messages) {
+            latest.max(now - RATED_SEND_TOLERANCE_SECS)
+        } else {
+            latest
+        };
+        Ok(sent_at.max(earliest).min(now))
+    }
+
+    /// Determine the game status after a move has been applied
+    fn status_after_move(&self, board: &Board) -> GameStatus {
         // TODO: Implement game end detection (checkmate, stalemate, etc.)
         // This requires comprehensive chess logic that would be added in future phases
 
@@ -750,9 +1498,8 @@ impl<'a> MoveProcessor<'a> {
         // - Threefold repetition
 
         let _ = board; // Suppress unused variable warning
-        let _ = game_id;
 
-        Ok(())
+        GameStatus::Active
     }
 
     /// Analyze move characteristics
@@ -0,0 +1,266 @@
+use crate::cli::csv::csv_record;
+use crate::cli::inbox::format_time_control;
+use crate::storage::game_stats::{ResultTally, StatsPeriod};
+use crate::storage::models::{GameResult, PlayerColor, TimeControl};
+use crate::storage::{Database, StorageError};
+use std::collections::HashMap;
+
+/// Widest bar `mate stats` draws, in characters
+pub const CHART_WIDTH: usize = 40;
+
+/// Elo rating every player starts from
+pub const INITIAL_RATING: f64 = 1500.0;
+
+/// Most rating points one game can move
+pub const RATING_K_FACTOR: f64 = 32.0;
+
+/// My Elo rating over rated games
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    pub rating: f64,
+    /// Rated games the rating is based on
+    pub games: u32,
+}
+
+impl Rating {
+    /// Elo rating after playing `results` in order, as (opponent, result)
+    ///
+    /// Opponents start at `INITIAL_RATING` too and are rated from their games
+    /// against me, so beating a player I keep beating counts for less.
+    pub fn from_results(results: &[(String, GameResult)]) -> Option<Self> {
+        let mut rating = INITIAL_RATING;
+        let mut opponents: HashMap<&str, f64> = HashMap::new();
+        let mut games = 0;
+        for (opponent, result) in results {
+            let score = match result {
+                GameResult::Win => 1.0,
+                GameResult::Draw => 0.5,
+                GameResult::Loss => 0.0,
+                GameResult::Abandoned => continue,
+            };
+            let opponent = opponents.entry(opponent).or_insert(INITIAL_RATING);
+            let expected = 1.0 / (1.0 + 10f64.powf((*opponent - rating) / 400.0));
+            let change = RATING_K_FACTOR * (score - expected);
+            rating += change;
+            *opponent -= change;
+            games += 1;
+        }
+        (games > 0).then_some(Self { rating, games })
+    }
+}
+
+/// Everything `mate stats` reports about finished games
+#[derive(Debug, Clone, Default)]
+pub struct GameStatsReport {
+    pub period: StatsPeriod,
+    pub totals: ResultTally,
+    /// Results by the period games finished in, oldest first
+    pub periods: Vec<(String, ResultTally)>,
+    /// Results by the color I played, white first
+    pub colors: Vec<(PlayerColor, ResultTally)>,
+    /// Results by time control, most played first
+    pub time_controls: Vec<(Option<TimeControl>, ResultTally)>,
+    /// Rating over rated games, if any were decided
+    pub rating: Option<Rating>,
+}
+
+impl GameStatsReport {
+    /// Gather the report from the games in `database`
+    pub fn load(database: &Database, period: StatsPeriod) -> Result<Self, StorageError> {
+        Ok(Self {
+            period,
+            totals: database.result_totals()?,
+            periods: database.results_by_period(period)?,
+            colors: database.results_by_color()?,
+            time_controls: database.results_by_time_control()?,
+            rating: Rating::from_results(&database.rated_results()?),
+        })
+    }
+
+    /// Every group in the report as (section, group, tally), the totals first
+    pub fn groups(&self) -> Vec<(&'static str, String, &ResultTally)> {
+        let mut groups = vec![("total", "all".to_string(), &self.totals)];
+        groups.extend(
+            self.periods
+                .iter()
+                .map(|(period, tally)| (self.period.as_str(), period.clone(), tally)),
+        );
+        groups.extend(
+            self.colors
+                .iter()
+                .map(|(color, tally)| ("color", color.as_str().to_string(), tally)),
+        );
+        groups.extend(self.time_controls.iter().map(|(time_control, tally)| {
+            (
+                "time_control",
+                format_time_control(time_control.as_ref()),
+                tally,
+            )
+        }));
+        groups
+    }
+
+    /// The report as ASCII charts, one line each
+    pub fn chart_lines(&self) -> Vec<String> {
+        let totals = &self.totals;
+        let mut lines = vec![format!(
+            "{} finished games: {} wins, {} draws, {} losses ({})",
+            totals.games,
+            totals.wins,
+            totals.draws,
+            totals.losses,
+            format_score(totals.score())
+        )];
+
+        lines.push(String::new());
+        lines.push(format!(
+            "Results by {} (+ win, = draw, - loss)",
+            self.period.as_str()
+        ));
+        let max_games = self.periods.iter().map(|(_, t)| t.games).max().unwrap_or(0);
+        lines.extend(
+            self.periods
+                .iter()
+                .map(|(period, tally)| result_line(period, tally, max_games)),
+        );
+
+        lines.push(String::new());
+        lines.push("Results by color".to_string());
+        let max_games = self.colors.iter().map(|(_, t)| t.games).max().unwrap_or(0);
+        lines.extend(self.colors.iter().map(|(color, tally)| {
+            let label = match color {
+                PlayerColor::White => "White",
+                PlayerColor::Black => "Black",
+            };
+            result_line(label, tally, max_games)
+        }));
+
+        lines.push(String::new());
+        lines.push("Game length".to_string());
+        lines.push(match totals.average_moves() {
+            Some(average) => format!("Average {average:.1} moves over {} games", totals.games),
+            None => "No finished games".to_string(),
+        });
+        for (color, tally) in &self.colors {
+            if let Some(average) = tally.average_moves() {
+                lines.push(format!("As {}: {average:.1} moves", color.as_str()));
+            }
+        }
+
+        lines.push(String::new());
+        lines.push("Time controls".to_string());
+        let max_games = self
+            .time_controls
+            .iter()
+            .map(|(_, t)| t.games)
+            .max()
+            .unwrap_or(0);
+        for (time_control, tally) in &self.time_controls {
+            lines.push(format!(
+                "{:<9} {:<width$}  {:>4} games  {:>4}",
+                format_time_control(time_control.as_ref()),
+                count_bar(tally.games, max_games, CHART_WIDTH),
+                tally.games,
+                format_score(tally.score()),
+                width = CHART_WIDTH
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push("Rating".to_string());
+        lines.push(match &self.rating {
+            Some(rating) => format!("{:.0} over {} rated games", rating.rating, rating.games),
+            None => "No rated games; casual games are not rated".to_string(),
+        });
+        lines
+    }
+
+    /// The report as CSV, one record per group after a header
+    pub fn csv_lines(&self) -> Vec<String> {
+        let mut lines = vec![csv_record(&[
+            "section",
+            "group",
+            "games",
+            "wins",
+            "draws",
+            "losses",
+            "score",
+            "average_moves",
+        ])];
+        for (section, group, tally) in self.groups() {
+            lines.push(csv_record(&[
+                section.to_string(),
+                group,
+                tally.games.to_string(),
+                tally.wins.to_string(),
+                tally.draws.to_string(),
+                tally.losses.to_string(),
+                tally.score().map(|s| format!("{s:.1}")).unwrap_or_default(),
+                tally
+                    .average_moves()
+                    .map(|m| format!("{m:.1}"))
+                    .unwrap_or_default(),
+            ]));
+        }
+        lines
+    }
+}
+
+/// One labelled row of a results chart
+fn result_line(label: &str, tally: &ResultTally, max_games: u32) -> String {
+    format!(
+        "{label:<9} {:<width$}  {:>4} games  {:>4}",
+        result_bar(tally, max_games, CHART_WIDTH),
+        tally.games,
+        format_score(tally.score()),
+        width = CHART_WIDTH
+    )
+}
+
+/// Percentage score, or `-` with nothing decided
+fn format_score(score: Option<f64>) -> String {
+    score
+        .map(|score| format!("{score:.0}%"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Length of the bar for `count` when `max` fills `width`; anything
+/// counted gets at least one character
+fn bar_length(count: u32, max: u32, width: usize) -> usize {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+    ((count as usize * width + max as usize / 2) / max as usize).max(1)
+}
+
+/// A bar of `#` for `count` when `max` fills `width`
+pub fn count_bar(count: u32, max: u32, width: usize) -> String {
+    "#".repeat(bar_length(count, max, width))
+}
+
+/// A bar for `tally.games` when `max_games` fills `width`, split into
+/// wins (`+`), draws (`=`), losses (`-`), and games without a result (`.`)
+pub fn result_bar(tally: &ResultTally, max_games: u32, width: usize) -> String {
+    let length = bar_length(tally.games, max_games, width);
+    if length == 0 {
+        return String::new();
+    }
+    let other = tally.games - tally.wins - tally.draws - tally.losses;
+    let mut bar = String::with_capacity(length);
+    let mut counted = 0;
+    let mut drawn = 0;
+    for (count, symbol) in [
+        (tally.wins, '+'),
+        (tally.draws, '='),
+        (tally.losses, '-'),
+        (other, '.'),
+    ] {
+        // Round each boundary rather than each segment, so the segments
+        // always add up to the whole bar
+        counted += count as usize;
+        let end = (counted * length + tally.games as usize / 2) / tally.games as usize;
+        bar.extend(std::iter::repeat_n(symbol, end - drawn));
+        drawn = end;
+    }
+    bar
+}
@@ -0,0 +1,301 @@
+use crate::chess::{Board, Color};
+use crate::cli::app::App;
+use crate::cli::display::Highlights;
+use crate::cli::game_ops::{GameOps, GameState, MoveProcessor};
+use crate::cli::line_editor::{LineEditor, SharedSuggestions, Suggestions};
+use crate::cli::play::SessionCommand;
+use crate::cli::tui::move_list_lines;
+use crate::storage::models::{GameResult, GameStatus, PlayerColor};
+use anyhow::{Context, Result};
+use std::sync::{Arc, Mutex};
+
+/// Opponent recorded for games played by two people at one terminal
+pub const HOTSEAT_OPPONENT: &str = "local";
+
+/// Command words offered by tab completion
+const HOTSEAT_COMMANDS: [&str; 6] = ["board", "history", "draw", "resign", "help", "quit"];
+
+/// A question the other player or the player to move must answer first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pending {
+    /// The player to move offered a draw
+    Draw,
+    /// The player to move asked to resign
+    Resign,
+}
+
+/// A game between two people taking turns at one terminal
+///
+/// The game is stored like any other, with White as the local player and
+/// [`HOTSEAT_OPPONENT`] as the opponent, so `mate games`, `mate history`,
+/// `mate replay` and exports work on it. Nothing is sent over the network.
+pub struct HotseatSession<'a> {
+    app: &'a App,
+    game_id: String,
+    pending: Option<Pending>,
+}
+
+impl<'a> HotseatSession<'a> {
+    /// Start a new hotseat game
+    pub fn start(app: &'a App) -> Result<Self> {
+        let game = app
+            .database
+            .create_game(HOTSEAT_OPPONENT.to_string(), PlayerColor::White, None)
+            .context("Failed to create game")?;
+        app.database
+            .update_game_status(&game.id, GameStatus::Active)
+            .context("Failed to start game")?;
+        Ok(Self {
+            app,
+            game_id: game.id,
+            pending: None,
+        })
+    }
+
+    /// Continue a hotseat game started earlier
+    pub fn resume(app: &'a App, game_id: &str) -> Result<Self> {
+        let game = GameOps::new(&app.database)
+            .resolve_game(game_id)
+            .context("Failed to find game")?;
+        if game.opponent_peer_id != HOTSEAT_OPPONENT {
+            anyhow::bail!(
+                "Game {} is against {}, not a hotseat game; use 'mate play' for it",
+                game.id,
+                game.opponent_peer_id
+            );
+        }
+        Ok(Self {
+            app,
+            game_id: game.id,
+            pending: None,
+        })
+    }
+
+    pub fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn state(&self) -> Result<GameState> {
+        GameOps::new(&self.app.database)
+            .reconstruct_game_state(&self.game_id)
+            .context("Failed to load game state")
+    }
+
+    fn prompt(&self) -> Result<String> {
+        let to_move = self.state()?.board.active_color();
+        Ok(match self.pending {
+            Some(Pending::Draw) => format!("{}, accept a draw? (y/n): ", to_move.opposite()),
+            Some(Pending::Resign) => format!("{to_move}, resign this game? (y/n): "),
+            None => format!("{to_move}> "),
+        })
+    }
+
+    /// What the next line of input can be completed to
+    fn suggestions(&self) -> Result<Suggestions> {
+        if self.pending.is_some() {
+            return Ok(Suggestions::for_commands(&["yes", "no"], None));
+        }
+        let state = self.state()?;
+        if state.game.status == GameStatus::Active {
+            Ok(Suggestions::for_moves(&state.board, &HOTSEAT_COMMANDS))
+        } else {
+            Ok(Suggestions::for_commands(
+                &HOTSEAT_COMMANDS,
+                Some("game is not active"),
+            ))
+        }
+    }
+
+    /// Show the board from the side of the player to move
+    pub fn show_board(&self) -> Result<()> {
+        let state = self.state()?;
+        let last_move = state.move_history.last().map(String::as_str);
+        self.render(&state.board, last_move);
+        show_status(&state);
+        Ok(())
+    }
+
+    fn render(&self, board: &Board, last_move: Option<&str>) {
+        self.app.config.display.board_style().display(
+            board,
+            board.active_color(),
+            &Highlights::new(board, last_move),
+        );
+    }
+
+    /// Handle one line of input, returning `false` when the session should end
+    pub fn handle_line(&mut self, line: &str) -> Result<bool> {
+        if let Some(pending) = self.pending.take() {
+            let agreed = matches!(line.trim().to_lowercase().as_str(), "y" | "yes");
+            match (pending, agreed) {
+                (Pending::Draw, true) => self.finish(GameResult::Draw, "Draw agreed.")?,
+                (Pending::Draw, false) => println!("Draw declined."),
+                (Pending::Resign, true) => {
+                    let loser = self.state()?.board.active_color();
+                    self.finish(
+                        result_for_winner(loser.opposite()),
+                        &format!("{loser} resigned."),
+                    )?;
+                }
+                (Pending::Resign, false) => println!("Resignation cancelled."),
+            }
+            return Ok(true);
+        }
+
+        let Some(command) = SessionCommand::parse(line) else {
+            return Ok(true);
+        };
+
+        match command {
+            SessionCommand::Move(notation) => self.play_move(&notation)?,
+            SessionCommand::Board => self.show_board()?,
+            SessionCommand::History => {
+                let state = self.state()?;
+                if state.move_history.is_empty() {
+                    println!("No moves yet.");
+                }
+                for line in move_list_lines(&state.move_history) {
+                    println!("{line}");
+                }
+            }
+            SessionCommand::Draw => self.ask(Pending::Draw)?,
+            SessionCommand::Resign => self.ask(Pending::Resign)?,
+            SessionCommand::Chat(_) => println!("There is no one to chat with; you're both here."),
+            SessionCommand::Cancel => {
+                println!("There are no premoves; it is always someone's turn.")
+            }
+            SessionCommand::Help => print_help(),
+            SessionCommand::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn ask(&mut self, question: Pending) -> Result<()> {
+        if self.state()?.game.status == GameStatus::Active {
+            self.pending = Some(question);
+        } else {
+            println!("Game is not active.");
+        }
+        Ok(())
+    }
+
+    fn play_move(&mut self, notation: &str) -> Result<()> {
+        let state = self.state()?;
+        if state.game.status != GameStatus::Active {
+            println!("Game is not active.");
+            return Ok(());
+        }
+
+        let mv = match state.board.parse_move(notation) {
+            Ok(mv) => mv,
+            Err(e) => {
+                println!("{e}");
+                return Ok(());
+            }
+        };
+
+        // Both sides are played here, so the turn is not checked against
+        // the stored color
+        let coordinate = mv.to_string();
+        let result = match MoveProcessor::new(&self.app.database).process_move(
+            &self.game_id,
+            &coordinate,
+            false,
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("Move rejected: {e}");
+                return Ok(());
+            }
+        };
+
+        let board = &result.updated_board;
+        let mover = state.board.active_color();
+        if board.is_checkmate() {
+            self.finish(
+                result_for_winner(mover),
+                &format!("Checkmate. {mover} wins."),
+            )?;
+        } else if board.is_stalemate() {
+            self.finish(GameResult::Draw, "Stalemate.")?;
+        } else {
+            self.render(board, Some(&coordinate));
+            println!("✓ {mover} played {coordinate}");
+            show_status(&self.state()?);
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, result: GameResult, message: &str) -> Result<()> {
+        self.app
+            .database
+            .update_game_result(&self.game_id, result)
+            .context("Failed to record game result")?;
+        let state = self.state()?;
+        self.render(&state.board, state.move_history.last().map(String::as_str));
+        println!("{message}");
+        Ok(())
+    }
+}
+
+/// The stored result, from White's side, when `winner` wins
+fn result_for_winner(winner: Color) -> GameResult {
+    match winner {
+        Color::White => GameResult::Win,
+        Color::Black => GameResult::Loss,
+    }
+}
+
+fn show_status(state: &GameState) {
+    if state.game.status != GameStatus::Active {
+        match &state.game.result {
+            Some(result) => println!("Game over: {}", result.as_str()),
+            None => println!("Game over: {}", state.game.status.as_str()),
+        }
+        return;
+    }
+    let to_move = state.board.active_color();
+    if state.board.is_in_check(to_move) {
+        println!("{to_move} is in check.");
+    }
+    println!("{to_move} to move.");
+}
+
+fn print_help() {
+    println!("Take turns entering moves in SAN (Nf3, exd5, O-O) or coordinates (g1f3, e7e8q).");
+    println!("Commands:");
+    println!("  board    Show the board");
+    println!("  history  Show the move list");
+    println!("  draw     Offer a draw to the other player");
+    println!("  resign   Resign for the player to move");
+    println!("  help     Show this help");
+    println!("  quit     Leave; continue later with 'mate hotseat <game>'");
+}
+
+/// Play a game at this terminal, starting a new one unless `game_id` names
+/// a hotseat game to continue
+pub fn run(app: &App, game_id: Option<&str>) -> Result<()> {
+    let mut session = match game_id {
+        Some(game_id) => HotseatSession::resume(app, game_id)?,
+        None => HotseatSession::start(app)?,
+    };
+
+    println!(
+        "Hotseat game {}: White and Black take turns at this terminal. Type 'help' for commands.",
+        session.game_id
+    );
+    session.show_board()?;
+
+    let suggestions: SharedSuggestions = Arc::new(Mutex::new(session.suggestions()?));
+    let mut editor = LineEditor::new(Arc::clone(&suggestions))?;
+    while let Some(line) = editor.read_line(&session.prompt()?)? {
+        if !session.handle_line(&line)? {
+            break;
+        }
+        let next = session.suggestions()?;
+        if let Ok(mut current) = suggestions.lock() {
+            *current = next;
+        }
+    }
+    Ok(())
+}
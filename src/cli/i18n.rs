@@ -0,0 +1,793 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Language used for prompts, tables, and error messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Locale {
+    /// Every locale code, as accepted by `display.locale`
+    pub const NAMES: [&'static str; 2] = ["en", "es"];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Spanish => "es",
+        }
+    }
+
+    /// Locale named by a POSIX locale variable such as `es_ES.UTF-8`
+    ///
+    /// Returns `None` for `C`, `POSIX`, and languages without a translation.
+    pub fn from_posix(value: &str) -> Option<Self> {
+        let language = value.split(['_', '.', '@', '-']).next().unwrap_or_default();
+        language.parse().ok()
+    }
+
+    /// Locale from the first set of `LC_ALL`, `LC_MESSAGES`, and `LANG`
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_posix(&value))
+    }
+
+    /// The configured locale, falling back to the environment and then English
+    ///
+    /// An unknown configured locale is ignored rather than treated as an
+    /// error, so a typo never hides the error messages explaining it.
+    pub fn resolve(configured: Option<&str>) -> Self {
+        configured
+            .and_then(|code| code.parse().ok())
+            .or_else(Self::from_env)
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Locale::English),
+            "es" | "spanish" | "español" => Ok(Locale::Spanish),
+            _ => Err(format!(
+                "Unknown locale '{s}'. Expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+/// Use `locale` for all messages from now on
+pub fn set_locale(locale: Locale) {
+    CURRENT_LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// The locale messages are currently shown in
+pub fn locale() -> Locale {
+    match CURRENT_LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::Spanish,
+        _ => Locale::English,
+    }
+}
+
+/// A user-facing message in the catalog
+///
+/// `Err*` entries name a problem and `Sug*` entries the suggestion shown
+/// below it. Placeholders such as `{id}` are filled in by [`trf`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Msg {
+    // Labels
+    Suggestion,
+    Reason,
+    Issue,
+    TimeoutSeconds,
+    TimeoutMillis,
+
+    // Game list
+    NoGamesFound,
+    GamesTotal,
+    HeaderGameId,
+    HeaderOpponent,
+    HeaderStatus,
+    HeaderColor,
+    HeaderMoves,
+    HeaderTurn,
+    StatusPending,
+    StatusActive,
+    StatusDone,
+    StatusAbandoned,
+    TurnYou,
+    TurnThem,
+    ColumnGameId,
+    ColumnOpponent,
+    ColumnColor,
+    ColumnStatus,
+    ColumnCreated,
+    ColumnUpdated,
+    ColumnResult,
+
+    // Boards and history
+    White,
+    Black,
+    ToMove,
+    MoveNumber,
+    HalfmoveClock,
+    NoMovesInHistory,
+    MoveHistory,
+    HistoryMove,
+    CurrentMove,
+    GameStatusPending,
+    GameStatusActive,
+    GameStatusWon,
+    GameStatusLost,
+    GameStatusDraw,
+    GameStatusCompleted,
+    GameStatusAbandoned,
+    UnicodePrompt,
+    ConfirmConsequences,
+    ConfirmPrompt,
+    ConfirmYesAnswers,
+    ConfirmRetry,
+    ConfirmCancelled,
+
+    // Errors shown by the CLI error handler
+    ErrInvalidInput,
+    ErrConfirmationNeedsTerminal,
+    SugConfirmWithYes,
+    ErrConfiguration,
+    ErrNetworkTimeout,
+    ErrConnectFailed,
+    SugConnectFailed,
+    ErrAddressTooLong,
+    SugAddressTooLong,
+    ErrTimedOut,
+    SugTimedOut,
+    ErrInvalidAddress,
+    SugAddressFormat,
+    ErrDatabaseFailed,
+    SugDatabaseFailed,
+    ErrNetworkFailed,
+    SugNetworkFailed,
+    ErrUnexpected,
+    SugUnexpected,
+    ErrNoActiveGames,
+    SugNoActiveGames,
+    ErrGameNotFound,
+    SugGameNotFound,
+    SugAmbiguousGame,
+    ErrInvalidGameState,
+    SugInvalidGameState,
+    ErrDataFormat,
+    SugReportBug,
+    ErrInvalidMove,
+    SugMoveNotation,
+    ErrInvalidPosition,
+    SugCheckPosition,
+    ErrInvalidFen,
+    SugCheckFen,
+    ErrInvalidColor,
+    SugColorSelection,
+    ErrInvalidPiece,
+    SugPieceLetters,
+    ErrBoardState,
+    SugBoardCorrupted,
+    ErrStoredGameNotFound,
+    SugSeeGames,
+    ErrMessageNotFound,
+    SugCheckMessage,
+    ErrDatabaseConnection,
+    SugDatabaseConnection,
+    ErrDatabaseLocked,
+    SugDatabaseLocked,
+    ErrInvalidData,
+    SugCheckData,
+    ErrDatabase,
+    ErrProtocolCommunication,
+    SugCompatibleVersions,
+    ErrPeerConnect,
+    SugPeerConnect,
+    ErrAuthentication,
+    SugAuthentication,
+    ErrConnectionClosed,
+    SugConnectionClosed,
+    ErrVerification,
+    SugVerification,
+    ErrTiming,
+    SugTiming,
+    ErrClockSkew,
+    SugClockSkew,
+    ErrSendQueueFull,
+    SugSendQueueFull,
+    SugServerReachable,
+    ErrValidation,
+    SugCommunicationIssue,
+    ErrOperationTimeout,
+    SugSlowPeer,
+    ErrGameStateIn,
+    SugGameStateCorrupted,
+    ErrSecurityViolation,
+    SugMaliciousPeer,
+    ErrProtocol,
+    SugReconnectPeer,
+    ErrInvalidFormat,
+    SugSameVersion,
+    ErrMessageTooLarge,
+    SugMessageTooLarge,
+    ErrNetworkIo,
+    SugCheckNetwork,
+    ErrProtocolViolation,
+    SugIncompatibleClients,
+    ErrCommunication,
+    SugCheckAndReconnect,
+    ErrCannotAccessDatabase,
+    SugCannotAccessDatabase,
+    ErrNoGameSpecified,
+    SugNoGameSpecified,
+    ErrInviteFailed,
+    SugInviteFailed,
+    ErrInvitationNotFound,
+    SugInvitationNotFound,
+    ErrInvalidChessMove,
+    SugInvalidChessMove,
+    ErrHistoryGameNotFound,
+    SugHistoryGameNotFound,
+    SugTimeoutConnect,
+    SugTimeoutInvite,
+    SugTimeoutMove,
+    SugTimeoutHandshake,
+    SugTimeoutOther,
+    SugGameIdFormat,
+    SugColorInput,
+    SugCheckInput,
+}
+
+/// The message in the current locale
+pub fn tr(msg: Msg) -> &'static str {
+    tr_in(locale(), msg)
+}
+
+/// The message in the given locale
+pub fn tr_in(locale: Locale, msg: Msg) -> &'static str {
+    match locale {
+        Locale::English => english(msg),
+        Locale::Spanish => spanish(msg),
+    }
+}
+
+/// The message in the current locale with its `{name}` placeholders filled in
+pub fn trf(msg: Msg, args: &[(&str, &dyn fmt::Display)]) -> String {
+    fill(tr(msg), args)
+}
+
+/// Replace each `{name}` in `template` with the matching argument
+///
+/// Placeholders without an argument are kept as they are, and text from the
+/// arguments is never itself searched for placeholders.
+pub fn fill(template: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            args.iter()
+                .find(|(arg, _)| *arg == name)
+                .map(|(_, value)| (value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                text.push_str(&value.to_string());
+                rest = &after[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Suggestion => "Suggestion",
+        Msg::Reason => "Reason",
+        Msg::Issue => "Issue",
+        Msg::TimeoutSeconds => "Timeout: {seconds} seconds",
+        Msg::TimeoutMillis => "Timeout: {ms}ms",
+
+        Msg::NoGamesFound => "No games found.",
+        Msg::GamesTotal => "{count} game(s) total",
+        Msg::HeaderGameId => "Game ID",
+        Msg::HeaderOpponent => "Opponent",
+        Msg::HeaderStatus => "Status",
+        Msg::HeaderColor => "Color",
+        Msg::HeaderMoves => "Moves",
+        Msg::HeaderTurn => "Turn",
+        Msg::StatusPending => "Pending",
+        Msg::StatusActive => "Active",
+        Msg::StatusDone => "Done",
+        Msg::StatusAbandoned => "Abandoned",
+        Msg::TurnYou => "You",
+        Msg::TurnThem => "Them",
+        Msg::ColumnGameId => "GAME ID",
+        Msg::ColumnOpponent => "OPPONENT",
+        Msg::ColumnColor => "COLOR",
+        Msg::ColumnStatus => "STATUS",
+        Msg::ColumnCreated => "CREATED",
+        Msg::ColumnUpdated => "LAST UPDATED",
+        Msg::ColumnResult => "RESULT",
+
+        Msg::White => "White",
+        Msg::Black => "Black",
+        Msg::ToMove => "To move: {color}",
+        Msg::MoveNumber => "Move #: {number}",
+        Msg::HalfmoveClock => "Halfmove clock: {count} (50-move rule)",
+        Msg::NoMovesInHistory => "No moves in history.",
+        Msg::MoveHistory => "Move History",
+        Msg::HistoryMove => "Move",
+        Msg::CurrentMove => "Current move: {number}",
+        Msg::GameStatusPending => "Game Status: Waiting for opponent to accept",
+        Msg::GameStatusActive => "Game Status: In progress",
+        Msg::GameStatusWon => "Game Status: You won!",
+        Msg::GameStatusLost => "Game Status: You lost",
+        Msg::GameStatusDraw => "Game Status: Draw",
+        Msg::GameStatusCompleted => "Game Status: Completed",
+        Msg::GameStatusAbandoned => "Game Status: Abandoned",
+        Msg::UnicodePrompt => "Use Unicode chess pieces? [Y/n]: ",
+        Msg::ConfirmConsequences => "This will:",
+        Msg::ConfirmPrompt => "{question}? [y/N]: ",
+        Msg::ConfirmYesAnswers => "y,yes",
+        Msg::ConfirmRetry => "Please answer y or n.",
+        Msg::ConfirmCancelled => "Cancelled; nothing was changed.",
+
+        Msg::ErrInvalidInput => "Invalid {field}: '{value}'",
+        Msg::ErrConfirmationNeedsTerminal => {
+            "Confirmation required but stdin is not a terminal"
+        }
+        Msg::SugConfirmWithYes => "Pass --yes to proceed without confirming.",
+        Msg::ErrConfiguration => "Configuration Error: {setting}",
+        Msg::ErrNetworkTimeout => "Network timeout during {operation}",
+        Msg::ErrConnectFailed => "Failed to connect to server",
+        Msg::SugConnectFailed => {
+            "Check that the address is correct and the peer is online. Verify network connectivity."
+        }
+        Msg::ErrAddressTooLong => "Network address is too long",
+        Msg::SugAddressTooLong => {
+            "Use a shorter address format like 'host:port' (e.g., '127.0.0.1:8080')."
+        }
+        Msg::ErrTimedOut => "Network operation timed out",
+        Msg::SugTimedOut => {
+            "The peer may be slow to respond or unreachable. Check connectivity and try again."
+        }
+        Msg::ErrInvalidAddress => "Invalid network address format",
+        Msg::SugAddressFormat => {
+            "Use format 'host:port' (e.g., '192.168.1.100:8080' or 'example.com:8080')."
+        }
+        Msg::ErrDatabaseFailed => "Database operation failed",
+        Msg::SugDatabaseFailed => {
+            "Check file permissions and database integrity. Try restarting the application."
+        }
+        Msg::ErrNetworkFailed => "Network operation failed",
+        Msg::SugNetworkFailed => {
+            "Check network connectivity and peer availability. Try reconnecting."
+        }
+        Msg::ErrUnexpected => "An unexpected error occurred",
+        Msg::SugUnexpected => {
+            "Check the error details above and try again. If the problem persists, this may be a bug."
+        }
+        Msg::ErrNoActiveGames => "No active games found.",
+        Msg::SugNoActiveGames => {
+            "Start a new game with 'mate invite <address>' or use --game-id to specify a game."
+        }
+        Msg::ErrGameNotFound => "Game '{id}' not found.",
+        Msg::SugGameNotFound => {
+            "Use 'mate games' to see available games, or check the game ID."
+        }
+        Msg::SugAmbiguousGame => "Use 'mate games' to see full game IDs.",
+        Msg::ErrInvalidGameState => "Invalid game state: {reason}",
+        Msg::SugInvalidGameState => {
+            "Check the game status with 'mate games' and ensure the game is active."
+        }
+        Msg::ErrDataFormat => "Data format error: {reason}",
+        Msg::SugReportBug => "This may be a bug. Please report this issue.",
+        Msg::ErrInvalidMove => "Invalid move: {reason}",
+        Msg::SugMoveNotation => {
+            "Use standard algebraic notation (e.g., 'e4', 'Nf3', 'O-O'). Use 'mate board' to see the current position."
+        }
+        Msg::ErrInvalidPosition => "Invalid position: {reason}",
+        Msg::SugCheckPosition => "Check the board position with 'mate board' command.",
+        Msg::ErrInvalidFen => "Invalid board notation: {reason}",
+        Msg::SugCheckFen => "Check the FEN string format.",
+        Msg::ErrInvalidColor => "Invalid color: {reason}",
+        Msg::SugColorSelection => "Use 'white' or 'black' for color selection.",
+        Msg::ErrInvalidPiece => "Invalid piece: {reason}",
+        Msg::SugPieceLetters => "Use standard piece letters (K, Q, R, B, N, P).",
+        Msg::ErrBoardState => "Board state error: {reason}",
+        Msg::SugBoardCorrupted => {
+            "The game state may be corrupted. Try 'mate board' to see the current position."
+        }
+        Msg::ErrStoredGameNotFound => "Game '{id}' not found in database.",
+        Msg::SugSeeGames => "Use 'mate games' to see available games.",
+        Msg::ErrMessageNotFound => "Message '{id}' not found.",
+        Msg::SugCheckMessage => "Check the message ID or game history.",
+        Msg::ErrDatabaseConnection => "Database connection failed.",
+        Msg::SugDatabaseConnection => {
+            "Check file permissions and disk space. Try restarting the application."
+        }
+        Msg::ErrDatabaseLocked => "Database is locked during {operation}.",
+        Msg::SugDatabaseLocked => {
+            "Another process may be using the database. Wait a moment and try again."
+        }
+        Msg::ErrInvalidData => "Invalid data in {field}: {reason}",
+        Msg::SugCheckData => "Check the data format and try again.",
+        Msg::ErrDatabase => "Database error: {error}",
+        Msg::ErrProtocolCommunication => "Communication protocol error",
+        Msg::SugCompatibleVersions => {
+            "Check network connection and ensure both players use compatible versions."
+        }
+        Msg::ErrPeerConnect => "Failed to connect to peer",
+        Msg::SugPeerConnect => {
+            "Verify the peer address is correct and the peer is online. Check for network connectivity issues."
+        }
+        Msg::ErrAuthentication => "Authentication failed with peer",
+        Msg::SugAuthentication => {
+            "The peer may be using different credentials. Ensure both players have compatible identities."
+        }
+        Msg::ErrConnectionClosed => "Connection closed unexpectedly",
+        Msg::SugConnectionClosed => {
+            "The peer may have disconnected. Try reconnecting to continue the game."
+        }
+        Msg::ErrVerification => "Message verification failed",
+        Msg::SugVerification => {
+            "This may indicate a security issue or incompatible software versions. Try reconnecting."
+        }
+        Msg::ErrTiming => "Message timing validation failed",
+        Msg::SugTiming => "Check that your system clock is synchronized. Try reconnecting.",
+        Msg::ErrClockSkew => "The peer's clock differs from yours by {offset}s, more than the {max}s allowed",
+        Msg::SugClockSkew => "Synchronize the system clocks on both machines (e.g. enable NTP), or raise network.max_clock_skew_secs.",
+        Msg::ErrSendQueueFull => "The peer is not keeping up: {capacity} messages are already waiting to be sent",
+        Msg::SugSendQueueFull => "Wait for the peer to catch up, or raise network.send_queue_capacity.",
+        Msg::SugServerReachable => {
+            "Check that the address is correct and the peer is reachable. Verify network connectivity."
+        }
+        Msg::ErrValidation => "Message validation failed: {reason}",
+        Msg::SugCommunicationIssue => {
+            "This may indicate a communication issue. Try reconnecting."
+        }
+        Msg::ErrOperationTimeout => "Operation '{operation}' timed out after {ms}ms",
+        Msg::SugSlowPeer => {
+            "The peer may be slow to respond. Try again or check network connection."
+        }
+        Msg::ErrGameStateIn => "Game state error in {game_id}: {error}",
+        Msg::SugGameStateCorrupted => {
+            "The game state may be corrupted. Try 'mate board' to see current state."
+        }
+        Msg::ErrSecurityViolation => "Security violation in game {game_id}: {violation}",
+        Msg::SugMaliciousPeer => "This may indicate a malicious peer. Consider ending the game.",
+        Msg::ErrProtocol => "Protocol error: {error}",
+        Msg::SugReconnectPeer => {
+            "This may be a communication issue. Try reconnecting to the peer."
+        }
+        Msg::ErrInvalidFormat => "Invalid message format received",
+        Msg::SugSameVersion => {
+            "This may indicate incompatible versions. Ensure both players are using the same version."
+        }
+        Msg::ErrMessageTooLarge => "Message too large: {size} bytes (max: {max_size} bytes)",
+        Msg::SugMessageTooLarge => "The message is too big to send. This may be a bug.",
+        Msg::ErrNetworkIo => "Network I/O error",
+        Msg::SugCheckNetwork => "Check network connection and try again.",
+        Msg::ErrProtocolViolation => "Protocol violation: {description}",
+        Msg::SugIncompatibleClients => {
+            "This may indicate incompatible clients. Ensure both players use the same version."
+        }
+        Msg::ErrCommunication => "Communication error: {error}",
+        Msg::SugCheckAndReconnect => "Check network connection and try reconnecting.",
+        Msg::ErrCannotAccessDatabase => "Cannot access game database",
+        Msg::SugCannotAccessDatabase => {
+            "Check file permissions and disk space. The database may be corrupted or locked by another process."
+        }
+        Msg::ErrNoGameSpecified => "No game specified and no active games found",
+        Msg::SugNoGameSpecified => {
+            "Use 'mate games' to see available games, then 'mate board --game-id <id>' to view a specific game."
+        }
+        Msg::ErrInviteFailed => "Failed to send game invitation",
+        Msg::SugInviteFailed => {
+            "Check that the peer address is correct and reachable. The peer may be offline or behind a firewall."
+        }
+        Msg::ErrInvitationNotFound => "Game invitation not found",
+        Msg::SugInvitationNotFound => {
+            "Use 'mate games' to see pending invitations. The invitation may have expired or been withdrawn."
+        }
+        Msg::ErrInvalidChessMove => "Invalid chess move",
+        Msg::SugInvalidChessMove => {
+            "Use standard algebraic notation (e.g., 'e4', 'Nf3', 'O-O', 'Qxe7+'). Use 'mate board' to see the current position and legal moves."
+        }
+        Msg::ErrHistoryGameNotFound => "Game not found for history display",
+        Msg::SugHistoryGameNotFound => {
+            "Use 'mate games' to see available games, then 'mate history --game-id <id>' to view move history."
+        }
+        Msg::SugTimeoutConnect => {
+            "The peer may be offline or unreachable. Verify the address and try again."
+        }
+        Msg::SugTimeoutInvite => {
+            "The peer may be slow to respond. Try again or check if the peer is online."
+        }
+        Msg::SugTimeoutMove => {
+            "Move could not be sent. The peer may have disconnected. Check connection and try again."
+        }
+        Msg::SugTimeoutHandshake => {
+            "Initial connection handshake failed. The peer may be using incompatible software."
+        }
+        Msg::SugTimeoutOther => "Network operation timed out. Check connection and try again.",
+        Msg::SugGameIdFormat => {
+            "Game IDs should be in UUID format. Use 'mate games' to see valid game IDs."
+        }
+        Msg::SugColorInput => "Use 'white' or 'black' to specify player color.",
+        Msg::SugCheckInput => "Check the input format and try again.",
+    }
+}
+
+fn spanish(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Suggestion => "Sugerencia",
+        Msg::Reason => "Motivo",
+        Msg::Issue => "Problema",
+        Msg::TimeoutSeconds => "Tiempo de espera: {seconds} segundos",
+        Msg::TimeoutMillis => "Tiempo de espera: {ms} ms",
+
+        Msg::NoGamesFound => "No se encontraron partidas.",
+        Msg::GamesTotal => "{count} partida(s) en total",
+        Msg::HeaderGameId => "ID de partida",
+        Msg::HeaderOpponent => "Rival",
+        Msg::HeaderStatus => "Estado",
+        Msg::HeaderColor => "Color",
+        Msg::HeaderMoves => "Jugadas",
+        Msg::HeaderTurn => "Turno",
+        Msg::StatusPending => "Pendiente",
+        Msg::StatusActive => "Activa",
+        Msg::StatusDone => "Terminada",
+        Msg::StatusAbandoned => "Abandonada",
+        Msg::TurnYou => "Tú",
+        Msg::TurnThem => "Rival",
+        Msg::ColumnGameId => "ID PARTIDA",
+        Msg::ColumnOpponent => "RIVAL",
+        Msg::ColumnColor => "COLOR",
+        Msg::ColumnStatus => "ESTADO",
+        Msg::ColumnCreated => "CREADA",
+        Msg::ColumnUpdated => "ÚLTIMO CAMBIO",
+        Msg::ColumnResult => "RESULTADO",
+
+        Msg::White => "Blancas",
+        Msg::Black => "Negras",
+        Msg::ToMove => "Juegan: {color}",
+        Msg::MoveNumber => "Jugada n.º: {number}",
+        Msg::HalfmoveClock => "Reloj de medias jugadas: {count} (regla de 50 jugadas)",
+        Msg::NoMovesInHistory => "No hay jugadas en el historial.",
+        Msg::MoveHistory => "Historial de jugadas",
+        Msg::HistoryMove => "N.º",
+        Msg::CurrentMove => "Jugada actual: {number}",
+        Msg::GameStatusPending => "Estado: esperando a que el rival acepte",
+        Msg::GameStatusActive => "Estado: en curso",
+        Msg::GameStatusWon => "Estado: ¡has ganado!",
+        Msg::GameStatusLost => "Estado: has perdido",
+        Msg::GameStatusDraw => "Estado: tablas",
+        Msg::GameStatusCompleted => "Estado: terminada",
+        Msg::GameStatusAbandoned => "Estado: abandonada",
+        Msg::UnicodePrompt => "¿Usar piezas de ajedrez Unicode? [S/n]: ",
+        Msg::ConfirmConsequences => "Esto hará lo siguiente:",
+        Msg::ConfirmPrompt => "¿{question}? [s/N]: ",
+        Msg::ConfirmYesAnswers => "s,si,sí,y,yes",
+        Msg::ConfirmRetry => "Responde s o n.",
+        Msg::ConfirmCancelled => "Cancelado; no se ha cambiado nada.",
+
+        Msg::ErrInvalidInput => "Valor no válido para {field}: '{value}'",
+        Msg::ErrConfirmationNeedsTerminal => {
+            "Hace falta confirmar, pero la entrada estándar no es una terminal"
+        }
+        Msg::SugConfirmWithYes => "Usa --yes para continuar sin confirmar.",
+        Msg::ErrConfiguration => "Error de configuración: {setting}",
+        Msg::ErrNetworkTimeout => "Tiempo de red agotado durante {operation}",
+        Msg::ErrConnectFailed => "No se pudo conectar con el servidor",
+        Msg::SugConnectFailed => {
+            "Comprueba que la dirección sea correcta y que el rival esté conectado. Verifica la conexión de red."
+        }
+        Msg::ErrAddressTooLong => "La dirección de red es demasiado larga",
+        Msg::SugAddressTooLong => {
+            "Usa una dirección más corta con el formato 'host:puerto' (p. ej., '127.0.0.1:8080')."
+        }
+        Msg::ErrTimedOut => "Se agotó el tiempo de la operación de red",
+        Msg::SugTimedOut => {
+            "El rival puede tardar en responder o no estar disponible. Comprueba la conexión e inténtalo de nuevo."
+        }
+        Msg::ErrInvalidAddress => "Formato de dirección de red no válido",
+        Msg::SugAddressFormat => {
+            "Usa el formato 'host:puerto' (p. ej., '192.168.1.100:8080' o 'example.com:8080')."
+        }
+        Msg::ErrDatabaseFailed => "Falló la operación de base de datos",
+        Msg::SugDatabaseFailed => {
+            "Comprueba los permisos de los archivos y la integridad de la base de datos. Prueba a reiniciar la aplicación."
+        }
+        Msg::ErrNetworkFailed => "Falló la operación de red",
+        Msg::SugNetworkFailed => {
+            "Comprueba la conexión de red y que el rival esté disponible. Prueba a reconectar."
+        }
+        Msg::ErrUnexpected => "Se produjo un error inesperado",
+        Msg::SugUnexpected => {
+            "Revisa los detalles del error e inténtalo de nuevo. Si el problema continúa, puede ser un fallo del programa."
+        }
+        Msg::ErrNoActiveGames => "No hay partidas activas.",
+        Msg::SugNoActiveGames => {
+            "Empieza una partida con 'mate invite <dirección>' o indica una con --game-id."
+        }
+        Msg::ErrGameNotFound => "No se encontró la partida '{id}'.",
+        Msg::SugGameNotFound => {
+            "Usa 'mate games' para ver las partidas disponibles o comprueba el ID de la partida."
+        }
+        Msg::SugAmbiguousGame => "Usa 'mate games' para ver los ID completos de las partidas.",
+        Msg::ErrInvalidGameState => "Estado de partida no válido: {reason}",
+        Msg::SugInvalidGameState => {
+            "Comprueba el estado de la partida con 'mate games' y asegúrate de que esté activa."
+        }
+        Msg::ErrDataFormat => "Error de formato de datos: {reason}",
+        Msg::SugReportBug => "Puede ser un fallo del programa. Por favor, notifícalo.",
+        Msg::ErrInvalidMove => "Jugada no válida: {reason}",
+        Msg::SugMoveNotation => {
+            "Usa notación algebraica estándar (p. ej., 'e4', 'Nf3', 'O-O'). Usa 'mate board' para ver la posición actual."
+        }
+        Msg::ErrInvalidPosition => "Posición no válida: {reason}",
+        Msg::SugCheckPosition => "Comprueba la posición con el comando 'mate board'.",
+        Msg::ErrInvalidFen => "Notación de tablero no válida: {reason}",
+        Msg::SugCheckFen => "Comprueba el formato de la cadena FEN.",
+        Msg::ErrInvalidColor => "Color no válido: {reason}",
+        Msg::SugColorSelection => "Elige el color con 'white' o 'black'.",
+        Msg::ErrInvalidPiece => "Pieza no válida: {reason}",
+        Msg::SugPieceLetters => "Usa las letras estándar de las piezas (K, Q, R, B, N, P).",
+        Msg::ErrBoardState => "Error en el estado del tablero: {reason}",
+        Msg::SugBoardCorrupted => {
+            "El estado de la partida puede estar dañado. Usa 'mate board' para ver la posición actual."
+        }
+        Msg::ErrStoredGameNotFound => "No se encontró la partida '{id}' en la base de datos.",
+        Msg::SugSeeGames => "Usa 'mate games' para ver las partidas disponibles.",
+        Msg::ErrMessageNotFound => "No se encontró el mensaje '{id}'.",
+        Msg::SugCheckMessage => "Comprueba el ID del mensaje o el historial de la partida.",
+        Msg::ErrDatabaseConnection => "No se pudo abrir la base de datos.",
+        Msg::SugDatabaseConnection => {
+            "Comprueba los permisos de los archivos y el espacio en disco. Prueba a reiniciar la aplicación."
+        }
+        Msg::ErrDatabaseLocked => "La base de datos está bloqueada durante {operation}.",
+        Msg::SugDatabaseLocked => {
+            "Puede que otro proceso esté usando la base de datos. Espera un momento e inténtalo de nuevo."
+        }
+        Msg::ErrInvalidData => "Datos no válidos en {field}: {reason}",
+        Msg::SugCheckData => "Comprueba el formato de los datos e inténtalo de nuevo.",
+        Msg::ErrDatabase => "Error de base de datos: {error}",
+        Msg::ErrProtocolCommunication => "Error en el protocolo de comunicación",
+        Msg::SugCompatibleVersions => {
+            "Comprueba la conexión de red y que ambos jugadores usen versiones compatibles."
+        }
+        Msg::ErrPeerConnect => "No se pudo conectar con el rival",
+        Msg::SugPeerConnect => {
+            "Verifica que la dirección del rival sea correcta y que esté conectado. Comprueba si hay problemas de red."
+        }
+        Msg::ErrAuthentication => "Falló la autenticación con el rival",
+        Msg::SugAuthentication => {
+            "Puede que el rival use otras credenciales. Asegúrate de que ambos jugadores tengan identidades compatibles."
+        }
+        Msg::ErrConnectionClosed => "La conexión se cerró inesperadamente",
+        Msg::SugConnectionClosed => {
+            "Puede que el rival se haya desconectado. Reconecta para continuar la partida."
+        }
+        Msg::ErrVerification => "Falló la verificación del mensaje",
+        Msg::SugVerification => {
+            "Puede indicar un problema de seguridad o versiones incompatibles. Prueba a reconectar."
+        }
+        Msg::ErrTiming => "Falló la validación de la hora del mensaje",
+        Msg::SugTiming => "Comprueba que el reloj del sistema esté sincronizado. Prueba a reconectar.",
+        Msg::ErrClockSkew => "El reloj del par difiere del tuyo en {offset} s, más de los {max} s permitidos",
+        Msg::SugClockSkew => "Sincroniza los relojes de ambos equipos (por ejemplo, activa NTP) o aumenta network.max_clock_skew_secs.",
+        Msg::ErrSendQueueFull => "El par no da abasto: ya hay {capacity} mensajes esperando para enviarse",
+        Msg::SugSendQueueFull => "Espera a que el par se ponga al día o aumenta network.send_queue_capacity.",
+        Msg::SugServerReachable => {
+            "Comprueba que la dirección sea correcta y que el rival sea accesible. Verifica la conexión de red."
+        }
+        Msg::ErrValidation => "Falló la validación del mensaje: {reason}",
+        Msg::SugCommunicationIssue => {
+            "Puede indicar un problema de comunicación. Prueba a reconectar."
+        }
+        Msg::ErrOperationTimeout => "La operación '{operation}' superó el tiempo de espera tras {ms} ms",
+        Msg::SugSlowPeer => {
+            "El rival puede tardar en responder. Inténtalo de nuevo o comprueba la conexión de red."
+        }
+        Msg::ErrGameStateIn => "Error en el estado de la partida {game_id}: {error}",
+        Msg::SugGameStateCorrupted => {
+            "El estado de la partida puede estar dañado. Usa 'mate board' para ver el estado actual."
+        }
+        Msg::ErrSecurityViolation => "Infracción de seguridad en la partida {game_id}: {violation}",
+        Msg::SugMaliciousPeer => "Puede indicar un rival malintencionado. Considera terminar la partida.",
+        Msg::ErrProtocol => "Error de protocolo: {error}",
+        Msg::SugReconnectPeer => {
+            "Puede ser un problema de comunicación. Prueba a reconectar con el rival."
+        }
+        Msg::ErrInvalidFormat => "Se recibió un mensaje con formato no válido",
+        Msg::SugSameVersion => {
+            "Puede indicar versiones incompatibles. Asegúrate de que ambos jugadores usen la misma versión."
+        }
+        Msg::ErrMessageTooLarge => {
+            "Mensaje demasiado grande: {size} bytes (máximo: {max_size} bytes)"
+        }
+        Msg::SugMessageTooLarge => {
+            "El mensaje es demasiado grande para enviarlo. Puede ser un fallo del programa."
+        }
+        Msg::ErrNetworkIo => "Error de E/S de red",
+        Msg::SugCheckNetwork => "Comprueba la conexión de red e inténtalo de nuevo.",
+        Msg::ErrProtocolViolation => "Infracción del protocolo: {description}",
+        Msg::SugIncompatibleClients => {
+            "Puede indicar clientes incompatibles. Asegúrate de que ambos jugadores usen la misma versión."
+        }
+        Msg::ErrCommunication => "Error de comunicación: {error}",
+        Msg::SugCheckAndReconnect => "Comprueba la conexión de red y prueba a reconectar.",
+        Msg::ErrCannotAccessDatabase => "No se puede acceder a la base de datos de partidas",
+        Msg::SugCannotAccessDatabase => {
+            "Comprueba los permisos de los archivos y el espacio en disco. Puede que la base de datos esté dañada o bloqueada por otro proceso."
+        }
+        Msg::ErrNoGameSpecified => "No se indicó ninguna partida y no hay partidas activas",
+        Msg::SugNoGameSpecified => {
+            "Usa 'mate games' para ver las partidas disponibles y luego 'mate board --game-id <id>' para ver una en concreto."
+        }
+        Msg::ErrInviteFailed => "No se pudo enviar la invitación",
+        Msg::SugInviteFailed => {
+            "Comprueba que la dirección del rival sea correcta y accesible. Puede que esté desconectado o detrás de un cortafuegos."
+        }
+        Msg::ErrInvitationNotFound => "No se encontró la invitación",
+        Msg::SugInvitationNotFound => {
+            "Usa 'mate games' para ver las invitaciones pendientes. Puede que la invitación haya caducado o se haya retirado."
+        }
+        Msg::ErrInvalidChessMove => "Jugada de ajedrez no válida",
+        Msg::SugInvalidChessMove => {
+            "Usa notación algebraica estándar (p. ej., 'e4', 'Nf3', 'O-O', 'Qxe7+'). Usa 'mate board' para ver la posición actual y las jugadas legales."
+        }
+        Msg::ErrHistoryGameNotFound => "No se encontró la partida para mostrar su historial",
+        Msg::SugHistoryGameNotFound => {
+            "Usa 'mate games' para ver las partidas disponibles y luego 'mate history --game-id <id>' para ver su historial."
+        }
+        Msg::SugTimeoutConnect => {
+            "Puede que el rival esté desconectado o no sea accesible. Verifica la dirección e inténtalo de nuevo."
+        }
+        Msg::SugTimeoutInvite => {
+            "El rival puede tardar en responder. Inténtalo de nuevo o comprueba si está conectado."
+        }
+        Msg::SugTimeoutMove => {
+            "No se pudo enviar la jugada. Puede que el rival se haya desconectado. Comprueba la conexión e inténtalo de nuevo."
+        }
+        Msg::SugTimeoutHandshake => {
+            "Falló el saludo inicial de la conexión. Puede que el rival use software incompatible."
+        }
+        Msg::SugTimeoutOther => {
+            "Se agotó el tiempo de la operación de red. Comprueba la conexión e inténtalo de nuevo."
+        }
+        Msg::SugGameIdFormat => {
+            "Los ID de partida tienen formato UUID. Usa 'mate games' para ver los ID válidos."
+        }
+        Msg::SugColorInput => "Indica el color del jugador con 'white' o 'black'.",
+        Msg::SugCheckInput => "Comprueba el formato de la entrada e inténtalo de nuevo.",
+    }
+}
@@ -0,0 +1,88 @@
+use crate::cli::game_ops::InvitationRecord;
+use crate::storage::models::{PlayerColor, TimeControl};
+use anyhow::{Context, Result};
+use std::io::{BufRead, Write};
+
+/// Time control as `minutes+increment`, e.g. `5+3`, or `untimed`
+pub fn format_time_control(time_control: Option<&TimeControl>) -> String {
+    let Some(time_control) = time_control else {
+        return "untimed".to_string();
+    };
+    let increment = time_control.increment_ms / 1000;
+    if time_control.initial_time_ms % 60_000 == 0 {
+        format!("{}+{increment}", time_control.initial_time_ms / 60_000)
+    } else {
+        format!("{}s+{increment}", time_control.initial_time_ms / 1000)
+    }
+}
+
+/// Whether a game is `rated` or `casual`
+pub fn game_mode(rated: bool) -> &'static str {
+    if rated {
+        "rated"
+    } else {
+        "casual"
+    }
+}
+
+/// One inbox line: who invited me, which side they suggest I play, the
+/// clock, and whether the game is rated
+pub fn invitation_line(invitation: &InvitationRecord, inviter: &str, received: &str) -> String {
+    let game_display = if invitation.game_id.len() > 8 {
+        format!("{}...", &invitation.game_id[..8])
+    } else {
+        invitation.game_id.clone()
+    };
+    let side = match invitation.suggested_color {
+        Some(PlayerColor::White) => "you play White",
+        Some(PlayerColor::Black) => "you play Black",
+        None => "any color",
+    };
+    format!(
+        "{game_display}  from {inviter}  {side}  {} {}  {received}",
+        format_time_control(invitation.time_control.as_ref()),
+        game_mode(invitation.rated)
+    )
+}
+
+/// List the inbox and ask which invitation to accept
+///
+/// Returns the index of the chosen line, or `None` if the answer was left
+/// empty, was `q`, or input ended.
+pub fn choose_invitation<R: BufRead, W: Write>(
+    lines: &[String],
+    mut input: R,
+    mut output: W,
+) -> Result<Option<usize>> {
+    writeln!(output, "Incoming invitations:")?;
+    for (number, line) in lines.iter().enumerate() {
+        writeln!(output, "  {:>2}) {line}", number + 1)?;
+    }
+
+    loop {
+        write!(
+            output,
+            "Accept which invitation? [1-{}, Enter to cancel]: ",
+            lines.len()
+        )?;
+        output.flush()?;
+
+        let mut answer = String::new();
+        if input
+            .read_line(&mut answer)
+            .context("Failed to read answer")?
+            == 0
+        {
+            writeln!(output)?;
+            return Ok(None);
+        }
+        let answer = answer.trim();
+        if answer.is_empty() || answer.eq_ignore_ascii_case("q") {
+            return Ok(None);
+        }
+        match answer.parse::<usize>() {
+            Ok(number) if (1..=lines.len()).contains(&number) => return Ok(Some(number - 1)),
+            _ => writeln!(output, "  Enter a number from 1 to {}", lines.len())?,
+        }
+    }
+}
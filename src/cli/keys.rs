@@ -0,0 +1,336 @@
+use crate::cli::account::{read_passphrase_from_env, read_recovery_phrase};
+use crate::cli::app::Config;
+use crate::cli::display::{confirm, Confirmation};
+use crate::cli::{display_error_and_exit, CliError, ErrorCode};
+use crate::crypto::identity::KEY_PASSPHRASE_ENV_VAR;
+use crate::crypto::storage::{
+    default_key_dir, default_key_path, identity_key_path, key_path_in, list_identities, KeyBackend,
+    DEFAULT_IDENTITY,
+};
+use crate::crypto::Identity;
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+
+/// Default identity key path, failing if no identity has been created there
+fn existing_key_path() -> Result<PathBuf> {
+    let key_path = default_key_path().context("Failed to determine key storage path")?;
+    if !key_path.exists() {
+        anyhow::bail!(
+            "No identity at {}. Run 'mate key generate' to create one",
+            key_path.display()
+        );
+    }
+    Ok(key_path)
+}
+
+/// Identity for the key labelled `label` on the token behind `module`
+#[cfg(feature = "hardware-keys")]
+fn open_token_identity(module: &Path, label: &str) -> Result<Identity> {
+    let signer = crate::crypto::hardware::TokenSigner::open(module, label)?;
+    Ok(Identity::from_signer(std::sync::Arc::new(signer)))
+}
+
+#[cfg(not(feature = "hardware-keys"))]
+fn open_token_identity(_module: &Path, _label: &str) -> Result<Identity> {
+    anyhow::bail!(
+        "This build of mate has no hardware key support (rebuild with --features hardware-keys)"
+    )
+}
+
+/// Whether the user agreed to `confirmation`, exiting if they could not be asked
+fn confirmed(confirmation: &Confirmation, yes: bool) -> bool {
+    match confirm(confirmation, yes) {
+        Ok(confirmed) => confirmed,
+        Err(e) => display_error_and_exit(CliError::from(e)),
+    }
+}
+
+/// Show where the identity key is stored and whether it exists
+pub fn handle_key_path() {
+    info!("Showing default key storage path...");
+    match default_key_path() {
+        Ok(path) => {
+            info!("Default key storage path: {}", path.display());
+            if path.exists() {
+                info!("✓ Identity file exists");
+            } else {
+                info!("✗ Identity file does not exist");
+                info!("Run 'mate key generate' to create a new identity");
+            }
+        }
+        Err(e) => {
+            error!("Failed to determine key storage path: {}", e);
+        }
+    }
+}
+
+/// Generate a new identity, asking before replacing an existing one
+pub fn handle_key_generate(yes: bool) -> Result<()> {
+    info!("Generating new identity...");
+
+    // Get the key path once and reuse it
+    let key_path = match default_key_path() {
+        Ok(path) => path,
+        Err(e) => {
+            error!("Failed to determine key storage path: {}", e);
+            return Ok(());
+        }
+    };
+
+    // Replacing an identity changes the peer ID, so ask first
+    if key_path.exists() {
+        warn!("An identity already exists at: {}", key_path.display());
+        let mut confirmation = Confirmation::new("Replace your identity")
+            .consequence(format!("overwrite the identity at {}", key_path.display()));
+        if let Ok(existing) = Identity::stored_peer_id(&key_path) {
+            confirmation = confirmation.consequence(format!("change your peer ID from {existing}"));
+        }
+        confirmation = confirmation.consequence(
+            "opponents will no longer recognize you and games in progress cannot continue",
+        );
+        if !confirmed(&confirmation, yes) {
+            return Ok(());
+        }
+    }
+
+    let backend = Config::load_or_default()
+        .map(|config| config.identity.storage)
+        .unwrap_or_default();
+    let identity = Identity::generate()?;
+    identity.save_with_backend(&key_path, backend)?;
+
+    info!("Identity generated successfully!");
+    info!("Peer ID: {}", identity.peer_id());
+    info!(
+        "Public Key: {}",
+        general_purpose::STANDARD.encode(identity.verifying_key().to_bytes())
+    );
+    info!("Saved to: {}", key_path.display());
+    Ok(())
+}
+
+/// Show the identity's peer ID, public key, and how its secret key is kept
+pub fn handle_key_info() {
+    info!("Showing identity information...");
+
+    match Identity::from_default_storage() {
+        Ok(identity) => {
+            info!("Peer ID: {}", identity.peer_id());
+            info!(
+                "Public Key: {}",
+                general_purpose::STANDARD.encode(identity.verifying_key().to_bytes())
+            );
+
+            if let Ok(path) = default_key_path() {
+                info!("Storage location: {}", path.display());
+                if identity.is_hardware_backed() {
+                    info!("Secret key: on a hardware token");
+                } else if Identity::backend_at(&path)
+                    .is_ok_and(|backend| backend == KeyBackend::Keychain)
+                {
+                    info!("Secret key: in the OS keychain");
+                } else if Identity::is_protected_at(&path).unwrap_or(false) {
+                    info!("Passphrase protected: yes");
+                } else {
+                    info!("Passphrase protected: no (run 'mate key protect')");
+                }
+            }
+        }
+        Err(e) => {
+            error!("No identity found: {}", e);
+            info!("Run 'mate key generate' to create a new identity");
+        }
+    }
+}
+
+/// Encrypt the identity key with a passphrase
+pub fn handle_key_protect() -> Result<()> {
+    let key_path = existing_key_path()?;
+    let identity = Identity::from_storage_path(&key_path).context("Failed to load identity")?;
+
+    info!("Protecting identity {}...", identity.peer_id());
+    let passphrase =
+        read_passphrase_from_env(KEY_PASSPHRASE_ENV_VAR, "New key passphrase: ", true)?;
+    identity
+        .save_protected_to_storage_path(&key_path, &passphrase)
+        .context("Failed to save protected identity")?;
+
+    info!("✓ Identity key is now passphrase protected");
+    info!(
+        "The passphrase is asked for when mate starts, or read from {}",
+        KEY_PASSPHRASE_ENV_VAR
+    );
+    Ok(())
+}
+
+/// Store the identity key without passphrase protection
+pub fn handle_key_unprotect() -> Result<()> {
+    let key_path = existing_key_path()?;
+    if !Identity::is_protected_at(&key_path)? {
+        info!("Identity key is not passphrase protected");
+        return Ok(());
+    }
+    let identity = Identity::from_storage_path(&key_path).context("Failed to load identity")?;
+    identity
+        .save_to_storage_path(&key_path)
+        .context("Failed to save identity")?;
+
+    warn!(
+        "Identity key at {} is no longer passphrase protected",
+        key_path.display()
+    );
+    Ok(())
+}
+
+/// Print the recovery phrase for the identity key
+pub fn handle_key_backup() -> Result<()> {
+    let key_path = existing_key_path()?;
+    let identity = Identity::from_storage_path(&key_path).context("Failed to load identity")?;
+
+    let mnemonic = identity
+        .to_mnemonic()
+        .context("Cannot back up this identity")?;
+
+    warn!("Anyone with these words can act as you. Keep them offline and never share them.");
+    println!("Recovery phrase for {}:", identity.peer_id());
+    for (i, word) in mnemonic.split(' ').enumerate() {
+        println!("{:>2}. {}", i + 1, word);
+    }
+    Ok(())
+}
+
+/// List the named identities, marking the active one
+pub fn handle_key_list() -> Result<()> {
+    let key_dir = default_key_dir().context("Failed to determine key storage path")?;
+    let active = crate::crypto::storage::active_identity();
+    let names = list_identities(&key_dir);
+    if names.is_empty() {
+        println!("No identities yet. Run 'mate key generate' to create one.");
+    }
+    for name in names {
+        let marker = if name == active { '*' } else { ' ' };
+        let peer_id = key_path_in(&key_dir, &name)
+            .map_err(anyhow::Error::from)
+            .and_then(|path| Identity::stored_peer_id(&path))
+            .map(|peer_id| peer_id.to_string())
+            .unwrap_or_else(|_| "(unreadable key)".to_string());
+        println!("{marker} {name:<16} {peer_id}");
+    }
+    Ok(())
+}
+
+/// Make the identity called `name` the active one
+pub fn handle_key_use(name: &str) -> Result<()> {
+    let key_path = identity_key_path(name).context("Failed to determine key storage path")?;
+    if !key_path.exists() {
+        display_error_and_exit(CliError::UserError {
+            message: format!("There is no identity named '{name}'"),
+            suggestion: Some(format!(
+                "Create it with 'mate --identity {name} key generate', or run 'mate key list'"
+            )),
+            code: ErrorCode::InvalidInput,
+        });
+    }
+
+    // Store only the file's own setting, not environment overrides
+    let mut config = Config::load_stored().context("Failed to load configuration")?;
+    config.identity.active = (name != DEFAULT_IDENTITY).then(|| name.to_string());
+    config.save().context("Failed to save configuration")?;
+
+    info!("Now using identity '{}'", name);
+    if let Ok(peer_id) = Identity::stored_peer_id(&key_path) {
+        info!("Peer ID: {}", peer_id);
+    }
+    Ok(())
+}
+
+/// Restore the identity from its recovery phrase
+pub fn handle_key_recover(yes: bool) -> Result<()> {
+    let key_path = default_key_path().context("Failed to determine key storage path")?;
+    let phrase = read_recovery_phrase()?;
+    let identity = match Identity::from_mnemonic(&phrase) {
+        Ok(identity) => identity,
+        Err(e) => display_error_and_exit(CliError::UserError {
+            message: format!("{e:#}"),
+            suggestion: Some("Check the words and their order against your backup".to_string()),
+            code: ErrorCode::InvalidInput,
+        }),
+    };
+
+    if key_path.exists() {
+        match Identity::stored_peer_id(&key_path) {
+            Ok(existing) if &existing == identity.peer_id() => {
+                info!("The recovered identity is already in use: {}", existing);
+                return Ok(());
+            }
+            existing => {
+                let mut confirmation = Confirmation::new("Replace your identity")
+                    .consequence(format!("overwrite the identity at {}", key_path.display()));
+                if let Ok(existing) = existing {
+                    confirmation = confirmation.consequence(format!(
+                        "change your peer ID from {existing} to {}",
+                        identity.peer_id()
+                    ));
+                }
+                if !confirmed(&confirmation, yes) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    let backend = Config::load_or_default()
+        .map(|config| config.identity.storage)
+        .unwrap_or_default();
+    identity.save_with_backend(&key_path, backend)?;
+
+    info!("Identity recovered successfully!");
+    info!("Peer ID: {}", identity.peer_id());
+    info!("Saved to: {}", key_path.display());
+    Ok(())
+}
+
+/// Switch to the key labelled `label` on the hardware token behind `module`
+pub fn handle_key_token(module: &Path, label: &str, yes: bool) -> Result<()> {
+    let key_path = default_key_path().context("Failed to determine key storage path")?;
+    let identity = match open_token_identity(module, label) {
+        Ok(identity) => identity,
+        Err(e) => display_error_and_exit(CliError::UserError {
+            message: format!("{e:#}"),
+            suggestion: Some(
+                "Check that the token is plugged in and holds an Ed25519 key with that label"
+                    .to_string(),
+            ),
+            code: ErrorCode::Configuration,
+        }),
+    };
+
+    if key_path.exists() {
+        if Identity::stored_peer_id(&key_path).is_ok_and(|existing| &existing == identity.peer_id())
+            && Identity::is_hardware_at(&key_path).unwrap_or(false)
+        {
+            info!("The token key is already in use: {}", identity.peer_id());
+            return Ok(());
+        }
+        let confirmation = Confirmation::new("Switch to the token key")
+            .consequence(format!(
+                "overwrite the identity at {} (back it up first with 'mate key backup')",
+                key_path.display()
+            ))
+            .consequence(format!("change your peer ID to {}", identity.peer_id()));
+        if !confirmed(&confirmation, yes) {
+            return Ok(());
+        }
+    }
+
+    identity
+        .save_token_reference(&key_path, module, label)
+        .context("Failed to save identity")?;
+
+    info!("Now using the token key '{}'", label);
+    info!("Peer ID: {}", identity.peer_id());
+    info!("Saved to: {}", key_path.display());
+    Ok(())
+}
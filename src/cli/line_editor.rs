@@ -0,0 +1,204 @@
+use crate::chess::Board;
+use crate::cli::display::ColorSupport;
+use anyhow::{Context as _, Result};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::{Hint, Hinter};
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, ExternalPrinter, Helper};
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+/// What tab completion and inline hints offer for the next line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Suggestions {
+    /// Words the first word of the line can be completed to
+    pub words: Vec<String>,
+    /// Hint shown when the typed word starts none of `words`; `None` allows free text
+    pub unmatched_hint: Option<String>,
+}
+
+impl Suggestions {
+    /// Session commands followed by every legal move in SAN and coordinate notation
+    pub fn for_moves(board: &Board, commands: &[&str]) -> Self {
+        let mut words: Vec<String> = commands.iter().map(|c| c.to_string()).collect();
+        let legal = board.legal_moves();
+        words.extend(legal.iter().map(|&mv| board.to_san(mv)));
+        words.extend(legal.iter().map(|mv| mv.to_string().to_lowercase()));
+
+        Self {
+            words,
+            unmatched_hint: Some("not a legal move or command".to_string()),
+        }
+    }
+
+    /// Session commands only, with the given hint for anything else
+    pub fn for_commands(commands: &[&str], unmatched_hint: Option<&str>) -> Self {
+        Self {
+            words: commands.iter().map(|c| c.to_string()).collect(),
+            unmatched_hint: unmatched_hint.map(String::from),
+        }
+    }
+
+    /// Words starting with `prefix`, ignoring case, in their listed order
+    pub fn matching(&self, prefix: &str) -> Vec<&str> {
+        let prefix = prefix.to_lowercase();
+        self.words
+            .iter()
+            .filter(|word| word.to_lowercase().starts_with(&prefix))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Inline hint for a partially typed word
+    ///
+    /// A single match is suggested in full (accepted with →); input that
+    /// matches nothing gets the unmatched hint.
+    pub fn hint(&self, input: &str) -> Option<InputHint> {
+        if input.trim().is_empty() || input.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let matches = self.matching(input);
+        match matches.as_slice() {
+            [] => self.unmatched_hint.as_ref().map(|hint| InputHint {
+                display: format!("  ✗ {hint}"),
+                completion: None,
+            }),
+            [word] => word
+                .get(input.len()..)
+                .filter(|rest| !rest.is_empty())
+                .map(|rest| InputHint {
+                    display: rest.to_string(),
+                    completion: Some(rest.to_string()),
+                }),
+            _ => None,
+        }
+    }
+}
+
+/// Text shown after the cursor: either the rest of a suggestion or an error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputHint {
+    pub display: String,
+    pub completion: Option<String>,
+}
+
+impl Hint for InputHint {
+    fn display(&self) -> &str {
+        &self.display
+    }
+
+    fn completion(&self) -> Option<&str> {
+        self.completion.as_deref()
+    }
+}
+
+/// Suggestions shared between the session and the editor reading its input
+pub type SharedSuggestions = Arc<Mutex<Suggestions>>;
+
+/// Rustyline helper completing and hinting from the shared suggestions
+struct InputHelper {
+    suggestions: SharedSuggestions,
+    dim_hints: bool,
+}
+
+impl InputHelper {
+    fn suggestions(&self) -> Suggestions {
+        self.suggestions
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Completer for InputHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        // Only the first word (a move or command) is completed
+        let typed = &line[..pos];
+        if typed.contains(char::is_whitespace) {
+            return Ok((pos, Vec::new()));
+        }
+        let suggestions = self.suggestions();
+        let candidates = suggestions
+            .matching(typed)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        Ok((0, candidates))
+    }
+}
+
+impl Hinter for InputHelper {
+    type Hint = InputHint;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<InputHint> {
+        if pos < line.len() {
+            return None;
+        }
+        self.suggestions().hint(line)
+    }
+}
+
+impl Highlighter for InputHelper {
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        if self.dim_hints {
+            Cow::Owned(format!("\x1B[2m{hint}\x1B[0m"))
+        } else {
+            Cow::Borrowed(hint)
+        }
+    }
+}
+
+impl Validator for InputHelper {}
+
+impl Helper for InputHelper {}
+
+/// Line editor with history, tab completion, and inline hints
+pub struct LineEditor {
+    editor: Editor<InputHelper, DefaultHistory>,
+}
+
+impl LineEditor {
+    pub fn new(suggestions: SharedSuggestions) -> Result<Self> {
+        let mut editor = Editor::new().context("Failed to initialize line editor")?;
+        editor.set_helper(Some(InputHelper {
+            suggestions,
+            dim_hints: ColorSupport::detect() != ColorSupport::None,
+        }));
+        Ok(Self { editor })
+    }
+
+    /// Read one line, or `None` once the user presses Ctrl-D or Ctrl-C
+    pub fn read_line(&mut self, prompt: &str) -> Result<Option<String>> {
+        match self.editor.readline(prompt) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    let _ = self.editor.add_history_entry(line.as_str());
+                }
+                Ok(Some(line))
+            }
+            Err(ReadlineError::Eof | ReadlineError::Interrupted) => Ok(None),
+            Err(e) => Err(e).context("Failed to read input"),
+        }
+    }
+
+    /// Printer that writes above the prompt while a line is being edited
+    ///
+    /// Returns `None` when stdin is not a terminal.
+    pub fn printer(&mut self) -> Option<Box<dyn ExternalPrinter + Send>> {
+        self.editor
+            .create_external_printer()
+            .ok()
+            .map(|printer| Box::new(printer) as Box<dyn ExternalPrinter + Send>)
+    }
+}
@@ -0,0 +1,167 @@
+use crate::api::PgnFeedServer;
+use crate::cli::app::{App, LivePgnSettings};
+use crate::cli::pgn::export_game_pgn;
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// A game's PGN, rewritten wherever it is streamed to whenever it changes
+///
+/// The file is replaced in one step, so a tool polling it never reads a
+/// half-written game.
+pub struct LivePgn {
+    database: Arc<Database>,
+    game_id: String,
+    my_peer_id: String,
+    path: Option<PathBuf>,
+    feed: Option<watch::Sender<String>>,
+    last: Option<String>,
+}
+
+impl LivePgn {
+    /// Stream `game_id` as seen by `my_peer_id`, to nowhere yet
+    pub fn new(database: Arc<Database>, game_id: &str, my_peer_id: &str) -> Self {
+        Self {
+            database,
+            game_id: game_id.to_string(),
+            my_peer_id: my_peer_id.to_string(),
+            path: None,
+            feed: None,
+            last: None,
+        }
+    }
+
+    /// Also keep `path` rewritten with the PGN
+    pub fn with_file(mut self, path: PathBuf) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Also send the PGN on `feed`, e.g. to a [`PgnFeedServer`]
+    pub fn with_feed(mut self, feed: watch::Sender<String>) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    /// Export the game again, passing it on if it changed since the last
+    /// update; returns whether it did
+    pub fn update(&mut self) -> Result<bool> {
+        let pgn = export_game_pgn(&self.database, &self.game_id, &self.my_peer_id)?;
+        if self.last.as_ref() == Some(&pgn) {
+            return Ok(false);
+        }
+
+        if let Some(path) = &self.path {
+            write_replacing(path, &pgn)?;
+        }
+        if let Some(feed) = &self.feed {
+            feed.send_replace(pgn.clone());
+        }
+        self.last = Some(pgn);
+        Ok(true)
+    }
+}
+
+/// Write `contents` to a sibling temporary file and rename it over `path`
+fn write_replacing(path: &Path, contents: &str) -> Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    std::fs::write(&temp, contents)
+        .with_context(|| format!("Failed to write {}", temp.display()))?;
+    std::fs::rename(&temp, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Background task streaming a game's PGN, stopped when dropped
+pub struct LivePgnTask {
+    updates: JoinHandle<()>,
+    feed: Option<JoinHandle<Result<()>>>,
+    http_addr: Option<SocketAddr>,
+}
+
+impl LivePgnTask {
+    /// Where the HTTP endpoint is listening, if one was started
+    pub fn http_addr(&self) -> Option<SocketAddr> {
+        self.http_addr
+    }
+}
+
+impl Drop for LivePgnTask {
+    fn drop(&mut self) {
+        self.updates.abort();
+        if let Some(feed) = &self.feed {
+            feed.abort();
+        }
+    }
+}
+
+/// Start streaming `game_id` as `settings` ask, or return `None` if they
+/// send it nowhere
+///
+/// The PGN is written once before returning, then checked for new moves
+/// every `settings.update_secs`.
+pub async fn start(
+    database: Arc<Database>,
+    game_id: &str,
+    my_peer_id: &str,
+    settings: &LivePgnSettings,
+) -> Result<Option<LivePgnTask>> {
+    if !settings.is_enabled() {
+        return Ok(None);
+    }
+
+    let mut live = LivePgn::new(database, game_id, my_peer_id);
+    if let Some(path) = &settings.path {
+        live = live.with_file(path.clone());
+    }
+    let mut feed = None;
+    let mut http_addr = None;
+    if let Some(addr) = &settings.http_addr {
+        let (sender, receiver) = watch::channel(String::new());
+        let server = PgnFeedServer::bind(addr, receiver).await?;
+        http_addr = Some(server.local_addr()?);
+        feed = Some(tokio::spawn(server.run()));
+        live = live.with_feed(sender);
+    }
+    live.update().context("Failed to write the live PGN")?;
+
+    let interval = settings.interval();
+    let updates = tokio::spawn(async move {
+        let mut poll = tokio::time::interval(interval);
+        loop {
+            poll.tick().await;
+            match live.update() {
+                Ok(true) => debug!("Live PGN updated"),
+                Ok(false) => {}
+                Err(e) => warn!("Failed to update the live PGN: {e:#}"),
+            }
+        }
+    });
+
+    Ok(Some(LivePgnTask {
+        updates,
+        feed,
+        http_addr,
+    }))
+}
+
+/// Start streaming a game played or followed in `app`, telling the user
+/// where it goes
+pub async fn start_for(app: &App, game_id: &str) -> Result<Option<LivePgnTask>> {
+    let settings = &app.config.live_pgn;
+    let task = start(Arc::clone(&app.database), game_id, app.peer_id(), settings).await?;
+    if task.is_some() {
+        if let Some(path) = &settings.path {
+            println!("Live PGN: {}", path.display());
+        }
+    }
+    if let Some(addr) = task.as_ref().and_then(LivePgnTask::http_addr) {
+        println!("Live PGN: http://{addr}/game.pgn");
+    }
+    Ok(task)
+}
@@ -0,0 +1,319 @@
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::FormatTime;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Size at which a log file is rotated by default
+pub const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Number of rotated log files kept next to the current one (`mate.log.1` ...)
+pub const ROTATED_LOG_FILES: usize = 3;
+
+/// Filter directive for the log file, which always records full debug output
+pub const LOG_FILE_DIRECTIVE: &str = "mate=debug";
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, with the fields of enclosing spans such as
+    /// `peer_id`, `game_id`, and `connection_id` alongside the event's own
+    Json,
+}
+
+impl LogFormat {
+    /// Names accepted by `--log-format`
+    pub const NAMES: [&'static str; 2] = ["text", "json"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(LogFormat::Text),
+            "json" => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// How often the log file is started afresh, whatever its size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogRotation {
+    /// Only when the file reaches its size limit
+    #[default]
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl LogRotation {
+    /// Names accepted by `--log-rotate`
+    pub const NAMES: [&'static str; 3] = ["never", "hourly", "daily"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "never" => Some(LogRotation::Never),
+            "hourly" => Some(LogRotation::Hourly),
+            "daily" => Some(LogRotation::Daily),
+            _ => None,
+        }
+    }
+
+    /// Length of one period, if rotation is time-based
+    pub fn period(&self) -> Option<Duration> {
+        match self {
+            LogRotation::Never => None,
+            LogRotation::Hourly => Some(Duration::from_secs(60 * 60)),
+            LogRotation::Daily => Some(Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+}
+
+/// Console filter directive for the `-q`/`-v` flags
+///
+/// `-q` shows only errors, the default shows info, `-v` adds debug output
+/// and `-vv` (or more) traces everything.
+pub fn console_directive(quiet: bool, verbose: u8) -> &'static str {
+    match (quiet, verbose) {
+        (true, _) => "mate=error",
+        (false, 0) => "mate=info",
+        (false, 1) => "mate=debug",
+        (false, _) => "mate=trace",
+    }
+}
+
+/// Open a log file for appending, rotating it first if it has grown too large
+pub fn open_log_file(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    rotate_log_file(path, MAX_LOG_FILE_BYTES, ROTATED_LOG_FILES)?;
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Shift `path` to `path.1`, `path.1` to `path.2`, and so on once `path`
+/// reaches `max_bytes`, dropping the oldest beyond `keep`
+pub fn rotate_log_file(path: &Path, max_bytes: u64, keep: usize) -> io::Result<()> {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.len() >= max_bytes => {}
+        Ok(_) => return Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    }
+
+    if keep == 0 {
+        return fs::remove_file(path);
+    }
+
+    for index in (1..keep).rev() {
+        let from = rotated_path(path, index);
+        if from.exists() {
+            fs::rename(&from, rotated_path(path, index + 1))?;
+        }
+    }
+    fs::rename(path, rotated_path(path, 1))
+}
+
+/// Path of the `index`th rotated copy of a log file
+pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+/// A log file that rotates itself while it is written, once it reaches
+/// `max_bytes` or a new rotation period (UTC hour or day) begins
+///
+/// Rotated copies are shifted as by [`rotate_log_file`].
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    rotation: LogRotation,
+    /// Rotation period the current file belongs to
+    period: Option<u64>,
+}
+
+impl RotatingFile {
+    /// Open `path` for appending, rotating it first if it is already too
+    /// large or was last written in an earlier period
+    pub fn open(path: &Path, max_bytes: u64, rotation: LogRotation) -> io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let now = period_of(rotation, SystemTime::now());
+        let stale = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| period_of(rotation, modified) != now);
+        rotate_log_file(path, if stale { 0 } else { max_bytes }, ROTATED_LOG_FILES)?;
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            written: file.metadata()?.len(),
+            file,
+            max_bytes,
+            rotation,
+            period: now,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        rotate_log_file(&self.path, 0, ROTATED_LOG_FILES)?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let period = period_of(self.rotation, SystemTime::now());
+        let full = self.written > 0 && self.written + buf.len() as u64 > self.max_bytes;
+        if full || period != self.period {
+            self.rotate()?;
+            self.period = period;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Index of the rotation period containing `time`
+fn period_of(rotation: LogRotation, time: SystemTime) -> Option<u64> {
+    let period = rotation.period()?;
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Some(since_epoch.as_secs() / period.as_secs())
+}
+
+/// Event format for `--log-format json`
+///
+/// Each event is one JSON object with `timestamp`, `level`, `target`,
+/// `message`, and `spans` (outermost first). The fields of enclosing spans
+/// and of the event itself are flattened into the object, innermost winning,
+/// so collectors can index `peer_id`, `game_id`, and `connection_id`
+/// directly. Spans must be recorded with [`JsonFields`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut timestamp = String::new();
+        tracing_subscriber::fmt::time::SystemTime.format_time(&mut Writer::new(&mut timestamp))?;
+
+        let metadata = event.metadata();
+        let mut object = Map::new();
+        object.insert("timestamp".to_string(), Value::String(timestamp));
+        object.insert("level".to_string(), metadata.level().as_str().into());
+        object.insert("target".to_string(), metadata.target().into());
+
+        let mut spans = Vec::new();
+        for span in ctx
+            .event_scope()
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+        {
+            spans.push(Value::from(span.name()));
+            if let Some(fields) = span.extensions().get::<FormattedFields<N>>() {
+                if let Ok(Value::Object(fields)) = serde_json::from_str(fields.as_str()) {
+                    object.extend(fields);
+                }
+            }
+        }
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+        object.extend(visitor.0);
+        object.insert("spans".to_string(), Value::Array(spans));
+
+        let line = serde_json::to_string(&object).map_err(|_| fmt::Error)?;
+        writeln!(writer, "{line}")
+    }
+}
+
+/// Records span fields as a JSON object, for [`JsonFormat`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor::default();
+        fields.record(&mut visitor);
+        let object = serde_json::to_string(&visitor.0).map_err(|_| fmt::Error)?;
+        writer.write_str(&object)
+    }
+
+    fn add_fields(
+        &self,
+        current: &mut FormattedFields<Self>,
+        fields: &tracing::span::Record<'_>,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor(serde_json::from_str(current.as_str()).unwrap_or_default());
+        fields.record(&mut visitor);
+        current.fields = serde_json::to_string(&visitor.0).map_err(|_| fmt::Error)?;
+        Ok(())
+    }
+}
+
+/// Collects fields into a JSON object
+#[derive(Default)]
+pub(crate) struct JsonVisitor(pub(crate) Map<String, Value>);
+
+impl Visit for JsonVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}").into());
+    }
+}
@@ -1,13 +1,56 @@
+pub mod account;
 pub mod app;
+pub mod auto_accept;
+pub mod certificate;
+pub mod clipboard;
 pub mod commands;
+pub mod crash;
+pub mod csv;
+pub mod data_export;
+pub mod debug;
+pub mod devices;
 pub mod display;
+pub mod doctor;
 pub mod error_handler;
+pub mod events;
+pub mod follow;
 pub mod game_ops;
+pub mod game_stats;
+pub mod hotseat;
+pub mod i18n;
+pub mod inbox;
+pub mod keys;
+pub mod line_editor;
+pub mod live_pgn;
+pub mod logging;
 pub mod network_manager;
+pub mod openings;
+pub mod pgn;
+pub mod play;
+pub mod progress;
+pub mod reminders;
+pub mod replay;
+pub mod selfplay;
+pub mod serve;
+pub mod session;
+pub mod setup;
+pub mod stress;
+pub mod study;
+pub mod telemetry;
+pub mod timeline;
+pub mod top;
+pub mod tui;
+pub mod uci;
+pub mod usage;
 pub mod validation;
+pub mod voting;
 
 pub use app::{App, Config};
-pub use commands::{Cli, Commands, KeyCommand};
+pub use commands::{
+    ArbiterCommand, CertificateCommand, Cli, Commands, ConfigCommand, DebugCommand, DevicesCommand,
+    KeyCommand, LichessCommand, LivePgnOptions, NetworkOptions, PeersCommand, ServerCommand,
+    TelemetryCommand,
+};
 pub use display::{
     display_board, display_board_ascii, display_board_unicode, display_game_status,
     display_games_list, display_move_history, get_display_preference, supports_unicode,
@@ -15,10 +58,12 @@ pub use display::{
 pub use error_handler::{
     create_input_validation_error, create_network_timeout_error, display_error,
     display_error_and_exit, handle_chess_command_error, is_recoverable_error, CliError, CliResult,
+    ErrorCode,
 };
 pub use game_ops::{
     GameOps, GameOpsError, GameOpsResult, GameRecord, GameState, GameStatistics, InvitationRecord,
     MoveHistoryEntry, MoveProcessingError, MoveProcessingResult, MoveProcessor, MoveResult,
 };
 pub use network_manager::{NetworkConfig, NetworkManager, NetworkStats};
+pub use pgn::export_game_pgn;
 pub use validation::{InputValidationUtils, InputValidator, ValidationError, ValidationResult};
@@ -1,14 +1,27 @@
-use crate::crypto::Identity;
-use crate::messages::chess::{GameAccept, GameInvite, Move as ChessMove};
+use crate::cli::events::GameEvent;
+use crate::cli::progress::{ProgressCallback, ProgressEvent};
+use crate::crypto::{
+    ArbiterRuling, Identity, ResultSignature, RevocationRecord, RotationCertificate, SealedMove,
+};
+use crate::messages::chess::{
+    AbuseReport, ArbiterUpdate, DeviceSync, GameAccept, GameInvite, Move as ChessMove,
+    PresenceStatus, Study,
+};
 use crate::messages::types::Message;
-use crate::messages::{FailureClass, RetryStrategy};
-use crate::network::{Client, Connection};
+use crate::messages::{FailureClass, RetryConfig, RetryStrategy};
+use crate::network::known_peers::{check_peer_key, KeyCheck};
+use crate::network::{Client, Connection, ConnectionStats, StatsRegistry, WireConfig};
+use crate::storage::Database;
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
-use tracing::{debug, error, info, warn};
+use tokio::sync::{broadcast, Mutex};
+use tracing::{debug, error, info, instrument, warn};
+
+/// Connection losses kept for subscribers that fall behind
+const EVENT_CAPACITY: usize = 64;
 
 /// Configuration for network operations
 #[derive(Debug, Clone)]
@@ -21,6 +34,16 @@ pub struct NetworkConfig {
     pub max_persistent_connections: usize,
     /// How long to keep connections alive when idle
     pub connection_keepalive: Duration,
+    /// Message size limit and read/write timeouts for connections
+    pub wire_config: WireConfig,
+    /// Attempts and backoff to use for every operation instead of the
+    /// per-operation [`RetryStrategy`]
+    pub retry_config: Option<RetryConfig>,
+    /// File pinning the key first seen at each peer address; keys are not
+    /// checked when unset
+    pub known_peers: Option<PathBuf>,
+    /// Replace a pinned key that a peer no longer presents instead of failing
+    pub accept_changed_keys: bool,
 }
 
 impl Default for NetworkConfig {
@@ -30,10 +53,39 @@ impl Default for NetworkConfig {
             connection_timeout: Duration::from_secs(10),
             max_persistent_connections: 10,
             connection_keepalive: Duration::from_secs(300), // 5 minutes
+            wire_config: WireConfig::for_client(),
+            retry_config: None,
+            known_peers: None,
+            accept_changed_keys: false,
         }
     }
 }
 
+impl NetworkConfig {
+    /// Give up on connecting, sending, or waiting for a reply after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = timeout;
+        self.wire_config.read_timeout = timeout;
+        self.wire_config.write_timeout = timeout;
+        self
+    }
+
+    /// Pin peer keys in the file at `path` on first use and check them after
+    pub fn with_known_peers(mut self, path: PathBuf) -> Self {
+        self.known_peers = Some(path);
+        self
+    }
+
+    /// Retry failed operations `retries` times, whatever the operation
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retry_config = Some(RetryConfig {
+            max_attempts: retries.saturating_add(1),
+            ..RetryConfig::default()
+        });
+        self
+    }
+}
+
 /// Connection state tracking
 #[derive(Debug, Clone)]
 struct ConnectionInfo {
@@ -55,6 +107,16 @@ pub struct NetworkManager {
     connections: Arc<Mutex<HashMap<String, (Connection, ConnectionInfo)>>>,
     /// Pending messages for offline peers
     pending_messages: Arc<Mutex<HashMap<String, Vec<PendingMessage>>>>,
+    /// Where connection attempts and queue flushes report their progress
+    progress: Option<ProgressCallback>,
+    /// Session of the latest connection to each peer, by peer ID
+    sessions: Arc<Mutex<HashMap<String, u64>>>,
+    /// Where connection losses are announced to subscribers
+    events: broadcast::Sender<GameEvent>,
+    /// Traffic of the connections made to deliver messages
+    stats: StatsRegistry,
+    /// Where server announcements are kept for other frontends to show
+    announcement_log: Option<Arc<Database>>,
 }
 
 /// A message waiting to be sent when peer comes online
@@ -79,20 +141,61 @@ impl NetworkManager {
             config: NetworkConfig::default(),
             connections: Arc::new(Mutex::new(HashMap::new())),
             pending_messages: Arc::new(Mutex::new(HashMap::new())),
+            progress: None,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            stats: StatsRegistry::new(),
+            announcement_log: None,
         }
     }
 
     /// Create a network manager with custom configuration
     pub fn with_config(identity: Arc<Identity>, config: NetworkConfig) -> Self {
-        let client = Client::new(identity);
+        let client = Client::new_with_config(identity, config.wire_config.clone());
         Self {
             client,
             config,
             connections: Arc::new(Mutex::new(HashMap::new())),
             pending_messages: Arc::new(Mutex::new(HashMap::new())),
+            progress: None,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            stats: StatsRegistry::new(),
+            announcement_log: None,
         }
     }
 
+    /// Receive a [`GameEvent::ConnectionLost`] whenever a message cannot be
+    /// delivered to a peer, and a [`GameEvent::ServerAnnouncement`] for each
+    /// announcement that arrives while waiting for a reply
+    pub fn subscribe_events(&self) -> broadcast::Receiver<GameEvent> {
+        self.events.subscribe()
+    }
+
+    /// Report progress of connection attempts and queue flushes to `progress`
+    pub fn set_progress(&mut self, progress: Option<ProgressCallback>) {
+        self.progress = progress;
+    }
+
+    /// Keep server announcements in `database`, where 'mate tui' shows them
+    pub fn set_announcement_log(&mut self, database: Option<Arc<Database>>) {
+        self.announcement_log = database;
+    }
+
+    /// Peers reached by this manager with the session of the latest
+    /// connection to each, sorted by peer ID
+    pub async fn peer_sessions(&self) -> Vec<(String, u64)> {
+        let mut sessions: Vec<_> = self
+            .sessions
+            .lock()
+            .await
+            .iter()
+            .map(|(peer_id, session)| (peer_id.clone(), *session))
+            .collect();
+        sessions.sort();
+        sessions
+    }
+
     /// Send a game invitation with retry logic
     pub async fn send_game_invite(
         &self,
@@ -100,7 +203,8 @@ impl NetworkManager {
         game_id: String,
         invite: GameInvite,
     ) -> Result<Message> {
-        let message = Message::new_game_invite(game_id.clone(), invite.suggested_color);
+        // Sent as given so any odds travel with it
+        let message = Message::GameInvite(invite);
 
         match self
             .send_message_with_retry(peer_address, message.clone(), &game_id)
@@ -124,6 +228,44 @@ impl NetworkManager {
         }
     }
 
+    /// Take back an invitation that has not been answered
+    ///
+    /// An invitation still queued for the peer is dropped first. The
+    /// withdrawal itself is not queued if the peer is unreachable, since the
+    /// invitation lapses on its own.
+    pub async fn send_invite_withdraw(
+        &self,
+        peer_address: &str,
+        game_id: String,
+    ) -> Result<Message> {
+        {
+            let mut pending = self.pending_messages.lock().await;
+            if let Some(messages) = pending.get_mut(peer_address) {
+                messages.retain(|msg| {
+                    !(msg.game_id == game_id && matches!(msg.message, Message::GameInvite(_)))
+                });
+            }
+        }
+
+        let message = Message::new_invite_withdraw(game_id.clone());
+        match self
+            .send_message_with_retry(peer_address, message, &game_id)
+            .await
+        {
+            Ok(response) => {
+                info!(
+                    "Invitation withdrawal sent successfully to {}",
+                    peer_address
+                );
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to withdraw invitation from {}: {}", peer_address, e);
+                Err(e)
+            }
+        }
+    }
+
     /// Send a game acceptance with retry logic
     pub async fn send_game_accept(
         &self,
@@ -158,11 +300,8 @@ impl NetworkManager {
         game_id: String,
         chess_move: ChessMove,
     ) -> Result<Message> {
-        let message = Message::new_move(
-            game_id.clone(),
-            chess_move.chess_move.clone(),
-            chess_move.board_state_hash.clone(),
-        );
+        // Sent as given so the move's signature travels with it
+        let message = Message::Move(chess_move);
 
         match self
             .send_message_with_retry(peer_address, message.clone(), &game_id)
@@ -182,6 +321,429 @@ impl NetworkManager {
         }
     }
 
+    /// Send the commitment to a move sealed on adjourning a game
+    ///
+    /// The commitment is not queued: a game only counts as adjourned once
+    /// the opponent has it.
+    pub async fn send_move_seal(&self, peer_address: &str, sealed: &SealedMove) -> Result<Message> {
+        let message = Message::new_move_seal(sealed);
+        match self
+            .send_message_with_retry(peer_address, message, &sealed.game_id)
+            .await
+        {
+            Ok(response) => {
+                info!("Sealed move sent successfully to {}", peer_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to send sealed move to {}: {}", peer_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Reveal a sealed move when an adjourned game resumes
+    pub async fn send_seal_reveal(
+        &self,
+        peer_address: &str,
+        sealed: SealedMove,
+    ) -> Result<Message> {
+        let game_id = sealed.game_id.clone();
+        let message = Message::new_seal_reveal(sealed);
+        match self
+            .send_message_with_retry(peer_address, message, &game_id)
+            .await
+        {
+            Ok(response) => {
+                info!("Sealed move revealed to {}", peer_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to reveal sealed move to {}: {}", peer_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Tell an opponent whether we are available to play
+    ///
+    /// Presence is not queued: by the time the opponent comes online it
+    /// would be out of date.
+    pub async fn send_presence(
+        &self,
+        peer_address: &str,
+        status: PresenceStatus,
+    ) -> Result<Message> {
+        let message = Message::new_presence(status);
+        match self
+            .send_message_with_retry(peer_address, message, "")
+            .await
+        {
+            Ok(response) => {
+                info!("Presence ({}) sent to {}", status, peer_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to send presence to {}: {}", peer_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send an abuse report to the operator of a lobby or relay
+    ///
+    /// Reports are not queued, so the user learns right away whether the
+    /// operator has it.
+    pub async fn send_abuse_report(
+        &self,
+        operator_address: &str,
+        report: AbuseReport,
+    ) -> Result<Message> {
+        let message = Message::AbuseReport(report);
+        match self
+            .send_message_with_retry(operator_address, message, "")
+            .await
+        {
+            Ok(response) => {
+                info!("Abuse report sent to {}", operator_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to send abuse report to {}: {}", operator_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Offer a pairing code to another of our devices
+    ///
+    /// Returns the peer ID of the device, which echoes the request once it
+    /// has accepted the code. `own_address` is where the device can reach
+    /// this one to sync.
+    pub async fn send_device_pair(
+        &self,
+        device_address: &str,
+        code: &str,
+        own_address: &str,
+    ) -> Result<String> {
+        let message = Message::new_device_pair(code.to_string(), own_address.to_string());
+        match self.send_message_with_sender(device_address, message).await {
+            Ok((Message::DevicePair(_), device)) => {
+                info!("Paired with device {} at {}", device, device_address);
+                Ok(device)
+            }
+            Ok((response, _)) => Err(anyhow::anyhow!(
+                "Unexpected reply to pairing request: {}",
+                response.message_type()
+            )),
+            Err(e) => {
+                warn!("Failed to pair with device at {}: {}", device_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Exchange synced changes with a linked device
+    ///
+    /// Returns the device's changes with the peer ID that sent them, which
+    /// the caller checks is the device it meant to reach.
+    pub async fn send_device_sync(
+        &self,
+        device_address: &str,
+        sync: DeviceSync,
+    ) -> Result<(DeviceSync, String)> {
+        let sent = sync.changes.len();
+        match self
+            .send_message_with_sender(device_address, Message::DeviceSync(sync))
+            .await
+        {
+            Ok((Message::DeviceSync(reply), device)) => {
+                info!(
+                    "Synced with device {}: sent {} changes, received {}",
+                    device,
+                    sent,
+                    reply.changes.len()
+                );
+                Ok((reply, device))
+            }
+            Ok((response, _)) => Err(anyhow::anyhow!(
+                "Unexpected reply to device sync: {}",
+                response.message_type()
+            )),
+            Err(e) => {
+                warn!("Failed to sync with device at {}: {}", device_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send a resignation with retry logic
+    pub async fn send_resign(&self, peer_address: &str, game_id: String) -> Result<Message> {
+        let message = Message::new_resign(game_id.clone());
+        self.send_game_end_message(peer_address, message, game_id, "resignation")
+            .await
+    }
+
+    /// Send a draw offer with retry logic
+    pub async fn send_draw_offer(&self, peer_address: &str, game_id: String) -> Result<Message> {
+        let message = Message::new_draw_offer(game_id.clone());
+        self.send_game_end_message(peer_address, message, game_id, "draw offer")
+            .await
+    }
+
+    /// Send a draw acceptance with retry logic
+    pub async fn send_draw_accept(&self, peer_address: &str, game_id: String) -> Result<Message> {
+        let message = Message::new_draw_accept(game_id.clone());
+        self.send_game_end_message(peer_address, message, game_id, "draw acceptance")
+            .await
+    }
+
+    /// Send our signature over a finished game's result with retry logic
+    pub async fn send_result_signature(
+        &self,
+        peer_address: &str,
+        signature: ResultSignature,
+    ) -> Result<Message> {
+        let game_id = signature.game_id.clone();
+        let message = Message::new_result_signature(signature);
+        self.send_game_end_message(peer_address, message, game_id, "result signature")
+            .await
+    }
+
+    /// Announce a key rotation to a peer
+    ///
+    /// The certificate is not queued if the peer is unreachable; the caller
+    /// decides whether to announce it again later.
+    pub async fn send_key_rotation(
+        &self,
+        peer_address: &str,
+        certificate: RotationCertificate,
+    ) -> Result<Message> {
+        let message = Message::new_key_rotation(certificate);
+        match self
+            .send_message_with_retry(peer_address, message, "")
+            .await
+        {
+            Ok(response) => {
+                info!("Key rotation announced to {}", peer_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to announce key rotation to {}: {}", peer_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Announce to a peer that our key is revoked
+    ///
+    /// Like rotations, revocations are not queued for unreachable peers.
+    pub async fn send_key_revocation(
+        &self,
+        peer_address: &str,
+        record: RevocationRecord,
+    ) -> Result<Message> {
+        let message = Message::new_key_revocation(record);
+        match self
+            .send_message_with_retry(peer_address, message, "")
+            .await
+        {
+            Ok(response) => {
+                info!("Key revocation announced to {}", peer_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to announce key revocation to {}: {}",
+                    peer_address, e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Share a study with a peer
+    ///
+    /// Studies are not part of any game and are not queued; the peer must be
+    /// reachable.
+    pub async fn send_study(&self, peer_address: &str, study: Study) -> Result<Message> {
+        let message = Message::new_study(study);
+        match self
+            .send_message_with_retry(peer_address, message, "")
+            .await
+        {
+            Ok(response) => {
+                info!("Study sent to {}", peer_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to send study to {}: {}", peer_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Register with the host of a consultation game as one of its voters
+    pub async fn send_voter_join(&self, host_address: &str, game_id: String) -> Result<Message> {
+        let message = Message::new_voter_join(game_id.clone());
+        match self
+            .send_message_with_retry(host_address, message, &game_id)
+            .await
+        {
+            Ok(response) => {
+                info!("Joined the vote in game {} at {}", game_id, host_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to join the vote at {}: {}", host_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send a vote for the next move to the host of a consultation game
+    ///
+    /// Votes are not queued: one arriving after the vote closes would count
+    /// towards a later move.
+    pub async fn send_vote(
+        &self,
+        host_address: &str,
+        game_id: String,
+        chess_move: String,
+    ) -> Result<Message> {
+        let message = Message::new_vote(game_id.clone(), chess_move);
+        match self
+            .send_message_with_retry(host_address, message, &game_id)
+            .await
+        {
+            Ok(response) => {
+                info!("Vote in game {} sent to {}", game_id, host_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to send vote to {}: {}", host_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Join a game as its arbiter through one of its players
+    ///
+    /// Returns the game so far with the peer ID of the player who sent it.
+    /// `own_address` is where the player can reach us with later moves.
+    pub async fn send_arbiter_join(
+        &self,
+        host_address: &str,
+        game_id: String,
+        own_address: &str,
+    ) -> Result<(ArbiterUpdate, String)> {
+        let message = Message::new_arbiter_join(game_id.clone(), own_address.to_string());
+        match self.send_message_with_sender(host_address, message).await {
+            Ok((Message::ArbiterUpdate(update), player)) => {
+                info!("Joined game {} as arbiter through {}", game_id, player);
+                Ok((update, player))
+            }
+            Ok((response, _)) => Err(anyhow::anyhow!(
+                "Unexpected reply to arbiter join: {}",
+                response.message_type()
+            )),
+            Err(e) => {
+                warn!("Failed to join game {} at {}: {}", game_id, host_address, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Report a game's moves and result to its arbiter
+    ///
+    /// The arbiter answers a finished game with its `ResultSignature` and
+    /// echoes any other update. Updates are not queued; the next one carries
+    /// every move anyway.
+    pub async fn send_arbiter_update(
+        &self,
+        arbiter_address: &str,
+        update: ArbiterUpdate,
+    ) -> Result<Message> {
+        let game_id = update.game_id.clone();
+        let message = Message::ArbiterUpdate(update);
+        match self
+            .send_message_with_retry(arbiter_address, message, &game_id)
+            .await
+        {
+            Ok(response) => {
+                debug!(
+                    "Game {} reported to arbiter at {}",
+                    game_id, arbiter_address
+                );
+                Ok(response)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to report game {} to arbiter at {}: {}",
+                    game_id, arbiter_address, e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Claim that a move was illegal and wait for the arbiter's ruling
+    pub async fn send_illegal_move_claim(
+        &self,
+        arbiter_address: &str,
+        game_id: String,
+        ply: u32,
+        reason: Option<String>,
+    ) -> Result<ArbiterRuling> {
+        let message = Message::new_illegal_move_claim(game_id.clone(), ply, reason);
+        match self
+            .send_message_with_retry(arbiter_address, message, &game_id)
+            .await
+        {
+            Ok(Message::ArbiterRuling(ruling)) => {
+                info!("Arbiter ruled on move {} of game {}", ply, game_id);
+                Ok(ruling)
+            }
+            Ok(response) => Err(anyhow::anyhow!(
+                "Unexpected reply to illegal move claim: {}",
+                response.message_type()
+            )),
+            Err(e) => {
+                warn!(
+                    "Failed to send claim to arbiter at {}: {}",
+                    arbiter_address, e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Send a resign or draw message, queueing it if the peer is unreachable
+    async fn send_game_end_message(
+        &self,
+        peer_address: &str,
+        message: Message,
+        game_id: String,
+        description: &str,
+    ) -> Result<Message> {
+        match self
+            .send_message_with_retry(peer_address, message.clone(), &game_id)
+            .await
+        {
+            Ok(response) => {
+                info!("{} sent successfully to {}", description, peer_address);
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to send {} to {}: {}", description, peer_address, e);
+                // Store as pending message for when peer comes online
+                self.store_pending_message(peer_address, message, game_id)
+                    .await?;
+                Err(e)
+            }
+        }
+    }
+
     /// Send a message with retry logic and connection management
     async fn send_message_with_retry(
         &self,
@@ -198,40 +760,160 @@ impl NetworkManager {
     }
 
     /// Send a message with a specific retry strategy
+    #[instrument(level = "debug", skip_all, fields(peer_addr = peer_address, game_id = game_id, msg_type = message.message_type()))]
     async fn send_message_with_strategy(
         &self,
         peer_address: &str,
         message: Message,
-        _game_id: &str,
+        game_id: &str,
         strategy: RetryStrategy,
     ) -> Result<Message> {
-        let max_attempts = strategy.max_attempts();
-        let base_delay = strategy.base_delay();
+        let result = self
+            .deliver(peer_address, message, strategy, self.progress.as_ref())
+            .await;
+        self.report(ProgressEvent::Finished);
+        result.map(|(response, _sender)| response)
+    }
+
+    /// Send a message and return the reply with the peer ID that sent it
+    async fn send_message_with_sender(
+        &self,
+        peer_address: &str,
+        message: Message,
+    ) -> Result<(Message, String)> {
+        let operation = self.classify_operation(&message);
+        let strategy = RetryStrategy::for_cli_operation(&operation);
+        let result = self
+            .deliver(peer_address, message, strategy, self.progress.as_ref())
+            .await;
+        self.report(ProgressEvent::Finished);
+        result
+    }
+
+    /// Send a message and wait for the reply, retrying as `strategy` allows
+    ///
+    /// Returns the reply with the peer ID that sent it. Each attempt is
+    /// reported to `progress`; the caller reports when the operation as a
+    /// whole is finished. Subscribers hear of the failure when no attempt
+    /// succeeds.
+    async fn deliver(
+        &self,
+        peer_address: &str,
+        message: Message,
+        strategy: RetryStrategy,
+        progress: Option<&ProgressCallback>,
+    ) -> Result<(Message, String)> {
+        let result = self
+            .attempt_delivery(peer_address, message, strategy, progress)
+            .await;
+        if let Err(e) = &result {
+            // Nobody may be listening
+            let _ = self.events.send(GameEvent::ConnectionLost {
+                peer_address: peer_address.to_string(),
+                error: format!("{e:#}"),
+            });
+        }
+        result
+    }
+
+    async fn attempt_delivery(
+        &self,
+        peer_address: &str,
+        message: Message,
+        strategy: RetryStrategy,
+        progress: Option<&ProgressCallback>,
+    ) -> Result<(Message, String)> {
+        let report = |event: ProgressEvent| {
+            if let Some(progress) = progress {
+                progress(&event);
+            }
+        };
+        // A configured retry policy replaces both this loop's attempts and
+        // the client's own reconnects, so attempts are not multiplied
+        let (max_attempts, connect_strategy) = match &self.config.retry_config {
+            Some(retry_config) => (retry_config.max_attempts.max(1), RetryStrategy::NoRetry),
+            None => (strategy.max_attempts(), strategy),
+        };
         let mut last_error = None;
 
+        // A connection kept from an earlier message to this peer is tried
+        // first, so messages it set aside for other games reach them; if the
+        // peer has closed it since, the attempts below connect afresh
+        if let Some(mut connection) = self.take_connection(peer_address).await {
+            report(ProgressEvent::AwaitingReply {
+                address: peer_address.to_string(),
+            });
+            let received = match connection.send_message(message.clone()).await {
+                Ok(()) => connection.receive_reply_to(&message).await,
+                Err(e) => Err(e),
+            };
+            self.forward_announcements(&mut connection);
+            match received {
+                Ok(reply) => {
+                    self.keep_connection(peer_address, connection).await;
+                    return Ok(reply);
+                }
+                Err(e) => {
+                    debug!(
+                        "Kept connection to {} failed, reconnecting: {}",
+                        peer_address, e
+                    );
+                    self.stats
+                        .close(connection.connection_id(), &connection.stats());
+                }
+            }
+        }
+
         for attempt in 1..=max_attempts {
             debug!(
                 "Attempting to send message to {} (attempt {}/{}, strategy: {:?})",
                 peer_address, attempt, max_attempts, strategy
             );
+            if attempt > 1 {
+                self.stats.record_retries(1);
+            }
+
+            report(ProgressEvent::Connecting {
+                address: peer_address.to_string(),
+                attempt,
+                max_attempts,
+            });
 
             // Try to get or create a connection
             match self
-                .get_or_create_connection_with_strategy(peer_address, strategy)
+                .get_or_create_connection_with_strategy(peer_address, connect_strategy)
                 .await
             {
                 Ok(mut connection) => {
+                    // Nothing is sent to a peer presenting an unexpected key
+                    self.check_peer_key(peer_address, &connection)?;
+
                     // Send the message
                     match connection.send_message(message.clone()).await {
                         Ok(()) => {
+                            report(ProgressEvent::AwaitingReply {
+                                address: peer_address.to_string(),
+                            });
+
                             // Now receive the response
-                            match connection.receive_message().await {
-                                Ok((response, _sender)) => {
-                                    // Update connection as healthy
-                                    self.update_connection_health(peer_address, true).await;
-                                    return Ok(response);
+                            let received = connection.receive_reply_to(&message).await;
+                            self.forward_announcements(&mut connection);
+                            match received {
+                                Ok((response, sender)) => {
+                                    if let (Some(peer_id), Some(session)) =
+                                        (connection.peer_identity(), connection.session_id())
+                                    {
+                                        self.sessions
+                                            .lock()
+                                            .await
+                                            .insert(peer_id.to_string(), session);
+                                    }
+                                    self.keep_connection(peer_address, connection).await;
+                                    return Ok((response, sender));
                                 }
                                 Err(e) => {
+                                    self.stats
+                                        .close(connection.connection_id(), &connection.stats());
                                     error!(
                                         "Failed to receive response from {} (attempt {}): {}",
                                         peer_address, attempt, e
@@ -250,6 +932,8 @@ impl NetworkManager {
                             }
                         }
                         Err(e) => {
+                            self.stats
+                                .close(connection.connection_id(), &connection.stats());
                             error!(
                                 "Failed to send message to {} (attempt {}): {}",
                                 peer_address, attempt, e
@@ -281,9 +965,13 @@ impl NetworkManager {
             }
 
             // Wait before retrying (except on last attempt)
-            if attempt < max_attempts && base_delay > Duration::from_millis(0) {
-                let delay = self.calculate_retry_delay_for_strategy(attempt, strategy);
+            let delay = self.retry_delay(attempt, strategy);
+            if attempt < max_attempts && delay > Duration::from_millis(0) {
                 debug!("Waiting {}ms before retry", delay.as_millis());
+                report(ProgressEvent::Retrying {
+                    address: peer_address.to_string(),
+                    delay,
+                });
                 tokio::time::sleep(delay).await;
             }
         }
@@ -300,13 +988,90 @@ impl NetworkManager {
         Err(final_error)
     }
 
-    /// Get an existing healthy connection or create a new one with a specific retry strategy
+    /// Store and announce the server announcements `connection` received
+    fn forward_announcements(&self, connection: &mut Connection) {
+        for (announcement, sender) in connection.take_announcements() {
+            info!("Announcement from {}: {}", sender, announcement.text);
+            if let Some(database) = &self.announcement_log {
+                if let Err(e) = database.store_announcement(&sender, &announcement.text) {
+                    warn!("Failed to keep announcement: {}", e);
+                }
+            }
+            // Nobody may be listening
+            let _ = self.events.send(GameEvent::ServerAnnouncement {
+                from: sender,
+                text: announcement.text,
+            });
+        }
+    }
+
+    /// Take the connection kept for `peer_address`, unless it has been idle
+    /// longer than the keepalive
+    async fn take_connection(&self, peer_address: &str) -> Option<Connection> {
+        let (connection, info) = self.connections.lock().await.remove(peer_address)?;
+        if info.is_healthy && info.last_used.elapsed() < self.config.connection_keepalive {
+            debug!("Reusing connection to {}", peer_address);
+            return Some(connection);
+        }
+        self.stats
+            .close(connection.connection_id(), &connection.stats());
+        None
+    }
+
+    /// Keep `connection` for the next message to `peer_address`, while
+    /// fewer than the maximum number of connections are kept
+    async fn keep_connection(&self, peer_address: &str, connection: Connection) {
+        let mut connections = self.connections.lock().await;
+        if connections.len() >= self.config.max_persistent_connections
+            && !connections.contains_key(peer_address)
+        {
+            self.stats
+                .close(connection.connection_id(), &connection.stats());
+            return;
+        }
+        self.stats
+            .update(connection.connection_id(), &connection.stats());
+        let info = ConnectionInfo {
+            last_used: Instant::now(),
+            is_healthy: true,
+            failure_count: 0,
+        };
+        if let Some((replaced, _)) =
+            connections.insert(peer_address.to_string(), (connection, info))
+        {
+            self.stats
+                .close(replaced.connection_id(), &replaced.stats());
+        }
+    }
+
+    /// Check the key presented at `peer_address` against the pinned one,
+    /// pinning it if the address is new
+    fn check_peer_key(&self, peer_address: &str, connection: &Connection) -> Result<()> {
+        let (Some(path), Some(peer_id)) = (&self.config.known_peers, connection.peer_identity())
+        else {
+            return Ok(());
+        };
+
+        match check_peer_key(path, peer_address, peer_id, self.config.accept_changed_keys)? {
+            KeyCheck::Match => {}
+            KeyCheck::FirstUse => debug!("Pinned key {} for {}", peer_id, peer_address),
+            KeyCheck::Changed { pinned } => warn!(
+                "Accepted new key {} for {} in place of {}",
+                peer_id, peer_address, pinned
+            ),
+        }
+        Ok(())
+    }
+
+    /// Create a new connection with a specific retry strategy
+    ///
+    /// A connection kept from an earlier message is taken with
+    /// [`take_connection`](Self::take_connection) instead.
     async fn get_or_create_connection_with_strategy(
         &self,
         peer_address: &str,
         strategy: RetryStrategy,
     ) -> Result<Connection> {
-        // Create a new connection each time since we can't clone connections
         debug!(
             "Creating new connection to {} (strategy: {:?})",
             peer_address, strategy
@@ -395,15 +1160,20 @@ impl NetworkManager {
             messages_count, peer_address
         );
 
-        for mut pending_msg in messages {
+        let label = format!("Sending queued messages to {peer_address}");
+        for (done, mut pending_msg) in messages.into_iter().enumerate() {
             pending_msg.attempts += 1;
+            self.report(ProgressEvent::Advanced {
+                label: label.clone(),
+                done: done as u64,
+                total: messages_count as u64,
+            });
 
+            // Individual connection attempts are not shown, only the overall count
+            let operation = self.classify_operation(&pending_msg.message);
+            let strategy = RetryStrategy::for_cli_operation(&operation);
             match self
-                .send_message_with_retry(
-                    peer_address,
-                    pending_msg.message.clone(),
-                    &pending_msg.game_id,
-                )
+                .deliver(peer_address, pending_msg.message.clone(), strategy, None)
                 .await
             {
                 Ok(_) => {
@@ -437,6 +1207,8 @@ impl NetworkManager {
             }
         }
 
+        self.report(ProgressEvent::Finished);
+
         info!(
             "Sent {}/{} pending messages to {}",
             sent_count, messages_count, peer_address
@@ -488,6 +1260,20 @@ impl NetworkManager {
             }
         }
 
+        // Close connections idle for longer than the keepalive
+        {
+            let mut connections = self.connections.lock().await;
+            let keepalive = self.config.connection_keepalive;
+            connections.retain(|_, (connection, info)| {
+                let keep = info.last_used.elapsed() < keepalive;
+                if !keep {
+                    self.stats
+                        .close(connection.connection_id(), &connection.stats());
+                }
+                keep
+            });
+        }
+
         debug!("Connection cleanup completed");
     }
 
@@ -503,28 +1289,62 @@ impl NetworkManager {
             .count();
         let total_pending_messages = pending.values().map(|msgs| msgs.len()).sum();
 
+        let traffic = self.stats.snapshot();
         NetworkStats {
             active_connections,
             healthy_connections,
             total_pending_messages,
+            total_connections: traffic.total_connections,
+            traffic: traffic.traffic,
+        }
+    }
+
+    fn report(&self, event: ProgressEvent) {
+        if let Some(progress) = &self.progress {
+            progress(&event);
         }
     }
 
     /// Classify a message to determine the appropriate operation type
     fn classify_operation(&self, message: &Message) -> String {
         match message {
-            Message::GameInvite(_) => "invite".to_string(),
+            Message::GameInvite(_) | Message::InviteWithdraw(_) => "invite".to_string(),
             Message::GameAccept(_) => "accept".to_string(),
             Message::GameDecline(_) => "decline".to_string(),
             Message::Move(_) => "move".to_string(),
+            Message::MoveSeal(_) | Message::SealReveal(_) => "seal".to_string(),
             Message::MoveAck(_) => "move_ack".to_string(),
             Message::SyncRequest(_) => "sync".to_string(),
             Message::SyncResponse(_) => "sync".to_string(),
+            Message::Resign(_) => "resign".to_string(),
+            Message::DrawOffer(_) | Message::DrawAccept(_) => "draw".to_string(),
+            Message::KeyRotation(_) => "key_rotation".to_string(),
+            Message::KeyRevocation(_) => "key_revocation".to_string(),
+            Message::ResultSignature(_) => "result_signature".to_string(),
+            Message::Study(_) => "study".to_string(),
+            Message::VoterJoin(_) | Message::Vote(_) => "vote".to_string(),
+            Message::ServerAnnouncement(_) => "announcement".to_string(),
+            Message::Presence(_) => "presence".to_string(),
+            Message::AbuseReport(_) => "report".to_string(),
+            Message::DevicePair(_) | Message::DeviceSync(_) => "device".to_string(),
+            Message::ArbiterJoin(_)
+            | Message::ArbiterUpdate(_)
+            | Message::IllegalMoveClaim(_)
+            | Message::ArbiterRuling(_) => "arbiter".to_string(),
             Message::Ping { .. } => "ping".to_string(),
             Message::Pong { .. } => "pong".to_string(),
         }
     }
 
+    /// Delay before the attempt after `attempt`, from the configured retry
+    /// policy if there is one
+    fn retry_delay(&self, attempt: u32, strategy: RetryStrategy) -> Duration {
+        match &self.config.retry_config {
+            Some(retry_config) => retry_config.calculate_delay(attempt),
+            None => self.calculate_retry_delay_for_strategy(attempt, strategy),
+        }
+    }
+
     /// Calculate retry delay for a specific strategy with exponential backoff
     fn calculate_retry_delay_for_strategy(
         &self,
@@ -548,14 +1368,23 @@ pub struct NetworkStats {
     pub active_connections: usize,
     pub healthy_connections: usize,
     pub total_pending_messages: usize,
+    /// Connections made since the manager was created
+    pub total_connections: u64,
+    /// Traffic over those connections, with delivery retries
+    pub traffic: ConnectionStats,
 }
 
 impl std::fmt::Display for NetworkStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Active connections: {}, Healthy: {}, Pending messages: {}",
-            self.active_connections, self.healthy_connections, self.total_pending_messages
+            "Active connections: {}, Healthy: {}, Pending messages: {}, Messages sent/received: {}/{}, Retries: {}",
+            self.active_connections,
+            self.healthy_connections,
+            self.total_pending_messages,
+            self.traffic.messages_sent,
+            self.traffic.messages_received,
+            self.traffic.retries
         )
     }
 }
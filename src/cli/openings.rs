@@ -0,0 +1,203 @@
+use crate::chess::{classify_opening, max_opening_plies, Board, ChessError, Opening};
+use crate::storage::openings::OpeningLineStats;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Half-moves `mate openings --by moves` groups by when no moves are given
+pub const DEFAULT_OPENING_DEPTH: usize = 4;
+
+/// How `mate openings` groups games
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OpeningGrouping {
+    /// By the named ECO opening each game follows
+    #[default]
+    Eco,
+    /// By the exact first moves of each game
+    Moves,
+}
+
+impl OpeningGrouping {
+    /// Every grouping name, as accepted by `--by`
+    pub const NAMES: [&'static str; 2] = ["eco", "moves"];
+}
+
+impl FromStr for OpeningGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "eco" => Ok(OpeningGrouping::Eco),
+            "moves" => Ok(OpeningGrouping::Moves),
+            _ => Err(format!(
+                "Invalid grouping '{s}'. Expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Which games `mate openings` looks at and how it groups them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpeningQuery {
+    pub grouping: OpeningGrouping,
+    /// Only games starting with these moves (SAN)
+    pub prefix: Vec<String>,
+    /// Half-moves after the prefix to group by when grouping by moves;
+    /// defaults to one after a prefix and [`DEFAULT_OPENING_DEPTH`] otherwise
+    pub depth: Option<usize>,
+    /// Only games in openings whose ECO code starts with this
+    pub eco: Option<String>,
+}
+
+impl OpeningQuery {
+    /// Half-moves of each game needed to answer the query
+    pub fn plies(&self) -> usize {
+        let grouped = match self.grouping {
+            OpeningGrouping::Eco => max_opening_plies(),
+            OpeningGrouping::Moves => self.moves_key_len(),
+        };
+        let classified = if self.eco.is_some() {
+            max_opening_plies()
+        } else {
+            0
+        };
+        grouped.max(classified).max(self.prefix.len())
+    }
+
+    fn moves_key_len(&self) -> usize {
+        let default_depth = if self.prefix.is_empty() {
+            DEFAULT_OPENING_DEPTH
+        } else {
+            1
+        };
+        self.prefix.len() + self.depth.unwrap_or(default_depth)
+    }
+}
+
+/// My results in the games grouped under one opening or line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpeningSummary {
+    /// Named opening the shared moves follow, if any
+    pub opening: Option<&'static Opening>,
+    /// Moves (SAN) shared by every game in the group
+    pub moves: Vec<String>,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub game_ids: Vec<String>,
+}
+
+impl OpeningSummary {
+    /// Percentage of points won in finished games, counting draws as half
+    pub fn score(&self) -> Option<f64> {
+        let finished = self.wins + self.draws + self.losses;
+        (finished > 0).then(|| {
+            (f64::from(self.wins) + f64::from(self.draws) / 2.0) * 100.0 / f64::from(finished)
+        })
+    }
+
+    /// Games in the group that have not finished with a result
+    pub fn unfinished(&self) -> u32 {
+        self.games - self.wins - self.draws - self.losses
+    }
+}
+
+/// Rewrite stored moves in SAN by replaying them from the starting position
+///
+/// Moves may be stored in SAN or coordinate notation. Replay stops at the
+/// first move that is not legal, so a damaged history yields the part of it
+/// that can be played.
+pub fn san_line<S: AsRef<str>>(moves: &[S]) -> Vec<String> {
+    let mut board = Board::new();
+    let mut line = Vec::with_capacity(moves.len());
+    for chess_move in moves {
+        let Ok(mv) = board.parse_move(chess_move.as_ref()) else {
+            break;
+        };
+        let san = board.to_san(mv);
+        if board.make_move(mv).is_err() {
+            break;
+        }
+        line.push(san);
+    }
+    line
+}
+
+/// Parse moves typed by the user ("e4 e5 Nf3", "e2e4 e7e5", "1. e4 e5 2. Nf3") into SAN
+pub fn parse_move_prefix(input: &str) -> Result<Vec<String>, ChessError> {
+    let mut board = Board::new();
+    let mut line = Vec::new();
+    for token in input.split_whitespace() {
+        // Move numbers ("1.", "2...") and numbers run into a move ("1.e4")
+        let token = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+        if token.is_empty() {
+            continue;
+        }
+        let mv = board.parse_move(token)?;
+        line.push(board.to_san(mv));
+        board.make_move(mv)?;
+    }
+    Ok(line)
+}
+
+/// Move text with move numbers, e.g. "1. e4 e5 2. Nf3"
+pub fn format_move_text<S: AsRef<str>>(moves: &[S]) -> String {
+    moves
+        .iter()
+        .enumerate()
+        .map(|(index, san)| {
+            if index % 2 == 0 {
+                format!("{}. {}", index / 2 + 1, san.as_ref())
+            } else {
+                san.as_ref().to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Group stored opening lines as the query asks, most played first
+///
+/// Lines are normalized to SAN first, so games stored in different
+/// notations still count towards the same opening.
+pub fn summarize_openings(lines: &[OpeningLineStats], query: &OpeningQuery) -> Vec<OpeningSummary> {
+    let eco_prefix = query.eco.as_ref().map(|eco| eco.to_uppercase());
+    let mut groups: HashMap<Vec<String>, OpeningSummary> = HashMap::new();
+
+    for line in lines {
+        let moves = san_line(&line.moves);
+        if !moves.starts_with(&query.prefix) {
+            continue;
+        }
+
+        let opening = classify_opening(&moves);
+        if let Some(eco_prefix) = &eco_prefix {
+            if !opening.is_some_and(|o| o.eco.starts_with(eco_prefix.as_str())) {
+                continue;
+            }
+        }
+
+        let key = match query.grouping {
+            OpeningGrouping::Eco => opening
+                .map(|o| o.moves.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+            OpeningGrouping::Moves => moves[..query.moves_key_len().min(moves.len())].to_vec(),
+        };
+
+        let summary = groups.entry(key.clone()).or_insert_with(|| OpeningSummary {
+            opening: classify_opening(&key),
+            moves: key,
+            ..OpeningSummary::default()
+        });
+        summary.games += line.games;
+        summary.wins += line.wins;
+        summary.draws += line.draws;
+        summary.losses += line.losses;
+        summary.game_ids.extend(line.game_ids.iter().cloned());
+    }
+
+    let mut summaries: Vec<OpeningSummary> = groups.into_values().collect();
+    summaries.sort_by(|a, b| b.games.cmp(&a.games).then_with(|| a.moves.cmp(&b.moves)));
+    summaries
+}
@@ -0,0 +1,173 @@
+use crate::chess::{Color, Move as ChessMove, Odds};
+use crate::cli::game_ops::game_odds;
+use crate::messages::chess::Move as MoveMessage;
+use crate::storage::models::PlayerColor;
+use crate::storage::Database;
+use anyhow::{Context, Result};
+
+/// Export a stored game as a PGN document
+///
+/// Standard seven-tag-roster headers are derived from the game record. Tags and
+/// notes attached to the game are included as the custom `Tags` and `Note` headers.
+/// Positions with stored engine analysis are annotated with `[%eval ...]` comments.
+pub fn export_game_pgn(database: &Database, game_id: &str, my_peer_id: &str) -> Result<String> {
+    let game = database
+        .get_game(game_id)
+        .context("Failed to retrieve game from database")?;
+    let messages = database
+        .get_messages_for_game(game_id)
+        .context("Failed to retrieve game messages")?;
+    let tags = database
+        .get_game_tags(game_id)
+        .context("Failed to retrieve game tags")?;
+    let note = database
+        .get_game_note(game_id)
+        .context("Failed to retrieve game note")?;
+
+    let moves: Vec<String> = messages
+        .iter()
+        .filter(|m| m.message_type.eq_ignore_ascii_case("move"))
+        .filter_map(|m| serde_json::from_str::<MoveMessage>(&m.content).ok())
+        .map(|m| m.chess_move)
+        .collect();
+
+    let (white, black) = match game.my_color {
+        PlayerColor::White => (my_peer_id, game.opponent_peer_id.as_str()),
+        PlayerColor::Black => (game.opponent_peer_id.as_str(), my_peer_id),
+    };
+    let result = game.pgn_result();
+
+    let mut pgn = String::new();
+    push_header(&mut pgn, "Event", "mate P2P game");
+    push_header(&mut pgn, "Site", "mate");
+    push_header(&mut pgn, "Date", &pgn_date(game.created_at));
+    push_header(&mut pgn, "Round", "-");
+    push_header(&mut pgn, "White", white);
+    push_header(&mut pgn, "Black", black);
+    push_header(&mut pgn, "Result", result);
+    push_header(&mut pgn, "GameId", &game.id);
+    if !tags.is_empty() {
+        push_header(&mut pgn, "Tags", &tags.join(","));
+    }
+    if let Some(note) = note {
+        push_header(&mut pgn, "Note", &note.content);
+    }
+    let odds = game_odds(&game, &messages);
+    let start = Odds::starting_board(odds.as_ref()).ok();
+    if let Some(odds) = &odds {
+        push_header(&mut pgn, "Odds", &odds.to_string());
+        if let Some(start) = &start {
+            push_header(&mut pgn, "SetUp", "1");
+            push_header(&mut pgn, "FEN", &start.to_fen());
+        }
+    }
+    pgn.push('\n');
+
+    // Replay the game so each resulting position can be looked up in the
+    // analysis cache; annotation stops at the first move that fails to replay.
+    let mut board = start;
+
+    // Extra moves given as odds put the same side on move twice in a row,
+    // so numbering follows the side to move rather than the ply count
+    let mut movetext = String::new();
+    let mut move_number = 1;
+    for (index, chess_move) in moves.iter().enumerate() {
+        let side = Odds::side_to_move(odds.as_ref(), index);
+        let previous = index
+            .checked_sub(1)
+            .map(|previous| Odds::side_to_move(odds.as_ref(), previous));
+        match side {
+            Color::White => movetext.push_str(&format!("{move_number}. ")),
+            Color::Black if previous != Some(Color::White) => {
+                movetext.push_str(&format!("{move_number}... "))
+            }
+            Color::Black => {}
+        }
+        if side == Color::Black || Odds::side_to_move(odds.as_ref(), index + 1) == side {
+            move_number += 1;
+        }
+        movetext.push_str(chess_move);
+        movetext.push(' ');
+
+        board = board.and_then(|mut board| {
+            let parsed = ChessMove::from_str_with_color(chess_move, board.active_color()).ok()?;
+            Odds::make_move(odds.as_ref(), &mut board, index, parsed).ok()?;
+            Some(board)
+        });
+        if let Some(board) = &board {
+            let analysis = database
+                .get_analysis(board.zobrist_hash())
+                .context("Failed to retrieve position analysis")?;
+            if let Some(eval) = analysis.and_then(|a| a.pgn_eval()) {
+                movetext.push_str(&format!("{{[%eval {eval}]}} "));
+            }
+        }
+    }
+    movetext.push_str(result);
+
+    pgn.push_str(&movetext);
+    pgn.push('\n');
+
+    Ok(pgn)
+}
+
+/// Append a single `[Name "value"]` header line, escaping the value
+fn push_header(pgn: &mut String, name: &str, value: &str) {
+    let escaped = escape_pgn_string(value);
+    pgn.push_str(&format!("[{name} \"{escaped}\"]\n"));
+}
+
+/// Escape a value for use inside a PGN string token
+///
+/// PGN strings may not span lines, so line breaks are folded into spaces.
+pub fn escape_pgn_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\r' => {}
+            '\n' | '\t' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Format a Unix timestamp as a PGN date (YYYY.MM.DD, UTC)
+pub fn pgn_date(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86_400);
+
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}.{month:02}.{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pgn_date() {
+        assert_eq!(pgn_date(0), "1970.01.01");
+        assert_eq!(pgn_date(951_782_400), "2000.02.29");
+        assert_eq!(pgn_date(1_700_000_000), "2023.11.14");
+    }
+
+    #[test]
+    fn test_escape_pgn_string() {
+        assert_eq!(escape_pgn_string("plain"), "plain");
+        assert_eq!(escape_pgn_string("say \"hi\""), "say \\\"hi\\\"");
+        assert_eq!(escape_pgn_string("a\\b"), "a\\\\b");
+        assert_eq!(escape_pgn_string("line one\nline two"), "line one line two");
+    }
+}
@@ -0,0 +1,635 @@
+use crate::chess::{Board, Color, Move};
+use crate::cli::app::App;
+use crate::cli::display::Highlights;
+use crate::cli::game_ops::{GameOps, GameState, MoveProcessingResult, MoveProcessor};
+use crate::cli::line_editor::{LineEditor, SharedSuggestions, Suggestions};
+use crate::cli::tui::move_list_lines;
+use crate::storage::models::{GameResult, GameStatus, Message};
+use anyhow::{Context, Result};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+/// How often the database is checked for moves and messages from the opponent
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the database is checked while a premove waits, so it is played
+/// soon after the opponent's move arrives rather than on the user's clock
+const PREMOVE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Message types the session shows as they arrive
+const SESSION_MESSAGE_TYPES: [&str; 4] = ["Chat", "DrawOffer", "DrawAccept", "Resign"];
+
+/// Command words offered by tab completion
+const SESSION_COMMANDS: [&str; 8] = [
+    "board", "history", "draw", "resign", "chat", "cancel", "help", "quit",
+];
+
+/// A line of input entered during a play session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionCommand {
+    /// A move in SAN or coordinate notation
+    Move(String),
+    Board,
+    History,
+    Draw,
+    Resign,
+    Chat(String),
+    /// Drop the premove waiting for the opponent's move
+    Cancel,
+    Help,
+    Quit,
+}
+
+impl SessionCommand {
+    /// Parse a line of input, returning `None` for blank lines
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+
+        let (word, rest) = match line.split_once(char::is_whitespace) {
+            Some((word, rest)) => (word, rest.trim()),
+            None => (line, ""),
+        };
+
+        let command = match word.trim_start_matches('/').to_lowercase().as_str() {
+            "board" | "b" => SessionCommand::Board,
+            "history" | "moves" => SessionCommand::History,
+            "draw" => SessionCommand::Draw,
+            "resign" => SessionCommand::Resign,
+            "chat" | "say" => SessionCommand::Chat(rest.to_string()),
+            "cancel" => SessionCommand::Cancel,
+            "help" | "?" => SessionCommand::Help,
+            "quit" | "exit" | "q" => SessionCommand::Quit,
+            _ => SessionCommand::Move(line.to_string()),
+        };
+        Some(command)
+    }
+}
+
+/// State for one interactive session
+struct PlaySession<'a> {
+    app: &'a App,
+    game_id: String,
+    my_color: Color,
+    opponent: String,
+    /// Number of moves already shown to the user
+    seen_moves: usize,
+    /// Highest message ID already shown to the user
+    last_message_id: i64,
+    /// Set after `resign` until the user confirms or cancels
+    confirming_resign: bool,
+    /// Set when the opponent moved while input was being typed
+    board_pending: bool,
+    /// Move entered while waiting for the opponent, played as soon as it is
+    /// this player's turn if it is legal then
+    premove: Option<String>,
+}
+
+impl<'a> PlaySession<'a> {
+    fn new(app: &'a App, game_id: &str) -> Result<Self> {
+        let game_ops = GameOps::new(&app.database);
+        let game = game_ops
+            .resolve_game(game_id)
+            .context("Failed to find game")?;
+        let state = game_ops
+            .reconstruct_game_state(&game.id)
+            .context("Failed to load game state")?;
+        let last_message_id = app
+            .database
+            .get_messages_for_game(&game.id)
+            .context("Failed to retrieve game messages")?
+            .iter()
+            .filter_map(|m| m.id)
+            .max()
+            .unwrap_or(0);
+
+        Ok(Self {
+            app,
+            my_color: game.my_color.clone().into(),
+            opponent: game.opponent_peer_id.clone(),
+            game_id: game.id,
+            seen_moves: state.move_history.len(),
+            last_message_id,
+            confirming_resign: false,
+            board_pending: false,
+            premove: None,
+        })
+    }
+
+    /// How long to wait before checking for the opponent's moves again
+    fn poll_interval(&self) -> Duration {
+        match self.premove {
+            Some(_) => PREMOVE_POLL_INTERVAL,
+            None => POLL_INTERVAL,
+        }
+    }
+
+    fn state(&self) -> Result<GameState> {
+        GameOps::new(&self.app.database)
+            .reconstruct_game_state(&self.game_id)
+            .context("Failed to load game state")
+    }
+
+    fn prompt(&self) -> &'static str {
+        if self.confirming_resign {
+            "Resign this game? (y/n): "
+        } else {
+            "mate> "
+        }
+    }
+
+    /// What the next line of input can be completed to
+    fn suggestions(&self) -> Result<Suggestions> {
+        if self.confirming_resign {
+            return Ok(Suggestions::for_commands(&["yes", "no"], None));
+        }
+
+        let state = self.state()?;
+        if state.game.status == GameStatus::Active && state.your_turn {
+            Ok(Suggestions::for_moves(&state.board, &SESSION_COMMANDS))
+        } else if state.game.status == GameStatus::Active {
+            Ok(Suggestions::for_commands(
+                &SESSION_COMMANDS,
+                Some("premove, played if legal after the opponent's move"),
+            ))
+        } else {
+            Ok(Suggestions::for_commands(
+                &SESSION_COMMANDS,
+                Some("game is not active"),
+            ))
+        }
+    }
+
+    fn show_board(&self) -> Result<()> {
+        let state = self.state()?;
+        self.render(&state.board, state.move_history.last().map(String::as_str));
+        self.show_status(&state);
+        Ok(())
+    }
+
+    /// Draw a board using the configured style, highlighting the last move
+    fn render(&self, board: &Board, last_move: Option<&str>) {
+        self.app.config.display.board_style().display(
+            board,
+            self.my_color,
+            &Highlights::new(board, last_move),
+        );
+    }
+
+    fn show_status(&self, state: &GameState) {
+        match state.game.status {
+            GameStatus::Active if state.board.is_checkmate() => println!("Checkmate."),
+            GameStatus::Active if state.board.is_stalemate() => println!("Stalemate."),
+            GameStatus::Active if state.your_turn => {
+                if state.board.is_in_check(self.my_color) {
+                    println!("You are in check.");
+                }
+                println!("Your move ({}).", self.my_color);
+            }
+            GameStatus::Active => match &self.premove {
+                Some(premove) => println!("Waiting for {}... (premove: {premove})", self.opponent),
+                None => println!("Waiting for {}...", self.opponent),
+            },
+            _ => match &state.game.result {
+                Some(result) => println!("Game over: {}", result.as_str()),
+                None => println!("Game over: {}", state.game.status.as_str()),
+            },
+        }
+    }
+
+    /// Handle one line of input, returning `false` when the session should end
+    async fn handle_line(&mut self, line: &str) -> Result<bool> {
+        if std::mem::take(&mut self.board_pending) {
+            self.show_board()?;
+        }
+
+        if self.confirming_resign {
+            self.confirming_resign = false;
+            if matches!(line.trim().to_lowercase().as_str(), "y" | "yes") {
+                self.resign().await?;
+            } else {
+                println!("Resignation cancelled.");
+            }
+            return Ok(true);
+        }
+
+        let Some(command) = SessionCommand::parse(line) else {
+            return Ok(true);
+        };
+
+        match command {
+            SessionCommand::Move(notation) => self.play_move(&notation).await?,
+            SessionCommand::Board => self.show_board()?,
+            SessionCommand::History => {
+                let state = self.state()?;
+                if state.move_history.is_empty() {
+                    println!("No moves yet.");
+                }
+                for line in move_list_lines(&state.move_history) {
+                    println!("{line}");
+                }
+            }
+            SessionCommand::Draw => self.offer_draw().await?,
+            SessionCommand::Resign => {
+                if self.state()?.game.status == GameStatus::Active {
+                    self.confirming_resign = true;
+                } else {
+                    println!("Game is not active.");
+                }
+            }
+            SessionCommand::Chat(text) => {
+                if text.is_empty() {
+                    println!("Usage: chat <message>");
+                } else {
+                    self.record("Chat", serde_json::json!({ "text": text }))?;
+                }
+            }
+            SessionCommand::Cancel => match self.premove.take() {
+                Some(premove) => println!("Premove {premove} cancelled."),
+                None => println!("No premove to cancel."),
+            },
+            SessionCommand::Help => print_help(),
+            SessionCommand::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    async fn play_move(&mut self, notation: &str) -> Result<()> {
+        let state = self.state()?;
+        if state.game.status != GameStatus::Active {
+            println!("Game is not active.");
+            return Ok(());
+        }
+        if !state.your_turn {
+            // Checked against the position once the opponent has moved
+            self.premove = Some(notation.to_string());
+            println!("Premove {notation} set; it is played if legal after the opponent's move.");
+            println!("Type 'cancel' to drop it.");
+            return Ok(());
+        }
+        // A move entered on this player's turn replaces any premove
+        self.premove = None;
+
+        let mv = match state.board.parse_move(notation) {
+            Ok(mv) => mv,
+            Err(e) => {
+                println!("{e}");
+                return Ok(());
+            }
+        };
+
+        let coordinate = mv.to_string();
+        let Some(result) = self.store_move(&state, mv, &mut |notice| println!("{notice}"))? else {
+            return Ok(());
+        };
+
+        self.render(&result.updated_board, Some(&coordinate));
+        println!("✓ Played {coordinate}");
+        self.send_move(result, &mut |notice| println!("{notice}"))
+            .await;
+
+        self.show_status(&self.state()?);
+        Ok(())
+    }
+
+    /// Play the premove if it is now this player's turn
+    ///
+    /// A premove that is not legal in the new position is dropped. Notices
+    /// go through `notify`, as the user may be typing; the board is redrawn
+    /// once that line is entered. Returns whether anything was reported.
+    async fn play_premove(&mut self, notify: &mut dyn FnMut(String)) -> Result<bool> {
+        if self.premove.is_none() {
+            return Ok(false);
+        }
+        let state = self.state()?;
+        if state.game.status != GameStatus::Active {
+            if let Some(premove) = self.premove.take() {
+                notify(format!("Premove {premove} dropped; the game is over."));
+            }
+            return Ok(true);
+        }
+        if !state.your_turn {
+            return Ok(false);
+        }
+        let Some(premove) = self.premove.take() else {
+            return Ok(false);
+        };
+
+        let mv = match state.board.parse_move(&premove) {
+            Ok(mv) => mv,
+            Err(e) => {
+                notify(format!("Premove {premove} dropped: {e}"));
+                return Ok(true);
+            }
+        };
+        let san = state.board.to_san(mv);
+        if let Some(result) = self.store_move(&state, mv, notify)? {
+            notify(format!(
+                "✓ Premove {san} played (press Enter to see the board)"
+            ));
+            self.board_pending = true;
+            self.send_move(result, notify).await;
+        }
+        Ok(true)
+    }
+
+    /// Store a legal move of this player's and record a finished game's
+    /// result
+    ///
+    /// Returns `None` if the move was rejected, which is reported through
+    /// `notify`.
+    fn store_move(
+        &mut self,
+        state: &GameState,
+        mv: Move,
+        notify: &mut dyn FnMut(String),
+    ) -> Result<Option<MoveProcessingResult>> {
+        let result = match MoveProcessor::new(&self.app.database)
+            .with_signer(&self.app.identity)
+            .process_move(&self.game_id, &mv.to_string(), true)
+        {
+            Ok(result) => result,
+            Err(e) => {
+                notify(format!("Move rejected: {e}"));
+                return Ok(None);
+            }
+        };
+        self.seen_moves = state.move_history.len() + 1;
+
+        let board = &result.updated_board;
+        if board.is_checkmate() {
+            self.app
+                .database
+                .update_game_result(&self.game_id, GameResult::Win)
+                .context("Failed to record game result")?;
+        } else if board.is_stalemate() {
+            self.app
+                .database
+                .update_game_result(&self.game_id, GameResult::Draw)
+                .context("Failed to record game result")?;
+        }
+        Ok(Some(result))
+    }
+
+    /// Send a stored move to the opponent, and sign the result of a game it
+    /// finished
+    async fn send_move(&self, result: MoveProcessingResult, notify: &mut dyn FnMut(String)) {
+        let finished = result.updated_board.is_checkmate() || result.updated_board.is_stalemate();
+        if let Err(e) = self
+            .app
+            .network_manager
+            .send_chess_move(&self.opponent, self.game_id.clone(), result.move_message)
+            .await
+        {
+            notify(format!(
+                "⚠ Could not reach opponent ({e}); the move is queued and will be resent."
+            ));
+        }
+        if finished {
+            self.app.sign_game_result(&self.game_id).await;
+        } else {
+            self.app.update_arbiter(&self.game_id).await;
+        }
+    }
+
+    async fn offer_draw(&mut self) -> Result<()> {
+        let state = self.state()?;
+        if state.game.status != GameStatus::Active {
+            println!("Game is not active.");
+            return Ok(());
+        }
+
+        let opponent_offered = GameOps::new(&self.app.database)
+            .has_open_draw_offer(&self.game_id, self.app.peer_id())
+            .context("Failed to check for a draw offer")?;
+        if opponent_offered {
+            self.app.accept_draw(&state.game).await?;
+            println!("Draw agreed.");
+        } else {
+            self.app.offer_draw(&state.game).await?;
+            println!("Draw offered.");
+        }
+        Ok(())
+    }
+
+    async fn resign(&mut self) -> Result<()> {
+        let state = self.state()?;
+        self.app.resign_game(&state.game).await?;
+        println!("You resigned.");
+        Ok(())
+    }
+
+    /// Store a session message sent by this player
+    fn record(&mut self, message_type: &str, content: serde_json::Value) -> Result<()> {
+        let message = self
+            .app
+            .database
+            .store_message(
+                self.game_id.clone(),
+                message_type.to_string(),
+                content.to_string(),
+                String::new(),
+                self.app.peer_id().to_string(),
+            )
+            .with_context(|| format!("Failed to store {message_type} message"))?;
+        if let Some(id) = message.id {
+            self.last_message_id = self.last_message_id.max(id);
+        }
+        Ok(())
+    }
+
+    /// Report moves and messages that arrived since the last check
+    ///
+    /// Notices go through `notify` so they can be printed above a line being
+    /// edited; the board itself is redrawn once that line is entered.
+    /// Returns whether anything was reported.
+    fn check_for_updates(&mut self, notify: &mut dyn FnMut(String)) -> Result<bool> {
+        let messages = self
+            .app
+            .database
+            .get_messages_for_game(&self.game_id)
+            .context("Failed to retrieve game messages")?;
+        let new_messages: Vec<&Message> = messages
+            .iter()
+            .filter(|m| m.id.is_some_and(|id| id > self.last_message_id))
+            .collect();
+        let muted = self
+            .app
+            .database
+            .get_muted_peers()
+            .context("Failed to retrieve muted peers")?;
+        let mut printed = false;
+
+        for message in &new_messages {
+            if message.sender_peer_id != self.app.peer_id()
+                && SESSION_MESSAGE_TYPES.contains(&message.message_type.as_str())
+                && !(message.message_type == "Chat" && muted.contains(&message.sender_peer_id))
+            {
+                notify(describe_message(message));
+                printed = true;
+            }
+        }
+        if let Some(id) = new_messages.iter().filter_map(|m| m.id).max() {
+            self.last_message_id = id;
+        }
+
+        let state = self.state()?;
+        if state.move_history.len() > self.seen_moves {
+            for notation in &state.move_history[self.seen_moves..] {
+                notify(format!(
+                    "Opponent played {notation} (press Enter to see the board)"
+                ));
+            }
+            self.seen_moves = state.move_history.len();
+            self.board_pending = true;
+            printed = true;
+        }
+
+        Ok(printed)
+    }
+}
+
+/// One-line description of an incoming session message
+fn describe_message(message: &Message) -> String {
+    match message.message_type.as_str() {
+        "Chat" => {
+            let text = serde_json::from_str::<serde_json::Value>(&message.content)
+                .ok()
+                .and_then(|v| v.get("text").and_then(|t| t.as_str()).map(String::from))
+                .unwrap_or_default();
+            format!("[opponent] {text}")
+        }
+        "DrawOffer" => "Opponent offers a draw. Type 'draw' to accept.".to_string(),
+        "DrawAccept" => "Opponent accepted the draw.".to_string(),
+        "Resign" => "Opponent resigned.".to_string(),
+        other => format!("Opponent sent {other}"),
+    }
+}
+
+fn print_help() {
+    println!("Enter a move in SAN (Nf3, exd5, O-O, e8=Q) or coordinates (g1f3, e7e8q).");
+    println!("A move entered while waiting is a premove, played as soon as the opponent");
+    println!("has moved if it is legal then.");
+    println!("Commands:");
+    println!("  board           Show the board");
+    println!("  history         Show the move list");
+    println!("  draw            Offer a draw, or accept the opponent's offer");
+    println!("  resign          Resign the game");
+    println!("  chat <message>  Send a message to the opponent");
+    println!("  cancel          Drop the premove");
+    println!("  help            Show this help");
+    println!("  quit            Leave the session (the game continues)");
+}
+
+/// Run an interactive session for a game until the user quits
+///
+/// Moves stored by a running `mate serve` are picked up by polling the
+/// database, so the opponent's replies appear without re-running commands.
+/// Input is read with a line editor offering history and tab completion of
+/// legal moves. A premove is only kept by this session; it is checked more
+/// often so it goes out right after the opponent's move, and is lost on quit.
+pub async fn run(app: &App, game_id: &str) -> Result<()> {
+    let mut session = PlaySession::new(app, game_id)?;
+
+    println!(
+        "Playing game {} as {} against {}. Type 'help' for commands.",
+        session.game_id, session.my_color, session.opponent
+    );
+    let _live_pgn = crate::cli::live_pgn::start_for(app, &session.game_id).await?;
+    session.show_board()?;
+
+    let suggestions: SharedSuggestions = Arc::new(Mutex::new(session.suggestions()?));
+    let mut editor = LineEditor::new(Arc::clone(&suggestions))?;
+    let mut printer = editor.printer();
+    let mut notify = |text: String| match printer.as_mut() {
+        Some(printer) => {
+            if printer.print(text.clone()).is_err() {
+                println!("{text}");
+            }
+        }
+        None => println!("{text}"),
+    };
+
+    // The editor blocks while reading, so it runs on its own thread and
+    // hands each line back to the session.
+    let (prompt_tx, prompt_rx) = mpsc::channel::<&'static str>();
+    let (line_tx, mut line_rx) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(prompt) = prompt_rx.recv() {
+            let line = editor.read_line(prompt);
+            let finished = !matches!(line, Ok(Some(_)));
+            if line_tx.send(line).is_err() || finished {
+                break;
+            }
+        }
+    });
+
+    let _ = prompt_tx.send(session.prompt());
+
+    loop {
+        tokio::select! {
+            line = line_rx.recv() => {
+                let Some(line) = line.transpose()?.flatten() else {
+                    println!();
+                    break;
+                };
+                if !session.handle_line(&line).await? {
+                    break;
+                }
+                refresh_suggestions(&session, &suggestions)?;
+                let _ = prompt_tx.send(session.prompt());
+            }
+            _ = tokio::time::sleep(session.poll_interval()) => {
+                let updated = session.check_for_updates(&mut notify)?;
+                let premoved = session.play_premove(&mut notify).await?;
+                if updated || premoved {
+                    refresh_suggestions(&session, &suggestions)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn refresh_suggestions(session: &PlaySession, suggestions: &SharedSuggestions) -> Result<()> {
+    let next = session.suggestions()?;
+    if let Ok(mut current) = suggestions.lock() {
+        *current = next;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_commands() {
+        assert_eq!(SessionCommand::parse("   "), None);
+        assert_eq!(
+            SessionCommand::parse("Nf3"),
+            Some(SessionCommand::Move("Nf3".to_string()))
+        );
+        assert_eq!(
+            SessionCommand::parse(" e2e4 "),
+            Some(SessionCommand::Move("e2e4".to_string()))
+        );
+        assert_eq!(
+            SessionCommand::parse("/resign"),
+            Some(SessionCommand::Resign)
+        );
+        assert_eq!(SessionCommand::parse("DRAW"), Some(SessionCommand::Draw));
+        assert_eq!(
+            SessionCommand::parse("chat good luck!"),
+            Some(SessionCommand::Chat("good luck!".to_string()))
+        );
+        assert_eq!(
+            SessionCommand::parse("cancel"),
+            Some(SessionCommand::Cancel)
+        );
+        assert_eq!(SessionCommand::parse("quit"), Some(SessionCommand::Quit));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::cli::display::OutputFormat;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often spinners advance while waiting on the network
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A step in a long-running operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// Opening a connection; `attempt` counts from 1
+    Connecting {
+        address: String,
+        attempt: u32,
+        max_attempts: u32,
+    },
+    /// Waiting before the next connection attempt
+    Retrying { address: String, delay: Duration },
+    /// Connected and waiting for the peer to reply
+    AwaitingReply { address: String },
+    /// `done` of `total` items processed
+    Advanced {
+        label: String,
+        done: u64,
+        total: u64,
+    },
+    /// The operation ended, successfully or not
+    Finished,
+}
+
+/// Receives progress of long-running operations as it happens
+pub type ProgressCallback = Arc<dyn Fn(&ProgressEvent) + Send + Sync>;
+
+/// Whether progress should be drawn for the given output format
+///
+/// Progress is drawn on stderr, and only when it is a terminal and results
+/// are not printed as JSON.
+pub fn progress_enabled(output_format: OutputFormat) -> bool {
+    output_format != OutputFormat::Json && std::io::stderr().is_terminal()
+}
+
+/// A callback drawing spinners and progress bars on stderr, or `None` when
+/// progress should not be shown for this output format
+pub fn terminal_progress(output_format: OutputFormat) -> Option<ProgressCallback> {
+    progress_enabled(output_format).then(|| {
+        let display = ProgressDisplay::new(ProgressDrawTarget::stderr);
+        Arc::new(move |event: &ProgressEvent| display.update(event)) as ProgressCallback
+    })
+}
+
+/// Renders [`ProgressEvent`]s as one spinner or bar that is replaced as
+/// operations move from step to step
+pub struct ProgressDisplay {
+    target: fn() -> ProgressDrawTarget,
+    bar: Mutex<Option<ProgressBar>>,
+}
+
+impl ProgressDisplay {
+    /// Draw progress to targets made by `target`
+    pub fn new(target: fn() -> ProgressDrawTarget) -> Self {
+        Self {
+            target,
+            bar: Mutex::new(None),
+        }
+    }
+
+    /// The bar currently shown, if any
+    pub fn current(&self) -> Option<ProgressBar> {
+        self.bar.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Show the given event, replacing whatever was shown before
+    pub fn update(&self, event: &ProgressEvent) {
+        let mut bar = self.bar.lock().unwrap_or_else(|e| e.into_inner());
+        match event {
+            ProgressEvent::Connecting {
+                address,
+                attempt,
+                max_attempts,
+            } => {
+                let message = if *attempt > 1 {
+                    format!("Connecting to {address} (attempt {attempt}/{max_attempts})...")
+                } else {
+                    format!("Connecting to {address}...")
+                };
+                self.spinner(&mut bar).set_message(message);
+            }
+            ProgressEvent::Retrying { address, delay } => {
+                let seconds = delay.as_secs_f64();
+                self.spinner(&mut bar).set_message(format!(
+                    "Could not reach {address}; retrying in {seconds:.1}s..."
+                ));
+            }
+            ProgressEvent::AwaitingReply { address } => {
+                self.spinner(&mut bar)
+                    .set_message(format!("Waiting for {address} to reply..."));
+            }
+            ProgressEvent::Advanced { label, done, total } => {
+                let progress = self.counter(&mut bar, *total);
+                progress.set_message(label.clone());
+                progress.set_position(*done);
+            }
+            ProgressEvent::Finished => {
+                if let Some(finished) = bar.take() {
+                    finished.finish_and_clear();
+                }
+            }
+        }
+    }
+
+    /// The current spinner, started if something else was shown
+    fn spinner<'a>(&self, bar: &'a mut Option<ProgressBar>) -> &'a ProgressBar {
+        if bar.as_ref().is_some_and(|b| b.length().is_some()) {
+            if let Some(previous) = bar.take() {
+                previous.finish_and_clear();
+            }
+        }
+        bar.get_or_insert_with(|| {
+            let spinner = ProgressBar::with_draw_target(None, (self.target)());
+            spinner.set_style(
+                ProgressStyle::with_template("{spinner} {msg}")
+                    .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+            );
+            spinner.enable_steady_tick(TICK_INTERVAL);
+            spinner
+        })
+    }
+
+    /// The current bar counting to `total`, started if something else was shown
+    fn counter<'a>(&self, bar: &'a mut Option<ProgressBar>, total: u64) -> &'a ProgressBar {
+        if bar.as_ref().is_some_and(|b| b.length() != Some(total)) {
+            if let Some(previous) = bar.take() {
+                previous.finish_and_clear();
+            }
+        }
+        bar.get_or_insert_with(|| {
+            let counter = ProgressBar::with_draw_target(Some(total), (self.target)());
+            counter.set_style(
+                ProgressStyle::with_template("{msg} [{bar:30}] {pos}/{len}")
+                    .unwrap_or_else(|_| ProgressStyle::default_bar())
+                    .progress_chars("=> "),
+            );
+            counter
+        })
+    }
+}
@@ -0,0 +1,198 @@
+use crate::cli::game_ops::{GameOps, GameRecord};
+use crate::cli::hotseat::HOTSEAT_OPPONENT;
+use crate::network::notifications::show_notification;
+use crate::network::webhooks::{WebhookPayload, Webhooks};
+use crate::network::Heartbeat;
+use crate::storage::models::GameStatus;
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// How often `mate serve` looks for games waiting on the user
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// When the game in `record` started waiting on the local player's move, as
+/// Unix seconds, or `None` if it isn't waiting on them
+///
+/// That is when the opponent's last move was stored, or when the game was
+/// created if no move has been played. Hotseat games never wait on anyone.
+pub fn your_move_since(database: &Database, record: &GameRecord) -> Result<Option<i64>> {
+    let game = &record.game;
+    if !record.your_turn
+        || game.status != GameStatus::Active
+        || game.opponent_peer_id == HOTSEAT_OPPONENT
+    {
+        return Ok(None);
+    }
+    let last_move = database
+        .get_messages_for_game(&game.id)
+        .context("Failed to retrieve game messages")?
+        .iter()
+        .filter(|m| m.message_type == "Move")
+        .map(|m| m.created_at)
+        .max();
+    Ok(Some(last_move.unwrap_or(game.created_at)))
+}
+
+/// A wait as a rough length, e.g. `3 days` or `5 hours`
+pub fn format_waiting(secs: u64) -> String {
+    let (count, unit) = match secs {
+        0..=3599 => ((secs / 60).max(1), "minute"),
+        3600..=86_399 => (secs / 3600, "hour"),
+        _ => (secs / 86_400, "day"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{count} {unit}{plural}")
+}
+
+/// A game that has waited on the user long enough to remind them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    pub game_id: String,
+    pub opponent_peer_id: String,
+    /// The opponent's alias, when one is set
+    pub opponent_name: Option<String>,
+    pub waiting_secs: u64,
+}
+
+impl Reminder {
+    /// Summary and body of the desktop notification
+    pub fn notification(&self) -> (String, String) {
+        let opponent = self
+            .opponent_name
+            .as_deref()
+            .unwrap_or(&self.opponent_peer_id);
+        (
+            format!(
+                "Your move against {opponent} for {}",
+                format_waiting(self.waiting_secs)
+            ),
+            format!("Run `mate play {}` to continue", self.game_id),
+        )
+    }
+
+    /// The `reminder` event posted to webhooks
+    pub fn webhook_payload(&self, now: i64) -> WebhookPayload {
+        WebhookPayload {
+            event: "reminder",
+            game_id: self.game_id.clone(),
+            from: self.opponent_peer_id.clone(),
+            chess_move: None,
+            result: None,
+            waiting_secs: Some(self.waiting_secs),
+            timestamp: now.max(0) as u64,
+        }
+    }
+}
+
+/// Reminds the user about correspondence games waiting on their move
+///
+/// A game is due once it has waited `after` since the opponent moved, and
+/// again each further `after` until the user moves.
+pub struct Reminders {
+    database: Arc<Database>,
+    after: Duration,
+    notify: bool,
+    webhooks: Arc<Webhooks>,
+    /// When each game was last reminded about
+    reminded: HashMap<String, i64>,
+    heartbeat: Option<Heartbeat>,
+}
+
+impl Reminders {
+    pub fn new(database: Arc<Database>, after: Duration) -> Self {
+        Self {
+            database,
+            after,
+            notify: true,
+            webhooks: Arc::new(Webhooks::default()),
+            reminded: HashMap::new(),
+            heartbeat: None,
+        }
+    }
+
+    /// Whether to show desktop notifications (on by default)
+    pub fn with_notifications(mut self, notify: bool) -> Self {
+        self.notify = notify;
+        self
+    }
+
+    /// Also post each reminder to `webhooks` as a `reminder` event
+    pub fn with_webhooks(mut self, webhooks: Webhooks) -> Self {
+        self.webhooks = Arc::new(webhooks);
+        self
+    }
+
+    /// Beat `heartbeat` after every check, for the watchdog of `mate serve`
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Games due for a reminder at `now` (Unix seconds), which are then
+    /// counted as reminded
+    pub fn due(&mut self, now: i64) -> Result<Vec<Reminder>> {
+        let after = self.after.as_secs() as i64;
+        let records = GameOps::new(&self.database)
+            .list_games_by_status(GameStatus::Active)
+            .context("Failed to retrieve active games")?;
+
+        let mut due = Vec::new();
+        for record in &records {
+            let Some(since) = your_move_since(&self.database, record)? else {
+                self.reminded.remove(&record.game.id);
+                continue;
+            };
+            let last = self
+                .reminded
+                .get(&record.game.id)
+                .copied()
+                .filter(|&reminded| reminded >= since)
+                .unwrap_or(since);
+            if now - last < after {
+                continue;
+            }
+            self.reminded.insert(record.game.id.clone(), now);
+            due.push(Reminder {
+                game_id: record.game.id.clone(),
+                opponent_peer_id: record.game.opponent_peer_id.clone(),
+                opponent_name: record.opponent_name.clone(),
+                waiting_secs: (now - since).max(0) as u64,
+            });
+        }
+        Ok(due)
+    }
+
+    /// Send a reminder through the enabled channels
+    pub fn send(&self, reminder: &Reminder, now: i64) {
+        debug!(
+            "Reminding about game {} after {}s",
+            reminder.game_id, reminder.waiting_secs
+        );
+        if self.notify {
+            let (summary, body) = reminder.notification();
+            show_notification(summary, body);
+        }
+        self.webhooks
+            .dispatch_payload(reminder.webhook_payload(now));
+    }
+
+    /// Check for due games every [`CHECK_INTERVAL`] until the task is cancelled
+    pub async fn run(mut self) -> Result<()> {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = Database::current_timestamp();
+            match self.due(now) {
+                Ok(due) => due.iter().for_each(|reminder| self.send(reminder, now)),
+                Err(e) => warn!("Failed to check for games waiting on you: {:#}", e),
+            }
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
+        }
+    }
+}
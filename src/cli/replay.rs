@@ -0,0 +1,283 @@
+use crate::chess::{Board, Color, Move};
+use crate::cli::app::App;
+use crate::cli::game_ops::GameOps;
+use crate::cli::tui::{board_lines, move_list_lines};
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+/// Every position of a game, from the starting position to the final move
+#[derive(Debug, Clone)]
+pub struct Replay {
+    positions: Vec<Board>,
+    moves: Vec<String>,
+    ply: usize,
+}
+
+impl Replay {
+    /// Replay a move list from the starting position
+    pub fn from_moves(moves: &[String]) -> Result<Self> {
+        let mut board = Board::new();
+        let mut positions = Vec::with_capacity(moves.len() + 1);
+        positions.push(board.clone());
+
+        for (index, notation) in moves.iter().enumerate() {
+            let mv = Move::from_str_with_color(notation, board.active_color())
+                .with_context(|| format!("Invalid move {} ({})", index + 1, notation))?;
+            board
+                .make_move(mv)
+                .with_context(|| format!("Illegal move {} ({})", index + 1, notation))?;
+            positions.push(board.clone());
+        }
+
+        Ok(Self {
+            positions,
+            moves: moves.to_vec(),
+            ply: 0,
+        })
+    }
+
+    /// Board at the current ply
+    pub fn board(&self) -> &Board {
+        &self.positions[self.ply]
+    }
+
+    /// Number of moves played to reach the current position
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Total number of moves in the game
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// The move that led to the current position
+    pub fn last_move(&self) -> Option<&str> {
+        self.ply
+            .checked_sub(1)
+            .map(|index| self.moves[index].as_str())
+    }
+
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
+    /// Step forward one ply; returns false at the end of the game
+    pub fn step_forward(&mut self) -> bool {
+        if self.ply < self.len() {
+            self.ply += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Step back one ply; returns false at the starting position
+    pub fn step_back(&mut self) -> bool {
+        if self.ply > 0 {
+            self.ply -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Jump to the position after White's `move_number`th move (1-based)
+    ///
+    /// Move 0 is the starting position; numbers past the end go to the final position.
+    pub fn jump_to_move(&mut self, move_number: usize) {
+        self.ply = (move_number * 2).saturating_sub(1).min(self.len());
+    }
+
+    pub fn jump_to_start(&mut self) {
+        self.ply = 0;
+    }
+
+    pub fn jump_to_end(&mut self) {
+        self.ply = self.len();
+    }
+}
+
+/// State behind the replay screen
+struct ReplayView {
+    replay: Replay,
+    game_id: String,
+    perspective: Color,
+    /// Digits typed before `j`
+    pending_number: String,
+}
+
+/// Step through a stored game in a full-screen viewer until the user quits
+pub fn run(app: &App, game_id: &str) -> Result<()> {
+    let game_ops = GameOps::new(&app.database);
+    let game = game_ops
+        .resolve_game(game_id)
+        .context("Failed to find game")?;
+    let state = game_ops
+        .reconstruct_game_state(&game.id)
+        .context("Failed to load game state")?;
+
+    let mut view = ReplayView {
+        replay: Replay::from_moves(&state.move_history)?,
+        game_id: game.id,
+        perspective: game.my_color.into(),
+        pending_number: String::new(),
+    };
+
+    let mut terminal = ratatui::try_init().context("Failed to initialize terminal")?;
+    let result = event_loop(&mut terminal, &mut view);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, view: &mut ReplayView) -> Result<()> {
+    loop {
+        terminal
+            .draw(|frame| draw(frame, view))
+            .context("Failed to draw replay")?;
+
+        let Event::Key(key) = event::read().context("Failed to read terminal events")? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char(digit @ '0'..='9') => view.pending_number.push(digit),
+            KeyCode::Backspace => {
+                view.pending_number.pop();
+            }
+            KeyCode::Char('j') => {
+                match view.pending_number.parse() {
+                    Ok(move_number) => view.replay.jump_to_move(move_number),
+                    Err(_) => view.replay.jump_to_end(),
+                }
+                view.pending_number.clear();
+            }
+            KeyCode::Char('n') | KeyCode::Right | KeyCode::Char(' ') => {
+                view.replay.step_forward();
+            }
+            KeyCode::Char('p') | KeyCode::Left => {
+                view.replay.step_back();
+            }
+            KeyCode::Home | KeyCode::Char('g') => view.replay.jump_to_start(),
+            KeyCode::End | KeyCode::Char('G') => view.replay.jump_to_end(),
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, view: &ReplayView) {
+    let [main_area, status_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [board_area, moves_area] =
+        Layout::horizontal([Constraint::Length(24), Constraint::Min(24)]).areas(main_area);
+
+    let replay = &view.replay;
+    let mut board: Vec<Line> = board_lines(replay.board(), view.perspective)
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    board.push(Line::from(""));
+    board.push(Line::from(format!("Ply {}/{}", replay.ply(), replay.len())));
+    board.push(Line::from(format!(
+        "Last: {}",
+        replay.last_move().unwrap_or("-")
+    )));
+    frame.render_widget(
+        Paragraph::new(board).block(Block::bordered().title(" Board ")),
+        board_area,
+    );
+
+    // Highlight the pair containing the current move
+    let current_line = replay.ply().checked_sub(1).map(|index| index / 2);
+    let moves: Vec<Line> = move_list_lines(replay.moves())
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if Some(i) == current_line {
+                Line::from(Span::styled(
+                    line,
+                    Style::default().add_modifier(Modifier::REVERSED),
+                ))
+            } else {
+                Line::from(line)
+            }
+        })
+        .collect();
+    let id_short = &view.game_id[..view.game_id.len().min(12)];
+    frame.render_widget(
+        Paragraph::new(moves)
+            .scroll((current_line.unwrap_or(0).saturating_sub(5) as u16, 0))
+            .block(Block::bordered().title(format!(" Moves — {id_short} "))),
+        moves_area,
+    );
+
+    let status = if view.pending_number.is_empty() {
+        "n/→ next  p/← previous  <number>j jump to move  j end  g start  q quit".to_string()
+    } else {
+        format!("Jump to move {} (press j)", view.pending_number)
+    };
+    frame.render_widget(Paragraph::new(status), status_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(moves: &[&str]) -> Replay {
+        let moves: Vec<String> = moves.iter().map(|m| m.to_string()).collect();
+        Replay::from_moves(&moves).unwrap()
+    }
+
+    #[test]
+    fn test_step_forward_and_back() {
+        let mut replay = replay(&["e2e4", "e7e5", "g1f3"]);
+        assert_eq!(replay.ply(), 0);
+        assert_eq!(replay.last_move(), None);
+        assert_eq!(replay.board().to_fen(), Board::new().to_fen());
+
+        assert!(replay.step_forward());
+        assert_eq!(replay.last_move(), Some("e2e4"));
+        assert_eq!(replay.board().active_color(), Color::Black);
+
+        assert!(replay.step_forward());
+        assert!(replay.step_forward());
+        assert!(!replay.step_forward());
+        assert_eq!(replay.ply(), 3);
+
+        assert!(replay.step_back());
+        assert_eq!(replay.last_move(), Some("e7e5"));
+        replay.jump_to_start();
+        assert!(!replay.step_back());
+    }
+
+    #[test]
+    fn test_jump_to_move() {
+        let mut replay = replay(&["e2e4", "e7e5", "g1f3", "b8c6"]);
+        replay.jump_to_move(2);
+        assert_eq!(replay.last_move(), Some("g1f3"));
+        replay.jump_to_move(0);
+        assert_eq!(replay.ply(), 0);
+        replay.jump_to_move(99);
+        assert_eq!(replay.ply(), 4);
+    }
+
+    #[test]
+    fn test_invalid_move_list_is_rejected() {
+        let moves = vec!["e2e4".to_string(), "e2e4".to_string()];
+        let err = Replay::from_moves(&moves).unwrap_err();
+        assert!(err.to_string().contains("move 2"));
+    }
+}
@@ -0,0 +1,771 @@
+use crate::chess::{Board, Color, Move as ChessMove, PieceType};
+use crate::crypto::Identity;
+use crate::messages::chess::{generate_game_id, hash_board_state, Move};
+use crate::messages::Message;
+use crate::network::connection::Connection;
+use crate::network::{Client, Server};
+use crate::storage::models::{GameResult, GameStatus, PlayerColor, TimeControl};
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use rand::seq::SliceRandom;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// Name of the built-in player in `--engines`
+pub const BUILTIN_ENGINE: &str = "builtin";
+
+/// Tag put on every game a self-play match stores
+pub const SELFPLAY_TAG: &str = "selfplay";
+
+/// Plies after which a game is called a draw
+const MAX_PLIES: usize = 400;
+
+/// Time an engine is given beyond its clock before it is stopped
+const MOVE_GRACE: Duration = Duration::from_secs(1);
+
+/// How long an engine has to answer `uci` and `isready`
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A match between two players, as given on the command line
+#[derive(Debug, Clone)]
+pub struct SelfplayOptions {
+    /// `builtin` or the command line of a UCI engine, for each player
+    pub engines: [String; 2],
+    /// Games to play; the players swap colors after each one
+    pub games: u32,
+    pub time_control: TimeControl,
+}
+
+impl Default for SelfplayOptions {
+    fn default() -> Self {
+        Self {
+            engines: [BUILTIN_ENGINE.to_string(), BUILTIN_ENGINE.to_string()],
+            games: 2,
+            time_control: TimeControl {
+                initial_time_ms: 60_000,
+                increment_ms: 0,
+            },
+        }
+    }
+}
+
+/// Parse a time control given as `minutes+increment`, e.g. `1+0` or `0.5+1`
+pub fn parse_time_control(text: &str) -> Result<TimeControl> {
+    let (minutes, increment) = text
+        .split_once('+')
+        .with_context(|| format!("Invalid time control '{text}', expected e.g. 1+0"))?;
+    let minutes: f64 = minutes
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid minutes in time control '{text}'"))?;
+    let increment: f64 = increment
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid increment in time control '{text}'"))?;
+    if !(minutes > 0.0 && increment >= 0.0) {
+        anyhow::bail!("Time control '{text}' needs a positive clock");
+    }
+    Ok(TimeControl {
+        initial_time_ms: (minutes * 60_000.0) as u64,
+        increment_ms: (increment * 1000.0) as u64,
+    })
+}
+
+/// Why a game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    Repetition,
+    FiftyMoves,
+    InsufficientMaterial,
+    MoveLimit,
+    Timeout,
+    IllegalMove,
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Termination::Checkmate => "checkmate",
+            Termination::Stalemate => "stalemate",
+            Termination::Repetition => "threefold repetition",
+            Termination::FiftyMoves => "fifty-move rule",
+            Termination::InsufficientMaterial => "insufficient material",
+            Termination::MoveLimit => "move limit",
+            Termination::Timeout => "time forfeit",
+            Termination::IllegalMove => "illegal move",
+        };
+        f.write_str(text)
+    }
+}
+
+/// One finished game of a match
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfplayGame {
+    pub game_id: String,
+    pub white: String,
+    pub black: String,
+    /// `1-0`, `0-1`, or `1/2-1/2`
+    pub result: &'static str,
+    pub termination: Termination,
+    pub plies: usize,
+}
+
+/// Score of one player over a match
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Standing {
+    pub engine: String,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Standing {
+    /// Points, one per win and a half per draw
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+}
+
+/// What a self-play match produced
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfplayReport {
+    pub games: Vec<SelfplayGame>,
+    pub standings: [Standing; 2],
+    /// Moves sent over the loopback connections
+    pub moves_sent: u64,
+    /// Mean time from sending a move to the server confirming it
+    pub avg_round_trip_ms: f64,
+    pub duration_secs: f64,
+}
+
+impl fmt::Display for SelfplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (number, game) in self.games.iter().enumerate() {
+            writeln!(
+                f,
+                "Game {:>3}  {} - {}  {:<7}  {} after {} plies  {}",
+                number + 1,
+                game.white,
+                game.black,
+                game.result,
+                game.termination,
+                game.plies,
+                &game.game_id[..8.min(game.game_id.len())]
+            )?;
+        }
+        writeln!(f)?;
+        let width = self
+            .standings
+            .iter()
+            .map(|standing| standing.engine.len())
+            .max()
+            .unwrap_or(0)
+            .max("Engine".len());
+        writeln!(
+            f,
+            "{:<width$}  {:>4}  {:>4}  {:>4}  {:>6}",
+            "Engine", "Won", "Drew", "Lost", "Points"
+        )?;
+        for standing in &self.standings {
+            writeln!(
+                f,
+                "{:<width$}  {:>4}  {:>4}  {:>4}  {:>6.1}",
+                standing.engine,
+                standing.wins,
+                standing.draws,
+                standing.losses,
+                standing.points()
+            )?;
+        }
+        write!(
+            f,
+            "\n{} moves over loopback in {:.1}s, {:.2} ms average round trip",
+            self.moves_sent, self.duration_secs, self.avg_round_trip_ms
+        )
+    }
+}
+
+/// A side in a self-play match
+enum Player {
+    /// Two-ply material search, choosing at random between equal moves
+    Builtin,
+    Uci(Box<UciEngine>),
+}
+
+impl Player {
+    async fn start(spec: &str) -> Result<Self> {
+        if spec == BUILTIN_ENGINE {
+            Ok(Player::Builtin)
+        } else {
+            Ok(Player::Uci(Box::new(UciEngine::start(spec).await?)))
+        }
+    }
+
+    async fn new_game(&mut self) -> Result<()> {
+        match self {
+            Player::Builtin => Ok(()),
+            Player::Uci(engine) => engine.new_game().await,
+        }
+    }
+
+    /// The move to play, or `None` if no move came within `limit`
+    async fn choose(
+        &mut self,
+        board: &Board,
+        moves: &[String],
+        clocks: &Clocks,
+        limit: Duration,
+    ) -> Result<Option<String>> {
+        match self {
+            Player::Builtin => Ok(builtin_move(board).map(|mv| mv.to_string())),
+            Player::Uci(engine) => engine.best_move(moves, clocks, limit).await,
+        }
+    }
+}
+
+/// Material balance for `color`, in pawns
+fn material(board: &Board, color: Color) -> i32 {
+    let mut balance = 0;
+    for rank in 0..8 {
+        for file in 0..8 {
+            let square = crate::chess::Position::new_unchecked(file, rank);
+            if let Some(piece) = board.get_piece(square) {
+                let value = piece.value() as i32;
+                balance += if piece.color == color { value } else { -value };
+            }
+        }
+    }
+    balance
+}
+
+/// Pick a move for the side to move by looking two plies ahead
+///
+/// Mates are played at once; otherwise the move keeping the most material
+/// after the opponent's best reply wins, and ties are broken at random so
+/// games between built-in players differ.
+pub fn builtin_move(board: &Board) -> Option<ChessMove> {
+    const MATE: i32 = 10_000;
+    let me = board.active_color();
+    let mut best = Vec::new();
+    let mut best_score = i32::MIN;
+
+    for mv in board.legal_moves() {
+        let mut after = board.clone();
+        if after.make_move(mv).is_err() {
+            continue;
+        }
+        let replies = after.legal_moves();
+        let score = if replies.is_empty() {
+            if after.is_checkmate() {
+                MATE
+            } else {
+                0
+            }
+        } else {
+            replies
+                .into_iter()
+                .filter_map(|reply| {
+                    let mut next = after.clone();
+                    next.make_move(reply).ok()?;
+                    Some(if next.is_checkmate() {
+                        -MATE
+                    } else {
+                        material(&next, me)
+                    })
+                })
+                .min()
+                .unwrap_or(0)
+        };
+        // Promote to a queen unless an underpromotion is strictly better
+        let score = score * 2 + i32::from(mv.promotion.is_none_or(|p| p == PieceType::Queen));
+        match score.cmp(&best_score) {
+            std::cmp::Ordering::Greater => {
+                best_score = score;
+                best = vec![mv];
+            }
+            std::cmp::Ordering::Equal => best.push(mv),
+            std::cmp::Ordering::Less => {}
+        }
+    }
+    best.choose(&mut rand::thread_rng()).copied()
+}
+
+/// A UCI engine running as a child process
+struct UciEngine {
+    name: String,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl UciEngine {
+    /// Start the engine at `command` (a program and its arguments) and
+    /// wait until it is ready
+    async fn start(command: &str) -> Result<Self> {
+        let mut words = command.split_whitespace();
+        let program = words.next().context("Empty engine command")?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start engine '{command}'"))?;
+        let stdin = child.stdin.take().context("Engine has no stdin")?;
+        let stdout = child.stdout.take().context("Engine has no stdout")?;
+        let mut engine = Self {
+            name: command.to_string(),
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        };
+
+        engine.send("uci").await?;
+        engine.wait_for("uciok", HANDSHAKE_TIMEOUT).await?;
+        engine.new_game().await?;
+        Ok(engine)
+    }
+
+    async fn new_game(&mut self) -> Result<()> {
+        self.send("ucinewgame").await?;
+        self.send("isready").await?;
+        self.wait_for("readyok", HANDSHAKE_TIMEOUT).await?;
+        Ok(())
+    }
+
+    async fn send(&mut self, line: &str) -> Result<()> {
+        debug!("{} < {}", self.name, line);
+        self.stdin
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .with_context(|| format!("Failed to write to engine '{}'", self.name))?;
+        self.stdin.flush().await?;
+        Ok(())
+    }
+
+    /// Read lines until one starts with `token`, returning it
+    async fn wait_for(&mut self, token: &str, limit: Duration) -> Result<Option<String>> {
+        let read = async {
+            while let Some(line) = self.stdout.next_line().await? {
+                debug!("{} > {}", self.name, line);
+                if line.split_whitespace().next() == Some(token) {
+                    return Ok(line);
+                }
+            }
+            anyhow::bail!("Engine '{}' exited", self.name)
+        };
+        match tokio::time::timeout(limit, read).await {
+            Ok(line) => line.map(Some),
+            Err(_) if token == "bestmove" => Ok(None),
+            Err(_) => anyhow::bail!("Engine '{}' did not answer '{token}'", self.name),
+        }
+    }
+
+    async fn best_move(
+        &mut self,
+        moves: &[String],
+        clocks: &Clocks,
+        limit: Duration,
+    ) -> Result<Option<String>> {
+        let position = if moves.is_empty() {
+            "position startpos".to_string()
+        } else {
+            format!("position startpos moves {}", moves.join(" ")).to_lowercase()
+        };
+        self.send(&position).await?;
+        self.send(&format!(
+            "go wtime {} btime {} winc {} binc {}",
+            clocks.remaining_ms[0],
+            clocks.remaining_ms[1],
+            clocks.increment_ms,
+            clocks.increment_ms
+        ))
+        .await?;
+        let Some(line) = self.wait_for("bestmove", limit).await? else {
+            return Ok(None);
+        };
+        Ok(line.split_whitespace().nth(1).map(str::to_string))
+    }
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        let _ = self.child.start_kill();
+    }
+}
+
+/// Remaining time for White and Black
+#[derive(Debug, Clone)]
+struct Clocks {
+    remaining_ms: [u64; 2],
+    increment_ms: u64,
+}
+
+impl Clocks {
+    fn new(time_control: &TimeControl) -> Self {
+        Self {
+            remaining_ms: [time_control.initial_time_ms; 2],
+            increment_ms: time_control.increment_ms,
+        }
+    }
+
+    fn remaining(&self, color: Color) -> Duration {
+        Duration::from_millis(self.remaining_ms[side(color)])
+    }
+
+    /// Charge `color` for `spent`; returns false if its flag fell
+    fn charge(&mut self, color: Color, spent: Duration) -> bool {
+        let remaining = &mut self.remaining_ms[side(color)];
+        let spent = spent.as_millis() as u64;
+        if spent > *remaining {
+            *remaining = 0;
+            return false;
+        }
+        *remaining = *remaining - spent + self.increment_ms;
+        true
+    }
+}
+
+fn side(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+/// A player's end of the loopback: its own server, and a connection to the
+/// other player's
+struct Seat {
+    identity: Arc<Identity>,
+    server: JoinHandle<Result<()>>,
+    address: String,
+}
+
+impl Seat {
+    async fn open() -> Result<Self> {
+        let identity = Arc::new(Identity::generate().context("Failed to create identity")?);
+        let server = Server::bind("127.0.0.1:0", Arc::clone(&identity)).await?;
+        let address = server.local_addr()?.to_string();
+        Ok(Self {
+            identity,
+            server: tokio::spawn(server.run()),
+            address,
+        })
+    }
+}
+
+impl Drop for Seat {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// A player's connection to the other player's server, opened again if
+/// the server closed it while the other player was thinking
+struct Link {
+    client: Client,
+    address: String,
+    connection: Option<Connection>,
+}
+
+impl Link {
+    fn new(identity: Arc<Identity>, address: &str) -> Self {
+        Self {
+            client: Client::new(identity),
+            address: address.to_string(),
+            connection: None,
+        }
+    }
+
+    async fn deliver(
+        &mut self,
+        game_id: &str,
+        chess_move: &str,
+        board: &Board,
+        traffic: &mut Traffic,
+    ) -> Result<()> {
+        if let Some(connection) = &mut self.connection {
+            match deliver(connection, game_id, chess_move, board, traffic).await {
+                Ok(()) => return Ok(()),
+                Err(e) => debug!("Reconnecting to {} after: {e:#}", self.address),
+            }
+        }
+        let connection = self
+            .connection
+            .insert(self.client.connect(&self.address).await?);
+        deliver(connection, game_id, chess_move, board, traffic).await
+    }
+
+    async fn close(&mut self) {
+        if let Some(mut connection) = self.connection.take() {
+            let _ = connection.close().await;
+        }
+    }
+}
+
+/// Round trips of the moves sent so far
+#[derive(Default)]
+struct Traffic {
+    moves: u64,
+    round_trip_total: Duration,
+}
+
+/// Send `chess_move` to the opponent's server, then a ping, and wait for
+/// the pong, which the server only sends once the move is handled
+async fn deliver(
+    connection: &mut Connection,
+    game_id: &str,
+    chess_move: &str,
+    board: &Board,
+    traffic: &mut Traffic,
+) -> Result<()> {
+    let message = Message::Move(Move::new(
+        game_id.to_string(),
+        chess_move.to_string(),
+        hash_board_state(board),
+    ));
+    message
+        .validate()
+        .map_err(|e| anyhow::anyhow!("Invalid move message: {e}"))?;
+
+    let sent = Instant::now();
+    connection.send_message(message).await?;
+    let nonce = traffic.moves;
+    connection
+        .send_message(Message::new_ping(nonce, game_id.to_string()))
+        .await?;
+    let (reply, _sender) = connection.receive_message().await?;
+    if !reply.is_ping() || reply.get_nonce() != nonce {
+        anyhow::bail!(
+            "Expected the echo of ping {nonce}, got {}",
+            reply.message_type()
+        );
+    }
+    traffic.moves += 1;
+    traffic.round_trip_total += sent.elapsed();
+    Ok(())
+}
+
+/// Play `options.games` games between the two engines over loopback
+/// connections, storing each in `database` tagged `selfplay`
+///
+/// Each player runs its own in-process server, as with `mate serve`, and
+/// every move travels to the opponent's server as a signed `Move` message
+/// before the next is asked for.
+pub async fn run_selfplay(
+    database: &Database,
+    options: &SelfplayOptions,
+) -> Result<SelfplayReport> {
+    if options.games == 0 {
+        anyhow::bail!("Play at least one game");
+    }
+    let start = Instant::now();
+    let mut players = [
+        Player::start(&options.engines[0]).await?,
+        Player::start(&options.engines[1]).await?,
+    ];
+    let seats = [Seat::open().await?, Seat::open().await?];
+    let mut links = [
+        Link::new(Arc::clone(&seats[0].identity), &seats[1].address),
+        Link::new(Arc::clone(&seats[1].identity), &seats[0].address),
+    ];
+
+    let mut traffic = Traffic::default();
+    let mut standings = [
+        Standing {
+            engine: options.engines[0].clone(),
+            ..Standing::default()
+        },
+        Standing {
+            engine: options.engines[1].clone(),
+            ..Standing::default()
+        },
+    ];
+    let mut games = Vec::new();
+
+    for number in 0..options.games as usize {
+        // Player 0 has White in even games
+        let white = number % 2;
+        let black = 1 - white;
+        let game = play_game(
+            database,
+            options,
+            [white, black],
+            &mut players,
+            &mut links,
+            &mut traffic,
+        )
+        .await
+        .with_context(|| format!("Game {} failed", number + 1))?;
+        info!(
+            "Self-play game {} finished {} by {}",
+            number + 1,
+            game.result,
+            game.termination
+        );
+
+        match game.result {
+            "1-0" => {
+                standings[white].wins += 1;
+                standings[black].losses += 1;
+            }
+            "0-1" => {
+                standings[black].wins += 1;
+                standings[white].losses += 1;
+            }
+            _ => {
+                standings[white].draws += 1;
+                standings[black].draws += 1;
+            }
+        }
+        games.push(game);
+    }
+
+    for link in &mut links {
+        link.close().await;
+    }
+
+    Ok(SelfplayReport {
+        games,
+        standings,
+        moves_sent: traffic.moves,
+        avg_round_trip_ms: if traffic.moves == 0 {
+            0.0
+        } else {
+            traffic.round_trip_total.as_secs_f64() * 1000.0 / traffic.moves as f64
+        },
+        duration_secs: start.elapsed().as_secs_f64(),
+    })
+}
+
+/// Play one game; `order` holds the indexes of the White and Black players
+async fn play_game(
+    database: &Database,
+    options: &SelfplayOptions,
+    order: [usize; 2],
+    players: &mut [Player; 2],
+    links: &mut [Link; 2],
+    traffic: &mut Traffic,
+) -> Result<SelfplayGame> {
+    let names = [
+        options.engines[order[0]].clone(),
+        options.engines[order[1]].clone(),
+    ];
+    let metadata = serde_json::json!({
+        "time_control": options.time_control,
+        "selfplay": { "white": names[0], "black": names[1] },
+    });
+    let record = database
+        .create_game(
+            format!("selfplay:{}", names[1]),
+            PlayerColor::White,
+            Some(metadata),
+        )
+        .context("Failed to create game record")?;
+    database.add_game_tag(&record.id, SELFPLAY_TAG)?;
+    database.update_game_status(&record.id, GameStatus::Active)?;
+    // Local game IDs are not the UUIDs the wire format requires
+    let wire_id = generate_game_id();
+
+    for &player in &order {
+        players[player].new_game().await?;
+    }
+
+    let mut board = Board::new();
+    let mut moves: Vec<String> = Vec::new();
+    let mut seen: HashMap<u64, u32> = HashMap::from([(board.zobrist_hash(), 1)]);
+    let mut clocks = Clocks::new(&options.time_control);
+
+    let (winner, termination) = loop {
+        let mover = board.active_color();
+        let player = order[side(mover)];
+
+        let thinking = Instant::now();
+        let limit = clocks.remaining(mover) + MOVE_GRACE;
+        let chosen = players[player]
+            .choose(&board, &moves, &clocks, limit)
+            .await?;
+        let in_time = clocks.charge(mover, thinking.elapsed());
+        let Some(chosen) = chosen.filter(|_| in_time) else {
+            break (Some(mover.opposite()), Termination::Timeout);
+        };
+        let Ok(mv) = board.parse_move(&chosen) else {
+            break (Some(mover.opposite()), Termination::IllegalMove);
+        };
+        board.make_move(mv)?;
+        let coordinate = mv.to_string().to_lowercase();
+
+        links[player]
+            .deliver(&wire_id, &coordinate, &board, traffic)
+            .await
+            .context("Failed to send the move over loopback")?;
+        database.store_message(
+            record.id.clone(),
+            "move".to_string(),
+            serde_json::to_string(&Move::new(
+                record.id.clone(),
+                coordinate.clone(),
+                hash_board_state(&board),
+            ))?,
+            String::new(),
+            format!("selfplay:{}", names[side(mover)]),
+        )?;
+        moves.push(coordinate);
+
+        let repetitions = seen.entry(board.zobrist_hash()).or_insert(0);
+        *repetitions += 1;
+        if board.is_checkmate() {
+            break (Some(mover), Termination::Checkmate);
+        } else if board.is_stalemate() {
+            break (None, Termination::Stalemate);
+        } else if *repetitions >= 3 {
+            break (None, Termination::Repetition);
+        } else if board.halfmove_clock() >= 100 {
+            break (None, Termination::FiftyMoves);
+        } else if only_kings(&board) {
+            break (None, Termination::InsufficientMaterial);
+        } else if moves.len() >= MAX_PLIES {
+            break (None, Termination::MoveLimit);
+        }
+    };
+
+    // Stored from White's side
+    let (result, stored) = match winner {
+        Some(Color::White) => ("1-0", GameResult::Win),
+        Some(Color::Black) => ("0-1", GameResult::Loss),
+        None => ("1/2-1/2", GameResult::Draw),
+    };
+    database.update_game_result(&record.id, stored)?;
+
+    Ok(SelfplayGame {
+        game_id: record.id,
+        white: names[0].clone(),
+        black: names[1].clone(),
+        result,
+        termination,
+        plies: moves.len(),
+    })
+}
+
+/// Whether only the two kings are left
+fn only_kings(board: &Board) -> bool {
+    (0..8).all(|rank| {
+        (0..8).all(|file| {
+            board
+                .get_piece(crate::chess::Position::new_unchecked(file, rank))
+                .is_none_or(|piece| piece.piece_type == PieceType::King)
+        })
+    })
+}
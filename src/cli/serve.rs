@@ -0,0 +1,438 @@
+use crate::api::{ApiServer, MetricsServer};
+use crate::cli::app::{App, Config};
+use crate::cli::auto_accept::{AutoAccept, AUTO_ACCEPT_INTERVAL};
+use crate::cli::devices;
+use crate::cli::network_manager::NetworkManager;
+use crate::cli::reminders::{Reminders, CHECK_INTERVAL as REMINDER_CHECK_INTERVAL};
+use crate::cli::usage::{UsageLog, UsageReporter, USAGE_REPORT_INTERVAL};
+use crate::cli::voting::{Voting, VOTE_CHECK_INTERVAL};
+use crate::crypto::Identity;
+use crate::network::known_peers::KNOWN_PEERS_FILE;
+use crate::network::port_mapping::{self, PortMapper, PortMapping};
+use crate::network::server::ACCEPT_HEARTBEAT_INTERVAL;
+use crate::network::watchdog::{stall_limit, INCIDENT_LOG};
+use crate::network::{Server, Watchdog};
+use crate::storage::{Database, DatabaseLocation};
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::signal;
+use tracing::{debug, error, info, warn};
+
+/// How `mate serve` was asked to run
+#[derive(Debug, Clone, Default)]
+pub struct ServeOptions {
+    /// Address to listen on, or [`port_mapping::AUTO_BIND`]; the configured
+    /// default bind address when unset
+    pub bind: Option<String>,
+    /// Address to also serve the HTTP API on
+    pub http: Option<String>,
+    pub db_path: Option<PathBuf>,
+    pub ephemeral: bool,
+}
+
+/// Run the server of `mate serve` until it stops or a shutdown signal arrives
+pub async fn run_serve(options: ServeOptions) -> Result<()> {
+    // Flags override the environment, which overrides the config file
+    let config = Config::load_or_default().context("Failed to load configuration")?;
+    let bind = options
+        .bind
+        .unwrap_or_else(|| config.default_bind_addr.clone());
+    let auto_bind = bind == port_mapping::AUTO_BIND;
+    let bind = match auto_bind {
+        true => port_mapping::AUTO_BIND_ADDR.to_string(),
+        false => bind,
+    };
+    info!("Starting server on {}", bind);
+    debug!("Server lifecycle: Initializing server components");
+
+    // The HTTP API works on the same identity and games as the server
+    let app = match &options.http {
+        Some(_) => Some(Arc::new(
+            App::new_with_storage(options.db_path.clone(), options.ephemeral).await?,
+        )),
+        None => None,
+    };
+
+    // Use secure storage for identity
+    let identity = match &app {
+        Some(app) => app.identity.clone(),
+        None => Arc::new(Identity::load_or_generate()?),
+    };
+    info!("Loaded identity: {}", identity.peer_id());
+    debug!("Server lifecycle: Identity loaded successfully");
+
+    // Open the database so the server can keep per-peer connection history
+    let peer_history = match &app {
+        Some(app) => app.database.clone(),
+        None => {
+            let database_location = if options.ephemeral {
+                DatabaseLocation::InMemory
+            } else {
+                let db_path = match options.db_path {
+                    Some(path) => path,
+                    None => config.effective_database_path()?,
+                };
+                DatabaseLocation::File(db_path)
+            };
+            Arc::new(
+                Database::open(identity.peer_id().as_str(), &database_location)
+                    .context("Failed to open database for peer history")?,
+            )
+        }
+    };
+    debug!("Server lifecycle: Peer history database opened");
+
+    // Linked devices are answered from the sync journal, so it starts
+    // out in step with the settings, address book, and games here
+    if !options.ephemeral
+        && !peer_history
+            .get_linked_devices()
+            .context("Failed to retrieve linked devices")?
+            .is_empty()
+    {
+        devices::apply_synced_settings(&peer_history)?;
+        devices::record_local_changes(&peer_history, identity.peer_id().as_str())?;
+    }
+
+    let api = match (options.http, app) {
+        (Some(http), Some(app)) => {
+            let token = match &app.config.api.token {
+                Some(token) => token.clone(),
+                None => {
+                    let token = crate::api::generate_token();
+                    println!("HTTP API token: {token}");
+                    token
+                }
+            };
+            let api = ApiServer::bind(&http, app, token).await?;
+            println!("HTTP API listening on http://{}", api.local_addr()?);
+            Some(api)
+        }
+        _ => None,
+    };
+    let api = async move {
+        match api {
+            Some(api) => api.run().await,
+            None => std::future::pending().await,
+        }
+    };
+
+    // Create and run server with graceful shutdown handling
+    let server = bind_server(
+        &bind,
+        Arc::clone(&identity),
+        &config,
+        Arc::clone(&peer_history),
+    )
+    .await?;
+
+    // Peers outside the local network need the router to forward the port
+    let local_addr = server.local_addr()?;
+    if auto_bind {
+        println!("Listening on {local_addr}");
+    }
+    let port_mapping = match auto_bind || config.network.port_mapping {
+        true => map_port(local_addr, &config, options.ephemeral).await?,
+        false => None,
+    };
+    let mapped = port_mapping.as_ref().map(|(_, mapping)| mapping.clone());
+    let mapping_dir = (!options.ephemeral).then(|| config.data_dir.clone());
+    let port_mapping = async move {
+        match port_mapping {
+            Some((mapper, mapping)) => mapper.run(mapping, mapping_dir).await,
+            None => std::future::pending().await,
+        }
+    };
+
+    let metrics = match config.metrics.enabled {
+        true => {
+            let metrics = MetricsServer::bind(&config.metrics.bind_addr, server.stats()).await?;
+            println!("Metrics listening on http://{}", metrics.local_addr()?);
+            Some(metrics)
+        }
+        false => None,
+    };
+    let metrics = async move {
+        match metrics {
+            Some(metrics) => metrics.run().await,
+            None => std::future::pending().await,
+        }
+    };
+
+    let watchdog = build_watchdog(server, identity, peer_history, &config, options.ephemeral)?;
+
+    info!("Server bound successfully, starting to accept connections...");
+    debug!("Server lifecycle: Server bound, installing signal handlers");
+
+    // Run server with graceful shutdown
+    tokio::select! {
+        result = api => {
+            if let Err(e) = result {
+                error!("HTTP API error: {:#}", e);
+            }
+        }
+        result = metrics => {
+            if let Err(e) = result {
+                error!("Metrics endpoint error: {:#}", e);
+            }
+        }
+        result = port_mapping => {
+            if let Err(e) = result {
+                error!("Port mapping renewal stopped: {:#}", e);
+            }
+        }
+        result = watchdog.run() => {
+            match result {
+                Ok(()) => {
+                    info!("Server shutdown complete");
+                    debug!("Server lifecycle: Server terminated normally");
+                }
+                Err(e) => {
+                    error!("Server error: {}", e);
+                    debug!("Server lifecycle: Server terminated with error");
+                }
+            }
+        }
+        _ = shutdown_signal() => {
+            info!("Server lifecycle: Shutdown signal received, terminating server...");
+            debug!("Server lifecycle: Graceful shutdown initiated");
+
+            // The accept loop and connections stop with the runtime once
+            // this returns and the process exits
+            info!("Server lifecycle: Graceful shutdown completed");
+        }
+    }
+
+    // Leave the router as it was found
+    if let Some(mapping) = mapped {
+        if let Err(e) = PortMapper::new(local_addr).unmap(&mapping).await {
+            warn!("Failed to remove port mapping: {:#}", e);
+        }
+        if !options.ephemeral {
+            if let Err(e) = PortMapping::remove(&config.data_dir) {
+                warn!("{:#}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Bind the server of `mate serve` with the peer history, notifications,
+/// webhooks, and email its configuration asks for
+async fn bind_server(
+    addr: &str,
+    identity: Arc<Identity>,
+    config: &Config,
+    peer_history: Arc<Database>,
+) -> Result<Server> {
+    let wire_config = config
+        .network
+        .wire_config(crate::messages::wire::WireConfig::for_server());
+    let server = Server::bind_with_config(addr, identity, wire_config)
+        .await?
+        .with_peer_history(peer_history)
+        .with_notifications(config.notifications.notifier())
+        .with_webhooks(config.webhooks.webhooks()?);
+    Ok(match config.email.notifier(&config.data_dir)? {
+        Some(email) => server.with_email(email),
+        None => server,
+    })
+}
+
+/// Ask the router to forward the port of `local_addr`, returning the mapper
+/// and mapping to renew, or `None` if the router would not
+async fn map_port(
+    local_addr: SocketAddr,
+    config: &Config,
+    ephemeral: bool,
+) -> Result<Option<(PortMapper, PortMapping)>> {
+    let mapper = PortMapper::new(local_addr).with_gateway(config.network.gateway);
+    match mapper.map().await {
+        Ok(mapping) => {
+            println!(
+                "Port {} forwarded with {}; share this address: {}",
+                local_addr.port(),
+                mapping.method.name(),
+                mapping.external_addr
+            );
+            if !ephemeral {
+                mapping.save(&config.data_dir)?;
+            }
+            Ok(Some((mapper, mapping)))
+        }
+        Err(e) => {
+            warn!("Port mapping failed: {:#}", e);
+            println!(
+                "Could not forward port {} on the router ({:#}); forward it by hand for peers outside your network",
+                local_addr.port(),
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// The watchdog running the accept loop of `server` and the background tasks
+/// `config` turns on
+///
+/// Storage and outgoing messages have no long-running task of their own to
+/// supervise: the database is written by whichever task changes it, and each
+/// connection's writer task ends with its connection, whose write timeout
+/// clears a stuck one.
+fn build_watchdog(
+    server: Server,
+    identity: Arc<Identity>,
+    peer_history: Arc<Database>,
+    config: &Config,
+    ephemeral: bool,
+) -> Result<Watchdog> {
+    // The accept loop and background tasks are restarted by the
+    // watchdog if they get stuck or stop
+    let mut watchdog = Watchdog::new();
+    if !ephemeral {
+        watchdog = watchdog.with_incident_log(config.data_dir.join(INCIDENT_LOG));
+    }
+    {
+        let stats = server.stats();
+        let addr = server.local_addr()?.to_string();
+        let identity = Arc::clone(&identity);
+        let config = config.clone();
+        let peer_history = Arc::clone(&peer_history);
+        let mut bound = Some(server);
+        watchdog = watchdog.supervise(
+            "accept loop",
+            Some(stall_limit(ACCEPT_HEARTBEAT_INTERVAL)),
+            move |heartbeat| {
+                let bound = bound.take();
+                let (addr, identity, config, peer_history, stats) = (
+                    addr.clone(),
+                    Arc::clone(&identity),
+                    config.clone(),
+                    Arc::clone(&peer_history),
+                    stats.clone(),
+                );
+                async move {
+                    // A restarted accept loop listens on the same port
+                    let server = match bound {
+                        Some(server) => server,
+                        None => bind_server(&addr, identity, &config, peer_history)
+                            .await?
+                            .with_stats(stats),
+                    };
+                    server.with_heartbeat(heartbeat).run().await
+                }
+            },
+        );
+    }
+    if config.reminders.enabled {
+        let database = Arc::clone(&peer_history);
+        let after = config.reminders.after();
+        let webhooks = config.webhooks.webhooks()?;
+        watchdog = watchdog.supervise(
+            "turn reminders",
+            Some(stall_limit(REMINDER_CHECK_INTERVAL)),
+            move |heartbeat| {
+                Reminders::new(Arc::clone(&database), after)
+                    .with_webhooks(webhooks.clone())
+                    .with_heartbeat(heartbeat)
+                    .run()
+            },
+        );
+    }
+
+    // Consultation games are moved by the daemon once each vote closes
+    {
+        let database = Arc::clone(&peer_history);
+        let identity = Arc::clone(&identity);
+        let network_config = config.network_config();
+        watchdog = watchdog.supervise(
+            "consultation voting",
+            Some(stall_limit(VOTE_CHECK_INTERVAL)),
+            move |heartbeat| {
+                Voting::new(
+                    Arc::clone(&database),
+                    Arc::clone(&identity),
+                    NetworkManager::with_config(Arc::clone(&identity), network_config.clone()),
+                )
+                .with_heartbeat(heartbeat)
+                .run()
+            },
+        );
+    }
+
+    // Invitations passing the [auto_accept] rules are answered by the daemon
+    if config.auto_accept.enabled {
+        let database = Arc::clone(&peer_history);
+        let identity = Arc::clone(&identity);
+        let network_config = config.network_config();
+        let settings = config.auto_accept.clone();
+        let known_peers = config.data_dir.join(KNOWN_PEERS_FILE);
+        watchdog = watchdog.supervise(
+            "auto-accept",
+            Some(stall_limit(AUTO_ACCEPT_INTERVAL)),
+            move |heartbeat| {
+                AutoAccept::new(
+                    Arc::clone(&database),
+                    Arc::clone(&identity),
+                    NetworkManager::with_config(Arc::clone(&identity), network_config.clone()),
+                    settings.clone(),
+                )
+                .with_known_peers(known_peers.clone())
+                .with_heartbeat(heartbeat)
+                .run()
+            },
+        );
+    }
+
+    // Opted-in usage counts are reported once a day
+    match config.telemetry.usage_endpoint()? {
+        Some(endpoint) if !ephemeral => {
+            let database = Arc::clone(&peer_history);
+            let data_dir = config.data_dir.clone();
+            watchdog = watchdog.supervise(
+                "usage reports",
+                Some(stall_limit(USAGE_REPORT_INTERVAL)),
+                move |heartbeat| {
+                    UsageReporter::new(
+                        Arc::clone(&database),
+                        UsageLog::in_dir(&data_dir),
+                        endpoint.clone(),
+                    )
+                    .with_heartbeat(heartbeat)
+                    .run()
+                },
+            );
+        }
+        _ => {}
+    }
+    Ok(watchdog)
+}
+
+/// Wait for Ctrl+C or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+        info!("Received Ctrl+C signal, initiating graceful shutdown...");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+        info!("Received SIGTERM signal, initiating graceful shutdown...");
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
@@ -0,0 +1,477 @@
+use crate::cli::commands::NetworkOptions;
+use crate::cli::display::{print_json_line, ConnectReplyJson};
+use crate::cli::line_editor::{LineEditor, Suggestions};
+use crate::cli::network_manager::NetworkConfig;
+use crate::messages::chess::ServerAnnouncement;
+use crate::messages::Message;
+use crate::network::{Client, Connection};
+use anyhow::{Context, Result};
+use std::io::BufRead;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+
+/// Commands offered by tab completion in the interactive chat session
+pub const CHAT_COMMANDS: [&str; 4] = ["help", "info", "quit", "exit"];
+
+/// A line of input entered during a chat session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatCommand {
+    Help,
+    Info,
+    Quit,
+    /// Text to send to the peer
+    Send(String),
+}
+
+impl ChatCommand {
+    /// Parse a line of input, returning `None` for blank lines
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let command = match line {
+            "" => return None,
+            "help" => ChatCommand::Help,
+            "info" => ChatCommand::Info,
+            "quit" | "exit" => ChatCommand::Quit,
+            _ => ChatCommand::Send(line.to_string()),
+        };
+        Some(command)
+    }
+}
+
+/// The peer's reply to one message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Echo {
+    pub payload: String,
+    pub round_trip: Duration,
+    /// Whether the connection had to be re-established to deliver the message
+    pub reconnected: bool,
+}
+
+/// Counters shown by `info` and at the end of a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionStats {
+    pub peer_id: String,
+    pub duration: Duration,
+    /// Messages the peer replied to
+    pub messages_sent: u32,
+    pub average_round_trip: Option<Duration>,
+}
+
+/// What the frontend should show after a line of input
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatEvent {
+    Help,
+    Info(SessionStats),
+    Echo(Echo),
+    /// The reply was lost and the connection re-established; the message
+    /// was not resent
+    Reconnected,
+    Quit,
+}
+
+/// A connection to a peer that messages are exchanged over, reconnecting
+/// when it is lost
+///
+/// Holds no terminal state, so the CLI, the TUI, and tests can all drive a
+/// session; [`CommandRouter`] turns lines of user input into calls on it.
+pub struct ChatSession {
+    client: Client,
+    address: String,
+    network: NetworkOptions,
+    connection: Connection,
+    started: Instant,
+    messages_sent: u32,
+    total_round_trip: Duration,
+}
+
+impl ChatSession {
+    /// Connect to `address`, honouring any `--timeout` and `--retries` given
+    pub async fn connect(client: Client, address: &str, network: NetworkOptions) -> Result<Self> {
+        let connection = connect_with_options(&client, address, network).await?;
+        Ok(Self {
+            client,
+            address: address.to_string(),
+            network,
+            connection,
+            started: Instant::now(),
+            messages_sent: 0,
+            total_round_trip: Duration::ZERO,
+        })
+    }
+
+    /// Peer ID the current connection authenticated
+    pub fn peer_id(&self) -> &str {
+        self.connection.peer_identity().unwrap_or("unknown")
+    }
+
+    /// The current connection to the peer
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Announcements the server sent since the last call, oldest first
+    pub fn take_announcements(&mut self) -> Vec<ServerAnnouncement> {
+        self.connection
+            .take_announcements()
+            .into_iter()
+            .map(|(announcement, _sender)| announcement)
+            .collect()
+    }
+
+    /// Counters for the session so far
+    pub fn stats(&self) -> SessionStats {
+        SessionStats {
+            peer_id: self.peer_id().to_string(),
+            duration: self.started.elapsed(),
+            messages_sent: self.messages_sent,
+            average_round_trip: (self.messages_sent > 0)
+                .then(|| self.total_round_trip / self.messages_sent),
+        }
+    }
+
+    /// Send `text` to the peer once and wait for its reply
+    pub async fn exchange(&mut self, text: &str) -> Result<Echo> {
+        let start_time = Instant::now();
+        let ping = Message::new_ping(rand::random::<u64>(), text.to_string());
+        self.connection
+            .send_message(ping)
+            .await
+            .context("Failed to send message")?;
+        let (response, _sender) = self
+            .connection
+            .receive_message()
+            .await
+            .context("Failed to receive response")?;
+
+        let round_trip = start_time.elapsed();
+        self.messages_sent += 1;
+        self.total_round_trip += round_trip;
+        Ok(Echo {
+            payload: response.get_payload().to_string(),
+            round_trip,
+            reconnected: false,
+        })
+    }
+
+    /// Send `text` to the peer, reconnecting if the connection was lost
+    ///
+    /// A message that could not be sent is resent once after reconnecting;
+    /// one whose reply was lost is not, since the peer may have seen it.
+    /// Fails when the peer cannot be reached again.
+    pub async fn send(&mut self, text: &str) -> Result<ChatEvent> {
+        let start_time = Instant::now();
+        let ping = Message::new_ping(rand::random::<u64>(), text.to_string());
+
+        if let Err(e) = self.connection.send_message(ping).await {
+            error!("Connection error: Failed to send message: {}", e);
+            warn!("The connection to the peer may have been lost.");
+            self.reconnect().await?;
+            let echo = self
+                .exchange(text)
+                .await
+                .context("Still failed after reconnect")?;
+            return Ok(ChatEvent::Echo(Echo {
+                reconnected: true,
+                ..echo
+            }));
+        }
+
+        match self.connection.receive_message().await {
+            Ok((response, _sender)) => {
+                let round_trip = start_time.elapsed();
+                self.messages_sent += 1;
+                self.total_round_trip += round_trip;
+                Ok(ChatEvent::Echo(Echo {
+                    payload: response.get_payload().to_string(),
+                    round_trip,
+                    reconnected: false,
+                }))
+            }
+            Err(e) => {
+                error!("Connection error: Failed to receive response: {}", e);
+                warn!("The connection to the peer may have been lost.");
+                self.reconnect().await?;
+                Ok(ChatEvent::Reconnected)
+            }
+        }
+    }
+
+    /// Replace the connection with a new one to the same address
+    pub async fn reconnect(&mut self) -> Result<()> {
+        let connection = connect_with_options(&self.client, &self.address, self.network)
+            .await
+            .context("Failed to reconnect")?;
+        self.connection = connection;
+        info!("Reconnected to peer: {}", self.peer_id());
+        Ok(())
+    }
+
+    /// Send every line of `input` to the peer, passing each reply to `emit`
+    ///
+    /// A lost connection is re-established before the next message. Returns
+    /// the number of messages that did not get a reply.
+    pub async fn run_batch(
+        &mut self,
+        input: impl BufRead,
+        mut emit: impl FnMut(&ConnectReplyJson),
+    ) -> usize {
+        let mut failures = 0;
+        let mut connected = true;
+
+        for (index, line) in input.lines().enumerate() {
+            let seq = index + 1;
+            let text = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    error!("Failed to read input: {}", e);
+                    failures += 1;
+                    break;
+                }
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            if !connected {
+                match self.reconnect().await {
+                    Ok(()) => connected = true,
+                    Err(e) => {
+                        failures += 1;
+                        emit(&ConnectReplyJson::failure(seq, text, format!("{e:#}")));
+                        continue;
+                    }
+                }
+            }
+
+            let reply = match self.exchange(&text).await {
+                Ok(echo) => ConnectReplyJson::success(seq, text, echo.payload, echo.round_trip),
+                Err(e) => ConnectReplyJson::failure(seq, text, format!("{e:#}")),
+            };
+
+            if !reply.ok {
+                warn!("The connection to the peer may have been lost");
+                failures += 1;
+                connected = false;
+            }
+            emit(&reply);
+        }
+
+        failures
+    }
+
+    /// Close the connection to the peer
+    pub async fn close(&mut self) {
+        if let Err(e) = self.connection.close().await {
+            warn!("Failed to close connection cleanly: {}", e);
+        }
+    }
+}
+
+/// Routes lines of user input to a [`ChatSession`]
+pub struct CommandRouter {
+    session: ChatSession,
+}
+
+impl CommandRouter {
+    pub fn new(session: ChatSession) -> Self {
+        Self { session }
+    }
+
+    pub fn session(&self) -> &ChatSession {
+        &self.session
+    }
+
+    pub fn session_mut(&mut self) -> &mut ChatSession {
+        &mut self.session
+    }
+
+    pub fn into_session(self) -> ChatSession {
+        self.session
+    }
+
+    /// Act on a line of input, returning `None` for blank lines
+    pub async fn handle_line(&mut self, line: &str) -> Result<Option<ChatEvent>> {
+        let Some(command) = ChatCommand::parse(line) else {
+            return Ok(None);
+        };
+        let event = match command {
+            ChatCommand::Help => ChatEvent::Help,
+            ChatCommand::Info => ChatEvent::Info(self.session.stats()),
+            ChatCommand::Quit => ChatEvent::Quit,
+            ChatCommand::Send(text) => self.session.send(&text).await?,
+        };
+        Ok(Some(event))
+    }
+}
+
+/// Connect to `address`, honouring any `--timeout` and `--retries` given
+pub async fn connect_with_options(
+    client: &Client,
+    address: &str,
+    network: NetworkOptions,
+) -> Result<Connection> {
+    let connecting = async {
+        match network.apply(NetworkConfig::default()).retry_config {
+            Some(retry_config) => {
+                client
+                    .connect_with_retry_config(address, &retry_config)
+                    .await
+            }
+            None => client.connect(address).await,
+        }
+    };
+    match network.timeout {
+        Some(secs) => tokio::time::timeout(Duration::from_secs(secs), connecting)
+            .await
+            .unwrap_or_else(|_| {
+                Err(anyhow::anyhow!(
+                    "Timed out connecting to {address} after {secs}s"
+                ))
+            }),
+        None => connecting.await,
+    }
+}
+
+/// Run an interactive chat session on the terminal until the user quits
+pub async fn run_terminal(router: &mut CommandRouter) -> Result<()> {
+    println!("=== MATE Chat Session ===");
+    println!("Connected to peer: {}", router.session().peer_id());
+    println!("Connection status: Active");
+    println!();
+    println!("Available commands:");
+    print_commands();
+    println!();
+    println!("Type messages and press Enter to send. Press Ctrl+C or Ctrl+D to exit.");
+    println!("{}", "=".repeat(30));
+
+    let mut editor = LineEditor::new(Arc::new(Mutex::new(Suggestions::for_commands(
+        &CHAT_COMMANDS,
+        None,
+    ))))?;
+
+    loop {
+        // Read user input with history and command completion
+        let input = match editor.read_line("mate> ") {
+            Ok(Some(input)) => input,
+            Ok(None) => {
+                // EOF (Ctrl+D) or Ctrl+C
+                println!(); // New line after Ctrl+D
+                break;
+            }
+            Err(e) => {
+                error!("Failed to read input: {}", e);
+                break;
+            }
+        };
+
+        let event = router.handle_line(&input).await;
+        print_announcements(router.session_mut());
+        match event {
+            Ok(None) => {}
+            Ok(Some(ChatEvent::Help)) => {
+                println!("=== Available Commands ===");
+                print_commands();
+                println!();
+                println!("Any other text will be sent as a message to the peer.");
+            }
+            Ok(Some(ChatEvent::Info(stats))) => {
+                println!("=== Connection Information ===");
+                println!("Peer ID: {}", stats.peer_id);
+                println!("Connection status: Active");
+                println!(
+                    "Session duration: {}",
+                    format_round_trip_time(stats.duration)
+                );
+                println!("Messages sent: {}", stats.messages_sent);
+                if let Some(average) = stats.average_round_trip {
+                    println!(
+                        "Average round-trip time: {}",
+                        format_round_trip_time(average)
+                    );
+                }
+            }
+            Ok(Some(ChatEvent::Echo(echo))) => {
+                if echo.reconnected {
+                    print_reconnected();
+                }
+                println!(
+                    "← Received echo: \"{}\" (round-trip: {})",
+                    echo.payload,
+                    format_round_trip_time(echo.round_trip)
+                );
+            }
+            Ok(Some(ChatEvent::Reconnected)) => print_reconnected(),
+            Ok(Some(ChatEvent::Quit)) => break,
+            Err(e) => {
+                error!("{:#}", e);
+                println!("Connection status: Disconnected");
+                println!("Reconnection failed. Please restart the session.");
+                break;
+            }
+        }
+    }
+
+    // Display session summary
+    let stats = router.session().stats();
+    println!();
+    println!("=== Session Summary ===");
+    println!(
+        "Session duration: {}",
+        format_round_trip_time(stats.duration)
+    );
+    match stats.average_round_trip {
+        Some(average) => {
+            println!("Messages sent: {}", stats.messages_sent);
+            println!(
+                "Average round-trip time: {}",
+                format_round_trip_time(average)
+            );
+        }
+        None => println!("No messages sent during this session"),
+    }
+    println!("Goodbye!");
+    Ok(())
+}
+
+fn print_commands() {
+    println!("  help    - Show this help message");
+    println!("  info    - Show connection information");
+    println!("  quit    - Exit the chat session");
+    println!("  exit    - Exit the chat session");
+}
+
+fn print_announcements(session: &mut ChatSession) {
+    for announcement in session.take_announcements() {
+        println!("📢 Server announcement: {}", announcement.text);
+    }
+}
+
+fn print_reconnected() {
+    println!("Connection status: Disconnected");
+    println!("Attempting to reconnect...");
+    println!("Connection status: Reconnected");
+}
+
+/// Send each reply to stdout as a JSON line
+pub fn print_reply(reply: &ConnectReplyJson) {
+    if let Err(e) = print_json_line(reply) {
+        error!("Failed to write reply: {}", e);
+    }
+}
+
+/// Format round-trip time for display with appropriate precision
+pub fn format_round_trip_time(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    let micros = duration.as_micros();
+
+    if millis == 0 {
+        format!("{micros}μs")
+    } else if millis < 1000 {
+        format!("{millis}ms")
+    } else {
+        let seconds = duration.as_secs_f64();
+        format!("{seconds:.2}s")
+    }
+}
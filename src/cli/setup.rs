@@ -0,0 +1,244 @@
+use crate::cli::app::Config;
+use crate::cli::display::{Theme, NO_THEME};
+use crate::crypto::storage::active_key_path_in;
+use crate::crypto::Identity;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Scheme prefix of a shareable connection string
+const CONNECTION_SCHEME: &str = "mate://";
+
+/// Shareable `mate://<peer_id>@<host:port>` address of a player
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionString {
+    pub peer_id: String,
+    pub address: String,
+}
+
+impl ConnectionString {
+    pub fn new(peer_id: impl Into<String>, address: impl Into<String>) -> Self {
+        Self {
+            peer_id: peer_id.into(),
+            address: address.into(),
+        }
+    }
+
+    /// Whether the host is a wildcard address that opponents cannot dial
+    pub fn has_unspecified_host(&self) -> bool {
+        self.address
+            .parse::<SocketAddr>()
+            .is_ok_and(|addr| addr.ip().is_unspecified())
+    }
+}
+
+impl fmt::Display for ConnectionString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{CONNECTION_SCHEME}{}@{}", self.peer_id, self.address)
+    }
+}
+
+impl FromStr for ConnectionString {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let rest = s
+            .trim()
+            .strip_prefix(CONNECTION_SCHEME)
+            .with_context(|| format!("Connection string must start with '{CONNECTION_SCHEME}'"))?;
+        match rest.split_once('@') {
+            Some((peer_id, address)) if !peer_id.is_empty() && !address.is_empty() => {
+                Ok(Self::new(peer_id, address))
+            }
+            _ => anyhow::bail!(
+                "Connection string must look like {CONNECTION_SCHEME}<peer_id>@<host:port>"
+            ),
+        }
+    }
+}
+
+/// The network address in `input`, which may be a plain `host:port` or a connection string
+pub fn resolve_address(input: &str) -> String {
+    input
+        .parse::<ConnectionString>()
+        .map(|connection| connection.address)
+        .unwrap_or_else(|_| input.to_string())
+}
+
+/// Whether mate has never been set up: no configuration file has been written yet
+pub fn is_first_run() -> bool {
+    Config::default_config_file().is_ok_and(|path| !path.exists())
+}
+
+/// What the wizard configured
+pub struct SetupSummary {
+    pub config: Config,
+    pub peer_id: String,
+    pub connection_string: ConnectionString,
+}
+
+/// Asks the onboarding questions, one answer per input line
+///
+/// An empty answer (or end of input) accepts the default shown in brackets.
+pub struct SetupWizard<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> SetupWizard<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    /// Walk through identity, data directory, bind address, and display preferences
+    ///
+    /// The identity is written to the chosen data directory; the returned
+    /// configuration is not saved.
+    pub fn run(&mut self, mut config: Config) -> Result<SetupSummary> {
+        writeln!(
+            self.output,
+            "Let's set up mate. Press Enter to accept a default."
+        )?;
+        writeln!(self.output)?;
+
+        let default_data_dir = config.data_dir.clone();
+        let data_dir =
+            PathBuf::from(self.ask("Data directory", &default_data_dir.display().to_string())?);
+        if data_dir != default_data_dir {
+            // Keep the database next to the identity in the chosen directory
+            config.db_path = Some(data_dir.join("database.sqlite"));
+        }
+        config.data_dir = data_dir;
+
+        let identity = self.setup_identity(&config.data_dir)?;
+        let peer_id = identity.peer_id().to_string();
+
+        config.default_bind_addr = loop {
+            let answer = self.ask(
+                "Address to listen on for opponents",
+                &config.default_bind_addr,
+            )?;
+            match answer.parse::<SocketAddr>() {
+                Ok(_) => break answer,
+                Err(_) => writeln!(self.output, "  Enter an address like 0.0.0.0:8080")?,
+            }
+        };
+
+        let unicode = self.confirm(
+            "Draw pieces with Unicode symbols?",
+            config.display.use_unicode(),
+        )?;
+        config.display.unicode = Some(unicode);
+
+        let theme_names: Vec<&str> = Theme::names().collect();
+        let current_theme = config.display.theme.clone().unwrap_or(NO_THEME.to_string());
+        config.display.theme = loop {
+            let answer = self.ask(
+                &format!("Board color theme ({})", theme_names.join(", ")),
+                &current_theme,
+            )?;
+            if answer.eq_ignore_ascii_case(NO_THEME) {
+                break None;
+            }
+            match Theme::by_name(&answer) {
+                Some(theme) => break Some(theme.name.to_string()),
+                None => writeln!(self.output, "  Unknown theme '{answer}'")?,
+            }
+        };
+
+        let connection_string = ConnectionString::new(&peer_id, &config.default_bind_addr);
+        Ok(SetupSummary {
+            config,
+            peer_id,
+            connection_string,
+        })
+    }
+
+    /// Keep or replace the identity in the data directory, generating one if missing
+    fn setup_identity(&mut self, data_dir: &std::path::Path) -> Result<Identity> {
+        let identity_path = active_key_path_in(data_dir);
+
+        if identity_path.exists() {
+            if let Ok(existing) = Identity::from_storage_path(&identity_path) {
+                let keep = self.confirm(
+                    &format!("Keep your existing identity {}?", existing.peer_id()),
+                    true,
+                )?;
+                if keep {
+                    return Ok(existing);
+                }
+            }
+        }
+
+        let identity = Identity::generate().context("Failed to generate identity")?;
+        identity
+            .save_to_storage_path(&identity_path)
+            .context("Failed to save identity")?;
+        writeln!(self.output, "  Generated identity {}", identity.peer_id())?;
+        Ok(identity)
+    }
+
+    fn ask(&mut self, question: &str, default: &str) -> Result<String> {
+        Ok(self
+            .read_answer(&format!("{question} [{default}]: "))?
+            .unwrap_or_else(|| default.to_string()))
+    }
+
+    fn confirm(&mut self, question: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        loop {
+            let Some(answer) = self.read_answer(&format!("{question} [{hint}]: "))? else {
+                return Ok(default);
+            };
+            match answer.to_lowercase().as_str() {
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => writeln!(self.output, "  Please answer y or n")?,
+            }
+        }
+    }
+
+    /// Print a prompt and read one trimmed answer, or `None` if it was left empty
+    fn read_answer(&mut self, prompt: &str) -> Result<Option<String>> {
+        write!(self.output, "{prompt}")?;
+        self.output.flush()?;
+
+        let mut answer = String::new();
+        self.input
+            .read_line(&mut answer)
+            .context("Failed to read answer")?;
+        let answer = answer.trim();
+        Ok((!answer.is_empty()).then(|| answer.to_string()))
+    }
+}
+
+/// Whether the first-run wizard can ask questions: both stdin and stdout are terminals
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Run the wizard on the terminal, save the configuration, and print the connection string
+pub fn run_interactive() -> Result<()> {
+    let stdin = io::stdin();
+    let config = Config::load_or_default().context("Failed to load configuration")?;
+    let summary = SetupWizard::new(stdin.lock(), io::stdout()).run(config)?;
+
+    summary
+        .config
+        .save()
+        .context("Failed to save configuration")?;
+
+    println!();
+    println!("✓ Saved {}", Config::default_config_file()?.display());
+    println!("Your peer ID: {}", summary.peer_id);
+    println!("Share this with opponents:");
+    println!("  {}", summary.connection_string);
+    if summary.connection_string.has_unspecified_host() {
+        println!("  (replace the host with an address they can reach, e.g. your LAN IP)");
+    }
+    println!("Start listening with 'mate serve'.");
+    Ok(())
+}
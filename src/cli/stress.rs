@@ -0,0 +1,311 @@
+use crate::crypto::Identity;
+use crate::messages::chess::Move;
+use crate::messages::Message;
+use crate::network::{Client, Server};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+/// Exchanges a client makes on one connection before reconnecting, so
+/// connections keep being opened and closed throughout a run
+const EXCHANGES_PER_CONNECTION: u32 = 50;
+
+/// Latencies from the first and the last fifth of a run are compared
+const WINDOWS: u32 = 5;
+
+/// How long the in-process server is given to close its connections once
+/// the clients are done
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A load test: simulated clients sending moves to a server
+#[derive(Debug, Clone)]
+pub struct StressOptions {
+    /// Simulated clients, each playing its own game
+    pub clients: usize,
+    pub duration: Duration,
+    /// Pause between a client's moves
+    pub interval: Duration,
+    /// Server to load; one is started in-process when unset
+    pub address: Option<String>,
+}
+
+impl Default for StressOptions {
+    fn default() -> Self {
+        Self {
+            clients: 32,
+            duration: Duration::from_secs(60),
+            interval: Duration::from_millis(50),
+            address: None,
+        }
+    }
+}
+
+/// Round-trip latencies over part of a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    fn from_samples(mut latencies: Vec<Duration>) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+        latencies.sort();
+        let percentile = |p: usize| {
+            let index = (latencies.len() * p / 100).min(latencies.len() - 1);
+            latencies[index].as_secs_f64() * 1000.0
+        };
+        Self {
+            samples: latencies.len(),
+            p50_ms: percentile(50),
+            p99_ms: percentile(99),
+            max_ms: percentile(100),
+        }
+    }
+}
+
+/// What a stress run measured
+///
+/// Descriptor and memory figures are read from `/proc` and are `None` on
+/// other platforms.
+#[derive(Debug, Clone, Serialize)]
+pub struct StressReport {
+    pub clients: usize,
+    pub duration_secs: f64,
+    /// Moves acknowledged by the server
+    pub exchanges: u64,
+    /// Connections the clients opened
+    pub connections: u64,
+    pub errors: u64,
+    /// The first error, to tell what went wrong
+    pub first_error: Option<String>,
+    pub first_window: LatencySummary,
+    pub last_window: LatencySummary,
+    pub open_fds_before: Option<usize>,
+    pub open_fds_after: Option<usize>,
+    pub rss_before_bytes: Option<u64>,
+    pub rss_after_bytes: Option<u64>,
+    /// Connections the in-process server still had open once the clients
+    /// were done
+    pub server_open_connections: Option<usize>,
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        writeln!(
+            f,
+            "{} clients for {:.0}s: {} moves over {} connections, {} errors",
+            self.clients, self.duration_secs, self.exchanges, self.connections, self.errors
+        )?;
+        if let Some(error) = &self.first_error {
+            writeln!(f, "  First error: {error}")?;
+        }
+        for (name, window) in [("start", &self.first_window), ("end", &self.last_window)] {
+            writeln!(
+                f,
+                "  Round trip at the {name}: p50 {:.1} ms, p99 {:.1} ms, max {:.1} ms ({} samples)",
+                window.p50_ms, window.p99_ms, window.max_ms, window.samples
+            )?;
+        }
+        writeln!(
+            f,
+            "  Open descriptors: {} before, {} after",
+            or_dash(self.open_fds_before.map(|n| n.to_string())),
+            or_dash(self.open_fds_after.map(|n| n.to_string()))
+        )?;
+        writeln!(
+            f,
+            "  Resident memory: {} before, {} after",
+            or_dash(self.rss_before_bytes.map(format_mib)),
+            or_dash(self.rss_after_bytes.map(format_mib))
+        )?;
+        write!(
+            f,
+            "  Server connections left open: {}",
+            or_dash(self.server_open_connections.map(|n| n.to_string()))
+        )
+    }
+}
+
+fn format_mib(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// One client's share of the results
+#[derive(Default)]
+struct ClientOutcome {
+    /// Time since the start of the run and round trip of each move
+    samples: Vec<(Duration, Duration)>,
+    connections: u64,
+    errors: u64,
+    first_error: Option<String>,
+}
+
+impl ClientOutcome {
+    fn record_error(&mut self, error: impl fmt::Display) {
+        self.errors += 1;
+        self.first_error.get_or_insert_with(|| error.to_string());
+    }
+}
+
+/// Run simulated clients against a server for `options.duration`
+///
+/// Each client sends a move followed by a ping on one connection and waits
+/// for the pong, which the server only sends once the move is handled;
+/// every few dozen moves it reconnects.
+pub async fn run_stress(options: &StressOptions) -> Result<StressReport> {
+    let open_fds_before = open_fds();
+    let rss_before_bytes = resident_memory();
+
+    let (address, server) = match &options.address {
+        Some(address) => (address.clone(), None),
+        None => {
+            let identity = Arc::new(Identity::generate().context("Failed to create identity")?);
+            let server = Server::bind("127.0.0.1:0", identity).await?;
+            let address = server.local_addr()?.to_string();
+            let stats = server.stats();
+            (address, Some((stats, tokio::spawn(server.run()))))
+        }
+    };
+
+    let start = Instant::now();
+    let mut clients = JoinSet::new();
+    for client in 0..options.clients {
+        let address = address.clone();
+        let options = options.clone();
+        clients.spawn(async move { run_client(client, &address, &options, start).await });
+    }
+
+    let mut outcomes = Vec::new();
+    while let Some(outcome) = clients.join_next().await {
+        outcomes.push(outcome.context("Simulated client panicked")?);
+    }
+    let duration = start.elapsed();
+
+    let server_open_connections = match server {
+        Some((stats, task)) => {
+            let drained = Instant::now();
+            while stats.snapshot().open_connections > 0 && drained.elapsed() < DRAIN_TIMEOUT {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            task.abort();
+            let _ = task.await;
+            Some(stats.snapshot().open_connections)
+        }
+        None => None,
+    };
+
+    let window = options.duration / WINDOWS;
+    let last_window_start = options.duration.saturating_sub(window);
+    let mut first = Vec::new();
+    let mut last = Vec::new();
+    for (at, latency) in outcomes.iter().flat_map(|outcome| &outcome.samples) {
+        if *at < window {
+            first.push(*latency);
+        } else if *at >= last_window_start {
+            last.push(*latency);
+        }
+    }
+
+    Ok(StressReport {
+        clients: options.clients,
+        duration_secs: duration.as_secs_f64(),
+        exchanges: outcomes.iter().map(|o| o.samples.len() as u64).sum(),
+        connections: outcomes.iter().map(|o| o.connections).sum(),
+        errors: outcomes.iter().map(|o| o.errors).sum(),
+        first_error: outcomes.iter().find_map(|o| o.first_error.clone()),
+        first_window: LatencySummary::from_samples(first),
+        last_window: LatencySummary::from_samples(last),
+        open_fds_before,
+        open_fds_after: open_fds(),
+        rss_before_bytes,
+        rss_after_bytes: resident_memory(),
+        server_open_connections,
+    })
+}
+
+async fn run_client(
+    client: usize,
+    address: &str,
+    options: &StressOptions,
+    start: Instant,
+) -> ClientOutcome {
+    let mut outcome = ClientOutcome::default();
+    let identity = match Identity::generate() {
+        Ok(identity) => Arc::new(identity),
+        Err(e) => {
+            outcome.record_error(e);
+            return outcome;
+        }
+    };
+    let game_id = format!("stress-{client}");
+    let deadline = start + options.duration;
+
+    while Instant::now() < deadline {
+        let mut connection = match Client::new(Arc::clone(&identity)).connect(address).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                outcome.record_error(format!("{e:#}"));
+                tokio::time::sleep(options.interval).await;
+                continue;
+            }
+        };
+        outcome.connections += 1;
+
+        for exchange in 0..EXCHANGES_PER_CONNECTION {
+            if Instant::now() >= deadline {
+                break;
+            }
+            let chess_move = if exchange % 2 == 0 { "e2e4" } else { "e7e5" };
+            let sent = Instant::now();
+            let result = async {
+                connection
+                    .send_message(Message::Move(Move::new(
+                        game_id.clone(),
+                        chess_move.to_string(),
+                        "0".repeat(64),
+                    )))
+                    .await?;
+                connection
+                    .send_message(Message::new_ping(exchange.into(), game_id.clone()))
+                    .await?;
+                connection.receive_message().await
+            }
+            .await;
+            match result {
+                Ok(_) => outcome
+                    .samples
+                    .push((sent.duration_since(start), sent.elapsed())),
+                Err(e) => {
+                    outcome.record_error(e);
+                    break;
+                }
+            }
+            tokio::time::sleep(options.interval).await;
+        }
+    }
+    outcome
+}
+
+/// Descriptors open in this process
+fn open_fds() -> Option<usize> {
+    std::fs::read_dir("/proc/self/fd")
+        .ok()
+        .map(|entries| entries.count())
+}
+
+/// Resident set size of this process in bytes
+fn resident_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
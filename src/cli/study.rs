@@ -0,0 +1,128 @@
+use crate::chess::{Board, ChessError, Color};
+use crate::messages::chess::{Study, StudyMove, ValidationError};
+use crate::storage::models::StudyRecord;
+
+/// Parse an annotated line typed by the user, e.g. "1. e4 {Best by test} e5 2. Nf3"
+///
+/// Moves may be in SAN or coordinate notation and are kept in coordinate
+/// notation. A `{comment}` belongs to the move before it; one before the
+/// first move is returned separately as the comment on the starting position.
+pub fn parse_annotated_line(
+    fen: &str,
+    input: &str,
+) -> Result<(Option<String>, Vec<StudyMove>), ChessError> {
+    let mut board = Board::from_fen(fen)?;
+    let mut start_comment: Option<String> = None;
+    let mut moves: Vec<StudyMove> = Vec::new();
+
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        if let Some(after_brace) = rest.strip_prefix('{') {
+            let end = after_brace.find('}').ok_or_else(|| {
+                ChessError::InvalidMove("Comment is missing its closing '}'".to_string())
+            })?;
+            let comment = after_brace[..end].trim();
+            let target = match moves.last_mut() {
+                Some(study_move) => &mut study_move.comment,
+                None => &mut start_comment,
+            };
+            if !comment.is_empty() {
+                *target = Some(match target.take() {
+                    Some(previous) => format!("{previous} {comment}"),
+                    None => comment.to_string(),
+                });
+            }
+            rest = after_brace[end + 1..].trim_start();
+            continue;
+        }
+
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '{')
+            .unwrap_or(rest.len());
+        let (token, remainder) = rest.split_at(end);
+        rest = remainder.trim_start();
+
+        // Move numbers ("1.", "2...") and numbers run into a move ("1.e4")
+        let token = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+        if token.is_empty() {
+            continue;
+        }
+        let mv = board.parse_move(token)?;
+        board.make_move(mv)?;
+        moves.push(StudyMove {
+            chess_move: mv.to_string(),
+            comment: None,
+        });
+    }
+
+    Ok((start_comment, moves))
+}
+
+/// The moves of a study in SAN, replayed from its starting position
+pub fn study_san(study: &Study) -> Result<Vec<String>, ValidationError> {
+    let mut board = Board::from_fen(&study.fen)
+        .map_err(|_| ValidationError::InvalidFen(format!("Invalid FEN: '{}'", study.fen)))?;
+    let mut line = Vec::with_capacity(study.moves.len());
+    for (index, study_move) in study.moves.iter().enumerate() {
+        let mv = board
+            .parse_move(&study_move.chess_move)
+            .map_err(|e| ValidationError::InvalidMove(format!("Move {}: {e}", index + 1)))?;
+        line.push(board.to_san(mv));
+        board
+            .make_move(mv)
+            .map_err(|e| ValidationError::InvalidMove(format!("Move {}: {e}", index + 1)))?;
+    }
+    Ok(line)
+}
+
+/// Move text for a study with comments in braces, e.g. "1. e4 {Best by test} 1... e5"
+///
+/// Numbering starts from the move number and side to move of the study's
+/// starting position, and Black's move is numbered again after a comment, as
+/// in PGN.
+pub fn format_study_text(study: &Study) -> Result<String, ValidationError> {
+    let board = Board::from_fen(&study.fen)
+        .map_err(|_| ValidationError::InvalidFen(format!("Invalid FEN: '{}'", study.fen)))?;
+    let mut number = board.fullmove_number();
+    let mut color = board.active_color();
+    let mut parts = Vec::new();
+    let mut after_comment = true;
+
+    for (study_move, san) in study.moves.iter().zip(study_san(study)?) {
+        match color {
+            Color::White => parts.push(format!("{number}. {san}")),
+            Color::Black if after_comment => parts.push(format!("{number}... {san}")),
+            Color::Black => parts.push(san),
+        }
+        after_comment = false;
+        if let Some(comment) = &study_move.comment {
+            parts.push(format!("{{{comment}}}"));
+            after_comment = true;
+        }
+        if color == Color::Black {
+            number += 1;
+        }
+        color = color.opposite();
+    }
+
+    Ok(parts.join(" "))
+}
+
+/// Find a kept study by ID or unique ID prefix
+pub fn find_study<'a>(studies: &'a [StudyRecord], id: &str) -> Result<&'a StudyRecord, String> {
+    if let Some(study) = studies.iter().find(|study| study.id == id) {
+        return Ok(study);
+    }
+    let matches: Vec<&StudyRecord> = studies
+        .iter()
+        .filter(|study| study.id.starts_with(id))
+        .collect();
+    match matches.as_slice() {
+        [study] => Ok(study),
+        [] => Err(format!("No study matches '{id}'")),
+        _ => Err(format!(
+            "'{id}' matches {} studies; give more of the ID",
+            matches.len()
+        )),
+    }
+}
@@ -0,0 +1,294 @@
+use crate::cli::logging::JsonVisitor;
+use crate::network::http_client::{self, HttpUrl};
+use serde_json::{json, Map, Value};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::span::{Attributes, Id, Record};
+use tracing::{debug, warn, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Standard OpenTelemetry variable naming the collector when
+/// `telemetry.otlp_endpoint` is unset
+pub const OTLP_ENDPOINT_ENV_VAR: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Spans exported to the collector, whatever the terminal shows
+pub const OTLP_DIRECTIVE: &str = "mate=debug";
+
+/// Spans sent in one request at most
+const BATCH_SIZE: usize = 512;
+
+/// How long finished spans wait before being sent
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long one export, or the final flush on exit, may take
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A finished span, ready to export
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanRecord {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    /// `None` for the root span of a trace
+    pub parent_span_id: Option<[u8; 8]>,
+    pub name: String,
+    /// Module the span was created in
+    pub target: String,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    /// Span fields, including those recorded after the span was created
+    pub attributes: Map<String, Value>,
+}
+
+/// State kept in a span's extensions while it is open
+struct OpenSpan {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    start: SystemTime,
+    attributes: Map<String, Value>,
+}
+
+/// Layer passing each closed span to an exporter
+///
+/// Spans inherit the trace ID of their parent; spans without one start a
+/// new trace.
+pub struct OtlpLayer {
+    spans: mpsc::UnboundedSender<SpanRecord>,
+}
+
+impl OtlpLayer {
+    /// Send closed spans to `spans`
+    pub fn new(spans: mpsc::UnboundedSender<SpanRecord>) -> Self {
+        Self { spans }
+    }
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<OpenSpan>()
+                .map(|open| (open.trace_id, open.span_id))
+        });
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, parent_span_id)) => (trace_id, Some(parent_span_id)),
+            None => (rand::random(), None),
+        };
+
+        let mut visitor = JsonVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(OpenSpan {
+            trace_id,
+            span_id: rand::random(),
+            parent_span_id,
+            start: SystemTime::now(),
+            attributes: visitor.0,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(open) = extensions.get_mut::<OpenSpan>() {
+            let mut visitor = JsonVisitor(std::mem::take(&mut open.attributes));
+            values.record(&mut visitor);
+            open.attributes = visitor.0;
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(open) = span.extensions_mut().remove::<OpenSpan>() else {
+            return;
+        };
+        // The exporter only stops once mate is exiting
+        let _ = self.spans.send(SpanRecord {
+            trace_id: open.trace_id,
+            span_id: open.span_id,
+            parent_span_id: open.parent_span_id,
+            name: span.name().to_string(),
+            target: span.metadata().target().to_string(),
+            start: open.start,
+            end: SystemTime::now(),
+            attributes: open.attributes,
+        });
+    }
+}
+
+/// Background task sending spans to an OTLP/HTTP collector
+pub struct OtlpExporter {
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl OtlpExporter {
+    /// Start exporting to the collector at `endpoint`, returning the layer
+    /// that feeds it
+    ///
+    /// Spans are posted as OTLP/HTTP JSON to `{endpoint}/v1/traces` in
+    /// batches, at least every few seconds.
+    pub fn start(endpoint: &HttpUrl, service_name: &str) -> (OtlpLayer, Self) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let task = tokio::spawn(export_loop(
+            endpoint.join("v1/traces"),
+            service_name.to_string(),
+            receiver,
+            shutdown_rx,
+        ));
+        (OtlpLayer::new(sender), Self { shutdown, task })
+    }
+
+    /// Send the spans closed so far and stop
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(());
+        if tokio::time::timeout(EXPORT_TIMEOUT, self.task)
+            .await
+            .is_err()
+        {
+            debug!("Timed out flushing spans to the OTLP collector");
+        }
+    }
+}
+
+async fn export_loop(
+    url: HttpUrl,
+    service_name: String,
+    mut spans: mpsc::UnboundedReceiver<SpanRecord>,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let mut batch = Vec::new();
+    let mut interval = tokio::time::interval(EXPORT_INTERVAL);
+    let mut failing = false;
+
+    loop {
+        tokio::select! {
+            span = spans.recv() => match span {
+                Some(span) => {
+                    batch.push(span);
+                    if batch.len() < BATCH_SIZE {
+                        continue;
+                    }
+                }
+                None => break,
+            },
+            _ = interval.tick() => {}
+            _ = &mut shutdown => break,
+        }
+        export(&url, &service_name, &mut batch, &mut failing).await;
+    }
+
+    while let Ok(span) = spans.try_recv() {
+        batch.push(span);
+    }
+    export(&url, &service_name, &mut batch, &mut failing).await;
+}
+
+/// Post `batch` to the collector and empty it; spans are dropped when the
+/// collector can't be reached
+async fn export(
+    url: &HttpUrl,
+    service_name: &str,
+    batch: &mut Vec<SpanRecord>,
+    failing: &mut bool,
+) {
+    for chunk in batch.chunks(BATCH_SIZE) {
+        let body = encode_spans(service_name, chunk).to_string();
+        let result = tokio::time::timeout(
+            EXPORT_TIMEOUT,
+            http_client::post_json(url, &[], body.as_bytes()),
+        )
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")));
+        match result {
+            Ok(status) if (200..300).contains(&status) => *failing = false,
+            outcome => {
+                let reason = match outcome {
+                    Ok(status) => format!("HTTP status {status}"),
+                    Err(e) => format!("{e:#}"),
+                };
+                // Warn once rather than every few seconds while the collector is down
+                if !*failing {
+                    warn!("Failed to export spans to {}: {}", url.as_str(), reason);
+                }
+                *failing = true;
+            }
+        }
+    }
+    batch.clear();
+}
+
+/// The OTLP/HTTP JSON body for `spans`
+pub fn encode_spans(service_name: &str, spans: &[SpanRecord]) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [attribute("service.name", &Value::from(service_name))],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "mate", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans.iter().map(encode_span).collect::<Vec<_>>(),
+            }],
+        }],
+    })
+}
+
+fn encode_span(span: &SpanRecord) -> Value {
+    let mut attributes: Vec<Value> = span
+        .attributes
+        .iter()
+        .map(|(key, value)| attribute(key, value))
+        .collect();
+    attributes.push(attribute(
+        "code.namespace",
+        &Value::from(span.target.as_str()),
+    ));
+
+    let mut encoded = json!({
+        "traceId": hex::encode(span.trace_id),
+        "spanId": hex::encode(span.span_id),
+        "name": span.name,
+        // SPAN_KIND_INTERNAL
+        "kind": 1,
+        "startTimeUnixNano": unix_nanos(span.start),
+        "endTimeUnixNano": unix_nanos(span.end),
+        "attributes": attributes,
+    });
+    if let Some(parent_span_id) = span.parent_span_id {
+        encoded["parentSpanId"] = hex::encode(parent_span_id).into();
+    }
+    encoded
+}
+
+/// An OTLP key-value pair; 64-bit integers are strings in OTLP JSON
+fn attribute(key: &str, value: &Value) -> Value {
+    let value = match value {
+        Value::Bool(b) => json!({ "boolValue": b }),
+        Value::Number(n) if n.is_f64() => json!({ "doubleValue": n }),
+        Value::Number(n) => json!({ "intValue": n.to_string() }),
+        Value::String(s) => json!({ "stringValue": s }),
+        other => json!({ "stringValue": other.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
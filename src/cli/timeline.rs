@@ -0,0 +1,213 @@
+use crate::cli::pgn::pgn_date;
+use crate::storage::models::{Game, GameSyncIssue, Message, PeerEvent, PeerEventType};
+use serde::Serialize;
+
+/// What kind of thing happened at a point on a game's timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    Game,
+    Invitation,
+    Move,
+    Chat,
+    Draw,
+    Resign,
+    Sync,
+    Connection,
+}
+
+impl TimelineKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimelineKind::Game => "game",
+            TimelineKind::Invitation => "invitation",
+            TimelineKind::Move => "move",
+            TimelineKind::Chat => "chat",
+            TimelineKind::Draw => "draw",
+            TimelineKind::Resign => "resign",
+            TimelineKind::Sync => "sync",
+            TimelineKind::Connection => "connection",
+        }
+    }
+}
+
+/// Who caused a timeline entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineActor {
+    Me,
+    Opponent,
+    /// Recorded by mate itself, e.g. a connection incident
+    System,
+}
+
+impl TimelineActor {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimelineActor::Me => "You",
+            TimelineActor::Opponent => "Opponent",
+            TimelineActor::System => "-",
+        }
+    }
+}
+
+/// One thing that happened during a game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimelineEntry {
+    pub timestamp: i64,
+    pub kind: TimelineKind,
+    pub actor: TimelineActor,
+    pub description: String,
+}
+
+/// Interleave a game's messages, its sync issue, and connection incidents
+/// with the opponent into one chronological list
+///
+/// Entries in the same second keep the order they were stored in, with
+/// messages ahead of sync and connection events.
+pub fn build_timeline(
+    game: &Game,
+    messages: &[Message],
+    peer_events: &[PeerEvent],
+    sync_issue: Option<&GameSyncIssue>,
+    my_peer_id: &str,
+) -> Vec<TimelineEntry> {
+    let mut entries = vec![TimelineEntry {
+        timestamp: game.created_at,
+        kind: TimelineKind::Game,
+        actor: TimelineActor::System,
+        description: format!("Game created against {}", game.opponent_peer_id),
+    }];
+
+    for message in messages {
+        let actor = if message.sender_peer_id == game.opponent_peer_id {
+            TimelineActor::Opponent
+        } else if message.sender_peer_id == my_peer_id
+            || message.sender_peer_id == "self"
+            || message.sender_peer_id == "local"
+        {
+            TimelineActor::Me
+        } else {
+            TimelineActor::System
+        };
+        let (kind, description) = describe_message(message);
+        entries.push(TimelineEntry {
+            timestamp: message.created_at,
+            kind,
+            actor,
+            description,
+        });
+    }
+
+    if let Some(issue) = sync_issue {
+        entries.push(TimelineEntry {
+            timestamp: issue.detected_at,
+            kind: TimelineKind::Sync,
+            actor: TimelineActor::System,
+            description: format!("Flagged as out of sync: {}", issue.reason),
+        });
+    }
+
+    for event in peer_events {
+        entries.push(TimelineEntry {
+            timestamp: event.created_at,
+            kind: TimelineKind::Connection,
+            actor: TimelineActor::System,
+            description: describe_peer_event(event),
+        });
+    }
+
+    if let Some(completed_at) = game.completed_at {
+        let outcome = match &game.result {
+            Some(result) => format!("Game over: {result:?}"),
+            None => "Game over".to_string(),
+        };
+        entries.push(TimelineEntry {
+            timestamp: completed_at,
+            kind: TimelineKind::Game,
+            actor: TimelineActor::System,
+            description: outcome,
+        });
+    }
+
+    entries.sort_by_key(|entry| entry.timestamp);
+    entries
+}
+
+fn describe_message(message: &Message) -> (TimelineKind, String) {
+    let content: serde_json::Value =
+        serde_json::from_str(&message.content).unwrap_or(serde_json::Value::Null);
+    let field = |name: &str| {
+        content
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+
+    match message.message_type.to_ascii_lowercase().as_str() {
+        "move" => (
+            TimelineKind::Move,
+            match field("chess_move") {
+                Some(chess_move) => format!("Played {chess_move}"),
+                None => "Played a move".to_string(),
+            },
+        ),
+        "moveack" | "move_ack" => (TimelineKind::Move, "Acknowledged a move".to_string()),
+        "chat" => (
+            TimelineKind::Chat,
+            format!("\"{}\"", field("text").unwrap_or_default()),
+        ),
+        "gameinvite" | "game_invite" => {
+            (TimelineKind::Invitation, "Sent the invitation".to_string())
+        }
+        "gameaccept" | "game_accept" => (
+            TimelineKind::Invitation,
+            "Accepted the invitation".to_string(),
+        ),
+        "auto_accept" => (
+            TimelineKind::Invitation,
+            "Accepted the invitation automatically".to_string(),
+        ),
+        "gamedecline" | "game_decline" => (
+            TimelineKind::Invitation,
+            "Declined the invitation".to_string(),
+        ),
+        "drawoffer" | "draw_offer" => (TimelineKind::Draw, "Offered a draw".to_string()),
+        "drawaccept" | "draw_accept" => (TimelineKind::Draw, "Accepted the draw".to_string()),
+        "resign" => (TimelineKind::Resign, "Resigned".to_string()),
+        "syncrequest" | "sync_request" => {
+            (TimelineKind::Sync, "Asked for the game state".to_string())
+        }
+        "syncresponse" | "sync_response" => (TimelineKind::Sync, "Sent the game state".to_string()),
+        _ => (TimelineKind::Game, message.message_type.clone()),
+    }
+}
+
+fn describe_peer_event(event: &PeerEvent) -> String {
+    let what = match event.event_type {
+        PeerEventType::HandshakeSuccess => "Connected",
+        PeerEventType::HandshakeFailure => "Handshake failed",
+        PeerEventType::ProtocolViolation => "Protocol violation",
+        PeerEventType::DosEvent => "Connection limited",
+    };
+    let mut description = what.to_string();
+    if let Some(addr) = &event.remote_addr {
+        description.push_str(&format!(" from {addr}"));
+    }
+    if let Some(details) = &event.details {
+        description.push_str(&format!(" ({details})"));
+    }
+    description
+}
+
+/// Format a Unix timestamp as `YYYY.MM.DD HH:MM:SS` in UTC
+pub fn format_timeline_time(timestamp: i64) -> String {
+    let seconds = timestamp.rem_euclid(86_400);
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        pgn_date(timestamp),
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
@@ -0,0 +1,300 @@
+use crate::network::http_client::{self, HttpUrl};
+use crate::network::{ConnectionStats, MonitorSnapshot, OpenConnection};
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long to wait for a key press before checking whether to refresh
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long to wait for the metrics endpoint before showing it as down
+const FETCH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Read the counters served at `metrics_addr` by `mate serve`
+pub async fn fetch_monitor(metrics_addr: &str) -> Result<MonitorSnapshot> {
+    let url = HttpUrl::parse(&format!("http://{metrics_addr}/top"))?;
+    let (status, body) = tokio::time::timeout(FETCH_TIMEOUT, http_client::get(&url))
+        .await
+        .context("Timed out reading server statistics")??;
+    if status != 200 {
+        anyhow::bail!("Metrics endpoint answered with HTTP status {status}");
+    }
+    serde_json::from_slice(&body).context("Malformed server statistics")
+}
+
+/// Messages and bytes per second over the time between two readings
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rates {
+    pub messages_in: f64,
+    pub messages_out: f64,
+    pub bytes_in: f64,
+    pub bytes_out: f64,
+}
+
+impl Rates {
+    /// Rates from `previous` to `current` counters, read `elapsed` apart
+    ///
+    /// Counters that went down, as when a connection ID is reused, count
+    /// as no traffic.
+    pub fn between(
+        previous: &ConnectionStats,
+        current: &ConnectionStats,
+        elapsed: Duration,
+    ) -> Self {
+        let secs = elapsed.as_secs_f64();
+        if secs <= 0.0 {
+            return Self::default();
+        }
+        let rate = |before: u64, after: u64| after.saturating_sub(before) as f64 / secs;
+        Self {
+            messages_in: rate(previous.messages_received, current.messages_received),
+            messages_out: rate(previous.messages_sent, current.messages_sent),
+            bytes_in: rate(previous.bytes_received, current.bytes_received),
+            bytes_out: rate(previous.bytes_sent, current.bytes_sent),
+        }
+    }
+
+    /// Bytes per second in both directions
+    pub fn bandwidth(&self) -> f64 {
+        self.bytes_in + self.bytes_out
+    }
+}
+
+/// The latest reading and the rates since the one before
+#[derive(Debug, Default)]
+pub struct TopView {
+    pub snapshot: MonitorSnapshot,
+    pub total: Rates,
+    /// Rates of each open connection, by connection ID
+    pub connections: HashMap<String, Rates>,
+    read_at: Option<Instant>,
+}
+
+impl TopView {
+    /// Take in a new reading made at `now`
+    pub fn update(&mut self, snapshot: MonitorSnapshot, now: Instant) {
+        let elapsed = self
+            .read_at
+            .map(|read_at| now.saturating_duration_since(read_at))
+            .unwrap_or_default();
+
+        self.total = Rates::between(
+            &self.snapshot.totals.traffic,
+            &snapshot.totals.traffic,
+            elapsed,
+        );
+        let previous: HashMap<&str, &ConnectionStats> = self
+            .snapshot
+            .connections
+            .iter()
+            .map(|open| (open.connection_id.as_str(), &open.stats))
+            .collect();
+        self.connections = snapshot
+            .connections
+            .iter()
+            .map(|open| {
+                // A connection opened since the last reading started from zero
+                let before = previous
+                    .get(open.connection_id.as_str())
+                    .copied()
+                    .cloned()
+                    .unwrap_or_default();
+                (
+                    open.connection_id.clone(),
+                    Rates::between(&before, &open.stats, elapsed),
+                )
+            })
+            .collect();
+        self.snapshot = snapshot;
+        self.read_at = Some(now);
+    }
+
+    /// Open connections, busiest first
+    pub fn busiest(&self) -> Vec<(&OpenConnection, Rates)> {
+        let mut rows: Vec<(&OpenConnection, Rates)> = self
+            .snapshot
+            .connections
+            .iter()
+            .map(|open| {
+                let rates = self
+                    .connections
+                    .get(&open.connection_id)
+                    .copied()
+                    .unwrap_or_default();
+                (open, rates)
+            })
+            .collect();
+        rows.sort_by(|(a, a_rates), (b, b_rates)| {
+            b_rates
+                .bandwidth()
+                .total_cmp(&a_rates.bandwidth())
+                .then_with(|| a.connection_id.cmp(&b.connection_id))
+        });
+        rows
+    }
+
+    /// The server summary above the connection table
+    pub fn summary_lines(&self) -> Vec<String> {
+        let totals = &self.snapshot.totals;
+        let traffic = &totals.traffic;
+        let round_trip = traffic
+            .avg_round_trip()
+            .map(|avg| format!("{} ms", avg.as_millis()))
+            .unwrap_or_else(|| "-".to_string());
+        vec![
+            format!(
+                "Connections: {} open of {} allowed, {} since start, {} refused at the limit",
+                totals.open_connections,
+                self.snapshot.connection_limit,
+                totals.total_connections,
+                totals.refused_connections
+            ),
+            format!(
+                "Messages:    {:.1}/s in, {:.1}/s out ({} received, {} sent)",
+                self.total.messages_in,
+                self.total.messages_out,
+                traffic.messages_received,
+                traffic.messages_sent
+            ),
+            format!(
+                "Bandwidth:   {} in, {} out",
+                format_rate(self.total.bytes_in),
+                format_rate(self.total.bytes_out)
+            ),
+            format!(
+                "Refused:     {} messages rejected, {} oversized frames, {} handshake timeouts",
+                totals.rejected_messages, totals.oversized_messages, totals.handshake_timeouts
+            ),
+            format!("Round trip:  {round_trip} average"),
+        ]
+    }
+}
+
+/// Header of the connection table
+pub const CONNECTION_HEADER: &str =
+    "CONNECTION  PEER                  MSGS IN/OUT        IN/s        OUT/s";
+
+/// One row of the connection table
+pub fn connection_line(open: &OpenConnection, rates: &Rates) -> String {
+    let peer = match &open.peer_id {
+        Some(peer_id) if peer_id.len() > 20 => format!("{}…", &peer_id[..19]),
+        Some(peer_id) => peer_id.clone(),
+        None => "(handshake)".to_string(),
+    };
+    format!(
+        "{:<10}  {:<20}  {:>15}  {:>10}  {:>11}",
+        open.connection_id,
+        peer,
+        format!(
+            "{}/{}",
+            open.stats.messages_received, open.stats.messages_sent
+        ),
+        format_rate(rates.bytes_in),
+        format_rate(rates.bytes_out)
+    )
+}
+
+/// Bytes per second in B/s, KiB/s, or MiB/s
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    const KIB: f64 = 1024.0;
+    if bytes_per_sec >= KIB * KIB {
+        format!("{:.1} MiB/s", bytes_per_sec / (KIB * KIB))
+    } else if bytes_per_sec >= KIB {
+        format!("{:.1} KiB/s", bytes_per_sec / KIB)
+    } else {
+        format!("{bytes_per_sec:.0} B/s")
+    }
+}
+
+/// Show the server's connections full-screen until the user quits,
+/// reading them from `metrics_addr` every `interval`
+pub async fn run(metrics_addr: &str, interval: Duration) -> Result<()> {
+    let mut terminal = ratatui::try_init().context("Failed to initialize terminal")?;
+    let result = event_loop(&mut terminal, metrics_addr, interval).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    metrics_addr: &str,
+    interval: Duration,
+) -> Result<()> {
+    let mut view = TopView::default();
+    let mut error = None;
+    let mut next_fetch = Instant::now();
+
+    loop {
+        if Instant::now() >= next_fetch {
+            match fetch_monitor(metrics_addr).await {
+                Ok(snapshot) => {
+                    view.update(snapshot, Instant::now());
+                    error = None;
+                }
+                Err(e) => error = Some(format!("{e:#}")),
+            }
+            next_fetch = Instant::now() + interval;
+        }
+
+        terminal
+            .draw(|frame| draw(frame, &view, error.as_deref(), metrics_addr))
+            .context("Failed to draw interface")?;
+
+        if event::poll(INPUT_POLL_INTERVAL).context("Failed to read terminal events")? {
+            if let Event::Key(key) = event::read().context("Failed to read terminal events")? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('r') => next_fetch = Instant::now(),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, view: &TopView, error: Option<&str>, metrics_addr: &str) {
+    let [summary_area, connections_area, status_area] = Layout::vertical([
+        Constraint::Length(7),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let summary = Paragraph::new(
+        view.summary_lines()
+            .into_iter()
+            .map(Line::from)
+            .collect::<Vec<_>>(),
+    )
+    .block(Block::bordered().title(format!(" mate serve at {metrics_addr} ")));
+    frame.render_widget(summary, summary_area);
+
+    let mut lines = vec![Line::styled(
+        CONNECTION_HEADER,
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    lines.extend(
+        view.busiest()
+            .into_iter()
+            .map(|(open, rates)| Line::from(connection_line(open, &rates))),
+    );
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Connections ")),
+        connections_area,
+    );
+
+    let status = match error {
+        Some(error) => format!("{error} (is 'mate serve' running?)"),
+        None => "r refresh  q quit".to_string(),
+    };
+    frame.render_widget(Paragraph::new(status), status_area);
+}
@@ -0,0 +1,402 @@
+use crate::chess::{Board, Color, Position};
+use crate::cli::app::App;
+use crate::cli::game_ops::{GameOps, GameRecord};
+use crate::storage::models::{GameStatus, Message, PlayerColor};
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::time::{Duration, Instant};
+
+/// How long to wait for a key press before redrawing
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often stored games are reloaded to pick up moves written by other processes
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of non-move messages shown in the message pane
+const MESSAGE_LOG_LIMIT: usize = 50;
+
+/// How long a server announcement stays in the status line
+const ANNOUNCEMENT_DISPLAY_SECS: i64 = 24 * 60 * 60;
+
+/// Snapshot of the selected game, rebuilt on every refresh
+struct GameView {
+    board: Board,
+    moves: Vec<String>,
+    messages: Vec<Message>,
+    my_color: PlayerColor,
+    last_activity: i64,
+}
+
+/// State behind the full-screen interface
+struct TuiState {
+    games: Vec<GameRecord>,
+    list_state: ListState,
+    view: Option<GameView>,
+    error: Option<String>,
+    /// The latest recent server announcement
+    announcement: Option<String>,
+    last_refresh: Instant,
+}
+
+impl TuiState {
+    fn new() -> Self {
+        Self {
+            games: Vec::new(),
+            list_state: ListState::default(),
+            view: None,
+            error: None,
+            announcement: None,
+            last_refresh: Instant::now(),
+        }
+    }
+
+    /// Reload the game list and the selected game from the database
+    fn refresh(&mut self, database: &Database) {
+        self.last_refresh = Instant::now();
+        let game_ops = GameOps::new(database);
+
+        // A failure here only hides the announcement
+        self.announcement = database
+            .get_announcements_since(Database::current_timestamp() - ANNOUNCEMENT_DISPLAY_SECS)
+            .ok()
+            .and_then(|announcements| announcements.into_iter().next())
+            .map(|announcement| announcement.text);
+
+        // Keep the same game selected even if the list order changes
+        let selected_id = self.selected_game().map(|record| record.game.id.clone());
+        self.games = match game_ops.list_games() {
+            Ok(games) => games,
+            Err(e) => {
+                self.error = Some(format!("Failed to load games: {e}"));
+                return;
+            }
+        };
+
+        let selected = selected_id
+            .and_then(|id| self.games.iter().position(|r| r.game.id == id))
+            .or(if self.games.is_empty() { None } else { Some(0) });
+        self.list_state.select(selected);
+
+        self.view = None;
+        self.error = None;
+        if let Some(record) = self.selected_game() {
+            let game_id = record.game.id.clone();
+            let my_color = record.game.my_color.clone();
+            match load_game_view(database, &game_ops, &game_id, my_color) {
+                Ok(view) => self.view = Some(view),
+                Err(e) => self.error = Some(format!("Failed to load game {game_id}: {e}")),
+            }
+        }
+    }
+
+    fn selected_game(&self) -> Option<&GameRecord> {
+        self.list_state.selected().and_then(|i| self.games.get(i))
+    }
+
+    /// Move the selection by `offset`, wrapping around the list
+    fn move_selection(&mut self, offset: isize, database: &Database) {
+        if self.games.is_empty() {
+            return;
+        }
+        let len = self.games.len() as isize;
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        let next = (current + offset).rem_euclid(len) as usize;
+        self.list_state.select(Some(next));
+        self.refresh(database);
+    }
+}
+
+/// Load board, moves, and message log for a game
+fn load_game_view(
+    database: &Database,
+    game_ops: &GameOps,
+    game_id: &str,
+    my_color: PlayerColor,
+) -> Result<GameView> {
+    let state = game_ops.reconstruct_game_state(game_id)?;
+    let all_messages = database.get_messages_for_game(game_id)?;
+    let last_activity = all_messages
+        .last()
+        .map(|m| m.created_at)
+        .unwrap_or(state.game.updated_at);
+
+    let mut messages: Vec<Message> = all_messages
+        .into_iter()
+        .filter(|m| m.message_type != "Move")
+        .collect();
+    if messages.len() > MESSAGE_LOG_LIMIT {
+        messages.drain(..messages.len() - MESSAGE_LOG_LIMIT);
+    }
+
+    Ok(GameView {
+        board: state.board,
+        moves: state.move_history,
+        messages,
+        my_color,
+        last_activity,
+    })
+}
+
+/// Run the full-screen interface until the user quits
+///
+/// Games are reloaded from the database every second, so moves stored by a
+/// running `mate serve` or `mate connect` appear without restarting.
+pub fn run(app: &App) -> Result<()> {
+    let mut terminal = ratatui::try_init().context("Failed to initialize terminal")?;
+    let result = event_loop(&mut terminal, app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &App) -> Result<()> {
+    let mut state = TuiState::new();
+    state.refresh(&app.database);
+
+    loop {
+        terminal
+            .draw(|frame| draw(frame, &mut state, app.peer_id()))
+            .context("Failed to draw interface")?;
+
+        if event::poll(INPUT_POLL_INTERVAL).context("Failed to read terminal events")? {
+            if let Event::Key(key) = event::read().context("Failed to read terminal events")? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Down | KeyCode::Char('j') => state.move_selection(1, &app.database),
+                    KeyCode::Up | KeyCode::Char('k') => state.move_selection(-1, &app.database),
+                    KeyCode::Char('r') => state.refresh(&app.database),
+                    _ => {}
+                }
+            }
+        }
+
+        if state.last_refresh.elapsed() >= REFRESH_INTERVAL {
+            state.refresh(&app.database);
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, state: &mut TuiState, peer_id: &str) {
+    let [main_area, status_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [games_area, board_area, side_area] = Layout::horizontal([
+        Constraint::Length(34),
+        Constraint::Length(24),
+        Constraint::Min(24),
+    ])
+    .areas(main_area);
+    let [moves_area, clock_area, messages_area] = Layout::vertical([
+        Constraint::Percentage(50),
+        Constraint::Length(4),
+        Constraint::Min(3),
+    ])
+    .areas(side_area);
+
+    draw_games(frame, state, games_area);
+
+    match &state.view {
+        Some(view) => {
+            let perspective = match view.my_color {
+                PlayerColor::White => Color::White,
+                PlayerColor::Black => Color::Black,
+            };
+            let board = Paragraph::new(
+                board_lines(&view.board, perspective)
+                    .into_iter()
+                    .map(Line::from)
+                    .collect::<Vec<_>>(),
+            )
+            .block(Block::bordered().title(" Board "));
+            frame.render_widget(board, board_area);
+
+            let moves = Paragraph::new(
+                move_list_lines(&view.moves)
+                    .into_iter()
+                    .map(Line::from)
+                    .collect::<Vec<_>>(),
+            )
+            .block(Block::bordered().title(" Moves "));
+            frame.render_widget(moves, moves_area);
+
+            let clock = Paragraph::new(vec![
+                Line::from(format!("To move: {}", view.board.active_color())),
+                Line::from(format!(
+                    "Waiting: {}",
+                    format_elapsed(Database::current_timestamp() - view.last_activity)
+                )),
+            ])
+            .block(Block::bordered().title(" Clock "));
+            frame.render_widget(clock, clock_area);
+
+            let messages: Vec<ListItem> = view
+                .messages
+                .iter()
+                .map(|m| {
+                    let sender = if m.sender_peer_id == peer_id {
+                        "you"
+                    } else {
+                        "opponent"
+                    };
+                    ListItem::new(format!("{} from {}", m.message_type, sender))
+                })
+                .collect();
+            frame.render_widget(
+                List::new(messages).block(Block::bordered().title(" Messages ")),
+                messages_area,
+            );
+        }
+        None => {
+            let placeholder = state
+                .error
+                .clone()
+                .unwrap_or_else(|| "No game selected".to_string());
+            frame.render_widget(
+                Paragraph::new(placeholder).block(Block::bordered().title(" Board ")),
+                board_area,
+            );
+            frame.render_widget(Block::bordered().title(" Moves "), moves_area);
+            frame.render_widget(Block::bordered().title(" Clock "), clock_area);
+            frame.render_widget(Block::bordered().title(" Messages "), messages_area);
+        }
+    }
+
+    let status = match (&state.error, &state.announcement) {
+        (Some(error), _) => error.clone(),
+        (None, Some(announcement)) => {
+            format!("📢 {announcement}  │  ↑/↓ select game  r refresh  q quit")
+        }
+        (None, None) => "↑/↓ select game  r refresh  q quit".to_string(),
+    };
+    frame.render_widget(Paragraph::new(status), status_area);
+}
+
+fn draw_games(frame: &mut Frame, state: &mut TuiState, area: Rect) {
+    let items: Vec<ListItem> = state
+        .games
+        .iter()
+        .map(|record| {
+            let id_short = &record.game.id[..record.game.id.len().min(12)];
+            let marker = match (&record.game.status, record.your_turn) {
+                (GameStatus::Active, true) => "●",
+                (GameStatus::Active, false) => "○",
+                (GameStatus::Pending, _) => "…",
+                _ => " ",
+            };
+            ListItem::new(format!(
+                "{marker} {id_short} {:>3} {}",
+                record.move_count,
+                record.game.status.as_str()
+            ))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::bordered().title(" Games "))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut state.list_state);
+}
+
+/// Render the board as text lines from the given side's perspective
+pub fn board_lines(board: &Board, perspective: Color) -> Vec<String> {
+    let ranks: Vec<u8> = match perspective {
+        Color::White => (0..8).rev().collect(),
+        Color::Black => (0..8).collect(),
+    };
+    let files: Vec<u8> = match perspective {
+        Color::White => (0..8).collect(),
+        Color::Black => (0..8).rev().collect(),
+    };
+
+    let mut lines = Vec::with_capacity(9);
+    for &rank in &ranks {
+        let mut line = format!("{} ", rank + 1);
+        for &file in &files {
+            let symbol = board
+                .get_piece(Position::new_unchecked(file, rank))
+                .map(|piece| piece.to_string())
+                .unwrap_or_else(|| if (file + rank) % 2 == 0 { "·" } else { " " }.to_string());
+            line.push_str(&symbol);
+            line.push(' ');
+        }
+        lines.push(line.trim_end().to_string());
+    }
+
+    let footer: String = files
+        .iter()
+        .map(|&file| format!("{} ", (b'a' + file) as char))
+        .collect();
+    lines.push(format!("  {}", footer.trim_end()));
+    lines
+}
+
+/// Format moves as numbered White/Black pairs
+pub fn move_list_lines(moves: &[String]) -> Vec<String> {
+    moves
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| match pair {
+            [white, black] => format!("{:>3}. {:<8} {}", i + 1, white, black),
+            [white] => format!("{:>3}. {}", i + 1, white),
+            _ => unreachable!("chunks(2) yields one or two moves"),
+        })
+        .collect()
+}
+
+/// Format a duration in seconds as a compact clock value
+pub fn format_elapsed(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (hours, minutes, secs) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_list_lines() {
+        let moves: Vec<String> = ["e2e4", "e7e5", "g1f3"]
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            move_list_lines(&moves),
+            vec!["  1. e2e4     e7e5", "  2. g1f3"]
+        );
+        assert!(move_list_lines(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(0), "0:00");
+        assert_eq!(format_elapsed(75), "1:15");
+        assert_eq!(format_elapsed(3_725), "1:02:05");
+        assert_eq!(format_elapsed(-5), "0:00");
+    }
+
+    #[test]
+    fn test_board_lines_perspective() {
+        let board = Board::new();
+
+        let white = board_lines(&board, Color::White);
+        assert_eq!(white.len(), 9);
+        assert!(white[0].starts_with("8 "));
+        assert_eq!(white[8], "  a b c d e f g h");
+
+        let black = board_lines(&board, Color::Black);
+        assert!(black[0].starts_with("1 "));
+        assert_eq!(black[8], "  h g f e d c b a");
+    }
+}
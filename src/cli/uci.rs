@@ -0,0 +1,296 @@
+use crate::chess::Board;
+use crate::cli::app::App;
+use crate::cli::game_ops::{GameOps, GameState, MoveProcessor};
+use crate::storage::models::{GameResult, GameStatus};
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// How often the database is checked for the opponent's move while the GUI
+/// waits for one
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What UCI sends for "no move"
+const NULL_MOVE: &str = "0000";
+
+/// A command from a UCI GUI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UciCommand {
+    Uci,
+    IsReady,
+    NewGame,
+    /// Moves from the starting position, or `None` for any other position
+    Position(Option<Vec<String>>),
+    Go,
+    Stop,
+    Quit,
+}
+
+impl UciCommand {
+    /// Parse a line from the GUI, returning `None` for blank lines and
+    /// commands the shim has no use for (`setoption`, `debug`, ...)
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let command = match words.next()? {
+            "uci" => UciCommand::Uci,
+            "isready" => UciCommand::IsReady,
+            "ucinewgame" => UciCommand::NewGame,
+            "position" => UciCommand::Position(parse_position(words.collect())),
+            "go" => UciCommand::Go,
+            "stop" => UciCommand::Stop,
+            "quit" => UciCommand::Quit,
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+/// Moves of a `position` command, if it starts from the standard position
+fn parse_position(words: Vec<&str>) -> Option<Vec<String>> {
+    let (start, moves) = match words.iter().position(|w| *w == "moves") {
+        Some(index) => (&words[..index], &words[index + 1..]),
+        None => (&words[..], &[][..]),
+    };
+    let standard = match start {
+        ["startpos"] => true,
+        ["fen", fen @ ..] => fen.join(" ") == Board::new().to_fen(),
+        _ => false,
+    };
+    standard.then(|| moves.iter().map(|m| m.to_string()).collect())
+}
+
+/// State for one GUI connection
+struct UciShim<'a> {
+    app: &'a App,
+    game_id: String,
+    opponent: String,
+    /// Moves of the last position the GUI sent
+    gui_moves: Vec<String>,
+    /// Set by `go` until the opponent's move is passed back as `bestmove`
+    waiting: bool,
+}
+
+impl<'a> UciShim<'a> {
+    fn new(app: &'a App, game_id: &str) -> Result<Self> {
+        let game = GameOps::new(&app.database)
+            .resolve_game(game_id)
+            .context("Failed to find game")?;
+        Ok(Self {
+            app,
+            opponent: game.opponent_peer_id.clone(),
+            game_id: game.id,
+            gui_moves: Vec::new(),
+            waiting: false,
+        })
+    }
+
+    fn state(&self) -> Result<GameState> {
+        GameOps::new(&self.app.database)
+            .reconstruct_game_state(&self.game_id)
+            .context("Failed to load game state")
+    }
+
+    /// Handle one command, returning the lines to send back
+    async fn handle(&mut self, command: UciCommand) -> Result<Vec<String>> {
+        match command {
+            UciCommand::Uci => Ok(vec![
+                format!("id name mate {}", env!("CARGO_PKG_VERSION")),
+                "id author mate".to_string(),
+                "uciok".to_string(),
+            ]),
+            UciCommand::IsReady => Ok(vec!["readyok".to_string()]),
+            UciCommand::Position(Some(moves)) => self.set_position(&moves).await,
+            UciCommand::Position(None) => Ok(vec![info(
+                "only games from the starting position can be played",
+            )]),
+            UciCommand::Go => self.go(),
+            UciCommand::Stop if self.waiting => Ok(vec![info(&format!(
+                "the move comes from {}, who is still thinking",
+                self.opponent
+            ))]),
+            UciCommand::NewGame | UciCommand::Stop | UciCommand::Quit => Ok(Vec::new()),
+        }
+    }
+
+    /// Follow the GUI's position, sending the user's new move to the opponent
+    async fn set_position(&mut self, moves: &[String]) -> Result<Vec<String>> {
+        let state = self.state()?;
+        let stored = coordinates(&state.move_history)?;
+        let gui = match coordinates(moves) {
+            Ok(gui) => gui,
+            Err(e) => return Ok(vec![info(&format!("illegal position: {e}"))]),
+        };
+        self.gui_moves = gui.clone();
+
+        if gui.len() == stored.len() + 1 && gui.starts_with(&stored) {
+            return self.play_move(&state, &gui[stored.len()]).await;
+        }
+        // The GUI may be a move behind until the opponent's move is reported
+        if stored.starts_with(&gui) {
+            return Ok(Vec::new());
+        }
+        Ok(vec![info(&format!(
+            "position does not match game {}, which has moves: {}",
+            self.game_id,
+            stored.join(" ")
+        ))])
+    }
+
+    async fn play_move(&mut self, state: &GameState, coordinate: &str) -> Result<Vec<String>> {
+        if state.game.status != GameStatus::Active {
+            return Ok(vec![info("game is not active")]);
+        }
+        if !state.your_turn {
+            return Ok(vec![info("it's not your turn")]);
+        }
+
+        let result = match MoveProcessor::new(&self.app.database)
+            .with_signer(&self.app.identity)
+            .process_move(&self.game_id, coordinate, true)
+        {
+            Ok(result) => result,
+            Err(e) => return Ok(vec![info(&format!("move rejected: {e}"))]),
+        };
+
+        let board = &result.updated_board;
+        let game_result = if board.is_checkmate() {
+            Some(GameResult::Win)
+        } else if board.is_stalemate() {
+            Some(GameResult::Draw)
+        } else {
+            None
+        };
+        if let Some(game_result) = &game_result {
+            self.app
+                .database
+                .update_game_result(&self.game_id, game_result.clone())
+                .context("Failed to record game result")?;
+        }
+
+        let mut replies = Vec::new();
+        if let Err(e) = self
+            .app
+            .network_manager
+            .send_chess_move(&self.opponent, self.game_id.clone(), result.move_message)
+            .await
+        {
+            replies.push(info(&format!(
+                "could not reach opponent ({e}); the move is queued and will be resent"
+            )));
+        }
+        if game_result.is_some() {
+            self.app.sign_game_result(&self.game_id).await;
+        } else {
+            self.app.update_arbiter(&self.game_id).await;
+        }
+        Ok(replies)
+    }
+
+    /// Start waiting for the opponent's move, or answer at once if there is
+    /// nothing to wait for
+    fn go(&mut self) -> Result<Vec<String>> {
+        let state = self.state()?;
+        if state.game.status == GameStatus::Active && state.your_turn {
+            return Ok(vec![
+                info("it's your turn; make your move on the board"),
+                format!("bestmove {NULL_MOVE}"),
+            ]);
+        }
+        self.waiting = true;
+        Ok(self.check_for_reply()?.unwrap_or_default())
+    }
+
+    /// The `bestmove` reply once the opponent has moved or the game is over
+    fn check_for_reply(&mut self) -> Result<Option<Vec<String>>> {
+        let state = self.state()?;
+        let stored = coordinates(&state.move_history)?;
+        if stored.len() > self.gui_moves.len() && stored.starts_with(&self.gui_moves) {
+            self.waiting = false;
+            return Ok(Some(vec![format!(
+                "bestmove {}",
+                stored[self.gui_moves.len()]
+            )]));
+        }
+        if state.game.status != GameStatus::Active {
+            self.waiting = false;
+            let outcome = match &state.game.result {
+                Some(result) => result.as_str(),
+                None => state.game.status.as_str(),
+            };
+            return Ok(Some(vec![
+                info(&format!("game over: {outcome}")),
+                format!("bestmove {NULL_MOVE}"),
+            ]));
+        }
+        Ok(None)
+    }
+}
+
+fn info(text: &str) -> String {
+    format!("info string {text}")
+}
+
+/// Moves in coordinate notation, as UCI writes them
+fn coordinates(moves: &[String]) -> Result<Vec<String>> {
+    let mut board = Board::new();
+    moves
+        .iter()
+        .map(|notation| {
+            let mv = board.parse_move(notation)?;
+            board.make_move(mv)?;
+            Ok(mv.to_string().to_lowercase())
+        })
+        .collect()
+}
+
+/// Act as a UCI engine on stdin and stdout for a game
+///
+/// The GUI's moves are played and sent to the opponent, and the opponent's
+/// replies, stored by a running `mate serve`, are given back as the
+/// engine's `bestmove`.
+pub async fn run(app: &App, game_id: &str) -> Result<()> {
+    let input = tokio::io::BufReader::new(tokio::io::stdin());
+    run_with(app, game_id, input, tokio::io::stdout()).await
+}
+
+/// Speak UCI over `input` and `output` for a game until the GUI quits
+pub async fn run_with<R, W>(app: &App, game_id: &str, input: R, mut output: W) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut shim = UciShim::new(app, game_id)?;
+    let mut lines = input.lines();
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        let replies = tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line.context("Failed to read from the GUI")? else {
+                    break;
+                };
+                match UciCommand::parse(&line) {
+                    Some(UciCommand::Quit) => break,
+                    Some(command) => shim
+                        .handle(command)
+                        .await
+                        .unwrap_or_else(|e| vec![info(&format!("error: {e:#}"))]),
+                    None => continue,
+                }
+            }
+            _ = poll.tick(), if shim.waiting => shim
+                .check_for_reply()
+                .unwrap_or_else(|e| Some(vec![info(&format!("error: {e:#}"))]))
+                .unwrap_or_default(),
+        };
+
+        for reply in replies {
+            output
+                .write_all(format!("{reply}\n").as_bytes())
+                .await
+                .context("Failed to write to the GUI")?;
+        }
+        output.flush().await.context("Failed to write to the GUI")?;
+    }
+    Ok(())
+}
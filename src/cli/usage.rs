@@ -0,0 +1,231 @@
+use crate::cli::error_handler::ErrorCode;
+use crate::network::http_client::{self, HttpUrl};
+use crate::network::Heartbeat;
+use crate::storage::games::GameFilter;
+use crate::storage::models::GameStatus;
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// File in the data directory holding the counters not yet reported
+pub const USAGE_FILE: &str = "usage.json";
+
+/// How often `mate serve` sends a usage report
+pub const USAGE_REPORT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long after a failed report the first retry is made; each further
+/// failure doubles the wait, up to [`USAGE_REPORT_INTERVAL`]
+pub const USAGE_RETRY_DELAY: Duration = Duration::from_secs(15 * 60);
+
+/// How long sending a report may take
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Name of the command in `matches` as counted, e.g. `peers mute`
+pub fn command_name(matches: &clap::ArgMatches) -> Option<String> {
+    let (name, command) = matches.subcommand()?;
+    Some(match command.subcommand_name() {
+        Some(subcommand) => format!("{name} {subcommand}"),
+        None => name.to_string(),
+    })
+}
+
+/// Counts gathered since the last report
+///
+/// Only command names and error codes are counted, never their arguments,
+/// so nothing here names a peer, a game, or a move.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageCounters {
+    /// Times each command was run, e.g. `move` or `peers mute`
+    pub commands: BTreeMap<String, u64>,
+    /// Commands that failed, by the code shown in `--json` errors
+    pub errors: BTreeMap<String, u64>,
+}
+
+/// The usage counters kept in a data directory
+#[derive(Debug, Clone)]
+pub struct UsageLog {
+    path: PathBuf,
+}
+
+impl UsageLog {
+    /// Counters kept in `data_dir`
+    pub fn in_dir(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(USAGE_FILE),
+        }
+    }
+
+    /// Counters gathered so far, none if nothing was recorded
+    pub fn load(&self) -> Result<UsageCounters> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Malformed usage counters in {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(UsageCounters::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        }
+    }
+
+    /// Count a run of the command `name`
+    pub fn record_command(&self, name: &str) -> Result<()> {
+        self.update(|counters| *counters.commands.entry(name.to_string()).or_default() += 1)
+    }
+
+    /// Count a command failing with `code`
+    pub fn record_error(&self, code: ErrorCode) -> Result<()> {
+        self.update(|counters| {
+            *counters
+                .errors
+                .entry(code.as_str().to_string())
+                .or_default() += 1
+        })
+    }
+
+    /// Forget the counters, once they have been reported
+    pub fn clear(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn update(&self, change: impl FnOnce(&mut UsageCounters)) -> Result<()> {
+        let mut counters = self.load()?;
+        change(&mut counters);
+        let json = serde_json::to_vec(&counters)?;
+        // The first command counted may run before anything else made the
+        // data directory
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&self.path, json)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+/// Everything a usage report sends, as `mate telemetry show` previews it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// mate version sending the report
+    pub version: String,
+    /// Games stored, by status
+    pub games: BTreeMap<String, u64>,
+    #[serde(flatten)]
+    pub counters: UsageCounters,
+}
+
+impl UsageReport {
+    /// A report of the games in `database` and the `counters` gathered
+    pub fn new(database: &Database, counters: UsageCounters) -> Result<Self> {
+        let mut games = BTreeMap::new();
+        for status in [
+            GameStatus::Pending,
+            GameStatus::Active,
+            GameStatus::Completed,
+            GameStatus::Abandoned,
+        ] {
+            let count = database
+                .count_games(&GameFilter {
+                    statuses: vec![status.clone()],
+                    ..GameFilter::default()
+                })
+                .context("Failed to count games")?;
+            games.insert(status.as_str().to_string(), u64::from(count));
+        }
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            games,
+            counters,
+        })
+    }
+
+    /// Post the report to `endpoint`
+    pub async fn send(&self, endpoint: &HttpUrl) -> Result<()> {
+        let body = serde_json::to_vec(self)?;
+        let status =
+            tokio::time::timeout(SEND_TIMEOUT, http_client::post_json(endpoint, &[], &body))
+                .await
+                .context("Timed out sending usage report")??;
+        if !(200..300).contains(&status) {
+            anyhow::bail!("Usage endpoint answered with HTTP status {status}");
+        }
+        Ok(())
+    }
+}
+
+/// Sends a usage report every [`USAGE_REPORT_INTERVAL`] while `mate serve`
+/// runs, when `telemetry.usage_reports` is on
+pub struct UsageReporter {
+    database: Arc<Database>,
+    log: UsageLog,
+    endpoint: HttpUrl,
+    heartbeat: Option<Heartbeat>,
+}
+
+impl UsageReporter {
+    pub fn new(database: Arc<Database>, log: UsageLog, endpoint: HttpUrl) -> Self {
+        Self {
+            database,
+            log,
+            endpoint,
+            heartbeat: None,
+        }
+    }
+
+    /// Beat `heartbeat` after every attempt, for the watchdog of `mate serve`
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Build and send a report, then start counting afresh
+    pub async fn report(&self) -> Result<UsageReport> {
+        let report = UsageReport::new(&self.database, self.log.load()?)?;
+        report.send(&self.endpoint).await?;
+        self.log.clear()?;
+        info!("Sent usage report to {}", self.endpoint.as_str());
+        Ok(report)
+    }
+
+    /// Report every [`USAGE_REPORT_INTERVAL`], the first a full interval
+    /// after starting, until the task is cancelled
+    ///
+    /// A report that fails is retried sooner, after [`retry_delay`].
+    pub async fn run(self) -> Result<()> {
+        let mut failures = 0;
+        loop {
+            let wait = match failures {
+                0 => USAGE_REPORT_INTERVAL,
+                failures => retry_delay(failures),
+            };
+            tokio::time::sleep(wait).await;
+            match self.report().await {
+                Ok(_) => failures = 0,
+                Err(e) => {
+                    failures += 1;
+                    warn!(
+                        "Failed to send usage report, retrying in {}s: {:#}",
+                        retry_delay(failures).as_secs(),
+                        e
+                    );
+                }
+            }
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
+        }
+    }
+}
+
+/// How long to wait before retrying after `failures` reports in a row failed
+pub fn retry_delay(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    (USAGE_RETRY_DELAY * 2u32.pow(doublings)).min(USAGE_REPORT_INTERVAL)
+}
@@ -428,6 +428,55 @@ impl InputValidationUtils {
         }
     }
 
+    /// Parse a YYYY-MM-DD date into the Unix timestamp of its start (UTC)
+    pub fn parse_date(date: &str) -> Option<i64> {
+        let mut parts = date.trim().splitn(3, '-');
+        let year: i64 = parts.next()?.parse().ok()?;
+        let month: i64 = parts.next()?.parse().ok()?;
+        let day: i64 = parts.next()?.parse().ok()?;
+
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+        if !(1..=days_in_month).contains(&day) {
+            return None;
+        }
+
+        // Days-from-civil conversion (proleptic Gregorian calendar)
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y.rem_euclid(400);
+        let mp = (month + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+
+        Some(days * 86_400)
+    }
+
+    /// Parse a YYYY-MM-DD date (UTC), or an age such as `12h`, `7d`, or `2w`
+    /// counted back from `now`, into a Unix timestamp
+    pub fn parse_since(value: &str, now: i64) -> Option<i64> {
+        let value = value.trim();
+        if let Some(timestamp) = Self::parse_date(value) {
+            return Some(timestamp);
+        }
+
+        let unit = value.chars().last()?;
+        let amount: u32 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+        let seconds = match unit.to_ascii_lowercase() {
+            'h' => 3_600,
+            'd' => 86_400,
+            'w' => 604_800,
+            _ => return None,
+        };
+        Some(now - i64::from(amount) * seconds)
+    }
+
     /// Generate a user-friendly error message for invalid moves
     pub fn move_error_with_suggestions(invalid_move: &str) -> String {
         let mut suggestions = Vec::new();
@@ -559,4 +608,48 @@ mod tests {
         assert!(!InputValidationUtils::looks_like_uuid("123-456"));
         assert!(!InputValidationUtils::looks_like_uuid(""));
     }
+
+    #[test]
+    fn test_date_parsing() {
+        assert_eq!(InputValidationUtils::parse_date("1970-01-01"), Some(0));
+        assert_eq!(
+            InputValidationUtils::parse_date("2000-02-29"),
+            Some(951_782_400)
+        );
+        assert_eq!(
+            InputValidationUtils::parse_date("2024-01-01"),
+            Some(1_704_067_200)
+        );
+
+        assert_eq!(InputValidationUtils::parse_date("2023-02-29"), None);
+        assert_eq!(InputValidationUtils::parse_date("2024-13-01"), None);
+        assert_eq!(InputValidationUtils::parse_date("2024-01"), None);
+        assert_eq!(InputValidationUtils::parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_since_parsing() {
+        let now = 1_000_000;
+        assert_eq!(
+            InputValidationUtils::parse_since("2024-01-01", now),
+            Some(1_704_067_200)
+        );
+        assert_eq!(
+            InputValidationUtils::parse_since("12h", now),
+            Some(now - 43_200)
+        );
+        assert_eq!(
+            InputValidationUtils::parse_since("7D", now),
+            Some(now - 604_800)
+        );
+        assert_eq!(
+            InputValidationUtils::parse_since("2w", now),
+            Some(now - 1_209_600)
+        );
+
+        assert_eq!(InputValidationUtils::parse_since("d", now), None);
+        assert_eq!(InputValidationUtils::parse_since("-3d", now), None);
+        assert_eq!(InputValidationUtils::parse_since("3m", now), None);
+        assert_eq!(InputValidationUtils::parse_since("yesterday", now), None);
+    }
 }
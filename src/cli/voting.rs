@@ -0,0 +1,227 @@
+use crate::chess::Board;
+use crate::cli::game_ops::{GameOps, GameRecord, MoveProcessor};
+use crate::cli::network_manager::NetworkManager;
+use crate::cli::reminders::your_move_since;
+use crate::crypto::Identity;
+use crate::network::Heartbeat;
+use crate::storage::models::{Consultation, ConsultationVote, GameResult, GameStatus};
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How often `mate serve` looks for votes that have closed
+pub const VOTE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long each vote stays open unless `mate vote --open` says otherwise
+pub const DEFAULT_VOTE_WINDOW_SECS: u64 = 300;
+
+/// The votes for one move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tally {
+    /// The move in coordinate notation
+    pub chess_move: String,
+    pub san: String,
+    pub votes: usize,
+    /// When the first vote for the move was cast, in Unix seconds
+    pub first_cast_at: i64,
+}
+
+/// Count the votes for each legal move in a position, most voted first
+///
+/// Votes for the same move in SAN and in coordinate notation count
+/// together, and votes for moves that are not legal are left out. Ties go to
+/// the move that was voted for first.
+pub fn tally(board: &Board, votes: &[ConsultationVote]) -> Vec<Tally> {
+    let mut tallies: Vec<Tally> = Vec::new();
+    for vote in votes {
+        let Ok(mv) = board.parse_move(&vote.chess_move) else {
+            continue;
+        };
+        let chess_move = mv.to_string();
+        match tallies.iter_mut().find(|t| t.chess_move == chess_move) {
+            Some(tally) => {
+                tally.votes += 1;
+                tally.first_cast_at = tally.first_cast_at.min(vote.cast_at);
+            }
+            None => tallies.push(Tally {
+                chess_move,
+                san: board.to_san(mv),
+                votes: 1,
+                first_cast_at: vote.cast_at,
+            }),
+        }
+    }
+    tallies.sort_by(|a, b| {
+        b.votes
+            .cmp(&a.votes)
+            .then(a.first_cast_at.cmp(&b.first_cast_at))
+            .then_with(|| a.chess_move.cmp(&b.chess_move))
+    });
+    tallies
+}
+
+/// When the vote on the group's next move opened, in Unix seconds, or
+/// `None` if the game isn't waiting on the group
+///
+/// That is when the opponent last moved, or when the game was put to a vote
+/// if that was later.
+pub fn vote_opened_at(
+    database: &Database,
+    record: &GameRecord,
+    consultation: &Consultation,
+) -> Result<Option<i64>> {
+    Ok(your_move_since(database, record)?.map(|since| since.max(consultation.created_at)))
+}
+
+/// A vote that has closed, with the move the group chose
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedVote {
+    pub game_id: String,
+    pub opponent_peer_id: String,
+    pub winner: Tally,
+    /// Votes cast for legal moves
+    pub total_votes: usize,
+}
+
+/// Plays the group's most voted move in consultation games once each vote
+/// closes
+///
+/// A vote closes when its window has passed since the group's turn began
+/// and at least one legal move has been voted for.
+pub struct Voting {
+    database: Arc<Database>,
+    identity: Arc<Identity>,
+    network_manager: NetworkManager,
+    heartbeat: Option<Heartbeat>,
+}
+
+impl Voting {
+    pub fn new(
+        database: Arc<Database>,
+        identity: Arc<Identity>,
+        network_manager: NetworkManager,
+    ) -> Self {
+        Self {
+            database,
+            identity,
+            network_manager,
+            heartbeat: None,
+        }
+    }
+
+    /// Beat `heartbeat` after every check, for the watchdog of `mate serve`
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Votes that have closed at `now` (Unix seconds)
+    pub fn closed(&self, now: i64) -> Result<Vec<ClosedVote>> {
+        let game_ops = GameOps::new(&self.database);
+        let mut closed = Vec::new();
+        for consultation in self
+            .database
+            .get_consultations()
+            .context("Failed to retrieve consultation games")?
+        {
+            let record = game_ops
+                .get_game_record(&consultation.game_id)
+                .context("Failed to retrieve consultation game")?;
+            if record.game.status != GameStatus::Active {
+                continue;
+            }
+            let Some(opened_at) = vote_opened_at(&self.database, &record, &consultation)? else {
+                continue;
+            };
+            if now < opened_at + consultation.window_secs {
+                continue;
+            }
+
+            let votes = self
+                .database
+                .get_votes(&consultation.game_id, opened_at)
+                .context("Failed to retrieve votes")?;
+            let state = game_ops
+                .reconstruct_game_state(&consultation.game_id)
+                .context("Failed to reconstruct consultation game")?;
+            let tallies = tally(&state.board, &votes);
+            let total_votes = tallies.iter().map(|t| t.votes).sum();
+            if let Some(winner) = tallies.into_iter().next() {
+                closed.push(ClosedVote {
+                    game_id: consultation.game_id,
+                    opponent_peer_id: record.game.opponent_peer_id,
+                    winner,
+                    total_votes,
+                });
+            }
+        }
+        Ok(closed)
+    }
+
+    /// Play the move a closed vote chose and send it to the opponent
+    pub async fn play(&self, closed: &ClosedVote) -> Result<()> {
+        let result = MoveProcessor::new(&self.database)
+            .with_signer(&self.identity)
+            .process_move(&closed.game_id, &closed.winner.chess_move, true)
+            .with_context(|| format!("Failed to play the voted move {}", closed.winner.san))?;
+        self.database
+            .clear_votes(&closed.game_id)
+            .context("Failed to clear votes")?;
+        info!(
+            "Played {} in game {} with {} of {} votes",
+            closed.winner.san, closed.game_id, closed.winner.votes, closed.total_votes
+        );
+
+        let board = &result.updated_board;
+        if board.is_checkmate() {
+            self.database
+                .update_game_result(&closed.game_id, GameResult::Win)
+                .context("Failed to record game result")?;
+        } else if board.is_stalemate() {
+            self.database
+                .update_game_result(&closed.game_id, GameResult::Draw)
+                .context("Failed to record game result")?;
+        }
+
+        if let Err(e) = self
+            .network_manager
+            .send_chess_move(
+                &closed.opponent_peer_id,
+                closed.game_id.clone(),
+                result.move_message,
+            )
+            .await
+        {
+            warn!(
+                "Could not send the voted move in game {} ({:#}); it is queued and will be resent",
+                closed.game_id, e
+            );
+        }
+        Ok(())
+    }
+
+    /// Check for closed votes every [`VOTE_CHECK_INTERVAL`] until the task is
+    /// cancelled
+    pub async fn run(self) -> Result<()> {
+        let mut interval = tokio::time::interval(VOTE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = Database::current_timestamp();
+            match self.closed(now) {
+                Ok(closed) => {
+                    for vote in &closed {
+                        if let Err(e) = self.play(vote).await {
+                            warn!("{:#}", e);
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to check consultation votes: {:#}", e),
+            }
+            if let Some(heartbeat) = &self.heartbeat {
+                heartbeat.beat();
+            }
+        }
+    }
+}
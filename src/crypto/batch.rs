@@ -0,0 +1,47 @@
+use std::num::NonZeroUsize;
+
+/// Batches smaller than this are verified on the calling thread, where
+/// starting workers would cost more than it saves
+pub const PARALLEL_VERIFY_THRESHOLD: usize = 64;
+
+/// Run `verify` over `items` on every available core, returning the results
+/// in the order of `items`
+///
+/// Blocks until all items are checked, so async callers should run it with
+/// `tokio::task::spawn_blocking` rather than on the runtime.
+pub fn verify_in_parallel<T, R>(items: &[T], verify: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let workers = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    verify_on_workers(workers, items, verify)
+}
+
+/// [`verify_in_parallel`] with at most `workers` threads
+pub fn verify_on_workers<T, R>(
+    workers: usize,
+    items: &[T],
+    verify: impl Fn(&T) -> R + Sync,
+) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    if workers <= 1 || items.len() < PARALLEL_VERIFY_THRESHOLD {
+        return items.iter().map(verify).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(workers);
+    let verify = &verify;
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(verify).collect::<Vec<_>>()))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("signature verification panicked"))
+            .collect()
+    })
+}
@@ -0,0 +1,543 @@
+use crate::chess::Board;
+use crate::crypto::identity::{Identity, PeerId};
+use crate::messages::chess::ArbiterUpdate;
+#[cfg(feature = "native")]
+use crate::messages::chess::Move;
+#[cfg(feature = "native")]
+use crate::storage::models::{Game, Message, PlayerColor};
+#[cfg(feature = "native")]
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::Signature;
+use serde::{Deserialize, Serialize};
+
+/// Domain separator so a result signature cannot be mistaken for any other
+/// signed data
+const RESULT_SIGNATURE_CONTEXT: &[u8] = b"mate-game-result-v1";
+
+/// Domain separator for an arbiter's ruling on an illegal move claim
+const ARBITER_RULING_CONTEXT: &[u8] = b"mate-arbiter-ruling-v1";
+
+/// Version of the certificate file format written by this build
+pub const CERTIFICATE_VERSION: u32 = 1;
+
+/// Message type under which result signatures are stored with a game
+pub const RESULT_SIGNATURE_MESSAGE_TYPE: &str = "ResultSignature";
+
+/// Message type under which a player records the arbiter they appointed
+pub const ARBITER_APPOINTMENT_MESSAGE_TYPE: &str = "ArbiterAppointment";
+
+/// Message type under which a player records where the arbiter joined from
+pub const ARBITER_JOIN_MESSAGE_TYPE: &str = "ArbiterJoin";
+
+/// Message type under which a player keeps the arbiter's rulings
+pub const ARBITER_RULING_MESSAGE_TYPE: &str = "ArbiterRuling";
+
+/// PGN result tokens for a finished game
+const FINISHED_RESULTS: [&str; 3] = ["1-0", "0-1", "1/2-1/2"];
+
+/// What both players sign when a game ends, like the scoresheets signed
+/// after an over-the-board game
+///
+/// Built from each player's own copy of the game, so the two signatures only
+/// match if both sides recorded the same players, moves, and result. With an
+/// arbiter, both players must also have appointed the same one, who signs
+/// the sheet too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreSheet {
+    pub game_id: String,
+    /// Peer ID of the player with the white pieces
+    pub white: String,
+    /// Peer ID of the player with the black pieces
+    pub black: String,
+    /// PGN result token
+    pub result: String,
+    /// Moves in the order they were played
+    pub moves: Vec<String>,
+    /// Peer ID of the arbiter, if the game has one
+    pub arbiter: Option<String>,
+}
+
+impl ScoreSheet {
+    /// The record of a stored game as seen by `local_peer_id`
+    #[cfg(feature = "native")]
+    pub fn from_game(game: &Game, local_peer_id: &str, messages: &[Message]) -> Self {
+        let (white, black) = match game.my_color {
+            PlayerColor::White => (local_peer_id, game.opponent_peer_id.as_str()),
+            PlayerColor::Black => (game.opponent_peer_id.as_str(), local_peer_id),
+        };
+        let moves = messages
+            .iter()
+            .filter(|m| m.message_type.eq_ignore_ascii_case("move"))
+            .filter_map(|m| serde_json::from_str::<Move>(&m.content).ok())
+            .map(|m| m.chess_move)
+            .collect();
+
+        Self {
+            game_id: game.id.clone(),
+            white: white.to_string(),
+            black: black.to_string(),
+            result: game.pgn_result().to_string(),
+            moves,
+            arbiter: appointed_arbiter(messages),
+        }
+    }
+
+    /// The record of an arbitrated game as the arbiter `arbiter` saw it
+    pub fn from_arbiter_update(update: &ArbiterUpdate, arbiter: &str) -> Self {
+        Self {
+            game_id: update.game_id.clone(),
+            white: update.white.clone(),
+            black: update.black.clone(),
+            result: update.result.clone(),
+            moves: update.moves.clone(),
+            arbiter: Some(arbiter.to_string()),
+        }
+    }
+
+    /// The game as sent to its arbiter
+    pub fn arbiter_update(&self) -> ArbiterUpdate {
+        ArbiterUpdate {
+            game_id: self.game_id.clone(),
+            white: self.white.clone(),
+            black: self.black.clone(),
+            moves: self.moves.clone(),
+            result: self.result.clone(),
+        }
+    }
+
+    /// Whether `peer_id` may sign the sheet, as a player or its arbiter
+    pub fn is_signer(&self, peer_id: &str) -> bool {
+        peer_id == self.white || peer_id == self.black || self.arbiter.as_deref() == Some(peer_id)
+    }
+
+    /// Whether the game has a decisive or drawn result to sign
+    pub fn is_finished(&self) -> bool {
+        FINISHED_RESULTS.contains(&self.result.as_str())
+    }
+
+    /// The game as a PGN document, identical for both players
+    ///
+    /// Only the players, arbiter, result, and moves are included; dates,
+    /// tags, and notes differ between the two copies of a game and are left
+    /// out.
+    pub fn pgn(&self) -> String {
+        let mut pgn = String::new();
+        let mut headers = vec![
+            ("Event", "mate P2P game"),
+            ("Site", "mate"),
+            ("Date", "????.??.??"),
+            ("Round", "-"),
+            ("White", self.white.as_str()),
+            ("Black", self.black.as_str()),
+            ("Result", self.result.as_str()),
+            ("GameId", self.game_id.as_str()),
+        ];
+        if let Some(arbiter) = &self.arbiter {
+            headers.push(("Arbiter", arbiter.as_str()));
+        }
+        for (name, value) in headers {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            pgn.push_str(&format!("[{name} \"{value}\"]\n"));
+        }
+        pgn.push('\n');
+
+        for (index, chess_move) in self.moves.iter().enumerate() {
+            if index % 2 == 0 {
+                pgn.push_str(&format!("{}. ", index / 2 + 1));
+            }
+            pgn.push_str(chess_move);
+            pgn.push(' ');
+        }
+        pgn.push_str(&self.result);
+        pgn.push('\n');
+        pgn
+    }
+}
+
+/// One player's, or the arbiter's, signature over the result of a game
+///
+/// Each player signs when their copy of the game ends and sends the signature
+/// to the opponent, who keeps it for the game's certificate. An arbiter signs
+/// in answer to the final `ArbiterUpdate` from either player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultSignature {
+    pub game_id: String,
+    /// Peer ID of the signing player or arbiter
+    pub signer: String,
+    /// PGN result token the signer recorded
+    pub result: String,
+    /// Base64 Ed25519 signature
+    pub signature: String,
+}
+
+impl ResultSignature {
+    /// Sign the result of a finished game as one of its players or its arbiter
+    pub fn sign(identity: &Identity, record: &ScoreSheet) -> Result<Self> {
+        let signer = identity.peer_id().to_string();
+        if !record.is_finished() {
+            anyhow::bail!("Game {} has not finished", record.game_id);
+        }
+        if !record.is_signer(&signer) {
+            anyhow::bail!(
+                "Only the players and arbiter of game {} can sign it",
+                record.game_id
+            );
+        }
+        let signature = identity.sign(&signed_bytes(
+            &record.game_id,
+            &record.white,
+            &record.black,
+            &record.result,
+            &record.pgn(),
+        ))?;
+
+        Ok(Self {
+            game_id: record.game_id.clone(),
+            signer,
+            result: record.result.clone(),
+            signature: general_purpose::STANDARD.encode(signature.to_bytes()),
+        })
+    }
+
+    /// Check that a player or the arbiter of `record` signed exactly that
+    /// record
+    pub fn verify(&self, record: &ScoreSheet) -> Result<()> {
+        if self.game_id != record.game_id {
+            anyhow::bail!("Signature is for game {}", self.game_id);
+        }
+        if !record.is_signer(&self.signer) {
+            anyhow::bail!(
+                "Signer {} did not play or arbitrate game {}",
+                self.signer,
+                record.game_id
+            );
+        }
+        if self.result != record.result {
+            anyhow::bail!(
+                "Signer recorded the result {}, not {}",
+                self.result,
+                record.result
+            );
+        }
+        verify_signature(
+            &self.signer,
+            &self.signature,
+            &signed_bytes(
+                &record.game_id,
+                &record.white,
+                &record.black,
+                &record.result,
+                &record.pgn(),
+            ),
+        )
+    }
+}
+
+/// A finished game signed by both players, and by its arbiter if it had one
+///
+/// Self-contained: anyone can check it without a mate database or a
+/// connection to either player, e.g. before submitting the game for rating.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameCertificate {
+    pub version: u32,
+    pub game_id: String,
+    pub white: String,
+    pub black: String,
+    pub result: String,
+    pub pgn: String,
+    /// Base64 Ed25519 signature by the white player
+    pub white_signature: String,
+    /// Base64 Ed25519 signature by the black player
+    pub black_signature: String,
+    /// Peer ID of the arbiter, absent from games without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arbiter: Option<String>,
+    /// Base64 Ed25519 signature by the arbiter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arbiter_signature: Option<String>,
+}
+
+impl GameCertificate {
+    /// Assemble the certificate for `record` from the players' signatures
+    ///
+    /// Fails unless `signatures` holds a valid signature by each player, and
+    /// by the arbiter if the game has one.
+    pub fn new(record: &ScoreSheet, signatures: &[ResultSignature]) -> Result<Self> {
+        let signature_by = |player: &str| {
+            signatures
+                .iter()
+                .find(|s| s.signer == player && s.verify(record).is_ok())
+                .map(|s| s.signature.clone())
+                .with_context(|| format!("No valid signature from {player}"))
+        };
+
+        Ok(Self {
+            version: CERTIFICATE_VERSION,
+            game_id: record.game_id.clone(),
+            white: record.white.clone(),
+            black: record.black.clone(),
+            result: record.result.clone(),
+            pgn: record.pgn(),
+            white_signature: signature_by(&record.white)?,
+            black_signature: signature_by(&record.black)?,
+            arbiter: record.arbiter.clone(),
+            arbiter_signature: record.arbiter.as_deref().map(signature_by).transpose()?,
+        })
+    }
+
+    /// Check that both players, and the arbiter if any, signed this game and
+    /// result
+    pub fn verify(&self) -> Result<()> {
+        if self.version != CERTIFICATE_VERSION {
+            anyhow::bail!("Unsupported certificate version {}", self.version);
+        }
+        if !FINISHED_RESULTS.contains(&self.result.as_str()) {
+            anyhow::bail!(
+                "Certificate result '{}' is not a finished game",
+                self.result
+            );
+        }
+        if self.white == self.black {
+            anyhow::bail!("Certificate names the same player for both sides");
+        }
+        // The players signed the PGN, so its header decides whether the
+        // arbiter had to sign too
+        if self.pgn_header("Arbiter") != self.arbiter.as_deref() {
+            anyhow::bail!("Certificate's arbiter does not match its PGN");
+        }
+
+        let signed = signed_bytes(
+            &self.game_id,
+            &self.white,
+            &self.black,
+            &self.result,
+            &self.pgn,
+        );
+        verify_signature(&self.white, &self.white_signature, &signed)
+            .context("White's signature is invalid")?;
+        verify_signature(&self.black, &self.black_signature, &signed)
+            .context("Black's signature is invalid")?;
+        if let Some(arbiter) = &self.arbiter {
+            if arbiter == &self.white || arbiter == &self.black {
+                anyhow::bail!("Certificate names a player as the arbiter");
+            }
+            let signature = self
+                .arbiter_signature
+                .as_deref()
+                .context("Certificate names an arbiter who did not sign it")?;
+            verify_signature(arbiter, signature, &signed)
+                .context("The arbiter's signature is invalid")?;
+        }
+        Ok(())
+    }
+
+    /// Value of a tag in the certified PGN
+    fn pgn_header(&self, name: &str) -> Option<&str> {
+        let prefix = format!("[{name} \"");
+        self.pgn
+            .lines()
+            .take_while(|line| line.starts_with('['))
+            .find_map(|line| line.strip_prefix(&prefix)?.strip_suffix("\"]"))
+    }
+
+    /// Number of moves in the certified game
+    pub fn move_count(&self) -> usize {
+        let movetext = self.pgn.split("\n\n").nth(1).unwrap_or_default();
+        movetext
+            .split_whitespace()
+            .filter(|token| !token.ends_with('.') && *token != self.result)
+            .count()
+    }
+}
+
+/// An arbiter's signed ruling on a claim that a move was illegal
+///
+/// The arbiter replays its own copy of the moves, which both players sent
+/// it, up to the claimed move. The claim is upheld if that move was not
+/// legal in the position before it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArbiterRuling {
+    pub game_id: String,
+    /// Peer ID of the arbiter
+    pub arbiter: String,
+    /// Peer ID of the player who made the claim
+    pub claimant: String,
+    /// Half-move the claim is about, counting from 1
+    pub ply: u32,
+    /// The move at `ply` in the arbiter's copy, if it has one
+    pub chess_move: Option<String>,
+    /// Whether the move was found illegal
+    pub upheld: bool,
+    pub reason: String,
+    /// Base64 Ed25519 signature by the arbiter
+    pub signature: String,
+}
+
+impl ArbiterRuling {
+    /// Rule on `claimant`'s claim that the move at `ply` of `moves` was illegal
+    pub fn rule(
+        identity: &Identity,
+        game_id: &str,
+        claimant: &str,
+        moves: &[String],
+        ply: u32,
+    ) -> Result<Self> {
+        let index = (ply as usize)
+            .checked_sub(1)
+            .context("Moves are counted from 1")?;
+        let chess_move = moves.get(index).cloned();
+        let (upheld, reason) = match &chess_move {
+            None => (
+                false,
+                format!(
+                    "No move {ply} has been played; the game has {} moves",
+                    moves.len()
+                ),
+            ),
+            Some(claimed) => {
+                let mut board = Board::new();
+                let mut ruling = None;
+                for (number, played) in moves[..index].iter().enumerate() {
+                    match board.parse_move(played).and_then(|mv| board.make_move(mv)) {
+                        Ok(()) => {}
+                        Err(e) => {
+                            ruling = Some((
+                                false,
+                                format!("Move {} ({played}) was already illegal: {e}", number + 1),
+                            ));
+                            break;
+                        }
+                    }
+                }
+                ruling.unwrap_or_else(|| match board.parse_move(claimed) {
+                    Ok(_) => (false, format!("{claimed} was legal")),
+                    Err(e) => (true, format!("{claimed} was illegal: {e}")),
+                })
+            }
+        };
+
+        let mut ruling = Self {
+            game_id: game_id.to_string(),
+            arbiter: identity.peer_id().to_string(),
+            claimant: claimant.to_string(),
+            ply,
+            chess_move,
+            upheld,
+            reason,
+            signature: String::new(),
+        };
+        let signature = identity.sign(&ruling.signed_bytes())?;
+        ruling.signature = general_purpose::STANDARD.encode(signature.to_bytes());
+        Ok(ruling)
+    }
+
+    /// Check that the arbiter signed this ruling
+    pub fn verify(&self) -> Result<()> {
+        verify_signature(&self.arbiter, &self.signature, &self.signed_bytes())
+    }
+
+    /// `Upheld` or `Rejected`
+    pub fn verdict(&self) -> &'static str {
+        if self.upheld {
+            "Upheld"
+        } else {
+            "Rejected"
+        }
+    }
+
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut bytes = ARBITER_RULING_CONTEXT.to_vec();
+        for part in [
+            self.game_id.as_str(),
+            &self.arbiter,
+            &self.claimant,
+            &self.ply.to_string(),
+            self.chess_move.as_deref().unwrap_or_default(),
+            if self.upheld { "upheld" } else { "rejected" },
+            &self.reason,
+        ] {
+            bytes.push(b'\n');
+            bytes.extend_from_slice(part.as_bytes());
+        }
+        bytes
+    }
+}
+
+/// The arbiter a player appointed for a game, from its stored messages
+#[cfg(feature = "native")]
+pub fn appointed_arbiter(messages: &[Message]) -> Option<String> {
+    messages
+        .iter()
+        .rev()
+        .find(|m| m.message_type == ARBITER_APPOINTMENT_MESSAGE_TYPE)
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(&m.content).ok())
+        .and_then(|content| content["arbiter"].as_str().map(str::to_string))
+}
+
+/// The address the appointed arbiter last joined a game from
+#[cfg(feature = "native")]
+pub fn arbiter_address(messages: &[Message]) -> Option<String> {
+    let arbiter = appointed_arbiter(messages)?;
+    messages
+        .iter()
+        .rev()
+        .find(|m| m.message_type == ARBITER_JOIN_MESSAGE_TYPE && m.sender_peer_id == arbiter)
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(&m.content).ok())
+        .and_then(|content| content["address"].as_str().map(str::to_string))
+}
+
+/// Result signatures stored with a game's messages
+#[cfg(feature = "native")]
+pub fn stored_result_signatures(messages: &[Message]) -> Vec<ResultSignature> {
+    messages
+        .iter()
+        .filter(|m| m.message_type == RESULT_SIGNATURE_MESSAGE_TYPE)
+        .filter_map(|m| serde_json::from_str(&m.content).ok())
+        .collect()
+}
+
+/// Keep a verified result signature with its game, unless it is already there
+#[cfg(feature = "native")]
+pub fn store_result_signature(database: &Database, signature: &ResultSignature) -> Result<()> {
+    let messages = database
+        .get_messages_for_game(&signature.game_id)
+        .context("Failed to retrieve game messages")?;
+    if stored_result_signatures(&messages).contains(signature) {
+        return Ok(());
+    }
+    database
+        .store_message(
+            signature.game_id.clone(),
+            RESULT_SIGNATURE_MESSAGE_TYPE.to_string(),
+            serde_json::to_string(signature).context("Failed to serialize result signature")?,
+            signature.signature.clone(),
+            signature.signer.clone(),
+        )
+        .context("Failed to store result signature")?;
+    Ok(())
+}
+
+fn signed_bytes(game_id: &str, white: &str, black: &str, result: &str, pgn: &str) -> Vec<u8> {
+    let mut bytes = RESULT_SIGNATURE_CONTEXT.to_vec();
+    for part in [game_id, white, black, result, pgn] {
+        bytes.push(b'\n');
+        bytes.extend_from_slice(part.as_bytes());
+    }
+    bytes
+}
+
+fn verify_signature(signer: &str, signature: &str, signed: &[u8]) -> Result<()> {
+    let key = PeerId::from_string(signer.to_string())
+        .to_verifying_key()
+        .context("Invalid peer ID in result signature")?;
+    let bytes = general_purpose::STANDARD
+        .decode(signature)
+        .context("Invalid result signature encoding")?;
+    let signature = Signature::from_slice(&bytes).context("Invalid result signature")?;
+    if !Identity::verify(&key, signed, &signature) {
+        anyhow::bail!("Result signature does not verify");
+    }
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::crypto::encryption::EncryptedData;
+use crate::crypto::identity::Identity;
+use anyhow::{Context, Result};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Domain separator so a move commitment cannot collide with other digests
+const COMMITMENT_CONTEXT: &[u8] = b"mate-sealed-move-v1";
+
+/// Domain separator for the signature a sealed move is encrypted with
+const SEAL_KEY_CONTEXT: &[u8] = b"mate-sealed-move-key-v1";
+
+/// Random bytes mixed into a commitment, so the few legal moves in a
+/// position cannot be tried one by one against it
+const NONCE_LEN: usize = 32;
+
+/// A move written down but kept secret until the game resumes, like the
+/// sealed move of an adjourned over-the-board game
+///
+/// The opponent is sent only the [`commitment`](Self::commitment). On
+/// resumption the move and nonce are revealed, and the opponent checks that
+/// they produce the same commitment, so the move cannot be changed after
+/// sealing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedMove {
+    pub game_id: String,
+    /// Number of the move in the game, counting both sides' moves from 1
+    pub move_number: u32,
+    pub chess_move: String,
+    /// Hex-encoded random nonce
+    pub nonce: String,
+}
+
+impl SealedMove {
+    /// Seal `chess_move` as move `move_number` of a game, with a fresh nonce
+    pub fn new(game_id: &str, move_number: u32, chess_move: &str) -> Self {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        Self {
+            game_id: game_id.to_string(),
+            move_number,
+            chess_move: chess_move.to_string(),
+            nonce: hex::encode(nonce),
+        }
+    }
+
+    /// Hex-encoded SHA-256 commitment to the game, move number, move, and
+    /// nonce
+    pub fn commitment(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(COMMITMENT_CONTEXT);
+        for part in [
+            self.game_id.as_str(),
+            &self.move_number.to_string(),
+            &self.chess_move,
+            &self.nonce,
+        ] {
+            hasher.update(b"\n");
+            hasher.update(part.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Check that this is the move committed to by `commitment`
+    pub fn verify(&self, commitment: &str) -> Result<()> {
+        if !self.commitment().eq_ignore_ascii_case(commitment) {
+            anyhow::bail!(
+                "Move {} of game {} does not match the sealed commitment",
+                self.move_number,
+                self.game_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Encrypt the sealed move so only `identity` can open it again
+    pub fn encrypt(&self, identity: &Identity) -> Result<EncryptedData> {
+        let plaintext = serde_json::to_vec(self).context("Failed to serialize sealed move")?;
+        EncryptedData::encrypt(&plaintext, &seal_key(identity, &self.game_id)?)
+    }
+
+    /// Open a sealed move of `game_id` encrypted by [`Self::encrypt`]
+    pub fn decrypt(data: &EncryptedData, identity: &Identity, game_id: &str) -> Result<Self> {
+        let plaintext = data
+            .decrypt(&seal_key(identity, game_id)?)
+            .context("The sealed move was encrypted with another key")?;
+        let sealed: Self =
+            serde_json::from_slice(&plaintext).context("Failed to read the sealed move")?;
+        if sealed.game_id != game_id {
+            anyhow::bail!("The sealed move belongs to game {}", sealed.game_id);
+        }
+        Ok(sealed)
+    }
+}
+
+/// Passphrase a game's sealed move is encrypted with
+///
+/// Ed25519 signatures are deterministic, so signing the same text always
+/// gives the same passphrase, and nobody without the key can produce it.
+fn seal_key(identity: &Identity, game_id: &str) -> Result<String> {
+    let mut text = SEAL_KEY_CONTEXT.to_vec();
+    text.extend_from_slice(b"\n");
+    text.extend_from_slice(game_id.as_bytes());
+    let signature = identity
+        .sign(&text)
+        .context("Failed to derive the sealing key")?;
+    Ok(hex::encode(signature.to_bytes()))
+}
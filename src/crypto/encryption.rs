@@ -0,0 +1,176 @@
+use anyhow::{Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Key derivation function identifier recorded alongside encrypted data
+pub const KDF_PBKDF2_SHA256: &str = "pbkdf2-sha256";
+
+/// Memory-hard key derivation function used for identity keys
+pub const KDF_ARGON2ID: &str = "argon2id";
+
+/// Default PBKDF2 iteration count for passphrase-derived keys
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+
+/// Default Argon2id memory cost in KiB
+pub const DEFAULT_ARGON2_MEMORY_KIB: u32 = 19_456;
+
+/// Default Argon2id pass count
+pub const DEFAULT_ARGON2_ITERATIONS: u32 = 2;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Data encrypted with a passphrase-derived ChaCha20-Poly1305 key
+///
+/// All binary fields are base64 encoded so the structure can be embedded
+/// directly in JSON or TOML documents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedData {
+    pub kdf: String,
+    pub iterations: u32,
+    /// Argon2id memory cost in KiB; absent for PBKDF2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_kib: Option<u32>,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl EncryptedData {
+    /// Encrypt plaintext with a key derived from the passphrase
+    pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Self> {
+        Self::encrypt_with_iterations(plaintext, passphrase, DEFAULT_KDF_ITERATIONS)
+    }
+
+    /// Encrypt plaintext using an explicit KDF iteration count
+    pub fn encrypt_with_iterations(
+        plaintext: &[u8],
+        passphrase: &str,
+        iterations: u32,
+    ) -> Result<Self> {
+        Self::seal(plaintext, passphrase, KDF_PBKDF2_SHA256, iterations, None)
+    }
+
+    /// Encrypt plaintext with an Argon2id-derived key using the default costs
+    pub fn encrypt_argon2(plaintext: &[u8], passphrase: &str) -> Result<Self> {
+        Self::encrypt_argon2_with_params(
+            plaintext,
+            passphrase,
+            DEFAULT_ARGON2_MEMORY_KIB,
+            DEFAULT_ARGON2_ITERATIONS,
+        )
+    }
+
+    /// Encrypt plaintext with an Argon2id-derived key using explicit costs
+    pub fn encrypt_argon2_with_params(
+        plaintext: &[u8],
+        passphrase: &str,
+        memory_kib: u32,
+        iterations: u32,
+    ) -> Result<Self> {
+        Self::seal(
+            plaintext,
+            passphrase,
+            KDF_ARGON2ID,
+            iterations,
+            Some(memory_kib),
+        )
+    }
+
+    fn seal(
+        plaintext: &[u8],
+        passphrase: &str,
+        kdf: &str,
+        iterations: u32,
+        memory_kib: Option<u32>,
+    ) -> Result<Self> {
+        if passphrase.is_empty() {
+            anyhow::bail!("Passphrase cannot be empty");
+        }
+
+        let mut rng = rand::rngs::OsRng;
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce);
+
+        let cipher = derive_cipher(passphrase, &salt, kdf, iterations, memory_kib)?;
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+
+        Ok(Self {
+            kdf: kdf.to_string(),
+            iterations,
+            memory_kib,
+            salt: general_purpose::STANDARD.encode(salt),
+            nonce: general_purpose::STANDARD.encode(nonce),
+            ciphertext: general_purpose::STANDARD.encode(ciphertext),
+        })
+    }
+
+    /// Decrypt the data with the given passphrase
+    ///
+    /// Fails if the passphrase is wrong or the data has been tampered with.
+    pub fn decrypt(&self, passphrase: &str) -> Result<Vec<u8>> {
+        let salt = general_purpose::STANDARD
+            .decode(&self.salt)
+            .context("Invalid salt encoding")?;
+        let nonce = general_purpose::STANDARD
+            .decode(&self.nonce)
+            .context("Invalid nonce encoding")?;
+        let ciphertext = general_purpose::STANDARD
+            .decode(&self.ciphertext)
+            .context("Invalid ciphertext encoding")?;
+
+        if nonce.len() != NONCE_LEN {
+            anyhow::bail!(
+                "Invalid nonce length: expected {} bytes, got {}",
+                NONCE_LEN,
+                nonce.len()
+            );
+        }
+
+        let cipher = derive_cipher(
+            passphrase,
+            &salt,
+            &self.kdf,
+            self.iterations,
+            self.memory_kib,
+        )?;
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow::anyhow!("Decryption failed: wrong passphrase or corrupted data"))
+    }
+}
+
+/// Derive a ChaCha20-Poly1305 cipher from a passphrase and salt
+fn derive_cipher(
+    passphrase: &str,
+    salt: &[u8],
+    kdf: &str,
+    iterations: u32,
+    memory_kib: Option<u32>,
+) -> Result<ChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    match kdf {
+        KDF_PBKDF2_SHA256 => {
+            pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key)
+        }
+        KDF_ARGON2ID => {
+            let memory_kib = memory_kib.unwrap_or(DEFAULT_ARGON2_MEMORY_KIB);
+            let params = Params::new(memory_kib, iterations, 1, Some(key.len()))
+                .map_err(|e| anyhow::anyhow!("Invalid Argon2 parameters: {e}"))?;
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                .map_err(|e| anyhow::anyhow!("Key derivation failed: {e}"))?;
+        }
+        other => anyhow::bail!("Unsupported key derivation function: {other}"),
+    }
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
@@ -0,0 +1,130 @@
+use crate::crypto::identity::Signer;
+use anyhow::{Context, Result};
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::eddsa::{EddsaParams, EddsaSignatureScheme};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use ed25519_dalek::{Signature, VerifyingKey};
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Environment variable holding the PIN of a hardware token
+pub const TOKEN_PIN_ENV_VAR: &str = "MATE_TOKEN_PIN";
+
+/// Ed25519 key pair on a PKCS#11 token (a PIV smart card through ykcs11 or
+/// OpenSC, for example) that signs without the secret key leaving the device
+pub struct TokenSigner {
+    // Keeps the module loaded for as long as the session is open
+    _context: Pkcs11,
+    session: Mutex<Session>,
+    private_key: ObjectHandle,
+    verifying_key: VerifyingKey,
+}
+
+impl TokenSigner {
+    /// Open the key pair labelled `key_label` on the first token found by the
+    /// PKCS#11 module at `module`, logging in with the token PIN
+    ///
+    /// The PIN is taken from `MATE_TOKEN_PIN` or asked for on the terminal.
+    pub fn open(module: &Path, key_label: &str) -> Result<Self> {
+        let context = Pkcs11::new(module)
+            .with_context(|| format!("Failed to load PKCS#11 module {}", module.display()))?;
+        context
+            .initialize(CInitializeArgs::OsThreads)
+            .context("Failed to initialize PKCS#11 module")?;
+        let slot = context
+            .get_slots_with_token()
+            .context("Failed to list hardware tokens")?
+            .into_iter()
+            .next()
+            .context("No hardware token found; is it plugged in?")?;
+        let session = context
+            .open_ro_session(slot)
+            .context("Failed to open a session with the hardware token")?;
+        session
+            .login(UserType::User, Some(&AuthPin::new(token_pin(key_label)?)))
+            .context("Failed to log in to the hardware token")?;
+
+        let public_key = find_key(&session, ObjectClass::PUBLIC_KEY, key_label)?;
+        let private_key = find_key(&session, ObjectClass::PRIVATE_KEY, key_label)?;
+        let ec_point = match session
+            .get_attributes(public_key, &[AttributeType::EcPoint])
+            .context("Failed to read the public key from the hardware token")?
+            .pop()
+        {
+            Some(Attribute::EcPoint(bytes)) => bytes,
+            _ => anyhow::bail!("Token key '{key_label}' has no public key point"),
+        };
+        let verifying_key = VerifyingKey::from_bytes(&decode_ec_point(&ec_point)?)
+            .context("Token key is not a valid Ed25519 public key")?;
+
+        Ok(Self {
+            _context: context,
+            session: Mutex::new(session),
+            private_key,
+            verifying_key,
+        })
+    }
+}
+
+impl Signer for TokenSigner {
+    fn verifying_key(&self) -> VerifyingKey {
+        self.verifying_key
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature> {
+        let mechanism = Mechanism::Eddsa(EddsaParams::new(EddsaSignatureScheme::Pure));
+        let signature = self
+            .session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .sign(&mechanism, self.private_key, message)
+            .context("Hardware token failed to sign")?;
+        let signature =
+            Signature::from_slice(&signature).context("Hardware token returned a bad signature")?;
+        self.verifying_key
+            .verify_strict(message, &signature)
+            .context("Hardware token signed with a different key")?;
+        Ok(signature)
+    }
+}
+
+fn find_key(session: &Session, class: ObjectClass, key_label: &str) -> Result<ObjectHandle> {
+    session
+        .find_objects(&[
+            Attribute::Class(class),
+            Attribute::KeyType(KeyType::EC_EDWARDS),
+            Attribute::Label(key_label.as_bytes().to_vec()),
+        ])
+        .context("Failed to search the hardware token")?
+        .into_iter()
+        .next()
+        .with_context(|| format!("No Ed25519 key labelled '{key_label}' on the hardware token"))
+}
+
+/// The 32-byte key from a CKA_EC_POINT, which tokens return either raw or
+/// wrapped in a DER OCTET STRING
+fn decode_ec_point(ec_point: &[u8]) -> Result<[u8; 32]> {
+    let raw = match ec_point {
+        [0x04, 0x20, rest @ ..] if rest.len() == 32 => rest,
+        raw => raw,
+    };
+    raw.try_into()
+        .map_err(|_| anyhow::anyhow!("Token key is not an Ed25519 key"))
+}
+
+fn token_pin(key_label: &str) -> Result<String> {
+    if let Ok(pin) = std::env::var(TOKEN_PIN_ENV_VAR) {
+        return Ok(pin);
+    }
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "Identity key '{key_label}' is on a hardware token; set {TOKEN_PIN_ENV_VAR} or run from a terminal"
+        );
+    }
+    rpassword::prompt_password(format!("PIN for hardware token key '{key_label}': "))
+        .context("Failed to read token PIN")
+}
@@ -1,8 +1,23 @@
+#[cfg(feature = "native")]
+use crate::crypto::storage::{
+    active_key_path_in, KeyBackend, KeychainKeyStorage, DEFAULT_KEY_FILE,
+};
+#[cfg(feature = "native")]
+use crate::crypto::EncryptedData;
 use anyhow::{Context, Result};
 use base64::{engine::general_purpose, Engine as _};
-use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use ed25519_dalek::{Signature, Signer as _, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use sha2::{Digest, Sha256};
+#[cfg(feature = "native")]
+use std::collections::HashMap;
+#[cfg(feature = "native")]
+use std::io::IsTerminal;
+#[cfg(feature = "native")]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+#[cfg(feature = "native")]
+use std::sync::{Mutex, OnceLock};
 
 /// Unique identifier for a peer, derived from their public key
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -23,6 +38,11 @@ impl PeerId {
         &self.0
     }
 
+    /// Short hex digest of the peer ID for reading out or comparing by eye
+    pub fn fingerprint(&self) -> String {
+        hex::encode(&Sha256::digest(self.0.as_bytes())[..8])
+    }
+
     /// Convert PeerId back to VerifyingKey for signature verification
     pub fn to_verifying_key(&self) -> Result<VerifyingKey> {
         let decoded_bytes = general_purpose::STANDARD
@@ -50,19 +70,141 @@ impl std::fmt::Display for PeerId {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+/// Environment variable consulted before prompting for the identity key passphrase
+pub const KEY_PASSPHRASE_ENV_VAR: &str = "MATE_KEY_PASSPHRASE";
 
+#[cfg(feature = "native")]
+#[derive(Serialize, Deserialize)]
 struct IdentityData {
     secret_key: String,
     public_key: String,
 }
 
+/// Identity file whose secret key is encrypted with a passphrase
+#[cfg(feature = "native")]
+#[derive(Serialize, Deserialize)]
+struct ProtectedIdentityData {
+    public_key: String,
+    encrypted_secret_key: EncryptedData,
+}
+
+/// Identity file whose secret key is kept in the OS keychain
+#[cfg(feature = "native")]
+#[derive(Serialize, Deserialize)]
+struct KeychainIdentityData {
+    public_key: String,
+    keychain_account: String,
+}
+
+/// Identity file pointing at a key on a hardware token
+#[cfg(feature = "native")]
+#[derive(Serialize, Deserialize)]
+struct HardwareIdentityData {
+    public_key: String,
+    /// PKCS#11 module that talks to the token
+    pkcs11_module: PathBuf,
+    /// Label of the key pair on the token
+    key_label: String,
+}
+
+/// Any layout of an identity file on disk
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredIdentity {
+    Hardware(HardwareIdentityData),
+    Protected(ProtectedIdentityData),
+    Keychain(KeychainIdentityData),
+    Plain(IdentityData),
+}
+
+#[cfg(feature = "native")]
+impl StoredIdentity {
+    fn load(path: &Path) -> Result<Self> {
+        let content = crate::crypto::storage::load_key_secure(path)
+            .map_err(|e| anyhow::anyhow!("Storage error: {}", e))?;
+
+        let content_str = String::from_utf8(content).context("Invalid UTF-8 in identity file")?;
+
+        serde_json::from_str(&content_str).context("Failed to parse identity file")
+    }
+
+    fn public_key(&self) -> &str {
+        match self {
+            StoredIdentity::Hardware(data) => &data.public_key,
+            StoredIdentity::Protected(data) => &data.public_key,
+            StoredIdentity::Keychain(data) => &data.public_key,
+            StoredIdentity::Plain(data) => &data.public_key,
+        }
+    }
+
+    fn keychain_account(&self) -> Option<&str> {
+        match self {
+            StoredIdentity::Keychain(data) => Some(&data.keychain_account),
+            _ => None,
+        }
+    }
+}
+
+/// Passphrases that unlocked an identity file in this process, by path
+#[cfg(feature = "native")]
+fn passphrase_cache() -> &'static Mutex<HashMap<PathBuf, String>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, String>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Passphrase for the protected key at `path`: cached, from the environment,
+/// or asked for on the terminal
+#[cfg(feature = "native")]
+fn key_passphrase(path: &Path) -> Result<String> {
+    if let Some(passphrase) = passphrase_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(path)
+    {
+        return Ok(passphrase.clone());
+    }
+    if let Ok(passphrase) = std::env::var(KEY_PASSPHRASE_ENV_VAR) {
+        return Ok(passphrase);
+    }
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "Identity key at {} is passphrase protected; set {} or run from a terminal",
+            path.display(),
+            KEY_PASSPHRASE_ENV_VAR
+        );
+    }
+    rpassword::prompt_password(format!("Passphrase for {}: ", path.display()))
+        .context("Failed to read passphrase")
+}
+
+/// Something that signs with an Ed25519 key
+///
+/// [`Identity`] signs with a key in memory; hardware token backends sign on
+/// the device, which can fail, so signing returns a `Result`.
+pub trait Signer: Send + Sync {
+    /// Public key matching the signing key
+    fn verifying_key(&self) -> VerifyingKey;
+
+    /// Sign `message`
+    fn sign(&self, message: &[u8]) -> Result<Signature>;
+}
+
 /// Cryptographic identity using Ed25519 keys
+///
+/// The secret key is held in memory, or stays on a hardware token that signs
+/// on the identity's behalf.
 pub struct Identity {
-    signing_key: SigningKey,
+    key: IdentityKey,
     peer_id: PeerId,
 }
 
+/// Where an identity's signatures are made
+enum IdentityKey {
+    Memory(Box<SigningKey>),
+    Device(Arc<dyn Signer>),
+}
+
 impl Identity {
     /// Generate a new random identity
     pub fn generate() -> Result<Self> {
@@ -73,26 +215,13 @@ impl Identity {
         let signing_key = SigningKey::from_bytes(&secret_bytes);
         let peer_id = PeerId::from_verifying_key(&signing_key.verifying_key());
         Ok(Self {
-            signing_key,
+            key: IdentityKey::Memory(Box::new(signing_key)),
             peer_id,
         })
     }
 
-    /// Load identity from custom storage location  
-    pub fn from_storage_path(path: &Path) -> Result<Self> {
-        // Load using secure storage
-        let content = crate::crypto::storage::load_key_secure(path)
-            .map_err(|e| anyhow::anyhow!("Storage error: {}", e))?;
-
-        let content_str = String::from_utf8(content).context("Invalid UTF-8 in identity file")?;
-
-        let data: IdentityData =
-            serde_json::from_str(&content_str).context("Failed to parse identity file")?;
-        let secret_bytes = general_purpose::STANDARD
-            .decode(&data.secret_key)
-            .context("Invalid secret key encoding")?;
-
-        // Convert Vec<u8> to [u8; 32]
+    /// Reconstruct an identity from raw secret key bytes
+    pub fn from_secret_bytes(secret_bytes: &[u8]) -> Result<Self> {
         if secret_bytes.len() != 32 {
             return Err(anyhow::anyhow!(
                 "Invalid secret key length: expected 32 bytes, got {}",
@@ -100,48 +229,376 @@ impl Identity {
             ));
         }
         let mut secret_array = [0u8; 32];
-        secret_array.copy_from_slice(&secret_bytes);
+        secret_array.copy_from_slice(secret_bytes);
 
         let signing_key = SigningKey::from_bytes(&secret_array);
         let peer_id = PeerId::from_verifying_key(&signing_key.verifying_key());
         Ok(Self {
-            signing_key,
+            key: IdentityKey::Memory(Box::new(signing_key)),
             peer_id,
         })
     }
 
+    /// Identity whose key is held by `signer`, such as a hardware token
+    ///
+    /// Its secret key cannot be exported, backed up, or saved to a file.
+    pub fn from_signer(signer: Arc<dyn Signer>) -> Self {
+        let peer_id = PeerId::from_verifying_key(&signer.verifying_key());
+        Self {
+            key: IdentityKey::Device(signer),
+            peer_id,
+        }
+    }
+
+    /// Whether the secret key is on a device rather than in memory
+    pub fn is_hardware_backed(&self) -> bool {
+        matches!(self.key, IdentityKey::Device(_))
+    }
+
+    fn secret_key(&self) -> Result<&SigningKey> {
+        match &self.key {
+            IdentityKey::Memory(signing_key) => Ok(signing_key),
+            IdentityKey::Device(_) => {
+                anyhow::bail!("The identity key is on a hardware token and cannot be exported")
+            }
+        }
+    }
+
+    /// Get the raw secret key bytes (for encrypted backup only)
+    pub fn to_secret_bytes(&self) -> Result<[u8; 32]> {
+        Ok(self.secret_key()?.to_bytes())
+    }
+
+    /// The secret key as a 24-word BIP39 mnemonic (English word list)
+    ///
+    /// Anyone holding the words holds the identity, so they are meant to be
+    /// written down and kept offline.
+    pub fn to_mnemonic(&self) -> Result<String> {
+        Ok(bip39::Mnemonic::from_entropy(&self.to_secret_bytes()?)
+            .expect("32 bytes is a valid BIP39 entropy length")
+            .to_string())
+    }
+
+    /// Reconstruct an identity from a mnemonic made by [`Identity::to_mnemonic`]
+    ///
+    /// Case and spacing between words do not matter; a mistyped word is
+    /// caught by the mnemonic's checksum.
+    pub fn from_mnemonic(phrase: &str) -> Result<Self> {
+        let normalized = phrase
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mnemonic = bip39::Mnemonic::parse_in_normalized(bip39::Language::English, &normalized)
+            .map_err(|e| anyhow::anyhow!("Invalid recovery phrase: {e}"))?;
+        Self::from_secret_bytes(&mnemonic.to_entropy())
+            .context("Recovery phrase does not encode an identity key")
+    }
+}
+
+#[cfg(feature = "native")]
+impl Identity {
+    /// Load identity from custom storage location
+    ///
+    /// A passphrase-protected key is unlocked with a passphrase cached from an
+    /// earlier load, taken from `MATE_KEY_PASSPHRASE`, or asked for on the
+    /// terminal.
+    pub fn from_storage_path(path: &Path) -> Result<Self> {
+        match StoredIdentity::load(path)? {
+            StoredIdentity::Hardware(data) => Self::from_token(&data),
+            StoredIdentity::Plain(data) => Self::from_encoded_secret(&data.secret_key),
+            StoredIdentity::Keychain(data) => Self::from_keychain(&data),
+            StoredIdentity::Protected(data) => {
+                let passphrase = key_passphrase(path)?;
+                let identity = Self::unlock(&data, &passphrase)?;
+                passphrase_cache()
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(path.to_path_buf(), passphrase);
+                Ok(identity)
+            }
+        }
+    }
+
+    /// Load identity from custom storage location, unlocking a protected key
+    /// with the given passphrase
+    pub fn from_storage_path_with_passphrase(path: &Path, passphrase: &str) -> Result<Self> {
+        match StoredIdentity::load(path)? {
+            StoredIdentity::Hardware(data) => Self::from_token(&data),
+            StoredIdentity::Plain(data) => Self::from_encoded_secret(&data.secret_key),
+            StoredIdentity::Keychain(data) => Self::from_keychain(&data),
+            StoredIdentity::Protected(data) => Self::unlock(&data, passphrase),
+        }
+    }
+
+    /// Where the secret key of the identity file at `path` is kept
+    pub fn backend_at(path: &Path) -> Result<KeyBackend> {
+        Ok(match StoredIdentity::load(path)? {
+            StoredIdentity::Keychain(_) => KeyBackend::Keychain,
+            _ => KeyBackend::File,
+        })
+    }
+
+    /// Whether the identity file at `path` is passphrase protected
+    pub fn is_protected_at(path: &Path) -> Result<bool> {
+        Ok(matches!(
+            StoredIdentity::load(path)?,
+            StoredIdentity::Protected(_)
+        ))
+    }
+
+    /// Whether the identity file at `path` points at a key on a hardware token
+    pub fn is_hardware_at(path: &Path) -> Result<bool> {
+        Ok(matches!(
+            StoredIdentity::load(path)?,
+            StoredIdentity::Hardware(_)
+        ))
+    }
+
+    /// Peer ID of the identity file at `path`, read without unlocking it
+    pub fn stored_peer_id(path: &Path) -> Result<PeerId> {
+        let stored = StoredIdentity::load(path)?;
+        let peer_id = PeerId::from_string(stored.public_key().to_string());
+        peer_id
+            .to_verifying_key()
+            .context("Invalid public key in identity file")?;
+        Ok(peer_id)
+    }
+
+    fn unlock(data: &ProtectedIdentityData, passphrase: &str) -> Result<Self> {
+        let secret_bytes = data
+            .encrypted_secret_key
+            .decrypt(passphrase)
+            .context("Failed to unlock identity key")?;
+        Self::from_secret_bytes(&secret_bytes)
+    }
+
+    fn from_keychain(data: &KeychainIdentityData) -> Result<Self> {
+        let secret_key = KeychainKeyStorage::load_secret(&data.keychain_account)
+            .context("Failed to read identity key from the OS keychain")?;
+        let identity = Self::from_encoded_secret(&secret_key)?;
+        if identity.peer_id.as_str() != data.public_key {
+            anyhow::bail!(
+                "Keychain entry '{}' holds a different identity than expected",
+                data.keychain_account
+            );
+        }
+        Ok(identity)
+    }
+
+    #[cfg(feature = "hardware-keys")]
+    fn from_token(data: &HardwareIdentityData) -> Result<Self> {
+        let signer =
+            crate::crypto::hardware::TokenSigner::open(&data.pkcs11_module, &data.key_label)?;
+        let identity = Self::from_signer(Arc::new(signer));
+        if identity.peer_id.as_str() != data.public_key {
+            anyhow::bail!(
+                "Token key '{}' is a different identity than expected",
+                data.key_label
+            );
+        }
+        Ok(identity)
+    }
+
+    #[cfg(not(feature = "hardware-keys"))]
+    fn from_token(data: &HardwareIdentityData) -> Result<Self> {
+        anyhow::bail!(
+            "The identity key '{}' is on a hardware token, but this build of mate has no \
+             hardware key support (rebuild with --features hardware-keys)",
+            data.key_label
+        )
+    }
+
+    fn from_encoded_secret(secret_key: &str) -> Result<Self> {
+        let secret_bytes = general_purpose::STANDARD
+            .decode(secret_key)
+            .context("Invalid secret key encoding")?;
+        Self::from_secret_bytes(&secret_bytes)
+    }
+
     /// Save identity to custom storage location
     pub fn save_to_storage_path(&self, path: &Path) -> Result<()> {
-        // Ensure directory exists
+        let data = IdentityData {
+            secret_key: general_purpose::STANDARD.encode(self.to_secret_bytes()?),
+            public_key: self.peer_id.as_str().to_string(),
+        };
+        let json = serde_json::to_string_pretty(&data).context("Failed to serialize identity")?;
+        Self::write_key_file(path, &json)?;
+        passphrase_cache()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(path);
+        Ok(())
+    }
+
+    /// Save identity to custom storage location with the secret key
+    /// encrypted under `passphrase` (Argon2id + ChaCha20-Poly1305)
+    pub fn save_protected_to_storage_path(&self, path: &Path, passphrase: &str) -> Result<()> {
+        let data = ProtectedIdentityData {
+            public_key: self.peer_id.as_str().to_string(),
+            encrypted_secret_key: EncryptedData::encrypt_argon2(
+                &self.to_secret_bytes()?,
+                passphrase,
+            )?,
+        };
+        let json = serde_json::to_string_pretty(&data).context("Failed to serialize identity")?;
+        Self::write_key_file(path, &json)?;
+        passphrase_cache()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path.to_path_buf(), passphrase.to_string());
+        Ok(())
+    }
+
+    /// Save identity with the secret key in the OS keychain, leaving only a
+    /// stub with the public key at `path`
+    pub fn save_to_keychain(&self, path: &Path) -> Result<()> {
+        let account = path.display().to_string();
+        KeychainKeyStorage::save_secret(
+            &account,
+            &general_purpose::STANDARD.encode(self.to_secret_bytes()?),
+        )
+        .context("Failed to store identity key in the OS keychain")?;
+
+        let data = KeychainIdentityData {
+            public_key: self.peer_id.as_str().to_string(),
+            keychain_account: account,
+        };
+        let json = serde_json::to_string_pretty(&data).context("Failed to serialize identity")?;
         crate::crypto::storage::ensure_directory_exists(path)
             .map_err(|e| anyhow::anyhow!("Storage error: {}", e))?;
+        crate::crypto::storage::save_key_secure(path, json.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Storage error: {}", e))?;
+        passphrase_cache()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(path);
+        Ok(())
+    }
 
-        // Serialize identity data
-        let data = IdentityData {
-            secret_key: general_purpose::STANDARD.encode(self.signing_key.to_bytes()),
-            public_key: general_purpose::STANDARD
-                .encode(self.signing_key.verifying_key().to_bytes()),
+    /// Save a stub at `path` pointing at this identity's key on a hardware
+    /// token, reached through the PKCS#11 module at `pkcs11_module`
+    pub fn save_token_reference(
+        &self,
+        path: &Path,
+        pkcs11_module: &Path,
+        key_label: &str,
+    ) -> Result<()> {
+        let data = HardwareIdentityData {
+            public_key: self.peer_id.as_str().to_string(),
+            pkcs11_module: pkcs11_module.to_path_buf(),
+            key_label: key_label.to_string(),
         };
         let json = serde_json::to_string_pretty(&data).context("Failed to serialize identity")?;
+        Self::write_key_file(path, &json)?;
+        passphrase_cache()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(path);
+        Ok(())
+    }
+
+    /// Save identity to `path` using the given backend
+    pub fn save_with_backend(&self, path: &Path, backend: KeyBackend) -> Result<()> {
+        match backend {
+            KeyBackend::File => self.save_to_storage_path(path),
+            KeyBackend::Keychain => self.save_to_keychain(path),
+        }
+    }
+
+    /// Replace this identity at `path` with `new`, keeping the old key next
+    /// to it as `<file name>.<fingerprint>.old`
+    ///
+    /// A passphrase-protected key unlocked in this process is replaced by one
+    /// protected with the same passphrase. Returns where the old key went.
+    pub fn replace_at(&self, path: &Path, new: &Identity, backend: KeyBackend) -> Result<PathBuf> {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| DEFAULT_KEY_FILE.to_string());
+        let archive_path =
+            path.with_file_name(format!("{file_name}.{}.old", self.peer_id.fingerprint()));
+
+        match Self::backend_at(path)? {
+            KeyBackend::Keychain => self.save_to_keychain(&archive_path)?,
+            KeyBackend::File => {
+                std::fs::copy(path, &archive_path).with_context(|| {
+                    format!(
+                        "Failed to archive identity key to {}",
+                        archive_path.display()
+                    )
+                })?;
+            }
+        }
+
+        let passphrase = passphrase_cache()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(path)
+            .cloned();
+        match (backend, passphrase) {
+            (KeyBackend::File, Some(passphrase)) if Self::is_protected_at(path)? => {
+                new.save_protected_to_storage_path(path, &passphrase)?
+            }
+            _ => new.save_with_backend(path, backend)?,
+        }
+        Ok(archive_path)
+    }
+
+    fn write_key_file(path: &Path, json: &str) -> Result<()> {
+        // A key moving out of the keychain leaves no copy behind there
+        let keychain_account = StoredIdentity::load(path)
+            .ok()
+            .and_then(|stored| stored.keychain_account().map(str::to_string));
+
+        // Ensure directory exists
+        crate::crypto::storage::ensure_directory_exists(path)
+            .map_err(|e| anyhow::anyhow!("Storage error: {}", e))?;
 
         // Save using secure storage
         crate::crypto::storage::save_key_secure(path, json.as_bytes())
             .map_err(|e| anyhow::anyhow!("Storage error: {}", e))?;
 
+        if let Some(account) = keychain_account {
+            KeychainKeyStorage::delete_secret(&account)
+                .context("Failed to remove identity key from the OS keychain")?;
+        }
+
         Ok(())
     }
 
     /// Load or generate identity from custom data directory
     pub fn load_or_generate_from_data_dir(data_dir: &Path) -> Result<Self> {
-        let identity_path = data_dir.join("identity.key");
-        match Self::from_storage_path(&identity_path) {
-            Ok(identity) => Ok(identity),
-            Err(_) => {
-                let identity = Self::generate()?;
-                identity.save_to_storage_path(&identity_path)?;
-                Ok(identity)
-            }
+        let identity_path = active_key_path_in(data_dir);
+        if identity_path.exists() {
+            // Never replace a key that exists but cannot be read or unlocked
+            return Self::from_storage_path(&identity_path);
+        }
+        let identity = Self::generate()?;
+        identity.save_to_storage_path(&identity_path)?;
+        Ok(identity)
+    }
+
+    /// Load or generate the identity in a data directory, keeping its
+    /// secret key in `backend`
+    ///
+    /// A key found in the other backend is moved into `backend`.
+    pub fn load_or_generate_in(data_dir: &Path, backend: KeyBackend) -> Result<Self> {
+        let identity_path = active_key_path_in(data_dir);
+        if !identity_path.exists() {
+            let identity = Self::generate()?;
+            identity.save_with_backend(&identity_path, backend)?;
+            return Ok(identity);
         }
+
+        let identity = Self::from_storage_path(&identity_path)?;
+        // A key on a token has no secret to move
+        if !identity.is_hardware_backed() && Self::backend_at(&identity_path)? != backend {
+            identity
+                .save_with_backend(&identity_path, backend)
+                .with_context(|| format!("Failed to move identity key to {}", backend.as_str()))?;
+        }
+        Ok(identity)
     }
 
     /// Load identity from default storage location  
@@ -162,16 +619,18 @@ impl Identity {
 
     /// Load or generate identity using secure storage
     pub fn load_or_generate() -> Result<Self> {
-        match Self::from_default_storage() {
-            Ok(identity) => Ok(identity),
-            Err(_) => {
-                let identity = Self::generate()?;
-                identity.save_to_default_storage()?;
-                Ok(identity)
-            }
+        let path = crate::crypto::storage::default_key_path()
+            .map_err(|e| anyhow::anyhow!("Storage error: {}", e))?;
+        if path.exists() {
+            return Self::from_storage_path(&path);
         }
+        let identity = Self::generate()?;
+        identity.save_to_storage_path(&path)?;
+        Ok(identity)
     }
+}
 
+impl Identity {
     /// Get the peer ID for this identity
     pub fn peer_id(&self) -> &PeerId {
         &self.peer_id
@@ -179,12 +638,18 @@ impl Identity {
 
     /// Get the verifying (public) key
     pub fn verifying_key(&self) -> VerifyingKey {
-        self.signing_key.verifying_key()
+        match &self.key {
+            IdentityKey::Memory(signing_key) => signing_key.verifying_key(),
+            IdentityKey::Device(signer) => signer.verifying_key(),
+        }
     }
 
     /// Sign a message
-    pub fn sign(&self, message: &[u8]) -> Signature {
-        self.signing_key.sign(message)
+    pub fn sign(&self, message: &[u8]) -> Result<Signature> {
+        match &self.key {
+            IdentityKey::Memory(signing_key) => Ok(signing_key.sign(message)),
+            IdentityKey::Device(signer) => signer.sign(message),
+        }
     }
 
     /// Verify a signature against a verifying key
@@ -192,3 +657,13 @@ impl Identity {
         verifying_key.verify(message, signature).is_ok()
     }
 }
+
+impl Signer for Identity {
+    fn verifying_key(&self) -> VerifyingKey {
+        Identity::verifying_key(self)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature> {
+        Identity::sign(self, message)
+    }
+}
@@ -1,4 +1,20 @@
+pub mod batch;
+pub mod certificate;
+pub mod commitment;
+pub mod encryption;
+#[cfg(feature = "hardware-keys")]
+pub mod hardware;
 pub mod identity;
+pub mod revocation;
+pub mod rotation;
+pub mod sas;
+#[cfg(feature = "native")]
 pub mod storage;
 
-pub use identity::{Identity, PeerId};
+pub use certificate::{ArbiterRuling, GameCertificate, ResultSignature, ScoreSheet};
+pub use commitment::SealedMove;
+pub use encryption::EncryptedData;
+pub use identity::{Identity, PeerId, Signer};
+pub use revocation::RevocationRecord;
+pub use rotation::RotationCertificate;
+pub use sas::VerificationCode;
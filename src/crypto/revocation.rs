@@ -0,0 +1,102 @@
+use crate::crypto::identity::{Identity, PeerId};
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::Signature;
+use serde::{Deserialize, Serialize};
+
+/// Domain separator so a revocation signature cannot be mistaken for any
+/// other signed data
+const REVOCATION_CONTEXT: &[u8] = b"mate-key-revocation-v1";
+
+/// Longest reason a revocation may carry
+pub const MAX_REVOCATION_REASON_LEN: usize = 200;
+
+/// Statement that the identity `peer_id` must no longer be trusted
+///
+/// Signed by the revoked key itself. Whoever holds a compromised key can
+/// revoke it, which is what makes the record safe to relay and act on: the
+/// worst it can do is retire a key that is no longer safe. A successor key
+/// named here is only a hint for the user to verify, since an attacker with
+/// the old key could name one too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationRecord {
+    pub peer_id: String,
+    /// Unix timestamp of the revocation
+    pub issued_at: i64,
+    /// Free-form reason, e.g. "laptop stolen"; may be empty
+    pub reason: String,
+    /// Key the owner moved to, if any
+    pub successor: Option<String>,
+    /// Base64 Ed25519 signature by the revoked key
+    pub signature: String,
+}
+
+impl RevocationRecord {
+    /// Revoke `identity`'s key, optionally pointing peers at `successor`
+    pub fn issue(
+        identity: &Identity,
+        issued_at: i64,
+        reason: &str,
+        successor: Option<&PeerId>,
+    ) -> Result<Self> {
+        let mut record = Self {
+            peer_id: identity.peer_id().to_string(),
+            issued_at,
+            reason: reason.trim().to_string(),
+            successor: successor.map(PeerId::to_string),
+            signature: String::new(),
+        };
+        record.check_fields()?;
+        let signature = identity.sign(&record.signed_bytes())?;
+        record.signature = general_purpose::STANDARD.encode(signature.to_bytes());
+        Ok(record)
+    }
+
+    /// Check the signature by the revoked key
+    pub fn verify(&self) -> Result<()> {
+        self.check_fields()?;
+        let key = PeerId::from_string(self.peer_id.clone())
+            .to_verifying_key()
+            .context("Invalid peer ID in revocation")?;
+        let bytes = general_purpose::STANDARD
+            .decode(&self.signature)
+            .context("Invalid revocation signature encoding")?;
+        let signature = Signature::from_slice(&bytes).context("Invalid revocation signature")?;
+        if !Identity::verify(&key, &self.signed_bytes(), &signature) {
+            anyhow::bail!("Revocation signature does not verify");
+        }
+        Ok(())
+    }
+
+    fn check_fields(&self) -> Result<()> {
+        if self.reason.chars().count() > MAX_REVOCATION_REASON_LEN {
+            anyhow::bail!(
+                "Revocation reason is longer than {} characters",
+                MAX_REVOCATION_REASON_LEN
+            );
+        }
+        if let Some(successor) = &self.successor {
+            if successor == &self.peer_id {
+                anyhow::bail!("A key cannot be revoked in favor of itself");
+            }
+            PeerId::from_string(successor.clone())
+                .to_verifying_key()
+                .context("Invalid successor peer ID in revocation")?;
+        }
+        Ok(())
+    }
+
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut bytes = REVOCATION_CONTEXT.to_vec();
+        for part in [
+            self.peer_id.as_str(),
+            &self.issued_at.to_string(),
+            &self.reason,
+            self.successor.as_deref().unwrap_or(""),
+        ] {
+            bytes.push(b'\n');
+            bytes.extend_from_slice(part.as_bytes());
+        }
+        bytes
+    }
+}
@@ -0,0 +1,76 @@
+use crate::crypto::identity::{Identity, PeerId};
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::Signature;
+use serde::{Deserialize, Serialize};
+
+/// Domain separator so a rotation signature cannot be mistaken for any other
+/// signed data
+const ROTATION_CONTEXT: &[u8] = b"mate-key-rotation-v1";
+
+/// Statement that the identity `old_peer_id` has moved to `new_peer_id`
+///
+/// Signed by the old key, which proves the rotation is wanted by the owner
+/// peers already know, and by the new key, which proves the new identity
+/// agreed to take over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationCertificate {
+    pub old_peer_id: String,
+    pub new_peer_id: String,
+    /// Unix timestamp of the rotation
+    pub issued_at: i64,
+    /// Base64 Ed25519 signature by the old key
+    pub old_key_signature: String,
+    /// Base64 Ed25519 signature by the new key
+    pub new_key_signature: String,
+}
+
+impl RotationCertificate {
+    /// Hand `old` over to `new`, signing with both keys
+    pub fn issue(old: &Identity, new: &Identity, issued_at: i64) -> Result<Self> {
+        let old_peer_id = old.peer_id().to_string();
+        let new_peer_id = new.peer_id().to_string();
+        let signed = Self::signed_bytes(&old_peer_id, &new_peer_id, issued_at);
+        Ok(Self {
+            old_key_signature: general_purpose::STANDARD.encode(old.sign(&signed)?.to_bytes()),
+            new_key_signature: general_purpose::STANDARD.encode(new.sign(&signed)?.to_bytes()),
+            old_peer_id,
+            new_peer_id,
+            issued_at,
+        })
+    }
+
+    /// Check both signatures
+    pub fn verify(&self) -> Result<()> {
+        if self.old_peer_id == self.new_peer_id {
+            anyhow::bail!("Rotation certificate names the same key twice");
+        }
+        let signed = Self::signed_bytes(&self.old_peer_id, &self.new_peer_id, self.issued_at);
+        for (peer_id, signature, which) in [
+            (&self.old_peer_id, &self.old_key_signature, "old"),
+            (&self.new_peer_id, &self.new_key_signature, "new"),
+        ] {
+            let key = PeerId::from_string(peer_id.clone())
+                .to_verifying_key()
+                .with_context(|| format!("Invalid {which} peer ID in rotation certificate"))?;
+            let bytes = general_purpose::STANDARD
+                .decode(signature)
+                .with_context(|| format!("Invalid {which} key signature encoding"))?;
+            let signature = Signature::from_slice(&bytes)
+                .with_context(|| format!("Invalid {which} key signature"))?;
+            if !Identity::verify(&key, &signed, &signature) {
+                anyhow::bail!("Rotation certificate {which} key signature does not verify");
+            }
+        }
+        Ok(())
+    }
+
+    fn signed_bytes(old_peer_id: &str, new_peer_id: &str, issued_at: i64) -> Vec<u8> {
+        let mut bytes = ROTATION_CONTEXT.to_vec();
+        for part in [old_peer_id, new_peer_id, &issued_at.to_string()] {
+            bytes.push(b'\n');
+            bytes.extend_from_slice(part.as_bytes());
+        }
+        bytes
+    }
+}
@@ -0,0 +1,144 @@
+use sha2::{Digest, Sha256};
+
+/// Domain separator so verification codes cannot collide with other digests
+const SAS_CONTEXT: &[u8] = b"mate-sas-v1";
+
+/// Number of symbols in a verification code (6 bits each, 42 bits in total)
+pub const SAS_LENGTH: usize = 7;
+
+/// Session value for a code that does not depend on any connection, which
+/// both peers can show at any time with `mate peers verify`
+pub const OFFLINE_SESSION: u64 = 0;
+
+/// One symbol of a verification code, with a name for terminals that
+/// cannot show emoji and for reading the code aloud
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SasSymbol {
+    pub emoji: &'static str,
+    pub name: &'static str,
+}
+
+const fn symbol(emoji: &'static str, name: &'static str) -> SasSymbol {
+    SasSymbol { emoji, name }
+}
+
+/// The 64 symbols a code is made of, chosen to be easy to tell apart
+pub const SAS_SYMBOLS: [SasSymbol; 64] = [
+    symbol("🐶", "Dog"),
+    symbol("🐱", "Cat"),
+    symbol("🦁", "Lion"),
+    symbol("🐎", "Horse"),
+    symbol("🦄", "Unicorn"),
+    symbol("🐷", "Pig"),
+    symbol("🐘", "Elephant"),
+    symbol("🐰", "Rabbit"),
+    symbol("🐼", "Panda"),
+    symbol("🐓", "Rooster"),
+    symbol("🐧", "Penguin"),
+    symbol("🐢", "Turtle"),
+    symbol("🐟", "Fish"),
+    symbol("🐙", "Octopus"),
+    symbol("🦋", "Butterfly"),
+    symbol("🌷", "Flower"),
+    symbol("🌳", "Tree"),
+    symbol("🌵", "Cactus"),
+    symbol("🍄", "Mushroom"),
+    symbol("🌏", "Globe"),
+    symbol("🌙", "Moon"),
+    symbol("☁️", "Cloud"),
+    symbol("🔥", "Fire"),
+    symbol("🍌", "Banana"),
+    symbol("🍎", "Apple"),
+    symbol("🍓", "Strawberry"),
+    symbol("🌽", "Corn"),
+    symbol("🍕", "Pizza"),
+    symbol("🎂", "Cake"),
+    symbol("❤️", "Heart"),
+    symbol("😀", "Smiley"),
+    symbol("🤖", "Robot"),
+    symbol("🎩", "Hat"),
+    symbol("👓", "Glasses"),
+    symbol("🔧", "Spanner"),
+    symbol("🎅", "Santa"),
+    symbol("👍", "Thumbs up"),
+    symbol("☂️", "Umbrella"),
+    symbol("⌛", "Hourglass"),
+    symbol("⏰", "Clock"),
+    symbol("🎁", "Gift"),
+    symbol("💡", "Light bulb"),
+    symbol("📕", "Book"),
+    symbol("✏️", "Pencil"),
+    symbol("📎", "Paperclip"),
+    symbol("✂️", "Scissors"),
+    symbol("🔒", "Lock"),
+    symbol("🔑", "Key"),
+    symbol("🔨", "Hammer"),
+    symbol("☎️", "Telephone"),
+    symbol("🏁", "Flag"),
+    symbol("🚂", "Train"),
+    symbol("🚲", "Bicycle"),
+    symbol("✈️", "Aeroplane"),
+    symbol("🚀", "Rocket"),
+    symbol("🏆", "Trophy"),
+    symbol("⚽", "Ball"),
+    symbol("🎸", "Guitar"),
+    symbol("🎺", "Trumpet"),
+    symbol("🔔", "Bell"),
+    symbol("⚓", "Anchor"),
+    symbol("🎧", "Headphones"),
+    symbol("📁", "Folder"),
+    symbol("📌", "Pin"),
+];
+
+/// Short authentication string two peers compare to rule out an impostor
+///
+/// Both sides derive the same code from the two public keys and the session,
+/// whichever side they are on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationCode([u8; SAS_LENGTH]);
+
+impl VerificationCode {
+    /// Code for a connection between the peers `a` and `b`
+    pub fn derive(a: &str, b: &str, session: u64) -> Self {
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        let mut hasher = Sha256::new();
+        hasher.update(SAS_CONTEXT);
+        for part in [first, second, &session.to_string()] {
+            hasher.update(b"\n");
+            hasher.update(part.as_bytes());
+        }
+        let digest = hasher.finalize();
+
+        // Read the digest as a bit string, six bits per symbol
+        let bits = u64::from_be_bytes(digest[..8].try_into().expect("digest has 32 bytes"));
+        let mut indices = [0u8; SAS_LENGTH];
+        for (i, index) in indices.iter_mut().enumerate() {
+            *index = ((bits >> (58 - 6 * i)) & 0x3f) as u8;
+        }
+        Self(indices)
+    }
+
+    /// The code's symbols in order
+    pub fn symbols(&self) -> impl Iterator<Item = &'static SasSymbol> + '_ {
+        self.0.iter().map(|&index| &SAS_SYMBOLS[index as usize])
+    }
+
+    /// The code as emoji followed by their names, or as names only
+    pub fn render(&self, unicode: bool) -> String {
+        let names = self
+            .symbols()
+            .map(|s| s.name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if unicode {
+            let emoji = self
+                .symbols()
+                .map(|s| s.emoji)
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{emoji}  ({names})")
+        } else {
+            names
+        }
+    }
+}
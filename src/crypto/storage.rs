@@ -1,6 +1,8 @@
 use anyhow::Result;
 use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 use thiserror::Error;
 
 // Enhanced error types for better error handling
@@ -18,12 +20,76 @@ pub enum StorageError {
     #[error("File operation failed: {operation} on {path}")]
     FileOperationFailed { operation: String, path: PathBuf },
 
-    #[error("Invalid file permissions: expected {expected:o}, found {found:o}")]
-    InvalidPermissions { expected: u32, found: u32 },
+    #[error(
+        "Key file {} can be read by other users (permissions {mode:o}). Run 'chmod 600 {}' to \
+         make it private, or set identity.key_permissions to 'warn' to load it anyway",
+        .path.display(),
+        .path.display()
+    )]
+    InsecurePermissions { path: PathBuf, mode: u32 },
+
+    #[error("OS keychain error: {0}")]
+    Keychain(String),
+
+    #[error("Invalid identity name '{0}': use 1-32 letters, digits, '-' or '_'")]
+    InvalidIdentityName(String),
+}
+
+/// Name of the identity kept in `identity.key`, used unless another is selected
+pub const DEFAULT_IDENTITY: &str = "default";
+
+/// Key file of the default identity in the data directory
+pub const DEFAULT_KEY_FILE: &str = "identity.key";
+
+/// Directory in the data directory holding the key files of named identities
+pub const IDENTITIES_DIR: &str = "identities";
+
+/// Identity selected for this process with [`select_identity`]
+static ACTIVE_IDENTITY: RwLock<Option<String>> = RwLock::new(None);
+
+/// How key files readable by other users are treated in this process
+static PERMISSION_POLICY: RwLock<KeyPermissionPolicy> = RwLock::new(KeyPermissionPolicy::Strict);
+
+/// What to do when a key file can be read by its group or by everyone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyPermissionPolicy {
+    /// Refuse to load the key
+    #[default]
+    Strict,
+    /// Load the key, logging a warning
+    Warn,
+}
+
+/// Where the identity's secret key is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyBackend {
+    /// A private file in the data directory
+    #[default]
+    File,
+    /// The OS keychain (macOS Keychain, Windows Credential Manager, or the
+    /// Secret Service on Linux); only a stub holding the public key is
+    /// written to the data directory
+    Keychain,
+}
+
+impl KeyBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyBackend::File => "file",
+            KeyBackend::Keychain => "keychain",
+        }
+    }
 }
 
 pub trait KeyStorage {
+    /// Key path of the selected identity
     fn default_key_path() -> Result<PathBuf, StorageError>;
+    /// Key path of the identity called `name`
+    fn identity_key_path(name: &str) -> Result<PathBuf, StorageError>;
+    /// Directory holding the key files of every identity
+    fn key_dir() -> Result<PathBuf, StorageError>;
     fn ensure_directory_exists(path: &Path) -> Result<(), StorageError>;
 }
 
@@ -31,18 +97,24 @@ pub struct DefaultKeyStorage;
 
 impl KeyStorage for DefaultKeyStorage {
     fn default_key_path() -> Result<PathBuf, StorageError> {
+        Self::identity_key_path(&active_identity())
+    }
+
+    fn identity_key_path(name: &str) -> Result<PathBuf, StorageError> {
+        key_path_in(&Self::key_dir()?, name)
+    }
+
+    fn key_dir() -> Result<PathBuf, StorageError> {
         // Check for test override environment variable first (consistent with database and config)
         if let Ok(custom_data_dir) = std::env::var("MATE_DATA_DIR") {
-            let key_path = PathBuf::from(custom_data_dir).join("identity.key");
-            return Ok(key_path);
+            return Ok(PathBuf::from(custom_data_dir));
         }
 
         // Use consistent project directories with the main app (dev.mate.mate instead of rust-chess)
         let proj_dirs =
             ProjectDirs::from("dev", "mate", "mate").ok_or(StorageError::DirectoryNotFound)?;
 
-        let key_path = proj_dirs.data_dir().join("identity.key");
-        Ok(key_path)
+        Ok(proj_dirs.data_dir().to_path_buf())
     }
 
     fn ensure_directory_exists(path: &Path) -> Result<(), StorageError> {
@@ -61,26 +133,35 @@ impl KeyStorage for DefaultKeyStorage {
 
 impl DefaultKeyStorage {
     /// Save key data with proper permissions and directory creation
+    ///
+    /// The data is written to a private temporary file next to `path`,
+    /// flushed to disk, and renamed over `path`, so a crash leaves either the
+    /// old key or the new one, never a truncated file.
     pub fn save_key_secure<P: AsRef<Path>>(path: P, data: &[u8]) -> Result<(), StorageError> {
         let path = path.as_ref();
 
         // Ensure parent directory exists
         Self::ensure_directory_exists(path)?;
 
-        // Write file
-        std::fs::write(path, data).map_err(|_| StorageError::FileOperationFailed {
-            operation: "write".to_string(),
-            path: path.to_path_buf(),
-        })?;
-
-        // Set secure permissions (Unix only)
-        #[cfg(unix)]
-        Self::set_secure_permissions(path)?;
-
-        Ok(())
+        let temp_path = temp_key_path(path);
+        let result = Self::write_synced(&temp_path, data)
+            .and_then(|()| {
+                std::fs::rename(&temp_path, path).map_err(|_| StorageError::FileOperationFailed {
+                    operation: "rename".to_string(),
+                    path: path.to_path_buf(),
+                })
+            })
+            .and_then(|()| Self::sync_parent_directory(path));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
     }
 
     /// Load key data from file
+    ///
+    /// Key files other users can read are refused or warned about, depending
+    /// on the policy set with [`set_permission_policy`].
     pub fn load_key_secure<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, StorageError> {
         let path = path.as_ref();
 
@@ -95,64 +176,200 @@ impl DefaultKeyStorage {
         })
     }
 
-    #[cfg(unix)]
-    fn set_secure_permissions<P: AsRef<Path>>(path: P) -> Result<(), StorageError> {
-        use std::os::unix::fs::PermissionsExt;
+    /// Create `path` readable only by its owner and write `data` to disk
+    fn write_synced(path: &Path, data: &[u8]) -> Result<(), StorageError> {
+        use std::io::Write;
 
-        let mut perms = std::fs::metadata(&path)
-            .map_err(|_| StorageError::FileOperationFailed {
-                operation: "get_permissions".to_string(),
-                path: path.as_ref().to_path_buf(),
-            })?
-            .permissions();
-
-        perms.set_mode(0o600);
-        std::fs::set_permissions(&path, perms).map_err(|_| StorageError::FileOperationFailed {
-            operation: "set_permissions".to_string(),
-            path: path.as_ref().to_path_buf(),
-        })?;
+        let failed = |operation: &str| StorageError::FileOperationFailed {
+            operation: operation.to_string(),
+            path: path.to_path_buf(),
+        };
+        // A leftover from an interrupted write is never a valid key
+        let _ = std::fs::remove_file(path);
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path).map_err(|_| failed("create"))?;
+        file.write_all(data).map_err(|_| failed("write"))?;
+        file.sync_all().map_err(|_| failed("sync"))
+    }
 
+    /// Flush the directory entry of a renamed file (Unix only)
+    fn sync_parent_directory(path: &Path) -> Result<(), StorageError> {
+        #[cfg(unix)]
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::File::open(parent)
+                .and_then(|dir| dir.sync_all())
+                .map_err(|_| StorageError::FileOperationFailed {
+                    operation: "sync".to_string(),
+                    path: parent.to_path_buf(),
+                })?;
+        }
+        #[cfg(not(unix))]
+        let _ = path;
         Ok(())
     }
 
     #[cfg(unix)]
-    fn verify_secure_permissions<P: AsRef<Path>>(path: P) -> Result<(), StorageError> {
+    fn verify_secure_permissions(path: &Path) -> Result<(), StorageError> {
         use std::os::unix::fs::PermissionsExt;
 
-        let metadata = std::fs::metadata(&path).map_err(|_| StorageError::FileOperationFailed {
+        let metadata = std::fs::metadata(path).map_err(|_| StorageError::FileOperationFailed {
             operation: "get_metadata".to_string(),
-            path: path.as_ref().to_path_buf(),
+            path: path.to_path_buf(),
         })?;
 
-        let mode = metadata.permissions().mode() & 0o777;
-        if mode != 0o600 {
-            return Err(StorageError::InvalidPermissions {
-                expected: 0o600,
-                found: mode,
-            });
+        check_key_permissions(path, metadata.permissions().mode(), permission_policy())
+    }
+}
+
+/// Temporary file a key is written to before being renamed into place
+fn temp_key_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| DEFAULT_KEY_FILE.to_string());
+    path.with_file_name(format!(".{file_name}.{}.tmp", std::process::id()))
+}
+
+/// Whether Unix permission bits keep a file private to its owner
+pub fn is_private_mode(mode: u32) -> bool {
+    mode & 0o077 == 0
+}
+
+/// Check the Unix permission bits of the key file at `path` under `policy`
+///
+/// A file its group or other users can read, write, or execute fails under
+/// [`KeyPermissionPolicy::Strict`] and is only logged under
+/// [`KeyPermissionPolicy::Warn`].
+pub fn check_key_permissions(
+    path: &Path,
+    mode: u32,
+    policy: KeyPermissionPolicy,
+) -> Result<(), StorageError> {
+    let mode = mode & 0o777;
+    if is_private_mode(mode) {
+        return Ok(());
+    }
+    let error = StorageError::InsecurePermissions {
+        path: path.to_path_buf(),
+        mode,
+    };
+    match policy {
+        KeyPermissionPolicy::Strict => Err(error),
+        KeyPermissionPolicy::Warn => {
+            tracing::warn!("{}", error);
+            Ok(())
         }
+    }
+}
+
+/// Treat key files readable by other users according to `policy` for the
+/// rest of the process
+pub fn set_permission_policy(policy: KeyPermissionPolicy) {
+    *PERMISSION_POLICY.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// How key files readable by other users are treated in this process
+pub fn permission_policy() -> KeyPermissionPolicy {
+    *PERMISSION_POLICY.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Get the default key storage path for the current platform
+pub fn default_key_path() -> Result<PathBuf, StorageError> {
+    DefaultKeyStorage::default_key_path()
+}
+
+/// Get the key path of the identity called `name` on this platform
+pub fn identity_key_path(name: &str) -> Result<PathBuf, StorageError> {
+    DefaultKeyStorage::identity_key_path(name)
+}
 
+/// Get the directory holding the key files of every identity
+pub fn default_key_dir() -> Result<PathBuf, StorageError> {
+    DefaultKeyStorage::key_dir()
+}
+
+/// Check that `name` can be used as an identity name and file name
+pub fn validate_identity_name(name: &str) -> Result<(), StorageError> {
+    let valid = (1..=32).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
         Ok(())
+    } else {
+        Err(StorageError::InvalidIdentityName(name.to_string()))
     }
+}
 
-    #[cfg(windows)]
-    fn set_secure_permissions<P: AsRef<Path>>(_path: P) -> Result<(), StorageError> {
-        // Windows permission handling would go here
-        // For now, return Ok since Windows file permissions work differently
-        Ok(())
+/// Key path of the identity called `name` in a data directory
+///
+/// The default identity keeps its original `identity.key`; named identities
+/// live in `identities/<name>.key`.
+pub fn key_path_in(data_dir: &Path, name: &str) -> Result<PathBuf, StorageError> {
+    validate_identity_name(name)?;
+    if name == DEFAULT_IDENTITY {
+        Ok(data_dir.join(DEFAULT_KEY_FILE))
+    } else {
+        Ok(data_dir.join(IDENTITIES_DIR).join(format!("{name}.key")))
     }
+}
 
-    #[cfg(windows)]
-    fn verify_secure_permissions<P: AsRef<Path>>(_path: P) -> Result<(), StorageError> {
-        // Windows permission verification would go here
-        // For now, return Ok since Windows file permissions work differently
-        Ok(())
+/// Key path of the selected identity in a data directory
+pub fn active_key_path_in(data_dir: &Path) -> PathBuf {
+    key_path_in(data_dir, &active_identity())
+        .expect("selected identity names are validated by select_identity")
+}
+
+/// Names of the identities with a key in a data directory, sorted, with the
+/// default identity first
+pub fn list_identities(data_dir: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(data_dir.join(IDENTITIES_DIR)) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "key") {
+                if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    if name != DEFAULT_IDENTITY && validate_identity_name(name).is_ok() {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
     }
+    names.sort();
+    if data_dir.join(DEFAULT_KEY_FILE).exists() {
+        names.insert(0, DEFAULT_IDENTITY.to_string());
+    }
+    names
 }
 
-/// Get the default key storage path for the current platform
-pub fn default_key_path() -> Result<PathBuf, StorageError> {
-    DefaultKeyStorage::default_key_path()
+/// Use the identity called `name` for the rest of the process
+///
+/// Every key path lookup ([`default_key_path`], [`active_key_path_in`])
+/// resolves to this identity's key afterwards.
+pub fn select_identity(name: &str) -> Result<(), StorageError> {
+    validate_identity_name(name)?;
+    *ACTIVE_IDENTITY.write().unwrap_or_else(|e| e.into_inner()) = Some(name.to_string());
+    Ok(())
+}
+
+/// Name of the identity in use by this process
+pub fn active_identity() -> String {
+    ACTIVE_IDENTITY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| DEFAULT_IDENTITY.to_string())
 }
 
 /// Ensure the directory for the given path exists
@@ -169,3 +386,49 @@ pub fn save_key_secure<P: AsRef<Path>>(path: P, data: &[u8]) -> Result<(), Stora
 pub fn load_key_secure<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, StorageError> {
     DefaultKeyStorage::load_key_secure(path)
 }
+
+/// Service name identity keys are filed under in the OS keychain
+pub const KEYCHAIN_SERVICE: &str = "dev.mate.mate";
+
+/// Secret storage in the OS keychain, one entry per account name
+pub struct KeychainKeyStorage;
+
+impl KeychainKeyStorage {
+    /// Store `secret` under `account`, replacing any previous value
+    pub fn save_secret(account: &str, secret: &str) -> Result<(), StorageError> {
+        Self::with_entry(account, |entry| entry.set_password(secret))
+    }
+
+    /// Read the secret stored under `account`
+    pub fn load_secret(account: &str) -> Result<String, StorageError> {
+        Self::with_entry(account, |entry| entry.get_password())
+    }
+
+    /// Remove the secret stored under `account`; a missing entry is not an error
+    pub fn delete_secret(account: &str) -> Result<(), StorageError> {
+        Self::with_entry(account, |entry| match entry.delete_credential() {
+            Err(keyring::Error::NoEntry) => Ok(()),
+            result => result,
+        })
+    }
+
+    /// Run a keychain operation on its own thread
+    ///
+    /// Some platform backends drive their own async runtime, which must not be
+    /// started from a thread already running one.
+    fn with_entry<T: Send>(
+        account: &str,
+        operation: impl FnOnce(&keyring::Entry) -> keyring::Result<T> + Send,
+    ) -> Result<T, StorageError> {
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, account)?;
+                    operation(&entry)
+                })
+                .join()
+                .map_err(|_| StorageError::Keychain("keychain access panicked".to_string()))?
+                .map_err(|e| StorageError::Keychain(e.to_string()))
+        })
+    }
+}
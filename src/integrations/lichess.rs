@@ -0,0 +1,378 @@
+use crate::chess::Board;
+use crate::cli::pgn::{escape_pgn_string, pgn_date};
+use crate::messages::chess::{hash_board_state, Move as MoveMessage};
+use crate::network::http_client::{self, HttpUrl};
+use crate::storage::models::{GameResult, GameStatus, PlayerColor};
+use crate::storage::Database;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Lichess server the bridge talks to
+pub const LICHESS_URL: &str = "https://lichess.org";
+
+/// Environment variable holding a Lichess personal API token
+///
+/// Optional: with a token, exported games are attached to its account and
+/// downloads are allowed a higher rate.
+pub const TOKEN_ENV_VAR: &str = "MATE_LICHESS_TOKEN";
+
+/// Tag added to every imported game
+pub const IMPORT_TAG: &str = "lichess";
+
+/// Prefix of the opponent ID recorded for imported games
+pub const OPPONENT_PREFIX: &str = "lichess:";
+
+/// Game metadata key holding the Lichess game ID
+const METADATA_ID_KEY: &str = "lichess_id";
+
+/// A game parsed from Lichess PGN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LichessGame {
+    /// Eight-character Lichess game ID, from the `Site` header
+    pub id: String,
+    pub white: String,
+    pub black: String,
+    /// PGN result token: `1-0`, `0-1`, `1/2-1/2`, or `*`
+    pub result: String,
+    /// Moves in SAN, as written in the movetext
+    pub moves: Vec<String>,
+}
+
+/// What an import stored and passed over
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Local IDs of the games stored
+    pub imported: Vec<String>,
+    /// Games imported before
+    pub duplicates: usize,
+    /// Games that could not be replayed or did not involve the user
+    pub invalid: usize,
+}
+
+/// Lichess's answer to an import
+#[derive(Debug, Deserialize)]
+struct ImportResponse {
+    url: String,
+}
+
+/// Download the games of a Lichess user as PGN, most recent first
+pub async fn fetch_user_games(username: &str, max: Option<u32>) -> Result<String> {
+    check_username(username)?;
+    let mut url = format!("{LICHESS_URL}/api/games/user/{username}?clocks=false&evals=false");
+    if let Some(max) = max {
+        url.push_str(&format!("&max={max}"));
+    }
+    let response = http_client::get_with_headers(
+        &HttpUrl::parse(&url)?,
+        &request_headers("application/x-chess-pgn"),
+    )
+    .await;
+    let body = lichess_body(response)?;
+    String::from_utf8(body).context("Lichess sent games that are not valid UTF-8")
+}
+
+/// Upload a finished game to the Lichess analysis board and return its URL
+pub async fn upload_game(database: &Database, game_id: &str, my_peer_id: &str) -> Result<String> {
+    let pgn = export_san_pgn(database, game_id, my_peer_id)?;
+    let response = http_client::post(
+        &HttpUrl::parse(&format!("{LICHESS_URL}/api/import"))?,
+        "application/x-www-form-urlencoded",
+        &request_headers("application/json"),
+        format!("pgn={}", form_urlencode(&pgn)).as_bytes(),
+    )
+    .await;
+    let body = lichess_body(response)?;
+    let response: ImportResponse =
+        serde_json::from_slice(&body).context("Unexpected answer from Lichess")?;
+    Ok(response.url)
+}
+
+/// A finished game as PGN in standard algebraic notation, which Lichess
+/// requires (mate stores moves as coordinates)
+pub fn export_san_pgn(database: &Database, game_id: &str, my_peer_id: &str) -> Result<String> {
+    let game = database
+        .get_game(game_id)
+        .context("Failed to retrieve game from database")?;
+    if game.status != GameStatus::Completed {
+        anyhow::bail!("Game {game_id} is not finished; only finished games can be exported");
+    }
+    let messages = database
+        .get_messages_for_game(game_id)
+        .context("Failed to retrieve game messages")?;
+
+    let mut board = Board::new();
+    let mut movetext = String::new();
+    let moves = messages
+        .iter()
+        .filter(|m| m.message_type.eq_ignore_ascii_case("move"))
+        .filter_map(|m| serde_json::from_str::<MoveMessage>(&m.content).ok());
+    for (index, message) in moves.enumerate() {
+        let chess_move = board
+            .parse_move(&message.chess_move)
+            .with_context(|| format!("Move {} of game {game_id} does not replay", index + 1))?;
+        if index % 2 == 0 {
+            movetext.push_str(&format!("{}. ", index / 2 + 1));
+        }
+        movetext.push_str(&board.to_san(chess_move));
+        movetext.push(' ');
+        board.make_move(chess_move)?;
+    }
+
+    let (white, black) = match game.my_color {
+        PlayerColor::White => (my_peer_id, game.opponent_peer_id.as_str()),
+        PlayerColor::Black => (game.opponent_peer_id.as_str(), my_peer_id),
+    };
+    let result = game.pgn_result();
+    let headers = [
+        ("Event", "mate P2P game"),
+        ("Site", "mate"),
+        ("Date", &pgn_date(game.created_at)),
+        ("White", white),
+        ("Black", black),
+        ("Result", result),
+    ];
+    let mut pgn = String::new();
+    for (name, value) in headers {
+        pgn.push_str(&format!("[{name} \"{}\"]\n", escape_pgn_string(value)));
+    }
+    pgn.push('\n');
+    pgn.push_str(&movetext);
+    pgn.push_str(result);
+    pgn.push('\n');
+    Ok(pgn)
+}
+
+/// Store the games in `pgn` that `username` played, skipping ones imported
+/// before
+///
+/// Each game is recorded against the opponent `lichess:<name>`, tagged
+/// `lichess`, and finished with the Lichess result.
+pub fn import_games(
+    database: &Database,
+    my_peer_id: &str,
+    username: &str,
+    pgn: &str,
+) -> Result<ImportSummary> {
+    let known: Vec<String> = database
+        .get_all_games()
+        .context("Failed to list games")?
+        .into_iter()
+        .filter_map(|game| {
+            game.metadata?
+                .get(METADATA_ID_KEY)?
+                .as_str()
+                .map(str::to_string)
+        })
+        .collect();
+
+    let mut summary = ImportSummary::default();
+    for game in parse_pgn(pgn) {
+        if known.contains(&game.id) {
+            summary.duplicates += 1;
+            continue;
+        }
+        match import_game(database, my_peer_id, username, &game) {
+            Ok(game_id) => summary.imported.push(game_id),
+            Err(e) => {
+                warn!("Skipping Lichess game {}: {:#}", game.id, e);
+                summary.invalid += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Replay one game and store it, returning its local ID
+fn import_game(
+    database: &Database,
+    my_peer_id: &str,
+    username: &str,
+    game: &LichessGame,
+) -> Result<String> {
+    let (my_color, opponent) = if game.white.eq_ignore_ascii_case(username) {
+        (PlayerColor::White, &game.black)
+    } else if game.black.eq_ignore_ascii_case(username) {
+        (PlayerColor::Black, &game.white)
+    } else {
+        anyhow::bail!("{username} did not play in this game");
+    };
+
+    // Replay before storing anything so a bad game leaves no trace
+    let mut board = Board::new();
+    let mut moves = Vec::with_capacity(game.moves.len());
+    for san in &game.moves {
+        let chess_move = board.parse_move(san)?;
+        board.make_move(chess_move)?;
+        moves.push((chess_move.to_string(), hash_board_state(&board)));
+    }
+
+    let opponent_peer_id = format!("{OPPONENT_PREFIX}{opponent}");
+    let metadata = serde_json::json!({
+        METADATA_ID_KEY: game.id,
+        "lichess_url": format!("{LICHESS_URL}/{}", game.id),
+    });
+    let stored =
+        database.create_game(opponent_peer_id.clone(), my_color.clone(), Some(metadata))?;
+    for (index, (chess_move, board_hash)) in moves.into_iter().enumerate() {
+        let mover = if (index % 2 == 0) == (my_color == PlayerColor::White) {
+            my_peer_id.to_string()
+        } else {
+            opponent_peer_id.clone()
+        };
+        let message = MoveMessage::new(stored.id.clone(), chess_move, board_hash);
+        database.store_message(
+            stored.id.clone(),
+            "Move".to_string(),
+            serde_json::to_string(&message)?,
+            String::new(),
+            mover,
+        )?;
+    }
+
+    let result = match (game.result.as_str(), my_color) {
+        ("1-0", PlayerColor::White) | ("0-1", PlayerColor::Black) => Some(GameResult::Win),
+        ("0-1", PlayerColor::White) | ("1-0", PlayerColor::Black) => Some(GameResult::Loss),
+        ("1/2-1/2", _) => Some(GameResult::Draw),
+        _ => None,
+    };
+    match result {
+        Some(result) => database.update_game_result(&stored.id, result)?,
+        None => database.update_game_status(&stored.id, GameStatus::Abandoned)?,
+    }
+    database.add_game_tag(&stored.id, IMPORT_TAG)?;
+    Ok(stored.id)
+}
+
+/// Split PGN text into games, ignoring comments, variations, and NAGs
+///
+/// Games without a Lichess `Site` header are dropped.
+pub fn parse_pgn(pgn: &str) -> Vec<LichessGame> {
+    let mut games = Vec::new();
+    let mut headers: HashMap<String, String> = HashMap::new();
+    let mut movetext = String::new();
+
+    for line in pgn.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            // A header after movetext starts the next game
+            if !movetext.trim().is_empty() {
+                games.extend(build_game(&headers, &movetext));
+                headers.clear();
+                movetext.clear();
+            }
+            if let Some((name, value)) = header.split_once(' ') {
+                let value = value.trim().trim_matches('"').replace("\\\"", "\"");
+                headers.insert(name.to_string(), value);
+            }
+        } else if !line.starts_with('%') {
+            movetext.push_str(line);
+            movetext.push('\n');
+        }
+    }
+    games.extend(build_game(&headers, &movetext));
+    games
+}
+
+fn build_game(headers: &HashMap<String, String>, movetext: &str) -> Option<LichessGame> {
+    let id = headers
+        .get("Site")?
+        .strip_prefix(LICHESS_URL)?
+        .trim_matches('/')
+        .to_string();
+    if id.is_empty() {
+        return None;
+    }
+    Some(LichessGame {
+        id,
+        white: headers.get("White").cloned().unwrap_or_default(),
+        black: headers.get("Black").cloned().unwrap_or_default(),
+        result: headers
+            .get("Result")
+            .cloned()
+            .unwrap_or_else(|| "*".to_string()),
+        moves: movetext_moves(movetext),
+    })
+}
+
+/// The moves of the main line
+fn movetext_moves(movetext: &str) -> Vec<String> {
+    let mut main_line = String::new();
+    let mut variation_depth = 0;
+    let mut chars = movetext.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                chars.by_ref().find(|&c| c == '}');
+            }
+            ';' => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '(' => variation_depth += 1,
+            ')' => variation_depth -= 1,
+            c if variation_depth == 0 => main_line.push(c),
+            _ => {}
+        }
+    }
+
+    main_line
+        .split_whitespace()
+        .filter(|token| !matches!(*token, "1-0" | "0-1" | "1/2-1/2" | "*"))
+        // "12." and "12..." move numbers, also when glued to the move
+        .filter_map(|token| token.rsplit('.').next())
+        .filter(|token| !token.is_empty() && !token.starts_with('$'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Lichess usernames are letters, digits, '-', and '_'
+fn check_username(username: &str) -> Result<()> {
+    if username.is_empty()
+        || !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("'{username}' is not a valid Lichess username");
+    }
+    Ok(())
+}
+
+/// Headers of a request to Lichess accepting `accept`, with the API token
+/// when one is set
+fn request_headers(accept: &str) -> Vec<(&'static str, String)> {
+    let mut headers = vec![("Accept", accept.to_string())];
+    if let Ok(token) = std::env::var(TOKEN_ENV_VAR) {
+        if !token.is_empty() {
+            headers.push(("Authorization", format!("Bearer {token}")));
+        }
+    }
+    headers
+}
+
+/// The body of a successful Lichess response
+fn lichess_body(response: Result<(u16, Vec<u8>)>) -> Result<Vec<u8>> {
+    let (status, body) = response.context("Request to Lichess failed")?;
+    if !(200..300).contains(&status) {
+        anyhow::bail!(
+            "Request to Lichess failed with HTTP {status}: {}",
+            String::from_utf8_lossy(&body).trim()
+        );
+    }
+    Ok(body)
+}
+
+/// Encode a value for an `application/x-www-form-urlencoded` body
+fn form_urlencode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            byte => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
@@ -0,0 +1,4 @@
+//! Bridges to other chess services, each behind its own feature flag
+
+#[cfg(feature = "lichess")]
+pub mod lichess;
@@ -1,13 +1,29 @@
+#![recursion_limit = "256"]
+
+//! With the default `native` feature this is the whole of mate. Without it
+//! only `chess`, `messages` and `crypto` are built: the rules, protocol
+//! types and validation a browser client shares, compiling to
+//! wasm32-unknown-unknown without tokio or SQLite.
+
+#[cfg(feature = "native")]
+pub mod api;
 pub mod chess;
+#[cfg(feature = "native")]
 pub mod cli;
 pub mod crypto;
+#[cfg(feature = "native")]
+pub mod integrations;
 pub mod messages;
+#[cfg(feature = "native")]
 pub mod network;
+#[cfg(feature = "native")]
 pub mod storage;
 
 // Re-export key types for easy testing (preserve existing + add chess)
 pub use chess::{Board, ChessError, Color, Move, Piece, PieceType, Position};
 pub use crypto::{Identity, PeerId};
 pub use messages::{Message, SignedEnvelope};
+#[cfg(feature = "native")]
 pub use network::{Client, Connection, Server};
+#[cfg(feature = "native")]
 pub use storage::{Database, StorageError};
@@ -1,29 +1,86 @@
+// The async main is large enough to exceed the default limit in release builds
+#![recursion_limit = "256"]
+
 use anyhow::{Context, Result};
 use base64::{engine::general_purpose, Engine as _};
-use clap::Parser;
-use mate::cli::{app::App, display_error_and_exit, Cli, CliError, Commands, KeyCommand};
+use clap::{CommandFactory, FromArgMatches};
+use mate::cli::{
+    app::{App, Config, GamesOptions, OpeningsOptions, CONFIG_KEYS},
+    crash,
+    display::{print_json, DoctorJson, JSON_SCHEMA_VERSION},
+    display_error_and_exit,
+    doctor::{self, CheckStatus, DoctorOptions},
+    error_handler::{set_json_errors, set_usage_log, ErrorCode},
+    i18n::{self, Locale},
+    keys,
+    logging::{self, LogFormat},
+    serve::{self, ServeOptions},
+    session::{self, format_round_trip_time, print_reply, ChatSession, CommandRouter},
+    setup,
+    stress::{self, StressOptions},
+    telemetry,
+    usage::{self, UsageLog},
+    ArbiterCommand, CertificateCommand, Cli, CliError, Commands, ConfigCommand, DebugCommand,
+    DevicesCommand, KeyCommand, LichessCommand, LivePgnOptions, NetworkConfig, NetworkOptions,
+    PeersCommand, ServerCommand, TelemetryCommand,
+};
+use mate::crypto::storage::DEFAULT_IDENTITY;
 use mate::crypto::Identity;
-use mate::messages::Message;
-use mate::network::Client;
+use mate::network::known_peers::{check_peer_key, KNOWN_PEERS_FILE};
+use mate::network::{Client, UserActivity};
 
-use std::io::{self, BufRead, Write};
+use std::io;
 use std::sync::Arc;
-use tokio::signal;
-use tokio::time::Instant;
+use std::time::Duration;
 use tracing::{debug, error, info, warn};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Import games from or export a game to Lichess
+#[cfg(feature = "lichess")]
+async fn run_lichess(app: &App, command: LichessCommand) -> Result<()> {
+    use mate::integrations::lichess;
+
+    match command {
+        LichessCommand::Import { username, max } => {
+            println!("Downloading games of {username} from Lichess...");
+            let pgn = lichess::fetch_user_games(&username, max).await?;
+            let summary = lichess::import_games(&app.database, app.peer_id(), &username, &pgn)?;
+            println!("✓ Imported {} games", summary.imported.len());
+            if summary.duplicates > 0 {
+                println!("Skipped {} games imported before", summary.duplicates);
+            }
+            if summary.invalid > 0 {
+                println!(
+                    "Skipped {} games that could not be replayed (e.g. variants)",
+                    summary.invalid
+                );
+            }
+            Ok(())
+        }
+        LichessCommand::Export { game_id } => {
+            let game_id = app.resolve_game(&game_id)?.id;
+            let url = lichess::upload_game(&app.database, &game_id, app.peer_id()).await?;
+            println!("✓ Exported game {game_id} to {url}");
+            Ok(())
+        }
+    }
+}
 
-/// Format round-trip time for display with appropriate precision
-fn format_round_trip_time(duration: std::time::Duration) -> String {
-    let millis = duration.as_millis();
-    let micros = duration.as_micros();
+#[cfg(not(feature = "lichess"))]
+async fn run_lichess(_app: &App, _command: LichessCommand) -> Result<()> {
+    anyhow::bail!("This build of mate has no Lichess support (rebuild with --features lichess)")
+}
 
-    if millis == 0 {
-        format!("{micros}μs")
-    } else if millis < 1000 {
-        format!("{millis}ms")
-    } else {
-        let seconds = duration.as_secs_f64();
-        format!("{seconds:.2}s")
+/// Address to reach a server listening on `bind_addr` from this machine
+fn local_server_address(bind_addr: &str) -> String {
+    match bind_addr.parse::<std::net::SocketAddr>() {
+        Ok(addr) if addr.ip().is_unspecified() => {
+            format!("{}:{}", std::net::Ipv4Addr::LOCALHOST, addr.port())
+        }
+        _ => bind_addr.to_string(),
     }
 }
 
@@ -32,35 +89,6 @@ pub async fn init_identity() -> Result<Identity> {
     Identity::load_or_generate()
 }
 
-/// Set up graceful shutdown signal handling
-async fn setup_shutdown_signal() -> Result<()> {
-    let ctrl_c = async {
-        signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
-        info!("Received Ctrl+C signal, initiating graceful shutdown...");
-    };
-
-    #[cfg(unix)]
-    let terminate = async {
-        signal::unix::signal(signal::unix::SignalKind::terminate())
-            .expect("Failed to install SIGTERM handler")
-            .recv()
-            .await;
-        info!("Received SIGTERM signal, initiating graceful shutdown...");
-    };
-
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {},
-        _ = terminate => {},
-    }
-
-    Ok(())
-}
-
 /// Gracefully shutdown the application with proper cleanup
 async fn graceful_shutdown(app: Option<&App>) -> Result<()> {
     info!("Starting graceful shutdown sequence...");
@@ -80,26 +108,187 @@ async fn graceful_shutdown(app: Option<&App>) -> Result<()> {
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    set_json_errors(cli.json);
+
+    // Keep stdout clean when it carries JSON, completions, or the man page
+    let log_writer = if cli.stdout_is_data() {
+        BoxMakeWriter::new(io::stderr)
+    } else {
+        BoxMakeWriter::new(io::stdout)
+    };
+
     // Step 5.1: Initialize tracing with appropriate logging levels for network operations
     // Set up structured logging with appropriate levels for production use
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::builder()
-                .with_default_directive("mate=info".parse()?)
-                .with_env_var("RUST_LOG")
-                .from_env_lossy(),
-        )
-        .with_target(false) // Hide target module in logs for cleaner output
-        .with_level(true) // Show log levels
-        .with_file(false) // Hide file names for production
-        .with_line_number(false) // Hide line numbers for production
+    let log_format = cli.log_format();
+    let console_layer = match log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .with_writer(log_writer)
+            .with_target(false) // Hide target module in logs for cleaner output
+            .with_level(true) // Show log levels
+            .with_file(false) // Hide file names for production
+            .with_line_number(false) // Hide line numbers for production
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .event_format(logging::JsonFormat)
+            .fmt_fields(logging::JsonFields)
+            .with_writer(log_writer)
+            .boxed(),
+    }
+    .with_filter(
+        tracing_subscriber::EnvFilter::builder()
+            .with_default_directive(cli.log_directive().parse()?)
+            .with_env_var("RUST_LOG")
+            .from_env_lossy(),
+    );
+
+    // The log file keeps full debug output whatever the terminal shows
+    let file_layer = match &cli.log_file {
+        Some(path) => {
+            let file = logging::RotatingFile::open(
+                path,
+                cli.log_max_size * 1024 * 1024,
+                cli.log_rotation(),
+            )
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            let writer = std::sync::Mutex::new(file);
+            let layer = match log_format {
+                LogFormat::Text => tracing_subscriber::fmt::layer()
+                    .with_writer(writer)
+                    .with_ansi(false)
+                    .boxed(),
+                LogFormat::Json => tracing_subscriber::fmt::layer()
+                    .event_format(logging::JsonFormat)
+                    .fmt_fields(logging::JsonFields)
+                    .with_writer(writer)
+                    .boxed(),
+            };
+            Some(layer.with_filter(tracing_subscriber::EnvFilter::new(
+                logging::LOG_FILE_DIRECTIVE,
+            )))
+        }
+        None => None,
+    };
+
+    // The latest debug lines are kept in memory for crash bundles
+    let recent_events = crash::RecentEvents::default();
+    let recent_events_layer = tracing_subscriber::fmt::layer()
+        .with_writer(recent_events.clone())
+        .with_ansi(false)
+        .with_filter(tracing_subscriber::EnvFilter::new(
+            logging::LOG_FILE_DIRECTIVE,
+        ));
+
+    // Spans go to an OTLP collector when one is configured
+    let telemetry_settings = Config::load_or_default()
+        .map(|config| config.telemetry)
+        .unwrap_or_default();
+    let (otlp_layer, otlp_exporter) = match telemetry_settings.endpoint()? {
+        Some(endpoint) => {
+            let (layer, exporter) =
+                telemetry::OtlpExporter::start(&endpoint, &telemetry_settings.service_name);
+            let layer = layer.with_filter(tracing_subscriber::EnvFilter::new(
+                telemetry::OTLP_DIRECTIVE,
+            ));
+            (Some(layer), Some(exporter))
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(console_layer)
+        .with(file_layer)
+        .with(otlp_layer)
+        .with(recent_events_layer)
         .init();
 
+    // Panics leave a crash bundle in the data directory instead of a raw
+    // backtrace; the redacted configuration is snapshotted now so the hook
+    // does no config loading while panicking
+    let crash_config = Config::load_or_default().ok();
+    crash::install_panic_hook(
+        crash_config
+            .as_ref()
+            .map(|config| config.data_dir.clone())
+            .unwrap_or_else(|| Config::default().data_dir),
+        recent_events,
+        crash_config.and_then(|config| crash::redacted_config(&config).ok()),
+    );
+
+    // Messages follow the configured language; a broken config file still
+    // gets its errors reported in the environment's language
+    i18n::set_locale(
+        Config::load_or_default()
+            .map(|config| config.display.locale())
+            .unwrap_or_else(|_| Locale::resolve(None)),
+    );
+
+    // Commands and errors are only counted once usage reports are turned on
+    if let Ok(config) = Config::load_or_default() {
+        if config.telemetry.usage_reports && !cli.ephemeral {
+            let log = UsageLog::in_dir(&config.data_dir);
+            if let Some(name) = usage::command_name(&matches) {
+                if let Err(e) = log.record_command(&name) {
+                    debug!("Failed to count command for usage reports: {:#}", e);
+                }
+            }
+            set_usage_log(log);
+        }
+    }
+
+    mate::crypto::storage::set_permission_policy(
+        Config::load_or_default()
+            .map(|config| config.identity.key_permissions)
+            .unwrap_or_default(),
+    );
+
+    // --identity overrides the identity chosen with 'mate key use'
+    let identity_name = match &cli.identity {
+        Some(name) => Some(name.clone()),
+        None => Config::load_or_default()
+            .ok()
+            .and_then(|config| config.identity.active),
+    };
+    if let Some(name) = identity_name {
+        if let Err(e) = mate::crypto::storage::select_identity(&name) {
+            display_error_and_exit(CliError::Configuration {
+                setting: "identity".to_string(),
+                issue: e.to_string(),
+                suggestion: "Run 'mate key list' to see your identities".to_string(),
+            });
+        }
+        // Only commands that manage keys may run without the identity's key;
+        // everything else would silently generate a new one
+        let manages_keys = matches!(
+            cli.command,
+            Commands::Key { .. }
+                | Commands::SetupWizard
+                | Commands::Config { .. }
+                | Commands::Doctor { .. }
+                | Commands::Stress { .. }
+                | Commands::Debug { .. }
+                | Commands::Completions { .. }
+                | Commands::Man
+                | Commands::Certificate {
+                    command: Some(_),
+                    ..
+                }
+        );
+        let key_exists = mate::crypto::storage::default_key_path().is_ok_and(|path| path.exists());
+        if name != DEFAULT_IDENTITY && !key_exists && !manages_keys && !cli.ephemeral {
+            display_error_and_exit(CliError::UserError {
+                message: format!("There is no identity named '{name}'"),
+                suggestion: Some(format!(
+                    "Create it with 'mate --identity {name} key generate', or run 'mate key list'"
+                )),
+                code: ErrorCode::Configuration,
+            });
+        }
+    }
+
     info!("Starting mate application with network-optimized logging configuration");
     debug!("Application lifecycle: Main function started");
-    debug!("Application lifecycle: Parsing command line arguments");
-
-    let cli = Cli::parse();
     debug!("Application lifecycle: Command line arguments parsed successfully");
 
     match cli.command {
@@ -150,124 +339,177 @@ async fn main() -> Result<()> {
                 }
             }
         }
-        Commands::Key { command } => {
-            match command {
-                KeyCommand::Path => {
-                    info!("Showing default key storage path...");
-                    match mate::crypto::storage::default_key_path() {
-                        Ok(path) => {
-                            info!("Default key storage path: {}", path.display());
-                            if path.exists() {
-                                info!("✓ Identity file exists");
-                            } else {
-                                info!("✗ Identity file does not exist");
-                                info!("Run 'mate key generate' to create a new identity");
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to determine key storage path: {}", e);
-                        }
-                    }
+        Commands::Key { command } => match command {
+            KeyCommand::Path => keys::handle_key_path(),
+            KeyCommand::Generate => keys::handle_key_generate(cli.yes)?,
+            KeyCommand::Info => keys::handle_key_info(),
+            KeyCommand::Protect => keys::handle_key_protect()?,
+            KeyCommand::Unprotect => keys::handle_key_unprotect()?,
+            KeyCommand::Rotate => {
+                info!("Rotating identity key...");
+                let app = App::new_with_storage(cli.db_path, cli.ephemeral)
+                    .await
+                    .context("Failed to initialize application")?;
+                let result = app
+                    .handle_key_rotate(cli.yes)
+                    .await
+                    .context("Failed to rotate identity key");
+                if let Err(e) = result {
+                    error!("Key rotation failed: {}", e);
+                    display_error_and_exit(CliError::from(e));
                 }
-                KeyCommand::Generate => {
-                    info!("Generating new identity...");
-
-                    // Get the key path once and reuse it
-                    let key_path = match mate::crypto::storage::default_key_path() {
-                        Ok(path) => path,
-                        Err(e) => {
-                            error!("Failed to determine key storage path: {}", e);
-                            return Ok(());
-                        }
+            }
+            KeyCommand::Revoke { reason } => {
+                info!("Revoking identity key...");
+                let app = App::new_with_storage(cli.db_path, cli.ephemeral)
+                    .await
+                    .context("Failed to initialize application")?;
+                let result = app
+                    .handle_key_revoke(reason, cli.yes)
+                    .await
+                    .context("Failed to revoke identity key");
+                if let Err(e) = result {
+                    error!("Key revocation failed: {}", e);
+                    display_error_and_exit(CliError::from(e));
+                }
+            }
+            KeyCommand::Backup => keys::handle_key_backup()?,
+            KeyCommand::List => keys::handle_key_list()?,
+            KeyCommand::Use { name } => keys::handle_key_use(&name)?,
+            KeyCommand::Recover => keys::handle_key_recover(cli.yes)?,
+            KeyCommand::Token { module, label } => {
+                keys::handle_key_token(&module, &label, cli.yes)?
+            }
+        },
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "mate", &mut io::stdout());
+        }
+        Commands::Man => {
+            clap_mangen::Man::new(Cli::command())
+                .render(&mut io::stdout())
+                .context("Failed to write man page")?;
+        }
+        Commands::Config { command } => match command {
+            ConfigCommand::Get { key } => {
+                let config = Config::load_or_default().context("Failed to load configuration")?;
+                if cli.json {
+                    let output = match key {
+                        Some(key) => serde_json::json!({
+                            "schema_version": JSON_SCHEMA_VERSION,
+                            "key": key,
+                            "value": config.get(&key)?,
+                        }),
+                        None => serde_json::json!({
+                            "schema_version": JSON_SCHEMA_VERSION,
+                            "config": config,
+                        }),
                     };
-
-                    // Check if identity already exists
-                    if key_path.exists() {
-                        warn!("An identity already exists at: {}", key_path.display());
-                        warn!("This will overwrite the existing identity!");
-                    }
-
-                    let identity = Identity::generate()?;
-                    identity.save_to_default_storage()?;
-
-                    info!("Identity generated successfully!");
-                    info!("Peer ID: {}", identity.peer_id());
-                    info!(
-                        "Public Key: {}",
-                        general_purpose::STANDARD.encode(identity.verifying_key().to_bytes())
-                    );
-                    info!("Saved to: {}", key_path.display());
+                    print_json(&output)?;
+                    return Ok(());
                 }
-                KeyCommand::Info => {
-                    info!("Showing identity information...");
-
-                    match Identity::from_default_storage() {
-                        Ok(identity) => {
-                            info!("Peer ID: {}", identity.peer_id());
-                            info!(
-                                "Public Key: {}",
-                                general_purpose::STANDARD
-                                    .encode(identity.verifying_key().to_bytes())
-                            );
-
-                            if let Ok(path) = mate::crypto::storage::default_key_path() {
-                                info!("Storage location: {}", path.display());
+                match key {
+                    Some(key) => match config.get(&key)? {
+                        Some(value) => println!("{value}"),
+                        None => println!("(unset)"),
+                    },
+                    None => {
+                        for key in CONFIG_KEYS {
+                            if let Some(value) = config.get(key)? {
+                                println!("{key} = {value}");
                             }
                         }
-                        Err(e) => {
-                            error!("No identity found: {}", e);
-                            info!("Run 'mate key generate' to create a new identity");
-                        }
                     }
                 }
             }
-        }
-        Commands::Serve { bind } => {
-            info!("Starting server on {}", bind);
-            debug!("Server lifecycle: Initializing server components");
-
-            // Use secure storage for identity
-            let identity = std::sync::Arc::new(init_identity().await?);
-            info!("Loaded identity: {}", identity.peer_id());
-            debug!("Server lifecycle: Identity loaded successfully");
-
-            // Create and run server with graceful shutdown handling
-            let server = mate::network::Server::bind(&bind, identity).await?;
-
-            info!("Server bound successfully, starting to accept connections...");
-            debug!("Server lifecycle: Server bound, installing signal handlers");
-
-            // Run server with graceful shutdown
-            tokio::select! {
-                result = server.run() => {
-                    match result {
-                        Ok(()) => {
-                            info!("Server shutdown complete");
-                            debug!("Server lifecycle: Server terminated normally");
-                        }
-                        Err(e) => {
-                            error!("Server error: {}", e);
-                            debug!("Server lifecycle: Server terminated with error");
-                        }
-                    }
-                }
-                _ = setup_shutdown_signal() => {
-                    info!("Server lifecycle: Shutdown signal received, terminating server...");
-                    debug!("Server lifecycle: Graceful shutdown initiated");
-
-                    // Note: The server will automatically stop when this scope ends
-                    // and the server variable is dropped, which closes all connections
-                    if let Err(cleanup_error) = graceful_shutdown(None).await {
-                        warn!("Server lifecycle: Cleanup encountered issues: {}", cleanup_error);
-                    } else {
-                        debug!("Server lifecycle: Cleanup completed successfully");
-                    }
-
-                    info!("Server lifecycle: Graceful shutdown completed");
+            ConfigCommand::Set { key, value } => {
+                // Edit only what is stored so environment overrides are not persisted
+                let mut config = Config::load_stored().context("Failed to load configuration")?;
+                config.set(&key, &value)?;
+                config.save().context("Failed to save configuration")?;
+                println!("{key} = {}", config.get(&key)?.unwrap_or_default());
+
+                let var = Config::env_var(&key);
+                if std::env::var(&var).is_ok() {
+                    println!("Note: {var} is set and overrides this value");
                 }
             }
+        },
+        Commands::Serve { bind, http } => {
+            serve::run_serve(ServeOptions {
+                bind,
+                http,
+                db_path: cli.db_path,
+                ephemeral: cli.ephemeral,
+            })
+            .await?;
         }
-        Commands::Connect { address, message } => {
+        Commands::Server {
+            command: ServerCommand::Announce { text, address },
+        } => {
+            let config = Config::load_or_default().context("Failed to load configuration")?;
+            let address = match address {
+                Some(address) => setup::resolve_address(&address),
+                None => local_server_address(&config.default_bind_addr),
+            };
+            let identity = Arc::new(init_identity().await?);
+            let client = Client::new_with_config(
+                identity,
+                config
+                    .network
+                    .wire_config(mate::messages::wire::WireConfig::default()),
+            );
+            if let Err(e) = client.announce(&address, &text).await {
+                display_error_and_exit(CliError::from(e));
+            }
+            println!("✓ Announced to peers connected to {address}");
+        }
+        Commands::SetupWizard => {
+            setup::run_interactive()?;
+        }
+        Commands::Doctor { peer } => {
+            let options = DoctorOptions {
+                db_path: cli.db_path,
+                peer: peer.map(|peer| setup::resolve_address(&peer)),
+            };
+            let checks = doctor::run_checks(&options).await;
+            if cli.json {
+                print_json(&DoctorJson {
+                    schema_version: JSON_SCHEMA_VERSION,
+                    checks: checks.clone(),
+                })?;
+            } else {
+                doctor::print_report(&checks);
+            }
+            if doctor::count(&checks, CheckStatus::Fail) > 0 {
+                std::process::exit(1);
+            }
+        }
+        Commands::Stress {
+            clients,
+            duration,
+            interval,
+            address,
+        } => {
+            let options = StressOptions {
+                clients,
+                duration: Duration::from_secs(duration),
+                interval: Duration::from_millis(interval),
+                address: address.map(|address| setup::resolve_address(&address)),
+            };
+            let report = stress::run_stress(&options).await?;
+            if cli.json {
+                print_json(&report)?;
+            } else {
+                println!("{report}");
+            }
+        }
+        Commands::Connect {
+            address,
+            message,
+            stdin,
+            network,
+        } => {
+            let address = setup::resolve_address(&address);
             info!("Connecting to {}", address);
 
             // Use secure storage for identity
@@ -275,307 +517,219 @@ async fn main() -> Result<()> {
             info!("Using identity: {}", identity.peer_id());
 
             // Create client instance
-            let client = Client::new(identity);
+            let wire_config = network.apply(NetworkConfig::default()).wire_config;
+            let client = Client::new_with_config(identity, wire_config);
 
             // Attempt connection
-            match client.connect(&address).await {
-                Ok(mut connection) => {
-                    let peer_id = connection.peer_identity().unwrap_or("unknown").to_string();
-                    info!("Connected to peer: {}", peer_id);
+            match ChatSession::connect(client, &address, network).await {
+                Ok(mut session) => {
+                    info!("Connected to peer: {}", session.peer_id());
+
+                    // Connect is a diagnostic tool that carries no game traffic, so a
+                    // changed key is reported but does not end the session
+                    if let Some(presented) = session.connection().peer_identity() {
+                        let known_peers = Config::load_or_default()
+                            .context("Failed to load configuration")?
+                            .data_dir
+                            .join(KNOWN_PEERS_FILE);
+                        if let Err(e) = check_peer_key(
+                            &known_peers,
+                            &address,
+                            presented,
+                            network.accept_new_key,
+                        ) {
+                            warn!("{:#}", e);
+                        }
+                    }
+
+                    if stdin {
+                        let failures = session.run_batch(io::stdin().lock(), print_reply).await;
+                        session.close().await;
+                        if failures > 0 {
+                            error!("{} message(s) did not get a reply", failures);
+                            std::process::exit(ErrorCode::Network.exit_status());
+                        }
+                        return Ok(());
+                    }
 
                     // Handle one-shot message mode
                     if let Some(msg_text) = message {
                         info!("Sending message: \"{}\"", msg_text);
-                        let start_time = Instant::now();
-                        let ping_message =
-                            Message::new_ping(rand::random::<u64>(), msg_text.clone());
-
-                        // Send message and measure round-trip time
-                        match connection.send_message(ping_message).await {
-                            Ok(()) => match connection.receive_message().await {
-                                Ok((response, _sender)) => {
-                                    let round_trip_time = start_time.elapsed();
-                                    info!(
-                                        "Received echo: \"{}\" (round-trip: {})",
-                                        response.get_payload(),
-                                        format_round_trip_time(round_trip_time)
-                                    );
-                                }
-                                Err(e) => {
-                                    error!("Failed to receive response: {}", e);
-                                }
-                            },
-                            Err(e) => {
-                                error!("Failed to send message: {}", e);
-                            }
+                        match session.exchange(&msg_text).await {
+                            Ok(echo) => info!(
+                                "Received echo: \"{}\" (round-trip: {})",
+                                echo.payload,
+                                format_round_trip_time(echo.round_trip)
+                            ),
+                            Err(e) => error!("{:#}", e),
                         }
+                        session.close().await;
                     } else {
-                        // Interactive mode - enhanced session management with help commands and status display
-                        println!("=== MATE Chat Session ===");
-                        println!("Connected to peer: {}", peer_id);
-                        println!("Connection status: Active");
-                        println!();
-                        println!("Available commands:");
-                        println!("  help    - Show this help message");
-                        println!("  info    - Show connection information");
-                        println!("  quit    - Exit the chat session");
-                        println!("  exit    - Exit the chat session");
-                        println!();
-                        println!("Type messages and press Enter to send. Press Ctrl+C or Ctrl+D to exit.");
-                        println!("{}", "=".repeat(30));
-
-                        let stdin = io::stdin();
-                        let mut stdin_lock = stdin.lock();
-                        let mut message_count = 0u32;
-                        let mut total_round_trip_time = std::time::Duration::ZERO;
-                        let session_start = Instant::now();
-
-                        loop {
-                            // Display prompt with connection status indicator
-                            print!("mate> ");
-                            io::stdout().flush().unwrap();
-
-                            // Read user input
-                            let mut input = String::new();
-                            match stdin_lock.read_line(&mut input) {
-                                Ok(0) => {
-                                    // EOF (Ctrl+D)
-                                    println!(); // New line after Ctrl+D
-                                    break;
-                                }
-                                Ok(_) => {
-                                    let input = input.trim().to_string();
-
-                                    // Handle empty input
-                                    if input.is_empty() {
-                                        continue;
-                                    }
-
-                                    // Handle special commands
-                                    match input.as_str() {
-                                        "help" => {
-                                            println!("=== Available Commands ===");
-                                            println!("  help    - Show this help message");
-                                            println!("  info    - Show connection information");
-                                            println!("  quit    - Exit the chat session");
-                                            println!("  exit    - Exit the chat session");
-                                            println!();
-                                            println!("Any other text will be sent as a message to the peer.");
-                                            continue;
-                                        }
-                                        "info" => {
-                                            let session_duration = session_start.elapsed();
-                                            println!("=== Connection Information ===");
-                                            println!("Peer ID: {}", peer_id);
-                                            println!("Connection status: Active");
-                                            println!(
-                                                "Session duration: {}",
-                                                format_round_trip_time(session_duration)
-                                            );
-                                            println!("Messages sent: {}", message_count);
-                                            if message_count > 0 {
-                                                let avg_time =
-                                                    total_round_trip_time / message_count;
-                                                println!(
-                                                    "Average round-trip time: {}",
-                                                    format_round_trip_time(avg_time)
-                                                );
-                                            }
-                                            continue;
-                                        }
-                                        "quit" | "exit" => {
-                                            break;
-                                        }
-                                        _ => {
-                                            // Regular message - send to peer
-                                        }
-                                    }
-
-                                    // Send message and measure round-trip time
-                                    let start_time = Instant::now();
-                                    let ping_message =
-                                        Message::new_ping(rand::random::<u64>(), input.clone());
-
-                                    match connection.send_message(ping_message).await {
-                                        Ok(()) => {
-                                            match connection.receive_message().await {
-                                                Ok((response, _sender)) => {
-                                                    let round_trip_time = start_time.elapsed();
-                                                    message_count += 1;
-                                                    total_round_trip_time += round_trip_time;
-                                                    println!(
-                                                        "← Received echo: \"{}\" (round-trip: {})",
-                                                        response.get_payload(),
-                                                        format_round_trip_time(round_trip_time)
-                                                    );
-                                                }
-                                                Err(e) => {
-                                                    error!("Connection error: Failed to receive response: {}", e);
-                                                    warn!("The connection to the peer may have been lost.");
-                                                    println!("Connection status: Disconnected");
-                                                    println!("Attempting to reconnect...");
-
-                                                    // Attempt to reconnect (basic retry logic)
-                                                    match client.connect(&address).await {
-                                                        Ok(new_connection) => {
-                                                            connection = new_connection;
-                                                            let new_peer_id = connection
-                                                                .peer_identity()
-                                                                .unwrap_or("unknown")
-                                                                .to_string();
-                                                            info!(
-                                                                "Reconnected to peer: {}",
-                                                                new_peer_id
-                                                            );
-                                                            println!(
-                                                                "Connection status: Reconnected"
-                                                            );
-                                                        }
-                                                        Err(reconnect_err) => {
-                                                            error!(
-                                                                "Failed to reconnect: {}",
-                                                                reconnect_err
-                                                            );
-                                                            println!("Reconnection failed. Please restart the session.");
-                                                            break;
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            error!(
-                                                "Connection error: Failed to send message: {}",
-                                                e
-                                            );
-                                            warn!("The connection to the peer may have been lost.");
-                                            println!("Connection status: Disconnected");
-                                            println!("Attempting to reconnect...");
-
-                                            // Attempt to reconnect (basic retry logic)
-                                            match client.connect(&address).await {
-                                                Ok(new_connection) => {
-                                                    connection = new_connection;
-                                                    let new_peer_id = connection
-                                                        .peer_identity()
-                                                        .unwrap_or("unknown")
-                                                        .to_string();
-                                                    info!("Reconnected to peer: {}", new_peer_id);
-                                                    println!("Connection status: Reconnected");
-
-                                                    // Retry sending the message
-                                                    let retry_start_time = Instant::now();
-                                                    let retry_ping_message = Message::new_ping(
-                                                        rand::random::<u64>(),
-                                                        input.clone(),
-                                                    );
-                                                    match connection
-                                                        .send_message(retry_ping_message)
-                                                        .await
-                                                    {
-                                                        Ok(()) => {
-                                                            match connection.receive_message().await
-                                                            {
-                                                                Ok((response, _sender)) => {
-                                                                    let round_trip_time =
-                                                                        retry_start_time.elapsed();
-                                                                    message_count += 1;
-                                                                    total_round_trip_time +=
-                                                                        round_trip_time;
-                                                                    println!("← Received echo: \"{}\" (round-trip: {})", 
-                                                                            response.get_payload(), format_round_trip_time(round_trip_time));
-                                                                }
-                                                                Err(e) => {
-                                                                    error!("Still failed to receive after reconnect: {}", e);
-                                                                    break;
-                                                                }
-                                                            }
-                                                        }
-                                                        Err(e) => {
-                                                            error!("Still failed to send after reconnect: {}", e);
-                                                            break;
-                                                        }
-                                                    }
-                                                }
-                                                Err(reconnect_err) => {
-                                                    error!(
-                                                        "Failed to reconnect: {}",
-                                                        reconnect_err
-                                                    );
-                                                    println!("Reconnection failed. Please restart the session.");
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    error!("Failed to read input: {}", e);
-                                    break;
-                                }
-                            }
-                        }
-
-                        // Display session summary
-                        let session_duration = session_start.elapsed();
-                        println!();
-                        println!("=== Session Summary ===");
-                        println!(
-                            "Session duration: {}",
-                            format_round_trip_time(session_duration)
-                        );
-                        if message_count > 0 {
-                            let avg_time = total_round_trip_time / message_count;
-                            println!("Messages sent: {}", message_count);
-                            println!(
-                                "Average round-trip time: {}",
-                                format_round_trip_time(avg_time)
-                            );
-                        } else {
-                            println!("No messages sent during this session");
-                        }
-                        println!("Goodbye!");
-                    }
-
-                    // Close connection
-                    if let Err(e) = connection.close().await {
-                        warn!("Failed to close connection cleanly: {}", e);
+                        let mut router = CommandRouter::new(session);
+                        session::run_terminal(&mut router).await?;
+                        router.session_mut().close().await;
                     }
                 }
                 Err(e) => {
                     error!("Failed to connect to {}: {}", address, e);
-                    std::process::exit(1);
+                    std::process::exit(ErrorCode::Network.exit_status());
                 }
             }
         }
 
+        Commands::ImportAccount { file, force } => {
+            info!("Importing account from {}", file.display());
+
+            if cli.ephemeral {
+                error!("Cannot import an account into an ephemeral session");
+                display_error_and_exit(CliError::UserError {
+                    message: "--ephemeral cannot be combined with import-account".to_string(),
+                    suggestion: None,
+                    code: ErrorCode::InvalidInput,
+                });
+            }
+
+            if let Err(e) =
+                mate::cli::account::handle_import_account(&file, force, cli.db_path, cli.yes)
+                    .context("Failed to import account")
+            {
+                let cli_error = CliError::from(e);
+                display_error_and_exit(cli_error);
+            }
+        }
+
+        Commands::Certificate {
+            command: Some(CertificateCommand::Verify { file }),
+            ..
+        } => {
+            if let Err(e) = mate::cli::certificate::handle_verify_certificate(&file, cli.json) {
+                display_error_and_exit(CliError::from(e));
+            }
+        }
+
+        Commands::Debug {
+            command: DebugCommand::Decode { file },
+        } => {
+            if let Err(e) = mate::cli::debug::handle_decode(&file, cli.json) {
+                display_error_and_exit(CliError::from(e));
+            }
+        }
+
         // Chess commands - Initialize App once and handle all chess operations with proper lifecycle management
-        Commands::Games
+        Commands::Games { .. }
+        | Commands::Status { .. }
+        | Commands::Top { .. }
         | Commands::Board { .. }
         | Commands::Invite { .. }
         | Commands::Accept { .. }
         | Commands::Move { .. }
-        | Commands::History { .. } => {
+        | Commands::Resign { .. }
+        | Commands::OfferDraw { .. }
+        | Commands::Seal { .. }
+        | Commands::Presence { .. }
+        | Commands::Report { .. }
+        | Commands::History { .. }
+        | Commands::Timeline { .. }
+        | Commands::Openings { .. }
+        | Commands::Stats { .. }
+        | Commands::Tag { .. }
+        | Commands::Note { .. }
+        | Commands::Export { .. }
+        | Commands::Certificate { .. }
+        | Commands::Vote { .. }
+        | Commands::Study { .. }
+        | Commands::Studies { .. }
+        | Commands::Purge { .. }
+        | Commands::Tui
+        | Commands::Play { .. }
+        | Commands::Hotseat { .. }
+        | Commands::Selfplay { .. }
+        | Commands::Uci { .. }
+        | Commands::Replay { .. }
+        | Commands::ExportAccount { .. }
+        | Commands::Peers { .. }
+        | Commands::Lichess { .. }
+        | Commands::Telemetry { .. }
+        | Commands::Devices { .. }
+        | Commands::Arbiter { .. } => {
+            // Set things up interactively before the first command creates defaults
+            if !cli.ephemeral && !cli.json && setup::is_first_run() && setup::is_interactive() {
+                println!("Welcome to mate! No configuration was found, so let's create one.");
+                setup::run_interactive()?;
+                println!();
+            }
+
             info!("Initializing chess application...");
             debug!("Chess command lifecycle: Starting application initialization");
 
             // Initialize App instance once for all chess commands
-            let app = App::new()
+            let output_format = cli.output_format();
+            let network_options = match &cli.command {
+                Commands::Invite { network, .. }
+                | Commands::Accept { network, .. }
+                | Commands::Move { network, .. } => *network,
+                _ => NetworkOptions::default(),
+            };
+            let live_pgn_options = match &cli.command {
+                Commands::Board { live_pgn, .. } | Commands::Play { live_pgn, .. } => {
+                    live_pgn.clone()
+                }
+                _ => LivePgnOptions::default(),
+            };
+            let compact = matches!(
+                cli.command,
+                Commands::Board { compact: true, .. } | Commands::Games { compact: true, .. }
+            );
+            let app = App::new_with_storage(cli.db_path, cli.ephemeral)
                 .await
-                .context("Failed to initialize application")?;
+                .context("Failed to initialize application")?
+                .with_output_format(output_format)
+                .with_network_options(network_options)
+                .with_live_pgn(live_pgn_options)
+                .with_theme(cli.theme)
+                .with_compact(compact);
 
             info!("Chess application initialized successfully");
             debug!("Chess command lifecycle: Application initialization complete");
             debug!("Peer ID: {}", app.peer_id());
             debug!("Data directory: {}", app.data_dir().display());
 
+            // Lets `mate serve` hold back email while you are at the keyboard
+            if !cli.ephemeral {
+                if let Err(e) = UserActivity::in_dir(app.data_dir()).touch() {
+                    debug!("Failed to record activity: {:#}", e);
+                }
+            }
+
             // Execute the chess command with proper lifecycle management
             let command_result = match cli.command {
-                Commands::Games => {
+                Commands::Games {
+                    tag,
+                    status,
+                    opponent,
+                    since,
+                    sort,
+                    limit,
+                    page,
+                    columns,
+                    ..
+                } => {
                     info!("Chess command lifecycle: Starting games list operation");
                     debug!("Retrieving active games from database");
 
-                    let result = app.handle_games().await.context("Failed to list games");
+                    let result = app
+                        .handle_games_with(GamesOptions {
+                            tag,
+                            status,
+                            opponent,
+                            since,
+                            sort: Some(sort),
+                            limit,
+                            page,
+                            columns,
+                        })
+                        .await
+                        .context("Failed to list games");
 
                     match &result {
                         Ok(()) => {
@@ -592,7 +746,60 @@ async fn main() -> Result<()> {
                     result
                 }
 
-                Commands::Board { game_id } => {
+                Commands::Status { network } => {
+                    info!("Chess command lifecycle: Showing status");
+
+                    let result = app
+                        .handle_status(network)
+                        .await
+                        .context("Failed to show status");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Status failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Top { interval } => {
+                    info!("Chess command lifecycle: Monitoring server");
+
+                    let result = app
+                        .handle_top(Duration::from_secs(interval))
+                        .await
+                        .context("Failed to monitor server");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Monitoring failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Board {
+                    game_id,
+                    follow: true,
+                    ..
+                } => {
+                    info!(
+                        "Chess command lifecycle: Following board for {}",
+                        game_id.as_deref().unwrap_or("most recent game")
+                    );
+
+                    let result = app
+                        .handle_board_follow(game_id)
+                        .await
+                        .context("Failed to follow board");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Board follow failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Board {
+                    game_id,
+                    follow: false,
+                    ..
+                } => {
                     if let Some(ref id) = game_id {
                         info!(
                             "Chess command lifecycle: Starting board display for game: {}",
@@ -626,7 +833,28 @@ async fn main() -> Result<()> {
                     result
                 }
 
-                Commands::Invite { address, color } => {
+                Commands::Invite {
+                    withdraw: Some(game_id),
+                    ..
+                } => {
+                    info!(
+                        "Chess command lifecycle: Withdrawing invitation to: {}",
+                        game_id
+                    );
+                    app.handle_invite_withdraw(game_id)
+                        .await
+                        .context("Failed to withdraw invitation")
+                }
+
+                Commands::Invite {
+                    address: Some(address),
+                    color,
+                    copy,
+                    odds,
+                    extra_moves,
+                    rated,
+                    ..
+                } => {
                     info!(
                         "Chess command lifecycle: Starting game invitation to: {}",
                         address
@@ -638,7 +866,7 @@ async fn main() -> Result<()> {
                     }
 
                     let result = app
-                        .handle_invite(address, color)
+                        .handle_invite_with_odds(address, color, copy, &odds, extra_moves, rated)
                         .await
                         .context("Failed to send invitation");
 
@@ -654,21 +882,34 @@ async fn main() -> Result<()> {
                     result
                 }
 
-                Commands::Accept { game_id, color } => {
-                    info!(
-                        "Chess command lifecycle: Starting game acceptance for: {}",
-                        game_id
-                    );
+                Commands::Accept {
+                    game_id,
+                    paste,
+                    color,
+                    ..
+                } => {
+                    if let Some(ref id) = game_id {
+                        info!(
+                            "Chess command lifecycle: Starting game acceptance for: {}",
+                            id
+                        );
+                    } else if paste {
+                        info!("Chess command lifecycle: Starting game acceptance from clipboard");
+                    } else {
+                        info!("Chess command lifecycle: Starting game acceptance from inbox");
+                    }
                     if let Some(ref color_pref) = color {
                         debug!("Color preference specified: {}", color_pref);
                     } else {
                         debug!("No color preference specified, will use automatic selection");
                     }
 
-                    let result = app
-                        .handle_accept(game_id, color)
-                        .await
-                        .context("Failed to accept invitation");
+                    let result = match game_id {
+                        Some(game_id) => app.handle_accept(game_id, color).await,
+                        None if paste => app.handle_accept_pasted(color).await,
+                        None => app.handle_accept_from_inbox(color).await,
+                    }
+                    .context("Failed to accept invitation");
 
                     match &result {
                         Ok(()) => {
@@ -685,6 +926,7 @@ async fn main() -> Result<()> {
                 Commands::Move {
                     chess_move,
                     game_id,
+                    ..
                 } => {
                     if let Some(ref id) = game_id {
                         info!(
@@ -717,6 +959,101 @@ async fn main() -> Result<()> {
                     result
                 }
 
+                Commands::Resign { game_id } => {
+                    info!(
+                        "Chess command lifecycle: Starting resignation of game: {}",
+                        game_id.as_deref().unwrap_or("most recent")
+                    );
+
+                    let result = app
+                        .handle_resign(game_id, cli.yes)
+                        .await
+                        .context("Failed to resign game");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Resignation failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::OfferDraw { game_id } => {
+                    info!(
+                        "Chess command lifecycle: Starting draw offer in game: {}",
+                        game_id.as_deref().unwrap_or("most recent")
+                    );
+
+                    let result = app
+                        .handle_offer_draw(game_id, cli.yes)
+                        .await
+                        .context("Failed to offer draw");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Draw offer failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Seal {
+                    game_id,
+                    chess_move,
+                    reveal,
+                } => {
+                    info!(
+                        "Chess command lifecycle: Starting sealed move in game: {}",
+                        game_id
+                    );
+
+                    let result = match chess_move {
+                        Some(chess_move) if !reveal => app
+                            .handle_seal(game_id, chess_move)
+                            .await
+                            .context("Failed to seal move"),
+                        _ => app
+                            .handle_seal_reveal(game_id)
+                            .await
+                            .context("Failed to reveal sealed move"),
+                    };
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Sealed move failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Presence { status } => {
+                    info!("Chess command lifecycle: Sending presence: {}", status);
+
+                    let result = app
+                        .handle_presence(status)
+                        .await
+                        .context("Failed to send presence");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Presence failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Report {
+                    peer,
+                    reason,
+                    evidence,
+                    game,
+                    local,
+                } => {
+                    info!("Chess command lifecycle: Reporting peer: {}", peer);
+
+                    let result = app
+                        .handle_report(peer, reason, evidence, game, local)
+                        .await
+                        .context("Failed to report peer");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Report failed: {}", e);
+                    }
+                    result
+                }
+
                 Commands::History { game_id } => {
                     if let Some(ref id) = game_id {
                         info!(
@@ -748,9 +1085,513 @@ async fn main() -> Result<()> {
                     result
                 }
 
+                Commands::Timeline { game_id } => {
+                    if let Some(ref id) = game_id {
+                        info!(
+                            "Chess command lifecycle: Starting timeline display for game: {}",
+                            id
+                        );
+                    } else {
+                        info!("Chess command lifecycle: Starting timeline display for most recent game");
+                    }
+
+                    let result = app
+                        .handle_timeline(game_id)
+                        .await
+                        .context("Failed to show game timeline");
+
+                    match &result {
+                        Ok(()) => {
+                            info!(
+                                "Chess command lifecycle: Timeline display completed successfully"
+                            );
+                        }
+                        Err(e) => {
+                            error!("Chess command lifecycle: Timeline display failed: {}", e);
+                        }
+                    }
+                    result
+                }
+
+                Commands::Openings {
+                    by,
+                    moves,
+                    depth,
+                    eco,
+                    color,
+                    games,
+                } => {
+                    info!("Chess command lifecycle: Starting opening summary");
+
+                    let result = app
+                        .handle_openings(OpeningsOptions {
+                            by: Some(by),
+                            moves,
+                            depth,
+                            eco,
+                            color,
+                            games,
+                        })
+                        .await
+                        .context("Failed to summarize openings");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Opening summary failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Stats { by, csv, output } => {
+                    info!("Chess command lifecycle: Starting game statistics");
+
+                    let result = app
+                        .handle_stats(&by, csv, output)
+                        .await
+                        .context("Failed to summarize game statistics");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Game statistics failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Tag {
+                    game_id,
+                    tag,
+                    remove,
+                } => {
+                    info!(
+                        "Chess command lifecycle: Updating tags for game: {}",
+                        game_id
+                    );
+
+                    let result = app
+                        .handle_tag(game_id, tag, remove)
+                        .await
+                        .context("Failed to update game tags");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Tag update failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Note {
+                    game_id,
+                    text,
+                    clear,
+                } => {
+                    info!(
+                        "Chess command lifecycle: Updating note for game: {}",
+                        game_id
+                    );
+
+                    let result = app
+                        .handle_note(game_id, text, clear)
+                        .await
+                        .context("Failed to update game note");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Note update failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Export {
+                    game_id,
+                    game,
+                    all,
+                    format,
+                    rows,
+                    output,
+                } => {
+                    let game_id = game_id.or(game);
+                    match &game_id {
+                        Some(game_id) => {
+                            info!("Chess command lifecycle: Exporting game: {}", game_id)
+                        }
+                        None => info!("Chess command lifecycle: Exporting all games"),
+                    }
+
+                    let result = app
+                        .handle_export(game_id.filter(|_| !all), &format, &rows, output)
+                        .await
+                        .context("Failed to export game");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Export failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Certificate {
+                    game_id, output, ..
+                } => {
+                    let game_id = game_id.unwrap_or_default();
+                    info!("Chess command lifecycle: Certifying game: {}", game_id);
+
+                    let result = app
+                        .handle_certificate(game_id, output)
+                        .await
+                        .context("Failed to export game certificate");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Certificate export failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Tui => {
+                    info!("Chess command lifecycle: Starting full-screen interface");
+
+                    let result = app
+                        .handle_tui()
+                        .await
+                        .context("Failed to run full-screen interface");
+
+                    if let Err(e) = &result {
+                        error!(
+                            "Chess command lifecycle: Full-screen interface failed: {}",
+                            e
+                        );
+                    }
+                    result
+                }
+
+                Commands::Uci { game_id } => {
+                    info!("Chess command lifecycle: Starting UCI shim for {}", game_id);
+
+                    let result = app
+                        .handle_uci(game_id)
+                        .await
+                        .context("Failed to run UCI shim");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: UCI shim failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Play { game_id, .. } => {
+                    info!(
+                        "Chess command lifecycle: Starting play session for {}",
+                        game_id
+                    );
+
+                    let result = app
+                        .handle_play(game_id)
+                        .await
+                        .context("Failed to run play session");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Play session failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Hotseat { game_id } => {
+                    info!("Chess command lifecycle: Starting hotseat session");
+
+                    let result = app
+                        .handle_hotseat(game_id)
+                        .await
+                        .context("Failed to run hotseat session");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Hotseat session failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Selfplay { engines, games, tc } => {
+                    info!("Chess command lifecycle: Starting self-play match");
+
+                    let result = app
+                        .handle_selfplay(engines, games, &tc)
+                        .await
+                        .context("Failed to run self-play match");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Self-play match failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Replay { game_id } => {
+                    info!("Chess command lifecycle: Replaying game {}", game_id);
+
+                    let result = app
+                        .handle_replay(game_id)
+                        .await
+                        .context("Failed to replay game");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Replay failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Vote {
+                    game_id,
+                    chess_move,
+                    host,
+                    join,
+                    open,
+                } => {
+                    info!("Chess command lifecycle: Voting in game {}", game_id);
+
+                    let result = app
+                        .handle_vote(game_id, chess_move, host, join, open)
+                        .await
+                        .context("Failed to vote");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Vote failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Study {
+                    address,
+                    title,
+                    fen,
+                    line,
+                    comment,
+                } => {
+                    info!("Chess command lifecycle: Sharing study with {}", address);
+
+                    let result = app
+                        .handle_study(address, title, fen, line, comment)
+                        .await
+                        .context("Failed to share study");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Study sharing failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Studies { study_id } => {
+                    info!("Chess command lifecycle: Browsing studies");
+
+                    let result = app
+                        .handle_studies(study_id)
+                        .await
+                        .context("Failed to show studies");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Showing studies failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Purge {
+                    peer,
+                    before,
+                    dry_run,
+                } => {
+                    info!(
+                        "Chess command lifecycle: Purging data (peer: {:?}, before: {:?}, dry run: {})",
+                        peer, before, dry_run
+                    );
+
+                    let result = app
+                        .handle_purge(peer, before, dry_run, cli.yes)
+                        .await
+                        .context("Failed to purge data");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Purge failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::ExportAccount { file } => {
+                    info!(
+                        "Chess command lifecycle: Exporting account to: {}",
+                        file.display()
+                    );
+
+                    let result = app
+                        .handle_export_account(file)
+                        .await
+                        .context("Failed to export account");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Account export failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Peers { command } => {
+                    let result = match command {
+                        PeersCommand::List => {
+                            info!("Chess command lifecycle: Listing peer history");
+                            app.handle_peers_list().await
+                        }
+                        PeersCommand::Info { peer_id } => {
+                            info!(
+                                "Chess command lifecycle: Showing history for peer: {}",
+                                peer_id
+                            );
+                            app.handle_peers_info(peer_id).await
+                        }
+                        PeersCommand::Alias {
+                            alias,
+                            peer_id,
+                            remove,
+                        } => {
+                            info!("Chess command lifecycle: Managing peer aliases");
+                            app.handle_peers_alias(alias, peer_id, remove).await
+                        }
+                        PeersCommand::Verify { peer } => {
+                            info!("Chess command lifecycle: Verifying peer: {}", peer);
+                            app.handle_peers_verify(peer, cli.yes).await
+                        }
+                        PeersCommand::Mute { peer } => {
+                            info!("Chess command lifecycle: Muting peer: {:?}", peer);
+                            app.handle_peers_mute(peer).await
+                        }
+                        PeersCommand::Unmute { peer } => {
+                            info!("Chess command lifecycle: Unmuting peer: {}", peer);
+                            app.handle_peers_unmute(peer).await
+                        }
+                    }
+                    .context("Failed to show peer history");
+
+                    if let Err(e) = &result {
+                        error!(
+                            "Chess command lifecycle: Peer history display failed: {}",
+                            e
+                        );
+                    }
+                    result
+                }
+
+                Commands::Lichess { command } => {
+                    info!("Chess command lifecycle: Running Lichess bridge");
+
+                    let result = run_lichess(&app, command)
+                        .await
+                        .context("Lichess bridge failed");
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Lichess bridge failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Telemetry { command } => {
+                    let result = match command {
+                        TelemetryCommand::Show => {
+                            info!("Chess command lifecycle: Previewing usage report");
+                            app.handle_telemetry_show()
+                                .await
+                                .context("Failed to preview usage report")
+                        }
+                        TelemetryCommand::Send => {
+                            info!("Chess command lifecycle: Sending usage report");
+                            app.handle_telemetry_send()
+                                .await
+                                .context("Failed to send usage report")
+                        }
+                    };
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Usage report failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Devices { command } => {
+                    let result = match command {
+                        DevicesCommand::Pair { address, code } => {
+                            info!("Chess command lifecycle: Pairing device: {:?}", address);
+                            app.handle_devices_pair(address, code)
+                                .await
+                                .context("Failed to pair device")
+                        }
+                        DevicesCommand::List => {
+                            info!("Chess command lifecycle: Listing linked devices");
+                            app.handle_devices_list()
+                                .await
+                                .context("Failed to list linked devices")
+                        }
+                        DevicesCommand::Sync => {
+                            info!("Chess command lifecycle: Syncing linked devices");
+                            app.handle_devices_sync()
+                                .await
+                                .context("Failed to sync linked devices")
+                        }
+                        DevicesCommand::Unpair { device } => {
+                            info!("Chess command lifecycle: Unpairing device: {}", device);
+                            app.handle_devices_unpair(device)
+                                .await
+                                .context("Failed to unpair device")
+                        }
+                    };
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Device command failed: {}", e);
+                    }
+                    result
+                }
+
+                Commands::Arbiter { command } => {
+                    let result = match command {
+                        ArbiterCommand::Appoint { game_id, arbiter } => {
+                            info!(
+                                "Chess command lifecycle: Appointing arbiter {} for game {}",
+                                arbiter, game_id
+                            );
+                            app.handle_arbiter_appoint(game_id, arbiter)
+                                .await
+                                .context("Failed to appoint arbiter")
+                        }
+                        ArbiterCommand::Join { game_id, host } => {
+                            info!(
+                                "Chess command lifecycle: Joining game {} as arbiter through {}",
+                                game_id, host
+                            );
+                            app.handle_arbiter_join(game_id, host)
+                                .await
+                                .context("Failed to join game as arbiter")
+                        }
+                        ArbiterCommand::Claim {
+                            game_id,
+                            ply,
+                            reason,
+                        } => {
+                            info!(
+                                "Chess command lifecycle: Claiming move {} of game {} was illegal",
+                                ply, game_id
+                            );
+                            app.handle_arbiter_claim(game_id, ply, reason)
+                                .await
+                                .context("Failed to claim an illegal move")
+                        }
+                        ArbiterCommand::List => {
+                            info!("Chess command lifecycle: Listing arbitrated games");
+                            app.handle_arbiter_list()
+                                .await
+                                .context("Failed to list arbitrated games")
+                        }
+                    };
+
+                    if let Err(e) = &result {
+                        error!("Chess command lifecycle: Arbiter command failed: {}", e);
+                    }
+                    result
+                }
+
                 _ => unreachable!("Non-chess commands should not reach this branch"),
             };
 
+            // Peers met for the first time get a code to compare
+            if let Err(e) = app.show_new_peer_codes().await {
+                warn!("Failed to show verification codes: {}", e);
+            }
+
             // Ensure graceful cleanup regardless of command result
             debug!("Chess command lifecycle: Starting cleanup phase");
             if let Err(cleanup_error) = graceful_shutdown(Some(&app)).await {
@@ -765,7 +1606,7 @@ async fn main() -> Result<()> {
             // Return the command result - handle errors gracefully
             if let Err(e) = command_result {
                 let cli_error = CliError::from(e);
-                display_error_and_exit(cli_error, 1);
+                display_error_and_exit(cli_error);
             }
             info!("Chess command lifecycle: Operation completed successfully");
         }
@@ -773,5 +1614,8 @@ async fn main() -> Result<()> {
 
     debug!("Application lifecycle: All operations completed successfully");
     info!("Mate application finished successfully");
+    if let Some(exporter) = otlp_exporter {
+        exporter.shutdown().await;
+    }
     Ok(())
 }
@@ -1,9 +1,16 @@
 use crate::chess::Board;
-use crate::chess::Color;
+use crate::chess::{Color, Odds};
+use crate::crypto::{Identity, PeerId, SealedMove};
+use anyhow::Context;
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::Signature;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+/// How long an invitation can be accepted for, in seconds
+pub const INVITE_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
 /// Chess game invitation message
 /// Sent to invite another player to a chess game
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,6 +19,18 @@ pub struct GameInvite {
     pub game_id: String,
     /// Suggested color for the invitee (None means invitee can choose)
     pub suggested_color: Option<Color>,
+    /// Material or extra-move odds the game is played at; absent from
+    /// invitations to standard games and from peers that predate odds
+    #[serde(default, deserialize_with = "appended_field")]
+    pub odds: Option<Odds>,
+    /// Unix timestamp (seconds) after which the invitation can no longer be
+    /// accepted; invitations from peers that predate expiry never expire
+    #[serde(default, deserialize_with = "appended_field")]
+    pub expires_at: Option<i64>,
+    /// Whether the game counts toward ratings, with opponent moves dated
+    /// strictly; invitations from peers that predate rated games are casual
+    #[serde(default, deserialize_with = "appended_field")]
+    pub rated: bool,
 }
 
 impl GameInvite {
@@ -20,9 +39,35 @@ impl GameInvite {
         Self {
             game_id,
             suggested_color,
+            odds: None,
+            expires_at: None,
+            rated: false,
         }
     }
 
+    /// Invite to a game played at `odds`
+    pub fn with_odds(mut self, odds: Odds) -> Self {
+        self.odds = Some(odds);
+        self
+    }
+
+    /// Invite to a rated game
+    pub fn with_rated(mut self) -> Self {
+        self.rated = true;
+        self
+    }
+
+    /// Invitation that lapses at `expires_at` (Unix seconds)
+    pub fn with_expiry(mut self, expires_at: i64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Whether the invitation has lapsed by `now` (Unix seconds)
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
     /// Create a game invitation without color suggestion
     pub fn new_no_color_preference(game_id: String) -> Self {
         Self::new(game_id, None)
@@ -81,6 +126,62 @@ impl GameDecline {
     }
 }
 
+/// Invitation withdrawal message
+/// Sent by the inviter to take back an invitation that has not been answered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InviteWithdraw {
+    /// Unique identifier for the game whose invitation is withdrawn
+    pub game_id: String,
+}
+
+impl InviteWithdraw {
+    /// Create a new invitation withdrawal
+    pub fn new(game_id: String) -> Self {
+        Self { game_id }
+    }
+}
+
+/// Sealed move message
+/// Sent when adjourning a game, committing to the next move without revealing it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveSeal {
+    /// Unique identifier for the adjourned game
+    pub game_id: String,
+    /// Number of the sealed move, counting both sides' moves from 1
+    pub move_number: u32,
+    /// Hex-encoded SHA-256 commitment to the move (see [`SealedMove`])
+    pub commitment: String,
+}
+
+impl MoveSeal {
+    /// Create a new sealed move message
+    pub fn new(game_id: String, move_number: u32, commitment: String) -> Self {
+        Self {
+            game_id,
+            move_number,
+            commitment,
+        }
+    }
+}
+
+/// Read a field appended to a message struct after it was first sent
+///
+/// Binary messages from versions that predate the field end before it, so
+/// failing to read it there means it is absent; those versions in turn ignore
+/// the trailing bytes of fields they do not know. JSON carries field names,
+/// so a missing field is left to `#[serde(default)]` and errors still surface.
+fn appended_field<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    if deserializer.is_human_readable() {
+        T::deserialize(deserializer)
+    } else {
+        Ok(T::deserialize(deserializer).unwrap_or_default())
+    }
+}
+
 /// Chess move message
 /// Sent to communicate a chess move to the opponent
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -91,6 +192,32 @@ pub struct Move {
     pub chess_move: String,
     /// SHA-256 hash of the board state after the move for verification
     pub board_state_hash: String,
+    /// The mover's signature binding the move to its place in the game;
+    /// absent from moves stored before move signing and from peers that
+    /// predate it
+    #[serde(default, deserialize_with = "appended_field")]
+    pub signature: Option<MoveSignature>,
+}
+
+/// Domain separator so a move signature cannot be mistaken for any other
+/// signed data
+const MOVE_SIGNATURE_CONTEXT: &[u8] = b"mate-move-v1";
+
+/// Signature over a move and the game context it was made in
+///
+/// Signs the game ID, move number, move, and the board hashes before and
+/// after the move, so a stored game record can be checked move by move
+/// without trusting the connection it arrived over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveSignature {
+    /// Peer ID of the player who made the move
+    pub signer: String,
+    /// Number of the move in the game, counting from 1
+    pub move_number: u32,
+    /// SHA-256 hash of the board state before the move
+    pub prior_hash: String,
+    /// Base64 Ed25519 signature
+    pub signature: String,
 }
 
 impl Move {
@@ -100,8 +227,74 @@ impl Move {
             game_id,
             chess_move,
             board_state_hash,
+            signature: None,
         }
     }
+
+    /// Sign the move as move `move_number`, made from the board with `prior_hash`
+    pub fn signed(
+        mut self,
+        identity: &Identity,
+        move_number: u32,
+        prior_hash: &str,
+    ) -> anyhow::Result<Self> {
+        let signed = self.signed_bytes(move_number, prior_hash);
+        self.signature = Some(MoveSignature {
+            signer: identity.peer_id().to_string(),
+            move_number,
+            prior_hash: prior_hash.to_string(),
+            signature: general_purpose::STANDARD.encode(identity.sign(&signed)?.to_bytes()),
+        });
+        Ok(self)
+    }
+
+    /// Check the move's signature against the game context it is applied in
+    ///
+    /// Fails if the move is unsigned, was signed for another move number or
+    /// position, or the signature does not verify. Returns the signer's peer ID.
+    pub fn verify_signature(&self, move_number: u32, prior_hash: &str) -> anyhow::Result<&str> {
+        let signature = self.signature.as_ref().context("Move is not signed")?;
+        if signature.move_number != move_number {
+            anyhow::bail!(
+                "Move was signed as move {}, but is move {move_number}",
+                signature.move_number
+            );
+        }
+        if signature.prior_hash != prior_hash {
+            anyhow::bail!("Move was signed for a different position");
+        }
+
+        let key = PeerId::from_string(signature.signer.clone())
+            .to_verifying_key()
+            .context("Invalid signer in move signature")?;
+        let bytes = general_purpose::STANDARD
+            .decode(&signature.signature)
+            .context("Invalid move signature encoding")?;
+        let ed_signature = Signature::from_slice(&bytes).context("Invalid move signature")?;
+        if !Identity::verify(
+            &key,
+            &self.signed_bytes(move_number, prior_hash),
+            &ed_signature,
+        ) {
+            anyhow::bail!("Move signature does not verify");
+        }
+        Ok(&signature.signer)
+    }
+
+    fn signed_bytes(&self, move_number: u32, prior_hash: &str) -> Vec<u8> {
+        let mut bytes = MOVE_SIGNATURE_CONTEXT.to_vec();
+        for part in [
+            self.game_id.as_str(),
+            &move_number.to_string(),
+            &self.chess_move,
+            prior_hash,
+            &self.board_state_hash,
+        ] {
+            bytes.push(b'\n');
+            bytes.extend_from_slice(part.as_bytes());
+        }
+        bytes
+    }
 }
 
 /// Chess move acknowledgment message
@@ -177,6 +370,403 @@ impl SyncResponse {
     }
 }
 
+/// Resignation message
+/// Sent when a player concedes the game, which the opponent wins
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Resign {
+    /// Unique identifier for the game
+    pub game_id: String,
+}
+
+impl Resign {
+    /// Create a new resignation
+    pub fn new(game_id: String) -> Self {
+        Self { game_id }
+    }
+}
+
+/// Draw offer message
+/// Sent to propose a draw; the offer lapses once either player moves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawOffer {
+    /// Unique identifier for the game
+    pub game_id: String,
+}
+
+impl DrawOffer {
+    /// Create a new draw offer
+    pub fn new(game_id: String) -> Self {
+        Self { game_id }
+    }
+}
+
+/// Draw acceptance message
+/// Sent to accept the opponent's open draw offer, ending the game in a draw
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawAccept {
+    /// Unique identifier for the game
+    pub game_id: String,
+}
+
+impl DrawAccept {
+    /// Create a new draw acceptance
+    pub fn new(game_id: String) -> Self {
+        Self { game_id }
+    }
+}
+
+/// Study message
+/// Sent to share an annotated position and line with a peer, outside any game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Study {
+    /// Unique identifier for the study
+    pub study_id: String,
+    /// Short title shown when browsing studies
+    pub title: String,
+    /// Position the line starts from, in FEN notation
+    pub fen: String,
+    /// Comment on the starting position
+    pub comment: Option<String>,
+    /// Moves of the line, in order, each with an optional comment
+    pub moves: Vec<StudyMove>,
+}
+
+/// One move of a study line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StudyMove {
+    /// The move in coordinate notation (e.g. "e2e4")
+    pub chess_move: String,
+    /// Comment on the position after the move
+    pub comment: Option<String>,
+}
+
+impl Study {
+    /// Create a new study with a fresh ID
+    pub fn new(title: String, fen: String, comment: Option<String>, moves: Vec<StudyMove>) -> Self {
+        Self {
+            study_id: generate_game_id(),
+            title,
+            fen,
+            comment,
+            moves,
+        }
+    }
+
+    /// Replay the line from the starting position
+    ///
+    /// Returns the board after each move, in order.
+    pub fn replay(&self) -> Result<Vec<Board>, ValidationError> {
+        let mut board = Board::from_fen(&self.fen)
+            .map_err(|_| ValidationError::InvalidFen(format!("Invalid FEN: '{}'", self.fen)))?;
+        let mut boards = Vec::with_capacity(self.moves.len());
+        for (index, study_move) in self.moves.iter().enumerate() {
+            board
+                .parse_move(&study_move.chess_move)
+                .and_then(|mv| board.make_move(mv))
+                .map_err(|e| {
+                    ValidationError::InvalidMove(format!(
+                        "Move {} ({}) cannot be played: {e}",
+                        index + 1,
+                        study_move.chess_move
+                    ))
+                })?;
+            boards.push(board.clone());
+        }
+        Ok(boards)
+    }
+}
+
+/// Voter registration message
+/// Sent to the peer hosting a consultation game to join the group voting on
+/// its moves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoterJoin {
+    /// Unique identifier for the game
+    pub game_id: String,
+}
+
+impl VoterJoin {
+    /// Create a new voter registration
+    pub fn new(game_id: String) -> Self {
+        Self { game_id }
+    }
+}
+
+/// Vote message
+/// Sent by a registered voter to propose the group's next move; a later vote
+/// replaces the voter's earlier one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vote {
+    /// Unique identifier for the game
+    pub game_id: String,
+    /// The proposed move in SAN or coordinate notation
+    pub chess_move: String,
+}
+
+impl Vote {
+    /// Create a new vote
+    pub fn new(game_id: String, chess_move: String) -> Self {
+        Self {
+            game_id,
+            chess_move,
+        }
+    }
+}
+
+/// Server announcement message
+/// Sent by the operator of a server to every peer connected to it, e.g. to
+/// warn of a maintenance window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerAnnouncement {
+    /// The announcement as shown to users
+    pub text: String,
+}
+
+impl ServerAnnouncement {
+    /// Create a new announcement
+    pub fn new(text: String) -> Self {
+        Self { text }
+    }
+}
+
+/// Whether a player is likely to answer moves and offers soon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PresenceStatus {
+    Available,
+    Busy,
+    Away,
+}
+
+impl PresenceStatus {
+    /// Lowercase name, as stored and accepted on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PresenceStatus::Available => "available",
+            PresenceStatus::Busy => "busy",
+            PresenceStatus::Away => "away",
+        }
+    }
+}
+
+impl std::fmt::Display for PresenceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PresenceStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "available" => Ok(PresenceStatus::Available),
+            "busy" => Ok(PresenceStatus::Busy),
+            "away" => Ok(PresenceStatus::Away),
+            other => Err(format!(
+                "Unknown presence '{other}'; use available, busy, or away"
+            )),
+        }
+    }
+}
+
+/// Presence message
+/// Sent to opponents in active games to tell them whether to expect a reply
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Presence {
+    pub status: PresenceStatus,
+}
+
+impl Presence {
+    /// Create a new presence message
+    pub fn new(status: PresenceStatus) -> Self {
+        Self { status }
+    }
+}
+
+/// A stored message attached to an abuse report as evidence
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportEvidence {
+    /// ID of the message in the reporter's database
+    pub message_id: i64,
+    pub game_id: String,
+    pub message_type: String,
+    pub content: String,
+    /// Signature the message arrived with
+    pub signature: String,
+    /// When the reporter received the message, in Unix seconds
+    pub received_at: i64,
+}
+
+/// Abuse report message
+/// Sent to the operator of a lobby or relay to report a misbehaving peer,
+/// signed like every other message by the reporter's key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbuseReport {
+    /// Peer being reported
+    pub reported_peer_id: String,
+    /// What the peer did, in the reporter's words
+    pub reason: String,
+    pub evidence: Vec<ReportEvidence>,
+}
+
+impl AbuseReport {
+    /// Create a new abuse report
+    pub fn new(reported_peer_id: String, reason: String, evidence: Vec<ReportEvidence>) -> Self {
+        Self {
+            reported_peer_id,
+            reason,
+            evidence,
+        }
+    }
+}
+
+/// Device pairing message
+/// Sent to another installation of the same player with the one-time code
+/// it showed, to link the two devices; echoed back to confirm the link
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevicePair {
+    /// Pairing code shown by `mate devices pair` on the other device
+    pub code: String,
+    /// Address the sender's server can be reached at
+    pub address: String,
+}
+
+impl DevicePair {
+    /// Create a new device pairing message
+    pub fn new(code: String, address: String) -> Self {
+        Self { code, address }
+    }
+}
+
+/// What a synced change is to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyncKind {
+    /// An address book entry, keyed by alias with the peer ID as value
+    Alias,
+    /// A setting, keyed by its dotted name
+    Setting,
+    /// A game's index entry, keyed by game ID with a JSON summary as value
+    Game,
+}
+
+impl SyncKind {
+    /// Lowercase name, as stored
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            SyncKind::Alias => "alias",
+            SyncKind::Setting => "setting",
+            SyncKind::Game => "game",
+        }
+    }
+}
+
+impl std::str::FromStr for SyncKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alias" => Ok(SyncKind::Alias),
+            "setting" => Ok(SyncKind::Setting),
+            "game" => Ok(SyncKind::Game),
+            other => Err(format!("Unknown sync kind '{other}'")),
+        }
+    }
+}
+
+/// One change to data kept in step between linked devices
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncChange {
+    pub kind: SyncKind,
+    pub key: String,
+    /// New value, or `None` when the entry was removed
+    pub value: Option<String>,
+    /// When the change was made, in Unix seconds; the latest change wins
+    pub updated_at: i64,
+}
+
+/// Device sync message
+/// Sent to a linked device with the sender's changes it has not seen, asking
+/// for the changes made there since `since`; the reply carries those
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceSync {
+    /// Last change of the receiver's the sender already has
+    pub since: u64,
+    /// Last change of the sender's included in or before `changes`
+    pub through: u64,
+    pub changes: Vec<SyncChange>,
+}
+
+impl DeviceSync {
+    /// Create a new device sync message
+    pub fn new(since: u64, through: u64, changes: Vec<SyncChange>) -> Self {
+        Self {
+            since,
+            through,
+            changes,
+        }
+    }
+}
+
+/// Arbiter registration message
+/// Sent by the arbiter a player appointed to that player's server, to be sent
+/// the game's moves; answered with an `ArbiterUpdate` of the game so far
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArbiterJoin {
+    /// Unique identifier for the game
+    pub game_id: String,
+    /// Address the arbiter's server can be reached at
+    pub address: String,
+}
+
+impl ArbiterJoin {
+    /// Create a new arbiter registration
+    pub fn new(game_id: String, address: String) -> Self {
+        Self { game_id, address }
+    }
+}
+
+/// Arbiter update message
+/// Sent by a player to the game's arbiter with every move played so far and
+/// the result, if the game has ended; the arbiter answers a finished game
+/// with its `ResultSignature`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArbiterUpdate {
+    /// Unique identifier for the game
+    pub game_id: String,
+    /// Peer ID of the player with the white pieces
+    pub white: String,
+    /// Peer ID of the player with the black pieces
+    pub black: String,
+    /// Moves in the order they were played
+    pub moves: Vec<String>,
+    /// PGN result token, `*` while the game is under way
+    pub result: String,
+}
+
+/// Illegal move claim message
+/// Sent by a player to the game's arbiter, claiming the move at `ply` was
+/// illegal; answered with the arbiter's signed `ArbiterRuling`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IllegalMoveClaim {
+    /// Unique identifier for the game
+    pub game_id: String,
+    /// Half-move claimed illegal, counting from 1
+    pub ply: u32,
+    /// Optional explanation from the claimant
+    pub reason: Option<String>,
+}
+
+impl IllegalMoveClaim {
+    /// Create a new illegal move claim
+    pub fn new(game_id: String, ply: u32, reason: Option<String>) -> Self {
+        Self {
+            game_id,
+            ply,
+            reason,
+        }
+    }
+}
+
 /// Generate a cryptographically secure game ID using UUID v4
 ///
 /// Creates a cryptographically secure, collision-resistant game identifier
@@ -419,7 +1009,11 @@ pub fn validate_game_invite(invite: &GameInvite) -> Result<(), ValidationError>
     }
 
     // Validate suggested color is a reasonable value (Color enum is already validated by type system)
-    // Additional business logic validation could go here if needed
+
+    if let Some(odds) = &invite.odds {
+        odds.validate()
+            .map_err(|e| ValidationError::InvalidMessageFormat(format!("Invalid odds: {e}")))?;
+    }
 
     Ok(())
 }
@@ -471,6 +1065,15 @@ pub fn validate_move_message(msg: &Move) -> Result<(), ValidationError> {
     // Validate board state hash format
     validate_board_hash_format(&msg.board_state_hash)?;
 
+    if let Some(signature) = &msg.signature {
+        if signature.move_number == 0 {
+            return Err(ValidationError::InvalidMessageFormat(
+                "Move numbers start at 1".to_string(),
+            ));
+        }
+        validate_board_hash_format(&signature.prior_hash)?;
+    }
+
     Ok(())
 }
 
@@ -536,13 +1139,13 @@ pub fn validate_chess_move_format(chess_move: &str) -> Result<(), ValidationErro
 /// Validates moves in the format: [file][rank][file][rank][promotion?]
 /// Examples: "e2e4", "a7a8q", "h1g1"
 fn validate_standard_algebraic_notation(chess_move: &str) -> bool {
-    // Standard move: 4 characters (e2e4) or 5 characters with promotion (e7e8q)
-    if chess_move.len() != 4 && chess_move.len() != 5 {
+    // Standard move: 4 characters (e2e4) or 5 characters with promotion (e7e8q),
+    // counted as characters since non-ASCII input is sliced by character below
+    let chars: Vec<char> = chess_move.chars().collect();
+    if chars.len() != 4 && chars.len() != 5 {
         return false;
     }
 
-    let chars: Vec<char> = chess_move.chars().collect();
-
     // Validate source square (first two characters)
     if !validate_square_notation(&chars[0..2]) {
         return false;
@@ -662,6 +1265,100 @@ pub fn validate_game_accept(accept: &GameAccept) -> Result<(), ValidationError>
     Ok(())
 }
 
+/// Validate an invitation withdrawal message
+///
+/// Validates that an InviteWithdraw message has a properly formatted game ID.
+///
+/// # Arguments
+///
+/// * `withdraw` - The withdrawal message to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the withdrawal message is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_invite_withdraw(withdraw: &InviteWithdraw) -> Result<(), ValidationError> {
+    if !validate_game_id(&withdraw.game_id) {
+        let game_id = &withdraw.game_id;
+        return Err(ValidationError::InvalidGameId(format!(
+            "Game ID '{game_id}' is not a valid UUID format"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a sealed move message
+///
+/// Checks the game ID, the move number, and that the commitment is a
+/// hex-encoded SHA-256 digest.
+///
+/// # Arguments
+///
+/// * `seal` - The sealed move message to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the sealed move message is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_move_seal(seal: &MoveSeal) -> Result<(), ValidationError> {
+    validate_game_end_message(&seal.game_id)?;
+    if seal.move_number == 0 {
+        return Err(ValidationError::InvalidMessageFormat(
+            "Sealed moves are numbered from 1".to_string(),
+        ));
+    }
+    if !is_hex_digest(&seal.commitment) {
+        return Err(ValidationError::InvalidMessageFormat(
+            "Commitment must be 64 hex characters".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate a sealed move reveal message
+///
+/// Checks the game ID, that the move looks like SAN or coordinate notation,
+/// and that the nonce is 32 hex-encoded bytes. Whether the move matches the
+/// commitment is checked by the receiver, which holds it.
+///
+/// # Arguments
+///
+/// * `sealed` - The revealed sealed move to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the reveal is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_seal_reveal(sealed: &SealedMove) -> Result<(), ValidationError> {
+    validate_game_end_message(&sealed.game_id)?;
+
+    let chess_move = sealed.chess_move.trim();
+    if chess_move.is_empty()
+        || chess_move.len() > security::MAX_MOVE_NOTATION_LENGTH
+        || !chess_move
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+#=-x".contains(c))
+    {
+        return Err(ValidationError::InvalidMove(format!(
+            "'{chess_move}' is not a move in SAN or coordinate notation"
+        )));
+    }
+    if !is_hex_digest(&sealed.nonce) {
+        return Err(ValidationError::InvalidMessageFormat(
+            "Nonce must be 64 hex characters".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Whether `text` is 32 hex-encoded bytes
+fn is_hex_digest(text: &str) -> bool {
+    text.len() == 64 && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 /// Validate a game decline message
 ///
 /// Validates that a GameDecline message has a properly formatted game ID
@@ -874,6 +1571,384 @@ pub fn validate_move_ack(ack: &MoveAck) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Validate a resign, draw offer, or draw accept message
+///
+/// These messages carry nothing but the game they end, so only the game ID
+/// is checked.
+///
+/// # Arguments
+///
+/// * `game_id` - The game ID carried by the message
+///
+/// # Returns
+///
+/// * `Ok(())` - If the game ID is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_game_end_message(game_id: &str) -> Result<(), ValidationError> {
+    if game_id.trim().is_empty() {
+        return Err(ValidationError::InvalidGameId(
+            "Game ID cannot be empty".to_string(),
+        ));
+    }
+
+    if !validate_game_id(game_id) {
+        return Err(ValidationError::InvalidGameId(format!(
+            "Invalid game ID format: '{game_id}'"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a vote message
+///
+/// Checks the game ID and that the move looks like SAN or coordinate
+/// notation. Whether it is legal is only known to the host, when the votes
+/// are counted.
+///
+/// # Arguments
+///
+/// * `vote` - The vote message to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the vote is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_vote(vote: &Vote) -> Result<(), ValidationError> {
+    validate_game_end_message(&vote.game_id)?;
+
+    let chess_move = vote.chess_move.trim();
+    if chess_move.is_empty() || chess_move.len() > security::MAX_MOVE_NOTATION_LENGTH {
+        return Err(ValidationError::InvalidMove(format!(
+            "Vote must name a move of at most {} characters",
+            security::MAX_MOVE_NOTATION_LENGTH
+        )));
+    }
+    if !chess_move
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "+#=-x".contains(c))
+    {
+        return Err(ValidationError::InvalidMove(format!(
+            "'{chess_move}' is not a move in SAN or coordinate notation"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a server announcement message
+///
+/// Checks that the announcement has text and is not too long to show.
+///
+/// # Arguments
+///
+/// * `announcement` - The announcement message to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the announcement is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_server_announcement(
+    announcement: &ServerAnnouncement,
+) -> Result<(), ValidationError> {
+    use security::MAX_ANNOUNCEMENT_LENGTH;
+
+    if announcement.text.trim().is_empty() {
+        return Err(ValidationError::InvalidMessageFormat(
+            "Announcement cannot be empty".to_string(),
+        ));
+    }
+    if announcement.text.len() > MAX_ANNOUNCEMENT_LENGTH {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Announcement is too long (max {MAX_ANNOUNCEMENT_LENGTH} characters)"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate an abuse report message
+///
+/// Checks that a peer is named, the reason is present and not too long, and
+/// that the evidence is limited in count and size.
+///
+/// # Arguments
+///
+/// * `report` - The abuse report to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the report is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_abuse_report(report: &AbuseReport) -> Result<(), ValidationError> {
+    use security::{MAX_EVIDENCE_CONTENT_LENGTH, MAX_REASON_LENGTH, MAX_REPORT_EVIDENCE};
+
+    if report.reported_peer_id.trim().is_empty() {
+        return Err(ValidationError::InvalidMessageFormat(
+            "Abuse report must name a peer".to_string(),
+        ));
+    }
+    if report.reason.trim().is_empty() {
+        return Err(ValidationError::InvalidMessageFormat(
+            "Abuse report must give a reason".to_string(),
+        ));
+    }
+    if report.reason.len() > MAX_REASON_LENGTH {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Report reason is too long (max {MAX_REASON_LENGTH} characters)"
+        )));
+    }
+    if report.evidence.len() > MAX_REPORT_EVIDENCE {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Too much evidence (max {MAX_REPORT_EVIDENCE} messages)"
+        )));
+    }
+    if report
+        .evidence
+        .iter()
+        .any(|evidence| evidence.content.len() > MAX_EVIDENCE_CONTENT_LENGTH)
+    {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Evidence message is too long (max {MAX_EVIDENCE_CONTENT_LENGTH} characters)"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a device pairing message
+///
+/// Checks that the code and address are present and not too long.
+///
+/// # Arguments
+///
+/// * `pair` - The pairing message to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the message is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_device_pair(pair: &DevicePair) -> Result<(), ValidationError> {
+    use security::{MAX_ADDRESS_LENGTH, MAX_PAIRING_CODE_LENGTH};
+
+    if pair.code.trim().is_empty() || pair.code.len() > MAX_PAIRING_CODE_LENGTH {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Pairing code must be 1 to {MAX_PAIRING_CODE_LENGTH} characters"
+        )));
+    }
+    if pair.address.trim().is_empty() || pair.address.len() > MAX_ADDRESS_LENGTH {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Device address must be 1 to {MAX_ADDRESS_LENGTH} characters"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a device sync message
+///
+/// Checks that the changes are limited in count and size and that `through`
+/// does not go backwards from `since`.
+///
+/// # Arguments
+///
+/// * `sync` - The sync message to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the message is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_device_sync(sync: &DeviceSync) -> Result<(), ValidationError> {
+    use security::{MAX_SYNC_CHANGES, MAX_SYNC_KEY_LENGTH, MAX_SYNC_VALUE_LENGTH};
+
+    if sync.changes.len() > MAX_SYNC_CHANGES {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Too many changes (max {MAX_SYNC_CHANGES})"
+        )));
+    }
+    for change in &sync.changes {
+        if change.key.is_empty() || change.key.len() > MAX_SYNC_KEY_LENGTH {
+            return Err(ValidationError::InvalidMessageFormat(format!(
+                "Sync key must be 1 to {MAX_SYNC_KEY_LENGTH} characters"
+            )));
+        }
+        if change
+            .value
+            .as_ref()
+            .is_some_and(|value| value.len() > MAX_SYNC_VALUE_LENGTH)
+        {
+            return Err(ValidationError::InvalidMessageFormat(format!(
+                "Sync value is too long (max {MAX_SYNC_VALUE_LENGTH} characters)"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate an arbiter registration message
+///
+/// # Arguments
+///
+/// * `join` - The registration to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the message is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_arbiter_join(join: &ArbiterJoin) -> Result<(), ValidationError> {
+    use security::MAX_ADDRESS_LENGTH;
+
+    validate_game_end_message(&join.game_id)?;
+    if join.address.trim().is_empty() || join.address.len() > MAX_ADDRESS_LENGTH {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Arbiter address must be 1 to {MAX_ADDRESS_LENGTH} characters"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate an arbiter update message
+///
+/// Checks the game ID and players, and that the result is a PGN result token.
+///
+/// # Arguments
+///
+/// * `update` - The update to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the message is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_arbiter_update(update: &ArbiterUpdate) -> Result<(), ValidationError> {
+    validate_game_end_message(&update.game_id)?;
+    if update.white.is_empty() || update.black.is_empty() || update.white == update.black {
+        return Err(ValidationError::InvalidMessageFormat(
+            "An arbiter update needs two different players".to_string(),
+        ));
+    }
+    if !["1-0", "0-1", "1/2-1/2", "*"].contains(&update.result.as_str()) {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Invalid result '{}'",
+            update.result
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate an illegal move claim
+///
+/// # Arguments
+///
+/// * `claim` - The claim to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the message is valid
+/// * `Err(ValidationError)` - If validation fails
+pub fn validate_illegal_move_claim(claim: &IllegalMoveClaim) -> Result<(), ValidationError> {
+    use security::{MAX_MOVE_HISTORY_SIZE, MAX_REASON_LENGTH};
+
+    validate_game_end_message(&claim.game_id)?;
+    if claim.ply == 0 || claim.ply as usize > MAX_MOVE_HISTORY_SIZE {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Claimed move must be 1 to {MAX_MOVE_HISTORY_SIZE}"
+        )));
+    }
+    if claim
+        .reason
+        .as_ref()
+        .is_some_and(|reason| reason.len() > MAX_REASON_LENGTH)
+    {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Claim reason is too long (max {MAX_REASON_LENGTH} characters)"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a study message
+///
+/// Checks the study ID and the lengths of the title and comments, and that
+/// the line can be played from the starting position.
+///
+/// # Arguments
+///
+/// * `study` - The study message to validate
+///
+/// # Returns
+///
+/// * `Ok(())` - If the study is valid
+/// * `Err(ValidationError)` - If validation fails
+///
+/// # Examples
+///
+/// ```
+/// use mate::messages::chess::{validate_study, Study, StudyMove};
+///
+/// let line = vec![StudyMove { chess_move: "e2e4".to_string(), comment: None }];
+/// let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string();
+/// let study = Study::new("King's pawn".to_string(), start.clone(), None, line);
+/// assert!(validate_study(&study).is_ok());
+///
+/// let illegal = vec![StudyMove { chess_move: "e2e5".to_string(), comment: None }];
+/// let study = Study::new("Too far".to_string(), start, None, illegal);
+/// assert!(validate_study(&study).is_err());
+/// ```
+pub fn validate_study(study: &Study) -> Result<(), ValidationError> {
+    use security::{MAX_COMMENT_LENGTH, MAX_MOVE_HISTORY_SIZE, MAX_STUDY_TITLE_LENGTH};
+
+    if !validate_game_id(&study.study_id) {
+        let study_id = &study.study_id;
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Study ID '{study_id}' is not a valid UUID format"
+        )));
+    }
+
+    if study.title.trim().is_empty() {
+        return Err(ValidationError::InvalidMessageFormat(
+            "Study title cannot be empty".to_string(),
+        ));
+    }
+    if study.title.len() > MAX_STUDY_TITLE_LENGTH {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Study title is too long (max {MAX_STUDY_TITLE_LENGTH} characters)"
+        )));
+    }
+
+    if study.moves.len() > MAX_MOVE_HISTORY_SIZE {
+        return Err(ValidationError::InvalidMessageFormat(format!(
+            "Study line is too long (max {MAX_MOVE_HISTORY_SIZE} moves)"
+        )));
+    }
+
+    let comments = study
+        .comment
+        .iter()
+        .chain(study.moves.iter().filter_map(|m| m.comment.as_ref()));
+    for comment in comments {
+        if comment.len() > MAX_COMMENT_LENGTH {
+            return Err(ValidationError::InvalidMessageFormat(format!(
+                "Study comment is too long (max {MAX_COMMENT_LENGTH} characters)"
+            )));
+        }
+    }
+
+    for (index, study_move) in study.moves.iter().enumerate() {
+        if let Err(e) = validate_chess_move_format(&study_move.chess_move) {
+            return Err(ValidationError::InvalidMove(format!(
+                "Invalid move at position {index}: {e}"
+            )));
+        }
+    }
+    study.replay()?;
+
+    Ok(())
+}
+
 // =============================================================================
 // Integration Functions for Chess Module
 // =============================================================================
@@ -1167,6 +2242,7 @@ impl From<crate::chess::ChessError> for ChessProtocolError {
     }
 }
 
+#[cfg(feature = "native")]
 impl From<crate::messages::wire::WireProtocolError> for ChessProtocolError {
     fn from(err: crate::messages::wire::WireProtocolError) -> Self {
         ChessProtocolError::Wire(err.to_string())
@@ -1666,6 +2742,16 @@ pub mod security {
     pub const MAX_MOVE_NOTATION_LENGTH: usize = 20;
     pub const MAX_FEN_LENGTH: usize = 200;
     pub const MAX_MOVE_HISTORY_SIZE: usize = 1000;
+    pub const MAX_STUDY_TITLE_LENGTH: usize = 100;
+    pub const MAX_COMMENT_LENGTH: usize = 1000;
+    pub const MAX_ANNOUNCEMENT_LENGTH: usize = 500;
+    pub const MAX_REPORT_EVIDENCE: usize = 20;
+    pub const MAX_EVIDENCE_CONTENT_LENGTH: usize = 4096;
+    pub const MAX_PAIRING_CODE_LENGTH: usize = 32;
+    pub const MAX_ADDRESS_LENGTH: usize = 255;
+    pub const MAX_SYNC_CHANGES: usize = 1000;
+    pub const MAX_SYNC_KEY_LENGTH: usize = 128;
+    pub const MAX_SYNC_VALUE_LENGTH: usize = 4096;
 
     /// Rate limiting configuration and tracking for chess messages
     ///
@@ -2424,6 +3510,20 @@ pub mod security {
             crate::messages::types::Message::GameAccept(accept) => {
                 validate_secure_game_id(&accept.game_id)?;
             }
+            crate::messages::types::Message::InviteWithdraw(withdraw) => {
+                validate_secure_game_id(&withdraw.game_id)?;
+            }
+            crate::messages::types::Message::MoveSeal(seal) => {
+                validate_secure_game_id(&seal.game_id)?;
+            }
+            crate::messages::types::Message::SealReveal(sealed) => {
+                validate_secure_game_id(&sealed.game_id)?;
+                validate_safe_text_input(
+                    &sealed.chess_move,
+                    "chess_move",
+                    MAX_MOVE_NOTATION_LENGTH,
+                )?;
+            }
             crate::messages::types::Message::GameDecline(decline) => {
                 validate_secure_game_id(&decline.game_id)?;
                 if let Some(reason) = &decline.reason {
@@ -2444,12 +3544,70 @@ pub mod security {
             crate::messages::types::Message::SyncRequest(request) => {
                 validate_secure_game_id(&request.game_id)?;
             }
+            crate::messages::types::Message::Resign(resign) => {
+                validate_secure_game_id(&resign.game_id)?;
+            }
+            crate::messages::types::Message::DrawOffer(offer) => {
+                validate_secure_game_id(&offer.game_id)?;
+            }
+            crate::messages::types::Message::DrawAccept(accept) => {
+                validate_secure_game_id(&accept.game_id)?;
+            }
+            crate::messages::types::Message::ResultSignature(signature) => {
+                validate_secure_game_id(&signature.game_id)?;
+            }
             crate::messages::types::Message::SyncResponse(response) => {
                 validate_secure_game_id(&response.game_id)?;
                 validate_secure_fen_notation(&response.board_state)?;
                 validate_secure_move_history(&response.move_history)?;
                 validate_safe_text_input(&response.board_state_hash, "board_state_hash", 64)?;
             }
+            crate::messages::types::Message::VoterJoin(join) => {
+                validate_secure_game_id(&join.game_id)?;
+            }
+            crate::messages::types::Message::ArbiterJoin(join) => {
+                validate_secure_game_id(&join.game_id)?;
+            }
+            crate::messages::types::Message::ArbiterUpdate(update) => {
+                validate_secure_game_id(&update.game_id)?;
+                validate_secure_move_history(&update.moves)?;
+            }
+            crate::messages::types::Message::IllegalMoveClaim(claim) => {
+                validate_secure_game_id(&claim.game_id)?;
+                if let Some(reason) = &claim.reason {
+                    validate_secure_reason_text(reason)?;
+                }
+            }
+            crate::messages::types::Message::ArbiterRuling(ruling) => {
+                validate_secure_game_id(&ruling.game_id)?;
+                validate_safe_text_input(&ruling.reason, "reason", MAX_REASON_LENGTH)?;
+            }
+            crate::messages::types::Message::Vote(vote) => {
+                validate_secure_game_id(&vote.game_id)?;
+                validate_safe_text_input(&vote.chess_move, "chess_move", MAX_MOVE_NOTATION_LENGTH)?;
+            }
+            crate::messages::types::Message::ServerAnnouncement(announcement) => {
+                validate_safe_text_input(&announcement.text, "text", MAX_ANNOUNCEMENT_LENGTH)?;
+            }
+            crate::messages::types::Message::Study(study) => {
+                validate_secure_game_id(&study.study_id)?;
+                validate_safe_text_input(&study.title, "title", MAX_STUDY_TITLE_LENGTH)?;
+                validate_secure_fen_notation(&study.fen)?;
+                if let Some(comment) = &study.comment {
+                    validate_safe_text_input(comment, "comment", MAX_COMMENT_LENGTH)?;
+                }
+                for (i, study_move) in study.moves.iter().enumerate() {
+                    if let Some(comment) = &study_move.comment {
+                        validate_safe_text_input(
+                            comment,
+                            &format!("moves[{i}].comment"),
+                            MAX_COMMENT_LENGTH,
+                        )?;
+                    }
+                }
+                let line: Vec<String> = study.moves.iter().map(|m| m.chess_move.clone()).collect();
+                validate_secure_move_history(&line)?;
+            }
             // Non-chess messages are not subject to chess-specific security validation
             _ => {}
         }
@@ -1,5 +1,6 @@
 pub mod chess;
 pub mod types;
+#[cfg(feature = "native")]
 pub mod wire;
 
 pub use chess::{
@@ -16,32 +17,60 @@ pub use chess::{
     propagate_error,
     // Security module re-exports
     security,
+    validate_abuse_report,
+    validate_arbiter_join,
+    validate_arbiter_update,
     validate_chess_move_format,
     validate_chess_move_graceful,
+    validate_device_pair,
+    validate_device_sync,
     validate_game_accept,
     validate_game_decline,
+    validate_game_end_message,
     validate_game_id,
     validate_game_id_graceful,
     validate_game_invite,
+    validate_illegal_move_claim,
+    validate_invite_withdraw,
     validate_move_ack,
     validate_move_message,
+    validate_move_seal,
+    validate_seal_reveal,
     validate_sync_request,
     validate_sync_response,
     verify_board_hash,
     verify_board_hash_graceful,
     // Chess protocol types
+    AbuseReport,
+    ArbiterJoin,
+    ArbiterUpdate,
     ChessProtocolError,
     ChessProtocolResult,
+    DevicePair,
+    DeviceSync,
+    DrawAccept,
+    DrawOffer,
     GameAccept,
     GameDecline,
     GameInvite,
+    IllegalMoveClaim,
+    InviteWithdraw,
     Move as ChessMove,
     MoveAck,
+    MoveSeal,
+    MoveSignature,
+    Presence,
+    PresenceStatus,
+    ReportEvidence,
+    Resign,
+    SyncChange,
+    SyncKind,
     SyncRequest,
     SyncResponse,
     ValidationError,
 };
-pub use types::{Message, SignedEnvelope};
+pub use types::{Message, MessagePriority, SignedEnvelope, SignedEnvelopeRef};
+#[cfg(feature = "native")]
 pub use wire::{
     ConnectionState,
     DosProtectionConfig,
@@ -53,6 +82,7 @@ pub use wire::{
     // Graceful degradation types (Step 4.3)
     RetryConfig,
     RetryStrategy,
+    SendQueuePolicy,
     SessionSummary,
 
     WireConfig,
@@ -1,6 +1,14 @@
+use crate::crypto::batch::verify_in_parallel;
+use crate::crypto::certificate::{ArbiterRuling, ResultSignature};
+use crate::crypto::commitment::SealedMove;
 use crate::crypto::identity::{Identity, PeerId};
+use crate::crypto::revocation::RevocationRecord;
+use crate::crypto::rotation::RotationCertificate;
 use crate::messages::chess::{
-    GameAccept, GameDecline, GameInvite, Move, MoveAck, SyncRequest, SyncResponse,
+    AbuseReport, ArbiterJoin, ArbiterUpdate, DevicePair, DeviceSync, DrawAccept, DrawOffer,
+    GameAccept, GameDecline, GameInvite, IllegalMoveClaim, InviteWithdraw, Move, MoveAck, MoveSeal,
+    Presence, PresenceStatus, ReportEvidence, Resign, ServerAnnouncement, Study, SyncChange,
+    SyncRequest, SyncResponse, Vote, VoterJoin,
 };
 use anyhow::{Context, Result};
 use ed25519_dalek::Signature;
@@ -13,6 +21,22 @@ pub const DEFAULT_MAX_MESSAGE_AGE_SECONDS: u64 = 300;
 /// Expected Ed25519 signature length in bytes
 pub const ED25519_SIGNATURE_LENGTH: usize = 64;
 
+/// Lane a message waits in on a connection's send queue
+///
+/// Lanes are sent in order, so a queued move or acknowledgment goes out
+/// ahead of a sync or chat backlog; within a lane messages keep their order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MessagePriority {
+    /// Acknowledgments, keepalives, key changes, and presence
+    Control,
+    /// Moves and the other messages that change a game
+    Move,
+    /// Game syncs and other bulk transfers
+    Sync,
+    /// Chat messages, the first to be dropped when the queue is full
+    Chat,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Message {
     // Existing variants
@@ -27,6 +51,51 @@ pub enum Message {
     MoveAck(MoveAck),
     SyncRequest(SyncRequest),
     SyncResponse(SyncResponse),
+
+    // Game ending variants (appended to keep existing wire tags stable)
+    Resign(Resign),
+    DrawOffer(DrawOffer),
+    DrawAccept(DrawAccept),
+
+    // Identity variants
+    KeyRotation(RotationCertificate),
+    KeyRevocation(RevocationRecord),
+
+    // Game certificate variants
+    ResultSignature(ResultSignature),
+
+    // Teaching variants
+    Study(Study),
+
+    // Consultation game variants
+    VoterJoin(VoterJoin),
+    Vote(Vote),
+
+    // Server operator variants
+    ServerAnnouncement(ServerAnnouncement),
+
+    // Invitation lifecycle variants
+    InviteWithdraw(InviteWithdraw),
+
+    // Adjournment variants
+    MoveSeal(MoveSeal),
+    SealReveal(SealedMove),
+
+    // Presence variants
+    Presence(Presence),
+
+    // Moderation variants
+    AbuseReport(AbuseReport),
+
+    // Device sync variants
+    DevicePair(DevicePair),
+    DeviceSync(DeviceSync),
+
+    // Arbiter variants
+    ArbiterJoin(ArbiterJoin),
+    ArbiterUpdate(ArbiterUpdate),
+    IllegalMoveClaim(IllegalMoveClaim),
+    ArbiterRuling(ArbiterRuling),
 }
 
 impl Message {
@@ -200,6 +269,130 @@ impl Message {
         ))
     }
 
+    /// Create a new Resign message
+    ///
+    /// # Arguments
+    /// * `game_id` - Game being resigned
+    pub fn new_resign(game_id: String) -> Self {
+        Message::Resign(Resign::new(game_id))
+    }
+
+    /// Create a new DrawOffer message
+    ///
+    /// # Arguments
+    /// * `game_id` - Game a draw is offered in
+    pub fn new_draw_offer(game_id: String) -> Self {
+        Message::DrawOffer(DrawOffer::new(game_id))
+    }
+
+    /// Create a new DrawAccept message
+    ///
+    /// # Arguments
+    /// * `game_id` - Game whose draw offer is accepted
+    pub fn new_draw_accept(game_id: String) -> Self {
+        Message::DrawAccept(DrawAccept::new(game_id))
+    }
+
+    /// Create a new KeyRotation message
+    ///
+    /// # Arguments
+    /// * `certificate` - Rotation signed by the sender's old and new keys
+    pub fn new_key_rotation(certificate: RotationCertificate) -> Self {
+        Message::KeyRotation(certificate)
+    }
+
+    /// Create a new KeyRevocation message
+    ///
+    /// # Arguments
+    /// * `record` - Revocation signed by the revoked key
+    pub fn new_key_revocation(record: RevocationRecord) -> Self {
+        Message::KeyRevocation(record)
+    }
+
+    /// Create a new ResultSignature message
+    ///
+    /// # Arguments
+    /// * `signature` - The sender's signature over a finished game
+    pub fn new_result_signature(signature: ResultSignature) -> Self {
+        Message::ResultSignature(signature)
+    }
+
+    /// Create a new Study message
+    ///
+    /// # Arguments
+    /// * `study` - The annotated position and line to share
+    pub fn new_study(study: Study) -> Self {
+        Message::Study(study)
+    }
+
+    /// Create a new VoterJoin message
+    pub fn new_voter_join(game_id: String) -> Self {
+        Message::VoterJoin(VoterJoin::new(game_id))
+    }
+
+    /// Create a new Vote message
+    pub fn new_vote(game_id: String, chess_move: String) -> Self {
+        Message::Vote(Vote::new(game_id, chess_move))
+    }
+
+    /// Create a new ServerAnnouncement message
+    pub fn new_server_announcement(text: String) -> Self {
+        Message::ServerAnnouncement(ServerAnnouncement::new(text))
+    }
+
+    /// Create a new InviteWithdraw message
+    pub fn new_invite_withdraw(game_id: String) -> Self {
+        Message::InviteWithdraw(InviteWithdraw::new(game_id))
+    }
+
+    /// Create a new MoveSeal message committing to a sealed move
+    pub fn new_move_seal(sealed: &SealedMove) -> Self {
+        Message::MoveSeal(MoveSeal::new(
+            sealed.game_id.clone(),
+            sealed.move_number,
+            sealed.commitment(),
+        ))
+    }
+
+    /// Create a new SealReveal message opening a sealed move
+    pub fn new_seal_reveal(sealed: SealedMove) -> Self {
+        Message::SealReveal(sealed)
+    }
+
+    /// Create a new Presence message
+    pub fn new_presence(status: PresenceStatus) -> Self {
+        Message::Presence(Presence::new(status))
+    }
+
+    /// Create a new AbuseReport message
+    pub fn new_abuse_report(
+        reported_peer_id: String,
+        reason: String,
+        evidence: Vec<ReportEvidence>,
+    ) -> Self {
+        Message::AbuseReport(AbuseReport::new(reported_peer_id, reason, evidence))
+    }
+
+    /// Create a new DevicePair message
+    pub fn new_device_pair(code: String, address: String) -> Self {
+        Message::DevicePair(DevicePair::new(code, address))
+    }
+
+    /// Create a new DeviceSync message
+    pub fn new_device_sync(since: u64, through: u64, changes: Vec<SyncChange>) -> Self {
+        Message::DeviceSync(DeviceSync::new(since, through, changes))
+    }
+
+    /// Create a new ArbiterJoin message
+    pub fn new_arbiter_join(game_id: String, address: String) -> Self {
+        Message::ArbiterJoin(ArbiterJoin::new(game_id, address))
+    }
+
+    /// Create a new IllegalMoveClaim message
+    pub fn new_illegal_move_claim(game_id: String, ply: u32, reason: Option<String>) -> Self {
+        Message::IllegalMoveClaim(IllegalMoveClaim::new(game_id, ply, reason))
+    }
+
     /// Get the nonce from either Ping or Pong message
     /// Panics for chess messages as they don't have nonces
     pub fn get_nonce(&self) -> u64 {
@@ -212,7 +405,28 @@ impl Message {
             | Message::Move(_)
             | Message::MoveAck(_)
             | Message::SyncRequest(_)
-            | Message::SyncResponse(_) => {
+            | Message::SyncResponse(_)
+            | Message::Resign(_)
+            | Message::DrawOffer(_)
+            | Message::DrawAccept(_)
+            | Message::KeyRotation(_)
+            | Message::KeyRevocation(_)
+            | Message::ResultSignature(_)
+            | Message::Study(_)
+            | Message::VoterJoin(_)
+            | Message::Vote(_)
+            | Message::ServerAnnouncement(_)
+            | Message::InviteWithdraw(_)
+            | Message::MoveSeal(_)
+            | Message::SealReveal(_)
+            | Message::Presence(_)
+            | Message::AbuseReport(_)
+            | Message::DevicePair(_)
+            | Message::DeviceSync(_)
+            | Message::ArbiterJoin(_)
+            | Message::ArbiterUpdate(_)
+            | Message::IllegalMoveClaim(_)
+            | Message::ArbiterRuling(_) => {
                 panic!("get_nonce() called on chess message - use get_game_id() instead")
             }
         }
@@ -230,7 +444,28 @@ impl Message {
             | Message::Move(_)
             | Message::MoveAck(_)
             | Message::SyncRequest(_)
-            | Message::SyncResponse(_) => {
+            | Message::SyncResponse(_)
+            | Message::Resign(_)
+            | Message::DrawOffer(_)
+            | Message::DrawAccept(_)
+            | Message::KeyRotation(_)
+            | Message::KeyRevocation(_)
+            | Message::ResultSignature(_)
+            | Message::Study(_)
+            | Message::VoterJoin(_)
+            | Message::Vote(_)
+            | Message::ServerAnnouncement(_)
+            | Message::InviteWithdraw(_)
+            | Message::MoveSeal(_)
+            | Message::SealReveal(_)
+            | Message::Presence(_)
+            | Message::AbuseReport(_)
+            | Message::DevicePair(_)
+            | Message::DeviceSync(_)
+            | Message::ArbiterJoin(_)
+            | Message::ArbiterUpdate(_)
+            | Message::IllegalMoveClaim(_)
+            | Message::ArbiterRuling(_) => {
                 panic!("get_payload() called on chess message - chess messages don't have payloads")
             }
         }
@@ -257,6 +492,9 @@ impl Message {
                 | Message::MoveAck(_)
                 | Message::SyncRequest(_)
                 | Message::SyncResponse(_)
+                | Message::Resign(_)
+                | Message::DrawOffer(_)
+                | Message::DrawAccept(_)
         )
     }
 
@@ -271,7 +509,29 @@ impl Message {
             Message::MoveAck(msg) => Some(&msg.game_id),
             Message::SyncRequest(msg) => Some(&msg.game_id),
             Message::SyncResponse(msg) => Some(&msg.game_id),
-            Message::Ping { .. } | Message::Pong { .. } => None,
+            Message::Resign(msg) => Some(&msg.game_id),
+            Message::DrawOffer(msg) => Some(&msg.game_id),
+            Message::DrawAccept(msg) => Some(&msg.game_id),
+            Message::ResultSignature(msg) => Some(&msg.game_id),
+            Message::VoterJoin(msg) => Some(&msg.game_id),
+            Message::Vote(msg) => Some(&msg.game_id),
+            Message::InviteWithdraw(msg) => Some(&msg.game_id),
+            Message::MoveSeal(msg) => Some(&msg.game_id),
+            Message::SealReveal(msg) => Some(&msg.game_id),
+            Message::ArbiterJoin(msg) => Some(&msg.game_id),
+            Message::ArbiterUpdate(msg) => Some(&msg.game_id),
+            Message::IllegalMoveClaim(msg) => Some(&msg.game_id),
+            Message::ArbiterRuling(msg) => Some(&msg.game_id),
+            Message::Ping { .. }
+            | Message::Pong { .. }
+            | Message::KeyRotation(_)
+            | Message::KeyRevocation(_)
+            | Message::Study(_)
+            | Message::ServerAnnouncement(_)
+            | Message::Presence(_)
+            | Message::AbuseReport(_)
+            | Message::DevicePair(_)
+            | Message::DeviceSync(_) => None,
         }
     }
 
@@ -287,6 +547,27 @@ impl Message {
             Message::MoveAck(_) => "MoveAck",
             Message::SyncRequest(_) => "SyncRequest",
             Message::SyncResponse(_) => "SyncResponse",
+            Message::Resign(_) => "Resign",
+            Message::DrawOffer(_) => "DrawOffer",
+            Message::DrawAccept(_) => "DrawAccept",
+            Message::KeyRotation(_) => "KeyRotation",
+            Message::KeyRevocation(_) => "KeyRevocation",
+            Message::ResultSignature(_) => "ResultSignature",
+            Message::Study(_) => "Study",
+            Message::VoterJoin(_) => "VoterJoin",
+            Message::Vote(_) => "Vote",
+            Message::ServerAnnouncement(_) => "ServerAnnouncement",
+            Message::InviteWithdraw(_) => "InviteWithdraw",
+            Message::MoveSeal(_) => "MoveSeal",
+            Message::SealReveal(_) => "SealReveal",
+            Message::Presence(_) => "Presence",
+            Message::AbuseReport(_) => "AbuseReport",
+            Message::DevicePair(_) => "DevicePair",
+            Message::DeviceSync(_) => "DeviceSync",
+            Message::ArbiterJoin(_) => "ArbiterJoin",
+            Message::ArbiterUpdate(_) => "ArbiterUpdate",
+            Message::IllegalMoveClaim(_) => "IllegalMoveClaim",
+            Message::ArbiterRuling(_) => "ArbiterRuling",
         }
     }
 
@@ -437,6 +718,103 @@ impl Message {
                 // Base overhead + game_id
                 32 + req.game_id.len() + 8
             }
+            Message::Resign(resign) => 32 + resign.game_id.len() + 8,
+            Message::DrawOffer(offer) => 32 + offer.game_id.len() + 8,
+            Message::DrawAccept(accept) => 32 + accept.game_id.len() + 8,
+            Message::VoterJoin(join) => 32 + join.game_id.len() + 8,
+            Message::Vote(vote) => 32 + vote.game_id.len() + vote.chess_move.len() + 8,
+            Message::ServerAnnouncement(announcement) => 32 + announcement.text.len() + 8,
+            Message::InviteWithdraw(withdraw) => 32 + withdraw.game_id.len() + 8,
+            Message::MoveSeal(seal) => 32 + seal.game_id.len() + seal.commitment.len() + 8,
+            Message::SealReveal(sealed) => {
+                32 + sealed.game_id.len() + sealed.chess_move.len() + sealed.nonce.len() + 8
+            }
+            Message::Presence(_) => 32 + 8,
+            Message::AbuseReport(report) => {
+                32 + report.reported_peer_id.len()
+                    + report.reason.len()
+                    + report
+                        .evidence
+                        .iter()
+                        .map(|evidence| {
+                            evidence.game_id.len()
+                                + evidence.message_type.len()
+                                + evidence.content.len()
+                                + evidence.signature.len()
+                                + 16
+                        })
+                        .sum::<usize>()
+                    + 8
+            }
+            Message::DevicePair(pair) => 32 + pair.code.len() + pair.address.len() + 8,
+            Message::DeviceSync(sync) => {
+                32 + sync
+                    .changes
+                    .iter()
+                    .map(|change| {
+                        change.key.len() + change.value.as_ref().map_or(0, String::len) + 16
+                    })
+                    .sum::<usize>()
+                    + 16
+            }
+            Message::ArbiterJoin(join) => 32 + join.game_id.len() + join.address.len() + 8,
+            Message::ArbiterUpdate(update) => {
+                32 + update.game_id.len()
+                    + update.white.len()
+                    + update.black.len()
+                    + update.moves.iter().map(|m| m.len() + 8).sum::<usize>()
+                    + update.result.len()
+                    + 16
+            }
+            Message::IllegalMoveClaim(claim) => {
+                32 + claim.game_id.len() + claim.reason.as_ref().map_or(0, String::len) + 16
+            }
+            Message::ArbiterRuling(ruling) => {
+                32 + ruling.game_id.len()
+                    + ruling.arbiter.len()
+                    + ruling.claimant.len()
+                    + ruling.chess_move.as_ref().map_or(0, String::len)
+                    + ruling.reason.len()
+                    + ruling.signature.len()
+                    + 16
+            }
+            Message::KeyRotation(certificate) => {
+                // Base overhead + both peer IDs and signatures + timestamp
+                32 + certificate.old_peer_id.len()
+                    + certificate.new_peer_id.len()
+                    + certificate.old_key_signature.len()
+                    + certificate.new_key_signature.len()
+                    + 8
+            }
+            Message::KeyRevocation(record) => {
+                // Base overhead + peer IDs + reason + signature + timestamp
+                32 + record.peer_id.len()
+                    + record.successor.as_ref().map_or(0, |s| s.len())
+                    + record.reason.len()
+                    + record.signature.len()
+                    + 8
+            }
+            Message::ResultSignature(signature) => {
+                // Base overhead + game_id + signer + result + signature
+                32 + signature.game_id.len()
+                    + signature.signer.len()
+                    + signature.result.len()
+                    + signature.signature.len()
+            }
+            Message::Study(study) => {
+                // Base overhead + ID + title + FEN + comments + moves
+                let comment_size = study.comment.as_ref().map_or(0, |c| c.len());
+                let line_size: usize = study
+                    .moves
+                    .iter()
+                    .map(|m| m.chess_move.len() + m.comment.as_ref().map_or(0, |c| c.len()) + 8)
+                    .sum();
+                32 + study.study_id.len()
+                    + study.title.len()
+                    + study.fen.len()
+                    + comment_size
+                    + line_size
+            }
             Message::SyncResponse(resp) => {
                 // Base overhead + game_id + board_state (FEN ~80 chars) + move_history + hash
                 let move_history_size: usize = resp.move_history.iter().map(|m| m.len() + 4).sum();
@@ -480,13 +858,86 @@ impl Message {
             // Ping/Pong are typically small
             Message::Ping { .. } | Message::Pong { .. } => false,
             // Game management messages are typically small
-            Message::GameInvite(_) | Message::GameAccept(_) | Message::GameDecline(_) => false,
+            Message::GameInvite(_)
+            | Message::GameAccept(_)
+            | Message::GameDecline(_)
+            | Message::InviteWithdraw(_) => false,
             // Move messages are small
             Message::Move(_) | Message::MoveAck(_) => false,
             // Sync requests are small
             Message::SyncRequest(_) => false,
+            // Resignations and draw offers only carry a game ID
+            Message::Resign(_) | Message::DrawOffer(_) | Message::DrawAccept(_) => false,
+            // Rotation certificates are two keys and two signatures
+            Message::KeyRotation(_) => false,
+            // Revocations are a key, a short reason, and a signature
+            Message::KeyRevocation(_) => false,
+            // Result signatures are a game ID, a key, and a signature
+            Message::ResultSignature(_) => false,
             // Sync responses can be large due to move history and board state
             Message::SyncResponse(_) => true,
+            // Studies can carry a long line with a comment on every move
+            Message::Study(_) => true,
+            // Voter registrations and votes are a game ID and at most a move
+            Message::VoterJoin(_) | Message::Vote(_) => false,
+            // Announcements are a short line of text
+            Message::ServerAnnouncement(_) => false,
+            // Sealed moves are a game ID, a move, and a digest or nonce
+            Message::MoveSeal(_) | Message::SealReveal(_) => false,
+            // Presence is a single status
+            Message::Presence(_) => false,
+            // Reports can carry many evidence messages
+            Message::AbuseReport(_) => true,
+            // Pairing is a code and an address; a sync can carry many changes
+            Message::DevicePair(_) => false,
+            Message::DeviceSync(_) => true,
+            // Arbiter updates carry the whole move list; the rest are short
+            Message::ArbiterUpdate(_) => true,
+            Message::ArbiterJoin(_) | Message::IllegalMoveClaim(_) | Message::ArbiterRuling(_) => {
+                false
+            }
+        }
+    }
+
+    /// Lane this message takes on a connection's send queue
+    ///
+    /// A ping or pong with a payload is chat; an empty one is a keepalive.
+    pub fn priority(&self) -> MessagePriority {
+        match self {
+            Message::Ping { payload, .. } | Message::Pong { payload, .. } => {
+                match payload.is_empty() {
+                    true => MessagePriority::Control,
+                    false => MessagePriority::Chat,
+                }
+            }
+            Message::MoveAck(_)
+            | Message::KeyRotation(_)
+            | Message::KeyRevocation(_)
+            | Message::Presence(_)
+            | Message::DevicePair(_) => MessagePriority::Control,
+            Message::GameInvite(_)
+            | Message::GameAccept(_)
+            | Message::GameDecline(_)
+            | Message::InviteWithdraw(_)
+            | Message::Move(_)
+            | Message::MoveSeal(_)
+            | Message::SealReveal(_)
+            | Message::Resign(_)
+            | Message::DrawOffer(_)
+            | Message::DrawAccept(_)
+            | Message::ResultSignature(_)
+            | Message::VoterJoin(_)
+            | Message::Vote(_)
+            | Message::ArbiterJoin(_)
+            | Message::ArbiterUpdate(_)
+            | Message::IllegalMoveClaim(_)
+            | Message::ArbiterRuling(_) => MessagePriority::Move,
+            Message::SyncRequest(_)
+            | Message::SyncResponse(_)
+            | Message::Study(_)
+            | Message::ServerAnnouncement(_)
+            | Message::AbuseReport(_)
+            | Message::DeviceSync(_) => MessagePriority::Sync,
         }
     }
 
@@ -552,6 +1003,108 @@ impl Message {
                 let game_id_short = &req.game_id[..8.min(req.game_id.len())];
                 format!("SyncRequest(game={game_id_short})")
             }
+            Message::Resign(resign) => {
+                let game_id_short = &resign.game_id[..8.min(resign.game_id.len())];
+                format!("Resign(game={game_id_short})")
+            }
+            Message::DrawOffer(offer) => {
+                let game_id_short = &offer.game_id[..8.min(offer.game_id.len())];
+                format!("DrawOffer(game={game_id_short})")
+            }
+            Message::DrawAccept(accept) => {
+                let game_id_short = &accept.game_id[..8.min(accept.game_id.len())];
+                format!("DrawAccept(game={game_id_short})")
+            }
+            Message::KeyRotation(certificate) => {
+                let old_short = &certificate.old_peer_id[..8.min(certificate.old_peer_id.len())];
+                let new_short = &certificate.new_peer_id[..8.min(certificate.new_peer_id.len())];
+                format!("KeyRotation(old={old_short}, new={new_short})")
+            }
+            Message::KeyRevocation(record) => {
+                let key_short = &record.peer_id[..8.min(record.peer_id.len())];
+                format!("KeyRevocation(key={key_short})")
+            }
+            Message::ResultSignature(signature) => {
+                let game_id_short = &signature.game_id[..8.min(signature.game_id.len())];
+                let result = &signature.result;
+                format!("ResultSignature(game={game_id_short}, result={result})")
+            }
+            Message::Study(study) => {
+                let study_id_short = &study.study_id[..8.min(study.study_id.len())];
+                let moves_len = study.moves.len();
+                format!("Study(study={study_id_short}, moves={moves_len})")
+            }
+            Message::VoterJoin(join) => {
+                let game_id_short = &join.game_id[..8.min(join.game_id.len())];
+                format!("VoterJoin(game={game_id_short})")
+            }
+            Message::Vote(vote) => {
+                let game_id_short = &vote.game_id[..8.min(vote.game_id.len())];
+                let chess_move = &vote.chess_move;
+                format!("Vote(game={game_id_short}, move={chess_move})")
+            }
+            Message::ServerAnnouncement(announcement) => {
+                let text_len = announcement.text.len();
+                format!("ServerAnnouncement(len={text_len})")
+            }
+            Message::InviteWithdraw(withdraw) => {
+                let game_id_short = &withdraw.game_id[..8.min(withdraw.game_id.len())];
+                format!("InviteWithdraw(game={game_id_short})")
+            }
+            Message::MoveSeal(seal) => {
+                let game_id_short = &seal.game_id[..8.min(seal.game_id.len())];
+                let move_number = seal.move_number;
+                format!("MoveSeal(game={game_id_short}, move_number={move_number})")
+            }
+            Message::SealReveal(sealed) => {
+                // Logged only once revealed, so the move is no longer secret
+                let game_id_short = &sealed.game_id[..8.min(sealed.game_id.len())];
+                let chess_move = &sealed.chess_move;
+                format!("SealReveal(game={game_id_short}, move={chess_move})")
+            }
+            Message::Presence(presence) => {
+                let status = presence.status;
+                format!("Presence(status={status})")
+            }
+            Message::AbuseReport(report) => {
+                // The reason may quote abuse, so only its size is logged
+                let peer_short = &report.reported_peer_id[..8.min(report.reported_peer_id.len())];
+                let evidence = report.evidence.len();
+                format!("AbuseReport(peer={peer_short}, evidence={evidence})")
+            }
+            Message::DevicePair(pair) => {
+                // The code is a secret until used, so it is not logged
+                let address = &pair.address;
+                format!("DevicePair(address={address})")
+            }
+            Message::DeviceSync(sync) => {
+                let since = sync.since;
+                let through = sync.through;
+                let changes = sync.changes.len();
+                format!("DeviceSync(since={since}, through={through}, changes={changes})")
+            }
+            Message::ArbiterJoin(join) => {
+                let game_id_short = &join.game_id[..8.min(join.game_id.len())];
+                let address = &join.address;
+                format!("ArbiterJoin(game={game_id_short}, address={address})")
+            }
+            Message::ArbiterUpdate(update) => {
+                let game_id_short = &update.game_id[..8.min(update.game_id.len())];
+                let moves_len = update.moves.len();
+                let result = &update.result;
+                format!("ArbiterUpdate(game={game_id_short}, moves={moves_len}, result={result})")
+            }
+            Message::IllegalMoveClaim(claim) => {
+                let game_id_short = &claim.game_id[..8.min(claim.game_id.len())];
+                let ply = claim.ply;
+                format!("IllegalMoveClaim(game={game_id_short}, ply={ply})")
+            }
+            Message::ArbiterRuling(ruling) => {
+                let game_id_short = &ruling.game_id[..8.min(ruling.game_id.len())];
+                let ply = ruling.ply;
+                let verdict = ruling.verdict();
+                format!("ArbiterRuling(game={game_id_short}, ply={ply}, verdict={verdict})")
+            }
             Message::SyncResponse(resp) => {
                 let game_id_short = &resp.game_id[..8.min(resp.game_id.len())];
                 let moves_len = resp.move_history.len();
@@ -586,8 +1139,13 @@ impl Message {
     /// ```
     pub fn validate(&self) -> Result<(), crate::messages::chess::ValidationError> {
         use crate::messages::chess::{
-            validate_game_accept, validate_game_decline, validate_game_invite, validate_move_ack,
-            validate_move_message, validate_sync_request, validate_sync_response,
+            validate_abuse_report, validate_arbiter_join, validate_arbiter_update,
+            validate_device_pair, validate_device_sync, validate_game_accept,
+            validate_game_decline, validate_game_end_message, validate_game_invite,
+            validate_illegal_move_claim, validate_invite_withdraw, validate_move_ack,
+            validate_move_message, validate_move_seal, validate_seal_reveal,
+            validate_server_announcement, validate_study, validate_sync_request,
+            validate_sync_response, validate_vote,
         };
 
         // First perform the basic validation
@@ -602,6 +1160,56 @@ impl Message {
             Message::MoveAck(ack) => validate_move_ack(ack),
             Message::SyncRequest(req) => validate_sync_request(req),
             Message::SyncResponse(resp) => validate_sync_response(resp),
+            Message::Resign(resign) => validate_game_end_message(&resign.game_id),
+            Message::DrawOffer(offer) => validate_game_end_message(&offer.game_id),
+            Message::DrawAccept(accept) => validate_game_end_message(&accept.game_id),
+            Message::KeyRotation(certificate) => certificate.verify().map_err(|e| {
+                crate::messages::chess::ValidationError::InvalidMessageFormat(format!(
+                    "Invalid key rotation: {e:#}"
+                ))
+            }),
+            Message::KeyRevocation(record) => record.verify().map_err(|e| {
+                crate::messages::chess::ValidationError::InvalidMessageFormat(format!(
+                    "Invalid key revocation: {e:#}"
+                ))
+            }),
+            Message::ResultSignature(signature) => {
+                validate_game_end_message(&signature.game_id)?;
+                PeerId::from_string(signature.signer.clone())
+                    .to_verifying_key()
+                    .map(|_| ())
+                    .map_err(|e| {
+                        crate::messages::chess::ValidationError::InvalidMessageFormat(format!(
+                            "Invalid result signer: {e:#}"
+                        ))
+                    })
+            }
+            Message::Study(study) => validate_study(study),
+            Message::VoterJoin(join) => validate_game_end_message(&join.game_id),
+            Message::Vote(vote) => validate_vote(vote),
+            Message::ServerAnnouncement(announcement) => validate_server_announcement(announcement),
+            Message::InviteWithdraw(withdraw) => validate_invite_withdraw(withdraw),
+            Message::MoveSeal(seal) => validate_move_seal(seal),
+            Message::SealReveal(sealed) => validate_seal_reveal(sealed),
+            // Every status the enum can hold is valid
+            Message::Presence(_) => Ok(()),
+            Message::AbuseReport(report) => validate_abuse_report(report),
+            Message::DevicePair(pair) => validate_device_pair(pair),
+            Message::DeviceSync(sync) => validate_device_sync(sync),
+            Message::ArbiterJoin(join) => validate_arbiter_join(join),
+            Message::ArbiterUpdate(update) => validate_arbiter_update(update),
+            Message::IllegalMoveClaim(claim) => validate_illegal_move_claim(claim),
+            Message::ArbiterRuling(ruling) => {
+                validate_game_end_message(&ruling.game_id)?;
+                PeerId::from_string(ruling.arbiter.clone())
+                    .to_verifying_key()
+                    .map(|_| ())
+                    .map_err(|e| {
+                        crate::messages::chess::ValidationError::InvalidMessageFormat(format!(
+                            "Invalid arbiter: {e:#}"
+                        ))
+                    })
+            }
         };
 
         // If basic validation passes, perform enhanced security validation
@@ -704,7 +1312,7 @@ impl SignedEnvelope {
         };
 
         // Sign the serialized message
-        let signature = identity.sign(&message_bytes);
+        let signature = identity.sign(&message_bytes)?;
         let signature_bytes = signature.to_bytes().to_vec();
 
         // Get sender PeerId
@@ -714,6 +1322,129 @@ impl SignedEnvelope {
         Self::new(message_bytes, signature_bytes, sender, envelope_timestamp)
     }
 
+    /// Verify the signature of this envelope
+    ///
+    /// # Returns
+    /// * `bool` - True if signature is valid, false otherwise
+    ///
+    /// # Notes
+    /// * Returns false on any error (invalid PeerId, malformed signature, etc.)
+    /// * Uses constant-time verification for security
+    pub fn verify_signature(&self) -> bool {
+        self.borrowed().verify_signature()
+    }
+
+    /// Deserialize the message from this envelope
+    ///
+    /// # Returns
+    /// * `Result<Message>` - Deserialized message or error
+    pub fn get_message(&self) -> Result<Message> {
+        self.borrowed().get_message()
+    }
+
+    /// Get the sender PeerId
+    pub fn sender(&self) -> &str {
+        &self.sender
+    }
+
+    /// Get the timestamp
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Check if the envelope timestamp is within the acceptable age
+    ///
+    /// # Arguments
+    /// * `max_age_seconds` - Maximum acceptable age in seconds
+    ///
+    /// # Returns
+    /// * `bool` - True if timestamp is valid (not too old, not in future)
+    pub fn is_timestamp_valid(&self, max_age_seconds: u64) -> bool {
+        self.borrowed().is_timestamp_valid(max_age_seconds)
+    }
+
+    /// Check the envelope timestamp against our clock, allowing for the
+    /// sender's known clock offset
+    ///
+    /// # Arguments
+    /// * `max_age_seconds` - Maximum acceptable age in seconds
+    /// * `max_skew_seconds` - How far past our clock the corrected timestamp may be
+    /// * `clock_offset` - Sender's clock minus ours in seconds, 0 when unknown
+    ///
+    /// # Returns
+    /// * `bool` - True if the corrected timestamp is neither too old nor in the future
+    pub fn is_timestamp_within(
+        &self,
+        max_age_seconds: u64,
+        max_skew_seconds: u64,
+        clock_offset: i64,
+    ) -> bool {
+        self.borrowed()
+            .is_timestamp_within(max_age_seconds, max_skew_seconds, clock_offset)
+    }
+
+    /// The envelope timestamp translated to our clock, given the sender's
+    /// clock minus ours in seconds
+    pub fn corrected_timestamp(&self, clock_offset: i64) -> i64 {
+        self.borrowed().corrected_timestamp(clock_offset)
+    }
+
+    /// Get the age of this envelope in seconds
+    ///
+    /// # Returns
+    /// * `u64` - Age in seconds (0 if timestamp is in the future)
+    pub fn get_age_seconds(&self) -> u64 {
+        self.borrowed().get_age_seconds()
+    }
+
+    /// Verify the signatures of `envelopes` across all cores, in order
+    ///
+    /// Blocks until done; async code should use [`Self::verify_batch`].
+    pub fn verify_all(envelopes: &[SignedEnvelope]) -> Vec<bool> {
+        verify_in_parallel(envelopes, SignedEnvelope::verify_signature)
+    }
+
+    /// Verify a batch of received envelopes on the blocking thread pool, so
+    /// ingesting a backlog doesn't stall other connections
+    ///
+    /// Returns each envelope with whether its signature is valid.
+    #[cfg(feature = "native")]
+    pub async fn verify_batch(
+        envelopes: Vec<SignedEnvelope>,
+    ) -> Result<Vec<(SignedEnvelope, bool)>> {
+        tokio::task::spawn_blocking(move || {
+            let valid = Self::verify_all(&envelopes);
+            envelopes.into_iter().zip(valid).collect()
+        })
+        .await
+        .context("Signature verification task failed")
+    }
+
+    /// View this envelope's fields without copying them
+    pub fn borrowed(&self) -> SignedEnvelopeRef<'_> {
+        SignedEnvelopeRef {
+            message: &self.message,
+            signature: &self.signature,
+            sender: &self.sender,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// A `SignedEnvelope` whose fields borrow from the buffer it was
+/// deserialized from
+///
+/// Large messages such as `SyncResponse` can then be verified and decoded
+/// without first copying their bytes out of the frame.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct SignedEnvelopeRef<'a> {
+    pub message: &'a [u8],
+    pub signature: &'a [u8],
+    pub sender: &'a str,
+    pub timestamp: u64,
+}
+
+impl<'a> SignedEnvelopeRef<'a> {
     /// Verify the signature of this envelope
     ///
     /// # Returns
@@ -724,14 +1455,14 @@ impl SignedEnvelope {
     /// * Uses constant-time verification for security
     pub fn verify_signature(&self) -> bool {
         // Convert sender to PeerId and then to VerifyingKey
-        let peer_id = PeerId::from_string(self.sender.clone());
+        let peer_id = PeerId::from_string(self.sender.to_string());
         let verifying_key = match peer_id.to_verifying_key() {
             Ok(key) => key,
             Err(_) => return false, // Invalid PeerId format
         };
 
         // Convert signature bytes to Signature
-        let signature_array: [u8; 64] = match self.signature.as_slice().try_into() {
+        let signature_array: [u8; 64] = match self.signature.try_into() {
             Ok(arr) => arr,
             Err(_) => return false, // Invalid signature length
         };
@@ -739,7 +1470,7 @@ impl SignedEnvelope {
         let signature = Signature::from_bytes(&signature_array);
 
         // Verify signature against message bytes
-        Identity::verify(&verifying_key, &self.message, &signature)
+        Identity::verify(&verifying_key, self.message, &signature)
     }
 
     /// Deserialize the message from this envelope
@@ -747,13 +1478,13 @@ impl SignedEnvelope {
     /// # Returns
     /// * `Result<Message>` - Deserialized message or error
     pub fn get_message(&self) -> Result<Message> {
-        Message::deserialize(&self.message)
+        Message::deserialize(self.message)
             .map_err(|e| anyhow::anyhow!("Failed to deserialize message: {e}"))
     }
 
     /// Get the sender PeerId
-    pub fn sender(&self) -> &str {
-        &self.sender
+    pub fn sender(&self) -> &'a str {
+        self.sender
     }
 
     /// Get the timestamp
@@ -788,6 +1519,37 @@ impl SignedEnvelope {
         true
     }
 
+    /// Check the envelope timestamp against our clock, allowing for the
+    /// sender's known clock offset
+    ///
+    /// # Arguments
+    /// * `max_age_seconds` - Maximum acceptable age in seconds
+    /// * `max_skew_seconds` - How far past our clock the corrected timestamp may be
+    /// * `clock_offset` - Sender's clock minus ours in seconds, 0 when unknown
+    ///
+    /// # Returns
+    /// * `bool` - True if the corrected timestamp is neither too old nor in the future
+    pub fn is_timestamp_within(
+        &self,
+        max_age_seconds: u64,
+        max_skew_seconds: u64,
+        clock_offset: i64,
+    ) -> bool {
+        let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(_) => return false, // System time error
+        };
+
+        let sent = self.corrected_timestamp(clock_offset);
+        sent + max_age_seconds as i64 >= now && sent <= now + max_skew_seconds as i64
+    }
+
+    /// The envelope timestamp translated to our clock, given the sender's
+    /// clock minus ours in seconds
+    pub fn corrected_timestamp(&self, clock_offset: i64) -> i64 {
+        self.timestamp as i64 - clock_offset
+    }
+
     /// Get the age of this envelope in seconds
     ///
     /// # Returns
@@ -800,4 +1562,14 @@ impl SignedEnvelope {
 
         now.saturating_sub(self.timestamp)
     }
+
+    /// Copy the fields into an owned envelope
+    pub fn to_owned_envelope(&self) -> SignedEnvelope {
+        SignedEnvelope {
+            message: self.message.to_vec(),
+            signature: self.signature.to_vec(),
+            sender: self.sender.to_string(),
+            timestamp: self.timestamp,
+        }
+    }
 }
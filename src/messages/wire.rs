@@ -1,5 +1,8 @@
-use crate::messages::SignedEnvelope;
+use crate::messages::{SignedEnvelope, SignedEnvelopeRef};
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use thiserror::Error;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
@@ -10,6 +13,31 @@ pub const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024; // 16MB
 pub const LENGTH_PREFIX_SIZE: usize = 4; // 4 bytes for u32 length prefix
 pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
 pub const DEFAULT_WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+/// How far a peer's clock may be from ours before its messages are refused
+pub const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+/// Chess messages are small and latency-sensitive, so Nagle's algorithm is
+/// off by default
+pub const DEFAULT_TCP_NODELAY: bool = true;
+/// Messages a connection holds for sending before its
+/// [`SendQueuePolicy`] applies
+pub const DEFAULT_SEND_QUEUE_CAPACITY: usize = 64;
+
+/// What a connection does when a message is queued while its send queue is
+/// full, as it is when the peer reads slower than we write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SendQueuePolicy {
+    /// Send the next queued message first, waiting on the peer up to the
+    /// write timeout
+    #[default]
+    Block,
+    /// Drop the oldest queued chat message (ping with a payload) to make
+    /// room; moves and other game messages are never dropped, so with no
+    /// chat queued the message is refused as with `Error`
+    DropOldestChat,
+    /// Refuse the new message
+    Error,
+}
 
 // Network-specific default configurations for Step 5.1
 // These provide appropriate defaults optimized for network operations
@@ -28,6 +56,20 @@ pub const CONNECTION_IDLE_TIMEOUT: Duration = Duration::from_secs(600); // 10 mi
 pub const SERVER_ACCEPT_TIMEOUT: Duration = Duration::from_millis(100);
 pub const SERVER_MAX_CONCURRENT_CONNECTIONS: usize = 1000;
 pub const SERVER_CONNECTION_BACKLOG: usize = 128;
+/// How long a new connection may stay silent before sending its first byte
+pub const SERVER_FIRST_BYTE_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long after being accepted a connection must have completed its
+/// handshake, however steadily it trickles in bytes
+pub const SERVER_HANDSHAKE_DEADLINE: Duration = NETWORK_DEFAULT_HANDSHAKE_TIMEOUT;
+/// Connections that may be mid-handshake at once; more are turned away so
+/// slow clients cannot hold every slot
+pub const SERVER_MAX_PENDING_HANDSHAKES: usize = 64;
+/// Messages a connection may have handled per second once its burst is spent
+pub const SERVER_MESSAGES_PER_SECOND: u32 = 100;
+/// Messages a connection may send back to back before its rate applies
+pub const SERVER_MESSAGE_BURST: u32 = 200;
+/// Time per second the server may spend handling one connection's messages
+pub const SERVER_HANDLING_TIME_PER_SECOND: Duration = Duration::from_millis(250);
 
 // Client-specific configuration
 pub const CLIENT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
@@ -54,12 +96,27 @@ pub const SUSPICIOUS_MESSAGE_THRESHOLD: usize = 8 * 1024 * 1024; // 8MB threshol
 pub const MAX_CONCURRENT_CONNECTIONS: usize = 1000; // TODO: Implement connection limiting
 pub const MAX_ALLOCATION_SIZE: usize = MAX_MESSAGE_SIZE; // Maximum single allocation
 
+// Buffer reuse
+/// Buffers a `FramedMessage` keeps for reuse between messages
+pub const BUFFER_POOL_SIZE: usize = 4;
+/// Largest buffer returned to the pool; buffers grown by rare large messages
+/// are freed instead of being held for the life of the connection
+pub const MAX_POOLED_BUFFER_SIZE: usize = NETWORK_DEFAULT_MESSAGE_SIZE;
+
 /// Configuration for wire protocol operations including timeouts and message size limits
 #[derive(Debug, Clone)]
 pub struct WireConfig {
     pub max_message_size: usize,
     pub read_timeout: Duration,
     pub write_timeout: Duration,
+    /// Largest difference between a peer's clock and ours that is accepted
+    pub max_clock_skew: Duration,
+    /// Send small frames immediately rather than letting TCP batch them
+    /// (`TCP_NODELAY`)
+    pub tcp_nodelay: bool,
+    /// Messages a connection queues before `send_queue_policy` applies
+    pub send_queue_capacity: usize,
+    pub send_queue_policy: SendQueuePolicy,
 }
 
 impl Default for WireConfig {
@@ -68,6 +125,10 @@ impl Default for WireConfig {
             max_message_size: MAX_MESSAGE_SIZE,
             read_timeout: DEFAULT_READ_TIMEOUT,
             write_timeout: DEFAULT_WRITE_TIMEOUT,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 }
@@ -79,6 +140,10 @@ impl WireConfig {
             max_message_size,
             read_timeout,
             write_timeout,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -88,6 +153,10 @@ impl WireConfig {
             max_message_size,
             read_timeout: DEFAULT_READ_TIMEOUT,
             write_timeout: DEFAULT_WRITE_TIMEOUT,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -97,6 +166,10 @@ impl WireConfig {
             max_message_size: MAX_MESSAGE_SIZE,
             read_timeout,
             write_timeout,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -106,9 +179,35 @@ impl WireConfig {
             max_message_size: MAX_MESSAGE_SIZE,
             read_timeout: timeout,
             write_timeout: timeout,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
+    /// The same configuration accepting peers whose clocks differ from ours
+    /// by up to `max_clock_skew`
+    pub fn with_max_clock_skew(mut self, max_clock_skew: Duration) -> Self {
+        self.max_clock_skew = max_clock_skew;
+        self
+    }
+
+    /// The same configuration with `TCP_NODELAY` set to `tcp_nodelay` on
+    /// connections
+    pub fn with_tcp_nodelay(mut self, tcp_nodelay: bool) -> Self {
+        self.tcp_nodelay = tcp_nodelay;
+        self
+    }
+
+    /// The same configuration with connections queueing up to `capacity`
+    /// messages before `policy` applies
+    pub fn with_send_queue(mut self, capacity: usize, policy: SendQueuePolicy) -> Self {
+        self.send_queue_capacity = capacity;
+        self.send_queue_policy = policy;
+        self
+    }
+
     // Step 5.1: Network-specific configuration presets for appropriate defaults
 
     /// Create a WireConfig optimized for network operations with standard timeouts
@@ -118,6 +217,10 @@ impl WireConfig {
             max_message_size: NETWORK_DEFAULT_MESSAGE_SIZE,
             read_timeout: NETWORK_DEFAULT_READ_TIMEOUT,
             write_timeout: NETWORK_DEFAULT_WRITE_TIMEOUT,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -128,6 +231,10 @@ impl WireConfig {
             max_message_size: NETWORK_SMALL_MESSAGE_SIZE,
             read_timeout: NETWORK_DEFAULT_HANDSHAKE_TIMEOUT,
             write_timeout: NETWORK_DEFAULT_HANDSHAKE_TIMEOUT,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -138,6 +245,10 @@ impl WireConfig {
             max_message_size: NETWORK_LARGE_MESSAGE_SIZE,
             read_timeout: Duration::from_secs(120), // 2 minutes for large messages
             write_timeout: Duration::from_secs(120),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -148,6 +259,10 @@ impl WireConfig {
             max_message_size: NETWORK_DEFAULT_MESSAGE_SIZE,
             read_timeout: NETWORK_DEFAULT_READ_TIMEOUT,
             write_timeout: NETWORK_DEFAULT_WRITE_TIMEOUT,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -158,6 +273,10 @@ impl WireConfig {
             max_message_size: NETWORK_DEFAULT_MESSAGE_SIZE,
             read_timeout: Duration::from_secs(20), // Slightly shorter for clients
             write_timeout: Duration::from_secs(20),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -168,6 +287,10 @@ impl WireConfig {
             max_message_size: NETWORK_SMALL_MESSAGE_SIZE, // Handshakes should be small
             read_timeout: NETWORK_DEFAULT_HANDSHAKE_TIMEOUT,
             write_timeout: NETWORK_DEFAULT_HANDSHAKE_TIMEOUT,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -179,6 +302,10 @@ impl WireConfig {
             max_message_size: MAX_MESSAGE_SIZE, // Allow maximum size for tests
             read_timeout: Duration::from_secs(60), // Long timeouts for debugging
             write_timeout: Duration::from_secs(60),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -189,6 +316,10 @@ impl WireConfig {
             max_message_size: NETWORK_DEFAULT_MESSAGE_SIZE, // Conservative 1MB limit
             read_timeout: NETWORK_DEFAULT_READ_TIMEOUT,
             write_timeout: NETWORK_DEFAULT_WRITE_TIMEOUT,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -202,6 +333,10 @@ impl WireConfig {
             max_message_size: NETWORK_SMALL_MESSAGE_SIZE, // 64KB - more than enough for standard chess messages
             read_timeout: Duration::from_secs(15),        // Faster timeout for responsive gameplay
             write_timeout: Duration::from_secs(15),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -213,6 +348,10 @@ impl WireConfig {
             max_message_size: NETWORK_LARGE_MESSAGE_SIZE, // 8MB - handles games with extensive move history
             read_timeout: Duration::from_secs(60),        // Extended timeout for large sync data
             write_timeout: Duration::from_secs(60),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -224,6 +363,10 @@ impl WireConfig {
             max_message_size: MAX_MESSAGE_SIZE, // Full 16MB - for tournament or bulk operations
             read_timeout: Duration::from_secs(120), // Very extended timeout for bulk operations
             write_timeout: Duration::from_secs(120),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 
@@ -235,6 +378,10 @@ impl WireConfig {
             max_message_size: NETWORK_DEFAULT_MESSAGE_SIZE, // 1MB - balanced for real-time use
             read_timeout: Duration::from_secs(10), // Very fast timeout for real-time responsiveness
             write_timeout: Duration::from_secs(10),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            tcp_nodelay: DEFAULT_TCP_NODELAY,
+            send_queue_capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            send_queue_policy: SendQueuePolicy::Block,
         }
     }
 }
@@ -532,10 +679,141 @@ impl From<anyhow::Error> for WireProtocolError {
     }
 }
 
+/// Buffers reused for framing messages, so a steady stream of messages
+/// does not allocate a new buffer for each one
+///
+/// Clones share the same buffers.
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    buffers: Arc<Mutex<Vec<Vec<u8>>>>,
+    max_buffers: usize,
+    max_buffer_size: usize,
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(BUFFER_POOL_SIZE, MAX_POOLED_BUFFER_SIZE)
+    }
+}
+
+impl BufferPool {
+    /// Keep up to `max_buffers` buffers of at most `max_buffer_size` bytes
+    pub fn new(max_buffers: usize, max_buffer_size: usize) -> Self {
+        Self {
+            buffers: Arc::new(Mutex::new(Vec::with_capacity(max_buffers))),
+            max_buffers,
+            max_buffer_size,
+        }
+    }
+
+    /// An empty buffer, reusing a pooled one when available
+    pub fn take(&self) -> PooledBuffer {
+        let buffer = self
+            .buffers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop()
+            .unwrap_or_default();
+        PooledBuffer {
+            buffer,
+            pool: self.clone(),
+        }
+    }
+
+    /// Number of buffers waiting to be reused
+    pub fn available(&self) -> usize {
+        self.buffers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+
+    fn give_back(&self, mut buffer: Vec<u8>) {
+        if buffer.capacity() == 0 || buffer.capacity() > self.max_buffer_size {
+            return;
+        }
+        buffer.clear();
+        let mut buffers = self
+            .buffers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if buffers.len() < self.max_buffers {
+            buffers.push(buffer);
+        }
+    }
+}
+
+/// A buffer on loan from a [`BufferPool`], returned to it when dropped
+#[derive(Debug)]
+pub struct PooledBuffer {
+    buffer: Vec<u8>,
+    pool: BufferPool,
+}
+
+impl Deref for PooledBuffer {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.buffer
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        self.pool.give_back(std::mem::take(&mut self.buffer));
+    }
+}
+
+/// The bytes of one message read from the wire, without its length prefix
+///
+/// The frame owns a pooled buffer, which goes back to the pool when the
+/// frame is dropped.
+#[derive(Debug)]
+pub struct Frame {
+    buffer: PooledBuffer,
+}
+
+impl Frame {
+    /// Deserialize the envelope, borrowing its message bytes, signature, and
+    /// sender from this frame instead of copying them
+    pub fn envelope(&self) -> Result<SignedEnvelopeRef<'_>, WireProtocolError> {
+        decode_envelope(&self.buffer)
+    }
+}
+
+impl Deref for Frame {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+/// Decode a serialized envelope, owned or borrowed from `data`
+fn decode_envelope<'a, T: Deserialize<'a>>(data: &'a [u8]) -> Result<T, WireProtocolError> {
+    bincode::deserialize(data).map_err(|e| {
+        error!(
+            error = %e,
+            data_size = data.len(),
+            "Failed to deserialize data to SignedEnvelope"
+        );
+        WireProtocolError::CorruptedData {
+            reason: format!("Failed to deserialize SignedEnvelope: {e}"),
+        }
+    })
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct FramedMessage {
     wire_config: WireConfig,
     dos_config: DosProtectionConfig,
+    buffers: BufferPool,
 }
 
 impl FramedMessage {
@@ -549,6 +827,7 @@ impl FramedMessage {
         Self {
             wire_config,
             dos_config,
+            buffers: BufferPool::default(),
         }
     }
 
@@ -557,6 +836,7 @@ impl FramedMessage {
         Self {
             wire_config,
             dos_config,
+            buffers: BufferPool::default(),
         }
     }
 
@@ -570,6 +850,7 @@ impl FramedMessage {
         Self {
             wire_config,
             dos_config: config,
+            buffers: BufferPool::default(),
         }
     }
 
@@ -581,6 +862,7 @@ impl FramedMessage {
         Self {
             wire_config,
             dos_config,
+            buffers: BufferPool::default(),
         }
     }
 
@@ -592,6 +874,7 @@ impl FramedMessage {
         Self {
             wire_config,
             dos_config,
+            buffers: BufferPool::default(),
         }
     }
 
@@ -606,6 +889,7 @@ impl FramedMessage {
         Self {
             wire_config,
             dos_config,
+            buffers: BufferPool::default(),
         }
     }
 
@@ -624,6 +908,11 @@ impl FramedMessage {
         &self.dos_config
     }
 
+    /// Buffers reused between the messages framed by this instance
+    pub fn buffer_pool(&self) -> &BufferPool {
+        &self.buffers
+    }
+
     /// Get the read timeout from wire configuration
     pub fn read_timeout(&self) -> Duration {
         self.wire_config.read_timeout
@@ -713,8 +1002,20 @@ impl FramedMessage {
     }
 
     /// Safe memory allocation with DoS protection
-    #[instrument(level = "trace", skip(self), fields(size, max_allocation = self.dos_config.max_allocation_size))]
     fn safe_allocate(&self, size: usize) -> Result<Vec<u8>, WireProtocolError> {
+        let mut buffer = Vec::new();
+        self.safe_allocate_into(&mut buffer, size)?;
+        Ok(buffer)
+    }
+
+    /// Resize `buffer` to `size` zeroed bytes, with the same DoS protection
+    /// as [`Self::safe_allocate`], reusing its capacity
+    #[instrument(level = "trace", skip(self, buffer), fields(size, max_allocation = self.dos_config.max_allocation_size))]
+    fn safe_allocate_into(
+        &self,
+        buffer: &mut Vec<u8>,
+        size: usize,
+    ) -> Result<(), WireProtocolError> {
         tracing::Span::current().record("size", size);
 
         // Validate allocation size against configured limits
@@ -742,32 +1043,40 @@ impl FramedMessage {
 
         trace!("Allocating {} bytes for message buffer", size);
 
-        // Attempt allocation with error handling
-        // Initialize the vector to the required size
-        let vec = vec![0; size];
+        // Initialize the buffer to the required size, only allocating when
+        // its capacity is too small
+        buffer.clear();
+        buffer.resize(size, 0);
         debug!("Successfully allocated {} byte buffer", size);
-        Ok(vec)
+        Ok(())
     }
 
-    /// Serialize a SignedEnvelope to bytes with enhanced DoS protection
-    #[instrument(level = "trace", skip(self, envelope), fields(envelope_size))]
-    fn serialize_envelope(&self, envelope: &SignedEnvelope) -> Result<Vec<u8>, WireProtocolError> {
+    /// Append a SignedEnvelope's bytes to `buffer` with enhanced DoS protection,
+    /// returning how many bytes were added
+    #[instrument(level = "trace", skip(self, envelope, buffer), fields(envelope_size))]
+    fn serialize_envelope_into(
+        &self,
+        envelope: &SignedEnvelope,
+        buffer: &mut Vec<u8>,
+    ) -> Result<usize, WireProtocolError> {
         trace!("Starting envelope serialization with DoS protection");
 
-        // Serialize the envelope using bincode
-        let serialized = bincode::serialize(envelope).map_err(|e| {
+        // Serialize the envelope using bincode, straight into the buffer
+        let start = buffer.len();
+        bincode::serialize_into(&mut *buffer, envelope).map_err(|e| {
             error!(error = %e, "Failed to serialize SignedEnvelope with bincode");
             WireProtocolError::Serialization(e)
         })?;
+        let serialized_len = buffer.len() - start;
 
-        tracing::Span::current().record("envelope_size", serialized.len());
+        tracing::Span::current().record("envelope_size", serialized_len);
 
         // Validate serialized message size against DoS protection
-        self.validate_message_size(serialized.len())?;
+        self.validate_message_size(serialized_len)?;
 
-        debug!("Serialized envelope to {} bytes", serialized.len());
+        debug!("Serialized envelope to {} bytes", serialized_len);
         trace!("Envelope serialization completed successfully with DoS validation");
-        Ok(serialized)
+        Ok(serialized_len)
     }
 
     /// Deserialize bytes back to SignedEnvelope with enhanced DoS protection
@@ -778,17 +1087,9 @@ impl FramedMessage {
         // Validate data size against DoS protection
         self.validate_message_size(data.len())?;
 
-        // Deserialize the data using bincode
-        let envelope = bincode::deserialize(data).map_err(|e| {
-            error!(
-                error = %e,
-                data_size = data.len(),
-                "Failed to deserialize data to SignedEnvelope"
-            );
-            WireProtocolError::CorruptedData {
-                reason: format!("Failed to deserialize SignedEnvelope: {e}"),
-            }
-        })?;
+        // Decode borrowing from `data` first: lengths are then checked
+        // against the bytes actually present before anything is allocated
+        let envelope = decode_envelope::<SignedEnvelopeRef>(data)?.to_owned_envelope();
 
         debug!(
             "Successfully deserialized envelope from {} bytes",
@@ -1002,13 +1303,15 @@ impl FramedMessage {
         let start_time = std::time::Instant::now();
         debug!("Starting message write operation with DoS protection");
 
-        // Serialize the envelope to bytes with enhanced validation
-        let message_bytes = self
-            .serialize_envelope(envelope)
-            .with_context(|| "Failed to serialize envelope for writing")?;
+        // Frame the message in a reused buffer: room for the length prefix,
+        // then the envelope serialized with enhanced validation
+        let mut frame = self.buffers.take();
+        frame.extend_from_slice(&[0; LENGTH_PREFIX_SIZE]);
+        let message_length =
+            self.serialize_envelope_into(envelope, &mut frame)
+                .with_context(|| "Failed to serialize envelope for writing")? as u32;
 
         // Record message size for metrics
-        let message_length = message_bytes.len() as u32;
         tracing::Span::current().record("message_size", message_length);
 
         debug!(
@@ -1016,20 +1319,14 @@ impl FramedMessage {
             message_length
         );
 
-        // Create the 4-byte length prefix (big-endian)
-        let length_prefix = message_length.to_be_bytes();
+        // Fill in the 4-byte length prefix (big-endian)
+        frame[..LENGTH_PREFIX_SIZE].copy_from_slice(&message_length.to_be_bytes());
 
-        // Write the length prefix first with recovery logic
-        Self::write_all_with_recovery(writer, &length_prefix)
-            .await
-            .with_context(|| format!("Failed to write 4-byte length prefix ({message_length})"))?;
-
-        // Write the message bytes with recovery logic
-        Self::write_all_with_recovery(writer, &message_bytes)
+        // Write the length prefix and message bytes together with recovery logic
+        Self::write_all_with_recovery(writer, &frame)
             .await
             .with_context(|| {
-                let message_bytes_len = message_bytes.len();
-                format!("Failed to write message data ({message_bytes_len} bytes)")
+                format!("Failed to write message ({message_length} bytes plus length prefix)")
             })?;
 
         // Ensure all data is flushed to the underlying writer
@@ -1052,6 +1349,24 @@ impl FramedMessage {
         &self,
         reader: &mut (impl AsyncRead + Unpin),
     ) -> Result<SignedEnvelope> {
+        let frame = self.read_frame(reader).await?;
+
+        // Deserialize the message bytes back to SignedEnvelope with enhanced validation
+        let envelope = self
+            .deserialize_envelope(&frame)
+            .with_context(|| format!("Failed to deserialize {} byte message", frame.len()))?;
+
+        debug!("Message read operation completed successfully with DoS protection");
+        Ok(envelope)
+    }
+
+    /// Read one frame without deserializing it
+    ///
+    /// The frame's envelope can then be decoded in place with
+    /// [`Frame::envelope`], so large messages are not copied out of the
+    /// buffer before their own deserialization.
+    #[instrument(level = "debug", skip(self, reader))]
+    pub async fn read_frame(&self, reader: &mut (impl AsyncRead + Unpin)) -> Result<Frame> {
         debug!("Starting message read operation with DoS protection");
 
         // Read the 4-byte length prefix with recovery logic
@@ -1069,9 +1384,9 @@ impl FramedMessage {
             .validate_length(message_length)
             .with_context(|| format!("Invalid message length received: {message_length}"))?;
 
-        // Safe allocation with DoS protection
-        let mut message_buffer = self
-            .safe_allocate(validated_length)
+        // Safe allocation with DoS protection, reusing a pooled buffer
+        let mut message_buffer = self.buffers.take();
+        self.safe_allocate_into(&mut message_buffer, validated_length)
             .with_context(|| format!("Failed to allocate {validated_length} byte buffer"))?;
         debug!(
             "Safely allocated buffer for {} byte message",
@@ -1085,13 +1400,9 @@ impl FramedMessage {
                 format!("Failed to read message data: expected {validated_length} bytes")
             })?;
 
-        // Deserialize the message bytes back to SignedEnvelope with enhanced validation
-        let envelope = self
-            .deserialize_envelope(&message_buffer)
-            .with_context(|| format!("Failed to deserialize {validated_length} byte message"))?;
-
-        debug!("Message read operation completed successfully with DoS protection");
-        Ok(envelope)
+        Ok(Frame {
+            buffer: message_buffer,
+        })
     }
 
     /// Read a message with a timeout using enhanced DoS protection
@@ -1196,6 +1507,17 @@ impl FramedMessage {
         }
     }
 
+    /// Read a frame using the configured default timeout and DoS protection
+    #[instrument(level = "debug", skip(self, reader), fields(timeout_secs = self.wire_config.read_timeout.as_secs()))]
+    pub async fn read_frame_with_default_timeout(
+        &self,
+        reader: &mut (impl AsyncRead + Unpin),
+    ) -> Result<Frame> {
+        tokio::time::timeout(self.wire_config.read_timeout, self.read_frame(reader))
+            .await
+            .map_err(WireProtocolError::from)?
+    }
+
     /// Read a message using the configured default timeout and DoS protection
     ///
     /// Convenience method that uses the read timeout from the wire configuration.
@@ -2379,7 +2701,7 @@ impl RetryStrategy {
     /// Get the appropriate strategy for a CLI operation
     pub fn for_cli_operation(operation: &str) -> Self {
         match operation {
-            "invite" | "accept" | "move" => RetryStrategy::Normal,
+            "invite" | "accept" | "move" | "resign" | "draw" => RetryStrategy::Normal,
             "games" | "board" | "history" => RetryStrategy::NoRetry,
             "sync" => RetryStrategy::Patient,
             _ => RetryStrategy::Quick,
@@ -2414,6 +2736,7 @@ impl FailureClass {
             || error_str.contains("name resolution failed")
             || error_str.contains("temporary failure in name resolution")
             || error_str.contains("could not resolve hostname")
+            || error_str.contains("peer clock is") // Clock skew persists until fixed
             || error_str.contains("connection refused")  // Often means service not running
             || error_str.contains("network unreachable") // Network config issue
             || error_str.contains("no route to host")
@@ -1,6 +1,6 @@
 use crate::crypto::Identity;
 use crate::messages::{
-    wire::{FailureClass, RetryStrategy, WireConfig, FAST_FAIL_CONNECTION_TIMEOUT},
+    wire::{FailureClass, RetryConfig, RetryStrategy, WireConfig, FAST_FAIL_CONNECTION_TIMEOUT},
     Message,
 };
 use crate::network::Connection;
@@ -144,46 +144,67 @@ impl Client {
         addr: &str,
         strategy: RetryStrategy,
     ) -> Result<Connection> {
-        info!("Attempting to connect to {}", addr);
-        debug!(
-            "Using wire config - max_message_size: {}, read_timeout: {:?}, write_timeout: {:?}",
-            self.wire_config.max_message_size,
-            self.wire_config.read_timeout,
-            self.wire_config.write_timeout
-        );
-
         // Use strategy-appropriate retry counts and delays
-        let max_retry_attempts = match strategy {
+        let max_attempts = match strategy {
             RetryStrategy::NoRetry => 1,
             RetryStrategy::Quick => 2, // Quick: 2 attempts, ~3 seconds total
             RetryStrategy::Normal => 3, // Normal: 3 attempts, ~7 seconds total
             RetryStrategy::Patient => 4, // Patient: 4 attempts, ~15 seconds total
         };
 
-        let base_retry_delay = match strategy {
+        let base_delay = match strategy {
             RetryStrategy::NoRetry => Duration::from_millis(0),
             RetryStrategy::Quick => Duration::from_millis(500), // 0.5s base delay
             RetryStrategy::Normal => Duration::from_millis(1000), // 1s base delay
             RetryStrategy::Patient => Duration::from_millis(2000), // 2s base delay
         };
 
+        let retry_config = RetryConfig {
+            max_attempts,
+            base_delay,
+            ..RetryConfig::default()
+        };
+        self.connect_with_retry_config(addr, &retry_config).await
+    }
+
+    /// Connect to a peer, making up to `retry_config.max_attempts` attempts
+    ///
+    /// Attempts are spaced by `retry_config.calculate_delay`; errors that
+    /// retrying cannot fix, such as an unknown host, fail immediately.
+    #[instrument(level = "info", skip(self, retry_config), fields(connection_id = tracing::field::Empty))]
+    pub async fn connect_with_retry_config(
+        &self,
+        addr: &str,
+        retry_config: &RetryConfig,
+    ) -> Result<Connection> {
+        info!("Attempting to connect to {}", addr);
+        debug!(
+            "Using wire config - max_message_size: {}, read_timeout: {:?}, write_timeout: {:?}",
+            self.wire_config.max_message_size,
+            self.wire_config.read_timeout,
+            self.wire_config.write_timeout
+        );
+
+        let max_retry_attempts = retry_config.max_attempts.max(1);
         let mut last_error = None;
         let mut failure_class: FailureClass;
 
         for attempt in 1..=max_retry_attempts {
             debug!(
-                "Connection attempt {} of {} to {} (strategy: {:?})",
-                attempt, max_retry_attempts, addr, strategy
+                "Connection attempt {} of {} to {}",
+                attempt, max_retry_attempts, addr
             );
 
             match self.try_connect_once_with_fast_fail(addr).await {
                 Ok(mut connection) => {
+                    tracing::Span::current().record("connection_id", connection.connection_id());
                     info!("TCP connection established to {}, starting handshake", addr);
 
                     // Perform client-side handshake
                     match connection.handshake().await {
                         Ok(peer_id) => {
                             info!("Handshake completed successfully with peer: {}", peer_id);
+                            connection.record_retries(u64::from(attempt - 1));
                             debug!(
                                 "Connection fully established to {} (peer: {})",
                                 addr, peer_id
@@ -192,7 +213,7 @@ impl Client {
                         }
                         Err(e) => {
                             error!("Handshake failed with {}: {}", addr, e);
-                            last_error = Some(anyhow::anyhow!("Handshake failed: {}", e));
+                            last_error = Some(anyhow::anyhow!("Handshake failed: {:#}", e));
                             failure_class =
                                 FailureClass::classify_error(last_error.as_ref().unwrap());
 
@@ -232,10 +253,14 @@ impl Client {
             }
 
             // Add exponential backoff delay before retry (except for last attempt)
-            if attempt < max_retry_attempts && base_retry_delay > Duration::from_millis(0) {
-                let delay_ms = base_retry_delay.as_millis() * (2_u128.pow(attempt - 1)); // Exponential backoff
-                debug!("Retrying connection to {} in {} ms", addr, delay_ms);
-                tokio::time::sleep(std::time::Duration::from_millis(delay_ms as u64)).await;
+            let delay = retry_config.calculate_delay(attempt);
+            if attempt < max_retry_attempts && delay > Duration::from_millis(0) {
+                debug!(
+                    "Retrying connection to {} in {} ms",
+                    addr,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
             }
         }
 
@@ -243,8 +268,8 @@ impl Client {
         let final_error =
             last_error.unwrap_or_else(|| anyhow::anyhow!("Unknown connection failure"));
         error!(
-            "Failed to connect to {} after {} attempts (strategy: {:?}): {}",
-            addr, max_retry_attempts, strategy, final_error
+            "Failed to connect to {} after {} attempts: {}",
+            addr, max_retry_attempts, final_error
         );
         Err(final_error)
     }
@@ -304,7 +329,10 @@ impl Client {
         )
         .await;
 
-        debug!("Connection object created with custom wire config");
+        debug!(
+            connection_id = connection.connection_id(),
+            "Connection object created with custom wire config"
+        );
         Ok(connection)
     }
 
@@ -456,11 +484,7 @@ impl Client {
         };
 
         let total_bytes_sent = message_sizes.iter().sum::<usize>();
-        let avg_message_size = if successful_echoes > 0 {
-            total_bytes_sent / successful_echoes
-        } else {
-            0
-        };
+        let avg_message_size = total_bytes_sent.checked_div(successful_echoes).unwrap_or(0);
 
         info!(
             "Echo session completed: {}/{} successful ({:.1}% success rate)",
@@ -549,7 +573,7 @@ impl Client {
     /// 5. Close connection
     ///
     /// For multiple messages to the same peer, prefer using `connect()` and reusing the connection.
-    #[instrument(level = "info", skip(self, message), fields(addr = addr, msg_type = message.message_type(), local_peer = self.identity.peer_id().as_str()))]
+    #[instrument(level = "info", skip(self, message), fields(addr = addr, msg_type = message.message_type(), game_id = message.get_game_id(), local_peer = self.identity.peer_id().as_str(), connection_id = tracing::field::Empty))]
     pub async fn send_message_to(&self, addr: &str, message: Message) -> Result<Message> {
         info!("Starting one-shot message send to {}", addr);
         debug!(
@@ -564,6 +588,7 @@ impl Client {
             .connect(addr)
             .await
             .with_context(|| format!("Failed to connect to {addr}"))?;
+        tracing::Span::current().record("connection_id", connection.connection_id());
 
         let peer_id = connection.peer_identity().unwrap_or("unknown").to_string();
 
@@ -634,6 +659,50 @@ impl Client {
         Ok(response)
     }
 
+    /// Ask the server at `addr` to pass `text` on to every peer connected to it
+    ///
+    /// Servers only take announcements from their own key, so this must run
+    /// with the identity the server was started with. A ping follows the
+    /// announcement; its echo means the server has sent it on.
+    #[instrument(level = "info", skip(self, text), fields(addr = addr, local_peer = self.identity.peer_id().as_str()))]
+    pub async fn announce(&self, addr: &str, text: &str) -> Result<()> {
+        let announcement = Message::new_server_announcement(text.to_string());
+        announcement
+            .validate()
+            .map_err(|e| anyhow::anyhow!("Invalid announcement: {e}"))?;
+
+        let mut connection = self
+            .connect(addr)
+            .await
+            .with_context(|| format!("Failed to connect to {addr}"))?;
+        if connection.peer_identity() != Some(self.identity.peer_id().as_str()) {
+            let _ = connection.close().await;
+            anyhow::bail!(
+                "The server at {addr} runs with a different identity; announce with the key it was started with"
+            );
+        }
+
+        connection
+            .send_message(announcement)
+            .await
+            .with_context(|| format!("Failed to send announcement to {addr}"))?;
+        let nonce = rand::random::<u64>();
+        connection
+            .send_message(Message::new_ping(nonce, String::new()))
+            .await
+            .with_context(|| format!("Failed to send message to {addr}"))?;
+        connection
+            .receive_message()
+            .await
+            .with_context(|| format!("Failed to receive response from {addr}"))?;
+
+        if let Err(e) = connection.close().await {
+            warn!("Failed to cleanly close connection to {}: {}", addr, e);
+        }
+        info!("Announcement sent to {}", addr);
+        Ok(())
+    }
+
     /// Test connection quality to a peer using echo session
     ///
     /// This method combines connection establishment with echo session testing
@@ -1,13 +1,23 @@
 use crate::crypto::Identity;
-use crate::messages::wire::{FramedMessage, WireConfig, WireProtocolError};
-use crate::messages::{Message, SignedEnvelope};
+use crate::messages::chess::ServerAnnouncement;
+use crate::messages::wire::{
+    Frame, FramedMessage, SendQueuePolicy, WireConfig, WireProtocolError, DEFAULT_MAX_CLOCK_SKEW,
+    LENGTH_PREFIX_SIZE,
+};
+use crate::messages::{Message, MessagePriority, SignedEnvelope};
+use crate::network::stats::ConnectionStats;
 use anyhow::{Context, Result};
 use rand;
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
-use tracing::{debug, error, info, instrument, warn};
+use tokio::sync::{mpsc, Semaphore, TryAcquireError};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, instrument, warn, Instrument};
 
 #[derive(Error, Debug)]
 pub enum ConnectionError {
@@ -23,10 +33,33 @@ pub enum ConnectionError {
     InvalidSignature,
     #[error("Message timestamp validation failed")]
     InvalidTimestamp,
+    #[error(
+        "Peer clock is {}s {} ours, more than the {max_secs}s allowed; check that both clocks are synchronized",
+        .offset_secs.unsigned_abs(),
+        if *.offset_secs > 0 { "ahead of" } else { "behind" }
+    )]
+    ClockSkew { offset_secs: i64, max_secs: u64 },
+    #[error("Send queue is full ({capacity} messages); the peer is not keeping up")]
+    SendQueueFull { capacity: usize },
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
+/// Frames read and verified together at most, when several have arrived
+pub const READ_AHEAD_FRAMES: usize = 64;
+
+/// Bytes of frames read ahead at most, counting the one being received, so
+/// a peer sending large frames can't make a connection hold many of them
+pub const READ_AHEAD_BYTES: usize = 1024 * 1024;
+
+/// A new random ID for a connection, logged with everything done on it
+///
+/// Both the accepting and the dialing side generate their own, so each
+/// end's logs can be followed separately.
+pub fn new_connection_id() -> String {
+    format!("{:08x}", rand::random::<u32>())
+}
+
 /// Represents an authenticated peer-to-peer connection with integrated wire protocol support.
 ///
 /// The `Connection` struct provides a secure, authenticated communication channel between peers
@@ -39,7 +72,9 @@ pub enum ConnectionError {
 /// - **Message Authentication**: All messages are automatically wrapped in `SignedEnvelope` with
 ///   cryptographic signatures. Invalid signatures are rejected automatically.
 /// - **Timestamp Validation**: Messages are validated for freshness (5-minute maximum age) to
-///   prevent replay attacks.
+///   prevent replay attacks, after correcting for the peer's clock offset measured during the
+///   handshake. Peers whose clocks differ from ours by more than the configured maximum skew
+///   are refused.
 /// - **Handshake Protocol**: Bidirectional authentication ensures both peers verify each other's
 ///   identity before message exchange.
 /// - **Nonce-based Handshake**: Handshake uses random nonces to prevent replay attacks.
@@ -57,6 +92,8 @@ pub enum ConnectionError {
 ///   - *Recovery*: Do not retry. Log security event. May indicate tampering or wrong keys.
 /// - **`InvalidTimestamp`**: Message timestamp outside acceptable window.
 ///   - *Recovery*: Check system clock synchronization. Retry with fresh message.
+/// - **`ClockSkew`**: The peer's clock differs from ours by more than `WireConfig::max_clock_skew`.
+///   - *Recovery*: Synchronize the clocks (e.g. enable NTP) on both machines.
 /// - **`ConnectionClosed`**: Peer closed connection unexpectedly.
 ///   - *Recovery*: Attempt reconnection after brief delay.
 ///
@@ -107,72 +144,197 @@ pub enum ConnectionError {
 /// `Connection` is NOT thread-safe. For concurrent access, wrap in appropriate synchronization
 /// primitives or use separate connections per thread.
 pub struct Connection {
-    stream: TcpStream,
+    reader: OwnedReadHalf,
+    /// Correlates the log lines and spans of this connection
+    connection_id: String,
     peer_id: Option<String>,
     identity: Arc<Identity>,
     framed_message: FramedMessage,
+    /// Sender's clock when it signed the last message received, in Unix seconds
+    last_received_timestamp: Option<u64>,
+    /// Nonce of the handshake that opened this connection, shared by both ends
+    session_id: Option<u64>,
+    /// Largest difference between the peer's clock and ours that is accepted
+    max_clock_skew: Duration,
+    /// Peer's clock minus ours in seconds, estimated during the handshake
+    clock_offset: Option<i64>,
+    /// When the oldest unanswered message was sent, on connections we opened
+    awaiting_reply_since: Option<Instant>,
+    /// Whether we opened this connection, so replies can be timed
+    initiator: bool,
+    /// Room in the send queue, and the traffic counters, shared with the
+    /// writer task
+    send_queue: Arc<SendQueue>,
+    /// The writer task, started by the first message sent
+    writer: Writer,
+    /// Server announcements received while waiting for a reply, with their
+    /// senders, on connections we opened
+    announcements: Vec<(ServerAnnouncement, String)>,
+    /// Messages about other games received while waiting for a reply, by
+    /// game ID, with their senders
+    other_games: HashMap<String, VecDeque<(Message, String)>>,
+    /// Envelopes read ahead of the one being received, with whether their
+    /// signature is valid and their frame size
+    read_ahead: VecDeque<(SignedEnvelope, bool, usize)>,
 }
 
-impl Connection {
-    pub async fn new(stream: TcpStream, identity: Arc<Identity>) -> Self {
-        info!("Creating new connection with default network configuration");
-
-        // Initialize FramedMessage with network-optimized default configuration (Step 5.1)
-        let framed_message = FramedMessage::for_network();
-
-        debug!(
-            "Connection initialized with peer address: {:?}, using network-optimized config",
-            stream
-                .peer_addr()
-                .unwrap_or_else(|_| "unknown".parse().unwrap())
-        );
+/// What a connection and its writer task both keep track of
+#[derive(Debug)]
+struct SendQueue {
+    /// One permit for each message that may still be queued; the writer
+    /// gives them back as messages are sent
+    room: Semaphore,
+    capacity: usize,
+    /// Queued chat messages a full queue may still drop: not yet being
+    /// sent, nor claimed by a message taking their place
+    chats: AtomicUsize,
+    /// Traffic on this connection so far
+    stats: Mutex<ConnectionStats>,
+}
 
+impl SendQueue {
+    fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
         Self {
-            stream,
-            peer_id: None, // Will be set during handshake
-            identity,
-            framed_message,
+            room: Semaphore::new(capacity),
+            capacity,
+            chats: AtomicUsize::new(0),
+            stats: Mutex::new(ConnectionStats::default()),
         }
     }
 
-    /// Create a new Connection with custom WireConfig for advanced configuration
-    pub async fn new_with_config(
-        stream: TcpStream,
-        identity: Arc<Identity>,
-        wire_config: WireConfig,
-    ) -> Self {
-        info!("Creating new connection with custom wire config");
+    fn stats(&self) -> std::sync::MutexGuard<'_, ConnectionStats> {
+        // Counters stay usable even if a holder panicked
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
 
-        // Initialize FramedMessage with custom WireConfig
-        let framed_message = FramedMessage::new(wire_config);
+    /// Take one queued chat message off the droppable count, if any is left
+    fn claim_chat(&self) -> bool {
+        self.chats
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+}
 
-        debug!(
-            "Connection initialized with custom config and peer address: {:?}",
-            stream
-                .peer_addr()
-                .unwrap_or_else(|_| "unknown".parse().unwrap())
-        );
+/// A queued message, and whether it takes the place of the oldest queued
+/// chat message
+type Queued = (Message, bool);
+
+/// A connection's writer task, or what it needs to start
+enum Writer {
+    Idle(Box<MessageWriter>),
+    Running {
+        outgoing: mpsc::Sender<Queued>,
+        task: Option<JoinHandle<Result<(), ConnectionError>>>,
+    },
+}
 
-        Self {
-            stream,
-            peer_id: None, // Will be set during handshake
-            identity,
-            framed_message,
+/// The write half of a connection, which signs and sends the queued
+/// messages one at a time
+struct MessageWriter {
+    stream: OwnedWriteHalf,
+    identity: Arc<Identity>,
+    framed_message: FramedMessage,
+    send_queue: Arc<SendQueue>,
+    lanes: SendLanes,
+    /// Claimed chat messages that were sent before the messages taking
+    /// their place arrived; those messages take their room instead
+    claimed_sent: usize,
+}
+
+/// A connection's send queue, one lane per [`MessagePriority`]
+#[derive(Debug, Default)]
+struct SendLanes {
+    lanes: [VecDeque<Message>; 4],
+}
+
+impl SendLanes {
+    fn push(&mut self, msg: Message) {
+        self.lanes[msg.priority() as usize].push_back(msg);
+    }
+
+    /// The next message to send: the oldest in the most urgent lane
+    fn pop_front(&mut self) -> Option<Message> {
+        self.lanes.iter_mut().find_map(|lane| lane.pop_front())
+    }
+
+    fn drop_oldest_chat(&mut self) -> bool {
+        self.lanes[MessagePriority::Chat as usize]
+            .pop_front()
+            .is_some()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lanes.iter().all(VecDeque::is_empty)
+    }
+}
+
+impl MessageWriter {
+    /// Send the messages received on `outgoing` until it is closed and the
+    /// queue is empty
+    ///
+    /// Messages that arrived while the last one was written are queued
+    /// before the next is picked, so a move submitted during a long sync
+    /// goes out after the sync message being written rather than after the
+    /// whole sync.
+    async fn run(mut self, mut outgoing: mpsc::Receiver<Queued>) -> Result<(), ConnectionError> {
+        let result = self.send_all(&mut outgoing).await;
+        // Senders waiting for room would otherwise wait forever
+        self.send_queue.room.close();
+        result
+    }
+
+    async fn send_all(
+        &mut self,
+        outgoing: &mut mpsc::Receiver<Queued>,
+    ) -> Result<(), ConnectionError> {
+        loop {
+            if self.lanes.is_empty() {
+                match outgoing.recv().await {
+                    Some(queued) => self.queue(queued),
+                    None => return Ok(()),
+                }
+            }
+            while let Ok(queued) = outgoing.try_recv() {
+                self.queue(queued);
+            }
+            if let Some(msg) = self.lanes.pop_front() {
+                // With every queued chat message claimed, this one is sent
+                // in place of dropping it
+                let claimed =
+                    msg.priority() == MessagePriority::Chat && !self.send_queue.claim_chat();
+                self.send_message(&msg).await?;
+                if claimed {
+                    self.claimed_sent += 1;
+                } else {
+                    self.send_queue.room.add_permits(1);
+                }
+            }
         }
     }
 
-    #[instrument(level = "debug", skip(self, msg), fields(msg_type = msg.message_type()))]
-    pub async fn send_message(&mut self, msg: Message) -> Result<(), ConnectionError> {
+    /// Add `msg` to its lane, dropping the chat message it replaces
+    ///
+    /// The replacing message takes the dropped message's room in the queue.
+    fn queue(&mut self, (msg, replaces_chat): Queued) {
+        if replaces_chat {
+            if self.claimed_sent > 0 {
+                self.claimed_sent -= 1;
+            } else if self.lanes.drop_oldest_chat() {
+                warn!("Send queue full, dropped the oldest queued chat message");
+            }
+        }
+        self.lanes.push(msg);
+    }
+
+    async fn send_message(&mut self, msg: &Message) -> Result<(), ConnectionError> {
         let send_start = std::time::Instant::now();
         info!("Sending {} message", msg.message_type());
-        debug!(
-            "Message nonce: {}, payload_len: {}",
-            msg.get_nonce(),
-            msg.get_payload().len()
-        );
+        // Chess messages have no nonce or payload to show
+        debug!("Message details: {}", msg.log_summary());
 
         // Create SignedEnvelope using our identity
-        let envelope = SignedEnvelope::create(&msg, &self.identity, None).map_err(|e| {
+        let envelope = SignedEnvelope::create(msg, &self.identity, None).map_err(|e| {
             error!("Failed to create signed envelope: {}", e);
             ConnectionError::WireProtocol(WireProtocolError::Serialization(bincode::Error::from(
                 std::io::Error::new(
@@ -185,9 +347,7 @@ impl Connection {
         debug!("Created signed envelope with sender: {}", envelope.sender());
 
         // Get the message size for logging
-        let envelope_size = bincode::serialize(&envelope)
-            .map(|bytes| bytes.len())
-            .unwrap_or(0);
+        let envelope_size = bincode::serialized_size(&envelope).unwrap_or(0);
 
         debug!("Envelope size: {} bytes", envelope_size);
 
@@ -196,12 +356,12 @@ impl Connection {
             .write_message_with_default_timeout(&mut self.stream, &envelope)
             .await
             .map_err(|e| {
-                error!("Failed to write message: {}", e);
-                ConnectionError::WireProtocol(WireProtocolError::WriteTimeout {
-                    timeout: Duration::from_secs(30), // Default timeout
-                })
+                error!("Failed to write message: {:#}", e);
+                write_error(e)
             })?;
 
+        self.send_queue.stats().record_sent(envelope_size as usize);
+
         let send_duration = send_start.elapsed();
         info!(
             "Successfully sent {} message ({} bytes) in {:?}",
@@ -225,28 +385,444 @@ impl Connection {
 
         Ok(())
     }
+}
 
-    #[instrument(level = "debug", skip(self), fields(peer_id = self.peer_id.as_deref()))]
-    pub async fn receive_message(&mut self) -> Result<(Message, String), ConnectionError> {
-        let receive_start = std::time::Instant::now();
-        info!("Waiting to receive message");
+/// The connection error behind a failed write, keeping its kind so a
+/// message that was too large or a peer that went away is not reported as
+/// a timeout
+fn write_error(e: anyhow::Error) -> ConnectionError {
+    let e = match e.downcast::<WireProtocolError>() {
+        Ok(e) => return ConnectionError::WireProtocol(e),
+        Err(e) => e,
+    };
+    let kind = e
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .map_or(std::io::ErrorKind::Other, std::io::Error::kind);
+    ConnectionError::Io(std::io::Error::new(kind, format!("{e:#}")))
+}
+
+/// Set `TCP_NODELAY` on a new connection's socket; a failure only costs
+/// latency, so it is logged rather than returned
+fn apply_tcp_nodelay(stream: &TcpStream, tcp_nodelay: bool) {
+    if let Err(e) = stream.set_nodelay(tcp_nodelay) {
+        warn!("Failed to set TCP_NODELAY to {}: {}", tcp_nodelay, e);
+    }
+}
+
+impl Connection {
+    pub async fn new(stream: TcpStream, identity: Arc<Identity>) -> Self {
+        info!("Creating new connection with default network configuration");
+
+        // Initialize FramedMessage with network-optimized default configuration (Step 5.1)
+        let framed_message = FramedMessage::for_network();
+        apply_tcp_nodelay(&stream, framed_message.wire_config().tcp_nodelay);
+
+        debug!(
+            "Connection initialized with peer address: {:?}, using network-optimized config",
+            stream
+                .peer_addr()
+                .unwrap_or_else(|_| "unknown".parse().unwrap())
+        );
+
+        Self::from_parts(stream, identity, framed_message, DEFAULT_MAX_CLOCK_SKEW)
+    }
+
+    /// Create a new Connection with custom WireConfig for advanced configuration
+    pub async fn new_with_config(
+        stream: TcpStream,
+        identity: Arc<Identity>,
+        wire_config: WireConfig,
+    ) -> Self {
+        info!("Creating new connection with custom wire config");
+
+        // Initialize FramedMessage with custom WireConfig
+        let max_clock_skew = wire_config.max_clock_skew;
+        apply_tcp_nodelay(&stream, wire_config.tcp_nodelay);
+        let framed_message = FramedMessage::new(wire_config);
+
+        debug!(
+            "Connection initialized with custom config and peer address: {:?}",
+            stream
+                .peer_addr()
+                .unwrap_or_else(|_| "unknown".parse().unwrap())
+        );
+
+        Self::from_parts(stream, identity, framed_message, max_clock_skew)
+    }
+
+    fn from_parts(
+        stream: TcpStream,
+        identity: Arc<Identity>,
+        framed_message: FramedMessage,
+        max_clock_skew: Duration,
+    ) -> Self {
+        let (reader, writer) = stream.into_split();
+        let send_queue = Arc::new(SendQueue::new(
+            framed_message.wire_config().send_queue_capacity,
+        ));
+        let writer = MessageWriter {
+            stream: writer,
+            identity: Arc::clone(&identity),
+            framed_message: framed_message.clone(),
+            send_queue: Arc::clone(&send_queue),
+            lanes: SendLanes::default(),
+            claimed_sent: 0,
+        };
 
-        // Use framed_message to read with default timeout
-        let envelope = self
+        Self {
+            reader,
+            connection_id: new_connection_id(),
+            peer_id: None, // Will be set during handshake
+            identity,
+            framed_message,
+            last_received_timestamp: None,
+            session_id: None,
+            max_clock_skew,
+            clock_offset: None,
+            awaiting_reply_since: None,
+            initiator: false,
+            send_queue,
+            writer: Writer::Idle(Box::new(writer)),
+            announcements: Vec::new(),
+            other_games: HashMap::new(),
+            read_ahead: VecDeque::new(),
+        }
+    }
+
+    /// Queue `msg` for the connection's writer task
+    ///
+    /// Returns once the message is queued, not once it is sent; use
+    /// [`flush`](Self::flush) to wait for that. Queued messages wait in the
+    /// lane of their [`MessagePriority`], so a move queued behind a sync
+    /// backlog is still sent first. The queue holds the wire config's
+    /// `send_queue_capacity` messages at most, so a peer that stops reading
+    /// can't make it grow without bound. When it is full,
+    /// `send_queue_policy` decides: `Block` waits for the writer to send a
+    /// message, `DropOldestChat` drops the oldest queued chat message, and
+    /// `Error` (or `DropOldestChat` with no chat queued) fails with
+    /// [`ConnectionError::SendQueueFull`]. If the writer has failed, its
+    /// error is returned; a message that fails to be sent after it was
+    /// queued fails the next receive, such as the wait for its reply, with
+    /// the same error.
+    #[instrument(level = "debug", skip(self, msg), fields(connection_id = %self.connection_id, msg_type = msg.message_type(), game_id = msg.get_game_id(), peer_id = self.peer_id.as_deref()))]
+    pub async fn send_message(&mut self, msg: Message) -> Result<(), ConnectionError> {
+        let send_queue = Arc::clone(&self.send_queue);
+        let mut replaces_chat = false;
+        match send_queue.room.try_acquire() {
+            Ok(permit) => permit.forget(),
+            Err(TryAcquireError::Closed) => return Err(self.writer_error().await),
+            Err(TryAcquireError::NoPermits) => {
+                match self.framed_message.wire_config().send_queue_policy {
+                    SendQueuePolicy::Block => {
+                        debug!("Send queue full, waiting for the writer");
+                        match send_queue.room.acquire().await {
+                            Ok(permit) => permit.forget(),
+                            Err(_) => return Err(self.writer_error().await),
+                        }
+                    }
+                    SendQueuePolicy::DropOldestChat if send_queue.claim_chat() => {
+                        replaces_chat = true;
+                    }
+                    _ => {
+                        return Err(ConnectionError::SendQueueFull {
+                            capacity: send_queue.capacity,
+                        })
+                    }
+                }
+            }
+        }
+
+        if msg.priority() == MessagePriority::Chat {
+            send_queue.chats.fetch_add(1, Ordering::SeqCst);
+        }
+        debug!("Queued {} message", msg.message_type());
+        if self.outgoing().send((msg, replaces_chat)).await.is_err() {
+            return Err(self.writer_error().await);
+        }
+
+        if self.initiator && self.awaiting_reply_since.is_none() {
+            self.awaiting_reply_since = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// Wait until every queued message has been sent
+    ///
+    /// Fails with the writer's error if a message could not be sent; the
+    /// write may have been cut short, so the connection should be dropped.
+    pub async fn flush(&mut self) -> Result<(), ConnectionError> {
+        let capacity = self.send_queue.capacity as u32;
+        // Holding every permit at once means nothing is left queued
+        let drained = self.send_queue.room.acquire_many(capacity).await.is_ok();
+        if drained {
+            Ok(())
+        } else {
+            Err(self.writer_error().await)
+        }
+    }
+
+    /// Messages queued and not yet sent, including the one being written
+    pub fn queued_messages(&self) -> usize {
+        self.send_queue.capacity - self.send_queue.room.available_permits()
+    }
+
+    /// The writer task's channel, starting the task on first use
+    fn outgoing(&mut self) -> &mpsc::Sender<Queued> {
+        if let Writer::Idle(_) = self.writer {
+            // A message replacing a chat one may wait beside it, so there
+            // may be twice as many messages in flight as there is room
+            let (outgoing, receiver) = mpsc::channel(self.send_queue.capacity * 2);
+            let Writer::Idle(writer) = std::mem::replace(
+                &mut self.writer,
+                Writer::Running {
+                    outgoing,
+                    task: None,
+                },
+            ) else {
+                unreachable!("the writer was idle");
+            };
+            let span = tracing::debug_span!("writer", connection_id = %self.connection_id);
+            let task = tokio::spawn(writer.run(receiver).instrument(span));
+            if let Writer::Running { task: slot, .. } = &mut self.writer {
+                *slot = Some(task);
+            }
+        }
+        match &self.writer {
+            Writer::Running { outgoing, .. } => outgoing,
+            Writer::Idle(_) => unreachable!("the writer was started"),
+        }
+    }
+
+    /// Why the writer task stopped
+    async fn writer_error(&mut self) -> ConnectionError {
+        let Writer::Running { task, .. } = &mut self.writer else {
+            return ConnectionError::ConnectionClosed;
+        };
+        match task.take() {
+            Some(task) => match task.await {
+                Ok(Err(e)) => e,
+                _ => ConnectionError::ConnectionClosed,
+            },
+            None => ConnectionError::ConnectionClosed,
+        }
+    }
+
+    async fn read_frame(&mut self) -> Result<Frame, ConnectionError> {
+        let read = self
             .framed_message
-            .read_message_with_default_timeout(&mut self.stream)
-            .await
-            .map_err(|e| {
+            .read_frame_with_default_timeout(&mut self.reader);
+        // A queued message that could not be sent fails the wait for its
+        // reply rather than leaving it to time out
+        let writer = match &mut self.writer {
+            Writer::Running { task, .. } => task,
+            Writer::Idle(_) => &mut None,
+        };
+        let writer_failed = async {
+            match writer {
+                Some(running) => {
+                    let result = running.await;
+                    *writer = None;
+                    match result {
+                        Ok(Err(e)) => e,
+                        _ => ConnectionError::ConnectionClosed,
+                    }
+                }
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            frame = read => frame.map_err(|e| {
                 error!("Failed to read message: {}", e);
                 ConnectionError::WireProtocol(WireProtocolError::ReadTimeout {
                     timeout: Duration::from_secs(30), // Default timeout
                 })
-            })?;
+            }),
+            e = writer_failed => {
+                error!("Failed to send a queued message: {}", e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Read the frames that have already started arriving behind one of
+    /// `first_len` bytes, up to [`READ_AHEAD_FRAMES`] and
+    /// [`READ_AHEAD_BYTES`] in all
+    async fn read_backlog(&mut self, first_len: usize) -> Result<Vec<Frame>, ConnectionError> {
+        let mut backlog = Vec::new();
+        let mut buffered = first_len;
+        while backlog.len() + 1 < READ_AHEAD_FRAMES {
+            // Peeking reports the length prefix only once it has arrived
+            let mut prefix = [0u8; LENGTH_PREFIX_SIZE];
+            let peeked = tokio::time::timeout(Duration::ZERO, self.reader.peek(&mut prefix)).await;
+            if !matches!(peeked, Ok(Ok(LENGTH_PREFIX_SIZE))) {
+                break;
+            }
+            // A frame that doesn't fit is left for a later receive
+            buffered += u32::from_be_bytes(prefix) as usize;
+            if buffered > READ_AHEAD_BYTES {
+                break;
+            }
+            backlog.push(self.read_frame().await?);
+        }
+        Ok(backlog)
+    }
+
+    /// Receive the next message from the peer
+    ///
+    /// On connections we opened, a server can send announcements at any
+    /// time; those are set aside for [`Connection::take_announcements`]
+    /// rather than mistaken for the reply being waited on.
+    pub async fn receive_message(&mut self) -> Result<(Message, String), ConnectionError> {
+        loop {
+            match self.receive_any_message().await? {
+                (Message::ServerAnnouncement(announcement), sender) if self.initiator => {
+                    self.announcements.push((announcement, sender));
+                }
+                received => return Ok(received),
+            }
+        }
+    }
+
+    /// Receive the reply to `sent`
+    ///
+    /// Several games against one peer can share a connection, so while
+    /// waiting for a reply about one game, messages about another are set
+    /// aside and handed out when a reply about that game is awaited. At most
+    /// [`READ_AHEAD_FRAMES`] are kept; beyond that, the game with the most
+    /// waiting loses its oldest.
+    /// Replies to messages outside any game are not matched by game.
+    pub async fn receive_reply_to(
+        &mut self,
+        sent: &Message,
+    ) -> Result<(Message, String), ConnectionError> {
+        let Some(game_id) = sent.get_game_id() else {
+            return self.receive_message().await;
+        };
+        if let Some(waiting) = self.other_games.get_mut(game_id) {
+            let reply = waiting.pop_front();
+            if waiting.is_empty() {
+                self.other_games.remove(game_id);
+            }
+            if let Some(reply) = reply {
+                return Ok(reply);
+            }
+        }
+
+        loop {
+            let (message, sender) = self.receive_message().await?;
+            let other = match message.get_game_id() {
+                Some(other) if other != game_id => other.to_string(),
+                _ => return Ok((message, sender)),
+            };
+            debug!(
+                "Set aside {} message for game {}",
+                message.message_type(),
+                other
+            );
+            if self.set_aside_messages() >= READ_AHEAD_FRAMES {
+                if let Some((longest_game, longest)) = self
+                    .other_games
+                    .iter_mut()
+                    .max_by_key(|(_, queue)| queue.len())
+                {
+                    if let Some((dropped, sender)) = longest.pop_front() {
+                        warn!(
+                            "Too many messages set aside for other games, dropped {} from {} for game {}",
+                            dropped.message_type(),
+                            sender,
+                            longest_game
+                        );
+                    }
+                }
+            }
+            self.other_games
+                .entry(other)
+                .or_default()
+                .push_back((message, sender));
+        }
+    }
+
+    /// Messages set aside for games other than the one last waited on
+    pub fn set_aside_messages(&self) -> usize {
+        self.other_games.values().map(VecDeque::len).sum()
+    }
+
+    /// Server announcements received since the last call, oldest first, with
+    /// the peer ID of the server that sent each
+    pub fn take_announcements(&mut self) -> Vec<(ServerAnnouncement, String)> {
+        std::mem::take(&mut self.announcements)
+    }
+
+    /// Bytes of received messages read ahead and not yet handed out
+    pub fn read_ahead_bytes(&self) -> usize {
+        self.read_ahead.iter().map(|(_, _, size)| size).sum()
+    }
+
+    #[instrument(level = "debug", skip(self), fields(
+        connection_id = %self.connection_id,
+        peer_id = self.peer_id.as_deref(),
+        msg_type = tracing::field::Empty,
+        game_id = tracing::field::Empty
+    ))]
+    async fn receive_any_message(&mut self) -> Result<(Message, String), ConnectionError> {
+        let receive_start = std::time::Instant::now();
+        info!("Waiting to receive message");
+
+        // Frames that arrived behind the next one, such as a sync backlog,
+        // are read with it and their signatures checked together off the
+        // runtime; a lone frame is decoded in place, so a large sync is only
+        // copied once: from the frame into the decoded message
+        let frame;
+        let read_ahead;
+        let (envelope, signature_valid, envelope_size) = match self.read_ahead.pop_front() {
+            Some((envelope, valid, size)) => {
+                read_ahead = envelope;
+                (read_ahead.borrowed(), valid, size)
+            }
+            None => {
+                frame = self.read_frame().await?;
+                let backlog = self.read_backlog(frame.len()).await?;
+                if backlog.is_empty() {
+                    let envelope = frame.envelope().map_err(ConnectionError::WireProtocol)?;
+                    (envelope, envelope.verify_signature(), frame.len())
+                } else {
+                    let mut sizes = vec![frame.len()];
+                    let mut envelopes = vec![frame
+                        .envelope()
+                        .map_err(ConnectionError::WireProtocol)?
+                        .to_owned_envelope()];
+                    for next in &backlog {
+                        sizes.push(next.len());
+                        envelopes.push(
+                            next.envelope()
+                                .map_err(ConnectionError::WireProtocol)?
+                                .to_owned_envelope(),
+                        );
+                    }
+                    debug!("Verifying {} envelopes read together", envelopes.len());
+                    let verified = SignedEnvelope::verify_batch(envelopes)
+                        .await
+                        .map_err(|e| ConnectionError::Io(std::io::Error::other(e)))?;
+                    self.read_ahead = verified
+                        .into_iter()
+                        .zip(sizes)
+                        .map(|((envelope, valid), size)| (envelope, valid, size))
+                        .collect();
+                    let (envelope, valid, size) = self
+                        .read_ahead
+                        .pop_front()
+                        .expect("the batch holds the first frame");
+                    read_ahead = envelope;
+                    (read_ahead.borrowed(), valid, size)
+                }
+            }
+        };
 
         debug!("Received envelope from sender: {}", envelope.sender());
 
         // Verify the signature of the received envelope
-        if !envelope.verify_signature() {
+        if !signature_valid {
             error!(
                 "Signature verification failed for message from {}",
                 envelope.sender()
@@ -258,15 +834,29 @@ impl Connection {
             "Signature verification passed for sender: {}",
             envelope.sender()
         );
+        self.last_received_timestamp = Some(envelope.timestamp);
 
-        // Validate timestamp (using default max age of 5 minutes)
+        // Validate timestamp (using default max age of 5 minutes), on our clock
         const MAX_MESSAGE_AGE_SECONDS: u64 = 300; // 5 minutes
-        if !envelope.is_timestamp_valid(MAX_MESSAGE_AGE_SECONDS) {
+        let max_skew_seconds = self.max_clock_skew.as_secs();
+        if !envelope.is_timestamp_within(
+            MAX_MESSAGE_AGE_SECONDS,
+            max_skew_seconds,
+            self.clock_offset.unwrap_or(0),
+        ) {
             warn!(
                 "Message timestamp validation failed for sender: {}, age: {} seconds",
                 envelope.sender(),
                 envelope.get_age_seconds()
             );
+            // Before the handshake has measured the offset, a fresh message
+            // outside the window means the clocks disagree
+            if self.clock_offset.is_none() {
+                return Err(ConnectionError::ClockSkew {
+                    offset_secs: envelope.timestamp() as i64 - (unix_millis() / 1000) as i64,
+                    max_secs: max_skew_seconds,
+                });
+            }
             return Err(ConnectionError::InvalidTimestamp);
         }
 
@@ -278,13 +868,24 @@ impl Connection {
             })
         })?;
 
+        let span = tracing::Span::current();
+        span.record("msg_type", message.message_type());
+        if let Some(game_id) = message.get_game_id() {
+            span.record("game_id", game_id);
+        }
+
         let sender_id = envelope.sender().to_string();
         let receive_duration = receive_start.elapsed();
 
-        // Calculate message size for performance metrics
-        let envelope_size = bincode::serialize(&envelope)
-            .map(|bytes| bytes.len())
-            .unwrap_or(0);
+        // An announcement isn't a reply, so the wait for one goes on
+        let round_trip = if self.initiator && matches!(message, Message::ServerAnnouncement(_)) {
+            None
+        } else {
+            self.awaiting_reply_since.take().map(|sent| sent.elapsed())
+        };
+        self.send_queue
+            .stats()
+            .record_received(envelope_size, round_trip);
 
         info!(
             "Successfully received {} message from {} (age: {} seconds) in {:?}",
@@ -294,11 +895,7 @@ impl Connection {
             receive_duration
         );
 
-        debug!(
-            "Message details - nonce: {}, payload_len: {}",
-            message.get_nonce(),
-            message.get_payload().len()
-        );
+        debug!("Message details: {}", message.log_summary());
 
         // Performance metrics logging
         debug!(
@@ -316,10 +913,11 @@ impl Connection {
         Ok((message, sender_id))
     }
 
-    #[instrument(level = "debug", skip(self), fields(local_peer = self.identity.peer_id().as_str()))]
+    #[instrument(level = "debug", skip(self), fields(connection_id = %self.connection_id, local_peer = self.identity.peer_id().as_str()))]
     pub async fn handshake(&mut self) -> Result<String> {
         let handshake_start = std::time::Instant::now();
         info!("Starting handshake protocol");
+        self.initiator = true;
 
         // Step 5.1: Use appropriate handshake timeout from network configuration constants
         // Generate a unique handshake nonce to prevent replay attacks
@@ -337,7 +935,8 @@ impl Connection {
             "Sending handshake request"
         );
 
-        // Send handshake request
+        // Send handshake request, timing the round trip to estimate the clock offset
+        let sent_at = unix_millis();
         self.send_message(handshake_request)
             .await
             .context("Failed to send handshake request")?;
@@ -352,6 +951,7 @@ impl Connection {
         )
         .await;
 
+        let received_at = unix_millis();
         let (response_message, peer_identity) = match receive_result {
             Ok(Ok((msg, peer_id))) => (msg, peer_id),
             Ok(Err(e)) => {
@@ -452,8 +1052,14 @@ impl Connection {
                 .context("Handshake peer identity validation failed");
         }
 
+        if let Some(peer_timestamp) = self.last_received_timestamp {
+            self.set_clock_offset(estimate_clock_offset(peer_timestamp, sent_at, received_at))
+                .context("Handshake clock check failed")?;
+        }
+
         // Store the authenticated peer identity
         self.peer_id = Some(peer_identity.clone());
+        self.session_id = Some(handshake_nonce);
 
         let handshake_duration = handshake_start.elapsed();
 
@@ -482,6 +1088,37 @@ impl Connection {
         Ok(peer_identity)
     }
 
+    /// Use `connection_id` for this connection's logs instead of a new one,
+    /// e.g. the ID the server logged when accepting it
+    pub fn with_connection_id(mut self, connection_id: String) -> Self {
+        self.connection_id = connection_id;
+        self
+    }
+
+    /// ID correlating this connection's log lines and spans
+    pub fn connection_id(&self) -> &str {
+        &self.connection_id
+    }
+
+    /// Messages, bytes, and reply times on this connection so far
+    ///
+    /// Round trips are only timed on connections we opened, from a message
+    /// sent to the next one received.
+    pub fn stats(&self) -> ConnectionStats {
+        self.send_queue.stats().clone()
+    }
+
+    /// Note how many connection attempts failed before this one
+    pub(crate) fn record_retries(&mut self, retries: u64) {
+        self.send_queue.stats().retries += retries;
+    }
+
+    /// Whether small frames are sent without waiting to batch them
+    /// (`TCP_NODELAY`)
+    pub fn tcp_nodelay(&self) -> bool {
+        self.reader.as_ref().nodelay().unwrap_or(false)
+    }
+
     /// Check if the connection has completed the handshake and is authenticated
     pub fn is_authenticated(&self) -> bool {
         self.peer_id.is_some()
@@ -492,17 +1129,63 @@ impl Connection {
         self.peer_id.as_deref()
     }
 
+    /// Identifier of this connection's session, known to both ends once the
+    /// handshake completes
+    pub fn session_id(&self) -> Option<u64> {
+        self.session_id
+    }
+
+    /// Timestamp the peer signed the last received message with, in Unix seconds
+    ///
+    /// Recorded once the signature checks out, even when the message is then
+    /// rejected as too old or from the future, so clock skew can be measured.
+    pub fn last_received_timestamp(&self) -> Option<u64> {
+        self.last_received_timestamp
+    }
+
+    /// Estimated difference between the peer's clock and ours in seconds,
+    /// positive when the peer is ahead, measured during the handshake
+    pub fn clock_offset(&self) -> Option<i64> {
+        self.clock_offset
+    }
+
+    /// A timestamp from the peer's clock translated to ours, in Unix seconds
+    pub fn corrected_timestamp(&self, peer_timestamp: u64) -> i64 {
+        peer_timestamp as i64 - self.clock_offset.unwrap_or(0)
+    }
+
+    /// Record the peer's clock offset, refusing peers too far out of sync
+    fn set_clock_offset(&mut self, offset_secs: i64) -> Result<(), ConnectionError> {
+        let max_secs = self.max_clock_skew.as_secs();
+        if offset_secs.unsigned_abs() > max_secs {
+            warn!(offset_secs, max_secs, "Peer clock skew exceeds the maximum");
+            return Err(ConnectionError::ClockSkew {
+                offset_secs,
+                max_secs,
+            });
+        }
+        debug!(offset_secs, "Estimated peer clock offset");
+        self.clock_offset = Some(offset_secs);
+        Ok(())
+    }
+
     /// Close the connection gracefully
     ///
-    /// This method attempts to shutdown the TCP stream gracefully.
+    /// Messages still queued are given the write timeout to be sent.
     /// Note: The actual close operation is handled by dropping the TcpStream.
-    #[instrument(level = "debug", skip(self))]
+    #[instrument(level = "debug", skip(self), fields(connection_id = %self.connection_id, peer_id = self.peer_id.as_deref()))]
     pub async fn close(&mut self) -> Result<(), ConnectionError> {
         info!("Closing connection to peer: {:?}", self.peer_id);
 
-        // In Tokio, TcpStream doesn't have an explicit close method
-        // The connection will be closed when the stream is dropped
-        // We could implement a graceful shutdown here if needed
+        let write_timeout = self.framed_message.wire_config().write_timeout;
+        let flushed = match tokio::time::timeout(write_timeout, self.flush()).await {
+            Ok(flushed) => flushed,
+            Err(_) => Err(ConnectionError::WireProtocol(
+                WireProtocolError::WriteTimeout {
+                    timeout: write_timeout,
+                },
+            )),
+        };
 
         // Mark the connection as closed by clearing peer_id
         if let Some(peer_id) = &self.peer_id {
@@ -510,6 +1193,7 @@ impl Connection {
         }
         self.peer_id = None;
 
+        flushed?;
         info!("Connection closed successfully");
         Ok(())
     }
@@ -523,12 +1207,12 @@ impl Connection {
 
     /// Get the local socket address of this connection
     pub fn local_addr(&self) -> Result<std::net::SocketAddr, std::io::Error> {
-        self.stream.local_addr()
+        self.reader.local_addr()
     }
 
     /// Get the remote socket address of this connection
     pub fn peer_addr(&self) -> Result<std::net::SocketAddr, std::io::Error> {
-        self.stream.peer_addr()
+        self.reader.peer_addr()
     }
 
     /// Handle an incoming handshake request (server-side handshake handling)
@@ -539,7 +1223,7 @@ impl Connection {
     /// # Returns
     /// * `Ok(String)` - The authenticated peer identity from the handshake request
     /// * `Err(anyhow::Error)` - Handshake failure
-    #[instrument(level = "debug", skip(self), fields(local_peer = self.identity.peer_id().as_str()))]
+    #[instrument(level = "debug", skip(self), fields(connection_id = %self.connection_id, local_peer = self.identity.peer_id().as_str()))]
     pub async fn handle_handshake_request(&mut self) -> Result<String> {
         info!("Waiting for incoming handshake request");
 
@@ -551,6 +1235,7 @@ impl Connection {
         )
         .await;
 
+        let received_at = unix_millis();
         let (request_message, peer_identity) = match receive_result {
             Ok(Ok((msg, peer_id))) => (msg, peer_id),
             Ok(Err(e)) => {
@@ -636,6 +1321,12 @@ impl Connection {
                 .context("Handshake request peer identity validation failed");
         }
 
+        // The request was not prompted by us, so its timestamp is compared
+        // with the moment it arrived
+        let clock_offset = self
+            .last_received_timestamp
+            .map(|peer_timestamp| estimate_clock_offset(peer_timestamp, received_at, received_at));
+
         // Create handshake response message
         let local_peer_id = self.identity.peer_id().as_str().to_string();
         let response_payload = format!("HANDSHAKE_RESPONSE:{local_peer_id}");
@@ -652,8 +1343,15 @@ impl Connection {
             .await
             .context("Failed to send handshake response")?;
 
+        // Checked after responding, so the peer can see the skew for itself
+        if let Some(offset) = clock_offset {
+            self.set_clock_offset(offset)
+                .context("Handshake clock check failed")?;
+        }
+
         // Store the authenticated peer identity
         self.peer_id = Some(peer_identity.clone());
+        self.session_id = Some(request_message.get_nonce());
 
         info!(
             peer_id = %peer_identity,
@@ -670,3 +1368,40 @@ impl Connection {
         Ok(peer_identity)
     }
 }
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        for (game_id, waiting) in &self.other_games {
+            for (message, sender) in waiting {
+                warn!(
+                    "Connection {} closed before a reply about game {} was awaited, dropped {} from {}",
+                    self.connection_id,
+                    game_id,
+                    message.message_type(),
+                    sender
+                );
+            }
+        }
+    }
+}
+
+/// Estimate the peer's clock minus ours in seconds, NTP style
+///
+/// `peer_timestamp` is the peer's clock, in whole Unix seconds, when it signed
+/// its reply to a request we sent at `sent_at_ms` and got the reply to at
+/// `received_at_ms` (Unix milliseconds). The peer is assumed to have replied
+/// halfway through the round trip; for a message we did not ask for, pass its
+/// arrival time for both.
+pub fn estimate_clock_offset(peer_timestamp: u64, sent_at_ms: u128, received_at_ms: u128) -> i64 {
+    // The peer's timestamp is truncated to the second, so take the middle of it
+    let peer_ms = peer_timestamp as i128 * 1000 + 500;
+    let midpoint_ms = ((sent_at_ms + received_at_ms) / 2) as i128;
+    (peer_ms - midpoint_ms + 500).div_euclid(1000) as i64
+}
+
+fn unix_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
@@ -0,0 +1,282 @@
+use crate::messages::Message;
+use crate::network::Notifier;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tracing::{debug, warn};
+
+/// How long one delivery, from connecting to `QUIT`, may take
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// File in the data directory holding when a mate command last ran
+pub const ACTIVITY_FILE: &str = "last-activity";
+
+/// When the user last ran a mate command, kept in the data directory so the
+/// server can tell whether they are at the keyboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserActivity {
+    path: PathBuf,
+}
+
+impl UserActivity {
+    /// Activity recorded in `data_dir`
+    pub fn in_dir(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(ACTIVITY_FILE),
+        }
+    }
+
+    /// Record that the user is active now
+    pub fn touch(&self) -> Result<()> {
+        self.record(SystemTime::now())
+    }
+
+    /// Record activity at `at`
+    pub fn record(&self, at: SystemTime) -> Result<()> {
+        let secs = at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        std::fs::write(&self.path, secs.to_string())
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// How long ago the user was last active, or `None` if no activity was
+    /// ever recorded
+    pub fn idle_for(&self) -> Option<Duration> {
+        let secs: u64 = std::fs::read_to_string(&self.path)
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        let last = UNIX_EPOCH + Duration::from_secs(secs);
+        Some(SystemTime::now().duration_since(last).unwrap_or_default())
+    }
+
+    /// Whether the user has been away for at least `threshold`
+    pub fn is_idle(&self, threshold: Duration) -> bool {
+        self.idle_for().is_none_or(|idle| idle >= threshold)
+    }
+}
+
+/// Emails incoming moves and invitations while the user is away
+///
+/// Mail is handed to an SMTP server without TLS or authentication, so
+/// `server` must be a relay on this machine (e.g. postfix or msmtpd) that
+/// forwards it on; any other server is refused rather than sent mail in
+/// plaintext. Deliveries run in the background and failures are only logged.
+#[derive(Debug, Clone)]
+pub struct EmailNotifier {
+    server: String,
+    from: String,
+    to: String,
+    activity: Option<Arc<UserActivity>>,
+    idle_after: Duration,
+}
+
+impl EmailNotifier {
+    /// Mail from `from` to `to` through the SMTP server at `server`
+    /// (`host:port`)
+    pub fn new(server: &str, from: &str, to: &str) -> Result<Self> {
+        validate_relay(server)?;
+        Ok(Self {
+            server: server.to_string(),
+            from: validate_address(from).context("Invalid sender address")?,
+            to: validate_address(to).context("Invalid recipient address")?,
+            activity: None,
+            idle_after: Duration::ZERO,
+        })
+    }
+
+    /// Only send mail once `activity` shows the user away for `idle_after`
+    pub fn when_idle(mut self, activity: UserActivity, idle_after: Duration) -> Self {
+        self.activity = Some(Arc::new(activity));
+        self.idle_after = idle_after;
+        self
+    }
+
+    /// Whether mail should go out now
+    pub fn user_is_idle(&self) -> bool {
+        self.activity
+            .as_ref()
+            .is_none_or(|activity| activity.is_idle(self.idle_after))
+    }
+
+    /// Subject and body of the email for a message, or `None` for messages
+    /// other than moves and invitations
+    pub fn email_for(&self, message: &Message, sender: &str) -> Option<(String, String)> {
+        if !matches!(message, Message::Move(_) | Message::GameInvite(_)) {
+            return None;
+        }
+        let notifier = Notifier {
+            moves: true,
+            invites: true,
+        };
+        let (summary, body) = notifier.notification_for(message, sender)?;
+        Some((
+            format!("[mate] {summary}"),
+            format!("{summary}\r\n{body}\r\n"),
+        ))
+    }
+
+    /// Email the message in the background if it is a move or invitation
+    /// and the user is away
+    pub fn notify(&self, message: &Message, sender: &str) {
+        let Some((subject, body)) = self.email_for(message, sender) else {
+            return;
+        };
+        if !self.user_is_idle() {
+            debug!("User is active; not emailing '{}'", subject);
+            return;
+        }
+        let email = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = email.send(&subject, &body).await {
+                warn!("Failed to email '{}' to {}: {:#}", subject, email.to, e);
+            }
+        });
+    }
+
+    /// Deliver one email
+    pub async fn send(&self, subject: &str, body: &str) -> Result<()> {
+        tokio::time::timeout(DELIVERY_TIMEOUT, self.deliver(subject, body))
+            .await
+            .with_context(|| format!("SMTP server {} timed out", self.server))?
+    }
+
+    async fn deliver(&self, subject: &str, body: &str) -> Result<()> {
+        let stream = TcpStream::connect(&self.server)
+            .await
+            .with_context(|| format!("Failed to connect to SMTP server {}", self.server))?;
+        let mut smtp = Smtp {
+            stream: BufReader::new(stream),
+        };
+
+        smtp.expect(220).await?;
+        smtp.command("EHLO localhost", 250).await?;
+        smtp.command(&format!("MAIL FROM:<{}>", self.from), 250)
+            .await?;
+        smtp.command(&format!("RCPT TO:<{}>", self.to), 250).await?;
+        smtp.command("DATA", 354).await?;
+
+        let subject = subject.replace(['\r', '\n'], " ");
+        let mut data = format!(
+            "From: <{}>\r\nTo: <{}>\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n",
+            self.from, self.to, subject
+        );
+        data.push_str(&dot_stuff(body));
+        if !data.ends_with("\r\n") {
+            data.push_str("\r\n");
+        }
+        data.push_str(".\r\n");
+        smtp.stream.get_mut().write_all(data.as_bytes()).await?;
+        smtp.expect(250).await?;
+
+        smtp.command("QUIT", 221).await?;
+        Ok(())
+    }
+}
+
+/// One SMTP session
+struct Smtp {
+    stream: BufReader<TcpStream>,
+}
+
+impl Smtp {
+    /// Send a command and check the reply code
+    async fn command(&mut self, command: &str, code: u16) -> Result<()> {
+        self.stream
+            .get_mut()
+            .write_all(format!("{command}\r\n").as_bytes())
+            .await?;
+        self.expect(code)
+            .await
+            .with_context(|| format!("SMTP server rejected {}", verb(command)))
+    }
+
+    /// Read a possibly multi-line reply and check its code
+    async fn expect(&mut self, code: u16) -> Result<()> {
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await? == 0 {
+                bail!("SMTP server closed the connection");
+            }
+            let line = line.trim_end();
+            let reply: u16 = line
+                .get(..3)
+                .and_then(|c| c.parse().ok())
+                .with_context(|| format!("Malformed SMTP reply: {line}"))?;
+            if reply != code {
+                bail!("Unexpected SMTP reply: {line}");
+            }
+            // `250-...` continues a multi-line reply; `250 ...` ends it
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// The command name, leaving addresses out of error messages
+fn verb(command: &str) -> &str {
+    command.split([' ', ':']).next().unwrap_or(command)
+}
+
+/// Normalize line endings to CRLF and escape lines starting with `.`
+pub fn dot_stuff(body: &str) -> String {
+    body.replace("\r\n", "\n")
+        .split('\n')
+        .map(|line| {
+            if line.starts_with('.') {
+                format!(".{line}")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+/// Accept an SMTP server only on this machine, given as `host:port` with a
+/// host of `localhost` or a loopback address
+///
+/// Delivery speaks plain SMTP without STARTTLS or AUTH, so mail to a remote
+/// server would cross the network unencrypted and could not log in.
+pub fn validate_relay(server: &str) -> Result<()> {
+    if server.is_empty() {
+        bail!("No SMTP server is configured");
+    }
+    let Some((host, port)) = server.rsplit_once(':') else {
+        bail!("SMTP server '{server}' must be given as host:port");
+    };
+    if port.parse::<u16>().is_err() {
+        bail!("SMTP server '{server}' has an invalid port");
+    }
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let local = host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback());
+    if !local {
+        bail!(
+            "SMTP server '{server}' is not on this machine; mail is sent without TLS or \
+             authentication, so use a local relay such as postfix or msmtpd that forwards it"
+        );
+    }
+    Ok(())
+}
+
+/// Accept a bare `user@host` address
+fn validate_address(address: &str) -> Result<String> {
+    let address = address.trim();
+    if address
+        .chars()
+        .any(|c| c.is_control() || c.is_whitespace() || c == '<' || c == '>')
+    {
+        bail!("'{address}' contains characters not allowed in an address");
+    }
+    match address.split_once('@') {
+        Some((user, host)) if !user.is_empty() && !host.is_empty() => Ok(address.to_string()),
+        _ => bail!("'{address}' is not an email address"),
+    }
+}
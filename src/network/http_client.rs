@@ -0,0 +1,370 @@
+use anyhow::{Context, Result};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+/// Largest response body read, so a misbehaving server cannot exhaust memory
+pub const MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Largest status line plus headers read before a response is refused
+const MAX_HEAD_BYTES: u64 = 64 * 1024;
+
+/// Longest a request may take, from connecting to the end of the response
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A parsed `http://` or `https://` URL to request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpUrl {
+    url: String,
+    /// Whether the URL is `https://`, so requests go over TLS
+    tls: bool,
+    /// Host and port as written, for the `Host` header
+    authority: String,
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl HttpUrl {
+    /// Parse an `http://` or `https://` URL
+    ///
+    /// HTTPS servers are checked against the Mozilla root certificates
+    /// built into mate.
+    pub fn parse(url: &str) -> Result<Self> {
+        let (tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else {
+            anyhow::bail!("URL '{url}' must start with http:// or https://");
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            // Not the colons of a bracketed IPv6 address
+            Some((host, port)) if !port.contains(']') => (
+                host,
+                port.parse()
+                    .with_context(|| format!("Invalid port in URL '{url}'"))?,
+            ),
+            _ => (authority, if tls { 443 } else { 80 }),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() {
+            anyhow::bail!("URL '{url}' has no host");
+        }
+        Ok(Self {
+            url: url.to_string(),
+            tls,
+            authority: authority.to_string(),
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    /// The URL as written
+    pub fn as_str(&self) -> &str {
+        &self.url
+    }
+
+    /// The URL `reference` points to from this one: `reference` itself when
+    /// it is a whole URL, or this server with `reference` as the path
+    pub fn resolve(&self, reference: &str) -> Result<Self> {
+        if reference.starts_with("http://") || reference.starts_with("https://") {
+            return Self::parse(reference);
+        }
+        Self::parse(&format!(
+            "{}{}/{}",
+            self.scheme(),
+            self.authority,
+            reference.trim_start_matches('/')
+        ))
+    }
+
+    /// `https://` or `http://`
+    fn scheme(&self) -> &'static str {
+        if self.tls {
+            "https://"
+        } else {
+            "http://"
+        }
+    }
+
+    /// The host as written, without brackets around an IPv6 address
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The same server with `path` appended to this URL's path
+    pub fn join(&self, path: &str) -> Self {
+        let mut joined = self.clone();
+        joined.path = format!("{}/{}", self.path.trim_end_matches('/'), path);
+        joined.url = format!("{}{}{}", self.scheme(), self.authority, joined.path);
+        joined
+    }
+}
+
+/// A connection to an HTTP server, over TLS for `https://` URLs
+trait Transport: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Transport for T {}
+
+/// Connect to the server of `url`
+async fn connect(url: &HttpUrl) -> Result<Box<dyn Transport>> {
+    let stream = TcpStream::connect((url.host.as_str(), url.port))
+        .await
+        .with_context(|| format!("Failed to connect to {}", url.url))?;
+    if !url.tls {
+        return Ok(Box::new(stream));
+    }
+    let server_name = ServerName::try_from(url.host.clone())
+        .with_context(|| format!("Invalid TLS server name in {}", url.url))?;
+    let stream = TlsConnector::from(tls_config())
+        .connect(server_name, stream)
+        .await
+        .with_context(|| format!("TLS handshake with {} failed", url.url))?;
+    Ok(Box::new(stream))
+}
+
+/// Client TLS settings trusting the Mozilla root certificates, built once
+fn tls_config() -> Arc<ClientConfig> {
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+    Arc::clone(CONFIG.get_or_init(|| {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        Arc::new(
+            ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        )
+    }))
+}
+
+/// Send one POST request with a JSON body and return the response status
+///
+/// `headers` are added to `Host`, `User-Agent`, `Content-Type`, and
+/// `Content-Length`. The connection is closed after the response.
+pub async fn post_json(url: &HttpUrl, headers: &[(&str, String)], body: &[u8]) -> Result<u16> {
+    let (status, _) = post(url, "application/json", headers, body).await?;
+    Ok(status)
+}
+
+/// Send one POST request with a `content_type` body and return the
+/// response status and body
+///
+/// `headers` are added as for [`post_json`].
+pub async fn post(
+    url: &HttpUrl,
+    content_type: &str,
+    headers: &[(&str, String)],
+    body: &[u8],
+) -> Result<(u16, Vec<u8>)> {
+    send(url, "POST", headers, Some((content_type, body))).await
+}
+
+/// Send one GET request and return the response status and body
+pub async fn get(url: &HttpUrl) -> Result<(u16, Vec<u8>)> {
+    get_with_headers(url, &[]).await
+}
+
+/// Send one GET request with `headers` added as for [`post_json`], and
+/// return the response status and body
+pub async fn get_with_headers(url: &HttpUrl, headers: &[(&str, String)]) -> Result<(u16, Vec<u8>)> {
+    send(url, "GET", headers, None).await
+}
+
+/// Send one request with an optional body of the given content type, and
+/// read the response, within [`REQUEST_TIMEOUT`]
+async fn send(
+    url: &HttpUrl,
+    method: &str,
+    headers: &[(&str, String)],
+    body: Option<(&str, &[u8])>,
+) -> Result<(u16, Vec<u8>)> {
+    let exchange = async {
+        let mut stream = connect(url).await?;
+
+        let mut request = format!(
+            "{method} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: mate/{}\r\n",
+            url.path,
+            url.authority,
+            env!("CARGO_PKG_VERSION")
+        );
+        if let Some((content_type, body)) = body {
+            request.push_str(&format!(
+                "Content-Type: {content_type}\r\nContent-Length: {}\r\n",
+                body.len()
+            ));
+        }
+        for (name, value) in headers {
+            request.push_str(&format!("{name}: {value}\r\n"));
+        }
+        request.push_str("Connection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await?;
+        if let Some((_, body)) = body {
+            stream.write_all(body).await?;
+        }
+        stream.flush().await?;
+        read_response(stream, url).await
+    };
+    tokio::time::timeout(REQUEST_TIMEOUT, exchange)
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "No response from {} within {} seconds",
+                url.url,
+                REQUEST_TIMEOUT.as_secs()
+            )
+        })?
+}
+
+/// Read a response: its status and a body delimited by `Content-Length`,
+/// chunked transfer encoding, or the server closing the connection
+///
+/// A body cut short, or larger than [`MAX_RESPONSE_BYTES`], is an error.
+async fn read_response(stream: Box<dyn Transport>, url: &HttpUrl) -> Result<(u16, Vec<u8>)> {
+    let mut reader = BufReader::new(stream);
+    let read_error = || format!("Failed to read response from {}", url.url);
+
+    let mut head = (&mut reader).take(MAX_HEAD_BYTES);
+    let mut status_line = String::new();
+    head.read_line(&mut status_line)
+        .await
+        .with_context(read_error)?;
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .with_context(|| format!("Malformed HTTP response '{}'", status_line.trim()))?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    loop {
+        let mut line = String::new();
+        if head.read_line(&mut line).await.with_context(read_error)? == 0 {
+            anyhow::bail!(
+                "Malformed HTTP response from {}: no end of headers",
+                url.url
+            );
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length =
+                Some(value.parse::<usize>().with_context(|| {
+                    format!("Malformed Content-Length '{value}' from {}", url.url)
+                })?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.to_ascii_lowercase().contains("chunked");
+        }
+    }
+
+    let body = if chunked {
+        read_chunked_body(&mut reader, url).await?
+    } else if let Some(length) = content_length {
+        if length > MAX_RESPONSE_BYTES {
+            return Err(too_large(url));
+        }
+        let mut body = vec![0; length];
+        reader
+            .read_exact(&mut body)
+            .await
+            .map_err(|e| body_error(e, url))?;
+        body
+    } else {
+        let mut body = Vec::new();
+        (&mut reader)
+            .take(MAX_RESPONSE_BYTES as u64 + 1)
+            .read_to_end(&mut body)
+            .await
+            .map_err(|e| body_error(e, url))?;
+        if body.len() > MAX_RESPONSE_BYTES {
+            return Err(too_large(url));
+        }
+        body
+    };
+    Ok((status, body))
+}
+
+/// Read a body sent with chunked transfer encoding, and its trailers
+async fn read_chunked_body<R: AsyncBufReadExt + Unpin>(
+    reader: &mut R,
+    url: &HttpUrl,
+) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let size_line = read_chunk_line(reader, url).await?;
+        let size = size_line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size, 16)
+            .with_context(|| format!("Malformed chunk size '{size}' from {}", url.url))?;
+        if size == 0 {
+            break;
+        }
+        if body.len() + size > MAX_RESPONSE_BYTES {
+            return Err(too_large(url));
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader
+            .read_exact(&mut body[start..])
+            .await
+            .map_err(|e| body_error(e, url))?;
+        if !read_chunk_line(reader, url).await?.is_empty() {
+            anyhow::bail!(
+                "Malformed HTTP response from {}: chunk overruns its size",
+                url.url
+            );
+        }
+    }
+    while !read_chunk_line(reader, url).await?.is_empty() {}
+    Ok(body)
+}
+
+/// Read one line of a chunked body without its line ending
+async fn read_chunk_line<R: AsyncBufReadExt + Unpin>(
+    reader: &mut R,
+    url: &HttpUrl,
+) -> Result<String> {
+    let mut line = String::new();
+    reader
+        .take(MAX_HEAD_BYTES)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| body_error(e, url))?;
+    if !line.ends_with('\n') {
+        return Err(body_error(std::io::ErrorKind::UnexpectedEof.into(), url));
+    }
+    Ok(line.trim_end().to_string())
+}
+
+/// Error for a failure reading a response body, calling out truncation
+fn body_error(error: std::io::Error, url: &HttpUrl) -> anyhow::Error {
+    if error.kind() == std::io::ErrorKind::UnexpectedEof {
+        anyhow::anyhow!("Response from {} was cut short", url.url)
+    } else {
+        anyhow::Error::new(error).context(format!("Failed to read response from {}", url.url))
+    }
+}
+
+/// Error for a response body over [`MAX_RESPONSE_BYTES`]
+fn too_large(url: &HttpUrl) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Response from {} is larger than {} bytes",
+        url.url,
+        MAX_RESPONSE_BYTES
+    )
+}
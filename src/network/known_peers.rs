@@ -0,0 +1,258 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File in the data directory holding pinned peer keys
+pub const KNOWN_PEERS_FILE: &str = "known_peers.json";
+
+/// The key a peer presented the first time it was reached at an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownPeer {
+    pub peer_id: String,
+    /// Unix timestamp of the first connection
+    pub first_seen: i64,
+    /// The key's owner revoked it, so it is no longer trusted
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+/// A peer presented a different key than the one pinned for its address
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "The peer at {address} presented key {presented}, but key {pinned} was seen there before. \
+     Someone may be impersonating the peer. If the peer replaced its key, confirm it with them \
+     and retry with --accept-new-key"
+)]
+pub struct PeerKeyChanged {
+    pub address: String,
+    pub pinned: String,
+    pub presented: String,
+}
+
+/// A peer presented a key its owner revoked
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "The peer at {address} presented key {peer_id}, which its owner revoked as compromised. \
+     Ask the peer for their new key"
+)]
+pub struct PeerKeyRevoked {
+    pub address: String,
+    pub peer_id: String,
+}
+
+/// Result of checking a presented key against the pinned one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyCheck {
+    /// Nothing was pinned for the address yet
+    FirstUse,
+    /// The presented key is the pinned one
+    Match,
+    /// The presented key differs from the pinned one
+    Changed { pinned: String },
+}
+
+/// Trust-on-first-use store of peer keys by the address (or alias target)
+/// they were reached at, kept as JSON like SSH's `known_hosts`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownPeers {
+    path: PathBuf,
+    peers: BTreeMap<String, KnownPeer>,
+}
+
+impl KnownPeers {
+    /// Load the store at `path`; a missing file is an empty store
+    pub fn load(path: &Path) -> Result<Self> {
+        let peers = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            peers,
+        })
+    }
+
+    /// Write the store back to the file it was loaded from
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json =
+            serde_json::to_string_pretty(&self.peers).context("Failed to serialize known peers")?;
+        std::fs::write(&self.path, json)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// The key pinned for an address, if any
+    pub fn get(&self, address: &str) -> Option<&KnownPeer> {
+        self.peers.get(address)
+    }
+
+    /// Every pinned address and key, sorted by address
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &KnownPeer)> {
+        self.peers.iter()
+    }
+
+    /// Compare the key presented at an address with the pinned one
+    pub fn check(&self, address: &str, peer_id: &str) -> KeyCheck {
+        match self.peers.get(address) {
+            None => KeyCheck::FirstUse,
+            Some(known) if known.peer_id == peer_id => KeyCheck::Match,
+            Some(known) => KeyCheck::Changed {
+                pinned: known.peer_id.clone(),
+            },
+        }
+    }
+
+    /// Pin `peer_id` for an address, replacing any earlier key
+    pub fn pin(&mut self, address: &str, peer_id: &str) {
+        self.peers.insert(
+            address.to_string(),
+            KnownPeer {
+                peer_id: peer_id.to_string(),
+                first_seen: unix_now(),
+                revoked: false,
+            },
+        );
+    }
+
+    /// Forget the key pinned for an address. Returns false if none was.
+    pub fn forget(&mut self, address: &str) -> bool {
+        self.peers.remove(address).is_some()
+    }
+
+    /// Forget every address a key is pinned at, returning how many there were
+    pub fn forget_peer(&mut self, peer_id: &str) -> usize {
+        let pinned = self.peers.len();
+        self.peers.retain(|_, known| known.peer_id != peer_id);
+        pinned - self.peers.len()
+    }
+
+    /// Pin the keys of another store at the addresses that have none here,
+    /// returning how many addresses changed
+    ///
+    /// A key already pinned here is kept, and marked revoked if the other
+    /// store has it revoked.
+    pub fn merge<'a>(
+        &mut self,
+        pins: impl IntoIterator<Item = (&'a String, &'a KnownPeer)>,
+    ) -> usize {
+        let mut changed = 0;
+        for (address, pin) in pins {
+            match self.peers.get_mut(address) {
+                None => {
+                    self.peers.insert(address.clone(), pin.clone());
+                    changed += 1;
+                }
+                Some(known) if known.peer_id == pin.peer_id && pin.revoked && !known.revoked => {
+                    known.revoked = true;
+                    changed += 1;
+                }
+                Some(_) => {}
+            }
+        }
+        changed
+    }
+
+    /// Re-pin every address of a peer that rotated its key, returning how
+    /// many addresses moved
+    pub fn apply_rotation(&mut self, old_peer_id: &str, new_peer_id: &str) -> usize {
+        let mut moved = 0;
+        for known in self.peers.values_mut() {
+            if known.peer_id == old_peer_id {
+                known.peer_id = new_peer_id.to_string();
+                moved += 1;
+            }
+        }
+        moved
+    }
+
+    /// Mark every address pinned to a revoked key, returning how many
+    /// addresses were newly marked
+    pub fn revoke(&mut self, peer_id: &str) -> usize {
+        let mut marked = 0;
+        for known in self.peers.values_mut() {
+            if known.peer_id == peer_id && !known.revoked {
+                known.revoked = true;
+                marked += 1;
+            }
+        }
+        marked
+    }
+
+    /// Whether the key is pinned anywhere as revoked
+    pub fn is_revoked(&self, peer_id: &str) -> bool {
+        self.peers
+            .values()
+            .any(|known| known.revoked && known.peer_id == peer_id)
+    }
+
+    /// Check the key presented at an address, pinning it on first use
+    ///
+    /// A changed key is an error unless `accept_changed_key` is set, in which
+    /// case the new key replaces the pinned one. This holds for a revoked pin
+    /// too: whoever revoked it may also be the one presenting the new key,
+    /// so it is only replaced once confirmed, or followed by
+    /// [`Self::apply_rotation`] when the old key signed a rotation.
+    pub fn verify_or_pin(
+        &mut self,
+        address: &str,
+        peer_id: &str,
+        accept_changed_key: bool,
+    ) -> std::result::Result<KeyCheck, PeerKeyChanged> {
+        let check = self.check(address, peer_id);
+        match &check {
+            KeyCheck::Match => {}
+            KeyCheck::FirstUse => self.pin(address, peer_id),
+            KeyCheck::Changed { pinned } => {
+                if !accept_changed_key {
+                    return Err(PeerKeyChanged {
+                        address: address.to_string(),
+                        pinned: pinned.clone(),
+                        presented: peer_id.to_string(),
+                    });
+                }
+                self.pin(address, peer_id);
+            }
+        }
+        Ok(check)
+    }
+}
+
+/// Check the key presented at an address against the store at `path`,
+/// saving the store when a key is pinned or replaced
+///
+/// A changed key fails with [`PeerKeyChanged`] unless `accept_changed_key`
+/// is set, and a revoked key always fails with [`PeerKeyRevoked`].
+pub fn check_peer_key(
+    path: &Path,
+    address: &str,
+    peer_id: &str,
+    accept_changed_key: bool,
+) -> Result<KeyCheck> {
+    let mut known_peers = KnownPeers::load(path)?;
+    if known_peers.is_revoked(peer_id) {
+        return Err(PeerKeyRevoked {
+            address: address.to_string(),
+            peer_id: peer_id.to_string(),
+        }
+        .into());
+    }
+    let check = known_peers.verify_or_pin(address, peer_id, accept_changed_key)?;
+    if check != KeyCheck::Match {
+        known_peers.save()?;
+    }
+    Ok(check)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -1,10 +1,28 @@
 pub mod client;
 pub mod connection;
+pub mod email;
+pub mod http_client;
+pub mod known_peers;
+pub mod notifications;
+pub mod port_mapping;
+pub mod quota;
 pub mod server;
+pub mod stats;
+pub mod watchdog;
+pub mod webhooks;
 
 pub use client::Client;
 pub use connection::{Connection, ConnectionError};
-pub use server::Server;
+pub use email::{EmailNotifier, UserActivity};
+pub use known_peers::{KnownPeers, PeerKeyChanged, PeerKeyRevoked};
+pub use notifications::Notifier;
+pub use port_mapping::{PortMapper, PortMapping};
+pub use quota::{ConnectionQuota, QuotaTracker};
+pub use server::{HandshakeLimits, Server};
+pub use stats::{ConnectionStats, MonitorSnapshot, OpenConnection, StatsRegistry, TrafficSnapshot};
+pub use watchdog::{Heartbeat, Incident, Watchdog};
+pub use webhooks::Webhooks;
 
 // Re-export wire protocol types for convenience
-pub use crate::messages::wire::{WireConfig, WireProtocolError};
+pub use crate::messages::wire::{SendQueuePolicy, WireConfig, WireProtocolError};
+pub use crate::messages::MessagePriority;
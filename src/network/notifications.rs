@@ -0,0 +1,84 @@
+use crate::messages::Message;
+use tracing::debug;
+
+/// Application name shown by the desktop notification service
+const APP_NAME: &str = "mate";
+
+/// Raises desktop notifications for incoming messages the user cares about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Notifier {
+    /// Notify when an opponent makes a move, offers or accepts a draw, or resigns
+    pub moves: bool,
+    /// Notify when a peer sends a game invitation
+    pub invites: bool,
+}
+
+impl Notifier {
+    /// Summary and body of the notification for a message, or `None` if that
+    /// kind of message is not enabled
+    pub fn notification_for(&self, message: &Message, sender: &str) -> Option<(String, String)> {
+        let peer = short_peer_id(sender);
+        match message {
+            Message::Move(mv) if self.moves => Some((
+                format!("{peer} played {}", mv.chess_move),
+                format!("Game {}", short_game_id(&mv.game_id)),
+            )),
+            Message::Resign(resign) if self.moves => Some((
+                format!("{peer} resigned"),
+                format!("You won game {}", short_game_id(&resign.game_id)),
+            )),
+            Message::DrawOffer(offer) if self.moves => Some((
+                format!("{peer} offers a draw"),
+                format!("Run `mate offer-draw {}` to accept", offer.game_id),
+            )),
+            Message::DrawAccept(accept) if self.moves => Some((
+                format!("{peer} accepted your draw offer"),
+                format!("Game {} is drawn", short_game_id(&accept.game_id)),
+            )),
+            Message::GameInvite(invite) if self.invites => Some((
+                format!("{peer} invited you to a game"),
+                format!("Run `mate accept {}` to play", invite.game_id),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Show a notification for the message if one is enabled
+    ///
+    /// Delivery runs on a blocking thread and failures (e.g. no notification
+    /// service on a headless machine) are only logged.
+    pub fn notify(&self, message: &Message, sender: &str) {
+        if let Some((summary, body)) = self.notification_for(message, sender) {
+            show_notification(summary, body);
+        }
+    }
+}
+
+/// Show a desktop notification on a blocking thread, only logging failures
+pub fn show_notification(summary: String, body: String) {
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = notify_rust::Notification::new()
+            .appname(APP_NAME)
+            .summary(&summary)
+            .body(&body)
+            .show()
+        {
+            debug!("Failed to show desktop notification '{}': {}", summary, e);
+        }
+    });
+}
+
+fn short_peer_id(peer_id: &str) -> &str {
+    truncate(peer_id, 12)
+}
+
+fn short_game_id(game_id: &str) -> &str {
+    truncate(game_id, 8)
+}
+
+/// The first `max_chars` characters of a peer-supplied string
+fn truncate(s: &str, max_chars: usize) -> &str {
+    s.char_indices()
+        .nth(max_chars)
+        .map_or(s, |(end, _)| &s[..end])
+}
@@ -0,0 +1,518 @@
+use crate::network::http_client::{self, HttpUrl};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tracing::{debug, info, warn};
+
+/// File in the data directory holding the port mapping of the running
+/// `mate serve`
+pub const PORT_MAPPING_FILE: &str = "port_mapping.json";
+
+/// Value of `mate serve --bind` that listens on every interface on a port
+/// the system picks
+pub const AUTO_BIND: &str = "auto";
+
+/// Address listened on for `--bind auto`
+pub const AUTO_BIND_ADDR: &str = "0.0.0.0:0";
+
+/// How long the router is asked to keep a mapping; it is renewed halfway
+pub const MAPPING_LIFETIME: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// UDP port NAT-PMP gateways listen on (RFC 6886)
+pub const NAT_PMP_PORT: u16 = 5351;
+
+/// Where SSDP searches for UPnP devices are sent
+const SSDP_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+
+/// How long to wait for an Internet Gateway Device to answer a search
+const SSDP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long each UPnP control request may take
+const UPNP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First NAT-PMP wait for a reply; it doubles with each of the retries
+const NAT_PMP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+const NAT_PMP_ATTEMPTS: u32 = 4;
+
+/// Description the router shows for mappings made by mate
+const MAPPING_DESCRIPTION: &str = "mate";
+
+/// How a port was mapped on the router
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "method")]
+pub enum MappingMethod {
+    NatPmp {
+        gateway: Ipv4Addr,
+    },
+    Upnp {
+        /// Control URL of the gateway's WAN connection service
+        control_url: String,
+        service_type: String,
+    },
+}
+
+impl MappingMethod {
+    /// Name of the protocol, as shown to the user
+    pub fn name(&self) -> &'static str {
+        match self {
+            MappingMethod::NatPmp { .. } => "NAT-PMP",
+            MappingMethod::Upnp { .. } => "UPnP",
+        }
+    }
+}
+
+/// A TCP port forwarded from the router to this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortMapping {
+    #[serde(flatten)]
+    pub method: MappingMethod,
+    /// Address the server listens on
+    pub local_addr: SocketAddr,
+    /// Address peers outside the local network connect to
+    pub external_addr: SocketAddr,
+    /// Seconds the router keeps the mapping after `mapped_at`
+    pub lifetime_secs: u64,
+    /// Unix time the mapping was made or last renewed
+    pub mapped_at: i64,
+}
+
+impl PortMapping {
+    /// Unix time the router drops the mapping unless it is renewed
+    pub fn expires_at(&self) -> i64 {
+        self.mapped_at + self.lifetime_secs as i64
+    }
+
+    /// Whether the mapping is still in place at Unix time `now`
+    pub fn is_current(&self, now: i64) -> bool {
+        now < self.expires_at()
+    }
+
+    /// The mapping stored in `data_dir` by a running `mate serve`, if it
+    /// has not expired
+    pub fn load_current(data_dir: &Path) -> Option<Self> {
+        let bytes = std::fs::read(mapping_path(data_dir)).ok()?;
+        let mapping: Self = serde_json::from_slice(&bytes).ok()?;
+        mapping.is_current(unix_now()).then_some(mapping)
+    }
+
+    /// Store the mapping in `data_dir`, so invites made while it lasts
+    /// carry the external address
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let path = mapping_path(data_dir);
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Forget the mapping stored in `data_dir`
+    pub fn remove(data_dir: &Path) -> Result<()> {
+        let path = mapping_path(data_dir);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn mapping_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(PORT_MAPPING_FILE)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Asks the router to forward a port to a local server, with NAT-PMP and
+/// then UPnP
+pub struct PortMapper {
+    local_addr: SocketAddr,
+    /// Router to ask over NAT-PMP; found from the routing table when unset
+    gateway: Option<Ipv4Addr>,
+    lifetime: Duration,
+}
+
+impl PortMapper {
+    /// Map the port of the server listening on `local_addr`
+    pub fn new(local_addr: SocketAddr) -> Self {
+        Self {
+            local_addr,
+            gateway: None,
+            lifetime: MAPPING_LIFETIME,
+        }
+    }
+
+    /// Ask `gateway` instead of the default route's router
+    pub fn with_gateway(mut self, gateway: Option<Ipv4Addr>) -> Self {
+        self.gateway = gateway.or(self.gateway);
+        self
+    }
+
+    /// Forward the same port on the router to the server
+    ///
+    /// NAT-PMP is tried first as it answers quickest; UPnP is tried when
+    /// there is no NAT-PMP gateway.
+    pub async fn map(&self) -> Result<PortMapping> {
+        let nat_pmp_error = match self.gateway.or_else(default_gateway) {
+            Some(gateway) => match self.map_nat_pmp(gateway).await {
+                Ok(mapping) => return Ok(mapping),
+                Err(e) => format!("NAT-PMP: {e:#}"),
+            },
+            None => "NAT-PMP: no default gateway found".to_string(),
+        };
+        debug!("Port mapping over NAT-PMP failed ({nat_pmp_error}), trying UPnP");
+        match self.map_upnp().await {
+            Ok(mapping) => Ok(mapping),
+            Err(e) => anyhow::bail!("{nat_pmp_error}; UPnP: {e:#}"),
+        }
+    }
+
+    /// Renew `mapping` with the protocol that made it
+    pub async fn renew(&self, mapping: &PortMapping) -> Result<PortMapping> {
+        match &mapping.method {
+            MappingMethod::NatPmp { gateway } => self.map_nat_pmp(*gateway).await,
+            MappingMethod::Upnp {
+                control_url,
+                service_type,
+            } => {
+                let control_url = HttpUrl::parse(control_url)?;
+                self.add_upnp_mapping(&control_url, service_type).await
+            }
+        }
+    }
+
+    /// Ask the router to drop `mapping`
+    pub async fn unmap(&self, mapping: &PortMapping) -> Result<()> {
+        match &mapping.method {
+            MappingMethod::NatPmp { gateway } => {
+                let request = nat_pmp_map_request(self.local_addr.port(), 0, 0);
+                nat_pmp_exchange(*gateway, &request, NAT_PMP_MAP_RESPONSE_LEN).await?;
+            }
+            MappingMethod::Upnp {
+                control_url,
+                service_type,
+            } => {
+                let arguments = format!(
+                    "<NewRemoteHost></NewRemoteHost><NewExternalPort>{}</NewExternalPort>\
+                     <NewProtocol>TCP</NewProtocol>",
+                    mapping.external_addr.port()
+                );
+                soap_request(
+                    &HttpUrl::parse(control_url)?,
+                    service_type,
+                    "DeletePortMapping",
+                    &arguments,
+                )
+                .await?;
+            }
+        }
+        info!(
+            "Removed {} port mapping of {}",
+            mapping.method.name(),
+            mapping.external_addr
+        );
+        Ok(())
+    }
+
+    /// Keep `mapping` in place until the task is cancelled, renewing it
+    /// halfway through each lifetime and storing it in `data_dir` if given
+    pub async fn run(self, mut mapping: PortMapping, data_dir: Option<PathBuf>) -> Result<()> {
+        loop {
+            tokio::time::sleep(
+                Duration::from_secs(mapping.lifetime_secs / 2).max(Duration::from_secs(60)),
+            )
+            .await;
+            match self.renew(&mapping).await {
+                Ok(renewed) => {
+                    if renewed.external_addr != mapping.external_addr {
+                        warn!(
+                            "The router moved the port mapping from {} to {}; invites from now on carry the new address",
+                            mapping.external_addr, renewed.external_addr
+                        );
+                    }
+                    mapping = renewed;
+                    if let Some(data_dir) = &data_dir {
+                        mapping.save(data_dir)?;
+                    }
+                }
+                Err(e) => warn!("Failed to renew port mapping: {:#}", e),
+            }
+        }
+    }
+
+    async fn map_nat_pmp(&self, gateway: Ipv4Addr) -> Result<PortMapping> {
+        let response = nat_pmp_exchange(gateway, &[0, 0], 12).await?;
+        let external_ip = parse_nat_pmp_address_response(&response)?;
+
+        let port = self.local_addr.port();
+        let request = nat_pmp_map_request(port, port, self.lifetime.as_secs() as u32);
+        let response = nat_pmp_exchange(gateway, &request, NAT_PMP_MAP_RESPONSE_LEN).await?;
+        let (external_port, lifetime_secs) = parse_nat_pmp_map_response(&response)?;
+
+        Ok(PortMapping {
+            method: MappingMethod::NatPmp { gateway },
+            local_addr: self.local_addr,
+            external_addr: SocketAddr::new(IpAddr::V4(external_ip), external_port),
+            lifetime_secs: u64::from(lifetime_secs),
+            mapped_at: unix_now(),
+        })
+    }
+
+    async fn map_upnp(&self) -> Result<PortMapping> {
+        let location = discover_gateway_device().await?;
+        let (status, description) = tokio::time::timeout(UPNP_TIMEOUT, http_client::get(&location))
+            .await
+            .context("Timed out reading the gateway's device description")??;
+        if status != 200 {
+            anyhow::bail!("Gateway device description answered with HTTP status {status}");
+        }
+        let (service_type, control_path) =
+            find_wan_connection_service(&String::from_utf8_lossy(&description))
+                .context("The gateway offers no WAN connection service")?;
+        let control_url = location.resolve(&control_path)?;
+        self.add_upnp_mapping(&control_url, &service_type).await
+    }
+
+    async fn add_upnp_mapping(
+        &self,
+        control_url: &HttpUrl,
+        service_type: &str,
+    ) -> Result<PortMapping> {
+        let port = self.local_addr.port();
+        let internal_client = local_ip_towards(control_url.host()).await?;
+        let arguments = format!(
+            "<NewRemoteHost></NewRemoteHost><NewExternalPort>{port}</NewExternalPort>\
+             <NewProtocol>TCP</NewProtocol><NewInternalPort>{port}</NewInternalPort>\
+             <NewInternalClient>{internal_client}</NewInternalClient><NewEnabled>1</NewEnabled>\
+             <NewPortMappingDescription>{MAPPING_DESCRIPTION}</NewPortMappingDescription>\
+             <NewLeaseDuration>{}</NewLeaseDuration>",
+            self.lifetime.as_secs()
+        );
+        soap_request(control_url, service_type, "AddPortMapping", &arguments).await?;
+
+        let response = soap_request(control_url, service_type, "GetExternalIPAddress", "").await?;
+        let external_ip: IpAddr = xml_element(&response, "NewExternalIPAddress")
+            .context("The gateway did not report its external address")?
+            .trim()
+            .parse()
+            .context("The gateway reported a malformed external address")?;
+
+        Ok(PortMapping {
+            method: MappingMethod::Upnp {
+                control_url: control_url.as_str().to_string(),
+                service_type: service_type.to_string(),
+            },
+            local_addr: self.local_addr,
+            external_addr: SocketAddr::new(external_ip, port),
+            lifetime_secs: self.lifetime.as_secs(),
+            mapped_at: unix_now(),
+        })
+    }
+}
+
+/// Length of a NAT-PMP reply to a mapping request
+const NAT_PMP_MAP_RESPONSE_LEN: usize = 16;
+
+/// A NAT-PMP request to map TCP `internal_port` to `external_port` for
+/// `lifetime_secs`; a lifetime of zero removes the mapping
+pub fn nat_pmp_map_request(internal_port: u16, external_port: u16, lifetime_secs: u32) -> [u8; 12] {
+    let mut request = [0u8; 12];
+    request[1] = 2; // Map TCP
+    request[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    request[6..8].copy_from_slice(&external_port.to_be_bytes());
+    request[8..12].copy_from_slice(&lifetime_secs.to_be_bytes());
+    request
+}
+
+/// Check the header of a NAT-PMP reply to opcode `op`
+fn check_nat_pmp_header(response: &[u8], op: u8, len: usize) -> Result<()> {
+    if response.len() < len {
+        anyhow::bail!("NAT-PMP reply is {} bytes, expected {len}", response.len());
+    }
+    if response[0] != 0 || response[1] != 128 + op {
+        anyhow::bail!(
+            "Unexpected NAT-PMP reply (version {}, opcode {})",
+            response[0],
+            response[1]
+        );
+    }
+    match u16::from_be_bytes([response[2], response[3]]) {
+        0 => Ok(()),
+        1 => anyhow::bail!("the gateway does not support this NAT-PMP version"),
+        2 => anyhow::bail!("the gateway refused: mapping is disabled by its administrator"),
+        3 => anyhow::bail!("the gateway has no external address yet"),
+        4 => anyhow::bail!("the gateway is out of mappings"),
+        code => anyhow::bail!("the gateway refused with result code {code}"),
+    }
+}
+
+/// The external address in a NAT-PMP reply to an address request
+pub fn parse_nat_pmp_address_response(response: &[u8]) -> Result<Ipv4Addr> {
+    check_nat_pmp_header(response, 0, 12)?;
+    Ok(Ipv4Addr::new(
+        response[8],
+        response[9],
+        response[10],
+        response[11],
+    ))
+}
+
+/// The external port and lifetime granted in a NAT-PMP reply to a mapping
+/// request
+pub fn parse_nat_pmp_map_response(response: &[u8]) -> Result<(u16, u32)> {
+    check_nat_pmp_header(response, 2, NAT_PMP_MAP_RESPONSE_LEN)?;
+    let external_port = u16::from_be_bytes([response[10], response[11]]);
+    let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+    Ok((external_port, lifetime))
+}
+
+/// Send `request` to the NAT-PMP port of `gateway` and wait for a reply,
+/// resending as RFC 6886 describes
+async fn nat_pmp_exchange(gateway: Ipv4Addr, request: &[u8], len: usize) -> Result<Vec<u8>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket
+        .connect((gateway, NAT_PMP_PORT))
+        .await
+        .with_context(|| format!("Failed to reach gateway {gateway}"))?;
+    let mut timeout = NAT_PMP_INITIAL_TIMEOUT;
+    let mut response = vec![0u8; len];
+    for _ in 0..NAT_PMP_ATTEMPTS {
+        socket.send(request).await?;
+        if let Ok(received) = tokio::time::timeout(timeout, socket.recv(&mut response)).await {
+            let received =
+                received.with_context(|| format!("gateway {gateway} does not offer NAT-PMP"))?;
+            response.truncate(received);
+            return Ok(response);
+        }
+        timeout *= 2;
+    }
+    anyhow::bail!("gateway {gateway} did not answer")
+}
+
+/// The router of the default route, read from `/proc/net/route` on Linux
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    let table = std::fs::read_to_string("/proc/net/route").ok()?;
+    parse_route_table(&table)
+}
+
+/// The gateway of the default route in a `/proc/net/route` table
+pub fn parse_route_table(table: &str) -> Option<Ipv4Addr> {
+    table.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            [_, "00000000", gateway, ..] => {
+                // The kernel prints the address as a number in host byte
+                // order, so its native bytes are the address in order
+                let gateway = u32::from_str_radix(gateway, 16).ok()?;
+                (gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Search the local network for an Internet Gateway Device and return the
+/// URL of its description
+async fn discover_gateway_device() -> Result<HttpUrl> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\n\
+         ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\r\n"
+    );
+    socket.send_to(search.as_bytes(), SSDP_ADDR).await?;
+
+    let mut buf = vec![0u8; 2048];
+    let deadline = tokio::time::Instant::now() + SSDP_TIMEOUT;
+    loop {
+        let (len, _) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf))
+            .await
+            .context("no UPnP gateway answered")??;
+        if let Some(location) = ssdp_location(&String::from_utf8_lossy(&buf[..len])) {
+            return HttpUrl::parse(&location);
+        }
+    }
+}
+
+/// The `LOCATION` header of an SSDP search reply
+pub fn ssdp_location(reply: &str) -> Option<String> {
+    reply.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("location")
+            .then(|| value.trim().to_string())
+    })
+}
+
+/// The service type and control URL of the WAN connection service in a
+/// gateway's device description
+pub fn find_wan_connection_service(description: &str) -> Option<(String, String)> {
+    description.split("<service>").skip(1).find_map(|service| {
+        let service_type = xml_element(service, "serviceType")?;
+        if !service_type.contains(":WANIPConnection:")
+            && !service_type.contains(":WANPPPConnection:")
+        {
+            return None;
+        }
+        Some((
+            service_type.trim().to_string(),
+            xml_element(service, "controlURL")?.trim().to_string(),
+        ))
+    })
+}
+
+/// Text of the first `<name>` element in `xml`, ignoring any namespace
+/// prefix
+fn xml_element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let open = xml.find(&format!("{name}>"))?;
+    let start = open + name.len() + 1;
+    let end = start + xml[start..].find("</")?;
+    Some(&xml[start..end])
+}
+
+/// Call `action` on a UPnP service and return the response body
+async fn soap_request(
+    control_url: &HttpUrl,
+    service_type: &str,
+    action: &str,
+    arguments: &str,
+) -> Result<String> {
+    let body = format!(
+        "<?xml version=\"1.0\"?>\r\n<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body>\
+         <u:{action} xmlns:u=\"{service_type}\">{arguments}</u:{action}></s:Body></s:Envelope>\r\n"
+    );
+    let headers = [("SOAPAction", format!("\"{service_type}#{action}\""))];
+    let (status, response) = tokio::time::timeout(
+        UPNP_TIMEOUT,
+        http_client::post(
+            control_url,
+            "text/xml; charset=\"utf-8\"",
+            &headers,
+            body.as_bytes(),
+        ),
+    )
+    .await
+    .with_context(|| format!("Timed out calling {action} on the gateway"))??;
+    let response = String::from_utf8_lossy(&response).into_owned();
+    if status != 200 {
+        let reason = xml_element(&response, "errorDescription").unwrap_or("no reason given");
+        anyhow::bail!("the gateway refused {action} with HTTP status {status}: {reason}");
+    }
+    Ok(response)
+}
+
+/// The local address used to reach `host`, which the router forwards to
+async fn local_ip_towards(host: &str) -> Result<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket
+        .connect((host, 1900))
+        .await
+        .with_context(|| format!("Failed to find a route to {host}"))?;
+    Ok(socket.local_addr()?.ip())
+}
@@ -0,0 +1,87 @@
+use crate::messages::wire::{
+    SERVER_HANDLING_TIME_PER_SECOND, SERVER_MESSAGES_PER_SECOND, SERVER_MESSAGE_BURST,
+};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How much of the server one connection may use, so a chatty peer cannot
+/// starve the others
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionQuota {
+    /// Messages handled per second once the burst is spent
+    pub messages_per_second: u32,
+    /// Messages handled back to back before the rate applies
+    pub burst: u32,
+    /// Time spent handling the connection's messages per second
+    pub handling_time_per_second: Duration,
+}
+
+impl Default for ConnectionQuota {
+    fn default() -> Self {
+        Self {
+            messages_per_second: SERVER_MESSAGES_PER_SECOND,
+            burst: SERVER_MESSAGE_BURST,
+            handling_time_per_second: SERVER_HANDLING_TIME_PER_SECOND,
+        }
+    }
+}
+
+/// What one connection has left of its [`ConnectionQuota`]
+///
+/// Both allowances refill continuously. Handling time is charged after the
+/// fact, so one slow message can overdraw it; the connection then waits
+/// until the debt is repaid.
+#[derive(Debug, Clone)]
+pub struct QuotaTracker {
+    quota: ConnectionQuota,
+    /// Messages that may be handled now, up to the burst
+    messages: f64,
+    /// Seconds of handling time left, negative when overdrawn
+    handling: f64,
+    refilled_at: Instant,
+}
+
+impl QuotaTracker {
+    /// A connection starting with its full burst and handling time
+    pub fn new(quota: ConnectionQuota, now: Instant) -> Self {
+        Self {
+            quota,
+            messages: f64::from(quota.burst.max(1)),
+            handling: quota.handling_time_per_second.as_secs_f64(),
+            refilled_at: now,
+        }
+    }
+
+    /// How long to wait before reading the connection's next message
+    pub fn delay(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        let rate = f64::from(self.quota.messages_per_second.max(1));
+        let for_message = ((1.0 - self.messages) / rate).max(0.0);
+        let handling_rate = self.quota.handling_time_per_second.as_secs_f64();
+        let for_handling = if self.handling >= 0.0 || handling_rate <= 0.0 {
+            0.0
+        } else {
+            -self.handling / handling_rate
+        };
+        Duration::from_secs_f64(for_message.max(for_handling))
+    }
+
+    /// Charge one message that took `handling` to process
+    pub fn charge(&mut self, handling: Duration, now: Instant) {
+        self.refill(now);
+        self.messages -= 1.0;
+        self.handling -= handling.as_secs_f64();
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.refilled_at = now;
+        let burst = f64::from(self.quota.burst.max(1));
+        self.messages =
+            (self.messages + elapsed * f64::from(self.quota.messages_per_second.max(1))).min(burst);
+        let handling_rate = self.quota.handling_time_per_second.as_secs_f64();
+        self.handling = (self.handling + elapsed * handling_rate).min(handling_rate);
+    }
+}
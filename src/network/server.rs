@@ -1,13 +1,36 @@
-use crate::crypto::Identity;
+use crate::crypto::certificate::{
+    appointed_arbiter, store_result_signature, ARBITER_JOIN_MESSAGE_TYPE,
+};
+use crate::crypto::{
+    ArbiterRuling, Identity, PeerId, ResultSignature, RevocationRecord, RotationCertificate,
+    ScoreSheet, SealedMove, VerificationCode,
+};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 // Step 2.1: Add Required Imports
 // Add wire protocol imports
-use crate::messages::wire::{WireConfig, WireProtocolError, SERVER_MAX_CONCURRENT_CONNECTIONS};
-use crate::network::{Connection, ConnectionError};
+use crate::messages::chess::{
+    AbuseReport, ArbiterJoin, ArbiterUpdate, DevicePair, DeviceSync, IllegalMoveClaim,
+    InviteWithdraw, MoveSeal, Presence, ServerAnnouncement, Study, Vote, VoterJoin,
+};
+use crate::messages::types::Message;
+use crate::messages::wire::{
+    WireConfig, WireProtocolError, SERVER_FIRST_BYTE_TIMEOUT, SERVER_HANDSHAKE_DEADLINE,
+    SERVER_MAX_CONCURRENT_CONNECTIONS, SERVER_MAX_PENDING_HANDSHAKES,
+};
+use crate::network::connection::new_connection_id;
+use crate::network::{
+    Connection, ConnectionError, ConnectionQuota, EmailNotifier, Heartbeat, Notifier, QuotaTracker,
+    StatsRegistry, Webhooks,
+};
+use crate::storage::models::{ArbitrationRuling, GameStatus, PeerEventType, SyncEntry};
+use crate::storage::Database;
+use crate::storage::StorageError;
 // Add async handling imports
 use tokio::task::{self, JoinHandle};
 use tracing::{debug, error, info, instrument, warn};
@@ -67,10 +90,18 @@ use tokio::signal;
 /// ## Wire Protocol Configuration
 /// - **Message size limits**: Configure via `WireConfig::max_message_size`
 /// - **Timeout values**: Adjust read/write timeouts for network conditions
-/// - **Handshake timeout**: Fixed at 10 seconds for security
+/// - **Handshake deadlines**: A new connection must send its first byte and
+///   complete its handshake within [`HandshakeLimits`], and only so many may
+///   be handshaking at once, so slow or half-open clients are dropped rather
+///   than holding connection slots
 ///
 /// ## Connection Management
-/// - **Concurrent connection limit**: Set via `SERVER_MAX_CONCURRENT_CONNECTIONS`
+/// - **Concurrent connection limit**: Set via `SERVER_MAX_CONCURRENT_CONNECTIONS`;
+///   each connection task holds one of that many semaphore permits
+/// - **Fair scheduling**: Messages wait their turn, first come first served,
+///   for one of a bounded number of handler slots, and each connection is
+///   held to a [`ConnectionQuota`] of messages and handling time, so a
+///   chatty peer is slowed down rather than starving the others
 /// - **Automatic cleanup**: Completed connections are cleaned up automatically
 ///
 /// # Graceful Shutdown
@@ -124,6 +155,107 @@ pub struct Server {
     identity: Arc<Identity>,
     listener: TcpListener,
     wire_config: WireConfig,
+    peer_history: Option<Arc<Database>>,
+    notifier: Notifier,
+    webhooks: Arc<Webhooks>,
+    email: Option<Arc<EmailNotifier>>,
+    stats: StatsRegistry,
+    announcements: broadcast::Sender<ServerAnnouncement>,
+    handshake_limits: HandshakeLimits,
+    pending_handshakes: Arc<AtomicUsize>,
+    connection_slots: Arc<Semaphore>,
+    scheduling: Scheduling,
+    heartbeat: Option<Heartbeat>,
+}
+
+/// How the messages of every connection share the server
+#[derive(Clone)]
+struct Scheduling {
+    quota: ConnectionQuota,
+    /// Permits to handle a message, granted in the order they were asked for
+    handlers: Arc<Semaphore>,
+}
+
+impl Scheduling {
+    fn new(quota: ConnectionQuota, parallel_handlers: usize) -> Self {
+        Self {
+            quota,
+            handlers: Arc::new(Semaphore::new(parallel_handlers.max(1))),
+        }
+    }
+}
+
+impl Default for Scheduling {
+    fn default() -> Self {
+        let parallel_handlers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        Self::new(ConnectionQuota::default(), parallel_handlers)
+    }
+}
+
+/// Deadlines a new connection must meet before it is trusted with a
+/// connection slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandshakeLimits {
+    /// How long a connection may stay silent after being accepted
+    pub first_byte_timeout: Duration,
+    /// How long after being accepted the handshake must be complete
+    pub handshake_deadline: Duration,
+    /// Connections that may be handshaking at once
+    pub max_pending: usize,
+}
+
+impl Default for HandshakeLimits {
+    fn default() -> Self {
+        Self {
+            first_byte_timeout: SERVER_FIRST_BYTE_TIMEOUT,
+            handshake_deadline: SERVER_HANDSHAKE_DEADLINE,
+            max_pending: SERVER_MAX_PENDING_HANDSHAKES,
+        }
+    }
+}
+
+/// A connection counted as handshaking until this is dropped
+struct PendingHandshake(Arc<AtomicUsize>);
+
+impl PendingHandshake {
+    fn start(pending: &Arc<AtomicUsize>) -> Self {
+        pending.fetch_add(1, Ordering::SeqCst);
+        Self(Arc::clone(pending))
+    }
+}
+
+impl Drop for PendingHandshake {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The task waiting for a shutdown signal, stopped when dropped
+///
+/// The watchdog of `mate serve` aborts a stuck accept loop, which then never
+/// reaches its graceful shutdown; without this each restart would leave
+/// another listener behind.
+struct ShutdownListener(JoinHandle<()>);
+
+impl Drop for ShutdownListener {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// How often a running accept loop beats its heartbeat
+pub const ACCEPT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How many announcements a slow connection may fall behind by before it
+/// misses some
+const ANNOUNCEMENT_CAPACITY: usize = 16;
+
+/// Where a connection reports the messages it receives
+struct Reporters {
+    notifier: Notifier,
+    webhooks: Arc<Webhooks>,
+    email: Option<Arc<EmailNotifier>>,
+    stats: StatsRegistry,
 }
 
 impl Server {
@@ -150,6 +282,17 @@ impl Server {
             identity,
             listener,
             wire_config,
+            peer_history: None,
+            notifier: Notifier::default(),
+            webhooks: Arc::default(),
+            email: None,
+            stats: StatsRegistry::new(),
+            announcements: broadcast::channel(ANNOUNCEMENT_CAPACITY).0,
+            handshake_limits: HandshakeLimits::default(),
+            pending_handshakes: Arc::default(),
+            connection_slots: Arc::new(Semaphore::new(SERVER_MAX_CONCURRENT_CONNECTIONS)),
+            scheduling: Scheduling::default(),
+            heartbeat: None,
         })
     }
 
@@ -178,9 +321,92 @@ impl Server {
             identity,
             listener,
             wire_config,
+            peer_history: None,
+            notifier: Notifier::default(),
+            webhooks: Arc::default(),
+            email: None,
+            stats: StatsRegistry::new(),
+            announcements: broadcast::channel(ANNOUNCEMENT_CAPACITY).0,
+            handshake_limits: HandshakeLimits::default(),
+            pending_handshakes: Arc::default(),
+            connection_slots: Arc::new(Semaphore::new(SERVER_MAX_CONCURRENT_CONNECTIONS)),
+            scheduling: Scheduling::default(),
+            heartbeat: None,
         })
     }
 
+    /// Record handshake outcomes, protocol violations, and DoS events per peer
+    pub fn with_peer_history(mut self, database: Arc<Database>) -> Self {
+        self.peer_history = Some(database);
+        self
+    }
+
+    /// Raise desktop notifications for incoming moves and invites
+    pub fn with_notifications(mut self, notifier: Notifier) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    /// Post incoming moves, invitations, and game results to webhooks
+    pub fn with_webhooks(mut self, webhooks: Webhooks) -> Self {
+        self.webhooks = Arc::new(webhooks);
+        self
+    }
+
+    /// Email incoming moves and invites while the user is away
+    pub fn with_email(mut self, email: EmailNotifier) -> Self {
+        self.email = Some(Arc::new(email));
+        self
+    }
+
+    /// Deadlines and limits for connections that have not yet handshaken
+    pub fn with_handshake_limits(mut self, limits: HandshakeLimits) -> Self {
+        self.handshake_limits = limits;
+        self
+    }
+
+    /// How many messages, and how much handling time, each connection may use
+    pub fn with_connection_quota(mut self, quota: ConnectionQuota) -> Self {
+        self.scheduling.quota = quota;
+        self
+    }
+
+    /// How many messages, across all connections, may be handled at once;
+    /// one per CPU by default
+    pub fn with_parallel_handlers(mut self, handlers: usize) -> Self {
+        self.scheduling = Scheduling::new(self.scheduling.quota, handlers);
+        self
+    }
+
+    /// Count connections in `stats`, e.g. those of the server this one
+    /// replaces
+    pub fn with_stats(mut self, stats: StatsRegistry) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Beat `heartbeat` every [`ACCEPT_HEARTBEAT_INTERVAL`] while the accept
+    /// loop is responsive
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Statistics of the connections this server accepts, which stay
+    /// readable while it runs
+    pub fn stats(&self) -> StatsRegistry {
+        self.stats.clone()
+    }
+
+    /// Send `text` to every peer connected to this server, returning how
+    /// many connections it was sent to
+    pub fn announce(&self, text: &str) -> usize {
+        Self::broadcast_announcement(
+            &self.announcements,
+            ServerAnnouncement::new(text.to_string()),
+        )
+    }
+
     /// Get the local address the server is bound to
     pub fn local_addr(&self) -> Result<std::net::SocketAddr> {
         Ok(self.listener.local_addr()?)
@@ -196,20 +422,21 @@ impl Server {
         let (shutdown_tx, mut shutdown_rx) = broadcast::channel::<()>(1);
 
         // Track active connections for management
-        let mut active_connections: HashMap<usize, JoinHandle<()>> = HashMap::new();
-        let mut connection_counter = 0usize;
+        let mut active_connections: HashMap<String, JoinHandle<()>> = HashMap::new();
 
         // Spawn shutdown signal handler
         let shutdown_handle = {
             let shutdown_tx = shutdown_tx.clone();
-            tokio::spawn(async move {
+            ShutdownListener(tokio::spawn(async move {
                 if let Err(e) = Self::wait_for_shutdown().await {
                     error!("Error in shutdown handler: {}", e);
                 }
                 let _ = shutdown_tx.send(());
-            })
+            }))
         };
 
+        let mut heartbeat_interval = tokio::time::interval(ACCEPT_HEARTBEAT_INTERVAL);
+
         // Main server loop with shutdown handling
         loop {
             tokio::select! {
@@ -219,35 +446,79 @@ impl Server {
                     break;
                 }
 
+                // Show the watchdog the loop is still turning
+                _ = heartbeat_interval.tick() => {
+                    if let Some(heartbeat) = &self.heartbeat {
+                        heartbeat.beat();
+                    }
+                }
+
                 // Accept new connections
                 result = self.listener.accept() => {
                     match result {
                         Ok((stream, peer_addr)) => {
-                            connection_counter += 1;
-                            let connection_id = connection_counter;
+                            let connection_id = new_connection_id();
 
                             info!("Accepted new connection {} from {}", connection_id, peer_addr);
 
                             // Check connection limits
-                            if active_connections.len() >= SERVER_MAX_CONCURRENT_CONNECTIONS {
+                            let Ok(slot) = Arc::clone(&self.connection_slots).try_acquire_owned() else {
                                 warn!("Connection limit reached ({}), rejecting connection from {}",
                                       SERVER_MAX_CONCURRENT_CONNECTIONS, peer_addr);
+                                self.stats.record_refused_connection();
+                                Self::record_peer_event(
+                                    self.peer_history.as_deref(),
+                                    &address_peer_key(&peer_addr),
+                                    PeerEventType::DosEvent,
+                                    Some("Connection limit reached".to_string()),
+                                    Some(peer_addr.to_string()),
+                                );
+                                continue;
+                            };
+
+                            // Slow clients may not fill every slot with handshakes
+                            // that never finish
+                            if self.pending_handshakes.load(Ordering::SeqCst) >= self.handshake_limits.max_pending {
+                                warn!("Too many pending handshakes ({}), rejecting connection from {}",
+                                      self.handshake_limits.max_pending, peer_addr);
+                                self.stats.record_refused_connection();
+                                Self::record_peer_event(
+                                    self.peer_history.as_deref(),
+                                    &address_peer_key(&peer_addr),
+                                    PeerEventType::DosEvent,
+                                    Some("Handshake limit reached".to_string()),
+                                    Some(peer_addr.to_string()),
+                                );
                                 continue;
                             }
+                            let pending = PendingHandshake::start(&self.pending_handshakes);
+                            let limits = self.handshake_limits;
+                            let scheduling = self.scheduling.clone();
 
                             // Clone necessary data for the spawned task
                             let identity = Arc::clone(&self.identity);
                             let wire_config = self.wire_config.clone();
                             let shutdown_rx = shutdown_tx.subscribe(); // Create subscriber for connection
+                            let peer_history = self.peer_history.clone();
+                            let announcements = self.announcements.clone();
+                            let reporters = Reporters {
+                                notifier: self.notifier,
+                                webhooks: Arc::clone(&self.webhooks),
+                                email: self.email.clone(),
+                                stats: self.stats.clone(),
+                            };
+                            let task_connection_id = connection_id.clone();
 
                             // Spawn async task for each connection with shutdown support
                             let handle = task::spawn(async move {
+                                // The slot is free again once the connection ends
+                                let _slot: OwnedSemaphorePermit = slot;
                                 if let Err(e) = Self::handle_connection_with_shutdown(
-                                    stream, identity, wire_config, connection_id, shutdown_rx
+                                    stream, identity, wire_config, task_connection_id.clone(), shutdown_rx, peer_history, announcements, reporters, limits, pending, scheduling
                                 ).await {
-                                    error!("Connection {} failed: {}", connection_id, e);
+                                    error!("Connection {} failed: {}", task_connection_id, e);
                                 } else {
-                                    info!("Connection {} completed successfully", connection_id);
+                                    info!("Connection {} completed successfully", task_connection_id);
                                 }
                             });
 
@@ -282,59 +553,566 @@ impl Server {
     }
 
     /// Handle individual connection lifecycle with shutdown support
-    #[instrument(skip(stream, identity, wire_config, shutdown_rx), fields(connection_id = connection_id))]
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip(stream, identity, wire_config, shutdown_rx, peer_history, announcements, reporters, limits, pending, scheduling), fields(connection_id = %connection_id, peer_id = tracing::field::Empty))]
     async fn handle_connection_with_shutdown(
         stream: tokio::net::TcpStream,
         identity: Arc<Identity>,
         wire_config: WireConfig,
-        connection_id: usize,
+        connection_id: String,
         mut shutdown_rx: broadcast::Receiver<()>,
+        peer_history: Option<Arc<Database>>,
+        announcements: broadcast::Sender<ServerAnnouncement>,
+        reporters: Reporters,
+        limits: HandshakeLimits,
+        pending: PendingHandshake,
+        scheduling: Scheduling,
     ) -> Result<()> {
         info!("Handling connection {}", connection_id);
 
+        let accepted_at = tokio::time::Instant::now();
+        let remote_addr = stream.peer_addr().ok();
+        let remote_addr_str = remote_addr.map(|addr| addr.to_string());
+
+        // A client that never speaks is dropped before any handshake state
+        // is set up for it
+        if tokio::time::timeout(limits.first_byte_timeout, stream.peek(&mut [0u8; 1]))
+            .await
+            .is_err()
+        {
+            warn!(
+                "Connection {} sent nothing within {:?}, closing it",
+                connection_id, limits.first_byte_timeout
+            );
+            Self::record_handshake_timeout(
+                &reporters.stats,
+                peer_history.as_deref(),
+                remote_addr.as_ref(),
+                "Sent nothing after connecting",
+            );
+            anyhow::bail!(
+                "No data received within {:?} of connecting",
+                limits.first_byte_timeout
+            );
+        }
+
+        let local_peer_id = identity.peer_id().to_string();
+        let signer = Arc::clone(&identity);
+
         // Create Connection with wire protocol
-        let mut connection = Connection::new_with_config(stream, identity, wire_config).await;
+        let mut connection = Connection::new_with_config(stream, identity, wire_config)
+            .await
+            .with_connection_id(connection_id.clone());
 
-        // Perform handshake
-        let _peer_id = match connection.handle_handshake_request().await {
+        // Perform handshake, which must finish by the deadline however
+        // slowly the client trickles in its request
+        let handshake = tokio::time::timeout_at(
+            accepted_at + limits.handshake_deadline,
+            connection.handle_handshake_request(),
+        )
+        .await;
+        let Ok(handshake) = handshake else {
+            warn!(
+                "Connection {} did not complete its handshake within {:?}, closing it",
+                connection_id, limits.handshake_deadline
+            );
+            Self::record_handshake_timeout(
+                &reporters.stats,
+                peer_history.as_deref(),
+                remote_addr.as_ref(),
+                "Handshake not completed in time",
+            );
+            reporters.stats.close(&connection_id, &connection.stats());
+            anyhow::bail!(
+                "Handshake not completed within {:?}",
+                limits.handshake_deadline
+            );
+        };
+        drop(pending);
+        let peer_id = match handshake {
             Ok(peer_id) => {
+                tracing::Span::current().record("peer_id", peer_id.as_str());
                 info!(
                     "Handshake successful for connection {} with peer: {}",
                     connection_id, peer_id
                 );
+                Self::record_peer_event(
+                    peer_history.as_deref(),
+                    &peer_id,
+                    PeerEventType::HandshakeSuccess,
+                    connection
+                        .clock_offset()
+                        .map(|offset| format!("Clock offset {offset:+}s")),
+                    remote_addr_str.clone(),
+                );
                 peer_id
             }
             Err(e) => {
                 error!("Handshake failed for connection {}: {}", connection_id, e);
+                // The peer has not proven an identity yet, so key the failure by address
+                if let Some(addr) = &remote_addr {
+                    Self::record_peer_event(
+                        peer_history.as_deref(),
+                        &address_peer_key(addr),
+                        PeerEventType::HandshakeFailure,
+                        Some(format!("{e:#}")),
+                        remote_addr_str.clone(),
+                    );
+                }
+                reporters.stats.close(&connection_id, &connection.stats());
                 return Err(e);
             }
         };
 
+        reporters.stats.identify(&connection_id, &peer_id);
+        if let Some(session) = connection.session_id() {
+            Self::introduce_new_peer(peer_history.as_deref(), &local_peer_id, &peer_id, session);
+        }
+
+        // Only announcements sent after the handshake reach this peer
+        let mut announcement_rx = announcements.subscribe();
+
+        // The message being handled holds a handler permit until the loop
+        // comes round again, and is then charged to the connection's quota
+        let mut quota = QuotaTracker::new(scheduling.quota, tokio::time::Instant::now());
+        let mut handling: Option<(OwnedSemaphorePermit, tokio::time::Instant)> = None;
+
         // Message processing loop with shutdown handling
         loop {
+            if let Some((permit, started)) = handling.take() {
+                drop(permit);
+                let now = tokio::time::Instant::now();
+                quota.charge(now - started, now);
+            }
+            reporters.stats.update(&connection_id, &connection.stats());
+
+            // A connection over its quota is not read from until it has
+            // earned more, which slows a flooding peer down to its share
+            let delay = quota.delay(tokio::time::Instant::now());
+            if !delay.is_zero() {
+                debug!(
+                    "Connection {} is over its quota, waiting {:?}",
+                    connection_id, delay
+                );
+                reporters.stats.record_throttled();
+                tokio::select! {
+                    Ok(()) = shutdown_rx.recv() => {
+                        info!("Shutdown signal received, closing connection {}", connection_id);
+                        break;
+                    }
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+
             tokio::select! {
-                // Handle shutdown signal
-                _ = shutdown_rx.recv() => {
+                // Handle shutdown signal; an accept loop restarted by the
+                // watchdog closes the channel, but leaves its connections be
+                Ok(()) = shutdown_rx.recv() => {
                     info!("Shutdown signal received, closing connection {}", connection_id);
                     break;
                 }
 
+                // Pass on the operator's announcements
+                announcement = announcement_rx.recv() => {
+                    match announcement {
+                        Ok(announcement) => {
+                            if let Err(e) = connection.send_message(Message::ServerAnnouncement(announcement)).await {
+                                error!("Failed to send announcement on connection {}: {}", connection_id, e);
+                                break;
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(missed)) => {
+                            warn!("Connection {} missed {} announcements", connection_id, missed);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {}
+                    }
+                }
+
                 // Process messages
                 result = connection.receive_message() => {
                     match result {
                         Ok((message, sender)) => {
-                            info!("Received {} message from {} on connection {}",
+                            let Ok(permit) = Arc::clone(&scheduling.handlers).acquire_owned().await else {
+                                break;
+                            };
+                            handling = Some((permit, tokio::time::Instant::now()));
+
+                            info!(msg_type = message.message_type(), game_id = message.get_game_id(),
+                                  "Received {} message from {} on connection {}",
                                   message.message_type(), sender, connection_id);
 
+                            if matches!(message, Message::GameInvite(_))
+                                && Self::is_revoked(peer_history.as_deref(), &sender)
+                            {
+                                warn!("Refused invitation from {}, whose key was revoked", sender);
+                                reporters.stats.record_rejected();
+                                Self::record_peer_event(
+                                    peer_history.as_deref(),
+                                    &peer_id,
+                                    PeerEventType::ProtocolViolation,
+                                    Some("Invitation signed with a revoked key".to_string()),
+                                    remote_addr_str.clone(),
+                                );
+                                continue;
+                            }
+
+                            if let Message::GameInvite(invite) = &message {
+                                if invite.is_expired(Database::current_timestamp()) {
+                                    warn!("Refused invitation {} from {}, which has expired", invite.game_id, sender);
+                                    continue;
+                                }
+                            }
+
+                            reporters.notifier.notify(&message, &sender);
+                            if let Some(email) = &reporters.email {
+                                email.notify(&message, &sender);
+                            }
+                            // Result signatures are reported once they check out
+                            if !matches!(message, Message::ResultSignature(_)) {
+                                reporters.webhooks.dispatch(&message, &sender);
+                            }
+
                             // Handle different message types
                             match message.message_type() {
                                 "Ping" => {
+                                    // Chat from a muted peer is not passed back; an empty
+                                    // echo keeps the connection alive
+                                    let message = match message {
+                                        Message::Ping { nonce, payload }
+                                            if !payload.is_empty()
+                                                && Self::is_muted(peer_history.as_deref(), &sender) =>
+                                        {
+                                            debug!("Dropped chat from muted peer {}", sender);
+                                            Message::new_ping(nonce, String::new())
+                                        }
+                                        message => message,
+                                    };
                                     debug!("Echoing ping message back to {}", sender);
                                     if let Err(e) = connection.send_message(message).await {
                                         error!("Failed to echo message on connection {}: {}", connection_id, e);
                                         break;
                                     }
                                 }
+                                "KeyRotation" => {
+                                    if let Message::KeyRotation(certificate) = &message {
+                                        if let Err(reason) = Self::apply_key_rotation(
+                                            peer_history.as_deref(),
+                                            certificate,
+                                            &sender,
+                                        ) {
+                                            warn!("Rejected key rotation from {}: {}", sender, reason);
+                                            reporters.stats.record_rejected();
+                                            Self::record_peer_event(
+                                                peer_history.as_deref(),
+                                                &peer_id,
+                                                PeerEventType::ProtocolViolation,
+                                                Some(format!("Rejected key rotation: {reason}")),
+                                                remote_addr_str.clone(),
+                                            );
+                                            continue;
+                                        }
+                                    }
+                                    // Echoing the certificate back acknowledges it
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to acknowledge key rotation on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "KeyRevocation" => {
+                                    if let Message::KeyRevocation(record) = &message {
+                                        if let Err(reason) = Self::apply_key_revocation(
+                                            peer_history.as_deref(),
+                                            record,
+                                        ) {
+                                            warn!("Rejected key revocation from {}: {}", sender, reason);
+                                            reporters.stats.record_rejected();
+                                            Self::record_peer_event(
+                                                peer_history.as_deref(),
+                                                &peer_id,
+                                                PeerEventType::ProtocolViolation,
+                                                Some(format!("Rejected key revocation: {reason}")),
+                                                remote_addr_str.clone(),
+                                            );
+                                            continue;
+                                        }
+                                    }
+                                    // Echoing the record back acknowledges it
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to acknowledge key revocation on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "ResultSignature" => {
+                                    let mut reply = message.clone();
+                                    if let Message::ResultSignature(signature) = &message {
+                                        match Self::apply_result_signature(
+                                            peer_history.as_deref(),
+                                            &signer,
+                                            signature,
+                                            &sender,
+                                        ) {
+                                            Ok(own) => {
+                                                reporters.webhooks.dispatch(&message, &sender);
+                                                if let Some(own) = own {
+                                                    reply = Message::new_result_signature(own);
+                                                }
+                                            }
+                                            Err(reason) => {
+                                                warn!("Rejected result signature from {}: {}", sender, reason);
+                                                reporters.stats.record_rejected();
+                                                Self::record_peer_event(
+                                                    peer_history.as_deref(),
+                                                    &peer_id,
+                                                    PeerEventType::ProtocolViolation,
+                                                    Some(format!("Rejected result signature: {reason}")),
+                                                    remote_addr_str.clone(),
+                                                );
+                                                continue;
+                                            }
+                                        }
+                                    }
+                                    // Reply with our own signature when our copy of the game
+                                    // has the same result; otherwise echo to acknowledge
+                                    if let Err(e) = connection.send_message(reply).await {
+                                        error!("Failed to answer result signature on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "VoterJoin" | "Vote" => {
+                                    let applied = match &message {
+                                        Message::VoterJoin(join) => Self::apply_voter_join(
+                                            peer_history.as_deref(),
+                                            join,
+                                            &sender,
+                                        ),
+                                        Message::Vote(vote) => {
+                                            Self::apply_vote(peer_history.as_deref(), vote, &sender)
+                                        }
+                                        _ => Ok(()),
+                                    };
+                                    if let Err(reason) = applied {
+                                        warn!("Refused {} from {}: {}", message.message_type(), sender, reason);
+                                        continue;
+                                    }
+                                    // Echoing the message back acknowledges it
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to acknowledge vote on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "Study" => {
+                                    if let Message::Study(study) = &message {
+                                        if let Err(reason) = Self::keep_study(
+                                            peer_history.as_deref(),
+                                            study,
+                                            &sender,
+                                        ) {
+                                            warn!("Failed to keep study from {}: {}", sender, reason);
+                                            continue;
+                                        }
+                                    }
+                                    // Echoing the study back acknowledges it
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to acknowledge study on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "ServerAnnouncement" => {
+                                    // Only the operator, holding the server's own key, may announce
+                                    if sender != local_peer_id {
+                                        warn!("Refused announcement from {}, who does not operate this server", sender);
+                                        reporters.stats.record_rejected();
+                                        Self::record_peer_event(
+                                            peer_history.as_deref(),
+                                            &peer_id,
+                                            PeerEventType::ProtocolViolation,
+                                            Some("Announcement from a peer other than the operator".to_string()),
+                                            remote_addr_str.clone(),
+                                        );
+                                        continue;
+                                    }
+                                    if let Message::ServerAnnouncement(announcement) = message {
+                                        let reached = Self::broadcast_announcement(&announcements, announcement);
+                                        info!("Operator announcement sent to {} connections", reached);
+                                    }
+                                }
+                                "InviteWithdraw" => {
+                                    if let Message::InviteWithdraw(withdraw) = &message {
+                                        if let Err(reason) = Self::apply_invite_withdraw(
+                                            peer_history.as_deref(),
+                                            withdraw,
+                                            &sender,
+                                        ) {
+                                            warn!("Refused invitation withdrawal from {}: {}", sender, reason);
+                                            continue;
+                                        }
+                                    }
+                                    // Echoing the withdrawal back acknowledges it
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to acknowledge invitation withdrawal on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "MoveSeal" | "SealReveal" => {
+                                    let applied = match &message {
+                                        Message::MoveSeal(seal) => Self::apply_move_seal(
+                                            peer_history.as_deref(),
+                                            seal,
+                                            &sender,
+                                        ),
+                                        Message::SealReveal(sealed) => Self::apply_seal_reveal(
+                                            peer_history.as_deref(),
+                                            sealed,
+                                            &sender,
+                                        ),
+                                        _ => Ok(()),
+                                    };
+                                    if let Err(reason) = applied {
+                                        warn!("Refused {} from {}: {}", message.message_type(), sender, reason);
+                                        reporters.stats.record_rejected();
+                                        Self::record_peer_event(
+                                            peer_history.as_deref(),
+                                            &peer_id,
+                                            PeerEventType::ProtocolViolation,
+                                            Some(format!("Refused {}: {reason}", message.message_type())),
+                                            remote_addr_str.clone(),
+                                        );
+                                        continue;
+                                    }
+                                    // Echoing the message back acknowledges it
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to acknowledge sealed move on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "Presence" => {
+                                    if let Message::Presence(presence) = &message {
+                                        if let Err(reason) = Self::apply_presence(
+                                            peer_history.as_deref(),
+                                            presence,
+                                            &sender,
+                                        ) {
+                                            warn!("Ignored presence from {}: {}", sender, reason);
+                                            continue;
+                                        }
+                                    }
+                                    // Echoing the presence back acknowledges it
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to acknowledge presence on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "AbuseReport" => {
+                                    if let Message::AbuseReport(report) = &message {
+                                        if let Err(reason) = Self::apply_abuse_report(
+                                            peer_history.as_deref(),
+                                            report,
+                                            &sender,
+                                        ) {
+                                            warn!("Refused abuse report from {}: {}", sender, reason);
+                                            continue;
+                                        }
+                                    }
+                                    // Echoing the report back acknowledges it
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to acknowledge abuse report on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "DevicePair" => {
+                                    if let Message::DevicePair(pair) = &message {
+                                        if let Err(reason) = Self::apply_device_pair(
+                                            peer_history.as_deref(),
+                                            pair,
+                                            &sender,
+                                        ) {
+                                            warn!("Refused pairing from {}: {}", sender, reason);
+                                            continue;
+                                        }
+                                    }
+                                    // Echoing the request back confirms the link
+                                    if let Err(e) = connection.send_message(message).await {
+                                        error!("Failed to confirm pairing on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "DeviceSync" => {
+                                    let reply = match &message {
+                                        Message::DeviceSync(sync) => Self::apply_device_sync(
+                                            peer_history.as_deref(),
+                                            sync,
+                                            &sender,
+                                            &local_peer_id,
+                                        ),
+                                        _ => Err("not a device sync".to_string()),
+                                    };
+                                    let reply = match reply {
+                                        Ok(reply) => reply,
+                                        Err(reason) => {
+                                            warn!("Refused device sync from {}: {}", sender, reason);
+                                            continue;
+                                        }
+                                    };
+                                    if let Err(e) = connection.send_message(Message::DeviceSync(reply)).await {
+                                        error!("Failed to answer device sync on connection {}: {}", connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "ArbiterJoin" | "ArbiterUpdate" | "IllegalMoveClaim" => {
+                                    let reply = match &message {
+                                        Message::ArbiterJoin(join) => Self::apply_arbiter_join(
+                                            peer_history.as_deref(),
+                                            &signer,
+                                            join,
+                                            &sender,
+                                        )
+                                        .map(Message::ArbiterUpdate),
+                                        Message::ArbiterUpdate(update) => Self::apply_arbiter_update(
+                                            peer_history.as_deref(),
+                                            &signer,
+                                            update,
+                                            &sender,
+                                        )
+                                        .map(|signature| {
+                                            signature.map_or_else(
+                                                || message.clone(),
+                                                Message::new_result_signature,
+                                            )
+                                        }),
+                                        Message::IllegalMoveClaim(claim) => {
+                                            Self::apply_illegal_move_claim(
+                                                peer_history.as_deref(),
+                                                &signer,
+                                                claim,
+                                                &sender,
+                                            )
+                                            .map(Message::ArbiterRuling)
+                                        }
+                                        _ => Err("not an arbiter message".to_string()),
+                                    };
+                                    let reply = match reply {
+                                        Ok(reply) => reply,
+                                        Err(reason) => {
+                                            warn!("Refused {} from {}: {}", message.message_type(), sender, reason);
+                                            continue;
+                                        }
+                                    };
+                                    // An arbiter joining gets the game so far, a finished game
+                                    // gets the arbiter's signature, and a claim gets a ruling;
+                                    // any other update is echoed to acknowledge it
+                                    if let Err(e) = connection.send_message(reply).await {
+                                        error!("Failed to answer {} on connection {}: {}", message.message_type(), connection_id, e);
+                                        break;
+                                    }
+                                }
+                                "GameInvite" => {
+                                    if !Self::is_verified(peer_history.as_deref(), &sender) {
+                                        warn!(
+                                            "Invitation from unverified peer {}; compare codes with 'mate peers verify {}' before trusting it",
+                                            sender, sender
+                                        );
+                                    }
+                                }
                                 _ => {
                                     debug!("Received {} message from {} (no specific handler)",
                                            message.message_type(), sender);
@@ -351,6 +1129,18 @@ impl Server {
                                 }
                                 _ => {
                                     error!("Error receiving message on connection {}: {}", connection_id, e);
+                                    if is_oversized(&e) {
+                                        reporters.stats.record_oversized();
+                                    } else {
+                                        reporters.stats.record_rejected();
+                                    }
+                                    Self::record_peer_event(
+                                        peer_history.as_deref(),
+                                        &peer_id,
+                                        PeerEventType::ProtocolViolation,
+                                        Some(e.to_string()),
+                                        remote_addr_str.clone(),
+                                    );
                                 }
                             }
                             break;
@@ -361,6 +1151,7 @@ impl Server {
         }
 
         // Connection cleanup
+        reporters.stats.close(&connection_id, &connection.stats());
         if let Err(e) = connection.close().await {
             warn!("Error during connection {} cleanup: {}", connection_id, e);
         } else {
@@ -370,6 +1161,675 @@ impl Server {
         Ok(())
     }
 
+    /// Queue an announcement for every open connection
+    fn broadcast_announcement(
+        announcements: &broadcast::Sender<ServerAnnouncement>,
+        announcement: ServerAnnouncement,
+    ) -> usize {
+        // Without connections there is nobody to tell
+        announcements.send(announcement).unwrap_or(0)
+    }
+
+    /// Show the verification code for a peer connecting for the first time
+    fn introduce_new_peer(
+        peer_history: Option<&Database>,
+        local_peer_id: &str,
+        peer_id: &str,
+        session: u64,
+    ) {
+        let Some(database) = peer_history else {
+            return;
+        };
+        match database.note_peer_seen(peer_id) {
+            Ok(true) if !Self::is_verified(Some(database), peer_id) => {
+                let code = VerificationCode::derive(local_peer_id, peer_id, session);
+                warn!(
+                    "First connection from peer {} ({}). Compare this code with them: {}. Once it matches, run 'mate peers verify {}'",
+                    peer_id,
+                    PeerId::from_string(peer_id.to_string()).fingerprint(),
+                    code.render(true),
+                    peer_id
+                );
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to record peer {}: {}", peer_id, e),
+        }
+    }
+
+    /// Whether the user verified a peer; unknown without a database
+    fn is_verified(peer_history: Option<&Database>, peer_id: &str) -> bool {
+        peer_history.is_some_and(|database| database.is_peer_verified(peer_id).unwrap_or(false))
+    }
+
+    /// Verify a peer's rotation certificate and move its stored history to the new key
+    ///
+    /// The certificate must be sent by the new key, so a third party cannot
+    /// replay someone else's rotation.
+    fn apply_key_rotation(
+        peer_history: Option<&Database>,
+        certificate: &RotationCertificate,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        certificate.verify().map_err(|e| e.to_string())?;
+        if certificate.new_peer_id != sender {
+            return Err("certificate was not sent by the new key".to_string());
+        }
+
+        let Some(database) = peer_history else {
+            return Ok(());
+        };
+        let certificate_json = serde_json::to_string(certificate).map_err(|e| e.to_string())?;
+        let summary = database
+            .apply_key_rotation(
+                &certificate.old_peer_id,
+                &certificate.new_peer_id,
+                certificate.issued_at,
+                &certificate_json,
+            )
+            .map_err(|e| e.to_string())?;
+        info!(
+            "Peer {} rotated its key to {}; moved {} games, {} messages, {} history events, {} aliases",
+            certificate.old_peer_id,
+            certificate.new_peer_id,
+            summary.games,
+            summary.messages,
+            summary.peer_events,
+            summary.aliases
+        );
+        Ok(())
+    }
+
+    /// Register a peer as a voter in one of our consultation games
+    fn apply_voter_join(
+        peer_history: Option<&Database>,
+        join: &VoterJoin,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Err("no database to keep votes in".to_string());
+        };
+        if database
+            .get_consultation(&join.game_id)
+            .map_err(|e| e.to_string())?
+            .is_none()
+        {
+            return Err(format!("game {} is not put to a vote", join.game_id));
+        }
+        if database
+            .add_voter(&join.game_id, sender)
+            .map_err(|e| e.to_string())?
+        {
+            info!("Peer {} joined the vote in game {}", sender, join.game_id);
+        }
+        Ok(())
+    }
+
+    /// Drop the pending game a withdrawn invitation opened
+    ///
+    /// Only the inviter may withdraw, and only while the invitation is
+    /// unanswered. Invitations that were never recorded need no cleanup.
+    fn apply_invite_withdraw(
+        peer_history: Option<&Database>,
+        withdraw: &InviteWithdraw,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Ok(());
+        };
+        let game = match database.get_game(&withdraw.game_id) {
+            Ok(game) => game,
+            Err(StorageError::GameNotFound { .. }) => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        };
+        if game.opponent_peer_id != sender {
+            return Err(format!("sender did not invite to game {}", game.id));
+        }
+        if game.status != GameStatus::Pending {
+            return Err(format!("game {} was already answered", game.id));
+        }
+        database.delete_game(&game.id).map_err(|e| e.to_string())?;
+        info!(
+            "Peer {} withdrew the invitation to game {}",
+            sender, game.id
+        );
+        Ok(())
+    }
+
+    /// Keep the commitment to a move the opponent sealed on adjourning
+    ///
+    /// Only the opponent in an active game may seal, and only once until the
+    /// move is revealed.
+    fn apply_move_seal(
+        peer_history: Option<&Database>,
+        seal: &MoveSeal,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Ok(());
+        };
+        let game = database
+            .get_game(&seal.game_id)
+            .map_err(|_| format!("unknown game {}", seal.game_id))?;
+        if game.opponent_peer_id != sender {
+            return Err(format!("sender does not play game {}", game.id));
+        }
+        if game.status != GameStatus::Active {
+            return Err(format!("game {} is not active", game.id));
+        }
+        database
+            .seal_move(&game.id, sender, seal.move_number, &seal.commitment, None)
+            .map_err(|e| e.to_string())?;
+        info!(
+            "Peer {} sealed move {} and adjourned game {}",
+            sender, seal.move_number, game.id
+        );
+        Ok(())
+    }
+
+    /// Check a revealed sealed move against the commitment the opponent
+    /// sent when adjourning, and record it
+    fn apply_seal_reveal(
+        peer_history: Option<&Database>,
+        sealed: &SealedMove,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Ok(());
+        };
+        let record = database
+            .get_sealed_move(&sealed.game_id)
+            .map_err(|e| e.to_string())?
+            .filter(|record| record.is_sealed() && record.sealed_by == sender)
+            .ok_or_else(|| format!("sender has no sealed move in game {}", sealed.game_id))?;
+        if record.move_number != sealed.move_number {
+            return Err(format!(
+                "move {} was sealed, not move {}",
+                record.move_number, sealed.move_number
+            ));
+        }
+        sealed
+            .verify(&record.commitment)
+            .map_err(|e| format!("{e:#}"))?;
+        database
+            .reveal_sealed_move(&sealed.game_id, &sealed.chess_move)
+            .map_err(|e| e.to_string())?;
+        info!(
+            "Peer {} revealed sealed move {} ({}) in game {}",
+            sender, sealed.move_number, sealed.chess_move, sealed.game_id
+        );
+        Ok(())
+    }
+
+    /// Record whether an opponent is available to play
+    ///
+    /// Presence is only kept from peers we have an active game with; it
+    /// means nothing for anyone else.
+    fn apply_presence(
+        peer_history: Option<&Database>,
+        presence: &Presence,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Ok(());
+        };
+        let games = database
+            .get_games_with_opponent(sender)
+            .map_err(|e| e.to_string())?;
+        if !games.iter().any(|game| game.status == GameStatus::Active) {
+            return Err("sender has no active game with us".to_string());
+        }
+        database
+            .set_peer_presence(sender, presence.status.as_str())
+            .map_err(|e| e.to_string())?;
+        debug!("Peer {} is {}", sender, presence.status);
+        Ok(())
+    }
+
+    /// Record a registered voter's proposal for the group's next move
+    ///
+    /// Votes are checked against the position when they are counted.
+    fn apply_vote(
+        peer_history: Option<&Database>,
+        vote: &Vote,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Err("no database to keep votes in".to_string());
+        };
+        let voters = database
+            .get_voters(&vote.game_id)
+            .map_err(|e| e.to_string())?;
+        if !voters.iter().any(|voter| voter == sender) {
+            return Err(format!("sender is not a voter in game {}", vote.game_id));
+        }
+        database
+            .cast_vote(&vote.game_id, sender, vote.chess_move.trim())
+            .map_err(|e| e.to_string())?;
+        debug!(
+            "Peer {} voted {} in game {}",
+            sender, vote.chess_move, vote.game_id
+        );
+        Ok(())
+    }
+
+    /// Keep a study a peer shared, for browsing with 'mate studies'
+    fn keep_study(
+        peer_history: Option<&Database>,
+        study: &Study,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Ok(());
+        };
+        let study_json = serde_json::to_string(study).map_err(|e| e.to_string())?;
+        if database
+            .store_study(&study.study_id, sender, false, &study.title, &study_json)
+            .map_err(|e| e.to_string())?
+        {
+            info!(
+                "Peer {} shared the study \"{}\"; view it with 'mate studies {}'",
+                sender,
+                study.title,
+                &study.study_id[..8.min(study.study_id.len())]
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether the operator muted a peer's chat; nobody is muted without a
+    /// database
+    fn is_muted(peer_history: Option<&Database>, peer_id: &str) -> bool {
+        peer_history.is_some_and(|database| database.is_peer_muted(peer_id).unwrap_or(false))
+    }
+
+    /// Keep a report another peer sent us as the operator of this server
+    fn apply_abuse_report(
+        peer_history: Option<&Database>,
+        report: &AbuseReport,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Err("no database to keep reports in".to_string());
+        };
+        if report.reported_peer_id == sender {
+            return Err("peers cannot report themselves".to_string());
+        }
+        let evidence = serde_json::to_string(&report.evidence).map_err(|e| e.to_string())?;
+        let stored = database
+            .record_peer_report(
+                &report.reported_peer_id,
+                Some(sender),
+                &report.reason,
+                &evidence,
+            )
+            .map_err(|e| e.to_string())?;
+        info!(
+            "Peer {} reported {} with {} evidence messages (report #{})",
+            sender,
+            report.reported_peer_id,
+            report.evidence.len(),
+            stored.id
+        );
+        Ok(())
+    }
+
+    /// Link the sender as another of our devices if it knows a pairing code
+    /// we issued
+    fn apply_device_pair(
+        peer_history: Option<&Database>,
+        pair: &DevicePair,
+        sender: &str,
+    ) -> std::result::Result<(), String> {
+        let Some(database) = peer_history else {
+            return Err("no database to keep linked devices in".to_string());
+        };
+        if !database
+            .take_pairing_code(&pair.code)
+            .map_err(|e| e.to_string())?
+        {
+            return Err("unknown or expired pairing code".to_string());
+        }
+        database
+            .link_device(sender, &pair.address)
+            .map_err(|e| e.to_string())?;
+        info!("Linked device {} at {}", sender, pair.address);
+        Ok(())
+    }
+
+    /// Apply a linked device's changes and answer with ours since `since`
+    ///
+    /// The journal is brought up to date with our address book and games
+    /// first, so the answer includes changes made since the last sync.
+    fn apply_device_sync(
+        peer_history: Option<&Database>,
+        sync: &DeviceSync,
+        sender: &str,
+        local_peer_id: &str,
+    ) -> std::result::Result<DeviceSync, String> {
+        let Some(database) = peer_history else {
+            return Err("no database to sync".to_string());
+        };
+        if database
+            .get_linked_device(sender)
+            .map_err(|e| e.to_string())?
+            .is_none()
+        {
+            return Err("sender is not a linked device".to_string());
+        }
+
+        let received: Vec<SyncEntry> = sync.changes.iter().map(SyncEntry::from).collect();
+        let applied = database
+            .apply_sync_changes(&received)
+            .map_err(|e| e.to_string())?;
+        database
+            .record_address_book_changes()
+            .and_then(|_| database.record_game_index_changes(local_peer_id))
+            .map_err(|e| e.to_string())?;
+        let reply = database
+            .device_sync(sync.since, sync.through)
+            .map_err(|e| e.to_string())?;
+        database
+            .record_device_sync(sender, sync.through, 0)
+            .map_err(|e| e.to_string())?;
+        info!(
+            "Synced with device {}: applied {} of {} changes, sent {}",
+            sender,
+            applied.len(),
+            received.len(),
+            reply.changes.len()
+        );
+        Ok(reply)
+    }
+
+    /// Let the arbiter we appointed for one of our games join it, and answer
+    /// with the game so far
+    fn apply_arbiter_join(
+        peer_history: Option<&Database>,
+        identity: &Identity,
+        join: &ArbiterJoin,
+        sender: &str,
+    ) -> std::result::Result<ArbiterUpdate, String> {
+        let Some(database) = peer_history else {
+            return Err("no database to look the game up in".to_string());
+        };
+        let game = database
+            .get_game(&join.game_id)
+            .map_err(|_| format!("unknown game {}", join.game_id))?;
+        let messages = database
+            .get_messages_for_game(&game.id)
+            .map_err(|e| e.to_string())?;
+        if appointed_arbiter(&messages).as_deref() != Some(sender) {
+            return Err(format!("sender is not the arbiter of game {}", game.id));
+        }
+        database
+            .store_message(
+                game.id.clone(),
+                ARBITER_JOIN_MESSAGE_TYPE.to_string(),
+                serde_json::json!({ "address": join.address }).to_string(),
+                String::new(),
+                sender.to_string(),
+            )
+            .map_err(|e| e.to_string())?;
+        info!(
+            "Arbiter {} joined game {} from {}",
+            sender, game.id, join.address
+        );
+        Ok(ScoreSheet::from_game(&game, identity.peer_id().as_str(), &messages).arbiter_update())
+    }
+
+    /// Record a player's report of a game we arbitrate
+    ///
+    /// The longer of the two move lists is kept as long as one continues the
+    /// other. Once a player reports a finished game whose moves match ours,
+    /// our signature over it is returned.
+    fn apply_arbiter_update(
+        peer_history: Option<&Database>,
+        identity: &Identity,
+        update: &ArbiterUpdate,
+        sender: &str,
+    ) -> std::result::Result<Option<ResultSignature>, String> {
+        let Some(database) = peer_history else {
+            return Err("no database to keep arbitrations in".to_string());
+        };
+        let arbitration = database
+            .get_arbitration(&update.game_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("not arbitrating game {}", update.game_id))?;
+        if sender != arbitration.white && sender != arbitration.black {
+            return Err(format!("sender did not play game {}", update.game_id));
+        }
+        if update.white != arbitration.white || update.black != arbitration.black {
+            return Err("players differ from those the game was joined with".to_string());
+        }
+
+        let reported = update.moves.as_slice();
+        let Some(moves) = arbitration.reconcile(reported) else {
+            return Err(format!(
+                "moves disagree with the other player's in game {}",
+                update.game_id
+            ));
+        };
+        let result = if moves == reported {
+            update.result.as_str()
+        } else {
+            arbitration.result.as_str()
+        };
+        database
+            .update_arbitration(&update.game_id, moves, result)
+            .map_err(|e| e.to_string())?;
+
+        let record = ScoreSheet::from_arbiter_update(update, identity.peer_id().as_str());
+        if !record.is_finished() || moves != reported {
+            return Ok(None);
+        }
+        let signature = ResultSignature::sign(identity, &record).map_err(|e| format!("{e:#}"))?;
+        info!(
+            "Signed result {} of arbitrated game {}",
+            record.result, record.game_id
+        );
+        Ok(Some(signature))
+    }
+
+    /// Rule on a player's claim that a move in a game we arbitrate was illegal
+    fn apply_illegal_move_claim(
+        peer_history: Option<&Database>,
+        identity: &Identity,
+        claim: &IllegalMoveClaim,
+        sender: &str,
+    ) -> std::result::Result<ArbiterRuling, String> {
+        let Some(database) = peer_history else {
+            return Err("no database to keep arbitrations in".to_string());
+        };
+        let arbitration = database
+            .get_arbitration(&claim.game_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("not arbitrating game {}", claim.game_id))?;
+        if sender != arbitration.white && sender != arbitration.black {
+            return Err(format!("sender did not play game {}", claim.game_id));
+        }
+        let ruling = ArbiterRuling::rule(
+            identity,
+            &claim.game_id,
+            sender,
+            &arbitration.moves,
+            claim.ply,
+        )
+        .map_err(|e| format!("{e:#}"))?;
+        database
+            .record_arbiter_ruling(&ArbitrationRuling {
+                game_id: ruling.game_id.clone(),
+                claimant: ruling.claimant.clone(),
+                ply: ruling.ply,
+                chess_move: ruling.chess_move.clone(),
+                upheld: ruling.upheld,
+                reason: ruling.reason.clone(),
+                ruled_at: Database::current_timestamp(),
+            })
+            .map_err(|e| e.to_string())?;
+        info!(
+            "{} {}'s claim against move {} of game {}",
+            ruling.verdict(),
+            sender,
+            ruling.ply,
+            ruling.game_id
+        );
+        Ok(ruling)
+    }
+
+    /// Whether a peer's key was revoked; unknown without a database
+    fn is_revoked(peer_history: Option<&Database>, peer_id: &str) -> bool {
+        peer_history.is_some_and(|database| database.is_key_revoked(peer_id).unwrap_or(false))
+    }
+
+    /// Verify a revocation record and remember the key as revoked
+    ///
+    /// The record is signed by the revoked key itself, so it is accepted from
+    /// any sender; anyone relaying it can only retire a compromised key.
+    fn apply_key_revocation(
+        peer_history: Option<&Database>,
+        record: &RevocationRecord,
+    ) -> std::result::Result<(), String> {
+        record.verify().map_err(|e| e.to_string())?;
+
+        let Some(database) = peer_history else {
+            return Ok(());
+        };
+        let record_json = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        let newly_revoked = database
+            .record_key_revocation(
+                &record.peer_id,
+                record.issued_at,
+                &record.reason,
+                record.successor.as_deref(),
+                &record_json,
+            )
+            .map_err(|e| e.to_string())?;
+        if newly_revoked {
+            warn!(
+                "Peer key {} was revoked by its owner{}; new games with it will be refused",
+                record.peer_id,
+                if record.reason.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", record.reason)
+                }
+            );
+            if let Some(successor) = &record.successor {
+                warn!(
+                    "The owner names {} as their new key; confirm it with 'mate peers verify {}' before trusting it",
+                    successor, successor
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify an opponent's signature over a finished game and keep it
+    ///
+    /// The signature is checked against our own copy of the game with the
+    /// result the opponent claims, so it is only kept if both sides agree on
+    /// the players and moves. If our copy already ends with that result, our
+    /// own signature is returned to complete the certificate.
+    fn apply_result_signature(
+        peer_history: Option<&Database>,
+        identity: &Identity,
+        signature: &ResultSignature,
+        sender: &str,
+    ) -> std::result::Result<Option<ResultSignature>, String> {
+        if signature.signer != sender {
+            return Err("signature was not sent by its signer".to_string());
+        }
+        let Some(database) = peer_history else {
+            return Ok(None);
+        };
+
+        let game = database
+            .get_game(&signature.game_id)
+            .map_err(|_| format!("unknown game {}", signature.game_id))?;
+        if game.opponent_peer_id != sender {
+            return Err(format!("sender did not play game {}", game.id));
+        }
+        let messages = database
+            .get_messages_for_game(&game.id)
+            .map_err(|e| e.to_string())?;
+        let record = ScoreSheet::from_game(&game, identity.peer_id().as_str(), &messages);
+        let claimed = ScoreSheet {
+            result: signature.result.clone(),
+            ..record.clone()
+        };
+        signature.verify(&claimed).map_err(|e| format!("{e:#}"))?;
+        store_result_signature(database, signature).map_err(|e| format!("{e:#}"))?;
+        info!("Stored {}'s signature over game {}", sender, game.id);
+
+        if record != claimed {
+            return Ok(None);
+        }
+        let own = ResultSignature::sign(identity, &record).map_err(|e| format!("{e:#}"))?;
+        store_result_signature(database, &own).map_err(|e| format!("{e:#}"))?;
+        Ok(Some(own))
+    }
+
+    /// Persist a peer event (if history is enabled) and report negative events to the security log
+    /// Count a connection dropped for being too slow to handshake, keyed by
+    /// address since the peer has not proven an identity
+    fn record_handshake_timeout(
+        stats: &StatsRegistry,
+        peer_history: Option<&Database>,
+        remote_addr: Option<&std::net::SocketAddr>,
+        details: &str,
+    ) {
+        stats.record_handshake_timeout();
+        if let Some(addr) = remote_addr {
+            Self::record_peer_event(
+                peer_history,
+                &address_peer_key(addr),
+                PeerEventType::DosEvent,
+                Some(details.to_string()),
+                Some(addr.to_string()),
+            );
+        }
+    }
+
+    fn record_peer_event(
+        peer_history: Option<&Database>,
+        peer_id: &str,
+        event_type: PeerEventType,
+        details: Option<String>,
+        remote_addr: Option<String>,
+    ) {
+        let Some(database) = peer_history else {
+            return;
+        };
+
+        if let Err(e) =
+            database.record_peer_event(peer_id, event_type, details.clone(), remote_addr)
+        {
+            warn!(
+                "Failed to record {} for peer {}: {}",
+                event_type.as_str(),
+                peer_id,
+                e
+            );
+            return;
+        }
+
+        if event_type.is_negative() {
+            match database.get_peer_summary(peer_id) {
+                Ok(summary) => warn!(
+                    "SECURITY: {} from peer {} ({}); reputation {}/100 after {} failures, {} violations, {} DoS events",
+                    event_type.as_str(),
+                    peer_id,
+                    details.as_deref().unwrap_or("no details"),
+                    summary.reputation_score(),
+                    summary.handshake_failures,
+                    summary.protocol_violations,
+                    summary.dos_events
+                ),
+                Err(e) => warn!("Failed to load history for peer {}: {}", peer_id, e),
+            }
+        }
+    }
+
     /// Wait for shutdown signals (SIGTERM, SIGINT)
     async fn wait_for_shutdown() -> Result<()> {
         #[cfg(unix)]
@@ -401,8 +1861,8 @@ impl Server {
     /// Perform graceful shutdown of the server
     async fn graceful_shutdown(
         &self,
-        mut active_connections: HashMap<usize, JoinHandle<()>>,
-        shutdown_handle: JoinHandle<()>,
+        mut active_connections: HashMap<String, JoinHandle<()>>,
+        shutdown_handle: ShutdownListener,
     ) -> Result<()> {
         info!("Shutting down server gracefully...");
 
@@ -449,9 +1909,29 @@ impl Server {
         }
 
         // Clean up shutdown handler
-        shutdown_handle.abort();
+        drop(shutdown_handle);
 
         info!("Server shutdown complete");
         Ok(())
     }
 }
+
+/// History key for a remote that has not (yet) authenticated with a peer ID
+pub fn address_peer_key(addr: &std::net::SocketAddr) -> String {
+    format!("addr:{}", addr.ip())
+}
+
+/// Whether a receive failed because the peer sent more bytes than allowed
+fn is_oversized(error: &ConnectionError) -> bool {
+    match error {
+        ConnectionError::WireProtocol(
+            WireProtocolError::MessageTooLarge { .. }
+            | WireProtocolError::SuspiciousMessageSize { .. }
+            | WireProtocolError::AllocationDenied { .. },
+        ) => true,
+        ConnectionError::WireProtocol(WireProtocolError::InvalidLength { length, max, .. }) => {
+            length > max
+        }
+        _ => false,
+    }
+}
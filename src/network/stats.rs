@@ -0,0 +1,241 @@
+use crate::messages::wire::SERVER_MAX_CONCURRENT_CONNECTIONS;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Traffic counters of one connection, or of several added together
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    /// Framed envelope bytes, without length prefixes
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Connection attempts that failed before this connection was made
+    pub retries: u64,
+    /// Unix time in seconds of the last message sent or received
+    pub last_activity: Option<u64>,
+    /// Replies timed, from sending a message to receiving the next one
+    pub round_trips: u64,
+    pub round_trip_total_ms: u64,
+}
+
+impl ConnectionStats {
+    /// Mean time from sending a message to receiving the next one
+    pub fn avg_round_trip(&self) -> Option<Duration> {
+        (self.round_trips > 0)
+            .then(|| Duration::from_millis(self.round_trip_total_ms / self.round_trips))
+    }
+
+    /// Add `other`'s counters to these
+    pub fn merge(&mut self, other: &ConnectionStats) {
+        self.messages_sent += other.messages_sent;
+        self.messages_received += other.messages_received;
+        self.bytes_sent += other.bytes_sent;
+        self.bytes_received += other.bytes_received;
+        self.retries += other.retries;
+        self.last_activity = self.last_activity.max(other.last_activity);
+        self.round_trips += other.round_trips;
+        self.round_trip_total_ms += other.round_trip_total_ms;
+    }
+
+    pub(crate) fn record_sent(&mut self, bytes: usize) {
+        self.messages_sent += 1;
+        self.bytes_sent += bytes as u64;
+        self.touch();
+    }
+
+    pub(crate) fn record_received(&mut self, bytes: usize, round_trip: Option<Duration>) {
+        self.messages_received += 1;
+        self.bytes_received += bytes as u64;
+        if let Some(round_trip) = round_trip {
+            self.round_trips += 1;
+            self.round_trip_total_ms += round_trip.as_millis() as u64;
+        }
+        self.touch();
+    }
+
+    fn touch(&mut self) {
+        self.last_activity = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|now| now.as_secs());
+    }
+}
+
+/// Totals of the connections made or accepted by one server or client
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrafficSnapshot {
+    /// Connections currently open
+    pub open_connections: usize,
+    /// Connections opened since start, including the open ones
+    pub total_connections: u64,
+    pub traffic: ConnectionStats,
+    /// Messages refused as invalid or not allowed from their sender
+    #[serde(default)]
+    pub rejected_messages: u64,
+    /// Frames refused for declaring more bytes than allowed
+    #[serde(default)]
+    pub oversized_messages: u64,
+    /// Connections turned away because the connection limit was reached,
+    /// or too many others were still handshaking
+    #[serde(default)]
+    pub refused_connections: u64,
+    /// Connections closed for sending nothing, or not completing the
+    /// handshake, before their deadline
+    #[serde(default)]
+    pub handshake_timeouts: u64,
+    /// Messages read late because their connection was over its quota
+    #[serde(default)]
+    pub throttled_messages: u64,
+}
+
+/// One open connection and the peer on the other end, once known
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenConnection {
+    pub connection_id: String,
+    /// `None` until the handshake completes
+    pub peer_id: Option<String>,
+    pub stats: ConnectionStats,
+}
+
+/// What `mate top` shows: totals, every open connection, and how close the
+/// server is to its connection limit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorSnapshot {
+    pub totals: TrafficSnapshot,
+    /// Most connections the server holds open at once
+    pub connection_limit: usize,
+    /// Open connections, ordered by ID
+    pub connections: Vec<OpenConnection>,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    open: HashMap<String, ConnectionStats>,
+    peers: HashMap<String, String>,
+    closed: ConnectionStats,
+    total_connections: u64,
+    rejected_messages: u64,
+    oversized_messages: u64,
+    refused_connections: u64,
+    handshake_timeouts: u64,
+    throttled_messages: u64,
+}
+
+/// Shared record of connection statistics, updated as connections make
+/// progress and read by status reports and the metrics endpoint
+#[derive(Debug, Clone, Default)]
+pub struct StatsRegistry {
+    inner: Arc<Mutex<Registry>>,
+}
+
+impl StatsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the latest statistics of an open connection
+    pub fn update(&self, connection_id: &str, stats: &ConnectionStats) {
+        let mut registry = self.lock();
+        if !registry.open.contains_key(connection_id) {
+            registry.total_connections += 1;
+        }
+        registry
+            .open
+            .insert(connection_id.to_string(), stats.clone());
+    }
+
+    /// Record which peer an open connection was made by
+    pub fn identify(&self, connection_id: &str, peer_id: &str) {
+        self.lock()
+            .peers
+            .insert(connection_id.to_string(), peer_id.to_string());
+    }
+
+    /// Record the final statistics of a connection that has closed
+    pub fn close(&self, connection_id: &str, stats: &ConnectionStats) {
+        let mut registry = self.lock();
+        if registry.open.remove(connection_id).is_none() {
+            registry.total_connections += 1;
+        }
+        registry.peers.remove(connection_id);
+        registry.closed.merge(stats);
+    }
+
+    /// Count a message refused as invalid or not allowed
+    pub fn record_rejected(&self) {
+        self.lock().rejected_messages += 1;
+    }
+
+    /// Count a frame refused for its size
+    pub fn record_oversized(&self) {
+        self.lock().oversized_messages += 1;
+    }
+
+    /// Count a connection turned away at the connection limit
+    pub fn record_refused_connection(&self) {
+        self.lock().refused_connections += 1;
+    }
+
+    /// Count a connection closed for being too slow to handshake
+    pub fn record_handshake_timeout(&self) {
+        self.lock().handshake_timeouts += 1;
+    }
+
+    /// Count a message held back by its connection's quota
+    pub fn record_throttled(&self) {
+        self.lock().throttled_messages += 1;
+    }
+
+    /// Count connection attempts that failed without opening a connection
+    pub fn record_retries(&self, retries: u64) {
+        self.lock().closed.retries += retries;
+    }
+
+    /// Totals over open and closed connections
+    pub fn snapshot(&self) -> TrafficSnapshot {
+        let registry = self.lock();
+        let mut traffic = registry.closed.clone();
+        for stats in registry.open.values() {
+            traffic.merge(stats);
+        }
+        TrafficSnapshot {
+            open_connections: registry.open.len(),
+            total_connections: registry.total_connections,
+            traffic,
+            rejected_messages: registry.rejected_messages,
+            oversized_messages: registry.oversized_messages,
+            refused_connections: registry.refused_connections,
+            handshake_timeouts: registry.handshake_timeouts,
+            throttled_messages: registry.throttled_messages,
+        }
+    }
+
+    /// Totals plus the counters of each open connection
+    pub fn monitor(&self) -> MonitorSnapshot {
+        let totals = self.snapshot();
+        let registry = self.lock();
+        let mut connections: Vec<OpenConnection> = registry
+            .open
+            .iter()
+            .map(|(connection_id, stats)| OpenConnection {
+                connection_id: connection_id.clone(),
+                peer_id: registry.peers.get(connection_id).cloned(),
+                stats: stats.clone(),
+            })
+            .collect();
+        connections.sort_by(|a, b| a.connection_id.cmp(&b.connection_id));
+        MonitorSnapshot {
+            totals,
+            connection_limit: SERVER_MAX_CONCURRENT_CONNECTIONS,
+            connections,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Registry> {
+        // Counters stay usable even if a holder panicked
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
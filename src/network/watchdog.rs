@@ -0,0 +1,314 @@
+//! Supervision of the long-running parts of `mate serve`
+//!
+//! Every supervised subsystem is handed a [`Heartbeat`] and beats it as it
+//! makes progress. The [`Watchdog`] looks at each heartbeat's watermark
+//! every check interval: a subsystem whose heartbeat fell silent for longer
+//! than its stall limit is aborted and started again, and so is one that
+//! failed or panicked. Each restart is reported as an [`Incident`], logged,
+//! and appended to the incident log if one is configured.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::{JoinError, JoinHandle};
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+
+/// File under the data directory that incidents are appended to
+pub const INCIDENT_LOG: &str = "incidents.log";
+
+/// How often the watchdog checks its subsystems by default
+pub const WATCHDOG_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time a subsystem may take over one round of work on top of waiting for
+/// its next round, before it counts as stuck
+pub const STALL_ALLOWANCE: Duration = Duration::from_secs(60);
+
+/// The stall limit of a subsystem that beats its heartbeat once every
+/// `interval`
+pub fn stall_limit(interval: Duration) -> Duration {
+    interval * 2 + STALL_ALLOWANCE
+}
+
+/// Progress watermark of a supervised subsystem
+///
+/// Clones share the watermark, so the subsystem can beat the heartbeat the
+/// watchdog reads.
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    last: Arc<Mutex<Instant>>,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self {
+            last: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Record that the subsystem made progress
+    pub fn beat(&self) {
+        let mut last = match self.last.lock() {
+            Ok(last) => last,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *last = Instant::now();
+    }
+
+    /// How long ago the heartbeat was last beaten
+    pub fn silent_for(&self) -> Duration {
+        let last = match self.last.lock() {
+            Ok(last) => *last,
+            Err(poisoned) => *poisoned.into_inner(),
+        };
+        last.elapsed()
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What went wrong with a supervised subsystem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IncidentKind {
+    /// The heartbeat was silent for longer than the stall limit
+    Stalled { silent_secs: u64 },
+    /// The subsystem returned an error
+    Failed { error: String },
+    /// The subsystem panicked
+    Panicked { message: String },
+}
+
+/// A subsystem the watchdog had to restart
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Incident {
+    pub subsystem: String,
+    #[serde(flatten)]
+    pub kind: IncidentKind,
+    /// How many times the subsystem has been restarted, this time included
+    pub restarts: u32,
+    /// Unix timestamp of the restart
+    pub at: i64,
+}
+
+impl fmt::Display for Incident {
+    /// e.g. "accept loop stalled (no heartbeat for 95s); restart 2"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            IncidentKind::Stalled { silent_secs } => write!(
+                f,
+                "{} stalled (no heartbeat for {silent_secs}s)",
+                self.subsystem
+            )?,
+            IncidentKind::Failed { error } => write!(f, "{} failed: {error}", self.subsystem)?,
+            IncidentKind::Panicked { message } => {
+                write!(f, "{} panicked: {message}", self.subsystem)?
+            }
+        }
+        write!(f, "; restart {}", self.restarts)
+    }
+}
+
+type SubsystemFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// A subsystem and how to start it
+struct Subsystem {
+    name: String,
+    stall_after: Option<Duration>,
+    start: Box<dyn FnMut(Heartbeat) -> SubsystemFuture + Send>,
+    restarts: u32,
+}
+
+/// A started subsystem
+struct Running {
+    heartbeat: Heartbeat,
+    task: JoinHandle<Result<()>>,
+}
+
+impl Subsystem {
+    fn spawn(&mut self) -> Running {
+        let heartbeat = Heartbeat::new();
+        let task = tokio::spawn((self.start)(heartbeat.clone()));
+        Running { heartbeat, task }
+    }
+}
+
+/// Restarts the subsystems of the daemon that get stuck or stop
+///
+/// Subsystems are restarted at most once per check interval, which keeps a
+/// subsystem that fails straight away from spinning.
+pub struct Watchdog {
+    subsystems: Vec<Subsystem>,
+    check_interval: Duration,
+    incident_log: Option<PathBuf>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Self {
+            subsystems: Vec::new(),
+            check_interval: WATCHDOG_CHECK_INTERVAL,
+            incident_log: None,
+        }
+    }
+
+    /// Check the subsystems every `interval` instead of every
+    /// [`WATCHDOG_CHECK_INTERVAL`]
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Append every incident to `path`, one JSON object per line
+    pub fn with_incident_log(mut self, path: PathBuf) -> Self {
+        self.incident_log = Some(path);
+        self
+    }
+
+    /// Supervise the subsystem `start` starts, calling it again for every
+    /// restart
+    ///
+    /// The subsystem counts as stuck once the heartbeat it is given has been
+    /// silent for `stall_after`; without a stall limit it is only restarted
+    /// when it fails.
+    pub fn supervise<F, Fut>(
+        mut self,
+        name: &str,
+        stall_after: Option<Duration>,
+        mut start: F,
+    ) -> Self
+    where
+        F: FnMut(Heartbeat) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.subsystems.push(Subsystem {
+            name: name.to_string(),
+            stall_after,
+            start: Box::new(move |heartbeat| Box::pin(start(heartbeat))),
+            restarts: 0,
+        });
+        self
+    }
+
+    /// Run the subsystems, restarting them as needed
+    ///
+    /// Returns once a subsystem finishes by itself, such as the accept loop
+    /// on a shutdown signal; the others are stopped.
+    pub async fn run(mut self) -> Result<()> {
+        let mut running: Vec<Running> = self.subsystems.iter_mut().map(Subsystem::spawn).collect();
+        let mut interval = tokio::time::interval(self.check_interval);
+        interval.tick().await;
+
+        let result = 'supervise: loop {
+            interval.tick().await;
+            for (subsystem, current) in self.subsystems.iter_mut().zip(running.iter_mut()) {
+                let kind = if current.task.is_finished() {
+                    match (&mut current.task).await {
+                        Ok(Ok(())) => {
+                            info!("{} finished, stopping the watchdog", subsystem.name);
+                            break 'supervise Ok(());
+                        }
+                        Ok(Err(e)) => IncidentKind::Failed {
+                            error: format!("{e:#}"),
+                        },
+                        Err(e) => join_failure(e),
+                    }
+                } else {
+                    let silent = current.heartbeat.silent_for();
+                    match subsystem.stall_after {
+                        Some(limit) if silent > limit => {
+                            current.task.abort();
+                            // Stuck in blocking code, the task may not stop
+                            // in time; it is replaced regardless
+                            if tokio::time::timeout(self.check_interval, &mut current.task)
+                                .await
+                                .is_err()
+                            {
+                                warn!("{} did not stop when aborted", subsystem.name);
+                            }
+                            IncidentKind::Stalled {
+                                silent_secs: silent.as_secs(),
+                            }
+                        }
+                        _ => continue,
+                    }
+                };
+
+                subsystem.restarts += 1;
+                let incident = Incident {
+                    subsystem: subsystem.name.clone(),
+                    kind,
+                    restarts: subsystem.restarts,
+                    at: unix_timestamp(),
+                };
+                report(self.incident_log.as_deref(), &incident);
+                *current = subsystem.spawn();
+            }
+        };
+
+        for current in &running {
+            current.task.abort();
+        }
+        result
+    }
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Log an incident and append it to the incident log, if there is one
+fn report(incident_log: Option<&Path>, incident: &Incident) {
+    error!("Watchdog: {}", incident);
+    if let Some(path) = incident_log {
+        if let Err(e) = append_incident(path, incident) {
+            warn!("Failed to record incident: {:#}", e);
+        }
+    }
+}
+
+fn append_incident(path: &Path, incident: &Incident) -> Result<()> {
+    let line = serde_json::to_string(incident).context("Failed to serialize incident")?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{line}").with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn join_failure(e: JoinError) -> IncidentKind {
+    if !e.is_panic() {
+        return IncidentKind::Failed {
+            error: e.to_string(),
+        };
+    }
+    let panic = e.into_panic();
+    let message = match panic.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match panic.downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "unknown panic".to_string(),
+        },
+    };
+    IncidentKind::Panicked { message }
+}
+
+fn unix_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
@@ -0,0 +1,202 @@
+use crate::messages::{Message, RetryConfig};
+use crate::network::http_client::{post_json, HttpUrl};
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Header carrying the HMAC-SHA256 of the body, as `sha256=<hex>`
+pub const SIGNATURE_HEADER: &str = "X-Mate-Signature";
+
+/// Header naming the event, e.g. `move`
+pub const EVENT_HEADER: &str = "X-Mate-Event";
+
+/// How long one delivery attempt may take
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What is posted to each webhook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WebhookPayload {
+    /// `move`, `invite`, `game_end`, or `reminder`
+    pub event: &'static str,
+    pub game_id: String,
+    /// Peer ID of the opponent who sent the message
+    pub from: String,
+    /// The move played, for `move` events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chess_move: Option<String>,
+    /// PGN result token, for `game_end` events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    /// How long the game has waited on the local player, for `reminder` events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waiting_secs: Option<u64>,
+    /// Unix seconds when the message was received or the reminder sent
+    pub timestamp: u64,
+}
+
+/// Posts incoming moves, invitations, and game results to HTTP endpoints
+///
+/// Each payload is JSON signed with HMAC-SHA256 under the shared secret (see
+/// [`SIGNATURE_HEADER`]), so receivers can check it came from this server.
+/// Deliveries run in the background and are retried with backoff.
+#[derive(Debug, Clone, Default)]
+pub struct Webhooks {
+    urls: Vec<HttpUrl>,
+    secret: Option<Arc<str>>,
+    retry: RetryConfig,
+}
+
+impl Webhooks {
+    /// Webhooks posting to `urls`, signed with `secret` when one is given
+    pub fn new(urls: &[String], secret: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            urls: urls
+                .iter()
+                .map(|url| HttpUrl::parse(url))
+                .collect::<Result<_>>()?,
+            secret: secret.filter(|s| !s.is_empty()).map(Arc::from),
+            retry: RetryConfig {
+                max_attempts: 5,
+                base_delay: Duration::from_secs(1),
+                max_delay: Duration::from_secs(60),
+                ..RetryConfig::default()
+            },
+        })
+    }
+
+    /// Retry failed deliveries as `retry` allows
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Whether any webhook is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.urls.is_empty()
+    }
+
+    /// The payload for a message, or `None` if webhooks do not report it
+    ///
+    /// A game's end is reported when the opponent signs its result, which
+    /// both players do however the game ended.
+    pub fn payload_for(message: &Message, sender: &str) -> Option<WebhookPayload> {
+        let (event, game_id, chess_move, result) = match message {
+            Message::Move(mv) => ("move", &mv.game_id, Some(mv.chess_move.clone()), None),
+            Message::GameInvite(invite) => ("invite", &invite.game_id, None, None),
+            Message::ResultSignature(signature) => (
+                "game_end",
+                &signature.game_id,
+                None,
+                Some(signature.result.clone()),
+            ),
+            _ => return None,
+        };
+        Some(WebhookPayload {
+            event,
+            game_id: game_id.clone(),
+            from: sender.to_string(),
+            chess_move,
+            result,
+            waiting_secs: None,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Post the payload for a message to every webhook in the background
+    pub fn dispatch(&self, message: &Message, sender: &str) {
+        if let Some(payload) = Self::payload_for(message, sender) {
+            self.dispatch_payload(payload);
+        }
+    }
+
+    /// Post `payload` to every webhook in the background
+    pub fn dispatch_payload(&self, payload: WebhookPayload) {
+        if !self.is_enabled() {
+            return;
+        }
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => Arc::<[u8]>::from(body),
+            Err(e) => {
+                warn!("Failed to serialize webhook payload: {}", e);
+                return;
+            }
+        };
+
+        for index in 0..self.urls.len() {
+            let webhooks = self.clone();
+            let body = Arc::clone(&body);
+            tokio::spawn(async move {
+                let url = &webhooks.urls[index];
+                if let Err(e) = webhooks.deliver(url, payload.event, &body).await {
+                    warn!("Giving up on webhook {}: {:#}", url.as_str(), e);
+                }
+            });
+        }
+    }
+
+    /// Post `body` to one webhook, retrying server errors and failed
+    /// connections with backoff
+    async fn deliver(&self, url: &HttpUrl, event: &str, body: &[u8]) -> Result<()> {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let error =
+                match tokio::time::timeout(DELIVERY_TIMEOUT, self.post(url, event, body)).await {
+                    Ok(Ok(status)) if (200..300).contains(&status) => {
+                        debug!("Webhook {} accepted {} event", url.as_str(), event);
+                        return Ok(());
+                    }
+                    // The receiver refused the payload; sending it again will not help
+                    Ok(Ok(status))
+                        if (400..500).contains(&status) && status != 408 && status != 429 =>
+                    {
+                        anyhow::bail!("Webhook answered HTTP {status}")
+                    }
+                    Ok(Ok(status)) => anyhow::anyhow!("Webhook answered HTTP {status}"),
+                    Ok(Err(e)) => e,
+                    Err(_) => anyhow::anyhow!("No answer within {:?}", DELIVERY_TIMEOUT),
+                };
+            if attempt >= max_attempts {
+                return Err(error.context(format!("{attempt} attempts failed")));
+            }
+            let delay = self.retry.calculate_delay(attempt);
+            debug!(
+                "Webhook {} failed (attempt {}/{}): {:#}; retrying in {:?}",
+                url.as_str(),
+                attempt,
+                max_attempts,
+                error,
+                delay
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send one signed POST request and return the response status
+    async fn post(&self, url: &HttpUrl, event: &str, body: &[u8]) -> Result<u16> {
+        let mut headers = vec![(EVENT_HEADER, event.to_string())];
+        if let Some(secret) = &self.secret {
+            headers.push((
+                SIGNATURE_HEADER,
+                format!("sha256={}", sign_payload(secret, body)),
+            ));
+        }
+        post_json(url, &headers, body).await
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret`, as sent in [`SIGNATURE_HEADER`]
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
@@ -0,0 +1,244 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::{Game, GameNote, Message, PeerAlias, PeerVerification};
+use serde::{Deserialize, Serialize};
+
+/// A tag attached to a game, as stored in an account snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameTagRecord {
+    pub game_id: String,
+    pub tag: String,
+    pub created_at: i64,
+}
+
+/// Complete snapshot of the stored account data
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountData {
+    pub games: Vec<Game>,
+    pub messages: Vec<Message>,
+    pub tags: Vec<GameTagRecord>,
+    pub notes: Vec<GameNote>,
+    /// Missing from archives made before aliases were exported
+    #[serde(default)]
+    pub aliases: Vec<PeerAlias>,
+    /// Missing from archives made before verifications were exported
+    #[serde(default)]
+    pub verifications: Vec<PeerVerification>,
+}
+
+/// Counts of rows written while importing an account snapshot
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub games: usize,
+    pub messages: usize,
+    pub tags: usize,
+    pub notes: usize,
+    pub aliases: usize,
+    pub verifications: usize,
+    /// Addresses pinned from the archive's `known_peers.json` keys, which are
+    /// not in the database, so the caller merges and counts them
+    pub pins: usize,
+    /// Identities other than the account's, which the caller writes to the
+    /// data directory and counts
+    pub identities: usize,
+    pub skipped_games: usize,
+}
+
+impl Database {
+    /// Export every game, message, tag, note, peer alias, and peer
+    /// verification in the database
+    pub fn export_account_data(&self) -> Result<AccountData> {
+        let games = self.get_all_games()?;
+
+        let mut messages = Vec::new();
+        for game in &games {
+            messages.extend(self.get_messages_for_game(&game.id)?);
+        }
+
+        let tags = self.with_connection(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT game_id, tag, created_at FROM game_tags ORDER BY game_id, tag")?;
+            let tag_iter = stmt.query_map([], |row| {
+                Ok(GameTagRecord {
+                    game_id: row.get("game_id")?,
+                    tag: row.get("tag")?,
+                    created_at: row.get("created_at")?,
+                })
+            })?;
+            let tags = tag_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(tags)
+        })?;
+
+        let notes = self.with_connection(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT game_id, content, updated_at FROM game_notes ORDER BY game_id")?;
+            let note_iter = stmt.query_map([], |row| {
+                Ok(GameNote {
+                    game_id: row.get("game_id")?,
+                    content: row.get("content")?,
+                    updated_at: row.get("updated_at")?,
+                })
+            })?;
+            let notes = note_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(notes)
+        })?;
+
+        Ok(AccountData {
+            games,
+            messages,
+            tags,
+            notes,
+            aliases: self.get_peer_aliases()?,
+            verifications: self.get_peer_verifications()?,
+        })
+    }
+
+    /// Import an account snapshot in a single transaction
+    ///
+    /// Games that already exist are left untouched (along with their messages,
+    /// tags, and notes) so importing the same archive twice is harmless. So
+    /// are aliases already in use locally, whichever peer they name. A peer
+    /// verified in the archive is marked verified if it is not already.
+    pub fn import_account_data(&self, data: &AccountData) -> Result<ImportSummary> {
+        self.import_account_data_with_progress(data, |_, _| {})
+    }
+
+    /// Import an account snapshot, calling `progress` with the number of
+    /// records handled so far and the total after each one
+    ///
+    /// Records of skipped games count as handled.
+    pub fn import_account_data_with_progress(
+        &self,
+        data: &AccountData,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<ImportSummary> {
+        let total = data.games.len()
+            + data.messages.len()
+            + data.tags.len()
+            + data.notes.len()
+            + data.aliases.len()
+            + data.verifications.len();
+        let mut done = 0;
+        let mut advance = || {
+            done += 1;
+            progress(done, total);
+        };
+
+        self.transaction(|tx| {
+            let conn = tx.connection();
+            let mut summary = ImportSummary::default();
+            let mut imported_games = std::collections::HashSet::new();
+
+            for game in &data.games {
+                advance();
+                let metadata = game
+                    .metadata
+                    .as_ref()
+                    .map(|m| {
+                        serde_json::to_string(m)
+                            .map_err(|e| StorageError::serialization_error("game metadata", e))
+                    })
+                    .transpose()?;
+
+                let inserted = conn.execute(
+                    r#"
+                    INSERT OR IGNORE INTO games (
+                        id, opponent_peer_id, my_color, status,
+                        created_at, updated_at, completed_at, result, metadata
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                    "#,
+                    rusqlite::params![
+                        game.id,
+                        game.opponent_peer_id,
+                        game.my_color.as_str(),
+                        game.status.as_str(),
+                        game.created_at,
+                        game.updated_at,
+                        game.completed_at,
+                        game.result.as_ref().map(|r| r.as_str()),
+                        metadata,
+                    ],
+                )?;
+
+                if inserted > 0 {
+                    summary.games += 1;
+                    imported_games.insert(game.id.as_str());
+                } else {
+                    summary.skipped_games += 1;
+                }
+            }
+
+            for message in &data.messages {
+                advance();
+                if !imported_games.contains(message.game_id.as_str()) {
+                    continue;
+                }
+                conn.execute(
+                    r#"
+                    INSERT INTO messages (
+                        game_id, message_type, content, signature, sender_peer_id, created_at
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                    "#,
+                    (
+                        &message.game_id,
+                        &message.message_type,
+                        &message.content,
+                        &message.signature,
+                        &message.sender_peer_id,
+                        message.created_at,
+                    ),
+                )?;
+                summary.messages += 1;
+            }
+
+            for tag in &data.tags {
+                advance();
+                if !imported_games.contains(tag.game_id.as_str()) {
+                    continue;
+                }
+                summary.tags += conn.execute(
+                    "INSERT OR IGNORE INTO game_tags (game_id, tag, created_at) VALUES (?1, ?2, ?3)",
+                    (&tag.game_id, &tag.tag, tag.created_at),
+                )?;
+            }
+
+            for note in &data.notes {
+                advance();
+                if !imported_games.contains(note.game_id.as_str()) {
+                    continue;
+                }
+                summary.notes += conn.execute(
+                    "INSERT OR REPLACE INTO game_notes (game_id, content, updated_at) VALUES (?1, ?2, ?3)",
+                    (&note.game_id, &note.content, note.updated_at),
+                )?;
+            }
+
+            for alias in &data.aliases {
+                advance();
+                summary.aliases += conn.execute(
+                    "INSERT OR IGNORE INTO peer_aliases (alias, peer_id, created_at) VALUES (?1, ?2, ?3)",
+                    (&alias.alias, &alias.peer_id, alias.created_at),
+                )?;
+            }
+
+            for verification in &data.verifications {
+                advance();
+                summary.verifications += conn.execute(
+                    r#"
+                    INSERT INTO peer_verifications (peer_id, first_seen_at, verified_at)
+                    VALUES (?1, ?2, ?3)
+                    ON CONFLICT(peer_id) DO UPDATE SET verified_at = excluded.verified_at
+                    WHERE verified_at IS NULL AND excluded.verified_at IS NOT NULL
+                    "#,
+                    (
+                        &verification.peer_id,
+                        verification.first_seen_at,
+                        verification.verified_at,
+                    ),
+                )?;
+            }
+
+            Ok(summary)
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::PeerAlias;
+use rusqlite::{OptionalExtension, Row};
+
+/// Maximum length of a peer alias
+pub const MAX_ALIAS_LENGTH: usize = 32;
+
+/// Normalize an alias for storage (trimmed, lowercase) and validate its contents
+pub fn normalize_alias(alias: &str) -> Result<String> {
+    let normalized = alias.trim().to_lowercase();
+
+    if normalized.is_empty() {
+        return Err(StorageError::invalid_data("alias", "Alias cannot be empty"));
+    }
+
+    if normalized.len() > MAX_ALIAS_LENGTH {
+        return Err(StorageError::invalid_data(
+            "alias",
+            format!("Alias exceeds maximum length of {MAX_ALIAS_LENGTH} characters"),
+        ));
+    }
+
+    if !normalized.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !normalized
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(StorageError::invalid_data(
+            "alias",
+            "Aliases must start with a letter and may only contain letters, digits, '-' and '_'",
+        ));
+    }
+
+    Ok(normalized)
+}
+
+impl Database {
+    /// Point an alias at a peer, replacing whichever peer it named before
+    pub fn set_peer_alias(&self, alias: &str, peer_id: &str) -> Result<PeerAlias> {
+        let alias = normalize_alias(alias)?;
+        let peer_id = peer_id.trim();
+        if peer_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO peer_aliases (alias, peer_id, created_at)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(alias) DO UPDATE SET peer_id = excluded.peer_id,
+                                                 created_at = excluded.created_at
+                "#,
+                (&alias, peer_id, now),
+            )?;
+            Ok(PeerAlias {
+                alias,
+                peer_id: peer_id.to_string(),
+                created_at: now,
+            })
+        })
+    }
+
+    /// Remove an alias. Returns false if no such alias existed.
+    pub fn remove_peer_alias(&self, alias: &str) -> Result<bool> {
+        let alias = normalize_alias(alias)?;
+
+        self.with_connection(|conn| {
+            let removed = conn.execute("DELETE FROM peer_aliases WHERE alias = ?1", [&alias])?;
+            Ok(removed > 0)
+        })
+    }
+
+    /// Peer ID an alias refers to, if the alias exists
+    ///
+    /// Input that is not a valid alias simply resolves to nothing.
+    pub fn resolve_peer_alias(&self, alias: &str) -> Result<Option<String>> {
+        let Ok(alias) = normalize_alias(alias) else {
+            return Ok(None);
+        };
+
+        self.with_connection(|conn| {
+            let peer_id = conn
+                .query_row(
+                    "SELECT peer_id FROM peer_aliases WHERE alias = ?1",
+                    [&alias],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(peer_id)
+        })
+    }
+
+    /// Get every alias in alphabetical order
+    pub fn get_peer_aliases(&self) -> Result<Vec<PeerAlias>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT alias, peer_id, created_at FROM peer_aliases ORDER BY alias ASC",
+            )?;
+
+            let alias_iter = stmt.query_map([], alias_from_row)?;
+            let aliases = alias_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(aliases)
+        })
+    }
+
+    /// Get the aliases naming a peer in alphabetical order
+    pub fn get_aliases_for_peer(&self, peer_id: &str) -> Result<Vec<String>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT alias FROM peer_aliases WHERE peer_id = ?1 ORDER BY alias ASC")?;
+
+            let alias_iter = stmt.query_map([peer_id], |row| row.get(0))?;
+            let aliases = alias_iter.collect::<std::result::Result<Vec<String>, _>>()?;
+            Ok(aliases)
+        })
+    }
+}
+
+fn alias_from_row(row: &Row) -> rusqlite::Result<PeerAlias> {
+    Ok(PeerAlias {
+        alias: row.get(0)?,
+        peer_id: row.get(1)?,
+        created_at: row.get(2)?,
+    })
+}
@@ -0,0 +1,121 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::PositionAnalysis;
+use rusqlite::{named_params, OptionalExtension, Row};
+
+impl Database {
+    /// Store an engine evaluation for a position
+    ///
+    /// An existing result from the same engine is only replaced by an analysis
+    /// of equal or greater depth. Returns whether the row was written.
+    pub fn store_analysis(&self, analysis: &PositionAnalysis) -> Result<bool> {
+        if analysis.engine.trim().is_empty() {
+            return Err(StorageError::invalid_data(
+                "engine",
+                "Engine name cannot be empty",
+            ));
+        }
+
+        self.with_connection(|conn| {
+            let written = conn.execute(
+                r#"
+                INSERT INTO analysis (
+                    position_hash, engine, fen, depth, score_cp, mate_in, best_move, pv, created_at
+                ) VALUES (
+                    :position_hash, :engine, :fen, :depth, :score_cp, :mate_in, :best_move, :pv, :created_at
+                )
+                ON CONFLICT(position_hash, engine) DO UPDATE SET
+                    fen = excluded.fen,
+                    depth = excluded.depth,
+                    score_cp = excluded.score_cp,
+                    mate_in = excluded.mate_in,
+                    best_move = excluded.best_move,
+                    pv = excluded.pv,
+                    created_at = excluded.created_at
+                WHERE excluded.depth >= analysis.depth
+                "#,
+                named_params! {
+                    ":position_hash": hash_to_sql(analysis.position_hash),
+                    ":engine": analysis.engine,
+                    ":fen": analysis.fen,
+                    ":depth": analysis.depth,
+                    ":score_cp": analysis.score_cp,
+                    ":mate_in": analysis.mate_in,
+                    ":best_move": analysis.best_move,
+                    ":pv": analysis.pv,
+                    ":created_at": analysis.created_at,
+                },
+            )?;
+            Ok(written > 0)
+        })
+    }
+
+    /// Get the deepest stored analysis for a position from any engine
+    pub fn get_analysis(&self, position_hash: u64) -> Result<Option<PositionAnalysis>> {
+        self.with_connection(|conn| {
+            let analysis = conn
+                .query_row(
+                    r#"
+                    SELECT position_hash, engine, fen, depth, score_cp, mate_in, best_move, pv, created_at
+                    FROM analysis
+                    WHERE position_hash = ?1
+                    ORDER BY depth DESC, created_at DESC
+                    LIMIT 1
+                    "#,
+                    [hash_to_sql(position_hash)],
+                    analysis_from_row,
+                )
+                .optional()?;
+            Ok(analysis)
+        })
+    }
+
+    /// Get the stored analysis for a position from a specific engine
+    pub fn get_engine_analysis(
+        &self,
+        position_hash: u64,
+        engine: &str,
+    ) -> Result<Option<PositionAnalysis>> {
+        self.with_connection(|conn| {
+            let analysis = conn
+                .query_row(
+                    r#"
+                    SELECT position_hash, engine, fen, depth, score_cp, mate_in, best_move, pv, created_at
+                    FROM analysis
+                    WHERE position_hash = ?1 AND engine = ?2
+                    "#,
+                    (hash_to_sql(position_hash), engine),
+                    analysis_from_row,
+                )
+                .optional()?;
+            Ok(analysis)
+        })
+    }
+
+    /// Remove every stored analysis, returning the number of rows deleted
+    pub fn clear_analysis(&self) -> Result<usize> {
+        self.with_connection(|conn| Ok(conn.execute("DELETE FROM analysis", [])?))
+    }
+}
+
+/// SQLite integers are signed, so hashes are stored by bit pattern
+fn hash_to_sql(hash: u64) -> i64 {
+    hash as i64
+}
+
+/// Convert a database row to a PositionAnalysis struct
+fn analysis_from_row(row: &Row) -> rusqlite::Result<PositionAnalysis> {
+    let position_hash: i64 = row.get("position_hash")?;
+
+    Ok(PositionAnalysis {
+        position_hash: position_hash as u64,
+        engine: row.get("engine")?,
+        fen: row.get("fen")?,
+        depth: row.get("depth")?,
+        score_cp: row.get("score_cp")?,
+        mate_in: row.get("mate_in")?,
+        best_move: row.get("best_move")?,
+        pv: row.get("pv")?,
+        created_at: row.get("created_at")?,
+    })
+}
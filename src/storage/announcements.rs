@@ -0,0 +1,58 @@
+use crate::storage::database::Database;
+use crate::storage::errors::Result;
+use crate::storage::models::Announcement;
+use rusqlite::{named_params, Row};
+
+impl Database {
+    /// Keep an announcement received from a server's operator
+    pub fn store_announcement(&self, server_peer_id: &str, text: &str) -> Result<Announcement> {
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO server_announcements (server_peer_id, text, received_at)
+                VALUES (:server_peer_id, :text, :received_at)
+                "#,
+                named_params! {
+                    ":server_peer_id": server_peer_id,
+                    ":text": text,
+                    ":received_at": now,
+                },
+            )?;
+            Ok(Announcement {
+                id: conn.last_insert_rowid(),
+                server_peer_id: server_peer_id.to_string(),
+                text: text.to_string(),
+                received_at: now,
+            })
+        })
+    }
+
+    /// Get the announcements received at or after `since`, most recent first
+    pub fn get_announcements_since(&self, since: i64) -> Result<Vec<Announcement>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, server_peer_id, text, received_at
+                FROM server_announcements
+                WHERE received_at >= ?1
+                ORDER BY received_at DESC, id DESC
+                "#,
+            )?;
+
+            let announcement_iter = stmt.query_map([since], announcement_from_row)?;
+            let announcements = announcement_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(announcements)
+        })
+    }
+}
+
+fn announcement_from_row(row: &Row) -> rusqlite::Result<Announcement> {
+    Ok(Announcement {
+        id: row.get(0)?,
+        server_peer_id: row.get(1)?,
+        text: row.get(2)?,
+        received_at: row.get(3)?,
+    })
+}
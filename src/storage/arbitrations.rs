@@ -0,0 +1,183 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::{Arbitration, ArbitrationRuling};
+use rusqlite::{named_params, OptionalExtension, Row};
+
+impl Database {
+    /// Start arbitrating a game between `white` and `black`, or refresh the
+    /// players' report of it if we already are
+    pub fn begin_arbitration(
+        &self,
+        game_id: &str,
+        white: &str,
+        black: &str,
+        moves: &[String],
+        result: &str,
+    ) -> Result<()> {
+        if white.is_empty() || black.is_empty() {
+            return Err(StorageError::invalid_data(
+                "players",
+                "Both players of an arbitrated game must be known",
+            ));
+        }
+        let moves = serde_json::to_string(moves)
+            .map_err(|e| StorageError::invalid_data("moves", e.to_string()))?;
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO arbitrations (game_id, white, black, moves, result, joined_at, updated_at)
+                VALUES (:game_id, :white, :black, :moves, :result, :now, :now)
+                ON CONFLICT(game_id) DO UPDATE SET
+                    moves = excluded.moves,
+                    result = excluded.result,
+                    updated_at = excluded.updated_at
+                "#,
+                named_params! {
+                    ":game_id": game_id,
+                    ":white": white,
+                    ":black": black,
+                    ":moves": moves,
+                    ":result": result,
+                    ":now": now,
+                },
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Record the moves and result a player reported for an arbitrated game
+    ///
+    /// Returns false if we don't arbitrate the game.
+    pub fn update_arbitration(
+        &self,
+        game_id: &str,
+        moves: &[String],
+        result: &str,
+    ) -> Result<bool> {
+        let moves = serde_json::to_string(moves)
+            .map_err(|e| StorageError::invalid_data("moves", e.to_string()))?;
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let updated = conn.execute(
+                r#"
+                UPDATE arbitrations
+                SET moves = :moves, result = :result, updated_at = :now
+                WHERE game_id = :game_id
+                "#,
+                named_params! {
+                    ":game_id": game_id,
+                    ":moves": moves,
+                    ":result": result,
+                    ":now": now,
+                },
+            )?;
+            Ok(updated > 0)
+        })
+    }
+
+    /// The arbitration of a game, if we arbitrate it
+    pub fn get_arbitration(&self, game_id: &str) -> Result<Option<Arbitration>> {
+        self.with_connection(|conn| {
+            let arbitration = conn
+                .query_row(
+                    r#"
+                    SELECT game_id, white, black, moves, result, joined_at, updated_at
+                    FROM arbitrations
+                    WHERE game_id = ?1
+                    "#,
+                    [game_id],
+                    arbitration_from_row,
+                )
+                .optional()?;
+            Ok(arbitration)
+        })
+    }
+
+    /// Get every game we arbitrate, most recently updated first
+    pub fn get_arbitrations(&self) -> Result<Vec<Arbitration>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT game_id, white, black, moves, result, joined_at, updated_at
+                FROM arbitrations
+                ORDER BY updated_at DESC, game_id
+                "#,
+            )?;
+
+            let arbitration_iter = stmt.query_map([], arbitration_from_row)?;
+            let arbitrations = arbitration_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(arbitrations)
+        })
+    }
+
+    /// Record our ruling on an illegal move claim in an arbitrated game
+    pub fn record_arbiter_ruling(&self, ruling: &ArbitrationRuling) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO arbiter_rulings
+                    (game_id, claimant, ply, chess_move, upheld, reason, ruled_at)
+                VALUES (:game_id, :claimant, :ply, :chess_move, :upheld, :reason, :ruled_at)
+                "#,
+                named_params! {
+                    ":game_id": ruling.game_id,
+                    ":claimant": ruling.claimant,
+                    ":ply": ruling.ply,
+                    ":chess_move": ruling.chess_move,
+                    ":upheld": ruling.upheld,
+                    ":reason": ruling.reason,
+                    ":ruled_at": ruling.ruled_at,
+                },
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Our rulings in an arbitrated game, oldest first
+    pub fn get_arbiter_rulings(&self, game_id: &str) -> Result<Vec<ArbitrationRuling>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT game_id, claimant, ply, chess_move, upheld, reason, ruled_at
+                FROM arbiter_rulings
+                WHERE game_id = ?1
+                ORDER BY ruled_at, id
+                "#,
+            )?;
+
+            let ruling_iter = stmt.query_map([game_id], ruling_from_row)?;
+            let rulings = ruling_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rulings)
+        })
+    }
+}
+
+fn arbitration_from_row(row: &Row) -> rusqlite::Result<Arbitration> {
+    let moves: String = row.get(3)?;
+    Ok(Arbitration {
+        game_id: row.get(0)?,
+        white: row.get(1)?,
+        black: row.get(2)?,
+        moves: serde_json::from_str(&moves).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        result: row.get(4)?,
+        joined_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn ruling_from_row(row: &Row) -> rusqlite::Result<ArbitrationRuling> {
+    Ok(ArbitrationRuling {
+        game_id: row.get(0)?,
+        claimant: row.get(1)?,
+        ply: row.get(2)?,
+        chess_move: row.get(3)?,
+        upheld: row.get(4)?,
+        reason: row.get(5)?,
+        ruled_at: row.get(6)?,
+    })
+}
@@ -0,0 +1,90 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::GameCheckpoint;
+use rusqlite::{named_params, OptionalExtension, Row};
+
+impl Database {
+    /// Save a game's checkpoint, replacing any earlier one
+    pub fn save_game_checkpoint(
+        &self,
+        game_id: &str,
+        ply: u32,
+        fen: &str,
+        moves_hash: &str,
+        board_hash: &str,
+    ) -> Result<GameCheckpoint> {
+        if game_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "game_id",
+                "Game ID cannot be empty",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT OR REPLACE INTO game_checkpoints
+                    (game_id, ply, fen, moves_hash, board_hash, created_at)
+                VALUES (:game_id, :ply, :fen, :moves_hash, :board_hash, :created_at)
+                "#,
+                named_params! {
+                    ":game_id": game_id,
+                    ":ply": ply,
+                    ":fen": fen,
+                    ":moves_hash": moves_hash,
+                    ":board_hash": board_hash,
+                    ":created_at": now,
+                },
+            )?;
+            Ok(())
+        })?;
+
+        Ok(GameCheckpoint {
+            game_id: game_id.to_string(),
+            ply,
+            fen: fen.to_string(),
+            moves_hash: moves_hash.to_string(),
+            board_hash: board_hash.to_string(),
+            created_at: now,
+        })
+    }
+
+    /// Get a game's checkpoint, if one has been saved
+    pub fn get_game_checkpoint(&self, game_id: &str) -> Result<Option<GameCheckpoint>> {
+        self.with_connection(|conn| {
+            let checkpoint = conn
+                .query_row(
+                    r#"
+                    SELECT game_id, ply, fen, moves_hash, board_hash, created_at
+                    FROM game_checkpoints
+                    WHERE game_id = ?1
+                    "#,
+                    [game_id],
+                    checkpoint_from_row,
+                )
+                .optional()?;
+            Ok(checkpoint)
+        })
+    }
+
+    /// Delete a game's checkpoint, returning whether there was one
+    pub fn delete_game_checkpoint(&self, game_id: &str) -> Result<bool> {
+        self.with_connection(|conn| {
+            let deleted =
+                conn.execute("DELETE FROM game_checkpoints WHERE game_id = ?1", [game_id])?;
+            Ok(deleted > 0)
+        })
+    }
+}
+
+fn checkpoint_from_row(row: &Row) -> rusqlite::Result<GameCheckpoint> {
+    Ok(GameCheckpoint {
+        game_id: row.get(0)?,
+        ply: row.get(1)?,
+        fen: row.get(2)?,
+        moves_hash: row.get(3)?,
+        board_hash: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
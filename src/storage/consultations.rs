@@ -0,0 +1,178 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::{Consultation, ConsultationVote};
+use rusqlite::{named_params, OptionalExtension, Row};
+
+impl Database {
+    /// Put a game to a group vote, or change how long each vote stays open
+    pub fn open_consultation(&self, game_id: &str, window_secs: i64) -> Result<()> {
+        if window_secs <= 0 {
+            return Err(StorageError::invalid_data(
+                "window_secs",
+                "The voting window must be at least one second",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO consultations (game_id, window_secs, created_at)
+                VALUES (:game_id, :window_secs, :created_at)
+                ON CONFLICT(game_id) DO UPDATE SET window_secs = excluded.window_secs
+                "#,
+                named_params! {
+                    ":game_id": game_id,
+                    ":window_secs": window_secs,
+                    ":created_at": now,
+                },
+            )?;
+            Ok(())
+        })
+    }
+
+    /// The consultation for a game, if the game is put to a vote
+    pub fn get_consultation(&self, game_id: &str) -> Result<Option<Consultation>> {
+        self.with_connection(|conn| {
+            let consultation = conn
+                .query_row(
+                    r#"
+                    SELECT game_id, window_secs, created_at
+                    FROM consultations
+                    WHERE game_id = ?1
+                    "#,
+                    [game_id],
+                    consultation_from_row,
+                )
+                .optional()?;
+            Ok(consultation)
+        })
+    }
+
+    /// Get every consultation, oldest first
+    pub fn get_consultations(&self) -> Result<Vec<Consultation>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT game_id, window_secs, created_at
+                FROM consultations
+                ORDER BY created_at, game_id
+                "#,
+            )?;
+
+            let consultation_iter = stmt.query_map([], consultation_from_row)?;
+            let consultations = consultation_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(consultations)
+        })
+    }
+
+    /// Register a peer as a voter in a consultation game
+    ///
+    /// Returns false if the peer was already registered.
+    pub fn add_voter(&self, game_id: &str, peer_id: &str) -> Result<bool> {
+        if peer_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let inserted = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO consultation_voters (game_id, peer_id, joined_at)
+                VALUES (:game_id, :peer_id, :joined_at)
+                "#,
+                named_params! {
+                    ":game_id": game_id,
+                    ":peer_id": peer_id,
+                    ":joined_at": now,
+                },
+            )?;
+            Ok(inserted > 0)
+        })
+    }
+
+    /// Peers registered to vote in a consultation game, in the order they joined
+    pub fn get_voters(&self, game_id: &str) -> Result<Vec<String>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT peer_id
+                FROM consultation_voters
+                WHERE game_id = ?1
+                ORDER BY joined_at, peer_id
+                "#,
+            )?;
+
+            let voter_iter = stmt.query_map([game_id], |row| row.get(0))?;
+            let voters = voter_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(voters)
+        })
+    }
+
+    /// Record a voter's proposal for the next move, replacing any earlier one
+    pub fn cast_vote(&self, game_id: &str, voter_peer_id: &str, chess_move: &str) -> Result<()> {
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT OR REPLACE INTO votes (game_id, voter_peer_id, chess_move, cast_at)
+                VALUES (:game_id, :voter, :chess_move, :cast_at)
+                "#,
+                named_params! {
+                    ":game_id": game_id,
+                    ":voter": voter_peer_id,
+                    ":chess_move": chess_move,
+                    ":cast_at": now,
+                },
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Votes in a game cast at or after `since` (Unix seconds), oldest first
+    pub fn get_votes(&self, game_id: &str, since: i64) -> Result<Vec<ConsultationVote>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT game_id, voter_peer_id, chess_move, cast_at
+                FROM votes
+                WHERE game_id = ?1 AND cast_at >= ?2
+                ORDER BY cast_at, voter_peer_id
+                "#,
+            )?;
+
+            let vote_iter = stmt.query_map(rusqlite::params![game_id, since], vote_from_row)?;
+            let votes = vote_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(votes)
+        })
+    }
+
+    /// Discard the votes in a game once its move has been played
+    pub fn clear_votes(&self, game_id: &str) -> Result<usize> {
+        self.with_connection(|conn| {
+            let deleted = conn.execute("DELETE FROM votes WHERE game_id = ?1", [game_id])?;
+            Ok(deleted)
+        })
+    }
+}
+
+fn consultation_from_row(row: &Row) -> rusqlite::Result<Consultation> {
+    Ok(Consultation {
+        game_id: row.get(0)?,
+        window_secs: row.get(1)?,
+        created_at: row.get(2)?,
+    })
+}
+
+fn vote_from_row(row: &Row) -> rusqlite::Result<ConsultationVote> {
+    Ok(ConsultationVote {
+        game_id: row.get(0)?,
+        voter_peer_id: row.get(1)?,
+        chess_move: row.get(2)?,
+        cast_at: row.get(3)?,
+    })
+}
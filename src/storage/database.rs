@@ -113,6 +113,45 @@ impl ManagedConnection {
     }
 }
 
+/// Handle for issuing writes inside a [`Database::transaction`]
+pub struct Transaction<'conn> {
+    conn: &'conn Connection,
+}
+
+impl Transaction<'_> {
+    /// Access the underlying connection for operations without a typed helper
+    pub fn connection(&self) -> &Connection {
+        self.conn
+    }
+}
+
+/// Environment variable that overrides the database file location
+pub const DATABASE_PATH_ENV_VAR: &str = "MATE_DB_PATH";
+
+/// Where a database is stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseLocation {
+    /// SQLite file on disk
+    File(PathBuf),
+    /// Private in-memory database, discarded when dropped
+    InMemory,
+}
+
+impl DatabaseLocation {
+    /// Path to the database file, or `:memory:` for in-memory databases
+    pub fn path(&self) -> PathBuf {
+        match self {
+            DatabaseLocation::File(path) => path.clone(),
+            DatabaseLocation::InMemory => PathBuf::from(":memory:"),
+        }
+    }
+
+    /// Whether the database lives only in memory
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self, DatabaseLocation::InMemory)
+    }
+}
+
 /// Main database interface with enhanced connection management
 pub struct Database {
     managed_conn: Arc<Mutex<ManagedConnection>>,
@@ -139,6 +178,27 @@ impl Database {
         }
 
         let conn = Self::create_optimized_connection(&db_path.to_path_buf())?;
+        Self::from_connection(peer_id, conn)
+    }
+
+    /// Create a new in-memory database that never touches disk
+    pub fn new_in_memory(peer_id: &str) -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        conn.pragma_update(None, "temp_store", "memory")?;
+        Self::from_connection(peer_id, conn)
+    }
+
+    /// Open a database at the given location
+    pub fn open(peer_id: &str, location: &DatabaseLocation) -> Result<Self> {
+        match location {
+            DatabaseLocation::File(path) => Self::new_with_path(peer_id, path),
+            DatabaseLocation::InMemory => Self::new_in_memory(peer_id),
+        }
+    }
+
+    /// Wrap an open connection and bring its schema up to date
+    fn from_connection(peer_id: &str, conn: Connection) -> Result<Self> {
         let managed_conn = ManagedConnection::new(conn);
 
         let database = Database {
@@ -369,6 +429,17 @@ impl Database {
         }
     }
 
+    /// Apply a batch of writes atomically
+    ///
+    /// Every operation issued through the [`Transaction`] handle is committed
+    /// together when the closure returns `Ok`, or rolled back if it returns an error.
+    pub fn transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Transaction) -> Result<T>,
+    {
+        self.with_transaction(|conn| f(&Transaction { conn }))
+    }
+
     /// Perform database maintenance (VACUUM, ANALYZE, etc.)
     pub fn perform_maintenance(&self) -> Result<()> {
         self.with_connection(|conn| {
@@ -385,6 +456,56 @@ impl Database {
         })
     }
 
+    /// Problems found by SQLite's integrity and foreign key checks
+    ///
+    /// Returns an empty list for a healthy database.
+    pub fn integrity_problems(&self) -> Result<Vec<String>> {
+        self.with_connection(|conn| {
+            let mut problems = Vec::new();
+
+            let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            for row in rows {
+                let message = row?;
+                if message != "ok" {
+                    problems.push(message);
+                }
+            }
+
+            let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+            let rows = stmt.query_map([], |row| {
+                Ok(format!(
+                    "{} row {} refers to a missing {} row",
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<i64>>(1)?
+                        .map_or_else(|| "?".to_string(), |id| id.to_string()),
+                    row.get::<_, String>(2)?
+                ))
+            })?;
+            for row in rows {
+                problems.push(row?);
+            }
+
+            Ok(problems)
+        })
+    }
+
+    /// Newest timestamp stored on any game or message, if there are any
+    pub fn latest_timestamp(&self) -> Result<Option<i64>> {
+        self.with_connection(|conn| {
+            let latest = conn.query_row(
+                "SELECT MAX(latest) FROM (
+                     SELECT MAX(updated_at) AS latest FROM games
+                     UNION ALL
+                     SELECT MAX(created_at) FROM messages
+                 )",
+                [],
+                |row| row.get(0),
+            )?;
+            Ok(latest)
+        })
+    }
+
     /// Get current Unix timestamp
     pub fn current_timestamp() -> i64 {
         SystemTime::now()
@@ -414,7 +535,14 @@ pub fn cleanup_database_files(db_path: &std::path::Path) -> std::io::Result<()>
 }
 
 /// Get the appropriate database path for the current platform
+///
+/// `MATE_DB_PATH` names the database file directly and takes precedence over
+/// `MATE_DATA_DIR`, which only relocates the data directory.
 pub fn get_database_path() -> Result<PathBuf> {
+    if let Ok(custom_db_path) = std::env::var(DATABASE_PATH_ENV_VAR) {
+        return Ok(PathBuf::from(custom_db_path));
+    }
+
     // Check for test override environment variable first
     if let Ok(custom_data_dir) = std::env::var("MATE_DATA_DIR") {
         let data_dir = PathBuf::from(custom_data_dir);
@@ -0,0 +1,471 @@
+use crate::messages::chess::security::MAX_SYNC_CHANGES;
+use crate::messages::chess::{DeviceSync, SyncChange, SyncKind};
+use crate::storage::aliases::normalize_alias;
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::{GameIndexEntry, LinkedDevice, SyncEntry};
+use rusqlite::{named_params, Connection, OptionalExtension, Row};
+
+/// Sync journal kind for address book entries
+pub const SYNC_ALIAS: &str = SyncKind::Alias.as_str();
+/// Sync journal kind for settings
+pub const SYNC_SETTING: &str = SyncKind::Setting.as_str();
+/// Sync journal kind for game index entries
+pub const SYNC_GAME: &str = SyncKind::Game.as_str();
+
+impl SyncEntry {
+    /// The entry as sent to linked devices; `None` for a kind this version
+    /// does not know
+    pub fn to_change(&self) -> Option<SyncChange> {
+        Some(SyncChange {
+            kind: self.kind.parse().ok()?,
+            key: self.key.clone(),
+            value: self.value.clone(),
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+impl From<&SyncChange> for SyncEntry {
+    /// A change received from a linked device, not yet in the journal
+    fn from(change: &SyncChange) -> Self {
+        Self {
+            kind: change.kind.as_str().to_string(),
+            key: change.key.clone(),
+            value: change.value.clone(),
+            updated_at: change.updated_at,
+            seq: 0,
+        }
+    }
+}
+
+impl Database {
+    /// Keep a one-time code another device can pair with until `expires_at`
+    pub fn create_pairing_code(&self, code: &str, expires_at: i64) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO pairing_codes (code, expires_at) VALUES (?1, ?2)",
+                (code, expires_at),
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Use up a pairing code; false if it was never issued or has expired
+    ///
+    /// Expired codes are dropped along the way.
+    pub fn take_pairing_code(&self, code: &str) -> Result<bool> {
+        let now = Self::current_timestamp();
+
+        self.with_transaction(|conn| {
+            conn.execute("DELETE FROM pairing_codes WHERE expires_at < ?1", [now])?;
+            let taken = conn.execute("DELETE FROM pairing_codes WHERE code = ?1", [code])?;
+            Ok(taken > 0)
+        })
+    }
+
+    /// Link another of our devices, or update the address of a linked one
+    pub fn link_device(&self, peer_id: &str, address: &str) -> Result<LinkedDevice> {
+        let peer_id = peer_id.trim();
+        if peer_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO linked_devices (peer_id, address, linked_at)
+                VALUES (:peer_id, :address, :linked_at)
+                ON CONFLICT(peer_id) DO UPDATE SET address = excluded.address
+                "#,
+                named_params! {
+                    ":peer_id": peer_id,
+                    ":address": address,
+                    ":linked_at": now,
+                },
+            )?;
+            let device = conn.query_row(
+                &format!("{DEVICE_COLUMNS} WHERE peer_id = ?1"),
+                [peer_id],
+                device_from_row,
+            )?;
+            Ok(device)
+        })
+    }
+
+    /// Stop syncing with a device. Returns false if it was not linked.
+    pub fn unlink_device(&self, peer_id: &str) -> Result<bool> {
+        self.with_connection(|conn| {
+            let removed =
+                conn.execute("DELETE FROM linked_devices WHERE peer_id = ?1", [peer_id])?;
+            Ok(removed > 0)
+        })
+    }
+
+    /// Every linked device, longest linked first
+    pub fn get_linked_devices(&self) -> Result<Vec<LinkedDevice>> {
+        self.with_connection(|conn| {
+            let mut stmt =
+                conn.prepare(&format!("{DEVICE_COLUMNS} ORDER BY linked_at, peer_id"))?;
+            let devices = stmt
+                .query_map([], device_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(devices)
+        })
+    }
+
+    /// A linked device by peer ID
+    pub fn get_linked_device(&self, peer_id: &str) -> Result<Option<LinkedDevice>> {
+        self.with_connection(|conn| {
+            let device = conn
+                .query_row(
+                    &format!("{DEVICE_COLUMNS} WHERE peer_id = ?1"),
+                    [peer_id],
+                    device_from_row,
+                )
+                .optional()?;
+            Ok(device)
+        })
+    }
+
+    /// Note a completed sync with a device, moving its journal positions
+    /// forward (never back)
+    pub fn record_device_sync(
+        &self,
+        peer_id: &str,
+        received_through: u64,
+        sent_through: u64,
+    ) -> Result<()> {
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                UPDATE linked_devices
+                SET last_synced_at = :now,
+                    received_through = MAX(received_through, :received_through),
+                    sent_through = MAX(sent_through, :sent_through)
+                WHERE peer_id = :peer_id
+                "#,
+                named_params! {
+                    ":peer_id": peer_id,
+                    ":now": now,
+                    ":received_through": received_through as i64,
+                    ":sent_through": sent_through as i64,
+                },
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Record a change made on this device to a synced entry
+    ///
+    /// Nothing is recorded when the journal already holds the value, or a
+    /// later change to the entry. Returns whether the change was recorded.
+    pub fn record_sync_change(
+        &self,
+        kind: &str,
+        key: &str,
+        value: Option<&str>,
+        updated_at: i64,
+    ) -> Result<bool> {
+        self.with_transaction(|conn| {
+            if let Some(current) = journal_entry(conn, kind, key)? {
+                if current.value.as_deref() == value || current.updated_at > updated_at {
+                    return Ok(false);
+                }
+            }
+            write_journal_entry(conn, kind, key, value, updated_at, false)?;
+            Ok(true)
+        })
+    }
+
+    /// Apply changes received from a linked device
+    ///
+    /// The latest change to each entry wins, with ties going to the greater
+    /// value so both devices settle on the same one. Address book changes
+    /// are applied to the aliases right away, and skipped if the alias is
+    /// not valid here; settings are marked pending until written to the
+    /// configuration file. Returns the changes applied.
+    pub fn apply_sync_changes(&self, changes: &[SyncEntry]) -> Result<Vec<SyncEntry>> {
+        self.with_transaction(|conn| {
+            let mut applied = Vec::new();
+            for change in changes {
+                if change.kind == SYNC_ALIAS
+                    && normalize_alias(&change.key).ok().as_deref() != Some(change.key.as_str())
+                {
+                    continue;
+                }
+                if let Some(current) = journal_entry(conn, &change.kind, &change.key)? {
+                    let newer = (change.updated_at, change.value.as_deref())
+                        > (current.updated_at, current.value.as_deref());
+                    if !newer {
+                        continue;
+                    }
+                }
+                if change.kind == SYNC_ALIAS {
+                    apply_alias(conn, change)?;
+                }
+                let seq = write_journal_entry(
+                    conn,
+                    &change.kind,
+                    &change.key,
+                    change.value.as_deref(),
+                    change.updated_at,
+                    change.kind == SYNC_SETTING,
+                )?;
+                applied.push(SyncEntry {
+                    seq,
+                    ..change.clone()
+                });
+            }
+            Ok(applied)
+        })
+    }
+
+    /// Journal entries after `seq`, oldest first, at most `limit` of them
+    pub fn sync_changes_since(&self, seq: u64, limit: usize) -> Result<Vec<SyncEntry>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{JOURNAL_COLUMNS} WHERE seq > ?1 ORDER BY seq LIMIT ?2"
+            ))?;
+            let entries = stmt
+                .query_map((seq as i64, limit as i64), journal_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+    }
+
+    /// Our changes a linked device does not have yet, as a sync message
+    ///
+    /// `sent_through` is the last of our journal entries the device has,
+    /// and `received_through` the last of its entries we have. At most
+    /// [`MAX_SYNC_CHANGES`] changes are included; `through` is the last.
+    pub fn device_sync(&self, sent_through: u64, received_through: u64) -> Result<DeviceSync> {
+        let entries = self.sync_changes_since(sent_through, MAX_SYNC_CHANGES)?;
+        let through = match entries.last() {
+            Some(last) => last.seq,
+            None => self.sync_journal_head()?.max(sent_through),
+        };
+        Ok(DeviceSync::new(
+            received_through,
+            through,
+            entries.iter().filter_map(SyncEntry::to_change).collect(),
+        ))
+    }
+
+    /// Position of the latest journal entry, or 0 with none
+    pub fn sync_journal_head(&self) -> Result<u64> {
+        self.with_connection(journal_head)
+    }
+
+    /// Every journal entry of one kind, by key
+    pub fn sync_entries(&self, kind: &str) -> Result<Vec<SyncEntry>> {
+        self.with_connection(|conn| {
+            let mut stmt =
+                conn.prepare(&format!("{JOURNAL_COLUMNS} WHERE kind = ?1 ORDER BY key"))?;
+            let entries = stmt
+                .query_map([kind], journal_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+    }
+
+    /// Settings received from linked devices but not yet written to the
+    /// configuration file
+    pub fn pending_sync_settings(&self) -> Result<Vec<SyncEntry>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{JOURNAL_COLUMNS} WHERE kind = '{SYNC_SETTING}' AND pending = 1 ORDER BY key"
+            ))?;
+            let entries = stmt
+                .query_map([], journal_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+    }
+
+    /// Note that a received setting was written to the configuration file
+    pub fn clear_pending_sync_setting(&self, key: &str) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE sync_journal SET pending = 0 WHERE kind = ?1 AND key = ?2",
+                (SYNC_SETTING, key),
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Bring the journal up to date with the address book
+    ///
+    /// Aliases carry the time they were last set; removed ones are recorded
+    /// as removed now. Returns how many changes were recorded.
+    pub fn record_address_book_changes(&self) -> Result<usize> {
+        let now = Self::current_timestamp();
+        let aliases = self.get_peer_aliases()?;
+        let mut recorded = 0;
+
+        for alias in &aliases {
+            if self.record_sync_change(
+                SYNC_ALIAS,
+                &alias.alias,
+                Some(&alias.peer_id),
+                alias.created_at,
+            )? {
+                recorded += 1;
+            }
+        }
+        for entry in self.sync_entries(SYNC_ALIAS)? {
+            let removed = entry.value.is_some() && !aliases.iter().any(|a| a.alias == entry.key);
+            if removed && self.record_sync_change(SYNC_ALIAS, &entry.key, None, now)? {
+                recorded += 1;
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Bring the journal up to date with the games played on this device,
+    /// whose peer ID is `device`
+    ///
+    /// Returns how many changes were recorded.
+    pub fn record_game_index_changes(&self, device: &str) -> Result<usize> {
+        let mut recorded = 0;
+
+        for game in self.get_all_games()? {
+            let entry = GameIndexEntry {
+                device: device.to_string(),
+                opponent: game.opponent_peer_id.clone(),
+                my_color: game.my_color.as_str().to_string(),
+                status: game.status.as_str().to_string(),
+                result: game.result.as_ref().map(|r| r.as_str().to_string()),
+                created_at: game.created_at,
+            };
+            let value = serde_json::to_string(&entry)
+                .map_err(|e| StorageError::invalid_data("game_index", e.to_string()))?;
+            if self.record_sync_change(SYNC_GAME, &game.id, Some(&value), game.updated_at)? {
+                recorded += 1;
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Games played on devices other than `this_device`, by game ID
+    ///
+    /// Index entries that do not parse are skipped.
+    pub fn linked_device_games(&self, this_device: &str) -> Result<Vec<(String, GameIndexEntry)>> {
+        Ok(self
+            .sync_entries(SYNC_GAME)?
+            .into_iter()
+            .filter_map(|entry| {
+                let index = serde_json::from_str::<GameIndexEntry>(entry.value.as_deref()?).ok()?;
+                (index.device != this_device).then_some((entry.key, index))
+            })
+            .collect())
+    }
+}
+
+const DEVICE_COLUMNS: &str = "SELECT peer_id, address, linked_at, last_synced_at, \
+                              received_through, sent_through FROM linked_devices";
+
+const JOURNAL_COLUMNS: &str = "SELECT kind, key, value, updated_at, seq FROM sync_journal";
+
+fn journal_head(conn: &Connection) -> Result<u64> {
+    let head: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(seq), 0) FROM sync_journal",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(head as u64)
+}
+
+fn journal_entry(conn: &Connection, kind: &str, key: &str) -> Result<Option<SyncEntry>> {
+    let entry = conn
+        .query_row(
+            &format!("{JOURNAL_COLUMNS} WHERE kind = ?1 AND key = ?2"),
+            [kind, key],
+            journal_from_row,
+        )
+        .optional()?;
+    Ok(entry)
+}
+
+/// Replace an entry's latest change, placing it at the end of the journal
+fn write_journal_entry(
+    conn: &Connection,
+    kind: &str,
+    key: &str,
+    value: Option<&str>,
+    updated_at: i64,
+    pending: bool,
+) -> Result<u64> {
+    let seq = journal_head(conn)? + 1;
+    conn.execute(
+        r#"
+        INSERT INTO sync_journal (kind, key, value, updated_at, seq, pending)
+        VALUES (:kind, :key, :value, :updated_at, :seq, :pending)
+        ON CONFLICT(kind, key) DO UPDATE SET
+            value = excluded.value,
+            updated_at = excluded.updated_at,
+            seq = excluded.seq,
+            pending = excluded.pending
+        "#,
+        named_params! {
+            ":kind": kind,
+            ":key": key,
+            ":value": value,
+            ":updated_at": updated_at,
+            ":seq": seq as i64,
+            ":pending": pending,
+        },
+    )?;
+    Ok(seq)
+}
+
+/// Point or remove an alias as a linked device did
+///
+/// The alias keeps the time of the change, so the address book matches
+/// the journal afterwards.
+fn apply_alias(conn: &Connection, change: &SyncEntry) -> Result<()> {
+    match &change.value {
+        Some(peer_id) => {
+            conn.execute(
+                r#"
+                INSERT INTO peer_aliases (alias, peer_id, created_at)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(alias) DO UPDATE SET peer_id = excluded.peer_id,
+                                                 created_at = excluded.created_at
+                "#,
+                (&change.key, peer_id, change.updated_at),
+            )?;
+        }
+        None => {
+            conn.execute("DELETE FROM peer_aliases WHERE alias = ?1", [&change.key])?;
+        }
+    }
+    Ok(())
+}
+
+fn device_from_row(row: &Row) -> rusqlite::Result<LinkedDevice> {
+    Ok(LinkedDevice {
+        peer_id: row.get(0)?,
+        address: row.get(1)?,
+        linked_at: row.get(2)?,
+        last_synced_at: row.get(3)?,
+        received_through: row.get::<_, i64>(4)? as u64,
+        sent_through: row.get::<_, i64>(5)? as u64,
+    })
+}
+
+fn journal_from_row(row: &Row) -> rusqlite::Result<SyncEntry> {
+    Ok(SyncEntry {
+        kind: row.get(0)?,
+        key: row.get(1)?,
+        value: row.get(2)?,
+        updated_at: row.get(3)?,
+        seq: row.get::<_, i64>(4)? as u64,
+    })
+}
@@ -0,0 +1,224 @@
+use crate::storage::database::Database;
+use crate::storage::errors::Result;
+use crate::storage::models::{GameResult, PlayerColor, TimeControl};
+use rusqlite::{Connection, Row};
+use std::str::FromStr;
+
+/// Length of the periods `mate stats` charts results over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatsPeriod {
+    Week,
+    #[default]
+    Month,
+    Year,
+}
+
+impl StatsPeriod {
+    /// Every period name, as accepted by `--by`
+    pub const NAMES: [&'static str; 3] = ["week", "month", "year"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsPeriod::Week => "week",
+            StatsPeriod::Month => "month",
+            StatsPeriod::Year => "year",
+        }
+    }
+
+    /// How SQLite's `strftime` names the period holding a time, e.g.
+    /// `2026-W07`, `2026-02`, or `2026`; names sort in time order
+    fn strftime_format(&self) -> &'static str {
+        match self {
+            StatsPeriod::Week => "%Y-W%W",
+            StatsPeriod::Month => "%Y-%m",
+            StatsPeriod::Year => "%Y",
+        }
+    }
+}
+
+impl FromStr for StatsPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "week" => Ok(StatsPeriod::Week),
+            "month" => Ok(StatsPeriod::Month),
+            "year" => Ok(StatsPeriod::Year),
+            _ => Err(format!(
+                "Invalid period '{s}'. Expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// How a set of finished games ended for me, and how long they ran
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultTally {
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Half-moves played across all the games
+    pub plies: u64,
+}
+
+impl ResultTally {
+    /// Percentage of points won, counting draws as half
+    pub fn score(&self) -> Option<f64> {
+        let decided = self.wins + self.draws + self.losses;
+        (decided > 0).then(|| {
+            (f64::from(self.wins) + f64::from(self.draws) / 2.0) * 100.0 / f64::from(decided)
+        })
+    }
+
+    /// Mean number of full moves per game
+    pub fn average_moves(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.plies as f64 / 2.0 / f64::from(self.games))
+    }
+}
+
+impl Database {
+    /// Results of finished games by the period they finished in, oldest first
+    pub fn results_by_period(&self, period: StatsPeriod) -> Result<Vec<(String, ResultTally)>> {
+        let key = format!(
+            "strftime('{}', COALESCE(games.completed_at, games.updated_at), 'unixepoch')",
+            period.strftime_format()
+        );
+        self.with_connection(|conn| grouped_results(conn, &key, "grouping", |row| row.get(0)))
+    }
+
+    /// Results of finished games by the color I played, white first
+    pub fn results_by_color(&self) -> Result<Vec<(PlayerColor, ResultTally)>> {
+        self.with_connection(|conn| {
+            grouped_results(conn, "games.my_color", "grouping DESC", |row| {
+                let color: String = row.get(0)?;
+                PlayerColor::from_str(&color).map_err(|_| {
+                    rusqlite::Error::InvalidColumnType(
+                        0,
+                        "my_color".to_string(),
+                        rusqlite::types::Type::Text,
+                    )
+                })
+            })
+        })
+    }
+
+    /// Results of finished games by time control, most played first; games
+    /// without a clock are grouped under `None`
+    pub fn results_by_time_control(&self) -> Result<Vec<(Option<TimeControl>, ResultTally)>> {
+        let key = "json_extract(games.metadata, '$.time_control.initial_time_ms') \
+                   || '+' || json_extract(games.metadata, '$.time_control.increment_ms')";
+        self.with_connection(|conn| {
+            grouped_results(conn, key, "COUNT(*) DESC, grouping", |row| {
+                let key: Option<String> = row.get(0)?;
+                Ok(key.and_then(|key| {
+                    let (initial, increment) = key.split_once('+')?;
+                    Some(TimeControl {
+                        initial_time_ms: initial.parse().ok()?,
+                        increment_ms: increment.parse().ok()?,
+                    })
+                }))
+            })
+        })
+    }
+
+    /// Results of every finished game
+    pub fn result_totals(&self) -> Result<ResultTally> {
+        self.with_connection(|conn| {
+            let totals = grouped_results(conn, "'all'", "grouping", |_| Ok(()))?;
+            Ok(totals
+                .into_iter()
+                .next()
+                .map(|(_, tally)| tally)
+                .unwrap_or_default())
+        })
+    }
+
+    /// Opponent and result of every decided rated game, in the order they
+    /// finished
+    ///
+    /// A game is rated if its metadata says so, or else if the invitation
+    /// that opened it did.
+    pub fn rated_results(&self) -> Result<Vec<(String, GameResult)>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT games.opponent_peer_id, games.result
+                FROM games
+                WHERE games.status = 'completed'
+                  AND games.result IN ('win', 'draw', 'loss')
+                  AND COALESCE(
+                        json_extract(games.metadata, '$.rated'),
+                        (SELECT json_extract(messages.content, '$.rated')
+                         FROM messages
+                         WHERE messages.game_id = games.id
+                           AND lower(messages.message_type) IN ('gameinvite', 'game_invite')
+                         ORDER BY messages.id
+                         LIMIT 1),
+                        0
+                      ) = 1
+                ORDER BY COALESCE(games.completed_at, games.updated_at), games.id
+                "#,
+            )?;
+            let rows = stmt.query_map([], |row| {
+                let result: String = row.get(1)?;
+                let result = GameResult::from_str(&result).map_err(|_| {
+                    rusqlite::Error::InvalidColumnType(
+                        1,
+                        "result".to_string(),
+                        rusqlite::types::Type::Text,
+                    )
+                })?;
+                Ok((row.get(0)?, result))
+            })?;
+            Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+        })
+    }
+}
+
+/// Tally finished games grouped by the SQL expression `key`
+///
+/// `key` and `order` are fixed by the callers above, never user input.
+fn grouped_results<K>(
+    conn: &Connection,
+    key: &str,
+    order: &str,
+    key_from_row: impl Fn(&Row) -> rusqlite::Result<K>,
+) -> Result<Vec<(K, ResultTally)>> {
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        WITH plies AS (
+            SELECT game_id, COUNT(*) AS plies
+            FROM messages
+            WHERE lower(message_type) = 'move'
+            GROUP BY game_id
+        )
+        SELECT {key} AS grouping,
+               COUNT(*),
+               COUNT(*) FILTER (WHERE games.result = 'win'),
+               COUNT(*) FILTER (WHERE games.result = 'draw'),
+               COUNT(*) FILTER (WHERE games.result = 'loss'),
+               COALESCE(SUM(plies.plies), 0)
+        FROM games
+        LEFT JOIN plies ON plies.game_id = games.id
+        WHERE games.status = 'completed'
+        GROUP BY grouping
+        ORDER BY {order}
+        "#
+    ))?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            key_from_row(row)?,
+            ResultTally {
+                games: row.get(1)?,
+                wins: row.get(2)?,
+                draws: row.get(3)?,
+                losses: row.get(4)?,
+                plies: row.get(5)?,
+            },
+        ))
+    })?;
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
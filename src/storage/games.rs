@@ -1,10 +1,123 @@
-use crate::storage::database::Database;
+use crate::storage::database::{Database, Transaction};
 use crate::storage::errors::{Result, StorageError};
 use crate::storage::models::{Game, GameResult, GameStatus, PlayerColor};
-use rusqlite::{named_params, Row};
+use crate::storage::tags::normalize_tag;
+use rusqlite::{named_params, Connection, Row};
+use tracing::instrument;
+
+/// Selects, orders, and pages the games returned by [`Database::query_games`]
+///
+/// Unset fields match every game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameFilter {
+    /// Only games in one of these states
+    pub statuses: Vec<GameStatus>,
+    /// Only games against a peer whose ID starts with this prefix
+    pub opponent: Option<String>,
+    /// Only games carrying this tag
+    pub tag: Option<String>,
+    /// Only games updated at or after this Unix timestamp
+    pub since: Option<i64>,
+    pub sort: GameSort,
+    /// Maximum number of games to return
+    pub limit: Option<u32>,
+    /// Number of matching games to skip before the first one returned
+    pub offset: u32,
+}
+
+/// Order of the games returned by [`Database::query_games`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameSort {
+    /// Most recently updated first
+    #[default]
+    Updated,
+    /// Most recently created first
+    Created,
+    /// By opponent peer ID, most recently updated first within each
+    Opponent,
+    /// Pending, active, completed, then abandoned
+    Status,
+}
+
+impl GameSort {
+    /// Names accepted by `mate games --sort`
+    pub const NAMES: [&'static str; 4] = ["updated", "created", "opponent", "status"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameSort::Updated => "updated",
+            GameSort::Created => "created",
+            GameSort::Opponent => "opponent",
+            GameSort::Status => "status",
+        }
+    }
+
+    fn order_by(&self) -> &'static str {
+        match self {
+            GameSort::Updated => "updated_at DESC, id ASC",
+            GameSort::Created => "created_at DESC, id ASC",
+            GameSort::Opponent => "opponent_peer_id ASC, updated_at DESC, id ASC",
+            GameSort::Status => {
+                "CASE status WHEN 'pending' THEN 0 WHEN 'active' THEN 1 \
+                 WHEN 'completed' THEN 2 ELSE 3 END, updated_at DESC, id ASC"
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for GameSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "updated" => Ok(GameSort::Updated),
+            "created" => Ok(GameSort::Created),
+            "opponent" => Ok(GameSort::Opponent),
+            "status" => Ok(GameSort::Status),
+            _ => Err(format!("Invalid sort order: {s}")),
+        }
+    }
+}
+
+/// Games matching a game filter
+const FILTER_WHERE: &str =
+    "(:statuses IS NULL OR status IN (SELECT value FROM json_each(:statuses))) \
+     AND (:opponent IS NULL OR substr(opponent_peer_id, 1, length(:opponent)) = :opponent) \
+     AND (:tag IS NULL OR id IN (SELECT game_id FROM game_tags WHERE tag = :tag)) \
+     AND (:since IS NULL OR updated_at >= :since)";
+
+/// Filter values bound to the named parameters of [`FILTER_WHERE`]
+struct FilterParams {
+    statuses: Option<String>,
+    opponent: Option<String>,
+    tag: Option<String>,
+    since: Option<i64>,
+}
+
+impl FilterParams {
+    fn new(filter: &GameFilter) -> Result<Self> {
+        let statuses = if filter.statuses.is_empty() {
+            None
+        } else {
+            let names: Vec<&str> = filter.statuses.iter().map(GameStatus::as_str).collect();
+            Some(
+                serde_json::to_string(&names)
+                    .map_err(|e| StorageError::serialization_error("game status filter", e))?,
+            )
+        };
+
+        Ok(Self {
+            statuses,
+            opponent: filter.opponent.clone().filter(|o| !o.is_empty()),
+            tag: filter.tag.as_deref().map(normalize_tag).transpose()?,
+            since: filter.since,
+        })
+    }
+}
 
 impl Database {
     /// Create a new game record
+    #[instrument(level = "debug", skip_all, fields(peer_id = %opponent_peer_id))]
     pub fn create_game(
         &self,
         opponent_peer_id: String,
@@ -64,53 +177,19 @@ impl Database {
     }
 
     /// Get a game by ID
+    #[instrument(level = "debug", skip(self))]
     pub fn get_game(&self, game_id: &str) -> Result<Game> {
-        self.with_connection(|conn| {
-            conn.query_row(
-                r#"
-                SELECT id, opponent_peer_id, my_color, status, 
-                       created_at, updated_at, completed_at, result, metadata
-                FROM games 
-                WHERE id = ?1
-                "#,
-                [game_id],
-                game_from_row,
-            )
-            .map_err(|e| match e {
-                rusqlite::Error::QueryReturnedNoRows => StorageError::game_not_found(game_id),
-                _ => StorageError::ConnectionFailed(e),
-            })
-        })
+        self.with_connection(|conn| select_game(conn, game_id))
     }
 
     /// Update game status
+    #[instrument(level = "debug", skip(self, status), fields(status = status.as_str()))]
     pub fn update_game_status(&self, game_id: &str, status: GameStatus) -> Result<()> {
-        let now = Self::current_timestamp();
-        let completed_at = if matches!(status, GameStatus::Completed | GameStatus::Abandoned) {
-            Some(now)
-        } else {
-            None
-        };
-
-        self.with_connection(|conn| {
-            let rows_affected = conn.execute(
-                r#"
-                UPDATE games 
-                SET status = ?1, updated_at = ?2, completed_at = ?3
-                WHERE id = ?4
-                "#,
-                (status.as_str(), now, completed_at, game_id),
-            )?;
-
-            if rows_affected == 0 {
-                return Err(StorageError::game_not_found(game_id));
-            }
-
-            Ok(())
-        })
+        self.with_connection(|conn| set_game_status(conn, game_id, status))
     }
 
     /// Update game result
+    #[instrument(level = "debug", skip(self, result), fields(result = result.as_str()))]
     pub fn update_game_result(&self, game_id: &str, result: GameResult) -> Result<()> {
         let now = Self::current_timestamp();
 
@@ -213,6 +292,60 @@ impl Database {
         })
     }
 
+    /// Get the games matching a filter, sorted and paged as it asks
+    #[instrument(level = "debug", skip_all)]
+    pub fn query_games(&self, filter: &GameFilter) -> Result<Vec<Game>> {
+        let params = FilterParams::new(filter)?;
+        let limit = filter.limit.map_or(-1, i64::from);
+
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT id, opponent_peer_id, my_color, status,
+                       created_at, updated_at, completed_at, result, metadata
+                FROM games
+                WHERE {FILTER_WHERE}
+                ORDER BY {}
+                LIMIT :limit OFFSET :offset
+                "#,
+                filter.sort.order_by()
+            ))?;
+
+            let game_iter = stmt.query_map(
+                named_params! {
+                    ":statuses": params.statuses,
+                    ":opponent": params.opponent,
+                    ":tag": params.tag,
+                    ":since": params.since,
+                    ":limit": limit,
+                    ":offset": filter.offset,
+                },
+                game_from_row,
+            )?;
+            let games = game_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(games)
+        })
+    }
+
+    /// Count the games matching a filter, ignoring its limit and offset
+    pub fn count_games(&self, filter: &GameFilter) -> Result<u32> {
+        let params = FilterParams::new(filter)?;
+
+        self.with_connection(|conn| {
+            let count: u32 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM games WHERE {FILTER_WHERE}"),
+                named_params! {
+                    ":statuses": params.statuses,
+                    ":opponent": params.opponent,
+                    ":tag": params.tag,
+                    ":since": params.since,
+                },
+                |row| row.get(0),
+            )?;
+            Ok(count)
+        })
+    }
+
     /// Delete a game and all associated messages
     pub fn delete_game(&self, game_id: &str) -> Result<()> {
         self.with_connection(|conn| {
@@ -227,8 +360,63 @@ impl Database {
     }
 }
 
+impl Transaction<'_> {
+    /// Get a game by ID, observing writes made earlier in the transaction
+    pub fn get_game(&self, game_id: &str) -> Result<Game> {
+        select_game(self.connection(), game_id)
+    }
+
+    /// Update game status as part of the transaction
+    pub fn update_game_status(&self, game_id: &str, status: GameStatus) -> Result<()> {
+        set_game_status(self.connection(), game_id, status)
+    }
+}
+
+/// Fetch a single game row on the given connection
+fn select_game(conn: &Connection, game_id: &str) -> Result<Game> {
+    conn.query_row(
+        r#"
+        SELECT id, opponent_peer_id, my_color, status, 
+               created_at, updated_at, completed_at, result, metadata
+        FROM games 
+        WHERE id = ?1
+        "#,
+        [game_id],
+        game_from_row,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => StorageError::game_not_found(game_id),
+        _ => StorageError::ConnectionFailed(e),
+    })
+}
+
+/// Set a game's status (and completion time for terminal states) on the given connection
+fn set_game_status(conn: &Connection, game_id: &str, status: GameStatus) -> Result<()> {
+    let now = Database::current_timestamp();
+    let completed_at = if matches!(status, GameStatus::Completed | GameStatus::Abandoned) {
+        Some(now)
+    } else {
+        None
+    };
+
+    let rows_affected = conn.execute(
+        r#"
+        UPDATE games 
+        SET status = ?1, updated_at = ?2, completed_at = ?3
+        WHERE id = ?4
+        "#,
+        (status.as_str(), now, completed_at, game_id),
+    )?;
+
+    if rows_affected == 0 {
+        return Err(StorageError::game_not_found(game_id));
+    }
+
+    Ok(())
+}
+
 /// Convert a database row to a Game struct
-fn game_from_row(row: &Row) -> rusqlite::Result<Game> {
+pub(crate) fn game_from_row(row: &Row) -> rusqlite::Result<Game> {
     let metadata_str: Option<String> = row.get("metadata")?;
     let metadata = match metadata_str {
         Some(s) => Some(serde_json::from_str(&s).map_err(|_e| {
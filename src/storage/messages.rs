@@ -1,10 +1,12 @@
-use crate::storage::database::Database;
+use crate::storage::database::{Database, Transaction};
 use crate::storage::errors::{Result, StorageError};
 use crate::storage::models::Message;
-use rusqlite::{named_params, Row};
+use rusqlite::{named_params, Connection, Row};
+use tracing::instrument;
 
 impl Database {
     /// Store a new message
+    #[instrument(level = "debug", skip_all, fields(game_id = %game_id, msg_type = %message_type, peer_id = %sender_peer_id))]
     pub fn store_message(
         &self,
         game_id: String,
@@ -13,38 +15,16 @@ impl Database {
         signature: String,
         sender_peer_id: String,
     ) -> Result<Message> {
-        let now = Self::current_timestamp();
-
         self.with_connection(|conn| {
-            conn.execute(
-                r#"
-                INSERT INTO messages (
-                    game_id, message_type, content, signature, sender_peer_id, created_at
-                ) VALUES (
-                    :game_id, :message_type, :content, :signature, :sender_peer_id, :created_at
-                )
-                "#,
-                named_params! {
-                    ":game_id": game_id,
-                    ":message_type": message_type,
-                    ":content": content,
-                    ":signature": signature,
-                    ":sender_peer_id": sender_peer_id,
-                    ":created_at": now,
-                },
-            )?;
-
-            let message_id = conn.last_insert_rowid();
-
-            Ok(Message {
-                id: Some(message_id),
+            insert_message(
+                conn,
                 game_id,
                 message_type,
                 content,
                 signature,
                 sender_peer_id,
-                created_at: now,
-            })
+                Database::current_timestamp(),
+            )
         })
     }
 
@@ -70,6 +50,7 @@ impl Database {
     }
 
     /// Get all messages for a specific game
+    #[instrument(level = "debug", skip(self))]
     pub fn get_messages_for_game(&self, game_id: &str) -> Result<Vec<Message>> {
         self.with_connection(|conn| {
             let mut stmt = conn.prepare(
@@ -172,6 +153,24 @@ impl Database {
         })
     }
 
+    /// Get messages stored after the message with ID `after_id`, oldest first
+    pub fn get_messages_after(&self, after_id: i64) -> Result<Vec<Message>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, game_id, message_type, content, signature, sender_peer_id, created_at
+                FROM messages
+                WHERE id > ?1
+                ORDER BY id ASC
+                "#,
+            )?;
+
+            let message_iter = stmt.query_map([after_id], message_from_row)?;
+            let messages = message_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(messages)
+        })
+    }
+
     /// Count messages for a specific game
     pub fn count_messages_for_game(&self, game_id: &str) -> Result<u32> {
         self.with_connection(|conn| {
@@ -208,6 +207,94 @@ impl Database {
     }
 }
 
+impl Transaction<'_> {
+    /// Store a new message as part of the transaction
+    pub fn store_message(
+        &self,
+        game_id: String,
+        message_type: String,
+        content: String,
+        signature: String,
+        sender_peer_id: String,
+    ) -> Result<Message> {
+        insert_message(
+            self.connection(),
+            game_id,
+            message_type,
+            content,
+            signature,
+            sender_peer_id,
+            Database::current_timestamp(),
+        )
+    }
+
+    /// Store a new message as part of the transaction, recorded as created
+    /// at `created_at` rather than now
+    ///
+    /// Used for messages from peers, which are dated by when the peer sent
+    /// them, translated to our clock.
+    pub fn store_message_at(
+        &self,
+        game_id: String,
+        message_type: String,
+        content: String,
+        signature: String,
+        sender_peer_id: String,
+        created_at: i64,
+    ) -> Result<Message> {
+        insert_message(
+            self.connection(),
+            game_id,
+            message_type,
+            content,
+            signature,
+            sender_peer_id,
+            created_at,
+        )
+    }
+}
+
+/// Insert a message row on the given connection
+fn insert_message(
+    conn: &Connection,
+    game_id: String,
+    message_type: String,
+    content: String,
+    signature: String,
+    sender_peer_id: String,
+    created_at: i64,
+) -> Result<Message> {
+    conn.execute(
+        r#"
+        INSERT INTO messages (
+            game_id, message_type, content, signature, sender_peer_id, created_at
+        ) VALUES (
+            :game_id, :message_type, :content, :signature, :sender_peer_id, :created_at
+        )
+        "#,
+        named_params! {
+            ":game_id": game_id,
+            ":message_type": message_type,
+            ":content": content,
+            ":signature": signature,
+            ":sender_peer_id": sender_peer_id,
+            ":created_at": created_at,
+        },
+    )?;
+
+    let message_id = conn.last_insert_rowid();
+
+    Ok(Message {
+        id: Some(message_id),
+        game_id,
+        message_type,
+        content,
+        signature,
+        sender_peer_id,
+        created_at,
+    })
+}
+
 /// Convert a database row to a Message struct
 fn message_from_row(row: &Row) -> rusqlite::Result<Message> {
     Ok(Message {
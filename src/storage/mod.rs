@@ -1,14 +1,41 @@
+pub mod account;
+pub mod aliases;
+pub mod analysis;
+pub mod announcements;
+pub mod arbitrations;
+pub mod checkpoints;
+pub mod consultations;
 pub mod database;
+pub mod devices;
 pub mod errors;
+pub mod game_stats;
 pub mod games;
 pub mod messages;
 pub mod models;
+pub mod moderation;
+pub mod openings;
+pub mod peers;
+pub mod presence;
+pub mod purge;
+pub mod revocations;
+pub mod rotations;
 pub mod schema;
+pub mod sealed_moves;
+pub mod studies;
+pub mod sync_issues;
+pub mod tags;
+pub mod verification;
 
 // Re-export key types for easy access
-pub use database::Database;
+pub use database::{Database, DatabaseLocation, Transaction};
 pub use errors::StorageError;
-pub use models::{Game, GameStatus, Message, PlayerColor};
+pub use models::{
+    Announcement, Arbitration, ArbitrationRuling, Consultation, ConsultationVote, Game,
+    GameCheckpoint, GameIndexEntry, GameNote, GameStatus, GameSyncIssue, KeyRevocation,
+    KeyRotation, LinkedDevice, Message, PeerAlias, PeerEvent, PeerEventType, PeerPresence,
+    PeerReport, PeerSummary, PeerVerification, PlayerColor, PositionAnalysis, SealedMoveRecord,
+    StudyRecord, SyncEntry,
+};
 
 // Re-export commonly used functions
 pub use database::get_database_path;
@@ -37,6 +37,9 @@ pub enum GameStatus {
 }
 
 impl GameStatus {
+    /// Every status name, in lifecycle order
+    pub const NAMES: [&'static str; 4] = ["pending", "active", "completed", "abandoned"];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             GameStatus::Pending => "pending",
@@ -94,6 +97,44 @@ impl FromStr for GameResult {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PeerEventType {
+    HandshakeSuccess,
+    HandshakeFailure,
+    ProtocolViolation,
+    DosEvent,
+}
+
+impl PeerEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PeerEventType::HandshakeSuccess => "handshake_success",
+            PeerEventType::HandshakeFailure => "handshake_failure",
+            PeerEventType::ProtocolViolation => "protocol_violation",
+            PeerEventType::DosEvent => "dos_event",
+        }
+    }
+
+    /// Whether the event counts against the peer's reputation
+    pub fn is_negative(&self) -> bool {
+        !matches!(self, PeerEventType::HandshakeSuccess)
+    }
+}
+
+impl FromStr for PeerEventType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "handshake_success" => Ok(PeerEventType::HandshakeSuccess),
+            "handshake_failure" => Ok(PeerEventType::HandshakeFailure),
+            "protocol_violation" => Ok(PeerEventType::ProtocolViolation),
+            "dos_event" => Ok(PeerEventType::DosEvent),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Game {
     pub id: String,
@@ -107,6 +148,21 @@ pub struct Game {
     pub metadata: Option<serde_json::Value>,
 }
 
+impl Game {
+    /// The outcome as a PGN result token ("1-0", "0-1", "1/2-1/2", or "*"
+    /// while undecided)
+    pub fn pgn_result(&self) -> &'static str {
+        match (&self.result, &self.my_color) {
+            (Some(GameResult::Win), PlayerColor::White) => "1-0",
+            (Some(GameResult::Win), PlayerColor::Black) => "0-1",
+            (Some(GameResult::Loss), PlayerColor::White) => "0-1",
+            (Some(GameResult::Loss), PlayerColor::Black) => "1-0",
+            (Some(GameResult::Draw), _) => "1/2-1/2",
+            _ => "*",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub id: Option<i64>, // Auto-increment from database
@@ -124,6 +180,9 @@ pub struct GameMetadata {
     pub time_control: Option<TimeControl>,
     pub rated: Option<bool>,
     pub tournament_id: Option<String>,
+    /// Odds the game is played at, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub odds: Option<crate::chess::Odds>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -131,3 +190,328 @@ pub struct TimeControl {
     pub initial_time_ms: u64,
     pub increment_ms: u64,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameNote {
+    pub game_id: String,
+    pub content: String,
+    pub updated_at: i64,
+}
+
+/// A short name the user chose for a peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerAlias {
+    pub alias: String,
+    pub peer_id: String,
+    pub created_at: i64,
+}
+
+/// When a peer was first met and when its verification code was confirmed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerVerification {
+    pub peer_id: String,
+    pub first_seen_at: i64,
+    pub verified_at: Option<i64>,
+}
+
+/// A peer identity key that was replaced, as recorded from its rotation certificate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRotation {
+    pub old_peer_id: String,
+    pub new_peer_id: String,
+    pub issued_at: i64,
+    /// The rotation certificate as JSON
+    pub certificate: String,
+    pub applied_at: i64,
+}
+
+/// A peer identity key that was revoked, as recorded from its revocation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRevocation {
+    pub peer_id: String,
+    pub issued_at: i64,
+    pub reason: String,
+    /// Key the owner says it moved to; unverified
+    pub successor_peer_id: Option<String>,
+    /// The revocation record as JSON
+    pub record: String,
+    pub received_at: i64,
+}
+
+/// A game in which our side is played by a group voting on each move
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Consultation {
+    pub game_id: String,
+    /// How long after the group's turn begins the vote closes
+    pub window_secs: i64,
+    pub created_at: i64,
+}
+
+/// The position after the first `ply` moves of a game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameCheckpoint {
+    pub game_id: String,
+    /// Number of moves the checkpoint covers
+    pub ply: u32,
+    /// The board, clocks and counters after those moves
+    pub fen: String,
+    /// Hash of the stored move messages the checkpoint covers
+    pub moves_hash: String,
+    /// Hash of the board, as recorded with each move
+    pub board_hash: String,
+    pub created_at: i64,
+}
+
+/// A voter's current proposal for the group's next move
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsultationVote {
+    pub game_id: String,
+    pub voter_peer_id: String,
+    /// The move as the voter gave it, in SAN or coordinate notation
+    pub chess_move: String,
+    pub cast_at: i64,
+}
+
+/// An annotated position or line shared with or by a peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StudyRecord {
+    pub id: String,
+    /// The peer who sent the study, or to whom it was sent
+    pub peer_id: String,
+    /// Whether we sent the study rather than received it
+    pub outgoing: bool,
+    pub title: String,
+    /// The study message as JSON
+    pub study: String,
+    pub created_at: i64,
+}
+
+/// The move sealed when a game was adjourned
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedMoveRecord {
+    pub game_id: String,
+    /// Peer ID of the player who sealed the move
+    pub sealed_by: String,
+    pub move_number: u32,
+    /// Hex-encoded commitment sent to the opponent
+    pub commitment: String,
+    /// Our own sealed move, encrypted as JSON; `None` for the opponent's
+    pub sealed_move: Option<String>,
+    /// The move once revealed on resumption
+    pub revealed_move: Option<String>,
+    pub sealed_at: i64,
+    pub revealed_at: Option<i64>,
+}
+
+impl SealedMoveRecord {
+    /// Whether the game is still adjourned, waiting on the move
+    pub fn is_sealed(&self) -> bool {
+        self.revealed_at.is_none()
+    }
+}
+
+/// The presence an opponent last sent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerPresence {
+    pub peer_id: String,
+    /// `"available"`, `"busy"`, or `"away"`
+    pub status: String,
+    pub updated_at: i64,
+}
+
+/// Misbehavior reported against a peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerReport {
+    pub id: i64,
+    pub peer_id: String,
+    /// Peer who sent us the report, or `None` for our own
+    pub reporter_peer_id: Option<String>,
+    pub reason: String,
+    /// The reported messages as a JSON array
+    pub evidence: String,
+    /// Operator our own report was forwarded to, if any
+    pub forwarded_to: Option<String>,
+    pub created_at: i64,
+}
+
+/// Another installation of ours whose address book, settings, and game
+/// index are kept in step with this one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedDevice {
+    /// Peer ID of the device's key
+    pub peer_id: String,
+    /// Address its server was last reached at
+    pub address: String,
+    pub linked_at: i64,
+    pub last_synced_at: Option<i64>,
+    /// Last entry of the device's sync journal we have
+    pub received_through: u64,
+    /// Last entry of our sync journal the device has
+    pub sent_through: u64,
+}
+
+/// A game between two other players that we arbitrate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Arbitration {
+    pub game_id: String,
+    /// Peer ID of the player with the white pieces
+    pub white: String,
+    /// Peer ID of the player with the black pieces
+    pub black: String,
+    /// Moves as last reported by either player
+    pub moves: Vec<String>,
+    /// PGN result token, `*` while the game is under way
+    pub result: String,
+    pub joined_at: i64,
+    pub updated_at: i64,
+}
+
+impl Arbitration {
+    /// The longer of our moves and a player's `reported` ones, if one list
+    /// continues the other
+    ///
+    /// Either player may be a move ahead of the other, so a shorter report
+    /// that agrees with ours is not a conflict.
+    pub fn reconcile<'a>(&'a self, reported: &'a [String]) -> Option<&'a [String]> {
+        if reported.starts_with(&self.moves) {
+            Some(reported)
+        } else if self.moves.starts_with(reported) {
+            Some(&self.moves)
+        } else {
+            None
+        }
+    }
+}
+
+/// Our ruling on a player's illegal move claim
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArbitrationRuling {
+    pub game_id: String,
+    /// Peer ID of the player who made the claim
+    pub claimant: String,
+    /// Half-move the claim is about, counting from 1
+    pub ply: u32,
+    /// The move at `ply`, if the game had reached it
+    pub chess_move: Option<String>,
+    /// Whether the move was found illegal
+    pub upheld: bool,
+    pub reason: String,
+    pub ruled_at: i64,
+}
+
+/// The latest change to a synced address book entry, setting, or game index
+/// entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncEntry {
+    /// `"alias"`, `"setting"`, or `"game"`
+    pub kind: String,
+    pub key: String,
+    /// `None` once the entry was removed
+    pub value: Option<String>,
+    /// When the change was made on the device that made it
+    pub updated_at: i64,
+    /// Position in this device's sync journal
+    pub seq: u64,
+}
+
+/// What linked devices learn about a game, stored as a sync entry's value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameIndexEntry {
+    /// Peer ID of the device the game is played on
+    pub device: String,
+    pub opponent: String,
+    pub my_color: String,
+    pub status: String,
+    pub result: Option<String>,
+    pub created_at: i64,
+}
+
+/// A notice from the operator of a server we connected to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Announcement {
+    pub id: i64,
+    /// The server that passed the announcement on
+    pub server_peer_id: String,
+    pub text: String,
+    pub received_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerEvent {
+    pub id: Option<i64>, // Auto-increment from database
+    pub peer_id: String,
+    pub event_type: PeerEventType,
+    pub details: Option<String>,
+    pub remote_addr: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerSummary {
+    pub peer_id: String,
+    pub handshake_successes: u32,
+    pub handshake_failures: u32,
+    pub protocol_violations: u32,
+    pub dos_events: u32,
+    pub first_seen: Option<i64>,
+    pub last_seen: Option<i64>,
+}
+
+impl PeerSummary {
+    /// Total number of events recorded for the peer
+    pub fn total_events(&self) -> u32 {
+        self.handshake_successes
+            + self.handshake_failures
+            + self.protocol_violations
+            + self.dos_events
+    }
+
+    /// Reputation score from 0 (hostile) to 100 (clean history)
+    ///
+    /// Successful handshakes build trust slowly while failures, protocol
+    /// violations, and DoS events are weighted progressively more heavily.
+    pub fn reputation_score(&self) -> u32 {
+        let penalty =
+            self.handshake_failures * 5 + self.protocol_violations * 10 + self.dos_events * 20;
+        let credit = self.handshake_successes.min(50);
+        (50 + credit).saturating_sub(penalty).min(100)
+    }
+}
+
+/// A game flagged as needing resynchronization with the opponent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSyncIssue {
+    pub game_id: String,
+    pub reason: String,
+    pub detected_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionAnalysis {
+    /// Zobrist hash of the analyzed position
+    pub position_hash: u64,
+    pub engine: String,
+    pub fen: String,
+    pub depth: u32,
+    /// Evaluation in centipawns from White's point of view
+    pub score_cp: Option<i32>,
+    /// Forced mate distance in moves (negative when Black mates)
+    pub mate_in: Option<i32>,
+    pub best_move: Option<String>,
+    /// Principal variation as space-separated moves
+    pub pv: Option<String>,
+    pub created_at: i64,
+}
+
+impl PositionAnalysis {
+    /// Format the evaluation for a PGN `[%eval ...]` command
+    ///
+    /// Returns `None` when the analysis carries neither a score nor a mate.
+    pub fn pgn_eval(&self) -> Option<String> {
+        match (self.mate_in, self.score_cp) {
+            (Some(mate), _) => Some(format!("#{mate}")),
+            (None, Some(cp)) => Some(format!("{:.2}", f64::from(cp) / 100.0)),
+            (None, None) => None,
+        }
+    }
+}
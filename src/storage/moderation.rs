@@ -0,0 +1,148 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::PeerReport;
+use rusqlite::{named_params, Row};
+
+impl Database {
+    /// Record a report against a peer
+    ///
+    /// `reporter_peer_id` is `None` for the user's own reports, and
+    /// `evidence` is the reported messages as a JSON array.
+    pub fn record_peer_report(
+        &self,
+        peer_id: &str,
+        reporter_peer_id: Option<&str>,
+        reason: &str,
+        evidence: &str,
+    ) -> Result<PeerReport> {
+        let peer_id = peer_id.trim();
+        if peer_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+        if reason.trim().is_empty() {
+            return Err(StorageError::invalid_data(
+                "reason",
+                "A report needs a reason",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO peer_reports (peer_id, reporter_peer_id, reason, evidence, created_at)
+                VALUES (:peer_id, :reporter_peer_id, :reason, :evidence, :created_at)
+                "#,
+                named_params! {
+                    ":peer_id": peer_id,
+                    ":reporter_peer_id": reporter_peer_id,
+                    ":reason": reason,
+                    ":evidence": evidence,
+                    ":created_at": now,
+                },
+            )?;
+            Ok(PeerReport {
+                id: conn.last_insert_rowid(),
+                peer_id: peer_id.to_string(),
+                reporter_peer_id: reporter_peer_id.map(str::to_string),
+                reason: reason.to_string(),
+                evidence: evidence.to_string(),
+                forwarded_to: None,
+                created_at: now,
+            })
+        })
+    }
+
+    /// Note that one of our reports was passed on to an operator
+    pub fn mark_report_forwarded(&self, report_id: i64, operator: &str) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE peer_reports SET forwarded_to = ?1 WHERE id = ?2",
+                (operator, report_id),
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Reports against a peer, newest first
+    pub fn get_peer_reports(&self, peer_id: &str) -> Result<Vec<PeerReport>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, peer_id, reporter_peer_id, reason, evidence, forwarded_to, created_at
+                FROM peer_reports
+                WHERE peer_id = ?1
+                ORDER BY created_at DESC, id DESC
+                "#,
+            )?;
+            let report_iter = stmt.query_map([peer_id], report_from_row)?;
+            let reports = report_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(reports)
+        })
+    }
+
+    /// Drop chat from a peer, returning false if it was already muted
+    pub fn mute_peer(&self, peer_id: &str) -> Result<bool> {
+        let peer_id = peer_id.trim();
+        if peer_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO muted_peers (peer_id, muted_at) VALUES (?1, ?2)",
+                (peer_id, now),
+            )?;
+            Ok(inserted > 0)
+        })
+    }
+
+    /// Show chat from a peer again, returning false if it was not muted
+    pub fn unmute_peer(&self, peer_id: &str) -> Result<bool> {
+        self.with_connection(|conn| {
+            let deleted = conn.execute("DELETE FROM muted_peers WHERE peer_id = ?1", [peer_id])?;
+            Ok(deleted > 0)
+        })
+    }
+
+    /// Whether chat from a peer is dropped
+    pub fn is_peer_muted(&self, peer_id: &str) -> Result<bool> {
+        self.with_connection(|conn| {
+            let muted = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM muted_peers WHERE peer_id = ?1)",
+                [peer_id],
+                |row| row.get(0),
+            )?;
+            Ok(muted)
+        })
+    }
+
+    /// Every muted peer in alphabetical order
+    pub fn get_muted_peers(&self) -> Result<Vec<String>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare("SELECT peer_id FROM muted_peers ORDER BY peer_id")?;
+            let peer_iter = stmt.query_map([], |row| row.get(0))?;
+            let peers = peer_iter.collect::<std::result::Result<Vec<String>, _>>()?;
+            Ok(peers)
+        })
+    }
+}
+
+fn report_from_row(row: &Row) -> rusqlite::Result<PeerReport> {
+    Ok(PeerReport {
+        id: row.get(0)?,
+        peer_id: row.get(1)?,
+        reporter_peer_id: row.get(2)?,
+        reason: row.get(3)?,
+        evidence: row.get(4)?,
+        forwarded_to: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
@@ -0,0 +1,89 @@
+use crate::storage::database::Database;
+use crate::storage::errors::Result;
+use crate::storage::models::PlayerColor;
+use rusqlite::{named_params, Row};
+
+/// Games that began with the same moves, and how they ended for me
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpeningLineStats {
+    /// First moves as stored, in the order they were played
+    pub moves: Vec<String>,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Games following this line, most recently updated first
+    pub game_ids: Vec<String>,
+}
+
+impl Database {
+    /// Group games by their first `plies` half-moves and total up my results
+    ///
+    /// Games shorter than `plies` are grouped by the moves they have; games
+    /// without moves are left out. `color` limits the games to those where I
+    /// played that side. Lines are ordered by how many games followed them.
+    pub fn opening_lines(
+        &self,
+        plies: u32,
+        color: Option<PlayerColor>,
+    ) -> Result<Vec<OpeningLineStats>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                WITH moves AS (
+                    SELECT game_id,
+                           json_extract(content, '$.chess_move') AS chess_move,
+                           ROW_NUMBER() OVER (PARTITION BY game_id ORDER BY created_at, id) AS ply
+                    FROM messages
+                    WHERE lower(message_type) = 'move' AND json_valid(content)
+                ),
+                lines AS (
+                    SELECT game_id, json_group_array(chess_move ORDER BY ply) AS line
+                    FROM moves
+                    WHERE ply <= :plies AND chess_move IS NOT NULL
+                    GROUP BY game_id
+                )
+                SELECT lines.line,
+                       COUNT(*),
+                       COUNT(*) FILTER (WHERE games.result = 'win'),
+                       COUNT(*) FILTER (WHERE games.result = 'draw'),
+                       COUNT(*) FILTER (WHERE games.result = 'loss'),
+                       json_group_array(games.id ORDER BY games.updated_at DESC, games.id)
+                FROM lines
+                JOIN games ON games.id = lines.game_id
+                WHERE (:color IS NULL OR games.my_color = :color)
+                GROUP BY lines.line
+                ORDER BY COUNT(*) DESC, lines.line
+                "#,
+            )?;
+
+            let line_iter = stmt.query_map(
+                named_params! {
+                    ":plies": plies,
+                    ":color": color.map(|c| c.as_str()),
+                },
+                line_from_row,
+            )?;
+            let lines = line_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(lines)
+        })
+    }
+}
+
+fn line_from_row(row: &Row) -> rusqlite::Result<OpeningLineStats> {
+    Ok(OpeningLineStats {
+        moves: json_strings(row, 0)?,
+        games: row.get(1)?,
+        wins: row.get(2)?,
+        draws: row.get(3)?,
+        losses: row.get(4)?,
+        game_ids: json_strings(row, 5)?,
+    })
+}
+
+fn json_strings(row: &Row, index: usize) -> rusqlite::Result<Vec<String>> {
+    let json: String = row.get(index)?;
+    serde_json::from_str(&json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
@@ -0,0 +1,173 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::{PeerEvent, PeerEventType, PeerSummary};
+use rusqlite::{named_params, Row};
+
+impl Database {
+    /// Record a connection or security event for a peer
+    pub fn record_peer_event(
+        &self,
+        peer_id: &str,
+        event_type: PeerEventType,
+        details: Option<String>,
+        remote_addr: Option<String>,
+    ) -> Result<PeerEvent> {
+        if peer_id.trim().is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO peer_history (peer_id, event_type, details, remote_addr, created_at)
+                VALUES (:peer_id, :event_type, :details, :remote_addr, :created_at)
+                "#,
+                named_params! {
+                    ":peer_id": peer_id,
+                    ":event_type": event_type.as_str(),
+                    ":details": details,
+                    ":remote_addr": remote_addr,
+                    ":created_at": now,
+                },
+            )?;
+
+            Ok(PeerEvent {
+                id: Some(conn.last_insert_rowid()),
+                peer_id: peer_id.to_string(),
+                event_type,
+                details,
+                remote_addr,
+                created_at: now,
+            })
+        })
+    }
+
+    /// Get the most recent events for a peer (newest first)
+    pub fn get_peer_events(&self, peer_id: &str, limit: u32) -> Result<Vec<PeerEvent>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, peer_id, event_type, details, remote_addr, created_at
+                FROM peer_history
+                WHERE peer_id = ?1
+                ORDER BY created_at DESC, id DESC
+                LIMIT ?2
+                "#,
+            )?;
+
+            let event_iter = stmt.query_map((peer_id, limit), peer_event_from_row)?;
+            let events = event_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(events)
+        })
+    }
+
+    /// Get a peer's events from `since` up to `until` (or now), oldest first
+    pub fn get_peer_events_between(
+        &self,
+        peer_id: &str,
+        since: i64,
+        until: Option<i64>,
+    ) -> Result<Vec<PeerEvent>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, peer_id, event_type, details, remote_addr, created_at
+                FROM peer_history
+                WHERE peer_id = :peer_id
+                  AND created_at >= :since
+                  AND (:until IS NULL OR created_at <= :until)
+                ORDER BY created_at, id
+                "#,
+            )?;
+
+            let event_iter = stmt.query_map(
+                named_params! {
+                    ":peer_id": peer_id,
+                    ":since": since,
+                    ":until": until,
+                },
+                peer_event_from_row,
+            )?;
+            let events = event_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(events)
+        })
+    }
+
+    /// Get aggregated history for a single peer
+    pub fn get_peer_summary(&self, peer_id: &str) -> Result<PeerSummary> {
+        self.with_connection(|conn| {
+            conn.query_row(
+                &format!("{SUMMARY_SELECT} WHERE peer_id = ?1 GROUP BY peer_id"),
+                [peer_id],
+                peer_summary_from_row,
+            )
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(PeerSummary {
+                    peer_id: peer_id.to_string(),
+                    ..Default::default()
+                }),
+                _ => Err(StorageError::ConnectionFailed(e)),
+            })
+        })
+    }
+
+    /// Get aggregated history for every known peer (most recently seen first)
+    pub fn get_all_peer_summaries(&self) -> Result<Vec<PeerSummary>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "{SUMMARY_SELECT} GROUP BY peer_id ORDER BY MAX(created_at) DESC"
+            ))?;
+
+            let summary_iter = stmt.query_map([], peer_summary_from_row)?;
+            let summaries = summary_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(summaries)
+        })
+    }
+}
+
+/// Aggregation shared by the per-peer and all-peer summary queries
+const SUMMARY_SELECT: &str = r#"
+    SELECT peer_id,
+           SUM(event_type = 'handshake_success') AS handshake_successes,
+           SUM(event_type = 'handshake_failure') AS handshake_failures,
+           SUM(event_type = 'protocol_violation') AS protocol_violations,
+           SUM(event_type = 'dos_event') AS dos_events,
+           MIN(created_at) AS first_seen,
+           MAX(created_at) AS last_seen
+    FROM peer_history
+"#;
+
+/// Convert a database row to a PeerEvent struct
+fn peer_event_from_row(row: &Row) -> rusqlite::Result<PeerEvent> {
+    let event_type_str: String = row.get("event_type")?;
+    let event_type = event_type_str.parse::<PeerEventType>().map_err(|_e| {
+        rusqlite::Error::InvalidColumnType(0, "event_type".to_string(), rusqlite::types::Type::Text)
+    })?;
+
+    Ok(PeerEvent {
+        id: Some(row.get("id")?),
+        peer_id: row.get("peer_id")?,
+        event_type,
+        details: row.get("details")?,
+        remote_addr: row.get("remote_addr")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Convert an aggregated row to a PeerSummary struct
+fn peer_summary_from_row(row: &Row) -> rusqlite::Result<PeerSummary> {
+    Ok(PeerSummary {
+        peer_id: row.get("peer_id")?,
+        handshake_successes: row.get("handshake_successes")?,
+        handshake_failures: row.get("handshake_failures")?,
+        protocol_violations: row.get("protocol_violations")?,
+        dos_events: row.get("dos_events")?,
+        first_seen: row.get("first_seen")?,
+        last_seen: row.get("last_seen")?,
+    })
+}
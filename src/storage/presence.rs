@@ -0,0 +1,51 @@
+use crate::storage::database::Database;
+use crate::storage::errors::Result;
+use crate::storage::models::PeerPresence;
+use rusqlite::{named_params, OptionalExtension, Row};
+
+impl Database {
+    /// Record the presence a peer just sent, replacing the last one
+    pub fn set_peer_presence(&self, peer_id: &str, status: &str) -> Result<()> {
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO peer_presence (peer_id, status, updated_at)
+                VALUES (:peer_id, :status, :updated_at)
+                ON CONFLICT(peer_id) DO UPDATE SET
+                    status = excluded.status,
+                    updated_at = excluded.updated_at
+                "#,
+                named_params! {
+                    ":peer_id": peer_id,
+                    ":status": status,
+                    ":updated_at": now,
+                },
+            )?;
+            Ok(())
+        })
+    }
+
+    /// The presence a peer last sent, if any
+    pub fn get_peer_presence(&self, peer_id: &str) -> Result<Option<PeerPresence>> {
+        self.with_connection(|conn| {
+            let presence = conn
+                .query_row(
+                    "SELECT peer_id, status, updated_at FROM peer_presence WHERE peer_id = ?1",
+                    [peer_id],
+                    presence_from_row,
+                )
+                .optional()?;
+            Ok(presence)
+        })
+    }
+}
+
+fn presence_from_row(row: &Row) -> rusqlite::Result<PeerPresence> {
+    Ok(PeerPresence {
+        peer_id: row.get(0)?,
+        status: row.get(1)?,
+        updated_at: row.get(2)?,
+    })
+}
@@ -0,0 +1,293 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use rusqlite::{named_params, Connection};
+
+/// Selects the data removed by a purge
+///
+/// When both fields are set only data matching both is removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PurgeFilter {
+    /// Remove games played against this peer and everything else kept about
+    /// it. When `before` is also set, what names or vouches for the peer,
+    /// such as its aliases and key rotations, is kept
+    pub peer_id: Option<String>,
+    /// Remove games and the dated records about peers, such as connection
+    /// history, created before this Unix timestamp
+    pub before: Option<i64>,
+}
+
+/// Counts of rows removed (or that would be removed) by a purge
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PurgeSummary {
+    pub games: usize,
+    pub messages: usize,
+    pub tags: usize,
+    pub notes: usize,
+    pub peer_events: usize,
+    pub aliases: usize,
+    pub key_rotations: usize,
+    pub verifications: usize,
+    pub revocations: usize,
+    pub studies: usize,
+    pub announcements: usize,
+    pub presence: usize,
+    pub reports: usize,
+    pub mutes: usize,
+    pub linked_devices: usize,
+    pub arbitrations: usize,
+    pub rulings: usize,
+    /// Addresses the peer's key is pinned at in `known_peers.json`, which is
+    /// not in the database, so the caller counts and forgets them
+    pub pins: usize,
+}
+
+impl PurgeSummary {
+    /// Total number of rows affected
+    pub fn total(&self) -> usize {
+        self.games
+            + self.messages
+            + self.tags
+            + self.notes
+            + self.peer_events
+            + self.aliases
+            + self.key_rotations
+            + self.verifications
+            + self.revocations
+            + self.studies
+            + self.announcements
+            + self.presence
+            + self.reports
+            + self.mutes
+            + self.linked_devices
+            + self.arbitrations
+            + self.rulings
+            + self.pins
+    }
+
+    /// Number of rows kept about peers rather than games
+    pub fn peer_records(&self) -> usize {
+        self.total() - self.games - self.messages - self.tags - self.notes
+    }
+}
+
+/// Games matching a purge filter
+const GAMES_WHERE: &str = "(:peer_id IS NULL OR opponent_peer_id = :peer_id) \
+     AND (:before IS NULL OR created_at < :before)";
+
+/// Peer history and studies matching a purge filter
+const PEER_HISTORY_WHERE: &str = "(:peer_id IS NULL OR peer_id = :peer_id) \
+     AND (:before IS NULL OR created_at < :before)";
+
+/// Aliases matching a purge filter; a date-limited purge keeps the peer's aliases
+const ALIASES_WHERE: &str = ":before IS NULL AND peer_id = :peer_id";
+
+/// Key rotations from or to the peer; a date-limited purge keeps them
+const KEY_ROTATIONS_WHERE: &str = ":before IS NULL AND :peer_id IN (old_peer_id, new_peer_id)";
+
+/// Verification status of the peer; a date-limited purge keeps it
+const VERIFICATIONS_WHERE: &str = ":before IS NULL AND peer_id = :peer_id";
+
+/// Revocation of the peer's key; a date-limited purge keeps it
+const REVOCATIONS_WHERE: &str = ":before IS NULL AND peer_id = :peer_id";
+
+/// Announcements from a server matching a purge filter
+const ANNOUNCEMENTS_WHERE: &str = "(:peer_id IS NULL OR server_peer_id = :peer_id) \
+     AND (:before IS NULL OR received_at < :before)";
+
+/// Last presence of a peer matching a purge filter
+const PRESENCE_WHERE: &str = "(:peer_id IS NULL OR peer_id = :peer_id) \
+     AND (:before IS NULL OR updated_at < :before)";
+
+/// Reports by or about a peer matching a purge filter, evidence included
+const REPORTS_WHERE: &str = "(:peer_id IS NULL OR :peer_id IN (peer_id, reporter_peer_id)) \
+     AND (:before IS NULL OR created_at < :before)";
+
+/// Whether the peer is muted; a date-limited purge keeps it
+const MUTES_WHERE: &str = ":before IS NULL AND peer_id = :peer_id";
+
+/// Link to the peer as one of our devices; a date-limited purge keeps it
+const LINKED_DEVICES_WHERE: &str = ":before IS NULL AND peer_id = :peer_id";
+
+/// Games arbitrated for a peer matching a purge filter; our rulings in them
+/// are removed along with them
+const ARBITRATIONS_WHERE: &str = "(:peer_id IS NULL OR :peer_id IN (white, black)) \
+     AND (:before IS NULL OR joined_at < :before)";
+
+impl Database {
+    /// Remove all stored data associated with a peer and/or period
+    ///
+    /// Everything happens in a single transaction. Messages, tags, and notes
+    /// belonging to purged games are removed along with them. With `dry_run`
+    /// set, nothing is deleted and the summary reports what would be.
+    pub fn purge(&self, filter: &PurgeFilter, dry_run: bool) -> Result<PurgeSummary> {
+        if filter.peer_id.is_none() && filter.before.is_none() {
+            return Err(StorageError::invalid_data(
+                "filter",
+                "A peer or a cutoff date is required",
+            ));
+        }
+
+        self.transaction(|tx| {
+            let conn = tx.connection();
+            let summary = PurgeSummary {
+                games: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM games WHERE {GAMES_WHERE}"),
+                    filter,
+                )?,
+                messages: count_game_children(conn, "messages", filter)?,
+                tags: count_game_children(conn, "game_tags", filter)?,
+                notes: count_game_children(conn, "game_notes", filter)?,
+                peer_events: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM peer_history WHERE {PEER_HISTORY_WHERE}"),
+                    filter,
+                )?,
+                aliases: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM peer_aliases WHERE {ALIASES_WHERE}"),
+                    filter,
+                )?,
+                key_rotations: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM key_rotations WHERE {KEY_ROTATIONS_WHERE}"),
+                    filter,
+                )?,
+                verifications: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM peer_verifications WHERE {VERIFICATIONS_WHERE}"),
+                    filter,
+                )?,
+                revocations: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM key_revocations WHERE {REVOCATIONS_WHERE}"),
+                    filter,
+                )?,
+                studies: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM studies WHERE {PEER_HISTORY_WHERE}"),
+                    filter,
+                )?,
+                announcements: count(
+                    conn,
+                    &format!(
+                        "SELECT COUNT(*) FROM server_announcements WHERE {ANNOUNCEMENTS_WHERE}"
+                    ),
+                    filter,
+                )?,
+                presence: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM peer_presence WHERE {PRESENCE_WHERE}"),
+                    filter,
+                )?,
+                reports: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM peer_reports WHERE {REPORTS_WHERE}"),
+                    filter,
+                )?,
+                mutes: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM muted_peers WHERE {MUTES_WHERE}"),
+                    filter,
+                )?,
+                linked_devices: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM linked_devices WHERE {LINKED_DEVICES_WHERE}"),
+                    filter,
+                )?,
+                arbitrations: count(
+                    conn,
+                    &format!("SELECT COUNT(*) FROM arbitrations WHERE {ARBITRATIONS_WHERE}"),
+                    filter,
+                )?,
+                rulings: count(
+                    conn,
+                    &format!(
+                        "SELECT COUNT(*) FROM arbiter_rulings WHERE game_id IN \
+                         (SELECT game_id FROM arbitrations WHERE {ARBITRATIONS_WHERE})"
+                    ),
+                    filter,
+                )?,
+                pins: 0,
+            };
+
+            if !dry_run {
+                conn.execute(
+                    &format!("DELETE FROM games WHERE {GAMES_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM peer_history WHERE {PEER_HISTORY_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM peer_aliases WHERE {ALIASES_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM key_rotations WHERE {KEY_ROTATIONS_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM peer_verifications WHERE {VERIFICATIONS_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM key_revocations WHERE {REVOCATIONS_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM studies WHERE {PEER_HISTORY_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM server_announcements WHERE {ANNOUNCEMENTS_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM peer_presence WHERE {PRESENCE_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM peer_reports WHERE {REPORTS_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM muted_peers WHERE {MUTES_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM linked_devices WHERE {LINKED_DEVICES_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+                conn.execute(
+                    &format!("DELETE FROM arbitrations WHERE {ARBITRATIONS_WHERE}"),
+                    named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+                )?;
+            }
+
+            Ok(summary)
+        })
+    }
+}
+
+/// Run a COUNT(*) query bound to the purge filter
+fn count(conn: &Connection, sql: &str, filter: &PurgeFilter) -> Result<usize> {
+    let count: i64 = conn.query_row(
+        sql,
+        named_params! { ":peer_id": filter.peer_id, ":before": filter.before },
+        |row| row.get(0),
+    )?;
+    Ok(count as usize)
+}
+
+/// Count rows of a game-owned table that belong to purged games
+fn count_game_children(conn: &Connection, table: &str, filter: &PurgeFilter) -> Result<usize> {
+    count(
+        conn,
+        &format!(
+            "SELECT COUNT(*) FROM {table} WHERE game_id IN (SELECT id FROM games WHERE {GAMES_WHERE})"
+        ),
+        filter,
+    )
+}
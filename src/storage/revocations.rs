@@ -0,0 +1,98 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::KeyRevocation;
+use rusqlite::{named_params, OptionalExtension, Row};
+
+impl Database {
+    /// Record that a peer's key was revoked
+    ///
+    /// The record must have been verified by the caller; it is stored as
+    /// given. Returns false if the key was already revoked, in which case the
+    /// first record is kept.
+    pub fn record_key_revocation(
+        &self,
+        peer_id: &str,
+        issued_at: i64,
+        reason: &str,
+        successor_peer_id: Option<&str>,
+        record: &str,
+    ) -> Result<bool> {
+        if peer_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let inserted = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO key_revocations
+                    (peer_id, issued_at, reason, successor_peer_id, record, received_at)
+                VALUES (:peer_id, :issued_at, :reason, :successor, :record, :received_at)
+                "#,
+                named_params! {
+                    ":peer_id": peer_id,
+                    ":issued_at": issued_at,
+                    ":reason": reason,
+                    ":successor": successor_peer_id,
+                    ":record": record,
+                    ":received_at": now,
+                },
+            )?;
+            Ok(inserted > 0)
+        })
+    }
+
+    /// The revocation of a peer's key, if it was revoked
+    pub fn get_key_revocation(&self, peer_id: &str) -> Result<Option<KeyRevocation>> {
+        self.with_connection(|conn| {
+            let revocation = conn
+                .query_row(
+                    r#"
+                    SELECT peer_id, issued_at, reason, successor_peer_id, record, received_at
+                    FROM key_revocations
+                    WHERE peer_id = ?1
+                    "#,
+                    [peer_id],
+                    revocation_from_row,
+                )
+                .optional()?;
+            Ok(revocation)
+        })
+    }
+
+    /// Whether a peer's key was revoked
+    pub fn is_key_revoked(&self, peer_id: &str) -> Result<bool> {
+        Ok(self.get_key_revocation(peer_id)?.is_some())
+    }
+
+    /// Get every revoked key, most recently received first
+    pub fn get_key_revocations(&self) -> Result<Vec<KeyRevocation>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT peer_id, issued_at, reason, successor_peer_id, record, received_at
+                FROM key_revocations
+                ORDER BY received_at DESC, issued_at DESC
+                "#,
+            )?;
+
+            let revocation_iter = stmt.query_map([], revocation_from_row)?;
+            let revocations = revocation_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(revocations)
+        })
+    }
+}
+
+fn revocation_from_row(row: &Row) -> rusqlite::Result<KeyRevocation> {
+    Ok(KeyRevocation {
+        peer_id: row.get(0)?,
+        issued_at: row.get(1)?,
+        reason: row.get(2)?,
+        successor_peer_id: row.get(3)?,
+        record: row.get(4)?,
+        received_at: row.get(5)?,
+    })
+}
@@ -0,0 +1,151 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::KeyRotation;
+use rusqlite::{named_params, OptionalExtension, Row};
+
+/// Counts of rows moved from a peer's old key to its new one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RotationSummary {
+    pub games: usize,
+    pub messages: usize,
+    pub peer_events: usize,
+    pub aliases: usize,
+    /// The rotation had already been applied, so nothing changed
+    pub already_applied: bool,
+}
+
+impl RotationSummary {
+    /// Total number of rows moved
+    pub fn total(&self) -> usize {
+        self.games + self.messages + self.peer_events + self.aliases
+    }
+}
+
+impl Database {
+    /// Record that a peer replaced `old_peer_id` with `new_peer_id` and move
+    /// its games, messages, connection history, aliases, and verification
+    /// status to the new key
+    ///
+    /// The certificate must have been verified by the caller; it is stored as
+    /// given. Everything happens in a single transaction. Applying the same
+    /// rotation twice is a no-op, while rotating one key to two different
+    /// keys is rejected.
+    pub fn apply_key_rotation(
+        &self,
+        old_peer_id: &str,
+        new_peer_id: &str,
+        issued_at: i64,
+        certificate: &str,
+    ) -> Result<RotationSummary> {
+        if old_peer_id.is_empty() || new_peer_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+        if old_peer_id == new_peer_id {
+            return Err(StorageError::invalid_data(
+                "new_peer_id",
+                "A key cannot be rotated to itself",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.transaction(|tx| {
+            let conn = tx.connection();
+            let existing: Option<String> = conn
+                .query_row(
+                    "SELECT new_peer_id FROM key_rotations WHERE old_peer_id = ?1",
+                    [old_peer_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match existing {
+                Some(existing) if existing == new_peer_id => {
+                    return Ok(RotationSummary {
+                        already_applied: true,
+                        ..RotationSummary::default()
+                    });
+                }
+                Some(existing) => {
+                    return Err(StorageError::invalid_data(
+                        "old_peer_id",
+                        format!("This key was already rotated to {existing}"),
+                    ));
+                }
+                None => {}
+            }
+
+            let params = named_params! { ":old": old_peer_id, ":new": new_peer_id };
+            let summary = RotationSummary {
+                games: conn.execute(
+                    "UPDATE games SET opponent_peer_id = :new WHERE opponent_peer_id = :old",
+                    params,
+                )?,
+                messages: conn.execute(
+                    "UPDATE messages SET sender_peer_id = :new WHERE sender_peer_id = :old",
+                    params,
+                )?,
+                peer_events: conn.execute(
+                    "UPDATE peer_history SET peer_id = :new WHERE peer_id = :old",
+                    params,
+                )?,
+                aliases: conn.execute(
+                    "UPDATE peer_aliases SET peer_id = :new WHERE peer_id = :old",
+                    params,
+                )?,
+                already_applied: false,
+            };
+            // Whether the peer was verified carries over, unless the new key
+            // already has a record of its own
+            conn.execute(
+                "UPDATE OR IGNORE peer_verifications SET peer_id = :new WHERE peer_id = :old",
+                params,
+            )?;
+
+            conn.execute(
+                r#"
+                INSERT INTO key_rotations
+                    (old_peer_id, new_peer_id, issued_at, certificate, applied_at)
+                VALUES (:old, :new, :issued_at, :certificate, :applied_at)
+                "#,
+                named_params! {
+                    ":old": old_peer_id,
+                    ":new": new_peer_id,
+                    ":issued_at": issued_at,
+                    ":certificate": certificate,
+                    ":applied_at": now,
+                },
+            )?;
+
+            Ok(summary)
+        })
+    }
+
+    /// Get every applied key rotation, most recent first
+    pub fn get_key_rotations(&self) -> Result<Vec<KeyRotation>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT old_peer_id, new_peer_id, issued_at, certificate, applied_at
+                FROM key_rotations
+                ORDER BY applied_at DESC, issued_at DESC
+                "#,
+            )?;
+
+            let rotation_iter = stmt.query_map([], rotation_from_row)?;
+            let rotations = rotation_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rotations)
+        })
+    }
+}
+
+fn rotation_from_row(row: &Row) -> rusqlite::Result<KeyRotation> {
+    Ok(KeyRotation {
+        old_peer_id: row.get(0)?,
+        new_peer_id: row.get(1)?,
+        issued_at: row.get(2)?,
+        certificate: row.get(3)?,
+        applied_at: row.get(4)?,
+    })
+}
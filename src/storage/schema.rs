@@ -1,7 +1,8 @@
 use crate::storage::errors::{Result, StorageError};
 use rusqlite::Connection;
 
-pub const CURRENT_SCHEMA_VERSION: i32 = 1;
+/// Version of the newest migration, which every opened database is brought to
+pub const CURRENT_SCHEMA_VERSION: i32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
 
 /// Migration represents a single database migration
 pub struct Migration {
@@ -11,10 +12,11 @@ pub struct Migration {
 }
 
 /// All database migrations in order
-pub const MIGRATIONS: &[Migration] = &[Migration {
-    version: 1,
-    description: "Initial schema with games and messages tables",
-    sql: r#"
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema with games and messages tables",
+        sql: r#"
             -- Games table
             CREATE TABLE games (
                 id TEXT PRIMARY KEY,
@@ -55,7 +57,371 @@ pub const MIGRATIONS: &[Migration] = &[Migration {
             CREATE INDEX idx_messages_type ON messages(message_type);
             CREATE INDEX idx_messages_sender ON messages(sender_peer_id);
         "#,
-}];
+    },
+    Migration {
+        version: 2,
+        description: "Add game tags and notes",
+        sql: r#"
+            -- Tags attached to games (many per game)
+            CREATE TABLE game_tags (
+                game_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (game_id, tag),
+                FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+            );
+
+            -- Free-form notes (one per game)
+            CREATE TABLE game_notes (
+                game_id TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX idx_game_tags_tag ON game_tags(tag);
+        "#,
+    },
+    Migration {
+        version: 3,
+        description: "Add indices for large game and message histories",
+        sql: r#"
+            -- Game listings ordered by last activity
+            CREATE INDEX idx_games_updated ON games(updated_at DESC);
+
+            -- Status and opponent filters return rows ordered by creation time
+            DROP INDEX idx_games_status;
+            CREATE INDEX idx_games_status_created ON games(status, created_at DESC);
+            DROP INDEX idx_games_opponent;
+            CREATE INDEX idx_games_opponent_created ON games(opponent_peer_id, created_at DESC);
+
+            -- Per-game message lookups by type (e.g. move history)
+            CREATE INDEX idx_messages_game_type ON messages(game_id, message_type, created_at);
+
+            -- Recent messages across all games
+            CREATE INDEX idx_messages_created ON messages(created_at DESC);
+        "#,
+    },
+    Migration {
+        version: 4,
+        description: "Add per-peer connection history",
+        sql: r#"
+            -- Connection and security events observed for each peer
+            CREATE TABLE peer_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                peer_id TEXT NOT NULL,
+                event_type TEXT NOT NULL CHECK(event_type IN (
+                    'handshake_success', 'handshake_failure', 'protocol_violation', 'dos_event'
+                )),
+                details TEXT,
+                remote_addr TEXT,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX idx_peer_history_peer ON peer_history(peer_id, created_at DESC);
+        "#,
+    },
+    Migration {
+        version: 5,
+        description: "Add cached engine analysis keyed by position",
+        sql: r#"
+            -- Engine evaluations keyed by Zobrist hash, one row per engine
+            CREATE TABLE analysis (
+                position_hash INTEGER NOT NULL,
+                engine TEXT NOT NULL,
+                fen TEXT NOT NULL,
+                depth INTEGER NOT NULL CHECK(depth >= 0),
+                score_cp INTEGER,
+                mate_in INTEGER,
+                best_move TEXT,
+                pv TEXT,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (position_hash, engine)
+            );
+        "#,
+    },
+    Migration {
+        version: 6,
+        description: "Add sync flags for games with inconsistent stored state",
+        sql: r#"
+            -- Games whose move list failed the startup consistency check
+            CREATE TABLE game_sync_issues (
+                game_id TEXT PRIMARY KEY,
+                reason TEXT NOT NULL,
+                detected_at INTEGER NOT NULL,
+                FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    Migration {
+        version: 7,
+        description: "Add user-chosen aliases for peers",
+        sql: r#"
+            -- Short names used in place of peer IDs, e.g. 'mate move e4 --game alice'
+            CREATE TABLE peer_aliases (
+                alias TEXT PRIMARY KEY,
+                peer_id TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX idx_peer_aliases_peer ON peer_aliases(peer_id);
+        "#,
+    },
+    Migration {
+        version: 8,
+        description: "Add applied peer key rotations",
+        sql: r#"
+            -- Rotation certificates from peers that replaced their identity key;
+            -- the certificate is kept as JSON so it can be re-verified later
+            CREATE TABLE key_rotations (
+                old_peer_id TEXT PRIMARY KEY,
+                new_peer_id TEXT NOT NULL,
+                issued_at INTEGER NOT NULL,
+                certificate TEXT NOT NULL,
+                applied_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX idx_key_rotations_new ON key_rotations(new_peer_id);
+        "#,
+    },
+    Migration {
+        version: 9,
+        description: "Add peer verification status",
+        sql: r#"
+            -- When each peer was first met and when the user confirmed its
+            -- verification code; verified_at is NULL until then
+            CREATE TABLE peer_verifications (
+                peer_id TEXT PRIMARY KEY,
+                first_seen_at INTEGER NOT NULL,
+                verified_at INTEGER
+            );
+        "#,
+    },
+    Migration {
+        version: 10,
+        description: "Add revoked peer keys",
+        sql: r#"
+            -- Revocation records from peers whose key was compromised; the
+            -- record is kept as JSON so it can be re-verified later
+            CREATE TABLE key_revocations (
+                peer_id TEXT PRIMARY KEY,
+                issued_at INTEGER NOT NULL,
+                reason TEXT NOT NULL,
+                successor_peer_id TEXT,
+                record TEXT NOT NULL,
+                received_at INTEGER NOT NULL
+            );
+        "#,
+    },
+    Migration {
+        version: 11,
+        description: "Add shared studies",
+        sql: r#"
+            -- Annotated positions and lines shared with or by peers; the
+            -- study message is kept as JSON
+            CREATE TABLE studies (
+                id TEXT PRIMARY KEY,
+                peer_id TEXT NOT NULL,
+                outgoing INTEGER NOT NULL,
+                title TEXT NOT NULL,
+                study TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX idx_studies_created_at ON studies(created_at);
+        "#,
+    },
+    Migration {
+        version: 12,
+        description: "Add consultation games voted on by a group",
+        sql: r#"
+            -- Games in which our side is played by a group voting on each move
+            CREATE TABLE consultations (
+                game_id TEXT PRIMARY KEY,
+                window_secs INTEGER NOT NULL CHECK(window_secs > 0),
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+            );
+
+            -- Peers registered to vote in a consultation game
+            CREATE TABLE consultation_voters (
+                game_id TEXT NOT NULL,
+                peer_id TEXT NOT NULL,
+                joined_at INTEGER NOT NULL,
+                PRIMARY KEY (game_id, peer_id),
+                FOREIGN KEY (game_id) REFERENCES consultations(game_id) ON DELETE CASCADE
+            );
+
+            -- Each voter's current proposal for the group's next move
+            CREATE TABLE votes (
+                game_id TEXT NOT NULL,
+                voter_peer_id TEXT NOT NULL,
+                chess_move TEXT NOT NULL,
+                cast_at INTEGER NOT NULL,
+                PRIMARY KEY (game_id, voter_peer_id),
+                FOREIGN KEY (game_id) REFERENCES consultations(game_id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    Migration {
+        version: 13,
+        description: "Add game state checkpoints",
+        sql: r#"
+            -- The position after the first moves of a game, so it can be
+            -- resumed without replaying them
+            CREATE TABLE game_checkpoints (
+                game_id TEXT PRIMARY KEY,
+                ply INTEGER NOT NULL CHECK(ply >= 0),
+                fen TEXT NOT NULL,
+                moves_hash TEXT NOT NULL,
+                board_hash TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    Migration {
+        version: 14,
+        description: "Add server announcements",
+        sql: r#"
+            -- Notices from the operators of servers we connected to
+            CREATE TABLE server_announcements (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                server_peer_id TEXT NOT NULL,
+                text TEXT NOT NULL,
+                received_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX idx_server_announcements_received_at
+                ON server_announcements(received_at);
+        "#,
+    },
+    Migration {
+        version: 15,
+        description: "Add sealed moves of adjourned games",
+        sql: r#"
+            -- The move sealed when a game was adjourned, ours or the
+            -- opponent's. Our own is kept encrypted until the game resumes;
+            -- of the opponent's only the commitment is known until revealed
+            CREATE TABLE sealed_moves (
+                game_id TEXT PRIMARY KEY,
+                sealed_by TEXT NOT NULL,
+                move_number INTEGER NOT NULL CHECK(move_number > 0),
+                commitment TEXT NOT NULL,
+                sealed_move TEXT,
+                revealed_move TEXT,
+                sealed_at INTEGER NOT NULL,
+                revealed_at INTEGER,
+                FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    Migration {
+        version: 16,
+        description: "Add opponent presence",
+        sql: r#"
+            -- The last presence each opponent sent: available, busy, or away
+            CREATE TABLE peer_presence (
+                peer_id TEXT PRIMARY KEY,
+                status TEXT NOT NULL CHECK(status IN ('available', 'busy', 'away')),
+                updated_at INTEGER NOT NULL
+            );
+        "#,
+    },
+    Migration {
+        version: 17,
+        description: "Add abuse reports and muted peers",
+        sql: r#"
+            -- Misbehavior reported by us, or sent to us as a server operator.
+            -- Evidence is the reported messages as JSON; forwarded_to is the
+            -- operator our own report was passed on to
+            CREATE TABLE peer_reports (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                peer_id TEXT NOT NULL,
+                reporter_peer_id TEXT,
+                reason TEXT NOT NULL,
+                evidence TEXT NOT NULL DEFAULT '[]',
+                forwarded_to TEXT,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX idx_peer_reports_peer ON peer_reports(peer_id);
+
+            -- Peers whose chat is dropped
+            CREATE TABLE muted_peers (
+                peer_id TEXT PRIMARY KEY,
+                muted_at INTEGER NOT NULL
+            );
+        "#,
+    },
+    Migration {
+        version: 18,
+        description: "Add linked devices and the device sync journal",
+        sql: r#"
+            -- Other installations of ours kept in step with this one.
+            -- received_through and sent_through are the last journal entries
+            -- exchanged in each direction
+            CREATE TABLE linked_devices (
+                peer_id TEXT PRIMARY KEY,
+                address TEXT NOT NULL,
+                linked_at INTEGER NOT NULL,
+                last_synced_at INTEGER,
+                received_through INTEGER NOT NULL DEFAULT 0,
+                sent_through INTEGER NOT NULL DEFAULT 0
+            );
+
+            -- One-time codes another device can pair with
+            CREATE TABLE pairing_codes (
+                code TEXT PRIMARY KEY,
+                expires_at INTEGER NOT NULL
+            );
+
+            -- The latest change to each synced entry. seq orders entries as
+            -- this device learned of them; updated_at decides between
+            -- conflicting changes. pending marks settings received from
+            -- another device but not yet written to the configuration file
+            CREATE TABLE sync_journal (
+                kind TEXT NOT NULL CHECK(kind IN ('alias', 'setting', 'game')),
+                key TEXT NOT NULL,
+                value TEXT,
+                updated_at INTEGER NOT NULL,
+                seq INTEGER NOT NULL,
+                pending INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (kind, key)
+            );
+            CREATE INDEX idx_sync_journal_seq ON sync_journal(seq);
+        "#,
+    },
+    Migration {
+        version: 19,
+        description: "Add games arbitrated for other players",
+        sql: r#"
+            -- Games between two other players that we arbitrate. moves is
+            -- the JSON move list as last reported by either player
+            CREATE TABLE arbitrations (
+                game_id TEXT PRIMARY KEY,
+                white TEXT NOT NULL,
+                black TEXT NOT NULL,
+                moves TEXT NOT NULL DEFAULT '[]',
+                result TEXT NOT NULL DEFAULT '*',
+                joined_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            -- Our rulings on illegal move claims in those games
+            CREATE TABLE arbiter_rulings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                game_id TEXT NOT NULL REFERENCES arbitrations(game_id) ON DELETE CASCADE,
+                claimant TEXT NOT NULL,
+                ply INTEGER NOT NULL,
+                chess_move TEXT,
+                upheld INTEGER NOT NULL,
+                reason TEXT NOT NULL,
+                ruled_at INTEGER NOT NULL
+            );
+            CREATE INDEX idx_arbiter_rulings_game ON arbiter_rulings(game_id);
+        "#,
+    },
+];
 
 /// Initialize the database schema and run any pending migrations
 pub fn initialize_schema(conn: &Connection) -> Result<()> {
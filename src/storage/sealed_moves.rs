@@ -0,0 +1,121 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::SealedMoveRecord;
+use rusqlite::{named_params, OptionalExtension, Row};
+
+impl Database {
+    /// Record the move sealed when adjourning a game
+    ///
+    /// `sealed_move` is our own encrypted move, or `None` when only the
+    /// opponent's commitment is known. A game holds one sealed move at a
+    /// time; it can only be sealed again once the last one was revealed.
+    pub fn seal_move(
+        &self,
+        game_id: &str,
+        sealed_by: &str,
+        move_number: u32,
+        commitment: &str,
+        sealed_move: Option<&str>,
+    ) -> Result<()> {
+        if move_number == 0 {
+            return Err(StorageError::invalid_data(
+                "move_number",
+                "Moves are numbered from 1",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let stored = conn.execute(
+                r#"
+                INSERT INTO sealed_moves
+                    (game_id, sealed_by, move_number, commitment, sealed_move, sealed_at)
+                VALUES (:game_id, :sealed_by, :move_number, :commitment, :sealed_move, :sealed_at)
+                ON CONFLICT(game_id) DO UPDATE SET
+                    sealed_by = excluded.sealed_by,
+                    move_number = excluded.move_number,
+                    commitment = excluded.commitment,
+                    sealed_move = excluded.sealed_move,
+                    revealed_move = NULL,
+                    sealed_at = excluded.sealed_at,
+                    revealed_at = NULL
+                WHERE sealed_moves.revealed_at IS NOT NULL
+                "#,
+                named_params! {
+                    ":game_id": game_id,
+                    ":sealed_by": sealed_by,
+                    ":move_number": move_number,
+                    ":commitment": commitment,
+                    ":sealed_move": sealed_move,
+                    ":sealed_at": now,
+                },
+            )?;
+            if stored == 0 {
+                return Err(StorageError::invalid_data(
+                    "game_id",
+                    format!("Game {game_id} already has a sealed move"),
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    /// The move last sealed in a game, revealed or not
+    pub fn get_sealed_move(&self, game_id: &str) -> Result<Option<SealedMoveRecord>> {
+        self.with_connection(|conn| {
+            let record = conn
+                .query_row(
+                    r#"
+                    SELECT game_id, sealed_by, move_number, commitment, sealed_move,
+                           revealed_move, sealed_at, revealed_at
+                    FROM sealed_moves
+                    WHERE game_id = ?1
+                    "#,
+                    [game_id],
+                    sealed_move_from_row,
+                )
+                .optional()?;
+            Ok(record)
+        })
+    }
+
+    /// Record the move a sealed commitment was opened to
+    pub fn reveal_sealed_move(&self, game_id: &str, chess_move: &str) -> Result<()> {
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let revealed = conn.execute(
+                r#"
+                UPDATE sealed_moves
+                SET revealed_move = :revealed_move, revealed_at = :revealed_at
+                WHERE game_id = :game_id AND revealed_at IS NULL
+                "#,
+                named_params! {
+                    ":game_id": game_id,
+                    ":revealed_move": chess_move,
+                    ":revealed_at": now,
+                },
+            )?;
+            if revealed == 0 {
+                return Err(StorageError::invalid_data(
+                    "game_id",
+                    format!("Game {game_id} has no sealed move to reveal"),
+                ));
+            }
+            Ok(())
+        })
+    }
+}
+
+fn sealed_move_from_row(row: &Row) -> rusqlite::Result<SealedMoveRecord> {
+    Ok(SealedMoveRecord {
+        game_id: row.get(0)?,
+        sealed_by: row.get(1)?,
+        move_number: row.get(2)?,
+        commitment: row.get(3)?,
+        sealed_move: row.get(4)?,
+        revealed_move: row.get(5)?,
+        sealed_at: row.get(6)?,
+        revealed_at: row.get(7)?,
+    })
+}
@@ -0,0 +1,88 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::StudyRecord;
+use rusqlite::{named_params, OptionalExtension, Row};
+
+impl Database {
+    /// Keep a study shared with or by a peer
+    ///
+    /// Returns false if a study with the same ID is already kept, in which
+    /// case the first copy is left as it was.
+    pub fn store_study(
+        &self,
+        id: &str,
+        peer_id: &str,
+        outgoing: bool,
+        title: &str,
+        study: &str,
+    ) -> Result<bool> {
+        if id.is_empty() {
+            return Err(StorageError::invalid_data("id", "Study ID cannot be empty"));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let inserted = conn.execute(
+                r#"
+                INSERT OR IGNORE INTO studies (id, peer_id, outgoing, title, study, created_at)
+                VALUES (:id, :peer_id, :outgoing, :title, :study, :created_at)
+                "#,
+                named_params! {
+                    ":id": id,
+                    ":peer_id": peer_id,
+                    ":outgoing": outgoing,
+                    ":title": title,
+                    ":study": study,
+                    ":created_at": now,
+                },
+            )?;
+            Ok(inserted > 0)
+        })
+    }
+
+    /// Get a study by ID
+    pub fn get_study(&self, id: &str) -> Result<Option<StudyRecord>> {
+        self.with_connection(|conn| {
+            let study = conn
+                .query_row(
+                    r#"
+                    SELECT id, peer_id, outgoing, title, study, created_at
+                    FROM studies
+                    WHERE id = ?1
+                    "#,
+                    [id],
+                    study_from_row,
+                )
+                .optional()?;
+            Ok(study)
+        })
+    }
+
+    /// Get every kept study, most recent first
+    pub fn get_studies(&self) -> Result<Vec<StudyRecord>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT id, peer_id, outgoing, title, study, created_at
+                FROM studies
+                ORDER BY created_at DESC, rowid DESC
+                "#,
+            )?;
+
+            let study_iter = stmt.query_map([], study_from_row)?;
+            let studies = study_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(studies)
+        })
+    }
+}
+
+fn study_from_row(row: &Row) -> rusqlite::Result<StudyRecord> {
+    Ok(StudyRecord {
+        id: row.get(0)?,
+        peer_id: row.get(1)?,
+        outgoing: row.get(2)?,
+        title: row.get(3)?,
+        study: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
@@ -0,0 +1,76 @@
+use crate::storage::database::Database;
+use crate::storage::errors::Result;
+use crate::storage::models::GameSyncIssue;
+use rusqlite::{OptionalExtension, Row};
+
+impl Database {
+    /// Flag a game as needing resynchronization, replacing any previous reason
+    pub fn flag_game_needs_sync(&self, game_id: &str, reason: &str) -> Result<GameSyncIssue> {
+        let now = Self::current_timestamp();
+
+        // Make sure the game exists so we can report a proper not-found error
+        self.get_game(game_id)?;
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO game_sync_issues (game_id, reason, detected_at)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(game_id) DO UPDATE SET reason = excluded.reason
+                "#,
+                (game_id, reason, now),
+            )?;
+
+            let issue = conn.query_row(
+                "SELECT game_id, reason, detected_at FROM game_sync_issues WHERE game_id = ?1",
+                [game_id],
+                sync_issue_from_row,
+            )?;
+            Ok(issue)
+        })
+    }
+
+    /// Remove the sync flag from a game, returning whether one was set
+    pub fn clear_game_sync_issue(&self, game_id: &str) -> Result<bool> {
+        self.with_connection(|conn| {
+            let removed =
+                conn.execute("DELETE FROM game_sync_issues WHERE game_id = ?1", [game_id])?;
+            Ok(removed > 0)
+        })
+    }
+
+    /// Get the sync flag for a game, if it has one
+    pub fn get_game_sync_issue(&self, game_id: &str) -> Result<Option<GameSyncIssue>> {
+        self.with_connection(|conn| {
+            let issue = conn
+                .query_row(
+                    "SELECT game_id, reason, detected_at FROM game_sync_issues WHERE game_id = ?1",
+                    [game_id],
+                    sync_issue_from_row,
+                )
+                .optional()?;
+            Ok(issue)
+        })
+    }
+
+    /// Get every game currently flagged as needing sync
+    pub fn get_game_sync_issues(&self) -> Result<Vec<GameSyncIssue>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT game_id, reason, detected_at FROM game_sync_issues ORDER BY detected_at DESC",
+            )?;
+            let issue_iter = stmt.query_map([], sync_issue_from_row)?;
+            let issues = issue_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(issues)
+        })
+    }
+}
+
+/// Convert a database row to a GameSyncIssue struct
+fn sync_issue_from_row(row: &Row) -> rusqlite::Result<GameSyncIssue> {
+    Ok(GameSyncIssue {
+        game_id: row.get("game_id")?,
+        reason: row.get("reason")?,
+        detected_at: row.get("detected_at")?,
+    })
+}
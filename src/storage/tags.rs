@@ -0,0 +1,177 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::{Game, GameNote};
+use rusqlite::OptionalExtension;
+
+/// Maximum length of a single game tag
+pub const MAX_TAG_LENGTH: usize = 32;
+
+/// Maximum length of a game note
+pub const MAX_NOTE_LENGTH: usize = 16 * 1024;
+
+/// Normalize a tag for storage (trimmed, lowercase) and validate its contents
+pub fn normalize_tag(tag: &str) -> Result<String> {
+    let normalized = tag.trim().to_lowercase();
+
+    if normalized.is_empty() {
+        return Err(StorageError::invalid_data("tag", "Tag cannot be empty"));
+    }
+
+    if normalized.len() > MAX_TAG_LENGTH {
+        return Err(StorageError::invalid_data(
+            "tag",
+            format!("Tag exceeds maximum length of {MAX_TAG_LENGTH} characters"),
+        ));
+    }
+
+    if !normalized
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(StorageError::invalid_data(
+            "tag",
+            "Tags may only contain letters, digits, '-' and '_'",
+        ));
+    }
+
+    Ok(normalized)
+}
+
+impl Database {
+    /// Attach a tag to a game. Returns false if the game already had the tag.
+    pub fn add_game_tag(&self, game_id: &str, tag: &str) -> Result<bool> {
+        let tag = normalize_tag(tag)?;
+        let now = Self::current_timestamp();
+
+        // Make sure the game exists so we can report a proper not-found error
+        self.get_game(game_id)?;
+
+        self.with_connection(|conn| {
+            let rows_affected = conn.execute(
+                "INSERT OR IGNORE INTO game_tags (game_id, tag, created_at) VALUES (?1, ?2, ?3)",
+                (game_id, &tag, now),
+            )?;
+            Ok(rows_affected > 0)
+        })
+    }
+
+    /// Remove a tag from a game. Returns false if the game did not have the tag.
+    pub fn remove_game_tag(&self, game_id: &str, tag: &str) -> Result<bool> {
+        let tag = normalize_tag(tag)?;
+
+        self.with_connection(|conn| {
+            let rows_affected = conn.execute(
+                "DELETE FROM game_tags WHERE game_id = ?1 AND tag = ?2",
+                (game_id, &tag),
+            )?;
+            Ok(rows_affected > 0)
+        })
+    }
+
+    /// Get all tags for a game in alphabetical order
+    pub fn get_game_tags(&self, game_id: &str) -> Result<Vec<String>> {
+        self.with_connection(|conn| {
+            let mut stmt =
+                conn.prepare("SELECT tag FROM game_tags WHERE game_id = ?1 ORDER BY tag ASC")?;
+
+            let tag_iter = stmt.query_map([game_id], |row| row.get(0))?;
+            let tags = tag_iter.collect::<std::result::Result<Vec<String>, _>>()?;
+            Ok(tags)
+        })
+    }
+
+    /// Get all games carrying a tag (ordered by most recent first)
+    pub fn get_games_with_tag(&self, tag: &str) -> Result<Vec<Game>> {
+        let tag = normalize_tag(tag)?;
+
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT g.id, g.opponent_peer_id, g.my_color, g.status,
+                       g.created_at, g.updated_at, g.completed_at, g.result, g.metadata
+                FROM games g
+                JOIN game_tags t ON t.game_id = g.id
+                WHERE t.tag = ?1
+                ORDER BY g.updated_at DESC
+                "#,
+            )?;
+
+            let game_iter = stmt.query_map([&tag], crate::storage::games::game_from_row)?;
+            let games = game_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(games)
+        })
+    }
+
+    /// Get every distinct tag with the number of games using it
+    pub fn get_all_tags(&self) -> Result<Vec<(String, u32)>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT tag, COUNT(*) FROM game_tags GROUP BY tag ORDER BY COUNT(*) DESC, tag ASC",
+            )?;
+
+            let tag_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            let tags = tag_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(tags)
+        })
+    }
+
+    /// Set (or replace) the note for a game
+    pub fn set_game_note(&self, game_id: &str, content: &str) -> Result<GameNote> {
+        if content.len() > MAX_NOTE_LENGTH {
+            return Err(StorageError::invalid_data(
+                "note",
+                format!("Note exceeds maximum length of {MAX_NOTE_LENGTH} bytes"),
+            ));
+        }
+
+        let now = Self::current_timestamp();
+
+        self.get_game(game_id)?;
+
+        self.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO game_notes (game_id, content, updated_at) VALUES (?1, ?2, ?3)
+                ON CONFLICT(game_id) DO UPDATE SET content = excluded.content,
+                                                   updated_at = excluded.updated_at
+                "#,
+                (game_id, content, now),
+            )?;
+
+            Ok(GameNote {
+                game_id: game_id.to_string(),
+                content: content.to_string(),
+                updated_at: now,
+            })
+        })
+    }
+
+    /// Get the note for a game, if one exists
+    pub fn get_game_note(&self, game_id: &str) -> Result<Option<GameNote>> {
+        self.with_connection(|conn| {
+            let note = conn
+                .query_row(
+                    "SELECT game_id, content, updated_at FROM game_notes WHERE game_id = ?1",
+                    [game_id],
+                    |row| {
+                        Ok(GameNote {
+                            game_id: row.get("game_id")?,
+                            content: row.get("content")?,
+                            updated_at: row.get("updated_at")?,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(note)
+        })
+    }
+
+    /// Delete the note for a game. Returns false if there was no note.
+    pub fn delete_game_note(&self, game_id: &str) -> Result<bool> {
+        self.with_connection(|conn| {
+            let rows_affected =
+                conn.execute("DELETE FROM game_notes WHERE game_id = ?1", [game_id])?;
+            Ok(rows_affected > 0)
+        })
+    }
+}
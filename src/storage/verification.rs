@@ -0,0 +1,84 @@
+use crate::storage::database::Database;
+use crate::storage::errors::{Result, StorageError};
+use crate::storage::models::PeerVerification;
+use rusqlite::OptionalExtension;
+
+impl Database {
+    /// Note that a peer was met, returning true the first time it is seen
+    pub fn note_peer_seen(&self, peer_id: &str) -> Result<bool> {
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO peer_verifications (peer_id, first_seen_at) VALUES (?1, ?2)",
+                (peer_id, now),
+            )?;
+            Ok(inserted > 0)
+        })
+    }
+
+    /// Mark a peer as verified after its code was compared out of band
+    ///
+    /// Returns false if the peer was already verified.
+    pub fn mark_peer_verified(&self, peer_id: &str) -> Result<bool> {
+        let peer_id = peer_id.trim();
+        if peer_id.is_empty() {
+            return Err(StorageError::invalid_data(
+                "peer_id",
+                "Peer ID cannot be empty",
+            ));
+        }
+        let now = Self::current_timestamp();
+
+        self.with_connection(|conn| {
+            let updated = conn.execute(
+                r#"
+                INSERT INTO peer_verifications (peer_id, first_seen_at, verified_at)
+                VALUES (?1, ?2, ?2)
+                ON CONFLICT(peer_id) DO UPDATE SET verified_at = excluded.verified_at
+                WHERE verified_at IS NULL
+                "#,
+                (peer_id, now),
+            )?;
+            Ok(updated > 0)
+        })
+    }
+
+    /// When a peer was verified, or `None` if it never was
+    pub fn peer_verified_at(&self, peer_id: &str) -> Result<Option<i64>> {
+        self.with_connection(|conn| {
+            let verified_at = conn
+                .query_row(
+                    "SELECT verified_at FROM peer_verifications WHERE peer_id = ?1",
+                    [peer_id],
+                    |row| row.get::<_, Option<i64>>(0),
+                )
+                .optional()?;
+            Ok(verified_at.flatten())
+        })
+    }
+
+    /// Whether the user verified a peer's code
+    pub fn is_peer_verified(&self, peer_id: &str) -> Result<bool> {
+        Ok(self.peer_verified_at(peer_id)?.is_some())
+    }
+
+    /// Every peer met, with its verification status, in peer ID order
+    pub fn get_peer_verifications(&self) -> Result<Vec<PeerVerification>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT peer_id, first_seen_at, verified_at FROM peer_verifications ORDER BY peer_id",
+            )?;
+
+            let verification_iter = stmt.query_map([], |row| {
+                Ok(PeerVerification {
+                    peer_id: row.get(0)?,
+                    first_seen_at: row.get(1)?,
+                    verified_at: row.get(2)?,
+                })
+            })?;
+            let verifications = verification_iter.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(verifications)
+        })
+    }
+}
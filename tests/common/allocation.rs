@@ -0,0 +1,71 @@
+//! Heap usage measurement for tests
+//!
+//! Registers a global allocator that, while tracking is on, records the heap
+//! used by the current thread. Other tests run on other threads, so they
+//! don't affect the numbers.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+    static LIVE: Cell<usize> = const { Cell::new(0) };
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+    static TOTAL: Cell<usize> = const { Cell::new(0) };
+}
+
+struct TrackingAllocator;
+
+fn track(allocated: usize, freed: usize) {
+    let _ = TRACKING.try_with(|tracking| {
+        if !tracking.get() {
+            return;
+        }
+        let live = (LIVE.get() + allocated).saturating_sub(freed);
+        LIVE.set(live);
+        PEAK.set(PEAK.get().max(live));
+        TOTAL.set(TOTAL.get() + allocated);
+    });
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        track(layout.size(), 0);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        track(0, layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        track(new_size, layout.size());
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// Peak heap in use and total bytes allocated on this thread since
+/// `start_tracking`
+pub struct Usage {
+    pub peak: usize,
+    pub total: usize,
+}
+
+pub fn start_tracking() {
+    LIVE.set(0);
+    PEAK.set(0);
+    TOTAL.set(0);
+    TRACKING.set(true);
+}
+
+pub fn stop_tracking() -> Usage {
+    TRACKING.set(false);
+    Usage {
+        peak: PEAK.get(),
+        total: TOTAL.get(),
+    }
+}
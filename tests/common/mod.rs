@@ -3,6 +3,7 @@
 //! This module provides shared functionality for all test files,
 //! including mock streams and test data creation utilities.
 
+pub mod allocation;
 pub mod ci_utils;
 pub mod mock_streams;
 pub mod port_utils;
@@ -3,8 +3,9 @@
 //! Tests for CLI command handlers in src/cli/app.rs based on actual implementation
 
 use anyhow::Result;
-use mate::cli::app::App;
-use mate::storage::models::{GameStatus, PlayerColor};
+use mate::cli::app::{App, GamesOptions, OpeningsOptions};
+use mate::cli::display::OutputFormat;
+use mate::storage::models::{GameResult, GameStatus, PlayerColor};
 use tempfile::TempDir;
 
 /// Create a test app with isolated temporary directory
@@ -93,6 +94,45 @@ async fn test_games_multiple_games_ordered_by_recent_activity() {
     assert_eq!(games.len(), 2, "Should have 2 games in database");
 }
 
+#[tokio::test]
+async fn test_games_with_filters_sorting_paging_and_columns() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+
+    for (opponent, status) in [
+        ("opponent1", GameStatus::Active),
+        ("opponent1", GameStatus::Completed),
+        ("opponent2", GameStatus::Active),
+    ] {
+        create_test_game(&app, opponent, PlayerColor::White, status)
+            .await
+            .expect("Failed to create test game");
+    }
+    app.database
+        .set_peer_alias("rival", "opponent2")
+        .expect("Failed to set alias");
+
+    let options = GamesOptions {
+        status: vec!["active".to_string()],
+        opponent: Some("rival".to_string()),
+        since: Some("7d".to_string()),
+        sort: Some("opponent".to_string()),
+        limit: Some(1),
+        page: Some(2),
+        columns: vec!["id".to_string(), "created".to_string()],
+        ..GamesOptions::default()
+    };
+    assert!(app.handle_games_with(options.clone()).await.is_ok());
+
+    let app = app.with_output_format(OutputFormat::Json);
+    assert!(app.handle_games_with(options).await.is_ok());
+
+    let invalid = GamesOptions {
+        status: vec!["finished".to_string()],
+        ..GamesOptions::default()
+    };
+    assert!(app.handle_games_with(invalid).await.is_err());
+}
+
 // =============================================================================
 // Board Command Tests
 // =============================================================================
@@ -153,6 +193,67 @@ async fn test_board_invalid_game_id_error_handling() {
     );
 }
 
+#[tokio::test]
+async fn test_board_follow_returns_once_game_is_over() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+
+    let game_id = create_test_game(
+        &app,
+        "test_opponent",
+        PlayerColor::White,
+        GameStatus::Completed,
+    )
+    .await
+    .expect("Failed to create test game");
+
+    // A finished game is drawn once and the follow loop ends
+    let result = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        app.handle_board_follow(Some(game_id[..8].to_string())),
+    )
+    .await
+    .expect("board --follow should stop for a completed game");
+    assert!(result.is_ok(), "board --follow failed: {:?}", result);
+
+    assert!(app
+        .handle_board_follow(Some("nonexistent_game_id".to_string()))
+        .await
+        .is_err());
+}
+
+// =============================================================================
+// Status Command Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_status_summarizes_games_in_text_and_json() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+    assert!(app.handle_status(false).await.is_ok());
+
+    create_test_game(
+        &app,
+        "test_opponent",
+        PlayerColor::White,
+        GameStatus::Active,
+    )
+    .await
+    .expect("Failed to create test game");
+    create_test_game(
+        &app,
+        "other_opponent",
+        PlayerColor::Black,
+        GameStatus::Pending,
+    )
+    .await
+    .expect("Failed to create test game");
+
+    assert!(app.handle_status(false).await.is_ok());
+    let app = app.with_output_format(OutputFormat::Json);
+    assert!(app.handle_status(false).await.is_ok());
+    // Without a metrics endpoint the network section explains what is missing
+    assert!(app.handle_status(true).await.is_ok());
+}
+
 // =============================================================================
 // Move Command Tests
 // =============================================================================
@@ -272,6 +373,186 @@ async fn test_accept_non_pending_game_error_handling() {
     );
 }
 
+#[tokio::test]
+async fn test_accept_without_game_and_empty_inbox_succeeds() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+
+    assert!(app.handle_accept_from_inbox(None).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_accept_without_game_needs_a_terminal_to_choose() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+    let app = app.with_output_format(OutputFormat::Json);
+    create_test_game(
+        &app,
+        "test_opponent",
+        PlayerColor::White,
+        GameStatus::Pending,
+    )
+    .await
+    .expect("Failed to create test game");
+
+    let error = app
+        .handle_accept_from_inbox(None)
+        .await
+        .expect_err("Should not prompt when printing JSON");
+    assert!(
+        error.to_string().contains("no game was given"),
+        "Error should ask for a game ID: {error}"
+    );
+}
+
+// =============================================================================
+// Timeline Command Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_timeline_shows_game_in_text_and_json() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+    let game_id = create_test_game(
+        &app,
+        "test_opponent",
+        PlayerColor::White,
+        GameStatus::Active,
+    )
+    .await
+    .expect("Failed to create test game");
+
+    assert!(app.handle_timeline(Some(game_id.clone())).await.is_ok());
+    assert!(app.handle_timeline(None).await.is_ok());
+
+    let app = app.with_output_format(OutputFormat::Json);
+    assert!(app.handle_timeline(Some(game_id)).await.is_ok());
+    assert!(app
+        .handle_timeline(Some("nonexistent_game_id".to_string()))
+        .await
+        .is_err());
+}
+
+// =============================================================================
+// Resign and Draw Command Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_resign_records_loss_even_when_opponent_unreachable() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+
+    let game_id = create_test_game(
+        &app,
+        "test_opponent",
+        PlayerColor::White,
+        GameStatus::Active,
+    )
+    .await
+    .expect("Failed to create test game");
+
+    let result = app.handle_resign(Some(game_id.clone()), true).await;
+    assert!(result.is_ok(), "Resigning should succeed: {result:?}");
+
+    let game = app.database.get_game(&game_id).expect("Failed to get game");
+    assert_eq!(game.status, GameStatus::Completed);
+    assert_eq!(game.result, Some(GameResult::Loss));
+
+    let result = app.handle_resign(Some(game_id), true).await;
+    assert!(result.is_err(), "A finished game cannot be resigned again");
+    let error_msg = result.unwrap_err().to_string();
+    assert!(
+        error_msg.contains("not active"),
+        "Error should indicate game is not active: {}",
+        error_msg
+    );
+}
+
+#[tokio::test]
+async fn test_report_attaches_only_the_peers_messages() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+    let game_id = create_test_game(&app, "rude_peer", PlayerColor::White, GameStatus::Active)
+        .await
+        .expect("Failed to create test game");
+    let store = |sender: &str, text: &str| {
+        app.database
+            .store_message(
+                game_id.clone(),
+                "Chat".to_string(),
+                serde_json::json!({ "text": text }).to_string(),
+                "sig".to_string(),
+                sender.to_string(),
+            )
+            .expect("Failed to store message")
+    };
+    store("rude_peer", "first insult");
+    let mine = store(app.peer_id(), "please stop");
+    store("rude_peer", "second insult");
+
+    let result = app
+        .handle_report(
+            "rude_peer".to_string(),
+            "Abusive chat".to_string(),
+            vec![mine.id.unwrap()],
+            None,
+            false,
+        )
+        .await;
+    assert!(result.is_err(), "Our own message is not evidence");
+
+    app.handle_report(
+        "rude_peer".to_string(),
+        "Abusive chat".to_string(),
+        Vec::new(),
+        Some(game_id),
+        false,
+    )
+    .await
+    .expect("Report should be recorded");
+
+    let reports = app.database.get_peer_reports("rude_peer").unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].forwarded_to, None);
+    let evidence: Vec<serde_json::Value> = serde_json::from_str(&reports[0].evidence).unwrap();
+    let texts: Vec<&str> = evidence
+        .iter()
+        .map(|item| item["content"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        texts,
+        [r#"{"text":"first insult"}"#, r#"{"text":"second insult"}"#]
+    );
+}
+
+#[tokio::test]
+async fn test_offer_draw_accepts_an_open_offer() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+
+    let game_id = create_test_game(
+        &app,
+        "test_opponent",
+        PlayerColor::White,
+        GameStatus::Active,
+    )
+    .await
+    .expect("Failed to create test game");
+    app.database
+        .store_message(
+            game_id.clone(),
+            "DrawOffer".to_string(),
+            "{}".to_string(),
+            String::new(),
+            "test_opponent".to_string(),
+        )
+        .expect("Failed to store draw offer");
+
+    let result = app.handle_offer_draw(Some(game_id.clone()), true).await;
+    assert!(
+        result.is_ok(),
+        "Accepting the draw should succeed: {result:?}"
+    );
+
+    let game = app.database.get_game(&game_id).expect("Failed to get game");
+    assert_eq!(game.status, GameStatus::Completed);
+    assert_eq!(game.result, Some(GameResult::Draw));
+}
+
 // =============================================================================
 // History Command Tests
 // =============================================================================
@@ -302,6 +583,55 @@ async fn test_history_nonexistent_game_error_handling() {
     );
 }
 
+// =============================================================================
+// Openings Command Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_openings_summarizes_and_rejects_illegal_move_prefixes() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+
+    let game_id = create_test_game(
+        &app,
+        "test_opponent",
+        PlayerColor::White,
+        GameStatus::Active,
+    )
+    .await
+    .expect("Failed to create test game");
+    for chess_move in ["e4", "c5"] {
+        app.database
+            .store_message(
+                game_id.clone(),
+                "move".to_string(),
+                serde_json::json!({ "game_id": game_id, "chess_move": chess_move, "board_state_hash": "hash" })
+                    .to_string(),
+                String::new(),
+                "test_opponent".to_string(),
+            )
+            .expect("Failed to store move");
+    }
+
+    let options = OpeningsOptions {
+        moves: Some("e4".to_string()),
+        games: true,
+        ..OpeningsOptions::default()
+    };
+    assert!(app.handle_openings(options).await.is_ok());
+
+    let app = app.with_output_format(OutputFormat::Json);
+    assert!(app
+        .handle_openings(OpeningsOptions::default())
+        .await
+        .is_ok());
+
+    let illegal = OpeningsOptions {
+        moves: Some("e5".to_string()),
+        ..OpeningsOptions::default()
+    };
+    assert!(app.handle_openings(illegal).await.is_err());
+}
+
 // =============================================================================
 // Invite Command Tests
 // =============================================================================
@@ -311,7 +641,11 @@ async fn test_invite_invalid_color_input_rejection() {
     let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
 
     let result = app
-        .handle_invite("127.0.0.1:8080".to_string(), Some("invalid".to_string()))
+        .handle_invite(
+            "127.0.0.1:8080".to_string(),
+            Some("invalid".to_string()),
+            false,
+        )
         .await;
 
     assert!(result.is_err(), "Should fail with invalid color");
@@ -322,3 +656,39 @@ async fn test_invite_invalid_color_input_rejection() {
         error_msg
     );
 }
+
+// =============================================================================
+// JSON Output Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_json_output_for_read_commands() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+    let app = app.with_output_format(OutputFormat::Json);
+    assert!(app.json_output());
+
+    let game_id = create_test_game(
+        &app,
+        "test_opponent",
+        PlayerColor::White,
+        GameStatus::Active,
+    )
+    .await
+    .expect("Failed to create test game");
+
+    assert!(app.handle_games().await.is_ok());
+    assert!(app.handle_board(Some(game_id.clone())).await.is_ok());
+    assert!(app.handle_history(Some(game_id)).await.is_ok());
+    assert!(app.handle_peers_list().await.is_ok());
+}
+
+#[tokio::test]
+async fn test_json_output_without_games_is_an_error() {
+    let (app, _temp_dir) = create_test_app().await.expect("Failed to create test app");
+    let app = app.with_output_format(OutputFormat::Json);
+
+    // Text mode prints a hint; JSON mode has no document to print
+    assert!(app.handle_board(None).await.is_err());
+    assert!(app.handle_history(None).await.is_err());
+    assert!(app.handle_games().await.is_ok());
+}
@@ -6,11 +6,12 @@
 use anyhow::Result;
 use mate::cli::app::App;
 use mate::cli::network_manager::{NetworkConfig, NetworkManager};
+use mate::cli::progress::ProgressEvent;
 use mate::crypto::Identity;
 use mate::messages::chess::Move as ChessMove;
 use mate::messages::{GameAccept, GameInvite, RetryStrategy};
 use mate::storage::{models::PlayerColor, GameStatus};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tempfile::TempDir;
 use tokio::time::timeout;
@@ -53,6 +54,7 @@ fn create_test_network_manager(identity: Arc<Identity>) -> NetworkManager {
         connection_timeout: Duration::from_secs(5),
         max_persistent_connections: 5,
         connection_keepalive: Duration::from_secs(30),
+        ..NetworkConfig::default()
     };
     NetworkManager::with_config(identity, config)
 }
@@ -71,7 +73,7 @@ async fn test_cli_commands_trigger_network_operations() {
     let invite_test_address = get_unique_test_address();
     let invite_result = timeout(
         Duration::from_secs(3),
-        app.handle_invite(invite_test_address, Some("white".to_string())),
+        app.handle_invite(invite_test_address, Some("white".to_string()), false),
     )
     .await;
 
@@ -169,6 +171,93 @@ async fn test_network_manager_retry_behavior() {
     );
 }
 
+#[tokio::test]
+async fn test_network_manager_reports_connection_progress() {
+    let identity = Arc::new(Identity::generate().expect("Failed to generate identity"));
+    let mut network_manager = create_test_network_manager(identity);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    network_manager.set_progress(Some(Arc::new(move |event: &ProgressEvent| {
+        recorded.lock().unwrap().push(event.clone());
+    })));
+
+    let address = "127.0.0.1:1";
+    let game_id = "test_progress".to_string();
+    let invite = GameInvite::new(game_id.clone(), None);
+    assert!(network_manager
+        .send_game_invite(address, game_id, invite)
+        .await
+        .is_err());
+
+    let sent = std::mem::take(&mut *events.lock().unwrap());
+    assert_eq!(
+        sent.first(),
+        Some(&ProgressEvent::Connecting {
+            address: address.to_string(),
+            attempt: 1,
+            max_attempts: RetryStrategy::for_cli_operation("invite").max_attempts(),
+        })
+    );
+    assert!(sent
+        .iter()
+        .any(|event| matches!(event, ProgressEvent::Retrying { .. })));
+    assert_eq!(sent.last(), Some(&ProgressEvent::Finished));
+
+    // The failed invite was queued; flushing it reports a count, not each connection
+    network_manager
+        .send_pending_messages(address)
+        .await
+        .unwrap();
+    let flushed = std::mem::take(&mut *events.lock().unwrap());
+    assert_eq!(
+        flushed,
+        vec![
+            ProgressEvent::Advanced {
+                label: format!("Sending queued messages to {address}"),
+                done: 0,
+                total: 1,
+            },
+            ProgressEvent::Finished,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_network_manager_retry_override_replaces_strategy() {
+    let identity = Arc::new(Identity::generate().expect("Failed to generate identity"));
+    let config = NetworkConfig::default()
+        .with_timeout(Duration::from_secs(2))
+        .with_retries(0);
+    let mut network_manager = NetworkManager::with_config(identity, config);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    network_manager.set_progress(Some(Arc::new(move |event: &ProgressEvent| {
+        recorded.lock().unwrap().push(event.clone());
+    })));
+
+    let address = "127.0.0.1:1";
+    let game_id = "test_retry_override".to_string();
+    let invite = GameInvite::new(game_id.clone(), None);
+    assert!(network_manager
+        .send_game_invite(address, game_id, invite)
+        .await
+        .is_err());
+
+    // With no retries the invite is tried once, not as often as its strategy allows
+    let sent = std::mem::take(&mut *events.lock().unwrap());
+    assert_eq!(
+        sent,
+        vec![
+            ProgressEvent::Connecting {
+                address: address.to_string(),
+                attempt: 1,
+                max_attempts: 1,
+            },
+            ProgressEvent::Finished,
+        ]
+    );
+}
+
 #[tokio::test]
 async fn test_network_manager_connection_state_tracking() {
     let identity = Arc::new(Identity::generate().expect("Failed to generate identity"));
@@ -319,6 +408,7 @@ async fn test_network_manager_configuration_behavior() {
         connection_timeout: Duration::from_millis(100),
         max_persistent_connections: 1,
         connection_keepalive: Duration::from_secs(1),
+        ..NetworkConfig::default()
     };
 
     let fast_manager = NetworkManager::with_config(identity, fast_config);
@@ -418,6 +508,7 @@ async fn test_timeout_behavior_consistency() {
         connection_timeout: Duration::from_millis(100), // Very short
         max_persistent_connections: 1,
         connection_keepalive: Duration::from_secs(1),
+        ..NetworkConfig::default()
     };
 
     let network_manager = NetworkManager::with_config(identity, config);
@@ -603,3 +603,77 @@ async fn test_one_shot_mode_comprehensive() {
     println!("   - Each message exchange completed independently");
     println!("   - Server handled multiple client connections");
 }
+
+/// Test that --stdin sends every input line and prints one JSON reply per line
+#[tokio::test]
+async fn test_stdin_batch_mode_prints_json_lines() {
+    use tokio::io::AsyncWriteExt;
+
+    let server_addr = "127.0.0.1:18701";
+    let server = start_test_server(server_addr)
+        .await
+        .expect("Failed to start test server");
+    let server_handle = tokio::spawn(async move { server.run().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let mut child = Command::new(get_mate_binary_path())
+        .args(["connect", server_addr, "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn mate");
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(b"first\n\nsecond message\n").await.unwrap();
+    drop(stdin);
+
+    let output = timeout(Duration::from_secs(10), child.wait_with_output())
+        .await
+        .expect("Command should complete within timeout")
+        .expect("Command should execute successfully");
+    server_handle.abort();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Batch mode failed: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Logs go to stderr, so every stdout line is a reply; blank lines are skipped
+    let replies: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Each line should be JSON"))
+        .collect();
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0]["seq"], 1);
+    assert_eq!(replies[0]["ok"], true);
+    assert_eq!(replies[0]["response"], "first");
+    assert_eq!(replies[1]["seq"], 3);
+    assert_eq!(replies[1]["response"], "second message");
+    assert!(replies[1]["round_trip_ms"].as_f64().is_some());
+}
+
+/// Test that --stdin exits with a failure status when the peer is unreachable
+#[tokio::test]
+async fn test_stdin_batch_mode_fails_without_peer() {
+    let output = timeout(
+        Duration::from_secs(30),
+        Command::new(get_mate_binary_path())
+            .args(["connect", "127.0.0.1:18702", "--stdin"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output(),
+    )
+    .await
+    .expect("Command should complete within timeout")
+    .expect("Command should execute successfully");
+
+    assert!(!output.status.success());
+    assert!(
+        output.stdout.is_empty(),
+        "Logs must not be written to stdout"
+    );
+}
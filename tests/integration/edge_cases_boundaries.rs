@@ -9,6 +9,7 @@
 //! - Test reconnection during various phases of operation
 
 use anyhow::Result;
+use mate::cli::error_handler::ErrorCode;
 use mate::crypto::Identity;
 use mate::network::Server;
 use std::process::Stdio;
@@ -776,7 +777,7 @@ async fn test_reconnection_during_various_phases() {
         let connection_established = combined_output.contains("Connected")
             || combined_output.contains("Handshake phase test message");
         let graceful_failure = combined_output.contains("Failed to connect")
-            && command_output.status.code() == Some(1);
+            && command_output.status.code() == Some(ErrorCode::Network.exit_status());
 
         assert!(
             connection_established || graceful_failure,
@@ -9,6 +9,7 @@
 //! - Test that errors don't cause crashes or undefined behavior
 
 use anyhow::Result;
+use mate::cli::error_handler::ErrorCode;
 use mate::crypto::Identity;
 use mate::network::Server;
 use std::process::Stdio;
@@ -154,8 +155,9 @@ async fn test_appropriate_program_exit_behavior_on_connection_failure() {
 
         let exit_code = command_output.status.code().unwrap_or(-1);
         assert_eq!(
-            exit_code, 1,
-            "Should exit with code 1 on connection failure"
+            exit_code,
+            ErrorCode::Network.exit_status(),
+            "Should exit with the network error code on connection failure"
         );
 
         let stdout = String::from_utf8_lossy(&command_output.stdout);
@@ -464,8 +466,8 @@ async fn test_cleanup_failures_dont_prevent_termination() {
 
     // Verify it doesn't hang even if cleanup logs warnings/errors
     assert!(
-        exit_code == 0 || exit_code == 1,
-        "Should exit with reasonable exit code (0 or 1), got: {}. Output: {}",
+        exit_code == 0 || exit_code == ErrorCode::Network.exit_status(),
+        "Should exit with success or the network error code, got: {}. Output: {}",
         exit_code,
         combined_output
     );
@@ -550,10 +552,13 @@ async fn test_errors_dont_cause_crashes_or_undefined_behavior() {
             combined_output
         );
 
-        // Verify we get a reasonable exit code (not -1, 128+signal, etc.)
+        // Verify we get a documented exit code (not -1, 128+signal, etc.)
         let exit_code = command_output.status.code().unwrap_or(-1);
         assert!(
-            (0..=2).contains(&exit_code),
+            exit_code == 0
+                || ErrorCode::ALL
+                    .iter()
+                    .any(|code| code.exit_status() == exit_code),
             "Should have reasonable exit code for {}, got: {}",
             description,
             exit_code
@@ -626,7 +631,7 @@ async fn test_comprehensive_error_handling() {
         ),
         (
             "appropriate_exit_code",
-            command_output.status.code() == Some(1),
+            command_output.status.code() == Some(ErrorCode::Network.exit_status()),
         ),
         (
             "error_logging",
@@ -76,3 +76,6 @@ pub mod chess_protocol_advanced;
 pub mod chess_security_integration;
 
 pub mod cli_network;
+
+// Soak tests against simulated clients
+pub mod soak;
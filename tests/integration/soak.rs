@@ -0,0 +1,92 @@
+//! Soak tests
+//!
+//! Runs `mate::cli::stress` against an in-process server. The long run is
+//! ignored by default since it takes minutes; run it on its own so other
+//! tests don't disturb the descriptor and memory figures:
+//!
+//! ```text
+//! cargo test --release --test mod soak -- --ignored
+//! ```
+//!
+//! `MATE_SOAK_SECS` sets its length (default 180).
+
+use mate::cli::stress::{run_stress, StressOptions, StressReport};
+use std::time::Duration;
+
+/// Descriptors the process may gain over a run without leaking: the
+/// runtime's signal pipe is created the first time a server runs
+const FD_SLACK: usize = 4;
+
+/// Resident memory the process may gain over a run
+const RSS_SLACK_BYTES: u64 = 64 * 1024 * 1024;
+
+fn assert_clean_run(report: &StressReport) {
+    assert_eq!(report.errors, 0, "first error: {:?}", report.first_error);
+    assert!(report.exchanges > 0, "no moves were exchanged");
+    assert_eq!(
+        report.server_open_connections,
+        Some(0),
+        "the server kept connections open"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stress_run_exchanges_moves_and_closes_connections() {
+    let report = run_stress(&StressOptions {
+        clients: 4,
+        duration: Duration::from_secs(2),
+        interval: Duration::from_millis(10),
+        address: None,
+    })
+    .await
+    .expect("stress run");
+
+    assert_clean_run(&report);
+    assert_eq!(report.clients, 4);
+    assert!(report.connections >= 4);
+    assert!(report.first_window.samples > 0);
+    assert!(report.last_window.samples > 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "runs for minutes; see the module docs"]
+async fn test_soak_dozens_of_clients_without_leaks() {
+    let secs = std::env::var("MATE_SOAK_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(180);
+    let report = run_stress(&StressOptions {
+        clients: 48,
+        duration: Duration::from_secs(secs),
+        interval: Duration::from_millis(100),
+        address: None,
+    })
+    .await
+    .expect("soak run");
+    println!("{report}");
+
+    assert_clean_run(&report);
+
+    if let (Some(before), Some(after)) = (report.open_fds_before, report.open_fds_after) {
+        assert!(
+            after <= before + FD_SLACK,
+            "open descriptors grew from {before} to {after}"
+        );
+    }
+    if let (Some(before), Some(after)) = (report.rss_before_bytes, report.rss_after_bytes) {
+        assert!(
+            after <= before + RSS_SLACK_BYTES,
+            "resident memory grew from {before} to {after} bytes"
+        );
+    }
+
+    // Latencies at the end of the run stay close to those at the start
+    let (first, last) = (&report.first_window, &report.last_window);
+    assert!(first.samples > 0 && last.samples > 0);
+    assert!(
+        last.p99_ms <= first.p99_ms * 3.0 + 20.0,
+        "p99 round trip grew from {:.1} ms to {:.1} ms",
+        first.p99_ms,
+        last.p99_ms
+    );
+}
@@ -9,6 +9,7 @@
 //! - Test that error recovery doesn't create user confusion
 
 use anyhow::Result;
+use mate::cli::error_handler::ErrorCode;
 use mate::crypto::Identity;
 use mate::network::Server;
 use std::process::Stdio;
@@ -937,7 +938,7 @@ async fn test_error_recovery_doesnt_create_user_confusion() {
     // Verify session state remains comprehensible (can be implicit through completion)
     let session_remains_comprehensible = combined_output.contains("Session")
         || combined_output.contains("Goodbye")
-        || command_output.status.code() == Some(1); // Graceful exit on error
+        || command_output.status.code() == Some(ErrorCode::Network.exit_status()); // Graceful exit on error
 
     assert!(
         session_remains_comprehensible,
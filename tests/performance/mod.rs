@@ -5,4 +5,6 @@
 
 pub mod chess_messages_benchmark;
 pub mod chess_protocol_stress;
+pub mod storage_queries;
+pub mod sync_memory;
 pub mod throughput;
@@ -0,0 +1,193 @@
+//! Storage query performance tests for large histories
+//!
+//! Seeds a database with 10k games (and two messages per game) and checks that
+//! the queries behind `mate games` and `mate history` stay within latency bounds
+//! and are served by indices rather than full table scans.
+
+use mate::storage::{Database, GameStatus};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+const GAME_COUNT: usize = 10_000;
+const OPPONENT_COUNT: usize = 50;
+
+/// Seed a fresh database with a large synthetic history
+fn seeded_database() -> (Database, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let db_path = temp_dir.path().join("database.sqlite");
+    let db = Database::new_with_path("perf_peer_12345678", &db_path)
+        .expect("Failed to create test database");
+
+    let statuses = ["pending", "active", "completed", "abandoned"];
+    db.transaction(|tx| {
+        let conn = tx.connection();
+        let mut insert_game = conn.prepare(
+            "INSERT INTO games (id, opponent_peer_id, my_color, status, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        let mut insert_message = conn.prepare(
+            "INSERT INTO messages (game_id, message_type, content, signature, sender_peer_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+
+        for i in 0..GAME_COUNT {
+            let game_id = format!("game-{i:05}");
+            let opponent = format!("opponent-{:02}", i % OPPONENT_COUNT);
+            let color = if i % 2 == 0 { "white" } else { "black" };
+            let created_at = 1_700_000_000 + i as i64;
+
+            insert_game.execute((
+                &game_id,
+                &opponent,
+                color,
+                statuses[i % statuses.len()],
+                created_at,
+                created_at + 60,
+            ))?;
+            insert_message.execute((
+                &game_id,
+                "game_invite",
+                "{}",
+                "sig",
+                &opponent,
+                created_at,
+            ))?;
+            insert_message.execute((&game_id, "move", "{}", "sig", &opponent, created_at + 30))?;
+        }
+        Ok(())
+    })
+    .expect("Failed to seed database");
+
+    (db, temp_dir)
+}
+
+/// Time a query, returning its result and the elapsed duration
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Collect the EXPLAIN QUERY PLAN details for a statement
+fn query_plan(db: &Database, sql: &str) -> Vec<String> {
+    db.with_connection(|conn| {
+        let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>("detail"))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    })
+    .expect("Failed to get query plan")
+}
+
+/// Assert that a statement's plan uses the named index and never scans a table
+fn assert_uses_index(db: &Database, sql: &str, index: &str) {
+    let plan = query_plan(db, sql);
+    assert!(
+        plan.iter().any(|detail| detail.contains(index)),
+        "Expected plan for `{sql}` to use {index}, got {plan:?}"
+    );
+    assert!(
+        !plan
+            .iter()
+            .any(|detail| detail.starts_with("SCAN") && !detail.contains("INDEX")),
+        "Plan for `{sql}` contains a full table scan: {plan:?}"
+    );
+    assert!(
+        !plan.iter().any(|detail| detail.contains("TEMP B-TREE")),
+        "Plan for `{sql}` needs a temporary sort: {plan:?}"
+    );
+}
+
+#[test]
+fn test_game_queries_use_indices() {
+    let (db, _temp_dir) = seeded_database();
+
+    assert_uses_index(
+        &db,
+        "SELECT * FROM games ORDER BY updated_at DESC",
+        "idx_games_updated",
+    );
+    assert_uses_index(
+        &db,
+        "SELECT * FROM games WHERE status = 'active' ORDER BY created_at DESC",
+        "idx_games_status_created",
+    );
+    assert_uses_index(
+        &db,
+        "SELECT * FROM games WHERE opponent_peer_id = 'opponent-01' ORDER BY created_at DESC",
+        "idx_games_opponent_created",
+    );
+    assert_uses_index(
+        &db,
+        "SELECT * FROM games ORDER BY created_at DESC LIMIT 20",
+        "idx_games_created",
+    );
+}
+
+#[test]
+fn test_message_queries_use_indices() {
+    let (db, _temp_dir) = seeded_database();
+
+    assert_uses_index(
+        &db,
+        "SELECT * FROM messages WHERE game_id = 'game-00001' ORDER BY created_at ASC",
+        "idx_messages_game",
+    );
+    assert_uses_index(
+        &db,
+        "SELECT * FROM messages WHERE game_id = 'game-00001' AND message_type = 'move' ORDER BY created_at ASC",
+        "idx_messages_game_type",
+    );
+    assert_uses_index(
+        &db,
+        "SELECT * FROM messages ORDER BY created_at DESC LIMIT 20",
+        "idx_messages_created",
+    );
+}
+
+#[test]
+fn test_large_history_query_latency() {
+    let (db, _temp_dir) = seeded_database();
+
+    // Generous bounds so debug builds on slow CI machines still pass, while
+    // a missing index (full scan + sort per query) would blow past them.
+    let (all_games, elapsed) = timed(|| db.get_all_games().unwrap());
+    assert_eq!(all_games.len(), GAME_COUNT);
+    assert!(
+        elapsed < Duration::from_secs(2),
+        "Listing {GAME_COUNT} games took {elapsed:?}"
+    );
+
+    let (active, elapsed) = timed(|| db.get_games_by_status(GameStatus::Active).unwrap());
+    assert_eq!(active.len(), GAME_COUNT / 4);
+    assert!(
+        elapsed < Duration::from_secs(1),
+        "Status filter took {elapsed:?}"
+    );
+
+    let (with_opponent, elapsed) = timed(|| db.get_games_with_opponent("opponent-07").unwrap());
+    assert_eq!(with_opponent.len(), GAME_COUNT / OPPONENT_COUNT);
+    assert!(
+        elapsed < Duration::from_millis(250),
+        "Opponent filter took {elapsed:?}"
+    );
+
+    let (recent, elapsed) = timed(|| db.get_recent_games(20).unwrap());
+    assert_eq!(recent.len(), 20);
+    assert!(
+        elapsed < Duration::from_millis(100),
+        "Recent games took {elapsed:?}"
+    );
+
+    // History lookups for individual games, repeated to smooth out noise
+    let (_, elapsed) = timed(|| {
+        for i in (0..GAME_COUNT).step_by(GAME_COUNT / 100) {
+            let game_id = format!("game-{i:05}");
+            assert_eq!(db.get_messages_for_game(&game_id).unwrap().len(), 2);
+            assert_eq!(db.get_messages_by_type(&game_id, "move").unwrap().len(), 1);
+        }
+    });
+    assert!(
+        elapsed < Duration::from_secs(1),
+        "100 history lookups took {elapsed:?}"
+    );
+}
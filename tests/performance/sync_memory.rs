@@ -0,0 +1,82 @@
+//! Memory used while receiving large sync payloads
+//!
+//! Measures the heap used by the test thread while a `SyncResponse` close
+//! to the 8MB large-transfer limit is read, verified, and decoded.
+
+use crate::common::allocation::{start_tracking, stop_tracking};
+use mate::crypto::Identity;
+use mate::messages::chess::SyncResponse;
+use mate::messages::wire::{FramedMessage, WireConfig};
+use mate::messages::{Message, SignedEnvelope};
+use std::io::Cursor;
+
+/// Entries in the synced move history
+const HISTORY_ENTRIES: usize = 900;
+
+/// Bytes per history entry, e.g. a move with a long comment
+const ENTRY_SIZE: usize = 8 * 1024;
+
+/// Room for the decoded history's `String` headers and allocator rounding
+const SLACK: usize = 64 * 1024;
+
+/// A framed `SyncResponse` of about 7MB and its payload size
+async fn large_sync_frame() -> (Vec<u8>, usize) {
+    let identity = Identity::generate().unwrap();
+    let history = (0..HISTORY_ENTRIES)
+        .map(|i| format!("{i}. e4 {{{}}}", "x".repeat(ENTRY_SIZE)))
+        .collect();
+    let sync = Message::SyncResponse(SyncResponse::new(
+        "large-sync".to_string(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".to_string(),
+        history,
+        "0".repeat(64),
+    ));
+    let envelope = SignedEnvelope::create(&sync, &identity, None).unwrap();
+    let payload_size = envelope.message.len();
+
+    let mut wire = Vec::new();
+    FramedMessage::new(WireConfig::for_large_transfers())
+        .write_message(&mut wire, &envelope)
+        .await
+        .unwrap();
+    (wire, payload_size)
+}
+
+#[tokio::test]
+async fn test_large_sync_is_decoded_from_the_frame_without_copying() {
+    let (wire, payload_size) = large_sync_frame().await;
+    let framed_message = FramedMessage::new(WireConfig::for_large_transfers());
+    let mut reader = Cursor::new(wire);
+
+    // The runtime is single-threaded, so everything below runs on this thread
+    start_tracking();
+    let frame = framed_message.read_frame(&mut reader).await.unwrap();
+    let envelope = frame.envelope().unwrap();
+    assert!(envelope.verify_signature());
+    let message = envelope.get_message().unwrap();
+    drop(frame);
+    let usage = stop_tracking();
+
+    let Message::SyncResponse(sync) = message else {
+        panic!("expected a SyncResponse");
+    };
+    assert_eq!(sync.move_history.len(), HISTORY_ENTRIES);
+    println!(
+        "{payload_size} byte payload: peak {} bytes, {} allocated",
+        usage.peak, usage.total
+    );
+
+    // The frame and the decoded message are the only copies alive at once
+    assert!(
+        usage.peak < 2 * payload_size + SLACK,
+        "peak {} bytes for a {payload_size} byte payload",
+        usage.peak
+    );
+    // The payload is copied once, from the frame into the message, rather
+    // than into an owned envelope first
+    assert!(
+        usage.total < 2 * payload_size + SLACK,
+        "{} bytes allocated for a {payload_size} byte payload",
+        usage.total
+    );
+}
@@ -0,0 +1,276 @@
+//! Property-based fuzz tests
+//!
+//! Feeds random and mutated input to the frame decoder and to the chess
+//! move, FEN, and game ID validators with proptest, checking that bad input
+//! is rejected without panicking and that decoding a frame never holds more
+//! memory than the configured message size allows.
+//!
+//! Each test runs 256 cases unless `PROPTEST_CASES` says otherwise. A failing
+//! input is shrunk, and its seed saved in `fuzz.proptest-regressions` next
+//! to this file so the case is replayed first on later runs; commit it with
+//! the fix.
+
+use crate::common::allocation::{start_tracking, stop_tracking};
+use mate::chess::{Board, Move, Position};
+use mate::cli::validation::InputValidator;
+use mate::crypto::Identity;
+use mate::messages::chess::{
+    validate_chess_move_format, validate_game_end_message, validate_game_id, SyncResponse,
+};
+use mate::messages::wire::{FramedMessage, WireConfig, LENGTH_PREFIX_SIZE};
+use mate::messages::{Message, SignedEnvelope};
+use mate::storage::{Database, DatabaseLocation};
+use proptest::prelude::*;
+use proptest::sample::{select, Index};
+use proptest::test_runner::FileFailurePersistence;
+use std::io::Cursor;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Room for decoded headers, error messages, and allocator rounding
+const SLACK: usize = 64 * 1024;
+
+/// Most serde reserves up front for a sequence, such as a sync's move
+/// history, whatever length a corrupted message claims
+const SERDE_PREALLOCATION: usize = 1024 * 1024;
+
+/// Characters that make up moves, FENs, and IDs, plus some that should be
+/// rejected: multi-byte, control, and bidirectional characters
+const ALPHABET: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'K',
+    'Q', 'R', 'B', 'N', 'P', 'k', 'q', 'r', 'n', 'p', 'w', 'x', 'O', 'o', '-', '=', '+', '#', '/',
+    ' ', '\t', '\n', '\0', 'é', '♔', '𝕒', '\u{202e}', '\u{feff}',
+];
+
+const VALID_TEXT: &[&str] = &[
+    "e2e4",
+    "e7e8q",
+    "O-O-O",
+    "Nf3",
+    "exd5",
+    "e8=Q+",
+    "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+    "8/8/8/8/8/8/8/K6k w - - 0 1",
+    "550e8400-e29b-41d4-a716-446655440000",
+];
+
+/// An edit to a frame's bytes
+#[derive(Debug, Clone)]
+enum ByteEdit {
+    Flip(Index, u8),
+    Insert(Index, u8),
+    Remove(Index),
+    Truncate(Index),
+    /// Overwrite the length prefix
+    Length(u32),
+}
+
+/// An edit to a string's characters
+#[derive(Debug, Clone)]
+enum CharEdit {
+    Replace(Index, char),
+    Insert(Index, char),
+    Remove(Index),
+}
+
+fn alphabet() -> impl Strategy<Value = char> {
+    prop_oneof![9 => select(ALPHABET), 1 => any::<char>()]
+}
+
+fn byte_edit(max_message_size: u32) -> impl Strategy<Value = ByteEdit> {
+    let length = prop_oneof![
+        Just(0),
+        Just(1),
+        Just(max_message_size - 1),
+        Just(max_message_size),
+        Just(max_message_size + 1),
+        Just(u32::MAX),
+        any::<u32>(),
+    ];
+    prop_oneof![
+        (any::<Index>(), 0..8u8).prop_map(|(at, bit)| ByteEdit::Flip(at, bit)),
+        (any::<Index>(), any::<u8>()).prop_map(|(at, byte)| ByteEdit::Insert(at, byte)),
+        any::<Index>().prop_map(ByteEdit::Remove),
+        any::<Index>().prop_map(ByteEdit::Truncate),
+        length.prop_map(ByteEdit::Length),
+    ]
+}
+
+fn char_edit() -> impl Strategy<Value = CharEdit> {
+    prop_oneof![
+        (any::<Index>(), alphabet()).prop_map(|(at, c)| CharEdit::Replace(at, c)),
+        (any::<Index>(), alphabet()).prop_map(|(at, c)| CharEdit::Insert(at, c)),
+        any::<Index>().prop_map(CharEdit::Remove),
+    ]
+}
+
+/// `bytes` with `edits` applied in turn
+fn apply_byte_edits(bytes: &[u8], edits: &[ByteEdit]) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    for edit in edits {
+        match *edit {
+            ByteEdit::Flip(at, bit) if !bytes.is_empty() => {
+                let at = at.index(bytes.len());
+                bytes[at] ^= 1 << bit;
+            }
+            ByteEdit::Insert(at, byte) => bytes.insert(at.index(bytes.len() + 1), byte),
+            ByteEdit::Remove(at) if !bytes.is_empty() => {
+                bytes.remove(at.index(bytes.len()));
+            }
+            ByteEdit::Truncate(at) => bytes.truncate(at.index(bytes.len() + 1)),
+            ByteEdit::Length(length) if bytes.len() >= LENGTH_PREFIX_SIZE => {
+                bytes[..LENGTH_PREFIX_SIZE].copy_from_slice(&length.to_be_bytes());
+            }
+            _ => {}
+        }
+    }
+    bytes
+}
+
+/// `text` with `edits` applied in turn
+fn apply_char_edits(text: &str, edits: &[CharEdit]) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+    for edit in edits {
+        match *edit {
+            CharEdit::Replace(at, c) if !chars.is_empty() => {
+                let at = at.index(chars.len());
+                chars[at] = c;
+            }
+            CharEdit::Insert(at, c) => chars.insert(at.index(chars.len() + 1), c),
+            CharEdit::Remove(at) if !chars.is_empty() => {
+                chars.remove(at.index(chars.len()));
+            }
+            _ => {}
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Random bytes, or a valid frame with a few edits
+fn frame_input(max_message_size: usize) -> impl Strategy<Value = Vec<u8>> {
+    let edited = (
+        select(valid_frames()),
+        prop::collection::vec(byte_edit(max_message_size as u32), 1..=4),
+    )
+        .prop_map(|(frame, edits)| apply_byte_edits(&frame, &edits));
+    prop_oneof![
+        1 => prop::collection::vec(any::<u8>(), 0..=256),
+        3 => edited,
+    ]
+}
+
+/// Random text, or a valid move, FEN, or ID with a few edits
+fn text_input() -> impl Strategy<Value = String> {
+    let edited = (
+        select(VALID_TEXT),
+        prop::collection::vec(char_edit(), 1..=3),
+    )
+        .prop_map(|(text, edits)| apply_char_edits(text, &edits));
+    prop_oneof![
+        prop::collection::vec(alphabet(), 0..=80).prop_map(String::from_iter),
+        edited,
+    ]
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    // Single-threaded, so decoding runs on the thread whose heap is tracked
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+/// Framed envelopes of a few message types, to mutate
+fn valid_frames() -> Vec<Vec<u8>> {
+    static FRAMES: OnceLock<Vec<Vec<u8>>> = OnceLock::new();
+    FRAMES
+        .get_or_init(|| runtime().block_on(frame_messages(&WireConfig::for_control_messages())))
+        .clone()
+}
+
+/// Each sample message, signed and framed with `config`
+async fn frame_messages(config: &WireConfig) -> Vec<Vec<u8>> {
+    let identity = Identity::generate().unwrap();
+    let messages = [
+        Message::new_ping(7, "hello".to_string()),
+        Message::new_resign("550e8400-e29b-41d4-a716-446655440000".to_string()),
+        Message::SyncResponse(SyncResponse::new(
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".to_string(),
+            vec!["e2e4".to_string(); 40],
+            "0".repeat(64),
+        )),
+    ];
+    let framed_message = FramedMessage::new(config.clone());
+    let mut frames = Vec::new();
+    for message in messages {
+        let envelope = SignedEnvelope::create(&message, &identity, None).unwrap();
+        let mut frame = Vec::new();
+        framed_message
+            .write_message(&mut frame, &envelope)
+            .await
+            .unwrap();
+        frames.push(frame);
+    }
+    frames
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        failure_persistence: Some(Box::new(FileFailurePersistence::WithSource(
+            "proptest-regressions",
+        ))),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn test_frame_decoder_rejects_arbitrary_bytes_within_bounded_memory(
+        input in frame_input(WireConfig::for_control_messages().max_message_size)
+    ) {
+        let runtime = runtime();
+        let config = WireConfig::for_control_messages();
+        let max_message_size = config.max_message_size;
+        let framed_message = FramedMessage::new(config);
+
+        start_tracking();
+        runtime.block_on(async {
+            if let Ok(frame) = framed_message.read_frame(&mut Cursor::new(&input)).await {
+                if let Ok(envelope) = frame.envelope() {
+                    envelope.verify_signature();
+                    let _ = envelope.get_message();
+                }
+            }
+            let _ = framed_message.read_message(&mut Cursor::new(&input)).await;
+        });
+        let usage = stop_tracking();
+        // The frame and the message decoded from it, at most; a bogus
+        // length never makes the decoder reserve what the input lacks
+        prop_assert!(
+            usage.peak <= 2 * max_message_size + SERDE_PREALLOCATION + SLACK,
+            "peak {} bytes",
+            usage.peak
+        );
+    }
+
+    #[test]
+    fn test_chess_validators_reject_arbitrary_text_without_panicking(input in text_input()) {
+        let database = Database::open("fuzz", &DatabaseLocation::InMemory).unwrap();
+        let validator = InputValidator::new(&database);
+        let board = Board::default();
+
+        let _ = validate_chess_move_format(&input);
+        let _ = validate_game_id(&input);
+        let _ = validate_game_end_message(&input);
+        let _ = validator.validate_chess_move(&input);
+        let _ = validator.validate_uuid_format(&input);
+        let _ = Move::from_str(&input);
+        let _ = Position::from_str(&input);
+        let _ = board.parse_move(&input);
+
+        // Whatever FEN is accepted is written back in a form read the same way
+        if let Ok(parsed) = Board::from_fen(&input) {
+            let fen = parsed.to_fen();
+            let reparsed = Board::from_fen(&fen).expect("written FEN parses");
+            prop_assert_eq!(reparsed.to_fen(), fen);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Trust-on-first-use peer key pinning tests
+//!
+//! A peer's key is pinned the first time it is reached at an address; later
+//! connections presenting a different key must fail unless the change is
+//! explicitly accepted.
+
+use mate::cli::commands::NetworkOptions;
+use mate::cli::network_manager::{NetworkConfig, NetworkManager};
+use mate::cli::App;
+use mate::crypto::Identity;
+use mate::messages::chess::GameInvite;
+use mate::network::known_peers::{check_peer_key, KeyCheck, KNOWN_PEERS_FILE};
+use mate::network::{KnownPeers, PeerKeyChanged, PeerKeyRevoked, Server};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+use crate::common::port_utils::get_unique_test_address;
+
+#[test]
+fn test_first_key_is_pinned_and_matched_later() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.1:8080", "key-a", false).unwrap(),
+        KeyCheck::FirstUse
+    );
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.1:8080", "key-a", false).unwrap(),
+        KeyCheck::Match
+    );
+
+    let known_peers = KnownPeers::load(&path).unwrap();
+    assert_eq!(known_peers.get("10.0.0.1:8080").unwrap().peer_id, "key-a");
+}
+
+#[test]
+fn test_changed_key_fails_without_override() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    check_peer_key(&path, "10.0.0.1:8080", "key-a", false).unwrap();
+
+    let error = check_peer_key(&path, "10.0.0.1:8080", "key-b", false).unwrap_err();
+    let changed = error
+        .downcast_ref::<PeerKeyChanged>()
+        .expect("a changed key should be reported as such");
+    assert_eq!(changed.pinned, "key-a");
+    assert_eq!(changed.presented, "key-b");
+    assert!(error.to_string().contains("--accept-new-key"));
+
+    // The pinned key is left alone
+    let known_peers = KnownPeers::load(&path).unwrap();
+    assert_eq!(known_peers.get("10.0.0.1:8080").unwrap().peer_id, "key-a");
+}
+
+#[test]
+fn test_accepting_changed_key_replaces_pin() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    check_peer_key(&path, "10.0.0.1:8080", "key-a", false).unwrap();
+
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.1:8080", "key-b", true).unwrap(),
+        KeyCheck::Changed {
+            pinned: "key-a".to_string()
+        }
+    );
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.1:8080", "key-b", false).unwrap(),
+        KeyCheck::Match
+    );
+}
+
+#[test]
+fn test_pins_are_per_address() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    check_peer_key(&path, "10.0.0.1:8080", "key-a", false).unwrap();
+
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.2:8080", "key-b", false).unwrap(),
+        KeyCheck::FirstUse
+    );
+
+    let mut known_peers = KnownPeers::load(&path).unwrap();
+    assert!(known_peers.forget("10.0.0.1:8080"));
+    assert!(!known_peers.forget("10.0.0.1:8080"));
+}
+
+#[test]
+fn test_key_rotation_moves_pins() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    check_peer_key(&path, "10.0.0.1:8080", "old-key", false).unwrap();
+    check_peer_key(&path, "home.example:8080", "old-key", false).unwrap();
+    check_peer_key(&path, "10.0.0.2:8080", "other-key", false).unwrap();
+
+    let mut known_peers = KnownPeers::load(&path).unwrap();
+    assert_eq!(known_peers.apply_rotation("old-key", "new-key"), 2);
+    known_peers.save().unwrap();
+
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.1:8080", "new-key", false).unwrap(),
+        KeyCheck::Match
+    );
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.2:8080", "other-key", false).unwrap(),
+        KeyCheck::Match
+    );
+}
+
+#[tokio::test]
+async fn test_purge_by_peer_forgets_its_pins() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    let app = App::new_with_data_dir(dir.path().to_path_buf())
+        .await
+        .unwrap();
+    check_peer_key(&path, "10.0.0.1:8080", "purged-key", false).unwrap();
+    check_peer_key(&path, "home.example:8080", "purged-key", false).unwrap();
+    check_peer_key(&path, "10.0.0.2:8080", "other-key", false).unwrap();
+
+    // A date-limited purge keeps what names the peer
+    app.handle_purge(
+        Some("purged-key".to_string()),
+        Some("2999-01-01".to_string()),
+        false,
+        true,
+    )
+    .await
+    .unwrap();
+    assert_eq!(KnownPeers::load(&path).unwrap().iter().count(), 3);
+
+    app.handle_purge(Some("purged-key".to_string()), None, false, true)
+        .await
+        .unwrap();
+    let known_peers = KnownPeers::load(&path).unwrap();
+    assert!(known_peers.get("10.0.0.1:8080").is_none());
+    assert!(known_peers.get("home.example:8080").is_none());
+    assert_eq!(
+        known_peers.get("10.0.0.2:8080").unwrap().peer_id,
+        "other-key"
+    );
+}
+
+#[test]
+fn test_corrupt_store_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    std::fs::write(&path, "not json").unwrap();
+
+    assert!(check_peer_key(&path, "10.0.0.1:8080", "key-a", false).is_err());
+}
+
+#[test]
+fn test_accept_new_key_option_sets_override() {
+    let options = NetworkOptions {
+        accept_new_key: true,
+        ..NetworkOptions::default()
+    };
+    assert!(options.apply(NetworkConfig::default()).accept_changed_keys);
+    assert!(
+        !NetworkOptions::default()
+            .apply(NetworkConfig::default())
+            .accept_changed_keys
+    );
+}
+
+#[tokio::test]
+async fn test_network_manager_refuses_impostor_key() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    let address = get_unique_test_address();
+
+    let server = Server::bind(&address, Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap();
+    let server_handle = tokio::spawn(server.run());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Someone else's key was seen at this address before
+    let mut known_peers = KnownPeers::load(&path).unwrap();
+    known_peers.pin(&address, "key-seen-before");
+    known_peers.save().unwrap();
+
+    let config = NetworkConfig::default()
+        .with_timeout(Duration::from_secs(2))
+        .with_known_peers(path.clone());
+    let manager = NetworkManager::with_config(Arc::new(Identity::generate().unwrap()), config);
+    let error = manager
+        .send_game_invite(
+            &address,
+            "game-1".to_string(),
+            GameInvite::new("game-1".to_string(), None),
+        )
+        .await
+        .unwrap_err();
+
+    assert!(
+        error.downcast_ref::<PeerKeyChanged>().is_some(),
+        "expected a key change error, got: {error:#}"
+    );
+    let known_peers = KnownPeers::load(&path).unwrap();
+    assert_eq!(
+        known_peers.get(&address).unwrap().peer_id,
+        "key-seen-before"
+    );
+
+    server_handle.abort();
+}
+
+#[test]
+fn test_revoked_key_is_refused_at_every_address() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    check_peer_key(&path, "10.0.0.1:8080", "stolen-key", false).unwrap();
+    check_peer_key(&path, "10.0.0.2:8080", "other-key", false).unwrap();
+
+    let mut known_peers = KnownPeers::load(&path).unwrap();
+    assert_eq!(known_peers.revoke("stolen-key"), 1);
+    assert_eq!(known_peers.revoke("stolen-key"), 0);
+    known_peers.save().unwrap();
+
+    for address in ["10.0.0.1:8080", "10.0.0.3:8080"] {
+        let error = check_peer_key(&path, address, "stolen-key", true).unwrap_err();
+        assert!(
+            error.downcast_ref::<PeerKeyRevoked>().is_some(),
+            "expected a revoked key error, got: {error:#}"
+        );
+    }
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.2:8080", "other-key", false).unwrap(),
+        KeyCheck::Match
+    );
+}
+
+#[test]
+fn test_new_key_replaces_revoked_pin_only_when_accepted() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(KNOWN_PEERS_FILE);
+    check_peer_key(&path, "10.0.0.1:8080", "stolen-key", false).unwrap();
+    let mut known_peers = KnownPeers::load(&path).unwrap();
+    known_peers.revoke("stolen-key");
+    known_peers.save().unwrap();
+
+    let error = check_peer_key(&path, "10.0.0.1:8080", "new-key", false).unwrap_err();
+    assert!(
+        error.downcast_ref::<PeerKeyChanged>().is_some(),
+        "expected a changed key error, got: {error:#}"
+    );
+    assert_eq!(
+        KnownPeers::load(&path)
+            .unwrap()
+            .get("10.0.0.1:8080")
+            .unwrap()
+            .peer_id,
+        "stolen-key"
+    );
+
+    assert_eq!(
+        check_peer_key(&path, "10.0.0.1:8080", "new-key", true).unwrap(),
+        KeyCheck::Changed {
+            pinned: "stolen-key".to_string()
+        }
+    );
+    let known_peers = KnownPeers::load(&path).unwrap();
+    let pinned = known_peers.get("10.0.0.1:8080").unwrap();
+    assert_eq!(pinned.peer_id, "new-key");
+    assert!(!pinned.revoked);
+}
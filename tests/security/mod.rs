@@ -5,3 +5,5 @@
 
 pub mod dos_protection;
 pub mod error_handling;
+pub mod fuzz;
+pub mod known_peers;
@@ -0,0 +1,160 @@
+use super::create_test_database;
+use mate::chess::{Board, Color, Move};
+use mate::cli::export_game_pgn;
+use mate::messages::chess::Move as MoveMessage;
+use mate::storage::{Database, PlayerColor, PositionAnalysis, StorageError};
+
+/// Build an analysis record for a board
+fn analysis_for(board: &Board, engine: &str, depth: u32, score_cp: i32) -> PositionAnalysis {
+    PositionAnalysis {
+        position_hash: board.zobrist_hash(),
+        engine: engine.to_string(),
+        fen: board.to_fen(),
+        depth,
+        score_cp: Some(score_cp),
+        mate_in: None,
+        best_move: Some("e7e5".to_string()),
+        pv: Some("e7e5 g1f3".to_string()),
+        created_at: Database::current_timestamp(),
+    }
+}
+
+/// Board after 1. e4
+fn board_after_e4() -> Board {
+    let mut board = Board::new();
+    board
+        .make_move(Move::from_str_with_color("e2e4", Color::White).unwrap())
+        .unwrap();
+    board
+}
+
+#[test]
+fn test_store_and_get_analysis() {
+    let (db, _temp_dir) = create_test_database();
+    let board = board_after_e4();
+
+    assert!(db.get_analysis(board.zobrist_hash()).unwrap().is_none());
+
+    let analysis = analysis_for(&board, "stockfish", 18, 35);
+    assert!(db.store_analysis(&analysis).unwrap());
+
+    let stored = db.get_analysis(board.zobrist_hash()).unwrap().unwrap();
+    assert_eq!(stored, analysis);
+    assert_eq!(
+        db.get_engine_analysis(board.zobrist_hash(), "stockfish")
+            .unwrap(),
+        Some(analysis)
+    );
+    assert!(db
+        .get_engine_analysis(board.zobrist_hash(), "other")
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_high_bit_hashes_round_trip() {
+    let (db, _temp_dir) = create_test_database();
+    let mut analysis = analysis_for(&Board::new(), "stockfish", 10, 20);
+    analysis.position_hash = u64::MAX - 7;
+
+    db.store_analysis(&analysis).unwrap();
+    let stored = db.get_analysis(u64::MAX - 7).unwrap().unwrap();
+    assert_eq!(stored.position_hash, u64::MAX - 7);
+}
+
+#[test]
+fn test_shallower_analysis_does_not_replace_deeper() {
+    let (db, _temp_dir) = create_test_database();
+    let board = board_after_e4();
+
+    db.store_analysis(&analysis_for(&board, "stockfish", 20, 30))
+        .unwrap();
+    assert!(!db
+        .store_analysis(&analysis_for(&board, "stockfish", 12, -50))
+        .unwrap());
+    assert_eq!(
+        db.get_analysis(board.zobrist_hash())
+            .unwrap()
+            .unwrap()
+            .depth,
+        20
+    );
+
+    assert!(db
+        .store_analysis(&analysis_for(&board, "stockfish", 24, 28))
+        .unwrap());
+    let stored = db.get_analysis(board.zobrist_hash()).unwrap().unwrap();
+    assert_eq!(stored.depth, 24);
+    assert_eq!(stored.score_cp, Some(28));
+}
+
+#[test]
+fn test_deepest_engine_wins() {
+    let (db, _temp_dir) = create_test_database();
+    let board = board_after_e4();
+
+    db.store_analysis(&analysis_for(&board, "stockfish", 16, 30))
+        .unwrap();
+    db.store_analysis(&analysis_for(&board, "lc0", 22, 25))
+        .unwrap();
+
+    let stored = db.get_analysis(board.zobrist_hash()).unwrap().unwrap();
+    assert_eq!(stored.engine, "lc0");
+
+    assert_eq!(db.clear_analysis().unwrap(), 2);
+    assert!(db.get_analysis(board.zobrist_hash()).unwrap().is_none());
+}
+
+#[test]
+fn test_empty_engine_is_rejected() {
+    let (db, _temp_dir) = create_test_database();
+    let analysis = analysis_for(&Board::new(), " ", 10, 0);
+
+    let result = db.store_analysis(&analysis);
+    assert!(matches!(result, Err(StorageError::InvalidData { .. })));
+}
+
+#[test]
+fn test_pgn_eval_formatting() {
+    let mut analysis = analysis_for(&Board::new(), "stockfish", 10, 35);
+    assert_eq!(analysis.pgn_eval().as_deref(), Some("0.35"));
+
+    analysis.score_cp = Some(-120);
+    assert_eq!(analysis.pgn_eval().as_deref(), Some("-1.20"));
+
+    analysis.mate_in = Some(-3);
+    assert_eq!(analysis.pgn_eval().as_deref(), Some("#-3"));
+
+    analysis.mate_in = None;
+    analysis.score_cp = None;
+    assert_eq!(analysis.pgn_eval(), None);
+}
+
+#[test]
+fn test_pgn_export_embeds_analysis() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    for chess_move in ["e2e4", "e7e5"] {
+        let message = MoveMessage::new(game.id.clone(), chess_move.to_string(), String::new());
+        db.store_message(
+            game.id.clone(),
+            "Move".to_string(),
+            serde_json::to_string(&message).unwrap(),
+            "sig".to_string(),
+            "my_peer".to_string(),
+        )
+        .unwrap();
+    }
+
+    db.store_analysis(&analysis_for(&board_after_e4(), "stockfish", 20, 35))
+        .unwrap();
+
+    let pgn = export_game_pgn(&db, &game.id, "my_peer").unwrap();
+    assert!(
+        pgn.contains("1. e2e4 {[%eval 0.35]} e7e5 *"),
+        "Unexpected movetext: {pgn}"
+    );
+}
@@ -0,0 +1,52 @@
+use super::create_test_database;
+use mate::storage::purge::PurgeFilter;
+
+#[test]
+fn test_announcements_are_listed_newest_first() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(db.get_announcements_since(0).unwrap().is_empty());
+
+    db.store_announcement("server", "Maintenance at 22:00")
+        .unwrap();
+    let latest = db.store_announcement("server", "Back online").unwrap();
+
+    let announcements = db.get_announcements_since(0).unwrap();
+    let texts: Vec<&str> = announcements.iter().map(|a| a.text.as_str()).collect();
+    assert_eq!(texts, ["Back online", "Maintenance at 22:00"]);
+    assert_eq!(announcements[0], latest);
+    assert_eq!(announcements[0].server_peer_id, "server");
+
+    // Announcements older than the cutoff are left out
+    assert!(db
+        .get_announcements_since(latest.received_at + 1)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_purge_removes_announcements_of_the_server_and_period() {
+    let (db, _temp_dir) = create_test_database();
+    let old = db.store_announcement("server", "Old news").unwrap();
+    db.store_announcement("server", "Back online").unwrap();
+    db.store_announcement("other-server", "Welcome").unwrap();
+    db.with_connection(|conn| {
+        conn.execute(
+            "UPDATE server_announcements SET received_at = 1000 WHERE id = ?1",
+            [old.id],
+        )?;
+        Ok(())
+    })
+    .unwrap();
+
+    let mut filter = PurgeFilter {
+        peer_id: Some("server".to_string()),
+        before: Some(2000),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().announcements, 1);
+
+    filter.before = None;
+    assert_eq!(db.purge(&filter, false).unwrap().announcements, 1);
+    let announcements = db.get_announcements_since(0).unwrap();
+    assert_eq!(announcements.len(), 1);
+    assert_eq!(announcements[0].server_peer_id, "other-server");
+}
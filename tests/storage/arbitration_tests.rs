@@ -0,0 +1,118 @@
+use super::create_test_database;
+use mate::storage::models::ArbitrationRuling;
+use mate::storage::purge::PurgeFilter;
+
+fn moves(list: &[&str]) -> Vec<String> {
+    list.iter().map(|m| m.to_string()).collect()
+}
+
+#[test]
+fn test_begin_and_update_arbitration() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(db.get_arbitration("game-1").unwrap().is_none());
+    assert!(!db.update_arbitration("game-1", &[], "*").unwrap());
+    assert!(db
+        .begin_arbitration("game-1", "", "black", &[], "*")
+        .is_err());
+
+    db.begin_arbitration("game-1", "white", "black", &moves(&["e2e4"]), "*")
+        .unwrap();
+    let arbitration = db.get_arbitration("game-1").unwrap().unwrap();
+    assert_eq!(arbitration.white, "white");
+    assert_eq!(arbitration.black, "black");
+    assert_eq!(arbitration.moves, ["e2e4"]);
+    assert_eq!(arbitration.result, "*");
+
+    assert!(db
+        .update_arbitration("game-1", &moves(&["e2e4", "e7e5"]), "1-0")
+        .unwrap());
+    let arbitration = db.get_arbitration("game-1").unwrap().unwrap();
+    assert_eq!(arbitration.moves, ["e2e4", "e7e5"]);
+    assert_eq!(arbitration.result, "1-0");
+
+    // Joining again through the other player keeps the players
+    db.begin_arbitration("game-1", "other", "players", &moves(&["e2e4"]), "*")
+        .unwrap();
+    let arbitration = db.get_arbitration("game-1").unwrap().unwrap();
+    assert_eq!(arbitration.white, "white");
+    assert_eq!(arbitration.moves, ["e2e4"]);
+    assert_eq!(db.get_arbitrations().unwrap().len(), 1);
+}
+
+#[test]
+fn test_reconcile_keeps_the_longer_agreeing_moves() {
+    let (db, _temp_dir) = create_test_database();
+    db.begin_arbitration("game-1", "white", "black", &moves(&["e2e4", "e7e5"]), "*")
+        .unwrap();
+    let arbitration = db.get_arbitration("game-1").unwrap().unwrap();
+
+    let behind = moves(&["e2e4"]);
+    assert_eq!(arbitration.reconcile(&behind).unwrap(), ["e2e4", "e7e5"]);
+    let ahead = moves(&["e2e4", "e7e5", "g1f3"]);
+    assert_eq!(arbitration.reconcile(&ahead).unwrap(), ahead.as_slice());
+    assert!(arbitration.reconcile(&moves(&["d2d4"])).is_none());
+}
+
+#[test]
+fn test_rulings_are_kept_with_their_arbitration() {
+    let (db, _temp_dir) = create_test_database();
+    db.begin_arbitration("game-1", "white", "black", &moves(&["e2e4"]), "*")
+        .unwrap();
+    let ruling = ArbitrationRuling {
+        game_id: "game-1".to_string(),
+        claimant: "black".to_string(),
+        ply: 1,
+        chess_move: Some("e2e4".to_string()),
+        upheld: false,
+        reason: "e2e4 was legal".to_string(),
+        ruled_at: 100,
+    };
+    db.record_arbiter_ruling(&ruling).unwrap();
+    let rulings = db.get_arbiter_rulings("game-1").unwrap();
+    assert_eq!(rulings.len(), 1);
+    assert_eq!(rulings[0], ruling);
+    assert!(db.get_arbiter_rulings("game-2").unwrap().is_empty());
+
+    // Rulings need a game being arbitrated
+    let stray = ArbitrationRuling {
+        game_id: "game-2".to_string(),
+        ..ruling
+    };
+    assert!(db.record_arbiter_ruling(&stray).is_err());
+}
+
+#[test]
+fn test_purge_by_peer_removes_its_arbitrations_and_rulings() {
+    let (db, _temp_dir) = create_test_database();
+    db.begin_arbitration("game-1", "white", "black", &[], "*")
+        .unwrap();
+    db.begin_arbitration("game-2", "other", "white", &[], "*")
+        .unwrap();
+    db.begin_arbitration("game-3", "other", "players", &[], "*")
+        .unwrap();
+    db.record_arbiter_ruling(&ArbitrationRuling {
+        game_id: "game-1".to_string(),
+        claimant: "black".to_string(),
+        ply: 1,
+        chess_move: Some("e2e5".to_string()),
+        upheld: true,
+        reason: "illegal move".to_string(),
+        ruled_at: 1_700_000_000,
+    })
+    .unwrap();
+
+    // The games were joined after the cutoff
+    let mut filter = PurgeFilter {
+        peer_id: Some("white".to_string()),
+        before: Some(1000),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().arbitrations, 0);
+
+    filter.before = None;
+    let summary = db.purge(&filter, false).unwrap();
+    assert_eq!((summary.arbitrations, summary.rulings), (2, 1));
+    assert!(db.get_arbiter_rulings("game-1").unwrap().is_empty());
+    let arbitrations = db.get_arbitrations().unwrap();
+    assert_eq!(arbitrations.len(), 1);
+    assert_eq!(arbitrations[0].game_id, "game-3");
+}
@@ -0,0 +1,34 @@
+use super::create_test_database;
+use mate::storage::PlayerColor;
+
+#[test]
+fn test_checkpoint_replaces_earlier_one() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+    assert!(db.get_game_checkpoint(&game.id).unwrap().is_none());
+
+    db.save_game_checkpoint(&game.id, 50, "fen-50", "moves-50", "board-50")
+        .unwrap();
+    let saved = db
+        .save_game_checkpoint(&game.id, 100, "fen-100", "moves-100", "board-100")
+        .unwrap();
+
+    let checkpoint = db.get_game_checkpoint(&game.id).unwrap().unwrap();
+    assert_eq!(checkpoint, saved);
+    assert_eq!(checkpoint.ply, 100);
+    assert_eq!(checkpoint.fen, "fen-100");
+
+    assert!(db.delete_game_checkpoint(&game.id).unwrap());
+    assert!(!db.delete_game_checkpoint(&game.id).unwrap());
+    assert!(db.get_game_checkpoint(&game.id).unwrap().is_none());
+}
+
+#[test]
+fn test_checkpoint_requires_existing_game() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(db
+        .save_game_checkpoint("missing", 50, "fen", "moves", "board")
+        .is_err());
+}
@@ -0,0 +1,50 @@
+use super::create_test_database;
+use mate::storage::{Database, PlayerColor};
+use tempfile::TempDir;
+
+/// Create a test database with one game
+fn create_database_with_game() -> (Database, TempDir, String) {
+    let (db, temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+    (db, temp_dir, game.id)
+}
+
+#[test]
+fn test_consultation_window_can_be_changed() {
+    let (db, _temp_dir, game_id) = create_database_with_game();
+    assert!(db.get_consultation(&game_id).unwrap().is_none());
+    assert!(db.open_consultation(&game_id, 0).is_err());
+
+    db.open_consultation(&game_id, 300).unwrap();
+    db.open_consultation(&game_id, 60).unwrap();
+
+    let consultation = db.get_consultation(&game_id).unwrap().unwrap();
+    assert_eq!(consultation.window_secs, 60);
+    assert_eq!(db.get_consultations().unwrap().len(), 1);
+    // Only games that exist can be put to a vote
+    assert!(db.open_consultation("no-such-game", 60).is_err());
+}
+
+#[test]
+fn test_later_vote_replaces_the_voters_earlier_one() {
+    let (db, _temp_dir, game_id) = create_database_with_game();
+    db.open_consultation(&game_id, 300).unwrap();
+    assert!(db.add_voter(&game_id, "alice").unwrap());
+    assert!(!db.add_voter(&game_id, "alice").unwrap());
+    assert!(db.add_voter(&game_id, "bob").unwrap());
+    assert_eq!(db.get_voters(&game_id).unwrap(), ["alice", "bob"]);
+
+    db.cast_vote(&game_id, "alice", "e4").unwrap();
+    db.cast_vote(&game_id, "alice", "d4").unwrap();
+    db.cast_vote(&game_id, "bob", "d4").unwrap();
+
+    let votes = db.get_votes(&game_id, 0).unwrap();
+    assert_eq!(votes.len(), 2);
+    assert!(votes.iter().all(|vote| vote.chess_move == "d4"));
+    assert!(db.get_votes(&game_id, i64::MAX).unwrap().is_empty());
+
+    assert_eq!(db.clear_votes(&game_id).unwrap(), 2);
+    assert!(db.get_votes(&game_id, 0).unwrap().is_empty());
+}
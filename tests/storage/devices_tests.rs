@@ -0,0 +1,225 @@
+use super::create_test_database;
+use mate::storage::devices::{SYNC_ALIAS, SYNC_GAME, SYNC_SETTING};
+use mate::storage::models::{GameIndexEntry, PlayerColor, SyncEntry};
+use mate::storage::purge::PurgeFilter;
+use mate::storage::Database;
+
+fn entry(kind: &str, key: &str, value: Option<&str>, updated_at: i64) -> SyncEntry {
+    SyncEntry {
+        kind: kind.to_string(),
+        key: key.to_string(),
+        value: value.map(str::to_string),
+        updated_at,
+        seq: 0,
+    }
+}
+
+#[test]
+fn test_pairing_code_is_used_once_and_expires() {
+    let (db, _temp_dir) = create_test_database();
+    let now = Database::current_timestamp();
+    db.create_pairing_code("7KQ4-MZ2P", now + 600).unwrap();
+    db.create_pairing_code("OLD0-CODE", now - 1).unwrap();
+
+    assert!(!db.take_pairing_code("WRONG-CODE").unwrap());
+    assert!(db.take_pairing_code("7KQ4-MZ2P").unwrap());
+    assert!(!db.take_pairing_code("7KQ4-MZ2P").unwrap());
+    assert!(!db.take_pairing_code("OLD0-CODE").unwrap());
+}
+
+#[test]
+fn test_link_update_and_unlink_device() {
+    let (db, _temp_dir) = create_test_database();
+    let device = db.link_device("laptop_peer", "192.0.2.10:8080").unwrap();
+    assert_eq!(device.address, "192.0.2.10:8080");
+    assert_eq!((device.received_through, device.sent_through), (0, 0));
+    assert_eq!(device.last_synced_at, None);
+
+    let relinked = db.link_device("laptop_peer", "192.0.2.11:8080").unwrap();
+    assert_eq!(relinked.address, "192.0.2.11:8080");
+    assert_eq!(relinked.linked_at, device.linked_at);
+
+    db.record_device_sync("laptop_peer", 5, 3).unwrap();
+    db.record_device_sync("laptop_peer", 2, 0).unwrap();
+    let synced = db.get_linked_device("laptop_peer").unwrap().unwrap();
+    assert_eq!((synced.received_through, synced.sent_through), (5, 3));
+    assert!(synced.last_synced_at.is_some());
+
+    assert!(db.link_device("  ", "192.0.2.12:8080").is_err());
+    assert!(db.unlink_device("laptop_peer").unwrap());
+    assert!(!db.unlink_device("laptop_peer").unwrap());
+    assert!(db.get_linked_devices().unwrap().is_empty());
+}
+
+#[test]
+fn test_latest_change_wins() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(db
+        .record_sync_change(SYNC_SETTING, "display.theme", Some("dark"), 100)
+        .unwrap());
+    assert!(!db
+        .record_sync_change(SYNC_SETTING, "display.theme", Some("dark"), 200)
+        .unwrap());
+
+    let applied = db
+        .apply_sync_changes(&[
+            entry(SYNC_SETTING, "display.theme", Some("light"), 50),
+            entry(SYNC_SETTING, "display.locale", Some("de"), 50),
+        ])
+        .unwrap();
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied[0].key, "display.locale");
+
+    let applied = db
+        .apply_sync_changes(&[entry(SYNC_SETTING, "display.theme", Some("light"), 150)])
+        .unwrap();
+    assert_eq!(applied.len(), 1);
+    let pending: Vec<_> = db
+        .pending_sync_settings()
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.key, e.value))
+        .collect();
+    assert_eq!(
+        pending,
+        vec![
+            ("display.locale".to_string(), Some("de".to_string())),
+            ("display.theme".to_string(), Some("light".to_string())),
+        ]
+    );
+
+    // An older local change no longer counts
+    assert!(!db
+        .record_sync_change(SYNC_SETTING, "display.theme", Some("dark"), 120)
+        .unwrap());
+    db.clear_pending_sync_setting("display.theme").unwrap();
+    assert_eq!(db.pending_sync_settings().unwrap().len(), 1);
+}
+
+#[test]
+fn test_received_aliases_update_the_address_book() {
+    let (db, _temp_dir) = create_test_database();
+    let applied = db
+        .apply_sync_changes(&[
+            entry(SYNC_ALIAS, "alice", Some("alice_peer"), 100),
+            entry(SYNC_ALIAS, "Not Valid", Some("bob_peer"), 100),
+        ])
+        .unwrap();
+    assert_eq!(applied.len(), 1);
+    assert_eq!(
+        db.resolve_peer_alias("alice").unwrap().as_deref(),
+        Some("alice_peer")
+    );
+    // Already in step with the journal
+    assert_eq!(db.record_address_book_changes().unwrap(), 0);
+
+    db.apply_sync_changes(&[entry(SYNC_ALIAS, "alice", None, 200)])
+        .unwrap();
+    assert_eq!(db.resolve_peer_alias("alice").unwrap(), None);
+}
+
+#[test]
+fn test_address_book_changes_are_journaled_with_removals() {
+    let (db, _temp_dir) = create_test_database();
+    db.set_peer_alias("alice", "alice_peer").unwrap();
+    db.set_peer_alias("bob", "bob_peer").unwrap();
+    assert_eq!(db.record_address_book_changes().unwrap(), 2);
+    assert_eq!(db.record_address_book_changes().unwrap(), 0);
+
+    db.remove_peer_alias("bob").unwrap();
+    assert_eq!(db.record_address_book_changes().unwrap(), 1);
+    let entries: Vec<_> = db
+        .sync_entries(SYNC_ALIAS)
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.key, e.value))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            ("alice".to_string(), Some("alice_peer".to_string())),
+            ("bob".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_device_sync_sends_changes_after_the_device_position() {
+    let (db, _temp_dir) = create_test_database();
+    db.record_sync_change(SYNC_SETTING, "display.theme", Some("dark"), 100)
+        .unwrap();
+    db.record_sync_change(SYNC_SETTING, "display.locale", Some("fr"), 100)
+        .unwrap();
+    let head = db.sync_journal_head().unwrap();
+
+    let sync = db.device_sync(0, 7).unwrap();
+    assert_eq!(sync.since, 7);
+    assert_eq!(sync.through, head);
+    assert_eq!(sync.changes.len(), 2);
+
+    let sync = db.device_sync(head, 7).unwrap();
+    assert!(sync.changes.is_empty());
+    assert_eq!(sync.through, head);
+
+    // Changing an entry moves it to the end of the journal
+    db.record_sync_change(SYNC_SETTING, "display.theme", Some("light"), 200)
+        .unwrap();
+    let sync = db.device_sync(head, 7).unwrap();
+    assert_eq!(sync.changes.len(), 1);
+    assert_eq!(sync.changes[0].value.as_deref(), Some("light"));
+    assert!(sync.through > head);
+}
+
+#[test]
+fn test_game_index_lists_games_of_other_devices() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    assert_eq!(db.record_game_index_changes("this_device").unwrap(), 1);
+    assert_eq!(db.record_game_index_changes("this_device").unwrap(), 0);
+
+    let remote = GameIndexEntry {
+        device: "laptop_peer".to_string(),
+        opponent: "carol_peer".to_string(),
+        my_color: "black".to_string(),
+        status: "active".to_string(),
+        result: None,
+        created_at: 100,
+    };
+    db.apply_sync_changes(&[entry(
+        SYNC_GAME,
+        "remote_game",
+        Some(&serde_json::to_string(&remote).unwrap()),
+        100,
+    )])
+    .unwrap();
+
+    let games = db.linked_device_games("this_device").unwrap();
+    assert_eq!(games, vec![("remote_game".to_string(), remote)]);
+    assert_eq!(db.sync_entries(SYNC_GAME).unwrap().len(), 2);
+    assert!(db
+        .sync_entries(SYNC_GAME)
+        .unwrap()
+        .iter()
+        .any(|e| e.key == game.id));
+}
+
+#[test]
+fn test_purge_by_peer_unlinks_the_device() {
+    let (db, _temp_dir) = create_test_database();
+    db.link_device("laptop-key", "192.168.1.20:8080").unwrap();
+    db.link_device("phone-key", "192.168.1.21:8080").unwrap();
+
+    // A date-limited purge keeps the link
+    let mut filter = PurgeFilter {
+        peer_id: Some("laptop-key".to_string()),
+        before: Some(i64::MAX),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().linked_devices, 0);
+
+    filter.before = None;
+    assert_eq!(db.purge(&filter, false).unwrap().linked_devices, 1);
+    assert!(db.get_linked_device("laptop-key").unwrap().is_none());
+    assert!(db.get_linked_device("phone-key").unwrap().is_some());
+}
@@ -0,0 +1,165 @@
+use super::create_test_database;
+use mate::storage::games::{GameFilter, GameSort};
+use mate::storage::{Database, GameStatus, PlayerColor};
+
+/// Create a game with fixed timestamps so ordering is deterministic
+fn create_game(
+    db: &Database,
+    opponent: &str,
+    status: GameStatus,
+    created_at: i64,
+    updated_at: i64,
+) -> String {
+    let game = db
+        .create_game(opponent.to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&game.id, status).unwrap();
+    db.with_connection(|conn| {
+        conn.execute(
+            "UPDATE games SET created_at = ?1, updated_at = ?2 WHERE id = ?3",
+            (created_at, updated_at, &game.id),
+        )?;
+        Ok(())
+    })
+    .unwrap();
+    game.id
+}
+
+/// Three games: alice (active), bob (completed), alice (pending)
+fn create_games(db: &Database) -> Vec<String> {
+    vec![
+        create_game(db, "12D3KooWAlice", GameStatus::Active, 100, 400),
+        create_game(db, "12D3KooWBob", GameStatus::Completed, 200, 300),
+        create_game(db, "12D3KooWAlice", GameStatus::Pending, 300, 200),
+    ]
+}
+
+fn ids(db: &Database, filter: &GameFilter) -> Vec<String> {
+    db.query_games(filter)
+        .unwrap()
+        .into_iter()
+        .map(|game| game.id)
+        .collect()
+}
+
+#[test]
+fn test_default_filter_returns_every_game_by_update_time() {
+    let (db, _temp_dir) = create_test_database();
+    let games = create_games(&db);
+
+    assert_eq!(ids(&db, &GameFilter::default()), games);
+    assert_eq!(db.count_games(&GameFilter::default()).unwrap(), 3);
+}
+
+#[test]
+fn test_filter_by_status_opponent_and_since() {
+    let (db, _temp_dir) = create_test_database();
+    let games = create_games(&db);
+
+    let filter = GameFilter {
+        statuses: vec![GameStatus::Active, GameStatus::Pending],
+        ..GameFilter::default()
+    };
+    assert_eq!(ids(&db, &filter), vec![games[0].clone(), games[2].clone()]);
+
+    let filter = GameFilter {
+        opponent: Some("12D3KooWB".to_string()),
+        ..GameFilter::default()
+    };
+    assert_eq!(ids(&db, &filter), vec![games[1].clone()]);
+
+    // Prefix matching is exact, so LIKE wildcards match nothing
+    let filter = GameFilter {
+        opponent: Some("12D3%".to_string()),
+        ..GameFilter::default()
+    };
+    assert!(ids(&db, &filter).is_empty());
+
+    let filter = GameFilter {
+        since: Some(300),
+        ..GameFilter::default()
+    };
+    assert_eq!(ids(&db, &filter), vec![games[0].clone(), games[1].clone()]);
+
+    let filter = GameFilter {
+        statuses: vec![GameStatus::Completed],
+        opponent: Some("12D3KooWAlice".to_string()),
+        ..GameFilter::default()
+    };
+    assert!(ids(&db, &filter).is_empty());
+    assert_eq!(db.count_games(&filter).unwrap(), 0);
+}
+
+#[test]
+fn test_filter_by_tag() {
+    let (db, _temp_dir) = create_test_database();
+    let games = create_games(&db);
+    db.add_game_tag(&games[1], "blitz").unwrap();
+
+    let filter = GameFilter {
+        tag: Some("Blitz".to_string()),
+        ..GameFilter::default()
+    };
+    assert_eq!(ids(&db, &filter), vec![games[1].clone()]);
+}
+
+#[test]
+fn test_sort_orders() {
+    let (db, _temp_dir) = create_test_database();
+    let games = create_games(&db);
+
+    let sorted = |sort| {
+        ids(
+            &db,
+            &GameFilter {
+                sort,
+                ..GameFilter::default()
+            },
+        )
+    };
+
+    assert_eq!(
+        sorted(GameSort::Created),
+        vec![games[2].clone(), games[1].clone(), games[0].clone()]
+    );
+    assert_eq!(
+        sorted(GameSort::Opponent),
+        vec![games[0].clone(), games[2].clone(), games[1].clone()]
+    );
+    assert_eq!(
+        sorted(GameSort::Status),
+        vec![games[2].clone(), games[0].clone(), games[1].clone()]
+    );
+
+    assert_eq!("created".parse::<GameSort>(), Ok(GameSort::Created));
+    assert!("size".parse::<GameSort>().is_err());
+}
+
+#[test]
+fn test_limit_and_offset_page_through_games() {
+    let (db, _temp_dir) = create_test_database();
+    let games = create_games(&db);
+
+    let page = |offset| {
+        ids(
+            &db,
+            &GameFilter {
+                limit: Some(2),
+                offset,
+                ..GameFilter::default()
+            },
+        )
+    };
+
+    assert_eq!(page(0), vec![games[0].clone(), games[1].clone()]);
+    assert_eq!(page(2), vec![games[2].clone()]);
+    assert!(page(4).is_empty());
+
+    // The count ignores paging
+    let filter = GameFilter {
+        limit: Some(1),
+        offset: 1,
+        ..GameFilter::default()
+    };
+    assert_eq!(db.count_games(&filter).unwrap(), 3);
+}
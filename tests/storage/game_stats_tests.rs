@@ -0,0 +1,232 @@
+use super::create_test_database;
+use mate::messages::chess::GameInvite;
+use mate::storage::game_stats::StatsPeriod;
+use mate::storage::models::{GameResult, TimeControl};
+use mate::storage::{Database, PlayerColor};
+
+/// Create a game with `plies` moves, finished with `result` at `completed_at`
+fn create_game(
+    db: &Database,
+    color: PlayerColor,
+    time_control: Option<(u64, u64)>,
+    plies: usize,
+    result: Option<(GameResult, i64)>,
+) -> String {
+    let metadata = time_control.map(|(initial_time_ms, increment_ms)| {
+        serde_json::json!({
+            "time_control": { "initial_time_ms": initial_time_ms, "increment_ms": increment_ms },
+        })
+    });
+    let game = db
+        .create_game("12D3KooWBob".to_string(), color, metadata)
+        .unwrap();
+    for ply in 0..plies {
+        let content = serde_json::json!({
+            "game_id": game.id,
+            "chess_move": format!("move{ply}"),
+            "board_state_hash": "hash",
+        });
+        db.store_message(
+            game.id.clone(),
+            "move".to_string(),
+            content.to_string(),
+            String::new(),
+            "test_peer_12345678".to_string(),
+        )
+        .unwrap();
+    }
+    if let Some((result, completed_at)) = result {
+        db.update_game_result(&game.id, result).unwrap();
+        db.with_connection(|conn| {
+            conn.execute(
+                "UPDATE games SET completed_at = ?1 WHERE id = ?2",
+                (completed_at, &game.id),
+            )?;
+            Ok(())
+        })
+        .unwrap();
+    }
+    game.id
+}
+
+// 2026-01-15 and 2026-02-10, both midday UTC
+const JANUARY: i64 = 1_768_478_400;
+const FEBRUARY: i64 = 1_770_724_800;
+
+#[test]
+fn test_finished_games_are_tallied_by_month_and_color() {
+    let (db, _temp_dir) = create_test_database();
+    create_game(
+        &db,
+        PlayerColor::White,
+        None,
+        40,
+        Some((GameResult::Win, JANUARY)),
+    );
+    create_game(
+        &db,
+        PlayerColor::Black,
+        None,
+        20,
+        Some((GameResult::Loss, JANUARY)),
+    );
+    create_game(
+        &db,
+        PlayerColor::White,
+        None,
+        30,
+        Some((GameResult::Draw, FEBRUARY)),
+    );
+    create_game(&db, PlayerColor::White, None, 10, None);
+
+    let periods = db.results_by_period(StatsPeriod::Month).unwrap();
+    let names: Vec<&str> = periods.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["2026-01", "2026-02"],
+        "unfinished games are left out"
+    );
+    let january = &periods[0].1;
+    assert_eq!(
+        (january.games, january.wins, january.draws, january.losses),
+        (2, 1, 0, 1)
+    );
+    assert_eq!(january.plies, 60);
+
+    let years = db.results_by_period(StatsPeriod::Year).unwrap();
+    assert_eq!(years.len(), 1);
+    assert_eq!(years[0].0, "2026");
+
+    let colors = db.results_by_color().unwrap();
+    assert_eq!(colors[0].0, PlayerColor::White);
+    assert_eq!((colors[0].1.wins, colors[0].1.draws), (1, 1));
+    assert_eq!(colors[1].0, PlayerColor::Black);
+    assert_eq!(colors[1].1.losses, 1);
+
+    let totals = db.result_totals().unwrap();
+    assert_eq!(totals.games, 3);
+    assert_eq!(totals.average_moves(), Some(15.0));
+    assert_eq!(totals.score(), Some(50.0));
+}
+
+#[test]
+fn test_time_controls_are_ordered_by_how_often_they_are_played() {
+    let (db, _temp_dir) = create_test_database();
+    let blitz = Some((300_000, 3_000));
+    create_game(
+        &db,
+        PlayerColor::White,
+        blitz,
+        0,
+        Some((GameResult::Win, JANUARY)),
+    );
+    create_game(
+        &db,
+        PlayerColor::Black,
+        blitz,
+        0,
+        Some((GameResult::Win, JANUARY)),
+    );
+    create_game(
+        &db,
+        PlayerColor::White,
+        None,
+        0,
+        Some((GameResult::Loss, JANUARY)),
+    );
+
+    let time_controls = db.results_by_time_control().unwrap();
+    assert_eq!(time_controls.len(), 2);
+    let (most_played, tally) = &time_controls[0];
+    let most_played: &TimeControl = most_played.as_ref().unwrap();
+    assert_eq!(
+        (most_played.initial_time_ms, most_played.increment_ms),
+        (300_000, 3_000)
+    );
+    assert_eq!(tally.games, 2);
+    assert!(time_controls[1].0.is_none());
+}
+
+#[test]
+fn test_no_finished_games_tally_to_nothing() {
+    let (db, _temp_dir) = create_test_database();
+    create_game(&db, PlayerColor::White, None, 4, None);
+
+    let totals = db.result_totals().unwrap();
+    assert_eq!(totals.games, 0);
+    assert_eq!(totals.score(), None);
+    assert_eq!(totals.average_moves(), None);
+    assert!(db.results_by_period(StatsPeriod::Week).unwrap().is_empty());
+}
+
+#[test]
+fn test_only_decided_rated_games_are_rated_results() {
+    let (db, _temp_dir) = create_test_database();
+    let finish = |id: &str, result: GameResult, completed_at: i64| {
+        db.update_game_result(id, result).unwrap();
+        db.with_connection(|conn| {
+            conn.execute(
+                "UPDATE games SET completed_at = ?1 WHERE id = ?2",
+                (completed_at, id),
+            )?;
+            Ok(())
+        })
+        .unwrap();
+    };
+
+    // Rated by the inviter's metadata, finished second
+    let inviter = db
+        .create_game(
+            "12D3KooWCarol".to_string(),
+            PlayerColor::White,
+            Some(serde_json::json!({ "rated": true })),
+        )
+        .unwrap();
+    finish(&inviter.id, GameResult::Loss, FEBRUARY);
+
+    // Rated by the invitation received, finished first
+    let invitee = db
+        .create_game("12D3KooWBob".to_string(), PlayerColor::Black, None)
+        .unwrap();
+    let invite = GameInvite::new(invitee.id.clone(), None).with_rated();
+    db.store_message(
+        invitee.id.clone(),
+        "GameInvite".to_string(),
+        serde_json::to_string(&invite).unwrap(),
+        String::new(),
+        "12D3KooWBob".to_string(),
+    )
+    .unwrap();
+    finish(&invitee.id, GameResult::Win, JANUARY);
+
+    // Casual, abandoned, and unfinished games are left out
+    create_game(
+        &db,
+        PlayerColor::White,
+        None,
+        0,
+        Some((GameResult::Win, JANUARY)),
+    );
+    let abandoned = db
+        .create_game(
+            "12D3KooWDave".to_string(),
+            PlayerColor::White,
+            Some(serde_json::json!({ "rated": true })),
+        )
+        .unwrap();
+    finish(&abandoned.id, GameResult::Abandoned, JANUARY);
+    db.create_game(
+        "12D3KooWErin".to_string(),
+        PlayerColor::White,
+        Some(serde_json::json!({ "rated": true })),
+    )
+    .unwrap();
+
+    assert_eq!(
+        db.rated_results().unwrap(),
+        vec![
+            ("12D3KooWBob".to_string(), GameResult::Win),
+            ("12D3KooWCarol".to_string(), GameResult::Loss),
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+use super::create_test_database;
+use mate::cli::export_game_pgn;
+use mate::storage::{PlayerColor, StorageError};
+
+#[test]
+fn test_add_and_remove_game_tags() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    assert!(db.add_game_tag(&game.id, "Blitz").unwrap());
+    assert!(db.add_game_tag(&game.id, "  endgame ").unwrap());
+    // Tags are normalized, so re-adding in a different case is a no-op
+    assert!(!db.add_game_tag(&game.id, "BLITZ").unwrap());
+
+    assert_eq!(
+        db.get_game_tags(&game.id).unwrap(),
+        vec!["blitz", "endgame"]
+    );
+
+    assert!(db.remove_game_tag(&game.id, "blitz").unwrap());
+    assert!(!db.remove_game_tag(&game.id, "blitz").unwrap());
+    assert_eq!(db.get_game_tags(&game.id).unwrap(), vec!["endgame"]);
+}
+
+#[test]
+fn test_invalid_tags_are_rejected() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    for tag in ["", "   ", "has space", "semi;colon", &"x".repeat(33)] {
+        let result = db.add_game_tag(&game.id, tag);
+        assert!(
+            matches!(result, Err(StorageError::InvalidData { .. })),
+            "tag {tag:?} should be rejected"
+        );
+    }
+
+    assert!(matches!(
+        db.add_game_tag("missing-game", "blitz"),
+        Err(StorageError::GameNotFound { .. })
+    ));
+}
+
+#[test]
+fn test_games_filtered_by_tag() {
+    let (db, _temp_dir) = create_test_database();
+    let tagged = db
+        .create_game("opponent1".to_string(), PlayerColor::White, None)
+        .unwrap();
+    let untagged = db
+        .create_game("opponent2".to_string(), PlayerColor::Black, None)
+        .unwrap();
+
+    db.add_game_tag(&tagged.id, "tournament").unwrap();
+
+    let games = db.get_games_with_tag("tournament").unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].id, tagged.id);
+    assert!(games.iter().all(|g| g.id != untagged.id));
+
+    assert!(db.get_games_with_tag("unused").unwrap().is_empty());
+    assert_eq!(
+        db.get_all_tags().unwrap(),
+        vec![("tournament".to_string(), 1)]
+    );
+}
+
+#[test]
+fn test_game_notes_lifecycle() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    assert!(db.get_game_note(&game.id).unwrap().is_none());
+
+    db.set_game_note(&game.id, "First thoughts").unwrap();
+    db.set_game_note(&game.id, "Missed the fork").unwrap();
+    let note = db.get_game_note(&game.id).unwrap().unwrap();
+    assert_eq!(note.content, "Missed the fork");
+
+    assert!(db.delete_game_note(&game.id).unwrap());
+    assert!(!db.delete_game_note(&game.id).unwrap());
+    assert!(db.get_game_note(&game.id).unwrap().is_none());
+}
+
+#[test]
+fn test_tags_and_notes_removed_with_game() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    db.add_game_tag(&game.id, "blitz").unwrap();
+    db.set_game_note(&game.id, "note").unwrap();
+    db.delete_game(&game.id).unwrap();
+
+    assert!(db.get_game_tags(&game.id).unwrap().is_empty());
+    assert!(db.get_game_note(&game.id).unwrap().is_none());
+    assert!(db.get_all_tags().unwrap().is_empty());
+}
+
+#[test]
+fn test_pgn_export_includes_tags_and_note() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::Black, None)
+        .unwrap();
+
+    db.add_game_tag(&game.id, "blitz").unwrap();
+    db.add_game_tag(&game.id, "club").unwrap();
+    db.set_game_note(&game.id, "Played the \"Dragon\"").unwrap();
+
+    let pgn = export_game_pgn(&db, &game.id, "my_peer").unwrap();
+
+    assert!(pgn.contains("[White \"opponent_peer\"]"));
+    assert!(pgn.contains("[Black \"my_peer\"]"));
+    assert!(pgn.contains("[Result \"*\"]"));
+    assert!(pgn.contains("[Tags \"blitz,club\"]"));
+    assert!(pgn.contains("[Note \"Played the \\\"Dragon\\\"\"]"));
+}
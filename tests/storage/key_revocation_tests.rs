@@ -0,0 +1,51 @@
+use super::create_test_database;
+use mate::storage::purge::PurgeFilter;
+
+#[test]
+fn test_key_revocation_is_recorded_once() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(!db.is_key_revoked("stolen-key").unwrap());
+
+    assert!(db
+        .record_key_revocation("stolen-key", 1_700_000_000, "lost", Some("new-key"), "{}")
+        .unwrap());
+    // A second record for the same key keeps the first one
+    assert!(!db
+        .record_key_revocation("stolen-key", 1_700_000_100, "again", None, "{}")
+        .unwrap());
+
+    assert!(db.is_key_revoked("stolen-key").unwrap());
+    assert!(!db.is_key_revoked("new-key").unwrap());
+    let revocation = db.get_key_revocation("stolen-key").unwrap().unwrap();
+    assert_eq!(revocation.issued_at, 1_700_000_000);
+    assert_eq!(revocation.reason, "lost");
+    assert_eq!(revocation.successor_peer_id.as_deref(), Some("new-key"));
+    assert_eq!(db.get_key_revocations().unwrap().len(), 1);
+}
+
+#[test]
+fn test_key_revocation_requires_peer_id() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(db.record_key_revocation("", 0, "", None, "{}").is_err());
+}
+
+#[test]
+fn test_purge_by_peer_removes_its_key_revocation() {
+    let (db, _temp_dir) = create_test_database();
+    db.record_key_revocation("stolen-key", 1_700_000_000, "lost", None, "{}")
+        .unwrap();
+    db.record_key_revocation("other-key", 1_700_000_000, "lost", None, "{}")
+        .unwrap();
+
+    // A date-limited purge keeps it
+    let mut filter = PurgeFilter {
+        peer_id: Some("stolen-key".to_string()),
+        before: Some(i64::MAX),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().revocations, 0);
+
+    filter.before = None;
+    assert_eq!(db.purge(&filter, false).unwrap().revocations, 1);
+    assert!(!db.is_key_revoked("stolen-key").unwrap());
+    assert!(db.is_key_revoked("other-key").unwrap());
+}
@@ -0,0 +1,113 @@
+use super::create_test_database;
+use mate::storage::purge::PurgeFilter;
+use mate::storage::rotations::RotationSummary;
+use mate::storage::{Database, PeerEventType, PlayerColor, StorageError};
+
+/// Create a game against `opponent` with one message from them
+fn create_game_with_message(db: &Database, opponent: &str) -> String {
+    let game = db
+        .create_game(opponent.to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.store_message(
+        game.id.clone(),
+        "Move".to_string(),
+        "{}".to_string(),
+        "sig".to_string(),
+        opponent.to_string(),
+    )
+    .unwrap();
+    game.id
+}
+
+#[test]
+fn test_key_rotation_moves_peer_data() {
+    let (db, _temp_dir) = create_test_database();
+    let rotated = create_game_with_message(&db, "old-key");
+    let other = create_game_with_message(&db, "other-key");
+    db.record_peer_event("old-key", PeerEventType::HandshakeSuccess, None, None)
+        .unwrap();
+    db.set_peer_alias("alice", "old-key").unwrap();
+
+    let summary = db
+        .apply_key_rotation("old-key", "new-key", 1_700_000_000, "{}")
+        .unwrap();
+
+    assert_eq!(
+        summary,
+        RotationSummary {
+            games: 1,
+            messages: 1,
+            peer_events: 1,
+            aliases: 1,
+            already_applied: false,
+        }
+    );
+    assert_eq!(db.get_game(&rotated).unwrap().opponent_peer_id, "new-key");
+    assert_eq!(
+        db.get_messages_for_game(&rotated).unwrap()[0].sender_peer_id,
+        "new-key"
+    );
+    assert_eq!(db.get_peer_summary("new-key").unwrap().total_events(), 1);
+    assert_eq!(db.get_peer_summary("old-key").unwrap().total_events(), 0);
+    assert_eq!(
+        db.resolve_peer_alias("alice").unwrap().as_deref(),
+        Some("new-key")
+    );
+    assert_eq!(db.get_game(&other).unwrap().opponent_peer_id, "other-key");
+
+    let rotations = db.get_key_rotations().unwrap();
+    assert_eq!(rotations.len(), 1);
+    assert_eq!(rotations[0].old_peer_id, "old-key");
+    assert_eq!(rotations[0].new_peer_id, "new-key");
+    assert_eq!(rotations[0].issued_at, 1_700_000_000);
+}
+
+#[test]
+fn test_key_rotation_is_applied_once() {
+    let (db, _temp_dir) = create_test_database();
+    create_game_with_message(&db, "old-key");
+    db.apply_key_rotation("old-key", "new-key", 1_700_000_000, "{}")
+        .unwrap();
+
+    let again = db
+        .apply_key_rotation("old-key", "new-key", 1_700_000_000, "{}")
+        .unwrap();
+    assert!(again.already_applied);
+    assert_eq!(again.total(), 0);
+
+    let conflicting = db.apply_key_rotation("old-key", "third-key", 1_700_000_100, "{}");
+    assert!(matches!(conflicting, Err(StorageError::InvalidData { .. })));
+    assert_eq!(db.get_key_rotations().unwrap().len(), 1);
+}
+
+#[test]
+fn test_key_rotation_to_same_key_is_rejected() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(matches!(
+        db.apply_key_rotation("same-key", "same-key", 1_700_000_000, "{}"),
+        Err(StorageError::InvalidData { .. })
+    ));
+    assert!(db.get_key_rotations().unwrap().is_empty());
+}
+
+#[test]
+fn test_purge_by_peer_removes_its_key_rotations() {
+    let (db, _temp_dir) = create_test_database();
+    db.apply_key_rotation("old-key", "new-key", 1_700_000_000, "{}")
+        .unwrap();
+    db.apply_key_rotation("other-old-key", "other-new-key", 1_700_000_000, "{}")
+        .unwrap();
+
+    // A date-limited purge keeps them
+    let mut filter = PurgeFilter {
+        peer_id: Some("new-key".to_string()),
+        before: Some(i64::MAX),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().key_rotations, 0);
+
+    filter.before = None;
+    assert_eq!(db.purge(&filter, false).unwrap().key_rotations, 1);
+    let rotations = db.get_key_rotations().unwrap();
+    assert_eq!(rotations.len(), 1);
+    assert_eq!(rotations[0].old_peer_id, "other-old-key");
+}
@@ -4,6 +4,37 @@
 //! to ensure proper cleanup of environment variables and temporary directories,
 //! preventing test interference and flaky behavior.
 
+use mate::storage::Database;
+use tempfile::TempDir;
+
+pub mod analysis_tests;
+pub mod announcement_tests;
+pub mod arbitration_tests;
+pub mod checkpoint_tests;
+pub mod consultation_tests;
+pub mod devices_tests;
+pub mod game_query_tests;
+pub mod game_stats_tests;
+pub mod game_tags_tests;
+pub mod key_revocation_tests;
+pub mod key_rotation_tests;
+pub mod moderation_tests;
+pub mod opening_lines_tests;
+pub mod peer_alias_tests;
+pub mod peer_history_tests;
+pub mod peer_verification_tests;
+pub mod purge_tests;
 pub mod storage_error_tests;
 pub mod storage_integration_tests;
 pub mod storage_tests;
+pub mod study_tests;
+pub mod transaction_tests;
+
+/// Create a database in its own temporary directory
+pub fn create_test_database() -> (Database, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let db_path = temp_dir.path().join("database.sqlite");
+    let db = Database::new_with_path("test_peer_12345678", &db_path)
+        .expect("Failed to create test database");
+    (db, temp_dir)
+}
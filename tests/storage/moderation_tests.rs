@@ -0,0 +1,89 @@
+use super::create_test_database;
+use mate::storage::purge::PurgeFilter;
+
+#[test]
+fn test_reports_are_listed_newest_first_and_forwarding_is_kept() {
+    let (db, _temp_dir) = create_test_database();
+
+    let first = db
+        .record_peer_report("peer-a", None, "Spam invitations", "[]")
+        .unwrap();
+    let second = db
+        .record_peer_report(
+            "peer-a",
+            Some("peer-c"),
+            "Abusive chat",
+            r#"[{"message_id":1}]"#,
+        )
+        .unwrap();
+    db.record_peer_report("peer-b", None, "Stalling", "[]")
+        .unwrap();
+    db.mark_report_forwarded(first.id, "lobby.example:8080")
+        .unwrap();
+
+    let reports = db.get_peer_reports("peer-a").unwrap();
+    let ids: Vec<i64> = reports.iter().map(|report| report.id).collect();
+    assert_eq!(ids, [second.id, first.id]);
+    assert_eq!(reports[0].reporter_peer_id.as_deref(), Some("peer-c"));
+    assert_eq!(reports[0].evidence, r#"[{"message_id":1}]"#);
+    assert_eq!(
+        reports[1].forwarded_to.as_deref(),
+        Some("lobby.example:8080")
+    );
+}
+
+#[test]
+fn test_report_needs_peer_and_reason() {
+    let (db, _temp_dir) = create_test_database();
+
+    assert!(db.record_peer_report(" ", None, "Spam", "[]").is_err());
+    assert!(db.record_peer_report("peer-a", None, "  ", "[]").is_err());
+    assert!(db.get_peer_reports("peer-a").unwrap().is_empty());
+}
+
+#[test]
+fn test_mute_and_unmute_peer() {
+    let (db, _temp_dir) = create_test_database();
+
+    assert!(db.mute_peer("peer-b").unwrap());
+    assert!(!db.mute_peer("peer-b").unwrap());
+    assert!(db.mute_peer("peer-a").unwrap());
+    assert!(db.is_peer_muted("peer-a").unwrap());
+    assert_eq!(db.get_muted_peers().unwrap(), ["peer-a", "peer-b"]);
+
+    assert!(db.unmute_peer("peer-a").unwrap());
+    assert!(!db.unmute_peer("peer-a").unwrap());
+    assert!(!db.is_peer_muted("peer-a").unwrap());
+    assert_eq!(db.get_muted_peers().unwrap(), ["peer-b"]);
+}
+
+#[test]
+fn test_purge_by_peer_removes_its_reports_and_mute() {
+    let (db, _temp_dir) = create_test_database();
+    db.record_peer_report("peer-a", None, "Spam invitations", "[]")
+        .unwrap();
+    db.record_peer_report("peer-b", Some("peer-a"), "Abusive chat", "[]")
+        .unwrap();
+    db.record_peer_report("peer-b", Some("peer-c"), "Stalling", "[]")
+        .unwrap();
+    db.mute_peer("peer-a").unwrap();
+    db.mute_peer("peer-b").unwrap();
+
+    // The reports were made after the cutoff, and the mute is kept
+    let mut filter = PurgeFilter {
+        peer_id: Some("peer-a".to_string()),
+        before: Some(1000),
+    };
+    let summary = db.purge(&filter, false).unwrap();
+    assert_eq!((summary.reports, summary.mutes), (0, 0));
+
+    // Reports about the peer and those it sent as a reporter both go
+    filter.before = None;
+    let summary = db.purge(&filter, false).unwrap();
+    assert_eq!((summary.reports, summary.mutes), (2, 1));
+    assert!(db.get_peer_reports("peer-a").unwrap().is_empty());
+    let reports = db.get_peer_reports("peer-b").unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].reporter_peer_id.as_deref(), Some("peer-c"));
+    assert_eq!(db.get_muted_peers().unwrap(), ["peer-b"]);
+}
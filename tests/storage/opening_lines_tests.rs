@@ -0,0 +1,90 @@
+use super::create_test_database;
+use mate::storage::models::GameResult;
+use mate::storage::{Database, PlayerColor};
+
+/// Create a game and store its moves in the order given
+fn create_game(
+    db: &Database,
+    color: PlayerColor,
+    result: Option<GameResult>,
+    moves: &[&str],
+) -> String {
+    let game = db
+        .create_game("12D3KooWBob".to_string(), color, None)
+        .unwrap();
+    for chess_move in moves {
+        let content = serde_json::json!({
+            "game_id": game.id,
+            "chess_move": chess_move,
+            "board_state_hash": "hash",
+        });
+        db.store_message(
+            game.id.clone(),
+            "move".to_string(),
+            content.to_string(),
+            String::new(),
+            "test_peer_12345678".to_string(),
+        )
+        .unwrap();
+    }
+    if let Some(result) = result {
+        db.update_game_result(&game.id, result).unwrap();
+    }
+    game.id
+}
+
+#[test]
+fn test_games_are_grouped_by_their_first_moves() {
+    let (db, _temp_dir) = create_test_database();
+    let won = create_game(
+        &db,
+        PlayerColor::White,
+        Some(GameResult::Win),
+        &["e4", "e5", "Nf3"],
+    );
+    let drawn = create_game(
+        &db,
+        PlayerColor::White,
+        Some(GameResult::Draw),
+        &["e4", "e5", "Bc4"],
+    );
+    let ongoing = create_game(&db, PlayerColor::Black, None, &["d4"]);
+    create_game(&db, PlayerColor::White, Some(GameResult::Loss), &[]);
+
+    let lines = db.opening_lines(2, None).unwrap();
+    assert_eq!(lines.len(), 2, "games without moves are left out");
+
+    let main_line = &lines[0];
+    assert_eq!(main_line.moves, vec!["e4", "e5"]);
+    assert_eq!(main_line.games, 2);
+    assert_eq!(
+        (main_line.wins, main_line.draws, main_line.losses),
+        (1, 1, 0)
+    );
+    let mut game_ids = main_line.game_ids.clone();
+    game_ids.sort();
+    let mut expected = vec![won, drawn];
+    expected.sort();
+    assert_eq!(game_ids, expected);
+
+    let short_line = &lines[1];
+    assert_eq!(short_line.moves, vec!["d4"]);
+    assert_eq!(short_line.games, 1);
+    assert_eq!(short_line.game_ids, vec![ongoing]);
+    assert_eq!(
+        (short_line.wins, short_line.draws, short_line.losses),
+        (0, 0, 0)
+    );
+}
+
+#[test]
+fn test_opening_lines_filter_by_color() {
+    let (db, _temp_dir) = create_test_database();
+    create_game(&db, PlayerColor::White, None, &["e4", "c5"]);
+    let black = create_game(&db, PlayerColor::Black, None, &["e4", "e6"]);
+
+    let lines = db.opening_lines(4, Some(PlayerColor::Black)).unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].moves, vec!["e4", "e6"]);
+    assert_eq!(lines[0].game_ids, vec![black]);
+}
@@ -0,0 +1,112 @@
+use super::create_test_database;
+use mate::storage::aliases::normalize_alias;
+use mate::storage::purge::PurgeFilter;
+use mate::storage::StorageError;
+
+#[test]
+fn test_normalize_alias() {
+    assert_eq!(normalize_alias("  Alice ").unwrap(), "alice");
+    assert_eq!(normalize_alias("bob_2-home").unwrap(), "bob_2-home");
+
+    for invalid in [
+        "",
+        "   ",
+        "2bob",
+        "-bob",
+        "bob smith",
+        "bob!",
+        &"a".repeat(33),
+    ] {
+        assert!(
+            matches!(
+                normalize_alias(invalid),
+                Err(StorageError::InvalidData { .. })
+            ),
+            "'{invalid}' should be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_set_resolve_and_remove_alias() {
+    let (db, _temp_dir) = create_test_database();
+
+    let alias = db.set_peer_alias("Alice", "peer-a").unwrap();
+    assert_eq!(alias.alias, "alice");
+    assert_eq!(
+        db.resolve_peer_alias("ALICE").unwrap().as_deref(),
+        Some("peer-a")
+    );
+    assert_eq!(db.resolve_peer_alias("not an alias!").unwrap(), None);
+
+    // Setting an existing alias points it at the new peer
+    db.set_peer_alias("alice", "peer-b").unwrap();
+    assert_eq!(
+        db.resolve_peer_alias("alice").unwrap().as_deref(),
+        Some("peer-b")
+    );
+    assert_eq!(db.get_peer_aliases().unwrap().len(), 1);
+
+    assert!(db.remove_peer_alias("alice").unwrap());
+    assert!(!db.remove_peer_alias("alice").unwrap());
+    assert_eq!(db.resolve_peer_alias("alice").unwrap(), None);
+}
+
+#[test]
+fn test_list_aliases() {
+    let (db, _temp_dir) = create_test_database();
+    db.set_peer_alias("carol", "peer-c").unwrap();
+    db.set_peer_alias("alice", "peer-a").unwrap();
+    db.set_peer_alias("ally", "peer-a").unwrap();
+
+    let names: Vec<String> = db
+        .get_peer_aliases()
+        .unwrap()
+        .into_iter()
+        .map(|a| a.alias)
+        .collect();
+    assert_eq!(names, ["alice", "ally", "carol"]);
+    assert_eq!(
+        db.get_aliases_for_peer("peer-a").unwrap(),
+        ["alice", "ally"]
+    );
+}
+
+#[test]
+fn test_set_alias_requires_peer_id() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(matches!(
+        db.set_peer_alias("alice", "  "),
+        Err(StorageError::InvalidData { .. })
+    ));
+}
+
+#[test]
+fn test_purge_by_peer_removes_aliases() {
+    let (db, _temp_dir) = create_test_database();
+    db.set_peer_alias("alice", "peer-a").unwrap();
+    db.set_peer_alias("bob", "peer-b").unwrap();
+
+    let filter = PurgeFilter {
+        peer_id: Some("peer-a".to_string()),
+        before: None,
+    };
+    assert_eq!(db.purge(&filter, true).unwrap().aliases, 1);
+    assert_eq!(db.purge(&filter, false).unwrap().aliases, 1);
+
+    assert_eq!(db.resolve_peer_alias("alice").unwrap(), None);
+    assert!(db.resolve_peer_alias("bob").unwrap().is_some());
+}
+
+#[test]
+fn test_purge_before_date_keeps_aliases() {
+    let (db, _temp_dir) = create_test_database();
+    db.set_peer_alias("alice", "peer-a").unwrap();
+
+    let filter = PurgeFilter {
+        peer_id: Some("peer-a".to_string()),
+        before: Some(i64::MAX),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().aliases, 0);
+    assert!(db.resolve_peer_alias("alice").unwrap().is_some());
+}
@@ -0,0 +1,129 @@
+use super::create_test_database;
+use mate::storage::{Database, PeerEventType, StorageError};
+
+#[test]
+fn test_record_and_retrieve_peer_events() {
+    let (db, _temp_dir) = create_test_database();
+
+    db.record_peer_event(
+        "peer-a",
+        PeerEventType::HandshakeSuccess,
+        None,
+        Some("127.0.0.1:4000".to_string()),
+    )
+    .unwrap();
+    db.record_peer_event(
+        "peer-a",
+        PeerEventType::ProtocolViolation,
+        Some("Invalid signature".to_string()),
+        Some("127.0.0.1:4000".to_string()),
+    )
+    .unwrap();
+    db.record_peer_event("peer-b", PeerEventType::HandshakeSuccess, None, None)
+        .unwrap();
+
+    let events = db.get_peer_events("peer-a", 10).unwrap();
+    assert_eq!(events.len(), 2);
+    // Newest first
+    assert_eq!(events[0].event_type, PeerEventType::ProtocolViolation);
+    assert_eq!(events[0].details.as_deref(), Some("Invalid signature"));
+    assert_eq!(events[1].event_type, PeerEventType::HandshakeSuccess);
+
+    assert_eq!(db.get_peer_events("peer-a", 1).unwrap().len(), 1);
+    assert!(db.get_peer_events("unknown", 10).unwrap().is_empty());
+}
+
+#[test]
+fn test_peer_events_between_are_oldest_first_and_bounded() {
+    let (db, _temp_dir) = create_test_database();
+
+    for event_type in [
+        PeerEventType::HandshakeSuccess,
+        PeerEventType::HandshakeFailure,
+    ] {
+        db.record_peer_event("peer-a", event_type, None, None)
+            .unwrap();
+    }
+    db.record_peer_event("peer-b", PeerEventType::DosEvent, None, None)
+        .unwrap();
+    let now = Database::current_timestamp();
+
+    let events = db
+        .get_peer_events_between("peer-a", now - 60, None)
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, PeerEventType::HandshakeSuccess);
+    assert_eq!(events[1].event_type, PeerEventType::HandshakeFailure);
+
+    assert!(db
+        .get_peer_events_between("peer-a", now + 60, None)
+        .unwrap()
+        .is_empty());
+    assert!(db
+        .get_peer_events_between("peer-a", now - 120, Some(now - 60))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_empty_peer_id_is_rejected() {
+    let (db, _temp_dir) = create_test_database();
+
+    let result = db.record_peer_event("  ", PeerEventType::DosEvent, None, None);
+    assert!(matches!(result, Err(StorageError::InvalidData { .. })));
+}
+
+#[test]
+fn test_peer_summary_and_reputation() {
+    let (db, _temp_dir) = create_test_database();
+
+    for _ in 0..3 {
+        db.record_peer_event("peer-a", PeerEventType::HandshakeSuccess, None, None)
+            .unwrap();
+    }
+    db.record_peer_event("peer-a", PeerEventType::HandshakeFailure, None, None)
+        .unwrap();
+    db.record_peer_event("peer-a", PeerEventType::ProtocolViolation, None, None)
+        .unwrap();
+    db.record_peer_event("peer-a", PeerEventType::DosEvent, None, None)
+        .unwrap();
+
+    let summary = db.get_peer_summary("peer-a").unwrap();
+    assert_eq!(summary.handshake_successes, 3);
+    assert_eq!(summary.handshake_failures, 1);
+    assert_eq!(summary.protocol_violations, 1);
+    assert_eq!(summary.dos_events, 1);
+    assert_eq!(summary.total_events(), 6);
+    assert!(summary.first_seen.is_some());
+    // 50 + 3 - (5 + 10 + 20)
+    assert_eq!(summary.reputation_score(), 18);
+
+    let unknown = db.get_peer_summary("unknown").unwrap();
+    assert_eq!(unknown.peer_id, "unknown");
+    assert_eq!(unknown.total_events(), 0);
+    assert_eq!(unknown.reputation_score(), 50);
+}
+
+#[test]
+fn test_all_peer_summaries() {
+    let (db, _temp_dir) = create_test_database();
+
+    assert!(db.get_all_peer_summaries().unwrap().is_empty());
+
+    db.record_peer_event("peer-a", PeerEventType::HandshakeSuccess, None, None)
+        .unwrap();
+    db.record_peer_event(
+        "addr:192.0.2.1",
+        PeerEventType::HandshakeFailure,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let summaries = db.get_all_peer_summaries().unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert!(summaries.iter().any(|s| s.peer_id == "peer-a"));
+    assert!(summaries
+        .iter()
+        .any(|s| s.peer_id == "addr:192.0.2.1" && s.handshake_failures == 1));
+}
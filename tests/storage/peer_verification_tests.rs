@@ -0,0 +1,64 @@
+use super::create_test_database;
+use mate::storage::purge::PurgeFilter;
+
+#[test]
+fn test_peer_is_new_only_once() {
+    let (db, _temp_dir) = create_test_database();
+
+    assert!(db.note_peer_seen("peer-a").unwrap());
+    assert!(!db.note_peer_seen("peer-a").unwrap());
+    assert!(db.note_peer_seen("peer-b").unwrap());
+    assert!(!db.is_peer_verified("peer-a").unwrap());
+}
+
+#[test]
+fn test_mark_peer_verified() {
+    let (db, _temp_dir) = create_test_database();
+    db.note_peer_seen("peer-a").unwrap();
+
+    assert!(db.mark_peer_verified("peer-a").unwrap());
+    assert!(db.is_peer_verified("peer-a").unwrap());
+    let verified_at = db.peer_verified_at("peer-a").unwrap();
+    assert!(verified_at.is_some());
+
+    // Verifying again keeps the original date
+    assert!(!db.mark_peer_verified("peer-a").unwrap());
+    assert_eq!(db.peer_verified_at("peer-a").unwrap(), verified_at);
+
+    // A peer can be verified before it ever connects
+    assert!(db.mark_peer_verified("peer-b").unwrap());
+    assert!(!db.note_peer_seen("peer-b").unwrap());
+    assert!(db.mark_peer_verified("  ").is_err());
+}
+
+#[test]
+fn test_verification_follows_key_rotation() {
+    let (db, _temp_dir) = create_test_database();
+    db.mark_peer_verified("old-key").unwrap();
+
+    db.apply_key_rotation("old-key", "new-key", 1_700_000_000, "{}")
+        .unwrap();
+
+    assert!(db.is_peer_verified("new-key").unwrap());
+    assert!(!db.is_peer_verified("old-key").unwrap());
+}
+
+#[test]
+fn test_purge_by_peer_removes_its_verification() {
+    let (db, _temp_dir) = create_test_database();
+    db.mark_peer_verified("peer-a").unwrap();
+    db.mark_peer_verified("peer-b").unwrap();
+
+    // A date-limited purge keeps it
+    let mut filter = PurgeFilter {
+        peer_id: Some("peer-a".to_string()),
+        before: Some(i64::MAX),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().verifications, 0);
+
+    filter.before = None;
+    assert_eq!(db.purge(&filter, false).unwrap().verifications, 1);
+    assert!(!db.is_peer_verified("peer-a").unwrap());
+    assert!(db.note_peer_seen("peer-a").unwrap());
+    assert!(db.is_peer_verified("peer-b").unwrap());
+}
@@ -0,0 +1,171 @@
+use super::create_test_database;
+use mate::storage::purge::{PurgeFilter, PurgeSummary};
+use mate::storage::{Database, PeerEventType, PlayerColor, StorageError};
+
+/// Create a game against `opponent` with one message, tag, and note
+fn create_game_with_data(db: &Database, opponent: &str) -> String {
+    let game = db
+        .create_game(opponent.to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.store_message(
+        game.id.clone(),
+        "Move".to_string(),
+        "{}".to_string(),
+        "sig".to_string(),
+        opponent.to_string(),
+    )
+    .unwrap();
+    db.add_game_tag(&game.id, "casual").unwrap();
+    db.set_game_note(&game.id, "note").unwrap();
+    game.id
+}
+
+/// Backdate a game so it falls before a cutoff
+fn set_created_at(db: &Database, game_id: &str, created_at: i64) {
+    db.with_connection(|conn| {
+        conn.execute(
+            "UPDATE games SET created_at = ?1 WHERE id = ?2",
+            (created_at, game_id),
+        )?;
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_purge_requires_a_filter() {
+    let (db, _temp_dir) = create_test_database();
+
+    let result = db.purge(&PurgeFilter::default(), false);
+    assert!(matches!(result, Err(StorageError::InvalidData { .. })));
+}
+
+#[test]
+fn test_purge_by_peer() {
+    let (db, _temp_dir) = create_test_database();
+    let purged = create_game_with_data(&db, "peer-a");
+    let kept = create_game_with_data(&db, "peer-b");
+    db.record_peer_event("peer-a", PeerEventType::HandshakeSuccess, None, None)
+        .unwrap();
+    db.record_peer_event("peer-b", PeerEventType::HandshakeSuccess, None, None)
+        .unwrap();
+
+    let filter = PurgeFilter {
+        peer_id: Some("peer-a".to_string()),
+        before: None,
+    };
+    let summary = db.purge(&filter, false).unwrap();
+
+    assert_eq!(
+        summary,
+        PurgeSummary {
+            games: 1,
+            messages: 1,
+            tags: 1,
+            notes: 1,
+            peer_events: 1,
+            aliases: 0,
+            key_rotations: 0,
+            verifications: 0,
+            revocations: 0,
+            studies: 0,
+            announcements: 0,
+            presence: 0,
+            reports: 0,
+            mutes: 0,
+            linked_devices: 0,
+            arbitrations: 0,
+            rulings: 0,
+            pins: 0,
+        }
+    );
+    assert!(db.get_game(&purged).is_err());
+    assert!(db.get_messages_for_game(&purged).unwrap().is_empty());
+    assert!(db.get_game_tags(&purged).unwrap().is_empty());
+    assert!(db.get_game_note(&purged).unwrap().is_none());
+    assert_eq!(db.get_peer_summary("peer-a").unwrap().total_events(), 0);
+
+    assert!(db.get_game(&kept).is_ok());
+    assert_eq!(db.get_messages_for_game(&kept).unwrap().len(), 1);
+    assert_eq!(db.get_peer_summary("peer-b").unwrap().total_events(), 1);
+}
+
+#[test]
+fn test_purge_before_date() {
+    let (db, _temp_dir) = create_test_database();
+    let old_game = create_game_with_data(&db, "peer-a");
+    let new_game = create_game_with_data(&db, "peer-a");
+    set_created_at(&db, &old_game, 1_000);
+
+    let filter = PurgeFilter {
+        peer_id: None,
+        before: Some(2_000),
+    };
+    let summary = db.purge(&filter, false).unwrap();
+
+    assert_eq!(summary.games, 1);
+    assert!(db.get_game(&old_game).is_err());
+    assert!(db.get_game(&new_game).is_ok());
+}
+
+#[test]
+fn test_purge_with_both_filters_matches_both() {
+    let (db, _temp_dir) = create_test_database();
+    let old_a = create_game_with_data(&db, "peer-a");
+    let old_b = create_game_with_data(&db, "peer-b");
+    let new_a = create_game_with_data(&db, "peer-a");
+    set_created_at(&db, &old_a, 1_000);
+    set_created_at(&db, &old_b, 1_000);
+
+    let filter = PurgeFilter {
+        peer_id: Some("peer-a".to_string()),
+        before: Some(2_000),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().games, 1);
+
+    assert!(db.get_game(&old_a).is_err());
+    assert!(db.get_game(&old_b).is_ok());
+    assert!(db.get_game(&new_a).is_ok());
+}
+
+#[test]
+fn test_purge_dry_run_deletes_nothing() {
+    let (db, _temp_dir) = create_test_database();
+    let game_id = create_game_with_data(&db, "peer-a");
+    db.record_peer_event("peer-a", PeerEventType::DosEvent, None, None)
+        .unwrap();
+
+    let filter = PurgeFilter {
+        peer_id: Some("peer-a".to_string()),
+        before: None,
+    };
+    let preview = db.purge(&filter, true).unwrap();
+    assert_eq!(preview.total(), 5);
+
+    assert!(db.get_game(&game_id).is_ok());
+    assert_eq!(db.get_messages_for_game(&game_id).unwrap().len(), 1);
+    assert_eq!(db.get_peer_summary("peer-a").unwrap().dos_events, 1);
+
+    // The real purge removes exactly what the dry run reported
+    assert_eq!(db.purge(&filter, false).unwrap(), preview);
+    assert_eq!(db.purge(&filter, false).unwrap().total(), 0);
+}
+
+#[test]
+fn test_purge_removes_presence_of_the_peer_and_period() {
+    let (db, _temp_dir) = create_test_database();
+    db.set_peer_presence("peer-a", "busy").unwrap();
+    db.set_peer_presence("peer-b", "away").unwrap();
+
+    // The presence was sent after the cutoff
+    let mut filter = PurgeFilter {
+        peer_id: Some("peer-a".to_string()),
+        before: Some(1000),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().presence, 0);
+
+    filter.before = None;
+    assert_eq!(db.purge(&filter, false).unwrap().presence, 1);
+    assert!(db.get_peer_presence("peer-a").unwrap().is_none());
+    assert!(db.get_peer_presence("peer-b").unwrap().is_some());
+}
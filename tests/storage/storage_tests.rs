@@ -1,3 +1,4 @@
+use mate::storage::schema::{CURRENT_SCHEMA_VERSION, MIGRATIONS};
 use mate::storage::{Database, GameStatus, PlayerColor};
 use tempfile::TempDir;
 
@@ -89,6 +90,30 @@ fn test_database_initialization() {
     assert_eq!(err, 0, "Error count should start at 0");
 }
 
+#[test]
+fn test_schema_version_matches_last_migration() {
+    let last = MIGRATIONS.last().unwrap();
+    assert_eq!(CURRENT_SCHEMA_VERSION, last.version);
+
+    // A new database is brought to the current version
+    let (db, _env) = create_test_database();
+    let applied: i32 = db
+        .with_connection(|conn| {
+            Ok(
+                conn.query_row("SELECT MAX(version) FROM schema_migrations", [], |row| {
+                    row.get(0)
+                })?,
+            )
+        })
+        .unwrap();
+    assert_eq!(applied, CURRENT_SCHEMA_VERSION);
+
+    // Versions count up by one, so the last is the newest
+    for (index, migration) in MIGRATIONS.iter().enumerate() {
+        assert_eq!(migration.version, index as i32 + 1);
+    }
+}
+
 #[test]
 fn test_game_id_generation() {
     let (db, _env) = create_test_database();
@@ -157,6 +182,19 @@ fn test_database_maintenance() {
     );
 }
 
+#[test]
+fn test_integrity_check_and_latest_timestamp() {
+    let (db, _env) = create_test_database();
+
+    assert!(db.integrity_problems().unwrap().is_empty());
+    assert_eq!(db.latest_timestamp().unwrap(), None);
+
+    let game = db
+        .create_game("peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    assert_eq!(db.latest_timestamp().unwrap(), Some(game.updated_at));
+}
+
 /// Priority 2: Game CRUD Operations (6 tests)
 /// Test complete game lifecycle operations
 
@@ -0,0 +1,66 @@
+use super::create_test_database;
+use mate::storage::purge::PurgeFilter;
+
+#[test]
+fn test_study_is_kept_once() {
+    let (db, _temp_dir) = create_test_database();
+    assert!(db.get_study("study-1").unwrap().is_none());
+
+    assert!(db
+        .store_study("study-1", "coach", false, "Italian", "{}")
+        .unwrap());
+    // A second copy of the same study keeps the first one
+    assert!(!db
+        .store_study("study-1", "someone-else", false, "Renamed", "{}")
+        .unwrap());
+
+    let study = db.get_study("study-1").unwrap().unwrap();
+    assert_eq!(study.peer_id, "coach");
+    assert_eq!(study.title, "Italian");
+    assert!(!study.outgoing);
+}
+
+#[test]
+fn test_studies_are_listed_newest_first() {
+    let (db, _temp_dir) = create_test_database();
+    db.store_study("study-1", "coach", false, "First", "{}")
+        .unwrap();
+    db.store_study("study-2", "student:8080", true, "Second", "{}")
+        .unwrap();
+
+    let studies = db.get_studies().unwrap();
+    let titles: Vec<&str> = studies.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(titles, ["Second", "First"]);
+    assert!(studies[0].outgoing);
+
+    assert!(db
+        .store_study("", "coach", false, "Untitled", "{}")
+        .is_err());
+}
+
+#[test]
+fn test_purge_removes_studies_of_the_peer_and_period() {
+    let (db, _temp_dir) = create_test_database();
+    db.store_study("old", "coach", false, "Old", "{}").unwrap();
+    db.store_study("new", "coach", false, "New", "{}").unwrap();
+    db.store_study("other", "student:8080", true, "Other", "{}")
+        .unwrap();
+    db.with_connection(|conn| {
+        conn.execute("UPDATE studies SET created_at = 1000 WHERE id = 'old'", [])?;
+        Ok(())
+    })
+    .unwrap();
+
+    let mut filter = PurgeFilter {
+        peer_id: Some("coach".to_string()),
+        before: Some(2000),
+    };
+    assert_eq!(db.purge(&filter, false).unwrap().studies, 1);
+    assert!(db.get_study("old").unwrap().is_none());
+    assert!(db.get_study("new").unwrap().is_some());
+
+    filter.before = None;
+    assert_eq!(db.purge(&filter, false).unwrap().studies, 1);
+    assert!(db.get_study("new").unwrap().is_none());
+    assert!(db.get_study("other").unwrap().is_some());
+}
@@ -0,0 +1,69 @@
+use super::create_test_database;
+use mate::storage::{GameStatus, PlayerColor, StorageError};
+
+#[test]
+fn test_transaction_commits_all_writes() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    db.transaction(|tx| {
+        tx.store_message(
+            game.id.clone(),
+            "Move".to_string(),
+            "{}".to_string(),
+            "sig".to_string(),
+            "self".to_string(),
+        )?;
+        tx.update_game_status(&game.id, GameStatus::Active)?;
+
+        // Reads inside the transaction observe earlier writes
+        assert_eq!(tx.get_game(&game.id)?.status, GameStatus::Active);
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(db.get_game(&game.id).unwrap().status, GameStatus::Active);
+    assert_eq!(db.count_messages_for_game(&game.id).unwrap(), 1);
+}
+
+#[test]
+fn test_transaction_rolls_back_on_error() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    let result: Result<(), StorageError> = db.transaction(|tx| {
+        tx.store_message(
+            game.id.clone(),
+            "Move".to_string(),
+            "{}".to_string(),
+            "sig".to_string(),
+            "self".to_string(),
+        )?;
+        tx.update_game_status(&game.id, GameStatus::Active)?;
+        // Fails after the earlier writes have been issued
+        tx.update_game_status("missing-game", GameStatus::Active)
+    });
+
+    assert!(matches!(result, Err(StorageError::GameNotFound { .. })));
+    assert_eq!(db.get_game(&game.id).unwrap().status, GameStatus::Pending);
+    assert_eq!(db.count_messages_for_game(&game.id).unwrap(), 0);
+}
+
+#[test]
+fn test_transaction_counted_in_stats() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    let (_, transactions_before, _, _) = db.get_connection_stats();
+    db.transaction(|tx| tx.update_game_status(&game.id, GameStatus::Active))
+        .unwrap();
+    let (_, transactions_after, _, _) = db.get_connection_stats();
+
+    assert_eq!(transactions_after, transactions_before + 1);
+}
@@ -0,0 +1,200 @@
+//! HTTP API Tests
+//!
+//! Tests for request parsing in `src/api/http.rs` and the endpoints served by
+//! `ApiServer` in `src/api/server.rs`.
+
+use mate::api::http::read_request;
+use mate::api::ApiServer;
+use mate::cli::App;
+use mate::messages::chess::Move;
+use mate::storage::{GameStatus, PlayerColor};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+const TOKEN: &str = "secret-token";
+
+async fn start_api(temp_dir: &TempDir) -> (Arc<App>, String) {
+    let app = Arc::new(
+        App::new_with_data_dir(temp_dir.path().to_path_buf())
+            .await
+            .unwrap(),
+    );
+    let api = ApiServer::bind("127.0.0.1:0", Arc::clone(&app), TOKEN.to_string())
+        .await
+        .unwrap();
+    let addr = api.local_addr().unwrap().to_string();
+    tokio::spawn(api.run());
+    (app, addr)
+}
+
+/// Send a request and return the status and body of the response
+async fn send(
+    addr: &str,
+    method: &str,
+    path: &str,
+    token: Option<&str>,
+    body: &str,
+) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut request = format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\n");
+    if let Some(token) = token {
+        request.push_str(&format!("Authorization: Bearer {token}\r\n"));
+    }
+    request.push_str(&format!("Content-Length: {}\r\n\r\n{body}", body.len()));
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response[9..12].parse().unwrap();
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body.to_string())
+        .unwrap_or_default();
+    (status, body)
+}
+
+#[tokio::test]
+async fn test_request_parsing_decodes_path_and_query() {
+    let raw = b"GET /games/abc%2Fdef/board?format=fen&access_token=a+b HTTP/1.1\r\nX-Test: yes\r\nContent-Length: 2\r\n\r\n{}";
+    let mut reader = BufReader::new(&raw[..]);
+    let request = read_request(&mut reader).await.unwrap().unwrap();
+
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.segments(), ["games", "abc/def", "board"]);
+    assert_eq!(request.query["format"], "fen");
+    assert_eq!(request.query["access_token"], "a b");
+    assert_eq!(request.header("x-test"), Some("yes"));
+    assert_eq!(request.body, b"{}");
+
+    let mut empty = BufReader::new(&b""[..]);
+    assert!(read_request(&mut empty).await.unwrap().is_none());
+    let mut garbage = BufReader::new(&b"nonsense\r\n\r\n"[..]);
+    assert!(read_request(&mut garbage).await.is_err());
+}
+
+#[tokio::test]
+async fn test_requests_without_the_token_are_refused() {
+    let temp_dir = TempDir::new().unwrap();
+    let (_app, addr) = start_api(&temp_dir).await;
+
+    assert_eq!(send(&addr, "GET", "/games", None, "").await.0, 401);
+    assert_eq!(send(&addr, "GET", "/games", Some("wrong"), "").await.0, 401);
+    assert_eq!(send(&addr, "GET", "/games", Some(TOKEN), "").await.0, 200);
+    let path = format!("/games?access_token={TOKEN}");
+    assert_eq!(send(&addr, "GET", &path, None, "").await.0, 200);
+}
+
+#[tokio::test]
+async fn test_games_can_be_listed_viewed_and_played() {
+    let temp_dir = TempDir::new().unwrap();
+    let (app, addr) = start_api(&temp_dir).await;
+    let game = app
+        .database
+        .create_game("them".to_string(), PlayerColor::White, None)
+        .unwrap();
+    app.database
+        .update_game_status(&game.id, GameStatus::Active)
+        .unwrap();
+
+    let (status, body) = send(&addr, "GET", "/games", Some(TOKEN), "").await;
+    assert_eq!(status, 200);
+    let games: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(games[0]["id"], game.id.as_str());
+    assert_eq!(games[0]["your_turn"], true);
+
+    // Algebraic notation is accepted and answered in coordinates
+    let game_path = format!("/games/{}", game.id.replace('/', "%2F"));
+    let path = format!("{game_path}/moves");
+    let (status, body) = send(&addr, "POST", &path, Some(TOKEN), r#"{"move":"e4"}"#).await;
+    assert_eq!(status, 201, "{body}");
+    let played: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(played["move"], "e2e4");
+    assert_eq!(played["move_number"], 1);
+    // The opponent is not reachable in tests
+    assert_eq!(played["delivered"], false);
+
+    // Not our turn any more
+    let (status, _) = send(&addr, "POST", &path, Some(TOKEN), r#"{"move":"d4"}"#).await;
+    assert_eq!(status, 409);
+    let (status, _) = send(&addr, "POST", &path, Some(TOKEN), "not json").await;
+    assert_eq!(status, 400);
+
+    // Games can be named by ID prefix
+    let prefix = game.id[..8].replace('/', "%2F");
+    let (status, body) = send(&addr, "GET", &format!("/games/{prefix}"), Some(TOKEN), "").await;
+    assert_eq!(status, 200);
+    let board: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(board["moves"], serde_json::json!(["e2e4"]));
+    assert_eq!(board["to_move"], "black");
+    assert_eq!(board["squares"]["e4"], "P");
+    assert_eq!(board["squares"]["e8"], "k");
+
+    let path = format!("{game_path}/board?format=fen");
+    let (status, body) = send(&addr, "GET", &path, Some(TOKEN), "").await;
+    assert_eq!(status, 200);
+    assert!(body.starts_with("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b"));
+
+    assert_eq!(
+        send(&addr, "GET", "/games/missing", Some(TOKEN), "")
+            .await
+            .0,
+        404
+    );
+    assert_eq!(
+        send(&addr, "DELETE", "/games", Some(TOKEN), "").await.0,
+        405
+    );
+    assert_eq!(send(&addr, "GET", "/nowhere", Some(TOKEN), "").await.0, 404);
+}
+
+#[tokio::test]
+async fn test_events_are_streamed_to_clients() {
+    let temp_dir = TempDir::new().unwrap();
+    let (app, addr) = start_api(&temp_dir).await;
+    let game = app
+        .database
+        .create_game("them".to_string(), PlayerColor::Black, None)
+        .unwrap();
+
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    stream
+        .write_all(format!("GET /events?access_token={TOKEN} HTTP/1.1\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).await.unwrap();
+    assert!(line.starts_with("HTTP/1.1 200"), "{line}");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // As the server would store an incoming move
+    let message = Move::new(game.id.clone(), "e2e4".to_string(), "0".repeat(64));
+    app.database
+        .store_message(
+            game.id.clone(),
+            "Move".to_string(),
+            serde_json::to_string(&message).unwrap(),
+            String::new(),
+            "them".to_string(),
+        )
+        .unwrap();
+
+    let received = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+            if line.starts_with("event:") {
+                return line.clone();
+            }
+        }
+    })
+    .await
+    .expect("event within the poll interval");
+    assert_eq!(received.trim_end(), "event: move_received");
+    line.clear();
+    reader.read_line(&mut line).await.unwrap();
+    assert!(line.contains("\"chess_move\":\"e2e4\""), "{line}");
+}
@@ -0,0 +1,90 @@
+//! Metrics Endpoint Tests
+//!
+//! Tests for the Prometheus and JSON statistics served by `MetricsServer` in
+//! `src/api/metrics.rs`.
+
+use mate::api::metrics::render_prometheus;
+use mate::api::MetricsServer;
+use mate::network::http_client::{self, HttpUrl};
+use mate::network::{ConnectionStats, MonitorSnapshot, StatsRegistry, TrafficSnapshot};
+
+fn registry() -> StatsRegistry {
+    let registry = StatsRegistry::new();
+    registry.close(
+        "a",
+        &ConnectionStats {
+            messages_sent: 3,
+            messages_received: 2,
+            bytes_sent: 300,
+            bytes_received: 200,
+            retries: 1,
+            last_activity: Some(1_700_000_000),
+            round_trips: 2,
+            round_trip_total_ms: 500,
+        },
+    );
+    registry
+}
+
+#[test]
+fn test_prometheus_text_lists_every_metric() {
+    let text = render_prometheus(&registry().snapshot());
+    for line in [
+        "# TYPE mate_connections_open gauge",
+        "mate_connections_open 0",
+        "mate_connections_total 1",
+        "# TYPE mate_messages_sent_total counter",
+        "mate_messages_sent_total 3",
+        "mate_messages_received_total 2",
+        "mate_bytes_sent_total 300",
+        "mate_bytes_received_total 200",
+        "mate_retries_total 1",
+        "# TYPE mate_messages_rejected_total counter",
+        "mate_messages_rejected_total 0",
+        "mate_messages_oversized_total 0",
+        "mate_connections_refused_total 0",
+        "mate_handshake_timeouts_total 0",
+        "mate_messages_throttled_total 0",
+        "mate_last_activity_seconds 1700000000",
+        "mate_round_trip_avg_seconds 0.25",
+    ] {
+        assert!(text.lines().any(|l| l == line), "missing {line}:\n{text}");
+    }
+}
+
+#[tokio::test]
+async fn test_endpoint_serves_metrics_and_stats() {
+    let registry = registry();
+    let server = MetricsServer::bind("127.0.0.1:0", registry.clone())
+        .await
+        .unwrap();
+    let base = format!("http://{}", server.local_addr().unwrap());
+    tokio::spawn(server.run());
+
+    let url = HttpUrl::parse(&format!("{base}/metrics")).unwrap();
+    let (status, body) = http_client::get(&url).await.unwrap();
+    assert_eq!(status, 200);
+    assert!(String::from_utf8(body)
+        .unwrap()
+        .contains("mate_messages_sent_total 3"));
+
+    // Counters are read at request time
+    registry.record_retries(4);
+    let url = HttpUrl::parse(&format!("{base}/stats")).unwrap();
+    let (status, body) = http_client::get(&url).await.unwrap();
+    assert_eq!(status, 200);
+    let snapshot: TrafficSnapshot = serde_json::from_slice(&body).unwrap();
+    assert_eq!(snapshot, registry.snapshot());
+    assert_eq!(snapshot.traffic.retries, 5);
+
+    registry.update("b", &ConnectionStats::default());
+    let url = HttpUrl::parse(&format!("{base}/top")).unwrap();
+    let (status, body) = http_client::get(&url).await.unwrap();
+    assert_eq!(status, 200);
+    let monitor: MonitorSnapshot = serde_json::from_slice(&body).unwrap();
+    assert_eq!(monitor, registry.monitor());
+    assert_eq!(monitor.connections.len(), 1);
+
+    let url = HttpUrl::parse(&format!("{base}/games")).unwrap();
+    assert_eq!(http_client::get(&url).await.unwrap().0, 404);
+}
@@ -0,0 +1,4 @@
+//! HTTP API unit tests
+
+pub mod http_api;
+pub mod metrics;
@@ -4,8 +4,11 @@ pub mod color;
 pub mod display;
 pub mod fen;
 pub mod move_application;
+pub mod movegen;
 pub mod moves;
+pub mod odds;
 pub mod piece;
 pub mod piece_type;
 pub mod position;
 pub mod serde;
+pub mod zobrist;
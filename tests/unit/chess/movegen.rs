@@ -0,0 +1,174 @@
+use mate::chess::{Board, Color, Move, PieceType, Position};
+
+/// Apply a sequence of moves in any supported notation to a fresh board
+fn play(moves: &[&str]) -> Board {
+    let mut board = Board::new();
+    for notation in moves {
+        let mv = board.parse_move(notation).unwrap();
+        board.make_move(mv).unwrap();
+    }
+    board
+}
+
+fn pos(s: &str) -> Position {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_starting_position_has_twenty_moves() {
+    let board = Board::new();
+    assert_eq!(board.legal_moves().len(), 20);
+    assert!(!board.is_in_check(Color::White));
+    assert!(!board.is_checkmate());
+    assert!(!board.is_stalemate());
+}
+
+#[test]
+fn test_perft_depth_two() {
+    let board = Board::new();
+    let total: usize = board
+        .legal_moves()
+        .into_iter()
+        .map(|mv| {
+            let mut next = board.clone();
+            next.make_move(mv).unwrap();
+            next.legal_moves().len()
+        })
+        .sum();
+    assert_eq!(total, 400);
+}
+
+#[test]
+fn test_scholars_mate_is_checkmate() {
+    let board = play(&["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7#"]);
+    assert!(board.is_in_check(Color::Black));
+    assert!(board.is_checkmate());
+    assert!(board.legal_moves().is_empty());
+}
+
+#[test]
+fn test_stalemate_detection() {
+    let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert!(board.is_stalemate());
+    assert!(!board.is_checkmate());
+}
+
+#[test]
+fn test_pinned_piece_cannot_move() {
+    // The e2 knight is pinned against the king by the e8 rook
+    let board = Board::from_fen("4r2k/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+    assert!(board.legal_moves().iter().all(|mv| mv.from != pos("e2")));
+}
+
+#[test]
+fn test_cannot_castle_through_check() {
+    // The f8 rook covers f1, so kingside castling is illegal
+    let board = Board::from_fen("5r1k/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    let moves = board.legal_moves();
+    assert!(!moves.contains(&Move::new_unchecked(pos("e1"), pos("g1"), None)));
+    assert!(moves.contains(&Move::new_unchecked(pos("e1"), pos("c1"), None)));
+    assert!(board.parse_move("O-O").is_err());
+    assert_eq!(
+        board.parse_move("O-O-O").unwrap(),
+        Move::new_unchecked(pos("e1"), pos("c1"), None)
+    );
+}
+
+#[test]
+fn test_en_passant_is_generated() {
+    let board = play(&["e4", "a6", "e5", "d5"]);
+    let mv = board.parse_move("exd6").unwrap();
+    assert_eq!(mv, Move::new_unchecked(pos("e5"), pos("d6"), None));
+}
+
+#[test]
+fn test_promotion_moves() {
+    let board = Board::from_fen("7k/P7/8/8/8/8/8/K7 w - - 0 1").unwrap();
+    let promotions = board
+        .legal_moves()
+        .into_iter()
+        .filter(|mv| mv.from == pos("a7"))
+        .count();
+    assert_eq!(promotions, 4);
+    assert_eq!(
+        board.parse_move("a8=Q+").unwrap().promotion,
+        Some(PieceType::Queen)
+    );
+    assert_eq!(
+        board.parse_move("a7a8n").unwrap().promotion,
+        Some(PieceType::Knight)
+    );
+}
+
+#[test]
+fn test_parse_move_san_and_coordinates_agree() {
+    let board = Board::new();
+    assert_eq!(
+        board.parse_move("Nf3").unwrap(),
+        board.parse_move("g1f3").unwrap()
+    );
+    assert_eq!(
+        board.parse_move("e4").unwrap(),
+        board.parse_move("e2e4").unwrap()
+    );
+}
+
+#[test]
+fn test_parse_move_rejects_illegal_and_ambiguous() {
+    let board = Board::new();
+    assert!(board.parse_move("e5").is_err());
+    assert!(board.parse_move("e2e5").is_err());
+    assert!(board.parse_move("Qh5").is_err());
+    assert!(board.parse_move("hello").is_err());
+
+    // Both knights can reach d2
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1").unwrap();
+    let err = board.parse_move("Nd2").unwrap_err();
+    assert!(err.to_string().contains("ambiguous"));
+    assert_eq!(
+        board.parse_move("Nbd2").unwrap(),
+        Move::new_unchecked(pos("b1"), pos("d2"), None)
+    );
+}
+
+#[test]
+fn test_to_san() {
+    let board = Board::new();
+    let san = |board: &Board, notation: &str| board.to_san(board.parse_move(notation).unwrap());
+    assert_eq!(san(&board, "g1f3"), "Nf3");
+    assert_eq!(san(&board, "e2e4"), "e4");
+
+    let board = play(&["e4", "d5"]);
+    assert_eq!(san(&board, "e4d5"), "exd5");
+
+    let board = play(&["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6"]);
+    assert_eq!(san(&board, "h5f7"), "Qxf7#");
+
+    let board = play(&["e4", "a6", "e5", "d5"]);
+    assert_eq!(san(&board, "e5d6"), "exd6");
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    assert_eq!(san(&board, "e1g1"), "O-O");
+    assert_eq!(san(&board, "e1c1"), "O-O-O");
+
+    let board = Board::from_fen("7k/P7/8/8/8/8/8/K7 w - - 0 1").unwrap();
+    assert_eq!(san(&board, "a7a8q"), "a8=Q+");
+}
+
+#[test]
+fn test_to_san_disambiguates() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1").unwrap();
+    let mv = Move::new_unchecked(pos("b1"), pos("d2"), None);
+    assert_eq!(board.to_san(mv), "Nbd2");
+
+    // Rooks on the same file are told apart by rank
+    let board = Board::from_fen("R7/8/7k/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    let mv = Move::new_unchecked(pos("a1"), pos("a4"), None);
+    assert_eq!(board.to_san(mv), "R1a4");
+
+    // Every legal move's SAN parses back to the same move
+    let board = play(&["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+    for mv in board.legal_moves() {
+        assert_eq!(board.parse_move(&board.to_san(mv)).unwrap(), mv);
+    }
+}
@@ -0,0 +1,91 @@
+use mate::chess::{Board, Color, Move, Odds, Position};
+
+fn square(name: &str) -> Position {
+    name.parse().unwrap()
+}
+
+fn odds(giver: Color, removed: &[&str], extra_moves: u8) -> Odds {
+    Odds {
+        giver,
+        removed: removed.iter().map(|name| square(name)).collect(),
+        extra_moves,
+    }
+}
+
+/// Play coordinate moves from the start of a game at `odds`
+fn play(odds: &Odds, moves: &[&str]) -> Board {
+    let mut board = Odds::starting_board(Some(odds)).unwrap();
+    for (ply, notation) in moves.iter().enumerate() {
+        let mv = Move::from_str_with_color(notation, board.active_color()).unwrap();
+        Odds::make_move(Some(odds), &mut board, ply, mv).unwrap();
+    }
+    board
+}
+
+#[test]
+fn test_removed_pieces_are_left_off_the_board() {
+    let rook_odds = odds(Color::White, &["a1"], 0);
+    let board = Odds::starting_board(Some(&rook_odds)).unwrap();
+
+    assert_eq!(board.get_piece(square("a1")), None);
+    assert!(board.get_piece(square("h1")).is_some());
+    assert!(!board.castling_rights().white_queenside);
+    assert!(board.castling_rights().white_kingside);
+    assert!(board.castling_rights().black_queenside);
+    assert_eq!(board.active_color(), Color::White);
+    assert_eq!(rook_odds.to_string(), "White gives Ra1");
+}
+
+#[test]
+fn test_invalid_odds_are_rejected() {
+    // The king, the receiver's pieces, empty squares, and repeats
+    assert!(odds(Color::White, &["e1"], 0).validate().is_err());
+    assert!(odds(Color::White, &["b8"], 0).validate().is_err());
+    assert!(odds(Color::White, &["e4"], 0).validate().is_err());
+    assert!(odds(Color::White, &["b1", "b1"], 0).validate().is_err());
+    assert!(odds(Color::White, &[], 0).validate().is_err());
+    assert!(odds(Color::White, &[], 4).validate().is_err());
+    assert!(odds(Color::Black, &["b8", "f7"], 2).validate().is_ok());
+}
+
+#[test]
+fn test_receiver_plays_extra_moves_in_a_row() {
+    // Black gives pawn and two moves: White plays twice, then play alternates
+    let pawn_and_move = odds(Color::Black, &["f7"], 1);
+    assert_eq!(Odds::side_to_move(Some(&pawn_and_move), 0), Color::White);
+    assert_eq!(Odds::side_to_move(Some(&pawn_and_move), 1), Color::White);
+    assert_eq!(Odds::side_to_move(Some(&pawn_and_move), 2), Color::Black);
+
+    let board = play(&pawn_and_move, &["e2e4", "d2d4"]);
+    assert_eq!(board.active_color(), Color::Black);
+    let board = play(&pawn_and_move, &["e2e4", "d2d4", "e7e6"]);
+    assert_eq!(board.active_color(), Color::White);
+
+    // Black as receiver moves first
+    let knight_and_move = odds(Color::White, &["b1"], 1);
+    let board = Odds::starting_board(Some(&knight_and_move)).unwrap();
+    assert_eq!(board.active_color(), Color::Black);
+    assert_eq!(
+        play(&knight_and_move, &["e7e5"]).active_color(),
+        Color::White
+    );
+    assert_eq!(knight_and_move.to_string(), "White gives Nb1 + 1 move");
+}
+
+#[test]
+fn test_extra_moves_may_not_give_check() {
+    let two_moves = odds(Color::Black, &["f7"], 2);
+    let mut board = play(&two_moves, &["e2e4"]);
+    let check = Move::from_str_with_color("d1h5", Color::White).unwrap();
+    assert!(Odds::make_move(Some(&two_moves), &mut board, 1, check).is_err());
+}
+
+#[test]
+fn test_standard_games_are_unchanged() {
+    assert_eq!(
+        Odds::starting_board(None).unwrap().to_fen(),
+        Board::new().to_fen()
+    );
+    assert_eq!(Odds::side_to_move(None, 0), Color::White);
+    assert_eq!(Odds::side_to_move(None, 1), Color::Black);
+}
@@ -0,0 +1,64 @@
+use mate::chess::{Board, Move};
+
+/// Apply a sequence of coordinate moves to a fresh board
+fn play(moves: &[&str]) -> Board {
+    let mut board = Board::new();
+    for notation in moves {
+        let color = board.active_color();
+        board
+            .make_move(Move::from_str_with_color(notation, color).unwrap())
+            .unwrap();
+    }
+    board
+}
+
+#[test]
+fn test_zobrist_hash_is_deterministic() {
+    assert_eq!(Board::new().zobrist_hash(), Board::new().zobrist_hash());
+    assert_eq!(
+        play(&["e2e4", "e7e5"]).zobrist_hash(),
+        play(&["e2e4", "e7e5"]).zobrist_hash()
+    );
+    assert_ne!(Board::new().zobrist_hash(), 0);
+}
+
+#[test]
+fn test_transpositions_share_a_hash() {
+    let a = play(&["g1f3", "g8f6", "b1c3", "b8c6"]);
+    let b = play(&["b1c3", "b8c6", "g1f3", "g8f6"]);
+    assert_eq!(a.zobrist_hash(), b.zobrist_hash());
+}
+
+#[test]
+fn test_move_counters_are_ignored() {
+    // Knights out and back: same placement, different move counters
+    let board = play(&["g1f3", "g8f6", "f3g1", "f6g8"]);
+    assert_ne!(board.fullmove_number(), Board::new().fullmove_number());
+    assert_eq!(board.zobrist_hash(), Board::new().zobrist_hash());
+}
+
+#[test]
+fn test_side_to_move_changes_hash() {
+    let white_to_move = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let black_to_move = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(white_to_move.zobrist_hash(), black_to_move.zobrist_hash());
+}
+
+#[test]
+fn test_castling_rights_change_hash() {
+    let all = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let some = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1").unwrap();
+    let none = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+    assert_ne!(all.zobrist_hash(), some.zobrist_hash());
+    assert_ne!(some.zobrist_hash(), none.zobrist_hash());
+    assert_ne!(all.zobrist_hash(), none.zobrist_hash());
+}
+
+#[test]
+fn test_en_passant_file_changes_hash() {
+    let with_ep =
+        Board::from_fen("rnbqkbnr/pppp1ppp/8/8/4pP2/8/PPPPP1PP/RNBQKBNR b KQkq f3 0 1").unwrap();
+    let without_ep =
+        Board::from_fen("rnbqkbnr/pppp1ppp/8/8/4pP2/8/PPPPP1PP/RNBQKBNR b KQkq - 0 1").unwrap();
+    assert_ne!(with_ep.zobrist_hash(), without_ep.zobrist_hash());
+}
@@ -0,0 +1,347 @@
+//! Account Archive Tests
+//!
+//! Tests for `src/cli/account.rs` export/import of the complete account state
+
+use mate::cli::account::{
+    export_account, import_account, import_account_with_progress, AccountArchive,
+};
+use mate::cli::app::{App, Config};
+use mate::cli::progress::ProgressEvent;
+use mate::crypto::storage::key_path_in;
+use mate::crypto::Identity;
+use mate::network::known_peers::{check_peer_key, KnownPeers, KNOWN_PEERS_FILE};
+use mate::storage::{Database, PlayerColor};
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+/// Create a source database with one tagged, annotated game, a move, and an
+/// alias for the verified opponent
+fn populated_database(dir: &std::path::Path, identity: &Identity) -> Database {
+    let db = Database::new_with_path(identity.peer_id().as_str(), &dir.join("database.sqlite"))
+        .expect("Failed to create database");
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.store_message(
+        game.id.clone(),
+        "move".to_string(),
+        "{}".to_string(),
+        "sig".to_string(),
+        identity.peer_id().to_string(),
+    )
+    .unwrap();
+    db.add_game_tag(&game.id, "blitz").unwrap();
+    db.set_game_note(&game.id, "good game").unwrap();
+    db.set_peer_alias("rival", "opponent_peer").unwrap();
+    db.mark_peer_verified("opponent_peer").unwrap();
+    db
+}
+
+fn test_config(data_dir: &std::path::Path) -> Config {
+    Config {
+        data_dir: data_dir.to_path_buf(),
+        default_bind_addr: "127.0.0.1:9999".to_string(),
+        max_concurrent_games: 3,
+        db_path: None,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_account_archive_roundtrip() {
+    let source_dir = TempDir::new().unwrap();
+    let target_dir = TempDir::new().unwrap();
+    let archive_path = source_dir.path().join("account.mate");
+
+    let identity = Identity::generate().unwrap();
+    let db = populated_database(source_dir.path(), &identity);
+
+    let archive = AccountArchive::create(
+        &identity,
+        &test_config(source_dir.path()),
+        db.export_account_data().unwrap(),
+        "passphrase",
+    )
+    .unwrap();
+    archive.save(&archive_path).unwrap();
+
+    let loaded = AccountArchive::load(&archive_path).unwrap();
+    assert_eq!(loaded.settings.default_bind_addr, "127.0.0.1:9999");
+
+    let target_db_path = target_dir.path().join("database.sqlite");
+    let summary = import_account(
+        &loaded,
+        "passphrase",
+        target_dir.path(),
+        &target_db_path,
+        false,
+    )
+    .unwrap();
+    assert_eq!(summary.games, 1);
+    assert_eq!(summary.messages, 1);
+    assert_eq!(summary.tags, 1);
+    assert_eq!(summary.notes, 1);
+    assert_eq!(summary.aliases, 1);
+    assert_eq!(summary.verifications, 1);
+
+    // Identity restored with the same peer ID
+    let restored = Identity::from_storage_path(&target_dir.path().join("identity.key")).unwrap();
+    assert_eq!(restored.peer_id(), identity.peer_id());
+
+    // Importing the same archive again skips existing games
+    let again = import_account(
+        &loaded,
+        "passphrase",
+        target_dir.path(),
+        &target_db_path,
+        false,
+    )
+    .unwrap();
+    assert_eq!(again.games, 0);
+    assert_eq!(again.skipped_games, 1);
+    assert_eq!(again.messages, 0);
+    assert_eq!(again.aliases, 0);
+    assert_eq!(again.verifications, 0);
+
+    let target_db = Database::new_with_path(identity.peer_id().as_str(), &target_db_path).unwrap();
+    let games = target_db.get_all_games().unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(
+        target_db.get_game_tags(&games[0].id).unwrap(),
+        vec!["blitz"]
+    );
+    assert_eq!(
+        target_db.resolve_peer_alias("rival").unwrap().as_deref(),
+        Some("opponent_peer")
+    );
+    assert!(target_db.is_peer_verified("opponent_peer").unwrap());
+}
+
+#[tokio::test]
+async fn test_account_archive_carries_pinned_keys() {
+    let source_dir = TempDir::new().unwrap();
+    let target_dir = TempDir::new().unwrap();
+    let archive_path = source_dir.path().join("account.mate");
+    let source_pins = source_dir.path().join(KNOWN_PEERS_FILE);
+    check_peer_key(&source_pins, "10.0.0.1:8080", "key-a", false).unwrap();
+    check_peer_key(&source_pins, "10.0.0.2:8080", "key-b", false).unwrap();
+    check_peer_key(&source_pins, "10.0.0.3:8080", "key-c", false).unwrap();
+    let mut known_peers = KnownPeers::load(&source_pins).unwrap();
+    known_peers.revoke("key-b");
+    known_peers.save().unwrap();
+
+    let app = App::new_with_data_dir(source_dir.path().to_path_buf())
+        .await
+        .unwrap();
+    export_account(&app, &archive_path, "passphrase").unwrap();
+
+    // A key pinned on the target for the same address is kept
+    let target_pins = target_dir.path().join(KNOWN_PEERS_FILE);
+    check_peer_key(&target_pins, "10.0.0.1:8080", "key-local", false).unwrap();
+    check_peer_key(&target_pins, "10.0.0.2:8080", "key-b", false).unwrap();
+
+    let archive = AccountArchive::load(&archive_path).unwrap();
+    let summary = import_account(
+        &archive,
+        "passphrase",
+        target_dir.path(),
+        &target_dir.path().join("database.sqlite"),
+        false,
+    )
+    .unwrap();
+    assert_eq!(summary.pins, 2);
+
+    let known_peers = KnownPeers::load(&target_pins).unwrap();
+    assert_eq!(
+        known_peers.get("10.0.0.1:8080").unwrap().peer_id,
+        "key-local"
+    );
+    assert!(known_peers.is_revoked("key-b"));
+    assert_eq!(known_peers.get("10.0.0.3:8080").unwrap().peer_id, "key-c");
+}
+
+#[tokio::test]
+async fn test_account_archive_carries_other_identities() {
+    let source_dir = TempDir::new().unwrap();
+    let target_dir = TempDir::new().unwrap();
+    let archive_path = source_dir.path().join("account.mate");
+    let work = Identity::generate().unwrap();
+    let club = Identity::generate().unwrap();
+    for (name, identity) in [("work", &work), ("club", &club)] {
+        identity
+            .save_to_storage_path(&key_path_in(source_dir.path(), name).unwrap())
+            .unwrap();
+    }
+
+    let app = App::new_with_data_dir(source_dir.path().to_path_buf())
+        .await
+        .unwrap();
+    let archive = export_account(&app, &archive_path, "passphrase").unwrap();
+    let names: Vec<&String> = archive.other_identities.keys().collect();
+    assert_eq!(names, ["club", "work"]);
+    let json = std::fs::read_to_string(&archive_path).unwrap();
+    let secret_b64 = base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        work.to_secret_bytes().unwrap(),
+    );
+    assert!(!json.contains(&secret_b64));
+
+    // An identity that already has a key on the target keeps it
+    let local_club = Identity::generate().unwrap();
+    local_club
+        .save_to_storage_path(&key_path_in(target_dir.path(), "club").unwrap())
+        .unwrap();
+
+    let summary = import_account(
+        &AccountArchive::load(&archive_path).unwrap(),
+        "passphrase",
+        target_dir.path(),
+        &target_dir.path().join("database.sqlite"),
+        false,
+    )
+    .unwrap();
+    assert_eq!(summary.identities, 1);
+
+    let stored_peer_id =
+        |name| Identity::stored_peer_id(&key_path_in(target_dir.path(), name).unwrap()).unwrap();
+    assert_eq!(&stored_peer_id("work"), work.peer_id());
+    assert_eq!(&stored_peer_id("club"), local_club.peer_id());
+    assert_eq!(&stored_peer_id("default"), app.identity.peer_id());
+}
+
+#[test]
+fn test_archive_does_not_contain_plaintext_key() {
+    let dir = TempDir::new().unwrap();
+    let identity = Identity::generate().unwrap();
+    let archive = AccountArchive::create(
+        &identity,
+        &test_config(dir.path()),
+        Default::default(),
+        "passphrase",
+    )
+    .unwrap();
+
+    let json = serde_json::to_string(&archive).unwrap();
+    let secret_b64 = base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        identity.to_secret_bytes().unwrap(),
+    );
+    assert!(!json.contains(&secret_b64));
+}
+
+#[test]
+fn test_archive_leaves_out_secrets_and_is_private() {
+    let dir = TempDir::new().unwrap();
+    let identity = Identity::generate().unwrap();
+    let mut config = test_config(dir.path());
+    config.api.token = Some("api-token-value".to_string());
+    config.webhooks.secret = Some("hmac-secret-value".to_string());
+    config.webhooks.urls = vec!["https://hooks.slack.com/services/T000/B000/XXXX".to_string()];
+
+    let archive =
+        AccountArchive::create(&identity, &config, Default::default(), "passphrase").unwrap();
+    let path = dir.path().join("account.json");
+    archive.save(&path).unwrap();
+
+    let json = std::fs::read_to_string(&path).unwrap();
+    assert!(!json.contains("api-token-value"));
+    assert!(!json.contains("hmac-secret-value"));
+    assert!(!json.contains("hooks.slack.com"));
+    assert_eq!(archive.settings.max_concurrent_games, 3);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn test_import_rejects_wrong_passphrase() {
+    let dir = TempDir::new().unwrap();
+    let identity = Identity::generate().unwrap();
+    let archive = AccountArchive::create(
+        &identity,
+        &test_config(dir.path()),
+        Default::default(),
+        "passphrase",
+    )
+    .unwrap();
+
+    let result = import_account(
+        &archive,
+        "not the passphrase",
+        dir.path(),
+        &dir.path().join("database.sqlite"),
+        false,
+    );
+    assert!(result.is_err());
+    assert!(!dir.path().join("identity.key").exists());
+}
+
+#[test]
+fn test_import_refuses_to_replace_different_identity() {
+    let dir = TempDir::new().unwrap();
+    let existing = Identity::generate().unwrap();
+    existing
+        .save_to_storage_path(&dir.path().join("identity.key"))
+        .unwrap();
+
+    let identity = Identity::generate().unwrap();
+    let archive = AccountArchive::create(
+        &identity,
+        &test_config(dir.path()),
+        Default::default(),
+        "passphrase",
+    )
+    .unwrap();
+    let db_path = dir.path().join("database.sqlite");
+
+    assert!(import_account(&archive, "passphrase", dir.path(), &db_path, false).is_err());
+    import_account(&archive, "passphrase", dir.path(), &db_path, true).unwrap();
+
+    let restored = Identity::from_storage_path(&dir.path().join("identity.key")).unwrap();
+    assert_eq!(restored.peer_id(), identity.peer_id());
+}
+
+#[test]
+fn test_import_reports_progress_for_every_record() {
+    let source_dir = TempDir::new().unwrap();
+    let target_dir = TempDir::new().unwrap();
+    let identity = Identity::generate().unwrap();
+    let db = populated_database(source_dir.path(), &identity);
+    let archive = AccountArchive::create(
+        &identity,
+        &test_config(source_dir.path()),
+        db.export_account_data().unwrap(),
+        "passphrase",
+    )
+    .unwrap();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    import_account_with_progress(
+        &archive,
+        "passphrase",
+        target_dir.path(),
+        &target_dir.path().join("database.sqlite"),
+        false,
+        Some(Arc::new(move |event: &ProgressEvent| {
+            recorded.lock().unwrap().push(event.clone());
+        })),
+    )
+    .unwrap();
+
+    let events = events.lock().unwrap();
+    // One game, one message, one tag, one note, one alias, one
+    // verification, then done
+    let counts: Vec<(u64, u64)> = events
+        .iter()
+        .filter_map(|event| match event {
+            ProgressEvent::Advanced { done, total, .. } => Some((*done, *total)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(counts, vec![(1, 6), (2, 6), (3, 6), (4, 6), (5, 6), (6, 6)]);
+    assert_eq!(events.last(), Some(&ProgressEvent::Finished));
+}
@@ -78,6 +78,8 @@ fn create_test_config(temp_dir: &TempDir) -> Config {
         data_dir: temp_dir.path().to_path_buf(),
         default_bind_addr: "127.0.0.1:8080".to_string(),
         max_concurrent_games: 10,
+        db_path: None,
+        ..Default::default()
     }
 }
 
@@ -392,6 +394,8 @@ fn test_config_database_path_resolution() {
         data_dir: temp_dir.path().to_path_buf(),
         default_bind_addr: "127.0.0.1:8080".to_string(),
         max_concurrent_games: 10,
+        db_path: None,
+        ..Default::default()
     };
 
     let db_path = config.database_path();
@@ -399,6 +403,87 @@ fn test_config_database_path_resolution() {
     assert_eq!(db_path.file_name().unwrap(), "database.sqlite");
 }
 
+#[test]
+fn test_config_custom_database_path() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let custom_path = temp_dir.path().join("elsewhere").join("games.db");
+    let config = Config {
+        data_dir: temp_dir.path().to_path_buf(),
+        default_bind_addr: "127.0.0.1:8080".to_string(),
+        max_concurrent_games: 10,
+        db_path: Some(custom_path.clone()),
+        ..Default::default()
+    };
+
+    assert_eq!(config.database_path(), custom_path);
+
+    // The setting round-trips through the config file format
+    let serialized = toml::to_string(&config).unwrap();
+    let deserialized: Config = toml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.db_path, Some(custom_path));
+}
+
+#[tokio::test]
+async fn test_app_new_with_config_uses_custom_database_path() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let custom_path = temp_dir.path().join("elsewhere").join("games.db");
+    let config = Config {
+        data_dir: temp_dir.path().join("data"),
+        default_bind_addr: "127.0.0.1:8080".to_string(),
+        max_concurrent_games: 10,
+        db_path: Some(custom_path.clone()),
+        ..Default::default()
+    };
+
+    let app = App::new_with_config(config)
+        .await
+        .expect("App should initialize with a custom database path");
+
+    assert_eq!(app.database_path(), custom_path);
+    assert!(
+        custom_path.exists(),
+        "Database should be created at db_path"
+    );
+    assert!(!app.is_ephemeral());
+    assert!(
+        !temp_dir
+            .path()
+            .join("data")
+            .join("database.sqlite")
+            .exists(),
+        "Default database location should not be used"
+    );
+}
+
+#[tokio::test]
+async fn test_app_ephemeral_mode() {
+    let app = App::new_ephemeral()
+        .await
+        .expect("Ephemeral app should initialize");
+
+    assert!(app.is_ephemeral());
+    assert_eq!(app.database_path(), std::path::PathBuf::from(":memory:"));
+    assert!(!app.peer_id().is_empty());
+
+    // The in-memory database is fully usable
+    let game = app
+        .database
+        .create_game(
+            "opponent".to_string(),
+            mate::storage::PlayerColor::White,
+            None,
+        )
+        .expect("Ephemeral database should accept writes");
+    assert!(app.database.get_game(&game.id).is_ok());
+
+    // Each ephemeral instance starts from a clean slate
+    let other = App::new_ephemeral()
+        .await
+        .expect("Ephemeral app should initialize");
+    assert_ne!(app.peer_id(), other.peer_id());
+    assert!(other.database.get_all_games().unwrap().is_empty());
+}
+
 #[test]
 fn test_ensure_data_dir_creates_directory() {
     // Test App::ensure_data_dir() creates directory and verifies writability
@@ -0,0 +1,201 @@
+//! Invitation Auto-Accept Tests
+//!
+//! Tests for the `[auto_accept]` rules and the daemon task accepting
+//! invitations that pass them, in `src/cli/auto_accept.rs`
+
+use mate::chess::Color;
+use mate::cli::app::AutoAcceptSettings;
+use mate::cli::auto_accept::{check, AutoAccept, AutoAcceptRecord, AUTO_ACCEPT_MESSAGE_TYPE};
+use mate::cli::game_ops::GameOps;
+use mate::cli::NetworkManager;
+use mate::crypto::Identity;
+use mate::messages::chess::GameInvite;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// An unused port, so acceptances are queued instead of delivered
+const INVITER: &str = "127.0.0.1:1";
+
+fn create_test_database() -> (Arc<Database>, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("test.db")).unwrap();
+    (Arc::new(db), temp_dir)
+}
+
+/// Store an invitation from `sender` with `base_minutes`+`increment_secs`
+/// on the clock, or untimed
+fn invite(db: &Database, sender: &str, clock: Option<(u64, u64)>) -> String {
+    let metadata = clock.map(|(base_minutes, increment_secs)| {
+        serde_json::json!({
+            "time_control": {
+                "initial_time_ms": base_minutes * 60_000,
+                "increment_ms": increment_secs * 1000,
+            }
+        })
+    });
+    let game = db
+        .create_game(sender.to_string(), PlayerColor::White, metadata)
+        .unwrap();
+    db.store_message(
+        game.id.clone(),
+        "GameInvite".to_string(),
+        serde_json::to_string(&GameInvite::new(game.id.clone(), Some(Color::Black))).unwrap(),
+        "sig".to_string(),
+        sender.to_string(),
+    )
+    .unwrap();
+    game.id
+}
+
+fn auto_accept(db: &Arc<Database>, settings: AutoAcceptSettings) -> AutoAccept {
+    let identity = Arc::new(Identity::generate().unwrap());
+    AutoAccept::new(
+        Arc::clone(db),
+        Arc::clone(&identity),
+        NetworkManager::new(identity),
+        AutoAcceptSettings {
+            enabled: true,
+            ..settings
+        },
+    )
+}
+
+#[test]
+fn test_rules_check_the_inviter_and_clock() {
+    let (db, _temp_dir) = create_test_database();
+    invite(&db, INVITER, Some((5, 3)));
+    let settings = AutoAcceptSettings {
+        aliases: vec!["Alice".to_string()],
+        min_base_minutes: Some(3),
+        max_base_minutes: Some(10),
+        max_increment_secs: Some(5),
+        ..AutoAcceptSettings::default()
+    };
+    let mut candidate = auto_accept(&db, AutoAcceptSettings::default())
+        .candidates()
+        .unwrap()
+        .remove(0);
+
+    // Unverified and unnamed peers are left alone by default
+    let reason = check(&settings, &candidate, 0).unwrap_err();
+    assert!(reason.contains("not verified"), "{reason}");
+
+    db.mark_peer_verified(INVITER).unwrap();
+    db.set_peer_alias("bob", INVITER).unwrap();
+    let mut candidate_of = |settings: &AutoAcceptSettings| {
+        candidate = auto_accept(&db, settings.clone())
+            .candidates()
+            .unwrap()
+            .remove(0);
+        check(settings, &candidate, 0)
+    };
+    let reason = candidate_of(&settings).unwrap_err();
+    assert!(reason.contains("not one of Alice"), "{reason}");
+
+    db.set_peer_alias("alice", INVITER).unwrap();
+    assert_eq!(candidate_of(&settings), Ok(()));
+
+    for (rules, expected) in [
+        (
+            AutoAcceptSettings {
+                min_base_minutes: Some(10),
+                ..settings.clone()
+            },
+            "shorter than 10 minutes",
+        ),
+        (
+            AutoAcceptSettings {
+                max_base_minutes: Some(3),
+                ..settings.clone()
+            },
+            "longer than 3 minutes",
+        ),
+        (
+            AutoAcceptSettings {
+                max_increment_secs: Some(2),
+                ..settings.clone()
+            },
+            "more than 2 seconds",
+        ),
+    ] {
+        let reason = candidate_of(&rules).unwrap_err();
+        assert!(reason.contains(expected), "{reason}");
+    }
+
+    let reason = check(&settings, &candidate, 3).unwrap_err();
+    assert!(reason.contains("3 games are active"), "{reason}");
+}
+
+#[test]
+fn test_untimed_invitations_follow_allow_untimed() {
+    let (db, _temp_dir) = create_test_database();
+    invite(&db, INVITER, None);
+    db.mark_peer_verified(INVITER).unwrap();
+
+    let settings = AutoAcceptSettings::default();
+    let candidate = auto_accept(&db, settings.clone())
+        .candidates()
+        .unwrap()
+        .remove(0);
+    assert_eq!(check(&settings, &candidate, 0), Ok(()));
+
+    let settings = AutoAcceptSettings {
+        allow_untimed: false,
+        ..settings
+    };
+    let reason = check(&settings, &candidate, 0).unwrap_err();
+    assert!(reason.contains("untimed"), "{reason}");
+}
+
+#[test]
+fn test_matching_stops_at_max_active_games() {
+    let (db, _temp_dir) = create_test_database();
+    db.mark_peer_verified(INVITER).unwrap();
+    invite(&db, INVITER, Some((5, 0)));
+    invite(&db, INVITER, Some((5, 0)));
+    invite(&db, INVITER, Some((5, 0)));
+    let active = db
+        .create_game("other_peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&active.id, GameStatus::Active)
+        .unwrap();
+
+    let settings = AutoAcceptSettings {
+        max_active_games: 3,
+        ..AutoAcceptSettings::default()
+    };
+    let matching = auto_accept(&db, settings).matching().unwrap();
+    let before: Vec<usize> = matching.iter().map(|(_, active)| *active).collect();
+    assert_eq!(before, [1, 2]);
+}
+
+#[tokio::test]
+async fn test_accepted_invitation_starts_game_with_audit_record() {
+    let (db, _temp_dir) = create_test_database();
+    db.mark_peer_verified(INVITER).unwrap();
+    let game_id = invite(&db, INVITER, Some((5, 3)));
+
+    let task = auto_accept(&db, AutoAcceptSettings::default());
+    let (candidate, active_games) = task.matching().unwrap().remove(0);
+    task.accept(&candidate, active_games).await.unwrap();
+
+    let game = db.get_game(&game_id).unwrap();
+    assert_eq!(game.status, GameStatus::Active);
+    assert!(GameOps::new(&db)
+        .list_incoming_invitations("test_peer")
+        .unwrap()
+        .is_empty());
+
+    let messages = db.get_messages_for_game(&game_id).unwrap();
+    assert!(messages.iter().any(|m| m.message_type == "game_accept"));
+    let audit = messages
+        .iter()
+        .find(|m| m.message_type == AUTO_ACCEPT_MESSAGE_TYPE)
+        .expect("auto-accepted games keep an audit record");
+    let record: AutoAcceptRecord = serde_json::from_str(&audit.content).unwrap();
+    assert_eq!(record.inviter, INVITER);
+    assert!(record.verified);
+    assert_eq!(record.time_control, "5+3");
+    assert_eq!(record.active_games, 0);
+}
@@ -0,0 +1,67 @@
+//! Clipboard Invite Tests
+//!
+//! Tests for the invite strings in `src/cli/clipboard.rs` and the
+//! `invite --copy` / `accept --paste` flags
+
+use clap::Parser;
+use mate::cli::clipboard::InviteString;
+use mate::cli::{Cli, Commands};
+
+#[test]
+fn test_invite_string_round_trips() {
+    let invite = InviteString::new(
+        "3f2a9c1e-0000-4000-8000-000000000000",
+        "0123456789abcdef",
+        "192.168.1.20:8080",
+    );
+    let text = invite.to_string();
+
+    assert_eq!(
+        text,
+        "mate-invite://3f2a9c1e-0000-4000-8000-000000000000/0123456789abcdef@192.168.1.20:8080"
+    );
+    assert_eq!(text.parse::<InviteString>().unwrap(), invite);
+}
+
+#[test]
+fn test_invite_string_ignores_surrounding_whitespace() {
+    let invite: InviteString = "  mate-invite://abc/00ff@host.example:9000\n"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        invite,
+        InviteString::new("abc", "00ff", "host.example:9000")
+    );
+}
+
+#[test]
+fn test_invite_string_rejects_malformed_input() {
+    for text in [
+        "",
+        "abc123",
+        "mate://peer@127.0.0.1:8080",
+        "mate-invite://abc@127.0.0.1:8080",
+        "mate-invite:///00ff@127.0.0.1:8080",
+        "mate-invite://abc/@127.0.0.1:8080",
+        "mate-invite://abc/not-hex@127.0.0.1:8080",
+        "mate-invite://abc/00ff@localhost",
+    ] {
+        assert!(text.parse::<InviteString>().is_err(), "{text:?}");
+    }
+}
+
+#[test]
+fn test_copy_and_paste_flags_parse() {
+    match Cli::parse_from(["mate", "invite", "127.0.0.1:8080", "--copy"]).command {
+        Commands::Invite { copy, .. } => assert!(copy),
+        _ => panic!("expected invite"),
+    }
+    match Cli::parse_from(["mate", "accept", "--paste"]).command {
+        Commands::Accept { game_id, paste, .. } => {
+            assert!(paste);
+            assert_eq!(game_id, None);
+        }
+        _ => panic!("expected accept"),
+    }
+    assert!(Cli::try_parse_from(["mate", "accept", "abc123", "--paste"]).is_err());
+}
@@ -0,0 +1,62 @@
+//! Shell Completion and Man Page Tests
+//!
+//! Tests that `mate completions` and `mate man` can be generated from the
+//! `Cli` definition in `src/cli/commands.rs`
+
+use clap::{CommandFactory, Parser};
+use clap_complete::Shell;
+use mate::cli::Cli;
+
+#[test]
+fn test_cli_definition_is_valid() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn test_completions_generate_for_every_shell() {
+    for shell in [
+        Shell::Bash,
+        Shell::Zsh,
+        Shell::Fish,
+        Shell::PowerShell,
+        Shell::Elvish,
+    ] {
+        let mut output = Vec::new();
+        clap_complete::generate(shell, &mut Cli::command(), "mate", &mut output);
+        let script = String::from_utf8(output).expect("Completion script is not UTF-8");
+
+        assert!(
+            script.contains("mate"),
+            "{shell} script missing binary name"
+        );
+        assert!(
+            script.contains("play"),
+            "{shell} script missing subcommands"
+        );
+    }
+}
+
+#[test]
+fn test_man_page_renders() {
+    let mut output = Vec::new();
+    clap_mangen::Man::new(Cli::command())
+        .render(&mut output)
+        .expect("Failed to render man page");
+    let page = String::from_utf8(output).expect("Man page is not UTF-8");
+
+    assert!(page.contains(".TH mate 1"));
+    assert!(page.contains("completions"));
+    assert!(page.contains(env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn test_completion_commands_keep_stdout_clean() {
+    let cli = Cli::parse_from(["mate", "completions", "bash"]);
+    assert!(cli.stdout_is_data());
+
+    let cli = Cli::parse_from(["mate", "games"]);
+    assert!(!cli.stdout_is_data());
+
+    let cli = Cli::parse_from(["mate", "--json", "games"]);
+    assert!(cli.stdout_is_data());
+}
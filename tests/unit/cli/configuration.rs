@@ -4,7 +4,9 @@
 //! Following step 1.2 from the testing-plan.md
 
 use anyhow::Result;
-use mate::cli::app::Config;
+use mate::cli::app::{Config, RetryPolicy};
+use mate::crypto::storage::{KeyBackend, KeyPermissionPolicy};
+use mate::network::SendQueuePolicy;
 use rand;
 use std::fs;
 use std::path::PathBuf;
@@ -151,6 +153,8 @@ fn test_config_database_path_resolution() {
         data_dir: data_dir.clone(),
         default_bind_addr: "127.0.0.1:8080".to_string(),
         max_concurrent_games: 10,
+        db_path: None,
+        ..Default::default()
     };
 
     let db_path = config.database_path();
@@ -226,6 +230,8 @@ fn test_config_persistence_across_app_restarts() {
             data_dir: data_dir.clone(),
             default_bind_addr: "10.0.0.1:3000".to_string(),
             max_concurrent_games: 15,
+            db_path: None,
+            ..Default::default()
         };
 
         // Save the configuration
@@ -359,6 +365,8 @@ fn test_config_save_creates_directory_structure() {
             data_dir: data_dir.clone(),
             default_bind_addr: "127.0.0.1:8080".to_string(),
             max_concurrent_games: 10,
+            db_path: None,
+            ..Default::default()
         };
 
         // Save should create the directory structure
@@ -399,6 +407,8 @@ fn test_config_serialization_roundtrip() {
         data_dir,
         default_bind_addr: "0.0.0.0:9999".to_string(),
         max_concurrent_games: 42,
+        db_path: None,
+        ..Default::default()
     };
 
     // Serialize to TOML
@@ -419,3 +429,273 @@ fn test_config_serialization_roundtrip() {
         deserialized_config.max_concurrent_games
     );
 }
+
+#[test]
+fn test_config_sections_default_when_missing_from_file() {
+    // Files written before the [network]/[display]/... sections existed still load
+    let config: Config = toml::from_str(
+        r#"
+data_dir = "/data"
+default_bind_addr = "127.0.0.1:8080"
+max_concurrent_games = 10
+"#,
+    )
+    .expect("Failed to parse config without sections");
+
+    assert_eq!(config.network.connection_timeout_secs, 10);
+    assert_eq!(config.network.retry_policy, RetryPolicy::None);
+    assert_eq!(config.display.unicode, None);
+    assert_eq!(config.time_control.base_minutes, None);
+    assert!(!config.metrics.enabled);
+    assert!(config.notifications.moves);
+    assert!(config.notifications.invites);
+}
+
+#[test]
+fn test_config_get_and_set_by_key() {
+    let mut config = Config::default();
+
+    config.set("network.retry_policy", "patient").unwrap();
+    config.set("network.connection_timeout_secs", "30").unwrap();
+    config.set("display.unicode", "false").unwrap();
+    config.set("time_control.base_minutes", "15").unwrap();
+    config.set("default_bind_addr", "0.0.0.0:9000").unwrap();
+
+    assert_eq!(config.network.retry_policy, RetryPolicy::Patient);
+    assert_eq!(config.network.connection_timeout_secs, 30);
+    assert_eq!(config.display.unicode, Some(false));
+    assert_eq!(config.time_control.base_minutes, Some(15));
+
+    assert_eq!(
+        config.get("network.retry_policy").unwrap().as_deref(),
+        Some("patient")
+    );
+    assert_eq!(
+        config.get("default_bind_addr").unwrap().as_deref(),
+        Some("0.0.0.0:9000")
+    );
+    assert_eq!(config.get("db_path").unwrap(), None);
+
+    // Settings survive a save/load roundtrip through TOML
+    let reloaded: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(reloaded.network, config.network);
+    assert_eq!(reloaded.time_control, config.time_control);
+}
+
+#[test]
+fn test_config_notifications_are_configurable_per_event() {
+    let mut config = Config::default();
+    config.set("notifications.invites", "false").unwrap();
+
+    let notifier = config.notifications.notifier();
+    assert!(notifier.moves);
+    assert!(!notifier.invites);
+    assert_eq!(
+        config.get("notifications.invites").unwrap().as_deref(),
+        Some("false")
+    );
+}
+
+#[test]
+fn test_config_identity_storage_selects_key_backend() {
+    let mut config = Config::default();
+    assert_eq!(config.identity.storage, KeyBackend::File);
+
+    config.set("identity.storage", "keychain").unwrap();
+    assert_eq!(config.identity.storage, KeyBackend::Keychain);
+    assert_eq!(
+        config.get("identity.storage").unwrap().as_deref(),
+        Some("keychain")
+    );
+    assert!(config.set("identity.storage", "usb-stick").is_err());
+
+    let toml = toml::to_string(&config).unwrap();
+    assert!(toml.contains("[identity]"));
+    let reloaded: Config = toml::from_str(&toml).unwrap();
+    assert_eq!(reloaded.identity, config.identity);
+}
+
+#[test]
+fn test_config_display_theme_selects_board_style() {
+    let mut config = Config::default();
+    assert!(config.display.board_style().theme.is_none());
+
+    config.set("display.theme", "blue").unwrap();
+    assert_eq!(config.display.board_style().theme.unwrap().name, "blue");
+
+    // 'none' and unknown names both draw a plain board
+    config.set("display.theme", "none").unwrap();
+    assert!(config.display.board_style().theme.is_none());
+    config.set("display.theme", "neon").unwrap();
+    assert!(config.display.board_style().theme.is_none());
+}
+
+#[test]
+fn test_config_set_rejects_unknown_keys_and_bad_values() {
+    let mut config = Config::default();
+
+    assert!(config.set("network.no_such_setting", "1").is_err());
+    assert!(config.get("bogus").is_err());
+    assert!(config.set("network.retry_policy", "forever").is_err());
+    assert!(config.set("max_concurrent_games", "many").is_err());
+
+    // Failed updates leave the configuration untouched
+    assert_eq!(config.network.retry_policy, RetryPolicy::None);
+    assert_eq!(config.max_concurrent_games, 10);
+}
+
+#[test]
+fn test_config_env_overrides_file_values() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (config_dir, data_dir) = setup_test_dirs(&temp_dir);
+    create_valid_config_toml(&config_dir).expect("Failed to create test config");
+
+    with_env_vars(&config_dir, &data_dir, || {
+        std::env::set_var("MATE_DEFAULT_BIND_ADDR", "10.0.0.5:7000");
+        std::env::set_var("MATE_NETWORK_RETRY_POLICY", "quick");
+        let config = Config::load_or_default();
+        let stored = Config::load_stored();
+        std::env::remove_var("MATE_DEFAULT_BIND_ADDR");
+        std::env::remove_var("MATE_NETWORK_RETRY_POLICY");
+
+        let config = config.expect("Failed to load config");
+        assert_eq!(config.default_bind_addr, "10.0.0.5:7000");
+        assert_eq!(config.network.retry_policy, RetryPolicy::Quick);
+        assert_eq!(config.max_concurrent_games, 5);
+
+        // The stored file is unaffected by the environment
+        let stored = stored.expect("Failed to load stored config");
+        assert_eq!(stored.default_bind_addr, "192.168.1.100:9090");
+    });
+
+    assert_eq!(
+        Config::env_var("network.retry_policy"),
+        "MATE_NETWORK_RETRY_POLICY"
+    );
+}
+
+#[test]
+fn test_config_identity_active_defaults_to_unset() {
+    let mut config = Config::default();
+    assert_eq!(config.identity.active, None);
+    assert_eq!(config.get("identity.active").unwrap(), None);
+
+    config.set("identity.active", "tournament").unwrap();
+    assert_eq!(config.identity.active.as_deref(), Some("tournament"));
+
+    let reloaded: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(reloaded.identity.active.as_deref(), Some("tournament"));
+}
+
+#[test]
+fn test_config_key_permissions_default_to_strict() {
+    let mut config = Config::default();
+    assert_eq!(config.identity.key_permissions, KeyPermissionPolicy::Strict);
+    assert_eq!(
+        config.get("identity.key_permissions").unwrap().as_deref(),
+        Some("strict")
+    );
+
+    config.set("identity.key_permissions", "warn").unwrap();
+    assert_eq!(config.identity.key_permissions, KeyPermissionPolicy::Warn);
+    assert!(config.set("identity.key_permissions", "never").is_err());
+}
+
+#[test]
+fn test_config_max_clock_skew_reaches_wire_config() {
+    let mut config = Config::default();
+    assert_eq!(config.network.max_clock_skew_secs, 60);
+
+    config.set("network.max_clock_skew_secs", "5").unwrap();
+    assert_eq!(
+        config.network.network_config().wire_config.max_clock_skew,
+        std::time::Duration::from_secs(5)
+    );
+    assert!(config.set("network.max_clock_skew_secs", "-1").is_err());
+}
+
+#[test]
+fn test_config_tcp_nodelay_reaches_wire_config() {
+    let mut config = Config::default();
+    assert!(config.network.tcp_nodelay);
+    assert!(config.network.network_config().wire_config.tcp_nodelay);
+
+    config.set("network.tcp_nodelay", "false").unwrap();
+    assert!(!config.network.network_config().wire_config.tcp_nodelay);
+}
+
+#[test]
+fn test_config_send_queue_reaches_wire_config() {
+    let mut config = Config::default();
+    assert_eq!(config.network.send_queue_policy, SendQueuePolicy::Block);
+
+    config.set("network.send_queue_capacity", "8").unwrap();
+    config
+        .set("network.send_queue_policy", "drop-oldest-chat")
+        .unwrap();
+    assert_eq!(
+        config.get("network.send_queue_policy").unwrap().as_deref(),
+        Some("drop-oldest-chat")
+    );
+    let wire_config = config.network.network_config().wire_config;
+    assert_eq!(wire_config.send_queue_capacity, 8);
+    assert_eq!(
+        wire_config.send_queue_policy,
+        SendQueuePolicy::DropOldestChat
+    );
+    assert!(config
+        .set("network.send_queue_policy", "sometimes")
+        .is_err());
+}
+
+#[test]
+fn test_config_webhook_urls_are_set_as_a_list() {
+    let mut config = Config::default();
+    assert!(config.webhooks.urls.is_empty());
+
+    config
+        .set(
+            "webhooks.urls",
+            "http://localhost:9000/hook, http://relay:8000",
+        )
+        .unwrap();
+    assert_eq!(
+        config.webhooks.urls,
+        ["http://localhost:9000/hook", "http://relay:8000"]
+    );
+    config.set("webhooks.secret", "s3cret").unwrap();
+    assert!(config.webhooks.webhooks().unwrap().is_enabled());
+
+    config
+        .set("webhooks.urls", "https://hooks.example.com")
+        .unwrap();
+    assert!(config.webhooks.webhooks().unwrap().is_enabled());
+}
+
+#[test]
+fn test_config_telemetry_endpoint_must_be_a_url() {
+    let mut config = Config::default();
+    assert_eq!(config.telemetry.service_name, "mate");
+
+    config
+        .set("telemetry.otlp_endpoint", "http://localhost:4318")
+        .unwrap();
+    let endpoint = config.telemetry.endpoint().unwrap().unwrap();
+    assert_eq!(
+        endpoint.join("v1/traces").as_str(),
+        "http://localhost:4318/v1/traces"
+    );
+
+    config
+        .set("telemetry.otlp_endpoint", "https://tempo.example.com")
+        .unwrap();
+    let endpoint = config.telemetry.endpoint().unwrap().unwrap();
+    assert_eq!(
+        endpoint.join("v1/traces").as_str(),
+        "https://tempo.example.com/v1/traces"
+    );
+
+    assert!(config
+        .set("telemetry.otlp_endpoint", "tempo.example.com:4318")
+        .is_err());
+}
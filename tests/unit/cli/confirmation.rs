@@ -0,0 +1,73 @@
+//! Confirmation Prompt Tests
+//!
+//! Tests for the shared confirmation helper in `src/cli/display.rs` and the
+//! global --yes flag that skips it
+
+use clap::Parser;
+use mate::cli::display::{ask_confirmation, confirm, Confirmation};
+use mate::cli::Cli;
+use std::io::Cursor;
+
+fn ask(confirmation: &Confirmation, answers: &str) -> (bool, String) {
+    let mut output = Vec::new();
+    let confirmed =
+        ask_confirmation(confirmation, Cursor::new(answers.as_bytes()), &mut output).unwrap();
+    (confirmed, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_confirmation_lists_consequences_before_the_question() {
+    let confirmation = Confirmation::new("Permanently delete this data")
+        .consequence("delete 3 games")
+        .consequence("this cannot be undone");
+
+    let (confirmed, output) = ask(&confirmation, "y\n");
+
+    assert!(confirmed);
+    let summary = output.find("  - delete 3 games").unwrap();
+    let last = output.find("  - this cannot be undone").unwrap();
+    let question = output.find("Permanently delete this data? [y/N]").unwrap();
+    assert!(summary < last && last < question);
+}
+
+#[test]
+fn test_confirmation_only_accepts_an_explicit_yes() {
+    let confirmation = Confirmation::new("Replace your identity");
+
+    assert!(ask(&confirmation, "yes\n").0);
+    assert!(ask(&confirmation, "Y\n").0);
+    assert!(!ask(&confirmation, "n\n").0);
+    assert!(!ask(&confirmation, "\n").0);
+    assert!(!ask(&confirmation, "").0);
+}
+
+#[test]
+fn test_confirmation_asks_again_after_an_unclear_answer() {
+    let confirmation = Confirmation::new("Resign game abc");
+
+    let (confirmed, output) = ask(&confirmation, "maybe\ny\n");
+
+    assert!(confirmed);
+    assert_eq!(output.matches("Resign game abc? [y/N]").count(), 2);
+}
+
+#[test]
+fn test_confirm_skips_the_prompt_with_yes() {
+    let confirmation = Confirmation::new("Import account abc").consequence("replace your settings");
+    assert!(confirm(&confirmation, true).unwrap());
+}
+
+#[test]
+fn test_yes_flag_is_global() {
+    for args in [
+        &["mate", "--yes", "purge", "--peer", "alice"][..],
+        &["mate", "purge", "--peer", "alice", "--yes"],
+        &["mate", "resign", "-y"],
+        &["mate", "offer-draw", "abc123", "--yes"],
+        &["mate", "key", "generate", "--yes"],
+        &["mate", "import-account", "backup.mate", "-y"],
+    ] {
+        assert!(Cli::parse_from(args).yes, "{args:?}");
+    }
+    assert!(!Cli::parse_from(["mate", "purge", "--peer", "alice"]).yes);
+}
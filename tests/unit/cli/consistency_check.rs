@@ -0,0 +1,269 @@
+//! Startup Consistency Check Tests
+//!
+//! Tests for replay verification of active games in `src/cli/game_ops.rs` and
+//! the check run by `App` on startup
+
+use mate::chess::{Board, Move};
+use mate::cli::app::App;
+use mate::cli::game_ops::{GameOps, MoveProcessor, CHECKPOINT_INTERVAL};
+use mate::messages::chess::{hash_board_state, Move as MoveMessage};
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::str::FromStr;
+use tempfile::TempDir;
+
+/// Create an active game in the database
+fn create_active_game(db: &Database) -> String {
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&game.id, GameStatus::Active).unwrap();
+    game.id
+}
+
+/// Store a move message with the given board hash
+fn store_move(db: &Database, game_id: &str, chess_move: &str, board_hash: String) {
+    let message = MoveMessage::new(game_id.to_string(), chess_move.to_string(), board_hash);
+    db.store_message(
+        game_id.to_string(),
+        "Move".to_string(),
+        serde_json::to_string(&message).unwrap(),
+        "sig".to_string(),
+        "opponent_peer".to_string(),
+    )
+    .unwrap();
+}
+
+/// Store a sequence of moves with correct board hashes
+fn store_valid_moves(db: &Database, game_id: &str, moves: &[&str]) -> Board {
+    let mut board = Board::new();
+    for chess_move in moves {
+        board
+            .make_move(Move::from_str(chess_move).unwrap())
+            .unwrap();
+        store_move(db, game_id, chess_move, hash_board_state(&board));
+    }
+    board
+}
+
+#[test]
+fn test_consistent_game_passes_verification() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    store_valid_moves(&db, &game_id, &["e2e4", "e7e5", "g1f3"]);
+
+    let game_ops = GameOps::new(&db);
+    assert_eq!(game_ops.verify_game_consistency(&game_id).unwrap(), None);
+    assert!(game_ops.check_active_games().unwrap().is_empty());
+    assert!(db.get_game_sync_issue(&game_id).unwrap().is_none());
+}
+
+#[test]
+fn test_hash_mismatch_flags_game() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    store_valid_moves(&db, &game_id, &["e2e4"]);
+    store_move(&db, &game_id, "e7e5", "0".repeat(64));
+
+    let game_ops = GameOps::new(&db);
+    let reason = game_ops.verify_game_consistency(&game_id).unwrap().unwrap();
+    assert!(reason.contains("move 2"), "Unexpected reason: {reason}");
+
+    let issues = game_ops.check_active_games().unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].game_id, game_id);
+    assert!(db.get_game_sync_issue(&game_id).unwrap().is_some());
+}
+
+#[test]
+fn test_unreplayable_move_flags_game() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    store_valid_moves(&db, &game_id, &["e2e4"]);
+    // No piece on e2 any more
+    store_move(&db, &game_id, "e2e4", String::new());
+
+    let reason = GameOps::new(&db)
+        .verify_game_consistency(&game_id)
+        .unwrap()
+        .unwrap();
+    assert!(
+        reason.contains("cannot be replayed"),
+        "Unexpected reason: {reason}"
+    );
+}
+
+#[test]
+fn test_flag_cleared_once_game_verifies() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    store_valid_moves(&db, &game_id, &["d2d4"]);
+    db.flag_game_needs_sync(&game_id, "stale flag").unwrap();
+
+    assert!(GameOps::new(&db).check_active_games().unwrap().is_empty());
+    assert!(db.get_game_sync_issue(&game_id).unwrap().is_none());
+}
+
+#[test]
+fn test_flagged_game_blocks_moves() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    db.flag_game_needs_sync(&game_id, "board mismatch").unwrap();
+
+    let result = MoveProcessor::new(&db).process_move(&game_id, "e2e4", false);
+    let error = result.expect_err("Moves should be blocked while the game needs sync");
+    assert!(error.to_string().contains("needs sync"), "{error}");
+
+    db.clear_game_sync_issue(&game_id).unwrap();
+    assert!(MoveProcessor::new(&db)
+        .process_move(&game_id, "e2e4", false)
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_app_startup_flags_inconsistent_games() {
+    let temp_dir = TempDir::new().unwrap();
+    let data_dir = temp_dir.path().to_path_buf();
+
+    let (good_game, bad_game) = {
+        let db = Database::new_with_path("test_peer", &data_dir.join("database.sqlite")).unwrap();
+        let good_game = create_active_game(&db);
+        store_valid_moves(&db, &good_game, &["e2e4", "c7c5"]);
+        let bad_game = create_active_game(&db);
+        store_move(&db, &bad_game, "e2e4", "not-the-hash".to_string());
+        (good_game, bad_game)
+    };
+
+    // Not attached to a terminal, so startup warns without prompting
+    let app = App::new_with_data_dir(data_dir)
+        .await
+        .expect("Startup should continue when not interactive");
+
+    assert!(app
+        .database
+        .get_game_sync_issue(&good_game)
+        .unwrap()
+        .is_none());
+    let issue = app
+        .database
+        .get_game_sync_issue(&bad_game)
+        .unwrap()
+        .unwrap();
+    assert!(issue.reason.contains("move 1"));
+}
+
+/// Knights out and back, `CHECKPOINT_INTERVAL` moves or more in all
+fn knight_shuffle() -> Vec<&'static str> {
+    let round = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    round
+        .iter()
+        .cycle()
+        .take(CHECKPOINT_INTERVAL.div_ceil(4) * 4)
+        .copied()
+        .collect()
+}
+
+#[test]
+fn test_verified_game_is_checkpointed() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    let moves = knight_shuffle();
+    let board = store_valid_moves(&db, &game_id, &moves);
+
+    let game_ops = GameOps::new(&db);
+    assert_eq!(game_ops.verify_game_consistency(&game_id).unwrap(), None);
+    let checkpoint = db.get_game_checkpoint(&game_id).unwrap().unwrap();
+    assert_eq!(checkpoint.ply as usize, moves.len());
+    assert_eq!(checkpoint.fen, board.to_fen());
+
+    // Moves after the checkpoint are still replayed and verified
+    store_move(&db, &game_id, "e2e4", "0".repeat(64));
+    let reason = game_ops.verify_game_consistency(&game_id).unwrap().unwrap();
+    assert!(
+        reason.contains(&format!("move {}", moves.len() + 1)),
+        "{reason}"
+    );
+}
+
+#[test]
+fn test_short_game_is_not_checkpointed() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    store_valid_moves(&db, &game_id, &["e2e4", "e7e5"]);
+
+    assert_eq!(
+        GameOps::new(&db).verify_game_consistency(&game_id).unwrap(),
+        None
+    );
+    assert!(db.get_game_checkpoint(&game_id).unwrap().is_none());
+}
+
+#[test]
+fn test_reconstruction_resumes_from_checkpoint() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    let moves = knight_shuffle();
+    store_valid_moves(&db, &game_id, &moves);
+    let game_ops = GameOps::new(&db);
+    game_ops.verify_game_consistency(&game_id).unwrap();
+    let checkpoint = db.get_game_checkpoint(&game_id).unwrap().unwrap();
+
+    // A checkpoint that matches the stored moves is taken as it is
+    let after_e4 =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+    db.save_game_checkpoint(
+        &game_id,
+        checkpoint.ply,
+        &after_e4.to_fen(),
+        &checkpoint.moves_hash,
+        &hash_board_state(&after_e4),
+    )
+    .unwrap();
+    let state = game_ops.reconstruct_game_state(&game_id).unwrap();
+    assert_eq!(state.board.to_fen(), after_e4.to_fen());
+    assert_eq!(state.move_history.len(), moves.len());
+}
+
+#[test]
+fn test_mismatched_checkpoint_falls_back_to_full_replay() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game_id = create_active_game(&db);
+    let board = store_valid_moves(&db, &game_id, &knight_shuffle());
+    let game_ops = GameOps::new(&db);
+    game_ops.verify_game_consistency(&game_id).unwrap();
+    let checkpoint = db.get_game_checkpoint(&game_id).unwrap().unwrap();
+
+    // Board hash that doesn't match the checkpoint's position
+    db.save_game_checkpoint(
+        &game_id,
+        checkpoint.ply,
+        "8/8/8/8/8/8/8/K6k w - - 0 1",
+        &checkpoint.moves_hash,
+        &checkpoint.board_hash,
+    )
+    .unwrap();
+    let state = game_ops.reconstruct_game_state(&game_id).unwrap();
+    assert_eq!(state.board.to_fen(), board.to_fen());
+
+    // Moves that changed since the checkpoint was saved
+    let lone_kings = Board::from_fen("8/8/8/8/8/8/8/K6k w - - 0 1").unwrap();
+    db.save_game_checkpoint(
+        &game_id,
+        checkpoint.ply,
+        &lone_kings.to_fen(),
+        &"0".repeat(64),
+        &hash_board_state(&lone_kings),
+    )
+    .unwrap();
+    let state = game_ops.reconstruct_game_state(&game_id).unwrap();
+    assert_eq!(state.board.to_fen(), board.to_fen());
+    assert_eq!(game_ops.verify_game_consistency(&game_id).unwrap(), None);
+}
@@ -0,0 +1,70 @@
+//! Crash Report Tests
+//!
+//! Tests for the crash bundles written by the panic hook in `src/cli/crash.rs`
+
+use mate::cli::app::Config;
+use mate::cli::crash::{redacted_config, CrashReport, RecentEvents, REDACTED};
+use std::fs;
+use std::io::Write;
+use tempfile::TempDir;
+use tracing_subscriber::fmt::MakeWriter;
+
+fn report() -> CrashReport {
+    CrashReport {
+        message: "index out of bounds".to_string(),
+        location: Some("src/chess/board.rs:10:5".to_string()),
+        thread: "main".to_string(),
+        version: "0.1.0".to_string(),
+        backtrace: "0: mate::main".to_string(),
+        events: vec!["DEBUG first".to_string(), "INFO second".to_string()],
+        config: Some("max_concurrent_games = 10\n".to_string()),
+    }
+}
+
+#[test]
+fn test_recent_events_keep_the_last_lines() {
+    let events = RecentEvents::new(2);
+    for line in ["one\n", "two\n", "three\nfour\n"] {
+        let mut writer = events.make_writer();
+        writer.write_all(line.as_bytes()).unwrap();
+    }
+    assert_eq!(events.lines(), vec!["three", "four"]);
+}
+
+#[test]
+fn test_bundle_holds_panic_events_and_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let bundle = report().write_bundle(temp_dir.path()).unwrap();
+
+    let panic = fs::read_to_string(bundle.join("panic.txt")).unwrap();
+    assert!(panic.contains("mate 0.1.0"));
+    assert!(panic.contains("panicked at src/chess/board.rs:10:5"));
+    assert!(panic.contains("index out of bounds"));
+    assert!(panic.contains("0: mate::main"));
+    assert_eq!(
+        fs::read_to_string(bundle.join("events.log")).unwrap(),
+        "DEBUG first\nINFO second\n"
+    );
+    assert!(bundle.join("config.toml").exists());
+
+    // A second crash doesn't overwrite the first bundle
+    let second = report().write_bundle(temp_dir.path()).unwrap();
+    assert_ne!(second, bundle);
+}
+
+#[test]
+fn test_config_secrets_are_redacted() {
+    let mut config = Config::default();
+    config.api.token = Some("api-token-value".to_string());
+    config.webhooks.secret = Some("hmac-secret-value".to_string());
+    config.email.to = Some("me@example.com".to_string());
+    config.webhooks.urls = vec!["https://hooks.slack.com/services/T000/B000/XXXX".to_string()];
+
+    let redacted = redacted_config(&config).unwrap();
+    assert!(!redacted.contains("api-token-value"));
+    assert!(!redacted.contains("hmac-secret-value"));
+    assert!(!redacted.contains("me@example.com"));
+    assert!(!redacted.contains("hooks.slack.com"));
+    assert!(redacted.contains(REDACTED));
+    assert!(redacted.contains("max_concurrent_games"));
+}
@@ -0,0 +1,150 @@
+//! Data Export Tests
+//!
+//! Tests for the CSV and JSON exports of games and moves in
+//! `src/cli/data_export.rs`.
+
+use mate::chess::{Color, Odds};
+use mate::cli::data_export::{export_csv, ExportFormat, ExportRows, GameExport};
+use mate::cli::game_ops::MoveProcessor;
+use mate::messages::chess::GameInvite;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use tempfile::TempDir;
+
+fn create_test_database() -> (TempDir, Database) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("my_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    (temp_dir, db)
+}
+
+/// An active game as White in which `moves` have been played
+fn game_with_moves(db: &Database, moves: &[&str]) -> String {
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&game.id, GameStatus::Active).unwrap();
+    let processor = MoveProcessor::new(db);
+    for (ply, mv) in moves.iter().enumerate() {
+        processor.process_move(&game.id, mv, ply % 2 == 0).unwrap();
+    }
+    game.id
+}
+
+#[test]
+fn test_moves_are_exported_in_san_with_their_hashes() {
+    let (_temp_dir, db) = create_test_database();
+    let game_id = game_with_moves(&db, &["e2e4", "e7e5", "g1f3"]);
+
+    let export = GameExport::load(&db, &game_id).unwrap();
+    assert_eq!(export.opponent, "opponent_peer");
+    assert_eq!(export.my_color, "white");
+    assert_eq!(export.pgn_result, "*");
+    assert_eq!(export.time_control, "untimed");
+
+    let moves: Vec<_> = export
+        .moves
+        .iter()
+        .map(|m| {
+            (
+                m.ply,
+                m.move_number,
+                m.color.as_str(),
+                m.san.as_deref(),
+                m.mine,
+            )
+        })
+        .collect();
+    assert_eq!(
+        moves,
+        vec![
+            (1, 1, "white", Some("e4"), true),
+            (2, 1, "black", Some("e5"), false),
+            (3, 2, "white", Some("Nf3"), true),
+        ]
+    );
+    assert_eq!(export.moves[2].notation, "g1f3");
+    assert!(export.moves.iter().all(|m| !m.board_state_hash.is_empty()));
+}
+
+#[test]
+fn test_extra_odds_moves_share_the_side_to_move() {
+    let (_temp_dir, db) = create_test_database();
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::Black, None)
+        .unwrap();
+    let odds = Odds {
+        giver: Color::White,
+        removed: vec!["b1".parse().unwrap()],
+        extra_moves: 1,
+    };
+    let invite = GameInvite::new(game.id.clone(), Some(Color::Black)).with_odds(odds);
+    db.store_message(
+        game.id.clone(),
+        "GameInvite".to_string(),
+        serde_json::to_string(&invite).unwrap(),
+        "signature".to_string(),
+        "opponent_peer".to_string(),
+    )
+    .unwrap();
+    db.update_game_status(&game.id, GameStatus::Active).unwrap();
+    let processor = MoveProcessor::new(&db);
+    processor.process_move(&game.id, "e7e5", true).unwrap();
+    processor.process_move(&game.id, "e2e4", false).unwrap();
+    processor.process_move(&game.id, "d7d5", true).unwrap();
+
+    let export = GameExport::load(&db, &game.id).unwrap();
+    let moves: Vec<_> = export
+        .moves
+        .iter()
+        .map(|m| (m.move_number, m.color.as_str(), m.san.as_deref(), m.mine))
+        .collect();
+    assert_eq!(
+        moves,
+        vec![
+            (1, "black", Some("e5"), true),
+            (2, "white", Some("e4"), false),
+            (2, "black", Some("d5"), true),
+        ]
+    );
+}
+
+#[test]
+fn test_csv_has_a_record_per_move_or_per_game() {
+    let (_temp_dir, db) = create_test_database();
+    let first = game_with_moves(&db, &["e2e4", "e7e5"]);
+    let second = game_with_moves(&db, &["d2d4"]);
+    db.add_game_tag(&first, "blitz").unwrap();
+    db.add_game_tag(&first, "club").unwrap();
+    let games = vec![
+        GameExport::load(&db, &first).unwrap(),
+        GameExport::load(&db, &second).unwrap(),
+    ];
+
+    let csv = export_csv(&games, ExportRows::Moves);
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("game_id,opponent,my_color,"));
+    assert!(
+        lines[0].ends_with(",ply,move_number,color,san,notation,board_state_hash,mine,played_at")
+    );
+    assert!(lines[1].starts_with(&format!(
+        "{first},opponent_peer,white,active,,*,untimed,blitz club,"
+    )));
+    assert!(lines[2].contains(",2,1,black,e5,e7e5,"));
+    assert!(lines[3].starts_with(&format!("{second},")));
+
+    let csv = export_csv(&games, ExportRows::Games);
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with(",completed_at,moves"));
+    assert!(lines[1].starts_with(&first) && lines[1].ends_with(",2"));
+    assert!(lines[2].starts_with(&second) && lines[2].ends_with(",1"));
+}
+
+#[test]
+fn test_export_options_parse() {
+    assert_eq!("CSV".parse::<ExportFormat>(), Ok(ExportFormat::Csv));
+    assert_eq!("json".parse::<ExportFormat>(), Ok(ExportFormat::Json));
+    assert_eq!("games".parse::<ExportRows>(), Ok(ExportRows::Games));
+    assert!("xml".parse::<ExportFormat>().is_err());
+    assert!("plies".parse::<ExportRows>().is_err());
+}
@@ -0,0 +1,71 @@
+//! Tests for `mate debug decode`
+
+use mate::cli::debug::{decode_frames, read_capture};
+use mate::crypto::Identity;
+use mate::messages::types::SignedEnvelope;
+use mate::messages::wire::FramedMessage;
+use mate::messages::Message;
+use tempfile::TempDir;
+
+async fn frame(message: &Message) -> Vec<u8> {
+    let identity = Identity::generate().unwrap();
+    let envelope = SignedEnvelope::create(message, &identity, None).unwrap();
+    let mut bytes = Vec::new();
+    FramedMessage::default()
+        .write_message(&mut bytes, &envelope)
+        .await
+        .unwrap();
+    bytes
+}
+
+#[tokio::test]
+async fn test_hex_capture_of_two_frames_is_decoded() {
+    let mut capture = frame(&Message::new_ping(7, "hello".to_string())).await;
+    capture.extend(frame(&Message::new_pong(7, "hello".to_string())).await);
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("frame.hex");
+    let spaced: Vec<String> = capture.iter().map(|byte| format!("{byte:02x}")).collect();
+    std::fs::write(&path, spaced.join(" ") + "\n").unwrap();
+
+    let report = decode_frames(&read_capture(&path).unwrap()).unwrap();
+    assert_eq!(report.frames.len(), 2);
+    assert_eq!(report.invalid_signatures(), 0);
+    assert_eq!(report.frames[0].offset, 0);
+    assert_eq!(report.frames[0].message_type, "Ping");
+    assert_eq!(report.frames[1].offset, report.frames[0].length + 4);
+    assert_eq!(report.frames[1].message_type, "Pong");
+    assert!(report.frames[0].validation_error.is_none());
+    assert!(report.to_string().contains("Signature: valid"));
+}
+
+#[tokio::test]
+async fn test_tampered_signature_is_reported() {
+    let mut capture = frame(&Message::new_ping(1, "payload".to_string())).await;
+    // The payload string sits inside the signed message bytes
+    let position = capture
+        .windows(7)
+        .position(|window| window == b"payload")
+        .unwrap();
+    capture[position] = b'P';
+
+    let report = decode_frames(&capture).unwrap();
+    assert_eq!(report.invalid_signatures(), 1);
+    assert!(report.to_string().contains("Signature: INVALID"));
+}
+
+#[tokio::test]
+async fn test_bad_length_prefixes_are_rejected() {
+    let capture = frame(&Message::new_ping(1, String::new())).await;
+
+    let error = decode_frames(&capture[..capture.len() - 1]).unwrap_err();
+    assert!(error.to_string().contains("only"), "{error}");
+
+    let error = decode_frames(&[0, 0, 0, 0]).unwrap_err();
+    assert!(error.to_string().contains("outside"), "{error}");
+
+    let mut trailing = capture.clone();
+    trailing.extend([0, 0]);
+    let error = decode_frames(&trailing).unwrap_err();
+    assert!(error.to_string().contains("too short"), "{error}");
+}
@@ -5,8 +5,9 @@
 
 use mate::chess::{Board, Color};
 use mate::cli::display::*;
+use mate::cli::game_ops::game_clocks;
 use mate::cli::GameRecord;
-use mate::storage::models::{Game, GameResult, GameStatus, PlayerColor};
+use mate::storage::models::{Game, GameResult, GameStatus, PlayerColor, TimeControl};
 
 /// Helper function to create test game records
 fn create_test_game_record(
@@ -33,6 +34,8 @@ fn create_test_game_record(
         last_move: None,
         your_turn,
         move_count,
+        odds: None,
+        rated: false,
     }
 }
 
@@ -480,3 +483,206 @@ fn test_move_history_edge_cases() {
     ];
     display_move_history(&special_moves, 3);
 }
+
+#[test]
+fn test_game_json_schema() {
+    let mut record = create_test_game_record(
+        "game-1",
+        None,
+        GameStatus::Completed,
+        PlayerColor::Black,
+        false,
+        42,
+    );
+    record.game.result = Some(GameResult::Draw);
+    record.game.completed_at = Some(1234567999);
+
+    let json = serde_json::to_value(GameJson::new(&record, vec!["blitz".to_string()], None))
+        .expect("Failed to serialize game");
+
+    assert_eq!(json["id"], "game-1");
+    assert_eq!(json["opponent"], "peer123");
+    assert_eq!(json["color"], "black");
+    assert_eq!(json["status"], "completed");
+    assert_eq!(json["result"], "draw");
+    assert_eq!(json["move_count"], 42);
+    assert_eq!(json["completed_at"], 1234567999);
+    assert_eq!(json["tags"], serde_json::json!(["blitz"]));
+    assert!(json["needs_sync"].is_null());
+}
+
+#[test]
+fn test_board_json_schema() {
+    let board = Board::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+        .expect("Failed to parse FEN");
+    let json = serde_json::to_value(BoardJson::new("game-1", &board, vec![], false))
+        .expect("Failed to serialize board");
+
+    assert_eq!(json["schema_version"], JSON_SCHEMA_VERSION);
+    assert_eq!(json["to_move"], "white");
+    assert_eq!(json["fen"], board.to_fen());
+    assert_eq!(json["in_check"], true);
+    assert_eq!(json["checkmate"], true);
+    assert_eq!(json["stalemate"], false);
+    assert_eq!(json["moves"], serde_json::json!([]));
+}
+
+#[test]
+fn test_output_format_defaults_to_text() {
+    assert_eq!(OutputFormat::default(), OutputFormat::Text);
+}
+
+// ==============================================
+// Themed Board Tests
+// ==============================================
+
+#[test]
+fn test_color_support_from_env() {
+    assert_eq!(
+        ColorSupport::from_env(Some("truecolor"), Some("xterm")),
+        ColorSupport::TrueColor
+    );
+    assert_eq!(
+        ColorSupport::from_env(None, Some("xterm-256color")),
+        ColorSupport::Ansi256
+    );
+    assert_eq!(
+        ColorSupport::from_env(None, Some("dumb")),
+        ColorSupport::None
+    );
+    assert_eq!(ColorSupport::from_env(None, None), ColorSupport::None);
+}
+
+#[test]
+fn test_rgb_downgrades_to_256_color_cube() {
+    assert_eq!(Rgb(0, 0, 0).to_ansi256(), 16);
+    assert_eq!(Rgb(255, 255, 255).to_ansi256(), 231);
+    assert_eq!(Rgb(255, 0, 0).to_ansi256(), 196);
+    assert_eq!(Rgb(95, 135, 175).to_ansi256(), 67);
+}
+
+#[test]
+fn test_theme_lookup() {
+    assert_eq!(Theme::by_name("classic").unwrap().name, "classic");
+    assert_eq!(Theme::by_name("GREEN").unwrap().name, "green");
+    assert!(Theme::by_name("neon").is_none());
+    assert!(Theme::names().any(|name| name == NO_THEME));
+}
+
+#[test]
+fn test_highlights_last_move_and_check() {
+    let board = Board::new();
+    assert_eq!(Highlights::new(&board, None), Highlights::default());
+
+    // After 1.f3 e5 2.g4 Qh4 the White king on e1 is in check
+    let board = board_from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+    let highlights = Highlights::new(&board, Some("d8h4"));
+    assert_eq!(
+        highlights.last_move,
+        Some(("d8".parse().unwrap(), "h4".parse().unwrap()))
+    );
+    assert_eq!(highlights.check, Some("e1".parse().unwrap()));
+}
+
+#[test]
+fn test_themed_board_uses_theme_colors() {
+    let board = board_from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2");
+    let theme = Theme::by_name("classic").unwrap();
+    let highlights = Highlights::new(&board, Some("e7e5"));
+
+    let lines = render_board_themed(
+        &board,
+        Color::White,
+        theme,
+        ColorSupport::TrueColor,
+        false,
+        &highlights,
+    );
+    assert_eq!(lines.len(), 9);
+    assert!(lines[0].starts_with("8 "));
+    assert!(lines[8].contains(" a  b  c "));
+
+    // Rank 7 holds e7 and rank 5 holds e5, both highlighted as the last move
+    let last_move = "\x1B[48;2;205;210;106m";
+    assert!(lines[1].contains(last_move));
+    assert!(lines[3].contains(last_move));
+    assert!(!lines[7].contains(last_move));
+
+    // The 256-color rendering carries no truecolor sequences
+    let lines = render_board_themed(
+        &board,
+        Color::Black,
+        theme,
+        ColorSupport::Ansi256,
+        true,
+        &highlights,
+    );
+    assert!(lines[0].starts_with("1 "));
+    assert!(lines.iter().all(|line| !line.contains(";2;")));
+    assert!(lines[0].contains("\x1B[48;5;"));
+}
+
+#[test]
+fn test_compact_game_line() {
+    let mut record = create_test_game_record(
+        "3f2a9c1e-7b44-4d6e",
+        Some("alexandra_the_great".to_string()),
+        GameStatus::Active,
+        PlayerColor::Black,
+        true,
+        12,
+    );
+    assert_eq!(
+        compact_game_line(&record),
+        "* 3f2a9c1e B alexandra_t~ active"
+    );
+
+    record.game.status = GameStatus::Completed;
+    record.game.result = Some(GameResult::Win);
+    record.opponent_name = None;
+    assert_eq!(compact_game_line(&record), "  3f2a9c1e B peer123      win");
+}
+
+#[test]
+fn test_compact_board_lines() {
+    let board = board_from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2");
+    let moves = vec!["e2e4".to_string(), "e7e5".to_string()];
+
+    let [position, status] = compact_board_lines(&board, &moves, Some([295_000, 3_725_000]));
+    assert_eq!(
+        position,
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6"
+    );
+    assert_eq!(status, "1... e7e5  W 4:55 B 1:02:05");
+
+    let [_, status] = compact_board_lines(&Board::new(), &[], None);
+    assert_eq!(status, "start  untimed");
+}
+
+#[test]
+fn test_game_clocks_charge_each_side_from_its_first_move() {
+    let time_control = TimeControl {
+        initial_time_ms: 300_000,
+        increment_ms: 2_000,
+    };
+
+    // White's first move and Black's reply are free; White then takes 10s
+    // and Black 30s, each earning the increment on every move
+    let times = [1000, 1020, 1030, 1060];
+    assert_eq!(
+        game_clocks(&time_control, None, &times, None),
+        [300_000 + 4_000 - 10_000, 300_000 + 4_000 - 30_000]
+    );
+
+    // White's clock runs while it is White's move
+    assert_eq!(
+        game_clocks(&time_control, None, &times, Some(1075)),
+        [300_000 + 4_000 - 25_000, 300_000 + 4_000 - 30_000]
+    );
+
+    // An empty clock doesn't go below zero
+    assert_eq!(
+        game_clocks(&time_control, None, &times, Some(2000)),
+        [0, 300_000 + 4_000 - 30_000]
+    );
+}
@@ -0,0 +1,51 @@
+//! Tests for the `mate doctor` checks
+
+use mate::cli::doctor::{run_checks, Check, CheckStatus, DoctorOptions};
+use mate::storage::{Database, PlayerColor};
+use tempfile::TempDir;
+
+fn find<'a>(checks: &'a [Check], name: &str) -> &'a Check {
+    checks
+        .iter()
+        .find(|check| check.name == name)
+        .unwrap_or_else(|| panic!("no '{name}' check in {checks:?}"))
+}
+
+#[tokio::test]
+async fn test_healthy_database_passes_and_peer_is_skipped() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("database.sqlite");
+    let db = Database::new_with_path("test_peer_12345678", &db_path).unwrap();
+    db.create_game("peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    drop(db);
+
+    let checks = run_checks(&DoctorOptions {
+        db_path: Some(db_path),
+        peer: None,
+    })
+    .await;
+
+    let database = find(&checks, "database");
+    assert_eq!(database.status, CheckStatus::Pass, "{database:?}");
+    assert!(database.detail.contains("integrity"));
+    assert_eq!(find(&checks, "peer").status, CheckStatus::Skip);
+    assert_eq!(find(&checks, "clock").status, CheckStatus::Pass);
+}
+
+#[tokio::test]
+async fn test_damaged_database_fails_with_a_fix() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("database.sqlite");
+    std::fs::write(&db_path, b"this is not a database, just some text").unwrap();
+
+    let checks = run_checks(&DoctorOptions {
+        db_path: Some(db_path),
+        peer: None,
+    })
+    .await;
+
+    let database = find(&checks, "database");
+    assert_eq!(database.status, CheckStatus::Fail, "{database:?}");
+    assert!(database.fix.is_some());
+}
@@ -0,0 +1,102 @@
+//! Error Code Tests
+//!
+//! Tests for the error codes and exit statuses in `src/cli/error_handler.rs`
+//! and the `--json` error envelope in `src/cli/display.rs`
+
+use anyhow::Context;
+use mate::chess::ChessError;
+use mate::cli::display::ErrorJson;
+use mate::cli::game_ops::MoveProcessingError;
+use mate::cli::{CliError, ErrorCode, GameOpsError};
+use mate::messages::wire::WireProtocolError;
+use mate::network::ConnectionError;
+use std::collections::HashSet;
+use std::time::Duration;
+
+#[test]
+fn test_codes_and_exit_statuses_are_distinct() {
+    let statuses: HashSet<i32> = ErrorCode::ALL.iter().map(|c| c.exit_status()).collect();
+    let names: HashSet<&str> = ErrorCode::ALL.iter().map(|c| c.as_str()).collect();
+    assert_eq!(statuses.len(), ErrorCode::ALL.len());
+    assert_eq!(names.len(), ErrorCode::ALL.len());
+    assert!(!statuses.contains(&0));
+    assert_eq!(ErrorCode::Internal.exit_status(), 1);
+    assert_eq!(ErrorCode::InvalidInput.exit_status(), 2);
+}
+
+#[test]
+fn test_typed_errors_map_to_codes() {
+    let cases = [
+        (
+            CliError::Chess(ChessError::InvalidMove("e2e5".to_string())),
+            ErrorCode::ChessRule,
+        ),
+        (
+            CliError::GameOps(GameOpsError::GameNotFound("abc".to_string())),
+            ErrorCode::GameNotFound,
+        ),
+        (
+            CliError::GameOps(GameOpsError::NoCurrentGame),
+            ErrorCode::NoCurrentGame,
+        ),
+        (
+            CliError::Connection(ConnectionError::ConnectionClosed),
+            ErrorCode::Network,
+        ),
+        (
+            CliError::Wire(WireProtocolError::ReadTimeout {
+                timeout: Duration::from_secs(5),
+            }),
+            ErrorCode::Timeout,
+        ),
+        (
+            CliError::Wire(WireProtocolError::ProtocolViolation {
+                description: "bad frame".to_string(),
+            }),
+            ErrorCode::Protocol,
+        ),
+    ];
+    for (error, code) in cases {
+        assert_eq!(error.code(), code, "{error:?}");
+        assert_eq!(error.exit_status(), code.exit_status());
+    }
+}
+
+#[test]
+fn test_wrapped_errors_keep_their_code() {
+    let illegal: anyhow::Result<()> = Err(MoveProcessingError::Chess(ChessError::InvalidMove(
+        "no piece on e3".to_string(),
+    )))
+    .context("Failed to play the move");
+    assert_eq!(
+        CliError::from(illegal.unwrap_err()).code(),
+        ErrorCode::ChessRule
+    );
+
+    let refused = anyhow::anyhow!("Failed to connect to 127.0.0.1:8080");
+    assert_eq!(CliError::from(refused).code(), ErrorCode::Network);
+
+    let unexpected = anyhow::anyhow!("Something else went wrong");
+    assert_eq!(CliError::from(unexpected).code(), ErrorCode::Internal);
+}
+
+#[test]
+fn test_json_envelope_carries_code() {
+    let error = CliError::UserError {
+        message: "The move is not legal".to_string(),
+        suggestion: Some("List legal moves with 'mate moves'".to_string()),
+        code: ErrorCode::ChessRule,
+    };
+    let json = serde_json::to_value(ErrorJson::new(&error)).unwrap();
+    assert_eq!(json["error"]["code"], "chess_rule");
+    assert_eq!(
+        json["error"]["exit_status"],
+        ErrorCode::ChessRule.exit_status()
+    );
+    assert_eq!(json["error"]["message"], "The move is not legal");
+    assert_eq!(
+        json["error"]["suggestion"],
+        "List legal moves with 'mate moves'"
+    );
+    assert!(json["schema_version"].is_number());
+}
@@ -0,0 +1,178 @@
+//! Game Event Tests
+//!
+//! Tests for the events in `src/cli/events.rs`: what the database watcher
+//! reports, and the stream returned by `App::subscribe`.
+
+use mate::cli::events::{EventWatcher, GameEvent};
+use mate::cli::{App, NetworkConfig, NetworkManager};
+use mate::crypto::Identity;
+use mate::messages::chess::Move;
+use mate::storage::models::GameResult;
+use mate::storage::{Database, PlayerColor};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio_stream::StreamExt;
+
+fn store(db: &Database, game_id: &str, message_type: &str, content: String, sender: &str) {
+    db.store_message(
+        game_id.to_string(),
+        message_type.to_string(),
+        content,
+        String::new(),
+        sender.to_string(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_watcher_reports_only_new_messages_from_the_opponent() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("me", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game = db
+        .create_game("them".to_string(), PlayerColor::White, None)
+        .unwrap();
+    let chess_move = |m: &str| {
+        serde_json::to_string(&Move::new(game.id.clone(), m.to_string(), "0".repeat(64))).unwrap()
+    };
+
+    // Stored before watching starts
+    store(&db, &game.id, "GameInvite", "{}".to_string(), "them");
+    let mut watcher = EventWatcher::start(&db, "me").unwrap();
+    assert!(watcher.poll(&db).unwrap().is_empty());
+
+    store(&db, &game.id, "move", chess_move("e2e4"), "me");
+    store(&db, &game.id, "Move", chess_move("e7e5"), "them");
+    store(
+        &db,
+        &game.id,
+        "Chat",
+        r#"{"text":"good luck"}"#.to_string(),
+        "them",
+    );
+    store(&db, &game.id, "DrawOffer", "{}".to_string(), "them");
+    store(
+        &db,
+        &game.id,
+        "Chat",
+        r#"{"text":"mine"}"#.to_string(),
+        "self",
+    );
+
+    let from = "them".to_string();
+    assert_eq!(
+        watcher.poll(&db).unwrap(),
+        [
+            GameEvent::MoveReceived {
+                game_id: game.id.clone(),
+                from: from.clone(),
+                chess_move: "e7e5".to_string(),
+            },
+            GameEvent::ChatReceived {
+                game_id: game.id.clone(),
+                from: from.clone(),
+                text: "good luck".to_string(),
+            },
+            GameEvent::DrawOffered {
+                game_id: game.id.clone(),
+                from,
+            },
+        ]
+    );
+    assert!(watcher.poll(&db).unwrap().is_empty());
+}
+
+#[test]
+fn test_watcher_reports_games_that_end() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("me", &temp_dir.path().join("db.sqlite")).unwrap();
+    let finished = db
+        .create_game("them".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_result(&finished.id, GameResult::Draw)
+        .unwrap();
+    let game = db
+        .create_game("them".to_string(), PlayerColor::Black, None)
+        .unwrap();
+
+    let mut watcher = EventWatcher::start(&db, "me").unwrap();
+    db.update_game_result(&game.id, GameResult::Win).unwrap();
+    assert_eq!(
+        watcher.poll(&db).unwrap(),
+        [GameEvent::GameEnded {
+            game_id: game.id,
+            result: Some(GameResult::Win),
+        }]
+    );
+    assert!(watcher.poll(&db).unwrap().is_empty());
+}
+
+#[test]
+fn test_events_serialize_with_their_kind() {
+    let event = GameEvent::InviteReceived {
+        game_id: "g".to_string(),
+        from: "p".to_string(),
+    };
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        serde_json::json!({"event": "invite_received", "game_id": "g", "from": "p"})
+    );
+}
+
+#[tokio::test]
+async fn test_network_manager_announces_lost_connections() {
+    let manager = NetworkManager::with_config(
+        Arc::new(Identity::generate().unwrap()),
+        NetworkConfig::default()
+            .with_timeout(Duration::from_millis(500))
+            .with_retries(0),
+    );
+    let mut events = manager.subscribe_events();
+
+    // Nothing listens on port 1
+    assert!(manager
+        .send_resign("127.0.0.1:1", "game".to_string())
+        .await
+        .is_err());
+    match events.try_recv().unwrap() {
+        GameEvent::ConnectionLost { peer_address, .. } => assert_eq!(peer_address, "127.0.0.1:1"),
+        other => panic!("unexpected event {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_subscribers_receive_moves_stored_by_others() {
+    let temp_dir = TempDir::new().unwrap();
+    let app = App::new_with_data_dir(temp_dir.path().to_path_buf())
+        .await
+        .unwrap();
+    let game = app
+        .database
+        .create_game("them".to_string(), PlayerColor::White, None)
+        .unwrap();
+    let mut events = app.subscribe();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // As the server would store an incoming move
+    let message = Move::new(game.id.clone(), "e7e5".to_string(), "0".repeat(64));
+    store(
+        &app.database,
+        &game.id,
+        "Move",
+        serde_json::to_string(&message).unwrap(),
+        "them",
+    );
+
+    let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("event within the poll interval")
+        .expect("stream still open");
+    assert_eq!(
+        event,
+        GameEvent::MoveReceived {
+            game_id: game.id,
+            from: "them".to_string(),
+            chess_move: "e7e5".to_string(),
+        }
+    );
+}
@@ -0,0 +1,213 @@
+//! Game Selection Tests
+//!
+//! Tests for resolving game IDs, ID prefixes, and peer aliases to games in
+//! `src/cli/game_ops.rs`
+
+use mate::cli::game_ops::{GameOps, GameOpsError};
+use mate::storage::{Database, GameStatus, PlayerColor};
+use tempfile::TempDir;
+
+fn create_test_database() -> (Database, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("test.db")).unwrap();
+    (db, temp_dir)
+}
+
+fn create_game(db: &Database, opponent: &str, status: GameStatus) -> String {
+    let game = db
+        .create_game(opponent.to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&game.id, status).unwrap();
+    game.id
+}
+
+fn set_updated_at(db: &Database, game_id: &str, updated_at: i64) {
+    db.with_connection(|conn| {
+        conn.execute(
+            "UPDATE games SET updated_at = ?1 WHERE id = ?2",
+            (updated_at, game_id),
+        )?;
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_resolve_by_full_id_and_prefix() {
+    let (db, _temp_dir) = create_test_database();
+    let game_id = create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    let game_ops = GameOps::new(&db);
+
+    assert_eq!(game_ops.resolve_game(&game_id).unwrap().id, game_id);
+    assert_eq!(game_ops.resolve_game(&game_id[..8]).unwrap().id, game_id);
+    assert_eq!(
+        game_ops
+            .resolve_game(&game_id[..8].to_uppercase())
+            .unwrap()
+            .id,
+        game_id
+    );
+}
+
+#[test]
+fn test_prefix_match_ignores_case_of_stored_ids() {
+    let temp_dir = TempDir::new().unwrap();
+    // Game IDs start with the local peer ID, which may contain capitals
+    let db = Database::new_with_path("12D3KooWBob", &temp_dir.path().join("test.db")).unwrap();
+    let game_id = create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    let game_ops = GameOps::new(&db);
+
+    assert_eq!(game_ops.resolve_game(&game_id[..8]).unwrap().id, game_id);
+}
+
+#[test]
+fn test_ambiguous_prefix_lists_candidates() {
+    let (db, _temp_dir) = create_test_database();
+    // Sixteen hex digits, so some pair of games must share a first character
+    let ids: Vec<String> = (0..17)
+        .map(|_| create_game(&db, "12D3KooWAlice", GameStatus::Active))
+        .collect();
+    let prefix = ids
+        .iter()
+        .map(|id| &id[..1])
+        .find(|prefix| ids.iter().filter(|id| id.starts_with(*prefix)).count() > 1)
+        .unwrap();
+
+    let err = GameOps::new(&db).resolve_game(prefix).unwrap_err();
+    let GameOpsError::AmbiguousGame { candidates, .. } = &err else {
+        panic!("expected an ambiguity error, got {err}");
+    };
+    assert!(candidates.len() > 1);
+    assert!(err.to_string().contains("longer game ID prefix"));
+    for id in ids.iter().filter(|id| id.starts_with(prefix)) {
+        assert!(candidates.iter().any(|line| line.contains(id.as_str())));
+    }
+}
+
+#[test]
+fn test_resolve_by_alias_prefers_game_in_progress() {
+    let (db, _temp_dir) = create_test_database();
+    let finished = create_game(&db, "12D3KooWAlice", GameStatus::Completed);
+    let active = create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    create_game(&db, "12D3KooWBob", GameStatus::Active);
+    set_updated_at(&db, &finished, i64::MAX / 2);
+    db.set_peer_alias("alice", "12D3KooWAlice").unwrap();
+
+    let game = GameOps::new(&db).resolve_game("Alice").unwrap();
+    assert_eq!(game.id, active);
+}
+
+#[test]
+fn test_resolve_by_alias_falls_back_to_most_recent_game() {
+    let (db, _temp_dir) = create_test_database();
+    let older = create_game(&db, "12D3KooWAlice", GameStatus::Completed);
+    let newer = create_game(&db, "12D3KooWAlice", GameStatus::Abandoned);
+    set_updated_at(&db, &older, 1_000);
+    set_updated_at(&db, &newer, 2_000);
+    db.set_peer_alias("alice", "12D3KooWAlice").unwrap();
+
+    assert_eq!(GameOps::new(&db).resolve_game("alice").unwrap().id, newer);
+}
+
+#[test]
+fn test_alias_with_several_games_in_progress_is_ambiguous() {
+    let (db, _temp_dir) = create_test_database();
+    create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    create_game(&db, "12D3KooWAlice", GameStatus::Pending);
+    db.set_peer_alias("alice", "12D3KooWAlice").unwrap();
+
+    let err = GameOps::new(&db).resolve_game("alice").unwrap_err();
+    assert!(
+        matches!(err, GameOpsError::AmbiguousGame { ref candidates, .. } if candidates.len() == 2)
+    );
+}
+
+#[test]
+fn test_resolve_by_opponent_peer_id_prefix() {
+    let (db, _temp_dir) = create_test_database();
+    let alice_game = create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    create_game(&db, "12D3KooWBob", GameStatus::Active);
+    let game_ops = GameOps::new(&db);
+
+    assert_eq!(game_ops.resolve_game("12D3KooWA").unwrap().id, alice_game);
+    assert!(matches!(
+        game_ops.resolve_game("12D3KooW"),
+        Err(GameOpsError::AmbiguousGame { .. })
+    ));
+}
+
+#[test]
+fn test_two_games_with_one_peer_resolve_by_state() {
+    let (db, _temp_dir) = create_test_database();
+    let active = create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    let invitation = create_game(&db, "12D3KooWAlice", GameStatus::Pending);
+    db.set_peer_alias("alice", "12D3KooWAlice").unwrap();
+    let game_ops = GameOps::new(&db);
+
+    // Accepting looks at invitations, moving at games under way
+    assert_eq!(
+        game_ops
+            .resolve_game_with_status("alice", &[GameStatus::Pending])
+            .unwrap()
+            .id,
+        invitation
+    );
+    assert_eq!(
+        game_ops
+            .resolve_game_with_status("12D3KooWA", &[GameStatus::Active])
+            .unwrap()
+            .id,
+        active
+    );
+    // A game ID picks its game whatever the state asked for
+    assert_eq!(
+        game_ops
+            .resolve_game_with_status(&invitation, &[GameStatus::Active])
+            .unwrap()
+            .id,
+        invitation
+    );
+}
+
+#[test]
+fn test_two_active_games_with_one_peer_need_a_game_id() {
+    let (db, _temp_dir) = create_test_database();
+    let first = create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    let second = create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    db.set_peer_alias("alice", "12D3KooWAlice").unwrap();
+    let game_ops = GameOps::new(&db);
+
+    let err = game_ops
+        .resolve_game_with_status("alice", &[GameStatus::Active])
+        .unwrap_err();
+    let GameOpsError::AmbiguousGame { candidates, .. } = &err else {
+        panic!("expected an ambiguity error, got {err}");
+    };
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(game_ops.resolve_game(&first).unwrap().id, first);
+    assert_eq!(game_ops.resolve_game(&second).unwrap().id, second);
+
+    // Neither game is waiting to be accepted
+    assert!(matches!(
+        game_ops.resolve_game_with_status("alice", &[GameStatus::Pending]),
+        Err(GameOpsError::GameNotFound(_))
+    ));
+}
+
+#[test]
+fn test_unknown_selector_is_not_found() {
+    let (db, _temp_dir) = create_test_database();
+    create_game(&db, "12D3KooWAlice", GameStatus::Active);
+    db.set_peer_alias("carol", "12D3KooWCarol").unwrap();
+    let game_ops = GameOps::new(&db);
+
+    assert!(matches!(
+        game_ops.resolve_game("nobody"),
+        Err(GameOpsError::GameNotFound(_))
+    ));
+    // An alias for a peer without games finds nothing
+    assert!(matches!(
+        game_ops.resolve_game("carol"),
+        Err(GameOpsError::GameNotFound(_))
+    ));
+}
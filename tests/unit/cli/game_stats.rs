@@ -0,0 +1,139 @@
+//! Game Statistics Tests
+//!
+//! Tests for the charts and CSV of `mate stats` in `src/cli/game_stats.rs`
+
+use mate::cli::csv::{csv_field, csv_record};
+use mate::cli::game_stats::{count_bar, result_bar, GameStatsReport, Rating, INITIAL_RATING};
+use mate::storage::game_stats::{ResultTally, StatsPeriod};
+use mate::storage::models::{GameResult, PlayerColor, TimeControl};
+
+fn tally(wins: u32, draws: u32, losses: u32) -> ResultTally {
+    ResultTally {
+        games: wins + draws + losses,
+        wins,
+        draws,
+        losses,
+        plies: u64::from(wins + draws + losses) * 60,
+    }
+}
+
+fn report() -> GameStatsReport {
+    GameStatsReport {
+        period: StatsPeriod::Month,
+        totals: tally(5, 2, 3),
+        periods: vec![
+            ("2026-01".to_string(), tally(4, 0, 2)),
+            ("2026-02".to_string(), tally(1, 2, 1)),
+        ],
+        colors: vec![
+            (PlayerColor::White, tally(3, 1, 1)),
+            (PlayerColor::Black, tally(2, 1, 2)),
+        ],
+        time_controls: vec![
+            (
+                Some(TimeControl {
+                    initial_time_ms: 300_000,
+                    increment_ms: 3_000,
+                }),
+                tally(4, 1, 2),
+            ),
+            (None, tally(1, 1, 1)),
+        ],
+        rating: Some(Rating {
+            rating: 1516.0,
+            games: 1,
+        }),
+    }
+}
+
+#[test]
+fn test_result_bar_splits_the_bar_by_outcome() {
+    assert_eq!(result_bar(&tally(2, 1, 1), 4, 8), "++++==--");
+    // Half as many games draw half as long a bar
+    assert_eq!(result_bar(&tally(1, 0, 1), 4, 8), "++--");
+    // Games without a result fill the rest
+    let unfinished = ResultTally {
+        games: 4,
+        wins: 2,
+        ..ResultTally::default()
+    };
+    assert_eq!(result_bar(&unfinished, 4, 8), "++++....");
+    assert_eq!(result_bar(&ResultTally::default(), 4, 8), "");
+}
+
+#[test]
+fn test_bars_never_hide_a_counted_group() {
+    assert_eq!(count_bar(1, 1000, 10), "#");
+    assert_eq!(count_bar(0, 1000, 10), "");
+    assert_eq!(count_bar(1000, 1000, 10).len(), 10);
+    assert_eq!(result_bar(&tally(0, 0, 1), 1000, 10), "-");
+}
+
+#[test]
+fn test_chart_lists_every_section() {
+    let lines = report().chart_lines();
+    assert_eq!(
+        lines[0],
+        "10 finished games: 5 wins, 2 draws, 3 losses (60%)"
+    );
+    let text = lines.join("\n");
+    assert!(text.contains("Results by month"));
+    assert!(text.contains(&format!("2026-01   {}{}", "+".repeat(27), "-".repeat(13))));
+    assert!(text.contains("Average 30.0 moves over 10 games"));
+    assert!(lines.iter().any(|l| l.starts_with("5+3 ")));
+    assert!(lines.iter().any(|l| l.starts_with("untimed ")));
+    assert_eq!(lines.last().unwrap(), "1516 over 1 rated games");
+}
+
+#[test]
+fn test_rating_follows_rated_results() {
+    assert_eq!(Rating::from_results(&[]), None);
+
+    let win = Rating::from_results(&[("bob".to_string(), GameResult::Win)]).unwrap();
+    assert_eq!(win.rating, INITIAL_RATING + 16.0);
+    assert_eq!(win.games, 1);
+
+    // Beating the same player again gains less than beating a new one
+    let bob_twice = Rating::from_results(&[
+        ("bob".to_string(), GameResult::Win),
+        ("bob".to_string(), GameResult::Win),
+    ])
+    .unwrap();
+    let bob_then_carol = Rating::from_results(&[
+        ("bob".to_string(), GameResult::Win),
+        ("carol".to_string(), GameResult::Win),
+    ])
+    .unwrap();
+    assert!(bob_twice.rating < bob_then_carol.rating);
+
+    let even = Rating::from_results(&[
+        ("bob".to_string(), GameResult::Draw),
+        ("carol".to_string(), GameResult::Abandoned),
+    ])
+    .unwrap();
+    assert_eq!(even.rating, INITIAL_RATING);
+    assert_eq!(even.games, 1);
+}
+
+#[test]
+fn test_csv_has_one_record_per_group() {
+    let lines = report().csv_lines();
+    assert_eq!(
+        lines[0],
+        "section,group,games,wins,draws,losses,score,average_moves"
+    );
+    assert_eq!(lines[1], "total,all,10,5,2,3,60.0,30.0");
+    assert_eq!(lines[2], "month,2026-01,6,4,0,2,66.7,30.0");
+    assert_eq!(lines[4], "color,white,5,3,1,1,70.0,30.0");
+    assert_eq!(lines[6], "time_control,5+3,7,4,1,2,64.3,30.0");
+    assert_eq!(lines[7], "time_control,untimed,3,1,1,1,50.0,30.0");
+    assert_eq!(lines.len(), 8);
+}
+
+#[test]
+fn test_csv_fields_are_quoted_only_when_needed() {
+    assert_eq!(csv_field("plain"), "plain");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_record(&["a", "b c", "d\ne"]), "a,b c,\"d\ne\"");
+}
@@ -0,0 +1,95 @@
+//! Hotseat Tests
+//!
+//! Tests for two-player games at one terminal in `src/cli/hotseat.rs`.
+
+use mate::cli::hotseat::{HotseatSession, HOTSEAT_OPPONENT};
+use mate::cli::{App, GameOps};
+use mate::storage::models::GameResult;
+use mate::storage::{GameStatus, PlayerColor};
+use tempfile::TempDir;
+
+async fn test_app() -> (App, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let app = App::new_with_data_dir(temp_dir.path().to_path_buf())
+        .await
+        .unwrap();
+    (app, temp_dir)
+}
+
+#[tokio::test]
+async fn test_both_sides_move_and_checkmate_is_recorded() {
+    let (app, _temp_dir) = test_app().await;
+    let mut session = HotseatSession::start(&app).unwrap();
+    let game_id = session.game_id().to_string();
+
+    for line in ["f3", "e5", "g4", "Qh4#"] {
+        assert!(session.handle_line(line).unwrap());
+    }
+
+    let game = app.database.get_game(&game_id).unwrap();
+    assert_eq!(game.opponent_peer_id, HOTSEAT_OPPONENT);
+    assert_eq!(game.my_color, PlayerColor::White);
+    assert_eq!(game.status, GameStatus::Completed);
+    // Results are stored from White's side
+    assert_eq!(game.result, Some(GameResult::Loss));
+
+    let state = GameOps::new(&app.database)
+        .reconstruct_game_state(&game_id)
+        .unwrap();
+    assert_eq!(state.move_history, ["f2f3", "e7e5", "g2g4", "d8h4"]);
+}
+
+#[tokio::test]
+async fn test_draw_needs_the_other_player_and_games_resume() {
+    let (app, _temp_dir) = test_app().await;
+    let mut session = HotseatSession::start(&app).unwrap();
+    let game_id = session.game_id().to_string();
+    assert!(session.handle_line("e4").unwrap());
+
+    // Black offers, White declines
+    assert!(session.handle_line("draw").unwrap());
+    assert!(session.handle_line("n").unwrap());
+    assert_eq!(
+        app.database.get_game(&game_id).unwrap().status,
+        GameStatus::Active
+    );
+    assert!(!session.handle_line("quit").unwrap());
+
+    let mut session = HotseatSession::resume(&app, &game_id[..8]).unwrap();
+    assert!(session.handle_line("e5").unwrap());
+    assert!(session.handle_line("draw").unwrap());
+    assert!(session.handle_line("yes").unwrap());
+
+    let game = app.database.get_game(&game_id).unwrap();
+    assert_eq!(game.result, Some(GameResult::Draw));
+    // Moves are refused once the game is over
+    assert!(session.handle_line("Nf3").unwrap());
+    let state = GameOps::new(&app.database)
+        .reconstruct_game_state(&game_id)
+        .unwrap();
+    assert_eq!(state.move_history.len(), 2);
+}
+
+#[tokio::test]
+async fn test_resigning_and_resuming_networked_games_is_refused() {
+    let (app, _temp_dir) = test_app().await;
+    let mut session = HotseatSession::start(&app).unwrap();
+    let game_id = session.game_id().to_string();
+
+    // White resigns before moving
+    assert!(session.handle_line("resign").unwrap());
+    assert!(session.handle_line("y").unwrap());
+    assert_eq!(
+        app.database.get_game(&game_id).unwrap().result,
+        Some(GameResult::Loss)
+    );
+
+    let networked = app
+        .database
+        .create_game("peer".to_string(), PlayerColor::Black, None)
+        .unwrap();
+    let error = HotseatSession::resume(&app, &networked.id)
+        .err()
+        .expect("a networked game is not a hotseat game");
+    assert!(error.to_string().contains("not a hotseat game"));
+}
@@ -0,0 +1,69 @@
+//! Tests for the message catalog and locale selection
+
+use mate::cli::i18n::{fill, tr_in, Locale, Msg};
+
+#[test]
+fn test_locale_codes_and_posix_names() {
+    assert_eq!("es".parse::<Locale>(), Ok(Locale::Spanish));
+    assert_eq!("EN".parse::<Locale>(), Ok(Locale::English));
+    assert!("xx".parse::<Locale>().is_err());
+
+    assert_eq!(Locale::from_posix("es_ES.UTF-8"), Some(Locale::Spanish));
+    assert_eq!(Locale::from_posix("en_GB"), Some(Locale::English));
+    assert_eq!(Locale::from_posix("C.UTF-8"), None);
+    assert_eq!(Locale::from_posix("de_DE.UTF-8"), None);
+}
+
+#[test]
+fn test_configured_locale_wins_over_environment() {
+    assert_eq!(Locale::resolve(Some("es")), Locale::Spanish);
+    assert_eq!(Locale::resolve(Some("en")), Locale::English);
+}
+
+#[test]
+fn test_fill_replaces_named_placeholders_once() {
+    let text = fill(
+        "Invalid {field}: '{value}' ({missing})",
+        &[("field", &"color"), ("value", &"{field}")],
+    );
+    assert_eq!(text, "Invalid color: '{field}' ({missing})");
+}
+
+#[test]
+fn test_translations_keep_placeholders() {
+    for msg in [
+        Msg::GamesTotal,
+        Msg::ToMove,
+        Msg::ErrGameNotFound,
+        Msg::ErrInvalidInput,
+        Msg::ErrMessageTooLarge,
+        Msg::ErrSecurityViolation,
+    ] {
+        let english = tr_in(Locale::English, msg);
+        let spanish = tr_in(Locale::Spanish, msg);
+        assert_ne!(english, spanish, "{msg:?} is not translated");
+        for placeholder in english.split('{').skip(1) {
+            let name = &placeholder[..placeholder.find('}').unwrap()];
+            assert!(
+                spanish.contains(&format!("{{{name}}}")),
+                "{msg:?} lost {{{name}}} in Spanish"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_english_catalog_matches_existing_wording() {
+    assert_eq!(tr_in(Locale::English, Msg::Suggestion), "Suggestion");
+    assert_eq!(
+        fill(
+            tr_in(Locale::English, Msg::ErrGameNotFound),
+            &[("id", &"abc")]
+        ),
+        "Game 'abc' not found."
+    );
+    assert_eq!(
+        tr_in(Locale::Spanish, Msg::ErrNoActiveGames),
+        "No hay partidas activas."
+    );
+}
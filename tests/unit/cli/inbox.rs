@@ -0,0 +1,182 @@
+//! Invitation Inbox Tests
+//!
+//! Tests for listing incoming invitations in `src/cli/game_ops.rs` and
+//! choosing one to accept in `src/cli/inbox.rs`
+
+use mate::chess::Color;
+use mate::cli::game_ops::GameOps;
+use mate::cli::inbox::{choose_invitation, format_time_control, invitation_line};
+use mate::messages::chess::GameInvite;
+use mate::storage::models::TimeControl;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::io::Cursor;
+use tempfile::TempDir;
+
+const ME: &str = "test_peer";
+
+fn create_test_database() -> (Database, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path(ME, &temp_dir.path().join("test.db")).unwrap();
+    (db, temp_dir)
+}
+
+fn invite(db: &Database, sender: &str, opponent: &str, suggested: Option<Color>) -> String {
+    let metadata = serde_json::json!({
+        "time_control": { "initial_time_ms": 300_000, "increment_ms": 3_000 }
+    });
+    let game = db
+        .create_game(opponent.to_string(), PlayerColor::White, Some(metadata))
+        .unwrap();
+    db.store_message(
+        game.id.clone(),
+        "GameInvite".to_string(),
+        serde_json::to_string(&GameInvite::new(game.id.clone(), suggested)).unwrap(),
+        "sig".to_string(),
+        sender.to_string(),
+    )
+    .unwrap();
+    game.id
+}
+
+#[test]
+fn test_inbox_lists_only_invitations_from_opponents() {
+    let (db, _temp_dir) = create_test_database();
+    let incoming = invite(&db, "alice", "alice", Some(Color::Black));
+    invite(&db, ME, "bob", None);
+    let active = invite(&db, "carol", "carol", None);
+    db.update_game_status(&active, GameStatus::Active).unwrap();
+
+    let invitations = GameOps::new(&db).list_incoming_invitations(ME).unwrap();
+
+    assert_eq!(invitations.len(), 1);
+    assert_eq!(invitations[0].game_id, incoming);
+    assert_eq!(invitations[0].opponent_peer_id, "alice");
+    assert_eq!(invitations[0].suggested_color, Some(PlayerColor::Black));
+    assert_eq!(
+        format_time_control(invitations[0].time_control.as_ref()),
+        "5+3"
+    );
+}
+
+#[test]
+fn test_invitation_line_describes_inviter_color_and_clock() {
+    let (db, _temp_dir) = create_test_database();
+    let game_id = invite(&db, "alice", "alice", Some(Color::White));
+    let invitations = GameOps::new(&db).list_incoming_invitations(ME).unwrap();
+
+    let line = invitation_line(&invitations[0], "alice (12D3KooWAlice)", "Just now");
+    assert_eq!(
+        line,
+        format!(
+            "{}...  from alice (12D3KooWAlice)  you play White  5+3 casual  Just now",
+            &game_id[..8]
+        )
+    );
+}
+
+#[test]
+fn test_rated_invitations_are_marked_rated() {
+    let (db, _temp_dir) = create_test_database();
+    let game = db
+        .create_game("alice".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.store_message(
+        game.id.clone(),
+        "GameInvite".to_string(),
+        serde_json::to_string(&GameInvite::new(game.id.clone(), None).with_rated()).unwrap(),
+        "sig".to_string(),
+        "alice".to_string(),
+    )
+    .unwrap();
+
+    let invitations = GameOps::new(&db).list_incoming_invitations(ME).unwrap();
+    assert!(invitations[0].rated);
+    let line = invitation_line(&invitations[0], "alice", "Just now");
+    assert!(
+        line.ends_with("any color  untimed rated  Just now"),
+        "{line}"
+    );
+    assert!(GameOps::new(&db).get_game_record(&game.id).unwrap().rated);
+}
+
+#[test]
+fn test_time_control_formats() {
+    assert_eq!(format_time_control(None), "untimed");
+    let bullet = TimeControl {
+        initial_time_ms: 90_000,
+        increment_ms: 0,
+    };
+    assert_eq!(format_time_control(Some(&bullet)), "90s+0");
+}
+
+#[test]
+fn test_choose_invitation_reprompts_until_valid() {
+    let lines = vec!["first".to_string(), "second".to_string()];
+    let mut output = Vec::new();
+
+    let chosen = choose_invitation(&lines, Cursor::new("7\nabc\n2\n"), &mut output).unwrap();
+
+    assert_eq!(chosen, Some(1));
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("   1) first"));
+    assert!(output.contains("   2) second"));
+    assert_eq!(output.matches("Enter a number from 1 to 2").count(), 2);
+}
+
+#[test]
+fn test_choose_invitation_can_be_cancelled() {
+    let lines = vec!["only".to_string()];
+
+    for input in ["\n", "q\n", ""] {
+        let chosen = choose_invitation(&lines, Cursor::new(input), Vec::new()).unwrap();
+        assert_eq!(chosen, None, "{input:?}");
+    }
+}
+
+#[test]
+fn test_expired_invitations_are_abandoned_on_both_sides() {
+    let (db, _temp_dir) = create_test_database();
+    let now = Database::current_timestamp();
+    let expiring = |sender: &str, opponent: &str, expires_at: Option<i64>| {
+        let game = db
+            .create_game(opponent.to_string(), PlayerColor::White, None)
+            .unwrap();
+        let mut invite = GameInvite::new(game.id.clone(), None);
+        invite.expires_at = expires_at;
+        db.store_message(
+            game.id.clone(),
+            "GameInvite".to_string(),
+            serde_json::to_string(&invite).unwrap(),
+            "sig".to_string(),
+            sender.to_string(),
+        )
+        .unwrap();
+        game.id
+    };
+    let lapsed_incoming = expiring("alice", "alice", Some(now - 1));
+    let lapsed_outgoing = expiring(ME, "bob", Some(now - 3600));
+    let fresh = expiring("carol", "carol", Some(now + 3600));
+    // Invitations from older versions carry no expiry
+    let legacy = invite(&db, "dave", "dave", None);
+
+    let mut expired = GameOps::new(&db).expire_invitations(now).unwrap();
+    expired.sort();
+    let mut expected = vec![lapsed_incoming.clone(), lapsed_outgoing.clone()];
+    expected.sort();
+    assert_eq!(expired, expected);
+
+    for game_id in [&lapsed_incoming, &lapsed_outgoing] {
+        assert_eq!(db.get_game(game_id).unwrap().status, GameStatus::Abandoned);
+    }
+    for game_id in [&fresh, &legacy] {
+        assert_eq!(db.get_game(game_id).unwrap().status, GameStatus::Pending);
+    }
+    let incoming: Vec<_> = GameOps::new(&db)
+        .list_incoming_invitations(ME)
+        .unwrap()
+        .into_iter()
+        .map(|invitation| invitation.game_id)
+        .collect();
+    assert_eq!(incoming.len(), 2);
+    assert!(!incoming.contains(&lapsed_incoming));
+}
@@ -0,0 +1,74 @@
+//! Line Editor Tests
+//!
+//! Tests for move completion and inline hints in `src/cli/line_editor.rs`
+
+use mate::chess::Board;
+use mate::cli::line_editor::Suggestions;
+
+#[test]
+fn test_move_suggestions_cover_legal_moves_and_commands() {
+    let suggestions = Suggestions::for_moves(&Board::new(), &["board", "resign"]);
+
+    assert!(suggestions.words.contains(&"board".to_string()));
+    assert!(suggestions.words.contains(&"Nf3".to_string()));
+    assert!(suggestions.words.contains(&"e4".to_string()));
+    assert!(suggestions.words.contains(&"g1f3".to_string()));
+    // 20 legal moves in both notations plus the two commands
+    assert_eq!(suggestions.words.len(), 42);
+}
+
+#[test]
+fn test_matching_ignores_case() {
+    let suggestions = Suggestions::for_moves(&Board::new(), &[]);
+
+    assert_eq!(suggestions.matching("nf"), vec!["Nf3"]);
+    let knight_moves = suggestions.matching("N");
+    assert_eq!(knight_moves.len(), 4);
+    assert!(suggestions.matching("Ke2").is_empty());
+}
+
+#[test]
+fn test_hint_completes_unique_match() {
+    let suggestions = Suggestions::for_moves(&Board::new(), &["resign"]);
+
+    let hint = suggestions
+        .hint("res")
+        .expect("unique match should be hinted");
+    assert_eq!(hint.display, "ign");
+    assert_eq!(hint.completion.as_deref(), Some("ign"));
+
+    assert!(
+        suggestions.hint("N").is_none(),
+        "Several matches show no hint"
+    );
+    assert!(suggestions.hint("").is_none());
+}
+
+#[test]
+fn test_hint_flags_illegal_input() {
+    let suggestions = Suggestions::for_moves(&Board::new(), &[]);
+
+    let hint = suggestions
+        .hint("Ke2")
+        .expect("illegal input should be flagged");
+    assert!(hint.display.contains("not a legal move"));
+    assert_eq!(hint.completion, None, "Error hints must not be insertable");
+
+    // Arguments after the first word are free text
+    assert!(suggestions.hint("chat hello").is_none());
+}
+
+#[test]
+fn test_command_suggestions_allow_free_text() {
+    let suggestions = Suggestions::for_commands(&["help", "quit"], None);
+
+    assert!(suggestions.hint("hello").is_none());
+    assert_eq!(suggestions.hint("q").unwrap().display, "uit");
+
+    let waiting = Suggestions::for_commands(&["help"], Some("not your turn"));
+    assert!(waiting
+        .hint("e4")
+        .unwrap()
+        .display
+        .contains("not your turn"));
+}
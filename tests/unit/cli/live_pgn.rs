@@ -0,0 +1,88 @@
+//! Live PGN Tests
+//!
+//! Tests for the PGN kept up to date by `src/cli/live_pgn.rs` and served by
+//! `PgnFeedServer` in `src/api/pgn_feed.rs`.
+
+use mate::cli::app::LivePgnSettings;
+use mate::cli::live_pgn::{self, LivePgn};
+use mate::messages::chess::Move;
+use mate::network::http_client::{self, HttpUrl};
+use mate::storage::{Database, PlayerColor};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn play(db: &Database, game_id: &str, chess_move: &str, sender: &str) {
+    db.store_message(
+        game_id.to_string(),
+        "move".to_string(),
+        serde_json::to_string(&Move::new(
+            game_id.to_string(),
+            chess_move.to_string(),
+            "0".repeat(64),
+        ))
+        .unwrap(),
+        String::new(),
+        sender.to_string(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_file_is_rewritten_only_when_the_game_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Arc::new(Database::new_in_memory("me").unwrap());
+    let game = db
+        .create_game("them".to_string(), PlayerColor::White, None)
+        .unwrap();
+    let path = temp_dir.path().join("live.pgn");
+    let mut live = LivePgn::new(Arc::clone(&db), &game.id, "me").with_file(path.clone());
+
+    assert!(live.update().unwrap());
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .contains(&format!("[GameId \"{}\"]", game.id)));
+    assert!(!live.update().unwrap());
+
+    play(&db, &game.id, "e2e4", "me");
+    assert!(live.update().unwrap());
+    assert!(std::fs::read_to_string(&path).unwrap().contains("1. e2e4"));
+    // Nothing is left behind from replacing the file
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+}
+
+#[tokio::test]
+async fn test_http_endpoint_serves_the_latest_pgn() {
+    let db = Arc::new(Database::new_in_memory("me").unwrap());
+    let game = db
+        .create_game("them".to_string(), PlayerColor::White, None)
+        .unwrap();
+    play(&db, &game.id, "e2e4", "me");
+    let settings = LivePgnSettings {
+        http_addr: Some("127.0.0.1:0".to_string()),
+        update_secs: 1,
+        ..LivePgnSettings::default()
+    };
+
+    let task = live_pgn::start(Arc::clone(&db), &game.id, "me", &settings)
+        .await
+        .unwrap()
+        .unwrap();
+    let base = format!("http://{}", task.http_addr().unwrap());
+
+    let url = HttpUrl::parse(&format!("{base}/game.pgn")).unwrap();
+    let (status, body) = http_client::get(&url).await.unwrap();
+    assert_eq!(status, 200);
+    assert!(String::from_utf8(body).unwrap().contains("1. e2e4"));
+
+    let url = HttpUrl::parse(&format!("{base}/other")).unwrap();
+    assert_eq!(http_client::get(&url).await.unwrap().0, 404);
+}
+
+#[tokio::test]
+async fn test_nothing_is_started_without_a_destination() {
+    let db = Arc::new(Database::new_in_memory("me").unwrap());
+    let task = live_pgn::start(db, "any", "me", &LivePgnSettings::default())
+        .await
+        .unwrap();
+    assert!(task.is_none());
+}
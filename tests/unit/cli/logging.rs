@@ -0,0 +1,205 @@
+//! Logging Option Tests
+//!
+//! Tests for the -q/-v/--log-file/--log-format flags, log file rotation,
+//! and JSON log lines in `src/cli/logging.rs`
+
+use clap::Parser;
+use mate::cli::logging::{
+    open_log_file, rotate_log_file, rotated_path, JsonFields, JsonFormat, LogFormat, LogRotation,
+    RotatingFile,
+};
+use mate::cli::Cli;
+use std::fs;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+use tracing_subscriber::layer::SubscriberExt;
+
+#[test]
+fn test_verbosity_flags_select_console_filter() {
+    let directive = |args: &[&str]| Cli::parse_from(args).log_directive();
+
+    assert_eq!(directive(&["mate", "games"]), "mate=info");
+    assert_eq!(directive(&["mate", "-q", "games"]), "mate=error");
+    assert_eq!(directive(&["mate", "games", "-v"]), "mate=debug");
+    assert_eq!(directive(&["mate", "-vv", "games"]), "mate=trace");
+    assert_eq!(directive(&["mate", "-vvv", "games"]), "mate=trace");
+}
+
+#[test]
+fn test_quiet_conflicts_with_verbose() {
+    assert!(Cli::try_parse_from(["mate", "-q", "-v", "games"]).is_err());
+}
+
+#[test]
+fn test_log_file_flag_is_global() {
+    let cli = Cli::parse_from(["mate", "games", "--log-file", "/tmp/mate.log"]);
+    assert_eq!(cli.log_file.unwrap().to_str(), Some("/tmp/mate.log"));
+}
+
+#[test]
+fn test_small_log_file_is_not_rotated() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("mate.log");
+    fs::write(&path, "short").unwrap();
+
+    rotate_log_file(&path, 1024, 3).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "short");
+    assert!(!rotated_path(&path, 1).exists());
+
+    // A missing file is fine too
+    rotate_log_file(&temp_dir.path().join("missing.log"), 1024, 3).unwrap();
+}
+
+#[test]
+fn test_rotation_shifts_files_and_drops_oldest() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("mate.log");
+
+    for generation in ["first", "second", "third", "fourth"] {
+        fs::write(&path, generation).unwrap();
+        rotate_log_file(&path, 1, 2).unwrap();
+    }
+
+    assert!(!path.exists());
+    assert_eq!(
+        fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+        "fourth"
+    );
+    assert_eq!(fs::read_to_string(rotated_path(&path, 2)).unwrap(), "third");
+    assert!(!rotated_path(&path, 3).exists());
+}
+
+#[test]
+fn test_open_log_file_appends_and_creates_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("logs").join("mate.log");
+
+    writeln!(open_log_file(&path).unwrap(), "one").unwrap();
+    writeln!(open_log_file(&path).unwrap(), "two").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+}
+
+#[test]
+fn test_log_format_and_rotation_flags() {
+    let cli = Cli::parse_from(["mate", "games"]);
+    assert_eq!(cli.log_format(), LogFormat::Text);
+    assert_eq!(cli.log_rotation(), LogRotation::Never);
+    assert_eq!(cli.log_max_size, 10);
+
+    let cli = Cli::parse_from([
+        "mate",
+        "games",
+        "--log-format",
+        "json",
+        "--log-rotate",
+        "daily",
+        "--log-max-size",
+        "50",
+    ]);
+    assert_eq!(cli.log_format(), LogFormat::Json);
+    assert_eq!(cli.log_rotation(), LogRotation::Daily);
+    assert_eq!(cli.log_max_size, 50);
+
+    assert!(Cli::try_parse_from(["mate", "--log-format", "xml", "games"]).is_err());
+    assert!(Cli::try_parse_from(["mate", "--log-max-size", "0", "games"]).is_err());
+}
+
+#[test]
+fn test_log_file_rotates_when_full() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("mate.log");
+    let mut file = RotatingFile::open(&path, 10, LogRotation::Never).unwrap();
+
+    file.write_all(b"first\n").unwrap();
+    file.write_all(b"second\n").unwrap();
+    file.write_all(b"third\n").unwrap();
+    file.flush().unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+    assert_eq!(
+        fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+        "second\n"
+    );
+    assert_eq!(
+        fs::read_to_string(rotated_path(&path, 2)).unwrap(),
+        "first\n"
+    );
+}
+
+#[test]
+fn test_log_file_from_an_earlier_day_is_rotated_on_open() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("mate.log");
+    fs::write(&path, "yesterday\n").unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60))
+        .unwrap();
+
+    let mut file = RotatingFile::open(&path, 1024, LogRotation::Daily).unwrap();
+    file.write_all(b"today\n").unwrap();
+    file.flush().unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "today\n");
+    assert_eq!(
+        fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+        "yesterday\n"
+    );
+}
+
+/// Collects everything written by a subscriber
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_json_lines_carry_span_fields() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .event_format(JsonFormat)
+            .fmt_fields(JsonFields)
+            .with_writer(move || writer.clone()),
+    );
+
+    tracing::subscriber::with_default(subscriber, || {
+        let connection = tracing::info_span!(
+            "connection",
+            connection_id = 7,
+            peer_id = tracing::field::Empty
+        );
+        let _connection = connection.enter();
+        connection.record("peer_id", "12D3KooWpeer");
+        let game = tracing::info_span!("process_move", game_id = "game-1");
+        let _game = game.enter();
+        tracing::info!(chess_move = "e2e4", "Move applied");
+    });
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+    assert_eq!(line["level"], "INFO");
+    assert_eq!(line["message"], "Move applied");
+    assert_eq!(line["connection_id"], 7);
+    assert_eq!(line["peer_id"], "12D3KooWpeer");
+    assert_eq!(line["game_id"], "game-1");
+    assert_eq!(line["chess_move"], "e2e4");
+    assert_eq!(
+        line["spans"],
+        serde_json::json!(["connection", "process_move"])
+    );
+    assert!(line["timestamp"].as_str().unwrap().ends_with('Z'));
+}
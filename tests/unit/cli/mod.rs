@@ -1,6 +1,42 @@
 //! Unit tests for CLI components
 
+pub mod account;
 pub mod app_foundation;
+pub mod auto_accept;
+pub mod clipboard;
+pub mod completions;
 pub mod configuration;
+pub mod confirmation;
+pub mod consistency_check;
+pub mod crash;
+pub mod data_export;
+pub mod debug;
 pub mod display;
+pub mod doctor;
+pub mod error_codes;
+pub mod events;
+pub mod game_selection;
+pub mod game_stats;
+pub mod hotseat;
+pub mod i18n;
+pub mod inbox;
+pub mod line_editor;
+pub mod live_pgn;
+pub mod logging;
+pub mod move_signatures;
+pub mod network_options;
+pub mod odds_games;
+pub mod openings;
+pub mod progress;
+pub mod reminders;
+pub mod selfplay;
+pub mod session;
+pub mod setup;
+pub mod study;
+pub mod telemetry;
+pub mod timeline;
+pub mod top;
+pub mod uci;
+pub mod usage;
 pub mod validation;
+pub mod voting;
@@ -0,0 +1,295 @@
+//! Move Signature Tests
+//!
+//! Tests for signing moves in their game context in `src/messages/chess.rs`
+//! and for checking those signatures in `src/cli/game_ops.rs`
+
+use mate::chess::{Board, Move};
+use mate::cli::game_ops::{MoveProcessingError, MoveProcessor};
+use mate::crypto::Identity;
+use mate::messages::chess::{generate_game_id, hash_board_state, Move as MoveMessage};
+use mate::messages::Message as WireMessage;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tempfile::TempDir;
+
+/// Create an active game against `opponent` in which we play white
+///
+/// The game gets a UUID like games started over the network, since moves
+/// received from peers must name one.
+fn create_active_game(db: &Database, db_path: &Path, opponent: &str) -> String {
+    let game = db
+        .create_game(opponent.to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&game.id, GameStatus::Active).unwrap();
+    let game_id = generate_game_id();
+    rusqlite::Connection::open(db_path)
+        .unwrap()
+        .execute(
+            "UPDATE games SET id = ?1 WHERE id = ?2",
+            [&game_id, &game.id],
+        )
+        .unwrap();
+    game_id
+}
+
+/// Open a database in a fresh temporary directory
+fn open_database() -> (TempDir, PathBuf, Database) {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("db.sqlite");
+    let db = Database::new_with_path("test_peer", &db_path).unwrap();
+    (temp_dir, db_path, db)
+}
+
+/// The opponent's reply to 1. e4, signed by `signer` as move `move_number`
+fn signed_reply(game_id: &str, signer: &Identity, move_number: u32) -> MoveMessage {
+    let mut board = Board::new();
+    board.make_move(Move::from_str("e2e4").unwrap()).unwrap();
+    let prior_hash = hash_board_state(&board);
+    board.make_move(Move::from_str("e7e5").unwrap()).unwrap();
+    MoveMessage::new(
+        game_id.to_string(),
+        "e7e5".to_string(),
+        hash_board_state(&board),
+    )
+    .signed(signer, move_number, &prior_hash)
+    .unwrap()
+}
+
+#[test]
+fn test_signature_binds_move_to_its_context() {
+    let identity = Identity::generate().unwrap();
+    let game_id = generate_game_id();
+    let prior_hash = hash_board_state(&Board::new());
+    let mut board = Board::new();
+    board.make_move(Move::from_str("e2e4").unwrap()).unwrap();
+    let message = MoveMessage::new(game_id, "e2e4".to_string(), hash_board_state(&board))
+        .signed(&identity, 1, &prior_hash)
+        .unwrap();
+
+    assert_eq!(
+        message.verify_signature(1, &prior_hash).unwrap(),
+        identity.peer_id().as_str()
+    );
+    assert!(message.verify_signature(2, &prior_hash).is_err());
+    assert!(message
+        .verify_signature(1, &hash_board_state(&board))
+        .is_err());
+
+    let mut replayed = message.clone();
+    replayed.game_id = generate_game_id();
+    assert!(replayed.verify_signature(1, &prior_hash).is_err());
+
+    let mut altered = message.clone();
+    altered.chess_move = "e2e3".to_string();
+    assert!(altered.verify_signature(1, &prior_hash).is_err());
+
+    let unsigned = MoveMessage::new(generate_game_id(), "e2e4".to_string(), prior_hash.clone());
+    assert!(unsigned.verify_signature(1, &prior_hash).is_err());
+}
+
+#[test]
+fn test_moves_stored_before_signing_still_parse() {
+    let message = MoveMessage::new(
+        generate_game_id(),
+        "e2e4".to_string(),
+        hash_board_state(&Board::new()),
+    );
+    let old = format!(
+        r#"{{"game_id":"{}","chess_move":"e2e4","board_state_hash":"{}"}}"#,
+        message.game_id, message.board_state_hash
+    );
+    let parsed: MoveMessage = serde_json::from_str(&old).unwrap();
+    assert_eq!(parsed, message);
+}
+
+#[test]
+fn test_moves_from_peers_that_predate_signing_decode() {
+    let message = MoveMessage::new(
+        generate_game_id(),
+        "e2e4".to_string(),
+        hash_board_state(&Board::new()),
+    );
+    // Message::Move on the wire before moves were signed: the variant index
+    // followed by the three original fields
+    type OldMove = (u32, String, String, String);
+    let old: OldMove = (
+        5,
+        message.game_id.clone(),
+        message.chess_move.clone(),
+        message.board_state_hash.clone(),
+    );
+    let decoded = WireMessage::deserialize(&bincode::serialize(&old).unwrap()).unwrap();
+    let WireMessage::Move(decoded) = decoded else {
+        panic!("expected a move, got {}", decoded.message_type());
+    };
+    assert_eq!(decoded, message);
+
+    // Older peers read a signed move and skip its signature
+    let signed = message
+        .signed(
+            &Identity::generate().unwrap(),
+            1,
+            &hash_board_state(&Board::new()),
+        )
+        .unwrap();
+    let bytes = WireMessage::Move(signed).serialize().unwrap();
+    assert_eq!(bincode::deserialize::<OldMove>(&bytes).unwrap(), old);
+}
+
+#[test]
+fn test_own_moves_are_signed_and_stored_with_signature() {
+    let (_temp_dir, db_path, db) = open_database();
+    let identity = Identity::generate().unwrap();
+    let game_id = create_active_game(&db, &db_path, "opponent_peer");
+
+    let result = MoveProcessor::new(&db)
+        .with_signer(&identity)
+        .process_move(&game_id, "e2e4", true)
+        .unwrap();
+
+    let signature = result.move_message.signature.clone().unwrap();
+    assert_eq!(signature.move_number, 1);
+    assert_eq!(signature.prior_hash, hash_board_state(&Board::new()));
+
+    let stored = db.get_messages_by_type(&game_id, "Move").unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].signature, signature.signature);
+    assert_eq!(stored[0].sender_peer_id, identity.peer_id().as_str());
+
+    let report = MoveProcessor::new(&db).verify_signatures(&game_id).unwrap();
+    assert_eq!(report.signed, 1);
+    assert!(report.is_intact());
+}
+
+#[test]
+fn test_opponent_signed_move_is_verified_on_receipt() {
+    let (_temp_dir, db_path, db) = open_database();
+    let me = Identity::generate().unwrap();
+    let opponent = Identity::generate().unwrap();
+    let impostor = Identity::generate().unwrap();
+    let game_id = create_active_game(&db, &db_path, opponent.peer_id().as_str());
+    let processor = MoveProcessor::new(&db).with_signer(&me);
+    processor.process_move(&game_id, "e2e4", true).unwrap();
+
+    for (reply, reason) in [
+        (signed_reply(&game_id, &impostor, 2), "not by the opponent"),
+        (signed_reply(&game_id, &opponent, 3), "signed as move 3"),
+    ] {
+        let error = processor
+            .apply_opponent_move(&game_id, &reply)
+            .expect_err("Mis-signed move should be rejected");
+        assert!(
+            matches!(error, MoveProcessingError::SignatureError(_)),
+            "{error}"
+        );
+        assert!(error.to_string().contains(reason), "{error}");
+    }
+
+    let result = processor
+        .apply_opponent_move(&game_id, &signed_reply(&game_id, &opponent, 2))
+        .unwrap();
+    assert_eq!(result.move_number, 2);
+    let stored = db.get_messages_by_type(&game_id, "Move").unwrap();
+    assert_eq!(stored[1].sender_peer_id, opponent.peer_id().as_str());
+
+    let report = processor.verify_signatures(&game_id).unwrap();
+    assert_eq!(report.signed, 2);
+    assert!(report.is_intact());
+}
+
+/// The opponent's unsigned `reply` to 1. e4
+fn unsigned_reply(game_id: &str, reply: &str) -> MoveMessage {
+    let mut board = Board::new();
+    board.make_move(Move::from_str("e2e4").unwrap()).unwrap();
+    board.make_move(Move::from_str(reply).unwrap()).unwrap();
+    MoveMessage::new(
+        game_id.to_string(),
+        reply.to_string(),
+        hash_board_state(&board),
+    )
+}
+
+#[test]
+fn test_unsigned_move_is_rejected_when_the_opponent_signs() {
+    // The opponent is known by a key, so their moves must carry its signature
+    let (_temp_dir, db_path, db) = open_database();
+    let me = Identity::generate().unwrap();
+    let opponent = Identity::generate().unwrap();
+    let game_id = create_active_game(&db, &db_path, opponent.peer_id().as_str());
+    let processor = MoveProcessor::new(&db).with_signer(&me);
+    processor.process_move(&game_id, "e2e4", true).unwrap();
+
+    let error = processor
+        .apply_opponent_move(&game_id, &unsigned_reply(&game_id, "e7e5"))
+        .expect_err("Unsigned move should be rejected");
+    assert!(
+        matches!(error, MoveProcessingError::SignatureError(_)),
+        "{error}"
+    );
+    assert_eq!(db.get_messages_by_type(&game_id, "Move").unwrap().len(), 1);
+
+    // An opponent known only by name who has signed a move can't stop
+    // signing afterwards
+    let (_temp_dir, db_path, db) = open_database();
+    let game_id = create_active_game(&db, &db_path, "opponent_peer");
+    let processor = MoveProcessor::new(&db).with_signer(&me);
+    processor.process_move(&game_id, "e2e4", true).unwrap();
+    processor
+        .apply_opponent_move(&game_id, &signed_reply(&game_id, &opponent, 2))
+        .unwrap();
+    processor.process_move(&game_id, "g1f3", true).unwrap();
+
+    let mut board = Board::new();
+    for played in ["e2e4", "e7e5", "g1f3", "b8c6"] {
+        board.make_move(Move::from_str(played).unwrap()).unwrap();
+    }
+    let reply = MoveMessage::new(
+        game_id.clone(),
+        "b8c6".to_string(),
+        hash_board_state(&board),
+    );
+    let error = processor
+        .apply_opponent_move(&game_id, &reply)
+        .expect_err("Unsigned move should be rejected");
+    assert!(error.to_string().contains("unsigned"), "{error}");
+}
+
+#[test]
+fn test_edited_game_record_fails_signature_check() {
+    let (_temp_dir, db_path, db) = open_database();
+    let me = Identity::generate().unwrap();
+    let game_id = create_active_game(&db, &db_path, "opponent_peer");
+    let processor = MoveProcessor::new(&db).with_signer(&me);
+    processor.process_move(&game_id, "e2e4", true).unwrap();
+    // Unsigned moves from an older opponent are accepted and counted
+    processor
+        .apply_opponent_move(&game_id, &unsigned_reply(&game_id, "c7c5"))
+        .unwrap();
+
+    let report = processor.verify_signatures(&game_id).unwrap();
+    assert_eq!((report.signed, report.unsigned), (1, 1));
+
+    // Rewrite our first move in the stored record, keeping its signature
+    let first = &db.get_messages_by_type(&game_id, "Move").unwrap()[0];
+    let mut edited: MoveMessage = serde_json::from_str(&first.content).unwrap();
+    edited.chess_move = "d2d4".to_string();
+    let mut edited_board = Board::new();
+    edited_board
+        .make_move(Move::from_str("d2d4").unwrap())
+        .unwrap();
+    edited.board_state_hash = hash_board_state(&edited_board);
+    db.delete_messages_for_game(&game_id).unwrap();
+    db.store_message(
+        game_id.clone(),
+        "Move".to_string(),
+        serde_json::to_string(&edited).unwrap(),
+        first.signature.clone(),
+        first.sender_peer_id.clone(),
+    )
+    .unwrap();
+
+    let report = processor.verify_signatures(&game_id).unwrap();
+    assert!(!report.is_intact());
+    assert_eq!(report.invalid[0].0, 1);
+}
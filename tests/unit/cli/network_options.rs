@@ -0,0 +1,83 @@
+//! Network Option Tests
+//!
+//! Tests for the --timeout/--retries flags on networking commands and how
+//! they override the configured network settings
+
+use clap::Parser;
+use mate::cli::{Cli, Commands, NetworkConfig, NetworkOptions};
+use std::time::Duration;
+
+fn network_options(args: &[&str]) -> NetworkOptions {
+    match Cli::parse_from(args).command {
+        Commands::Invite { network, .. }
+        | Commands::Accept { network, .. }
+        | Commands::Move { network, .. }
+        | Commands::Connect { network, .. } => network,
+        _ => panic!("not a networking command"),
+    }
+}
+
+#[test]
+fn test_networking_commands_accept_timeout_and_retries() {
+    let expected = NetworkOptions {
+        timeout: Some(30),
+        retries: Some(5),
+        ..NetworkOptions::default()
+    };
+    for args in [
+        &["mate", "invite", "127.0.0.1:8080"][..],
+        &["mate", "accept", "abc123"],
+        &["mate", "move", "e4"],
+        &["mate", "connect", "127.0.0.1:8080"],
+    ] {
+        let mut args = args.to_vec();
+        args.extend(["--timeout", "30", "--retries", "5"]);
+        assert_eq!(network_options(&args), expected, "{args:?}");
+    }
+
+    assert_eq!(
+        network_options(&["mate", "move", "e4"]),
+        NetworkOptions::default()
+    );
+}
+
+#[test]
+fn test_invite_accepts_new_key_override() {
+    assert!(
+        network_options(&["mate", "invite", "127.0.0.1:8080", "--accept-new-key"]).accept_new_key
+    );
+    assert!(!network_options(&["mate", "invite", "127.0.0.1:8080"]).accept_new_key);
+}
+
+#[test]
+fn test_zero_timeout_is_rejected() {
+    assert!(Cli::try_parse_from(["mate", "move", "e4", "--timeout", "0"]).is_err());
+    assert!(Cli::try_parse_from(["mate", "games", "--timeout", "5"]).is_err());
+}
+
+#[test]
+fn test_options_override_configured_network_settings() {
+    let configured = NetworkConfig::default();
+
+    let unchanged = NetworkOptions::default().apply(configured.clone());
+    assert_eq!(unchanged.connection_timeout, configured.connection_timeout);
+    assert_eq!(
+        unchanged.wire_config.read_timeout,
+        configured.wire_config.read_timeout
+    );
+    assert!(unchanged.retry_config.is_none());
+
+    let overridden = NetworkOptions {
+        timeout: Some(45),
+        retries: Some(0),
+        ..NetworkOptions::default()
+    }
+    .apply(configured);
+    assert_eq!(overridden.connection_timeout, Duration::from_secs(45));
+    assert_eq!(overridden.wire_config.read_timeout, Duration::from_secs(45));
+    assert_eq!(
+        overridden.wire_config.write_timeout,
+        Duration::from_secs(45)
+    );
+    assert_eq!(overridden.retry_config.unwrap().max_attempts, 1);
+}
@@ -0,0 +1,75 @@
+//! Odds Game Tests
+//!
+//! Tests for playing, listing, and exporting games started with material or
+//! extra-move odds in `src/cli/game_ops.rs` and `src/cli/pgn.rs`.
+
+use mate::chess::{Color, Odds};
+use mate::cli::export_game_pgn;
+use mate::cli::game_ops::{GameOps, MoveProcessor};
+use mate::messages::chess::GameInvite;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use tempfile::TempDir;
+
+/// An active game in which the opponent, as White, gave us knight and move
+///
+/// Only the stored invitation records the odds, as for invitations received
+/// from a peer.
+fn knight_and_move_game() -> (TempDir, Database, String) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("my_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::Black, None)
+        .unwrap();
+    let odds = Odds {
+        giver: Color::White,
+        removed: vec!["b1".parse().unwrap()],
+        extra_moves: 1,
+    };
+    let invite = GameInvite::new(game.id.clone(), Some(Color::Black)).with_odds(odds);
+    db.store_message(
+        game.id.clone(),
+        "GameInvite".to_string(),
+        serde_json::to_string(&invite).unwrap(),
+        "signature".to_string(),
+        "opponent_peer".to_string(),
+    )
+    .unwrap();
+    db.update_game_status(&game.id, GameStatus::Active).unwrap();
+    (temp_dir, db, game.id)
+}
+
+#[test]
+fn test_receiver_moves_first_from_the_odds_position() {
+    let (_temp_dir, db, game_id) = knight_and_move_game();
+    let game_ops = GameOps::new(&db);
+
+    let state = game_ops.reconstruct_game_state(&game_id).unwrap();
+    assert!(state.your_turn);
+    assert_eq!(state.board.get_piece("b1".parse().unwrap()), None);
+    assert!(game_ops.get_game_record(&game_id).unwrap().your_turn);
+
+    MoveProcessor::new(&db)
+        .process_move(&game_id, "e7e5", true)
+        .unwrap();
+    let record = game_ops.get_game_record(&game_id).unwrap();
+    assert!(!record.your_turn);
+    assert_eq!(
+        record.odds.map(|odds| odds.to_string()).as_deref(),
+        Some("White gives Nb1 + 1 move")
+    );
+    assert_eq!(game_ops.verify_game_consistency(&game_id).unwrap(), None);
+}
+
+#[test]
+fn test_pgn_export_sets_up_the_odds_position() {
+    let (_temp_dir, db, game_id) = knight_and_move_game();
+    MoveProcessor::new(&db)
+        .process_move(&game_id, "e7e5", true)
+        .unwrap();
+
+    let pgn = export_game_pgn(&db, &game_id, "my_peer").unwrap();
+    assert!(pgn.contains("[Odds \"White gives Nb1 + 1 move\"]"));
+    assert!(pgn.contains("[SetUp \"1\"]"));
+    assert!(pgn.contains("[FEN \"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR b KQkq - 0 1\"]"));
+    assert!(pgn.contains("1... e7e5 *"));
+}
@@ -0,0 +1,105 @@
+//! Opening Explorer Tests
+//!
+//! Tests for opening classification in `src/chess/eco.rs` and grouping in
+//! `src/cli/openings.rs`
+
+use mate::chess::classify_opening;
+use mate::cli::openings::{
+    format_move_text, parse_move_prefix, san_line, summarize_openings, OpeningGrouping,
+    OpeningQuery,
+};
+use mate::storage::openings::OpeningLineStats;
+
+fn line(moves: &[&str], wins: u32, draws: u32, losses: u32, game_id: &str) -> OpeningLineStats {
+    OpeningLineStats {
+        moves: moves.iter().map(|m| m.to_string()).collect(),
+        games: 1,
+        wins,
+        draws,
+        losses,
+        game_ids: vec![game_id.to_string()],
+    }
+}
+
+#[test]
+fn test_classify_picks_the_most_specific_opening() {
+    let ruy_lopez = classify_opening(&["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6"])
+        .expect("known opening");
+    assert_eq!(ruy_lopez.eco, "C70");
+
+    let sicilian = classify_opening(&["e4", "c5", "Nf3+"]).expect("known opening");
+    assert_eq!(sicilian.name, "Sicilian Defence");
+
+    assert!(classify_opening(&["a3"]).is_none());
+    assert!(classify_opening::<&str>(&[]).is_none());
+}
+
+#[test]
+fn test_moves_are_normalized_to_san() {
+    assert_eq!(
+        san_line(&["e2e4", "e5", "g1f3", "Nc6", "e1e2"]),
+        vec!["e4", "e5", "Nf3", "Nc6", "Ke2"]
+    );
+    // Replay stops at the first move that cannot be played
+    assert_eq!(san_line(&["e4", "e4", "d4"]), vec!["e4"]);
+
+    assert_eq!(
+        parse_move_prefix("1. e4 c5 2.Nf3").unwrap(),
+        vec!["e4", "c5", "Nf3"]
+    );
+    assert!(parse_move_prefix("e4 Ke7").is_err());
+    assert_eq!(format_move_text(&["e4", "c5", "Nf3"]), "1. e4 c5 2. Nf3");
+}
+
+#[test]
+fn test_summaries_merge_notations_and_score_finished_games() {
+    let lines = vec![
+        line(&["e4", "e5", "Nf3", "Nc6", "Bc4"], 1, 0, 0, "g1"),
+        line(&["e2e4", "e7e5", "g1f3", "b8c6", "f1c4"], 0, 1, 0, "g2"),
+        line(&["e4", "c5"], 0, 0, 0, "g3"),
+    ];
+
+    let summaries = summarize_openings(&lines, &OpeningQuery::default());
+    assert_eq!(summaries.len(), 2);
+
+    let italian = &summaries[0];
+    assert_eq!(italian.opening.map(|o| o.name), Some("Italian Game"));
+    assert_eq!(italian.games, 2);
+    assert_eq!(italian.score(), Some(75.0));
+    assert_eq!(italian.game_ids, vec!["g1", "g2"]);
+
+    let sicilian = &summaries[1];
+    assert_eq!(sicilian.opening.map(|o| o.eco), Some("B20"));
+    assert_eq!(sicilian.score(), None);
+    assert_eq!(sicilian.unfinished(), 1);
+}
+
+#[test]
+fn test_move_prefix_drills_down_into_continuations() {
+    let lines = vec![
+        line(&["e4", "e5", "Nf3"], 1, 0, 0, "g1"),
+        line(&["e4", "c5", "Nf3"], 0, 0, 1, "g2"),
+        line(&["e4", "c5", "Nc3"], 1, 0, 0, "g3"),
+        line(&["d4", "d5"], 1, 0, 0, "g4"),
+    ];
+    let query = OpeningQuery {
+        grouping: OpeningGrouping::Moves,
+        prefix: vec!["e4".to_string()],
+        ..OpeningQuery::default()
+    };
+    assert_eq!(query.plies(), 2);
+
+    let summaries = summarize_openings(&lines, &query);
+    let moves: Vec<_> = summaries.iter().map(|s| s.moves.join(" ")).collect();
+    assert_eq!(moves, vec!["e4 c5", "e4 e5"]);
+    assert_eq!(summaries[0].games, 2);
+    assert_eq!(summaries[0].score(), Some(50.0));
+
+    let query = OpeningQuery {
+        eco: Some("d".to_string()),
+        ..OpeningQuery::default()
+    };
+    let summaries = summarize_openings(&lines, &query);
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].game_ids, vec!["g4"]);
+}
@@ -0,0 +1,52 @@
+//! Tests for drawing progress of long-running operations
+
+use indicatif::ProgressDrawTarget;
+use mate::cli::display::OutputFormat;
+use mate::cli::progress::{progress_enabled, ProgressDisplay, ProgressEvent};
+use std::time::Duration;
+
+#[test]
+fn test_progress_is_never_drawn_for_json_output() {
+    assert!(!progress_enabled(OutputFormat::Json));
+}
+
+#[test]
+fn test_display_switches_between_spinner_and_counter() {
+    let display = ProgressDisplay::new(ProgressDrawTarget::hidden);
+    assert!(display.current().is_none());
+
+    display.update(&ProgressEvent::Connecting {
+        address: "127.0.0.1:8080".to_string(),
+        attempt: 2,
+        max_attempts: 3,
+    });
+    let spinner = display.current().expect("spinner shown while connecting");
+    assert_eq!(spinner.length(), None);
+    assert_eq!(
+        spinner.message(),
+        "Connecting to 127.0.0.1:8080 (attempt 2/3)..."
+    );
+
+    display.update(&ProgressEvent::Retrying {
+        address: "127.0.0.1:8080".to_string(),
+        delay: Duration::from_millis(1500),
+    });
+    assert_eq!(
+        display.current().unwrap().message(),
+        "Could not reach 127.0.0.1:8080; retrying in 1.5s..."
+    );
+
+    display.update(&ProgressEvent::Advanced {
+        label: "Importing".to_string(),
+        done: 3,
+        total: 10,
+    });
+    let counter = display.current().expect("counter shown while importing");
+    assert!(spinner.is_finished());
+    assert_eq!(counter.length(), Some(10));
+    assert_eq!(counter.position(), 3);
+
+    display.update(&ProgressEvent::Finished);
+    assert!(display.current().is_none());
+    assert!(counter.is_finished());
+}
@@ -0,0 +1,94 @@
+//! Turn Reminder Tests
+//!
+//! Tests for the reminders `mate serve` sends about games waiting on the
+//! user's move, in `src/cli/reminders.rs`.
+
+use mate::cli::game_ops::MoveProcessor;
+use mate::cli::hotseat::HOTSEAT_OPPONENT;
+use mate::cli::reminders::{format_waiting, Reminders};
+use mate::messages::chess::Move;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::sync::Arc;
+use std::time::Duration;
+
+const AFTER: i64 = 3600;
+
+fn active_game(database: &Database, opponent: &str, color: PlayerColor) -> String {
+    let game = database
+        .create_game(opponent.to_string(), color, None)
+        .unwrap();
+    database
+        .update_game_status(&game.id, GameStatus::Active)
+        .unwrap();
+    game.id
+}
+
+#[test]
+fn test_waits_are_described_in_the_largest_whole_unit() {
+    assert_eq!(format_waiting(0), "1 minute");
+    assert_eq!(format_waiting(150), "2 minutes");
+    assert_eq!(format_waiting(3600), "1 hour");
+    assert_eq!(format_waiting(5 * 3600 + 59), "5 hours");
+    assert_eq!(format_waiting(3 * 86_400 + 7200), "3 days");
+}
+
+#[test]
+fn test_games_waiting_on_you_are_reminded_once_per_period() {
+    let database = Arc::new(Database::new_in_memory("me").unwrap());
+    let game_id = active_game(&database, "them", PlayerColor::White);
+    // Neither waits on the user: Black moves second, and hotseat games
+    // have both players at the board
+    active_game(&database, "other", PlayerColor::Black);
+    active_game(&database, HOTSEAT_OPPONENT, PlayerColor::White);
+    let created = database.get_game(&game_id).unwrap().created_at;
+
+    let mut reminders = Reminders::new(Arc::clone(&database), Duration::from_secs(AFTER as u64));
+    assert!(reminders.due(created + AFTER - 1).unwrap().is_empty());
+
+    let due = reminders.due(created + AFTER).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].game_id, game_id);
+    assert_eq!(due[0].opponent_peer_id, "them");
+    assert_eq!(due[0].waiting_secs, AFTER as u64);
+    assert!(due[0].notification().0.contains("for 1 hour"));
+
+    // Not again until another period has passed
+    assert!(reminders.due(created + AFTER + 60).unwrap().is_empty());
+    assert_eq!(reminders.due(created + 2 * AFTER).unwrap().len(), 1);
+
+    let payload = serde_json::to_value(due[0].webhook_payload(created + AFTER)).unwrap();
+    assert_eq!(payload["event"], "reminder");
+    assert_eq!(payload["waiting_secs"], AFTER);
+}
+
+#[test]
+fn test_the_wait_restarts_when_the_opponent_moves() {
+    let database = Arc::new(Database::new_in_memory("me").unwrap());
+    let game_id = active_game(&database, "them", PlayerColor::White);
+    let mut reminders = Reminders::new(Arc::clone(&database), Duration::from_secs(AFTER as u64));
+
+    MoveProcessor::new(&database)
+        .process_move(&game_id, "e2e4", true)
+        .unwrap();
+    let far_future = Database::current_timestamp() + 100 * AFTER;
+    assert!(reminders.due(far_future).unwrap().is_empty());
+
+    let reply = Move::new(game_id.clone(), "e7e5".to_string(), "0".repeat(64));
+    let stored = database
+        .store_message(
+            game_id.clone(),
+            "Move".to_string(),
+            serde_json::to_string(&reply).unwrap(),
+            String::new(),
+            "them".to_string(),
+        )
+        .unwrap();
+
+    assert!(reminders
+        .due(stored.created_at + AFTER - 1)
+        .unwrap()
+        .is_empty());
+    let due = reminders.due(stored.created_at + AFTER).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].waiting_secs, AFTER as u64);
+}
@@ -0,0 +1,65 @@
+//! Self-Play Tests
+//!
+//! Tests for the engine-vs-engine harness in `src/cli/selfplay.rs`.
+
+use mate::chess::{Board, Color, Position};
+use mate::cli::selfplay::{
+    builtin_move, parse_time_control, run_selfplay, SelfplayOptions, SELFPLAY_TAG,
+};
+use mate::storage::models::GameStatus;
+use mate::storage::Database;
+
+#[test]
+fn test_time_control_parses_minutes_and_increment() {
+    let tc = parse_time_control("1+0").unwrap();
+    assert_eq!((tc.initial_time_ms, tc.increment_ms), (60_000, 0));
+    let tc = parse_time_control("0.5+2").unwrap();
+    assert_eq!((tc.initial_time_ms, tc.increment_ms), (30_000, 2_000));
+    assert!(parse_time_control("5").is_err());
+    assert!(parse_time_control("0+1").is_err());
+}
+
+#[test]
+fn test_builtin_player_takes_mate_in_one() {
+    // Back-rank mate: Ra8#
+    let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    let mv = builtin_move(&board).unwrap();
+    assert_eq!(mv.to, Position::new(0, 7).unwrap());
+    assert_eq!(board.active_color(), Color::White);
+}
+
+#[tokio::test]
+async fn test_builtin_match_is_played_over_loopback_and_stored() {
+    let database = Database::new_in_memory("me").unwrap();
+    let options = SelfplayOptions {
+        games: 2,
+        ..SelfplayOptions::default()
+    };
+
+    let report = run_selfplay(&database, &options).await.unwrap();
+
+    assert_eq!(report.games.len(), 2);
+    let plies: usize = report.games.iter().map(|game| game.plies).sum();
+    assert_eq!(report.moves_sent, plies as u64);
+    let [first, second] = &report.standings;
+    assert_eq!(first.wins, second.losses);
+    assert_eq!(first.draws, second.draws);
+    assert_eq!(first.points() + second.points(), 2.0);
+
+    let stored = database.get_games_with_tag(SELFPLAY_TAG).unwrap();
+    assert_eq!(stored.len(), 2);
+    assert!(stored
+        .iter()
+        .all(|game| game.status == GameStatus::Completed));
+}
+
+#[tokio::test]
+async fn test_missing_uci_engine_is_reported() {
+    let database = Database::new_in_memory("me").unwrap();
+    let options = SelfplayOptions {
+        engines: ["builtin".to_string(), "/nonexistent/engine".to_string()],
+        ..SelfplayOptions::default()
+    };
+    let error = run_selfplay(&database, &options).await.unwrap_err();
+    assert!(format!("{error:#}").contains("/nonexistent/engine"));
+}
@@ -0,0 +1,100 @@
+//! Chat Session Tests
+//!
+//! Tests for driving a chat session programmatically through
+//! `src/cli/session.rs`, without a terminal
+
+use mate::cli::session::{ChatCommand, ChatEvent, ChatSession, CommandRouter};
+use mate::cli::NetworkOptions;
+use mate::crypto::Identity;
+use mate::network::{Client, Server};
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Start an echo server and connect a session to it
+async fn connected_session() -> (ChatSession, tokio::task::JoinHandle<anyhow::Result<()>>) {
+    let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap();
+    let address = server.local_addr().unwrap().to_string();
+    let handle = tokio::spawn(async move { server.run().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = Client::new(Arc::new(Identity::generate().unwrap()));
+    let session = ChatSession::connect(client, &address, NetworkOptions::default())
+        .await
+        .unwrap();
+    (session, handle)
+}
+
+#[test]
+fn test_chat_command_parsing() {
+    assert_eq!(ChatCommand::parse("   "), None);
+    assert_eq!(ChatCommand::parse(" help "), Some(ChatCommand::Help));
+    assert_eq!(ChatCommand::parse("info"), Some(ChatCommand::Info));
+    assert_eq!(ChatCommand::parse("quit"), Some(ChatCommand::Quit));
+    assert_eq!(ChatCommand::parse("exit"), Some(ChatCommand::Quit));
+    assert_eq!(
+        ChatCommand::parse(" hello there "),
+        Some(ChatCommand::Send("hello there".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_router_drives_a_session() {
+    let (session, server) = connected_session().await;
+    let mut router = CommandRouter::new(session);
+
+    assert_eq!(router.handle_line("").await.unwrap(), None);
+    assert_eq!(
+        router.handle_line("help").await.unwrap(),
+        Some(ChatEvent::Help)
+    );
+
+    match router.handle_line("hello").await.unwrap() {
+        Some(ChatEvent::Echo(echo)) => {
+            assert_eq!(echo.payload, "hello");
+            assert!(!echo.reconnected);
+        }
+        other => panic!("Expected an echo, got {other:?}"),
+    }
+
+    match router.handle_line("info").await.unwrap() {
+        Some(ChatEvent::Info(stats)) => {
+            assert_eq!(stats.messages_sent, 1);
+            assert!(stats.average_round_trip.is_some());
+            assert_eq!(stats.peer_id, router.session().peer_id());
+        }
+        other => panic!("Expected connection info, got {other:?}"),
+    }
+
+    assert_eq!(
+        router.handle_line("quit").await.unwrap(),
+        Some(ChatEvent::Quit)
+    );
+    router.session_mut().close().await;
+    server.abort();
+}
+
+#[tokio::test]
+async fn test_batch_replies_to_every_line() {
+    let (mut session, server) = connected_session().await;
+
+    let mut replies = Vec::new();
+    let failures = session
+        .run_batch(Cursor::new("first\n\nsecond\n"), |reply| {
+            replies.push(reply.clone())
+        })
+        .await;
+
+    assert_eq!(failures, 0);
+    let echoed: Vec<_> = replies
+        .iter()
+        .map(|reply| (reply.seq, reply.response.as_deref()))
+        .collect();
+    assert_eq!(echoed, [(1, Some("first")), (3, Some("second"))]);
+    assert_eq!(session.stats().messages_sent, 2);
+
+    session.close().await;
+    server.abort();
+}
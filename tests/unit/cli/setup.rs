@@ -0,0 +1,116 @@
+//! First-Run Setup Wizard Tests
+//!
+//! Tests the onboarding questions in `src/cli/setup.rs` with scripted
+//! answers and the `mate://` connection string format
+
+use mate::cli::app::Config;
+use mate::cli::setup::{resolve_address, ConnectionString, SetupWizard};
+use mate::crypto::Identity;
+use std::io::Cursor;
+use tempfile::TempDir;
+
+/// Run the wizard against a config rooted in `data_dir`, returning the summary and transcript
+fn run_wizard(
+    data_dir: &std::path::Path,
+    answers: &str,
+) -> (mate::cli::setup::SetupSummary, String) {
+    let config = Config {
+        data_dir: data_dir.to_path_buf(),
+        ..Default::default()
+    };
+    let mut output = Vec::new();
+    let summary = SetupWizard::new(Cursor::new(answers.to_string()), &mut output)
+        .run(config)
+        .expect("Wizard should complete");
+    (summary, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_connection_string_roundtrip() {
+    let connection = ConnectionString::new("abc123", "192.168.1.5:8080");
+    assert_eq!(connection.to_string(), "mate://abc123@192.168.1.5:8080");
+    assert_eq!(
+        "mate://abc123@192.168.1.5:8080"
+            .parse::<ConnectionString>()
+            .unwrap(),
+        connection
+    );
+
+    assert!("abc123@host:1".parse::<ConnectionString>().is_err());
+    assert!("mate://host:1".parse::<ConnectionString>().is_err());
+    assert!("mate://@host:1".parse::<ConnectionString>().is_err());
+}
+
+#[test]
+fn test_resolve_address_accepts_both_forms() {
+    assert_eq!(resolve_address("mate://abc@10.0.0.2:9000"), "10.0.0.2:9000");
+    assert_eq!(resolve_address("10.0.0.2:9000"), "10.0.0.2:9000");
+}
+
+#[test]
+fn test_unspecified_host_is_detected() {
+    assert!(ConnectionString::new("abc", "0.0.0.0:8080").has_unspecified_host());
+    assert!(!ConnectionString::new("abc", "10.0.0.2:8080").has_unspecified_host());
+}
+
+#[test]
+fn test_wizard_accepts_defaults_and_generates_identity() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Data dir, bind address, theme take defaults; Unicode answered explicitly
+    let (summary, transcript) = run_wizard(temp_dir.path(), "\n\nn\n\n");
+
+    assert!(temp_dir.path().join("identity.key").exists());
+    assert!(transcript.contains("Generated identity"));
+    assert_eq!(summary.config.data_dir, temp_dir.path());
+    assert_eq!(summary.config.db_path, None);
+    assert_eq!(summary.config.default_bind_addr, "127.0.0.1:8080");
+    assert_eq!(summary.config.display.unicode, Some(false));
+    assert_eq!(summary.config.display.theme, None);
+    assert_eq!(
+        summary.connection_string,
+        ConnectionString::new(&summary.peer_id, "127.0.0.1:8080")
+    );
+}
+
+#[test]
+fn test_wizard_applies_answers_and_retries_invalid_ones() {
+    let temp_dir = TempDir::new().unwrap();
+    let custom_dir = temp_dir.path().join("custom");
+
+    let answers = format!(
+        "{}\nnot-an-address\n0.0.0.0:9000\nmaybe\ny\nneon\ngreen\n",
+        custom_dir.display()
+    );
+    let (summary, transcript) = run_wizard(temp_dir.path(), &answers);
+
+    assert!(custom_dir.join("identity.key").exists());
+    assert_eq!(summary.config.data_dir, custom_dir);
+    assert_eq!(
+        summary.config.db_path,
+        Some(custom_dir.join("database.sqlite"))
+    );
+    assert_eq!(summary.config.default_bind_addr, "0.0.0.0:9000");
+    assert_eq!(summary.config.display.unicode, Some(true));
+    assert_eq!(summary.config.display.theme.as_deref(), Some("green"));
+    assert!(summary.connection_string.has_unspecified_host());
+
+    assert!(transcript.contains("Enter an address like"));
+    assert!(transcript.contains("Please answer y or n"));
+    assert!(transcript.contains("Unknown theme 'neon'"));
+}
+
+#[test]
+fn test_wizard_keeps_existing_identity() {
+    let temp_dir = TempDir::new().unwrap();
+    let existing = Identity::generate().unwrap();
+    existing
+        .save_to_storage_path(&temp_dir.path().join("identity.key"))
+        .unwrap();
+
+    // Keep the identity (default), then accept the remaining defaults
+    let (summary, transcript) = run_wizard(temp_dir.path(), "\n\n\nn\n\n");
+
+    assert_eq!(summary.peer_id, existing.peer_id().to_string());
+    assert!(!transcript.contains("Generated identity"));
+}
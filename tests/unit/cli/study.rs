@@ -0,0 +1,91 @@
+//! Study Tests
+//!
+//! Tests for parsing, validating, and rendering shared studies in
+//! `src/cli/study.rs` and `src/messages/chess.rs`.
+
+use mate::chess::Board;
+use mate::cli::study::{find_study, format_study_text, parse_annotated_line, study_san};
+use mate::messages::chess::{validate_study, Study};
+use mate::messages::types::Message;
+use mate::storage::StudyRecord;
+
+fn start() -> String {
+    Board::new().to_fen()
+}
+
+#[test]
+fn test_annotated_line_keeps_comments_with_their_moves() {
+    let (comment, moves) =
+        parse_annotated_line(&start(), "{Open game} 1. e4 {Best by test} e5 2.Nf3 g8f6").unwrap();
+
+    assert_eq!(comment.as_deref(), Some("Open game"));
+    let line: Vec<&str> = moves.iter().map(|m| m.chess_move.as_str()).collect();
+    assert_eq!(line, ["e2e4", "e7e5", "g1f3", "g8f6"]);
+    assert_eq!(moves[0].comment.as_deref(), Some("Best by test"));
+    assert_eq!(moves[1].comment, None);
+
+    assert!(parse_annotated_line(&start(), "e4 {unclosed").is_err());
+    assert!(parse_annotated_line(&start(), "e4 e4").is_err());
+}
+
+#[test]
+fn test_study_text_numbers_moves_from_the_position() {
+    let (_, moves) = parse_annotated_line(&start(), "e4 {Best by test} e5 Nf3").unwrap();
+    let study = Study::new("Open game".to_string(), start(), None, moves);
+    assert_eq!(study_san(&study).unwrap(), ["e4", "e5", "Nf3"]);
+    assert_eq!(
+        format_study_text(&study).unwrap(),
+        "1. e4 {Best by test} 1... e5 2. Nf3"
+    );
+
+    // Black to move on move 20
+    let fen = "6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 0 20";
+    let (_, moves) = parse_annotated_line(fen, "h6 Ra8+").unwrap();
+    let study = Study::new("Back rank".to_string(), fen.to_string(), None, moves);
+    assert_eq!(format_study_text(&study).unwrap(), "20... h6 21. Ra8+");
+}
+
+#[test]
+fn test_invalid_studies_are_rejected() {
+    let (_, moves) = parse_annotated_line(&start(), "e4 e5").unwrap();
+    let study = Study::new("Open game".to_string(), start(), None, moves.clone());
+    assert!(validate_study(&study).is_ok());
+    assert!(Message::new_study(study.clone()).validate().is_ok());
+
+    let untitled = Study::new(" ".to_string(), start(), None, moves.clone());
+    assert!(validate_study(&untitled).is_err());
+
+    let mut wrong_order = study.clone();
+    wrong_order.moves.reverse();
+    assert!(validate_study(&wrong_order).is_err());
+
+    let mut bad_fen = study.clone();
+    bad_fen.fen = "not a position".to_string();
+    assert!(validate_study(&bad_fen).is_err());
+
+    let long_comment = Study::new(
+        "Open game".to_string(),
+        start(),
+        Some("x".repeat(1001)),
+        moves,
+    );
+    assert!(validate_study(&long_comment).is_err());
+}
+
+#[test]
+fn test_studies_are_found_by_id_prefix() {
+    let record = |id: &str| StudyRecord {
+        id: id.to_string(),
+        peer_id: "coach".to_string(),
+        outgoing: false,
+        title: "Open game".to_string(),
+        study: String::new(),
+        created_at: 0,
+    };
+    let studies = vec![record("abc123"), record("abd456")];
+
+    assert_eq!(find_study(&studies, "abc").unwrap().id, "abc123");
+    assert_eq!(find_study(&studies, "abd456").unwrap().id, "abd456");
+    assert!(find_study(&studies, "ab").is_err());
+    assert!(find_study(&studies, "xyz").is_err());
+}
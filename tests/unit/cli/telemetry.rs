@@ -0,0 +1,147 @@
+//! Telemetry Tests
+//!
+//! Tests for span collection and OTLP/HTTP export in `src/cli/telemetry.rs`
+
+use mate::cli::telemetry::{encode_spans, OtlpExporter, OtlpLayer, SpanRecord};
+use mate::network::http_client::HttpUrl;
+use serde_json::{json, Map};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tracing_subscriber::layer::SubscriberExt;
+
+fn span_record(attributes: serde_json::Value) -> SpanRecord {
+    let serde_json::Value::Object(attributes) = attributes else {
+        panic!("attributes must be an object");
+    };
+    SpanRecord {
+        trace_id: [0xab; 16],
+        span_id: [0x01; 8],
+        parent_span_id: Some([0x02; 8]),
+        name: "write_message".to_string(),
+        target: "mate::messages::wire".to_string(),
+        start: UNIX_EPOCH + Duration::from_secs(1),
+        end: UNIX_EPOCH + Duration::from_secs(2),
+        attributes,
+    }
+}
+
+#[test]
+fn test_encode_spans_follows_otlp_json() {
+    let record = span_record(json!({ "peer_id": "abc", "message_size": 42, "ok": true }));
+    let body = encode_spans("mate-test", &[record]);
+
+    let resource = &body["resourceSpans"][0];
+    assert_eq!(
+        resource["resource"]["attributes"][0],
+        json!({ "key": "service.name", "value": { "stringValue": "mate-test" } })
+    );
+    let span = &resource["scopeSpans"][0]["spans"][0];
+    assert_eq!(span["traceId"], "ab".repeat(16));
+    assert_eq!(span["spanId"], "01".repeat(8));
+    assert_eq!(span["parentSpanId"], "02".repeat(8));
+    assert_eq!(span["name"], "write_message");
+    assert_eq!(span["startTimeUnixNano"], "1000000000");
+    assert_eq!(span["endTimeUnixNano"], "2000000000");
+
+    let attributes = span["attributes"].as_array().unwrap();
+    for expected in [
+        json!({ "key": "message_size", "value": { "intValue": "42" } }),
+        json!({ "key": "ok", "value": { "boolValue": true } }),
+        json!({ "key": "peer_id", "value": { "stringValue": "abc" } }),
+        json!({ "key": "code.namespace", "value": { "stringValue": "mate::messages::wire" } }),
+    ] {
+        assert!(attributes.contains(&expected), "{attributes:?}");
+    }
+
+    let root = SpanRecord {
+        parent_span_id: None,
+        ..span_record(json!({}))
+    };
+    let body = encode_spans("mate", &[root]);
+    assert!(body["resourceSpans"][0]["scopeSpans"][0]["spans"][0]
+        .get("parentSpanId")
+        .is_none());
+}
+
+#[test]
+fn test_layer_links_child_spans_and_records_late_fields() {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let subscriber = tracing_subscriber::registry().with(OtlpLayer::new(sender));
+
+    tracing::subscriber::with_default(subscriber, || {
+        let outer = tracing::info_span!(
+            "handle_connection",
+            connection_id = 7,
+            peer_id = tracing::field::Empty
+        );
+        let _entered = outer.enter();
+        outer.record("peer_id", "abc");
+        tracing::debug_span!("store_message", msg_type = "Move").in_scope(|| {});
+    });
+
+    let inner = receiver.try_recv().expect("inner span closes first");
+    let outer = receiver.try_recv().expect("outer span");
+    assert_eq!(inner.name, "store_message");
+    assert_eq!(inner.trace_id, outer.trace_id);
+    assert_eq!(inner.parent_span_id, Some(outer.span_id));
+    assert_eq!(outer.parent_span_id, None);
+    assert!(inner.start <= inner.end && inner.end <= SystemTime::now());
+
+    let mut expected = Map::new();
+    expected.insert("connection_id".to_string(), 7.into());
+    expected.insert("peer_id".to_string(), "abc".into());
+    assert_eq!(outer.attributes, expected);
+    assert_eq!(inner.attributes["msg_type"], "Move");
+}
+
+#[tokio::test]
+async fn test_exporter_posts_spans_on_shutdown() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = HttpUrl::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+
+    let collector = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = Vec::new();
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            head.push(line.trim_end().to_string());
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await.unwrap();
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .await
+            .unwrap();
+        (head, body)
+    });
+
+    let (layer, exporter) = OtlpExporter::start(&endpoint, "mate-test");
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!("perform_handshake").in_scope(|| {});
+    });
+    exporter.shutdown().await;
+
+    let (head, body) = tokio::time::timeout(Duration::from_secs(5), collector)
+        .await
+        .expect("export request")
+        .unwrap();
+    assert_eq!(head[0], "POST /v1/traces HTTP/1.1");
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body["resourceSpans"][0]["scopeSpans"][0]["spans"][0]["name"],
+        "perform_handshake"
+    );
+}
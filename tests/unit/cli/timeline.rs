@@ -0,0 +1,156 @@
+//! Game Timeline Tests
+//!
+//! Tests for interleaving messages, sync issues, and connection events in
+//! `src/cli/timeline.rs`
+
+use mate::cli::timeline::{
+    build_timeline, format_timeline_time, TimelineActor, TimelineEntry, TimelineKind,
+};
+use mate::storage::models::{
+    Game, GameResult, GameStatus, GameSyncIssue, Message, PeerEvent, PeerEventType, PlayerColor,
+};
+
+const ME: &str = "me";
+const OPPONENT: &str = "opponent";
+
+fn game() -> Game {
+    Game {
+        id: "game-1".to_string(),
+        opponent_peer_id: OPPONENT.to_string(),
+        my_color: PlayerColor::White,
+        status: GameStatus::Completed,
+        created_at: 100,
+        updated_at: 160,
+        completed_at: Some(160),
+        result: Some(GameResult::Win),
+        metadata: None,
+    }
+}
+
+fn message(message_type: &str, content: &str, sender: &str, created_at: i64) -> Message {
+    Message {
+        id: None,
+        game_id: "game-1".to_string(),
+        message_type: message_type.to_string(),
+        content: content.to_string(),
+        signature: String::new(),
+        sender_peer_id: sender.to_string(),
+        created_at,
+    }
+}
+
+fn entry(
+    timestamp: i64,
+    kind: TimelineKind,
+    actor: TimelineActor,
+    description: &str,
+) -> TimelineEntry {
+    TimelineEntry {
+        timestamp,
+        kind,
+        actor,
+        description: description.to_string(),
+    }
+}
+
+#[test]
+fn test_timeline_interleaves_everything_by_time() {
+    let messages = vec![
+        message("game_invite", "{}", ME, 100),
+        message("move", r#"{"chess_move":"e4"}"#, ME, 110),
+        message("Chat", r#"{"text":"good luck"}"#, OPPONENT, 120),
+        message("DrawOffer", "{}", OPPONENT, 140),
+        message("Resign", "{}", OPPONENT, 160),
+    ];
+    let events = vec![PeerEvent {
+        id: None,
+        peer_id: OPPONENT.to_string(),
+        event_type: PeerEventType::HandshakeFailure,
+        details: Some("bad signature".to_string()),
+        remote_addr: Some("10.0.0.2:8080".to_string()),
+        created_at: 130,
+    }];
+    let issue = GameSyncIssue {
+        game_id: "game-1".to_string(),
+        reason: "board mismatch".to_string(),
+        detected_at: 135,
+    };
+
+    let timeline = build_timeline(&game(), &messages, &events, Some(&issue), ME);
+
+    assert_eq!(
+        timeline,
+        vec![
+            entry(
+                100,
+                TimelineKind::Game,
+                TimelineActor::System,
+                "Game created against opponent"
+            ),
+            entry(
+                100,
+                TimelineKind::Invitation,
+                TimelineActor::Me,
+                "Sent the invitation"
+            ),
+            entry(110, TimelineKind::Move, TimelineActor::Me, "Played e4"),
+            entry(
+                120,
+                TimelineKind::Chat,
+                TimelineActor::Opponent,
+                "\"good luck\""
+            ),
+            entry(
+                130,
+                TimelineKind::Connection,
+                TimelineActor::System,
+                "Handshake failed from 10.0.0.2:8080 (bad signature)"
+            ),
+            entry(
+                135,
+                TimelineKind::Sync,
+                TimelineActor::System,
+                "Flagged as out of sync: board mismatch"
+            ),
+            entry(
+                140,
+                TimelineKind::Draw,
+                TimelineActor::Opponent,
+                "Offered a draw"
+            ),
+            entry(
+                160,
+                TimelineKind::Resign,
+                TimelineActor::Opponent,
+                "Resigned"
+            ),
+            entry(
+                160,
+                TimelineKind::Game,
+                TimelineActor::System,
+                "Game over: Win"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_moves_stored_by_either_code_path_count_as_mine() {
+    let messages = vec![
+        message("Move", r#"{"chess_move":"d4"}"#, "self", 110),
+        message("move", "not json", "local", 120),
+    ];
+
+    let timeline = build_timeline(&game(), &messages, &[], None, ME);
+
+    assert_eq!(timeline[1].actor, TimelineActor::Me);
+    assert_eq!(timeline[1].description, "Played d4");
+    assert_eq!(timeline[2].actor, TimelineActor::Me);
+    assert_eq!(timeline[2].description, "Played a move");
+}
+
+#[test]
+fn test_timeline_time_is_utc() {
+    assert_eq!(format_timeline_time(0), "1970.01.01 00:00:00");
+    assert_eq!(format_timeline_time(1_700_000_000), "2023.11.14 22:13:20");
+}
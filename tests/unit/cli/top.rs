@@ -0,0 +1,87 @@
+//! Top Tests
+//!
+//! Tests for the rates and rows shown by `mate top` in `src/cli/top.rs`.
+
+use mate::cli::top::{connection_line, format_rate, Rates, TopView};
+use mate::network::{ConnectionStats, MonitorSnapshot, OpenConnection};
+use std::time::{Duration, Instant};
+
+fn open(connection_id: &str, peer_id: Option<&str>, bytes_received: u64) -> OpenConnection {
+    OpenConnection {
+        connection_id: connection_id.to_string(),
+        peer_id: peer_id.map(str::to_string),
+        stats: ConnectionStats {
+            messages_received: bytes_received / 100,
+            bytes_received,
+            ..ConnectionStats::default()
+        },
+    }
+}
+
+fn reading(connections: Vec<OpenConnection>) -> MonitorSnapshot {
+    let mut snapshot = MonitorSnapshot {
+        connection_limit: 1000,
+        connections,
+        ..MonitorSnapshot::default()
+    };
+    for open in &snapshot.connections {
+        snapshot.totals.traffic.merge(&open.stats);
+    }
+    snapshot.totals.open_connections = snapshot.connections.len();
+    snapshot
+}
+
+#[test]
+fn test_rates_are_counted_between_readings() {
+    let start = Instant::now();
+    let mut view = TopView::default();
+    view.update(reading(vec![open("a", Some("peer-a"), 1_000)]), start);
+    // The first reading has nothing to compare with
+    assert_eq!(view.total, Rates::default());
+
+    view.update(
+        reading(vec![
+            open("a", Some("peer-a"), 3_000),
+            open("b", None, 8_000),
+        ]),
+        start + Duration::from_secs(2),
+    );
+    assert_eq!(view.connections["a"].bytes_in, 1_000.0);
+    assert_eq!(view.connections["a"].messages_in, 10.0);
+    // A connection opened since the last reading counts from zero
+    assert_eq!(view.connections["b"].bytes_in, 4_000.0);
+    assert_eq!(view.total.bytes_in, 5_000.0);
+
+    let busiest: Vec<&str> = view
+        .busiest()
+        .iter()
+        .map(|(open, _)| open.connection_id.as_str())
+        .collect();
+    assert_eq!(busiest, ["b", "a"]);
+    assert!(view.summary_lines()[0].starts_with("Connections: 2 open of 1000 allowed"));
+}
+
+#[test]
+fn test_connection_line_shows_peer_or_pending_handshake() {
+    let rates = Rates {
+        bytes_in: 2_048.0,
+        ..Rates::default()
+    };
+    let line = connection_line(&open("0000abcd", None, 500), &rates);
+    assert!(line.starts_with("0000abcd"));
+    assert!(line.contains("(handshake)"));
+    assert!(line.contains("5/0"));
+    assert!(line.contains("2.0 KiB/s"));
+
+    let long_peer = "f".repeat(64);
+    let line = connection_line(&open("1", Some(&long_peer), 0), &Rates::default());
+    assert!(line.contains(&format!("{}…", "f".repeat(19))));
+}
+
+#[test]
+fn test_format_rate_picks_a_unit() {
+    assert_eq!(format_rate(0.0), "0 B/s");
+    assert_eq!(format_rate(512.0), "512 B/s");
+    assert_eq!(format_rate(1_536.0), "1.5 KiB/s");
+    assert_eq!(format_rate(3.0 * 1024.0 * 1024.0), "3.0 MiB/s");
+}
@@ -0,0 +1,130 @@
+//! UCI Shim Tests
+//!
+//! Tests for the engine protocol spoken by `mate uci` in `src/cli/uci.rs`.
+
+use mate::cli::uci::{run_with, UciCommand};
+use mate::cli::App;
+use mate::messages::chess::Move;
+use mate::storage::{GameStatus, PlayerColor};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+async fn next_reply<R: tokio::io::AsyncBufRead + Unpin>(
+    replies: &mut tokio::io::Lines<R>,
+) -> String {
+    tokio::time::timeout(Duration::from_secs(10), replies.next_line())
+        .await
+        .expect("reply from the shim")
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn test_position_commands_from_the_starting_position_are_parsed() {
+    assert_eq!(
+        UciCommand::parse("position startpos moves e2e4 e7e5"),
+        Some(UciCommand::Position(Some(vec![
+            "e2e4".to_string(),
+            "e7e5".to_string()
+        ])))
+    );
+    assert_eq!(
+        UciCommand::parse("position startpos"),
+        Some(UciCommand::Position(Some(Vec::new())))
+    );
+    assert_eq!(
+        UciCommand::parse(
+            "position fen rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 moves d2d4"
+        ),
+        Some(UciCommand::Position(Some(vec!["d2d4".to_string()])))
+    );
+    assert_eq!(
+        UciCommand::parse("position fen 8/8/8/8/8/8/8/K6k w - - 0 1"),
+        Some(UciCommand::Position(None))
+    );
+    assert_eq!(
+        UciCommand::parse("go wtime 60000 btime 60000"),
+        Some(UciCommand::Go)
+    );
+    assert_eq!(UciCommand::parse("setoption name Hash value 16"), None);
+    assert_eq!(UciCommand::parse(""), None);
+}
+
+#[tokio::test]
+async fn test_gui_moves_are_played_and_opponent_moves_returned() {
+    let temp_dir = TempDir::new().unwrap();
+    let app = Arc::new(
+        App::new_with_data_dir(temp_dir.path().to_path_buf())
+            .await
+            .unwrap(),
+    );
+    let game = app
+        .database
+        .create_game("them".to_string(), PlayerColor::White, None)
+        .unwrap();
+    app.database
+        .update_game_status(&game.id, GameStatus::Active)
+        .unwrap();
+
+    let (gui, engine) = tokio::io::duplex(4096);
+    let (engine_in, engine_out) = tokio::io::split(engine);
+    let shim = {
+        let app = Arc::clone(&app);
+        let game_id = game.id.clone();
+        tokio::spawn(async move {
+            run_with(&app, &game_id, BufReader::new(engine_in), engine_out).await
+        })
+    };
+    let (gui_in, mut gui_out) = tokio::io::split(gui);
+    let mut replies = BufReader::new(gui_in).lines();
+
+    gui_out.write_all(b"uci\n").await.unwrap();
+    assert!(next_reply(&mut replies).await.starts_with("id name mate"));
+    assert_eq!(next_reply(&mut replies).await, "id author mate");
+    assert_eq!(next_reply(&mut replies).await, "uciok");
+
+    // Our move, played on the GUI's board
+    gui_out
+        .write_all(b"isready\nposition startpos moves e2e4\n")
+        .await
+        .unwrap();
+    assert_eq!(next_reply(&mut replies).await, "readyok");
+    // The opponent is not reachable in tests
+    assert!(next_reply(&mut replies)
+        .await
+        .contains("could not reach opponent"));
+    let state = mate::cli::GameOps::new(&app.database)
+        .reconstruct_game_state(&game.id)
+        .unwrap();
+    assert_eq!(state.move_history, ["e2e4"]);
+
+    // As the server would store the opponent's reply
+    gui_out.write_all(b"go wtime 60000\n").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let reply = Move::new(game.id.clone(), "c7c5".to_string(), "0".repeat(64));
+    app.database
+        .store_message(
+            game.id.clone(),
+            "Move".to_string(),
+            serde_json::to_string(&reply).unwrap(),
+            String::new(),
+            "them".to_string(),
+        )
+        .unwrap();
+    assert_eq!(next_reply(&mut replies).await, "bestmove c7c5");
+
+    // Asking the engine to move on our turn gets a null move
+    gui_out
+        .write_all(b"position startpos moves e2e4 c7c5\ngo\n")
+        .await
+        .unwrap();
+    assert!(next_reply(&mut replies)
+        .await
+        .starts_with("info string it's your turn"));
+    assert_eq!(next_reply(&mut replies).await, "bestmove 0000");
+
+    gui_out.write_all(b"quit\n").await.unwrap();
+    shim.await.unwrap().unwrap();
+}
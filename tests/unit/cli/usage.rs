@@ -0,0 +1,131 @@
+//! Usage Report Tests
+//!
+//! Tests for the opt-in usage counters and the reports built from them in
+//! `src/cli/usage.rs`
+
+use clap::CommandFactory;
+use mate::cli::app::TelemetrySettings;
+use mate::cli::error_handler::ErrorCode;
+use mate::cli::usage::{
+    command_name, retry_delay, UsageLog, UsageReport, UsageReporter, USAGE_REPORT_INTERVAL,
+    USAGE_RETRY_DELAY,
+};
+use mate::cli::Cli;
+use mate::network::http_client::HttpUrl;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+#[test]
+fn test_command_names_leave_out_arguments() {
+    let name = |args: &[&str]| command_name(&Cli::command().get_matches_from(args));
+    assert_eq!(
+        name(&["mate", "move", "e2e4", "--game", "a1b2c3d4"]).as_deref(),
+        Some("move")
+    );
+    assert_eq!(
+        name(&["mate", "peers", "mute", "alice"]).as_deref(),
+        Some("peers mute")
+    );
+}
+
+#[test]
+fn test_counters_accumulate_until_cleared() {
+    let temp_dir = TempDir::new().unwrap();
+    let log = UsageLog::in_dir(temp_dir.path());
+    assert!(log.load().unwrap().commands.is_empty());
+
+    log.record_command("move").unwrap();
+    log.record_command("move").unwrap();
+    log.record_command("games").unwrap();
+    log.record_error(ErrorCode::Network).unwrap();
+
+    let counters = log.load().unwrap();
+    assert_eq!(counters.commands["move"], 2);
+    assert_eq!(counters.commands["games"], 1);
+    assert_eq!(counters.errors["network"], 1);
+
+    log.clear().unwrap();
+    assert_eq!(log.load().unwrap(), Default::default());
+}
+
+#[test]
+fn test_reports_need_opting_in_and_an_endpoint() {
+    let settings = TelemetrySettings {
+        usage_endpoint: Some("http://127.0.0.1:9000/usage".to_string()),
+        ..TelemetrySettings::default()
+    };
+    assert!(!settings.usage_reports);
+    assert!(settings.usage_endpoint().unwrap().is_none());
+
+    let settings = TelemetrySettings {
+        usage_reports: true,
+        ..settings
+    };
+    assert!(settings.usage_endpoint().unwrap().is_some());
+}
+
+#[test]
+fn test_failed_reports_are_retried_with_backoff() {
+    assert_eq!(retry_delay(1), USAGE_RETRY_DELAY);
+    assert_eq!(retry_delay(2), USAGE_RETRY_DELAY * 2);
+    assert_eq!(retry_delay(3), USAGE_RETRY_DELAY * 4);
+    assert_eq!(retry_delay(20), USAGE_REPORT_INTERVAL);
+    assert_eq!(retry_delay(u32::MAX), USAGE_REPORT_INTERVAL);
+}
+
+#[tokio::test]
+async fn test_report_holds_only_counts_and_clears_once_sent() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("test_peer", &temp_dir.path().join("test.db")).unwrap();
+    let game = db
+        .create_game("secret_opponent".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&game.id, GameStatus::Completed)
+        .unwrap();
+    db.create_game("secret_opponent".to_string(), PlayerColor::Black, None)
+        .unwrap();
+    let log = UsageLog::in_dir(temp_dir.path());
+    log.record_command("invite").unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = HttpUrl::parse(&format!("http://{}/usage", listener.local_addr().unwrap())).unwrap();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await.unwrap();
+        reader
+            .into_inner()
+            .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+            .await
+            .unwrap();
+        String::from_utf8(body).unwrap()
+    });
+
+    let reporter = UsageReporter::new(Arc::new(db), log.clone(), url);
+    let report = reporter.report().await.unwrap();
+    let body = server.await.unwrap();
+
+    assert_eq!(report.games["completed"], 1);
+    assert_eq!(report.games["pending"], 1);
+    assert_eq!(report.counters.commands["invite"], 1);
+    let sent: UsageReport = serde_json::from_str(&body).unwrap();
+    assert_eq!(sent, report);
+    assert!(!body.contains("secret_opponent"));
+    assert!(!body.contains(&game.id));
+    assert!(log.load().unwrap().commands.is_empty());
+}
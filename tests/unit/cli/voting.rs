@@ -0,0 +1,99 @@
+//! Consultation Vote Tests
+//!
+//! Tests for counting votes and deciding when a vote closes in consultation
+//! games, in `src/cli/voting.rs`.
+
+use mate::chess::Board;
+use mate::cli::game_ops::MoveProcessor;
+use mate::cli::voting::{tally, Voting};
+use mate::cli::NetworkManager;
+use mate::crypto::Identity;
+use mate::storage::{ConsultationVote, Database, GameStatus, PlayerColor};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn vote(voter: &str, chess_move: &str, cast_at: i64) -> ConsultationVote {
+    ConsultationVote {
+        game_id: "game".to_string(),
+        voter_peer_id: voter.to_string(),
+        chess_move: chess_move.to_string(),
+        cast_at,
+    }
+}
+
+/// An active consultation game in which we play White
+fn consultation_game(window_secs: i64) -> (TempDir, Arc<Database>, String) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new_with_path("host_peer", &temp_dir.path().join("db.sqlite")).unwrap();
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&game.id, GameStatus::Active).unwrap();
+    db.open_consultation(&game.id, window_secs).unwrap();
+    (temp_dir, Arc::new(db), game.id)
+}
+
+fn voting(database: &Arc<Database>) -> Voting {
+    let identity = Arc::new(Identity::generate().unwrap());
+    Voting::new(
+        Arc::clone(database),
+        Arc::clone(&identity),
+        NetworkManager::new(identity),
+    )
+}
+
+#[test]
+fn test_votes_for_the_same_move_count_together() {
+    let board = Board::new();
+    let votes = [
+        vote("a", "d4", 10),
+        vote("b", "e4", 11),
+        vote("c", "e2e4", 12),
+        vote("d", "e5", 13),
+        vote("e", "Nf3", 14),
+    ];
+
+    let tallies = tally(&board, &votes);
+    let counted: Vec<(&str, usize)> = tallies.iter().map(|t| (t.san.as_str(), t.votes)).collect();
+    // The illegal e5 is left out, and d4 wins the tie with Nf3 by being first
+    assert_eq!(counted, [("e4", 2), ("d4", 1), ("Nf3", 1)]);
+    assert_eq!(tallies[0].chess_move, "e2e4");
+    assert_eq!(tallies[0].first_cast_at, 11);
+}
+
+#[test]
+fn test_vote_closes_after_its_window() {
+    let (_temp_dir, db, game_id) = consultation_game(60);
+    let voting = voting(&db);
+    let now = Database::current_timestamp();
+
+    // No votes, no move to play
+    assert!(voting.closed(now + 3600).unwrap().is_empty());
+
+    db.add_voter(&game_id, "alice").unwrap();
+    db.add_voter(&game_id, "bob").unwrap();
+    db.cast_vote(&game_id, "alice", "e4").unwrap();
+    db.cast_vote(&game_id, "bob", "d2d4").unwrap();
+    db.cast_vote(&game_id, "bob", "e2e4").unwrap();
+
+    assert!(voting.closed(now).unwrap().is_empty());
+    let closed = voting.closed(now + 61).unwrap();
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].winner.san, "e4");
+    assert_eq!(closed[0].winner.votes, 2);
+    assert_eq!(closed[0].total_votes, 2);
+    assert_eq!(closed[0].opponent_peer_id, "opponent_peer");
+}
+
+#[test]
+fn test_no_vote_while_waiting_on_the_opponent() {
+    let (_temp_dir, db, game_id) = consultation_game(60);
+    db.add_voter(&game_id, "alice").unwrap();
+    MoveProcessor::new(&db)
+        .process_move(&game_id, "e2e4", true)
+        .unwrap();
+    db.cast_vote(&game_id, "alice", "d4").unwrap();
+
+    let now = Database::current_timestamp();
+    assert!(voting(&db).closed(now + 3600).unwrap().is_empty());
+}
@@ -0,0 +1,132 @@
+//! Batch Verification Tests
+//!
+//! Tests for verifying many signatures at once in `src/crypto/batch.rs` and
+//! `SignedEnvelope::verify_all`, and for the connection checking a backlog
+//! of received envelopes together
+
+use mate::crypto::batch::{verify_on_workers, PARALLEL_VERIFY_THRESHOLD};
+use mate::crypto::Identity;
+use mate::messages::wire::{FramedMessage, WireConfig};
+use mate::messages::{Message, SignedEnvelope};
+use mate::network::connection::READ_AHEAD_BYTES;
+use mate::network::{Connection, ConnectionError};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+
+fn signed_envelopes(count: usize) -> Vec<SignedEnvelope> {
+    let identity = Identity::generate().unwrap();
+    (0..count)
+        .map(|i| {
+            let message = Message::new_ping(i as u64, format!("message {i}"));
+            SignedEnvelope::create(&message, &identity, None).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_verify_on_workers_keeps_results_in_order() {
+    let items: Vec<usize> = (0..1000).collect();
+    for workers in [1, 3, 8] {
+        let results = verify_on_workers(workers, &items, |i| i * 2);
+        assert_eq!(results, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    let small: Vec<usize> = (0..PARALLEL_VERIFY_THRESHOLD - 1).collect();
+    assert_eq!(verify_on_workers(4, &small, |i| *i), small);
+    assert!(verify_on_workers(4, &[] as &[usize], |i| *i).is_empty());
+}
+
+#[test]
+fn test_verify_on_workers_finds_bad_signatures() {
+    let mut envelopes = signed_envelopes(200);
+    for index in [0, 99, 199] {
+        envelopes[index].message[0] ^= 0xff;
+    }
+
+    let results = verify_on_workers(4, &envelopes, SignedEnvelope::verify_signature);
+    let invalid: Vec<usize> = (0..results.len()).filter(|&i| !results[i]).collect();
+    assert_eq!(invalid, vec![0, 99, 199]);
+    assert_eq!(SignedEnvelope::verify_all(&envelopes), results);
+}
+
+#[tokio::test]
+async fn test_verify_batch_runs_off_the_runtime() {
+    let mut envelopes = signed_envelopes(100);
+    envelopes[42].signature[0] ^= 0xff;
+
+    let verified = SignedEnvelope::verify_batch(envelopes).await.unwrap();
+    assert_eq!(verified.len(), 100);
+    for (index, (envelope, valid)) in verified.iter().enumerate() {
+        assert_eq!(*valid, index != 42);
+        assert_eq!(envelope.get_message().unwrap().get_nonce(), index as u64);
+    }
+}
+
+#[tokio::test]
+async fn test_connection_verifies_a_received_backlog_in_order() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut sender = TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut connection = Connection::new(stream, Arc::new(Identity::generate().unwrap())).await;
+
+    // The whole backlog is waiting before the first message is received
+    let mut envelopes = signed_envelopes(100);
+    envelopes[70].signature[0] ^= 0xff;
+    let framed = FramedMessage::new(WireConfig::for_network());
+    for envelope in &envelopes {
+        framed.write_message(&mut sender, envelope).await.unwrap();
+    }
+
+    for nonce in 0..70 {
+        let (message, _) = connection.receive_message().await.unwrap();
+        assert_eq!(message.get_nonce(), nonce);
+    }
+    assert!(matches!(
+        connection.receive_message().await,
+        Err(ConnectionError::InvalidSignature)
+    ));
+    let (message, _) = connection.receive_message().await.unwrap();
+    assert_eq!(message.get_nonce(), 71);
+}
+
+#[tokio::test]
+async fn test_connection_reads_ahead_a_bounded_number_of_bytes() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut sender = TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut connection = Connection::new(stream, Arc::new(Identity::generate().unwrap())).await;
+
+    // Frames of about 300 KiB, far more of them than fit in the budget
+    let identity = Identity::generate().unwrap();
+    let envelopes: Vec<SignedEnvelope> = (0..16)
+        .map(|i| {
+            let message = Message::new_ping(i, "x".repeat(300 * 1024));
+            SignedEnvelope::create(&message, &identity, None).unwrap()
+        })
+        .collect();
+    let writer = tokio::spawn(async move {
+        let framed = FramedMessage::new(WireConfig::for_network());
+        for envelope in &envelopes {
+            framed.write_message(&mut sender, envelope).await.unwrap();
+        }
+        sender
+    });
+    // Let the frames pile up before the first is received
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    for nonce in 0..16 {
+        let (message, _) = connection.receive_message().await.unwrap();
+        assert_eq!(message.get_nonce(), nonce);
+        assert!(
+            connection.read_ahead_bytes() < READ_AHEAD_BYTES,
+            "{} bytes read ahead",
+            connection.read_ahead_bytes()
+        );
+    }
+    writer.await.unwrap();
+}
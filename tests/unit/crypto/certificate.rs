@@ -0,0 +1,230 @@
+use mate::crypto::certificate::{store_result_signature, stored_result_signatures};
+use mate::crypto::{ArbiterRuling, GameCertificate, Identity, ResultSignature, ScoreSheet};
+use mate::messages::chess::Move;
+use mate::storage::models::GameResult;
+use mate::storage::{Database, PlayerColor};
+use tempfile::TempDir;
+
+/// Fool's mate, won by black
+fn score_sheet(white: &Identity, black: &Identity) -> ScoreSheet {
+    ScoreSheet {
+        game_id: "game-1".to_string(),
+        white: white.peer_id().to_string(),
+        black: black.peer_id().to_string(),
+        result: "0-1".to_string(),
+        moves: ["f2f3", "e7e5", "g2g4", "d8h4"]
+            .iter()
+            .map(|m| m.to_string())
+            .collect(),
+        arbiter: None,
+    }
+}
+
+#[test]
+fn test_certificate_signed_by_both_players_verifies() {
+    let white = Identity::generate().unwrap();
+    let black = Identity::generate().unwrap();
+    let sheet = score_sheet(&white, &black);
+
+    let signatures = [
+        ResultSignature::sign(&white, &sheet).unwrap(),
+        ResultSignature::sign(&black, &sheet).unwrap(),
+    ];
+    let certificate = GameCertificate::new(&sheet, &signatures).unwrap();
+    certificate.verify().unwrap();
+    assert_eq!(certificate.move_count(), 4);
+    assert!(certificate.pgn.contains("[Result \"0-1\"]"));
+    assert!(certificate.pgn.ends_with("1. f2f3 e7e5 2. g2g4 d8h4 0-1\n"));
+
+    let json = serde_json::to_string_pretty(&certificate).unwrap();
+    let parsed: GameCertificate = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, certificate);
+    parsed.verify().unwrap();
+}
+
+#[test]
+fn test_tampered_certificate_is_rejected() {
+    let white = Identity::generate().unwrap();
+    let black = Identity::generate().unwrap();
+    let sheet = score_sheet(&white, &black);
+    let signatures = [
+        ResultSignature::sign(&white, &sheet).unwrap(),
+        ResultSignature::sign(&black, &sheet).unwrap(),
+    ];
+    let certificate = GameCertificate::new(&sheet, &signatures).unwrap();
+
+    let mut flipped = certificate.clone();
+    flipped.result = "1-0".to_string();
+    assert!(flipped.verify().is_err());
+
+    let mut edited = certificate.clone();
+    edited.pgn = edited.pgn.replace("d8h4", "d8h5");
+    assert!(edited.verify().is_err());
+
+    let mut swapped = certificate.clone();
+    std::mem::swap(&mut swapped.white, &mut swapped.black);
+    assert!(swapped.verify().is_err());
+
+    // A third party cannot stand in for one of the players
+    let impostor = Identity::generate().unwrap();
+    let mut replaced = certificate.clone();
+    replaced.black = impostor.peer_id().to_string();
+    assert!(replaced.verify().is_err());
+}
+
+#[test]
+fn test_players_must_agree_on_the_game() {
+    let white = Identity::generate().unwrap();
+    let black = Identity::generate().unwrap();
+    let sheet = score_sheet(&white, &black);
+
+    // Black's copy is missing the last move
+    let mut black_copy = sheet.clone();
+    black_copy.moves.pop();
+    let black_signature = ResultSignature::sign(&black, &black_copy).unwrap();
+    assert!(black_signature.verify(&sheet).is_err());
+
+    let signatures = [
+        ResultSignature::sign(&white, &sheet).unwrap(),
+        black_signature,
+    ];
+    assert!(GameCertificate::new(&sheet, &signatures).is_err());
+}
+
+#[test]
+fn test_only_players_sign_finished_games() {
+    let white = Identity::generate().unwrap();
+    let black = Identity::generate().unwrap();
+    let outsider = Identity::generate().unwrap();
+    let sheet = score_sheet(&white, &black);
+    assert!(ResultSignature::sign(&outsider, &sheet).is_err());
+
+    let mut unfinished = sheet;
+    unfinished.result = "*".to_string();
+    assert!(!unfinished.is_finished());
+    assert!(ResultSignature::sign(&white, &unfinished).is_err());
+}
+
+#[test]
+fn test_arbiter_cosigns_certificate() {
+    let white = Identity::generate().unwrap();
+    let black = Identity::generate().unwrap();
+    let arbiter = Identity::generate().unwrap();
+    let sheet = ScoreSheet {
+        arbiter: Some(arbiter.peer_id().to_string()),
+        ..score_sheet(&white, &black)
+    };
+    assert!(sheet
+        .pgn()
+        .contains(&format!("[Arbiter \"{}\"]", arbiter.peer_id())));
+
+    let players = [
+        ResultSignature::sign(&white, &sheet).unwrap(),
+        ResultSignature::sign(&black, &sheet).unwrap(),
+    ];
+    // The arbiter's signature is required once the game has an arbiter
+    assert!(GameCertificate::new(&sheet, &players).is_err());
+
+    let mut signatures = players.to_vec();
+    signatures.push(ResultSignature::sign(&arbiter, &sheet).unwrap());
+    let certificate = GameCertificate::new(&sheet, &signatures).unwrap();
+    certificate.verify().unwrap();
+    assert_eq!(
+        certificate.arbiter.as_deref(),
+        Some(arbiter.peer_id().as_str())
+    );
+
+    let json = serde_json::to_string(&certificate).unwrap();
+    let parsed: GameCertificate = serde_json::from_str(&json).unwrap();
+    parsed.verify().unwrap();
+
+    let mut unsigned = certificate.clone();
+    unsigned.arbiter_signature = None;
+    assert!(unsigned.verify().is_err());
+
+    // Dropping the arbiter changes the signed PGN
+    let mut dropped = certificate;
+    dropped.arbiter = None;
+    dropped.arbiter_signature = None;
+    assert!(dropped.verify().is_err());
+
+    // A player's signature over the sheet without the arbiter does not count
+    let plain = score_sheet(&white, &black);
+    assert!(ResultSignature::sign(&white, &plain)
+        .unwrap()
+        .verify(&sheet)
+        .is_err());
+}
+
+#[test]
+fn test_arbiter_rules_on_illegal_move_claims() {
+    let arbiter = Identity::generate().unwrap();
+    let claimant = Identity::generate().unwrap().peer_id().to_string();
+    let moves: Vec<String> = ["e2e4", "e7e5", "e1e3"]
+        .iter()
+        .map(|m| m.to_string())
+        .collect();
+
+    let upheld = ArbiterRuling::rule(&arbiter, "game-1", &claimant, &moves, 3).unwrap();
+    assert!(upheld.upheld);
+    assert_eq!(upheld.verdict(), "Upheld");
+    assert_eq!(upheld.chess_move.as_deref(), Some("e1e3"));
+    upheld.verify().unwrap();
+
+    let rejected = ArbiterRuling::rule(&arbiter, "game-1", &claimant, &moves, 2).unwrap();
+    assert!(!rejected.upheld);
+    rejected.verify().unwrap();
+
+    // A move the game has not reached cannot be ruled illegal
+    let unplayed = ArbiterRuling::rule(&arbiter, "game-1", &claimant, &moves, 4).unwrap();
+    assert!(!unplayed.upheld);
+    assert_eq!(unplayed.chess_move, None);
+    assert!(ArbiterRuling::rule(&arbiter, "game-1", &claimant, &moves, 0).is_err());
+
+    let mut forged = upheld;
+    forged.upheld = false;
+    assert!(forged.verify().is_err());
+}
+
+#[test]
+fn test_score_sheet_from_stored_game() {
+    let temp_dir = TempDir::new().unwrap();
+    let identity = Identity::generate().unwrap();
+    let opponent = Identity::generate().unwrap();
+    let db = Database::new_with_path(
+        identity.peer_id().as_str(),
+        &temp_dir.path().join("db.sqlite"),
+    )
+    .unwrap();
+
+    let game = db
+        .create_game(opponent.peer_id().to_string(), PlayerColor::Black, None)
+        .unwrap();
+    for chess_move in ["e2e4", "e7e5"] {
+        let message = Move::new(game.id.clone(), chess_move.to_string(), "0".repeat(64));
+        db.store_message(
+            game.id.clone(),
+            "move".to_string(),
+            serde_json::to_string(&message).unwrap(),
+            String::new(),
+            identity.peer_id().to_string(),
+        )
+        .unwrap();
+    }
+    db.update_game_result(&game.id, GameResult::Loss).unwrap();
+
+    let game = db.get_game(&game.id).unwrap();
+    let messages = db.get_messages_for_game(&game.id).unwrap();
+    let sheet = ScoreSheet::from_game(&game, identity.peer_id().as_str(), &messages);
+    assert_eq!(sheet.white, opponent.peer_id().as_str());
+    assert_eq!(sheet.black, identity.peer_id().as_str());
+    assert_eq!(sheet.result, "1-0");
+    assert_eq!(sheet.moves, ["e2e4", "e7e5"]);
+
+    // Storing the same signature twice keeps one copy
+    let signature = ResultSignature::sign(&identity, &sheet).unwrap();
+    store_result_signature(&db, &signature).unwrap();
+    store_result_signature(&db, &signature).unwrap();
+    let messages = db.get_messages_for_game(&game.id).unwrap();
+    assert_eq!(stored_result_signatures(&messages), [signature]);
+}
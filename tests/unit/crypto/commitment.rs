@@ -0,0 +1,52 @@
+use mate::crypto::{Identity, SealedMove};
+
+#[test]
+fn test_revealed_move_matches_its_commitment() {
+    let sealed = SealedMove::new("game-1", 41, "g1f3");
+    let commitment = sealed.commitment();
+
+    assert_eq!(commitment.len(), 64);
+    sealed.verify(&commitment).unwrap();
+    sealed.verify(&commitment.to_uppercase()).unwrap();
+}
+
+#[test]
+fn test_commitment_binds_move_number_and_nonce() {
+    let sealed = SealedMove::new("game-1", 41, "g1f3");
+    let commitment = sealed.commitment();
+
+    for changed in [
+        SealedMove {
+            chess_move: "g1h3".to_string(),
+            ..sealed.clone()
+        },
+        SealedMove {
+            move_number: 42,
+            ..sealed.clone()
+        },
+        SealedMove {
+            game_id: "game-2".to_string(),
+            ..sealed.clone()
+        },
+        SealedMove::new("game-1", 41, "g1f3"),
+    ] {
+        assert!(changed.verify(&commitment).is_err(), "{changed:?}");
+    }
+}
+
+#[test]
+fn test_only_the_sealer_can_decrypt() {
+    let identity = Identity::generate().unwrap();
+    let sealed = SealedMove::new("game-1", 7, "e7e5");
+    let encrypted = sealed.encrypt(&identity).unwrap();
+
+    assert!(!encrypted.ciphertext.contains("e7e5"));
+    assert_eq!(
+        SealedMove::decrypt(&encrypted, &identity, "game-1").unwrap(),
+        sealed
+    );
+
+    let other = Identity::generate().unwrap();
+    assert!(SealedMove::decrypt(&encrypted, &other, "game-1").is_err());
+    assert!(SealedMove::decrypt(&encrypted, &identity, "game-2").is_err());
+}
@@ -0,0 +1,85 @@
+use mate::crypto::encryption::{EncryptedData, KDF_ARGON2ID, KDF_PBKDF2_SHA256};
+
+// Low iteration count keeps these tests fast; the KDF itself is not under test
+const TEST_ITERATIONS: u32 = 1_000;
+
+#[test]
+fn test_encrypt_decrypt_roundtrip() {
+    let plaintext = b"secret key material";
+    let encrypted =
+        EncryptedData::encrypt_with_iterations(plaintext, "correct horse", TEST_ITERATIONS)
+            .expect("Encryption should succeed");
+
+    assert_eq!(encrypted.kdf, KDF_PBKDF2_SHA256);
+    assert_eq!(encrypted.iterations, TEST_ITERATIONS);
+
+    let decrypted = encrypted
+        .decrypt("correct horse")
+        .expect("Decryption should succeed");
+    assert_eq!(decrypted, plaintext);
+}
+
+#[test]
+fn test_wrong_passphrase_fails() {
+    let encrypted = EncryptedData::encrypt_with_iterations(b"data", "right", TEST_ITERATIONS)
+        .expect("Encryption should succeed");
+
+    assert!(encrypted.decrypt("wrong").is_err());
+}
+
+#[test]
+fn test_tampered_ciphertext_fails() {
+    let mut encrypted = EncryptedData::encrypt_with_iterations(b"data", "pass", TEST_ITERATIONS)
+        .expect("Encryption should succeed");
+    let mut other = EncryptedData::encrypt_with_iterations(b"atad", "pass", TEST_ITERATIONS)
+        .expect("Encryption should succeed");
+
+    // Swap ciphertexts so the authentication tag no longer matches the nonce/salt
+    std::mem::swap(&mut encrypted.ciphertext, &mut other.ciphertext);
+    assert!(encrypted.decrypt("pass").is_err());
+}
+
+#[test]
+fn test_encryption_is_randomized() {
+    let first = EncryptedData::encrypt_with_iterations(b"same", "pass", TEST_ITERATIONS).unwrap();
+    let second = EncryptedData::encrypt_with_iterations(b"same", "pass", TEST_ITERATIONS).unwrap();
+
+    assert_ne!(first.salt, second.salt);
+    assert_ne!(first.ciphertext, second.ciphertext);
+}
+
+#[test]
+fn test_empty_passphrase_rejected() {
+    assert!(EncryptedData::encrypt_with_iterations(b"data", "", TEST_ITERATIONS).is_err());
+}
+
+#[test]
+fn test_argon2_roundtrip_records_costs() {
+    let encrypted = EncryptedData::encrypt_argon2_with_params(b"key bytes", "hunter2", 64, 1)
+        .expect("Encryption should succeed");
+
+    assert_eq!(encrypted.kdf, KDF_ARGON2ID);
+    assert_eq!(encrypted.iterations, 1);
+    assert_eq!(encrypted.memory_kib, Some(64));
+    assert_eq!(encrypted.decrypt("hunter2").unwrap(), b"key bytes");
+    assert!(encrypted.decrypt("hunter3").is_err());
+}
+
+#[test]
+fn test_pbkdf2_data_without_memory_cost_still_parses() {
+    let encrypted = EncryptedData::encrypt_with_iterations(b"data", "right", TEST_ITERATIONS)
+        .expect("Encryption should succeed");
+    let json = serde_json::to_string(&encrypted).unwrap();
+    assert!(!json.contains("memory_kib"));
+
+    let parsed: EncryptedData = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.decrypt("right").unwrap(), b"data");
+}
+
+#[test]
+fn test_unknown_kdf_rejected() {
+    let mut encrypted = EncryptedData::encrypt_with_iterations(b"data", "right", TEST_ITERATIONS)
+        .expect("Encryption should succeed");
+    encrypted.kdf = "rot13".to_string();
+    assert!(encrypted.decrypt("right").is_err());
+}
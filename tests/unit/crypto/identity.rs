@@ -1,6 +1,9 @@
 use ed25519_dalek::VerifyingKey;
 use mate::crypto::identity::{Identity, PeerId};
+use mate::crypto::storage::KeyBackend;
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tempfile::TempDir;
 
 /// Priority 1: Core Requirements Tests (6 tests)
 /// Direct fulfillment of issue-12.md requirements
@@ -79,8 +82,8 @@ fn test_message_signing_deterministic() {
     let message = b"test message";
 
     // Test basic signing functionality
-    let signature1 = identity.sign(message);
-    let signature2 = identity.sign(message);
+    let signature1 = identity.sign(message).unwrap();
+    let signature2 = identity.sign(message).unwrap();
 
     // Ed25519 signing should be deterministic (same message = same signature)
     assert_eq!(
@@ -91,7 +94,7 @@ fn test_message_signing_deterministic() {
 
     // Test signing different messages produces different signatures
     let different_message = b"different test message";
-    let signature3 = identity.sign(different_message);
+    let signature3 = identity.sign(different_message).unwrap();
     assert_ne!(
         signature1.to_bytes(),
         signature3.to_bytes(),
@@ -105,7 +108,7 @@ fn test_signature_verification_valid() {
     let message = b"test message for verification";
 
     // Test valid signature verification
-    let signature = identity.sign(message);
+    let signature = identity.sign(message).unwrap();
     let verifying_key = identity.verifying_key();
 
     let is_valid = Identity::verify(&verifying_key, message, &signature);
@@ -126,7 +129,7 @@ fn test_signature_verification_valid() {
 fn test_signature_verification_invalid_cases() {
     let identity = Identity::generate().expect("Failed to generate identity");
     let message = b"original message";
-    let signature = identity.sign(message);
+    let signature = identity.sign(message).unwrap();
     let verifying_key = identity.verifying_key();
 
     // Test with wrong key (different identity)
@@ -242,7 +245,7 @@ fn test_signing_edge_cases() {
 
     // Test empty message signing
     let empty_message = b"";
-    let empty_signature = identity.sign(empty_message);
+    let empty_signature = identity.sign(empty_message).unwrap();
     let verifying_key = identity.verifying_key();
     assert!(
         Identity::verify(&verifying_key, empty_message, &empty_signature),
@@ -251,7 +254,7 @@ fn test_signing_edge_cases() {
 
     // Test very large message signing (performance boundary)
     let large_message = vec![0u8; 1024 * 1024]; // 1MB message
-    let large_signature = identity.sign(&large_message);
+    let large_signature = identity.sign(&large_message).unwrap();
     assert!(
         Identity::verify(&verifying_key, &large_message, &large_signature),
         "Large message signature should verify successfully"
@@ -259,7 +262,7 @@ fn test_signing_edge_cases() {
 
     // Test message boundary conditions (single byte)
     let single_byte = &[42u8];
-    let single_signature = identity.sign(single_byte);
+    let single_signature = identity.sign(single_byte).unwrap();
     assert!(
         Identity::verify(&verifying_key, single_byte, &single_signature),
         "Single byte message signature should verify successfully"
@@ -273,7 +276,7 @@ fn test_verification_edge_cases() {
 
     // Test empty message verification
     let empty_message = b"";
-    let empty_signature = identity.sign(empty_message);
+    let empty_signature = identity.sign(empty_message).unwrap();
     assert!(
         Identity::verify(&verifying_key, empty_message, &empty_signature),
         "Empty message verification should succeed"
@@ -300,7 +303,7 @@ fn test_verification_edge_cases() {
     // Note: VerifyingKey::from_bytes may still succeed with some corrupted data
     // but verification should fail due to key mismatch
     if let Ok(corrupted_key) = VerifyingKey::from_bytes(&corrupted_key_bytes) {
-        let test_signature = identity.sign(b"test");
+        let test_signature = identity.sign(b"test").unwrap();
         assert!(
             !Identity::verify(&corrupted_key, b"test", &test_signature),
             "Corrupted key should fail signature verification"
@@ -384,7 +387,7 @@ fn test_crypto_memory_safety() {
     // Test that key data doesn't persist in memory after drop
     let signature = {
         let identity = Identity::generate().expect("Failed to generate identity");
-        let sig = identity.sign(message);
+        let sig = identity.sign(message).unwrap();
         let verifying_key = identity.verifying_key();
 
         // Verify signature works while identity is in scope
@@ -416,3 +419,297 @@ fn test_crypto_memory_safety() {
         "Reconstructed key should match original after move"
     );
 }
+
+#[test]
+fn test_peer_id_fingerprint_is_short_stable_hex() {
+    let identity1 = Identity::generate().expect("Failed to generate identity1");
+    let identity2 = Identity::generate().expect("Failed to generate identity2");
+
+    let fingerprint = identity1.peer_id().fingerprint();
+    assert_eq!(fingerprint.len(), 16);
+    assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(
+        fingerprint,
+        PeerId::from_string(identity1.peer_id().to_string()).fingerprint()
+    );
+    assert_ne!(fingerprint, identity2.peer_id().fingerprint());
+}
+
+#[test]
+fn test_protected_identity_roundtrip() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("identity.key");
+    let identity = Identity::generate().unwrap();
+
+    identity
+        .save_protected_to_storage_path(&path, "open sesame")
+        .unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(!content.contains("\"secret_key\""));
+    assert!(Identity::is_protected_at(&path).unwrap());
+    assert_eq!(
+        &Identity::stored_peer_id(&path).unwrap(),
+        identity.peer_id()
+    );
+
+    let unlocked = Identity::from_storage_path_with_passphrase(&path, "open sesame").unwrap();
+    assert_eq!(unlocked.peer_id(), identity.peer_id());
+    assert!(Identity::from_storage_path_with_passphrase(&path, "wrong").is_err());
+
+    // The passphrase used to protect the key is cached for later loads
+    let cached = Identity::from_storage_path(&path).unwrap();
+    assert_eq!(cached.peer_id(), identity.peer_id());
+}
+
+#[test]
+fn test_unprotecting_identity_writes_plain_key() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("identity.key");
+    let identity = Identity::generate().unwrap();
+    identity
+        .save_protected_to_storage_path(&path, "pw")
+        .unwrap();
+
+    identity.save_to_storage_path(&path).unwrap();
+
+    assert!(!Identity::is_protected_at(&path).unwrap());
+    let loaded = Identity::from_storage_path(&path).unwrap();
+    assert_eq!(loaded.peer_id(), identity.peer_id());
+}
+
+#[test]
+fn test_unreadable_identity_is_not_replaced() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("identity.key");
+    let identity = Identity::generate().unwrap();
+    identity.save_to_storage_path(&path).unwrap();
+    let original = std::fs::read(&path).unwrap();
+    let mut corrupted = original.clone();
+    corrupted.truncate(original.len() / 2);
+    std::fs::write(&path, &corrupted).unwrap();
+
+    assert!(Identity::load_or_generate_from_data_dir(dir.path()).is_err());
+    assert_eq!(std::fs::read(&path).unwrap(), corrupted);
+}
+
+#[test]
+fn test_keychain_stub_exposes_peer_id_without_the_keychain() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("identity.key");
+    let identity = Identity::generate().unwrap();
+    let stub = serde_json::json!({
+        "public_key": identity.peer_id().as_str(),
+        "keychain_account": path.display().to_string(),
+    });
+    std::fs::write(&path, stub.to_string()).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+    }
+
+    assert_eq!(Identity::backend_at(&path).unwrap(), KeyBackend::Keychain);
+    assert!(!Identity::is_protected_at(&path).unwrap());
+    assert_eq!(
+        &Identity::stored_peer_id(&path).unwrap(),
+        identity.peer_id()
+    );
+}
+
+#[test]
+fn test_file_backend_keeps_existing_key_file() {
+    let dir = TempDir::new().unwrap();
+    let identity = Identity::load_or_generate_in(dir.path(), KeyBackend::File).unwrap();
+    let path = dir.path().join("identity.key");
+    assert_eq!(Identity::backend_at(&path).unwrap(), KeyBackend::File);
+
+    let reloaded = Identity::load_or_generate_in(dir.path(), KeyBackend::File).unwrap();
+    assert_eq!(reloaded.peer_id(), identity.peer_id());
+}
+
+#[test]
+fn test_replacing_identity_archives_old_key() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("identity.key");
+    let old = Identity::generate().unwrap();
+    old.save_to_storage_path(&path).unwrap();
+    let new = Identity::generate().unwrap();
+
+    let archive = old.replace_at(&path, &new, KeyBackend::File).unwrap();
+
+    assert_eq!(
+        archive,
+        dir.path()
+            .join(format!("identity.key.{}.old", old.peer_id().fingerprint()))
+    );
+    assert_eq!(
+        Identity::from_storage_path(&archive).unwrap().peer_id(),
+        old.peer_id()
+    );
+    assert_eq!(
+        Identity::from_storage_path(&path).unwrap().peer_id(),
+        new.peer_id()
+    );
+}
+
+#[test]
+fn test_replacing_protected_identity_keeps_passphrase() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("identity.key");
+    let old = Identity::generate().unwrap();
+    old.save_protected_to_storage_path(&path, "rotate me")
+        .unwrap();
+    let new = Identity::generate().unwrap();
+
+    let archive = old.replace_at(&path, &new, KeyBackend::File).unwrap();
+
+    assert!(Identity::is_protected_at(&archive).unwrap());
+    assert!(Identity::is_protected_at(&path).unwrap());
+    let unlocked = Identity::from_storage_path_with_passphrase(&path, "rotate me").unwrap();
+    assert_eq!(unlocked.peer_id(), new.peer_id());
+}
+
+#[test]
+fn test_mnemonic_round_trip_restores_peer_id() {
+    let identity = Identity::generate().unwrap();
+    let phrase = identity.to_mnemonic().unwrap();
+
+    assert_eq!(phrase.split(' ').count(), 24);
+    let recovered = Identity::from_mnemonic(&phrase).unwrap();
+    assert_eq!(recovered.peer_id(), identity.peer_id());
+
+    // Case and spacing do not matter
+    let messy = format!("  {}\n", phrase.to_uppercase().replace(' ', "   "));
+    let recovered = Identity::from_mnemonic(&messy).unwrap();
+    assert_eq!(recovered.peer_id(), identity.peer_id());
+}
+
+#[test]
+fn test_mnemonic_rejects_mistyped_and_short_phrases() {
+    let phrase = Identity::from_secret_bytes(&[7u8; 32])
+        .unwrap()
+        .to_mnemonic()
+        .unwrap();
+    let mut words: Vec<&str> = phrase.split(' ').collect();
+
+    // Swapping two words breaks the checksum
+    assert_ne!(words[0], words[1]);
+    words.swap(0, 1);
+    assert!(Identity::from_mnemonic(&words.join(" ")).is_err());
+
+    assert!(Identity::from_mnemonic("notaword ".repeat(24).trim()).is_err());
+    // A valid 12-word phrase is too short for an Ed25519 seed
+    let twelve = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    assert!(Identity::from_mnemonic(twelve).is_err());
+}
+
+#[test]
+fn test_identity_names_map_to_separate_key_files() {
+    use mate::crypto::storage::{key_path_in, validate_identity_name, DEFAULT_IDENTITY};
+
+    let dir = TempDir::new().unwrap();
+    assert_eq!(
+        key_path_in(dir.path(), DEFAULT_IDENTITY).unwrap(),
+        dir.path().join("identity.key")
+    );
+    assert_eq!(
+        key_path_in(dir.path(), "tournament").unwrap(),
+        dir.path().join("identities").join("tournament.key")
+    );
+
+    for valid in ["casual", "club-2024", "a_b"] {
+        assert!(validate_identity_name(valid).is_ok(), "{valid}");
+    }
+    for invalid in ["", "../escape", "with space", "dot.key", &"x".repeat(33)] {
+        assert!(validate_identity_name(invalid).is_err(), "{invalid}");
+        assert!(key_path_in(dir.path(), invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_list_identities_puts_default_first() {
+    use mate::crypto::storage::{key_path_in, list_identities};
+
+    let dir = TempDir::new().unwrap();
+    assert!(list_identities(dir.path()).is_empty());
+
+    for name in ["tournament", "default", "casual"] {
+        let path = key_path_in(dir.path(), name).unwrap();
+        Identity::generate()
+            .unwrap()
+            .save_to_storage_path(&path)
+            .unwrap();
+    }
+    // Archived keys and stray files are not identities
+    std::fs::write(dir.path().join("identities").join("notes.txt"), "").unwrap();
+
+    assert_eq!(
+        list_identities(dir.path()),
+        vec!["default", "casual", "tournament"]
+    );
+
+    let casual = Identity::stored_peer_id(&key_path_in(dir.path(), "casual").unwrap()).unwrap();
+    let tournament =
+        Identity::stored_peer_id(&key_path_in(dir.path(), "tournament").unwrap()).unwrap();
+    assert_ne!(casual, tournament);
+}
+
+/// Stands in for a hardware token: signs with a key the identity never sees
+struct DeviceSigner(ed25519_dalek::SigningKey);
+
+impl mate::crypto::Signer for DeviceSigner {
+    fn verifying_key(&self) -> VerifyingKey {
+        self.0.verifying_key()
+    }
+
+    fn sign(&self, message: &[u8]) -> anyhow::Result<ed25519_dalek::Signature> {
+        Ok(ed25519_dalek::Signer::sign(&self.0, message))
+    }
+}
+
+#[test]
+fn test_device_backed_identity_signs_but_never_exports_its_key() {
+    let signing_key = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+    let identity = Identity::from_signer(std::sync::Arc::new(DeviceSigner(signing_key)));
+
+    assert!(identity.is_hardware_backed());
+    assert_eq!(
+        identity.peer_id(),
+        Identity::from_secret_bytes(&[9u8; 32]).unwrap().peer_id()
+    );
+    let signature = identity.sign(b"move").unwrap();
+    assert!(Identity::verify(
+        &identity.verifying_key(),
+        b"move",
+        &signature
+    ));
+
+    assert!(identity.to_secret_bytes().is_err());
+    assert!(identity.to_mnemonic().is_err());
+    let dir = TempDir::new().unwrap();
+    assert!(identity
+        .save_to_storage_path(&dir.path().join("identity.key"))
+        .is_err());
+}
+
+#[test]
+fn test_token_reference_keeps_only_the_public_key() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("identity.key");
+    let identity = Identity::generate().unwrap();
+    identity
+        .save_token_reference(&path, Path::new("/usr/lib/libykcs11.so"), "mate")
+        .unwrap();
+
+    assert!(Identity::is_hardware_at(&path).unwrap());
+    assert!(!Identity::is_protected_at(&path).unwrap());
+    assert_eq!(
+        &Identity::stored_peer_id(&path).unwrap(),
+        identity.peer_id()
+    );
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(!content.contains("secret_key"));
+    // Without a token to talk to, the key cannot be loaded
+    assert!(Identity::from_storage_path(&path).is_err());
+}
@@ -4,4 +4,12 @@
 //! for crypto functionality that complements the comprehensive
 //! integration test coverage in the messaging layer.
 
+pub mod batch;
+pub mod certificate;
+pub mod commitment;
+pub mod encryption;
 pub mod identity;
+pub mod revocation;
+pub mod rotation;
+pub mod sas;
+pub mod storage;
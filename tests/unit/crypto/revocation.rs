@@ -0,0 +1,89 @@
+use mate::crypto::revocation::MAX_REVOCATION_REASON_LEN;
+use mate::crypto::{Identity, RevocationRecord};
+use mate::messages::types::Message;
+
+#[test]
+fn test_revocation_record_verifies() {
+    let identity = Identity::generate().unwrap();
+    let successor = Identity::generate().unwrap();
+
+    let record = RevocationRecord::issue(
+        &identity,
+        1_700_000_000,
+        "  laptop stolen ",
+        Some(successor.peer_id()),
+    )
+    .unwrap();
+
+    assert_eq!(record.peer_id, identity.peer_id().as_str());
+    assert_eq!(record.reason, "laptop stolen");
+    assert_eq!(
+        record.successor.as_deref(),
+        Some(successor.peer_id().as_str())
+    );
+    record.verify().unwrap();
+
+    let json = serde_json::to_string(&record).unwrap();
+    let parsed: RevocationRecord = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, record);
+    parsed.verify().unwrap();
+}
+
+#[test]
+fn test_tampered_revocation_record_is_rejected() {
+    let identity = Identity::generate().unwrap();
+    let attacker = Identity::generate().unwrap();
+    let record = RevocationRecord::issue(&identity, 1_700_000_000, "lost", None).unwrap();
+
+    let mut redirected = record.clone();
+    redirected.successor = Some(attacker.peer_id().to_string());
+    assert!(redirected.verify().is_err());
+
+    let mut reworded = record.clone();
+    reworded.reason = "testing".to_string();
+    assert!(reworded.verify().is_err());
+
+    // Only the key itself can revoke it
+    let mut forged = RevocationRecord::issue(&attacker, 1_700_000_000, "lost", None).unwrap();
+    forged.peer_id = identity.peer_id().to_string();
+    assert!(forged.verify().is_err());
+
+    let mut garbled = record;
+    garbled.signature = "not base64!".to_string();
+    assert!(garbled.verify().is_err());
+}
+
+#[test]
+fn test_revocation_rejects_bad_fields() {
+    let identity = Identity::generate().unwrap();
+
+    assert!(
+        RevocationRecord::issue(&identity, 1_700_000_000, "", Some(identity.peer_id())).is_err()
+    );
+    let long_reason = "x".repeat(MAX_REVOCATION_REASON_LEN + 1);
+    assert!(RevocationRecord::issue(&identity, 1_700_000_000, &long_reason, None).is_err());
+
+    let mut bad_successor = RevocationRecord::issue(&identity, 1_700_000_000, "", None).unwrap();
+    bad_successor.successor = Some("not-a-key".to_string());
+    assert!(bad_successor.verify().is_err());
+}
+
+#[test]
+fn test_key_revocation_message_validates_record() {
+    let identity = Identity::generate().unwrap();
+    let record = RevocationRecord::issue(&identity, 1_700_000_000, "", None).unwrap();
+
+    let message = Message::new_key_revocation(record.clone());
+    assert_eq!(message.message_type(), "KeyRevocation");
+    assert_eq!(message.get_game_id(), None);
+    assert!(message.validate().is_ok());
+    let bytes = message.serialize().unwrap();
+    match Message::deserialize(&bytes).unwrap() {
+        Message::KeyRevocation(decoded) => assert_eq!(decoded, record),
+        other => panic!("expected a KeyRevocation, got {}", other.message_type()),
+    }
+
+    let mut tampered = record;
+    tampered.issued_at += 60;
+    assert!(Message::new_key_revocation(tampered).validate().is_err());
+}
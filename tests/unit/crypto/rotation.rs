@@ -0,0 +1,68 @@
+use mate::crypto::{Identity, RotationCertificate};
+use mate::messages::types::Message;
+
+#[test]
+fn test_rotation_certificate_verifies() {
+    let old = Identity::generate().unwrap();
+    let new = Identity::generate().unwrap();
+
+    let certificate = RotationCertificate::issue(&old, &new, 1_700_000_000).unwrap();
+
+    assert_eq!(certificate.old_peer_id, old.peer_id().as_str());
+    assert_eq!(certificate.new_peer_id, new.peer_id().as_str());
+    certificate.verify().unwrap();
+
+    let json = serde_json::to_string(&certificate).unwrap();
+    let parsed: RotationCertificate = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, certificate);
+    parsed.verify().unwrap();
+}
+
+#[test]
+fn test_tampered_rotation_certificate_is_rejected() {
+    let old = Identity::generate().unwrap();
+    let new = Identity::generate().unwrap();
+    let attacker = Identity::generate().unwrap();
+    let certificate = RotationCertificate::issue(&old, &new, 1_700_000_000).unwrap();
+
+    let mut redirected = certificate.clone();
+    redirected.new_peer_id = attacker.peer_id().to_string();
+    assert!(redirected.verify().is_err());
+
+    let mut backdated = certificate.clone();
+    backdated.issued_at -= 1;
+    assert!(backdated.verify().is_err());
+
+    // Someone holding only the new key cannot claim an old identity
+    let forged = RotationCertificate::issue(&attacker, &new, 1_700_000_000).unwrap();
+    let mut stolen = forged.clone();
+    stolen.old_peer_id = old.peer_id().to_string();
+    assert!(stolen.verify().is_err());
+
+    let mut garbled = certificate;
+    garbled.old_key_signature = "not base64!".to_string();
+    assert!(garbled.verify().is_err());
+}
+
+#[test]
+fn test_rotation_to_same_key_is_rejected() {
+    let identity = Identity::generate().unwrap();
+    let certificate = RotationCertificate::issue(&identity, &identity, 1_700_000_000).unwrap();
+    assert!(certificate.verify().is_err());
+}
+
+#[test]
+fn test_key_rotation_message_validates_certificate() {
+    let old = Identity::generate().unwrap();
+    let new = Identity::generate().unwrap();
+    let certificate = RotationCertificate::issue(&old, &new, 1_700_000_000).unwrap();
+
+    let message = Message::new_key_rotation(certificate.clone());
+    assert_eq!(message.message_type(), "KeyRotation");
+    assert_eq!(message.get_game_id(), None);
+    assert!(message.validate().is_ok());
+
+    let mut tampered = certificate;
+    tampered.issued_at += 60;
+    assert!(Message::new_key_rotation(tampered).validate().is_err());
+}
@@ -0,0 +1,56 @@
+use mate::crypto::sas::{OFFLINE_SESSION, SAS_LENGTH, SAS_SYMBOLS};
+use mate::crypto::{Identity, VerificationCode};
+use std::collections::HashSet;
+
+#[test]
+fn test_verification_code_is_the_same_on_both_sides() {
+    let alice = Identity::generate().unwrap();
+    let bob = Identity::generate().unwrap();
+
+    let seen_by_alice =
+        VerificationCode::derive(alice.peer_id().as_str(), bob.peer_id().as_str(), 42);
+    let seen_by_bob =
+        VerificationCode::derive(bob.peer_id().as_str(), alice.peer_id().as_str(), 42);
+
+    assert_eq!(seen_by_alice, seen_by_bob);
+    assert_eq!(seen_by_alice.symbols().count(), SAS_LENGTH);
+}
+
+#[test]
+fn test_verification_code_depends_on_keys_and_session() {
+    let alice = Identity::generate().unwrap();
+    let bob = Identity::generate().unwrap();
+    let mallory = Identity::generate().unwrap();
+    let (a, b, m) = (
+        alice.peer_id().as_str(),
+        bob.peer_id().as_str(),
+        mallory.peer_id().as_str(),
+    );
+
+    let code = VerificationCode::derive(a, b, OFFLINE_SESSION);
+    assert_ne!(code, VerificationCode::derive(a, m, OFFLINE_SESSION));
+    assert_ne!(code, VerificationCode::derive(a, b, 7));
+}
+
+#[test]
+fn test_verification_code_rendering() {
+    let code = VerificationCode::derive("peer-a", "peer-b", 1);
+
+    let plain = code.render(false);
+    assert!(plain.is_ascii());
+    assert_eq!(plain.split(", ").count(), SAS_LENGTH);
+
+    let fancy = code.render(true);
+    for symbol in code.symbols() {
+        assert!(fancy.contains(symbol.emoji));
+        assert!(fancy.contains(symbol.name));
+    }
+}
+
+#[test]
+fn test_sas_symbols_are_distinct() {
+    let names: HashSet<_> = SAS_SYMBOLS.iter().map(|s| s.name).collect();
+    let emoji: HashSet<_> = SAS_SYMBOLS.iter().map(|s| s.emoji).collect();
+    assert_eq!(names.len(), SAS_SYMBOLS.len());
+    assert_eq!(emoji.len(), SAS_SYMBOLS.len());
+}
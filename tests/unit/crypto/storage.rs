@@ -0,0 +1,90 @@
+use mate::crypto::storage::{
+    check_key_permissions, is_private_mode, load_key_secure, save_key_secure, KeyPermissionPolicy,
+    StorageError,
+};
+use std::path::Path;
+use tempfile::TempDir;
+
+// The permission rules are plain bit checks, so they are tested on every
+// platform even though only Unix reports these modes
+
+#[test]
+fn test_owner_only_modes_are_private() {
+    for mode in [0o600, 0o400, 0o700, 0o100600] {
+        assert!(is_private_mode(mode & 0o777), "{mode:o}");
+        assert!(
+            check_key_permissions(Path::new("identity.key"), mode, KeyPermissionPolicy::Strict)
+                .is_ok(),
+            "{mode:o}"
+        );
+    }
+}
+
+#[test]
+fn test_group_or_world_access_is_refused_when_strict() {
+    for mode in [0o644, 0o640, 0o604, 0o660, 0o606, 0o610, 0o777] {
+        assert!(!is_private_mode(mode), "{mode:o}");
+        let error = check_key_permissions(
+            Path::new("/data/identity.key"),
+            mode,
+            KeyPermissionPolicy::Strict,
+        )
+        .unwrap_err();
+        match &error {
+            StorageError::InsecurePermissions { mode: found, .. } => assert_eq!(*found, mode),
+            other => panic!("expected insecure permissions, got {other}"),
+        }
+        // The message says how to fix it
+        let message = error.to_string();
+        assert!(
+            message.contains("chmod 600 /data/identity.key"),
+            "{message}"
+        );
+        assert!(message.contains("identity.key_permissions"), "{message}");
+    }
+}
+
+#[test]
+fn test_group_or_world_access_is_allowed_when_warning() {
+    assert!(
+        check_key_permissions(Path::new("identity.key"), 0o644, KeyPermissionPolicy::Warn).is_ok()
+    );
+}
+
+#[test]
+fn test_save_replaces_key_without_leaving_temporary_files() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("keys").join("identity.key");
+
+    save_key_secure(&path, b"first").unwrap();
+    save_key_secure(&path, b"second").unwrap();
+
+    assert_eq!(load_key_secure(&path).unwrap(), b"second");
+    let entries: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(entries, vec![std::ffi::OsString::from("identity.key")]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_saved_key_is_private_and_shared_key_is_refused() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("identity.key");
+    save_key_secure(&path, b"secret").unwrap();
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode, 0o600);
+
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    assert!(matches!(
+        load_key_secure(&path),
+        Err(StorageError::InsecurePermissions { mode: 0o644, .. })
+    ));
+
+    // Read-only for the owner is still private
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o400)).unwrap();
+    assert_eq!(load_key_secure(&path).unwrap(), b"secret");
+}
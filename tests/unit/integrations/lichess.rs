@@ -0,0 +1,126 @@
+//! Lichess Bridge Tests
+//!
+//! Tests for PGN parsing, importing, and exporting in
+//! `src/integrations/lichess.rs`. Nothing here talks to Lichess.
+
+use mate::integrations::lichess::{export_san_pgn, import_games, parse_pgn, IMPORT_TAG};
+use mate::messages::chess::Move;
+use mate::storage::models::GameResult;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use tempfile::TempDir;
+
+const PGN: &str = r#"[Event "Rated blitz game"]
+[Site "https://lichess.org/abcd1234"]
+[White "alice"]
+[Black "Bob"]
+[Result "0-1"]
+
+1. f3 { [%clk 0:03:00] } e5 2. g4 (2. e4 Nc6) 2... Qh4# $1 0-1
+
+
+[Event "Casual game"]
+[Site "https://lichess.org/efgh5678"]
+[White "bob"]
+[Black "carol"]
+[Result "1/2-1/2"]
+
+1. e4 e5 1/2-1/2
+
+[Event "Rated rapid game"]
+[Site "https://lichess.org/ijkl9012"]
+[White "dave"]
+[Black "bob"]
+[Result "1-0"]
+
+1. e4 e5 2. Ke3 1-0
+"#;
+
+fn database(temp_dir: &TempDir) -> Database {
+    Database::new_with_path("me", &temp_dir.path().join("db.sqlite")).unwrap()
+}
+
+#[test]
+fn test_pgn_main_line_is_parsed_without_comments_or_variations() {
+    let games = parse_pgn(PGN);
+    assert_eq!(games.len(), 3);
+    assert_eq!(games[0].id, "abcd1234");
+    assert_eq!(games[0].white, "alice");
+    assert_eq!(games[0].black, "Bob");
+    assert_eq!(games[0].result, "0-1");
+    assert_eq!(games[0].moves, ["f3", "e5", "g4", "Qh4#"]);
+    assert_eq!(games[1].moves, ["e4", "e5"]);
+
+    // Games from elsewhere have no Lichess ID to deduplicate by
+    assert!(parse_pgn("[Site \"https://example.com/1\"]\n\n1. e4 *\n").is_empty());
+}
+
+#[test]
+fn test_games_are_imported_once_with_results_from_the_users_side() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = database(&temp_dir);
+
+    let summary = import_games(&db, "me", "bob", PGN).unwrap();
+    assert_eq!(summary.imported.len(), 2);
+    assert_eq!(summary.duplicates, 0);
+    // The king cannot reach e3 on move two
+    assert_eq!(summary.invalid, 1);
+
+    let won = db.get_game(&summary.imported[0]).unwrap();
+    assert_eq!(won.opponent_peer_id, "lichess:alice");
+    assert_eq!(won.my_color, PlayerColor::Black);
+    assert_eq!(won.status, GameStatus::Completed);
+    assert_eq!(won.result, Some(GameResult::Win));
+    assert_eq!(db.get_game_tags(&won.id).unwrap(), [IMPORT_TAG]);
+
+    let moves: Vec<(String, String)> = db
+        .get_messages_for_game(&won.id)
+        .unwrap()
+        .into_iter()
+        .map(|m| {
+            let chess_move = serde_json::from_str::<Move>(&m.content).unwrap();
+            (chess_move.chess_move, m.sender_peer_id)
+        })
+        .collect();
+    let expected = [
+        ("f2f3", "lichess:alice"),
+        ("e7e5", "me"),
+        ("g2g4", "lichess:alice"),
+        ("d8h4", "me"),
+    ];
+    assert_eq!(moves, expected.map(|(m, s)| (m.to_string(), s.to_string())));
+
+    let drawn = db.get_game(&summary.imported[1]).unwrap();
+    assert_eq!(drawn.my_color, PlayerColor::White);
+    assert_eq!(drawn.result, Some(GameResult::Draw));
+
+    let again = import_games(&db, "me", "BOB", PGN).unwrap();
+    assert!(again.imported.is_empty());
+    assert_eq!(again.duplicates, 2);
+}
+
+#[test]
+fn test_finished_games_export_in_algebraic_notation() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = database(&temp_dir);
+    let game = db
+        .create_game("them".to_string(), PlayerColor::White, None)
+        .unwrap();
+    for chess_move in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+        let message = Move::new(game.id.clone(), chess_move.to_string(), "0".repeat(64));
+        db.store_message(
+            game.id.clone(),
+            "Move".to_string(),
+            serde_json::to_string(&message).unwrap(),
+            String::new(),
+            "me".to_string(),
+        )
+        .unwrap();
+    }
+
+    assert!(export_san_pgn(&db, &game.id, "me").is_err());
+
+    db.update_game_result(&game.id, GameResult::Loss).unwrap();
+    let pgn = export_san_pgn(&db, &game.id, "me").unwrap();
+    assert!(pgn.contains("[White \"me\"]\n[Black \"them\"]\n[Result \"0-1\"]"));
+    assert!(pgn.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"), "{pgn}");
+}
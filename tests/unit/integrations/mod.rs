@@ -0,0 +1,6 @@
+//! Integration bridge unit tests
+//!
+//! Each bridge is only built with its feature, so its tests are too.
+
+#[cfg(feature = "lichess")]
+pub mod lichess;
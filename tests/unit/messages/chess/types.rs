@@ -1,10 +1,11 @@
 #[cfg(test)]
 mod tests {
-    use mate::chess::{Board, Color};
+    use mate::chess::{Board, Color, Odds};
     use mate::messages::chess::{
-        generate_game_id, hash_board_state, GameAccept, GameDecline, GameInvite, Move, MoveAck,
-        SyncRequest, SyncResponse,
+        generate_game_id, hash_board_state, DrawAccept, DrawOffer, GameAccept, GameDecline,
+        GameInvite, Move, MoveAck, Resign, SyncRequest, SyncResponse,
     };
+    use mate::messages::Message;
     use serde_json;
 
     // =============================================================================
@@ -100,6 +101,37 @@ mod tests {
         assert_eq!(invite, deserialized);
     }
 
+    #[test]
+    fn test_game_invite_binary_compatibility_with_older_peers() {
+        let game_id = generate_game_id();
+        // Message::GameInvite as older peers encode it: the variant index
+        // followed by the two original fields
+        type OldInvite = (u32, String, Option<Color>);
+        let old: OldInvite = (2, game_id.clone(), Some(Color::Black));
+
+        let decoded = Message::deserialize(&bincode::serialize(&old).unwrap()).unwrap();
+        let Message::GameInvite(decoded) = decoded else {
+            panic!("expected an invitation, got {}", decoded.message_type());
+        };
+        assert_eq!(
+            decoded,
+            GameInvite::new(game_id.clone(), Some(Color::Black))
+        );
+
+        // Older peers skip the fields appended since
+        let odds = Odds {
+            giver: Color::White,
+            removed: vec!["b1".parse().unwrap()],
+            extra_moves: 1,
+        };
+        let invite = GameInvite::new(game_id, Some(Color::Black))
+            .with_odds(odds)
+            .with_expiry(1_700_000_000)
+            .with_rated();
+        let bytes = Message::GameInvite(invite).serialize().unwrap();
+        assert_eq!(bincode::deserialize::<OldInvite>(&bytes).unwrap(), old);
+    }
+
     // =============================================================================
     // GameAccept Tests
     // =============================================================================
@@ -652,4 +684,40 @@ mod tests {
 
         assert_eq!(response.move_history.len(), 100);
     }
+
+    // =============================================================================
+    // Game Ending Tests
+    // =============================================================================
+
+    #[test]
+    fn test_game_ending_messages_roundtrip_and_validate() {
+        let game_id = generate_game_id();
+        let messages = [
+            Message::new_resign(game_id.clone()),
+            Message::new_draw_offer(game_id.clone()),
+            Message::new_draw_accept(game_id.clone()),
+        ];
+
+        for (message, expected_type) in messages.iter().zip(["Resign", "DrawOffer", "DrawAccept"]) {
+            assert_eq!(message.message_type(), expected_type);
+            assert!(message.validate().is_ok());
+
+            let bytes = message.serialize().expect("Failed to serialize");
+            let deserialized = Message::deserialize(&bytes).expect("Failed to deserialize");
+            assert_eq!(deserialized.get_game_id(), Some(game_id.as_str()));
+            assert_eq!(deserialized.message_type(), expected_type);
+        }
+
+        assert_eq!(Resign::new(game_id.clone()).game_id, game_id);
+        assert_eq!(DrawOffer::new(game_id.clone()).game_id, game_id);
+        assert_eq!(DrawAccept::new(game_id.clone()).game_id, game_id);
+    }
+
+    #[test]
+    fn test_game_ending_messages_reject_invalid_game_ids() {
+        assert!(Message::new_resign(String::new()).validate().is_err());
+        assert!(Message::new_draw_offer("not-a-uuid".to_string())
+            .validate()
+            .is_err());
+    }
 }
@@ -0,0 +1,72 @@
+//! Tests for the buffers reused between framed messages
+
+use crate::common::test_data::*;
+use mate::messages::wire::{BufferPool, FramedMessage};
+use std::io::Cursor;
+
+#[test]
+fn test_buffer_pool_reuses_returned_buffers() {
+    let pool = BufferPool::new(2, 1024);
+    assert_eq!(pool.available(), 0);
+
+    let mut buffer = pool.take();
+    buffer.extend_from_slice(&[1; 100]);
+    let capacity = buffer.capacity();
+    drop(buffer);
+    assert_eq!(pool.available(), 1);
+
+    let buffer = pool.take();
+    assert!(buffer.is_empty(), "reused buffers start empty");
+    assert_eq!(buffer.capacity(), capacity);
+    assert_eq!(pool.available(), 0);
+}
+
+#[test]
+fn test_buffer_pool_drops_oversized_and_surplus_buffers() {
+    let pool = BufferPool::new(2, 1024);
+
+    let mut oversized = pool.take();
+    oversized.resize(4096, 0);
+    drop(oversized);
+    assert_eq!(pool.available(), 0, "large buffers are not kept");
+
+    let buffers: Vec<_> = (0..3)
+        .map(|_| {
+            let mut buffer = pool.take();
+            buffer.push(0);
+            buffer
+        })
+        .collect();
+    drop(buffers);
+    assert_eq!(pool.available(), 2, "at most max_buffers are kept");
+}
+
+#[tokio::test]
+async fn test_framed_message_returns_buffers_to_shared_pool() {
+    let framed_message = FramedMessage::default();
+    let clone = framed_message.clone();
+    let (envelope, _) = create_test_envelope("pooled payload");
+
+    let mut wire = Vec::new();
+    framed_message
+        .write_message(&mut wire, &envelope)
+        .await
+        .unwrap();
+    assert_eq!(framed_message.buffer_pool().available(), 1);
+
+    // Clones share the pool, so reading reuses the buffer used for writing
+    let read = clone
+        .read_message(&mut Cursor::new(wire.clone()))
+        .await
+        .unwrap();
+    assert_eq!(read.sender(), envelope.sender());
+    assert_eq!(framed_message.buffer_pool().available(), 1);
+
+    // Reused buffers don't leak bytes between messages
+    let mut rewritten = Vec::new();
+    framed_message
+        .write_message(&mut rewritten, &envelope)
+        .await
+        .unwrap();
+    assert_eq!(rewritten, wire);
+}
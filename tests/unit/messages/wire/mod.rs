@@ -1,5 +1,6 @@
 //! Wire protocol unit tests
 
+pub mod buffer_pool;
 pub mod length_prefix;
 pub mod message_roundtrip;
 pub mod partial_io;
@@ -1,8 +1,10 @@
 //! Unit tests for individual components
 
+pub mod api;
 pub mod chess; // Chess data structures unit tests
 pub mod cli; // CLI application components unit tests
 pub mod crypto; // ADD: Focused crypto unit tests
 pub mod display;
+pub mod integrations;
 pub mod messages;
 pub mod network;
@@ -0,0 +1,59 @@
+//! Server Announcement Tests
+//!
+//! Tests for operator announcements passed on by `Server` in
+//! `src/network/server.rs` and set aside by `Connection::receive_message`
+//! on the connections that receive them.
+
+use super::{connect, connect_as};
+use mate::crypto::Identity;
+use mate::messages::Message;
+use mate::network::Client;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_operator_announcement_reaches_connected_peers() {
+    let operator = Arc::new(Identity::generate().unwrap());
+    let (_database, mut connection) = connect_as(
+        Arc::clone(&operator),
+        Arc::new(Identity::generate().unwrap()),
+    )
+    .await;
+    let addr = connection.peer_addr().unwrap().to_string();
+
+    Client::new(operator)
+        .announce(&addr, "Maintenance at 22:00")
+        .await
+        .unwrap();
+
+    // The announcement may come before or after the reply to any ping, but
+    // never in place of it
+    let mut announcements = Vec::new();
+    for nonce in 0..10 {
+        connection
+            .send_message(Message::new_ping(nonce, "hello".to_string()))
+            .await
+            .unwrap();
+        let (reply, _sender) = connection.receive_message().await.unwrap();
+        assert_eq!(reply.get_nonce(), nonce);
+        announcements = connection.take_announcements();
+        if !announcements.is_empty() {
+            break;
+        }
+    }
+
+    assert_eq!(announcements.len(), 1);
+    let (announcement, sender) = &announcements[0];
+    assert_eq!(announcement.text, "Maintenance at 22:00");
+    assert_eq!(sender, connection.peer_identity().unwrap());
+}
+
+#[tokio::test]
+async fn test_announce_requires_the_servers_identity() {
+    let (_database, connection) = connect(Arc::new(Identity::generate().unwrap())).await;
+    let addr = connection.peer_addr().unwrap().to_string();
+
+    let outsider = Client::new(Arc::new(Identity::generate().unwrap()));
+    assert!(outsider.announce(&addr, "Free games!").await.is_err());
+    // Blank announcements are refused before connecting
+    assert!(outsider.announce(&addr, "  ").await.is_err());
+}
@@ -0,0 +1,208 @@
+//! Arbiter Tests
+//!
+//! Tests for arbiters joining games, receiving moves, co-signing results, and
+//! ruling on illegal move claims with `Server` in `src/network/server.rs`.
+
+use super::{connect_as, exchange};
+use mate::crypto::certificate::{arbiter_address, ARBITER_APPOINTMENT_MESSAGE_TYPE};
+use mate::crypto::{Identity, ScoreSheet};
+use mate::messages::chess::generate_game_id;
+use mate::messages::{ArbiterUpdate, Message};
+use mate::storage::PlayerColor;
+use std::sync::Arc;
+
+fn update(game_id: &str, white: &Identity, black: &Identity, moves: &[&str]) -> ArbiterUpdate {
+    ArbiterUpdate {
+        game_id: game_id.to_string(),
+        white: white.peer_id().to_string(),
+        black: black.peer_id().to_string(),
+        moves: moves.iter().map(|m| m.to_string()).collect(),
+        result: "*".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_only_the_appointed_arbiter_joins() {
+    let player = Arc::new(Identity::generate().unwrap());
+    let arbiter = Arc::new(Identity::generate().unwrap());
+    let opponent = Identity::generate().unwrap();
+    let (database, mut connection) = connect_as(Arc::clone(&player), Arc::clone(&arbiter)).await;
+    let game = database
+        .create_game(opponent.peer_id().to_string(), PlayerColor::White, None)
+        .unwrap();
+
+    // Not appointed yet
+    let reply = exchange(
+        &mut connection,
+        Message::new_arbiter_join(game.id.clone(), "192.0.2.20:8080".to_string()),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "Ping");
+
+    database
+        .store_message(
+            game.id.clone(),
+            ARBITER_APPOINTMENT_MESSAGE_TYPE.to_string(),
+            serde_json::json!({ "arbiter": arbiter.peer_id().as_str() }).to_string(),
+            String::new(),
+            player.peer_id().to_string(),
+        )
+        .unwrap();
+    let reply = exchange(
+        &mut connection,
+        Message::new_arbiter_join(game.id.clone(), "192.0.2.20:8080".to_string()),
+    )
+    .await;
+    let Message::ArbiterUpdate(update) = reply else {
+        panic!("expected the game so far, got {}", reply.message_type());
+    };
+    assert_eq!(update.game_id, game.id);
+    assert_eq!(update.white, player.peer_id().as_str());
+    assert_eq!(update.black, opponent.peer_id().as_str());
+    assert_eq!(update.result, "*");
+
+    let messages = database.get_messages_for_game(&game.id).unwrap();
+    assert_eq!(
+        arbiter_address(&messages).as_deref(),
+        Some("192.0.2.20:8080")
+    );
+}
+
+#[tokio::test]
+async fn test_arbiter_signs_finished_game_reported_by_a_player() {
+    let arbiter = Arc::new(Identity::generate().unwrap());
+    let white = Arc::new(Identity::generate().unwrap());
+    let black = Identity::generate().unwrap();
+    let (database, mut connection) = connect_as(Arc::clone(&arbiter), Arc::clone(&white)).await;
+    let game_id = generate_game_id();
+    database
+        .begin_arbitration(
+            &game_id,
+            white.peer_id().as_str(),
+            black.peer_id().as_str(),
+            &[],
+            "*",
+        )
+        .unwrap();
+
+    // An unfinished game is acknowledged
+    let reply = exchange(
+        &mut connection,
+        Message::ArbiterUpdate(update(&game_id, &white, &black, &["f2f3", "e7e5"])),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "ArbiterUpdate");
+
+    // Moves that do not continue the game so far are refused
+    let reply = exchange(
+        &mut connection,
+        Message::ArbiterUpdate(update(&game_id, &white, &black, &["d2d4"])),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "Ping");
+
+    let finished = ArbiterUpdate {
+        result: "0-1".to_string(),
+        ..update(&game_id, &white, &black, &["f2f3", "e7e5", "g2g4", "d8h4"])
+    };
+    let reply = exchange(&mut connection, Message::ArbiterUpdate(finished.clone())).await;
+    let Message::ResultSignature(signature) = reply else {
+        panic!(
+            "expected the arbiter's signature, got {}",
+            reply.message_type()
+        );
+    };
+    assert_eq!(signature.signer, arbiter.peer_id().as_str());
+    signature
+        .verify(&ScoreSheet::from_arbiter_update(
+            &finished,
+            arbiter.peer_id().as_str(),
+        ))
+        .unwrap();
+
+    let arbitration = database.get_arbitration(&game_id).unwrap().unwrap();
+    assert_eq!(arbitration.moves.len(), 4);
+    assert_eq!(arbitration.result, "0-1");
+}
+
+#[tokio::test]
+async fn test_updates_from_outsiders_are_refused() {
+    let arbiter = Arc::new(Identity::generate().unwrap());
+    let stranger = Arc::new(Identity::generate().unwrap());
+    let white = Identity::generate().unwrap();
+    let black = Identity::generate().unwrap();
+    let (database, mut connection) = connect_as(Arc::clone(&arbiter), stranger).await;
+    let game_id = generate_game_id();
+    database
+        .begin_arbitration(
+            &game_id,
+            white.peer_id().as_str(),
+            black.peer_id().as_str(),
+            &[],
+            "*",
+        )
+        .unwrap();
+
+    let reply = exchange(
+        &mut connection,
+        Message::ArbiterUpdate(update(&game_id, &white, &black, &["e2e4"])),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "Ping");
+    let reply = exchange(
+        &mut connection,
+        Message::new_illegal_move_claim(game_id.clone(), 1, None),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "Ping");
+    assert!(database
+        .get_arbitration(&game_id)
+        .unwrap()
+        .unwrap()
+        .moves
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_arbiter_rules_on_claims() {
+    let arbiter = Arc::new(Identity::generate().unwrap());
+    let black = Arc::new(Identity::generate().unwrap());
+    let white = Identity::generate().unwrap();
+    let (database, mut connection) = connect_as(Arc::clone(&arbiter), Arc::clone(&black)).await;
+    let game_id = generate_game_id();
+    let moves: Vec<String> = ["e2e4", "e7e5", "e1e3"]
+        .iter()
+        .map(|m| m.to_string())
+        .collect();
+    database
+        .begin_arbitration(
+            &game_id,
+            white.peer_id().as_str(),
+            black.peer_id().as_str(),
+            &moves,
+            "*",
+        )
+        .unwrap();
+
+    let reply = exchange(
+        &mut connection,
+        Message::new_illegal_move_claim(
+            game_id.clone(),
+            3,
+            Some("the king moved two squares".to_string()),
+        ),
+    )
+    .await;
+    let Message::ArbiterRuling(ruling) = reply else {
+        panic!("expected a ruling, got {}", reply.message_type());
+    };
+    ruling.verify().unwrap();
+    assert!(ruling.upheld);
+    assert_eq!(ruling.arbiter, arbiter.peer_id().as_str());
+    assert_eq!(ruling.claimant, black.peer_id().as_str());
+
+    let rulings = database.get_arbiter_rulings(&game_id).unwrap();
+    assert_eq!(rulings.len(), 1);
+    assert!(rulings[0].upheld);
+    assert_eq!(rulings[0].chess_move.as_deref(), Some("e1e3"));
+}
@@ -0,0 +1,183 @@
+//! Clock Skew Tests
+//!
+//! Tests for the clock offset estimated during the handshake in
+//! `src/network/connection.rs`, timestamp checks corrected by it, and dating
+//! opponent moves by their corrected send time, strictly in rated games.
+
+use mate::chess::{Board, Move};
+use mate::cli::game_ops::{MoveProcessor, RATED_SEND_TOLERANCE_SECS};
+use mate::crypto::Identity;
+use mate::messages::chess::{generate_game_id, hash_board_state, Move as MoveMessage};
+use mate::messages::{Message, SignedEnvelope};
+use mate::network::connection::estimate_clock_offset;
+use mate::network::{Client, Server};
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[test]
+fn test_offset_is_estimated_from_the_round_trip_midpoint() {
+    // Request sent at 1000.0s and answered at 1002.0s on our clock
+    assert_eq!(estimate_clock_offset(1060, 1_000_000, 1_002_000), 60);
+    assert_eq!(estimate_clock_offset(940, 1_000_000, 1_002_000), -60);
+    assert_eq!(estimate_clock_offset(1000, 1_000_000, 1_002_000), 0);
+
+    // An unprompted message is compared with its arrival
+    assert_eq!(estimate_clock_offset(1030, 1_000_500, 1_000_500), 30);
+}
+
+#[test]
+fn test_timestamp_check_corrects_for_clock_offset() {
+    let identity = Identity::generate().unwrap();
+    let envelope =
+        SignedEnvelope::create(&Message::new_ping(1, "ping".to_string()), &identity, None).unwrap();
+
+    assert!(envelope.is_timestamp_within(300, 60, 0));
+    // A sender two minutes ahead signed this two minutes before "now"
+    assert!(envelope.is_timestamp_within(300, 60, 120));
+    // ...and one ten minutes ahead, too long ago
+    assert!(!envelope.is_timestamp_within(300, 60, 600));
+    // A sender two minutes behind would have signed it in our future
+    assert!(!envelope.is_timestamp_within(300, 60, -120));
+    assert!(envelope.is_timestamp_within(300, 180, -120));
+
+    assert_eq!(
+        envelope.corrected_timestamp(120),
+        envelope.timestamp() as i64 - 120
+    );
+}
+
+#[tokio::test]
+async fn test_handshake_measures_peer_clock_offset() {
+    let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap();
+    let server_addr = server.local_addr().unwrap().to_string();
+    let server_handle = tokio::spawn(async move { server.run().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = Client::new(Arc::new(Identity::generate().unwrap()));
+    let mut connection = client.connect(&server_addr).await.unwrap();
+
+    // Both ends share this machine's clock
+    let offset = connection.clock_offset().expect("offset after handshake");
+    assert!(offset.abs() <= 1, "offset {offset}");
+    assert_eq!(
+        connection.corrected_timestamp(1_000_000),
+        1_000_000 - offset
+    );
+
+    let _ = connection.close().await;
+    server_handle.abort();
+}
+
+#[test]
+fn test_opponent_moves_are_dated_by_corrected_send_time() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("db.sqlite");
+    let db = Database::new_with_path("test_peer", &db_path).unwrap();
+
+    // Moves received from peers must name a game by UUID
+    let game = db
+        .create_game("opponent_peer".to_string(), PlayerColor::White, None)
+        .unwrap();
+    db.update_game_status(&game.id, GameStatus::Active).unwrap();
+    let game_id = generate_game_id();
+    rusqlite::Connection::open(&db_path)
+        .unwrap()
+        .execute(
+            "UPDATE games SET id = ?1 WHERE id = ?2",
+            [&game_id, &game.id],
+        )
+        .unwrap();
+
+    let mut board = Board::new();
+    let mut reply = |ours: &str, theirs: &str| {
+        board.make_move(Move::from_str(ours).unwrap()).unwrap();
+        board.make_move(Move::from_str(theirs).unwrap()).unwrap();
+        MoveMessage::new(
+            game_id.clone(),
+            theirs.to_string(),
+            hash_board_state(&board),
+        )
+    };
+
+    // A reply dated before our own move cannot be recorded ahead of it
+    MoveProcessor::new(&db)
+        .process_move(&game_id, "e2e4", true)
+        .unwrap();
+    MoveProcessor::new(&db)
+        .with_sent_at(0)
+        .apply_opponent_move(&game_id, &reply("e2e4", "e7e5"))
+        .unwrap();
+
+    // Nor can one be dated in the future
+    MoveProcessor::new(&db)
+        .process_move(&game_id, "g1f3", true)
+        .unwrap();
+    MoveProcessor::new(&db)
+        .with_sent_at(i64::MAX / 2)
+        .apply_opponent_move(&game_id, &reply("g1f3", "b8c6"))
+        .unwrap();
+
+    let moves = db.get_messages_by_type(&game_id, "Move").unwrap();
+    let times: Vec<i64> = moves.iter().map(|m| m.created_at).collect();
+    assert_eq!(times.len(), 4);
+    assert_eq!(times[1], times[0]);
+    assert!(times[3] >= times[2]);
+    assert!(times[3] <= Database::current_timestamp());
+}
+
+#[test]
+fn test_rated_games_date_late_moves_by_arrival() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("db.sqlite");
+    let db = Database::new_with_path("test_peer", &db_path).unwrap();
+
+    // Reply to 1. e4 claimed sent five minutes ago, ten minutes after it
+    let reply_time = |metadata: Option<serde_json::Value>| {
+        let game = db
+            .create_game("opponent_peer".to_string(), PlayerColor::White, metadata)
+            .unwrap();
+        db.update_game_status(&game.id, GameStatus::Active).unwrap();
+        let game_id = generate_game_id();
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute(
+            "UPDATE games SET id = ?1 WHERE id = ?2",
+            [&game_id, &game.id],
+        )
+        .unwrap();
+
+        MoveProcessor::new(&db)
+            .process_move(&game_id, "e2e4", true)
+            .unwrap();
+        let now = Database::current_timestamp();
+        conn.execute(
+            "UPDATE messages SET created_at = ?1 WHERE game_id = ?2",
+            (now - 900, &game_id),
+        )
+        .unwrap();
+
+        let mut board = Board::new();
+        board.make_move(Move::from_str("e2e4").unwrap()).unwrap();
+        board.make_move(Move::from_str("e7e5").unwrap()).unwrap();
+        let reply = MoveMessage::new(
+            game_id.clone(),
+            "e7e5".to_string(),
+            hash_board_state(&board),
+        );
+        MoveProcessor::new(&db)
+            .with_sent_at(now - 300)
+            .apply_opponent_move(&game_id, &reply)
+            .unwrap();
+        let moves = db.get_messages_by_type(&game_id, "Move").unwrap();
+        (now, moves[1].created_at)
+    };
+
+    let (now, casual) = reply_time(None);
+    assert_eq!(casual, now - 300);
+    let (now, rated) = reply_time(Some(serde_json::json!({ "rated": true })));
+    assert!(rated >= now - RATED_SEND_TOLERANCE_SECS, "{rated} vs {now}");
+}
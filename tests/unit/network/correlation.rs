@@ -0,0 +1,113 @@
+//! Correlation ID Tests
+//!
+//! Tests for the connection IDs and game IDs attached to the spans of
+//! `src/network/connection.rs` and `src/network/server.rs`.
+
+use mate::cli::logging::{JsonFields, JsonFormat};
+use mate::crypto::Identity;
+use mate::messages::Message;
+use mate::network::connection::new_connection_id;
+use mate::network::{Client, Server};
+use serde_json::Value;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Collects everything written by a subscriber
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn is_connection_id(value: &Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|id| id.len() == 8 && id.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[test]
+fn test_connection_ids_are_short_and_random() {
+    let first = new_connection_id();
+    assert!(is_connection_id(&Value::from(first.as_str())), "{first}");
+    assert_ne!(first, new_connection_id());
+}
+
+#[tokio::test]
+async fn test_connection_and_game_ids_reach_both_ends_logs() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .event_format(JsonFormat)
+            .fmt_fields(JsonFields)
+            .with_writer(move || writer.clone()),
+    );
+    // The test runtime is single-threaded, so the server task logs here too
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap();
+    let server_addr = server.local_addr().unwrap().to_string();
+    let server_handle = tokio::spawn(async move { server.run().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = Client::new(Arc::new(Identity::generate().unwrap()));
+    let mut connection = client.connect(&server_addr).await.unwrap();
+    let client_id = connection.connection_id().to_string();
+    assert!(is_connection_id(&Value::from(client_id.as_str())));
+
+    let resign = Message::new_resign("game-42".to_string());
+    let resign_type = resign.message_type();
+    connection.send_message(resign).await.unwrap();
+    // The server answers pings in order, so the resignation has been handled
+    connection
+        .send_message(Message::new_ping(1, "ping".to_string()))
+        .await
+        .unwrap();
+    connection.receive_message().await.unwrap();
+    let _ = connection.close().await;
+    server_handle.abort();
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let in_span = |line: &Value, name: &str| {
+        line["spans"]
+            .as_array()
+            .is_some_and(|spans| spans.iter().any(|span| span == name))
+    };
+
+    let client_sends: Vec<&Value> = lines
+        .iter()
+        .filter(|line| {
+            in_span(line, "send_message") && !in_span(line, "handle_connection_with_shutdown")
+        })
+        .collect();
+    assert!(!client_sends.is_empty());
+    for line in &client_sends {
+        assert_eq!(line["connection_id"], client_id.as_str(), "{line}");
+    }
+    assert!(client_sends
+        .iter()
+        .any(|line| line["game_id"] == "game-42" && line["msg_type"] == resign_type));
+
+    let received = lines
+        .iter()
+        .find(|line| line["target"] == "mate::network::server" && line["msg_type"] == resign_type)
+        .expect("server logs the resignation");
+    assert_eq!(received["game_id"], "game-42");
+    assert!(is_connection_id(&received["connection_id"]), "{received}");
+    assert_ne!(received["connection_id"], client_id.as_str());
+}
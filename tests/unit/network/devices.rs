@@ -0,0 +1,129 @@
+//! Device Sync Tests
+//!
+//! Tests for pairing and syncing linked devices with `Server` in
+//! `src/network/server.rs`.
+
+use super::{connect, exchange};
+use mate::crypto::Identity;
+use mate::messages::{Message, SyncChange, SyncKind};
+use mate::storage::devices::{SYNC_ALIAS, SYNC_SETTING};
+use mate::storage::Database;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_pairing_code_links_the_sender_once() {
+    let laptop = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = connect(Arc::clone(&laptop)).await;
+    let expires_at = Database::current_timestamp() + 600;
+    database
+        .create_pairing_code("7KQ4-MZ2P", expires_at)
+        .unwrap();
+
+    let reply = exchange(
+        &mut connection,
+        Message::new_device_pair("7KQ4-MZ2P".to_string(), "192.0.2.10:8080".to_string()),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "DevicePair");
+    let device = database
+        .get_linked_device(laptop.peer_id().as_str())
+        .unwrap()
+        .unwrap();
+    assert_eq!(device.address, "192.0.2.10:8080");
+
+    // The code is used up
+    let reply = exchange(
+        &mut connection,
+        Message::new_device_pair("7KQ4-MZ2P".to_string(), "192.0.2.11:8080".to_string()),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "Ping");
+    assert_eq!(database.get_linked_devices().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_wrong_pairing_code_is_refused() {
+    let stranger = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = connect(stranger).await;
+    let expires_at = Database::current_timestamp() + 600;
+    database
+        .create_pairing_code("7KQ4-MZ2P", expires_at)
+        .unwrap();
+
+    let reply = exchange(
+        &mut connection,
+        Message::new_device_pair("AAAA-BBBB".to_string(), "192.0.2.10:8080".to_string()),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "Ping");
+    assert!(database.get_linked_devices().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_from_unlinked_peer_is_refused() {
+    let stranger = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = connect(stranger).await;
+
+    let change = SyncChange {
+        kind: SyncKind::Alias,
+        key: "alice".to_string(),
+        value: Some("alice_peer".to_string()),
+        updated_at: 100,
+    };
+    let reply = exchange(
+        &mut connection,
+        Message::new_device_sync(0, 1, vec![change]),
+    )
+    .await;
+    assert_eq!(reply.message_type(), "Ping");
+    assert_eq!(database.resolve_peer_alias("alice").unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_linked_devices_exchange_changes() {
+    let laptop = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = connect(Arc::clone(&laptop)).await;
+    database
+        .link_device(laptop.peer_id().as_str(), "192.0.2.10:8080")
+        .unwrap();
+    database
+        .record_sync_change(SYNC_SETTING, "display.theme", Some("dark"), 100)
+        .unwrap();
+
+    let change = SyncChange {
+        kind: SyncKind::Alias,
+        key: "alice".to_string(),
+        value: Some("alice_peer".to_string()),
+        updated_at: 100,
+    };
+    let reply = exchange(
+        &mut connection,
+        Message::new_device_sync(0, 4, vec![change]),
+    )
+    .await;
+
+    let Message::DeviceSync(reply) = reply else {
+        panic!("expected a device sync reply, got {}", reply.message_type());
+    };
+    assert_eq!(
+        database.resolve_peer_alias("alice").unwrap().as_deref(),
+        Some("alice_peer")
+    );
+    assert!(database
+        .sync_entries(SYNC_ALIAS)
+        .unwrap()
+        .iter()
+        .any(|e| e.key == "alice"));
+    assert!(reply
+        .changes
+        .iter()
+        .any(|c| c.kind == SyncKind::Setting && c.value.as_deref() == Some("dark")));
+    assert_eq!(reply.through, database.sync_journal_head().unwrap());
+
+    let device = database
+        .get_linked_device(laptop.peer_id().as_str())
+        .unwrap()
+        .unwrap();
+    assert_eq!(device.received_through, 4);
+    assert!(device.last_synced_at.is_some());
+}
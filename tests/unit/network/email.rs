@@ -0,0 +1,135 @@
+//! Email Notification Tests
+//!
+//! Tests for the SMTP delivery and idle detection in `src/network/email.rs`.
+
+use mate::messages::Message;
+use mate::network::email::{dot_stuff, validate_relay};
+use mate::network::{EmailNotifier, UserActivity};
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// Accept one SMTP session, returning everything the client sent
+async fn fake_smtp_server(listener: TcpListener) -> String {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut transcript = String::new();
+    reader
+        .get_mut()
+        .write_all(b"220 relay ESMTP\r\n")
+        .await
+        .unwrap();
+    let mut in_data = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.unwrap() == 0 {
+            break;
+        }
+        transcript.push_str(&line);
+        let reply: &[u8] = if in_data {
+            if line != ".\r\n" {
+                continue;
+            }
+            in_data = false;
+            b"250 queued\r\n"
+        } else if line.starts_with("EHLO") {
+            b"250-relay\r\n250 8BITMIME\r\n"
+        } else if line.starts_with("DATA") {
+            in_data = true;
+            b"354 go ahead\r\n"
+        } else if line.starts_with("QUIT") {
+            reader.get_mut().write_all(b"221 bye\r\n").await.unwrap();
+            break;
+        } else {
+            b"250 ok\r\n"
+        };
+        reader.get_mut().write_all(reply).await.unwrap();
+    }
+    transcript
+}
+
+#[tokio::test]
+async fn test_email_is_delivered_over_smtp() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server = listener.local_addr().unwrap().to_string();
+    let session = tokio::spawn(fake_smtp_server(listener));
+
+    let email = EmailNotifier::new(&server, "mate@localhost", "me@example.com").unwrap();
+    let chess_move = Message::new_move("game-1".to_string(), "e2e4".to_string(), "0".repeat(64));
+    let (subject, body) = email.email_for(&chess_move, "peer").unwrap();
+    email.send(&subject, &body).await.unwrap();
+
+    let transcript = session.await.unwrap();
+    assert!(transcript.contains("MAIL FROM:<mate@localhost>\r\n"));
+    assert!(transcript.contains("RCPT TO:<me@example.com>\r\n"));
+    assert!(transcript.contains("Subject: [mate] peer played e2e4\r\n"));
+    assert!(transcript.ends_with(".\r\nQUIT\r\n"));
+}
+
+#[test]
+fn test_only_moves_and_invites_are_emailed() {
+    let email = EmailNotifier::new("127.0.0.1:25", "a@b", "c@d").unwrap();
+    let invite = Message::new_game_invite("game-1".to_string(), None);
+    assert!(email.email_for(&invite, "peer").is_some());
+    let resign = Message::new_resign("game-1".to_string());
+    assert!(email.email_for(&resign, "peer").is_none());
+
+    assert!(EmailNotifier::new("127.0.0.1:25", "a@b", "c@d\r\nRCPT TO:<x@y>").is_err());
+    assert!(EmailNotifier::new("127.0.0.1:25", "a@b", "not-an-address").is_err());
+}
+
+#[test]
+fn test_only_local_relays_are_accepted() {
+    for server in ["127.0.0.1:25", "localhost:2525", "[::1]:25"] {
+        assert!(validate_relay(server).is_ok(), "{server}");
+    }
+    for server in [
+        "smtp.gmail.com:587",
+        "192.168.1.10:25",
+        "[2001:db8::1]:25",
+        "[::1]",
+        "127.0.0.1",
+        "",
+    ] {
+        assert!(validate_relay(server).is_err(), "{server}");
+    }
+    assert!(EmailNotifier::new("smtp.example.com:25", "a@b", "c@d").is_err());
+
+    let mut config = mate::cli::app::Config::default();
+    assert!(config
+        .set("email.smtp_server", "smtp.example.com:25")
+        .is_err());
+    assert_eq!(config.email.smtp_server, "127.0.0.1:25");
+    for server in ["localhost:2525", "[::1]:25"] {
+        config.set("email.smtp_server", server).unwrap();
+        assert_eq!(config.email.smtp_server, server);
+        assert_eq!(
+            config.get("email.smtp_server").unwrap().as_deref(),
+            Some(server)
+        );
+    }
+}
+
+#[test]
+fn test_bodies_are_dot_stuffed() {
+    assert_eq!(dot_stuff("hi\n.\n..x"), "hi\r\n..\r\n...x");
+}
+
+#[test]
+fn test_email_waits_until_the_user_is_idle() {
+    let dir = TempDir::new().unwrap();
+    let activity = UserActivity::in_dir(dir.path());
+    let email = EmailNotifier::new("127.0.0.1:25", "a@b", "c@d")
+        .unwrap()
+        .when_idle(activity.clone(), Duration::from_secs(15 * 60));
+
+    // Nothing recorded yet counts as away
+    assert!(email.user_is_idle());
+    activity.touch().unwrap();
+    assert!(!email.user_is_idle());
+    activity
+        .record(SystemTime::now() - Duration::from_secs(20 * 60))
+        .unwrap();
+    assert!(email.user_is_idle());
+}
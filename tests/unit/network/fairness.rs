@@ -0,0 +1,125 @@
+//! Connection Fairness Tests
+//!
+//! Tests for the per-connection quotas in `src/network/quota.rs` and for the
+//! server sharing its handlers fairly between a flooding peer and a quiet one.
+
+use mate::crypto::Identity;
+use mate::messages::Message;
+use mate::network::{Client, ConnectionQuota, QuotaTracker, Server};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+fn quota(messages_per_second: u32, burst: u32, handling_ms: u64) -> ConnectionQuota {
+    ConnectionQuota {
+        messages_per_second,
+        burst,
+        handling_time_per_second: Duration::from_millis(handling_ms),
+    }
+}
+
+#[test]
+fn test_tracker_allows_the_burst_then_the_rate() {
+    let start = Instant::now();
+    let mut tracker = QuotaTracker::new(quota(10, 3, 1000), start);
+
+    for _ in 0..3 {
+        assert_eq!(tracker.delay(start), Duration::ZERO);
+        tracker.charge(Duration::ZERO, start);
+    }
+    // The fourth message must wait for a tenth of a second's refill
+    let delay = tracker.delay(start);
+    assert!(delay > Duration::from_millis(90) && delay <= Duration::from_millis(100));
+    assert_eq!(
+        tracker.delay(start + Duration::from_millis(100)),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn test_tracker_makes_slow_handling_wait_off_its_debt() {
+    let start = Instant::now();
+    let mut tracker = QuotaTracker::new(quota(1000, 1000, 100), start);
+
+    // One message took three times the connection's handling time
+    tracker.charge(Duration::from_millis(300), start);
+    let delay = tracker.delay(start);
+    assert!(delay > Duration::from_millis(1900) && delay <= Duration::from_secs(2));
+    assert_eq!(
+        tracker.delay(start + Duration::from_secs(2)),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn test_tracker_does_not_bank_more_than_the_burst() {
+    let start = Instant::now();
+    let mut tracker = QuotaTracker::new(quota(10, 2, 1000), start);
+
+    // An hour idle still only allows the burst
+    let later = start + Duration::from_secs(3600);
+    tracker.charge(Duration::ZERO, later);
+    tracker.charge(Duration::ZERO, later);
+    assert!(tracker.delay(later) > Duration::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_quiet_peer_is_answered_promptly_while_another_floods() {
+    let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap()
+        .with_connection_quota(quota(20, 5, 250))
+        .with_parallel_handlers(1);
+    let addr = server.local_addr().unwrap().to_string();
+    let stats = server.stats();
+    let server_handle = tokio::spawn(server.run());
+
+    let mut flooder = Client::new(Arc::new(Identity::generate().unwrap()))
+        .connect(&addr)
+        .await
+        .unwrap();
+    let mut quiet = Client::new(Arc::new(Identity::generate().unwrap()))
+        .connect(&addr)
+        .await
+        .unwrap();
+
+    // Many more pings than the flooder's burst, sent as fast as possible
+    let flood = tokio::spawn(async move {
+        let start = Instant::now();
+        for nonce in 0..40 {
+            flooder
+                .send_message(Message::new_ping(nonce, "flood".to_string()))
+                .await
+                .unwrap();
+        }
+        for _ in 0..40 {
+            flooder.receive_message().await.unwrap();
+        }
+        start.elapsed()
+    });
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut slowest = Duration::ZERO;
+    for nonce in 0..5 {
+        let sent = Instant::now();
+        quiet
+            .send_message(Message::new_ping(nonce, "hello".to_string()))
+            .await
+            .unwrap();
+        quiet.receive_message().await.unwrap();
+        slowest = slowest.max(sent.elapsed());
+    }
+    let flood_duration = flood.await.unwrap();
+
+    server_handle.abort();
+    // The flooder is held to its rate while the quiet peer never waits on it
+    assert!(
+        flood_duration >= Duration::from_millis(1500),
+        "flood finished in {flood_duration:?}"
+    );
+    assert!(
+        slowest < flood_duration / 4,
+        "quiet peer waited {slowest:?}"
+    );
+    assert!(stats.snapshot().throttled_messages > 0);
+}
@@ -0,0 +1,105 @@
+//! Game Routing Tests
+//!
+//! Tests for `Connection::receive_reply_to` in `src/network/connection.rs`,
+//! which keeps replies about two games against the same peer apart, and for
+//! `NetworkManager` in `src/cli/network_manager.rs` keeping the connection
+//! they were set aside on.
+
+use super::connected_pair;
+use mate::cli::NetworkManager;
+use mate::crypto::Identity;
+use mate::messages::chess::{generate_game_id, Move};
+use mate::messages::Message;
+use mate::network::{Connection, WireConfig};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+fn game_move(game_id: &str, chess_move: &str) -> Message {
+    Message::Move(chess_move_in(game_id, chess_move))
+}
+
+fn chess_move_in(game_id: &str, chess_move: &str) -> Move {
+    Move::new(
+        game_id.to_string(),
+        chess_move.to_string(),
+        "hash".to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_replies_for_two_games_against_one_peer_do_not_cross() {
+    let (mut connection, mut peer) = connected_pair(WireConfig::for_network()).await;
+
+    // Moves in two games against the same peer, sent before either reply
+    let first = game_move("game-one", "e2e4");
+    let second = game_move("game-two", "d2d4");
+    connection.send_message(first.clone()).await.unwrap();
+    connection.send_message(second.clone()).await.unwrap();
+
+    // The peer answers the second game first
+    for _ in 0..2 {
+        peer.receive_message().await.unwrap();
+    }
+    peer.send_message(game_move("game-two", "d7d5"))
+        .await
+        .unwrap();
+    peer.send_message(game_move("game-one", "e7e5"))
+        .await
+        .unwrap();
+    peer.send_message(Message::new_ping(7, "hello".to_string()))
+        .await
+        .unwrap();
+
+    let (reply, _) = connection.receive_reply_to(&first).await.unwrap();
+    assert_eq!(reply.get_game_id(), Some("game-one"));
+    // The reply about the other game was kept for it
+    let (reply, _) = connection.receive_reply_to(&second).await.unwrap();
+    assert_eq!(reply.get_game_id(), Some("game-two"));
+
+    // Messages outside any game are not matched by game
+    let ping = Message::new_ping(7, String::new());
+    let (reply, _) = connection.receive_reply_to(&ping).await.unwrap();
+    assert_eq!(reply.message_type(), "Ping");
+}
+
+#[tokio::test]
+async fn test_replies_set_aside_on_a_kept_connection_reach_their_game() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let (first_game, second_game) = (generate_game_id(), generate_game_id());
+
+    // A peer that answers the first move with replies about both games
+    let (first, second) = (first_game.clone(), second_game.clone());
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut peer = Connection::new(stream, Arc::new(Identity::generate().unwrap())).await;
+        peer.handle_handshake_request().await.unwrap();
+        peer.receive_message().await.unwrap();
+        peer.send_message(game_move(&second, "d7d5")).await.unwrap();
+        peer.send_message(game_move(&first, "e7e5")).await.unwrap();
+        peer.flush().await.unwrap();
+        let _ = peer.receive_message().await;
+    });
+
+    let manager = NetworkManager::new(Arc::new(Identity::generate().unwrap()));
+    let reply = manager
+        .send_chess_move(
+            &address,
+            first_game.clone(),
+            chess_move_in(&first_game, "e2e4"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(reply.get_game_id(), Some(first_game.as_str()));
+
+    // The second game's reply was kept with the connection
+    let reply = manager
+        .send_chess_move(
+            &address,
+            second_game.clone(),
+            chess_move_in(&second_game, "d2d4"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(reply.get_game_id(), Some(second_game.as_str()));
+}
@@ -0,0 +1,125 @@
+//! HTTP Client Tests
+//!
+//! Tests for how `src/network/http_client.rs` reads response bodies.
+
+use mate::network::http_client::{self, HttpUrl, MAX_RESPONSE_BYTES};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// Answer one request with `response`, keeping the connection open
+/// afterwards unless `close` is set
+async fn serve_once(response: Vec<u8>, close: bool) -> HttpUrl {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = HttpUrl::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        while line != "\r\n" {
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+        }
+        let _ = reader.get_mut().write_all(&response).await;
+        if !close {
+            tokio::time::sleep(Duration::from_secs(120)).await;
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn test_body_ends_at_content_length() {
+    let url = serve_once(
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello, and more".to_vec(),
+        false,
+    )
+    .await;
+
+    let (status, body) = tokio::time::timeout(Duration::from_secs(5), http_client::get(&url))
+        .await
+        .expect("Body should end at its length, not when the connection closes")
+        .unwrap();
+    assert_eq!(status, 200);
+    assert_eq!(body, b"hello");
+}
+
+#[tokio::test]
+async fn test_chunked_body_is_decoded() {
+    let url = serve_once(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+          5\r\nhello\r\n7;ext=1\r\n, world\r\n0\r\nTrailer: x\r\n\r\n"
+            .to_vec(),
+        false,
+    )
+    .await;
+
+    let (status, body) = tokio::time::timeout(Duration::from_secs(5), http_client::get(&url))
+        .await
+        .expect("Body should end at the last chunk")
+        .unwrap();
+    assert_eq!(status, 200);
+    assert_eq!(body, b"hello, world");
+}
+
+#[tokio::test]
+async fn test_truncated_bodies_are_errors() {
+    for response in [
+        &b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nhello"[..],
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n7\r\n, wo",
+    ] {
+        let url = serve_once(response.to_vec(), true).await;
+        let error = http_client::get(&url).await.unwrap_err();
+        assert!(error.to_string().contains("cut short"), "{error:#}");
+    }
+
+    // A body read until the connection closes has no length to check
+    let url = serve_once(b"HTTP/1.1 200 OK\r\n\r\nhello".to_vec(), true).await;
+    assert_eq!(http_client::get(&url).await.unwrap().1, b"hello");
+}
+
+#[tokio::test]
+async fn test_oversized_bodies_are_refused() {
+    let announced = format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n",
+        MAX_RESPONSE_BYTES + 1
+    );
+    let mut unannounced = b"HTTP/1.1 200 OK\r\n\r\n".to_vec();
+    unannounced.resize(unannounced.len() + MAX_RESPONSE_BYTES + 1, b'x');
+
+    for (response, close) in [(announced.into_bytes(), false), (unannounced, true)] {
+        let url = serve_once(response, close).await;
+        let error = http_client::get(&url).await.unwrap_err();
+        assert!(error.to_string().contains("larger than"), "{error:#}");
+    }
+}
+
+#[tokio::test]
+async fn test_get_sends_extra_headers() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = HttpUrl::parse(&format!("http://{}/games", listener.local_addr().unwrap())).unwrap();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = Vec::new();
+        let mut line = String::new();
+        while line != "\r\n" {
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+            head.push(line.trim_end().to_string());
+        }
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+            .await
+            .unwrap();
+        head
+    });
+
+    let headers = [("Accept", "application/x-chess-pgn".to_string())];
+    let (status, body) = http_client::get_with_headers(&url, &headers).await.unwrap();
+    assert_eq!((status, body.len()), (204, 0));
+    let head = server.await.unwrap();
+    assert_eq!(head[0], "GET /games HTTP/1.1");
+    assert!(head.contains(&"Accept: application/x-chess-pgn".to_string()));
+}
@@ -0,0 +1,61 @@
+//! Invitation Withdrawal Tests
+//!
+//! Tests for `InviteWithdraw` messages handled by `Server` in
+//! `src/network/server.rs`, and for expired invitations it refuses.
+
+use super::{connect, exchange};
+use mate::chess::Color;
+use mate::crypto::Identity;
+use mate::messages::chess::GameInvite;
+use mate::messages::Message;
+use mate::storage::{Database, PlayerColor};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_inviter_withdrawal_removes_the_pending_game() {
+    let inviter = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = connect(Arc::clone(&inviter)).await;
+    let game = database
+        .create_game(inviter.peer_id().to_string(), PlayerColor::Black, None)
+        .unwrap();
+
+    let reply = exchange(
+        &mut connection,
+        Message::new_invite_withdraw(game.id.clone()),
+    )
+    .await;
+
+    assert_eq!(reply.message_type(), "InviteWithdraw");
+    assert_eq!(reply.get_game_id(), Some(game.id.as_str()));
+    assert!(database.get_game(&game.id).is_err());
+}
+
+#[tokio::test]
+async fn test_only_the_inviter_may_withdraw() {
+    let (database, mut connection) = connect(Arc::new(Identity::generate().unwrap())).await;
+    let game = database
+        .create_game("12D3KooWInviter".to_string(), PlayerColor::Black, None)
+        .unwrap();
+
+    // The withdrawal goes unanswered, so the first reply is the ping's
+    let reply = exchange(
+        &mut connection,
+        Message::new_invite_withdraw(game.id.clone()),
+    )
+    .await;
+    assert!(reply.is_ping());
+    assert!(database.get_game(&game.id).is_ok());
+}
+
+#[tokio::test]
+async fn test_expired_invitations_are_refused() {
+    let (_database, mut connection) = connect(Arc::new(Identity::generate().unwrap())).await;
+    let invite = GameInvite::new(
+        mate::messages::chess::generate_game_id(),
+        Some(Color::White),
+    )
+    .with_expiry(Database::current_timestamp() - 60);
+
+    let reply = exchange(&mut connection, Message::GameInvite(invite)).await;
+    assert!(reply.is_ping());
+}
@@ -2,5 +2,83 @@
 //!
 //! This module contains tests for network operations, timeouts, and interruptions.
 
+use mate::crypto::Identity;
+use mate::messages::Message;
+use mate::network::{Client, Connection, Server, WireConfig};
+use mate::storage::Database;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+
+pub mod announcements;
+pub mod arbiter;
+pub mod clock_skew;
+pub mod correlation;
+pub mod devices;
+pub mod email;
+pub mod fairness;
+pub mod game_routing;
+pub mod http_client;
 pub mod interruptions;
+pub mod invite_withdrawal;
+pub mod moderation;
+pub mod notifications;
+pub mod port_mapping;
+pub mod presence;
+pub mod sealed_moves;
+pub mod send_queue;
+pub mod slow_clients;
+pub mod stats;
 pub mod timeouts;
+pub mod watchdog;
+pub mod webhooks;
+
+/// A server with its own database, and a connection to it from `peer`
+pub async fn connect(peer: Arc<Identity>) -> (Arc<Database>, Connection) {
+    connect_as(Arc::new(Identity::generate().unwrap()), peer).await
+}
+
+/// A server for `host` with its own database, and a connection to it from
+/// `peer`
+pub async fn connect_as(host: Arc<Identity>, peer: Arc<Identity>) -> (Arc<Database>, Connection) {
+    let database = Arc::new(Database::new_in_memory(host.peer_id().as_str()).unwrap());
+    let server = Server::bind("127.0.0.1:0", host)
+        .await
+        .unwrap()
+        .with_peer_history(Arc::clone(&database));
+    let addr = server.local_addr().unwrap().to_string();
+    tokio::spawn(server.run());
+
+    let connection = Client::new(peer).connect(&addr).await.unwrap();
+    (database, connection)
+}
+
+/// A connected pair of connections, the first with `config` and both
+/// reading and writing
+pub async fn connected_pair(config: WireConfig) -> (Connection, Connection) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+    (
+        Connection::new_with_config(stream, Arc::new(Identity::generate().unwrap()), config).await,
+        Connection::new(peer, Arc::new(Identity::generate().unwrap())).await,
+    )
+}
+
+/// Send `message` then a ping, returning the first reply
+///
+/// The ping's echo is read too when `message` was answered, so each exchange
+/// starts with nothing left to read.
+pub async fn exchange(connection: &mut Connection, message: Message) -> Message {
+    connection.send_message(message).await.unwrap();
+    connection
+        .send_message(Message::new_ping(9, "after".to_string()))
+        .await
+        .unwrap();
+    let reply = connection.receive_message().await.unwrap().0;
+    if reply.message_type() != "Ping" {
+        connection.receive_message().await.unwrap();
+    }
+    reply
+}
@@ -0,0 +1,92 @@
+//! Moderation Tests
+//!
+//! Tests for abuse reports kept by `Server` in `src/network/server.rs` as a
+//! lobby operator, and for dropping chat from muted peers.
+
+use super::{connect, exchange};
+use mate::crypto::Identity;
+use mate::messages::{Message, ReportEvidence};
+use std::sync::Arc;
+
+fn evidence() -> ReportEvidence {
+    ReportEvidence {
+        message_id: 7,
+        game_id: "game".to_string(),
+        message_type: "Chat".to_string(),
+        content: r#"{"text":"abuse"}"#.to_string(),
+        signature: "sig".to_string(),
+        received_at: 1_700_000_000,
+    }
+}
+
+#[tokio::test]
+async fn test_operator_keeps_report_with_reporter_and_evidence() {
+    let reporter = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = connect(Arc::clone(&reporter)).await;
+
+    let reply = exchange(
+        &mut connection,
+        Message::new_abuse_report(
+            "offender".to_string(),
+            "Abusive chat".to_string(),
+            vec![evidence()],
+        ),
+    )
+    .await;
+
+    assert_eq!(reply.message_type(), "AbuseReport");
+    let reports = database.get_peer_reports("offender").unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(
+        reports[0].reporter_peer_id.as_deref(),
+        Some(reporter.peer_id().as_str())
+    );
+    assert_eq!(reports[0].reason, "Abusive chat");
+    let kept: Vec<ReportEvidence> = serde_json::from_str(&reports[0].evidence).unwrap();
+    assert_eq!(kept, [evidence()]);
+}
+
+#[tokio::test]
+async fn test_operator_refuses_report_against_the_reporter() {
+    let reporter = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = connect(Arc::clone(&reporter)).await;
+
+    let reply = exchange(
+        &mut connection,
+        Message::new_abuse_report(
+            reporter.peer_id().to_string(),
+            "Myself".to_string(),
+            Vec::new(),
+        ),
+    )
+    .await;
+
+    assert!(reply.is_ping());
+    assert!(database
+        .get_peer_reports(reporter.peer_id().as_str())
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_chat_from_muted_peer_is_not_echoed() {
+    let peer = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = connect(Arc::clone(&peer)).await;
+
+    connection
+        .send_message(Message::new_ping(1, "hello".to_string()))
+        .await
+        .unwrap();
+    let reply = connection.receive_message().await.unwrap().0;
+    assert_eq!(reply.get_payload(), "hello");
+
+    database.mute_peer(peer.peer_id().as_str()).unwrap();
+    connection
+        .send_message(Message::new_ping(2, "hello again".to_string()))
+        .await
+        .unwrap();
+    let reply = connection.receive_message().await.unwrap().0;
+    assert!(reply.is_ping());
+    assert_eq!(reply.get_nonce(), 2);
+    assert_eq!(reply.get_payload(), "");
+}
@@ -0,0 +1,115 @@
+//! Desktop Notification Tests
+//!
+//! This module verifies which incoming messages raise a desktop notification
+//! and what the notification says.
+
+use mate::chess::Color;
+use mate::messages::chess::{DrawAccept, DrawOffer, GameInvite, Move, Resign};
+use mate::messages::Message;
+use mate::network::Notifier;
+
+const SENDER: &str = "0123456789abcdef0123456789abcdef";
+
+fn move_message() -> Message {
+    Message::Move(Move::new(
+        "abcdef12-3456-7890-abcd-ef1234567890".to_string(),
+        "e2e4".to_string(),
+        "hash".to_string(),
+    ))
+}
+
+fn invite_message() -> Message {
+    Message::GameInvite(GameInvite::new_with_color(
+        "abcdef12-3456-7890-abcd-ef1234567890".to_string(),
+        Color::Black,
+    ))
+}
+
+#[test]
+fn test_enabled_events_produce_notifications() {
+    let notifier = Notifier {
+        moves: true,
+        invites: true,
+    };
+
+    let (summary, body) = notifier
+        .notification_for(&move_message(), SENDER)
+        .expect("moves should notify");
+    assert_eq!(summary, "0123456789ab played e2e4");
+    assert_eq!(body, "Game abcdef12");
+
+    let (summary, body) = notifier
+        .notification_for(&invite_message(), SENDER)
+        .expect("invites should notify");
+    assert_eq!(summary, "0123456789ab invited you to a game");
+    assert!(body.contains("mate accept abcdef12-3456-7890-abcd-ef1234567890"));
+}
+
+#[test]
+fn test_disabled_events_are_silent() {
+    let notifier = Notifier {
+        moves: false,
+        invites: true,
+    };
+    assert!(notifier.notification_for(&move_message(), SENDER).is_none());
+    assert!(notifier
+        .notification_for(&invite_message(), SENDER)
+        .is_some());
+
+    let silent = Notifier::default();
+    assert!(silent.notification_for(&invite_message(), SENDER).is_none());
+}
+
+#[test]
+fn test_other_messages_never_notify() {
+    let notifier = Notifier {
+        moves: true,
+        invites: true,
+    };
+    let ping = Message::new_ping(1, "hello".to_string());
+    assert!(notifier.notification_for(&ping, SENDER).is_none());
+}
+
+#[test]
+fn test_peer_supplied_ids_are_truncated_on_char_boundaries() {
+    let notifier = Notifier {
+        moves: true,
+        invites: false,
+    };
+    let message = Message::Move(Move::new(
+        "ééééééééééé".to_string(),
+        "e2e4".to_string(),
+        "hash".to_string(),
+    ));
+    let (_, body) = notifier.notification_for(&message, "short").unwrap();
+    assert_eq!(body, "Game éééééééé");
+}
+
+#[test]
+fn test_game_endings_notify_with_moves() {
+    let game_id = "abcdef12-3456-7890-abcd-ef1234567890".to_string();
+    let notifier = Notifier {
+        moves: true,
+        invites: false,
+    };
+
+    let resign = Message::Resign(Resign::new(game_id.clone()));
+    let (summary, body) = notifier.notification_for(&resign, SENDER).unwrap();
+    assert_eq!(summary, "0123456789ab resigned");
+    assert_eq!(body, "You won game abcdef12");
+
+    let offer = Message::DrawOffer(DrawOffer::new(game_id.clone()));
+    let (summary, body) = notifier.notification_for(&offer, SENDER).unwrap();
+    assert_eq!(summary, "0123456789ab offers a draw");
+    assert!(body.contains("mate offer-draw abcdef12-3456-7890-abcd-ef1234567890"));
+
+    let accept = Message::DrawAccept(DrawAccept::new(game_id));
+    let (summary, _) = notifier.notification_for(&accept, SENDER).unwrap();
+    assert_eq!(summary, "0123456789ab accepted your draw offer");
+
+    let silent = Notifier {
+        moves: false,
+        invites: true,
+    };
+    assert!(silent.notification_for(&resign, SENDER).is_none());
+}
@@ -0,0 +1,144 @@
+//! Port Mapping Tests
+//!
+//! Tests for the NAT-PMP and UPnP messages and the stored mapping that
+//! invites take their address from, in `src/network/port_mapping.rs`
+
+use mate::cli::app::Config;
+use mate::network::http_client::HttpUrl;
+use mate::network::port_mapping::{
+    find_wan_connection_service, nat_pmp_map_request, parse_nat_pmp_address_response,
+    parse_nat_pmp_map_response, parse_route_table, ssdp_location, MappingMethod, NAT_PMP_PORT,
+};
+use mate::network::{PortMapper, PortMapping};
+use std::net::{Ipv4Addr, SocketAddr};
+use tempfile::TempDir;
+use tokio::net::UdpSocket;
+
+const EXTERNAL_IP: [u8; 4] = [203, 0, 113, 7];
+
+#[test]
+fn test_nat_pmp_messages_follow_rfc_6886() {
+    assert_eq!(
+        nat_pmp_map_request(8080, 8080, 7200),
+        [0, 2, 0, 0, 0x1f, 0x90, 0x1f, 0x90, 0, 0, 0x1c, 0x20]
+    );
+
+    let mut reply = vec![0, 128, 0, 0, 0, 0, 0, 1];
+    reply.extend(EXTERNAL_IP);
+    assert_eq!(
+        parse_nat_pmp_address_response(&reply).unwrap(),
+        Ipv4Addr::from(EXTERNAL_IP)
+    );
+
+    let reply = [
+        0, 130, 0, 0, 0, 0, 0, 1, 0x1f, 0x90, 0x1f, 0x91, 0, 0, 0x0e, 0x10,
+    ];
+    assert_eq!(parse_nat_pmp_map_response(&reply).unwrap(), (8081, 3600));
+
+    // Result code 2: refused by the gateway's administrator
+    let refused = [0, 130, 0, 2, 0, 0, 0, 1, 0x1f, 0x90, 0, 0, 0, 0, 0, 0];
+    let error = parse_nat_pmp_map_response(&refused).unwrap_err();
+    assert!(error.to_string().contains("disabled"), "{error}");
+    assert!(parse_nat_pmp_map_response(&reply[..8]).is_err());
+}
+
+#[test]
+fn test_default_gateway_is_read_from_the_route_table() {
+    let table = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\n\
+                 eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\n\
+                 eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\n";
+    let expected = Ipv4Addr::from(u32::from_str_radix("0101A8C0", 16).unwrap().to_ne_bytes());
+    assert_eq!(parse_route_table(table), Some(expected));
+    assert_eq!(parse_route_table("Iface\tDestination\tGateway\n"), None);
+}
+
+#[test]
+fn test_upnp_gateway_description_is_understood() {
+    let reply = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\n\
+                 Location: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+    assert_eq!(
+        ssdp_location(reply).as_deref(),
+        Some("http://192.168.1.1:5000/rootDesc.xml")
+    );
+
+    let description = r#"<root><device><serviceList>
+        <service><serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+        <controlURL>/ctl/L3F</controlURL></service>
+        <service><serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+        <controlURL>/ctl/IPConn</controlURL></service>
+        </serviceList></device></root>"#;
+    let (service_type, control) = find_wan_connection_service(description).unwrap();
+    assert_eq!(
+        service_type,
+        "urn:schemas-upnp-org:service:WANIPConnection:1"
+    );
+    let location = HttpUrl::parse("http://192.168.1.1:5000/rootDesc.xml").unwrap();
+    assert_eq!(
+        location.resolve(&control).unwrap().as_str(),
+        "http://192.168.1.1:5000/ctl/IPConn"
+    );
+    assert!(find_wan_connection_service("<root></root>").is_none());
+}
+
+#[tokio::test]
+async fn test_nat_pmp_mapping_is_shared_in_invites() {
+    // A gateway on the loopback interface that grants every request
+    let gateway = UdpSocket::bind((Ipv4Addr::LOCALHOST, NAT_PMP_PORT))
+        .await
+        .unwrap();
+    tokio::spawn(async move {
+        let mut buf = [0u8; 12];
+        loop {
+            let (len, from) = gateway.recv_from(&mut buf).await.unwrap();
+            let mut reply = vec![0, 128 + buf[1], 0, 0, 0, 0, 0, 1];
+            match (len, buf[1]) {
+                (2, 0) => reply.extend(EXTERNAL_IP),
+                (12, 2) => {
+                    reply.extend(&buf[4..6]);
+                    reply.extend(&buf[6..8]);
+                    reply.extend(&buf[8..12]);
+                }
+                _ => continue,
+            }
+            gateway.send_to(&reply, from).await.unwrap();
+        }
+    });
+
+    let local_addr: SocketAddr = "0.0.0.0:45123".parse().unwrap();
+    let mapper = PortMapper::new(local_addr).with_gateway(Some(Ipv4Addr::LOCALHOST));
+    let mapping = mapper.map().await.unwrap();
+    assert_eq!(
+        mapping.method,
+        MappingMethod::NatPmp {
+            gateway: Ipv4Addr::LOCALHOST
+        }
+    );
+    assert_eq!(mapping.external_addr.to_string(), "203.0.113.7:45123");
+    assert_eq!(mapping.lifetime_secs, 7200);
+
+    let temp_dir = TempDir::new().unwrap();
+    let config = Config {
+        data_dir: temp_dir.path().to_path_buf(),
+        ..Config::default()
+    };
+    assert_eq!(config.shared_addr(), config.default_bind_addr);
+    mapping.save(temp_dir.path()).unwrap();
+    assert_eq!(
+        PortMapping::load_current(temp_dir.path()),
+        Some(mapping.clone())
+    );
+    assert_eq!(config.shared_addr(), "203.0.113.7:45123");
+
+    // An expired mapping is no longer handed out
+    PortMapping {
+        mapped_at: mapping.mapped_at - 7200,
+        ..mapping.clone()
+    }
+    .save(temp_dir.path())
+    .unwrap();
+    assert_eq!(config.shared_addr(), config.default_bind_addr);
+
+    mapper.unmap(&mapping).await.unwrap();
+    PortMapping::remove(temp_dir.path()).unwrap();
+    assert!(PortMapping::load_current(temp_dir.path()).is_none());
+}
@@ -0,0 +1,68 @@
+//! Presence Tests
+//!
+//! Tests for the `Presence` message, kept by `Server` in
+//! `src/network/server.rs` from opponents in active games.
+
+use mate::crypto::Identity;
+use mate::messages::{Message, PresenceStatus};
+use mate::network::connection::Connection;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::sync::Arc;
+
+use super::{connect, exchange};
+
+/// A game in `status` on a server against `opponent`, and a connection from them
+async fn game_with(opponent: Arc<Identity>, status: GameStatus) -> (Arc<Database>, Connection) {
+    let (database, connection) = connect(Arc::clone(&opponent)).await;
+    let game = database
+        .create_game(opponent.peer_id().to_string(), PlayerColor::White, None)
+        .unwrap();
+    database.update_game_status(&game.id, status).unwrap();
+    (database, connection)
+}
+
+#[tokio::test]
+async fn test_presence_from_opponent_is_kept_and_replaced() {
+    let opponent = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = game_with(Arc::clone(&opponent), GameStatus::Active).await;
+
+    let reply = exchange(&mut connection, Message::new_presence(PresenceStatus::Busy)).await;
+    assert_eq!(reply.message_type(), "Presence");
+    let presence = database
+        .get_peer_presence(opponent.peer_id().as_str())
+        .unwrap()
+        .unwrap();
+    assert_eq!(presence.status, "busy");
+
+    exchange(&mut connection, Message::new_presence(PresenceStatus::Away)).await;
+    let presence = database
+        .get_peer_presence(opponent.peer_id().as_str())
+        .unwrap()
+        .unwrap();
+    assert_eq!(presence.status, "away");
+}
+
+#[tokio::test]
+async fn test_presence_without_an_active_game_is_ignored() {
+    let opponent = Arc::new(Identity::generate().unwrap());
+    let (database, mut connection) = game_with(Arc::clone(&opponent), GameStatus::Completed).await;
+
+    let reply = exchange(
+        &mut connection,
+        Message::new_presence(PresenceStatus::Available),
+    )
+    .await;
+
+    assert!(reply.is_ping());
+    assert!(database
+        .get_peer_presence(opponent.peer_id().as_str())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_presence_status_parses_case_insensitively() {
+    assert_eq!("Away".parse::<PresenceStatus>(), Ok(PresenceStatus::Away));
+    assert_eq!(PresenceStatus::Busy.to_string(), "busy");
+    assert!("asleep".parse::<PresenceStatus>().is_err());
+}
@@ -0,0 +1,97 @@
+//! Sealed Move Tests
+//!
+//! Tests for the `MoveSeal` and `SealReveal` messages of adjourned games,
+//! handled by `Server` in `src/network/server.rs`.
+
+use mate::crypto::{Identity, SealedMove};
+use mate::messages::Message;
+use mate::network::connection::Connection;
+use mate::network::Client;
+use mate::storage::{Database, GameStatus, PlayerColor};
+use std::sync::Arc;
+
+use super::{connect, exchange};
+
+/// An active game on a server against `sealer`, and a connection from them
+async fn adjourned_game(sealer: Arc<Identity>) -> (Arc<Database>, String, Connection) {
+    let (database, connection) = connect(Arc::clone(&sealer)).await;
+    let game = database
+        .create_game(sealer.peer_id().to_string(), PlayerColor::Black, None)
+        .unwrap();
+    database
+        .update_game_status(&game.id, GameStatus::Active)
+        .unwrap();
+    (database, game.id, connection)
+}
+
+#[tokio::test]
+async fn test_sealed_move_is_kept_and_checked_on_reveal() {
+    let sealer = Arc::new(Identity::generate().unwrap());
+    let (database, game_id, mut connection) = adjourned_game(Arc::clone(&sealer)).await;
+    let sealed = SealedMove::new(&game_id, 1, "e2e4");
+
+    let reply = exchange(&mut connection, Message::new_move_seal(&sealed)).await;
+    assert_eq!(reply.message_type(), "MoveSeal");
+    let record = database.get_sealed_move(&game_id).unwrap().unwrap();
+    assert!(record.is_sealed());
+    assert_eq!(record.sealed_by, sealer.peer_id().as_str());
+    assert_eq!(record.commitment, sealed.commitment());
+    assert_eq!(record.sealed_move, None);
+
+    let reply = exchange(&mut connection, Message::new_seal_reveal(sealed)).await;
+    assert_eq!(reply.message_type(), "SealReveal");
+    let record = database.get_sealed_move(&game_id).unwrap().unwrap();
+    assert!(!record.is_sealed());
+    assert_eq!(record.revealed_move.as_deref(), Some("e2e4"));
+}
+
+#[tokio::test]
+async fn test_reveal_of_a_different_move_is_refused() {
+    let sealer = Arc::new(Identity::generate().unwrap());
+    let (database, game_id, mut connection) = adjourned_game(sealer).await;
+    let sealed = SealedMove::new(&game_id, 1, "e2e4");
+    exchange(&mut connection, Message::new_move_seal(&sealed)).await;
+
+    let changed = SealedMove {
+        chess_move: "d2d4".to_string(),
+        ..sealed
+    };
+    let reply = exchange(&mut connection, Message::new_seal_reveal(changed)).await;
+
+    assert!(reply.is_ping());
+    assert!(database
+        .get_sealed_move(&game_id)
+        .unwrap()
+        .unwrap()
+        .is_sealed());
+}
+
+#[tokio::test]
+async fn test_move_cannot_be_sealed_twice() {
+    let sealer = Arc::new(Identity::generate().unwrap());
+    let (database, game_id, mut connection) = adjourned_game(sealer).await;
+    let first = SealedMove::new(&game_id, 1, "e2e4");
+    exchange(&mut connection, Message::new_move_seal(&first)).await;
+
+    let second = SealedMove::new(&game_id, 1, "d2d4");
+    let reply = exchange(&mut connection, Message::new_move_seal(&second)).await;
+
+    assert!(reply.is_ping());
+    let record = database.get_sealed_move(&game_id).unwrap().unwrap();
+    assert_eq!(record.commitment, first.commitment());
+}
+
+#[tokio::test]
+async fn test_only_the_opponent_may_seal() {
+    let opponent = Arc::new(Identity::generate().unwrap());
+    let (database, game_id, connection) = adjourned_game(opponent).await;
+    let addr = connection.peer_addr().unwrap().to_string();
+
+    let stranger = Client::new(Arc::new(Identity::generate().unwrap()));
+    let mut connection = stranger.connect(&addr).await.unwrap();
+    let sealed = SealedMove::new(&game_id, 1, "e2e4");
+    let reply = exchange(&mut connection, Message::new_move_seal(&sealed)).await;
+
+    assert!(reply.is_ping());
+    assert!(database.get_sealed_move(&game_id).unwrap().is_none());
+}
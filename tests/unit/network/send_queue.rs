@@ -0,0 +1,293 @@
+//! Send Queue Tests
+//!
+//! Tests for the bounded outbound queue of `Connection::send_message` in
+//! `src/network/connection.rs`, against a peer that stops reading, and for
+//! the priority lanes of its writer task that send moves ahead of bulk data,
+//! and for how a message the writer fails to send is reported.
+
+use super::connected_pair;
+use mate::crypto::Identity;
+use mate::messages::wire::{FramedMessage, WireProtocolError};
+use mate::messages::Message;
+use mate::network::{Connection, ConnectionError, MessagePriority, SendQueuePolicy, WireConfig};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, TcpSocket, TcpStream};
+
+/// Socket buffers kept small so a stalled reader fills them quickly
+const SOCKET_BUFFER: u32 = 4096;
+
+/// A connection to a peer that accepts and then never reads, with the peer's
+/// end kept open
+async fn stalled_peer(config: WireConfig) -> (Connection, TcpStream) {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_recv_buffer_size(SOCKET_BUFFER).unwrap();
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let listener: TcpListener = socket.listen(1).unwrap();
+
+    let client = TcpSocket::new_v4().unwrap();
+    client.set_send_buffer_size(SOCKET_BUFFER).unwrap();
+    let stream = client
+        .connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+
+    let identity = Arc::new(Identity::generate().unwrap());
+    (
+        Connection::new_with_config(stream, identity, config).await,
+        peer,
+    )
+}
+
+/// A connection and its peer's end, with small socket buffers so the
+/// writer can only get a little ahead of the reader
+async fn throttled_pair() -> (Connection, TcpStream) {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_recv_buffer_size(SOCKET_BUFFER).unwrap();
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let listener: TcpListener = socket.listen(1).unwrap();
+
+    let client = TcpSocket::new_v4().unwrap();
+    client.set_send_buffer_size(SOCKET_BUFFER).unwrap();
+    let stream = client
+        .connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (peer, _) = listener.accept().await.unwrap();
+    (
+        Connection::new(stream, Arc::new(Identity::generate().unwrap())).await,
+        peer,
+    )
+}
+
+/// The next message on `peer`, read one frame at a time
+///
+/// A `Connection` reads ahead whatever has already arrived, which would
+/// take in the whole sync before the move was sent.
+async fn read_one(peer: &mut TcpStream) -> Message {
+    FramedMessage::for_network()
+        .read_message(peer)
+        .await
+        .unwrap()
+        .get_message()
+        .unwrap()
+}
+
+fn chat(text: &str) -> Message {
+    Message::new_ping(0, text.to_string())
+}
+
+fn game_message(game_id: &str) -> Message {
+    Message::new_resign(game_id.to_string())
+}
+
+/// A chat message large enough to fill the socket buffers in a few writes
+fn large_chat() -> Message {
+    chat(&"x".repeat(64 * 1024))
+}
+
+#[tokio::test]
+async fn test_error_policy_refuses_messages_beyond_capacity() {
+    let config = WireConfig::for_network().with_send_queue(2, SendQueuePolicy::Error);
+    let (mut connection, _peer) = stalled_peer(config).await;
+
+    connection.send_message(chat("one")).await.unwrap();
+    connection.send_message(chat("two")).await.unwrap();
+    let error = connection.send_message(chat("three")).await.unwrap_err();
+    assert!(
+        matches!(error, ConnectionError::SendQueueFull { capacity: 2 }),
+        "{error}"
+    );
+    assert_eq!(connection.queued_messages(), 2);
+    assert_eq!(connection.stats().messages_sent, 0);
+}
+
+#[tokio::test]
+async fn test_unsent_message_fails_the_wait_for_its_reply() {
+    let config = WireConfig {
+        max_message_size: 16 * 1024,
+        ..WireConfig::for_network()
+    };
+    let (mut connection, _peer) = connected_pair(config).await;
+
+    // Queued fine, but too large for the writer to send
+    connection.send_message(large_chat()).await.unwrap();
+    let error = tokio::time::timeout(Duration::from_secs(5), connection.receive_message())
+        .await
+        .expect("The failed send should end the wait for a reply")
+        .unwrap_err();
+    assert!(
+        matches!(
+            error,
+            ConnectionError::WireProtocol(WireProtocolError::MessageTooLarge { .. })
+        ),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn test_drop_oldest_chat_keeps_game_messages_in_order() {
+    let config = WireConfig::for_network().with_send_queue(3, SendQueuePolicy::DropOldestChat);
+    let (mut connection, mut peer) = connected_pair(config).await;
+
+    connection.send_message(chat("first")).await.unwrap();
+    connection.send_message(game_message("g1")).await.unwrap();
+    connection.send_message(chat("second")).await.unwrap();
+    // Each of these pushes out the oldest chat message still queued
+    connection.send_message(game_message("g2")).await.unwrap();
+    connection.send_message(game_message("g3")).await.unwrap();
+    assert_eq!(connection.queued_messages(), 3);
+
+    // With only game messages queued, nothing may be dropped
+    let error = connection.send_message(chat("third")).await.unwrap_err();
+    assert!(matches!(
+        error,
+        ConnectionError::SendQueueFull { capacity: 3 }
+    ));
+
+    connection.flush().await.unwrap();
+    assert_eq!(connection.queued_messages(), 0);
+    for expected in ["g1", "g2", "g3"] {
+        let (message, _) = peer.receive_message().await.unwrap();
+        assert_eq!(message.get_game_id(), Some(expected));
+    }
+}
+
+#[tokio::test]
+async fn test_block_policy_waits_on_a_stalled_reader_without_growing() {
+    let config = WireConfig::with_timeout(Duration::from_millis(200))
+        .with_send_queue(4, SendQueuePolicy::Block);
+    let (mut connection, _peer) = stalled_peer(config).await;
+
+    let mut outcome = Ok(());
+    for _ in 0..1000 {
+        outcome = connection.send_message(large_chat()).await;
+        assert!(connection.queued_messages() <= 4);
+        if outcome.is_err() {
+            break;
+        }
+    }
+
+    // The peer read nothing, so a write timed out long before 1000 messages
+    // (64 MiB) were held
+    assert!(
+        matches!(outcome, Err(ConnectionError::WireProtocol(_))),
+        "{outcome:?}"
+    );
+    assert!(connection.stats().messages_sent < 100);
+    // The message being written stays queued
+    assert_eq!(connection.queued_messages(), 4);
+}
+
+/// One part of a bulk sync, about `size` bytes of move history
+fn sync_chunk(size: usize) -> Message {
+    Message::new_sync_response(
+        "g1".to_string(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+        vec!["e2e4".repeat(256); size / 1024],
+        "hash".to_string(),
+    )
+}
+
+#[test]
+fn test_messages_are_classified_into_lanes() {
+    assert_eq!(
+        Message::new_move_ack("g1".to_string(), None).priority(),
+        MessagePriority::Control
+    );
+    assert_eq!(
+        Message::new_ping(1, String::new()).priority(),
+        MessagePriority::Control
+    );
+    assert_eq!(
+        Message::new_move("g1".to_string(), "e2e4".to_string(), "hash".to_string()).priority(),
+        MessagePriority::Move
+    );
+    assert_eq!(game_message("g1").priority(), MessagePriority::Move);
+    assert_eq!(sync_chunk(1024).priority(), MessagePriority::Sync);
+    assert_eq!(chat("hello").priority(), MessagePriority::Chat);
+}
+
+#[tokio::test]
+async fn test_flush_sends_the_most_urgent_lane_first() {
+    let (mut connection, mut peer) = connected_pair(WireConfig::for_network()).await;
+
+    connection.send_message(chat("hello")).await.unwrap();
+    connection.send_message(sync_chunk(1024)).await.unwrap();
+    connection
+        .send_message(Message::new_move(
+            "g1".to_string(),
+            "e2e4".to_string(),
+            "hash".to_string(),
+        ))
+        .await
+        .unwrap();
+    connection.send_message(game_message("g1")).await.unwrap();
+    connection
+        .send_message(Message::new_move_ack("g1".to_string(), None))
+        .await
+        .unwrap();
+    connection.flush().await.unwrap();
+
+    let mut received = Vec::new();
+    for _ in 0..5 {
+        let (message, _) = peer.receive_message().await.unwrap();
+        received.push(message.message_type());
+    }
+    assert_eq!(
+        received,
+        ["MoveAck", "Move", "Resign", "SyncResponse", "Ping"]
+    );
+}
+
+#[tokio::test]
+async fn test_move_overtakes_a_concurrent_large_sync() {
+    const CHUNK: usize = 512 * 1024;
+    const CHUNKS: usize = 16; // 8 MiB in all
+
+    let (mut connection, mut peer) = throttled_pair().await;
+
+    for _ in 0..CHUNKS {
+        connection.send_message(sync_chunk(CHUNK)).await.unwrap();
+    }
+    let first = read_one(&mut peer).await;
+    assert_eq!(first.priority(), MessagePriority::Sync);
+    let sync_started = Instant::now();
+
+    // Submitted while the rest of the sync is still waiting to be written
+    let submitted = Instant::now();
+    connection
+        .send_message(Message::new_move(
+            "g1".to_string(),
+            "e2e4".to_string(),
+            "hash".to_string(),
+        ))
+        .await
+        .unwrap();
+
+    let mut sync_before_move = 0;
+    let move_latency = loop {
+        match read_one(&mut peer).await.priority() {
+            MessagePriority::Move => break submitted.elapsed(),
+            _ => sync_before_move += 1,
+        }
+    };
+    let mut sync_after_move = 0;
+    while sync_before_move + sync_after_move < CHUNKS - 1 {
+        read_one(&mut peer).await;
+        sync_after_move += 1;
+    }
+    let sync_duration = sync_started.elapsed();
+    connection.flush().await.unwrap();
+
+    // Only the chunk already being written may go first
+    assert!(
+        sync_before_move <= 1,
+        "{sync_before_move} chunks went first"
+    );
+    assert!(
+        move_latency < sync_duration / 2,
+        "move took {move_latency:?} during a sync taking {sync_duration:?}"
+    );
+}
@@ -0,0 +1,119 @@
+//! Slow Client Tests
+//!
+//! Tests for the handshake deadlines `Server` holds new connections to, so
+//! silent, half-open, or deliberately slow clients are dropped and counted.
+
+use mate::crypto::Identity;
+use mate::network::{Client, HandshakeLimits, Server, StatsRegistry};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+
+async fn start_server(limits: HandshakeLimits) -> (String, StatsRegistry, JoinHandle<()>) {
+    let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap()
+        .with_handshake_limits(limits);
+    let addr = server.local_addr().unwrap().to_string();
+    let stats = server.stats();
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    (addr, stats, handle)
+}
+
+fn short_limits() -> HandshakeLimits {
+    HandshakeLimits {
+        first_byte_timeout: Duration::from_millis(200),
+        handshake_deadline: Duration::from_millis(600),
+        ..HandshakeLimits::default()
+    }
+}
+
+/// Wait for the server to close `stream`, returning how long that took
+async fn wait_for_close(stream: &mut TcpStream) -> Duration {
+    let start = Instant::now();
+    let mut buffer = [0u8; 64];
+    let closed = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match stream.read(&mut buffer).await {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+    })
+    .await;
+    assert!(closed.is_ok(), "server kept the connection open");
+    start.elapsed()
+}
+
+#[tokio::test]
+async fn test_silent_connection_is_closed_after_the_first_byte_timeout() {
+    let (addr, stats, handle) = start_server(short_limits()).await;
+
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    let waited = wait_for_close(&mut stream).await;
+
+    handle.abort();
+    assert!(
+        waited < Duration::from_millis(600),
+        "closed after {waited:?}"
+    );
+    assert_eq!(stats.snapshot().handshake_timeouts, 1);
+}
+
+#[tokio::test]
+async fn test_trickling_handshake_is_closed_at_the_deadline() {
+    let (addr, stats, handle) = start_server(short_limits()).await;
+
+    // A length prefix for a large handshake, then one byte at a time: each
+    // arrives well within any per-read timeout, but the whole never does
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    stream.write_all(&1000u32.to_be_bytes()).await.unwrap();
+    let start = Instant::now();
+    let trickle = async {
+        loop {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if stream.write_all(&[0]).await.is_err() {
+                break;
+            }
+        }
+    };
+    let _ = tokio::time::timeout(Duration::from_secs(5), trickle).await;
+    let waited = start.elapsed();
+
+    handle.abort();
+    assert!(waited < Duration::from_secs(2), "closed after {waited:?}");
+    assert_eq!(stats.snapshot().handshake_timeouts, 1);
+}
+
+#[tokio::test]
+async fn test_connections_past_the_pending_handshake_limit_are_refused() {
+    let (addr, stats, handle) = start_server(HandshakeLimits {
+        max_pending: 1,
+        ..short_limits()
+    })
+    .await;
+
+    let mut silent = TcpStream::connect(&addr).await.unwrap();
+    let mut refused = TcpStream::connect(&addr).await.unwrap();
+    let waited = wait_for_close(&mut refused).await;
+    assert!(
+        waited < Duration::from_millis(150),
+        "closed after {waited:?}"
+    );
+    wait_for_close(&mut silent).await;
+
+    // Once the slow client is gone a real one gets through
+    let connection = Client::new(Arc::new(Identity::generate().unwrap()))
+        .connect(&addr)
+        .await;
+
+    handle.abort();
+    assert!(connection.is_ok());
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.refused_connections, 1);
+    assert_eq!(snapshot.handshake_timeouts, 1);
+}
@@ -0,0 +1,175 @@
+//! Connection Statistics Tests
+//!
+//! Tests for the counters kept by `Connection::stats()` and aggregated by
+//! `StatsRegistry` in `src/network/stats.rs`.
+
+use mate::crypto::Identity;
+use mate::messages::wire::SERVER_MAX_CONCURRENT_CONNECTIONS;
+use mate::messages::Message;
+use mate::network::{Client, ConnectionStats, Server, StatsRegistry};
+use mate::storage::Database;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn stats(messages_sent: u64, retries: u64, last_activity: u64) -> ConnectionStats {
+    ConnectionStats {
+        messages_sent,
+        bytes_sent: messages_sent * 100,
+        retries,
+        last_activity: Some(last_activity),
+        round_trips: 1,
+        round_trip_total_ms: 10 * messages_sent,
+        ..ConnectionStats::default()
+    }
+}
+
+#[test]
+fn test_registry_totals_open_and_closed_connections() {
+    let registry = StatsRegistry::new();
+    registry.update("a", &stats(1, 0, 10));
+    // Later updates replace a connection's counters rather than adding to them
+    registry.update("a", &stats(2, 0, 20));
+    registry.update("b", &stats(3, 1, 30));
+    registry.close("b", &stats(4, 1, 40));
+    registry.record_retries(2);
+
+    let snapshot = registry.snapshot();
+    assert_eq!(snapshot.open_connections, 1);
+    assert_eq!(snapshot.total_connections, 2);
+    assert_eq!(snapshot.traffic.messages_sent, 6);
+    assert_eq!(snapshot.traffic.bytes_sent, 600);
+    assert_eq!(snapshot.traffic.retries, 3);
+    assert_eq!(snapshot.traffic.last_activity, Some(40));
+    assert_eq!(
+        snapshot.traffic.avg_round_trip(),
+        Some(Duration::from_millis(30))
+    );
+
+    // Connections closed without an update still count
+    registry.close("c", &ConnectionStats::default());
+    assert_eq!(registry.snapshot().total_connections, 3);
+    assert_eq!(ConnectionStats::default().avg_round_trip(), None);
+}
+
+#[tokio::test]
+async fn test_connection_stats_count_both_ends_of_an_exchange() {
+    let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap();
+    let server_addr = server.local_addr().unwrap().to_string();
+    let server_stats = server.stats();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let client = Client::new(Arc::new(Identity::generate().unwrap()));
+    let mut connection = client.connect(&server_addr).await.unwrap();
+    let after_handshake = connection.stats();
+    assert_eq!(after_handshake.retries, 0);
+
+    for nonce in 0..3 {
+        connection
+            .send_message(Message::new_ping(nonce, "ping".to_string()))
+            .await
+            .unwrap();
+        connection.receive_message().await.unwrap();
+    }
+
+    let stats = connection.stats();
+    assert_eq!(stats.messages_sent, after_handshake.messages_sent + 3);
+    assert_eq!(
+        stats.messages_received,
+        after_handshake.messages_received + 3
+    );
+    assert!(stats.bytes_sent > after_handshake.bytes_sent);
+    assert!(stats.bytes_received > after_handshake.bytes_received);
+    assert!(stats.last_activity.is_some());
+    assert_eq!(stats.round_trips, after_handshake.round_trips + 3);
+
+    // The server's counters for the same connection mirror the client's
+    let sent = stats.messages_sent;
+    // Dropping the connection shuts its socket
+    drop(connection);
+    let mut snapshot = server_stats.snapshot();
+    for _ in 0..50 {
+        if snapshot.open_connections == 0 && snapshot.total_connections == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        snapshot = server_stats.snapshot();
+    }
+    server_handle.abort();
+    assert_eq!(snapshot.total_connections, 1);
+    assert_eq!(snapshot.open_connections, 0);
+    assert_eq!(snapshot.traffic.messages_received, sent);
+    assert_eq!(snapshot.traffic.round_trips, 0);
+}
+
+#[test]
+fn test_monitor_lists_open_connections_with_their_peers() {
+    let registry = StatsRegistry::new();
+    registry.update("b", &stats(2, 0, 20));
+    registry.update("a", &stats(1, 0, 10));
+    registry.identify("a", "peer-a");
+    registry.record_rejected();
+    registry.record_oversized();
+    registry.record_refused_connection();
+    registry.record_handshake_timeout();
+
+    let monitor = registry.monitor();
+    assert_eq!(monitor.connection_limit, SERVER_MAX_CONCURRENT_CONNECTIONS);
+    let ids: Vec<&str> = monitor
+        .connections
+        .iter()
+        .map(|open| open.connection_id.as_str())
+        .collect();
+    assert_eq!(ids, ["a", "b"]);
+    assert_eq!(monitor.connections[0].peer_id.as_deref(), Some("peer-a"));
+    assert_eq!(monitor.connections[1].peer_id, None);
+    assert_eq!(monitor.connections[1].stats.messages_sent, 2);
+    assert_eq!(monitor.totals.rejected_messages, 1);
+    assert_eq!(monitor.totals.oversized_messages, 1);
+    assert_eq!(monitor.totals.refused_connections, 1);
+    assert_eq!(monitor.totals.handshake_timeouts, 1);
+
+    // Closed connections leave the list but not the totals
+    registry.close("a", &stats(1, 0, 10));
+    let monitor = registry.monitor();
+    assert_eq!(monitor.connections.len(), 1);
+    assert_eq!(monitor.totals.traffic.messages_sent, 3);
+}
+
+#[tokio::test]
+async fn test_server_names_peers_and_counts_refused_messages() {
+    let database = Arc::new(Database::new_in_memory("server").unwrap());
+    let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap()
+        .with_peer_history(database);
+    let server_addr = server.local_addr().unwrap().to_string();
+    let server_stats = server.stats();
+    let server_handle = tokio::spawn(server.run());
+
+    let identity = Arc::new(Identity::generate().unwrap());
+    let mut connection = Client::new(Arc::clone(&identity))
+        .connect(&server_addr)
+        .await
+        .unwrap();
+    // Only the server's operator may announce
+    connection
+        .send_message(Message::new_server_announcement("hello".to_string()))
+        .await
+        .unwrap();
+    connection
+        .send_message(Message::new_ping(1, "ping".to_string()))
+        .await
+        .unwrap();
+    connection.receive_message().await.unwrap();
+
+    let monitor = server_stats.monitor();
+    server_handle.abort();
+    assert_eq!(monitor.totals.rejected_messages, 1);
+    assert_eq!(monitor.connections.len(), 1);
+    assert_eq!(
+        monitor.connections[0].peer_id.as_deref(),
+        Some(identity.peer_id().as_str())
+    );
+}
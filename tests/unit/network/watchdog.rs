@@ -0,0 +1,175 @@
+//! Watchdog Tests
+//!
+//! Tests for restarting stuck, failed, and panicked subsystems with the
+//! `Watchdog` in `src/network/watchdog.rs`, and for the heartbeat of the
+//! server's accept loop, which stops its shutdown listener when aborted.
+
+use mate::crypto::Identity;
+use mate::network::watchdog::{Heartbeat, Incident, IncidentKind, Watchdog};
+use mate::network::Server;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const CHECK_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Counts how often a subsystem was started
+fn counter() -> (Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    let starts = Arc::new(AtomicUsize::new(0));
+    (Arc::clone(&starts), starts)
+}
+
+fn incidents(path: &std::path::Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_stalled_subsystem_is_restarted_and_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("incidents.log");
+    let (starts, counted) = counter();
+
+    let watchdog = Watchdog::new()
+        .with_check_interval(CHECK_INTERVAL)
+        .with_incident_log(log.clone())
+        .supervise("stuck", Some(Duration::from_millis(50)), move |_| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            std::future::pending()
+        });
+    let task = tokio::spawn(watchdog.run());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    task.abort();
+
+    assert!(starts.load(Ordering::SeqCst) >= 2);
+    let incidents = incidents(&log);
+    assert!(!incidents.is_empty());
+    assert_eq!(incidents[0]["subsystem"], "stuck");
+    assert_eq!(incidents[0]["kind"], "stalled");
+    assert_eq!(incidents[0]["restarts"], 1);
+}
+
+#[tokio::test]
+async fn test_beating_subsystem_is_left_running() {
+    let (starts, counted) = counter();
+
+    let watchdog = Watchdog::new()
+        .with_check_interval(CHECK_INTERVAL)
+        .supervise(
+            "busy",
+            Some(Duration::from_millis(50)),
+            move |heartbeat: Heartbeat| {
+                counted.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    loop {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        heartbeat.beat();
+                    }
+                }
+            },
+        );
+    let task = tokio::spawn(watchdog.run());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    task.abort();
+
+    assert_eq!(starts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_failed_and_panicked_subsystems_are_restarted() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("incidents.log");
+
+    let watchdog = Watchdog::new()
+        .with_check_interval(CHECK_INTERVAL)
+        .with_incident_log(log.clone())
+        .supervise("failing", None, |_| async {
+            anyhow::bail!("database is locked")
+        })
+        .supervise("panicking", None, |_| async { panic!("lost the board") });
+    let task = tokio::spawn(watchdog.run());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    task.abort();
+
+    let incidents = incidents(&log);
+    let failed = incidents
+        .iter()
+        .find(|incident| incident["subsystem"] == "failing")
+        .unwrap();
+    assert_eq!(failed["kind"], "failed");
+    assert_eq!(failed["error"], "database is locked");
+    let panicked = incidents
+        .iter()
+        .find(|incident| incident["subsystem"] == "panicking")
+        .unwrap();
+    assert_eq!(panicked["kind"], "panicked");
+    assert_eq!(panicked["message"], "lost the board");
+    assert!(incidents.iter().any(|incident| incident["restarts"] == 2));
+}
+
+#[tokio::test]
+async fn test_watchdog_stops_when_a_subsystem_finishes() {
+    let watchdog = Watchdog::new()
+        .with_check_interval(CHECK_INTERVAL)
+        .supervise("forever", None, |_| std::future::pending())
+        .supervise("finishing", None, |_| async { Ok(()) });
+
+    tokio::time::timeout(Duration::from_secs(5), watchdog.run())
+        .await
+        .expect("the watchdog should stop")
+        .unwrap();
+}
+
+#[test]
+fn test_incident_display() {
+    let incident = Incident {
+        subsystem: "accept loop".to_string(),
+        kind: IncidentKind::Stalled { silent_secs: 95 },
+        restarts: 2,
+        at: 1_700_000_000,
+    };
+    assert_eq!(
+        incident.to_string(),
+        "accept loop stalled (no heartbeat for 95s); restart 2"
+    );
+}
+
+#[tokio::test]
+async fn test_accept_loop_beats_its_heartbeat() {
+    let heartbeat = Heartbeat::new();
+    let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+        .await
+        .unwrap()
+        .with_heartbeat(heartbeat.clone());
+    let task = tokio::spawn(server.run());
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(heartbeat.silent_for() < Duration::from_millis(1200));
+    task.abort();
+}
+
+#[tokio::test]
+async fn test_aborted_accept_loop_leaves_no_tasks_behind() {
+    let tasks = || {
+        tokio::runtime::Handle::current()
+            .metrics()
+            .num_alive_tasks()
+    };
+    let before = tasks();
+
+    for _ in 0..3 {
+        let server = Server::bind("127.0.0.1:0", Arc::new(Identity::generate().unwrap()))
+            .await
+            .unwrap();
+        let task = tokio::spawn(server.run());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        task.abort();
+        let _ = task.await;
+    }
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(tasks(), before);
+}
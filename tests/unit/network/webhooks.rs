@@ -0,0 +1,155 @@
+//! Webhook Tests
+//!
+//! Tests for the payloads, signatures, and delivery of webhooks in
+//! `src/network/webhooks.rs`.
+
+use mate::cli::app::Config;
+use mate::messages::{Message, RetryConfig};
+use mate::network::http_client::{self, HttpUrl};
+use mate::network::webhooks::{sign_payload, Webhooks, EVENT_HEADER, SIGNATURE_HEADER};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+#[test]
+fn test_payload_signature_is_hmac_sha256() {
+    // RFC 4231, test case 2
+    assert_eq!(
+        sign_payload("Jefe", b"what do ya want for nothing?"),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+}
+
+#[test]
+fn test_payloads_cover_moves_invites_and_results() {
+    let chess_move = Message::new_move("game-1".to_string(), "e2e4".to_string(), "0".repeat(64));
+    let payload = Webhooks::payload_for(&chess_move, "peer").unwrap();
+    assert_eq!(payload.event, "move");
+    assert_eq!(payload.game_id, "game-1");
+    assert_eq!(payload.chess_move.as_deref(), Some("e2e4"));
+
+    let json = serde_json::to_value(&payload).unwrap();
+    assert_eq!(json["from"], "peer");
+    assert!(json.get("result").is_none());
+
+    let ping = Message::new_ping(1, "ping".to_string());
+    assert!(Webhooks::payload_for(&ping, "peer").is_none());
+}
+
+#[test]
+fn test_http_and_https_urls_are_accepted() {
+    let urls = |url: &str| vec![url.to_string()];
+    assert!(Webhooks::new(&urls("http://localhost:9000/hook"), None).is_ok());
+    assert!(Webhooks::new(&urls("http://[::1]:9000"), None).is_ok());
+    assert!(Webhooks::new(&urls("https://hooks.example.com"), None).is_ok());
+    assert!(Webhooks::new(&urls("ftp://hooks.example.com"), None).is_err());
+    assert!(Webhooks::new(&urls("http://host:port"), None).is_err());
+    assert!(!Webhooks::new(&[], None).unwrap().is_enabled());
+
+    let https = HttpUrl::parse("https://hooks.example.com/services/x").unwrap();
+    assert_eq!(https.host(), "hooks.example.com");
+    assert_eq!(
+        https.resolve("/other").unwrap().as_str(),
+        "https://hooks.example.com/other"
+    );
+}
+
+#[test]
+fn test_unusable_urls_are_refused_when_configured() {
+    let mut config = Config::default();
+    assert!(config
+        .set("webhooks.urls", "https://a.example.com,ftp://b.example.com")
+        .is_err());
+    assert!(config.webhooks.urls.is_empty());
+    config
+        .set(
+            "webhooks.urls",
+            "https://a.example.com,http://127.0.0.1:9000",
+        )
+        .unwrap();
+    assert_eq!(config.webhooks.urls.len(), 2);
+    assert!(config
+        .set("telemetry.otlp_endpoint", "127.0.0.1:4318")
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_https_urls_are_requested_over_tls() {
+    // A plain HTTP server can't complete the TLS handshake
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let _ = stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .await;
+    });
+
+    let url = HttpUrl::parse(&format!("https://localhost:{}/hook", addr.port())).unwrap();
+    let error = http_client::post_json(&url, &[], b"{}").await.unwrap_err();
+    assert!(format!("{error:#}").contains("TLS"), "{error:#}");
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_are_retried_with_a_signature() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let (requests, mut received) = mpsc::unbounded_channel();
+
+    // Fail the first delivery, accept the second
+    tokio::spawn(async move {
+        for status in ["500 Internal Server Error", "204 No Content"] {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = Vec::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).await.unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                head.push(line.trim_end().to_string());
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).await.unwrap();
+            let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+            reader
+                .get_mut()
+                .write_all(response.as_bytes())
+                .await
+                .unwrap();
+            requests.send((head, body)).unwrap();
+        }
+    });
+
+    let webhooks = Webhooks::new(&[url], Some("s3cret"))
+        .unwrap()
+        .with_retry(RetryConfig {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(10),
+            ..RetryConfig::default()
+        });
+    let chess_move = Message::new_move("game-1".to_string(), "e2e4".to_string(), "0".repeat(64));
+    webhooks.dispatch(&chess_move, "peer");
+
+    for _ in 0..2 {
+        let (head, body) = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .expect("delivery attempt")
+            .unwrap();
+        assert_eq!(head[0], "POST /hook HTTP/1.1");
+        let signature = format!(
+            "{SIGNATURE_HEADER}: sha256={}",
+            sign_payload("s3cret", &body)
+        );
+        assert!(head.contains(&signature), "{head:?}");
+        assert!(head.contains(&format!("{EVENT_HEADER}: move")), "{head:?}");
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["chess_move"], "e2e4");
+    }
+}